@@ -0,0 +1,35 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Avatar image decoding with the `image` crate
+
+use image::{imageops::FilterType, ImageReader};
+use project_tracker::storage::{ImageFormat, ImageProcessor};
+use project_tracker::Result;
+use std::io::Cursor;
+
+/// Decodes PNG and JPEG avatars and renders PNG thumbnails
+pub struct AvatarProcessor;
+
+fn reader(bytes: &[u8], format: ImageFormat) -> ImageReader<Cursor<&[u8]>> {
+    let format = match format {
+        ImageFormat::Png => image::ImageFormat::Png,
+        ImageFormat::Jpeg => image::ImageFormat::Jpeg,
+    };
+    ImageReader::with_format(Cursor::new(bytes), format)
+}
+
+impl ImageProcessor for AvatarProcessor {
+    fn dimensions(&self, bytes: &[u8], format: ImageFormat) -> Result<(u32, u32)> {
+        Ok(reader(bytes, format).into_dimensions()?)
+    }
+
+    fn square_thumbnail(&self, bytes: &[u8], format: ImageFormat, size: u32) -> Result<Vec<u8>> {
+        let image = reader(bytes, format).decode()?;
+        let thumbnail = image.resize_to_fill(size, size, FilterType::Lanczos3);
+        let mut png = Vec::new();
+        thumbnail.write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)?;
+        Ok(png)
+    }
+}
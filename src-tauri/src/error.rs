@@ -0,0 +1,326 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Errors returned to the frontend by commands
+//!
+//! `code` lets the frontend react to specific failures, such as retrying
+//! when the database is busy or highlighting the field a constraint
+//! rejected, without matching on message text. `details` carries the facts
+//! behind the code, such as the field name or the ID that didn't parse.
+
+use project_tracker::db;
+use serde::Serialize;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+/// Error codes sent to the frontend
+///
+/// `ui/src/services/errorCodes.json` lists the same codes for the frontend;
+/// a test keeps the two in step.
+pub mod codes {
+    /// A failure without a more specific code; the message says what happened
+    pub const ERROR: &str = "ERROR";
+    /// An unexpected database failure, which is also logged
+    pub const INTERNAL: &str = "INTERNAL";
+    /// The database is busy with another operation; retry in a moment
+    pub const DB_BUSY: &str = "DB_BUSY";
+    /// The record doesn't exist
+    pub const NOT_FOUND: &str = "NOT_FOUND";
+    /// The record conflicts with one that already exists, such as a duplicate email
+    pub const CONFLICT: &str = "CONFLICT";
+    /// A record with the same name already exists
+    pub const ALREADY_EXISTS: &str = "ALREADY_EXISTS";
+    /// An ID prefix matches more than one record
+    pub const AMBIGUOUS_ID: &str = "AMBIGUOUS_ID";
+    /// Someone else changed the record since it was read
+    pub const VERSION_CONFLICT: &str = "VERSION_CONFLICT";
+    /// A field has a value that isn't allowed
+    pub const INVALID: &str = "INVALID";
+    /// A value passed as an ID isn't a UUID
+    pub const INVALID_ID: &str = "INVALID_ID";
+    /// A field refers to a record that doesn't exist
+    pub const FOREIGN_KEY: &str = "FOREIGN_KEY";
+    /// A manager or parent team change would make a record its own ancestor
+    pub const CYCLE: &str = "CYCLE";
+    /// A new person's name closely matches people who already exist
+    pub const POSSIBLE_DUPLICATE: &str = "POSSIBLE_DUPLICATE";
+    /// A person's email is outside the allowed email domains
+    pub const DOMAIN_NOT_ALLOWED: &str = "DOMAIN_NOT_ALLOWED";
+    /// The current user's role doesn't allow the command
+    pub const PERMISSION_DENIED: &str = "PERMISSION_DENIED";
+
+    /// Every code with what it means and what `details` holds
+    pub const ALL: &[(&str, &str)] = &[
+        (ERROR, "A failure without a more specific code; show the message"),
+        (INTERNAL, "An unexpected database failure, which is also logged"),
+        (DB_BUSY, "The database is busy with another operation; retry in a moment"),
+        (NOT_FOUND, "The record doesn't exist; details: entity, id"),
+        (CONFLICT, "The record conflicts with one that already exists, such as a duplicate email"),
+        (ALREADY_EXISTS, "A record with the same name already exists; details: entity, name, id"),
+        (AMBIGUOUS_ID, "An ID prefix matches more than one record; details: entity, prefix, matches"),
+        (
+            VERSION_CONFLICT,
+            "Someone else changed the record since it was read; details: entity, id, expected, actual, current",
+        ),
+        (INVALID, "A field has a value that isn't allowed"),
+        (INVALID_ID, "A value passed as an ID isn't a UUID; details: value"),
+        (FOREIGN_KEY, "A field refers to a record that doesn't exist; details: field"),
+        (CYCLE, "A manager or parent team change would make a record its own ancestor; details: relation, chain"),
+        (POSSIBLE_DUPLICATE, "A new person's name closely matches existing people; details: name, matches"),
+        (
+            DOMAIN_NOT_ALLOWED,
+            "A person's email is outside the allowed email domains; details: email, allowed",
+        ),
+        (
+            PERMISSION_DENIED,
+            "The current user's role doesn't allow the command; details: operation, user, role, required",
+        ),
+    ];
+}
+
+/// Error returned to the frontend by commands
+#[derive(Debug, Serialize)]
+pub struct CommandError {
+    pub code: &'static str,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+}
+
+impl CommandError {
+    pub fn new(code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    pub fn db_busy() -> Self {
+        Self::new(codes::DB_BUSY, "The database is busy with another operation, try again in a moment")
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        Self::new(codes::ERROR, message)
+    }
+}
+
+impl From<&str> for CommandError {
+    fn from(message: &str) -> Self {
+        Self::new(codes::ERROR, message)
+    }
+}
+
+/// Missing records, conflicts and invalid references already say what to
+/// fix; anything else is also logged
+impl From<db::Error> for CommandError {
+    fn from(e: db::Error) -> Self {
+        let message = e.to_string();
+        let (code, details) = match e {
+            db::Error::NotFound { entity, id } => (codes::NOT_FOUND, Some(json!({ "entity": entity, "id": id }))),
+            db::Error::Conflict(_) => (codes::CONFLICT, None),
+            db::Error::AlreadyExists { entity, name, id } => {
+                (codes::ALREADY_EXISTS, Some(json!({ "entity": entity, "name": name, "id": id })))
+            }
+            db::Error::AmbiguousId { entity, prefix, matches } => (
+                codes::AMBIGUOUS_ID,
+                Some(json!({ "entity": entity, "prefix": prefix, "matches": matches })),
+            ),
+            db::Error::VersionConflict {
+                entity,
+                id,
+                expected,
+                actual,
+                current,
+            } => (
+                codes::VERSION_CONFLICT,
+                Some(json!({
+                    "entity": entity,
+                    "id": id,
+                    "expected": expected,
+                    "actual": actual,
+                    "current": current,
+                })),
+            ),
+            db::Error::Invalid(_) => (codes::INVALID, None),
+            db::Error::ForeignKeyViolation { field } => (codes::FOREIGN_KEY, Some(json!({ "field": field }))),
+            db::Error::CycleDetected { relation, chain } => {
+                (codes::CYCLE, Some(json!({ "relation": relation, "chain": chain })))
+            }
+            db::Error::PossibleDuplicate { name, matches } => {
+                (codes::POSSIBLE_DUPLICATE, Some(json!({ "name": name, "matches": matches })))
+            }
+            db::Error::DomainNotAllowed { email, allowed } => {
+                (codes::DOMAIN_NOT_ALLOWED, Some(json!({ "email": email, "allowed": allowed })))
+            }
+            db::Error::PermissionDenied {
+                operation,
+                user,
+                role,
+                required,
+            } => (
+                codes::PERMISSION_DENIED,
+                Some(json!({ "operation": operation, "user": user, "role": role, "required": required })),
+            ),
+            db::Error::Busy => (codes::DB_BUSY, None),
+            db::Error::Other(inner) => {
+                log::error!("Database error: {:#}", inner);
+                (codes::INTERNAL, None)
+            }
+        };
+        Self {
+            code,
+            message,
+            details,
+        }
+    }
+}
+
+/// Library functions that return `anyhow` errors often wrap a repository
+/// error; those keep their code, and the rest are reported with their context
+impl From<anyhow::Error> for CommandError {
+    fn from(e: anyhow::Error) -> Self {
+        match e.downcast::<db::Error>() {
+            Ok(e) => e.into(),
+            Err(e) => Self::new(codes::ERROR, format!("{:#}", e)),
+        }
+    }
+}
+
+/// Parse an ID passed by the frontend
+pub fn parse_id(value: &str) -> Result<Uuid, CommandError> {
+    Uuid::parse_str(value).map_err(|e| {
+        CommandError::new(codes::INVALID_ID, format!("Invalid ID '{}': {}", value, e))
+            .with_details(json!({ "value": value }))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use project_tracker::db::{Person, PersonNote, PersonRepository, Project, ProjectNote, ProjectRepository};
+    use rusqlite::Connection;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn code_of<T: std::fmt::Debug>(result: db::Result<T>) -> (&'static str, Option<Value>) {
+        let error = CommandError::from(result.unwrap_err());
+        (error.code, error.details)
+    }
+
+    #[test]
+    fn test_invalid_id() {
+        let error = parse_id("not-a-uuid").unwrap_err();
+        assert_eq!(error.code, codes::INVALID_ID);
+        assert_eq!(error.details, Some(json!({ "value": "not-a-uuid" })));
+        assert!(parse_id(&Uuid::new_v4().to_string()).is_ok());
+
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["code"], "INVALID_ID");
+        assert_eq!(json["details"]["value"], "not-a-uuid");
+        assert!(serde_json::to_value(CommandError::db_busy()).unwrap().get("details").is_none());
+    }
+
+    #[test]
+    fn test_project_errors() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+
+        let (code, details) = code_of(repo.resolve_project_ref("apollo"));
+        assert_eq!(code, codes::NOT_FOUND);
+        assert_eq!(details.unwrap()["entity"], "Project");
+
+        let mut project = Project::new("Apollo".to_string());
+        repo.create(&project).unwrap();
+        project.version += 1;
+        let (code, details) = code_of(repo.update(&project));
+        assert_eq!(code, codes::VERSION_CONFLICT);
+        assert_eq!(details.unwrap()["current"]["name"], "Apollo");
+    }
+
+    #[test]
+    fn test_person_errors() {
+        let conn = setup_test_db();
+        let repo = PersonRepository::new(&conn);
+        let ann = Person::new("ann@example.com".to_string(), "Ann Smith".to_string());
+        repo.create(&ann).unwrap();
+
+        assert_eq!(code_of(repo.create(&ann)).0, codes::CONFLICT);
+
+        let mut manages_herself = ann.clone();
+        manages_herself.manager = Some(ann.email.clone());
+        let (code, details) = code_of(repo.update(&manages_herself));
+        assert_eq!(code, codes::CYCLE);
+        assert_eq!(details.unwrap()["relation"], "manager");
+
+        let repo = PersonRepository::new(&conn).with_duplicate_check(0.8);
+        let annie = Person::new("annie@example.com".to_string(), "Ann Smith".to_string());
+        let (code, details) = code_of(repo.create(&annie));
+        assert_eq!(code, codes::POSSIBLE_DUPLICATE);
+        assert_eq!(details.unwrap()["matches"][0]["email"], "ann@example.com");
+
+        let allowed = vec!["company.com".to_string()];
+        let repo = PersonRepository::new(&conn).with_allowed_domains(&allowed);
+        let consultant = Person::new("consultant@gmail.com".to_string(), "Carl Consultant".to_string());
+        let (code, details) = code_of(repo.create(&consultant));
+        assert_eq!(code, codes::DOMAIN_NOT_ALLOWED);
+        assert_eq!(details, Some(json!({ "email": "consultant@gmail.com", "allowed": ["company.com"] })));
+    }
+
+    #[test]
+    fn test_note_errors() {
+        let conn = setup_test_db();
+        let people = PersonRepository::new(&conn);
+        let projects = ProjectRepository::new(&conn);
+
+        let note = PersonNote::new("nobody@example.com".to_string(), "1:1".to_string(), String::new());
+        let (code, details) = code_of(people.add_note(&note));
+        assert_eq!(code, codes::FOREIGN_KEY);
+        assert_eq!(details, Some(json!({ "field": "person_email" })));
+
+        let note = ProjectNote::new(Uuid::new_v4(), "Kickoff".to_string(), String::new());
+        assert_eq!(code_of(projects.add_project_note(&note)).0, codes::FOREIGN_KEY);
+        assert_eq!(code_of(projects.update_project_note(&note)).0, codes::NOT_FOUND);
+        assert_eq!(code_of(people.delete_note(&Uuid::new_v4())).0, codes::NOT_FOUND);
+    }
+
+    #[test]
+    fn test_anyhow_errors_keep_their_code() {
+        let wrapped = anyhow::Error::from(db::Error::not_found("Project", "apollo"));
+        assert_eq!(CommandError::from(wrapped).code, codes::NOT_FOUND);
+        let other = anyhow::anyhow!("Disk full").context("Failed to save avatar");
+        let error = CommandError::from(other);
+        assert_eq!((error.code, error.message.as_str()), (codes::ERROR, "Failed to save avatar: Disk full"));
+    }
+
+    // Run with UPDATE_ERROR_CODES=1 to rewrite the frontend's list after changing `codes::ALL`
+    #[test]
+    fn test_frontend_error_codes_match() {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../ui/src/services/errorCodes.json");
+        let expected: serde_json::Map<String, Value> = codes::ALL
+            .iter()
+            .map(|(code, meaning)| (code.to_string(), Value::from(*meaning)))
+            .collect();
+        if std::env::var_os("UPDATE_ERROR_CODES").is_some() {
+            let json = serde_json::to_string_pretty(&expected).unwrap();
+            std::fs::write(&path, json + "\n").unwrap();
+        }
+        let actual: serde_json::Map<String, Value> =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(actual, expected, "{} is out of date; run with UPDATE_ERROR_CODES=1", path.display());
+    }
+}
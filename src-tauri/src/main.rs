@@ -6,426 +6,2538 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use project_tracker::{
-    config::Config,
-    db::{self, Milestone, MilestoneNote, MilestoneResource, Person, Project, ProjectNote, ProjectResource, ProjectStakeholder, StakeholderNote, Team},
-    mcp::ProjectTrackerServer,
+    config::{Config, ConfigWatcher, SharedConfig},
+    core::recurrence,
+    diagram::{self, DiagramFormat, DiagramKind},
+    digest,
+    fixtures::{self, SeedSummary},
+    import_export::{self, DirectoryImportOptions, ImportReport, WorkbookSheet},
+    linkcheck::{self, HttpProbe},
+    db::{self, hygiene::HygieneReport, introspect::SchemaFormat, ActionItem, ActivityItem, Attachment, AvailabilityForecast, Backlink, BlockerEntry, BoardColumn, BudgetStatus, ChecklistItem, CustomField, CustomFieldType, Decision, DependencyGraph, EffortSummary, ExternalRef, ExternalRefTarget, FocusItem, InboxNote, Initiative, InitiativeProgress, LinkReport, LinkStatus, Milestone, MilestoneNote, MilestoneResource, MilestoneSlippage, NewNote, NoteTarget, NoteType, OneOnOne, Organization, OrganizationOverview, Person, PersonDeactivation, PersonNote, PersonSkill, PersonSuggestion, PhaseChange, PortfolioStats, Project, ProjectDashboard, ProjectDependency, ProjectDocument, ProjectExpense, ProjectNote, ProjectResource, ProjectRisk, ProjectSnapshot, ProjectStakeholder, ProjectStatus, ProjectSummary, ProjectWatcher, QuarterPlan, ReferenceKind, ResourceLimitWarning, ResourceSuggestion, Retrospective, RoleVariants, SnapshotDiff, StakeholderBrief, StakeholderMatrix, StakeholderNote, SubteamPolicy, Team, TeamAssignment, TeamTreeNode, User, UserRole},
+    mcp::sse::{SseController, SseStatus},
+    notes::{page_with_html, with_html, NotePage, RenderedNote},
+    notifications::{self, NotificationSettings},
+    report,
+    service::{auth, ProjectService},
+    storage::{self, BackupSettings, BackupStatus, HeartbeatGuard},
+    webhook::WebhookDispatcher,
+    utils, Storage,
 };
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::Utc;
 use rusqlite::Connection;
-use std::sync::{Arc, Mutex};
-use tauri::State;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard, RwLock, RwLockReadGuard};
+use std::time::Duration;
+use tauri::{ipc::Invoke, AppHandle, Emitter, Manager, State};
+use tauri_plugin_dialog::DialogExt;
+use tauri_plugin_notification::NotificationExt;
 use uuid::Uuid;
 
+mod avatar;
+mod error;
+
+use error::{codes, parse_id, CommandError};
+
 // Application state shared across Tauri commands
 struct AppState {
     db: Mutex<Connection>,
-    config: Arc<Config>,
+    config: SharedConfig,
+    config_watcher: Mutex<Option<ConfigWatcher>>,
+    webhooks: WebhookDispatcher,
+    people_index: Mutex<db::autocomplete::PeopleIndex>,
+    storage: RwLock<Storage>,
+    heartbeat: Mutex<Option<HeartbeatGuard>>,
+    sync_warning: Mutex<Option<String>>,
+    notification_settings: Mutex<NotificationSettings>,
+    workspace: Mutex<String>,
+    mcp: Arc<SseController>,
+}
+
+// How long db_ping waits for the database before reporting it busy
+const DB_PING_TIMEOUT: Duration = Duration::from_millis(250);
+
+// How often to check for milestone reminders
+const REMINDER_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+// How often to check whether today's database backup has been taken
+const BACKUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+// Lock the shared connection, giving up with DB_BUSY after the configured
+// timeout rather than blocking the command forever
+fn lock_db_within(state: &AppState, timeout: Duration) -> Result<MutexGuard<'_, Connection>, CommandError> {
+    utils::lock_with_timeout(&state.db, timeout).ok_or_else(|| {
+        log::warn!("Timed out after {:?} waiting for the database", timeout);
+        CommandError::db_busy()
+    })
+}
+
+fn lock_db(state: &AppState) -> Result<MutexGuard<'_, Connection>, CommandError> {
+    lock_db_within(state, Duration::from_millis(state.config.get().db_lock_timeout_ms))
+}
+
+// Resolve a project ID, slug or unique ID prefix. Full IDs are returned
+// without taking the database lock.
+fn project_ref(state: &AppState, reference: &str) -> Result<Uuid, CommandError> {
+    if let Ok(id) = Uuid::parse_str(reference) {
+        return Ok(id);
+    }
+    let db = lock_db(state)?;
+    db::ProjectRepository::new(&db).resolve_project_ref(reference).map_err(CommandError::from)
+}
+
+// The email to watch projects as: the one given, or the configured
+// current_user_email
+fn watcher_email(state: &AppState, email: Option<String>) -> Result<String, CommandError> {
+    email
+        .or_else(|| state.config.get().current_user_email.clone())
+        .ok_or_else(|| "Set current_user_email in the configuration to watch projects".into())
+}
+
+// The record an external reference is attached to: a project by ID or slug,
+// or a milestone by ID
+fn external_ref_target(state: &AppState, entity_type: &str, entity_id: &str) -> Result<(ExternalRefTarget, Uuid), CommandError> {
+    let target = entity_type.parse::<ExternalRefTarget>().map_err(CommandError::from)?;
+    let id = match target {
+        ExternalRefTarget::Project => project_ref(state, entity_id)?,
+        ExternalRefTarget::Milestone => parse_id(entity_id)?,
+    };
+    Ok((target, id))
+}
+
+// Files of the active workspace. Only switching workspaces writes to the lock,
+// while it holds the database.
+fn storage(state: &AppState) -> RwLockReadGuard<'_, Storage> {
+    state.storage.read().unwrap_or_else(|e| e.into_inner())
+}
+
+// Fill in the stored version for an update that didn't include one. Records
+// from older frontends deserialize with version 0; while unversioned updates
+// are allowed they overwrite the stored record, otherwise they are rejected.
+fn resolve_version(
+    version: &mut i64,
+    config: &Config,
+    stored: impl FnOnce() -> db::Result<Option<i64>>,
+) -> Result<(), CommandError> {
+    if *version != 0 {
+        return Ok(());
+    }
+    if !config.allow_unversioned_updates {
+        return Err("This update doesn't include the record's version, reload it and try again".into());
+    }
+    if let Some(current) = stored().map_err(CommandError::from)? {
+        *version = current;
+    }
+    Ok(())
 }
 
 // Tauri commands (IPC functions callable from frontend)
 
 #[tauri::command]
-async fn list_projects(state: State<'_, AppState>) -> Result<Vec<Project>, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+async fn list_projects(
+    only_watched: Option<bool>,
+    organization: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<Project>, CommandError> {
+    let email = match only_watched {
+        Some(true) => Some(watcher_email(&state, None)?),
+        _ => None,
+    };
+    let db = lock_db(&state)?;
+    let mut projects = match email {
+        Some(email) => db::WatcherRepository::new(&db).list_watched_projects(&email),
+        None => db::ProjectRepository::new(&db).list_all(),
+    }
+    .map_err(CommandError::from)?;
+    if let Some(organization) = organization {
+        let ids = db::OrganizationRepository::new(&db)
+            .get_project_ids_for_org(&organization)
+            .map_err(CommandError::from)?;
+        projects.retain(|p| ids.contains(&p.id));
+    }
+    Ok(projects)
+}
+
+#[tauri::command]
+async fn get_dashboard(state: State<'_, AppState>) -> Result<Vec<ProjectDashboard>, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.get_dashboard().map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn get_project(id: String, state: State<'_, AppState>) -> Result<Option<Project>, CommandError> {
+    let uuid = project_ref(&state, &id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.find_by_id(&uuid).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn list_project_summaries(state: State<'_, AppState>) -> Result<Vec<ProjectSummary>, CommandError> {
+    let config = state.config.get();
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db).with_checklist_progress(config.checklist_progress);
+    repo.list_project_summaries().map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn get_project_summary(id: String, state: State<'_, AppState>) -> Result<Option<ProjectSummary>, CommandError> {
+    let config = state.config.get();
+    let uuid = project_ref(&state, &id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db).with_checklist_progress(config.checklist_progress);
+    repo.get_project_summary(&uuid).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn get_slippage_report(
+    project_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<MilestoneSlippage>, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.get_slippage_report(&uuid).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn get_effort_summary(
+    project_id: String,
+    sort_by_variance: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<EffortSummary, CommandError> {
+    let config = state.config.get();
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    let mut summary = repo.get_effort_summary(&uuid, config.effort_warning_ratio).map_err(CommandError::from)?;
+    if sort_by_variance.unwrap_or(false) {
+        summary.sort_by_variance();
+    }
+    Ok(summary)
+}
+
+#[tauri::command]
+async fn get_portfolio_stats(state: State<'_, AppState>) -> Result<PortfolioStats, CommandError> {
+    let db = lock_db(&state)?;
+    db::stats::portfolio(&db).map_err(CommandError::from)
+}
+
+// Milestones due in a calendar quarter of the configured time zone, by team
+// and project, for the planning screen
+#[tauri::command]
+async fn get_quarter_plan(
+    year: i32,
+    quarter: u32,
+    team: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<QuarterPlan, CommandError> {
+    let config = state.config.get();
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.get_quarter_plan(year, quarter, config.tz(), team.as_deref()).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn get_hygiene_report(state: State<'_, AppState>) -> Result<HygieneReport, CommandError> {
+    let config = state.config.get();
+    let db = lock_db(&state)?;
+    db::hygiene::find_gaps_with(&db, &config.hygiene.checks, &config.allowed_email_domains).map_err(CommandError::from)
+}
+
+// Sent to the frontend as each link is checked
+#[derive(Clone, serde::Serialize)]
+struct LinkCheckProgress {
+    url: String,
+    status: LinkStatus,
+    checked: usize,
+    total: usize,
+}
+
+// Check the links of one project, or of every project, emitting
+// `link-check-progress` as results come in. The database is only locked to
+// read the links and save the results, not while the requests run.
+#[tauri::command]
+async fn check_links(
+    project_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<LinkReport>, CommandError> {
+    let config = state.config.get();
+    let project = project_id.map(|id| project_ref(&state, &id)).transpose()?;
+    let links = {
+        let db = lock_db(&state)?;
+        db::LinkCheckRepository::new(&db)
+            .with_jira_url(&config.jira_url)
+            .list_links(project.as_ref())
+            .map_err(CommandError::from)?
+    };
+
+    let probe = Arc::new(HttpProbe::new(&config.linkcheck).map_err(|e| e.to_string())?);
+    let checks = linkcheck::check_urls(
+        probe,
+        links.into_iter().map(|link| link.url),
+        &config.linkcheck,
+        |check, checked, total| {
+            let progress = LinkCheckProgress {
+                url: check.url.clone(),
+                status: check.status,
+                checked,
+                total,
+            };
+            if let Err(e) = app.emit("link-check-progress", progress) {
+                log::warn!("Failed to send link check progress: {}", e);
+            }
+        },
+    )
+    .await;
+
+    let db = lock_db(&state)?;
+    let repo = db::LinkCheckRepository::new(&db).with_jira_url(&config.jira_url);
+    repo.save_checks(&checks).map_err(CommandError::from)?;
+    repo.get_report(project.as_ref()).map_err(CommandError::from)
+}
+
+// A project's links with their last check, without checking them again
+#[tauri::command]
+async fn get_project_links(project_id: String, state: State<'_, AppState>) -> Result<Vec<LinkReport>, CommandError> {
+    let config = state.config.get();
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    db::LinkCheckRepository::new(&db)
+        .with_jira_url(&config.jira_url)
+        .get_report(Some(&uuid))
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn preview_digest(since: Option<String>, state: State<'_, AppState>) -> Result<String, CommandError> {
+    let config = state.config.get();
+    let tz = config.tz();
+    let now = Utc::now();
+    let since = match since {
+        Some(since) => utils::parse_user_date(&since, tz).map_err(|e| e.to_string())?,
+        None => now - chrono::Duration::days(digest::DEFAULT_LOOKBACK_DAYS),
+    };
+    let db = lock_db(&state)?;
+    let digest = digest::compose(&db, since, now, tz, config.current_user_email.as_deref()).map_err(CommandError::from)?;
+    Ok(digest.to_html(config.locale()))
+}
+
+// A team's quarterly review as markdown in the configured language, for the
+// team screen
+#[tauri::command]
+async fn generate_team_review(
+    team: String,
+    year: i32,
+    quarter: u32,
+    state: State<'_, AppState>,
+) -> Result<String, CommandError> {
+    let config = state.config.get();
+    let db = lock_db(&state)?;
+    let review = report::team_quarterly_review(&db, &team, year, quarter, config.tz()).map_err(CommandError::from)?;
+    Ok(review.to_markdown(config.locale()))
+}
+
+#[tauri::command]
+async fn create_project(
+    project: Project,
+    skip_bootstrap: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Project, CommandError> {
+    let config = state.config.get();
+    let db = lock_db(&state)?;
+    let mut service = ProjectService::new(&db, &state.webhooks)
+        .with_project_types(&config.project_types)
+        .with_phases(&config.project_phases)
+        .with_creator(config.current_user_email.as_deref());
+    if !skip_bootstrap.unwrap_or(false) {
+        service = service.with_bootstrap(&config.bootstrap);
+    }
+    service.create_project(&project).map_err(CommandError::from)?;
+    Ok(project)
+}
+
+#[tauri::command]
+async fn update_project(mut project: Project, state: State<'_, AppState>) -> Result<(), CommandError> {
+    let config = state.config.get();
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    resolve_version(&mut project.version, &config, || Ok(repo.find_by_id(&project.id)?.map(|p| p.version)))?;
+    let service = ProjectService::new(&db, &state.webhooks)
+        .with_project_types(&config.project_types)
+        .with_phases(&config.project_phases);
+    service.update_project(&project).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn delete_project(id: String, state: State<'_, AppState>) -> Result<(), CommandError> {
+    let uuid = project_ref(&state, &id)?;
+    let db = lock_db(&state)?;
+    let service = ProjectService::new(&db, &state.webhooks);
+    service.delete_project(&uuid).map_err(CommandError::from)?;
+    remove_orphaned_attachments(&state, &db);
+    Ok(())
+}
+
+#[tauri::command]
+async fn block_project(id: String, reason: String, state: State<'_, AppState>) -> Result<Project, CommandError> {
+    let uuid = project_ref(&state, &id)?;
+    if reason.trim().is_empty() {
+        return Err("A reason is required to block a project".into());
+    }
+    let db = lock_db(&state)?;
+    let service = ProjectService::new(&db, &state.webhooks);
+    service.block_project(&uuid, reason.trim()).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn unblock_project(id: String, state: State<'_, AppState>) -> Result<Project, CommandError> {
+    let uuid = project_ref(&state, &id)?;
+    let db = lock_db(&state)?;
+    let service = ProjectService::new(&db, &state.webhooks);
+    service.unblock_project(&uuid).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn set_project_phase(id: String, phase: String, state: State<'_, AppState>) -> Result<Project, CommandError> {
+    let uuid = project_ref(&state, &id)?;
+    let config = state.config.get();
+    let db = lock_db(&state)?;
+    let service = ProjectService::new(&db, &state.webhooks).with_phases(&config.project_phases);
+    service.set_project_phase(&uuid, &phase).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn get_phase_history(project_id: String, state: State<'_, AppState>) -> Result<Vec<PhaseChange>, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.get_phase_history(&uuid).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn list_projects_by_phase(phase: String, state: State<'_, AppState>) -> Result<Vec<Project>, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.list_by_phase(&phase).map_err(CommandError::from)
+}
+
+// Retrospective commands
+
+// A project has one retrospective, so saving replaces the one it has
+#[tauri::command]
+async fn save_retrospective(mut retrospective: Retrospective, state: State<'_, AppState>) -> Result<Retrospective, CommandError> {
+    retrospective.updated_at = Utc::now();
+    let db = lock_db(&state)?;
+    db::RetrospectiveRepository::new(&db).save(&retrospective).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn get_retrospective(project_id: String, state: State<'_, AppState>) -> Result<Option<Retrospective>, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    db::RetrospectiveRepository::new(&db).find_by_project(&uuid).map_err(CommandError::from)
+}
+
+// Decision commands
+
+#[tauri::command]
+async fn record_decision(decision: Decision, state: State<'_, AppState>) -> Result<Decision, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::DecisionRepository::new(&db);
+    repo.create(&decision).map_err(CommandError::from)?;
+    repo.find_by_id(&decision.id)
+        .map_err(CommandError::from)?
+        .ok_or_else(|| CommandError::from(db::Error::not_found("Decision", decision.id)))
+}
+
+#[tauri::command]
+async fn list_decisions(project_id: String, state: State<'_, AppState>) -> Result<Vec<Decision>, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    db::DecisionRepository::new(&db).list_decisions(&uuid).map_err(CommandError::from)
+}
+
+// Returns the old decision, now superseded
+#[tauri::command]
+async fn supersede_decision(old_id: String, new_id: String, state: State<'_, AppState>) -> Result<Decision, CommandError> {
+    let old_uuid = parse_id(&old_id)?;
+    let new_uuid = parse_id(&new_id)?;
+    let db = lock_db(&state)?;
+    db::DecisionRepository::new(&db).supersede_decision(&old_uuid, &new_uuid).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn watch_project(id: String, email: Option<String>, state: State<'_, AppState>) -> Result<Vec<ProjectWatcher>, CommandError> {
+    let uuid = project_ref(&state, &id)?;
+    let email = watcher_email(&state, email)?;
+    let db = lock_db(&state)?;
+    let repo = db::WatcherRepository::new(&db);
+    repo.watch(&uuid, &email).map_err(CommandError::from)?;
+    repo.list_watchers(&uuid).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn unwatch_project(id: String, email: Option<String>, state: State<'_, AppState>) -> Result<Vec<ProjectWatcher>, CommandError> {
+    let uuid = project_ref(&state, &id)?;
+    let email = watcher_email(&state, email)?;
+    let db = lock_db(&state)?;
+    let repo = db::WatcherRepository::new(&db);
+    repo.unwatch(&uuid, &email).map_err(CommandError::from)?;
+    repo.list_watchers(&uuid).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn get_focus_list(email: Option<String>, state: State<'_, AppState>) -> Result<Vec<FocusItem>, CommandError> {
+    let config = state.config.get();
+    let email = email
+        .or_else(|| config.current_user_email.clone())
+        .ok_or_else(|| CommandError::from("Set current_user_email in the configuration to see the focus list"))?;
+    let db = lock_db(&state)?;
+    db::ProjectRepository::new(&db)
+        .with_stakeholder_contact_days(config.stakeholder_contact_days)
+        .get_focus_list(&email, Utc::now())
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn add_external_ref(
+    entity_type: String,
+    entity_id: String,
+    system: String,
+    key: String,
+    url: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<ExternalRef>, CommandError> {
+    let (target, id) = external_ref_target(&state, &entity_type, &entity_id)?;
+    let templates = state.config.get().external_ref_url_templates();
+    let db = lock_db(&state)?;
+    let repo = db::ExternalRefRepository::new(&db).with_url_templates(&templates);
+    repo.add(target, &id, &system, &key, url.as_deref()).map_err(CommandError::from)?;
+    repo.get_external_refs(target, &id).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn list_external_refs(entity_type: String, entity_id: String, state: State<'_, AppState>) -> Result<Vec<ExternalRef>, CommandError> {
+    let (target, id) = external_ref_target(&state, &entity_type, &entity_id)?;
+    let templates = state.config.get().external_ref_url_templates();
+    let db = lock_db(&state)?;
+    let repo = db::ExternalRefRepository::new(&db).with_url_templates(&templates);
+    repo.get_external_refs(target, &id).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn remove_external_ref(
+    entity_type: String,
+    entity_id: String,
+    system: String,
+    key: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<ExternalRef>, CommandError> {
+    let (target, id) = external_ref_target(&state, &entity_type, &entity_id)?;
+    let templates = state.config.get().external_ref_url_templates();
+    let db = lock_db(&state)?;
+    let repo = db::ExternalRefRepository::new(&db).with_url_templates(&templates);
+    repo.remove(target, &id, &system, &key).map_err(CommandError::from)?;
+    repo.get_external_refs(target, &id).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn set_project_slug(id: String, slug: Option<String>, state: State<'_, AppState>) -> Result<String, CommandError> {
+    let uuid = project_ref(&state, &id)?;
+    let db = lock_db(&state)?;
+    let service = ProjectService::new(&db, &state.webhooks);
+    service.set_slug(&uuid, slug.as_deref().filter(|s| !s.trim().is_empty())).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn list_blocked_projects(state: State<'_, AppState>) -> Result<Vec<Project>, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.list_blocked().map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn get_board(state: State<'_, AppState>) -> Result<Vec<BoardColumn>, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.get_board().map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn reorder_project(
+    id: String,
+    status: ProjectStatus,
+    position: usize,
+    state: State<'_, AppState>,
+) -> Result<Project, CommandError> {
+    let uuid = project_ref(&state, &id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.reorder_project(&uuid, status, position).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn get_blocker_history(project_id: String, state: State<'_, AppState>) -> Result<Vec<BlockerEntry>, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.get_blocker_history(&uuid).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn get_project_activity(
+    project_id: String,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<ActivityItem>, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    let limit = limit.unwrap_or(db::project_repo::DEFAULT_ACTIVITY_LIMIT);
+    repo.get_activity_feed(&uuid, limit).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn suggest_project_due_date(project_id: String, state: State<'_, AppState>) -> Result<Option<String>, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    let suggested = repo.suggest_project_due_date(&uuid).map_err(CommandError::from)?;
+    Ok(suggested.map(|date| date.to_rfc3339()))
+}
+
+#[tauri::command]
+async fn get_milestone_date_warnings(milestone: Milestone, state: State<'_, AppState>) -> Result<Vec<String>, CommandError> {
+    let config = state.config.get();
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    let mut warnings = repo.milestone_date_warnings(&milestone).map_err(CommandError::from)?;
+    warnings.extend(milestone.effort_warning(config.effort_warning_ratio));
+    Ok(warnings)
+}
+
+#[tauri::command]
+async fn get_project_milestones(
+    project_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<Milestone>, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.get_milestones(&uuid).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn get_project_stakeholders(
+    project_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<ProjectStakeholder>, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.get_stakeholders(&uuid).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn add_project_milestone(
+    milestone: Milestone,
+    state: State<'_, AppState>,
+) -> Result<Milestone, CommandError> {
+    validate_recurrence_rule(&milestone)?;
+    let db = lock_db(&state)?;
+    let service = ProjectService::new(&db, &state.webhooks);
+    service.add_milestone(&milestone).map_err(CommandError::from)?;
+    Ok(milestone)
+}
+
+#[tauri::command]
+async fn update_milestone(mut milestone: Milestone, state: State<'_, AppState>) -> Result<(), CommandError> {
+    let config = state.config.get();
+    validate_recurrence_rule(&milestone)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    resolve_version(&mut milestone.version, &config, || {
+        Ok(repo.find_milestone_by_id(&milestone.id)?.map(|m| m.version))
+    })?;
+    let service = ProjectService::new(&db, &state.webhooks);
+    service.update_milestone(&milestone).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn delete_milestone(id: String, state: State<'_, AppState>) -> Result<(), CommandError> {
+    let uuid = parse_id(&id)?;
+    let db = lock_db(&state)?;
+    let service = ProjectService::new(&db, &state.webhooks);
+    service.delete_milestone(&uuid).map_err(CommandError::from)?;
+    remove_orphaned_attachments(&state, &db);
+    Ok(())
+}
+
+#[tauri::command]
+async fn delete_milestone_series(
+    id: String,
+    keep_occurrences: bool,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let uuid = parse_id(&id)?;
+    let db = lock_db(&state)?;
+    let service = ProjectService::new(&db, &state.webhooks);
+    service.delete_milestone_series(&uuid, keep_occurrences).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn materialize_recurring_milestones(
+    horizon_months: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<usize, CommandError> {
+    let config = state.config.get();
+    let horizon_months = horizon_months.unwrap_or(config.recurrence_horizon_months);
+    let db = lock_db(&state)?;
+    Ok(recurrence::materialize_recurring_milestones(&db, horizon_months).map_err(|e| e.to_string())?)
+}
+
+fn validate_recurrence_rule(milestone: &Milestone) -> Result<(), CommandError> {
+    if let Some(rule) = &milestone.recurrence_rule {
+        rule.parse::<recurrence::RecurrenceRule>().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn add_project_stakeholder(
+    project_id: String,
+    stakeholder: ProjectStakeholder,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let config = state.config.get();
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db).with_roles(&config.roles, config.allow_new_roles);
+    repo.add_stakeholder(&uuid, &stakeholder)
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn list_people(
+    include_inactive: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<Person>, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::PersonRepository::new(&db);
+    repo.list_all(include_inactive.unwrap_or(false))
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn search_people(
+    query: String,
+    include_inactive: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<Person>, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::PersonRepository::new(&db);
+    repo.search_by_name(&query, include_inactive.unwrap_or(false))
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn autocomplete_people(
+    query: String,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<PersonSuggestion>, CommandError> {
+    let db = lock_db(&state)?;
+    // Only taken while holding the database lock, so it's never contended;
+    // a panic while it was held leaves nothing worse than a stale index
+    let mut index = utils::lock_with_timeout(&state.people_index, Duration::ZERO).ok_or_else(CommandError::db_busy)?;
+    db::autocomplete::autocomplete_people(&db, &mut index, &query, limit.unwrap_or(10))
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn get_person(email: String, state: State<'_, AppState>) -> Result<Option<Person>, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::PersonRepository::new(&db);
+    repo.find_by_email(&email).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn create_person(
+    person: Person,
+    force: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Person, CommandError> {
+    let config = state.config.get();
+    let db = lock_db(&state)?;
+    let mut repo = db::PersonRepository::new(&db).with_allowed_domains(&config.allowed_email_domains);
+    if !force.unwrap_or(false) {
+        repo = repo.with_duplicate_check(config.person_duplicate_threshold);
+    }
+    repo.create(&person).map_err(CommandError::from)?;
+    Ok(person)
+}
+
+#[tauri::command]
+async fn update_person(mut person: Person, state: State<'_, AppState>) -> Result<(), CommandError> {
+    let config = state.config.get();
+    let db = lock_db(&state)?;
+    let repo = db::PersonRepository::new(&db).with_allowed_domains(&config.allowed_email_domains);
+    resolve_version(&mut person.version, &config, || Ok(repo.find_by_email(&person.email)?.map(|p| p.version)))?;
+    repo.update(&person).map_err(CommandError::from)?;
+    Ok(())
+}
+
+#[tauri::command]
+async fn delete_person(
+    email: String,
+    delete_one_on_ones: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::PersonRepository::new(&db);
+    let person = repo.find_by_email(&email).map_err(CommandError::from)?;
+    repo.delete(&email, delete_one_on_ones.unwrap_or(false)).map_err(CommandError::from)?;
+    if let Some(avatar_path) = person.and_then(|p| p.avatar_path) {
+        remove_avatar_file(&state, &avatar_path);
+    }
+    Ok(())
+}
+
+// Remove an avatar file that is no longer referenced. A failure only leaves
+// an orphan for `track db check` to sweep, so it is logged, not returned.
+fn remove_avatar_file(state: &AppState, avatar_path: &str) {
+    if let Err(e) = storage(state).remove_avatar(avatar_path) {
+        log::warn!("Failed to remove avatar {}: {:#}", avatar_path, e);
+    }
+}
+
+#[tauri::command]
+async fn get_avatar(email: String, state: State<'_, AppState>) -> Result<Option<String>, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::PersonRepository::new(&db);
+    let person = repo
+        .find_by_email(&email)
+        .map_err(CommandError::from)?
+        .ok_or_else(|| CommandError::from(db::Error::not_found("Person", &email)))?;
+    match person.avatar_path {
+        Some(path) => {
+            let bytes = storage(&state).read_avatar(&path).map_err(CommandError::from)?;
+            Ok(Some(BASE64.encode(bytes)))
+        }
+        None => Ok(None),
+    }
+}
+
+#[tauri::command]
+async fn set_avatar(email: String, data: String, state: State<'_, AppState>) -> Result<Person, CommandError> {
+    let bytes = BASE64.decode(data.trim()).map_err(|e| format!("Invalid avatar data: {}", e))?;
+    let db = lock_db(&state)?;
+    let repo = db::PersonRepository::new(&db);
+    if repo.find_by_email(&email).map_err(CommandError::from)?.is_none() {
+        return Err(CommandError::from(db::Error::not_found("Person", &email)));
+    }
+
+    let path = storage(&state).save_avatar(&email, &bytes).map_err(CommandError::from)?;
+    let path = path.to_string_lossy().into_owned();
+    let previous = repo.set_avatar_path(&email, Some(path.as_str())).map_err(CommandError::from)?;
+    if let Some(previous) = previous.filter(|previous| *previous != path) {
+        remove_avatar_file(&state, &previous);
+    }
+    repo.find_by_email(&email)
+        .map_err(CommandError::from)?
+        .ok_or_else(|| CommandError::from(db::Error::not_found("Person", &email)))
+}
+
+#[tauri::command]
+async fn clear_avatar(email: String, state: State<'_, AppState>) -> Result<Person, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::PersonRepository::new(&db);
+    if let Some(previous) = repo.set_avatar_path(&email, None).map_err(CommandError::from)? {
+        remove_avatar_file(&state, &previous);
+    }
+    repo.find_by_email(&email)
+        .map_err(CommandError::from)?
+        .ok_or_else(|| CommandError::from(db::Error::not_found("Person", &email)))
+}
+
+#[tauri::command]
+async fn deactivate_person(
+    email: String,
+    remove_future_assignments: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<PersonDeactivation, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::PersonRepository::new(&db);
+    let deactivation = repo
+        .deactivate(&email, remove_future_assignments.unwrap_or(false))
+        .map_err(CommandError::from)?;
+    Ok(deactivation)
+}
+
+#[tauri::command]
+async fn reactivate_person(email: String, state: State<'_, AppState>) -> Result<Person, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::PersonRepository::new(&db);
+    let person = repo.reactivate(&email).map_err(CommandError::from)?;
+    Ok(person)
+}
+
+#[tauri::command]
+async fn change_person_email(
+    old_email: String,
+    new_email: String,
+    state: State<'_, AppState>,
+) -> Result<Person, CommandError> {
+    let config = state.config.get();
+    let db = lock_db(&state)?;
+    let repo = db::PersonRepository::new(&db).with_allowed_domains(&config.allowed_email_domains);
+    let person = repo.change_email(&old_email, &new_email).map_err(CommandError::from)?;
+    Ok(person)
+}
+
+#[tauri::command]
+async fn add_person_skill(
+    person_email: String,
+    skill: String,
+    level: Option<u8>,
+    state: State<'_, AppState>,
+) -> Result<PersonSkill, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::PersonRepository::new(&db);
+    repo.add_skill(&person_email, &skill, level).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn remove_person_skill(person_email: String, skill: String, state: State<'_, AppState>) -> Result<(), CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::PersonRepository::new(&db);
+    repo.remove_skill(&person_email, &skill).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn find_people_by_skill(
+    skill: String,
+    min_level: Option<u8>,
+    state: State<'_, AppState>,
+) -> Result<Vec<Person>, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::PersonRepository::new(&db);
+    repo.find_people_by_skill(&skill, min_level).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn get_availability_forecast(
+    horizon_days: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<AvailabilityForecast, CommandError> {
+    let horizon_days = horizon_days.unwrap_or(db::person_repo::DEFAULT_FORECAST_DAYS);
+    let db = lock_db(&state)?;
+    db::PersonRepository::new(&db)
+        .get_availability_forecast(horizon_days, Utc::now())
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn import_directory(
+    contents: String,
+    options: DirectoryImportOptions,
+    state: State<'_, AppState>,
+) -> Result<ImportReport, CommandError> {
+    let db = lock_db(&state)?;
+    let report = import_export::import_directory_json(&db, contents.as_bytes(), &options).map_err(CommandError::from)?;
+    Ok(report)
+}
+
+// Team commands
+
+#[tauri::command]
+async fn list_teams(state: State<'_, AppState>) -> Result<Vec<Team>, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::TeamRepository::new(&db);
+    repo.list_all().map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn get_team(name: String, state: State<'_, AppState>) -> Result<Option<Team>, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::TeamRepository::new(&db);
+    repo.find_by_name(&name).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn create_team(team: Team, state: State<'_, AppState>) -> Result<Team, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::TeamRepository::new(&db);
+    repo.create(&team).map_err(CommandError::from)?;
+    Ok(team)
+}
+
+#[tauri::command]
+async fn update_team(team: Team, state: State<'_, AppState>) -> Result<(), CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::TeamRepository::new(&db);
+    repo.update(&team).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn delete_team(
+    name: String,
+    subteams: Option<SubteamPolicy>,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::TeamRepository::new(&db);
+    repo.delete(&name, subteams.unwrap_or(SubteamPolicy::Refuse)).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn list_organizations(state: State<'_, AppState>) -> Result<Vec<Organization>, CommandError> {
+    let db = lock_db(&state)?;
+    db::OrganizationRepository::new(&db).list_all().map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn create_organization(organization: Organization, state: State<'_, AppState>) -> Result<Organization, CommandError> {
+    let db = lock_db(&state)?;
+    db::OrganizationRepository::new(&db).create(&organization).map_err(CommandError::from)?;
+    Ok(organization)
+}
+
+#[tauri::command]
+async fn update_organization(organization: Organization, state: State<'_, AppState>) -> Result<(), CommandError> {
+    let db = lock_db(&state)?;
+    db::OrganizationRepository::new(&db).update(&organization).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn delete_organization(name: String, state: State<'_, AppState>) -> Result<(), CommandError> {
+    let db = lock_db(&state)?;
+    db::OrganizationRepository::new(&db).delete(&name).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn get_organization_overview(name: String, state: State<'_, AppState>) -> Result<OrganizationOverview, CommandError> {
+    let db = lock_db(&state)?;
+    db::OrganizationRepository::new(&db).get_overview(&name).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn get_team_tree(state: State<'_, AppState>) -> Result<Vec<TeamTreeNode>, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::TeamRepository::new(&db);
+    repo.get_team_tree().map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn search_teams(query: String, state: State<'_, AppState>) -> Result<Vec<Team>, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::TeamRepository::new(&db);
+    repo.search_by_name(&query).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn autocomplete_teams(
+    query: String,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, CommandError> {
+    let db = lock_db(&state)?;
+    db::autocomplete::autocomplete_teams(&db, &query, limit.unwrap_or(10)).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn autocomplete_roles(
+    query: String,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, CommandError> {
+    let config = state.config.get();
+    let db = lock_db(&state)?;
+    db::RoleRepository::new(&db)
+        .with_roles(&config.roles, config.allow_new_roles)
+        .autocomplete(&query, limit.unwrap_or(10))
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn list_role_variants(state: State<'_, AppState>) -> Result<Vec<RoleVariants>, CommandError> {
+    let db = lock_db(&state)?;
+    db::RoleRepository::new(&db).find_variants().map_err(CommandError::from)
+}
+
+// Replace a role with another on every resource and stakeholder, returning
+// how many were changed
+#[tauri::command]
+async fn merge_roles(old: String, new: String, state: State<'_, AppState>) -> Result<usize, CommandError> {
+    let config = state.config.get();
+    let db = lock_db(&state)?;
+    db::RoleRepository::new(&db)
+        .with_roles(&config.roles, config.allow_new_roles)
+        .merge_roles(&old, &new)
+        .map_err(CommandError::from)
+}
+
+// Fill the database with generated demo data; only available in debug builds
+#[tauri::command]
+async fn seed_demo_data(
+    seed: Option<u64>,
+    force: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<SeedSummary, CommandError> {
+    if !cfg!(debug_assertions) {
+        return Err("Demo data can only be seeded in debug builds".into());
+    }
+    let db = lock_db(&state)?;
+    if !force.unwrap_or(false) && !fixtures::is_empty(&db).map_err(CommandError::from)? {
+        return Err("The database isn't empty; seed with force to add demo data anyway".into());
+    }
+    let summary = fixtures::seed_demo_data(&db, seed.unwrap_or(fixtures::DEFAULT_SEED)).map_err(CommandError::from)?;
+    Ok(summary)
+}
+
+// Describe the database schema as markdown or DOT; only available in debug builds
+#[tauri::command]
+async fn get_database_schema(format: Option<String>, state: State<'_, AppState>) -> Result<String, CommandError> {
+    if !cfg!(debug_assertions) {
+        return Err("The database schema can only be shown in debug builds".into());
+    }
+    let format = match format {
+        Some(format) => format.parse::<SchemaFormat>().map_err(CommandError::from)?,
+        None => SchemaFormat::default(),
+    };
+    let db = lock_db(&state)?;
+    let description = db::introspect::describe_schema(&db).map_err(CommandError::from)?;
+    Ok(description.render(format))
+}
+
+#[tauri::command]
+async fn add_team_member(
+    team_name: String,
+    person_email: String,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::TeamRepository::new(&db);
+    repo.add_member(&team_name, &person_email).map_err(CommandError::from)?;
+    Ok(())
+}
+
+#[tauri::command]
+async fn remove_team_member(
+    team_name: String,
+    person_email: String,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::TeamRepository::new(&db);
+    repo.remove_member(&team_name, &person_email).map_err(CommandError::from)?;
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_team_members(
+    team_name: String,
+    include_subteams: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<Person>, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::TeamRepository::new(&db);
+    if include_subteams.unwrap_or(false) {
+        repo.get_members_recursive(&team_name).map_err(CommandError::from)
+    } else {
+        repo.get_members(&team_name).map_err(CommandError::from)
+    }
+}
+
+#[tauri::command]
+async fn get_jira_url(state: State<'_, AppState>) -> Result<String, CommandError> {
+    let config = state.config.get();
+    Ok(config.jira_url.clone())
+}
+
+#[tauri::command]
+async fn get_timezone(state: State<'_, AppState>) -> Result<String, CommandError> {
+    let config = state.config.get();
+    Ok(config.timezone.clone())
+}
+
+#[tauri::command]
+async fn get_default_email_domain(state: State<'_, AppState>) -> Result<String, CommandError> {
+    let config = state.config.get();
+    Ok(config.default_email_domain.clone())
+}
+
+#[tauri::command]
+async fn get_project_types(state: State<'_, AppState>) -> Result<Vec<String>, CommandError> {
+    let config = state.config.get();
+    Ok(config.project_types.clone())
+}
+
+#[tauri::command]
+async fn get_project_phases(state: State<'_, AppState>) -> Result<Vec<String>, CommandError> {
+    let config = state.config.get();
+    Ok(config.project_phases.clone())
+}
+
+#[tauri::command]
+async fn get_schema_info(state: State<'_, AppState>) -> Result<db::schema::SchemaInfo, CommandError> {
+    let db = lock_db(&state)?;
+    Ok(db::schema::get_schema_info(&db).map_err(|e| e.to_string())?)
+}
+
+// Check whether the database is free, waiting only briefly. The frontend polls
+// this to show when the database is busy with a long-running command.
+#[tauri::command]
+async fn db_ping(state: State<'_, AppState>) -> Result<(), CommandError> {
+    let db = lock_db_within(&state, DB_PING_TIMEOUT)?;
+    db.query_row("SELECT 1", [], |_| Ok(())).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+async fn list_project_types_in_use(state: State<'_, AppState>) -> Result<Vec<String>, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.list_project_types_in_use().map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn get_document_types(state: State<'_, AppState>) -> Result<Vec<String>, CommandError> {
+    let config = state.config.get();
+    Ok(config.document_types.clone())
+}
+
+#[tauri::command]
+async fn get_mcp_port(state: State<'_, AppState>) -> Result<u16, CommandError> {
+    let config = state.config.get();
+    Ok(config.mcp_http_port)
+}
+
+// Start the MCP HTTP server, if it isn't running, and return its status. A
+// port that can't be bound is reported as an error.
+#[tauri::command]
+async fn start_mcp_server(state: State<'_, AppState>) -> Result<SseStatus, CommandError> {
+    state.mcp.start().await.map_err(CommandError::from)?;
+    Ok(state.mcp.status().await)
+}
+
+#[tauri::command]
+async fn stop_mcp_server(state: State<'_, AppState>) -> Result<SseStatus, CommandError> {
+    state.mcp.stop().await;
+    Ok(state.mcp.status().await)
+}
+
+#[tauri::command]
+async fn get_mcp_status(state: State<'_, AppState>) -> Result<SseStatus, CommandError> {
+    Ok(state.mcp.status().await)
+}
+
+// Custom field commands
+
+#[tauri::command]
+async fn list_custom_fields(state: State<'_, AppState>) -> Result<Vec<CustomField>, CommandError> {
+    let db = lock_db(&state)?;
+    db::CustomFieldRepository::new(&db).list_custom_fields().map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn define_custom_field(key: String, label: String, field_type: CustomFieldType, state: State<'_, AppState>) -> Result<CustomField, CommandError> {
+    let db = lock_db(&state)?;
+    db::CustomFieldRepository::new(&db).define_custom_field(&key, &label, field_type).map_err(CommandError::from)
+}
+
+// Delete a custom field; with `force` its values on projects are deleted too.
+// Returns the number of values deleted.
+#[tauri::command]
+async fn delete_custom_field(key: String, force: Option<bool>, state: State<'_, AppState>) -> Result<usize, CommandError> {
+    let db = lock_db(&state)?;
+    db::CustomFieldRepository::new(&db).delete_custom_field(&key, force.unwrap_or(false)).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn get_project_custom_values(project_id: String, state: State<'_, AppState>) -> Result<BTreeMap<String, String>, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    db::CustomFieldRepository::new(&db).get_custom_values(&uuid).map_err(CommandError::from)
+}
+
+// Set or clear a project's value for a custom field, returning the value as stored
+#[tauri::command]
+async fn set_project_custom_value(
+    project_id: String,
+    key: String,
+    value: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    db::CustomFieldRepository::new(&db).set_custom_value(&uuid, &key, value.as_deref()).map_err(CommandError::from)
+}
+
+// User commands
+
+// Check current_user_email's role before a command runs; see
+// project_tracker::service::auth. Everyone may read, so reads don't wait for
+// the database.
+fn authorize_command(state: &AppState, command: &str) -> Result<(), CommandError> {
+    if auth::operation(command) == auth::Operation::Read {
+        return Ok(());
+    }
+    let db = lock_db(state)?;
+    auth::authorize_user(&db, state.config.get().current_user_email.as_deref(), command).map_err(CommandError::from)
+}
+
+// Run the permission check before every command
+fn authorized(handler: impl Fn(Invoke) -> bool + Send + Sync + 'static) -> impl Fn(Invoke) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        let webview = invoke.message.webview();
+        let state = webview.state::<AppState>();
+        if let Err(e) = authorize_command(&state, invoke.message.command()) {
+            invoke.resolver.reject(e);
+            return true;
+        }
+        handler(invoke)
+    }
+}
+
+// The local user's role, so the UI can leave out what they can't do
+#[derive(serde::Serialize)]
+struct CurrentRole {
+    email: Option<String>,
+    role: UserRole,
+    // False while nobody is listed, when everyone is an admin
+    permissions_enabled: bool,
+}
+
+#[tauri::command]
+async fn get_current_role(state: State<'_, AppState>) -> Result<CurrentRole, CommandError> {
+    let email = state.config.get().current_user_email.clone();
+    let db = lock_db(&state)?;
+    let users = db::UserRepository::new(&db);
+    Ok(CurrentRole {
+        role: users.effective_role(email.as_deref()).map_err(CommandError::from)?,
+        permissions_enabled: users.permissions_enabled().map_err(CommandError::from)?,
+        email,
+    })
+}
+
+#[tauri::command]
+async fn list_users(state: State<'_, AppState>) -> Result<Vec<User>, CommandError> {
+    let db = lock_db(&state)?;
+    db::UserRepository::new(&db).list_all().map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn set_user_role(email: String, role: UserRole, state: State<'_, AppState>) -> Result<User, CommandError> {
+    let acting = state.config.get().current_user_email.clone();
+    let db = lock_db(&state)?;
+    db::UserRepository::new(&db).set_role(acting.as_deref(), &email, role).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn remove_user(email: String, state: State<'_, AppState>) -> Result<(), CommandError> {
+    let acting = state.config.get().current_user_email.clone();
+    let db = lock_db(&state)?;
+    db::UserRepository::new(&db).remove(acting.as_deref(), &email).map_err(CommandError::from)
+}
+
+// Workspace commands
+
+// A workspace's database and file storage, and this app's claim on them
+struct OpenWorkspace {
+    conn: Connection,
+    storage: Storage,
+    // Tells other machines the database is open here, while it is held
+    heartbeat: Option<HeartbeatGuard>,
+    // Why keeping the data directory where it is may be unsafe
+    sync_warning: Option<String>,
+}
+
+// Open a workspace's database, creating it on first use, and its file storage.
+// In sync safety lock mode this fails while another machine has it open.
+fn open_workspace(config: &Config, name: &str) -> project_tracker::Result<OpenWorkspace> {
+    let storage = Storage::new(config.workspace_dir(name)?)?
+        .with_image_processor(Arc::new(avatar::AvatarProcessor))
+        .with_attachment_limits(config.max_attachment_bytes, config.max_attachment_storage_bytes);
+    let sync_warning = storage.check_sync_safety(&config.sync_safety)?;
+
+    let conn = db::open_database(config.database_path_for(name)?)?;
+
+    // The index behind the unique project name policy lives in the database,
+    // so it also covers the MCP server's connection
+    if let Err(e) = db::schema::set_unique_project_names(&conn, config.enforce_unique_project_names) {
+        log::warn!("Failed to apply the unique project name policy: {:#}", e);
+    }
+
+    // Make sure upcoming occurrences of recurring milestones exist
+    match recurrence::materialize_recurring_milestones(&conn, config.recurrence_horizon_months) {
+        Ok(created) => log::info!("Materialized {} recurring milestone occurrences", created),
+        Err(e) => log::error!("Failed to materialize recurring milestones: {}", e),
+    }
+
+    let heartbeat = storage.start_heartbeat("project-tracker", &config.sync_safety)?;
+
+    Ok(OpenWorkspace {
+        conn,
+        storage,
+        heartbeat,
+        sync_warning,
+    })
+}
+
+#[tauri::command]
+async fn list_workspaces(state: State<'_, AppState>) -> Result<Vec<String>, CommandError> {
+    let config = state.config.get();
+    Ok(config.workspace_names())
+}
+
+#[tauri::command]
+async fn get_active_workspace(state: State<'_, AppState>) -> Result<String, CommandError> {
+    Ok(state.workspace.lock().unwrap_or_else(|e| e.into_inner()).clone())
+}
+
+// Warning to show while the workspace's data directory is in a cloud-synced folder
+#[tauri::command]
+async fn get_sync_warning(state: State<'_, AppState>) -> Result<Option<String>, CommandError> {
+    Ok(state.sync_warning.lock().map_err(|e| e.to_string())?.clone())
+}
+
+// Close the current workspace's database and open another one. Commands
+// already holding the database finish first; if they take longer than the
+// lock timeout the switch is rejected as busy and nothing changes. The MCP
+// HTTP server is moved to the new database first, so an assistant never
+// keeps writing to the workspace the app left.
+#[tauri::command]
+async fn switch_workspace(name: String, state: State<'_, AppState>) -> Result<String, CommandError> {
+    let config = state.config.get();
+    if !config.has_workspace(&name) {
+        return Err(CommandError::new(codes::NOT_FOUND, format!("Unknown workspace '{}'", name)));
+    }
+
+    let opened = open_workspace(&config, &name).map_err(CommandError::from)?;
+    let previous = state.workspace.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    state.mcp.switch_workspace(&name).await.map_err(CommandError::from)?;
+    if let Err(e) = use_workspace(&state, &name, opened) {
+        if let Err(e) = state.mcp.switch_workspace(&previous).await {
+            log::error!("Failed to move the MCP server back to workspace {}: {:#}", previous, e);
+        }
+        return Err(e);
+    }
+
+    log::info!("Switched to workspace {}", name);
+    Ok(name)
+}
+
+// Swap the app's database and storage for an opened workspace's
+fn use_workspace(state: &AppState, name: &str, opened: OpenWorkspace) -> Result<(), CommandError> {
+    let mut db = lock_db(state)?;
+    *db = opened.conn;
+    *state.storage.write().unwrap_or_else(|e| e.into_inner()) = opened.storage;
+    *state.heartbeat.lock().unwrap_or_else(|e| e.into_inner()) = opened.heartbeat;
+    *state.sync_warning.lock().unwrap_or_else(|e| e.into_inner()) = opened.sync_warning;
+    *state.workspace.lock().unwrap_or_else(|e| e.into_inner()) = name.to_string();
+    // The autocomplete index was built from the old database. It's only
+    // taken while holding the database lock, so it's free here.
+    if let Some(mut index) = utils::lock_with_timeout(&state.people_index, Duration::ZERO) {
+        index.invalidate();
+    }
+    Ok(())
+}
+
+// Notification commands
+
+#[tauri::command]
+async fn get_notification_settings(state: State<'_, AppState>) -> Result<NotificationSettings, CommandError> {
+    Ok(*state.notification_settings.lock().map_err(|e| e.to_string())?)
+}
+
+// Change the reminder settings and save them to the config file
+#[tauri::command]
+async fn set_notification_settings(settings: NotificationSettings, state: State<'_, AppState>) -> Result<(), CommandError> {
+    if settings.days_before > notifications::MAX_DAYS_BEFORE {
+        return Err(CommandError::new(
+            codes::INVALID,
+            format!("Reminders can start at most {} days ahead", notifications::MAX_DAYS_BEFORE),
+        ));
+    }
+
+    let path = Config::default_path().map_err(CommandError::from)?;
+    let mut config = Config::load(&path).map_err(CommandError::from)?;
+    settings.apply(&mut config);
+    config.save(&path).map_err(CommandError::from)?;
+
+    *state.notification_settings.lock().map_err(|e| e.to_string())? = settings;
+    log::info!("Notification settings changed: {:?}", settings);
+    Ok(())
+}
+
+// Configuration commands
+
+// Event sent to the frontend after the config file is reloaded, so it can
+// fetch settings such as project types and the time zone again
+const CONFIG_CHANGED_EVENT: &str = "config://changed";
+
+// Use a reloaded configuration: refresh the settings copied out of it and
+// tell the frontend
+fn config_changed(app: &AppHandle, config: &Config) {
+    let state = app.state::<AppState>();
+    match state.notification_settings.lock() {
+        Ok(mut settings) => *settings = NotificationSettings::from_config(config),
+        Err(e) => log::warn!("Failed to update notification settings: {}", e),
+    }
+    if let Err(e) = app.emit(CONFIG_CHANGED_EVENT, ()) {
+        log::warn!("Failed to send {}: {}", CONFIG_CHANGED_EVENT, e);
+    }
+}
+
+// Reload the config file now instead of waiting for the watcher. An invalid
+// file is rejected and the current configuration is kept.
+#[tauri::command]
+async fn reload_config(app: AppHandle, state: State<'_, AppState>) -> Result<(), CommandError> {
+    let path = Config::default_path().map_err(CommandError::from)?;
+    let config = state.config.reload(&path).map_err(|e| {
+        log::warn!("Keeping the current configuration: {:#}", e);
+        CommandError::new(codes::INVALID, format!("{:#}", e))
+    })?;
+    log::info!("Reloaded configuration from {}", path.display());
+    config_changed(&app, &config);
+    Ok(())
+}
+
+// Reload the configuration whenever the config file changes
+fn watch_config(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let handle = app.clone();
+    let watcher = Config::default_path().and_then(|path| {
+        state.config.watch(&path, move |config| config_changed(&handle, &config))
+    });
+    match watcher {
+        Ok(watcher) => match state.config_watcher.lock() {
+            Ok(mut slot) => *slot = Some(watcher),
+            Err(e) => log::warn!("Failed to keep the config watcher: {}", e),
+        },
+        Err(e) => log::warn!("Config changes need a restart to take effect: {:#}", e),
+    }
+}
+
+// Show desktop notifications for milestones that are coming up, due today or
+// overdue, and remember which were shown
+fn show_reminders(app: &AppHandle) -> Result<(), CommandError> {
+    let state = app.state::<AppState>();
+    let settings = *state.notification_settings.lock().map_err(|e| e.to_string())?;
+    if !settings.enabled {
+        return Ok(());
+    }
+
+    let now = Utc::now();
+    let db = lock_db(&state)?;
+    let config = state.config.get();
+    let planned = notifications::plan_notifications(
+        &db,
+        now.with_timezone(&config.tz()),
+        &settings,
+        config.locale(),
+        config.current_user_email.as_deref(),
+    )
+    .map_err(CommandError::from)?;
+
+    let mut shown = Vec::with_capacity(planned.len());
+    for reminder in planned {
+        match app.notification().builder().title(&reminder.title).body(&reminder.body).show() {
+            Ok(()) => shown.push(reminder),
+            Err(e) => log::warn!("Failed to show a reminder for milestone {}: {}", reminder.milestone_id, e),
+        }
+    }
+    if !shown.is_empty() {
+        log::info!("Showed {} milestone reminder(s)", shown.len());
+    }
+    notifications::mark_shown(&db, &shown, now).map_err(CommandError::from)
+}
+
+// Check for milestone reminders when the app starts and every hour after that
+fn spawn_reminders(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(REMINDER_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = show_reminders(&app) {
+                log::warn!("Failed to check milestone reminders: {}", e.message);
+            }
+        }
+    });
+}
+
+// Back up the database if that hasn't been tried yet today, and tell the
+// user when the backup fails
+fn run_backup(app: &AppHandle) -> Result<(), CommandError> {
+    let state = app.state::<AppState>();
+    let config = state.config.get();
+    let data_dir = storage(&state).data_dir().clone();
+    let db = lock_db(&state)?;
+    let attempt = storage::auto_backup(
+        &db,
+        &data_dir,
+        Utc::now().with_timezone(&config.tz()),
+        &BackupSettings::from_config(&config),
+    )
+    .map_err(|e| format!("{:#}", e))?;
+    drop(db);
+
+    if let Some(error) = attempt.and_then(|attempt| attempt.error) {
+        if let Err(e) = app.notification().builder().title("Database backup failed").body(&error).show() {
+            log::warn!("Failed to show the backup failure: {}", e);
+        }
+    }
+    Ok(())
+}
+
+// Take the daily backup when the app starts and check every hour after that,
+// so an app left open overnight still backs up
+fn spawn_backups(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(BACKUP_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = run_backup(&app) {
+                log::warn!("Failed to back up the database: {}", e.message);
+            }
+        }
+    });
+}
+
+// The latest automatic backups, for the settings screen
+#[tauri::command]
+async fn get_backup_status(state: State<'_, AppState>) -> Result<BackupStatus, CommandError> {
+    let config = state.config.get();
+    let data_dir = storage(&state).data_dir().clone();
+    let db = lock_db(&state)?;
+    Ok(storage::backup_status(&db, &data_dir, &BackupSettings::from_config(&config)).map_err(|e| format!("{:#}", e))?)
+}
+
+// Ask where to save, then write the portfolio to an Excel workbook there;
+// returns nothing if the dialog was cancelled
+#[tauri::command]
+async fn export_workbook(app: AppHandle, state: State<'_, AppState>) -> Result<Option<Vec<WorkbookSheet>>, CommandError> {
+    let Some(path) = app
+        .dialog()
+        .file()
+        .set_title("Export to Excel")
+        .set_file_name("portfolio.xlsx")
+        .add_filter("Excel workbook", &["xlsx"])
+        .blocking_save_file()
+    else {
+        return Ok(None);
+    };
+    let path = path.into_path().map_err(|e| format!("Can't save the workbook there: {}", e))?;
+    let config = state.config.get();
+    let db = lock_db(&state)?;
+    import_export::export_workbook(&db, &path, config.tz()).map(Some).map_err(CommandError::from)
+}
+
+// Stakeholder commands
+
+#[tauri::command]
+async fn update_stakeholder(
+    project_id: String,
+    stakeholder: ProjectStakeholder,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let config = state.config.get();
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db).with_roles(&config.roles, config.allow_new_roles);
+    repo.update_stakeholder(&uuid, &stakeholder).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn remove_stakeholder(
+    project_id: String,
+    stakeholder_email: String,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.remove_stakeholder(&uuid, &stakeholder_email).map_err(CommandError::from)
+}
+
+// Project Resource commands
+
+#[tauri::command]
+async fn get_project_resources(
+    project_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<ProjectResource>, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.get_project_resources(&uuid).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn add_project_resource(
+    project_id: String,
+    resource: ProjectResource,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let config = state.config.get();
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db).with_roles(&config.roles, config.allow_new_roles);
+    repo.add_project_resource(&uuid, &resource).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn assign_team_to_project(
+    project_id: String,
+    team_name: String,
+    role: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<TeamAssignment, CommandError> {
+    let config = state.config.get();
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db).with_roles(&config.roles, config.allow_new_roles);
+    repo.add_team_as_resources(&uuid, &team_name, role.as_deref())
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn update_project_resource(
+    project_id: String,
+    resource: ProjectResource,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let config = state.config.get();
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db).with_roles(&config.roles, config.allow_new_roles);
+    repo.update_project_resource(&uuid, &resource).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn remove_project_resource(
+    project_id: String,
+    person_email: String,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.remove_project_resource(&uuid, &person_email).map_err(CommandError::from)
+}
+
+// Milestone Resource commands
+
+#[tauri::command]
+async fn get_milestone_resources(
+    milestone_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<MilestoneResource>, CommandError> {
+    let uuid = parse_id(&milestone_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.get_milestone_resources(&uuid).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn suggest_milestone_resources(
+    milestone_id: String,
+    skill: Option<String>,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<ResourceSuggestion>, CommandError> {
+    let uuid = parse_id(&milestone_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.suggest_milestone_resources(&uuid, skill.as_deref(), limit.unwrap_or(5))
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn add_milestone_resource(
+    milestone_id: String,
+    resource: MilestoneResource,
+    state: State<'_, AppState>,
+) -> Result<Option<ResourceLimitWarning>, CommandError> {
+    let config = state.config.get();
+    let uuid = parse_id(&milestone_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db)
+        .with_roles(&config.roles, config.allow_new_roles)
+        .with_resource_limit(config.max_resources_per_milestone, config.max_resources_hard_limit);
+    repo.add_milestone_resource(&uuid, &resource).map_err(CommandError::from)
+}
+
+// Number of resources on each of a project's milestones, keyed by milestone ID
+#[tauri::command]
+async fn get_resource_counts(
+    project_id: String,
+    state: State<'_, AppState>,
+) -> Result<BTreeMap<Uuid, usize>, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.get_resource_counts(&uuid).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn update_milestone_resource(
+    milestone_id: String,
+    resource: MilestoneResource,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let config = state.config.get();
+    let uuid = parse_id(&milestone_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db).with_roles(&config.roles, config.allow_new_roles);
+    repo.update_milestone_resource(&uuid, &resource).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn remove_milestone_resource(
+    milestone_id: String,
+    person_email: String,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let uuid = parse_id(&milestone_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.remove_milestone_resource(&uuid, &person_email).map_err(CommandError::from)
+}
+
+// Milestone checklist commands
+
+#[tauri::command]
+async fn list_checklist_items(milestone_id: String, state: State<'_, AppState>) -> Result<Vec<ChecklistItem>, CommandError> {
+    let uuid = parse_id(&milestone_id)?;
+    let db = lock_db(&state)?;
+    db::ChecklistRepository::new(&db).list_items(&uuid).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn add_checklist_item(
+    milestone_id: String,
+    text: String,
+    state: State<'_, AppState>,
+) -> Result<ChecklistItem, CommandError> {
+    let uuid = parse_id(&milestone_id)?;
+    let db = lock_db(&state)?;
+    db::ChecklistRepository::new(&db).add_item(&uuid, &text).map_err(CommandError::from)
+}
+
+// Check or uncheck an item, flipping it when `checked` isn't given
+#[tauri::command]
+async fn toggle_checklist_item(
+    id: String,
+    checked: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<ChecklistItem, CommandError> {
+    let uuid = parse_id(&id)?;
+    let db = lock_db(&state)?;
+    db::ChecklistRepository::new(&db).toggle_item(&uuid, checked).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn move_checklist_item(
+    id: String,
+    position: i32,
+    state: State<'_, AppState>,
+) -> Result<ChecklistItem, CommandError> {
+    let uuid = parse_id(&id)?;
+    let db = lock_db(&state)?;
+    db::ChecklistRepository::new(&db).move_item(&uuid, position).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn remove_checklist_item(id: String, state: State<'_, AppState>) -> Result<(), CommandError> {
+    let uuid = parse_id(&id)?;
+    let db = lock_db(&state)?;
+    db::ChecklistRepository::new(&db).remove_item(&uuid).map_err(CommandError::from)
+}
+
+// Project Note commands
+
+#[tauri::command]
+async fn get_project_notes(
+    project_id: String,
+    rendered: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<RenderedNote<ProjectNote>>, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
     let repo = db::ProjectRepository::new(&db);
-    repo.list_all().map_err(|e| e.to_string())
+    let notes = repo.get_project_notes(&uuid).map_err(CommandError::from)?;
+    Ok(with_html(notes, rendered.unwrap_or(false), None))
 }
 
 #[tauri::command]
-async fn get_project(id: String, state: State<'_, AppState>) -> Result<Option<Project>, String> {
-    let uuid = Uuid::parse_str(&id).map_err(|e| e.to_string())?;
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+async fn get_project_notes_paged(
+    project_id: String,
+    cursor: Option<String>,
+    limit: Option<usize>,
+    rendered: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<NotePage<ProjectNote>, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let cursor = cursor.map(|c| c.parse::<db::NoteCursor>()).transpose().map_err(CommandError::from)?;
+    let limit = limit.unwrap_or(db::project_repo::DEFAULT_NOTE_PAGE_SIZE);
+    let db = lock_db(&state)?;
     let repo = db::ProjectRepository::new(&db);
-    repo.find_by_id(&uuid).map_err(|e| e.to_string())
+    let notes = repo.get_project_notes_page(&uuid, cursor, limit).map_err(CommandError::from)?;
+    Ok(page_with_html(notes, limit, rendered.unwrap_or(false), None))
 }
 
 #[tauri::command]
-async fn create_project(project: Project, state: State<'_, AppState>) -> Result<Project, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+async fn add_project_note(
+    note: ProjectNote,
+    state: State<'_, AppState>,
+) -> Result<ProjectNote, CommandError> {
+    let db = lock_db(&state)?;
     let repo = db::ProjectRepository::new(&db);
-    repo.create(&project).map_err(|e| e.to_string())?;
-    Ok(project)
+    repo.add_project_note(&note).map_err(CommandError::from)?;
+    Ok(note)
 }
 
 #[tauri::command]
-async fn update_project(project: Project, state: State<'_, AppState>) -> Result<(), String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+async fn update_project_note(
+    mut note: ProjectNote,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let config = state.config.get();
+    let db = lock_db(&state)?;
     let repo = db::ProjectRepository::new(&db);
-    repo.update(&project).map_err(|e| e.to_string())
+    resolve_version(&mut note.version, &config, || Ok(repo.find_project_note_by_id(&note.id)?.map(|n| n.version)))?;
+    repo.update_project_note(&note).map_err(CommandError::from)
 }
 
+// Add a timestamped entry to the end of a project note, the only way to add
+// to a log note
 #[tauri::command]
-async fn delete_project(id: String, state: State<'_, AppState>) -> Result<(), String> {
-    let uuid = Uuid::parse_str(&id).map_err(|e| e.to_string())?;
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::ProjectRepository::new(&db);
-    repo.delete(&uuid).map_err(|e| e.to_string())
+async fn append_to_note(
+    note_id: String,
+    text: String,
+    author: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ProjectNote, CommandError> {
+    let uuid = parse_id(&note_id)?;
+    let author = author.or_else(|| state.config.get().current_user_email.clone());
+    let db = lock_db(&state)?;
+    db::ProjectRepository::new(&db)
+        .append_note_entry(&uuid, &text, author.as_deref())
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-async fn get_project_milestones(
-    project_id: String,
+async fn delete_project_note(
+    id: String,
     state: State<'_, AppState>,
-) -> Result<Vec<Milestone>, String> {
-    let uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+) -> Result<(), CommandError> {
+    let uuid = parse_id(&id)?;
+    let db = lock_db(&state)?;
     let repo = db::ProjectRepository::new(&db);
-    repo.get_milestones(&uuid).map_err(|e| e.to_string())
+    repo.delete_project_note(&uuid).map_err(CommandError::from)?;
+    remove_orphaned_attachments(&state, &db);
+    Ok(())
+}
+
+// Attachment commands
+
+// Remove stored files that no attachment refers to any more, such as after a
+// note with attachments is deleted
+fn remove_orphaned_attachments(state: &AppState, conn: &Connection) {
+    let in_use = match db::AttachmentRepository::new(conn).stored_hashes() {
+        Ok(in_use) => in_use,
+        Err(e) => {
+            log::warn!("Failed to list stored attachments: {}", e);
+            return;
+        }
+    };
+    match storage(state).sweep_attachments(&in_use) {
+        Ok(removed) if !removed.is_empty() => log::debug!("Removed {} orphaned attachment file(s)", removed.len()),
+        Ok(_) => {}
+        Err(e) => log::warn!("Failed to remove orphaned attachments: {:#}", e),
+    }
 }
 
 #[tauri::command]
-async fn get_project_stakeholders(
-    project_id: String,
+async fn list_attachments(note_type: String, note_id: String, state: State<'_, AppState>) -> Result<Vec<Attachment>, CommandError> {
+    let note_type: NoteType = note_type.parse().map_err(CommandError::from)?;
+    let uuid = parse_id(&note_id)?;
+    let db = lock_db(&state)?;
+    db::AttachmentRepository::new(&db).list_attachments(note_type, &uuid).map_err(CommandError::from)
+}
+
+// Attach a file to a note, given either its contents as base64 (`data`) or a
+// `path` on this machine
+#[tauri::command]
+async fn add_attachment(
+    note_type: String,
+    note_id: String,
+    filename: Option<String>,
+    mime_type: Option<String>,
+    data: Option<String>,
+    path: Option<String>,
     state: State<'_, AppState>,
-) -> Result<Vec<ProjectStakeholder>, String> {
-    let uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::ProjectRepository::new(&db);
-    repo.get_stakeholders(&uuid).map_err(|e| e.to_string())
+) -> Result<Attachment, CommandError> {
+    let config = state.config.get();
+    let note_type: NoteType = note_type.parse().map_err(CommandError::from)?;
+    let note_id = parse_id(&note_id)?;
+    let (bytes, filename) = match (data, path) {
+        (Some(data), None) => {
+            let bytes = BASE64.decode(data.trim()).map_err(|e| format!("Invalid attachment data: {}", e))?;
+            (bytes, filename.ok_or("A file name is required")?)
+        }
+        (None, Some(path)) => {
+            let metadata = std::fs::metadata(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+            if metadata.len() > config.max_attachment_bytes {
+                return Err(format!(
+                    "Attachment is too large: {} bytes, the limit is {} bytes per file",
+                    metadata.len(),
+                    config.max_attachment_bytes
+                )
+                .into());
+            }
+            let bytes = std::fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+            (bytes, filename.unwrap_or(path))
+        }
+        _ => return Err("Provide either the file contents or its path".into()),
+    };
+    let filename = db::attachment_repo::clean_filename(&filename).map_err(CommandError::from)?;
+
+    let db = lock_db(&state)?;
+    let repo = db::AttachmentRepository::new(&db);
+    let stored_bytes = repo.total_size().map_err(CommandError::from)?;
+    let sha256 = storage(&state).save_attachment(&bytes, stored_bytes).map_err(CommandError::from)?;
+    let mime_type = mime_type
+        .filter(|m| !m.trim().is_empty())
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+    let attachment = Attachment::new(note_type, note_id, filename, mime_type, bytes.len() as i64, sha256);
+    if let Err(e) = repo.add_attachment(&attachment) {
+        remove_orphaned_attachments(&state, &db);
+        return Err(CommandError::from(e));
+    }
+    Ok(attachment)
 }
 
+// An attachment's contents as base64
 #[tauri::command]
-async fn add_project_milestone(
-    milestone: Milestone,
+async fn get_attachment_data(id: String, state: State<'_, AppState>) -> Result<String, CommandError> {
+    let uuid = parse_id(&id)?;
+    let db = lock_db(&state)?;
+    let attachment = db::AttachmentRepository::new(&db)
+        .find_by_id(&uuid)
+        .map_err(CommandError::from)?
+        .ok_or_else(|| CommandError::from(db::Error::not_found("Attachment", uuid)))?;
+    let bytes = storage(&state).read_attachment(&attachment.sha256).map_err(CommandError::from)?;
+    Ok(BASE64.encode(bytes))
+}
+
+// Write an attachment's contents to a path on this machine
+#[tauri::command]
+async fn save_attachment_to(id: String, path: String, state: State<'_, AppState>) -> Result<(), CommandError> {
+    let uuid = parse_id(&id)?;
+    let db = lock_db(&state)?;
+    let attachment = db::AttachmentRepository::new(&db)
+        .find_by_id(&uuid)
+        .map_err(CommandError::from)?
+        .ok_or_else(|| CommandError::from(db::Error::not_found("Attachment", uuid)))?;
+    let bytes = storage(&state).read_attachment(&attachment.sha256).map_err(CommandError::from)?;
+    std::fs::write(&path, bytes).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    Ok(())
+}
+
+#[tauri::command]
+async fn remove_attachment(id: String, state: State<'_, AppState>) -> Result<(), CommandError> {
+    let uuid = parse_id(&id)?;
+    let db = lock_db(&state)?;
+    let unreferenced = db::AttachmentRepository::new(&db).remove_attachment(&uuid).map_err(CommandError::from)?;
+    if let Some(sha256) = unreferenced {
+        if let Err(e) = storage(&state).remove_attachment(&sha256) {
+            log::warn!("Failed to remove attachment file {}: {:#}", sha256, e);
+        }
+    }
+    Ok(())
+}
+
+// Notes that reference a project (by ID or slug), person (by email) or
+// milestone (by ID) with a [[kind:target]] token
+#[tauri::command]
+async fn get_backlinks(kind: String, target: String, state: State<'_, AppState>) -> Result<Vec<Backlink>, CommandError> {
+    let kind: ReferenceKind = kind.parse().map_err(CommandError::from)?;
+    let db = lock_db(&state)?;
+    db::NoteLinkRepository::new(&db).get_backlinks(kind, &target).map_err(CommandError::from)
+}
+
+// Note batch and move commands
+
+#[tauri::command]
+async fn create_notes_batch(
+    notes: Vec<NewNote>,
     state: State<'_, AppState>,
-) -> Result<Milestone, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+) -> Result<Vec<Uuid>, CommandError> {
+    let db = lock_db(&state)?;
     let repo = db::ProjectRepository::new(&db);
-    repo.add_milestone(&milestone).map_err(|e| e.to_string())?;
-    Ok(milestone)
+    repo.add_notes_batch(&notes).map_err(CommandError::from)
 }
 
 #[tauri::command]
-async fn update_milestone(milestone: Milestone, state: State<'_, AppState>) -> Result<(), String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+async fn move_note(
+    note_id: String,
+    target: NoteTarget,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let uuid = parse_id(&note_id)?;
+    let db = lock_db(&state)?;
     let repo = db::ProjectRepository::new(&db);
-    repo.update_milestone(&milestone).map_err(|e| e.to_string())
+    repo.move_note(&uuid, &target).map_err(CommandError::from)
 }
 
+// Inbox commands
+
 #[tauri::command]
-async fn delete_milestone(id: String, state: State<'_, AppState>) -> Result<(), String> {
-    let uuid = Uuid::parse_str(&id).map_err(|e| e.to_string())?;
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::ProjectRepository::new(&db);
-    repo.delete_milestone(&uuid).map_err(|e| e.to_string())
+async fn list_inbox_notes(state: State<'_, AppState>) -> Result<Vec<InboxNote>, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::InboxRepository::new(&db);
+    repo.list_all().map_err(CommandError::from)
 }
 
 #[tauri::command]
-async fn add_project_stakeholder(
-    project_id: String,
-    stakeholder: ProjectStakeholder,
+async fn create_inbox_note(
+    note: InboxNote,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::ProjectRepository::new(&db);
-    repo.add_stakeholder(&uuid, &stakeholder)
-        .map_err(|e| e.to_string())
+) -> Result<InboxNote, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::InboxRepository::new(&db);
+    repo.create(&note).map_err(CommandError::from)?;
+    Ok(note)
 }
 
 #[tauri::command]
-async fn list_people(state: State<'_, AppState>) -> Result<Vec<Person>, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::PersonRepository::new(&db);
-    repo.list_all().map_err(|e| e.to_string())
+async fn update_inbox_note(
+    note: InboxNote,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::InboxRepository::new(&db);
+    repo.update(&note).map_err(CommandError::from)
 }
 
 #[tauri::command]
-async fn search_people(query: String, state: State<'_, AppState>) -> Result<Vec<Person>, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::PersonRepository::new(&db);
-    repo.search_by_name(&query).map_err(|e| e.to_string())
+async fn delete_inbox_note(
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let uuid = parse_id(&id)?;
+    let db = lock_db(&state)?;
+    let repo = db::InboxRepository::new(&db);
+    repo.delete(&uuid).map_err(CommandError::from)
 }
 
 #[tauri::command]
-async fn get_person(email: String, state: State<'_, AppState>) -> Result<Option<Person>, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::PersonRepository::new(&db);
-    repo.find_by_email(&email).map_err(|e| e.to_string())
+async fn triage_inbox_note(
+    note_id: String,
+    target: NoteTarget,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let uuid = parse_id(&note_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::InboxRepository::new(&db);
+    repo.triage(&uuid, &target).map_err(CommandError::from)
 }
 
+// Project Document commands
+
 #[tauri::command]
-async fn create_person(person: Person, state: State<'_, AppState>) -> Result<Person, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::PersonRepository::new(&db);
-    repo.create(&person).map_err(|e| e.to_string())?;
-    Ok(person)
+async fn list_project_documents(
+    project_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<ProjectDocument>, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.get_project_documents(&uuid).map_err(CommandError::from)
 }
 
 #[tauri::command]
-async fn update_person(person: Person, state: State<'_, AppState>) -> Result<(), String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::PersonRepository::new(&db);
-    repo.update(&person).map_err(|e| e.to_string())
+async fn add_project_document(
+    document: ProjectDocument,
+    state: State<'_, AppState>,
+) -> Result<ProjectDocument, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.add_project_document(&document).map_err(CommandError::from)?;
+    Ok(document)
 }
 
 #[tauri::command]
-async fn delete_person(email: String, state: State<'_, AppState>) -> Result<(), String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::PersonRepository::new(&db);
-    repo.delete(&email).map_err(|e| e.to_string())
+async fn remove_project_document(
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let uuid = parse_id(&id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.delete_project_document(&uuid).map_err(CommandError::from)
 }
 
-// Team commands
+// Project Budget commands
 
 #[tauri::command]
-async fn list_teams(state: State<'_, AppState>) -> Result<Vec<Team>, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::TeamRepository::new(&db);
-    repo.list_all().map_err(|e| e.to_string())
+async fn list_expenses(
+    project_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<ProjectExpense>, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.list_expenses(&uuid).map_err(CommandError::from)
 }
 
 #[tauri::command]
-async fn get_team(name: String, state: State<'_, AppState>) -> Result<Option<Team>, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::TeamRepository::new(&db);
-    repo.find_by_name(&name).map_err(|e| e.to_string())
+async fn add_expense(
+    expense: ProjectExpense,
+    state: State<'_, AppState>,
+) -> Result<ProjectExpense, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.add_expense(&expense).map_err(CommandError::from)?;
+    Ok(expense)
 }
 
 #[tauri::command]
-async fn create_team(team: Team, state: State<'_, AppState>) -> Result<Team, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::TeamRepository::new(&db);
-    repo.create(&team).map_err(|e| e.to_string())?;
-    Ok(team)
+async fn delete_expense(
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let uuid = parse_id(&id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.delete_expense(&uuid).map_err(CommandError::from)
 }
 
 #[tauri::command]
-async fn update_team(team: Team, state: State<'_, AppState>) -> Result<(), String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::TeamRepository::new(&db);
-    repo.update(&team).map_err(|e| e.to_string())
+async fn get_budget_status(
+    project_id: String,
+    state: State<'_, AppState>,
+) -> Result<BudgetStatus, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.get_budget_status(&uuid).map_err(CommandError::from)
 }
 
+// Project Snapshot commands
+
 #[tauri::command]
-async fn delete_team(name: String, state: State<'_, AppState>) -> Result<(), String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::TeamRepository::new(&db);
-    repo.delete(&name).map_err(|e| e.to_string())
+async fn create_snapshot(
+    project_id: String,
+    label: String,
+    state: State<'_, AppState>,
+) -> Result<ProjectSnapshot, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.create_snapshot(&uuid, &label).map_err(CommandError::from)
 }
 
 #[tauri::command]
-async fn search_teams(query: String, state: State<'_, AppState>) -> Result<Vec<Team>, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::TeamRepository::new(&db);
-    repo.search_by_name(&query).map_err(|e| e.to_string())
+async fn list_snapshots(
+    project_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<ProjectSnapshot>, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.list_snapshots(&uuid).map_err(CommandError::from)
 }
 
 #[tauri::command]
-async fn add_team_member(
-    team_name: String,
-    person_email: String,
+async fn compare_snapshots(
+    from_snapshot_id: String,
+    to_snapshot_id: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::TeamRepository::new(&db);
-    repo.add_member(&team_name, &person_email).map_err(|e| e.to_string())
+) -> Result<SnapshotDiff, CommandError> {
+    let from = parse_id(&from_snapshot_id)?;
+    let to = parse_id(&to_snapshot_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.compare_snapshots(&from, &to).map_err(CommandError::from)
 }
 
+// Project Risk commands
+
 #[tauri::command]
-async fn remove_team_member(
-    team_name: String,
-    person_email: String,
+async fn list_project_risks(
+    project_id: String,
+    open_only: Option<bool>,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::TeamRepository::new(&db);
-    repo.remove_member(&team_name, &person_email).map_err(|e| e.to_string())
+) -> Result<Vec<ProjectRisk>, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    if open_only.unwrap_or(false) {
+        repo.list_open_risks(&uuid).map_err(CommandError::from)
+    } else {
+        repo.get_project_risks(&uuid).map_err(CommandError::from)
+    }
 }
 
 #[tauri::command]
-async fn get_team_members(team_name: String, state: State<'_, AppState>) -> Result<Vec<Person>, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::TeamRepository::new(&db);
-    repo.get_members(&team_name).map_err(|e| e.to_string())
+async fn list_high_risks(state: State<'_, AppState>) -> Result<Vec<ProjectRisk>, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.list_high_risks().map_err(CommandError::from)
 }
 
 #[tauri::command]
-async fn get_jira_url(state: State<'_, AppState>) -> Result<String, String> {
-    Ok(state.config.jira_url.clone())
+async fn add_project_risk(
+    risk: ProjectRisk,
+    state: State<'_, AppState>,
+) -> Result<ProjectRisk, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.add_project_risk(&risk).map_err(CommandError::from)?;
+    Ok(risk)
 }
 
 #[tauri::command]
-async fn get_default_email_domain(state: State<'_, AppState>) -> Result<String, String> {
-    Ok(state.config.default_email_domain.clone())
+async fn update_project_risk(
+    risk: ProjectRisk,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.update_project_risk(&risk).map_err(CommandError::from)
 }
 
 #[tauri::command]
-async fn get_project_types(state: State<'_, AppState>) -> Result<Vec<String>, String> {
-    Ok(state.config.project_types.clone())
+async fn close_project_risk(
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let uuid = parse_id(&id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    repo.close_project_risk(&uuid).map_err(CommandError::from)
 }
 
+// Initiative commands
+
 #[tauri::command]
-async fn get_mcp_port(state: State<'_, AppState>) -> Result<u16, String> {
-    Ok(state.config.mcp_http_port)
+async fn list_initiatives(
+    quarter: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<Initiative>, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::InitiativeRepository::new(&db);
+    match quarter {
+        Some(quarter) => repo.list_by_quarter(&quarter),
+        None => repo.list_all(),
+    }
+    .map_err(CommandError::from)
 }
 
-// Stakeholder commands
+#[tauri::command]
+async fn create_initiative(
+    mut initiative: Initiative,
+    state: State<'_, AppState>,
+) -> Result<Initiative, CommandError> {
+    initiative.quarter = Initiative::parse_quarter(&initiative.quarter).map_err(CommandError::from)?;
+    let db = lock_db(&state)?;
+    let repo = db::InitiativeRepository::new(&db);
+    repo.create(&initiative).map_err(CommandError::from)?;
+    Ok(initiative)
+}
 
 #[tauri::command]
-async fn update_stakeholder(
-    project_id: String,
-    stakeholder: ProjectStakeholder,
+async fn update_initiative(
+    initiative: Initiative,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::ProjectRepository::new(&db);
-    repo.update_stakeholder(&uuid, &stakeholder).map_err(|e| e.to_string())
+) -> Result<(), CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::InitiativeRepository::new(&db);
+    repo.update(&initiative).map_err(CommandError::from)
 }
 
 #[tauri::command]
-async fn remove_stakeholder(
-    project_id: String,
-    stakeholder_email: String,
+async fn delete_initiative(
+    id: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::ProjectRepository::new(&db);
-    repo.remove_stakeholder(&uuid, &stakeholder_email).map_err(|e| e.to_string())
+) -> Result<(), CommandError> {
+    let uuid = parse_id(&id)?;
+    let db = lock_db(&state)?;
+    let repo = db::InitiativeRepository::new(&db);
+    repo.delete(&uuid).map_err(CommandError::from)
 }
 
-// Project Resource commands
+#[tauri::command]
+async fn get_initiative_progress(
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<Option<InitiativeProgress>, CommandError> {
+    let config = state.config.get();
+    let uuid = parse_id(&id)?;
+    let db = lock_db(&state)?;
+    let repo = db::InitiativeRepository::new(&db).with_checklist_progress(config.checklist_progress);
+    repo.get_progress(&uuid).map_err(CommandError::from)
+}
 
 #[tauri::command]
-async fn get_project_resources(
+async fn get_project_initiative(
     project_id: String,
     state: State<'_, AppState>,
-) -> Result<Vec<ProjectResource>, String> {
-    let uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+) -> Result<Option<Initiative>, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
     let repo = db::ProjectRepository::new(&db);
-    repo.get_project_resources(&uuid).map_err(|e| e.to_string())
+    repo.get_initiative(&uuid).map_err(CommandError::from)
 }
 
 #[tauri::command]
-async fn add_project_resource(
+async fn set_project_initiative(
     project_id: String,
-    resource: ProjectResource,
+    initiative_id: Option<String>,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+) -> Result<(), CommandError> {
+    let project_uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
     let repo = db::ProjectRepository::new(&db);
-    repo.add_project_resource(&uuid, &resource).map_err(|e| e.to_string())
+    match initiative_id {
+        Some(initiative_id) => {
+            let initiative_uuid = parse_id(&initiative_id)?;
+            repo.set_initiative(&project_uuid, &initiative_uuid).map_err(CommandError::from)
+        }
+        None => repo.clear_initiative(&project_uuid).map_err(CommandError::from),
+    }
 }
 
+// Project dependency commands
+
 #[tauri::command]
-async fn update_project_resource(
+async fn list_project_dependencies(
     project_id: String,
-    resource: ProjectResource,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::ProjectRepository::new(&db);
-    repo.update_project_resource(&uuid, &resource).map_err(|e| e.to_string())
+) -> Result<Vec<ProjectDependency>, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::DependencyRepository::new(&db);
+    repo.list_dependencies(&uuid).map_err(CommandError::from)
 }
 
 #[tauri::command]
-async fn remove_project_resource(
+async fn add_project_dependency(
     project_id: String,
-    person_email: String,
+    depends_on_project_id: String,
+    note: Option<String>,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::ProjectRepository::new(&db);
-    repo.remove_project_resource(&uuid, &person_email).map_err(|e| e.to_string())
+) -> Result<ProjectDependency, CommandError> {
+    let project_uuid = project_ref(&state, &project_id)?;
+    let depends_on_uuid = project_ref(&state, &depends_on_project_id)?;
+    let mut dependency = ProjectDependency::new(project_uuid, depends_on_uuid);
+    dependency.note = note.filter(|n| !n.trim().is_empty());
+    let db = lock_db(&state)?;
+    let repo = db::DependencyRepository::new(&db);
+    repo.add(&dependency).map_err(CommandError::from)?;
+    Ok(dependency)
 }
 
-// Milestone Resource commands
-
 #[tauri::command]
-async fn get_milestone_resources(
-    milestone_id: String,
+async fn remove_project_dependency(
+    project_id: String,
+    depends_on_project_id: String,
     state: State<'_, AppState>,
-) -> Result<Vec<MilestoneResource>, String> {
-    let uuid = Uuid::parse_str(&milestone_id).map_err(|e| e.to_string())?;
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::ProjectRepository::new(&db);
-    repo.get_milestone_resources(&uuid).map_err(|e| e.to_string())
+) -> Result<(), CommandError> {
+    let project_uuid = project_ref(&state, &project_id)?;
+    let depends_on_uuid = project_ref(&state, &depends_on_project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::DependencyRepository::new(&db);
+    repo.remove(&project_uuid, &depends_on_uuid).map_err(CommandError::from)
 }
 
 #[tauri::command]
-async fn add_milestone_resource(
-    milestone_id: String,
-    resource: MilestoneResource,
+async fn get_dependency_graph(
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let uuid = Uuid::parse_str(&milestone_id).map_err(|e| e.to_string())?;
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::ProjectRepository::new(&db);
-    repo.add_milestone_resource(&uuid, &resource).map_err(|e| e.to_string())
+) -> Result<DependencyGraph, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::DependencyRepository::new(&db);
+    repo.get_dependency_graph().map_err(CommandError::from)
 }
 
+// Draw the org chart, teams or projects as Mermaid (the default) or DOT text
 #[tauri::command]
-async fn update_milestone_resource(
-    milestone_id: String,
-    resource: MilestoneResource,
+async fn generate_diagram(
+    kind: String,
+    format: Option<String>,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let uuid = Uuid::parse_str(&milestone_id).map_err(|e| e.to_string())?;
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let repo = db::ProjectRepository::new(&db);
-    repo.update_milestone_resource(&uuid, &resource).map_err(|e| e.to_string())
+) -> Result<String, CommandError> {
+    let kind: DiagramKind = kind.parse().map_err(CommandError::from)?;
+    let format = match format {
+        Some(format) => format.parse().map_err(CommandError::from)?,
+        None => DiagramFormat::default(),
+    };
+    let db = lock_db(&state)?;
+    let diagram = diagram::build(&db, kind, Utc::now()).map_err(CommandError::from)?;
+    Ok(diagram.render(format))
 }
 
+// Action Item commands
+
 #[tauri::command]
-async fn remove_milestone_resource(
-    milestone_id: String,
-    person_email: String,
+async fn list_action_items(
+    project_id: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let uuid = Uuid::parse_str(&milestone_id).map_err(|e| e.to_string())?;
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+) -> Result<Vec<ActionItem>, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
     let repo = db::ProjectRepository::new(&db);
-    repo.remove_milestone_resource(&uuid, &person_email).map_err(|e| e.to_string())
+    repo.list_action_items(&uuid).map_err(CommandError::from)
 }
 
-// Project Note commands
-
 #[tauri::command]
-async fn get_project_notes(
-    project_id: String,
+async fn create_action_item(
+    item: ActionItem,
     state: State<'_, AppState>,
-) -> Result<Vec<ProjectNote>, String> {
-    let uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+) -> Result<ActionItem, CommandError> {
+    let db = lock_db(&state)?;
     let repo = db::ProjectRepository::new(&db);
-    repo.get_project_notes(&uuid).map_err(|e| e.to_string())
+    repo.create_action_item(&item).map_err(CommandError::from)?;
+    Ok(item)
 }
 
 #[tauri::command]
-async fn add_project_note(
-    note: ProjectNote,
+async fn update_action_item(
+    item: ActionItem,
     state: State<'_, AppState>,
-) -> Result<ProjectNote, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+) -> Result<(), CommandError> {
+    let db = lock_db(&state)?;
     let repo = db::ProjectRepository::new(&db);
-    repo.add_project_note(&note).map_err(|e| e.to_string())?;
-    Ok(note)
+    repo.update_action_item(&item).map_err(CommandError::from)
 }
 
 #[tauri::command]
-async fn update_project_note(
-    note: ProjectNote,
+async fn complete_action_item(
+    id: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+) -> Result<ActionItem, CommandError> {
+    let uuid = parse_id(&id)?;
+    let db = lock_db(&state)?;
     let repo = db::ProjectRepository::new(&db);
-    repo.update_project_note(&note).map_err(|e| e.to_string())
+    repo.complete_action_item(&uuid).map_err(CommandError::from)
 }
 
 #[tauri::command]
-async fn delete_project_note(
+async fn delete_action_item(
     id: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let uuid = Uuid::parse_str(&id).map_err(|e| e.to_string())?;
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+) -> Result<(), CommandError> {
+    let uuid = parse_id(&id)?;
+    let db = lock_db(&state)?;
     let repo = db::ProjectRepository::new(&db);
-    repo.delete_project_note(&uuid).map_err(|e| e.to_string())
+    repo.delete_action_item(&uuid).map_err(CommandError::from)
 }
 
 // Milestone Note commands
@@ -433,90 +2545,236 @@ async fn delete_project_note(
 #[tauri::command]
 async fn get_milestone_notes(
     milestone_id: String,
+    rendered: Option<bool>,
     state: State<'_, AppState>,
-) -> Result<Vec<MilestoneNote>, String> {
-    let uuid = Uuid::parse_str(&milestone_id).map_err(|e| e.to_string())?;
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+) -> Result<Vec<RenderedNote<MilestoneNote>>, CommandError> {
+    let uuid = parse_id(&milestone_id)?;
+    let db = lock_db(&state)?;
     let repo = db::ProjectRepository::new(&db);
-    repo.get_milestone_notes(&uuid).map_err(|e| e.to_string())
+    let notes = repo.get_milestone_notes(&uuid).map_err(CommandError::from)?;
+    Ok(with_html(notes, rendered.unwrap_or(false), None))
 }
 
 #[tauri::command]
 async fn add_milestone_note(
     note: MilestoneNote,
     state: State<'_, AppState>,
-) -> Result<MilestoneNote, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+) -> Result<MilestoneNote, CommandError> {
+    let db = lock_db(&state)?;
     let repo = db::ProjectRepository::new(&db);
-    repo.add_milestone_note(&note).map_err(|e| e.to_string())?;
+    repo.add_milestone_note(&note).map_err(CommandError::from)?;
     Ok(note)
 }
 
 #[tauri::command]
 async fn update_milestone_note(
-    note: MilestoneNote,
+    mut note: MilestoneNote,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+) -> Result<(), CommandError> {
+    let config = state.config.get();
+    let db = lock_db(&state)?;
     let repo = db::ProjectRepository::new(&db);
-    repo.update_milestone_note(&note).map_err(|e| e.to_string())
+    resolve_version(&mut note.version, &config, || Ok(repo.find_milestone_note_by_id(&note.id)?.map(|n| n.version)))?;
+    repo.update_milestone_note(&note).map_err(CommandError::from)
 }
 
 #[tauri::command]
 async fn delete_milestone_note(
     id: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let uuid = Uuid::parse_str(&id).map_err(|e| e.to_string())?;
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+) -> Result<(), CommandError> {
+    let uuid = parse_id(&id)?;
+    let db = lock_db(&state)?;
     let repo = db::ProjectRepository::new(&db);
-    repo.delete_milestone_note(&uuid).map_err(|e| e.to_string())
+    repo.delete_milestone_note(&uuid).map_err(CommandError::from)?;
+    remove_orphaned_attachments(&state, &db);
+    Ok(())
+}
+
+// Sort a project's stakeholders into influence/interest quadrants
+#[tauri::command]
+async fn get_stakeholder_matrix(
+    project_id: String,
+    state: State<'_, AppState>,
+) -> Result<StakeholderMatrix, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    db::ProjectRepository::new(&db).get_stakeholder_matrix(&uuid).map_err(CommandError::from)
 }
 
 // Stakeholder Note commands
 
+#[tauri::command]
+async fn get_stakeholder_brief(
+    project_id: String,
+    stakeholder_email: String,
+    note_limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<StakeholderBrief, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
+    let repo = db::ProjectRepository::new(&db);
+    let note_limit = note_limit.unwrap_or(db::project_repo::DEFAULT_BRIEF_NOTE_LIMIT);
+    repo.get_stakeholder_brief(&uuid, &stakeholder_email, note_limit).map_err(CommandError::from)
+}
+
 #[tauri::command]
 async fn get_stakeholder_notes(
     project_id: String,
     stakeholder_email: String,
+    rendered: Option<bool>,
     state: State<'_, AppState>,
-) -> Result<Vec<StakeholderNote>, String> {
-    let uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+) -> Result<Vec<RenderedNote<StakeholderNote>>, CommandError> {
+    let uuid = project_ref(&state, &project_id)?;
+    let db = lock_db(&state)?;
     let repo = db::ProjectRepository::new(&db);
-    repo.get_stakeholder_notes(&uuid, &stakeholder_email).map_err(|e| e.to_string())
+    let notes = repo.get_stakeholder_notes(&uuid, &stakeholder_email).map_err(CommandError::from)?;
+    Ok(with_html(notes, rendered.unwrap_or(false), None))
 }
 
 #[tauri::command]
 async fn add_stakeholder_note(
     note: StakeholderNote,
     state: State<'_, AppState>,
-) -> Result<StakeholderNote, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+) -> Result<StakeholderNote, CommandError> {
+    let db = lock_db(&state)?;
     let repo = db::ProjectRepository::new(&db);
-    repo.add_stakeholder_note(&note).map_err(|e| e.to_string())?;
+    repo.add_stakeholder_note(&note).map_err(CommandError::from)?;
     Ok(note)
 }
 
 #[tauri::command]
 async fn update_stakeholder_note(
-    note: StakeholderNote,
+    mut note: StakeholderNote,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+) -> Result<(), CommandError> {
+    let config = state.config.get();
+    let db = lock_db(&state)?;
     let repo = db::ProjectRepository::new(&db);
-    repo.update_stakeholder_note(&note).map_err(|e| e.to_string())
+    resolve_version(&mut note.version, &config, || Ok(repo.find_stakeholder_note_by_id(&note.id)?.map(|n| n.version)))?;
+    repo.update_stakeholder_note(&note).map_err(CommandError::from)
 }
 
 #[tauri::command]
 async fn delete_stakeholder_note(
     id: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let uuid = Uuid::parse_str(&id).map_err(|e| e.to_string())?;
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+) -> Result<(), CommandError> {
+    let uuid = parse_id(&id)?;
+    let db = lock_db(&state)?;
     let repo = db::ProjectRepository::new(&db);
-    repo.delete_stakeholder_note(&uuid).map_err(|e| e.to_string())
+    repo.delete_stakeholder_note(&uuid).map_err(CommandError::from)?;
+    remove_orphaned_attachments(&state, &db);
+    Ok(())
+}
+
+#[tauri::command]
+async fn list_person_notes(
+    person_email: String,
+    limit: Option<usize>,
+    rendered: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<RenderedNote<PersonNote>>, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::PersonRepository::new(&db);
+    let notes = repo.list_notes(&person_email, limit).map_err(CommandError::from)?;
+    Ok(with_html(notes, rendered.unwrap_or(false), None))
+}
+
+#[tauri::command]
+async fn create_person_note(
+    note: PersonNote,
+    state: State<'_, AppState>,
+) -> Result<PersonNote, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::PersonRepository::new(&db);
+    repo.add_note(&note).map_err(CommandError::from)?;
+    Ok(note)
+}
+
+#[tauri::command]
+async fn update_person_note(
+    mut note: PersonNote,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let config = state.config.get();
+    let db = lock_db(&state)?;
+    let repo = db::PersonRepository::new(&db);
+    resolve_version(&mut note.version, &config, || Ok(repo.find_note_by_id(&note.id)?.map(|n| n.version)))?;
+    repo.update_note(&note).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn delete_person_note(
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let uuid = parse_id(&id)?;
+    let db = lock_db(&state)?;
+    let repo = db::PersonRepository::new(&db);
+    repo.delete_note(&uuid).map_err(CommandError::from)
+}
+
+// A logged 1:1 with the action items made from its follow-ups
+#[derive(serde::Serialize)]
+struct LoggedOneOnOne {
+    one_on_one: OneOnOne,
+    action_items: Vec<ActionItem>,
+}
+
+#[tauri::command]
+async fn list_one_on_ones(person_email: String, state: State<'_, AppState>) -> Result<Vec<OneOnOne>, CommandError> {
+    let db = lock_db(&state)?;
+    let repo = db::OneOnOneRepository::new(&db);
+    repo.list_one_on_ones(&person_email).map_err(CommandError::from)
+}
+
+// When `action_item_project` is given, each follow-up also becomes an action
+// item for the person on that project, in the same transaction as the 1:1
+#[tauri::command]
+async fn log_one_on_one(
+    one_on_one: OneOnOne,
+    action_item_project: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<LoggedOneOnOne, CommandError> {
+    let project_id = action_item_project
+        .map(|project| project_ref(&state, &project))
+        .transpose()?;
+    let db = lock_db(&state)?;
+    let repo = db::OneOnOneRepository::new(&db);
+    let tx = db.unchecked_transaction().map_err(|e| CommandError::from(db::Error::from(e)))?;
+    repo.create(&one_on_one).map_err(CommandError::from)?;
+    let action_items = match project_id {
+        Some(project_id) => repo
+            .create_followup_action_items(&one_on_one, &project_id)
+            .map_err(CommandError::from)?,
+        None => Vec::new(),
+    };
+    tx.commit().map_err(|e| CommandError::from(db::Error::from(e)))?;
+    let one_on_one = repo
+        .find_by_id(&one_on_one.id)
+        .map_err(CommandError::from)?
+        .ok_or_else(|| CommandError::from(db::Error::not_found("1:1", one_on_one.id)))?;
+    Ok(LoggedOneOnOne {
+        one_on_one,
+        action_items,
+    })
+}
+
+#[tauri::command]
+async fn update_one_on_one(mut one_on_one: OneOnOne, state: State<'_, AppState>) -> Result<(), CommandError> {
+    one_on_one.updated_at = Utc::now();
+    let db = lock_db(&state)?;
+    let repo = db::OneOnOneRepository::new(&db);
+    repo.update(&one_on_one).map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn delete_one_on_one(id: String, state: State<'_, AppState>) -> Result<(), CommandError> {
+    let uuid = parse_id(&id)?;
+    let db = lock_db(&state)?;
+    let repo = db::OneOnOneRepository::new(&db);
+    repo.delete(&uuid).map_err(CommandError::from)
 }
 
 fn main() {
@@ -526,130 +2784,263 @@ fn main() {
     let config = Config::load_or_default().expect("Failed to load configuration");
     config.ensure_data_dir().expect("Failed to create data directory");
 
-    // Open database
-    let db_path = config.database_path().expect("Failed to get database path");
-    let conn = db::open_database(&db_path).expect("Failed to open database");
-
-    // Start MCP HTTP server in background
-    let mcp_port = config.mcp_http_port;
-    let mcp_config = config.clone();
-    let mcp_db_path = db_path.clone();
-
-    std::thread::spawn(move || {
-        let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
-        rt.block_on(async {
-            match start_mcp_server(mcp_config, mcp_db_path, mcp_port).await {
-                Ok(_) => log::info!("MCP HTTP server stopped"),
-                Err(e) => log::error!("MCP HTTP server error: {}", e),
-            }
-        });
+    // Open the default workspace
+    let workspace = config.workspace().to_string();
+    let opened = open_workspace(&config, &workspace).expect("Failed to open workspace");
+
+    // Start webhook delivery workers
+    let (webhooks, workers) = WebhookDispatcher::new(&config.webhooks);
+    for worker in workers {
+        tauri::async_runtime::spawn(worker);
+    }
+
+    // Settings are read from the shared configuration as they are used, so
+    // the app and the MCP HTTP server both pick up changes to the file
+    let notification_settings = NotificationSettings::from_config(&config);
+    let config = SharedConfig::new(config);
+
+    // Start the MCP HTTP server in the background, unless it is turned off
+    let mcp = Arc::new(SseController::new(config.clone(), &workspace));
+    let mcp_on_launch = mcp.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = mcp_on_launch.start_if_enabled().await {
+            log::error!("MCP HTTP server error: {:#}", e);
+        }
     });
 
-    log::info!("MCP HTTP server starting on port {}", mcp_port);
-
     // Initialize app state
     let app_state = AppState {
-        db: Mutex::new(conn),
-        config: Arc::new(config),
+        db: Mutex::new(opened.conn),
+        config,
+        config_watcher: Mutex::new(None),
+        webhooks,
+        people_index: Mutex::new(db::autocomplete::PeopleIndex::new()),
+        storage: RwLock::new(opened.storage),
+        heartbeat: Mutex::new(opened.heartbeat),
+        sync_warning: Mutex::new(opened.sync_warning),
+        notification_settings: Mutex::new(notification_settings),
+        workspace: Mutex::new(workspace),
+        mcp,
     };
 
     tauri::Builder::default()
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_dialog::init())
         .manage(app_state)
-        .invoke_handler(tauri::generate_handler![
+        .setup(|app| {
+            spawn_reminders(app.handle().clone());
+            spawn_backups(app.handle().clone());
+            watch_config(app.handle());
+            Ok(())
+        })
+        .invoke_handler(authorized(tauri::generate_handler![
             list_projects,
+            get_dashboard,
             get_project,
+            list_project_summaries,
+            get_project_summary,
+            get_slippage_report,
+            get_effort_summary,
+            get_portfolio_stats,
+            get_hygiene_report,
+            check_links,
+            get_project_links,
+            preview_digest,
+            get_quarter_plan,
             create_project,
             update_project,
             delete_project,
+            block_project,
+            unblock_project,
+            set_project_phase,
+            get_phase_history,
+            list_projects_by_phase,
+            save_retrospective,
+            get_retrospective,
+            record_decision,
+            list_decisions,
+            supersede_decision,
+            watch_project,
+            unwatch_project,
+            get_focus_list,
+            add_external_ref,
+            list_external_refs,
+            remove_external_ref,
+            set_project_slug,
+            list_blocked_projects,
+            get_board,
+            reorder_project,
+            get_blocker_history,
+            get_project_activity,
+            suggest_project_due_date,
             get_project_milestones,
             get_project_stakeholders,
             add_project_milestone,
             update_milestone,
+            get_milestone_date_warnings,
             delete_milestone,
+            delete_milestone_series,
+            materialize_recurring_milestones,
             add_project_stakeholder,
             update_stakeholder,
             remove_stakeholder,
             get_project_resources,
             add_project_resource,
+            assign_team_to_project,
             update_project_resource,
             remove_project_resource,
             get_milestone_resources,
+            suggest_milestone_resources,
             add_milestone_resource,
+            get_resource_counts,
+            list_checklist_items,
+            add_checklist_item,
+            toggle_checklist_item,
+            move_checklist_item,
+            remove_checklist_item,
             update_milestone_resource,
             remove_milestone_resource,
             get_project_notes,
+            get_project_notes_paged,
             add_project_note,
             update_project_note,
+            append_to_note,
             delete_project_note,
+            list_attachments,
+            add_attachment,
+            get_attachment_data,
+            save_attachment_to,
+            remove_attachment,
+            get_backlinks,
+            create_notes_batch,
+            move_note,
+            list_inbox_notes,
+            create_inbox_note,
+            update_inbox_note,
+            delete_inbox_note,
+            triage_inbox_note,
+            list_project_documents,
+            add_project_document,
+            remove_project_document,
+            list_expenses,
+            add_expense,
+            delete_expense,
+            get_budget_status,
+            create_snapshot,
+            list_snapshots,
+            compare_snapshots,
+            list_project_risks,
+            list_high_risks,
+            add_project_risk,
+            update_project_risk,
+            close_project_risk,
+            list_initiatives,
+            create_initiative,
+            update_initiative,
+            delete_initiative,
+            get_initiative_progress,
+            get_project_initiative,
+            set_project_initiative,
+            list_project_dependencies,
+            add_project_dependency,
+            remove_project_dependency,
+            get_dependency_graph,
+            generate_diagram,
+            list_action_items,
+            create_action_item,
+            update_action_item,
+            complete_action_item,
+            delete_action_item,
             get_milestone_notes,
             add_milestone_note,
             update_milestone_note,
             delete_milestone_note,
+            get_stakeholder_brief,
+            get_stakeholder_matrix,
             get_stakeholder_notes,
             add_stakeholder_note,
             update_stakeholder_note,
             delete_stakeholder_note,
+            list_person_notes,
+            create_person_note,
+            update_person_note,
+            delete_person_note,
+            list_one_on_ones,
+            log_one_on_one,
+            update_one_on_one,
+            delete_one_on_one,
             list_people,
             search_people,
+            autocomplete_people,
             get_person,
             create_person,
             update_person,
             delete_person,
+            deactivate_person,
+            change_person_email,
+            reactivate_person,
+            add_person_skill,
+            remove_person_skill,
+            find_people_by_skill,
+            get_availability_forecast,
+            get_avatar,
+            set_avatar,
+            clear_avatar,
+            import_directory,
             list_teams,
             get_team,
             create_team,
             update_team,
             delete_team,
+            list_organizations,
+            create_organization,
+            update_organization,
+            delete_organization,
+            get_organization_overview,
+            get_team_tree,
             search_teams,
+            autocomplete_teams,
+            autocomplete_roles,
+            list_role_variants,
+            merge_roles,
+            seed_demo_data,
+            get_database_schema,
+            reload_config,
             add_team_member,
             remove_team_member,
             get_team_members,
+            generate_team_review,
             get_jira_url,
+            get_timezone,
             get_default_email_domain,
             get_project_types,
+            get_project_phases,
+            get_schema_info,
+            get_backup_status,
+            export_workbook,
+            db_ping,
+            list_project_types_in_use,
+            get_document_types,
             get_mcp_port,
-        ])
+            start_mcp_server,
+            stop_mcp_server,
+            get_mcp_status,
+            list_custom_fields,
+            define_custom_field,
+            delete_custom_field,
+            get_project_custom_values,
+            set_project_custom_value,
+            list_workspaces,
+            get_active_workspace,
+            get_sync_warning,
+            switch_workspace,
+            get_notification_settings,
+            set_notification_settings,
+            get_current_role,
+            list_users,
+            set_user_role,
+            remove_user,
+        ]))
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
-
-async fn start_mcp_server(
-    config: Config,
-    db_path: std::path::PathBuf,
-    port: u16,
-) -> Result<(), Box<dyn std::error::Error>> {
-    use rmcp::transport::sse_server::{SseServer, SseServerConfig};
-    use tokio_util::sync::CancellationToken;
-
-    log::info!("Starting MCP HTTP/SSE server on port {}", port);
-
-    // Open a new database connection for the MCP server
-    let conn = db::open_database(&db_path)?;
-
-    // Create MCP server
-    let mcp_server = ProjectTrackerServer::new(config, conn);
-
-    // Configure SSE server
-    let bind_addr = std::net::SocketAddr::from(([127, 0, 0, 1], port));
-    let sse_config = SseServerConfig {
-        bind: bind_addr,
-        sse_path: "/sse".to_string(),
-        post_path: "/message".to_string(),
-        ct: CancellationToken::new(),
-        sse_keep_alive: Some(std::time::Duration::from_secs(30)),
-    };
-
-    // Create SSE server and router
-    let (sse_server, router) = SseServer::new(sse_config);
-
-    // Attach MCP service to SSE server
-    let _cancel_token = sse_server.with_service(move || mcp_server.clone());
-
-    // Bind and serve
-    let listener = tokio::net::TcpListener::bind(bind_addr).await?;
-    log::info!("MCP HTTP/SSE server listening on http://{}", bind_addr);
-
-    axum::serve(listener, router.into_make_service()).await?;
-
-    Ok(())
-}
@@ -4,28 +4,72 @@
 
 //! CLI command handlers
 
-use clap::Subcommand;
-use project_tracker::{Config, Result};
-use project_tracker::db::{self, MilestoneResource, ProjectRepository, ProjectResource};
+use anyhow::Context;
+use clap::{Args, Subcommand};
+use project_tracker::{Config, Result, Storage};
+use project_tracker::fixtures;
+use project_tracker::diagram::{self, DiagramFormat, DiagramKind};
+use project_tracker::digest::{self, DigestFormat, DigestSink, SmtpSink, WriterSink};
+use project_tracker::db::{self, introspect::SchemaFormat, person_repo::DEFAULT_FORECAST_DAYS, project_repo::{DEFAULT_BRIEF_NOTE_LIMIT, MIN_ID_PREFIX_LEN}, AvailabilityStatus, LinkCheckRepository, LinkStatus, MilestoneResource, Person, PersonRepository, Project, ProjectRepository, ProjectResource, role_repo::role_key, RoleRepository, SavedQueryRepository, SubteamPolicy, Team, TeamRepository};
+use project_tracker::import_export::{self, DirectoryField, DirectoryImportOptions};
+use project_tracker::linkcheck::{self, HttpProbe};
+use project_tracker::mcp::resources;
+use project_tracker::notes;
+use project_tracker::report;
+use project_tracker::secrets::SecretStore;
+use project_tracker::utils::{format_local_date, parse_user_date};
+use project_tracker::webhook::{self, RetryPolicy, WebhookEvent};
 use chrono::Utc;
+use render::{Output, OutputFormat, ProjectDetails, TeamDetails};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use uuid::Uuid;
 
+pub mod render;
+
 #[derive(Subcommand)]
 pub enum ProjectAction {
     /// List all projects
-    List,
-    /// Add a new project
+    List {
+        /// Only projects watched by current_user_email
+        #[arg(long)]
+        watched: bool,
+        /// Only projects whose stakeholders or resources include people from this organization
+        #[arg(long)]
+        organization: Option<String>,
+    },
+    /// Add a new project; it is watched by current_user_email when that is set
     Add { name: String },
     /// Remove a project
     Remove { id: String },
     /// Show project details
     Show { id: String },
-    /// Add a resource to a project
+    /// Change a project's slug, or make a new one from its current name
+    Slug { id: String, slug: Option<String> },
+    /// Move a project to a phase, one of the configured project_phases
+    Phase { id: String, phase: String },
+    /// Watch a project, as current_user_email unless --email is given
+    Watch {
+        id: String,
+        #[arg(long)]
+        email: Option<String>,
+    },
+    /// Stop watching a project, as current_user_email unless --email is given
+    Unwatch {
+        id: String,
+        #[arg(long)]
+        email: Option<String>,
+    },
+    /// Add a resource to a project, or every member of a team with --team
     AddResource {
         project_id: String,
-        person_email: String,
+        #[arg(required_unless_present = "team", conflicts_with = "team")]
+        person_email: Option<String>,
         #[arg(short, long)]
         role: Option<String>,
+        /// Add every active member of this team instead of a single person
+        #[arg(short, long)]
+        team: Option<String>,
     },
     /// List resources for a project
     ListResources { project_id: String },
@@ -53,13 +97,67 @@ pub enum ProjectAction {
 #[derive(Subcommand)]
 pub enum PeopleAction {
     /// List all people
-    List,
+    List {
+        /// Also list deactivated people
+        #[arg(long)]
+        include_inactive: bool,
+    },
     /// Add a new person
-    Add { name: String },
+    Add {
+        email: String,
+        name: String,
+        #[arg(short, long)]
+        team: Option<String>,
+        /// Add the person even if their name looks like an existing person's
+        #[arg(long)]
+        force: bool,
+        /// Mark the person as a sanctioned exception to allowed_email_domains
+        #[arg(long)]
+        external: bool,
+        /// Organization the person works for, such as a vendor
+        #[arg(long)]
+        organization: Option<String>,
+    },
     /// Remove a person
-    Remove { id: String },
+    Remove {
+        email: String,
+        /// Also delete the records of 1:1s with the person
+        #[arg(long)]
+        delete_one_on_ones: bool,
+    },
     /// Show person details
-    Show { id: String },
+    Show { email: String },
+    /// Find active people with a skill, the most proficient first
+    Find {
+        /// Skill to look for, ignoring case
+        #[arg(long)]
+        skill: String,
+        /// Only people rated at least this level (1-5)
+        #[arg(long)]
+        min_level: Option<u8>,
+    },
+    /// Record that a person has a skill
+    AddSkill {
+        email: String,
+        skill: String,
+        /// Proficiency from 1 (beginner) to 5 (expert)
+        #[arg(long)]
+        level: Option<u8>,
+    },
+    /// Remove a skill from a person
+    RemoveSkill { email: String, skill: String },
+    /// Import people from a directory JSON export (displayName, primaryEmail, managerEmail, department)
+    ImportDirectory {
+        /// Directory export to read
+        #[arg(short, long)]
+        file: PathBuf,
+        /// Create a team for each department that doesn't have one
+        #[arg(long)]
+        create_teams: bool,
+        /// Fields of existing people to overwrite (name, team, manager)
+        #[arg(long, value_delimiter = ',')]
+        update: Vec<DirectoryField>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -84,111 +182,1295 @@ pub enum TeamAction {
     },
 }
 
-pub async fn handle_projects(action: ProjectAction, config: &Config) -> Result<()> {
+#[derive(Subcommand)]
+pub enum StakeholderAction {
+    /// Print a brief on a stakeholder: role, recent notes and open questions
+    Brief {
+        project_id: String,
+        email: String,
+        /// Number of recent notes to include
+        #[arg(short, long, default_value_t = DEFAULT_BRIEF_NOTE_LIMIT)]
+        notes: usize,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RoleAction {
+    /// List roles with how many resources and stakeholders use each
+    List,
+    /// List roles written in more than one way, such as "Tech Lead" and "tech-lead"
+    Variants,
+    /// Replace a role with another on every resource and stakeholder
+    Merge { old: String, new: String },
+}
+
+#[derive(Subcommand)]
+pub enum SnapshotAction {
+    /// Save a labelled copy of a project and its milestones
+    Create { project_id: String, label: String },
+    /// List a project's snapshots, oldest first
+    List { project_id: String },
+    /// Show what changed between two snapshots of the same project
+    Diff {
+        /// Older snapshot ID or prefix
+        from: String,
+        /// Newer snapshot ID or prefix
+        to: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum IdsAction {
+    /// Print short IDs and names of projects, optionally only IDs starting with a prefix
+    Projects { prefix: Option<String> },
+    /// Print short IDs and names of a project's milestones
+    Milestones { project_id: String },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    /// Rename a project type in the configuration and on every project that uses it
+    RenameType { old: String, new: String },
+}
+
+#[derive(Subcommand)]
+pub enum DbAction {
+    /// Show the database's schema version and the newest one this build supports
+    Version,
+    /// Check the data for problems such as circular manager references, and remove orphaned avatar files
+    Check,
+    /// Write a snapshot of the database to a single file for copying to another machine
+    ExportBundle {
+        /// Bundle file to write; an existing file is replaced
+        file: PathBuf,
+    },
+    /// Replace the database with a bundle exported on another machine
+    ImportBundle {
+        /// Bundle file written by `track db export-bundle`
+        file: PathBuf,
+        /// Import even if this database changed since its last sync; a backup is kept
+        #[arg(long)]
+        force: bool,
+    },
+    /// Describe the database's tables, columns, references and indexes
+    Schema {
+        /// Text format: markdown, or dot for a Graphviz entity-relationship diagram
+        #[arg(long, default_value = "markdown")]
+        format: SchemaFormat,
+        /// File to write to instead of stdout
+        #[arg(short = 'f', long)]
+        file: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DevAction {
+    /// Fill the database with generated teams, people and projects for trying things out
+    Seed {
+        /// Seed for the generator; the same seed always produces the same data
+        #[arg(long, default_value_t = fixtures::DEFAULT_SEED)]
+        seed: u64,
+        /// Add the demo data even if the database already has people, teams or projects
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum WebhookAction {
+    /// Send a signed ping event to a URL
+    Test {
+        url: String,
+        /// Secret to sign with (defaults to the secret configured for the URL)
+        #[arg(short, long)]
+        secret: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DigestAction {
+    /// Write the digest to stdout or a file
+    Generate {
+        /// Start of the period covered, as YYYY-MM-DD (defaults to 7 days ago)
+        #[arg(long)]
+        since: Option<String>,
+        /// Write HTML instead of markdown
+        #[arg(long)]
+        html: bool,
+        /// File to write to instead of stdout
+        #[arg(short = 'f', long)]
+        file: Option<PathBuf>,
+    },
+    /// Email the digest through the server in the [digest.smtp] config section
+    Send {
+        /// Start of the period covered, as YYYY-MM-DD (defaults to 7 days ago)
+        #[arg(long)]
+        since: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AuthAction {
+    /// Store a secret, such as smtp.password, read from stdin
+    SetKey {
+        /// Name of the secret
+        name: String,
+    },
+    /// Show where secrets are stored and which ones are set
+    Status,
+}
+
+#[derive(Subcommand)]
+pub enum ReportAction {
+    /// Write a team's quarterly review as markdown
+    TeamReview {
+        /// Team to review
+        team: String,
+        /// Calendar year, such as 2025
+        year: i32,
+        /// Quarter, such as Q2 or 2
+        quarter: String,
+        /// Print the review as JSON
+        #[arg(long)]
+        json: bool,
+        /// File to write to instead of stdout
+        #[arg(short = 'f', long)]
+        file: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ExportAction {
+    /// Write projects, milestones, people, teams, assignments and stakeholders to an Excel workbook
+    Xlsx {
+        /// Workbook file to write; an existing file is replaced
+        ///
+        /// Called --file rather than --output, which already picks the output format.
+        #[arg(short = 'f', long)]
+        file: PathBuf,
+    },
+}
+
+/// The email to watch projects as: the one given, or current_user_email
+fn watcher_email(email: Option<String>, config: &Config) -> Result<String> {
+    email
+        .or_else(|| config.current_user_email.clone())
+        .context("No email given; pass --email or set current_user_email in the config")
+}
+
+pub async fn handle_projects(action: ProjectAction, config: &Config) -> Result<Output> {
     log::debug!("Data directory: {}", config.data_dir);
 
     let db_path = config.database_path()?;
     let conn = db::open_database(&db_path)?;
-    let repo = ProjectRepository::new(&conn);
+    let repo = ProjectRepository::new(&conn)
+        .with_roles(&config.roles, config.allow_new_roles)
+        .with_resource_limit(config.max_resources_per_milestone, config.max_resources_hard_limit)
+        .with_phases(&config.project_phases);
 
-    match action {
-        ProjectAction::List => {
-            println!("Project management - coming soon");
-        }
-        ProjectAction::Add { .. } => {
-            println!("Project management - coming soon");
-        }
-        ProjectAction::Remove { .. } => {
-            println!("Project management - coming soon");
+    let output = match action {
+        ProjectAction::List { watched, organization } => {
+            let mut projects = if watched {
+                let email = watcher_email(None, config)?;
+                db::WatcherRepository::new(&conn).list_watched_projects(&email)?
+            } else {
+                repo.list_all()?
+            };
+            if let Some(organization) = organization {
+                let ids = db::OrganizationRepository::new(&conn).get_project_ids_for_org(&organization)?;
+                projects.retain(|p| ids.contains(&p.id));
+            }
+            Output::Projects(projects)
         }
-        ProjectAction::Show { .. } => {
-            println!("Project management - coming soon");
+        ProjectAction::Add { name } => {
+            let project = Project::new(name);
+            let tx = conn.unchecked_transaction()?;
+            repo.create(&project)?;
+            if let Some(email) = &config.current_user_email {
+                db::WatcherRepository::new(&conn).watch(&project.id, email)?;
+            }
+            tx.commit()?;
+            Output::done_with(format!("Added project {} ({})", project.name, project.id), &project)?
         }
-        ProjectAction::AddResource { project_id, person_email, role } => {
-            let project_uuid = Uuid::parse_str(&project_id)?;
-            let resource = ProjectResource {
-                project_id: project_uuid,
-                person_email: person_email.clone(),
-                role,
-                created_at: Utc::now(),
-            };
-            repo.add_project_resource(&project_uuid, &resource)?;
-            println!("Added resource {} to project {}", person_email, project_id);
+        ProjectAction::Remove { id } => {
+            let project_uuid = repo.resolve_project_ref(&id)?;
+            let project = repo
+                .find_by_id(&project_uuid)?
+                .ok_or_else(|| anyhow::anyhow!("Project not found: {}", id))?;
+            repo.delete(&project_uuid)?;
+            Output::done(format!("Removed project {} ({})", project.name, project.id))
         }
-        ProjectAction::ListResources { project_id } => {
-            let project_uuid = Uuid::parse_str(&project_id)?;
-            let resources = repo.get_project_resources(&project_uuid)?;
+        ProjectAction::Show { id } => {
+            let project_uuid = repo.resolve_project_ref(&id)?;
+            let project = repo
+                .find_by_id(&project_uuid)?
+                .ok_or_else(|| anyhow::anyhow!("Project not found: {}", id))?;
 
-            if resources.is_empty() {
-                println!("No resources found for project {}", project_id);
-            } else {
-                println!("Resources for project {}:", project_id);
-                for resource in resources {
-                    if let Some(role) = resource.role {
-                        println!("  {} ({})", resource.person_email, role);
-                    } else {
-                        println!("  {}", resource.person_email);
+            let mut links = Vec::new();
+            for note in repo.get_project_notes(&project_uuid)? {
+                for link in notes::extract_links(&note.body).into_iter().map(String::from) {
+                    if !links.contains(&link) {
+                        links.push(link);
                     }
                 }
             }
+
+            Output::Project(Box::new(ProjectDetails {
+                milestones: repo.get_milestones(&project_uuid)?,
+                links,
+                blocker_history: repo.get_blocker_history(&project_uuid)?,
+                project,
+            }))
+        }
+        ProjectAction::Slug { id, slug } => {
+            let project_uuid = repo.resolve_project_ref(&id)?;
+            let slug = repo.set_slug(&project_uuid, slug.as_deref())?;
+            Output::done(format!("Project {} is now {}", project_uuid, slug))
+        }
+        ProjectAction::Phase { id, phase } => {
+            let project_uuid = repo.resolve_project_ref(&id)?;
+            let project = repo.set_phase(&project_uuid, &phase)?;
+            let phase = project.phase.clone().unwrap_or_default();
+            Output::done_with(format!("Project {} is now in {}", project.name, phase), &project)?
+        }
+        ProjectAction::Watch { id, email } => {
+            let project_uuid = repo.resolve_project_ref(&id)?;
+            let email = watcher_email(email, config)?;
+            db::WatcherRepository::new(&conn).watch(&project_uuid, &email)?;
+            Output::done(format!("{} is watching project {}", email, id))
+        }
+        ProjectAction::Unwatch { id, email } => {
+            let project_uuid = repo.resolve_project_ref(&id)?;
+            let email = watcher_email(email, config)?;
+            db::WatcherRepository::new(&conn).unwatch(&project_uuid, &email)?;
+            Output::done(format!("{} stopped watching project {}", email, id))
+        }
+        ProjectAction::AddResource { project_id, person_email, role, team } => {
+            let project_uuid = repo.resolve_project_ref(&project_id)?;
+            if let Some(team) = team {
+                let result = repo.add_team_as_resources(&project_uuid, &team, role.as_deref())?;
+                let mut lines = Vec::new();
+                for email in &result.added {
+                    lines.push(format!("Added resource {} to project {}", email, project_id));
+                }
+                for email in &result.skipped {
+                    lines.push(format!("Skipped {} (already assigned or inactive)", email));
+                }
+                Output::done_with(lines.join("\n"), &result)?
+            } else {
+                let person_email = person_email.expect("clap requires a person email without --team");
+                let resource = ProjectResource {
+                    project_id: project_uuid,
+                    person_email: person_email.clone(),
+                    role: repo.normalize_role(role.as_deref())?,
+                    created_at: Utc::now(),
+                };
+                repo.add_project_resource(&project_uuid, &resource)?;
+                Output::done_with(format!("Added resource {} to project {}", person_email, project_id), &resource)?
+            }
+        }
+        ProjectAction::ListResources { project_id } => {
+            let project_uuid = repo.resolve_project_ref(&project_id)?;
+            Output::ProjectResources(repo.get_project_resources(&project_uuid)?)
         }
         ProjectAction::RemoveResource { project_id, person_email } => {
-            let project_uuid = Uuid::parse_str(&project_id)?;
+            let project_uuid = repo.resolve_project_ref(&project_id)?;
             repo.remove_project_resource(&project_uuid, &person_email)?;
-            println!("Removed resource {} from project {}", person_email, project_id);
+            Output::done(format!("Removed resource {} from project {}", person_email, project_id))
         }
         ProjectAction::AddMilestoneResource { milestone_id, person_email, role } => {
-            let milestone_uuid = Uuid::parse_str(&milestone_id)?;
+            let milestone_uuid = repo.resolve_milestone_id_prefix(&milestone_id)?;
             let resource = MilestoneResource {
                 milestone_id: milestone_uuid,
                 person_email: person_email.clone(),
-                role,
+                role: repo.normalize_role(role.as_deref())?,
                 created_at: Utc::now(),
             };
-            repo.add_milestone_resource(&milestone_uuid, &resource)?;
-            println!("Added resource {} to milestone {}", person_email, milestone_id);
+            if let Some(warning) = repo.add_milestone_resource(&milestone_uuid, &resource)? {
+                eprintln!("Warning: {}", warning);
+            }
+            Output::done_with(format!("Added resource {} to milestone {}", person_email, milestone_id), &resource)?
         }
         ProjectAction::ListMilestoneResources { milestone_id } => {
-            let milestone_uuid = Uuid::parse_str(&milestone_id)?;
-            let resources = repo.get_milestone_resources(&milestone_uuid)?;
+            let milestone_uuid = repo.resolve_milestone_id_prefix(&milestone_id)?;
+            Output::MilestoneResources(repo.get_milestone_resources(&milestone_uuid)?)
+        }
+        ProjectAction::RemoveMilestoneResource { milestone_id, person_email } => {
+            let milestone_uuid = repo.resolve_milestone_id_prefix(&milestone_id)?;
+            repo.remove_milestone_resource(&milestone_uuid, &person_email)?;
+            Output::done(format!("Removed resource {} from milestone {}", person_email, milestone_id))
+        }
+    };
 
-            if resources.is_empty() {
-                println!("No resources found for milestone {}", milestone_id);
+    Ok(output)
+}
+
+pub async fn handle_stakeholders(action: StakeholderAction, config: &Config) -> Result<()> {
+    let db_path = config.database_path()?;
+    let conn = db::open_database(&db_path)?;
+    let repo = ProjectRepository::new(&conn);
+
+    match action {
+        StakeholderAction::Brief { project_id, email, notes } => {
+            let project_uuid = repo.resolve_project_ref(&project_id)?;
+            let brief = repo.get_stakeholder_brief(&project_uuid, &email, notes)?;
+            print!("{}", resources::render_stakeholder_brief(&brief, config.tz()));
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn handle_roles(action: RoleAction, config: &Config) -> Result<()> {
+    let db_path = config.database_path()?;
+    let conn = db::open_database(&db_path)?;
+    let repo = RoleRepository::new(&conn).with_roles(&config.roles, config.allow_new_roles);
+
+    match action {
+        RoleAction::List => {
+            let in_use = repo.list_roles_in_use()?;
+            for role in repo.list_roles()? {
+                let uses: usize = in_use
+                    .iter()
+                    .filter(|usage| role_key(&usage.role) == role_key(&role))
+                    .map(|usage| usage.uses)
+                    .sum();
+                println!("{} ({})", role, uses);
+            }
+        }
+        RoleAction::Variants => {
+            let variants = repo.find_variants()?;
+            if variants.is_empty() {
+                println!("Every role is written one way");
+            }
+            for group in variants {
+                let spellings: Vec<String> = group
+                    .variants
+                    .iter()
+                    .map(|usage| format!("'{}' ({})", usage.role, usage.uses))
+                    .collect();
+                println!("{}: {}", group.canonical, spellings.join(", "));
+            }
+        }
+        RoleAction::Merge { old, new } => {
+            let changed = repo.merge_roles(&old, &new)?;
+            println!("Merged role '{}' into '{}' ({} assignments changed)", old, new, changed);
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn handle_snapshots(action: SnapshotAction, config: &Config) -> Result<Output> {
+    let db_path = config.database_path()?;
+    let conn = db::open_database(&db_path)?;
+    let repo = ProjectRepository::new(&conn);
+
+    let output = match action {
+        SnapshotAction::Create { project_id, label } => {
+            let project_uuid = repo.resolve_project_ref(&project_id)?;
+            let snapshot = repo.create_snapshot(&project_uuid, &label)?;
+            Output::done_with(
+                format!("Saved snapshot '{}' ({})", snapshot.label, short_id(&snapshot.id)),
+                &snapshot,
+            )?
+        }
+        SnapshotAction::List { project_id } => {
+            let project_uuid = repo.resolve_project_ref(&project_id)?;
+            Output::Snapshots(repo.list_snapshots(&project_uuid)?)
+        }
+        SnapshotAction::Diff { from, to } => {
+            let from = repo.resolve_snapshot_id_prefix(&from)?;
+            let to = repo.resolve_snapshot_id_prefix(&to)?;
+            Output::SnapshotDiff(Box::new(repo.compare_snapshots(&from, &to)?))
+        }
+    };
+
+    Ok(output)
+}
+
+pub async fn handle_ids(action: IdsAction, config: &Config) -> Result<()> {
+    let db_path = config.database_path()?;
+    let conn = db::open_database(&db_path)?;
+    let repo = ProjectRepository::new(&conn);
+
+    // Tab separated so the output can be piped through fzf and cut
+    match action {
+        IdsAction::Projects { prefix } => {
+            let prefix = prefix.map(|p| p.to_ascii_lowercase()).unwrap_or_default();
+            for project in repo.list_all()? {
+                let id = project.id.to_string();
+                if id.starts_with(&prefix) {
+                    println!("{}\t{}", short_id(&project.id), project.name);
+                }
+            }
+        }
+        IdsAction::Milestones { project_id } => {
+            let project_uuid = repo.resolve_project_ref(&project_id)?;
+            for milestone in repo.get_milestones(&project_uuid)? {
+                println!("{}\t{}", short_id(&milestone.id), milestone.name);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Arguments of `track hygiene`
+#[derive(Args)]
+pub struct HygieneArgs {
+    /// Gaps allowed before exiting with an error (defaults to hygiene.max_gaps in the config)
+    #[arg(long, value_name = "N")]
+    pub max_gaps: Option<usize>,
+    /// Print the report as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Arguments of `track linkcheck`
+#[derive(Args)]
+pub struct LinkcheckArgs {
+    /// Only check the links of this project (ID or slug)
+    #[arg(long)]
+    pub project: Option<String>,
+    /// Broken links allowed before exiting with an error (defaults to linkcheck.max_dead in the config)
+    #[arg(long, value_name = "N")]
+    pub max_dead: Option<usize>,
+    /// Print every link and its result as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Arguments of `track diagram`
+#[derive(Args)]
+pub struct DiagramArgs {
+    /// What to draw: org, teams or projects
+    pub kind: DiagramKind,
+    /// Text format: mermaid or dot
+    #[arg(long, default_value = "mermaid")]
+    pub format: DiagramFormat,
+    /// File to write to instead of stdout
+    #[arg(short = 'f', long)]
+    pub file: Option<PathBuf>,
+}
+
+/// Arguments of `track query`
+#[derive(Args)]
+pub struct QueryArgs {
+    /// Filter expression, such as 'team = "Platform" AND milestone.due_date < 2025-04-01'
+    #[arg(required_unless_present_any = ["saved", "list", "delete"])]
+    pub expression: Option<String>,
+    /// Save the expression under this name before running it
+    #[arg(long, value_name = "NAME", requires = "expression")]
+    pub save: Option<String>,
+    /// Run the query saved under this name
+    #[arg(short = 'n', long, value_name = "NAME", conflicts_with = "expression")]
+    pub saved: Option<String>,
+    /// List saved queries
+    #[arg(long, conflicts_with_all = ["expression", "saved", "delete"])]
+    pub list: bool,
+    /// Delete the query saved under this name
+    #[arg(long, value_name = "NAME", conflicts_with_all = ["expression", "saved"])]
+    pub delete: Option<String>,
+    /// Print matching projects as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub async fn handle_query(args: QueryArgs, config: &Config) -> Result<()> {
+    let db_path = config.database_path()?;
+    let conn = db::open_database(&db_path)?;
+    let saved_queries = SavedQueryRepository::new(&conn);
+
+    if args.list {
+        for saved in saved_queries.list_all()? {
+            println!("{}\t{}", saved.name, saved.expression);
+        }
+        return Ok(());
+    }
+    if let Some(name) = args.delete {
+        saved_queries.delete(&name)?;
+        println!("Deleted saved query {}", name);
+        return Ok(());
+    }
+
+    let projects = match (args.expression, args.saved) {
+        (Some(expression), _) => {
+            if let Some(name) = &args.save {
+                saved_queries.save(name, &expression)?;
+                eprintln!("Saved query {}", name.trim());
+            }
+            db::query::run_query(&conn, &expression)?
+        }
+        (None, Some(name)) => saved_queries.run(&name)?,
+        (None, None) => return Ok(()),
+    };
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&projects)?);
+        return Ok(());
+    }
+    // Tab separated like `track ids`, so results can be piped through cut
+    for project in projects {
+        let due = project
+            .due_date
+            .map(|due| format_local_date(due, config.tz()))
+            .unwrap_or_default();
+        println!(
+            "{}\t{}\t{}\t{}",
+            short_id(&project.id),
+            project.name,
+            project.team.as_deref().unwrap_or(""),
+            due
+        );
+    }
+
+    Ok(())
+}
+
+/// The ID prefix printed by `track ids`
+fn short_id(id: &Uuid) -> String {
+    id.to_string()[..MIN_ID_PREFIX_LEN].to_string()
+}
+
+pub async fn handle_people(action: PeopleAction, config: &Config) -> Result<Output> {
+    let db_path = config.database_path()?;
+    let conn = db::open_database(&db_path)?;
+    let repo = PersonRepository::new(&conn).with_allowed_domains(&config.allowed_email_domains);
+
+    let output = match action {
+        PeopleAction::List { include_inactive } => Output::People(repo.list_all(include_inactive)?),
+        PeopleAction::Add { email, name, team, force, external, organization } => {
+            let mut person = Person::new(email, name);
+            person.team = team;
+            person.external = external;
+            person.organization = organization;
+            if force {
+                repo.create(&person)?;
             } else {
-                println!("Resources for milestone {}:", milestone_id);
-                for resource in resources {
-                    if let Some(role) = resource.role {
-                        println!("  {} ({})", resource.person_email, role);
-                    } else {
-                        println!("  {}", resource.person_email);
-                    }
+                PersonRepository::new(&conn)
+                    .with_allowed_domains(&config.allowed_email_domains)
+                    .with_duplicate_check(config.person_duplicate_threshold)
+                    .create(&person)?;
+            }
+            Output::done_with(format!("Added person {}", person.email), &person)?
+        }
+        PeopleAction::Remove { email, delete_one_on_ones } => {
+            repo.delete(&email, delete_one_on_ones)?;
+            Output::done(format!("Removed person {}", email))
+        }
+        PeopleAction::Show { email } => {
+            let person = repo
+                .find_by_email(&email)?
+                .ok_or_else(|| anyhow::anyhow!("Person not found: {}", email))?;
+            Output::Person(Box::new(person))
+        }
+        PeopleAction::Find { skill, min_level } => Output::People(repo.find_people_by_skill(&skill, min_level)?),
+        PeopleAction::AddSkill { email, skill, level } => {
+            let skill = repo.add_skill(&email, &skill, level)?;
+            Output::done_with(format!("Added skill {} to {}", skill.skill, email), &skill)?
+        }
+        PeopleAction::RemoveSkill { email, skill } => {
+            repo.remove_skill(&email, &skill)?;
+            Output::done(format!("Removed skill {} from {}", skill, email))
+        }
+        PeopleAction::ImportDirectory { file, create_teams, update } => {
+            let reader = std::io::BufReader::new(std::fs::File::open(&file)?);
+            let opts = DirectoryImportOptions { create_teams, update_fields: update };
+            let report = import_export::import_directory_json(&conn, reader, &opts)?;
+
+            let mut message = format!(
+                "Imported {}: {} created, {} updated, {} skipped",
+                file.display(),
+                report.created,
+                report.updated,
+                report.skipped
+            );
+            for warning in &report.warnings {
+                message.push_str(&format!("\nwarning: {}", warning));
+            }
+            Output::done_with(message, &report)?
+        }
+    };
+
+    Ok(output)
+}
+
+pub async fn handle_teams(action: TeamAction, config: &Config) -> Result<Output> {
+    let db_path = config.database_path()?;
+    let conn = db::open_database(&db_path)?;
+    let repo = TeamRepository::new(&conn);
+
+    let output = match action {
+        TeamAction::List => Output::Teams(repo.list_all()?),
+        TeamAction::Add { name } => {
+            let team = Team::new(name);
+            repo.create(&team)?;
+            Output::done_with(format!("Added team {}", team.name), &team)?
+        }
+        TeamAction::Remove { name } => {
+            repo.delete(&name, SubteamPolicy::Refuse)?;
+            Output::done(format!("Removed team {}", name))
+        }
+        TeamAction::Show { name } => {
+            let team = repo
+                .find_by_name(&name)?
+                .ok_or_else(|| anyhow::anyhow!("Team not found: {}", name))?;
+            Output::Team(TeamDetails { members: repo.get_members(&name)?, team })
+        }
+        TeamAction::AddMember { team_name, person_email } => {
+            repo.add_member(&team_name, &person_email)?;
+            Output::done(format!("Added {} to team {}", person_email, team_name))
+        }
+        TeamAction::RemoveMember { team_name, person_email } => {
+            repo.remove_member(&team_name, &person_email)?;
+            Output::done(format!("Removed {} from team {}", person_email, team_name))
+        }
+    };
+
+    Ok(output)
+}
+
+pub async fn handle_report(action: ReportAction, config: &Config) -> Result<()> {
+    match action {
+        ReportAction::TeamReview { team, year, quarter, json, file } => {
+            let quarter = parse_quarter(&quarter)?;
+            let db_path = config.database_path()?;
+            let conn = db::open_database(&db_path)?;
+            let review = report::team_quarterly_review(&conn, &team, year, quarter, config.tz())?;
+            let text = if json {
+                format!("{}\n", serde_json::to_string_pretty(&review)?)
+            } else {
+                review.to_markdown(config.locale())
+            };
+            match file {
+                Some(path) => {
+                    std::fs::write(&path, text).with_context(|| format!("Failed to write {}", path.display()))?;
+                    println!("Wrote {}", path.display());
                 }
+                None => print!("{}", text),
             }
         }
-        ProjectAction::RemoveMilestoneResource { milestone_id, person_email } => {
-            let milestone_uuid = Uuid::parse_str(&milestone_id)?;
-            repo.remove_milestone_resource(&milestone_uuid, &person_email)?;
-            println!("Removed resource {} from milestone {}", person_email, milestone_id);
+    }
+    Ok(())
+}
+
+pub async fn handle_export(action: ExportAction, config: &Config) -> Result<Output> {
+    match action {
+        ExportAction::Xlsx { file } => {
+            let db_path = config.database_path()?;
+            let conn = db::open_database(&db_path)?;
+            let sheets = import_export::export_workbook(&conn, &file, config.tz())?;
+            let counts: Vec<String> = sheets
+                .iter()
+                .filter(|sheet| sheet.name != "Metadata")
+                .map(|sheet| format!("{} {}", sheet.rows, sheet.name.to_lowercase()))
+                .collect();
+            Ok(Output::done_with(format!("Wrote {} ({})", file.display(), counts.join(", ")), &sheets)?)
         }
     }
+}
+
+pub async fn handle_stats(format: OutputFormat, config: &Config) -> Result<()> {
+    let db_path = config.database_path()?;
+    let conn = db::open_database(&db_path)?;
+    let stats = db::stats::portfolio(&conn)?;
+
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+
+    let locale = config.locale();
+    let text = |key| locale.text(key).to_string();
+    let mut rows = vec![(text("report.projects"), stats.total_projects.to_string())];
+    for group in &stats.projects_by_type {
+        let name = group.name.as_deref().unwrap_or(locale.text("report.no_type"));
+        rows.push((format!("  {}", locale.format("report.by_type", &[("name", &name)])), group.count.to_string()));
+    }
+    for group in &stats.projects_by_team {
+        let name = group.name.as_deref().unwrap_or(locale.text("report.no_team"));
+        rows.push((format!("  {}", locale.format("report.by_team", &[("name", &name)])), group.count.to_string()));
+    }
+    rows.extend([
+        (text("report.milestones"), stats.total_milestones.to_string()),
+        (format!("  {}", text("report.due_this_month")), stats.milestones_due_this_month.to_string()),
+        (format!("  {}", text("report.due_this_quarter")), stats.milestones_due_this_quarter.to_string()),
+        (text("report.milestones_per_project"), locale.number(stats.average_milestones_per_project, 1)),
+        (text("report.unassigned_people"), stats.unassigned_people.to_string()),
+        (text("report.teams_without_manager"), stats.teams_without_manager.to_string()),
+        (text("report.recent_notes"), stats.notes_last_7_days.to_string()),
+    ]);
+
+    let as_of = locale.datetime(stats.as_of, config.tz());
+    println!("{}", locale.format("report.as_of", &[("date", &as_of)]));
+    // Count characters, not bytes, so labels with umlauts still line up
+    let width = rows.iter().map(|(label, _)| label.chars().count()).max().unwrap_or(0);
+    for (label, value) in rows {
+        println!("{:<width$}  {:>6}", label, value, width = width);
+    }
 
     Ok(())
 }
 
-pub async fn handle_people(_action: PeopleAction, config: &Config) -> Result<()> {
-    log::debug!("Data directory: {}", config.data_dir);
-    println!("People management - coming soon");
+pub async fn handle_hygiene(args: HygieneArgs, config: &Config) -> Result<()> {
+    let db_path = config.database_path()?;
+    let conn = db::open_database(&db_path)?;
+    let report = db::hygiene::find_gaps_with(&conn, &config.hygiene.checks, &config.allowed_email_domains)?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_gaps("People without a manager", &report.people_missing_manager, |p| {
+            format!("{} <{}>", p.name, p.email)
+        });
+        print_gaps("People without a team", &report.people_missing_team, |p| {
+            format!("{} <{}>", p.name, p.email)
+        });
+        print_gaps("Teams without a manager", &report.teams_missing_manager, |t| t.name.clone());
+        print_gaps("Projects without a technical lead", &report.projects_missing_technical_lead, |p| {
+            format!("{} ({})", p.name, p.id)
+        });
+        print_gaps("Projects without a requirements owner", &report.projects_missing_requirements_owner, |p| {
+            format!("{} ({})", p.name, p.id)
+        });
+        print_gaps("Projects without a due date", &report.projects_missing_due_date, |p| {
+            format!("{} ({})", p.name, p.id)
+        });
+        print_gaps("Milestones without a due date", &report.milestones_missing_due_date, |m| {
+            format!("{} #{} {} ({})", m.project_name, m.number, m.name, m.id)
+        });
+        print_gaps("Milestones without a technical lead", &report.milestones_missing_lead, |m| {
+            format!("{} #{} {} ({})", m.project_name, m.number, m.name, m.id)
+        });
+        print_gaps("Stakeholders who aren't people", &report.unknown_stakeholders, |s| {
+            format!("{} on {} ({})", s.stakeholder_email, s.project_name, s.project_id)
+        });
+        print_gaps("People outside the allowed email domains", &report.people_outside_allowed_domains, |p| {
+            format!("{} <{}>", p.name, p.email)
+        });
+        print_gaps("Note references that don't lead anywhere", &report.dangling_references, |r| {
+            format!("{} in \"{}\" on {} ({})", r.reference, r.note_title, r.project_name, r.note_id)
+        });
+        print_gaps("Completed projects without a retrospective", &report.projects_missing_retrospective, |p| {
+            format!("{} ({})", p.name, p.id)
+        });
+        if report.total() == 0 {
+            println!("No gaps found");
+        }
+    }
+
+    // Fail so cron jobs and scripts notice
+    let max_gaps = args.max_gaps.unwrap_or(config.hygiene.max_gaps);
+    if report.total() > max_gaps {
+        anyhow::bail!("Found {} gap(s), more than the {} allowed", report.total(), max_gaps);
+    }
+
     Ok(())
 }
 
-pub async fn handle_teams(_action: TeamAction, config: &Config) -> Result<()> {
-    log::debug!("Data directory: {}", config.data_dir);
-    println!("Team management - coming soon");
+pub async fn handle_linkcheck(args: LinkcheckArgs, config: &Config) -> Result<()> {
+    let db_path = config.database_path()?;
+    let conn = db::open_database(&db_path)?;
+    let project = args
+        .project
+        .map(|id| ProjectRepository::new(&conn).resolve_project_ref(&id))
+        .transpose()?;
+    let repo = LinkCheckRepository::new(&conn).with_jira_url(&config.jira_url);
+    let links = repo.list_links(project.as_ref())?;
+
+    let probe = Arc::new(HttpProbe::new(&config.linkcheck)?);
+    let checks = linkcheck::check_urls(
+        probe,
+        links.iter().map(|link| link.url.clone()),
+        &config.linkcheck,
+        |check, checked, total| log::debug!("[{}/{}] {} {}", checked, total, check.status, check.url),
+    )
+    .await;
+    repo.save_checks(&checks)?;
+
+    let report = repo.get_report(project.as_ref())?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        let broken: Vec<_> = report
+            .iter()
+            .filter(|r| r.check.as_ref().is_some_and(|c| c.status.is_broken()))
+            .collect();
+        if !broken.is_empty() {
+            println!("Broken links ({}):", broken.len());
+        }
+        for r in &broken {
+            let check = r.check.as_ref().expect("broken links have a check");
+            let detail = match (check.http_status, &check.error) {
+                (Some(code), _) => format!("HTTP {}", code),
+                (None, Some(error)) => error.clone(),
+                (None, None) => check.status.to_string(),
+            };
+            println!("  {} - {}: {} ({})", r.link.project_name, r.link.label, r.link.url, detail);
+        }
+        let count = |status: LinkStatus| checks.iter().filter(|c| c.status == status).count();
+        println!(
+            "Checked {} link(s): {} ok, {} dead, {} unreachable, {} skipped",
+            checks.len(),
+            count(LinkStatus::Ok),
+            count(LinkStatus::Dead),
+            count(LinkStatus::Unreachable),
+            count(LinkStatus::Skipped)
+        );
+    }
+
+    // Fail so cron jobs and scripts notice
+    let broken = linkcheck::count_broken(&checks);
+    let max_dead = args.max_dead.unwrap_or(config.linkcheck.max_dead);
+    if broken > max_dead {
+        anyhow::bail!("Found {} broken link(s), more than the {} allowed", broken, max_dead);
+    }
+
     Ok(())
 }
 
-pub async fn handle_report(_format: &str, config: &Config) -> Result<()> {
-    log::debug!("Data directory: {}", config.data_dir);
-    println!("Report generation - coming soon");
+fn print_gaps<T>(title: &str, gaps: &[T], describe: impl Fn(&T) -> String) {
+    if gaps.is_empty() {
+        return;
+    }
+    println!("{} ({}):", title, gaps.len());
+    for gap in gaps {
+        println!("  {}", describe(gap));
+    }
+}
+
+/// Arguments of `track plan`
+#[derive(Args)]
+pub struct PlanArgs {
+    /// Calendar year, such as 2025
+    pub year: i32,
+    /// Quarter, such as Q3 or 3
+    pub quarter: String,
+    /// Only include milestones owned by this team
+    #[arg(short, long)]
+    pub team: Option<String>,
+    /// Print the plan as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Arguments of `track today`
+#[derive(Args)]
+pub struct TodayArgs {
+    /// Whose list to show; defaults to current_user_email
+    #[arg(long)]
+    pub email: Option<String>,
+    /// Print the list as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Arguments of `track forecast`
+#[derive(Args)]
+pub struct ForecastArgs {
+    /// Days ahead to look
+    #[arg(long, default_value_t = DEFAULT_FORECAST_DAYS)]
+    pub days: u32,
+    /// Print the forecast as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Parse a quarter entered as "Q3", "q3" or "3"
+fn parse_quarter(s: &str) -> Result<u32> {
+    let digits = s.strip_prefix(['Q', 'q']).unwrap_or(s);
+    match digits.parse::<u32>() {
+        Ok(quarter @ 1..=4) => Ok(quarter),
+        _ => anyhow::bail!("Invalid quarter '{}', expected Q1 to Q4", s),
+    }
+}
+
+pub async fn handle_plan(args: PlanArgs, config: &Config) -> Result<()> {
+    let quarter = parse_quarter(&args.quarter)?;
+    let db_path = config.database_path()?;
+    let conn = db::open_database(&db_path)?;
+    let tz = config.tz();
+    let plan = ProjectRepository::new(&conn).get_quarter_plan(args.year, quarter, tz, args.team.as_deref())?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&plan)?);
+        return Ok(());
+    }
+
+    println!("Q{} {}: {} milestone(s)", plan.quarter, plan.year, plan.milestone_count);
+    for team in &plan.teams {
+        println!();
+        println!("{} ({}):", team.team.as_deref().unwrap_or("Unassigned"), team.milestone_count);
+        for project in &team.projects {
+            println!("  {}:", project.project_name);
+            for milestone in &project.milestones {
+                let due = milestone.due_date.map(|d| format_local_date(d, tz)).unwrap_or_default();
+                println!("    #{} {}  {}", milestone.number, milestone.name, due);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn handle_today(args: TodayArgs, config: &Config) -> Result<()> {
+    let email = watcher_email(args.email, config)?;
+    let db_path = config.database_path()?;
+    let conn = db::open_database(&db_path)?;
+    let focus = ProjectRepository::new(&conn)
+        .with_stakeholder_contact_days(config.stakeholder_contact_days)
+        .get_focus_list(&email, Utc::now())?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&focus)?);
+        return Ok(());
+    }
+
+    if focus.is_empty() {
+        println!("Nothing needs attention today");
+        return Ok(());
+    }
+    for item in &focus {
+        // Project items are titled with the project's name already
+        if item.title == item.project_name {
+            println!("{:>3}  {} - {}", item.score, item.title, item.reason);
+        } else {
+            println!("{:>3}  {} ({}) - {}", item.score, item.title, item.project_name, item.reason);
+        }
+        println!("     {}", item.link);
+    }
+
+    Ok(())
+}
+
+pub async fn handle_forecast(args: ForecastArgs, config: &Config) -> Result<()> {
+    let db_path = config.database_path()?;
+    let conn = db::open_database(&db_path)?;
+    let tz = config.tz();
+    let forecast = PersonRepository::new(&conn).get_availability_forecast(args.days, Utc::now())?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&forecast)?);
+        return Ok(());
+    }
+
+    println!(
+        "Next {} days, to {}: {} unassigned, {} freeing up, {} busy, {} unknown",
+        forecast.horizon_days,
+        format_local_date(forecast.until, tz),
+        forecast.unassigned,
+        forecast.freeing_up,
+        forecast.busy,
+        forecast.unknown
+    );
+    let mut out = std::io::stdout().lock();
+    for team in &forecast.teams {
+        println!();
+        println!("{}:", team.team.as_deref().unwrap_or("No team"));
+        render::table(
+            &mut out,
+            &["NAME", "EMAIL", "STATUS", "FREE ON", "MILESTONES"],
+            team.people.iter().map(|person| {
+                let status = match person.status {
+                    AvailabilityStatus::Unassigned => "unassigned",
+                    AvailabilityStatus::FreesUp => "frees up",
+                    AvailabilityStatus::Busy => "busy",
+                    AvailabilityStatus::Unknown => "unknown",
+                };
+                let milestones = match person.undated_milestones {
+                    0 => person.open_milestones.to_string(),
+                    undated => format!("{} ({} undated)", person.open_milestones, undated),
+                };
+                vec![
+                    person.name.clone(),
+                    person.email.clone(),
+                    status.to_string(),
+                    person.free_on.map(|date| format_local_date(date, tz)).unwrap_or_default(),
+                    milestones,
+                ]
+            }),
+        )?;
+    }
+
+    Ok(())
+}
+
+pub async fn handle_config(action: ConfigAction, config: &Config, config_path: &Path) -> Result<()> {
+    match action {
+        ConfigAction::RenameType { old, new } => {
+            let db_path = config.database_path()?;
+            let conn = db::open_database(&db_path)?;
+
+            // Only commit the projects once the new configuration is saved
+            let tx = conn.unchecked_transaction()?;
+            let renamed = ProjectRepository::new(&conn).rename_project_type(&old, &new)?;
+            let mut updated = config.clone();
+            updated.rename_project_type(&old, &new);
+            updated.save(config_path)?;
+            tx.commit()?;
+
+            println!(
+                "Renamed project type '{}' to '{}' in {} and on {} project(s)",
+                old,
+                new,
+                config_path.display(),
+                renamed
+            );
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn handle_db(action: DbAction, config: &Config) -> Result<()> {
+    match action {
+        DbAction::Version => {
+            let db_path = config.database_path()?;
+            println!("Workspace: {}", config.workspace());
+            println!("Database: {}", db_path.display());
+
+            // Read the version without opening the database, which would migrate it
+            let current = if db_path.exists() {
+                let conn = rusqlite::Connection::open_with_flags(&db_path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+                db::schema::read_schema_version(&conn)?
+            } else {
+                None
+            };
+            let supported = db::schema::SUPPORTED_SCHEMA_VERSION;
+
+            match current {
+                Some(version) => println!("Schema version: {}", version),
+                None => println!("Schema version: none (not created yet)"),
+            }
+            println!("Supported schema version: {}", supported);
+
+            match current {
+                Some(version) if version > supported => {
+                    println!("This database was written by a newer version of Project Tracker; upgrade to open it")
+                }
+                Some(version) if version < supported => println!(
+                    "Migrations will be applied the next time the database is opened (backup: {})",
+                    db::backup_path(&db_path, supported).display()
+                ),
+                _ => {}
+            }
+        }
+        DbAction::Check => {
+            let db_path = config.database_path()?;
+            let conn = db::open_database(&db_path)?;
+
+            let cycles = db::maintenance::find_manager_cycles(&conn)?;
+            let invalid_ids = db::maintenance::find_invalid_uuids(&conn)?;
+            if cycles.is_empty() && invalid_ids.is_empty() {
+                println!("No problems found");
+            }
+            if !cycles.is_empty() {
+                println!("Circular manager references ({}):", cycles.len());
+                for cycle in &cycles {
+                    println!("  {}", cycle.join(" -> "));
+                }
+                println!("Edit one person in each cycle to remove or change their manager");
+            }
+            if !invalid_ids.is_empty() {
+                println!("Invalid IDs ({}):", invalid_ids.len());
+                for row in &invalid_ids {
+                    println!("  {}.{} = '{}' (rowid {})", row.table, row.column, row.value, row.rowid);
+                }
+                println!("Records with these IDs can't be loaded until the rows are fixed or removed");
+            }
+
+            let storage = Storage::new(config.data_dir_path()?)?;
+            let in_use = db::PersonRepository::new(&conn).avatar_paths()?;
+            let removed = storage.sweep_avatars(&in_use)?;
+            if !removed.is_empty() {
+                println!("Removed {} orphaned avatar file(s)", removed.len());
+            }
+
+            let in_use = db::AttachmentRepository::new(&conn).stored_hashes()?;
+            let removed = storage.sweep_attachments(&in_use)?;
+            if !removed.is_empty() {
+                println!("Removed {} orphaned attachment file(s)", removed.len());
+            }
+        }
+        DbAction::ExportBundle { file } => {
+            let conn = db::open_database(config.database_path()?)?;
+            let storage = Storage::new(config.data_dir_path()?)?;
+            let bundle = storage.export_sync_bundle(&conn, &file)?;
+            println!("Exported bundle {} to {}", bundle.id, file.display());
+            println!("Import it on the other machine with: track db import-bundle {}", file.display());
+        }
+        DbAction::ImportBundle { file, force } => {
+            let mut conn = db::open_database(config.database_path()?)?;
+            let storage = Storage::new(config.data_dir_path()?)?;
+            match storage.import_sync_bundle(&mut conn, &file, force)? {
+                Some(bundle) => println!(
+                    "Imported bundle {} exported on {} at {}",
+                    bundle.id,
+                    bundle.host,
+                    config.locale().datetime(bundle.created_at, config.tz())
+                ),
+                None => println!("The database already matches this bundle"),
+            }
+        }
+        DbAction::Schema { format, file } => {
+            let conn = db::open_database(config.database_path()?)?;
+            let text = db::introspect::describe_schema(&conn)?.render(format);
+            match file {
+                Some(path) => {
+                    std::fs::write(&path, text).with_context(|| format!("Failed to write {}", path.display()))?;
+                    println!("Wrote {} schema to {}", format, path.display());
+                }
+                None => print!("{}", text),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn handle_dev(action: DevAction, config: &Config) -> Result<()> {
+    match action {
+        DevAction::Seed { seed, force } => {
+            let db_path = config.database_path()?;
+            let conn = db::open_database(&db_path)?;
+            if !force && !fixtures::is_empty(&conn)? {
+                anyhow::bail!("The database isn't empty; use --force to add demo data anyway");
+            }
+
+            let summary = fixtures::seed_demo_data(&conn, seed)?;
+            println!("Seeded demo data with seed {}:", seed);
+            println!("  Teams: {}", summary.teams);
+            println!("  People: {}", summary.people);
+            println!("  Projects: {}", summary.projects);
+            println!("  Milestones: {}", summary.milestones);
+            println!("  Resources: {}", summary.resources);
+            println!("  Stakeholders: {}", summary.stakeholders);
+            println!("  Notes: {}", summary.notes);
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn handle_digest(action: DigestAction, config: &Config) -> Result<()> {
+    let tz = config.tz();
+    let since = match &action {
+        DigestAction::Generate { since, .. } | DigestAction::Send { since } => since.as_deref(),
+    };
+    let now = Utc::now();
+    let since = match since {
+        Some(since) => parse_user_date(since, tz)?,
+        None => now - chrono::Duration::days(digest::DEFAULT_LOOKBACK_DAYS),
+    };
+
+    let db_path = config.database_path()?;
+    let conn = db::open_database(&db_path)?;
+    let report = digest::compose(&conn, since, now, tz, config.current_user_email.as_deref())?;
+
+    match action {
+        DigestAction::Generate { html, file, .. } => {
+            let format = if html { DigestFormat::Html } else { DigestFormat::Markdown };
+            match file {
+                Some(path) => {
+                    let file = std::fs::File::create(&path)
+                        .with_context(|| format!("Failed to create {}", path.display()))?;
+                    WriterSink::new(file, format, config.locale()).deliver(&report)?;
+                    println!("Wrote digest to {}", path.display());
+                }
+                None => WriterSink::new(std::io::stdout().lock(), format, config.locale()).deliver(&report)?,
+            }
+        }
+        DigestAction::Send { .. } => {
+            let smtp = config
+                .digest
+                .smtp
+                .as_ref()
+                .context("No mail server configured; add a [digest.smtp] section to the config")?;
+            let secrets = SecretStore::open(config)?;
+            SmtpSink::from_config(smtp, &secrets, config.locale())?.deliver(&report)?;
+            println!("Sent digest to {}", smtp.to.join(", "));
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn handle_auth(action: AuthAction, config: &Config) -> Result<()> {
+    let store = SecretStore::open(config)?;
+    match action {
+        AuthAction::SetKey { name } => {
+            let mut value = String::new();
+            std::io::Read::read_to_string(&mut std::io::stdin().lock(), &mut value)
+                .context("Failed to read the secret from stdin")?;
+            let value = value.trim_end_matches(['\r', '\n']);
+            if value.is_empty() {
+                anyhow::bail!("No secret given on stdin");
+            }
+            store.set(&name, value)?;
+            println!("Stored {} in the {}", name, store.location());
+        }
+        AuthAction::Status => {
+            println!("Backend: {}", store.location());
+            match store.names() {
+                Ok(names) if names.is_empty() => println!("No secrets stored"),
+                Ok(names) => {
+                    println!("Stored secrets:");
+                    for name in names {
+                        println!("  {}", name);
+                    }
+                }
+                Err(e) => println!("Unavailable: {:#}", e),
+            }
+        }
+    }
+    Ok(())
+}
+
+pub async fn handle_diagram(args: DiagramArgs, config: &Config) -> Result<()> {
+    let db_path = config.database_path()?;
+    let conn = db::open_database(&db_path)?;
+    let text = diagram::build(&conn, args.kind, Utc::now())?.render(args.format);
+
+    match args.file {
+        Some(path) => {
+            std::fs::write(&path, text).with_context(|| format!("Failed to write {}", path.display()))?;
+            println!("Wrote {} {} diagram to {}", args.kind, args.format, path.display());
+        }
+        None => print!("{}", text),
+    }
+    Ok(())
+}
+
+pub async fn handle_webhooks(action: WebhookAction, config: &Config) -> Result<()> {
+    match action {
+        WebhookAction::Test { url, secret } => {
+            let secret = secret.or_else(|| {
+                config
+                    .webhooks
+                    .iter()
+                    .find(|w| w.url == url)
+                    .and_then(|w| w.secret.clone())
+            });
+
+            let event = WebhookEvent::new(
+                webhook::PING,
+                "webhook",
+                &url,
+                serde_json::json!({ "message": "Test event from track webhooks test" }),
+            );
+            let body = serde_json::to_vec(&event)?;
+
+            let client = reqwest::Client::new();
+            webhook::deliver(&client, &url, secret.as_deref(), &body, &RetryPolicy::none()).await?;
+
+            if secret.is_some() {
+                println!("Delivered signed ping to {}", url);
+            } else {
+                println!("Delivered unsigned ping to {} (no secret configured)", url);
+            }
+        }
+    }
+
     Ok(())
 }
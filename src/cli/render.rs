@@ -0,0 +1,660 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Printing command results as tables or JSON
+//!
+//! Handlers return an [`Output`] instead of printing. `--output table`, the
+//! default, prints it for people to read; `--output json` prints the same
+//! data for scripts. Records are the library's serde models unchanged, the
+//! same JSON the MCP server returns. Commands that change data print
+//! `{"status": "ok", "message": ...}`, with the record they created under
+//! `result` when there is one, and failures print
+//! `{"status": "error", "error": ...}` to stderr.
+
+use super::short_id;
+use chrono_tz::Tz;
+use clap::ValueEnum;
+use project_tracker::db::{
+    BlockerEntry, FieldChange, Milestone, MilestoneResource, Person, Project, ProjectResource,
+    ProjectSnapshot, SnapshotDiff, Team,
+};
+use project_tracker::i18n::Locale;
+use serde::Serialize;
+use std::io::{self, Write};
+
+/// How command results are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum OutputFormat {
+    /// Text for reading in a terminal
+    #[default]
+    Table,
+    /// JSON for scripts and jq
+    Json,
+}
+
+/// A project with its milestones, the links in its notes and its blocker history
+#[derive(Debug, Serialize)]
+pub struct ProjectDetails {
+    pub project: Project,
+    pub milestones: Vec<Milestone>,
+    pub links: Vec<String>,
+    pub blocker_history: Vec<BlockerEntry>,
+}
+
+/// A team with its members
+#[derive(Debug, Serialize)]
+pub struct TeamDetails {
+    pub team: Team,
+    pub members: Vec<Person>,
+}
+
+/// Result of a command that changed data
+#[derive(Debug, Serialize)]
+pub struct Done {
+    status: &'static str,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+}
+
+/// What a command produced
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum Output {
+    Projects(Vec<Project>),
+    Project(Box<ProjectDetails>),
+    People(Vec<Person>),
+    Person(Box<Person>),
+    Teams(Vec<Team>),
+    Team(TeamDetails),
+    ProjectResources(Vec<ProjectResource>),
+    MilestoneResources(Vec<MilestoneResource>),
+    Snapshots(Vec<ProjectSnapshot>),
+    SnapshotDiff(Box<SnapshotDiff>),
+    Done(Done),
+}
+
+impl Output {
+    /// A change that doesn't return a record
+    pub fn done(message: impl Into<String>) -> Self {
+        Output::Done(Done {
+            status: "ok",
+            message: message.into(),
+            result: None,
+        })
+    }
+
+    /// A change that returns the record it created
+    pub fn done_with<T: Serialize>(
+        message: impl Into<String>,
+        result: &T,
+    ) -> serde_json::Result<Self> {
+        Ok(Output::Done(Done {
+            status: "ok",
+            message: message.into(),
+            result: Some(serde_json::to_value(result)?),
+        }))
+    }
+
+    /// Print to stdout
+    pub fn print(&self, format: OutputFormat, tz: Tz, locale: Locale) -> io::Result<()> {
+        self.write(format, tz, locale, &mut io::stdout().lock())
+    }
+
+    /// Write in `format`; tables show dates in `tz` and headers and dates in `locale`
+    pub fn write(&self, format: OutputFormat, tz: Tz, locale: Locale, w: &mut impl Write) -> io::Result<()> {
+        match format {
+            OutputFormat::Json => {
+                serde_json::to_writer_pretty(&mut *w, self)?;
+                writeln!(w)
+            }
+            OutputFormat::Table => self.write_table(tz, locale, w),
+        }
+    }
+
+    fn write_table(&self, tz: Tz, locale: Locale, w: &mut impl Write) -> io::Result<()> {
+        let date = |d: Option<chrono::DateTime<chrono::Utc>>| {
+            d.map(|d| locale.date(d, tz)).unwrap_or_default()
+        };
+        let text = |key| locale.text(key);
+        match self {
+            Output::Projects(projects) if projects.is_empty() => writeln!(w, "{}", text("table.no_projects")),
+            Output::Projects(projects) => table(
+                w,
+                &[
+                    text("table.id"),
+                    text("table.slug"),
+                    text("table.name"),
+                    text("table.type"),
+                    text("table.status"),
+                    text("table.team"),
+                    text("table.due"),
+                ],
+                projects.iter().map(|p| {
+                    vec![
+                        short_id(&p.id),
+                        p.slug.clone(),
+                        p.name.clone(),
+                        p.project_type.clone(),
+                        p.status.to_string(),
+                        p.team.clone().unwrap_or_default(),
+                        date(p.due_date),
+                    ]
+                }),
+            ),
+            Output::Project(details) => write_project(w, details, tz, locale),
+            Output::People(people) if people.is_empty() => writeln!(w, "{}", text("table.no_people")),
+            Output::People(people) => table(
+                w,
+                &[text("table.email"), text("table.name"), text("table.team"), text("table.manager")],
+                people.iter().map(|p| {
+                    vec![
+                        p.email.clone(),
+                        if p.external { format!("{} (external)", p.name) } else { p.name.clone() },
+                        p.team.clone().unwrap_or_default(),
+                        p.manager.clone().unwrap_or_default(),
+                    ]
+                }),
+            ),
+            Output::Person(person) => {
+                writeln!(w, "{} <{}>", person.name, person.email)?;
+                if let Some(team) = &person.team {
+                    writeln!(w, "  Team: {}", team)?;
+                }
+                if let Some(organization) = &person.organization {
+                    writeln!(w, "  Organization: {}", organization)?;
+                }
+                if let Some(manager) = &person.manager {
+                    writeln!(w, "  Manager: {}", manager)?;
+                }
+                if !person.active {
+                    writeln!(w, "  Inactive")?;
+                }
+                if person.external {
+                    writeln!(w, "  External")?;
+                }
+                if !person.skills.is_empty() {
+                    let skills: Vec<String> = person
+                        .skills
+                        .iter()
+                        .map(|s| match s.level {
+                            Some(level) => format!("{} ({})", s.skill, level),
+                            None => s.skill.clone(),
+                        })
+                        .collect();
+                    writeln!(w, "  Skills: {}", skills.join(", "))?;
+                }
+                if let Some(notes) = &person.notes {
+                    writeln!(w, "  Notes: {}", notes)?;
+                }
+                Ok(())
+            }
+            Output::Teams(teams) if teams.is_empty() => writeln!(w, "{}", text("table.no_teams")),
+            Output::Teams(teams) => table(
+                w,
+                &[text("table.name"), text("table.parent"), text("table.manager")],
+                teams.iter().map(|t| {
+                    vec![
+                        t.name.clone(),
+                        t.parent_team.clone().unwrap_or_default(),
+                        t.manager.clone().unwrap_or_default(),
+                    ]
+                }),
+            ),
+            Output::Team(details) => {
+                let team = &details.team;
+                writeln!(w, "{}", team.name)?;
+                if let Some(description) = &team.description {
+                    writeln!(w, "  Description: {}", description)?;
+                }
+                if let Some(manager) = &team.manager {
+                    writeln!(w, "  Manager: {}", manager)?;
+                }
+                if let Some(parent) = &team.parent_team {
+                    writeln!(w, "  Parent team: {}", parent)?;
+                }
+                if !details.members.is_empty() {
+                    writeln!(w, "Members:")?;
+                    for member in &details.members {
+                        writeln!(w, "  {} <{}>", member.name, member.email)?;
+                    }
+                }
+                Ok(())
+            }
+            Output::ProjectResources(resources) if resources.is_empty() => {
+                writeln!(w, "{}", text("table.no_resources"))
+            }
+            Output::ProjectResources(resources) => table(
+                w,
+                &[text("table.email"), text("table.role")],
+                resources
+                    .iter()
+                    .map(|r| vec![r.person_email.clone(), r.role.clone().unwrap_or_default()]),
+            ),
+            Output::MilestoneResources(resources) if resources.is_empty() => {
+                writeln!(w, "{}", text("table.no_resources"))
+            }
+            Output::MilestoneResources(resources) => table(
+                w,
+                &[text("table.email"), text("table.role")],
+                resources
+                    .iter()
+                    .map(|r| vec![r.person_email.clone(), r.role.clone().unwrap_or_default()]),
+            ),
+            Output::Snapshots(snapshots) if snapshots.is_empty() => {
+                writeln!(w, "{}", text("table.no_snapshots"))
+            }
+            Output::Snapshots(snapshots) => table(
+                w,
+                &[text("table.id"), text("table.label"), text("table.created")],
+                snapshots.iter().map(|s| {
+                    vec![
+                        short_id(&s.id),
+                        s.label.clone(),
+                        date(Some(s.created_at)),
+                    ]
+                }),
+            ),
+            Output::SnapshotDiff(diff) => write_snapshot_diff(w, diff),
+            Output::Done(done) => writeln!(w, "{}", done.message),
+        }
+    }
+}
+
+fn write_snapshot_diff(w: &mut impl Write, diff: &SnapshotDiff) -> io::Result<()> {
+    writeln!(
+        w,
+        "Changes from '{}' ({}) to '{}' ({})",
+        diff.from.label,
+        short_id(&diff.from.id),
+        diff.to.label,
+        short_id(&diff.to.id)
+    )?;
+    if diff.is_empty() {
+        return writeln!(w, "No changes");
+    }
+
+    if !diff.project_changes.is_empty() {
+        writeln!(w, "Project:")?;
+        write_field_changes(w, &diff.project_changes)?;
+    }
+    if !diff.added_milestones.is_empty() {
+        writeln!(w, "Added milestones:")?;
+        for milestone in &diff.added_milestones {
+            writeln!(w, "  + {}. {}", milestone.number, milestone.name)?;
+        }
+    }
+    if !diff.removed_milestones.is_empty() {
+        writeln!(w, "Removed milestones:")?;
+        for milestone in &diff.removed_milestones {
+            writeln!(w, "  - {}. {}", milestone.number, milestone.name)?;
+        }
+    }
+    if !diff.changed_milestones.is_empty() {
+        writeln!(w, "Changed milestones:")?;
+        for milestone in &diff.changed_milestones {
+            writeln!(w, "  {}", milestone.name)?;
+            write_field_changes(w, &milestone.changes)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_field_changes(w: &mut impl Write, changes: &[FieldChange]) -> io::Result<()> {
+    let value = |v: &serde_json::Value| match v {
+        serde_json::Value::Null => "(none)".to_string(),
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    for change in changes {
+        writeln!(
+            w,
+            "    {}: {} -> {}",
+            change.field,
+            value(&change.old),
+            value(&change.new)
+        )?;
+    }
+    Ok(())
+}
+
+fn write_project(w: &mut impl Write, details: &ProjectDetails, tz: Tz, locale: Locale) -> io::Result<()> {
+    let project = &details.project;
+    writeln!(w, "{} ({})", project.name, project.id)?;
+    writeln!(w, "  Slug: {}", project.slug)?;
+    writeln!(w, "  Type: {}", project.project_type)?;
+    writeln!(w, "  Status: {}", project.status)?;
+    if let Some(phase) = &project.phase {
+        writeln!(w, "  Phase: {}", phase)?;
+    }
+    if let Some(description) = &project.description {
+        writeln!(w, "  Description: {}", description)?;
+    }
+    if let Some(due_date) = project.due_date {
+        writeln!(w, "  Due: {}", locale.date(due_date, tz))?;
+    }
+    if project.blocked {
+        writeln!(
+            w,
+            "  Blocked: {}",
+            project
+                .blocked_reason
+                .as_deref()
+                .unwrap_or("no reason given")
+        )?;
+    }
+
+    if !details.milestones.is_empty() {
+        writeln!(w, "Milestones:")?;
+        for milestone in &details.milestones {
+            match milestone.due_date {
+                Some(due_date) => writeln!(
+                    w,
+                    "  {}. {} (due {})",
+                    milestone.number,
+                    milestone.name,
+                    locale.date(due_date, tz)
+                )?,
+                None => writeln!(w, "  {}. {}", milestone.number, milestone.name)?,
+            }
+        }
+    }
+
+    if !details.links.is_empty() {
+        writeln!(w, "Links:")?;
+        for link in &details.links {
+            writeln!(w, "  {}", link)?;
+        }
+    }
+
+    if !details.blocker_history.is_empty() {
+        writeln!(w, "Blocker history:")?;
+        for entry in &details.blocker_history {
+            let reason = entry.reason.as_deref().unwrap_or("no reason given");
+            let blocked_at = locale.date(entry.blocked_at, tz);
+            match entry.cleared_at {
+                Some(cleared_at) => writeln!(
+                    w,
+                    "  {} to {}: {}",
+                    blocked_at,
+                    locale.date(cleared_at, tz),
+                    reason
+                )?,
+                None => writeln!(w, "  Since {}: {}", blocked_at, reason)?,
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Write rows under a header, with each column as wide as its widest cell
+pub(super) fn table(
+    w: &mut impl Write,
+    headers: &[&str],
+    rows: impl Iterator<Item = Vec<String>>,
+) -> io::Result<()> {
+    let rows: Vec<Vec<String>> = rows.collect();
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    writeln!(w, "{}", pad_row(headers.iter().copied(), &widths))?;
+    for row in &rows {
+        writeln!(w, "{}", pad_row(row.iter().map(String::as_str), &widths))?;
+    }
+    Ok(())
+}
+
+/// Join cells padded to their column's width, without trailing spaces
+fn pad_row<'a>(cells: impl Iterator<Item = &'a str>, widths: &[usize]) -> String {
+    let padded: Vec<String> = cells
+        .zip(widths)
+        .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+        .collect();
+    padded.join("  ").trim_end().to_string()
+}
+
+/// Print a failed command's error to stderr as JSON
+pub fn print_json_error(err: &anyhow::Error) {
+    let error = serde_json::json!({"status": "error", "error": format!("{:#}", err)});
+    eprintln!("{}", error);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use project_tracker::db::PersonSkill;
+    use uuid::Uuid;
+
+    fn render(output: &Output, format: OutputFormat) -> String {
+        let mut buf = Vec::new();
+        output.write(format, Tz::UTC, Locale::En, &mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    fn project(id: &str, name: &str) -> Project {
+        let created = Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap();
+        let mut project = Project::new(name.to_string());
+        project.id = Uuid::parse_str(id).unwrap();
+        project.created_at = created;
+        project.updated_at = created;
+        project
+    }
+
+    fn person() -> Person {
+        let created = Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap();
+        let mut person = Person::new("alice@example.com".to_string(), "Alice Smith".to_string());
+        person.team = Some("Platform".to_string());
+        person.manager = Some("bob@example.com".to_string());
+        person.created_at = created;
+        person.updated_at = created;
+        person
+    }
+
+    #[test]
+    fn test_project_list() {
+        let mut apollo = project("a1b2c3d4-0000-4000-8000-000000000001", "Apollo");
+        apollo.team = Some("Platform".to_string());
+        apollo.due_date = Some(Utc.with_ymd_and_hms(2025, 6, 30, 12, 0, 0).unwrap());
+        let gemini = project("e5f6a7b8-0000-4000-8000-000000000002", "Gemini");
+        let output = Output::Projects(vec![apollo, gemini]);
+
+        assert_eq!(
+            render(&output, OutputFormat::Table),
+            "\
+ID        SLUG    NAME    TYPE      STATUS  TEAM      DUE
+a1b2c3d4  apollo  Apollo  Personal  active  Platform  2025-06-30
+e5f6a7b8  gemini  Gemini  Personal  active
+"
+        );
+        assert_eq!(
+            render(&Output::Projects(Vec::new()), OutputFormat::Table),
+            "No projects found\n"
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render(&output, OutputFormat::Json)).unwrap();
+        assert_eq!(
+            json[0],
+            serde_json::json!({
+                "id": "a1b2c3d4-0000-4000-8000-000000000001",
+                "name": "Apollo",
+                "slug": "apollo",
+                "description": null,
+                "type": "Personal",
+                "requirements_owner": null,
+                "technical_lead": null,
+                "manager": null,
+                "team": "Platform",
+                "start_date": null,
+                "due_date": "2025-06-30T12:00:00Z",
+                "jira_initiative": null,
+                "blocked": false,
+                "blocked_reason": null,
+                "budget_amount": null,
+                "budget_currency": null,
+                "status": "active",
+                "phase": null,
+                "board_position": null,
+                "created_at": "2025-01-02T03:04:05Z",
+                "updated_at": "2025-01-02T03:04:05Z",
+                "version": 1,
+                "custom_fields": {},
+                "external_refs": []
+            })
+        );
+        assert_eq!(json[1]["name"], "Gemini");
+    }
+
+    #[test]
+    fn test_project_list_german() {
+        let mut apollo = project("a1b2c3d4-0000-4000-8000-000000000001", "Apollo");
+        apollo.due_date = Some(Utc.with_ymd_and_hms(2025, 6, 30, 12, 0, 0).unwrap());
+        let output = Output::Projects(vec![apollo]);
+
+        let mut buf = Vec::new();
+        output.write(OutputFormat::Table, Tz::UTC, Locale::De, &mut buf).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "\
+ID        KÜRZEL  NAME    TYP       STATUS  TEAM  FÄLLIG
+a1b2c3d4  apollo  Apollo  Personal  active        30.06.2025
+"
+        );
+
+        // JSON stays the same in every locale
+        let mut json = Vec::new();
+        output.write(OutputFormat::Json, Tz::UTC, Locale::De, &mut json).unwrap();
+        assert_eq!(String::from_utf8(json).unwrap(), render(&output, OutputFormat::Json));
+    }
+
+    #[test]
+    fn test_person_show() {
+        let mut person = person();
+        person.skills = vec![
+            PersonSkill {
+                skill: "go".to_string(),
+                level: None,
+                created_at: person.created_at,
+            },
+            PersonSkill {
+                skill: "kubernetes".to_string(),
+                level: Some(4),
+                created_at: person.created_at,
+            },
+        ];
+        let output = Output::Person(Box::new(person));
+
+        assert_eq!(
+            render(&output, OutputFormat::Table),
+            "\
+Alice Smith <alice@example.com>
+  Team: Platform
+  Manager: bob@example.com
+  Skills: go, kubernetes (4)
+"
+        );
+        assert_eq!(
+            render(&output, OutputFormat::Json),
+            r#"{
+  "email": "alice@example.com",
+  "name": "Alice Smith",
+  "team": "Platform",
+  "organization": null,
+  "manager": "bob@example.com",
+  "notes": null,
+  "active": true,
+  "external": false,
+  "avatar_path": null,
+  "skills": [
+    {
+      "skill": "go",
+      "level": null,
+      "created_at": "2025-01-02T03:04:05Z"
+    },
+    {
+      "skill": "kubernetes",
+      "level": 4,
+      "created_at": "2025-01-02T03:04:05Z"
+    }
+  ],
+  "created_at": "2025-01-02T03:04:05Z",
+  "updated_at": "2025-01-02T03:04:05Z",
+  "version": 1
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_snapshot_diff() {
+        let project_id = Uuid::parse_str("a1b2c3d4-0000-4000-8000-000000000001").unwrap();
+        let snapshot = |id: &str, label: &str| ProjectSnapshot {
+            id: Uuid::parse_str(id).unwrap(),
+            project_id,
+            label: label.to_string(),
+            created_at: Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap(),
+        };
+        let change = |field: &str, old: serde_json::Value, new: serde_json::Value| FieldChange {
+            field: field.to_string(),
+            old,
+            new,
+        };
+        let diff = SnapshotDiff {
+            from: snapshot("11111111-0000-4000-8000-000000000001", "Q3 start"),
+            to: snapshot("22222222-0000-4000-8000-000000000002", "Q3 end"),
+            project_changes: vec![
+                change("due_date", "2025-09-30T00:00:00Z".into(), "2025-10-31T00:00:00Z".into()),
+                change("team", serde_json::Value::Null, "Platform".into()),
+            ],
+            added_milestones: vec![Milestone::new(project_id, 3, "Launch".to_string())],
+            removed_milestones: vec![Milestone::new(project_id, 2, "Beta".to_string())],
+            changed_milestones: vec![project_tracker::db::MilestoneChange {
+                milestone_id: Uuid::nil(),
+                name: "Design".to_string(),
+                changes: vec![change("estimated_days", 5.0.into(), 8.5.into())],
+            }],
+        };
+
+        assert_eq!(
+            render(&Output::SnapshotDiff(Box::new(diff)), OutputFormat::Table),
+            "\
+Changes from 'Q3 start' (11111111) to 'Q3 end' (22222222)
+Project:
+    due_date: 2025-09-30T00:00:00Z -> 2025-10-31T00:00:00Z
+    team: (none) -> Platform
+Added milestones:
+  + 3. Launch
+Removed milestones:
+  - 2. Beta
+Changed milestones:
+  Design
+    estimated_days: 5.0 -> 8.5
+"
+        );
+    }
+
+    #[test]
+    fn test_done() {
+        assert_eq!(
+            render(&Output::done("Removed team Platform"), OutputFormat::Table),
+            "Removed team Platform\n"
+        );
+        assert_eq!(
+            render(&Output::done("Removed team Platform"), OutputFormat::Json),
+            "{\n  \"status\": \"ok\",\n  \"message\": \"Removed team Platform\"\n}\n"
+        );
+
+        let output = Output::done_with("Added person alice@example.com", &person()).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&render(&output, OutputFormat::Json)).unwrap();
+        assert_eq!(json["status"], "ok");
+        assert_eq!(json["result"]["email"], "alice@example.com");
+    }
+}
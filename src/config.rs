@@ -2,45 +2,326 @@
 //
 // SPDX-License-Identifier: MIT
 
-use anyhow::{Context, Result};
+use crate::db::hygiene::HygieneChecks;
+use crate::i18n::Locale;
+use anyhow::{anyhow, bail, Context, Result};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
+use notify::{RecursiveMode, Watcher};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, RwLock};
+use std::time::Duration;
 
 /// Application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
-    /// Data storage directory
+    /// Data storage directory (defaults to $XDG_DATA_HOME/project-tracker)
     #[serde(default = "default_data_dir")]
     pub data_dir: String,
 
+    /// Data directories of additional workspaces, by name
+    ///
+    /// Each workspace has its own database and attachments. The workspace
+    /// named "default" uses `data_dir` unless it is listed here.
+    #[serde(default)]
+    pub workspaces: BTreeMap<String, String>,
+
+    /// Workspace opened when none is chosen
+    #[serde(default = "default_workspace")]
+    pub default_workspace: String,
+
+    /// Workspace chosen for this run, e.g. with `--workspace`; not saved
+    #[serde(skip)]
+    active_workspace: Option<String>,
+
+    /// Let MCP clients switch the server to another workspace
+    #[serde(default)]
+    pub mcp_workspace_switching: bool,
+
+    /// Make MCP clients confirm deletes of projects, people, teams and milestones
+    #[serde(default)]
+    pub mcp_require_confirmation: bool,
+
+    /// Hours an MCP idempotency key is remembered; a retry after that runs the tool again
+    #[serde(default = "default_mcp_idempotency_ttl_hours")]
+    pub mcp_idempotency_ttl_hours: u32,
+
     /// Jira base URL (e.g., "https://jira.company.com/browse/")
     #[serde(default = "default_jira_url")]
     pub jira_url: String,
 
+    /// Ticket URL templates for external references by system, with `{key}` where the key goes
+    /// (e.g., linear = "https://linear.app/acme/issue/{key}"); jira defaults to `jira_url`
+    #[serde(default)]
+    pub external_ref_urls: BTreeMap<String, String>,
+
     /// Default email domain (e.g., "company.com")
     #[serde(default = "default_email_domain")]
     pub default_email_domain: String,
 
+    /// Email domains people may have; subdomains are included and an empty list allows any address
+    #[serde(default)]
+    pub allowed_email_domains: Vec<String>,
+
+    /// Email of the person using this configuration; scopes the digest and reminders to their projects
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_user_email: Option<String>,
+
     /// Available project types
     #[serde(default = "default_project_types")]
     pub project_types: Vec<String>,
 
+    /// Project phases, in the order projects move through them; an empty list allows any phase
+    #[serde(default = "default_project_phases")]
+    pub project_phases: Vec<String>,
+
+    /// Suggested project document types
+    #[serde(default = "default_document_types")]
+    pub document_types: Vec<String>,
+
+    /// Roles for project resources, milestone resources and stakeholders
+    #[serde(default = "default_roles")]
+    pub roles: Vec<String>,
+
+    /// Let a role that isn't one of `roles` or already in use become a new role
+    #[serde(default = "default_allow_new_roles")]
+    pub allow_new_roles: bool,
+
+    /// Start the desktop app's MCP HTTP server on launch
+    #[serde(default = "default_mcp_enabled")]
+    pub mcp_enabled: bool,
+
     /// MCP HTTP server port
     #[serde(default = "default_mcp_http_port")]
     pub mcp_http_port: u16,
 
+    /// Serve tool call metrics at `/metrics` on the MCP HTTP server
+    #[serde(default = "default_mcp_metrics_enabled")]
+    pub mcp_metrics_enabled: bool,
+
+    /// Requests per minute each client may make to the MCP HTTP server; 0 turns the limit off
+    #[serde(default = "default_mcp_rate_limit_per_minute")]
+    pub mcp_rate_limit_per_minute: u32,
+
+    /// Requests a client may make to the MCP HTTP server at once before the limit applies
+    #[serde(default = "default_mcp_rate_limit_burst")]
+    pub mcp_rate_limit_burst: u32,
+
+    /// Bearer token required by `/api/v1/sync`; the sync endpoints are off while unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_token: Option<String>,
+
+    /// Days deletes are remembered for syncing clients
+    #[serde(default = "default_sync_tombstone_days")]
+    pub sync_tombstone_days: u32,
+
+    /// How many months ahead recurring milestones are materialized
+    #[serde(default = "default_recurrence_horizon_months")]
+    pub recurrence_horizon_months: u32,
+
+    /// IANA time zone used to display dates and interpret plain dates (e.g., "Asia/Tokyo")
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+
+    /// Language for the digest, notifications, CLI tables and reports (en or de)
+    #[serde(default = "default_locale")]
+    pub locale: String,
+
+    /// Reject projects whose name matches an existing project, ignoring case
+    #[serde(default)]
+    pub enforce_unique_project_names: bool,
+
+    /// Let updates that don't include a version overwrite the stored record
+    ///
+    /// Updates that do include a version are always checked. Turn this off
+    /// once every client sends versions.
+    #[serde(default = "default_allow_unversioned_updates")]
+    pub allow_unversioned_updates: bool,
+
+    /// Largest file that can be attached to a note, in bytes
+    #[serde(default = "default_max_attachment_bytes")]
+    pub max_attachment_bytes: u64,
+
+    /// Limit on the bytes all note attachments may use together
+    #[serde(default = "default_max_attachment_storage_bytes")]
+    pub max_attachment_storage_bytes: u64,
+
+    /// How long the desktop app waits for the database before reporting it busy, in milliseconds
+    #[serde(default = "default_db_lock_timeout_ms")]
+    pub db_lock_timeout_ms: u64,
+
+    /// Show desktop notifications for upcoming and overdue milestones
+    #[serde(default = "default_notifications_enabled")]
+    pub notifications_enabled: bool,
+
+    /// Days ahead of a due date to start reminding about a milestone, 0 for only on the day
+    #[serde(default = "default_notify_days_before")]
+    pub notify_days_before: u32,
+
+    /// Warn when a milestone's actual effort is more than this many times its estimate
+    #[serde(default = "default_effort_warning_ratio")]
+    pub effort_warning_ratio: f64,
+
+    /// Most resources to assign to one milestone before warning; unset for no limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_resources_per_milestone: Option<usize>,
+
+    /// Refuse to assign more than `max_resources_per_milestone` resources instead of warning
+    #[serde(default)]
+    pub max_resources_hard_limit: bool,
+
+    /// Count checked milestone checklist items towards project and initiative progress
+    #[serde(default)]
+    pub checklist_progress: bool,
+
+    /// Ask before creating a person whose name is at least this similar (0.0 to 1.0) to an existing person's
+    #[serde(default = "default_person_duplicate_threshold")]
+    pub person_duplicate_threshold: f64,
+
+    /// Daily database backups the desktop app keeps in the backups directory; 0 turns them off
+    #[serde(default = "default_backup_retention_count")]
+    pub backup_retention_count: u32,
+
+    /// Longest project brief the MCP server's get_project_brief returns, in characters
+    #[serde(default = "default_brief_max_chars")]
+    pub brief_max_chars: usize,
+
+    /// Days without a stakeholder note before the focus list suggests a follow-up (0 turns it off)
+    #[serde(default = "default_stakeholder_contact_days")]
+    pub stakeholder_contact_days: u32,
+
+    /// Move a legacy ~/.project-tracker directory to the XDG directories on the next start
+    #[serde(default)]
+    pub migrate_legacy_dir: bool,
+
+    /// Webhook endpoints notified when data changes
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+
+    /// Setup added to new projects, by project type
+    #[serde(default = "default_bootstrap")]
+    pub bootstrap: BTreeMap<String, BootstrapRule>,
+
+    /// Data hygiene report settings
+    #[serde(default)]
+    pub hygiene: HygieneConfig,
+
+    /// Weekly digest settings
+    #[serde(default)]
+    pub digest: DigestConfig,
+
+    /// Link checking settings
+    #[serde(default)]
+    pub linkcheck: LinkCheckConfig,
+
+    /// Protection for a data directory shared between machines
+    #[serde(default)]
+    pub sync_safety: SyncSafetyConfig,
+
+    /// Where passwords and keys are kept
+    #[serde(default)]
+    pub secrets: SecretsConfig,
+
     /// Logging configuration
     #[serde(default)]
     pub logging: LoggingConfig,
 }
 
+fn default_mcp_enabled() -> bool {
+    true
+}
+
 fn default_mcp_http_port() -> u16 {
     8080
 }
 
+fn default_mcp_metrics_enabled() -> bool {
+    true
+}
+
+fn default_mcp_idempotency_ttl_hours() -> u32 {
+    24
+}
+
+fn default_mcp_rate_limit_per_minute() -> u32 {
+    600
+}
+
+fn default_mcp_rate_limit_burst() -> u32 {
+    100
+}
+
+fn default_sync_tombstone_days() -> u32 {
+    90
+}
+
+fn default_recurrence_horizon_months() -> u32 {
+    3
+}
+
+fn default_timezone() -> String {
+    "UTC".to_string()
+}
+
+fn default_locale() -> String {
+    "en".to_string()
+}
+
+fn default_allow_unversioned_updates() -> bool {
+    true
+}
+
+fn default_max_attachment_bytes() -> u64 {
+    crate::storage::DEFAULT_MAX_ATTACHMENT_BYTES
+}
+
+fn default_max_attachment_storage_bytes() -> u64 {
+    crate::storage::DEFAULT_MAX_ATTACHMENT_STORAGE_BYTES
+}
+
+fn default_db_lock_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_notifications_enabled() -> bool {
+    true
+}
+
+fn default_notify_days_before() -> u32 {
+    1
+}
+
+fn default_effort_warning_ratio() -> f64 {
+    1.25
+}
+
+fn default_person_duplicate_threshold() -> f64 {
+    0.9
+}
+
+fn default_backup_retention_count() -> u32 {
+    crate::storage::backup::DEFAULT_BACKUP_RETENTION_COUNT
+}
+
+fn default_brief_max_chars() -> usize {
+    crate::mcp::resources::DEFAULT_BRIEF_MAX_CHARS
+}
+
+fn default_stakeholder_contact_days() -> u32 {
+    crate::db::project_repo::DEFAULT_STAKEHOLDER_CONTACT_DAYS
+}
+
 fn default_data_dir() -> String {
-    "~/.project-tracker".to_string()
+    match AppDirs::from_env() {
+        Ok(dirs) => dirs.default_data_dir(),
+        Err(_) => format!("~/{}", LEGACY_DIR),
+    }
+}
+
+fn default_workspace() -> String {
+    DEFAULT_WORKSPACE.to_string()
 }
 
 fn default_jira_url() -> String {
@@ -59,6 +340,427 @@ fn default_project_types() -> Vec<String> {
     ]
 }
 
+fn default_project_phases() -> Vec<String> {
+    vec![
+        "Discovery".to_string(),
+        "Design".to_string(),
+        "Build".to_string(),
+        "Rollout".to_string(),
+        "Hypercare".to_string(),
+    ]
+}
+
+fn default_document_types() -> Vec<String> {
+    vec![
+        "Design Doc".to_string(),
+        "PRD".to_string(),
+        "RFC".to_string(),
+        "Runbook".to_string(),
+    ]
+}
+
+fn default_roles() -> Vec<String> {
+    vec![
+        "Technical Lead".to_string(),
+        "Developer".to_string(),
+        "Designer".to_string(),
+        "QA".to_string(),
+        "Product Owner".to_string(),
+        "Sponsor".to_string(),
+    ]
+}
+
+fn default_allow_new_roles() -> bool {
+    true
+}
+
+/// A webhook endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    /// URL events are POSTed to
+    pub url: String,
+
+    /// Events to send (e.g., "milestone.due_date_changed", "milestone.*"); empty sends every event
+    #[serde(default)]
+    pub events: Vec<String>,
+
+    /// Shared secret used to sign request bodies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+/// Milestones, notes and stakeholders added to every new project of a type
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BootstrapRule {
+    /// Milestone names, numbered in this order
+    #[serde(default)]
+    pub milestones: Vec<String>,
+
+    /// Titles of empty project notes
+    #[serde(default)]
+    pub notes: Vec<String>,
+
+    /// Titles of empty append-only log notes, such as a decision log
+    #[serde(default)]
+    pub log_notes: Vec<String>,
+
+    /// People named on the project who become its stakeholders
+    #[serde(default)]
+    pub stakeholders: Vec<BootstrapStakeholder>,
+}
+
+/// A person named on a project who becomes a stakeholder when it is created
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BootstrapStakeholder {
+    Manager,
+    RequirementsOwner,
+    TechnicalLead,
+}
+
+impl BootstrapStakeholder {
+    /// The role the stakeholder is added with
+    pub fn role(self) -> &'static str {
+        match self {
+            BootstrapStakeholder::Manager => "Manager",
+            BootstrapStakeholder::RequirementsOwner => "Requirements Owner",
+            BootstrapStakeholder::TechnicalLead => "Technical Lead",
+        }
+    }
+}
+
+fn default_bootstrap() -> BTreeMap<String, BootstrapRule> {
+    let rule = BootstrapRule {
+        milestones: vec!["Kickoff".to_string()],
+        notes: Vec::new(),
+        log_notes: vec!["Decision log".to_string()],
+        stakeholders: vec![BootstrapStakeholder::Manager, BootstrapStakeholder::RequirementsOwner],
+    };
+    BTreeMap::from([("Team".to_string(), rule.clone()), ("Company".to_string(), rule)])
+}
+
+/// Name of the workspace kept in `data_dir`
+pub const DEFAULT_WORKSPACE: &str = "default";
+
+/// Environment variable that overrides the config file location
+pub const CONFIG_ENV_VAR: &str = "PROJECT_TRACKER_CONFIG";
+
+/// Directory name used under the XDG config and data directories
+const APP_DIR: &str = "project-tracker";
+
+/// Home directory entry used for config and data before XDG support
+const LEGACY_DIR: &str = ".project-tracker";
+
+/// Database file name inside the data directory
+const DATABASE_FILE: &str = "project-tracker.db";
+
+/// Config and data locations, resolved from the environment
+///
+/// Follows the XDG base directory spec: `$XDG_CONFIG_HOME` and
+/// `$XDG_DATA_HOME` when set to absolute paths, otherwise `~/.config` and
+/// `~/.local/share`.
+#[derive(Debug)]
+struct AppDirs {
+    /// Path from $PROJECT_TRACKER_CONFIG, if set
+    config_override: Option<PathBuf>,
+    /// $XDG_CONFIG_HOME/project-tracker
+    config_dir: PathBuf,
+    /// $XDG_DATA_HOME/project-tracker
+    data_dir: PathBuf,
+    /// ~/.project-tracker
+    legacy_dir: PathBuf,
+}
+
+impl AppDirs {
+    fn from_env() -> Result<Self> {
+        let home = dirs::home_dir().context("Could not determine home directory")?;
+        Ok(Self::resolve(|name| std::env::var(name).ok(), &home))
+    }
+
+    fn resolve(var: impl Fn(&str) -> Option<String>, home: &Path) -> Self {
+        let xdg_dir = |name: &str, fallback: &str| {
+            var(name)
+                .map(PathBuf::from)
+                .filter(|path| path.is_absolute())
+                .unwrap_or_else(|| home.join(fallback))
+        };
+
+        Self {
+            config_override: var(CONFIG_ENV_VAR)
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from),
+            config_dir: xdg_dir("XDG_CONFIG_HOME", ".config").join(APP_DIR),
+            data_dir: xdg_dir("XDG_DATA_HOME", ".local/share").join(APP_DIR),
+            legacy_dir: home.join(LEGACY_DIR),
+        }
+    }
+
+    /// The config file to use
+    ///
+    /// The legacy file is only used when it exists and the XDG one doesn't.
+    fn config_path(&self) -> PathBuf {
+        if let Some(path) = &self.config_override {
+            return path.clone();
+        }
+
+        let xdg = self.config_dir.join("config.toml");
+        let legacy = self.legacy_dir.join("config.toml");
+        if !xdg.exists() && legacy.exists() {
+            legacy
+        } else {
+            xdg
+        }
+    }
+
+    /// The data directory for configs that don't set one
+    ///
+    /// Stays in the legacy directory while it holds the database.
+    fn default_data_dir(&self) -> String {
+        let dir = if self.legacy_dir.join(DATABASE_FILE).exists() {
+            &self.legacy_dir
+        } else {
+            &self.data_dir
+        };
+        dir.to_string_lossy().into_owned()
+    }
+
+    /// Move the legacy directory's config file and data to the XDG directories
+    ///
+    /// Returns the new config file path. `config.data_dir` is updated if it
+    /// pointed at the legacy directory. Nothing is moved if either target
+    /// already exists.
+    fn migrate_legacy(&self, config: &mut Config) -> Result<PathBuf> {
+        let legacy_config = self.legacy_dir.join("config.toml");
+        let new_config = self.config_dir.join("config.toml");
+        if new_config.exists() {
+            bail!("Cannot migrate, {} already exists", new_config.display());
+        }
+
+        let moves_data = config.expand_path(&config.data_dir)? == self.legacy_dir;
+        if moves_data {
+            if self.data_dir.exists() {
+                bail!("Cannot migrate, {} already exists", self.data_dir.display());
+            }
+            let parent = self.data_dir.parent().unwrap();
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create data directory: {}", parent.display()))?;
+            fs::rename(&self.legacy_dir, &self.data_dir).with_context(|| {
+                format!("Failed to move {} to {}", self.legacy_dir.display(), self.data_dir.display())
+            })?;
+            config.data_dir = self.data_dir.to_string_lossy().into_owned();
+        }
+
+        // The config file moved along with the data, if the data moved
+        let old_config = if moves_data {
+            self.data_dir.join("config.toml")
+        } else {
+            legacy_config
+        };
+        fs::create_dir_all(&self.config_dir)
+            .with_context(|| format!("Failed to create config directory: {}", self.config_dir.display()))?;
+        config.migrate_legacy_dir = false;
+        config.save(&new_config)?;
+        fs::remove_file(&old_config)
+            .with_context(|| format!("Failed to remove {}", old_config.display()))?;
+
+        // Clean up the legacy directory if only the config file was in it
+        if !moves_data {
+            let _ = fs::remove_dir(&self.legacy_dir);
+        }
+
+        Ok(new_config)
+    }
+}
+
+/// Data hygiene report settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HygieneConfig {
+    /// Checks to run, each on unless turned off
+    #[serde(flatten)]
+    pub checks: HygieneChecks,
+
+    /// Gaps `track hygiene` allows before exiting with an error
+    #[serde(default)]
+    pub max_gaps: usize,
+}
+
+/// Link checking settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkCheckConfig {
+    /// Domains that are never checked, including their subdomains
+    #[serde(default)]
+    pub skip_domains: Vec<String>,
+
+    /// Links checked at the same time
+    #[serde(default = "default_linkcheck_concurrency")]
+    pub concurrency: usize,
+
+    /// Seconds to wait for each link
+    #[serde(default = "default_linkcheck_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// Redirects followed before a link counts as unreachable
+    #[serde(default = "default_linkcheck_max_redirects")]
+    pub max_redirects: usize,
+
+    /// Broken links `track linkcheck` allows before exiting with an error
+    #[serde(default)]
+    pub max_dead: usize,
+}
+
+fn default_linkcheck_concurrency() -> usize {
+    8
+}
+
+fn default_linkcheck_timeout_secs() -> u64 {
+    10
+}
+
+fn default_linkcheck_max_redirects() -> usize {
+    5
+}
+
+impl Default for LinkCheckConfig {
+    fn default() -> Self {
+        Self {
+            skip_domains: Vec::new(),
+            concurrency: default_linkcheck_concurrency(),
+            timeout_secs: default_linkcheck_timeout_secs(),
+            max_redirects: default_linkcheck_max_redirects(),
+            max_dead: 0,
+        }
+    }
+}
+
+/// How much to guard a data directory that more than one machine can reach
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncSafetyMode {
+    /// No checks
+    Off,
+    /// Warn when the data directory is inside a cloud-synced folder
+    #[default]
+    Warn,
+    /// Also keep a heartbeat file while running, and refuse to open the
+    /// database while another machine's heartbeat is live
+    Lock,
+}
+
+/// Protection for a data directory shared between machines
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncSafetyConfig {
+    /// Checks to make when opening the database
+    #[serde(default)]
+    pub mode: SyncSafetyMode,
+
+    /// Seconds without an update after which another machine's heartbeat is ignored
+    #[serde(default = "default_heartbeat_stale_secs")]
+    pub stale_after_secs: u64,
+}
+
+fn default_heartbeat_stale_secs() -> u64 {
+    120
+}
+
+impl Default for SyncSafetyConfig {
+    fn default() -> Self {
+        Self {
+            mode: SyncSafetyMode::default(),
+            stale_after_secs: default_heartbeat_stale_secs(),
+        }
+    }
+}
+
+/// Where secrets are stored
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SecretsBackend {
+    /// The operating system's keychain
+    #[default]
+    Keyring,
+    /// A file encrypted with a passphrase, for machines without a keychain
+    File,
+}
+
+/// Where passwords and keys are kept
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretsConfig {
+    /// Store to use
+    #[serde(default)]
+    pub backend: SecretsBackend,
+
+    /// Encrypted file used by the file backend (defaults to secrets.enc in `data_dir`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+
+    /// Environment variable holding the file backend's passphrase
+    #[serde(default = "default_secrets_passphrase_env")]
+    pub passphrase_env: String,
+}
+
+fn default_secrets_passphrase_env() -> String {
+    "PROJECT_TRACKER_SECRETS_PASSPHRASE".to_string()
+}
+
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        Self {
+            backend: SecretsBackend::default(),
+            file: None,
+            passphrase_env: default_secrets_passphrase_env(),
+        }
+    }
+}
+
+/// Weekly digest settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DigestConfig {
+    /// Mail server `track digest send` uses; without one the digest can only be generated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub smtp: Option<SmtpConfig>,
+}
+
+/// Mail server the digest is sent through
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmtpConfig {
+    /// Server host name
+    pub host: String,
+
+    /// Server port; STARTTLS is required
+    #[serde(default = "default_smtp_port")]
+    pub port: u16,
+
+    /// Sender address, such as "Project Tracker <tracker@company.com>"
+    pub from: String,
+
+    /// Recipient addresses
+    pub to: Vec<String>,
+
+    /// User name to log in with, if the server requires it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+
+    /// Name of the password in the secret store, so it stays out of the config file
+    #[serde(default = "default_smtp_password_secret")]
+    pub password_secret: String,
+
+    /// Environment variable that, when set, holds the password instead of the secret store
+    #[serde(default = "default_smtp_password_env")]
+    pub password_env: String,
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
+fn default_smtp_password_secret() -> String {
+    "smtp.password".to_string()
+}
+
+fn default_smtp_password_env() -> String {
+    "PROJECT_TRACKER_SMTP_PASSWORD".to_string()
+}
+
 /// Logging configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoggingConfig {
@@ -88,27 +790,150 @@ impl Config {
 
         let config: Config = toml::from_str(&contents)
             .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+        config
+            .validate()
+            .with_context(|| format!("Invalid config file: {}", path.display()))?;
 
         Ok(config)
     }
 
-    /// Get the default config file path (~/.project-tracker/config.toml)
-    pub fn default_path() -> Result<PathBuf> {
-        let home = dirs::home_dir()
-            .context("Could not determine home directory")?;
+    /// Check settings that can't be validated while parsing
+    pub fn validate(&self) -> Result<()> {
+        self.timezone
+            .parse::<Tz>()
+            .map_err(|e| anyhow!("Unknown timezone '{}': {}", self.timezone, e))?;
+        self.locale.parse::<Locale>()?;
+        if !self.has_workspace(&self.default_workspace) {
+            bail!("Unknown default workspace '{}'", self.default_workspace);
+        }
+        if !(self.effort_warning_ratio.is_finite() && self.effort_warning_ratio > 0.0) {
+            bail!("effort_warning_ratio must be a positive number, got {}", self.effort_warning_ratio);
+        }
+        if self.max_resources_per_milestone == Some(0) {
+            bail!("max_resources_per_milestone must be at least 1");
+        }
+        if self.mcp_rate_limit_per_minute > 0 && self.mcp_rate_limit_burst == 0 {
+            bail!("mcp_rate_limit_burst must be at least 1 while mcp_rate_limit_per_minute is set");
+        }
+        if !(0.0..=1.0).contains(&self.person_duplicate_threshold) {
+            bail!(
+                "person_duplicate_threshold must be between 0 and 1, got {}",
+                self.person_duplicate_threshold
+            );
+        }
+        for (system, template) in &self.external_ref_urls {
+            crate::db::external_ref_repo::normalize_system(system)?;
+            if !template.contains("{key}") {
+                bail!("external_ref_urls.{} must contain {{key}}, got '{}'", system, template);
+            }
+        }
+        if self.brief_max_chars < crate::mcp::resources::MIN_BRIEF_MAX_CHARS {
+            bail!(
+                "brief_max_chars must be at least {}, got {}",
+                crate::mcp::resources::MIN_BRIEF_MAX_CHARS,
+                self.brief_max_chars
+            );
+        }
+        if let Some(domain) = self
+            .allowed_email_domains
+            .iter()
+            .find(|d| d.is_empty() || d.contains('@') || d.starts_with('.'))
+        {
+            bail!("allowed_email_domains entries must be domains such as company.com, got '{}'", domain);
+        }
+        if let Some(email) = self.current_user_email.as_deref().filter(|e| !e.contains('@')) {
+            bail!("current_user_email must be an email address, got '{}'", email);
+        }
+        if self.project_phases.iter().any(|p| p.trim().is_empty()) {
+            bail!("project_phases entries can't be blank");
+        }
+        if let Some((_, phase)) = self
+            .project_phases
+            .iter()
+            .enumerate()
+            .find(|(i, p)| self.project_phases[..*i].iter().any(|q| q.eq_ignore_ascii_case(p)))
+        {
+            bail!("project_phases lists '{}' more than once", phase);
+        }
+        for (project_type, rule) in &self.bootstrap {
+            if rule.milestones.iter().chain(&rule.notes).chain(&rule.log_notes).any(|name| name.trim().is_empty()) {
+                bail!("bootstrap.{} milestone and note names can't be blank", project_type);
+            }
+        }
+        if self.linkcheck.concurrency == 0 {
+            bail!("linkcheck.concurrency must be at least 1");
+        }
+        if self.sync_safety.stale_after_secs < 10 {
+            bail!("sync_safety.stale_after_secs must be at least 10");
+        }
+        if !self.allow_new_roles && self.roles.is_empty() {
+            bail!("roles needs at least one role when allow_new_roles is false");
+        }
+        if let Some(smtp) = &self.digest.smtp {
+            if smtp.to.is_empty() {
+                bail!("digest.smtp.to needs at least one recipient");
+            }
+        }
+        Ok(())
+    }
+
+    /// The configured time zone
+    ///
+    /// Falls back to UTC if `timezone` is invalid, which `load` rejects.
+    pub fn tz(&self) -> Tz {
+        self.timezone.parse().unwrap_or(Tz::UTC)
+    }
 
-        Ok(home.join(".project-tracker").join("config.toml"))
+    /// The configured locale
+    ///
+    /// Falls back to English if `locale` is unsupported, which `load` rejects.
+    pub fn locale(&self) -> Locale {
+        self.locale.parse().unwrap_or_default()
+    }
+
+    /// Get the default config file path
+    ///
+    /// In order: `$PROJECT_TRACKER_CONFIG`,
+    /// `$XDG_CONFIG_HOME/project-tracker/config.toml`, then the legacy
+    /// `~/.project-tracker/config.toml` if it exists and the XDG file doesn't.
+    pub fn default_path() -> Result<PathBuf> {
+        Ok(AppDirs::from_env()?.config_path())
     }
 
     /// Load configuration from the default location or create a default config
+    ///
+    /// A config loaded from the legacy directory is moved to the XDG
+    /// directories, along with its data, when `migrate_legacy_dir` is set.
     pub fn load_or_default() -> Result<Self> {
-        let default_path = Self::default_path()?;
+        Self::load_or_create(&AppDirs::from_env()?)
+    }
+
+    fn load_or_create(dirs: &AppDirs) -> Result<Self> {
+        let default_path = dirs.config_path();
 
         if default_path.exists() {
-            Self::load(&default_path)
+            let mut config = Self::load(&default_path)?;
+            if default_path.starts_with(&dirs.legacy_dir) {
+                if config.migrate_legacy_dir {
+                    let new_path = dirs.migrate_legacy(&mut config)?;
+                    log::info!("Moved legacy configuration to {}", new_path.display());
+                } else {
+                    log::warn!(
+                        "Using legacy directory {}; set migrate_legacy_dir = true in {} to move it to {}",
+                        dirs.legacy_dir.display(),
+                        default_path.display(),
+                        dirs.config_dir.display()
+                    );
+                }
+            }
+            Ok(config)
         } else {
-            // Create default config directory and file
-            let config_dir = default_path.parent().unwrap();
+            // Create default config directory and file; a bare file name
+            // from $PROJECT_TRACKER_CONFIG is in the current directory
+            let config_dir = default_path
+                .parent()
+                .filter(|dir| !dir.as_os_str().is_empty())
+                .unwrap_or(Path::new("."));
             fs::create_dir_all(config_dir)
                 .with_context(|| format!("Failed to create config directory: {}", config_dir.display()))?;
 
@@ -136,19 +961,76 @@ impl Config {
 
     /// Expand tilde (~) in path to home directory
     pub fn expand_path(&self, path: &str) -> Result<PathBuf> {
-        if path.starts_with('~') {
+        if let Some(rest) = path.strip_prefix('~') {
             let home = dirs::home_dir()
                 .context("Could not determine home directory")?;
-            let path_without_tilde = path.strip_prefix("~/").unwrap_or(&path[1..]);
+            let path_without_tilde = rest.strip_prefix('/').unwrap_or(rest);
             Ok(home.join(path_without_tilde))
         } else {
             Ok(PathBuf::from(path))
         }
     }
 
-    /// Get the expanded data directory path
+    /// Get the expanded data directory path of the active workspace
     pub fn data_dir_path(&self) -> Result<PathBuf> {
-        self.expand_path(&self.data_dir)
+        self.workspace_dir(self.workspace())
+    }
+
+    /// Names of every workspace, the default one first
+    pub fn workspace_names(&self) -> Vec<String> {
+        let mut names = vec![DEFAULT_WORKSPACE.to_string()];
+        names.extend(
+            self.workspaces
+                .keys()
+                .filter(|name| *name != DEFAULT_WORKSPACE)
+                .cloned(),
+        );
+        names
+    }
+
+    /// Whether a workspace with this name is configured
+    pub fn has_workspace(&self, name: &str) -> bool {
+        name == DEFAULT_WORKSPACE || self.workspaces.contains_key(name)
+    }
+
+    /// The workspace in use: the one chosen for this run, or the default
+    pub fn workspace(&self) -> &str {
+        self.active_workspace
+            .as_deref()
+            .unwrap_or(&self.default_workspace)
+    }
+
+    /// Use another workspace for this run, without changing the saved default
+    pub fn use_workspace(&mut self, name: &str) -> Result<()> {
+        if !self.has_workspace(name) {
+            bail!(
+                "Unknown workspace '{}' (configured: {})",
+                name,
+                self.workspace_names().join(", ")
+            );
+        }
+        self.active_workspace = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Get the expanded data directory path of a workspace
+    pub fn workspace_dir(&self, name: &str) -> Result<PathBuf> {
+        match self.workspaces.get(name) {
+            Some(dir) => self.expand_path(dir),
+            None if name == DEFAULT_WORKSPACE => self.expand_path(&self.data_dir),
+            None => bail!("Unknown workspace '{}'", name),
+        }
+    }
+
+    /// Get the expanded path of the encrypted secrets file
+    ///
+    /// Secrets belong to the user rather than a workspace, so the default
+    /// file sits in the default workspace's data directory.
+    pub fn secrets_file_path(&self) -> Result<PathBuf> {
+        match &self.secrets.file {
+            Some(file) => self.expand_path(file),
+            None => Ok(self.expand_path(&self.data_dir)?.join("secrets.enc")),
+        }
     }
 
     /// Ensure data directory exists
@@ -160,15 +1042,50 @@ impl Config {
         Ok(())
     }
 
+    /// Rename a project type, keeping its place in `project_types`
+    ///
+    /// If `new` is already listed, `old` is just removed; if `old` isn't
+    /// listed, `new` is added at the end.
+    pub fn rename_project_type(&mut self, old: &str, new: &str) {
+        let has_new = self.project_types.iter().any(|t| t == new);
+        match self.project_types.iter().position(|t| t == old) {
+            Some(index) if has_new => {
+                self.project_types.remove(index);
+            }
+            Some(index) => self.project_types[index] = new.to_string(),
+            None if !has_new => self.project_types.push(new.to_string()),
+            None => {}
+        }
+    }
+
     /// Get the full Jira ticket URL for a ticket number
     pub fn jira_ticket_url(&self, ticket: &str) -> String {
         format!("{}{}", self.jira_url, ticket)
     }
 
-    /// Get the database file path
+    /// URL templates for external references by lowercase system name
+    ///
+    /// Adds a `jira` template built from `jira_url` unless one is configured.
+    pub fn external_ref_url_templates(&self) -> BTreeMap<String, String> {
+        let mut templates: BTreeMap<String, String> = self
+            .external_ref_urls
+            .iter()
+            .map(|(system, template)| (system.trim().to_ascii_lowercase(), template.clone()))
+            .collect();
+        templates
+            .entry(crate::db::external_ref_repo::JIRA_SYSTEM.to_string())
+            .or_insert_with(|| format!("{}{{key}}", self.jira_url));
+        templates
+    }
+
+    /// Get the database file path of the active workspace
     pub fn database_path(&self) -> Result<PathBuf> {
-        let data_dir = self.data_dir_path()?;
-        Ok(data_dir.join("project-tracker.db"))
+        self.database_path_for(self.workspace())
+    }
+
+    /// Get the database file path of a workspace
+    pub fn database_path_for(&self, workspace: &str) -> Result<PathBuf> {
+        Ok(self.workspace_dir(workspace)?.join(DATABASE_FILE))
     }
 }
 
@@ -176,15 +1093,175 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             data_dir: default_data_dir(),
+            workspaces: BTreeMap::new(),
+            default_workspace: default_workspace(),
+            active_workspace: None,
+            mcp_workspace_switching: false,
+            mcp_require_confirmation: false,
+            mcp_idempotency_ttl_hours: default_mcp_idempotency_ttl_hours(),
             jira_url: default_jira_url(),
+            external_ref_urls: BTreeMap::new(),
             default_email_domain: default_email_domain(),
+            allowed_email_domains: Vec::new(),
+            current_user_email: None,
             project_types: default_project_types(),
+            project_phases: default_project_phases(),
+            document_types: default_document_types(),
+            roles: default_roles(),
+            allow_new_roles: default_allow_new_roles(),
+            mcp_enabled: default_mcp_enabled(),
             mcp_http_port: default_mcp_http_port(),
+            mcp_metrics_enabled: default_mcp_metrics_enabled(),
+            mcp_rate_limit_per_minute: default_mcp_rate_limit_per_minute(),
+            mcp_rate_limit_burst: default_mcp_rate_limit_burst(),
+            sync_token: None,
+            sync_tombstone_days: default_sync_tombstone_days(),
+            recurrence_horizon_months: default_recurrence_horizon_months(),
+            timezone: default_timezone(),
+            locale: default_locale(),
+            enforce_unique_project_names: false,
+            allow_unversioned_updates: default_allow_unversioned_updates(),
+            max_attachment_bytes: default_max_attachment_bytes(),
+            max_attachment_storage_bytes: default_max_attachment_storage_bytes(),
+            db_lock_timeout_ms: default_db_lock_timeout_ms(),
+            notifications_enabled: default_notifications_enabled(),
+            notify_days_before: default_notify_days_before(),
+            effort_warning_ratio: default_effort_warning_ratio(),
+            max_resources_per_milestone: None,
+            max_resources_hard_limit: false,
+            checklist_progress: false,
+            person_duplicate_threshold: default_person_duplicate_threshold(),
+            backup_retention_count: default_backup_retention_count(),
+            brief_max_chars: default_brief_max_chars(),
+            stakeholder_contact_days: default_stakeholder_contact_days(),
+            migrate_legacy_dir: false,
+            webhooks: Vec::new(),
+            bootstrap: default_bootstrap(),
+            hygiene: HygieneConfig::default(),
+            digest: DigestConfig::default(),
+            linkcheck: LinkCheckConfig::default(),
+            sync_safety: SyncSafetyConfig::default(),
+            secrets: SecretsConfig::default(),
             logging: LoggingConfig::default(),
         }
     }
 }
 
+/// How long to wait after the config file changes before reading it, so
+/// that an editor saving it in several steps has finished
+const RELOAD_DELAY: Duration = Duration::from_millis(250);
+
+/// Configuration shared by the parts of a long-running program, which can be
+/// replaced while it runs
+///
+/// Clones share the same configuration. Take a snapshot with
+/// [`get`](Self::get) once per operation, so the whole operation sees the
+/// same settings even if the file is reloaded partway through.
+#[derive(Debug, Clone)]
+pub struct SharedConfig {
+    current: Arc<RwLock<Arc<Config>>>,
+}
+
+impl SharedConfig {
+    pub fn new(config: Config) -> Self {
+        Self {
+            current: Arc::new(RwLock::new(Arc::new(config))),
+        }
+    }
+
+    /// The configuration as it is now
+    pub fn get(&self) -> Arc<Config> {
+        self.current.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Use `config` from now on
+    ///
+    /// The workspace chosen for this run is kept if `config` still has it.
+    pub fn replace(&self, mut config: Config) -> Arc<Config> {
+        let mut current = self.current.write().unwrap_or_else(|e| e.into_inner());
+        if let Some(workspace) = &current.active_workspace {
+            if config.has_workspace(workspace) {
+                config.active_workspace = Some(workspace.clone());
+            }
+        }
+        *current = Arc::new(config);
+        current.clone()
+    }
+
+    /// Load the config file at `path` and use it from now on
+    ///
+    /// A file that can't be read, parsed or validated is rejected and the
+    /// current configuration is kept.
+    pub fn reload(&self, path: &Path) -> Result<Arc<Config>> {
+        let config = Config::load(path)?;
+        Ok(self.replace(config))
+    }
+
+    /// Reload the configuration whenever the file at `path` changes
+    ///
+    /// `on_change` is called with each configuration that loads. Files that
+    /// don't load are logged and the current configuration is kept. The
+    /// directory holding the file is watched, so editors that save by
+    /// replacing the file are noticed too. Changes are picked up until the
+    /// returned watcher is dropped.
+    pub fn watch<F>(&self, path: &Path, on_change: F) -> Result<ConfigWatcher>
+    where
+        F: Fn(Arc<Config>) + Send + 'static,
+    {
+        let path = path.to_path_buf();
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let file_name = path.file_name().map(|name| name.to_os_string());
+
+        let (changed, changes) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+            Ok(event) if !event.kind.is_access() => {
+                if event.paths.iter().any(|p| p.file_name() == file_name.as_deref()) {
+                    let _ = changed.send(());
+                }
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("Error watching the config file: {}", e),
+        })
+        .context("Failed to watch the config file")?;
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .with_context(|| format!("Failed to watch {}", dir.display()))?;
+        log::debug!("Watching {} for changes", path.display());
+
+        // The thread stops once the watcher, and with it the sender, is dropped
+        let shared = self.clone();
+        std::thread::spawn(move || {
+            while changes.recv().is_ok() {
+                std::thread::sleep(RELOAD_DELAY);
+                while changes.try_recv().is_ok() {}
+                match shared.reload(&path) {
+                    Ok(config) => {
+                        log::info!("Reloaded configuration from {}", path.display());
+                        on_change(config);
+                    }
+                    Err(e) => log::warn!("Keeping the current configuration: {:#}", e),
+                }
+            }
+        });
+
+        Ok(ConfigWatcher { _watcher: watcher })
+    }
+}
+
+impl From<Config> for SharedConfig {
+    fn from(config: Config) -> Self {
+        Self::new(config)
+    }
+}
+
+/// Keeps reloading a [`SharedConfig`] from its file until dropped
+pub struct ConfigWatcher {
+    _watcher: notify::RecommendedWatcher,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -193,8 +1270,10 @@ mod tests {
     #[test]
     fn test_config_default() {
         let config = Config::default();
-        assert_eq!(config.data_dir, "~/.project-tracker");
+        assert!(config.data_dir.ends_with("project-tracker"));
         assert_eq!(config.logging.level, "info");
+        assert_eq!(config.recurrence_horizon_months, 3);
+        assert_eq!(config.document_types, vec!["Design Doc", "PRD", "RFC", "Runbook"]);
     }
 
     #[test]
@@ -216,6 +1295,288 @@ mod tests {
         assert_eq!(loaded.data_dir, config.data_dir);
     }
 
+    #[test]
+    fn test_config_webhooks() {
+        let config: Config = toml::from_str(
+            r#"
+            [[webhooks]]
+            url = "https://hooks.example.com/tracker"
+            events = ["milestone.due_date_changed"]
+            secret = "s3cret"
+
+            [[webhooks]]
+            url = "https://other.example.com/all"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.webhooks.len(), 2);
+        assert_eq!(config.webhooks[0].events, vec!["milestone.due_date_changed"]);
+        assert_eq!(config.webhooks[0].secret.as_deref(), Some("s3cret"));
+        assert!(config.webhooks[1].events.is_empty());
+        assert!(config.webhooks[1].secret.is_none());
+        assert!(Config::default().webhooks.is_empty());
+    }
+
+    #[test]
+    fn test_config_timezone() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+
+        assert_eq!(Config::default().tz(), Tz::UTC);
+
+        std::fs::write(&config_path, "timezone = \"Asia/Tokyo\"\n").unwrap();
+        let config = Config::load(&config_path).unwrap();
+        assert_eq!(config.tz(), chrono_tz::Asia::Tokyo);
+
+        std::fs::write(&config_path, "timezone = \"Mars/Olympus_Mons\"\n").unwrap();
+        let err = Config::load(&config_path).unwrap_err();
+        assert!(format!("{:#}", err).contains("Mars/Olympus_Mons"));
+    }
+
+    #[test]
+    fn test_config_unique_project_names() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+
+        assert!(!Config::default().enforce_unique_project_names);
+
+        std::fs::write(&config_path, "enforce_unique_project_names = true\n").unwrap();
+        let config = Config::load(&config_path).unwrap();
+        assert!(config.enforce_unique_project_names);
+    }
+
+    #[test]
+    fn test_config_unversioned_updates() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+
+        assert!(Config::default().allow_unversioned_updates);
+
+        std::fs::write(&config_path, "allow_unversioned_updates = false\n").unwrap();
+        assert!(!Config::load(&config_path).unwrap().allow_unversioned_updates);
+    }
+
+    #[test]
+    fn test_config_allowed_email_domains() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+
+        assert!(Config::default().allowed_email_domains.is_empty());
+
+        std::fs::write(&config_path, "allowed_email_domains = [\"company.com\"]\n").unwrap();
+        assert_eq!(Config::load(&config_path).unwrap().allowed_email_domains, ["company.com"]);
+
+        std::fs::write(&config_path, "allowed_email_domains = [\"@company.com\"]\n").unwrap();
+        assert!(Config::load(&config_path).is_err());
+    }
+
+    #[test]
+    fn test_config_current_user_email() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+
+        assert_eq!(Config::default().current_user_email, None);
+
+        std::fs::write(&config_path, "current_user_email = \"alice@company.com\"\n").unwrap();
+        assert_eq!(
+            Config::load(&config_path).unwrap().current_user_email.as_deref(),
+            Some("alice@company.com")
+        );
+
+        std::fs::write(&config_path, "current_user_email = \"alice\"\n").unwrap();
+        assert!(Config::load(&config_path).is_err());
+    }
+
+    #[test]
+    fn test_config_external_ref_urls() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+
+        let templates = Config::default().external_ref_url_templates();
+        assert_eq!(
+            templates.get("jira").map(String::as_str),
+            Some("https://jira.company.com/browse/{key}")
+        );
+
+        std::fs::write(
+            &config_path,
+            "jira_url = \"https://jira.example.com/browse/\"\n\
+             [external_ref_urls]\n\
+             Linear = \"https://linear.app/acme/issue/{key}\"\n",
+        )
+        .unwrap();
+        let templates = Config::load(&config_path).unwrap().external_ref_url_templates();
+        assert_eq!(
+            templates.get("linear").map(String::as_str),
+            Some("https://linear.app/acme/issue/{key}")
+        );
+        assert_eq!(
+            templates.get("jira").map(String::as_str),
+            Some("https://jira.example.com/browse/{key}")
+        );
+
+        std::fs::write(&config_path, "[external_ref_urls]\nlinear = \"https://linear.app/acme/issue/\"\n").unwrap();
+        assert!(Config::load(&config_path).is_err());
+    }
+
+    #[test]
+    fn test_config_project_phases() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+
+        assert_eq!(
+            Config::default().project_phases,
+            ["Discovery", "Design", "Build", "Rollout", "Hypercare"]
+        );
+
+        std::fs::write(&config_path, "project_phases = [\"Plan\", \"Ship\"]\n").unwrap();
+        assert_eq!(Config::load(&config_path).unwrap().project_phases, ["Plan", "Ship"]);
+
+        std::fs::write(&config_path, "project_phases = [\"Plan\", \"plan\"]\n").unwrap();
+        assert!(Config::load(&config_path).is_err());
+        std::fs::write(&config_path, "project_phases = [\"Plan\", \" \"]\n").unwrap();
+        assert!(Config::load(&config_path).is_err());
+    }
+
+    #[test]
+    fn test_config_bootstrap() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+
+        let defaults = Config::default().bootstrap;
+        assert_eq!(defaults.keys().collect::<Vec<_>>(), ["Company", "Team"]);
+
+        std::fs::write(
+            &config_path,
+            "[bootstrap.Team]\nmilestones = [\"Kickoff\", \"Design complete\"]\nstakeholders = [\"technical_lead\"]\n",
+        )
+        .unwrap();
+        let config = Config::load(&config_path).unwrap();
+        assert_eq!(config.bootstrap.len(), 1);
+        let rule = &config.bootstrap["Team"];
+        assert_eq!(rule.milestones, ["Kickoff", "Design complete"]);
+        assert!(rule.notes.is_empty());
+        assert_eq!(rule.stakeholders, [BootstrapStakeholder::TechnicalLead]);
+
+        std::fs::write(&config_path, "[bootstrap.Team]\nnotes = [\"\"]\n").unwrap();
+        assert!(Config::load(&config_path).is_err());
+    }
+
+    #[test]
+    fn test_config_rename_project_type() {
+        let mut config = Config::default();
+        config.rename_project_type("Company", "Org-wide");
+        assert_eq!(config.project_types, vec!["Personal", "Team", "Org-wide"]);
+
+        config.rename_project_type("Personal", "Team");
+        assert_eq!(config.project_types, vec!["Team", "Org-wide"]);
+
+        // Renaming a type that's only in the data adds the new name
+        config.rename_project_type("Company", "Department");
+        assert_eq!(config.project_types, vec!["Team", "Org-wide", "Department"]);
+        config.rename_project_type("Company", "Team");
+        assert_eq!(config.project_types, vec!["Team", "Org-wide", "Department"]);
+    }
+
+    #[test]
+    fn test_config_workspaces() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            format!(
+                "data_dir = \"{0}/work\"\ndefault_workspace = \"personal\"\n\n[workspaces]\npersonal = \"{0}/personal\"\n",
+                dir.path().display()
+            ),
+        )
+        .unwrap();
+
+        let mut config = Config::load(&config_path).unwrap();
+        assert_eq!(config.workspace_names(), vec!["default", "personal"]);
+        assert_eq!(config.workspace(), "personal");
+        assert_eq!(
+            config.database_path().unwrap(),
+            dir.path().join("personal/project-tracker.db")
+        );
+        assert_eq!(
+            config.database_path_for("default").unwrap(),
+            dir.path().join("work/project-tracker.db")
+        );
+        assert!(config.database_path_for("hobby").is_err());
+
+        // Choosing a workspace for a run isn't saved
+        config.use_workspace("default").unwrap();
+        assert_eq!(config.data_dir_path().unwrap(), dir.path().join("work"));
+        assert!(config.use_workspace("hobby").is_err());
+        config.save(&config_path).unwrap();
+        assert_eq!(Config::load(&config_path).unwrap().workspace(), "personal");
+
+        std::fs::write(&config_path, "default_workspace = \"hobby\"\n").unwrap();
+        let err = Config::load(&config_path).unwrap_err();
+        assert!(format!("{:#}", err).contains("hobby"));
+    }
+
+    #[test]
+    fn test_config_hygiene() {
+        let config: Config = toml::from_str(
+            r#"
+            [hygiene]
+            people_missing_team = false
+            max_gaps = 10
+            "#,
+        )
+        .unwrap();
+
+        assert!(!config.hygiene.checks.people_missing_team);
+        assert!(config.hygiene.checks.people_missing_manager);
+        assert_eq!(config.hygiene.max_gaps, 10);
+        assert_eq!(Config::default().hygiene.max_gaps, 0);
+
+        let saved: Config = toml::from_str(&toml::to_string_pretty(&config).unwrap()).unwrap();
+        assert!(!saved.hygiene.checks.people_missing_team);
+    }
+
+    #[test]
+    fn test_config_mcp_rate_limit() {
+        let config = Config::default();
+        assert_eq!(config.mcp_rate_limit_per_minute, 600);
+        assert_eq!(config.mcp_rate_limit_burst, 100);
+
+        let config: Config = toml::from_str("mcp_rate_limit_per_minute = 0\nmcp_rate_limit_burst = 0").unwrap();
+        assert!(config.validate().is_ok());
+
+        let config: Config = toml::from_str("mcp_rate_limit_per_minute = 60\nmcp_rate_limit_burst = 0").unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_digest() {
+        assert!(Config::default().digest.smtp.is_none());
+
+        let config: Config = toml::from_str(
+            r#"
+            [digest.smtp]
+            host = "smtp.company.com"
+            from = "tracker@company.com"
+            to = ["me@company.com"]
+            username = "tracker"
+            "#,
+        )
+        .unwrap();
+        config.validate().unwrap();
+
+        let smtp = config.digest.smtp.as_ref().unwrap();
+        assert_eq!(smtp.port, 587);
+        assert_eq!(smtp.password_secret, "smtp.password");
+        assert_eq!(smtp.password_env, "PROJECT_TRACKER_SMTP_PASSWORD");
+        assert_eq!(smtp.username.as_deref(), Some("tracker"));
+
+        let mut config = config;
+        config.digest.smtp.as_mut().unwrap().to.clear();
+        assert!(config.validate().is_err());
+    }
+
     #[test]
     fn test_expand_path() {
         let config = Config::default();
@@ -232,12 +1593,200 @@ mod tests {
     #[test]
     fn test_ensure_data_dir() {
         let dir = tempdir().unwrap();
-        let mut config = Config::default();
-        config.data_dir = dir.path().to_string_lossy().to_string();
+        let config = Config {
+            data_dir: dir.path().to_string_lossy().to_string(),
+            ..Config::default()
+        };
 
         config.ensure_data_dir().unwrap();
 
         // Check that data directory was created
         assert!(dir.path().exists());
     }
+
+    /// Resolve directories for a fake home with the given environment variables
+    fn resolve(home: &Path, vars: &[(&str, &str)]) -> AppDirs {
+        let vars: Vec<(String, String)> =
+            vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        AppDirs::resolve(
+            |name| vars.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone()),
+            home,
+        )
+    }
+
+    #[test]
+    fn test_config_path_resolution() {
+        let home = tempdir().unwrap();
+        let home = home.path();
+        let xdg_config = home.join("xdg-config");
+        let xdg_config_str = xdg_config.to_str().unwrap();
+
+        // Defaults to ~/.config without XDG_CONFIG_HOME, or with a relative one
+        assert_eq!(
+            resolve(home, &[]).config_path(),
+            home.join(".config/project-tracker/config.toml")
+        );
+        assert_eq!(
+            resolve(home, &[("XDG_CONFIG_HOME", "relative/config")]).config_path(),
+            home.join(".config/project-tracker/config.toml")
+        );
+        assert_eq!(
+            resolve(home, &[("XDG_CONFIG_HOME", xdg_config_str)]).config_path(),
+            xdg_config.join("project-tracker/config.toml")
+        );
+
+        // An existing legacy config is used until an XDG one exists
+        let legacy = home.join(".project-tracker/config.toml");
+        fs::create_dir_all(legacy.parent().unwrap()).unwrap();
+        fs::write(&legacy, "").unwrap();
+        assert_eq!(resolve(home, &[("XDG_CONFIG_HOME", xdg_config_str)]).config_path(), legacy);
+
+        let xdg = xdg_config.join("project-tracker/config.toml");
+        fs::create_dir_all(xdg.parent().unwrap()).unwrap();
+        fs::write(&xdg, "").unwrap();
+        assert_eq!(resolve(home, &[("XDG_CONFIG_HOME", xdg_config_str)]).config_path(), xdg);
+
+        // The environment override wins over both
+        let custom = home.join("custom.toml");
+        let dirs = resolve(
+            home,
+            &[
+                ("XDG_CONFIG_HOME", xdg_config_str),
+                (CONFIG_ENV_VAR, custom.to_str().unwrap()),
+            ],
+        );
+        assert_eq!(dirs.config_path(), custom);
+        // An empty override is ignored
+        assert_eq!(resolve(home, &[(CONFIG_ENV_VAR, "")]).config_path(), legacy);
+    }
+
+    #[test]
+    fn test_load_or_create_with_empty_override() {
+        let home = tempdir().unwrap();
+        let home = home.path();
+
+        // An empty $PROJECT_TRACKER_CONFIG falls back to the XDG location
+        let config = Config::load_or_create(&resolve(home, &[(CONFIG_ENV_VAR, "")])).unwrap();
+        let path = home.join(".config/project-tracker/config.toml");
+        assert!(path.exists());
+        assert_eq!(Config::load(&path).unwrap().data_dir, config.data_dir);
+    }
+
+    #[test]
+    fn test_default_data_dir_resolution() {
+        let home = tempdir().unwrap();
+        let home = home.path();
+        let xdg_data = home.join("xdg-data");
+
+        let dirs = resolve(home, &[("XDG_DATA_HOME", xdg_data.to_str().unwrap())]);
+        assert_eq!(PathBuf::from(dirs.default_data_dir()), xdg_data.join("project-tracker"));
+        assert_eq!(
+            PathBuf::from(resolve(home, &[]).default_data_dir()),
+            home.join(".local/share/project-tracker")
+        );
+
+        // Existing databases in the legacy directory stay where they are
+        let legacy = home.join(".project-tracker");
+        fs::create_dir_all(&legacy).unwrap();
+        fs::write(legacy.join(DATABASE_FILE), "").unwrap();
+        assert_eq!(PathBuf::from(dirs.default_data_dir()), legacy);
+    }
+
+    #[test]
+    fn test_migrate_legacy_dir() {
+        let home = tempdir().unwrap();
+        let home = home.path();
+        let dirs = resolve(home, &[]);
+
+        let legacy = home.join(".project-tracker");
+        fs::create_dir_all(&legacy).unwrap();
+        fs::write(legacy.join(DATABASE_FILE), "data").unwrap();
+        let mut config = Config {
+            data_dir: legacy.to_string_lossy().into_owned(),
+            migrate_legacy_dir: true,
+            ..Config::default()
+        };
+        config.save(legacy.join("config.toml")).unwrap();
+
+        let new_path = dirs.migrate_legacy(&mut config).unwrap();
+        assert_eq!(new_path, home.join(".config/project-tracker/config.toml"));
+        assert!(!legacy.exists());
+
+        let data_dir = home.join(".local/share/project-tracker");
+        assert_eq!(fs::read_to_string(data_dir.join(DATABASE_FILE)).unwrap(), "data");
+        assert!(!data_dir.join("config.toml").exists());
+
+        let saved = Config::load(&new_path).unwrap();
+        assert_eq!(PathBuf::from(&saved.data_dir), data_dir);
+        assert!(!saved.migrate_legacy_dir);
+        assert_eq!(dirs.config_path(), new_path);
+
+        // A second migration doesn't overwrite anything
+        fs::create_dir_all(&legacy).unwrap();
+        config.save(legacy.join("config.toml")).unwrap();
+        assert!(dirs.migrate_legacy(&mut config).is_err());
+        assert!(legacy.join("config.toml").exists());
+    }
+
+    #[test]
+    fn test_shared_config_reload() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "jira_url = \"https://a.example.com/browse/\"\n[workspaces]\npersonal = \"/tmp/personal\"\n",
+        )
+        .unwrap();
+        let mut config = Config::load(&path).unwrap();
+        config.use_workspace("personal").unwrap();
+        let shared = SharedConfig::new(config);
+        let before = shared.get();
+
+        fs::write(
+            &path,
+            "jira_url = \"https://b.example.com/browse/\"\ntimezone = \"Europe/Paris\"\n[workspaces]\npersonal = \"/tmp/personal\"\n",
+        )
+        .unwrap();
+        let reloaded = shared.reload(&path).unwrap();
+        assert_eq!(reloaded.jira_url, "https://b.example.com/browse/");
+        assert_eq!(shared.get().tz(), chrono_tz::Europe::Paris);
+        // The workspace chosen for this run survives the reload
+        assert_eq!(shared.get().workspace(), "personal");
+        // Snapshots taken earlier don't change
+        assert_eq!(before.jira_url, "https://a.example.com/browse/");
+
+        // A half-written file and an invalid one are rejected, keeping the last good config
+        fs::write(&path, "jira_url = \"https://c.exa").unwrap();
+        assert!(shared.reload(&path).is_err());
+        fs::write(&path, "jira_url = \"https://c.example.com/browse/\"\ntimezone = \"Mars/Olympus\"\n").unwrap();
+        let err = shared.reload(&path).unwrap_err();
+        assert!(format!("{:#}", err).contains("Unknown timezone 'Mars/Olympus'"));
+        assert_eq!(shared.get().jira_url, "https://b.example.com/browse/");
+
+        // A workspace that is no longer configured isn't kept
+        fs::write(&path, "jira_url = \"https://c.example.com/browse/\"\n").unwrap();
+        shared.reload(&path).unwrap();
+        assert_eq!(shared.get().jira_url, "https://c.example.com/browse/");
+        assert_eq!(shared.get().workspace(), DEFAULT_WORKSPACE);
+    }
+
+    #[test]
+    fn test_watch_reloads_config() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        Config::default().save(&path).unwrap();
+        let shared = SharedConfig::new(Config::load(&path).unwrap());
+
+        let (changed, changes) = mpsc::channel();
+        let _watcher = shared
+            .watch(&path, move |config| {
+                let _ = changed.send(config.jira_url.clone());
+            })
+            .unwrap();
+
+        fs::write(&path, "jira_url = \"https://new.example.com/browse/\"\n").unwrap();
+        let jira_url = changes.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(jira_url, "https://new.example.com/browse/");
+        assert_eq!(shared.get().jira_url, "https://new.example.com/browse/");
+    }
 }
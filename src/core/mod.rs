@@ -4,6 +4,8 @@
 
 //! Core domain models and business logic
 
+pub mod recurrence;
+
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 
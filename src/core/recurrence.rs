@@ -0,0 +1,410 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Recurring milestones
+//!
+//! A milestone with a recurrence rule defines a series. Its due date is the
+//! first occurrence; later occurrences are materialized as separate milestone
+//! rows linked back to the series so they can be edited independently.
+
+use crate::db::{Milestone, ProjectRepository};
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Duration, Months, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use rusqlite::Connection;
+use std::fmt;
+use std::str::FromStr;
+
+/// How often a series repeats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Weekly,
+    Monthly,
+}
+
+/// A recurrence rule (subset of iCalendar RRULE)
+///
+/// Supports `FREQ=WEEKLY|MONTHLY`, `INTERVAL`, and either `COUNT` or `UNTIL`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub count: Option<u32>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl FromStr for RecurrenceRule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let s = s.strip_prefix("RRULE:").unwrap_or(s);
+
+        let mut frequency = None;
+        let mut interval = None;
+        let mut count = None;
+        let mut until = None;
+
+        for part in s.split(';').filter(|p| !p.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| anyhow!("Invalid recurrence rule part: {}", part))?;
+
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.to_ascii_uppercase().as_str() {
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        other => bail!("Unsupported recurrence frequency: {}", other),
+                    });
+                }
+                "INTERVAL" => interval = Some(parse_positive("INTERVAL", value)?),
+                "COUNT" => count = Some(parse_positive("COUNT", value)?),
+                "UNTIL" => until = Some(parse_until(value)?),
+                other => bail!("Unsupported recurrence rule part: {}", other),
+            }
+        }
+
+        let frequency = frequency.ok_or_else(|| anyhow!("Recurrence rule is missing FREQ"))?;
+        if count.is_some() && until.is_some() {
+            bail!("Recurrence rule cannot have both COUNT and UNTIL");
+        }
+
+        Ok(Self {
+            frequency,
+            interval: interval.unwrap_or(1),
+            count,
+            until,
+        })
+    }
+}
+
+impl fmt::Display for RecurrenceRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let freq = match self.frequency {
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+        };
+        write!(f, "FREQ={}", freq)?;
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={}", count)?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%dT%H%M%SZ"))?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_positive(key: &str, value: &str) -> Result<u32> {
+    let n: u32 = value
+        .parse()
+        .map_err(|_| anyhow!("Invalid {} value: {}", key, value))?;
+    if n == 0 {
+        bail!("{} must be at least 1", key);
+    }
+    Ok(n)
+}
+
+/// Parse an UNTIL value (`YYYYMMDD` or `YYYYMMDDTHHMMSSZ`); a bare date includes the whole day
+fn parse_until(value: &str) -> Result<DateTime<Utc>> {
+    if let Ok(dt) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%SZ") {
+        return Ok(Utc.from_utc_datetime(&dt));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y%m%d") {
+        return Ok(Utc.from_utc_datetime(&date.and_hms_opt(23, 59, 59).unwrap()));
+    }
+    bail!("Invalid UNTIL value: {} (expected YYYYMMDD or YYYYMMDDTHHMMSSZ)", value)
+}
+
+impl RecurrenceRule {
+    /// Date of the occurrence at `index`, where index 0 is `start`
+    ///
+    /// Monthly occurrences are computed from `start` rather than the previous
+    /// occurrence, so a series on the 31st clamps to short months without drifting.
+    pub fn nth(&self, start: DateTime<Utc>, index: u32) -> Option<DateTime<Utc>> {
+        let steps = index.checked_mul(self.interval)?;
+        match self.frequency {
+            Frequency::Weekly => start.checked_add_signed(Duration::weeks(i64::from(steps))),
+            Frequency::Monthly => start.checked_add_months(Months::new(steps)),
+        }
+    }
+
+    /// Expand the rule into `(index, date)` occurrences from `start` through `horizon`
+    pub fn occurrences(&self, start: DateTime<Utc>, horizon: DateTime<Utc>) -> Vec<(u32, DateTime<Utc>)> {
+        let mut occurrences = Vec::new();
+        let mut index = 0;
+
+        loop {
+            if self.count.is_some_and(|count| index >= count) {
+                break;
+            }
+            let Some(date) = self.nth(start, index) else {
+                break;
+            };
+            if date > horizon || self.until.is_some_and(|until| date > until) {
+                break;
+            }
+            occurrences.push((index, date));
+            index += 1;
+        }
+
+        occurrences
+    }
+}
+
+/// Ensure milestone rows exist for every recurring series occurrence due in the next `horizon_months`
+///
+/// Returns the number of milestones created.
+pub fn materialize_recurring_milestones(conn: &Connection, horizon_months: u32) -> Result<usize> {
+    let horizon = Utc::now()
+        .checked_add_months(Months::new(horizon_months))
+        .ok_or_else(|| anyhow!("Invalid recurrence horizon: {} months", horizon_months))?;
+    materialize_recurring_milestones_until(conn, horizon)
+}
+
+/// Ensure milestone rows exist for every recurring series occurrence due on or before `horizon`
+///
+/// Occurrences are identified by their index in the series, so an occurrence
+/// that has been edited (even rescheduled) is never recreated or overwritten.
+pub fn materialize_recurring_milestones_until(conn: &Connection, horizon: DateTime<Utc>) -> Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+    let repo = ProjectRepository::new(&tx);
+    let mut created = 0;
+
+    for series in repo.get_recurring_milestones()? {
+        let rule = match series.recurrence_rule.as_deref().map(RecurrenceRule::from_str) {
+            Some(Ok(rule)) => rule,
+            Some(Err(e)) => {
+                log::warn!("Skipping milestone {} with invalid recurrence rule: {}", series.id, e);
+                continue;
+            }
+            None => continue,
+        };
+        let Some(start) = series.due_date else {
+            log::warn!("Skipping recurring milestone {} without a due date", series.id);
+            continue;
+        };
+
+        let existing = repo.get_series_indexes(&series.id)?;
+        let mut number = repo.next_milestone_number(&series.project_id)?;
+
+        for (index, due_date) in rule.occurrences(start, horizon) {
+            let index = index as i32;
+            if index == 0 || existing.contains(&index) {
+                continue;
+            }
+
+            let mut occurrence = Milestone::new(series.project_id, number, series.name.clone());
+            occurrence.description = series.description.clone();
+            occurrence.technical_lead = series.technical_lead.clone();
+            occurrence.team = series.team.clone();
+            occurrence.design_doc_url = series.design_doc_url.clone();
+            occurrence.estimated_days = series.estimated_days;
+            occurrence.start_date = series.start_date.map(|s| due_date - (start - s));
+            occurrence.due_date = Some(due_date);
+            occurrence.series_id = Some(series.id);
+            occurrence.series_index = Some(index);
+
+            repo.add_milestone(&occurrence)?;
+            log::debug!("Materialized occurrence {} of milestone series {}", index, series.id);
+            number += 1;
+            created += 1;
+        }
+    }
+
+    tx.commit()?;
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{self, Project};
+
+    fn utc(y: i32, m: u32, d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, 12, 0, 0).unwrap()
+    }
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn create_series(conn: &Connection, rule: &str, due: DateTime<Utc>) -> (Project, Milestone) {
+        let repo = ProjectRepository::new(conn);
+        let project = Project::new("Compliance".to_string());
+        repo.create(&project).unwrap();
+
+        let mut series = Milestone::new(project.id, 1, "Compliance review".to_string());
+        series.due_date = Some(due);
+        series.recurrence_rule = Some(rule.to_string());
+        repo.add_milestone(&series).unwrap();
+        (project, series)
+    }
+
+    // Rule parsing tests
+
+    #[test]
+    fn test_parse_rule() {
+        let rule: RecurrenceRule = "FREQ=MONTHLY;INTERVAL=2;COUNT=6".parse().unwrap();
+        assert_eq!(rule.frequency, Frequency::Monthly);
+        assert_eq!(rule.interval, 2);
+        assert_eq!(rule.count, Some(6));
+        assert!(rule.until.is_none());
+
+        let rule: RecurrenceRule = "RRULE:FREQ=WEEKLY;UNTIL=20250301".parse().unwrap();
+        assert_eq!(rule.frequency, Frequency::Weekly);
+        assert_eq!(rule.interval, 1);
+        assert_eq!(rule.until, Some(Utc.with_ymd_and_hms(2025, 3, 1, 23, 59, 59).unwrap()));
+    }
+
+    #[test]
+    fn test_parse_rule_errors() {
+        assert!("INTERVAL=2".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=DAILY".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=WEEKLY;INTERVAL=0".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=WEEKLY;COUNT=abc".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=WEEKLY;COUNT=3;UNTIL=20250101".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=WEEKLY;BYDAY=MO".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=WEEKLY;UNTIL=2025-01-01".parse::<RecurrenceRule>().is_err());
+    }
+
+    #[test]
+    fn test_rule_display_round_trip() {
+        for text in ["FREQ=WEEKLY", "FREQ=MONTHLY;INTERVAL=3;COUNT=4", "FREQ=WEEKLY;UNTIL=20250301T000000Z"] {
+            let rule: RecurrenceRule = text.parse().unwrap();
+            assert_eq!(rule.to_string(), text);
+        }
+    }
+
+    // Expansion tests
+
+    #[test]
+    fn test_weekly_occurrences() {
+        let rule: RecurrenceRule = "FREQ=WEEKLY;INTERVAL=2".parse().unwrap();
+        let dates = rule.occurrences(utc(2025, 1, 1), utc(2025, 2, 1));
+        assert_eq!(
+            dates,
+            vec![(0, utc(2025, 1, 1)), (1, utc(2025, 1, 15)), (2, utc(2025, 1, 29))]
+        );
+    }
+
+    #[test]
+    fn test_monthly_occurrences_clamp_to_month_end() {
+        let rule: RecurrenceRule = "FREQ=MONTHLY;COUNT=4".parse().unwrap();
+        let dates: Vec<_> = rule
+            .occurrences(utc(2024, 1, 31), utc(2030, 1, 1))
+            .into_iter()
+            .map(|(_, d)| d)
+            .collect();
+        assert_eq!(dates, vec![utc(2024, 1, 31), utc(2024, 2, 29), utc(2024, 3, 31), utc(2024, 4, 30)]);
+    }
+
+    #[test]
+    fn test_occurrences_respect_until() {
+        let rule: RecurrenceRule = "FREQ=MONTHLY;UNTIL=20250315".parse().unwrap();
+        let dates = rule.occurrences(utc(2025, 1, 15), utc(2030, 1, 1));
+        assert_eq!(dates.len(), 3);
+    }
+
+    // Materialization tests
+
+    #[test]
+    fn test_materialize_creates_occurrences() {
+        let conn = setup_test_db();
+        let (project, series) = create_series(&conn, "FREQ=MONTHLY", utc(2025, 1, 15));
+
+        let created = materialize_recurring_milestones_until(&conn, utc(2025, 4, 30)).unwrap();
+        assert_eq!(created, 3);
+
+        let milestones = ProjectRepository::new(&conn).get_milestones(&project.id).unwrap();
+        assert_eq!(milestones.len(), 4);
+        assert_eq!(milestones[1].series_id, Some(series.id));
+        assert_eq!(milestones[1].series_index, Some(1));
+        assert_eq!(milestones[1].number, 2);
+        assert_eq!(milestones[1].due_date, Some(utc(2025, 2, 15)));
+        assert!(milestones[1].recurrence_rule.is_none());
+        assert_eq!(milestones[3].due_date, Some(utc(2025, 4, 15)));
+    }
+
+    #[test]
+    fn test_materialize_is_idempotent() {
+        let conn = setup_test_db();
+        let (project, _) = create_series(&conn, "FREQ=WEEKLY;COUNT=5", utc(2025, 1, 1));
+
+        assert_eq!(materialize_recurring_milestones_until(&conn, utc(2026, 1, 1)).unwrap(), 4);
+        assert_eq!(materialize_recurring_milestones_until(&conn, utc(2026, 1, 1)).unwrap(), 0);
+
+        let milestones = ProjectRepository::new(&conn).get_milestones(&project.id).unwrap();
+        assert_eq!(milestones.len(), 5);
+    }
+
+    #[test]
+    fn test_editing_occurrence_does_not_clobber_others() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let (project, _) = create_series(&conn, "FREQ=MONTHLY", utc(2025, 1, 15));
+        materialize_recurring_milestones_until(&conn, utc(2025, 3, 31)).unwrap();
+
+        // Reschedule and rename the February occurrence
+        let mut milestones = repo.get_milestones(&project.id).unwrap();
+        let mut february = milestones.remove(1);
+        february.name = "Compliance review (moved)".to_string();
+        february.due_date = Some(utc(2025, 2, 20));
+        repo.update_milestone(&february).unwrap();
+
+        // Extending the horizon only adds the new occurrences
+        let created = materialize_recurring_milestones_until(&conn, utc(2025, 5, 31)).unwrap();
+        assert_eq!(created, 2);
+
+        let milestones = repo.get_milestones(&project.id).unwrap();
+        assert_eq!(milestones.len(), 5);
+        assert_eq!(milestones[1].name, "Compliance review (moved)");
+        assert_eq!(milestones[1].due_date, Some(utc(2025, 2, 20)));
+        assert_eq!(milestones[2].name, "Compliance review");
+        assert_eq!(milestones[2].due_date, Some(utc(2025, 3, 15)));
+    }
+
+    #[test]
+    fn test_materialize_skips_invalid_rules() {
+        let conn = setup_test_db();
+        let (project, _) = create_series(&conn, "FREQ=YEARLY", utc(2025, 1, 15));
+
+        let created = materialize_recurring_milestones_until(&conn, utc(2026, 1, 1)).unwrap();
+        assert_eq!(created, 0);
+        assert_eq!(ProjectRepository::new(&conn).get_milestones(&project.id).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_delete_series_keeping_occurrences() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let (project, series) = create_series(&conn, "FREQ=MONTHLY", utc(2025, 1, 15));
+        materialize_recurring_milestones_until(&conn, utc(2025, 3, 31)).unwrap();
+
+        repo.delete_milestone_series(&series.id, true).unwrap();
+
+        let milestones = repo.get_milestones(&project.id).unwrap();
+        assert_eq!(milestones.len(), 2);
+        assert!(milestones.iter().all(|m| m.series_id.is_none()));
+    }
+
+    #[test]
+    fn test_delete_series_with_occurrences() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let (project, series) = create_series(&conn, "FREQ=MONTHLY", utc(2025, 1, 15));
+        materialize_recurring_milestones_until(&conn, utc(2025, 3, 31)).unwrap();
+
+        repo.delete_milestone_series(&series.id, false).unwrap();
+
+        let milestones = repo.get_milestones(&project.id).unwrap();
+        assert!(milestones.is_empty());
+    }
+}
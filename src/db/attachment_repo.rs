@@ -0,0 +1,364 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+use super::error::{Error, Result};
+use super::{begin_or_join, get_datetime, get_uuid};
+use super::models::{Attachment, MilestoneNote, NoteType, ProjectNote, StakeholderNote};
+use super::project_repo::parse_column;
+use crate::utils::dt_to_db;
+use rusqlite::{params, Connection, OptionalExtension};
+use uuid::Uuid;
+
+/// Columns selected for an attachment, in the order `attachment_from_row` expects
+const ATTACHMENT_COLUMNS: &str =
+    "id, note_type, note_id, filename, mime_type, size_bytes, sha256, created_at";
+
+/// Longest file name kept for an attachment, in characters
+pub const MAX_FILENAME_LEN: usize = 255;
+
+/// Map a row selected with `ATTACHMENT_COLUMNS` to an attachment
+fn attachment_from_row(row: &rusqlite::Row) -> rusqlite::Result<Attachment> {
+    Ok(Attachment {
+        id: get_uuid(row, 0)?,
+        note_type: parse_column(row, 1)?,
+        note_id: get_uuid(row, 2)?,
+        filename: row.get(3)?,
+        mime_type: row.get(4)?,
+        size_bytes: row.get(5)?,
+        sha256: row.get(6)?,
+        created_at: get_datetime(row, 7)?,
+    })
+}
+
+/// Reduce an uploaded file name to its last path component
+///
+/// Browsers and file pickers may send full paths, and the name is shown
+/// back to users and used when saving, so directories are never kept.
+pub fn clean_filename(filename: &str) -> Result<String> {
+    let name = filename
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .trim();
+    if name.is_empty() || name == "." || name == ".." {
+        return Err(Error::Invalid(format!(
+            "Invalid attachment file name '{}'",
+            filename
+        )));
+    }
+    Ok(name.chars().take(MAX_FILENAME_LEN).collect())
+}
+
+/// A note that files can be attached to
+pub trait AttachedNote {
+    /// Kind of note, as stored with its attachments
+    const NOTE_TYPE: NoteType;
+
+    /// The note's ID
+    fn note_id(&self) -> Uuid;
+
+    /// The note's attachment list, to fill in after loading
+    fn attachments_mut(&mut self) -> &mut Vec<Attachment>;
+}
+
+impl AttachedNote for ProjectNote {
+    const NOTE_TYPE: NoteType = NoteType::Project;
+
+    fn note_id(&self) -> Uuid {
+        self.id
+    }
+
+    fn attachments_mut(&mut self) -> &mut Vec<Attachment> {
+        &mut self.attachments
+    }
+}
+
+impl AttachedNote for MilestoneNote {
+    const NOTE_TYPE: NoteType = NoteType::Milestone;
+
+    fn note_id(&self) -> Uuid {
+        self.id
+    }
+
+    fn attachments_mut(&mut self) -> &mut Vec<Attachment> {
+        &mut self.attachments
+    }
+}
+
+impl AttachedNote for StakeholderNote {
+    const NOTE_TYPE: NoteType = NoteType::Stakeholder;
+
+    fn note_id(&self) -> Uuid {
+        self.id
+    }
+
+    fn attachments_mut(&mut self) -> &mut Vec<Attachment> {
+        &mut self.attachments
+    }
+}
+
+/// Attachment repository for the metadata of files attached to notes
+///
+/// The files themselves are kept by `Storage`; this only tracks which notes
+/// refer to which file.
+pub struct AttachmentRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> AttachmentRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Record an attachment on a note, which must exist
+    pub fn add_attachment(&self, attachment: &Attachment) -> Result<()> {
+        let exists = self
+            .conn
+            .prepare_cached(&format!("SELECT 1 FROM {} WHERE id = ?1", attachment.note_type.table()))?
+            .query_row(params![attachment.note_id.to_string()], |_| Ok(()))
+            .optional()?
+            .is_some();
+        if !exists {
+            return Err(Error::not_found("Note", attachment.note_id));
+        }
+
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT INTO attachments (id, note_type, note_id, filename, mime_type, size_bytes, sha256, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )?;
+        stmt.execute(params![
+            attachment.id.to_string(),
+            attachment.note_type.as_str(),
+            attachment.note_id.to_string(),
+            &attachment.filename,
+            &attachment.mime_type,
+            attachment.size_bytes,
+            &attachment.sha256,
+            dt_to_db(attachment.created_at),
+        ])?;
+        log::debug!(
+            "Attached {} to {} note {}",
+            attachment.filename,
+            attachment.note_type,
+            attachment.note_id
+        );
+        Ok(())
+    }
+
+    /// Find an attachment by ID
+    pub fn find_by_id(&self, id: &Uuid) -> Result<Option<Attachment>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM attachments WHERE id = ?1",
+            ATTACHMENT_COLUMNS
+        ))?;
+        let attachment = stmt
+            .query_row(params![id.to_string()], attachment_from_row)
+            .optional()?;
+        Ok(attachment)
+    }
+
+    /// List a note's attachments in upload order
+    pub fn list_attachments(&self, note_type: NoteType, note_id: &Uuid) -> Result<Vec<Attachment>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM attachments WHERE note_type = ?1 AND note_id = ?2 ORDER BY created_at, id",
+            ATTACHMENT_COLUMNS
+        ))?;
+
+        let attachments = stmt
+            .query_map(
+                params![note_type.as_str(), note_id.to_string()],
+                attachment_from_row,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(attachments)
+    }
+
+    /// Fill in the attachments of each note
+    pub fn load_for_notes<N: AttachedNote>(&self, notes: &mut [N]) -> Result<()> {
+        for note in notes {
+            *note.attachments_mut() = self.list_attachments(N::NOTE_TYPE, &note.note_id())?;
+        }
+        Ok(())
+    }
+
+    /// Remove an attachment's record
+    ///
+    /// Returns the file's hash if no other attachment refers to it, in which
+    /// case the caller should remove the stored file.
+    pub fn remove_attachment(&self, id: &Uuid) -> Result<Option<String>> {
+        let attachment = self
+            .find_by_id(id)?
+            .ok_or_else(|| Error::not_found("Attachment", id))?;
+
+        let tx = begin_or_join(self.conn)?;
+        self.conn
+            .prepare_cached("DELETE FROM attachments WHERE id = ?1")?
+            .execute(params![id.to_string()])?;
+        let unreferenced = !self.is_referenced(&attachment.sha256)?;
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+
+        log::debug!("Removed attachment {}", id);
+        Ok(unreferenced.then_some(attachment.sha256))
+    }
+
+    /// Whether any attachment refers to the file with this hash
+    pub fn is_referenced(&self, sha256: &str) -> Result<bool> {
+        let count: i64 = self
+            .conn
+            .prepare_cached("SELECT COUNT(*) FROM attachments WHERE sha256 = ?1")?
+            .query_row(params![sha256], |row| row.get(0))?;
+        Ok(count > 0)
+    }
+
+    /// Bytes used by stored files, counting each distinct file once
+    pub fn total_size(&self) -> Result<u64> {
+        let total: i64 = self
+            .conn
+            .prepare_cached(
+                "SELECT COALESCE(SUM(size_bytes), 0)
+                 FROM (SELECT MAX(size_bytes) AS size_bytes FROM attachments GROUP BY sha256)",
+            )?
+            .query_row([], |row| row.get(0))?;
+        Ok(total.max(0) as u64)
+    }
+
+    /// Hashes of every file some attachment refers to
+    pub fn stored_hashes(&self) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare_cached("SELECT DISTINCT sha256 FROM attachments ORDER BY sha256")?;
+        let hashes = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(hashes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{schema, Project, ProjectRepository};
+    use chrono::Utc;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        schema::initialize_schema(&conn).unwrap();
+        schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn attachment(note: &ProjectNote, filename: &str, sha256: &str, size_bytes: i64) -> Attachment {
+        Attachment {
+            id: Uuid::new_v4(),
+            note_type: NoteType::Project,
+            note_id: note.id,
+            filename: filename.to_string(),
+            mime_type: "image/png".to_string(),
+            size_bytes,
+            sha256: sha256.to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_attachments() {
+        let conn = setup_test_db();
+        let projects = ProjectRepository::new(&conn);
+        let project = Project::new("Apollo".to_string());
+        projects.create(&project).unwrap();
+        let note = ProjectNote::new(project.id, "Design".to_string(), "See attached".to_string());
+        projects.add_project_note(&note).unwrap();
+
+        let repo = AttachmentRepository::new(&conn);
+        let first = attachment(&note, "diagram.png", "aa", 100);
+        let copy = attachment(&note, "diagram copy.png", "aa", 100);
+        let other = attachment(&note, "spec.pdf", "bb", 50);
+        for a in [&first, &copy, &other] {
+            repo.add_attachment(a).unwrap();
+        }
+        // The same file attached twice only counts once
+        assert_eq!(repo.total_size().unwrap(), 150);
+        assert_eq!(repo.stored_hashes().unwrap(), vec!["aa", "bb"]);
+
+        // Notes carry their attachments
+        let loaded = projects.find_project_note_by_id(&note.id).unwrap().unwrap();
+        assert_eq!(loaded.attachments.len(), 3);
+        assert_eq!(loaded.attachments[0].filename, "diagram.png");
+
+        // The file is only unreferenced once its last attachment is removed
+        assert_eq!(repo.remove_attachment(&first.id).unwrap(), None);
+        assert_eq!(
+            repo.remove_attachment(&copy.id).unwrap().as_deref(),
+            Some("aa")
+        );
+        assert!(matches!(
+            repo.remove_attachment(&copy.id),
+            Err(Error::NotFound { .. })
+        ));
+
+        // Attachments need a note, and go away with it
+        let mut orphan = attachment(&note, "x.png", "cc", 1);
+        orphan.note_type = NoteType::Milestone;
+        assert!(matches!(
+            repo.add_attachment(&orphan),
+            Err(Error::NotFound { .. })
+        ));
+        projects.delete_project_note(&note.id).unwrap();
+        assert!(repo
+            .list_attachments(NoteType::Project, &note.id)
+            .unwrap()
+            .is_empty());
+        assert_eq!(repo.total_size().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_attachments_follow_moved_notes() {
+        let conn = setup_test_db();
+        let projects = ProjectRepository::new(&conn);
+        let project = Project::new("Apollo".to_string());
+        projects.create(&project).unwrap();
+        let milestone = crate::db::Milestone::new(project.id, 1, "Launch".to_string());
+        projects.add_milestone(&milestone).unwrap();
+        let note = ProjectNote::new(project.id, "Design".to_string(), String::new());
+        projects.add_project_note(&note).unwrap();
+
+        let repo = AttachmentRepository::new(&conn);
+        repo.add_attachment(&attachment(&note, "diagram.png", "aa", 10))
+            .unwrap();
+
+        projects
+            .move_note(
+                &note.id,
+                &crate::db::NoteTarget::Milestone {
+                    milestone_id: milestone.id,
+                },
+            )
+            .unwrap();
+        let moved = projects
+            .find_milestone_note_by_id(&note.id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(moved.attachments.len(), 1);
+        assert_eq!(moved.attachments[0].note_type, NoteType::Milestone);
+    }
+
+    #[test]
+    fn test_clean_filename() {
+        assert_eq!(clean_filename("report.pdf").unwrap(), "report.pdf");
+        assert_eq!(
+            clean_filename("C:\\Users\\me\\shot.png").unwrap(),
+            "shot.png"
+        );
+        assert_eq!(clean_filename("../../etc/passwd").unwrap(), "passwd");
+        assert!(clean_filename("dir/").is_err());
+        assert!(clean_filename("..").is_err());
+        assert_eq!(
+            clean_filename(&"a".repeat(300)).unwrap().len(),
+            MAX_FILENAME_LEN
+        );
+    }
+}
@@ -0,0 +1,420 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Autocomplete for people and teams
+//!
+//! Suggestions come from an indexed prefix search first. When that finds
+//! fewer than the requested number, the rest are filled with fuzzy matches:
+//! the query appearing anywhere in a name or email, or its characters
+//! appearing in order. People are matched against an in-memory index of
+//! lowercase names and email usernames so fuzzy matching doesn't touch the
+//! database on every keystroke. The index is rebuilt whenever the database
+//! has changed since it was built, whoever changed it.
+
+use super::error::Result;
+use super::models::PersonSuggestion;
+use super::person_repo::PersonRepository;
+use super::team_repo::TeamRepository;
+use rusqlite::{params, Connection};
+
+/// Counters that change whenever the database does
+///
+/// `data_version` changes when another connection commits and
+/// `total_changes()` when this one writes, so together they notice changes
+/// from the desktop app, the CLI and the MCP server alike.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct ChangeStamp {
+    data_version: i64,
+    total_changes: i64,
+}
+
+impl ChangeStamp {
+    fn read(conn: &Connection) -> Result<Self> {
+        let mut stmt = conn.prepare_cached("SELECT data_version, total_changes() FROM pragma_data_version")?;
+        Ok(stmt.query_row([], |row| {
+            Ok(Self {
+                data_version: row.get(0)?,
+                total_changes: row.get(1)?,
+            })
+        })?)
+    }
+}
+
+/// A person in the index, with lowercase copies of the fields that are matched
+struct IndexedPerson {
+    name: String,
+    /// The part of the email before the `@`, since every domain matches alike
+    username: String,
+    /// [`byte_mask`] of the name and username together
+    bytes: u64,
+    suggestion: PersonSuggestion,
+}
+
+/// Cached lowercase names and email usernames of active people
+#[derive(Default)]
+pub struct PeopleIndex {
+    people: Vec<IndexedPerson>,
+    built_at: Option<ChangeStamp>,
+}
+
+impl PeopleIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rebuild the index on its next use, such as after switching to another database
+    ///
+    /// Changes to the same database are noticed without this.
+    pub fn invalidate(&mut self) {
+        self.built_at = None;
+    }
+
+    fn refresh(&mut self, conn: &Connection) -> Result<()> {
+        let stamp = ChangeStamp::read(conn)?;
+        if self.built_at == Some(stamp) {
+            return Ok(());
+        }
+
+        let mut stmt = conn.prepare_cached("SELECT email, name, team FROM people WHERE active")?;
+        self.people = stmt
+            .query_map([], |row| {
+                let suggestion = PersonSuggestion {
+                    email: row.get(0)?,
+                    name: row.get(1)?,
+                    team: row.get(2)?,
+                };
+                let name = suggestion.name.to_lowercase();
+                let username = suggestion
+                    .email
+                    .split('@')
+                    .next()
+                    .unwrap_or_default()
+                    .to_lowercase();
+                Ok(IndexedPerson {
+                    bytes: byte_mask(&name) | byte_mask(&username),
+                    name,
+                    username,
+                    suggestion,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        self.built_at = Some(stamp);
+        log::debug!(
+            "Rebuilt people autocomplete index ({} people)",
+            self.people.len()
+        );
+        Ok(())
+    }
+
+    /// Find up to `limit` fuzzy matches for `query`, best first, skipping emails in `exclude`
+    fn fuzzy(
+        &self,
+        query: &str,
+        limit: usize,
+        exclude: &[PersonSuggestion],
+    ) -> Vec<PersonSuggestion> {
+        if limit == 0 {
+            return Vec::new();
+        }
+        let query = query.to_lowercase();
+        let query_bytes = byte_mask(&query);
+        let mut matches: Vec<_> = self
+            .people
+            .iter()
+            // Whoever lacks one of the query's characters can't match it
+            .filter(|p| p.bytes & query_bytes == query_bytes)
+            .filter_map(|p| {
+                let score = fuzzy_score(&p.name, &query).max(fuzzy_score(&p.username, &query))?;
+                Some((score, p))
+            })
+            .filter(|(_, p)| !exclude.iter().any(|e| e.email == p.suggestion.email))
+            .collect();
+        // Short queries match most people, so only the best few are sorted
+        let best_first = |(a, pa): &(i32, &IndexedPerson), (b, pb): &(i32, &IndexedPerson)| {
+            b.cmp(a).then_with(|| pa.name.cmp(&pb.name))
+        };
+        if matches.len() > limit {
+            matches.select_nth_unstable_by(limit - 1, best_first);
+            matches.truncate(limit);
+        }
+        matches.sort_by(best_first);
+        matches
+            .into_iter()
+            .map(|(_, p)| p.suggestion.clone())
+            .collect()
+    }
+}
+
+/// Suggest up to `limit` active people for `query`
+///
+/// Prefix matches on name or email come first, sorted by name, followed by
+/// fuzzy matches from `index`.
+pub fn autocomplete_people(
+    conn: &Connection,
+    index: &mut PeopleIndex,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<PersonSuggestion>> {
+    let query = query.trim();
+    let mut suggestions = PersonRepository::new(conn).autocomplete(query, limit)?;
+    if suggestions.len() < limit && !query.is_empty() {
+        index.refresh(conn)?;
+        let more = index.fuzzy(query, limit - suggestions.len(), &suggestions);
+        suggestions.extend(more);
+    }
+    Ok(suggestions)
+}
+
+/// Suggest up to `limit` team names for `query`
+///
+/// Prefix matches come first, followed by fuzzy matches. Teams are few, so
+/// they are matched straight from the database.
+pub fn autocomplete_teams(conn: &Connection, query: &str, limit: usize) -> Result<Vec<String>> {
+    let query = query.trim();
+    let mut names = TeamRepository::new(conn).autocomplete(query, limit)?;
+    if names.len() < limit && !query.is_empty() {
+        let lowercase_query = query.to_lowercase();
+        let mut stmt = conn.prepare_cached("SELECT name FROM teams")?;
+        let mut matches: Vec<(i32, String)> = stmt
+            .query_map(params![], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?
+            .into_iter()
+            .filter(|name| !names.contains(name))
+            .filter_map(|name| Some((fuzzy_score(&name.to_lowercase(), &lowercase_query)?, name)))
+            .collect();
+        matches.sort_by(|(a, na), (b, nb)| b.cmp(a).then_with(|| na.cmp(nb)));
+        names.extend(
+            matches
+                .into_iter()
+                .take(limit - names.len())
+                .map(|(_, name)| name),
+        );
+    }
+    Ok(names)
+}
+
+/// A bit for each byte value in `text`, folded into 64 bits
+///
+/// If `text` contains `query`, or its characters in order, the mask of
+/// `text` includes every bit of the mask of `query`.
+fn byte_mask(text: &str) -> u64 {
+    text.bytes().fold(0, |mask, byte| mask | 1 << (byte & 63))
+}
+
+/// Score how well `query` matches `candidate`, both lowercase; higher is better
+///
+/// A substring match scores highest, the earlier the better. Otherwise every
+/// character of the query must appear in order, losing a point for each
+/// character skipped between them.
+fn fuzzy_score(candidate: &str, query: &str) -> Option<i32> {
+    if let Some(position) = candidate.find(query) {
+        return Some(1000 - position as i32);
+    }
+
+    // Names are nearly always ASCII, where bytes and characters line up
+    if candidate.is_ascii() && query.is_ascii() {
+        in_order_score(candidate.bytes(), query.bytes())
+    } else {
+        in_order_score(candidate.chars(), query.chars())
+    }
+}
+
+/// Score `query` appearing in order in `candidate`, a point off per item skipped
+fn in_order_score<T: PartialEq>(candidate: impl Iterator<Item = T>, query: impl Iterator<Item = T>) -> Option<i32> {
+    let mut score = 500;
+    let mut candidate = candidate.enumerate();
+    let mut last = None;
+    for q in query {
+        let (position, _) = candidate.by_ref().find(|(_, c)| *c == q)?;
+        if let Some(last) = last {
+            score -= (position - last - 1) as i32;
+        }
+        last = Some(position);
+    }
+    Some(score.max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{self, Person, Team};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn add_person(conn: &Connection, email: &str, name: &str) {
+        PersonRepository::new(conn)
+            .create(&Person::new(email.to_string(), name.to_string()))
+            .unwrap();
+    }
+
+    fn emails(suggestions: &[PersonSuggestion]) -> Vec<&str> {
+        suggestions.iter().map(|s| s.email.as_str()).collect()
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("alice smith", "alice"), Some(1000));
+        assert_eq!(fuzzy_score("alice smith", "smith"), Some(994));
+        // "asm" skips "lice " between "a" and "s"
+        assert_eq!(fuzzy_score("alice smith", "asm"), Some(495));
+        assert_eq!(fuzzy_score("alice smith", "zed"), None);
+        assert_eq!(fuzzy_score("alice smith", "htims"), None);
+        // Positions count characters, not bytes
+        assert_eq!(fuzzy_score("josé garcía", "jgarc"), Some(496));
+    }
+
+    #[test]
+    fn test_autocomplete_people() {
+        let conn = setup_test_db();
+        add_person(&conn, "alice@example.com", "Alice Smith");
+        add_person(&conn, "bob@example.com", "Bob Jones");
+        add_person(&conn, "carol@example.com", "Carol Alison");
+        add_person(&conn, "ann_lee@example.com", "Ann Lee");
+        let mut index = PeopleIndex::new();
+
+        // Prefix matches on name and email, ignoring case
+        let found = PersonRepository::new(&conn).autocomplete("al", 10).unwrap();
+        assert_eq!(emails(&found), vec!["alice@example.com"]);
+        let found = PersonRepository::new(&conn)
+            .autocomplete("BOB@", 10)
+            .unwrap();
+        assert_eq!(emails(&found), vec!["bob@example.com"]);
+
+        // Wildcards in the query are matched literally
+        let found = PersonRepository::new(&conn)
+            .autocomplete("ann_", 10)
+            .unwrap();
+        assert_eq!(emails(&found), vec!["ann_lee@example.com"]);
+        assert!(PersonRepository::new(&conn)
+            .autocomplete("%", 10)
+            .unwrap()
+            .is_empty());
+
+        // Fuzzy matches fill in after the prefix matches
+        let found = autocomplete_people(&conn, &mut index, "al", 10).unwrap();
+        assert_eq!(
+            emails(&found),
+            vec![
+                "alice@example.com",
+                "carol@example.com",
+                "ann_lee@example.com"
+            ]
+        );
+        let found = autocomplete_people(&conn, &mut index, "bjns", 10).unwrap();
+        assert_eq!(emails(&found), vec!["bob@example.com"]);
+        let found = autocomplete_people(&conn, &mut index, "al", 1).unwrap();
+        assert_eq!(emails(&found), vec!["alice@example.com"]);
+
+        // The index sees changes without being invalidated
+        add_person(&conn, "dave@example.com", "Dave Malone");
+        let found = autocomplete_people(&conn, &mut index, "malo", 10).unwrap();
+        assert_eq!(emails(&found), vec!["dave@example.com"]);
+    }
+
+    #[test]
+    fn test_index_sees_changes_from_other_connections() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.db");
+        let conn = db::open_database(&path).unwrap();
+        let other = db::open_database(&path).unwrap();
+        add_person(&conn, "alice@example.com", "Alice Smith");
+        let mut index = PeopleIndex::new();
+        let found = autocomplete_people(&conn, &mut index, "malo", 10).unwrap();
+        assert!(found.is_empty());
+
+        // As when the CLI or MCP server adds someone while the app is open
+        add_person(&other, "dave@example.com", "Dave Malone");
+        let found = autocomplete_people(&conn, &mut index, "malo", 10).unwrap();
+        assert_eq!(emails(&found), vec!["dave@example.com"]);
+
+        PersonRepository::new(&other)
+            .deactivate("dave@example.com", false)
+            .unwrap();
+        let found = autocomplete_people(&conn, &mut index, "malo", 10).unwrap();
+        assert!(found.is_empty());
+    }
+
+    /// Run with `cargo test --release -- --ignored test_autocomplete_speed`
+    #[test]
+    #[ignore = "timing test; run in release builds"]
+    fn test_autocomplete_speed() {
+        let conn = setup_test_db();
+        let count = crate::fixtures::seed_people(&conn, 10_000, 42).unwrap();
+        assert_eq!(count, 10_000);
+        let mut index = PeopleIndex::new();
+        autocomplete_people(&conn, &mut index, "a", 10).unwrap();
+
+        let queries = ["al", "smi", "jns", "ei", "mrtn", "zq", "kim lee", "o"];
+        let rounds = 50;
+        let started = std::time::Instant::now();
+        for _ in 0..rounds {
+            for query in queries {
+                autocomplete_people(&conn, &mut index, query, 10).unwrap();
+            }
+        }
+        let average = started.elapsed() / (rounds * queries.len()) as u32;
+        println!("Average autocomplete over 10,000 people: {:?}", average);
+        assert!(average < std::time::Duration::from_millis(1), "{:?}", average);
+    }
+
+    #[test]
+    fn test_autocomplete_skips_inactive_people() {
+        let conn = setup_test_db();
+        add_person(&conn, "alice@example.com", "Alice Smith");
+        PersonRepository::new(&conn)
+            .deactivate("alice@example.com", false)
+            .unwrap();
+
+        assert!(PersonRepository::new(&conn)
+            .autocomplete("ali", 10)
+            .unwrap()
+            .is_empty());
+        let found = autocomplete_people(&conn, &mut PeopleIndex::new(), "smith", 10).unwrap();
+        assert!(found.is_empty());
+    }
+
+    #[test]
+    fn test_autocomplete_teams() {
+        let conn = setup_test_db();
+        let teams = TeamRepository::new(&conn);
+        for name in ["Platform", "Mobile", "Data Platform"] {
+            teams.create(&Team::new(name.to_string())).unwrap();
+        }
+
+        assert_eq!(teams.autocomplete("plat", 10).unwrap(), vec!["Platform"]);
+        assert_eq!(
+            autocomplete_teams(&conn, "plat", 10).unwrap(),
+            vec!["Platform", "Data Platform"]
+        );
+        assert_eq!(
+            autocomplete_teams(&conn, "mbl", 10).unwrap(),
+            vec!["Mobile"]
+        );
+    }
+
+    #[test]
+    fn test_prefix_search_uses_indexes() {
+        let conn = setup_test_db();
+        let plan: Vec<String> = conn
+            .prepare(
+                "EXPLAIN QUERY PLAN SELECT email, name, team FROM people
+                 WHERE (name LIKE ?1 ESCAPE '\\' OR email LIKE ?1 ESCAPE '\\') AND active
+                 LIMIT ?2",
+            )
+            .unwrap()
+            .query_map(params!["ali%", 10], |row| row.get(3))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        let plan = plan.join("\n");
+
+        assert!(plan.contains("idx_people_name_nocase"), "{}", plan);
+        assert!(plan.contains("idx_people_email_nocase"), "{}", plan);
+    }
+}
@@ -0,0 +1,301 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+use super::error::{Error, Result};
+use super::models::ChecklistItem;
+use super::{begin_or_join, get_datetime, get_opt_datetime, get_uuid};
+use crate::utils::dt_to_db;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Columns selected for a checklist item, in the order `checklist_item_from_row` expects
+const CHECKLIST_COLUMNS: &str = "id, milestone_id, position, text, checked, checked_at, created_at";
+
+/// Longest checklist item text, in characters
+pub const MAX_CHECKLIST_TEXT_LEN: usize = 500;
+
+/// Map a row selected with `CHECKLIST_COLUMNS` to a checklist item
+fn checklist_item_from_row(row: &rusqlite::Row) -> rusqlite::Result<ChecklistItem> {
+    Ok(ChecklistItem {
+        id: get_uuid(row, 0)?,
+        milestone_id: get_uuid(row, 1)?,
+        position: row.get(2)?,
+        text: row.get(3)?,
+        checked: row.get(4)?,
+        checked_at: get_opt_datetime(row, 5)?,
+        created_at: get_datetime(row, 6)?,
+    })
+}
+
+/// Checklist repository for the small sub-tasks on milestones
+///
+/// Items are numbered from 1 in checklist order, and the numbers stay
+/// dense: removing or moving an item renumbers the ones after it.
+pub struct ChecklistRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> ChecklistRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Add an item to the end of a milestone's checklist
+    pub fn add_item(&self, milestone_id: &Uuid, text: &str) -> Result<ChecklistItem> {
+        let text = validate_text(text)?;
+        let exists = self
+            .conn
+            .prepare_cached("SELECT 1 FROM milestones WHERE id = ?1")?
+            .query_row(params![milestone_id.to_string()], |_| Ok(()))
+            .optional()?
+            .is_some();
+        if !exists {
+            return Err(Error::not_found("Milestone", milestone_id));
+        }
+
+        let item = ChecklistItem {
+            id: Uuid::new_v4(),
+            milestone_id: *milestone_id,
+            position: self.count_items(milestone_id)? as i32 + 1,
+            text,
+            checked: false,
+            checked_at: None,
+            created_at: Utc::now(),
+        };
+        self.conn
+            .prepare_cached(
+                "INSERT INTO milestone_checklist_items (id, milestone_id, position, text, checked, checked_at, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )?
+            .execute(params![
+                item.id.to_string(),
+                item.milestone_id.to_string(),
+                item.position,
+                &item.text,
+                item.checked,
+                item.checked_at.map(dt_to_db),
+                dt_to_db(item.created_at),
+            ])?;
+
+        log::debug!("Added checklist item {} to milestone {}", item.id, milestone_id);
+        Ok(item)
+    }
+
+    /// Find a checklist item by ID
+    pub fn find_by_id(&self, id: &Uuid) -> Result<Option<ChecklistItem>> {
+        let item = self
+            .conn
+            .prepare_cached(&format!(
+                "SELECT {} FROM milestone_checklist_items WHERE id = ?1",
+                CHECKLIST_COLUMNS
+            ))?
+            .query_row(params![id.to_string()], checklist_item_from_row)
+            .optional()?;
+        Ok(item)
+    }
+
+    /// List a milestone's checklist in order
+    pub fn list_items(&self, milestone_id: &Uuid) -> Result<Vec<ChecklistItem>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM milestone_checklist_items WHERE milestone_id = ?1 ORDER BY position",
+            CHECKLIST_COLUMNS
+        ))?;
+        let items = stmt
+            .query_map(params![milestone_id.to_string()], checklist_item_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(items)
+    }
+
+    /// List the checklists of every milestone in a project, by milestone
+    ///
+    /// Milestones without a checklist are left out.
+    pub fn list_for_project(&self, project_id: &Uuid) -> Result<HashMap<Uuid, Vec<ChecklistItem>>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM milestone_checklist_items
+             WHERE milestone_id IN (SELECT id FROM milestones WHERE project_id = ?1)
+             ORDER BY milestone_id, position",
+            CHECKLIST_COLUMNS
+        ))?;
+        let mut checklists: HashMap<Uuid, Vec<ChecklistItem>> = HashMap::new();
+        for item in stmt.query_map(params![project_id.to_string()], checklist_item_from_row)? {
+            let item = item?;
+            checklists.entry(item.milestone_id).or_default().push(item);
+        }
+        Ok(checklists)
+    }
+
+    /// Check or uncheck an item, flipping it when `checked` isn't given
+    ///
+    /// Returns the item as stored.
+    pub fn toggle_item(&self, id: &Uuid, checked: Option<bool>) -> Result<ChecklistItem> {
+        let mut item = self
+            .find_by_id(id)?
+            .ok_or_else(|| Error::not_found("Checklist item", id))?;
+        let checked = checked.unwrap_or(!item.checked);
+        if checked != item.checked {
+            item.checked = checked;
+            item.checked_at = checked.then(Utc::now);
+            self.conn
+                .prepare_cached("UPDATE milestone_checklist_items SET checked = ?1, checked_at = ?2 WHERE id = ?3")?
+                .execute(params![item.checked, item.checked_at.map(dt_to_db), id.to_string()])?;
+        }
+        Ok(item)
+    }
+
+    /// Move an item to `position`, shifting the items between its old and new place
+    ///
+    /// Positions past the end move the item to the end. Returns the item as stored.
+    pub fn move_item(&self, id: &Uuid, position: i32) -> Result<ChecklistItem> {
+        if position < 1 {
+            return Err(Error::Invalid(format!(
+                "Invalid checklist position {}: positions start at 1",
+                position
+            )));
+        }
+        let mut item = self
+            .find_by_id(id)?
+            .ok_or_else(|| Error::not_found("Checklist item", id))?;
+        let position = position.min(self.count_items(&item.milestone_id)? as i32);
+        if position == item.position {
+            return Ok(item);
+        }
+
+        let tx = begin_or_join(self.conn)?;
+        let (shift, from, to) = if position < item.position {
+            (1, position, item.position - 1)
+        } else {
+            (-1, item.position + 1, position)
+        };
+        self.conn
+            .prepare_cached(
+                "UPDATE milestone_checklist_items SET position = position + ?1
+                 WHERE milestone_id = ?2 AND position BETWEEN ?3 AND ?4",
+            )?
+            .execute(params![shift, item.milestone_id.to_string(), from, to])?;
+        self.conn
+            .prepare_cached("UPDATE milestone_checklist_items SET position = ?1 WHERE id = ?2")?
+            .execute(params![position, id.to_string()])?;
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+
+        item.position = position;
+        Ok(item)
+    }
+
+    /// Remove an item, moving the items after it up
+    pub fn remove_item(&self, id: &Uuid) -> Result<()> {
+        let item = self
+            .find_by_id(id)?
+            .ok_or_else(|| Error::not_found("Checklist item", id))?;
+
+        let tx = begin_or_join(self.conn)?;
+        self.conn
+            .prepare_cached("DELETE FROM milestone_checklist_items WHERE id = ?1")?
+            .execute(params![id.to_string()])?;
+        self.conn
+            .prepare_cached(
+                "UPDATE milestone_checklist_items SET position = position - 1
+                 WHERE milestone_id = ?1 AND position > ?2",
+            )?
+            .execute(params![item.milestone_id.to_string(), item.position])?;
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+
+        log::debug!("Removed checklist item {} from milestone {}", id, item.milestone_id);
+        Ok(())
+    }
+
+    fn count_items(&self, milestone_id: &Uuid) -> Result<usize> {
+        let count = self
+            .conn
+            .prepare_cached("SELECT COUNT(*) FROM milestone_checklist_items WHERE milestone_id = ?1")?
+            .query_row(params![milestone_id.to_string()], |row| row.get(0))?;
+        Ok(count)
+    }
+}
+
+/// Checklist items need text, and it's kept to one line
+fn validate_text(text: &str) -> Result<String> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return Err(Error::Invalid("Checklist item text cannot be empty".to_string()));
+    }
+    if text.chars().count() > MAX_CHECKLIST_TEXT_LEN {
+        return Err(Error::Invalid(format!(
+            "Checklist item text is longer than {} characters",
+            MAX_CHECKLIST_TEXT_LEN
+        )));
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{self, Milestone, Project, ProjectRepository};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn texts(items: &[ChecklistItem]) -> Vec<(i32, &str)> {
+        items.iter().map(|item| (item.position, item.text.as_str())).collect()
+    }
+
+    #[test]
+    fn test_checklist_crud_and_order() {
+        let conn = setup_test_db();
+        let projects = ProjectRepository::new(&conn);
+        let project = Project::new("Apollo".to_string());
+        projects.create(&project).unwrap();
+        let milestone = Milestone::new(project.id, 1, "Launch".to_string());
+        projects.add_milestone(&milestone).unwrap();
+
+        let repo = ChecklistRepository::new(&conn);
+        let design = repo.add_item(&milestone.id, "  Design   signed off ").unwrap();
+        let security = repo.add_item(&milestone.id, "Security review").unwrap();
+        let runbook = repo.add_item(&milestone.id, "Runbook written").unwrap();
+        assert_eq!(design.text, "Design signed off");
+        assert_eq!(runbook.position, 3);
+        assert!(matches!(repo.add_item(&milestone.id, " "), Err(Error::Invalid(_))));
+        assert!(matches!(repo.add_item(&Uuid::new_v4(), "x"), Err(Error::NotFound { .. })));
+
+        let checked = repo.toggle_item(&security.id, None).unwrap();
+        assert!(checked.checked && checked.checked_at.is_some());
+        assert!(repo.toggle_item(&security.id, Some(true)).unwrap().checked);
+        assert!(!repo.toggle_item(&design.id, Some(false)).unwrap().checked);
+
+        // Moving up and down shifts the items in between
+        repo.move_item(&runbook.id, 1).unwrap();
+        let items = repo.list_items(&milestone.id).unwrap();
+        assert_eq!(texts(&items), [(1, "Runbook written"), (2, "Design signed off"), (3, "Security review")]);
+        assert_eq!(repo.move_item(&runbook.id, 10).unwrap().position, 3);
+        assert!(repo.move_item(&runbook.id, 0).is_err());
+
+        // Positions stay dense after a removal
+        repo.remove_item(&design.id).unwrap();
+        let items = repo.list_items(&milestone.id).unwrap();
+        assert_eq!(texts(&items), [(1, "Security review"), (2, "Runbook written")]);
+        assert_eq!(repo.add_item(&milestone.id, "Launch party").unwrap().position, 3);
+        assert!(repo.remove_item(&design.id).is_err());
+
+        // Loaded milestones carry their checklist and how much of it is done
+        let milestones = projects.get_milestones(&project.id).unwrap();
+        assert_eq!(milestones[0].checklist.len(), 3);
+        assert_eq!(milestones[0].checklist_completion, Some(1.0 / 3.0));
+        let found = projects.find_milestone_by_id(&milestone.id).unwrap().unwrap();
+        assert_eq!(found.checklist, milestones[0].checklist);
+
+        // Deleting the milestone deletes its checklist
+        projects.delete_milestone(&milestone.id).unwrap();
+        assert!(repo.list_items(&milestone.id).unwrap().is_empty());
+    }
+}
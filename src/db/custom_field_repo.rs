@@ -0,0 +1,388 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+use super::error::{Error, Result};
+use super::{begin_or_join, get_datetime};
+use super::models::{CustomField, CustomFieldTarget, CustomFieldType};
+use super::project_repo::parse_column;
+use crate::utils::dt_to_db;
+use chrono::{NaiveDate, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Longest allowed custom field key
+pub const MAX_KEY_LEN: usize = 64;
+
+/// Columns selected for a custom field, in the order `custom_field_from_row` expects
+const CUSTOM_FIELD_COLUMNS: &str = "key, label, field_type, applies_to, created_at, updated_at";
+
+/// Map a row selected with `CUSTOM_FIELD_COLUMNS` to a custom field
+fn custom_field_from_row(row: &rusqlite::Row) -> rusqlite::Result<CustomField> {
+    Ok(CustomField {
+        key: row.get(0)?,
+        label: row.get(1)?,
+        field_type: parse_column(row, 2)?,
+        applies_to: parse_column(row, 3)?,
+        created_at: get_datetime(row, 4)?,
+        updated_at: get_datetime(row, 5)?,
+    })
+}
+
+/// Check that a key is lowercase letters, digits and underscores, starting with a letter
+fn validate_key(key: &str) -> Result<()> {
+    let valid = key.len() <= MAX_KEY_LEN
+        && key.starts_with(|c: char| c.is_ascii_lowercase())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !valid {
+        return Err(Error::Invalid(format!(
+            "Invalid custom field key '{}': use up to {} lowercase letters, digits and underscores, starting with a letter",
+            key, MAX_KEY_LEN
+        )));
+    }
+    Ok(())
+}
+
+/// Check a value against a field's type and return it in its stored form
+pub fn normalize_value(field: &CustomField, value: &str) -> Result<String> {
+    let value = value.trim();
+    let invalid = |expected: &str| {
+        Error::Invalid(format!(
+            "Invalid value '{}' for {}: expected {}",
+            value, field.key, expected
+        ))
+    };
+
+    match field.field_type {
+        CustomFieldType::Text => Ok(value.to_string()),
+        CustomFieldType::Number => match value.parse::<f64>() {
+            Ok(number) if number.is_finite() => Ok(number.to_string()),
+            _ => Err(invalid("a number")),
+        },
+        CustomFieldType::Date => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map(|date| date.format("%Y-%m-%d").to_string())
+            .map_err(|_| invalid("a date (YYYY-MM-DD)")),
+        CustomFieldType::Bool => match value.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "1" => Ok("true".to_string()),
+            "false" | "no" | "0" => Ok("false".to_string()),
+            _ => Err(invalid("true or false")),
+        },
+    }
+}
+
+/// Custom field repository for user-defined project fields and their values
+pub struct CustomFieldRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> CustomFieldRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Define a project field, or change the label of one that exists
+    ///
+    /// A field's type can only change while no project has a value for it.
+    pub fn define_custom_field(
+        &self,
+        key: &str,
+        label: &str,
+        field_type: CustomFieldType,
+    ) -> Result<CustomField> {
+        let key = key.trim();
+        validate_key(key)?;
+        let label = label.trim();
+        if label.is_empty() {
+            return Err(Error::Invalid(
+                "Custom field label cannot be empty".to_string(),
+            ));
+        }
+
+        let tx = begin_or_join(self.conn)?;
+        if let Some(existing) = self.find_custom_field(key)? {
+            if existing.field_type != field_type && self.value_count(key)? > 0 {
+                return Err(Error::Conflict(format!(
+                    "Custom field '{}' is a {} field with values; clear them before changing its type to {}",
+                    key, existing.field_type, field_type
+                )));
+            }
+        }
+
+        let now = dt_to_db(Utc::now());
+        self.conn
+            .prepare_cached(
+                "INSERT INTO custom_fields (key, label, field_type, applies_to, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+                 ON CONFLICT(key) DO UPDATE SET
+                     label = excluded.label,
+                     field_type = excluded.field_type,
+                     updated_at = excluded.updated_at",
+            )?
+            .execute(params![
+                key,
+                label,
+                field_type.as_str(),
+                CustomFieldTarget::Project.as_str(),
+                now
+            ])?;
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+        log::debug!("Defined custom field: {} ({})", key, field_type);
+
+        self.find_custom_field(key)?
+            .ok_or_else(|| Error::not_found("Custom field", key))
+    }
+
+    /// Find a custom field by key
+    pub fn find_custom_field(&self, key: &str) -> Result<Option<CustomField>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM custom_fields WHERE key = ?1",
+            CUSTOM_FIELD_COLUMNS
+        ))?;
+        let field = stmt
+            .query_row(params![key.trim()], custom_field_from_row)
+            .optional()?;
+        Ok(field)
+    }
+
+    /// List custom fields by key
+    pub fn list_custom_fields(&self) -> Result<Vec<CustomField>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM custom_fields ORDER BY key",
+            CUSTOM_FIELD_COLUMNS
+        ))?;
+
+        let fields = stmt
+            .query_map([], custom_field_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(fields)
+    }
+
+    /// Delete a custom field
+    ///
+    /// Refuses while projects have values for the field unless `force` is
+    /// set, in which case the values are deleted too. Returns the number of
+    /// values deleted.
+    pub fn delete_custom_field(&self, key: &str, force: bool) -> Result<usize> {
+        let key = key.trim();
+        let tx = begin_or_join(self.conn)?;
+        if self.find_custom_field(key)?.is_none() {
+            return Err(Error::not_found("Custom field", key));
+        }
+
+        let values = self.value_count(key)?;
+        if values > 0 && !force {
+            return Err(Error::Conflict(format!(
+                "Custom field '{}' has values on {} project(s); clear them first or force the delete",
+                key, values
+            )));
+        }
+
+        self.conn
+            .prepare_cached("DELETE FROM project_custom_values WHERE key = ?1")?
+            .execute(params![key])?;
+        self.conn
+            .prepare_cached("DELETE FROM custom_fields WHERE key = ?1")?
+            .execute(params![key])?;
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+        log::debug!("Deleted custom field {} and {} value(s)", key, values);
+
+        Ok(values)
+    }
+
+    /// Set a project's value for a custom field, or clear it with `None` or a blank value
+    ///
+    /// The value is checked against the field's type and returned in the
+    /// form it was stored in.
+    pub fn set_custom_value(
+        &self,
+        project_id: &Uuid,
+        key: &str,
+        value: Option<&str>,
+    ) -> Result<Option<String>> {
+        let field = self
+            .find_custom_field(key)?
+            .ok_or_else(|| Error::not_found("Custom field", key.trim()))?;
+
+        let project_exists: bool = self
+            .conn
+            .prepare_cached("SELECT EXISTS(SELECT 1 FROM projects WHERE id = ?1)")?
+            .query_row(params![project_id.to_string()], |row| row.get(0))?;
+        if !project_exists {
+            return Err(Error::not_found("Project", project_id));
+        }
+
+        let value = match value.map(str::trim).filter(|v| !v.is_empty()) {
+            Some(value) => Some(normalize_value(&field, value)?),
+            None => None,
+        };
+
+        match &value {
+            Some(value) => {
+                self.conn
+                    .prepare_cached(
+                        "INSERT INTO project_custom_values (project_id, key, value)
+                         VALUES (?1, ?2, ?3)
+                         ON CONFLICT(project_id, key) DO UPDATE SET value = excluded.value",
+                    )?
+                    .execute(params![project_id.to_string(), field.key, value])?;
+            }
+            None => {
+                self.conn
+                    .prepare_cached(
+                        "DELETE FROM project_custom_values WHERE project_id = ?1 AND key = ?2",
+                    )?
+                    .execute(params![project_id.to_string(), field.key])?;
+            }
+        }
+
+        Ok(value)
+    }
+
+    /// A project's custom field values by key
+    pub fn get_custom_values(&self, project_id: &Uuid) -> Result<BTreeMap<String, String>> {
+        let mut stmt = self
+            .conn
+            .prepare_cached("SELECT key, value FROM project_custom_values WHERE project_id = ?1")?;
+
+        let values = stmt
+            .query_map(params![project_id.to_string()], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
+
+        Ok(values)
+    }
+
+    /// Number of projects with a value for a field
+    fn value_count(&self, key: &str) -> Result<usize> {
+        let count: i64 = self
+            .conn
+            .prepare_cached("SELECT COUNT(*) FROM project_custom_values WHERE key = ?1")?
+            .query_row(params![key], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{schema, Project, ProjectRepository};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        schema::initialize_schema(&conn).unwrap();
+        schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_custom_fields() {
+        let conn = setup_test_db();
+        let repo = CustomFieldRepository::new(&conn);
+        let projects = ProjectRepository::new(&conn);
+        let project = Project::new("Apollo".to_string());
+        projects.create(&project).unwrap();
+
+        let field = repo
+            .define_custom_field("cost_center", " Cost Center ", CustomFieldType::Number)
+            .unwrap();
+        assert_eq!(field.label, "Cost Center");
+        assert_eq!(field.applies_to, CustomFieldTarget::Project);
+        repo.define_custom_field("launched", "Launched", CustomFieldType::Bool)
+            .unwrap();
+        repo.define_custom_field("okr_due", "OKR due", CustomFieldType::Date)
+            .unwrap();
+
+        assert_eq!(
+            repo.set_custom_value(&project.id, "cost_center", Some("1200.50"))
+                .unwrap(),
+            Some("1200.5".to_string())
+        );
+        repo.set_custom_value(&project.id, "launched", Some("Yes"))
+            .unwrap();
+        repo.set_custom_value(&project.id, "okr_due", Some("2025-06-30"))
+            .unwrap();
+
+        // Values are checked against the field's type
+        for (key, value) in [
+            ("cost_center", "lots"),
+            ("cost_center", "NaN"),
+            ("launched", "maybe"),
+            ("okr_due", "June 30"),
+            ("okr_due", "2025-02-30"),
+        ] {
+            assert!(
+                matches!(
+                    repo.set_custom_value(&project.id, key, Some(value)),
+                    Err(Error::Invalid(_))
+                ),
+                "{} = {}",
+                key,
+                value
+            );
+        }
+        assert!(matches!(
+            repo.set_custom_value(&project.id, "customer", Some("Acme")),
+            Err(Error::NotFound { .. })
+        ));
+        assert!(matches!(
+            repo.set_custom_value(&Uuid::new_v4(), "launched", Some("true")),
+            Err(Error::NotFound { .. })
+        ));
+
+        let found = projects.find_by_id(&project.id).unwrap().unwrap();
+        assert_eq!(
+            found.custom_fields,
+            BTreeMap::from([
+                ("cost_center".to_string(), "1200.5".to_string()),
+                ("launched".to_string(), "true".to_string()),
+                ("okr_due".to_string(), "2025-06-30".to_string()),
+            ])
+        );
+
+        // Clearing a value removes it
+        assert_eq!(
+            repo.set_custom_value(&project.id, "okr_due", Some("  "))
+                .unwrap(),
+            None
+        );
+        assert!(!repo
+            .get_custom_values(&project.id)
+            .unwrap()
+            .contains_key("okr_due"));
+
+        // A field's type is fixed while it has values
+        assert!(matches!(
+            repo.define_custom_field("cost_center", "Cost Center", CustomFieldType::Text),
+            Err(Error::Conflict(_))
+        ));
+        repo.define_custom_field("okr_due", "OKR deadline", CustomFieldType::Text)
+            .unwrap();
+        assert!(matches!(
+            repo.define_custom_field("Cost Center", "Cost Center", CustomFieldType::Text),
+            Err(Error::Invalid(_))
+        ));
+        assert_eq!(repo.list_custom_fields().unwrap().len(), 3);
+
+        // Deleting a field with values needs force
+        assert!(matches!(
+            repo.delete_custom_field("launched", false),
+            Err(Error::Conflict(_))
+        ));
+        assert_eq!(repo.delete_custom_field("launched", true).unwrap(), 1);
+        assert_eq!(repo.delete_custom_field("okr_due", false).unwrap(), 0);
+        assert!(matches!(
+            repo.delete_custom_field("okr_due", false),
+            Err(Error::NotFound { .. })
+        ));
+        assert_eq!(repo.get_custom_values(&project.id).unwrap().len(), 1);
+    }
+}
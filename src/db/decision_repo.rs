@@ -0,0 +1,423 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Decision records for projects
+//!
+//! A decision is proposed, then accepted, and stays in force until a later
+//! decision on the same project supersedes it. Superseding is the only way a
+//! decision becomes superseded, so `superseded_by` always names the decision
+//! that replaced it and [`DecisionRepository::get_chain`] can follow it.
+
+use super::error::{Error, Result};
+use super::models::{Decision, DecisionStatus};
+use super::project_repo::parse_column;
+use super::{begin_or_join, get_datetime, get_opt_datetime, get_opt_uuid, get_uuid};
+use crate::utils::dt_to_db;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use uuid::Uuid;
+
+/// Columns selected for a decision, in the order `decision_from_row` expects
+const DECISION_COLUMNS: &str =
+    "id, project_id, title, context, decision, decided_on, status, superseded_by, created_at, updated_at";
+
+/// Map a row selected with `DECISION_COLUMNS` to a decision, without its approvers
+fn decision_from_row(row: &rusqlite::Row) -> rusqlite::Result<Decision> {
+    Ok(Decision {
+        id: get_uuid(row, 0)?,
+        project_id: get_uuid(row, 1)?,
+        title: row.get(2)?,
+        context: row.get(3)?,
+        decision: row.get(4)?,
+        decided_on: get_opt_datetime(row, 5)?,
+        status: parse_column(row, 6)?,
+        superseded_by: get_opt_uuid(row, 7)?,
+        approvers: Vec::new(),
+        created_at: get_datetime(row, 8)?,
+        updated_at: get_datetime(row, 9)?,
+    })
+}
+
+/// A decision needs a title and says what was decided; it can only be
+/// superseded through [`DecisionRepository::supersede_decision`]
+fn validate_decision(decision: &Decision) -> Result<()> {
+    if decision.title.trim().is_empty() {
+        return Err(Error::Invalid("Decision title cannot be empty".to_string()));
+    }
+    if decision.decision.trim().is_empty() {
+        return Err(Error::Invalid("Decision text cannot be empty".to_string()));
+    }
+    if decision.status == DecisionStatus::Superseded || decision.superseded_by.is_some() {
+        return Err(Error::Invalid(
+            "Decisions are superseded with supersede_decision, not by setting their status".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Decision repository for database operations
+pub struct DecisionRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> DecisionRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Record a new decision with its approvers
+    pub fn create(&self, decision: &Decision) -> Result<()> {
+        validate_decision(decision)?;
+
+        let tx = begin_or_join(self.conn)?;
+        self.conn
+            .prepare_cached(
+                "INSERT INTO decisions
+                     (id, project_id, title, context, decision, decided_on, status, superseded_by, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, NULL, ?8, ?9)",
+            )?
+            .execute(params![
+                decision.id.to_string(),
+                decision.project_id.to_string(),
+                decision.title.trim(),
+                &decision.context,
+                &decision.decision,
+                decision.decided_on.map(dt_to_db),
+                decision.status.as_str(),
+                dt_to_db(decision.created_at),
+                dt_to_db(decision.updated_at),
+            ])
+            .map_err(|e| Error::from(e).with_field("project_id"))?;
+        self.insert_approvers(&decision.id, &decision.approvers)?;
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+        log::debug!("Created decision: {} ({})", decision.title, decision.id);
+        Ok(())
+    }
+
+    /// Find a decision by ID
+    pub fn find_by_id(&self, id: &Uuid) -> Result<Option<Decision>> {
+        let decision = self
+            .conn
+            .prepare_cached(&format!("SELECT {} FROM decisions WHERE id = ?1", DECISION_COLUMNS))?
+            .query_row(params![id.to_string()], decision_from_row)
+            .optional()?;
+        match decision {
+            Some(decision) => Ok(Some(self.with_approvers(decision)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// List a project's decisions, oldest first
+    ///
+    /// Decisions are ordered by when they were agreed, with proposals that
+    /// haven't been decided yet last.
+    pub fn list_decisions(&self, project_id: &Uuid) -> Result<Vec<Decision>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM decisions WHERE project_id = ?1
+             ORDER BY decided_on IS NULL, decided_on, created_at, id",
+            DECISION_COLUMNS
+        ))?;
+        let decisions = stmt
+            .query_map(params![project_id.to_string()], decision_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        decisions.into_iter().map(|d| self.with_approvers(d)).collect()
+    }
+
+    /// Decisions on a project that `email` approved, oldest first
+    pub fn approved_by(&self, project_id: &Uuid, email: &str) -> Result<Vec<Decision>> {
+        let mut decisions = self.list_decisions(project_id)?;
+        decisions.retain(|d| d.approvers.iter().any(|a| a.eq_ignore_ascii_case(email)));
+        Ok(decisions)
+    }
+
+    /// Update a decision and replace its approvers
+    ///
+    /// A superseded decision keeps its status and successor, so its text can
+    /// still be corrected.
+    pub fn update(&self, decision: &Decision) -> Result<()> {
+        let existing = self
+            .find_by_id(&decision.id)?
+            .ok_or_else(|| Error::not_found("Decision", decision.id))?;
+        let mut checked = decision.clone();
+        if existing.status == DecisionStatus::Superseded {
+            if decision.status != DecisionStatus::Superseded || decision.superseded_by != existing.superseded_by {
+                return Err(Error::Invalid(format!(
+                    "Decision {} has been superseded; its status can't be changed",
+                    decision.id
+                )));
+            }
+            checked.status = DecisionStatus::Accepted;
+            checked.superseded_by = None;
+        }
+        validate_decision(&checked)?;
+
+        let tx = begin_or_join(self.conn)?;
+        self.conn
+            .prepare_cached(
+                "UPDATE decisions SET title = ?1, context = ?2, decision = ?3, decided_on = ?4, status = ?5,
+                     updated_at = ?6
+                 WHERE id = ?7",
+            )?
+            .execute(params![
+                decision.title.trim(),
+                &decision.context,
+                &decision.decision,
+                decision.decided_on.map(dt_to_db),
+                decision.status.as_str(),
+                dt_to_db(decision.updated_at),
+                decision.id.to_string(),
+            ])?;
+        self.conn
+            .prepare_cached("DELETE FROM decision_approvers WHERE decision_id = ?1")?
+            .execute(params![decision.id.to_string()])?;
+        self.insert_approvers(&decision.id, &decision.approvers)?;
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+        log::debug!("Updated decision: {} ({})", decision.title, decision.id);
+        Ok(())
+    }
+
+    /// Delete a decision
+    ///
+    /// Decisions it superseded are back in force, so they are accepted again.
+    pub fn delete(&self, id: &Uuid) -> Result<()> {
+        let tx = begin_or_join(self.conn)?;
+        self.conn
+            .prepare_cached(
+                "UPDATE decisions SET status = 'accepted', superseded_by = NULL, updated_at = ?1
+                 WHERE superseded_by = ?2",
+            )?
+            .execute(params![dt_to_db(Utc::now()), id.to_string()])?;
+        let rows = self
+            .conn
+            .prepare_cached("DELETE FROM decisions WHERE id = ?1")?
+            .execute(params![id.to_string()])?;
+        if rows == 0 {
+            return Err(Error::not_found("Decision", id));
+        }
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+        log::debug!("Deleted decision {}", id);
+        Ok(())
+    }
+
+    /// Replace `old_id` with `new_id`
+    ///
+    /// Both must be on the same project and neither may already be
+    /// superseded. The old decision is marked superseded, and a proposed
+    /// replacement is accepted as of now. Returns the old decision as stored.
+    pub fn supersede_decision(&self, old_id: &Uuid, new_id: &Uuid) -> Result<Decision> {
+        if old_id == new_id {
+            return Err(Error::Invalid("A decision can't supersede itself".to_string()));
+        }
+
+        let tx = begin_or_join(self.conn)?;
+        let old = self
+            .find_by_id(old_id)?
+            .ok_or_else(|| Error::not_found("Decision", old_id))?;
+        let new = self
+            .find_by_id(new_id)?
+            .ok_or_else(|| Error::not_found("Decision", new_id))?;
+        if old.project_id != new.project_id {
+            return Err(Error::Invalid(format!(
+                "Decision {} is on a different project from {}",
+                new_id, old_id
+            )));
+        }
+        for decision in [&old, &new] {
+            if let Some(successor) = decision.superseded_by {
+                return Err(Error::Invalid(format!(
+                    "Decision {} has already been superseded by {}",
+                    decision.id, successor
+                )));
+            }
+        }
+
+        let now = Utc::now();
+        if new.status == DecisionStatus::Proposed {
+            self.conn
+                .prepare_cached(
+                    "UPDATE decisions SET status = 'accepted', decided_on = COALESCE(decided_on, ?1), updated_at = ?1
+                     WHERE id = ?2",
+                )?
+                .execute(params![dt_to_db(now), new_id.to_string()])?;
+        }
+        self.conn
+            .prepare_cached(
+                "UPDATE decisions SET status = 'superseded', superseded_by = ?1, updated_at = ?2 WHERE id = ?3",
+            )?
+            .execute(params![new_id.to_string(), dt_to_db(now), old_id.to_string()])?;
+        let old = self
+            .find_by_id(old_id)?
+            .ok_or_else(|| Error::not_found("Decision", old_id))?;
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+        log::info!("Decision {} superseded by {}", old_id, new_id);
+        Ok(old)
+    }
+
+    /// A decision followed by each decision that superseded it, ending with
+    /// the one in force
+    pub fn get_chain(&self, id: &Uuid) -> Result<Vec<Decision>> {
+        let mut chain: Vec<Decision> = Vec::new();
+        let mut next = Some(*id);
+        while let Some(id) = next {
+            // supersede_decision never supersedes a decision twice, but stop
+            // on a loop rather than follow it forever
+            if chain.iter().any(|d| d.id == id) {
+                break;
+            }
+            let decision = self.find_by_id(&id)?.ok_or_else(|| Error::not_found("Decision", id))?;
+            next = decision.superseded_by;
+            chain.push(decision);
+        }
+        Ok(chain)
+    }
+
+    fn insert_approvers(&self, decision_id: &Uuid, approvers: &[String]) -> Result<()> {
+        let now = dt_to_db(Utc::now());
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT OR IGNORE INTO decision_approvers (decision_id, approver_email, created_at)
+             VALUES (?1, ?2, ?3)",
+        )?;
+        for email in approvers.iter().map(|a| a.trim()).filter(|a| !a.is_empty()) {
+            stmt.execute(params![decision_id.to_string(), email, now])
+                .map_err(|e| Error::from(e).with_field("approvers"))?;
+        }
+        Ok(())
+    }
+
+    fn with_approvers(&self, mut decision: Decision) -> Result<Decision> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT approver_email FROM decision_approvers WHERE decision_id = ?1 ORDER BY approver_email",
+        )?;
+        decision.approvers = stmt
+            .query_map(params![decision.id.to_string()], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(decision)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{self, Person, PersonRepository, Project, ProjectRepository};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn add_person(conn: &Connection, email: &str, name: &str) {
+        PersonRepository::new(conn)
+            .create(&Person::new(email.to_string(), name.to_string()))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_supersede_chain() {
+        let conn = setup_test_db();
+        let project = Project::new("Apollo".to_string());
+        ProjectRepository::new(&conn).create(&project).unwrap();
+        add_person(&conn, "ada@example.com", "Ada");
+
+        let repo = DecisionRepository::new(&conn);
+        let mut first = Decision::new(project.id, "Gateway".to_string(), "Run our own gateway".to_string());
+        first.status = DecisionStatus::Accepted;
+        first.decided_on = Some(Utc::now());
+        first.approvers = vec!["ada@example.com".to_string()];
+        repo.create(&first).unwrap();
+        let second = Decision::new(project.id, "Gateway".to_string(), "Use the shared gateway".to_string());
+        repo.create(&second).unwrap();
+        let third = Decision::new(project.id, "Gateway".to_string(), "Use the vendor gateway".to_string());
+        repo.create(&third).unwrap();
+
+        // Only supersede_decision supersedes
+        let mut sneaky = Decision::new(project.id, "Gateway".to_string(), "Anything".to_string());
+        sneaky.status = DecisionStatus::Superseded;
+        assert!(matches!(repo.create(&sneaky), Err(Error::Invalid(_))));
+
+        let old = repo.supersede_decision(&first.id, &second.id).unwrap();
+        assert_eq!(old.status, DecisionStatus::Superseded);
+        assert_eq!(old.superseded_by, Some(second.id));
+        assert_eq!(old.approvers, ["ada@example.com"]);
+        let second_stored = repo.find_by_id(&second.id).unwrap().unwrap();
+        assert_eq!(second_stored.status, DecisionStatus::Accepted);
+        assert!(second_stored.decided_on.is_some());
+
+        // A superseded decision can't be superseded again or revived
+        assert!(matches!(repo.supersede_decision(&first.id, &third.id), Err(Error::Invalid(_))));
+        assert!(matches!(repo.supersede_decision(&second.id, &second.id), Err(Error::Invalid(_))));
+        let mut revived = old.clone();
+        revived.status = DecisionStatus::Accepted;
+        assert!(matches!(repo.update(&revived), Err(Error::Invalid(_))));
+
+        repo.supersede_decision(&second.id, &third.id).unwrap();
+        let chain = repo.get_chain(&first.id).unwrap();
+        assert_eq!(chain.iter().map(|d| d.id).collect::<Vec<_>>(), [first.id, second.id, third.id]);
+        assert_eq!(
+            chain.iter().map(|d| d.status).collect::<Vec<_>>(),
+            [DecisionStatus::Superseded, DecisionStatus::Superseded, DecisionStatus::Accepted]
+        );
+        assert_eq!(repo.approved_by(&project.id, "ADA@example.com").unwrap().len(), 1);
+
+        // Deleting the decision in force puts the one it replaced back
+        repo.delete(&third.id).unwrap();
+        let second_stored = repo.find_by_id(&second.id).unwrap().unwrap();
+        assert_eq!(second_stored.status, DecisionStatus::Accepted);
+        assert_eq!(second_stored.superseded_by, None);
+        assert_eq!(repo.get_chain(&first.id).unwrap().len(), 2);
+
+        // Decisions on other projects can't supersede each other
+        let other = Project::new("Gemini".to_string());
+        ProjectRepository::new(&conn).create(&other).unwrap();
+        let elsewhere = Decision::new(other.id, "Gateway".to_string(), "Something else".to_string());
+        repo.create(&elsewhere).unwrap();
+        assert!(matches!(repo.supersede_decision(&second.id, &elsewhere.id), Err(Error::Invalid(_))));
+    }
+
+    #[test]
+    fn test_project_delete_cascades_to_decisions() {
+        let conn = setup_test_db();
+        let project = Project::new("Apollo".to_string());
+        ProjectRepository::new(&conn).create(&project).unwrap();
+        add_person(&conn, "ada@example.com", "Ada");
+
+        let repo = DecisionRepository::new(&conn);
+        let mut decision = Decision::new(project.id, "Gateway".to_string(), "Use the shared gateway".to_string());
+        decision.approvers = vec!["ada@example.com".to_string(), "ada@example.com".to_string()];
+        repo.create(&decision).unwrap();
+        assert_eq!(repo.find_by_id(&decision.id).unwrap().unwrap().approvers, ["ada@example.com"]);
+
+        // Approvers must be people, and an approver can't be deleted
+        let mut unknown = Decision::new(project.id, "Other".to_string(), "Other".to_string());
+        unknown.approvers = vec!["nobody@example.com".to_string()];
+        assert!(matches!(
+            repo.create(&unknown),
+            Err(Error::ForeignKeyViolation { field }) if field == "approvers"
+        ));
+        assert!(repo.find_by_id(&unknown.id).unwrap().is_none());
+        assert!(PersonRepository::new(&conn).delete("ada@example.com", false).is_err());
+
+        // Snapshots keep the project's decisions
+        let bundle = ProjectRepository::new(&conn).get_project_bundle(&project.id).unwrap();
+        assert_eq!(bundle.decisions, [repo.find_by_id(&decision.id).unwrap().unwrap()]);
+
+        ProjectRepository::new(&conn).delete(&project.id).unwrap();
+        assert!(repo.find_by_id(&decision.id).unwrap().is_none());
+        let approvers: i64 = conn
+            .query_row("SELECT COUNT(*) FROM decision_approvers", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(approvers, 0);
+        PersonRepository::new(&conn).delete("ada@example.com", false).unwrap();
+    }
+}
@@ -0,0 +1,302 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! What deleting a record would take with it
+//!
+//! Each function looks up a record and counts the rows that are deleted
+//! along with it, so a caller can show what is about to go before it asks
+//! for confirmation.
+
+use super::error::{Error, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A record and the related rows deleting it would remove
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeletionImpact {
+    /// Kind of record, such as "project"
+    pub entity: String,
+
+    /// ID, email or name the record is identified by
+    pub id: String,
+
+    /// Name of the record
+    pub name: String,
+
+    /// Related rows that go with it, by kind, such as ("milestones", 3)
+    pub removes: Vec<(String, usize)>,
+}
+
+impl DeletionImpact {
+    fn new(entity: &str, id: String, name: String, counts: &[(&str, usize)]) -> Self {
+        Self {
+            entity: entity.to_string(),
+            id,
+            name,
+            removes: counts
+                .iter()
+                .map(|(kind, count)| (kind.to_string(), *count))
+                .collect(),
+        }
+    }
+
+    /// One-line description, such as
+    /// `Delete project "Apollo" (1234…) with 3 milestones and 5 notes`
+    pub fn summary(&self) -> String {
+        let parts: Vec<String> = self
+            .removes
+            .iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(kind, count)| format!("{} {}", count, kind))
+            .collect();
+        let mut summary = format!("Delete {} \"{}\" ({})", self.entity, self.name, self.id);
+        match parts.split_last() {
+            None => summary.push_str(" with nothing attached"),
+            Some((last, [])) => summary.push_str(&format!(" with {}", last)),
+            Some((last, rest)) => {
+                summary.push_str(&format!(" with {} and {}", rest.join(", "), last))
+            }
+        }
+        summary
+    }
+}
+
+/// What deleting a project removes: its milestones and everything attached to them
+pub fn project_impact(conn: &Connection, id: &Uuid) -> Result<DeletionImpact> {
+    conn.prepare_cached(
+        "SELECT name,
+                (SELECT COUNT(*) FROM milestones WHERE project_id = ?1),
+                (SELECT COUNT(*) FROM project_notes WHERE project_id = ?1)
+                  + (SELECT COUNT(*) FROM milestone_notes mn JOIN milestones m ON m.id = mn.milestone_id
+                     WHERE m.project_id = ?1)
+                  + (SELECT COUNT(*) FROM stakeholder_notes WHERE project_id = ?1),
+                (SELECT COUNT(*) FROM project_resources WHERE project_id = ?1)
+                  + (SELECT COUNT(*) FROM milestone_resources mr JOIN milestones m ON m.id = mr.milestone_id
+                     WHERE m.project_id = ?1),
+                (SELECT COUNT(*) FROM project_stakeholders WHERE project_id = ?1),
+                (SELECT COUNT(*) FROM project_documents WHERE project_id = ?1),
+                (SELECT COUNT(*) FROM project_dependencies
+                 WHERE project_id = ?1 OR depends_on_project_id = ?1),
+                (SELECT COUNT(*) FROM project_expenses WHERE project_id = ?1),
+                (SELECT COUNT(*) FROM project_snapshots WHERE project_id = ?1),
+                (SELECT COUNT(*) FROM retrospectives WHERE project_id = ?1),
+                (SELECT COUNT(*) FROM decisions WHERE project_id = ?1)
+         FROM projects WHERE id = ?1",
+    )?
+    .query_row(params![id.to_string()], |row| {
+        Ok(DeletionImpact::new(
+            "project",
+            id.to_string(),
+            row.get(0)?,
+            &[
+                ("milestones", row.get(1)?),
+                ("notes", row.get(2)?),
+                ("resource assignments", row.get(3)?),
+                ("stakeholders", row.get(4)?),
+                ("documents", row.get(5)?),
+                ("dependencies", row.get(6)?),
+                ("expenses", row.get(7)?),
+                ("snapshots", row.get(8)?),
+                ("retrospectives", row.get(9)?),
+                ("decisions", row.get(10)?),
+            ],
+        ))
+    })
+    .optional()?
+    .ok_or_else(|| Error::not_found("Project", id))
+}
+
+/// What deleting a milestone removes: its notes, resources, checklist and,
+/// when `with_occurrences` is set, the occurrences of its recurring series
+pub fn milestone_impact(
+    conn: &Connection,
+    id: &Uuid,
+    with_occurrences: bool,
+) -> Result<DeletionImpact> {
+    conn.prepare_cached(
+        "SELECT m.name, p.name,
+                (SELECT COUNT(*) FROM milestone_notes WHERE milestone_id = ?1),
+                (SELECT COUNT(*) FROM milestone_resources WHERE milestone_id = ?1),
+                (SELECT COUNT(*) FROM milestones WHERE series_id = ?1 AND ?2),
+                (SELECT COUNT(*) FROM milestone_checklist_items WHERE milestone_id = ?1)
+         FROM milestones m JOIN projects p ON p.id = m.project_id
+         WHERE m.id = ?1",
+    )?
+    .query_row(params![id.to_string(), with_occurrences], |row| {
+        Ok(DeletionImpact::new(
+            "milestone",
+            id.to_string(),
+            format!("{}: {}", row.get::<_, String>(1)?, row.get::<_, String>(0)?),
+            &[
+                ("notes", row.get(2)?),
+                ("resource assignments", row.get(3)?),
+                ("series occurrences", row.get(4)?),
+                ("checklist items", row.get(5)?),
+            ],
+        ))
+    })
+    .optional()?
+    .ok_or_else(|| Error::not_found("Milestone", id))
+}
+
+/// What deleting a person removes: their team memberships, assignments,
+/// stakeholder roles and notes, including their stakeholder notes, and the
+/// records of their 1:1s when those are deleted too
+pub fn person_impact(conn: &Connection, email: &str) -> Result<DeletionImpact> {
+    conn.prepare_cached(
+        "SELECT name,
+                (SELECT COUNT(*) FROM team_members WHERE person_email = ?1),
+                (SELECT COUNT(*) FROM project_resources WHERE person_email = ?1),
+                (SELECT COUNT(*) FROM milestone_resources WHERE person_email = ?1),
+                (SELECT COUNT(*) FROM project_stakeholders WHERE stakeholder_email = ?1),
+                (SELECT COUNT(*) FROM person_notes WHERE person_email = ?1)
+                  + (SELECT COUNT(*) FROM stakeholder_notes WHERE stakeholder_email = ?1),
+                (SELECT COUNT(*) FROM one_on_ones WHERE person_email = ?1)
+         FROM people WHERE email = ?1",
+    )?
+    .query_row(params![email], |row| {
+        Ok(DeletionImpact::new(
+            "person",
+            email.to_string(),
+            row.get(0)?,
+            &[
+                ("team memberships", row.get(1)?),
+                ("project assignments", row.get(2)?),
+                ("milestone assignments", row.get(3)?),
+                ("stakeholder roles", row.get(4)?),
+                ("notes", row.get(5)?),
+                ("1:1s", row.get(6)?),
+            ],
+        ))
+    })
+    .optional()?
+    .ok_or_else(|| Error::not_found("Person", email))
+}
+
+/// What deleting a team removes: its memberships, and the parent link of
+/// any sub-teams that are detached
+pub fn team_impact(conn: &Connection, name: &str) -> Result<DeletionImpact> {
+    conn.prepare_cached(
+        "SELECT name,
+                (SELECT COUNT(*) FROM team_members WHERE team_name = ?1),
+                (SELECT COUNT(*) FROM teams WHERE parent_team = ?1)
+         FROM teams WHERE name = ?1",
+    )?
+    .query_row(params![name], |row| {
+        Ok(DeletionImpact::new(
+            "team",
+            name.to_string(),
+            row.get(0)?,
+            &[("memberships", row.get(1)?), ("sub-teams", row.get(2)?)],
+        ))
+    })
+    .optional()?
+    .ok_or_else(|| Error::not_found("Team", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{
+        self, Milestone, MilestoneNote, MilestoneResource, Person, PersonRepository, Project,
+        ProjectNote, ProjectRepository, ProjectResource, Team, TeamRepository,
+    };
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_deletion_impact() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        PersonRepository::new(&conn)
+            .create(&Person::new(
+                "alice@example.com".to_string(),
+                "Alice".to_string(),
+            ))
+            .unwrap();
+        let teams = TeamRepository::new(&conn);
+        teams.create(&Team::new("Platform".to_string())).unwrap();
+        teams.add_member("Platform", "alice@example.com").unwrap();
+
+        let project = Project::new("Apollo".to_string());
+        repo.create(&project).unwrap();
+        let milestone = Milestone::new(project.id, 1, "Launch".to_string());
+        repo.add_milestone(&milestone).unwrap();
+        repo.add_project_note(&ProjectNote::new(
+            project.id,
+            "Status".to_string(),
+            "Fine".to_string(),
+        ))
+        .unwrap();
+        repo.add_milestone_note(&MilestoneNote::new(
+            milestone.id,
+            "Risk".to_string(),
+            "Late".to_string(),
+        ))
+        .unwrap();
+        repo.add_milestone_resource(
+            &milestone.id,
+            &MilestoneResource::new(milestone.id, "alice@example.com".to_string()),
+        )
+        .unwrap();
+        repo.add_project_resource(
+            &project.id,
+            &ProjectResource::new(project.id, "alice@example.com".to_string()),
+        )
+        .unwrap();
+
+        let impact = project_impact(&conn, &project.id).unwrap();
+        assert_eq!(impact.name, "Apollo");
+        assert_eq!(
+            impact.summary(),
+            format!(
+                "Delete project \"Apollo\" ({}) with 1 milestones, 2 notes and 2 resource assignments",
+                project.id
+            )
+        );
+
+        let impact = milestone_impact(&conn, &milestone.id, true).unwrap();
+        assert_eq!(impact.name, "Apollo: Launch");
+        assert_eq!(impact.removes[0], ("notes".to_string(), 1));
+
+        let impact = person_impact(&conn, "alice@example.com").unwrap();
+        assert_eq!(
+            impact.summary(),
+            "Delete person \"Alice\" (alice@example.com) with 1 team memberships, 1 project assignments and 1 milestone assignments"
+        );
+
+        let impact = team_impact(&conn, "Platform").unwrap();
+        assert_eq!(
+            impact.summary(),
+            "Delete team \"Platform\" (Platform) with 1 memberships"
+        );
+
+        teams.create(&Team::new("Empty".to_string())).unwrap();
+        assert_eq!(
+            team_impact(&conn, "Empty").unwrap().summary(),
+            "Delete team \"Empty\" (Empty) with nothing attached"
+        );
+
+        assert!(matches!(
+            project_impact(&conn, &Uuid::new_v4()).unwrap_err(),
+            Error::NotFound {
+                entity: "Project",
+                ..
+            }
+        ));
+        assert!(matches!(
+            person_impact(&conn, "nobody@example.com").unwrap_err(),
+            Error::NotFound {
+                entity: "Person",
+                ..
+            }
+        ));
+    }
+}
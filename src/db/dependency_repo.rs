@@ -0,0 +1,384 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+use super::error::{Error, Result};
+use super::{get_datetime, get_uuid};
+use super::models::{DependencyGraph, DependencyNode, Project, ProjectDependency};
+use super::project_repo::{project_from_row, PROJECT_COLUMNS_P};
+use crate::utils::dt_to_db;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::{HashMap, HashSet, VecDeque};
+use uuid::Uuid;
+
+/// Columns selected for a dependency, in the order `dependency_from_row` expects
+const DEPENDENCY_COLUMNS: &str = "project_id, depends_on_project_id, note, created_at";
+
+/// Map a row selected with `DEPENDENCY_COLUMNS` to a dependency
+fn dependency_from_row(row: &rusqlite::Row) -> rusqlite::Result<ProjectDependency> {
+    Ok(ProjectDependency {
+        project_id: get_uuid(row, 0)?,
+        depends_on_project_id: get_uuid(row, 1)?,
+        note: row.get(2)?,
+        created_at: get_datetime(row, 3)?,
+    })
+}
+
+/// Project dependency repository for database operations
+pub struct DependencyRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> DependencyRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Record that one project depends on another
+    ///
+    /// Fails with `CycleDetected` if the other project already depends on
+    /// this one, directly or through other projects.
+    pub fn add(&self, dependency: &ProjectDependency) -> Result<()> {
+        let names = [dependency.project_id, dependency.depends_on_project_id]
+            .iter()
+            .map(|id| {
+                self.project_name(id)?
+                    .ok_or_else(|| Error::not_found("Project", id))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if let Some(path) = self.path(&dependency.depends_on_project_id, &dependency.project_id)? {
+            let mut chain = vec![names[0].clone()];
+            for id in &path {
+                chain.push(self.project_name(id)?.unwrap_or_else(|| id.to_string()));
+            }
+            return Err(Error::CycleDetected {
+                relation: "project dependency",
+                chain,
+            });
+        }
+
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT INTO project_dependencies (project_id, depends_on_project_id, note, created_at)
+             VALUES (?1, ?2, ?3, ?4)",
+        )?;
+        stmt.execute(params![
+            dependency.project_id.to_string(),
+            dependency.depends_on_project_id.to_string(),
+            &dependency.note,
+            dt_to_db(dependency.created_at),
+        ])
+        .map_err(|e| {
+            Error::from(e).with_conflict(format!("{} already depends on {}", names[0], names[1]))
+        })?;
+        log::debug!("Added dependency: {} -> {}", names[0], names[1]);
+        Ok(())
+    }
+
+    /// Remove a dependency
+    pub fn remove(&self, project_id: &Uuid, depends_on_project_id: &Uuid) -> Result<()> {
+        let rows = self.conn.execute(
+            "DELETE FROM project_dependencies WHERE project_id = ?1 AND depends_on_project_id = ?2",
+            params![project_id.to_string(), depends_on_project_id.to_string()],
+        )?;
+        if rows == 0 {
+            return Err(Error::not_found(
+                "Project dependency",
+                format!("{} -> {}", project_id, depends_on_project_id),
+            ));
+        }
+        Ok(())
+    }
+
+    /// List the projects a project depends on, oldest dependency first
+    pub fn list_dependencies(&self, project_id: &Uuid) -> Result<Vec<ProjectDependency>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM project_dependencies WHERE project_id = ?1 ORDER BY created_at",
+            DEPENDENCY_COLUMNS
+        ))?;
+        let dependencies = stmt
+            .query_map(params![project_id.to_string()], dependency_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(dependencies)
+    }
+
+    /// List the projects that depend directly on a project, oldest dependency first
+    pub fn list_dependents(&self, project_id: &Uuid) -> Result<Vec<ProjectDependency>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM project_dependencies WHERE depends_on_project_id = ?1 ORDER BY created_at",
+            DEPENDENCY_COLUMNS
+        ))?;
+        let dependencies = stmt
+            .query_map(params![project_id.to_string()], dependency_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(dependencies)
+    }
+
+    /// Find every project waiting on a project, directly or through other
+    /// projects, ordered by name
+    pub fn find_projects_blocked_by(&self, project_id: &Uuid) -> Result<Vec<Project>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "WITH RECURSIVE downstream(id) AS (
+                 SELECT project_id FROM project_dependencies WHERE depends_on_project_id = ?1
+                 UNION
+                 SELECT d.project_id FROM project_dependencies d
+                 INNER JOIN downstream ON d.depends_on_project_id = downstream.id
+             )
+             SELECT {}
+             FROM projects p
+             INNER JOIN downstream ON downstream.id = p.id
+             WHERE p.id <> ?1
+             ORDER BY p.name",
+            PROJECT_COLUMNS_P
+        ))?;
+        let projects = stmt
+            .query_map(params![project_id.to_string()], project_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(projects)
+    }
+
+    /// Get every dependency in the portfolio, with the projects they connect
+    pub fn get_dependency_graph(&self) -> Result<DependencyGraph> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM project_dependencies ORDER BY created_at",
+            DEPENDENCY_COLUMNS
+        ))?;
+        let edges = stmt
+            .query_map([], dependency_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, name, blocked FROM projects
+             WHERE id IN (SELECT project_id FROM project_dependencies)
+                OR id IN (SELECT depends_on_project_id FROM project_dependencies)
+             ORDER BY name",
+        )?;
+        let projects = stmt
+            .query_map([], |row| {
+                Ok((
+                    get_uuid(row, 0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, bool>(2)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let blocked: HashSet<Uuid> = projects
+            .iter()
+            .filter(|(_, _, blocked)| *blocked)
+            .map(|(id, _, _)| *id)
+            .collect();
+        let mut upstream: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        for edge in &edges {
+            upstream
+                .entry(edge.project_id)
+                .or_default()
+                .push(edge.depends_on_project_id);
+        }
+
+        let nodes = projects
+            .into_iter()
+            .map(|(id, name, is_blocked)| DependencyNode {
+                upstream_blocked: reachable(&upstream, &id)
+                    .iter()
+                    .any(|other| blocked.contains(other)),
+                project_id: id,
+                project_name: name,
+                blocked: is_blocked,
+            })
+            .collect();
+
+        Ok(DependencyGraph { nodes, edges })
+    }
+
+    fn project_name(&self, id: &Uuid) -> Result<Option<String>> {
+        let name = self
+            .conn
+            .prepare_cached("SELECT name FROM projects WHERE id = ?1")?
+            .query_row(params![id.to_string()], |row| row.get(0))
+            .optional()?;
+        Ok(name)
+    }
+
+    /// The shortest chain of dependencies leading from `from` to `to`,
+    /// starting with `from` and ending with `to`
+    fn path(&self, from: &Uuid, to: &Uuid) -> Result<Option<Vec<Uuid>>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT depends_on_project_id FROM project_dependencies WHERE project_id = ?1",
+        )?;
+        let mut previous: HashMap<Uuid, Uuid> = HashMap::new();
+        let mut queue = VecDeque::from([*from]);
+        while let Some(current) = queue.pop_front() {
+            if current == *to {
+                let mut path = vec![current];
+                while let Some(prev) = previous.get(path.last().unwrap()) {
+                    path.push(*prev);
+                }
+                path.reverse();
+                return Ok(Some(path));
+            }
+            let next = stmt
+                .query_map(params![current.to_string()], |row| get_uuid(row, 0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            for next in next {
+                if next != *from && !previous.contains_key(&next) {
+                    previous.insert(next, current);
+                    queue.push_back(next);
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Every project `id` depends on, directly or further up
+fn reachable(upstream: &HashMap<Uuid, Vec<Uuid>>, id: &Uuid) -> HashSet<Uuid> {
+    let mut seen = HashSet::new();
+    let mut stack = vec![*id];
+    while let Some(current) = stack.pop() {
+        for next in upstream.get(&current).into_iter().flatten() {
+            if seen.insert(*next) {
+                stack.push(*next);
+            }
+        }
+    }
+    seen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{self, ProjectRepository};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn create_project(conn: &Connection, name: &str) -> Project {
+        let project = Project::new(name.to_string());
+        ProjectRepository::new(conn).create(&project).unwrap();
+        project
+    }
+
+    fn names(projects: &[Project]) -> Vec<&str> {
+        projects.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_diamond_dependency() {
+        let conn = setup_test_db();
+        let repo = DependencyRepository::new(&conn);
+
+        // Checkout and Search both wait on Platform; Launch waits on both
+        let platform = create_project(&conn, "Platform");
+        let checkout = create_project(&conn, "Checkout");
+        let search = create_project(&conn, "Search");
+        let launch = create_project(&conn, "Launch");
+        let unrelated = create_project(&conn, "Unrelated");
+        let mut dependency = ProjectDependency::new(checkout.id, platform.id);
+        dependency.note = Some("Needs the new API".to_string());
+        repo.add(&dependency).unwrap();
+        repo.add(&ProjectDependency::new(search.id, platform.id))
+            .unwrap();
+        repo.add(&ProjectDependency::new(launch.id, checkout.id))
+            .unwrap();
+        repo.add(&ProjectDependency::new(launch.id, search.id))
+            .unwrap();
+
+        assert_eq!(
+            names(&repo.find_projects_blocked_by(&platform.id).unwrap()),
+            vec!["Checkout", "Launch", "Search"]
+        );
+        assert_eq!(
+            names(&repo.find_projects_blocked_by(&checkout.id).unwrap()),
+            vec!["Launch"]
+        );
+        assert!(repo
+            .find_projects_blocked_by(&launch.id)
+            .unwrap()
+            .is_empty());
+
+        let dependencies = repo.list_dependencies(&checkout.id).unwrap();
+        assert_eq!(dependencies, vec![dependency]);
+        assert_eq!(repo.list_dependents(&platform.id).unwrap().len(), 2);
+
+        // Adding the same dependency again is a conflict
+        assert!(matches!(
+            repo.add(&ProjectDependency::new(launch.id, search.id))
+                .unwrap_err(),
+            Error::Conflict(_)
+        ));
+
+        // A blocked project marks everything downstream of it
+        ProjectRepository::new(&conn)
+            .set_blocked(&platform.id, "Waiting on hardware")
+            .unwrap();
+        let graph = repo.get_dependency_graph().unwrap();
+        assert_eq!(graph.edges.len(), 4);
+        let nodes: Vec<(&str, bool, bool)> = graph
+            .nodes
+            .iter()
+            .map(|n| (n.project_name.as_str(), n.blocked, n.upstream_blocked))
+            .collect();
+        assert_eq!(
+            nodes,
+            vec![
+                ("Checkout", false, true),
+                ("Launch", false, true),
+                ("Platform", true, false),
+                ("Search", false, true),
+            ]
+        );
+        assert!(!graph.nodes.iter().any(|n| n.project_id == unrelated.id));
+
+        repo.remove(&launch.id, &search.id).unwrap();
+        assert!(matches!(
+            repo.remove(&launch.id, &search.id).unwrap_err(),
+            Error::NotFound { .. }
+        ));
+        ProjectRepository::new(&conn).delete(&checkout.id).unwrap();
+        assert_eq!(repo.get_dependency_graph().unwrap().edges.len(), 1);
+    }
+
+    #[test]
+    fn test_dependency_cycle() {
+        let conn = setup_test_db();
+        let repo = DependencyRepository::new(&conn);
+
+        let platform = create_project(&conn, "Platform");
+        let checkout = create_project(&conn, "Checkout");
+        let launch = create_project(&conn, "Launch");
+        repo.add(&ProjectDependency::new(checkout.id, platform.id))
+            .unwrap();
+        repo.add(&ProjectDependency::new(launch.id, checkout.id))
+            .unwrap();
+
+        let err = repo
+            .add(&ProjectDependency::new(platform.id, launch.id))
+            .unwrap_err();
+        match err {
+            Error::CycleDetected { relation, chain } => {
+                assert_eq!(relation, "project dependency");
+                assert_eq!(chain, vec!["Platform", "Launch", "Checkout", "Platform"]);
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+        assert!(matches!(
+            repo.add(&ProjectDependency::new(platform.id, platform.id))
+                .unwrap_err(),
+            Error::CycleDetected { .. }
+        ));
+        assert!(repo.list_dependencies(&platform.id).unwrap().is_empty());
+
+        assert!(matches!(
+            repo.add(&ProjectDependency::new(platform.id, Uuid::new_v4()))
+                .unwrap_err(),
+            Error::NotFound {
+                entity: "Project",
+                ..
+            }
+        ));
+    }
+}
@@ -0,0 +1,272 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Error type returned by the repositories
+
+use super::models::{PersonMatch, UserRole};
+use rusqlite::ffi;
+
+/// Errors returned by database operations
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The requested record does not exist
+    #[error("{entity} not found: {id}")]
+    NotFound { entity: &'static str, id: String },
+
+    /// The record conflicts with one that already exists
+    #[error("{0}")]
+    Conflict(String),
+
+    /// A record with the same name already exists
+    #[error("{entity} named '{name}' already exists (ID {id})")]
+    AlreadyExists {
+        entity: &'static str,
+        name: String,
+        id: String,
+    },
+
+    /// An ID prefix matches more than one record
+    #[error("{entity} ID prefix '{prefix}' is ambiguous, it matches {matches} records")]
+    AmbiguousId {
+        entity: &'static str,
+        prefix: String,
+        matches: usize,
+    },
+
+    /// The record was changed by someone else since the caller read it
+    ///
+    /// `current` holds the record as it is now, so the caller can merge
+    /// their changes into it and retry.
+    #[error("{entity} {id} was changed by someone else (expected version {expected}, current version {actual}); re-fetch it and try again")]
+    VersionConflict {
+        entity: &'static str,
+        id: String,
+        expected: i64,
+        actual: i64,
+        current: serde_json::Value,
+    },
+
+    /// A field has a value that isn't allowed
+    #[error("{0}")]
+    Invalid(String),
+
+    /// A field refers to a record that does not exist
+    #[error("Invalid {field}: the referenced record does not exist")]
+    ForeignKeyViolation { field: String },
+
+    /// A manager or parent team assignment would make a record its own ancestor
+    ///
+    /// `relation` names the link, such as `manager`. `chain` starts with the
+    /// record being changed and follows that link upward until it comes back.
+    #[error("Circular {relation} reference: {}", .chain.join(" -> "))]
+    CycleDetected { relation: &'static str, chain: Vec<String> },
+
+    /// A new person's name closely matches people who already exist
+    ///
+    /// Returned instead of creating the person when the duplicate check is
+    /// on; `matches` lists the existing people, most similar first.
+    #[error("{name} looks like {}; create them anyway if this is a different person", describe_matches(.matches))]
+    PossibleDuplicate { name: String, matches: Vec<PersonMatch> },
+
+    /// A person's email isn't in one of the allowed email domains
+    ///
+    /// `allowed` lists the configured domains. People marked external are
+    /// exempt.
+    #[error("{email} is not in an allowed email domain ({}); mark the person as external if they are a sanctioned exception", .allowed.join(", "))]
+    DomainNotAllowed { email: String, allowed: Vec<String> },
+
+    /// The current user's role doesn't allow an operation
+    ///
+    /// `operation` names the command or tool that was refused and `user` is
+    /// the current_user_email, when one is set.
+    #[error("Permission denied: {operation} needs the {required} role, but {} has the {role} role", .user.as_deref().unwrap_or("the current user"))]
+    PermissionDenied {
+        operation: String,
+        user: Option<String>,
+        role: UserRole,
+        required: UserRole,
+    },
+
+    /// The database is locked by another connection
+    #[error("The database is busy, please try again")]
+    Busy,
+
+    /// Any other error
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+/// A column that should hold a UUID holds something else
+///
+/// Row mappers report this inside a `rusqlite::Error::FromSqlConversionFailure`
+/// when a record's ID has been corrupted, such as by editing the database by
+/// hand. [`super::maintenance::find_invalid_uuids`] lists every such row.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid UUID '{value}' in column {column}")]
+pub struct InvalidUuid {
+    pub column: String,
+    pub value: String,
+}
+
+/// Result type returned by database operations
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+    /// Create a `NotFound` error for the given entity and identifier
+    pub fn not_found(entity: &'static str, id: impl ToString) -> Self {
+        Error::NotFound {
+            entity,
+            id: id.to_string(),
+        }
+    }
+
+    /// Create a `VersionConflict` error carrying the current state of the record
+    pub(crate) fn version_conflict<T: serde::Serialize>(
+        entity: &'static str,
+        id: impl ToString,
+        expected: i64,
+        actual: i64,
+        current: &T,
+    ) -> Self {
+        Error::VersionConflict {
+            entity,
+            id: id.to_string(),
+            expected,
+            actual,
+            current: serde_json::to_value(current).unwrap_or(serde_json::Value::Null),
+        }
+    }
+
+    /// Name the field behind a foreign key violation
+    ///
+    /// SQLite doesn't report which foreign key failed, so repositories use
+    /// this to describe it. Other errors are returned unchanged.
+    pub(crate) fn with_field(self, field: &str) -> Self {
+        match self {
+            Error::ForeignKeyViolation { .. } => Error::ForeignKeyViolation {
+                field: field.to_string(),
+            },
+            other => other,
+        }
+    }
+
+    /// Replace SQLite's description of a conflict with a friendlier message
+    ///
+    /// Other errors are returned unchanged.
+    pub(crate) fn with_conflict(self, message: impl Into<String>) -> Self {
+        match self {
+            Error::Conflict(_) => Error::Conflict(message.into()),
+            other => other,
+        }
+    }
+}
+
+impl From<rusqlite::Error> for Error {
+    fn from(e: rusqlite::Error) -> Self {
+        if let rusqlite::Error::SqliteFailure(failure, message) = &e {
+            match failure.extended_code {
+                ffi::SQLITE_CONSTRAINT_PRIMARYKEY | ffi::SQLITE_CONSTRAINT_UNIQUE => {
+                    return Error::Conflict(
+                        message.clone().unwrap_or_else(|| "Record already exists".to_string()),
+                    );
+                }
+                ffi::SQLITE_CONSTRAINT_FOREIGNKEY => {
+                    return Error::ForeignKeyViolation {
+                        field: "reference".to_string(),
+                    };
+                }
+                _ => {}
+            }
+            if matches!(
+                failure.code,
+                rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked
+            ) {
+                return Error::Busy;
+            }
+        }
+        Error::Other(e.into())
+    }
+}
+
+impl From<anyhow::Error> for Error {
+    fn from(e: anyhow::Error) -> Self {
+        // Recover errors that were converted to anyhow on the way up
+        match e.downcast::<Error>() {
+            Ok(e) => e,
+            Err(e) => match e.downcast::<rusqlite::Error>() {
+                Ok(e) => e.into(),
+                Err(e) => Error::Other(e),
+            },
+        }
+    }
+}
+
+/// List possible duplicates as `Name <email>, ...` for an error message
+fn describe_matches(matches: &[PersonMatch]) -> String {
+    matches
+        .iter()
+        .map(|m| format!("{} <{}>", m.name, m.email))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "PRAGMA foreign_keys = ON;
+             CREATE TABLE parent (id INTEGER PRIMARY KEY);
+             CREATE TABLE child (id INTEGER PRIMARY KEY, parent_id INTEGER REFERENCES parent(id));
+             INSERT INTO parent (id) VALUES (1);",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_primary_key_violation_is_conflict() {
+        let conn = setup_test_db();
+        let err: Error = conn
+            .execute("INSERT INTO parent (id) VALUES (1)", [])
+            .unwrap_err()
+            .into();
+        assert!(matches!(err, Error::Conflict(_)));
+    }
+
+    #[test]
+    fn test_foreign_key_violation() {
+        let conn = setup_test_db();
+        let err: Error = conn
+            .execute("INSERT INTO child (id, parent_id) VALUES (1, 42)", [])
+            .unwrap_err()
+            .into();
+        let err = err.with_field("parent_id");
+        assert!(matches!(err, Error::ForeignKeyViolation { ref field } if field == "parent_id"));
+    }
+
+    #[test]
+    fn test_other_errors() {
+        let conn = setup_test_db();
+        let err: Error = conn
+            .execute("INSERT INTO missing_table (id) VALUES (1)", [])
+            .unwrap_err()
+            .into();
+        assert!(matches!(err, Error::Other(_)));
+
+        // Helpers leave unrelated variants alone
+        let err = Error::not_found("Project", 1).with_field("manager").with_conflict("exists");
+        assert!(matches!(err, Error::NotFound { entity: "Project", .. }));
+    }
+
+    #[test]
+    fn test_anyhow_round_trip() {
+        let original = Error::not_found("Person", "alice@example.com");
+        let err: Error = anyhow::Error::from(original).into();
+        assert!(matches!(err, Error::NotFound { entity: "Person", .. }));
+    }
+}
@@ -0,0 +1,437 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Tickets in other trackers linked to projects and milestones
+//!
+//! References live in the `external_refs` table. Projects and milestones
+//! still have their older `jira_initiative` and `jira_epic` fields, so while
+//! those are in use every read merges them in as references with the system
+//! `jira` and no ID. New references, Jira ones included, are only written to
+//! the table.
+
+use super::error::{Error, Result};
+use super::models::{ExternalRef, ExternalRefTarget};
+use super::project_repo::parse_column;
+use super::{get_datetime, get_opt_uuid, get_uuid};
+use crate::utils::{dt_to_db, is_http_url};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+/// System of the references read from `jira_initiative` and `jira_epic`
+pub const JIRA_SYSTEM: &str = "jira";
+
+/// Longest allowed system name
+pub const MAX_SYSTEM_LEN: usize = 32;
+
+/// Columns selected for a reference, in the order `external_ref_from_row` expects
+const EXTERNAL_REF_COLUMNS: &str = "id, entity_type, entity_id, system, key, url, created_at";
+
+/// Map a row selected with `EXTERNAL_REF_COLUMNS` to a reference
+fn external_ref_from_row(row: &rusqlite::Row) -> rusqlite::Result<ExternalRef> {
+    Ok(ExternalRef {
+        id: get_opt_uuid(row, 0)?,
+        entity_type: parse_column(row, 1)?,
+        entity_id: get_uuid(row, 2)?,
+        system: row.get(3)?,
+        key: row.get(4)?,
+        url: row.get(5)?,
+        created_at: get_datetime(row, 6)?,
+    })
+}
+
+/// Lowercase a system name and check that it is letters, digits, `-` and `_`
+pub fn normalize_system(system: &str) -> Result<String> {
+    let system = system.trim().to_ascii_lowercase();
+    let valid = !system.is_empty()
+        && system.len() <= MAX_SYSTEM_LEN
+        && system
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if !valid {
+        return Err(Error::Invalid(format!(
+            "Invalid system '{}': use up to {} letters, digits, '-' and '_', such as github or linear",
+            system, MAX_SYSTEM_LEN
+        )));
+    }
+    Ok(system)
+}
+
+/// Add the reference a legacy Jira field holds, unless the table already has it
+///
+/// `created_at` is the record's creation time, since the field doesn't
+/// record when it was set.
+pub(super) fn merge_legacy_jira(
+    mut refs: Vec<ExternalRef>,
+    entity_type: ExternalRefTarget,
+    entity_id: Uuid,
+    legacy: Option<&str>,
+    created_at: DateTime<Utc>,
+) -> Vec<ExternalRef> {
+    let Some(key) = legacy.map(str::trim).filter(|key| !key.is_empty()) else { return refs };
+    let stored = refs
+        .iter()
+        .any(|r| r.system == JIRA_SYSTEM && r.key.eq_ignore_ascii_case(key));
+    if !stored {
+        refs.insert(
+            0,
+            ExternalRef {
+                id: None,
+                entity_type,
+                entity_id,
+                system: JIRA_SYSTEM.to_string(),
+                key: key.to_string(),
+                url: None,
+                created_at,
+            },
+        );
+    }
+    refs
+}
+
+/// The table and legacy Jira column of a kind of record
+fn legacy_column(entity_type: ExternalRefTarget) -> (&'static str, &'static str) {
+    match entity_type {
+        ExternalRefTarget::Project => ("projects", "jira_initiative"),
+        ExternalRefTarget::Milestone => ("milestones", "jira_epic"),
+    }
+}
+
+/// Repository for references to tickets in other trackers
+pub struct ExternalRefRepository<'a> {
+    conn: &'a Connection,
+    url_templates: Option<&'a BTreeMap<String, String>>,
+}
+
+impl<'a> ExternalRefRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        Self {
+            conn,
+            url_templates: None,
+        }
+    }
+
+    /// Expand keys into links with per-system URL templates
+    ///
+    /// Templates are keyed by system and contain `{key}`, as returned by
+    /// `Config::external_ref_url_templates`. References added without a URL
+    /// get one when their system has a template, and references read without
+    /// one, such as those from the Jira fields, are filled in.
+    pub fn with_url_templates(mut self, url_templates: &'a BTreeMap<String, String>) -> Self {
+        self.url_templates = Some(url_templates);
+        self
+    }
+
+    /// The link for a key from its system's template, if there is one
+    fn expand_url(&self, system: &str, key: &str) -> Option<String> {
+        self.url_templates?
+            .get(system)
+            .map(|template| template.replace("{key}", key))
+    }
+
+    /// Fill in missing links from the URL templates
+    fn expand_urls(&self, refs: &mut [ExternalRef]) {
+        for r in refs.iter_mut().filter(|r| r.url.is_none()) {
+            r.url = self.expand_url(&r.system, &r.key);
+        }
+    }
+
+    /// The creation time and legacy Jira key of a project or milestone
+    fn legacy_jira(&self, entity_type: ExternalRefTarget, entity_id: &Uuid) -> Result<(DateTime<Utc>, Option<String>)> {
+        let (table, column) = legacy_column(entity_type);
+        self.conn
+            .prepare_cached(&format!("SELECT created_at, {} FROM {} WHERE id = ?1", column, table))?
+            .query_row(params![entity_id.to_string()], |row| Ok((get_datetime(row, 0)?, row.get(1)?)))
+            .optional()?
+            .ok_or_else(|| {
+                let kind = match entity_type {
+                    ExternalRefTarget::Project => "Project",
+                    ExternalRefTarget::Milestone => "Milestone",
+                };
+                Error::not_found(kind, entity_id)
+            })
+    }
+
+    /// Link a project or milestone to a ticket and return the reference
+    ///
+    /// Without a URL the system's template is used, if it has one. Adding a
+    /// key the record already has only replaces its URL, when one is given.
+    pub fn add(
+        &self,
+        entity_type: ExternalRefTarget,
+        entity_id: &Uuid,
+        system: &str,
+        key: &str,
+        url: Option<&str>,
+    ) -> Result<ExternalRef> {
+        let system = normalize_system(system)?;
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(Error::Invalid("Ticket key cannot be empty".to_string()));
+        }
+        let url = match url.map(str::trim).filter(|url| !url.is_empty()) {
+            Some(url) if !is_http_url(url) => {
+                return Err(Error::Invalid(format!("'{}' is not an http(s) URL", url)));
+            }
+            Some(url) => Some(url.to_string()),
+            None => self.expand_url(&system, key),
+        };
+        // References have no foreign key, so check the record is there
+        self.legacy_jira(entity_type, entity_id)?;
+
+        self.conn
+            .prepare_cached(
+                "INSERT INTO external_refs (id, entity_type, entity_id, system, key, url, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 ON CONFLICT (entity_type, entity_id, system, key)
+                 DO UPDATE SET url = COALESCE(excluded.url, url)",
+            )?
+            .execute(params![
+                Uuid::new_v4().to_string(),
+                entity_type.as_str(),
+                entity_id.to_string(),
+                system,
+                key,
+                url,
+                dt_to_db(Utc::now()),
+            ])?;
+        log::debug!("Linked {} {} to {} {}", entity_type.as_str(), entity_id, system, key);
+
+        let mut stored = self
+            .conn
+            .prepare_cached(&format!(
+                "SELECT {} FROM external_refs
+                 WHERE entity_type = ?1 AND entity_id = ?2 AND system = ?3 AND key = ?4",
+                EXTERNAL_REF_COLUMNS
+            ))?
+            .query_row(
+                params![entity_type.as_str(), entity_id.to_string(), system, key],
+                external_ref_from_row,
+            )?;
+        self.expand_urls(std::slice::from_mut(&mut stored));
+        Ok(stored)
+    }
+
+    /// Find a stored reference by ID
+    pub fn find_by_id(&self, id: &Uuid) -> Result<Option<ExternalRef>> {
+        let mut found = self
+            .conn
+            .prepare_cached(&format!("SELECT {} FROM external_refs WHERE id = ?1", EXTERNAL_REF_COLUMNS))?
+            .query_row(params![id.to_string()], external_ref_from_row)
+            .optional()?;
+        if let Some(found) = found.as_mut() {
+            self.expand_urls(std::slice::from_mut(found));
+        }
+        Ok(found)
+    }
+
+    /// A project's or milestone's references, the legacy Jira one first
+    pub fn get_external_refs(&self, entity_type: ExternalRefTarget, entity_id: &Uuid) -> Result<Vec<ExternalRef>> {
+        let (created_at, legacy) = self.legacy_jira(entity_type, entity_id)?;
+        let stored = self.list_stored(entity_type, entity_id)?;
+        let mut refs = merge_legacy_jira(stored, entity_type, *entity_id, legacy.as_deref(), created_at);
+        self.expand_urls(&mut refs);
+        Ok(refs)
+    }
+
+    /// The references in the table for one record, oldest first
+    pub(super) fn list_stored(&self, entity_type: ExternalRefTarget, entity_id: &Uuid) -> Result<Vec<ExternalRef>> {
+        let refs = self
+            .conn
+            .prepare_cached(&format!(
+                "SELECT {} FROM external_refs WHERE entity_type = ?1 AND entity_id = ?2
+                 ORDER BY created_at, system, key",
+                EXTERNAL_REF_COLUMNS
+            ))?
+            .query_map(params![entity_type.as_str(), entity_id.to_string()], external_ref_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(refs)
+    }
+
+    /// The references in the table for a project's milestones, by milestone ID
+    pub(super) fn list_stored_for_milestones(&self, project_id: &Uuid) -> Result<HashMap<Uuid, Vec<ExternalRef>>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT r.id, r.entity_type, r.entity_id, r.system, r.key, r.url, r.created_at
+             FROM external_refs r JOIN milestones m ON m.id = r.entity_id
+             WHERE r.entity_type = 'milestone' AND m.project_id = ?1
+             ORDER BY r.created_at, r.system, r.key",
+        )?;
+        let mut refs: HashMap<Uuid, Vec<ExternalRef>> = HashMap::new();
+        for r in stmt.query_map(params![project_id.to_string()], external_ref_from_row)? {
+            let r = r?;
+            refs.entry(r.entity_id).or_default().push(r);
+        }
+        Ok(refs)
+    }
+
+    /// Unlink a project or milestone from a ticket
+    ///
+    /// A Jira key held in `jira_initiative` or `jira_epic` is cleared there
+    /// too, bumping the record's version, so it doesn't come back on the
+    /// next read.
+    pub fn remove(&self, entity_type: ExternalRefTarget, entity_id: &Uuid, system: &str, key: &str) -> Result<()> {
+        let system = normalize_system(system)?;
+        let key = key.trim();
+        let mut rows = self
+            .conn
+            .prepare_cached(
+                "DELETE FROM external_refs
+                 WHERE entity_type = ?1 AND entity_id = ?2 AND system = ?3 AND key = ?4",
+            )?
+            .execute(params![entity_type.as_str(), entity_id.to_string(), system, key])?;
+
+        if system == JIRA_SYSTEM {
+            let (table, column) = legacy_column(entity_type);
+            rows += self.conn.execute(
+                &format!(
+                    "UPDATE {0} SET {1} = NULL, updated_at = ?1, version = version + 1
+                     WHERE id = ?2 AND TRIM({1}) = ?3 COLLATE NOCASE",
+                    table, column
+                ),
+                params![dt_to_db(Utc::now()), entity_id.to_string(), key],
+            )?;
+        }
+
+        if rows == 0 {
+            return Err(Error::not_found(
+                "External reference",
+                format!("{} {} on {} {}", system, key, entity_type.as_str(), entity_id),
+            ));
+        }
+        log::debug!("Unlinked {} {} from {} {}", entity_type.as_str(), entity_id, system, key);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{self, Milestone, Project, ProjectRepository};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn refs(list: &[ExternalRef]) -> Vec<(Option<bool>, &str, &str)> {
+        list.iter()
+            .map(|r| (r.id.map(|_| true), r.system.as_str(), r.key.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_add_and_remove_external_refs() {
+        let conn = setup_test_db();
+        let project = Project::new("Apollo".to_string());
+        ProjectRepository::new(&conn).create(&project).unwrap();
+        let templates = BTreeMap::from([("github".to_string(), "https://github.com/acme/app/issues/{key}".to_string())]);
+        let repo = ExternalRefRepository::new(&conn).with_url_templates(&templates);
+
+        let issue = repo
+            .add(ExternalRefTarget::Project, &project.id, " GitHub ", "42", None)
+            .unwrap();
+        assert_eq!(issue.system, "github");
+        assert_eq!(issue.url.as_deref(), Some("https://github.com/acme/app/issues/42"));
+        let linear = repo
+            .add(ExternalRefTarget::Project, &project.id, "linear", "ENG-7", Some("https://linear.app/acme/issue/ENG-7"))
+            .unwrap();
+        assert_eq!(repo.find_by_id(&linear.id.unwrap()).unwrap(), Some(linear.clone()));
+
+        // Adding the same key again keeps one reference and only updates the URL
+        let again = repo
+            .add(ExternalRefTarget::Project, &project.id, "linear", "eng-7", None)
+            .unwrap();
+        assert_eq!(again, linear);
+        assert_eq!(
+            refs(&repo.get_external_refs(ExternalRefTarget::Project, &project.id).unwrap()),
+            [(Some(true), "github", "42"), (Some(true), "linear", "ENG-7")]
+        );
+
+        assert!(matches!(
+            repo.add(ExternalRefTarget::Project, &project.id, "git hub", "1", None),
+            Err(Error::Invalid(_))
+        ));
+        assert!(matches!(
+            repo.add(ExternalRefTarget::Project, &project.id, "github", " ", None),
+            Err(Error::Invalid(_))
+        ));
+        assert!(matches!(
+            repo.add(ExternalRefTarget::Project, &project.id, "github", "1", Some("not a url")),
+            Err(Error::Invalid(_))
+        ));
+        assert!(matches!(
+            repo.add(ExternalRefTarget::Milestone, &project.id, "github", "1", None),
+            Err(Error::NotFound { .. })
+        ));
+
+        repo.remove(ExternalRefTarget::Project, &project.id, "github", "42").unwrap();
+        assert!(matches!(
+            repo.remove(ExternalRefTarget::Project, &project.id, "github", "42"),
+            Err(Error::NotFound { .. })
+        ));
+
+        // References go with their record
+        ProjectRepository::new(&conn).delete(&project.id).unwrap();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM external_refs", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn test_legacy_jira_fields_are_merged() {
+        let conn = setup_test_db();
+        let projects = ProjectRepository::new(&conn);
+        let mut project = Project::new("Apollo".to_string());
+        project.jira_initiative = Some("PROJ-1".to_string());
+        projects.create(&project).unwrap();
+        let mut epic = Milestone::new(project.id, 1, "Design".to_string());
+        epic.jira_epic = Some("PROJ-2".to_string());
+        projects.add_milestone(&epic).unwrap();
+        let plain = Milestone::new(project.id, 2, "Build".to_string());
+        projects.add_milestone(&plain).unwrap();
+
+        let templates = BTreeMap::from([(JIRA_SYSTEM.to_string(), "https://jira.example.com/browse/{key}".to_string())]);
+        let repo = ExternalRefRepository::new(&conn).with_url_templates(&templates);
+        repo.add(ExternalRefTarget::Milestone, &plain.id, "github", "7", None).unwrap();
+
+        // The legacy fields read as Jira references without an ID
+        let project_refs = repo.get_external_refs(ExternalRefTarget::Project, &project.id).unwrap();
+        assert_eq!(refs(&project_refs), [(None, "jira", "PROJ-1")]);
+        assert_eq!(project_refs[0].url.as_deref(), Some("https://jira.example.com/browse/PROJ-1"));
+        assert_eq!(project_refs[0].created_at, project.created_at);
+
+        // Loaded projects and milestones carry them too
+        let loaded = projects.find_by_id(&project.id).unwrap().unwrap();
+        assert_eq!(refs(&loaded.external_refs), [(None, "jira", "PROJ-1")]);
+        let milestones = projects.get_milestones(&project.id).unwrap();
+        assert_eq!(refs(&milestones[0].external_refs), [(None, "jira", "PROJ-2")]);
+        assert_eq!(refs(&milestones[1].external_refs), [(Some(true), "github", "7")]);
+        let found = projects.find_milestone_by_id(&epic.id).unwrap().unwrap();
+        assert_eq!(refs(&found.external_refs), [(None, "jira", "PROJ-2")]);
+
+        // Writing the same Jira key to the table doesn't list it twice
+        repo.add(ExternalRefTarget::Project, &project.id, "jira", "proj-1", None).unwrap();
+        repo.add(ExternalRefTarget::Project, &project.id, "jira", "PROJ-9", None).unwrap();
+        assert_eq!(
+            refs(&repo.get_external_refs(ExternalRefTarget::Project, &project.id).unwrap()),
+            [(Some(true), "jira", "proj-1"), (Some(true), "jira", "PROJ-9")]
+        );
+
+        // Removing a legacy key clears the field as well as any copy in the table
+        repo.remove(ExternalRefTarget::Project, &project.id, "JIRA", "PROJ-1").unwrap();
+        let loaded = projects.find_by_id(&project.id).unwrap().unwrap();
+        assert_eq!(loaded.jira_initiative, None);
+        assert_eq!(loaded.version, project.version + 1);
+        assert_eq!(refs(&loaded.external_refs), [(Some(true), "jira", "PROJ-9")]);
+
+        repo.remove(ExternalRefTarget::Milestone, &epic.id, "jira", "PROJ-2").unwrap();
+        assert!(repo.get_external_refs(ExternalRefTarget::Milestone, &epic.id).unwrap().is_empty());
+        assert_eq!(projects.find_milestone_by_id(&epic.id).unwrap().unwrap().jira_epic, None);
+    }
+}
@@ -0,0 +1,509 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Gaps in the data that are allowed but usually oversights
+//!
+//! Unlike the checks in [`super::maintenance`], nothing found here is
+//! invalid: someone at the top of the org chart has no manager, and an early
+//! project may not have a due date yet. The report lists each gap with the
+//! IDs needed to open and fix the record.
+
+use super::error::Result;
+use super::get_uuid;
+use super::models::NoteType;
+use super::note_link_repo::{NoteLinkRepository, NOTES_WITH_PROJECT};
+use crate::notes::links::NoteRef;
+use crate::utils::email_domain_allowed;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Which checks [`find_gaps_with`] runs
+///
+/// Every check is on by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HygieneChecks {
+    /// Active people without a manager
+    pub people_missing_manager: bool,
+    /// Active people without a team
+    pub people_missing_team: bool,
+    /// Teams without a manager
+    pub teams_missing_manager: bool,
+    /// Projects without a technical lead
+    pub projects_missing_technical_lead: bool,
+    /// Projects without a requirements owner
+    pub projects_missing_requirements_owner: bool,
+    /// Projects without a due date
+    pub projects_missing_due_date: bool,
+    /// Milestones without a due date
+    pub milestones_missing_due_date: bool,
+    /// Milestones without a technical lead
+    pub milestones_missing_lead: bool,
+    /// Stakeholders whose email doesn't belong to a person
+    pub unknown_stakeholders: bool,
+    /// People outside the allowed email domains who aren't marked external
+    pub people_outside_allowed_domains: bool,
+    /// Note references to projects, people or milestones that don't exist
+    pub dangling_references: bool,
+    /// Completed projects without a retrospective
+    pub projects_missing_retrospective: bool,
+}
+
+impl Default for HygieneChecks {
+    fn default() -> Self {
+        Self {
+            people_missing_manager: true,
+            people_missing_team: true,
+            teams_missing_manager: true,
+            projects_missing_technical_lead: true,
+            projects_missing_requirements_owner: true,
+            projects_missing_due_date: true,
+            milestones_missing_due_date: true,
+            milestones_missing_lead: true,
+            unknown_stakeholders: true,
+            people_outside_allowed_domains: true,
+            dangling_references: true,
+            projects_missing_retrospective: true,
+        }
+    }
+}
+
+/// A person with a gap
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersonGap {
+    pub email: String,
+    pub name: String,
+}
+
+/// A team with a gap
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TeamGap {
+    pub name: String,
+}
+
+/// A project with a gap
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectGap {
+    pub id: Uuid,
+    pub name: String,
+}
+
+/// A milestone with a gap
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MilestoneGap {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub project_name: String,
+    pub number: i32,
+    pub name: String,
+}
+
+/// A stakeholder entry whose email has no person
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StakeholderGap {
+    pub project_id: Uuid,
+    pub project_name: String,
+    pub stakeholder_email: String,
+}
+
+/// A `[[kind:target]]` reference in a note that doesn't lead anywhere
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReferenceGap {
+    pub note_type: NoteType,
+    pub note_id: Uuid,
+    pub note_title: String,
+    pub project_id: Uuid,
+    pub project_name: String,
+    /// The reference as written, such as `[[project:apollo]]`
+    pub reference: String,
+}
+
+/// Gaps found in the data, by kind
+///
+/// Lists for checks that didn't run are empty.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HygieneReport {
+    pub people_missing_manager: Vec<PersonGap>,
+    pub people_missing_team: Vec<PersonGap>,
+    pub teams_missing_manager: Vec<TeamGap>,
+    pub projects_missing_technical_lead: Vec<ProjectGap>,
+    pub projects_missing_requirements_owner: Vec<ProjectGap>,
+    pub projects_missing_due_date: Vec<ProjectGap>,
+    pub milestones_missing_due_date: Vec<MilestoneGap>,
+    pub milestones_missing_lead: Vec<MilestoneGap>,
+    pub unknown_stakeholders: Vec<StakeholderGap>,
+    pub people_outside_allowed_domains: Vec<PersonGap>,
+    pub dangling_references: Vec<ReferenceGap>,
+    pub projects_missing_retrospective: Vec<ProjectGap>,
+}
+
+impl HygieneReport {
+    /// Number of gaps of every kind
+    pub fn total(&self) -> usize {
+        self.people_missing_manager.len()
+            + self.people_missing_team.len()
+            + self.teams_missing_manager.len()
+            + self.projects_missing_technical_lead.len()
+            + self.projects_missing_requirements_owner.len()
+            + self.projects_missing_due_date.len()
+            + self.milestones_missing_due_date.len()
+            + self.milestones_missing_lead.len()
+            + self.unknown_stakeholders.len()
+            + self.people_outside_allowed_domains.len()
+            + self.dangling_references.len()
+            + self.projects_missing_retrospective.len()
+    }
+}
+
+/// Run every check, allowing every email domain
+pub fn find_gaps(conn: &Connection) -> Result<HygieneReport> {
+    find_gaps_with(conn, &HygieneChecks::default(), &[])
+}
+
+/// Run the enabled checks
+///
+/// `allowed_domains` is the `allowed_email_domains` setting; when it is
+/// empty nobody is outside it.
+pub fn find_gaps_with(conn: &Connection, checks: &HygieneChecks, allowed_domains: &[String]) -> Result<HygieneReport> {
+    let mut report = HygieneReport::default();
+
+    if checks.people_missing_manager {
+        report.people_missing_manager = people(
+            conn,
+            "SELECT email, name FROM people
+             WHERE active = 1 AND (manager IS NULL OR manager = '')
+             ORDER BY name, email",
+        )?;
+    }
+    if checks.people_missing_team {
+        report.people_missing_team = people(
+            conn,
+            "SELECT email, name FROM people p
+             WHERE active = 1 AND (team IS NULL OR team = '')
+               AND NOT EXISTS (SELECT 1 FROM team_members m WHERE m.person_email = p.email)
+             ORDER BY name, email",
+        )?;
+    }
+    if checks.teams_missing_manager {
+        let mut stmt = conn.prepare_cached(
+            "SELECT name FROM teams WHERE manager IS NULL OR manager = '' ORDER BY name",
+        )?;
+        report.teams_missing_manager = stmt
+            .query_map([], |row| Ok(TeamGap { name: row.get(0)? }))?
+            .collect::<rusqlite::Result<_>>()?;
+    }
+    if checks.projects_missing_technical_lead {
+        report.projects_missing_technical_lead =
+            projects(conn, "technical_lead IS NULL OR technical_lead = ''")?;
+    }
+    if checks.projects_missing_requirements_owner {
+        report.projects_missing_requirements_owner = projects(
+            conn,
+            "requirements_owner IS NULL OR requirements_owner = ''",
+        )?;
+    }
+    if checks.projects_missing_due_date {
+        report.projects_missing_due_date = projects(conn, "due_date IS NULL")?;
+    }
+    if checks.milestones_missing_due_date {
+        report.milestones_missing_due_date = milestones(conn, "m.due_date IS NULL")?;
+    }
+    if checks.milestones_missing_lead {
+        report.milestones_missing_lead =
+            milestones(conn, "m.technical_lead IS NULL OR m.technical_lead = ''")?;
+    }
+    if checks.unknown_stakeholders {
+        let mut stmt = conn.prepare_cached(
+            "SELECT p.id, p.name, s.stakeholder_email
+             FROM project_stakeholders s
+             JOIN projects p ON p.id = s.project_id
+             WHERE NOT EXISTS (SELECT 1 FROM people pe WHERE pe.email = s.stakeholder_email)
+             ORDER BY p.name, s.stakeholder_email",
+        )?;
+        report.unknown_stakeholders = stmt
+            .query_map([], |row| {
+                Ok(StakeholderGap {
+                    project_id: get_uuid(row, 0)?,
+                    project_name: row.get(1)?,
+                    stakeholder_email: row.get(2)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+    }
+    if checks.people_outside_allowed_domains && !allowed_domains.is_empty() {
+        report.people_outside_allowed_domains = people(
+            conn,
+            "SELECT email, name FROM people WHERE external = 0 ORDER BY name, email",
+        )?
+        .into_iter()
+        .filter(|p| !email_domain_allowed(&p.email, allowed_domains))
+        .collect();
+    }
+    if checks.dangling_references {
+        report.dangling_references = dangling_references(conn)?;
+    }
+    if checks.projects_missing_retrospective {
+        report.projects_missing_retrospective = projects(
+            conn,
+            "status = 'done' AND NOT EXISTS (SELECT 1 FROM retrospectives r WHERE r.project_id = projects.id)",
+        )?;
+    }
+
+    Ok(report)
+}
+
+fn dangling_references(conn: &Connection) -> Result<Vec<ReferenceGap>> {
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT title, project_id, project_name FROM ({}) WHERE note_type = ?1 AND note_id = ?2",
+        NOTES_WITH_PROJECT
+    ))?;
+    let mut gaps = Vec::new();
+    for link in NoteLinkRepository::new(conn).list_dangling()? {
+        let note = stmt
+            .query_row(params![link.note_type.as_str(), link.note_id.to_string()], |row| {
+                Ok((row.get(0)?, get_uuid(row, 1)?, row.get(2)?))
+            })
+            .optional()?;
+        if let Some((note_title, project_id, project_name)) = note {
+            gaps.push(ReferenceGap {
+                note_type: link.note_type,
+                note_id: link.note_id,
+                note_title,
+                project_id,
+                project_name,
+                reference: NoteRef::new(link.target_type, link.target).to_string(),
+            });
+        }
+    }
+    gaps.sort_by(|a, b| (&a.project_name, &a.note_title).cmp(&(&b.project_name, &b.note_title)));
+    Ok(gaps)
+}
+
+fn people(conn: &Connection, sql: &str) -> Result<Vec<PersonGap>> {
+    let mut stmt = conn.prepare_cached(sql)?;
+    let people = stmt
+        .query_map([], |row| {
+            Ok(PersonGap {
+                email: row.get(0)?,
+                name: row.get(1)?,
+            })
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(people)
+}
+
+fn projects(conn: &Connection, condition: &str) -> Result<Vec<ProjectGap>> {
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT id, name FROM projects WHERE {} ORDER BY name, id",
+        condition
+    ))?;
+    let projects = stmt
+        .query_map([], |row| {
+            Ok(ProjectGap {
+                id: get_uuid(row, 0)?,
+                name: row.get(1)?,
+            })
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(projects)
+}
+
+fn milestones(conn: &Connection, condition: &str) -> Result<Vec<MilestoneGap>> {
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT m.id, p.id, p.name, m.number, m.name
+         FROM milestones m
+         JOIN projects p ON p.id = m.project_id
+         WHERE {}
+         ORDER BY p.name, m.number",
+        condition
+    ))?;
+    let milestones = stmt
+        .query_map([], |row| {
+            Ok(MilestoneGap {
+                id: get_uuid(row, 0)?,
+                project_id: get_uuid(row, 1)?,
+                project_name: row.get(2)?,
+                number: row.get(3)?,
+                name: row.get(4)?,
+            })
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(milestones)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{
+        schema, Milestone, Person, PersonRepository, Project, ProjectNote, ProjectRepository,
+        ProjectStakeholder, ProjectStatus, Retrospective, RetrospectiveRepository, Team, TeamRepository,
+    };
+    use chrono::Utc;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        schema::initialize_schema(&conn).unwrap();
+        schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_find_gaps() {
+        let conn = setup_test_db();
+        let people = PersonRepository::new(&conn);
+        let mut boss = Person::new("boss@example.com".to_string(), "Boss".to_string());
+        boss.team = Some("Platform".to_string());
+        people.create(&boss).unwrap();
+        let mut alice = Person::new("alice@example.com".to_string(), "Alice".to_string());
+        alice.manager = Some(boss.email.clone());
+        people.create(&alice).unwrap();
+        let mut gone = Person::new("gone@example.com".to_string(), "Gone".to_string());
+        gone.active = false;
+        people.create(&gone).unwrap();
+
+        let teams = TeamRepository::new(&conn);
+        teams.create(&Team::new("Platform".to_string())).unwrap();
+        let mut managed = Team::new("Storage".to_string());
+        managed.manager = Some(boss.email.clone());
+        teams.create(&managed).unwrap();
+        teams.add_member("Storage", &alice.email).unwrap();
+
+        let projects = ProjectRepository::new(&conn);
+        let mut apollo = Project::new("Apollo".to_string());
+        apollo.technical_lead = Some(alice.email.clone());
+        apollo.due_date = Some(Utc::now());
+        projects.create(&apollo).unwrap();
+        let mut milestone = Milestone::new(apollo.id, 1, "Launch".to_string());
+        milestone.technical_lead = Some(alice.email.clone());
+        projects.add_milestone(&milestone).unwrap();
+
+        // Turn foreign keys off so an entry for an unknown email can be added
+        conn.pragma_update(None, "foreign_keys", false).unwrap();
+        projects
+            .add_stakeholder(
+                &apollo.id,
+                &ProjectStakeholder::new(apollo.id, "nobody@example.com".to_string()),
+            )
+            .unwrap();
+
+        let report = find_gaps(&conn).unwrap();
+        let boss_gap = PersonGap {
+            email: boss.email.clone(),
+            name: "Boss".to_string(),
+        };
+        assert_eq!(report.people_missing_manager, vec![boss_gap]);
+        assert!(report.people_missing_team.is_empty());
+        assert_eq!(
+            report.teams_missing_manager,
+            vec![TeamGap {
+                name: "Platform".to_string()
+            }]
+        );
+        assert!(report.projects_missing_technical_lead.is_empty());
+        assert_eq!(report.projects_missing_requirements_owner.len(), 1);
+        assert_eq!(report.projects_missing_requirements_owner[0].id, apollo.id);
+        assert!(report.projects_missing_due_date.is_empty());
+        assert_eq!(report.milestones_missing_due_date.len(), 1);
+        assert_eq!(report.milestones_missing_due_date[0].id, milestone.id);
+        assert_eq!(report.milestones_missing_due_date[0].project_name, "Apollo");
+        assert!(report.milestones_missing_lead.is_empty());
+        assert_eq!(
+            report.unknown_stakeholders[0].stakeholder_email,
+            "nobody@example.com"
+        );
+        assert_eq!(report.total(), 5);
+
+        // Checks that are turned off report nothing
+        let checks = HygieneChecks {
+            people_missing_manager: false,
+            unknown_stakeholders: false,
+            ..HygieneChecks::default()
+        };
+        let report = find_gaps_with(&conn, &checks, &[]).unwrap();
+        assert!(report.people_missing_manager.is_empty());
+        assert!(report.unknown_stakeholders.is_empty());
+        assert_eq!(report.total(), 3);
+    }
+
+    #[test]
+    fn test_people_outside_allowed_domains() {
+        let conn = setup_test_db();
+        let people = PersonRepository::new(&conn);
+        for email in ["alice@company.com", "bob@eu.company.com", "carl@gmail.com", "dana@partner.org"] {
+            people.create(&Person::new(email.to_string(), email.to_string())).unwrap();
+        }
+        let mut sanctioned = Person::new("erin@gmail.com".to_string(), "Erin".to_string());
+        sanctioned.external = true;
+        people.create(&sanctioned).unwrap();
+
+        let allowed = vec!["company.com".to_string()];
+        let report = find_gaps_with(&conn, &HygieneChecks::default(), &allowed).unwrap();
+        let outside: Vec<_> = report.people_outside_allowed_domains.iter().map(|p| p.email.as_str()).collect();
+        assert_eq!(outside, ["carl@gmail.com", "dana@partner.org"]);
+
+        // Nobody is outside an empty allow-list
+        assert!(find_gaps(&conn).unwrap().people_outside_allowed_domains.is_empty());
+    }
+
+    #[test]
+    fn test_dangling_references() {
+        let conn = setup_test_db();
+        let projects = ProjectRepository::new(&conn);
+        let apollo = Project::new("Apollo".to_string());
+        projects.create(&apollo).unwrap();
+        let note = ProjectNote::new(
+            apollo.id,
+            "Dependencies".to_string(),
+            "Waits on [[project:apollo]] and [[project:mercury]]\n\n`[[person:nobody@example.com]]`".to_string(),
+        );
+        projects.add_project_note(&note).unwrap();
+
+        let report = find_gaps(&conn).unwrap();
+        assert_eq!(
+            report.dangling_references,
+            vec![ReferenceGap {
+                note_type: NoteType::Project,
+                note_id: note.id,
+                note_title: "Dependencies".to_string(),
+                project_id: apollo.id,
+                project_name: "Apollo".to_string(),
+                reference: "[[project:mercury]]".to_string(),
+            }]
+        );
+
+        let checks = HygieneChecks {
+            dangling_references: false,
+            ..HygieneChecks::default()
+        };
+        assert!(find_gaps_with(&conn, &checks, &[]).unwrap().dangling_references.is_empty());
+    }
+
+    #[test]
+    fn test_projects_missing_retrospective() {
+        let conn = setup_test_db();
+        let projects = ProjectRepository::new(&conn);
+        let mut apollo = Project::new("Apollo".to_string());
+        projects.create(&apollo).unwrap();
+        assert!(find_gaps(&conn).unwrap().projects_missing_retrospective.is_empty());
+
+        // Marking the project done asks for a retrospective
+        apollo.status = ProjectStatus::Done;
+        projects.update(&apollo).unwrap();
+        let report = find_gaps(&conn).unwrap();
+        assert_eq!(
+            report.projects_missing_retrospective,
+            vec![ProjectGap {
+                id: apollo.id,
+                name: "Apollo".to_string(),
+            }]
+        );
+
+        // Until one is saved
+        RetrospectiveRepository::new(&conn)
+            .save(&Retrospective::new(apollo.id, Utc::now()))
+            .unwrap();
+        assert!(find_gaps(&conn).unwrap().projects_missing_retrospective.is_empty());
+    }
+}
@@ -0,0 +1,289 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+use super::error::{Error, Result};
+use super::{begin_or_join, get_datetime, get_uuid};
+use super::models::{InboxNote, MilestoneNote, NoteKind, NoteTarget, ProjectNote, StakeholderNote};
+use super::project_repo::ProjectRepository;
+use crate::utils::dt_to_db;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use uuid::Uuid;
+
+/// Columns selected for an inbox note, in the order `inbox_note_from_row` expects
+const INBOX_NOTE_COLUMNS: &str = "id, title, body, created_at, updated_at";
+
+/// Map a row selected with `INBOX_NOTE_COLUMNS` to an inbox note
+fn inbox_note_from_row(row: &rusqlite::Row) -> rusqlite::Result<InboxNote> {
+    Ok(InboxNote {
+        id: get_uuid(row, 0)?,
+        title: row.get(1)?,
+        body: row.get(2)?,
+        created_at: get_datetime(row, 3)?,
+        updated_at: get_datetime(row, 4)?,
+    })
+}
+
+/// Inbox repository for notes that aren't attached to anything yet
+pub struct InboxRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> InboxRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Capture a new inbox note
+    pub fn create(&self, note: &InboxNote) -> Result<()> {
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT INTO inbox_notes (id, title, body, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+        )?;
+        stmt.execute(params![
+            note.id.to_string(),
+            &note.title,
+            &note.body,
+            dt_to_db(note.created_at),
+            dt_to_db(note.updated_at),
+        ])?;
+        log::debug!("Created inbox note: {}", note.id);
+        Ok(())
+    }
+
+    /// Find an inbox note by ID
+    pub fn find_by_id(&self, id: &Uuid) -> Result<Option<InboxNote>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM inbox_notes WHERE id = ?1",
+            INBOX_NOTE_COLUMNS
+        ))?;
+        let note = stmt
+            .query_row(params![id.to_string()], inbox_note_from_row)
+            .optional()?;
+        Ok(note)
+    }
+
+    /// List the inbox, newest first
+    pub fn list_all(&self) -> Result<Vec<InboxNote>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM inbox_notes ORDER BY created_at DESC, id DESC",
+            INBOX_NOTE_COLUMNS
+        ))?;
+
+        let notes = stmt
+            .query_map([], inbox_note_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(notes)
+    }
+
+    /// Update an inbox note's title and body
+    pub fn update(&self, note: &InboxNote) -> Result<()> {
+        let rows = self
+            .conn
+            .prepare_cached(
+                "UPDATE inbox_notes SET title = ?1, body = ?2, updated_at = ?3 WHERE id = ?4",
+            )?
+            .execute(params![
+                &note.title,
+                &note.body,
+                dt_to_db(Utc::now()),
+                note.id.to_string()
+            ])?;
+
+        if rows == 0 {
+            return Err(Error::not_found("Inbox note", note.id));
+        }
+
+        Ok(())
+    }
+
+    /// Delete an inbox note
+    pub fn delete(&self, id: &Uuid) -> Result<()> {
+        let rows = self
+            .conn
+            .prepare_cached("DELETE FROM inbox_notes WHERE id = ?1")?
+            .execute(params![id.to_string()])?;
+
+        if rows == 0 {
+            return Err(Error::not_found("Inbox note", id));
+        }
+
+        Ok(())
+    }
+
+    /// File an inbox note under a project, milestone or stakeholder
+    ///
+    /// The note keeps its ID and timestamps and leaves the inbox in the same
+    /// transaction, so it is never in both places or lost.
+    pub fn triage(&self, id: &Uuid, target: &NoteTarget) -> Result<()> {
+        let note = self
+            .find_by_id(id)?
+            .ok_or_else(|| Error::not_found("Inbox note", id))?;
+        let notes = ProjectRepository::new(self.conn);
+
+        let tx = begin_or_join(self.conn)?;
+        match target {
+            NoteTarget::Project { project_id } => notes.add_project_note(&ProjectNote {
+                id: note.id,
+                project_id: *project_id,
+                title: note.title,
+                body: note.body,
+                created_at: note.created_at,
+                updated_at: note.updated_at,
+                version: 1,
+                kind: NoteKind::Freeform,
+                attachments: Vec::new(),
+            })?,
+            NoteTarget::Milestone { milestone_id } => notes.add_milestone_note(&MilestoneNote {
+                id: note.id,
+                milestone_id: *milestone_id,
+                title: note.title,
+                body: note.body,
+                created_at: note.created_at,
+                updated_at: note.updated_at,
+                version: 1,
+                attachments: Vec::new(),
+            })?,
+            NoteTarget::Stakeholder {
+                project_id,
+                stakeholder_email,
+            } => notes.add_stakeholder_note(&StakeholderNote {
+                id: note.id,
+                project_id: *project_id,
+                stakeholder_email: stakeholder_email.clone(),
+                title: note.title,
+                body: note.body,
+                created_at: note.created_at,
+                updated_at: note.updated_at,
+                version: 1,
+                attachments: Vec::new(),
+            })?,
+        }
+        self.delete(id)?;
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+
+        log::debug!("Triaged inbox note {} to {:?}", id, target);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{self, Milestone, Project};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_inbox_crud() {
+        let conn = setup_test_db();
+        let repo = InboxRepository::new(&conn);
+
+        let first = InboxNote::new("Idea".to_string(), "Cache the dashboard".to_string());
+        let mut second = InboxNote::new("Follow up".to_string(), "Ask about budget".to_string());
+        second.created_at = first.created_at + chrono::Duration::seconds(1);
+        repo.create(&first).unwrap();
+        repo.create(&second).unwrap();
+
+        let notes = repo.list_all().unwrap();
+        assert_eq!(
+            notes.iter().map(|n| n.id).collect::<Vec<_>>(),
+            vec![second.id, first.id]
+        );
+
+        second.body = "Ask about Q3 budget".to_string();
+        repo.update(&second).unwrap();
+        assert_eq!(
+            repo.find_by_id(&second.id).unwrap().unwrap().body,
+            "Ask about Q3 budget"
+        );
+
+        repo.delete(&first.id).unwrap();
+        assert!(repo.find_by_id(&first.id).unwrap().is_none());
+        assert!(matches!(
+            repo.delete(&first.id).unwrap_err(),
+            Error::NotFound { .. }
+        ));
+        assert!(matches!(
+            repo.update(&first).unwrap_err(),
+            Error::NotFound { .. }
+        ));
+    }
+
+    #[test]
+    fn test_triage_keeps_id_and_timestamps() {
+        let conn = setup_test_db();
+        let repo = InboxRepository::new(&conn);
+        let projects = ProjectRepository::new(&conn);
+        let project = Project::new("Apollo".to_string());
+        projects.create(&project).unwrap();
+        let milestone = Milestone::new(project.id, 1, "Launch".to_string());
+        projects.add_milestone(&milestone).unwrap();
+
+        let mut note = InboxNote::new("Idea".to_string(), "Cache the dashboard".to_string());
+        note.created_at -= chrono::Duration::days(2);
+        repo.create(&note).unwrap();
+        repo.triage(
+            &note.id,
+            &NoteTarget::Project {
+                project_id: project.id,
+            },
+        )
+        .unwrap();
+
+        assert!(repo.list_all().unwrap().is_empty());
+        let filed = projects.find_project_note_by_id(&note.id).unwrap().unwrap();
+        assert_eq!(filed.project_id, project.id);
+        assert_eq!(filed.title, "Idea");
+        assert_eq!(filed.created_at, note.created_at);
+        assert_eq!(filed.updated_at, note.updated_at);
+
+        let note = InboxNote::new("Risk".to_string(), "Vendor may slip".to_string());
+        repo.create(&note).unwrap();
+        repo.triage(
+            &note.id,
+            &NoteTarget::Milestone {
+                milestone_id: milestone.id,
+            },
+        )
+        .unwrap();
+        let filed = projects
+            .find_milestone_note_by_id(&note.id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(filed.milestone_id, milestone.id);
+    }
+
+    #[test]
+    fn test_failed_triage_leaves_note_in_inbox() {
+        let conn = setup_test_db();
+        let repo = InboxRepository::new(&conn);
+        let note = InboxNote::new("Idea".to_string(), String::new());
+        repo.create(&note).unwrap();
+
+        let missing = NoteTarget::Project {
+            project_id: Uuid::new_v4(),
+        };
+        let err = repo.triage(&note.id, &missing).unwrap_err();
+        assert!(matches!(err, Error::ForeignKeyViolation { .. }));
+        assert!(repo.find_by_id(&note.id).unwrap().is_some());
+
+        let err = repo.triage(&Uuid::new_v4(), &missing).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::NotFound {
+                entity: "Inbox note",
+                ..
+            }
+        ));
+    }
+}
@@ -0,0 +1,332 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+use super::error::{Error, Result};
+use super::{get_datetime, get_uuid};
+use super::models::{Initiative, InitiativeProgress, Project};
+use super::project_repo::{project_from_row, ProjectRepository, HIGH_RISK_THRESHOLD, PROJECT_COLUMNS_P};
+use crate::utils::dt_to_db;
+use rusqlite::{params, Connection, OptionalExtension};
+use uuid::Uuid;
+
+/// Columns selected for an initiative, in the order `initiative_from_row` expects
+const INITIATIVE_COLUMNS: &str = "id, name, description, quarter, owner_email, created_at, updated_at";
+
+/// Map a row selected with `INITIATIVE_COLUMNS` to an initiative
+fn initiative_from_row(row: &rusqlite::Row) -> rusqlite::Result<Initiative> {
+    Ok(Initiative {
+        id: get_uuid(row, 0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        quarter: row.get(3)?,
+        owner_email: row.get(4)?,
+        created_at: get_datetime(row, 5)?,
+        updated_at: get_datetime(row, 6)?,
+    })
+}
+
+/// An initiative needs a name, and its quarter is stored as "YYYY-Qn"
+fn validate_initiative(initiative: &Initiative) -> Result<String> {
+    if initiative.name.trim().is_empty() {
+        return Err(Error::Invalid("Initiative name cannot be empty".to_string()));
+    }
+    Initiative::parse_quarter(&initiative.quarter)
+}
+
+/// Initiative repository for database operations
+pub struct InitiativeRepository<'a> {
+    conn: &'a Connection,
+    checklist_progress: bool,
+}
+
+impl<'a> InitiativeRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        Self {
+            conn,
+            checklist_progress: false,
+        }
+    }
+
+    /// Count checked checklist items towards milestone progress, see
+    /// [`ProjectRepository::with_checklist_progress`]
+    pub fn with_checklist_progress(mut self, enabled: bool) -> Self {
+        self.checklist_progress = enabled;
+        self
+    }
+
+    /// Create a new initiative
+    pub fn create(&self, initiative: &Initiative) -> Result<()> {
+        let quarter = validate_initiative(initiative)?;
+
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT INTO initiatives (id, name, description, quarter, owner_email, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )?;
+        stmt.execute(params![
+            initiative.id.to_string(),
+            &initiative.name,
+            &initiative.description,
+            quarter,
+            &initiative.owner_email,
+            dt_to_db(initiative.created_at),
+            dt_to_db(initiative.updated_at),
+        ])
+        .map_err(|e| Error::from(e).with_field("owner_email"))?;
+        log::debug!("Created initiative: {} ({})", initiative.name, initiative.id);
+        Ok(())
+    }
+
+    /// Find an initiative by ID
+    pub fn find_by_id(&self, id: &Uuid) -> Result<Option<Initiative>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM initiatives WHERE id = ?1",
+            INITIATIVE_COLUMNS
+        ))?;
+        let initiative = stmt
+            .query_row(params![id.to_string()], initiative_from_row)
+            .optional()?;
+        Ok(initiative)
+    }
+
+    /// List all initiatives, ordered by quarter then name
+    pub fn list_all(&self) -> Result<Vec<Initiative>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM initiatives ORDER BY quarter, name",
+            INITIATIVE_COLUMNS
+        ))?;
+
+        let initiatives = stmt
+            .query_map([], initiative_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(initiatives)
+    }
+
+    /// List the initiatives planned for a quarter, ordered by name
+    pub fn list_by_quarter(&self, quarter: &str) -> Result<Vec<Initiative>> {
+        let quarter = Initiative::parse_quarter(quarter)?;
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM initiatives WHERE quarter = ?1 ORDER BY name",
+            INITIATIVE_COLUMNS
+        ))?;
+
+        let initiatives = stmt
+            .query_map(params![quarter], initiative_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(initiatives)
+    }
+
+    /// Update an initiative
+    pub fn update(&self, initiative: &Initiative) -> Result<()> {
+        let quarter = validate_initiative(initiative)?;
+
+        let mut stmt = self.conn.prepare_cached(
+            "UPDATE initiatives SET name = ?1, description = ?2, quarter = ?3, owner_email = ?4, updated_at = ?5
+             WHERE id = ?6",
+        )?;
+        let rows = stmt
+            .execute(params![
+                &initiative.name,
+                &initiative.description,
+                quarter,
+                &initiative.owner_email,
+                dt_to_db(initiative.updated_at),
+                initiative.id.to_string(),
+            ])
+            .map_err(|e| Error::from(e).with_field("owner_email"))?;
+
+        if rows == 0 {
+            return Err(Error::not_found("Initiative", initiative.id));
+        }
+
+        log::debug!("Updated initiative: {}", initiative.id);
+        Ok(())
+    }
+
+    /// Delete an initiative; its projects are unlinked but not deleted
+    pub fn delete(&self, id: &Uuid) -> Result<()> {
+        let rows = self
+            .conn
+            .prepare_cached("DELETE FROM initiatives WHERE id = ?1")?
+            .execute(params![id.to_string()])?;
+
+        if rows == 0 {
+            return Err(Error::not_found("Initiative", id));
+        }
+
+        log::debug!("Deleted initiative: {}", id);
+        Ok(())
+    }
+
+    /// Get the projects linked to an initiative, ordered by name
+    pub fn get_projects_for_initiative(&self, id: &Uuid) -> Result<Vec<Project>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {}
+             FROM projects p
+             INNER JOIN project_initiatives pi ON pi.project_id = p.id
+             WHERE pi.initiative_id = ?1
+             ORDER BY p.name",
+            PROJECT_COLUMNS_P
+        ))?;
+
+        let projects = stmt
+            .query_map(params![id.to_string()], project_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(projects)
+    }
+
+    /// Summarize the milestone completion and open high risks of an initiative's projects
+    pub fn get_progress(&self, id: &Uuid) -> Result<Option<InitiativeProgress>> {
+        let Some(initiative) = self.find_by_id(id)? else {
+            return Ok(None);
+        };
+
+        let project_repo = ProjectRepository::new(self.conn).with_checklist_progress(self.checklist_progress);
+        let mut projects = Vec::new();
+        let mut open_high_risks = 0;
+        for project in self.get_projects_for_initiative(id)? {
+            if let Some(summary) = project_repo.get_project_summary(&project.id)? {
+                projects.push(summary);
+            }
+            open_high_risks += project_repo
+                .list_open_risks(&project.id)?
+                .iter()
+                .filter(|risk| risk.score() > HIGH_RISK_THRESHOLD)
+                .count();
+        }
+
+        let total_milestones: usize = projects.iter().map(|p| p.total_milestones).sum();
+        let completed_milestones: usize = projects.iter().map(|p| p.completed_milestones).sum();
+
+        Ok(Some(InitiativeProgress {
+            initiative,
+            projects,
+            total_milestones,
+            completed_milestones,
+            progress_percent: (total_milestones > 0)
+                .then(|| (completed_milestones * 100 / total_milestones) as u8),
+            open_high_risks,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{self, Milestone, ProjectRisk, RiskLevel};
+    use chrono::{Duration, Utc};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_initiative_crud() {
+        let conn = setup_test_db();
+        let repo = InitiativeRepository::new(&conn);
+
+        let growth = Initiative::new("Growth".to_string(), "2025-q3".to_string());
+        repo.create(&growth).unwrap();
+        let mut reliability = Initiative::new("Reliability".to_string(), "2025-Q2".to_string());
+        repo.create(&reliability).unwrap();
+
+        // Quarters are stored normalized and sort chronologically
+        let all = repo.list_all().unwrap();
+        assert_eq!(all.iter().map(|i| i.name.as_str()).collect::<Vec<_>>(), vec!["Reliability", "Growth"]);
+        assert_eq!(all[1].quarter, "2025-Q3");
+        assert_eq!(repo.list_by_quarter("2025-Q3").unwrap().len(), 1);
+
+        reliability.description = Some("Fewer pages".to_string());
+        reliability.quarter = "2025-Q4".to_string();
+        repo.update(&reliability).unwrap();
+        let found = repo.find_by_id(&reliability.id).unwrap().unwrap();
+        assert_eq!(found.description.as_deref(), Some("Fewer pages"));
+        assert_eq!(found.quarter, "2025-Q4");
+
+        repo.delete(&growth.id).unwrap();
+        assert!(repo.find_by_id(&growth.id).unwrap().is_none());
+        assert!(matches!(repo.delete(&growth.id).unwrap_err(), Error::NotFound { .. }));
+    }
+
+    #[test]
+    fn test_initiative_validation() {
+        let conn = setup_test_db();
+        let repo = InitiativeRepository::new(&conn);
+
+        let bad_quarter = Initiative::new("Growth".to_string(), "Q3 2025".to_string());
+        assert!(matches!(repo.create(&bad_quarter).unwrap_err(), Error::Invalid(_)));
+        let unnamed = Initiative::new(" ".to_string(), "2025-Q3".to_string());
+        assert!(matches!(repo.create(&unnamed).unwrap_err(), Error::Invalid(_)));
+        let mut unowned = Initiative::new("Growth".to_string(), "2025-Q3".to_string());
+        unowned.owner_email = Some("nobody@example.com".to_string());
+        assert!(matches!(repo.create(&unowned).unwrap_err(), Error::ForeignKeyViolation { .. }));
+        assert!(matches!(repo.list_by_quarter("summer").unwrap_err(), Error::Invalid(_)));
+    }
+
+    #[test]
+    fn test_initiative_projects_and_progress() {
+        let conn = setup_test_db();
+        let repo = InitiativeRepository::new(&conn);
+        let projects = ProjectRepository::new(&conn);
+
+        let growth = Initiative::new("Growth".to_string(), "2025-Q3".to_string());
+        repo.create(&growth).unwrap();
+        let reliability = Initiative::new("Reliability".to_string(), "2025-Q3".to_string());
+        repo.create(&reliability).unwrap();
+
+        let beta = Project::new("Beta".to_string());
+        projects.create(&beta).unwrap();
+        let alpha = Project::new("Alpha".to_string());
+        projects.create(&alpha).unwrap();
+        let unrelated = Project::new("Unrelated".to_string());
+        projects.create(&unrelated).unwrap();
+
+        // One milestone done and one to go in Alpha, one done in Beta
+        for (project, number, days) in [(&alpha, 1, -10), (&alpha, 2, 10), (&beta, 1, -5)] {
+            let mut milestone = Milestone::new(project.id, number, format!("M{}", number));
+            milestone.due_date = Some(Utc::now() + Duration::days(days));
+            projects.add_milestone(&milestone).unwrap();
+        }
+        projects
+            .add_project_risk(&ProjectRisk::new(beta.id, "Outage".to_string(), RiskLevel::High, RiskLevel::High))
+            .unwrap();
+        projects
+            .add_project_risk(&ProjectRisk::new(beta.id, "Minor".to_string(), RiskLevel::Low, RiskLevel::Low))
+            .unwrap();
+
+        projects.set_initiative(&alpha.id, &growth.id).unwrap();
+        projects.set_initiative(&beta.id, &reliability.id).unwrap();
+        projects.set_initiative(&unrelated.id, &growth.id).unwrap();
+
+        // Setting a new initiative replaces the old one
+        projects.set_initiative(&beta.id, &growth.id).unwrap();
+        projects.clear_initiative(&unrelated.id).unwrap();
+        assert!(repo.get_projects_for_initiative(&reliability.id).unwrap().is_empty());
+        let linked = repo.get_projects_for_initiative(&growth.id).unwrap();
+        assert_eq!(linked.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), vec!["Alpha", "Beta"]);
+
+        let progress = repo.get_progress(&growth.id).unwrap().unwrap();
+        assert_eq!(progress.projects.len(), 2);
+        assert_eq!(progress.total_milestones, 3);
+        assert_eq!(progress.completed_milestones, 2);
+        assert_eq!(progress.progress_percent, Some(66));
+        assert_eq!(progress.open_high_risks, 1);
+        assert!(repo.get_progress(&Uuid::new_v4()).unwrap().is_none());
+
+        assert!(matches!(
+            projects.set_initiative(&alpha.id, &Uuid::new_v4()).unwrap_err(),
+            Error::ForeignKeyViolation { .. }
+        ));
+
+        // Deleting the initiative unlinks its projects without deleting them
+        repo.delete(&growth.id).unwrap();
+        assert!(projects.find_by_id(&alpha.id).unwrap().is_some());
+        assert!(projects.get_initiative(&alpha.id).unwrap().is_none());
+    }
+}
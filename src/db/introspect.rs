@@ -0,0 +1,500 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Documentation of the database schema, read from the database itself
+//!
+//! [`describe_schema`] reads the tables from `sqlite_master` and their
+//! columns, foreign keys and indexes from SQLite's pragmas, so the result
+//! always matches what the migrations actually built. A
+//! [`SchemaDescription`] renders as markdown, with a section per table, or as
+//! a Graphviz DOT entity-relationship diagram.
+//!
+//! `docs/schema.md` is the markdown of a new database. A test compares the
+//! two, so a migration that changes the schema has to update it as well.
+
+use super::error::{Error, Result};
+use super::schema::read_schema_version;
+use crate::diagram::escape_dot;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Write as _};
+use std::str::FromStr;
+
+/// Every table in a database, by name
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaDescription {
+    /// Version recorded in `schema_version`, if the table exists
+    pub version: Option<i32>,
+
+    pub tables: Vec<TableDescription>,
+}
+
+/// A table with its columns, references and indexes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableDescription {
+    pub name: String,
+
+    /// Columns in the order they were defined
+    pub columns: Vec<ColumnDescription>,
+
+    /// References to other tables, by referencing columns
+    pub foreign_keys: Vec<ForeignKeyDescription>,
+
+    /// Indexes created for the table or for its UNIQUE constraints, by name
+    pub indexes: Vec<IndexDescription>,
+}
+
+/// A column of a table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnDescription {
+    pub name: String,
+
+    /// Declared type, empty for a column declared without one
+    pub data_type: String,
+
+    pub not_null: bool,
+
+    /// Default value as written in the schema, such as `'vendor'` or `0`
+    pub default: Option<String>,
+
+    /// Position in the primary key, counting from 1, or 0 if not part of it
+    pub primary_key: u32,
+}
+
+/// A reference from some of a table's columns to another table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForeignKeyDescription {
+    /// Referencing columns
+    pub columns: Vec<String>,
+
+    /// Referenced table
+    pub table: String,
+
+    /// Referenced columns; empty when the reference is to the primary key
+    pub references: Vec<String>,
+
+    /// ON DELETE action, such as CASCADE; NO ACTION when none was given
+    pub on_delete: String,
+
+    /// ON UPDATE action
+    pub on_update: String,
+}
+
+/// An index on a table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexDescription {
+    pub name: String,
+
+    /// Indexed columns; an indexed expression is shown as `(expression)`
+    pub columns: Vec<String>,
+
+    pub unique: bool,
+
+    /// Whether the index only covers rows matching a WHERE clause
+    pub partial: bool,
+}
+
+/// Text format of a rendered schema description
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SchemaFormat {
+    /// Markdown with a section per table
+    #[default]
+    Markdown,
+    /// Graphviz DOT entity-relationship diagram
+    Dot,
+}
+
+impl SchemaFormat {
+    /// Name used on the command line and in command arguments
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SchemaFormat::Markdown => "markdown",
+            SchemaFormat::Dot => "dot",
+        }
+    }
+}
+
+impl FromStr for SchemaFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Ok(SchemaFormat::Markdown),
+            "dot" | "graphviz" => Ok(SchemaFormat::Dot),
+            _ => Err(Error::Invalid(format!(
+                "Invalid schema format '{}': expected markdown or dot",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for SchemaFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Describe every table in the database
+///
+/// SQLite's own tables are left out. Everything is read as SQLite reports
+/// it, so columns, types and indexes left behind by older versions are
+/// described like any other.
+pub fn describe_schema(conn: &Connection) -> Result<SchemaDescription> {
+    let mut stmt = conn.prepare(
+        "SELECT name FROM sqlite_master
+         WHERE type = 'table' AND name NOT LIKE 'sqlite\\_%' ESCAPE '\\'
+         ORDER BY name",
+    )?;
+    let names = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let tables = names
+        .into_iter()
+        .map(|name| describe_table(conn, name))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(SchemaDescription {
+        version: read_schema_version(conn)?,
+        tables,
+    })
+}
+
+fn describe_table(conn: &Connection, name: String) -> Result<TableDescription> {
+    let columns = conn
+        .prepare_cached(
+            "SELECT name, type, \"notnull\", dflt_value, pk FROM pragma_table_info(?1) ORDER BY cid",
+        )?
+        .query_map(params![&name], |row| {
+            Ok(ColumnDescription {
+                name: row.get::<_, Option<String>>(0)?.unwrap_or_default(),
+                data_type: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                not_null: row.get::<_, Option<bool>>(2)?.unwrap_or(false),
+                default: row.get(3)?,
+                primary_key: row.get::<_, Option<u32>>(4)?.unwrap_or(0),
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    // One row per referencing column; a reference over several columns
+    // shares an id, with seq giving the column order
+    let mut foreign_keys: Vec<(i64, ForeignKeyDescription)> = Vec::new();
+    let mut stmt = conn.prepare_cached(
+        "SELECT id, \"table\", \"from\", \"to\", on_update, on_delete
+         FROM pragma_foreign_key_list(?1) ORDER BY id, seq",
+    )?;
+    let rows = stmt.query_map(params![&name], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+            row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+            row.get::<_, Option<String>>(3)?,
+            row.get::<_, Option<String>>(4)?.unwrap_or_else(|| "NO ACTION".to_string()),
+            row.get::<_, Option<String>>(5)?.unwrap_or_else(|| "NO ACTION".to_string()),
+        ))
+    })?;
+    for row in rows {
+        let (id, table, from, to, on_update, on_delete) = row?;
+        match foreign_keys.last_mut() {
+            Some((last, key)) if *last == id => {
+                key.columns.push(from);
+                key.references.extend(to);
+            }
+            _ => foreign_keys.push((
+                id,
+                ForeignKeyDescription {
+                    columns: vec![from],
+                    table,
+                    references: to.into_iter().collect(),
+                    on_delete,
+                    on_update,
+                },
+            )),
+        }
+    }
+    let mut foreign_keys: Vec<_> = foreign_keys.into_iter().map(|(_, key)| key).collect();
+    foreign_keys.sort_by(|a, b| (&a.columns, &a.table).cmp(&(&b.columns, &b.table)));
+
+    // Primary key indexes only repeat the key, so they are left out
+    let indexes = conn
+        .prepare_cached(
+            "SELECT name, \"unique\", partial FROM pragma_index_list(?1)
+             WHERE origin <> 'pk' ORDER BY name",
+        )?
+        .query_map(params![&name], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<bool>>(1)?.unwrap_or(false),
+                row.get::<_, Option<bool>>(2)?.unwrap_or(false),
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    let indexes = indexes
+        .into_iter()
+        .map(|(index, unique, partial)| {
+            let columns = conn
+                .prepare_cached("SELECT name FROM pragma_index_info(?1) ORDER BY seqno")?
+                .query_map(params![&index], |row| {
+                    Ok(row
+                        .get::<_, Option<String>>(0)?
+                        .unwrap_or_else(|| "(expression)".to_string()))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(IndexDescription {
+                name: index,
+                columns,
+                unique,
+                partial,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(TableDescription {
+        name,
+        columns,
+        foreign_keys,
+        indexes,
+    })
+}
+
+impl SchemaDescription {
+    /// Render in the given format
+    pub fn render(&self, format: SchemaFormat) -> String {
+        match format {
+            SchemaFormat::Markdown => self.to_markdown(),
+            SchemaFormat::Dot => self.to_dot(),
+        }
+    }
+
+    /// Markdown with a section per table: its columns, the tables it
+    /// references and its indexes
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("# Database schema\n\n");
+        match self.version {
+            Some(version) => {
+                let _ = writeln!(out, "Schema version {}.", version);
+            }
+            None => out.push_str("No schema version recorded.\n"),
+        }
+        out.push_str(
+            "Generated by `track db schema` from the database; run it again rather than editing this file.\n",
+        );
+
+        for table in &self.tables {
+            let _ = write!(out, "\n## {}\n\n", table.name);
+            out.push_str("| Column | Type | Null | Default | Key |\n");
+            out.push_str("| --- | --- | --- | --- | --- |\n");
+            // Columns of a composite primary key are numbered in key order
+            let composite = table.columns.iter().filter(|c| c.primary_key > 0).count() > 1;
+            for column in &table.columns {
+                let mut keys = Vec::new();
+                match column.primary_key {
+                    0 => {}
+                    position if composite => keys.push(format!("PK {}", position)),
+                    _ => keys.push("PK".to_string()),
+                }
+                keys.extend(
+                    table
+                        .foreign_keys
+                        .iter()
+                        .filter(|fk| fk.columns.contains(&column.name))
+                        .map(|fk| format!("FK {}", escape_cell(&fk.table))),
+                );
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {} | {} | {} |",
+                    escape_cell(&column.name),
+                    if column.data_type.is_empty() { "-" } else { &column.data_type },
+                    if column.not_null { "no" } else { "yes" },
+                    column.default.as_deref().map(escape_cell).unwrap_or_default(),
+                    keys.join(", "),
+                );
+            }
+
+            if !table.foreign_keys.is_empty() {
+                out.push_str("\nReferences:\n\n");
+                for fk in &table.foreign_keys {
+                    let target = if fk.references.is_empty() {
+                        fk.table.clone()
+                    } else {
+                        format!("{}({})", fk.table, fk.references.join(", "))
+                    };
+                    let _ = write!(out, "- {} → {}", fk.columns.join(", "), target);
+                    let actions = [("on delete", &fk.on_delete), ("on update", &fk.on_update)]
+                        .into_iter()
+                        .filter(|(_, action)| action.as_str() != "NO ACTION")
+                        .map(|(label, action)| format!("{} {}", label, action.to_ascii_lowercase()))
+                        .collect::<Vec<_>>();
+                    if !actions.is_empty() {
+                        let _ = write!(out, ", {}", actions.join(", "));
+                    }
+                    out.push('\n');
+                }
+            }
+
+            if !table.indexes.is_empty() {
+                out.push_str("\nIndexes:\n\n");
+                for index in &table.indexes {
+                    let _ = write!(out, "- {} ({})", index.name, index.columns.join(", "));
+                    if index.unique {
+                        out.push_str(", unique");
+                    }
+                    if index.partial {
+                        out.push_str(", partial");
+                    }
+                    out.push('\n');
+                }
+            }
+        }
+        out
+    }
+
+    /// Graphviz DOT entity-relationship diagram: a box per table listing its
+    /// columns, and an arrow for each reference labelled with its columns
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph schema {\n");
+        out.push_str("    rankdir=LR;\n");
+        out.push_str("    node [shape=record, fontname=\"Helvetica\", fontsize=10];\n");
+        out.push_str("    edge [fontname=\"Helvetica\", fontsize=9];\n");
+        for table in &self.tables {
+            let columns: String = table
+                .columns
+                .iter()
+                .map(|c| {
+                    let key = if c.primary_key > 0 { " (PK)" } else { "" };
+                    let data_type = if c.data_type.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" : {}", c.data_type)
+                    };
+                    format!("{}{}{}\\l", escape_record(&c.name), escape_record(&data_type), key)
+                })
+                .collect();
+            let _ = writeln!(
+                out,
+                "    \"{}\" [label=\"{{{}|{}}}\"];",
+                escape_dot(&table.name),
+                escape_record(&table.name),
+                columns
+            );
+        }
+        for table in &self.tables {
+            for fk in &table.foreign_keys {
+                let _ = writeln!(
+                    out,
+                    "    \"{}\" -> \"{}\" [label=\"{}\"];",
+                    escape_dot(&table.name),
+                    escape_dot(&fk.table),
+                    escape_dot(&fk.columns.join(", "))
+                );
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// Escape text for a markdown table cell
+fn escape_cell(s: &str) -> String {
+    s.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Escape text for a field of a DOT record label, inside a double-quoted string
+fn escape_record(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in escape_dot(s).chars() {
+        if matches!(c, '{' | '}' | '|' | '<' | '>') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::schema;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+        schema::initialize_schema(&conn).unwrap();
+        schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_describe_schema() {
+        let conn = setup_test_db();
+        let description = describe_schema(&conn).unwrap();
+        assert_eq!(description.version, Some(schema::SUPPORTED_SCHEMA_VERSION));
+
+        let people = description.tables.iter().find(|t| t.name == "people").unwrap();
+        let email = &people.columns[0];
+        assert_eq!((email.name.as_str(), email.data_type.as_str(), email.primary_key), ("email", "TEXT", 1));
+        let organization = people
+            .foreign_keys
+            .iter()
+            .find(|fk| fk.columns == ["organization"])
+            .unwrap();
+        assert_eq!(organization.table, "organizations");
+        assert_eq!(organization.on_delete, "SET NULL");
+        assert!(people.indexes.iter().any(|i| i.name == "idx_people_organization"));
+        assert!(!description.tables.iter().any(|t| t.name.starts_with("sqlite_")));
+    }
+
+    #[test]
+    fn test_legacy_tables() {
+        // Untyped columns, references to missing tables and expression
+        // indexes are described rather than refused
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE legacy (id, note, owner REFERENCES gone, a INT, b INT,
+                 FOREIGN KEY (a, b) REFERENCES pairs(x, y) ON DELETE CASCADE);
+             CREATE INDEX idx_legacy_note ON legacy (lower(note)) WHERE note IS NOT NULL;
+             CREATE TABLE \"odd|name\" (\"col{1}\" TEXT DEFAULT 'a|b');",
+        )
+        .unwrap();
+
+        let description = describe_schema(&conn).unwrap();
+        assert_eq!(description.version, None);
+        let legacy = &description.tables[0];
+        assert_eq!(legacy.columns[0].data_type, "");
+        assert_eq!(legacy.foreign_keys.len(), 2);
+        assert_eq!(legacy.foreign_keys[0].columns, ["a", "b"]);
+        assert_eq!(legacy.foreign_keys[0].references, ["x", "y"]);
+        assert_eq!(legacy.foreign_keys[1].table, "gone");
+        assert!(legacy.foreign_keys[1].references.is_empty());
+        assert_eq!(
+            legacy.indexes,
+            [IndexDescription {
+                name: "idx_legacy_note".to_string(),
+                columns: vec!["(expression)".to_string()],
+                unique: false,
+                partial: true,
+            }]
+        );
+
+        let markdown = description.to_markdown();
+        assert!(markdown.contains("| id | - | yes |  |  |\n"));
+        assert!(markdown.contains("| a | INT | yes |  | FK pairs |\n"));
+        assert!(markdown.contains("- a, b → pairs(x, y), on delete cascade\n"));
+        assert!(markdown.contains("## odd|name\n"));
+        assert!(markdown.contains("| col{1} | TEXT | yes | 'a\\|b' |  |\n"));
+        let dot = description.to_dot();
+        assert!(dot.contains("\"odd|name\" [label=\"{odd\\|name|col\\{1\\} : TEXT\\l}\"];\n"));
+        assert!(dot.contains("\"legacy\" -> \"pairs\" [label=\"a, b\"];\n"));
+    }
+
+    #[test]
+    fn test_schema_doc_is_current() {
+        let conn = setup_test_db();
+        let markdown = describe_schema(&conn).unwrap().to_markdown();
+        assert!(
+            markdown == include_str!("../../docs/schema.md"),
+            "docs/schema.md doesn't match the schema; regenerate it with `track db schema` on a new database"
+        );
+    }
+}
@@ -0,0 +1,238 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+use super::error::Result;
+use super::{begin_or_join, get_datetime};
+use super::models::{LinkCheck, LinkKind, LinkReport, ProjectLink};
+use super::project_repo::parse_column;
+use crate::utils::dt_to_db;
+use rusqlite::{params, Connection, OptionalExtension};
+use uuid::Uuid;
+
+/// Every link stored on projects and milestones, optionally for one project
+const PROJECT_LINKS_SQL: &str = "
+    WITH links AS (
+        SELECT m.project_id, 'design_doc' AS kind,
+               'Milestone ' || m.number || ': ' || m.name AS label, m.design_doc_url AS url, 1 AS source
+        FROM milestones m
+        UNION ALL
+        SELECT project_id, 'document', title, url, 2 FROM project_documents
+        UNION ALL
+        SELECT id, 'jira_initiative', 'Jira initiative', jira_initiative, 0 FROM projects
+        UNION ALL
+        SELECT m.project_id, 'jira_epic', 'Milestone ' || m.number || ': ' || m.name || ' (epic)', m.jira_epic, 1
+        FROM milestones m
+    )
+    SELECT p.id, p.name, l.kind, l.label, TRIM(l.url)
+    FROM links l JOIN projects p ON p.id = l.project_id
+    WHERE TRIM(COALESCE(l.url, '')) != '' AND (?1 IS NULL OR p.id = ?1)
+    ORDER BY p.name, p.id, l.source, l.label";
+
+/// Columns selected for a link check, in the order `link_check_from_row` expects
+const LINK_CHECK_COLUMNS: &str = "url, status, http_status, error, checked_at";
+
+/// Map a row selected with `LINK_CHECK_COLUMNS` to a link check
+fn link_check_from_row(row: &rusqlite::Row) -> rusqlite::Result<LinkCheck> {
+    Ok(LinkCheck {
+        url: row.get(0)?,
+        status: parse_column(row, 1)?,
+        http_status: row.get(2)?,
+        error: row.get(3)?,
+        checked_at: get_datetime(row, 4)?,
+    })
+}
+
+/// Link check repository: the links stored in the tracker and the last check of each
+pub struct LinkCheckRepository<'a> {
+    conn: &'a Connection,
+    jira_url: Option<&'a str>,
+}
+
+impl<'a> LinkCheckRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        Self {
+            conn,
+            jira_url: None,
+        }
+    }
+
+    /// Expand Jira ticket keys into links under `jira_url`
+    ///
+    /// Without it Jira links are left out, since a bare key can't be checked.
+    pub fn with_jira_url(mut self, jira_url: &'a str) -> Self {
+        self.jira_url = Some(jira_url);
+        self
+    }
+
+    /// List the links on every project, or on one project
+    ///
+    /// Links come from milestone design docs and Jira epics, project
+    /// documents and project Jira initiatives. A URL used in several places
+    /// is listed once for each.
+    pub fn list_links(&self, project_id: Option<&Uuid>) -> Result<Vec<ProjectLink>> {
+        let mut stmt = self.conn.prepare_cached(PROJECT_LINKS_SQL)?;
+        let rows = stmt
+            .query_map(params![project_id.map(Uuid::to_string)], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut links = Vec::with_capacity(rows.len());
+        for (project_id, project_name, kind, label, url) in rows {
+            let kind = match kind.as_str() {
+                "design_doc" => LinkKind::DesignDoc,
+                "document" => LinkKind::Document,
+                "jira_initiative" => LinkKind::JiraInitiative,
+                _ => LinkKind::JiraEpic,
+            };
+            let url = match kind {
+                LinkKind::JiraInitiative | LinkKind::JiraEpic => match self.jira_url {
+                    Some(jira_url) => format!("{}{}", jira_url, url),
+                    None => continue,
+                },
+                LinkKind::DesignDoc | LinkKind::Document => url,
+            };
+            links.push(ProjectLink {
+                project_id: Uuid::parse_str(&project_id).map_err(anyhow::Error::from)?,
+                project_name,
+                kind,
+                label,
+                url,
+            });
+        }
+        Ok(links)
+    }
+
+    /// Store the results of checking links, replacing earlier results for the same URLs
+    pub fn save_checks(&self, checks: &[LinkCheck]) -> Result<()> {
+        let tx = begin_or_join(self.conn)?;
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT INTO link_checks (url, status, http_status, error, checked_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(url) DO UPDATE SET status = excluded.status, http_status = excluded.http_status,
+                                            error = excluded.error, checked_at = excluded.checked_at",
+        )?;
+        for check in checks {
+            stmt.execute(params![
+                &check.url,
+                check.status.as_str(),
+                check.http_status,
+                &check.error,
+                dt_to_db(check.checked_at),
+            ])?;
+        }
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+        log::debug!("Saved {} link check(s)", checks.len());
+        Ok(())
+    }
+
+    /// Find the last check of a URL
+    pub fn find_check(&self, url: &str) -> Result<Option<LinkCheck>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM link_checks WHERE url = ?1",
+            LINK_CHECK_COLUMNS
+        ))?;
+        let check = stmt.query_row(params![url], link_check_from_row).optional()?;
+        Ok(check)
+    }
+
+    /// List the links on every project, or on one project, with their last check
+    ///
+    /// Nothing is checked; links that were never checked have no result.
+    pub fn get_report(&self, project_id: Option<&Uuid>) -> Result<Vec<LinkReport>> {
+        self.list_links(project_id)?
+            .into_iter()
+            .map(|link| {
+                let check = self.find_check(&link.url)?;
+                Ok(LinkReport { link, check })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{self, LinkStatus, Milestone, Project, ProjectDocument, ProjectRepository};
+    use chrono::{TimeZone, Utc};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_links_and_checks() {
+        let conn = setup_test_db();
+        let projects = ProjectRepository::new(&conn);
+        let mut apollo = Project::new("Apollo".to_string());
+        apollo.jira_initiative = Some("PROJ-1".to_string());
+        projects.create(&apollo).unwrap();
+        let mut beta = Milestone::new(apollo.id, 1, "Beta".to_string());
+        beta.design_doc_url = Some("https://docs.example.com/beta".to_string());
+        beta.jira_epic = Some(" ".to_string());
+        projects.add_milestone(&beta).unwrap();
+        projects
+            .add_project_document(&ProjectDocument::new(
+                apollo.id,
+                "PRD".to_string(),
+                "https://docs.example.com/prd".to_string(),
+            ))
+            .unwrap();
+        let gemini = Project::new("Gemini".to_string());
+        projects.create(&gemini).unwrap();
+
+        // Jira keys are left out until they can be expanded
+        let repo = LinkCheckRepository::new(&conn);
+        let urls: Vec<_> = repo.list_links(None).unwrap().into_iter().map(|l| l.url).collect();
+        assert_eq!(urls, ["https://docs.example.com/beta", "https://docs.example.com/prd"]);
+
+        let repo = LinkCheckRepository::new(&conn).with_jira_url("https://jira.example.com/browse/");
+        let links = repo.list_links(Some(&apollo.id)).unwrap();
+        assert_eq!(
+            links.iter().map(|l| (l.kind, l.label.as_str(), l.url.as_str())).collect::<Vec<_>>(),
+            [
+                (LinkKind::JiraInitiative, "Jira initiative", "https://jira.example.com/browse/PROJ-1"),
+                (LinkKind::DesignDoc, "Milestone 1: Beta", "https://docs.example.com/beta"),
+                (LinkKind::Document, "PRD", "https://docs.example.com/prd"),
+            ]
+        );
+        assert!(repo.list_links(Some(&gemini.id)).unwrap().is_empty());
+
+        // Results replace earlier ones for the same URL
+        let checked_at = Utc.with_ymd_and_hms(2025, 5, 1, 9, 0, 0).unwrap();
+        let check = |url: &str, status, http_status| LinkCheck {
+            url: url.to_string(),
+            status,
+            http_status,
+            error: None,
+            checked_at,
+        };
+        repo.save_checks(&[
+            check("https://docs.example.com/beta", LinkStatus::Ok, Some(200)),
+            check("https://docs.example.com/prd", LinkStatus::Ok, Some(200)),
+        ])
+        .unwrap();
+        repo.save_checks(&[check("https://docs.example.com/prd", LinkStatus::Dead, Some(404))])
+            .unwrap();
+
+        let report = repo.get_report(Some(&apollo.id)).unwrap();
+        assert_eq!(report[0].check, None);
+        assert_eq!(report[1].check.as_ref().unwrap().status, LinkStatus::Ok);
+        assert_eq!(
+            report[2].check,
+            Some(check("https://docs.example.com/prd", LinkStatus::Dead, Some(404)))
+        );
+    }
+}
@@ -0,0 +1,211 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Checks for problems in existing data
+//!
+//! The repositories reject these problems on write, but data written by
+//! older versions, or edited outside the application, may still have them.
+
+use super::error::Result;
+use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Columns that hold UUIDs, by table
+///
+/// `note_links.target_id` is left out because person references store an
+/// email there.
+const UUID_COLUMNS: &[(&str, &[&str])] = &[
+    ("projects", &["id"]),
+    ("milestones", &["id", "project_id", "series_id"]),
+    ("project_stakeholders", &["project_id"]),
+    ("project_resources", &["project_id"]),
+    ("milestone_resources", &["milestone_id"]),
+    ("project_notes", &["id", "project_id"]),
+    ("project_documents", &["id", "project_id"]),
+    ("milestone_notes", &["id", "milestone_id"]),
+    ("stakeholder_notes", &["id", "project_id"]),
+    ("date_history", &["entity_id"]),
+    ("project_risks", &["id", "project_id"]),
+    ("action_items", &["id", "project_id", "milestone_id", "source_note_id"]),
+    ("initiatives", &["id"]),
+    ("project_initiatives", &["project_id", "initiative_id"]),
+    ("inbox_notes", &["id"]),
+    ("blocker_history", &["project_id"]),
+    ("attachments", &["id", "note_id"]),
+    ("notified", &["milestone_id"]),
+    ("project_custom_values", &["project_id"]),
+    ("project_dependencies", &["project_id", "depends_on_project_id"]),
+    ("person_notes", &["id"]),
+    ("project_expenses", &["id", "project_id"]),
+    ("project_snapshots", &["id", "project_id"]),
+    ("milestone_checklist_items", &["id", "milestone_id"]),
+    ("one_on_ones", &["id"]),
+    ("phase_history", &["project_id"]),
+    ("note_links", &["note_id"]),
+    ("retrospectives", &["id", "project_id"]),
+    ("project_watchers", &["project_id"]),
+    ("external_refs", &["id", "entity_id"]),
+    ("decisions", &["id", "project_id", "superseded_by"]),
+    ("decision_approvers", &["decision_id"]),
+];
+
+/// A stored ID that isn't a valid UUID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidUuidRow {
+    pub table: &'static str,
+    pub column: &'static str,
+    pub rowid: i64,
+    pub value: String,
+}
+
+/// Find every ID column value that doesn't parse as a UUID
+///
+/// Rows with these values can't be loaded, so listing a table that holds one
+/// fails with an [`super::InvalidUuid`] error until the row is fixed or
+/// removed. Results are ordered by table, column and rowid.
+pub fn find_invalid_uuids(conn: &Connection) -> Result<Vec<InvalidUuidRow>> {
+    let mut invalid = Vec::new();
+    for &(table, columns) in UUID_COLUMNS {
+        for &column in columns {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT rowid, CAST({column} AS TEXT) FROM {table} WHERE {column} IS NOT NULL ORDER BY rowid"
+            ))?;
+            let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?;
+            for row in rows {
+                let (rowid, value) = row?;
+                if Uuid::parse_str(&value).is_err() {
+                    invalid.push(InvalidUuidRow {
+                        table,
+                        column,
+                        rowid,
+                        value,
+                    });
+                }
+            }
+        }
+    }
+    Ok(invalid)
+}
+
+/// Find every cycle in the people's management chains
+///
+/// Each cycle starts with the email that sorts first among its members,
+/// follows their managers upward and ends back at that email, so
+/// `[a, b, a]` means `a` reports to `b` and `b` reports to `a`. Someone
+/// who is their own manager is reported as `[a, a]`.
+pub fn find_manager_cycles(conn: &Connection) -> Result<Vec<Vec<String>>> {
+    let mut stmt =
+        conn.prepare_cached("SELECT email, manager FROM people WHERE manager IS NOT NULL")?;
+    let managers: HashMap<String, String> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let mut emails: Vec<&str> = managers.keys().map(String::as_str).collect();
+    emails.sort_unstable();
+
+    let mut visited: HashSet<&str> = HashSet::new();
+    let mut cycles = Vec::new();
+    for start in emails {
+        let mut path: Vec<&str> = Vec::new();
+        let mut current = Some(start);
+        while let Some(email) = current {
+            if visited.contains(email) {
+                break;
+            }
+            if let Some(position) = path.iter().position(|&p| p == email) {
+                let mut cycle: Vec<String> =
+                    path[position..].iter().map(|p| p.to_string()).collect();
+                let first = (0..cycle.len())
+                    .min_by_key(|&i| &cycle[i])
+                    .unwrap_or_default();
+                cycle.rotate_left(first);
+                cycle.push(cycle[0].clone());
+                cycles.push(cycle);
+                break;
+            }
+            path.push(email);
+            current = managers.get(email).map(String::as_str);
+        }
+        visited.extend(path);
+    }
+
+    Ok(cycles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{self, Person, PersonRepository, Project, ProjectRepository};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_find_manager_cycles() {
+        let conn = setup_test_db();
+        let repo = PersonRepository::new(&conn);
+        for name in ["alice", "bob", "carol", "dave", "erin", "frank"] {
+            let email = format!("{}@example.com", name);
+            repo.create(&Person::new(email, name.to_string())).unwrap();
+        }
+        assert!(find_manager_cycles(&conn).unwrap().is_empty());
+
+        // Write cycles directly, as older versions allowed
+        conn.execute_batch(
+            "UPDATE people SET manager = 'carol@example.com' WHERE email = 'bob@example.com';
+             UPDATE people SET manager = 'bob@example.com' WHERE email = 'carol@example.com';
+             UPDATE people SET manager = 'bob@example.com' WHERE email = 'alice@example.com';
+             UPDATE people SET manager = 'erin@example.com' WHERE email = 'erin@example.com';
+             UPDATE people SET manager = 'erin@example.com' WHERE email = 'frank@example.com';",
+        )
+        .unwrap();
+
+        assert_eq!(
+            find_manager_cycles(&conn).unwrap(),
+            vec![
+                vec!["bob@example.com", "carol@example.com", "bob@example.com"],
+                vec!["erin@example.com", "erin@example.com"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_invalid_uuids() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let project = Project::new("Apollo".to_string());
+        repo.create(&project).unwrap();
+        assert!(find_invalid_uuids(&conn).unwrap().is_empty());
+
+        conn.execute(
+            "INSERT INTO projects (id, name, created_at, updated_at)
+             VALUES ('not-a-uuid', 'Broken', datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+        let rowid: i64 = conn
+            .query_row("SELECT rowid FROM projects WHERE id = 'not-a-uuid'", [], |row| row.get(0))
+            .unwrap();
+
+        assert_eq!(
+            find_invalid_uuids(&conn).unwrap(),
+            vec![InvalidUuidRow {
+                table: "projects",
+                column: "id",
+                rowid,
+                value: "not-a-uuid".to_string(),
+            }]
+        );
+
+        // Loading the row reports the bad value instead of panicking
+        let err = repo.list_all().unwrap_err();
+        assert!(err.to_string().contains("not-a-uuid"), "{}", err);
+        assert!(err.to_string().contains("column id"), "{}", err);
+    }
+}
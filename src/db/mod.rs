@@ -4,23 +4,141 @@
 
 //! Database module for SQLite operations
 
+pub mod attachment_repo;
+pub mod autocomplete;
+pub mod checklist_repo;
+pub mod custom_field_repo;
+pub mod decision_repo;
+pub mod deletion;
+pub mod dependency_repo;
+pub mod error;
+pub mod external_ref_repo;
+pub mod hygiene;
+pub mod inbox_repo;
+pub mod initiative_repo;
+pub mod introspect;
+pub mod link_check_repo;
+pub mod maintenance;
 pub mod models;
+pub mod note_link_repo;
+pub mod notification_repo;
+pub mod one_on_one_repo;
+pub mod operation_repo;
+pub mod organization_repo;
 pub mod person_repo;
 pub mod project_repo;
+pub mod query;
+pub mod retrospective_repo;
+pub mod role_repo;
+pub mod saved_query_repo;
 pub mod schema;
+pub mod stats;
+pub mod sync;
 pub mod team_repo;
+pub mod user_repo;
+pub mod watcher_repo;
 
-pub use models::{Milestone, MilestoneNote, MilestoneResource, Person, Project, ProjectNote, ProjectResource, ProjectStakeholder, StakeholderNote, Team, TeamMember};
+pub use error::{Error, InvalidUuid, Result};
+pub use models::{ActionItem, ActionItemStatus, ActivityItem, ActivityKind, Attachment, AvailabilityForecast, AvailabilityStatus, Backlink, BlockerEntry, BoardColumn, BriefDecision, BriefMilestone, BriefNote, BriefStakeholder, BudgetStatus, ChecklistItem, CustomField, CustomFieldTarget, CustomFieldType, DateChange, Decision, DecisionStatus, DependencyGraph, DependencyNode, DueMilestone, EffortSummary, ExternalRef, ExternalRefTarget, FieldChange, FocusItem, FocusKind, GroupCount, InboxNote, Initiative, InitiativeProgress, LinkCheck, LinkKind, LinkReport, LinkStatus, McpOperation, Milestone, MilestoneChange, MilestoneEffort, MilestoneNote, MilestoneResource, MilestoneSlippage, NewNote, NextMilestone, NoteActivity, NoteCursor, NoteKind, NoteLink, NoteSlice, NoteSummary, NoteTarget, NoteType, NotificationKind, OneOnOne, Organization, OrganizationInvolvement, OrganizationKind, OrganizationOverview, OrganizationProject, Person, PersonDeactivation, PersonMatch, PersonAvailability, PersonNote, PersonReference, PersonSkill, PersonSuggestion, PhaseChange, PhaseDuration, PortfolioStats, Project, ProjectBrief, ProjectBundle, ProjectDashboard, ProjectDependency, ProjectDocument, ProjectExpense, ProjectLink, ProjectNote, ProjectResource, ProjectRisk, ProjectRoleAssignment, ProjectSnapshot, ProjectStakeholder, ProjectStatus, ProjectSummary, ProjectWatcher, QuarterPlan, QuarterProject, QuarterTeam, ReferenceKind, ReferenceTarget, ResourceLimitWarning, ResourceSuggestion, Retrospective, RiskLevel, RiskStatus, RoleUsage, RoleVariants, SavedQuery, SnapshotDiff, StakeholderBrief, StakeholderMatrix, StakeholderNote, SubteamPolicy, Team, TeamAssignment, TeamAvailability, TeamMember, TeamTreeNode, User, UserRole};
+pub use attachment_repo::AttachmentRepository;
+pub use checklist_repo::ChecklistRepository;
+pub use custom_field_repo::CustomFieldRepository;
+pub use decision_repo::DecisionRepository;
+pub use dependency_repo::DependencyRepository;
+pub use external_ref_repo::ExternalRefRepository;
+pub use inbox_repo::InboxRepository;
+pub use initiative_repo::InitiativeRepository;
+pub use link_check_repo::LinkCheckRepository;
+pub use note_link_repo::NoteLinkRepository;
+pub use notification_repo::NotificationRepository;
+pub use one_on_one_repo::OneOnOneRepository;
+pub use operation_repo::OperationRepository;
+pub use organization_repo::OrganizationRepository;
 pub use person_repo::PersonRepository;
 pub use project_repo::ProjectRepository;
+pub use retrospective_repo::RetrospectiveRepository;
+pub use role_repo::RoleRepository;
+pub use saved_query_repo::SavedQueryRepository;
 pub use team_repo::TeamRepository;
+pub use user_repo::UserRepository;
+pub use watcher_repo::WatcherRepository;
 
-use anyhow::{Context, Result};
-use rusqlite::Connection;
-use std::path::Path;
+use crate::utils::dt_from_db;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use rusqlite::{types::Type, Connection, Row};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Read a timestamp column, accepting any format `dt_from_db` understands
+pub(crate) fn get_datetime(row: &Row, idx: usize) -> rusqlite::Result<DateTime<Utc>> {
+    let value: String = row.get(idx)?;
+    dt_from_db(&value).map_err(|e| rusqlite::Error::FromSqlConversionFailure(idx, Type::Text, e.into()))
+}
+
+/// Read a nullable timestamp column, accepting any format `dt_from_db` understands
+pub(crate) fn get_opt_datetime(row: &Row, idx: usize) -> rusqlite::Result<Option<DateTime<Utc>>> {
+    row.get::<_, Option<String>>(idx)?
+        .map(|value| {
+            dt_from_db(&value).map_err(|e| rusqlite::Error::FromSqlConversionFailure(idx, Type::Text, e.into()))
+        })
+        .transpose()
+}
+
+/// Read a UUID column
+///
+/// A value that isn't a UUID fails the read with an [`InvalidUuid`] naming
+/// the column and value, so one corrupted row fails the call that reads it
+/// instead of panicking.
+pub(crate) fn get_uuid(row: &Row, idx: usize) -> rusqlite::Result<Uuid> {
+    let value: String = row.get(idx)?;
+    parse_uuid_column(row, idx, value)
+}
+
+/// Read a nullable UUID column, failing like [`get_uuid`] on a value that isn't a UUID
+pub(crate) fn get_opt_uuid(row: &Row, idx: usize) -> rusqlite::Result<Option<Uuid>> {
+    row.get::<_, Option<String>>(idx)?
+        .map(|value| parse_uuid_column(row, idx, value))
+        .transpose()
+}
+
+fn parse_uuid_column(row: &Row, idx: usize, value: String) -> rusqlite::Result<Uuid> {
+    Uuid::parse_str(&value).map_err(|_| {
+        let column = row
+            .as_ref()
+            .column_name(idx)
+            .map_or_else(|_| idx.to_string(), str::to_string);
+        rusqlite::Error::FromSqlConversionFailure(idx, Type::Text, Box::new(InvalidUuid { column, value }))
+    })
+}
+
+/// Start a transaction, or join the one the caller already started
+///
+/// Returns `None` inside a transaction; the caller's commit or rollback then
+/// covers the work.
+pub(crate) fn begin_or_join(conn: &Connection) -> rusqlite::Result<Option<rusqlite::Transaction<'_>>> {
+    if conn.is_autocommit() {
+        conn.unchecked_transaction().map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Build a `LIKE ... ESCAPE '\'` pattern matching values that start with `prefix`
+pub(crate) fn like_prefix(prefix: &str) -> String {
+    let mut pattern = String::with_capacity(prefix.len() + 1);
+    for c in prefix.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
 
 /// Open or create a database connection
-pub fn open_database<P: AsRef<Path>>(path: P) -> Result<Connection> {
+pub fn open_database<P: AsRef<Path>>(path: P) -> anyhow::Result<Connection> {
     let path = path.as_ref();
 
     // Create parent directory if it doesn't exist
@@ -35,6 +153,18 @@ pub fn open_database<P: AsRef<Path>>(path: P) -> Result<Connection> {
     // Enable foreign keys
     conn.execute("PRAGMA foreign_keys = ON", [])?;
 
+    // Keep every repository statement in the prepared statement cache
+    conn.set_prepared_statement_cache_capacity(128);
+
+    // Refuse databases from newer builds, and back up older ones before migrating them
+    if let Some(version) = schema::read_schema_version(&conn)? {
+        schema::ensure_supported(version)
+            .with_context(|| format!("Cannot open database: {}", path.display()))?;
+        if version < schema::SUPPORTED_SCHEMA_VERSION && !is_in_memory(path) {
+            backup_before_migration(&conn, path, schema::SUPPORTED_SCHEMA_VERSION)?;
+        }
+    }
+
     // Initialize schema
     schema::initialize_schema(&conn)?;
 
@@ -45,6 +175,35 @@ pub fn open_database<P: AsRef<Path>>(path: P) -> Result<Connection> {
     Ok(conn)
 }
 
+fn is_in_memory(path: &Path) -> bool {
+    path.as_os_str().is_empty() || path.as_os_str() == ":memory:"
+}
+
+/// Path of the backup taken before migrating `path` to schema `version`
+pub fn backup_path(path: &Path, version: i32) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".pre-v{}.bak", version));
+    path.with_file_name(name)
+}
+
+/// Copy the database to `<name>.pre-v{version}.bak` before migrating it
+///
+/// An existing backup is kept: it was taken before an earlier attempt at the
+/// same migration, so it's the last copy known to be untouched.
+fn backup_before_migration(conn: &Connection, path: &Path, version: i32) -> anyhow::Result<()> {
+    let backup = backup_path(path, version);
+    if backup.exists() {
+        log::info!("Keeping existing database backup: {}", backup.display());
+        return Ok(());
+    }
+
+    // VACUUM INTO writes a consistent copy even while the database is open
+    conn.execute("VACUUM INTO ?1", [backup.to_string_lossy().to_string()])
+        .with_context(|| format!("Failed to back up database to {}", backup.display()))?;
+    log::info!("Backed up database to {} before migrating", backup.display());
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -65,6 +224,54 @@ mod tests {
 
         // Verify schema exists and migrations applied
         let version = schema::get_schema_version(&conn).unwrap();
-        assert_eq!(version, 5); // Current version after all migrations
+        assert_eq!(version, 53); // Current version after all migrations
+    }
+
+    #[test]
+    fn test_open_database_backs_up_before_migrating() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+
+        // A brand new database has nothing to back up
+        drop(open_database(&db_path).unwrap());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        // A database from an older build is backed up before it is migrated
+        let old_path = dir.path().join("old.db");
+        let conn = Connection::open(&old_path).unwrap();
+        schema::initialize_schema(&conn).unwrap();
+        drop(conn);
+
+        drop(open_database(&old_path).unwrap());
+        let backup = backup_path(&old_path, schema::SUPPORTED_SCHEMA_VERSION);
+        assert_eq!(backup.file_name().unwrap(), format!("old.db.pre-v{}.bak", schema::SUPPORTED_SCHEMA_VERSION).as_str());
+        let backed_up = Connection::open(&backup).unwrap();
+        assert_eq!(schema::get_schema_version(&backed_up).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_open_database_refuses_newer_schema() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+
+        let conn = open_database(&db_path).unwrap();
+        conn.execute(
+            "INSERT INTO schema_version (version, applied_at) VALUES (?1, datetime('now'))",
+            [schema::SUPPORTED_SCHEMA_VERSION + 1],
+        )
+        .unwrap();
+        drop(conn);
+
+        let err = open_database(&db_path).unwrap_err();
+        let message = format!("{:#}", err);
+        assert!(message.contains("Cannot open database"), "{}", message);
+        assert!(message.contains("upgrade Project Tracker"), "{}", message);
+
+        // The database is left untouched
+        let conn = Connection::open(&db_path).unwrap();
+        assert_eq!(
+            schema::get_schema_version(&conn).unwrap(),
+            schema::SUPPORTED_SCHEMA_VERSION + 1
+        );
     }
 }
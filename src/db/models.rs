@@ -2,8 +2,12 @@
 //
 // SPDX-License-Identifier: MIT
 
+use super::error::Error;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
 use uuid::Uuid;
 
 /// Represents a person in the system
@@ -18,17 +22,52 @@ pub struct Person {
     /// Team name
     pub team: Option<String>,
 
+    /// Organization the person works for, such as a vendor or client
+    #[serde(default)]
+    pub organization: Option<String>,
+
     /// Manager's email address
     pub manager: Option<String>,
 
     /// Additional notes
+    ///
+    /// Deprecated: kept readable for records written before person notes
+    /// existed. New notes should be added as `PersonNote`s instead.
     pub notes: Option<String>,
 
+    /// Whether the person is still active (inactive people are hidden from pickers)
+    #[serde(default = "default_active")]
+    pub active: bool,
+
+    /// Whether the person is a sanctioned exception to `allowed_email_domains`,
+    /// such as a consultant using their own company's address
+    #[serde(default)]
+    pub external: bool,
+
+    /// Avatar thumbnail file, relative to the data directory
+    #[serde(default)]
+    pub avatar_path: Option<String>,
+
+    /// What the person knows, sorted by skill name
+    ///
+    /// Read-only on the person; managed with `PersonRepository::add_skill`
+    /// and `remove_skill`.
+    #[serde(default)]
+    pub skills: Vec<PersonSkill>,
+
     /// Creation timestamp
     pub created_at: DateTime<Utc>,
 
     /// Last update timestamp
     pub updated_at: DateTime<Utc>,
+
+    /// Version bumped by every update, for optimistic concurrency (0 if the client didn't send one)
+    #[serde(default)]
+    pub version: i64,
+}
+
+fn default_active() -> bool {
+    true
 }
 
 impl Person {
@@ -39,14 +78,220 @@ impl Person {
             email,
             name,
             team: None,
+            organization: None,
             manager: None,
             notes: None,
+            active: true,
+            external: false,
+            avatar_path: None,
+            skills: Vec::new(),
+            created_at: now,
+            updated_at: now,
+            version: 1,
+        }
+    }
+}
+
+/// A skill a person has, for staffing searches
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersonSkill {
+    /// Skill name, lowercase
+    pub skill: String,
+
+    /// Proficiency from 1 (beginner) to 5 (expert), if rated
+    pub level: Option<u8>,
+
+    /// When the skill was first recorded
+    pub created_at: DateTime<Utc>,
+}
+
+/// Represents a timestamped note about a person
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersonNote {
+    /// Unique identifier
+    pub id: Uuid,
+
+    /// Email of the person the note is about
+    pub person_email: String,
+
+    /// Note title
+    pub title: String,
+
+    /// Note body/content
+    pub body: String,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+
+    /// Version bumped by every update, for optimistic concurrency (0 if the client didn't send one)
+    #[serde(default)]
+    pub version: i64,
+}
+
+impl PersonNote {
+    /// Create a new person note
+    pub fn new(person_email: String, title: String, body: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            person_email,
+            title,
+            body,
+            created_at: now,
+            updated_at: now,
+            version: 1,
+        }
+    }
+}
+
+/// A 1:1 meeting with a person: what was on the agenda, what was
+/// discussed and what was agreed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OneOnOne {
+    /// Unique identifier
+    pub id: Uuid,
+
+    /// Email of the person the 1:1 was with
+    pub person_email: String,
+
+    /// When the 1:1 took place
+    pub occurred_at: DateTime<Utc>,
+
+    /// Topics planned for the meeting
+    pub agenda: Option<String>,
+
+    /// What was discussed
+    pub notes: Option<String>,
+
+    /// Follow-ups agreed in the meeting, one per line
+    pub followups: Option<String>,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+}
+
+impl OneOnOne {
+    /// Create a new 1:1 record
+    pub fn new(person_email: String, occurred_at: DateTime<Utc>) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            person_email,
+            occurred_at,
+            agenda: None,
+            notes: None,
+            followups: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// The follow-ups as separate items, without list markers or blank lines
+    pub fn followup_items(&self) -> Vec<String> {
+        self.followups
+            .as_deref()
+            .unwrap_or_default()
+            .lines()
+            .map(|line| line.trim().trim_start_matches(['-', '*']).trim())
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// A look back at a finished project: what went well, what didn't and what
+/// to do differently next time
+///
+/// A project has at most one retrospective.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Retrospective {
+    /// Unique identifier
+    pub id: Uuid,
+
+    /// Project the retrospective is for
+    pub project_id: Uuid,
+
+    /// What went well
+    pub went_well: Option<String>,
+
+    /// What needs improvement
+    pub needs_improvement: Option<String>,
+
+    /// Actions agreed for future projects
+    pub action_summary: Option<String>,
+
+    /// When the retrospective was held
+    pub completed_on: DateTime<Utc>,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Retrospective {
+    /// Create a new, empty retrospective for a project
+    pub fn new(project_id: Uuid, completed_on: DateTime<Utc>) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            project_id,
+            went_well: None,
+            needs_improvement: None,
+            action_summary: None,
+            completed_on,
             created_at: now,
             updated_at: now,
         }
     }
 }
 
+/// Someone who follows a project
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectWatcher {
+    /// Project being watched
+    pub project_id: Uuid,
+
+    /// Email of the person watching it
+    pub watcher_email: String,
+
+    /// When they started watching
+    pub created_at: DateTime<Utc>,
+}
+
+/// A person offered by autocomplete, without the rest of their record
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersonSuggestion {
+    /// Email address
+    pub email: String,
+
+    /// Person's name
+    pub name: String,
+
+    /// Team name
+    pub team: Option<String>,
+}
+
+/// An existing person whose name closely matches one being added
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersonMatch {
+    /// Email address
+    pub email: String,
+
+    /// Person's name
+    pub name: String,
+
+    /// How alike the names are, from 0.0 to 1.0
+    pub similarity: f64,
+}
+
 /// Represents a team in the system
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Team {
@@ -59,6 +304,10 @@ pub struct Team {
     /// Manager's email address
     pub manager: Option<String>,
 
+    /// Name of the team this one belongs to, if any
+    #[serde(default)]
+    pub parent_team: Option<String>,
+
     /// Creation timestamp
     pub created_at: DateTime<Utc>,
 
@@ -74,12 +323,33 @@ impl Team {
             name,
             description: None,
             manager: None,
+            parent_team: None,
             created_at: now,
             updated_at: now,
         }
     }
 }
 
+/// A team and its sub-teams, for rendering the org tree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamTreeNode {
+    #[serde(flatten)]
+    pub team: Team,
+
+    /// Direct sub-teams, sorted by name
+    pub subteams: Vec<TeamTreeNode>,
+}
+
+/// What to do with a team's sub-teams when it is deleted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubteamPolicy {
+    /// Refuse to delete a team that still has sub-teams
+    Refuse,
+    /// Turn the sub-teams into top-level teams
+    Detach,
+}
+
 /// Represents a team member (junction table between teams and people)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TeamMember {
@@ -140,11 +410,49 @@ pub struct Milestone {
     /// Jira epic ticket number (e.g., "PROJ-123")
     pub jira_epic: Option<String>,
 
+    /// Recurrence rule (RRULE subset, e.g., "FREQ=MONTHLY;COUNT=12")
+    #[serde(default)]
+    pub recurrence_rule: Option<String>,
+
+    /// Milestone defining the recurring series this occurrence belongs to
+    #[serde(default)]
+    pub series_id: Option<Uuid>,
+
+    /// Position of this occurrence within its series (the series milestone is 0)
+    #[serde(default)]
+    pub series_index: Option<i32>,
+
+    /// Planned effort in days
+    #[serde(default)]
+    pub estimated_days: Option<f64>,
+
+    /// Effort actually spent in days
+    #[serde(default)]
+    pub actual_days: Option<f64>,
+
     /// Creation timestamp
     pub created_at: DateTime<Utc>,
 
     /// Last update timestamp
     pub updated_at: DateTime<Utc>,
+
+    /// Version bumped by every update, for optimistic concurrency (0 if the client didn't send one)
+    #[serde(default)]
+    pub version: i64,
+
+    /// Checklist items in order, filled in when milestones are loaded
+    #[serde(default)]
+    pub checklist: Vec<ChecklistItem>,
+
+    /// Share of the checklist that is checked, from 0.0 to 1.0, if it has any items
+    #[serde(default)]
+    pub checklist_completion: Option<f64>,
+
+    /// Tickets in other trackers, including `jira_epic`, filled in when milestones are loaded
+    ///
+    /// Manage them with `ExternalRefRepository`; milestone updates leave them alone.
+    #[serde(default)]
+    pub external_refs: Vec<ExternalRef>,
 }
 
 impl Milestone {
@@ -163,10 +471,46 @@ impl Milestone {
             start_date: None,
             due_date: None,
             jira_epic: None,
+            recurrence_rule: None,
+            series_id: None,
+            series_index: None,
+            estimated_days: None,
+            actual_days: None,
             created_at: now,
             updated_at: now,
+            version: 1,
+            checklist: Vec::new(),
+            checklist_completion: None,
+            external_refs: Vec::new(),
+        }
+    }
+
+    /// Set the checklist, and its completion to match
+    pub fn set_checklist(&mut self, items: Vec<ChecklistItem>) {
+        self.checklist_completion = (!items.is_empty())
+            .then(|| items.iter().filter(|item| item.checked).count() as f64 / items.len() as f64);
+        self.checklist = items;
+    }
+
+    /// Whether the actual effort is more than `ratio` times the estimate
+    pub fn exceeds_estimate(&self, ratio: f64) -> bool {
+        match (self.estimated_days, self.actual_days) {
+            (Some(estimated), Some(actual)) => actual > estimated * ratio,
+            _ => false,
         }
     }
+
+    /// A warning for responses when the milestone exceeds its estimate by more than `ratio`
+    pub fn effort_warning(&self, ratio: f64) -> Option<String> {
+        self.exceeds_estimate(ratio).then(|| {
+            format!(
+                "Milestone '{}' took {} days against an estimate of {}",
+                self.name,
+                self.actual_days.unwrap_or_default(),
+                self.estimated_days.unwrap_or_default()
+            )
+        })
+    }
 }
 
 /// Represents a project
@@ -178,260 +522,2942 @@ pub struct Project {
     /// Project name
     pub name: String,
 
+    /// Short unique identifier made from the name, such as `apollo-migration`
+    ///
+    /// Set when the project is created and kept when it is renamed; change
+    /// it with `ProjectRepository::set_slug`.
+    #[serde(default)]
+    pub slug: String,
+
     /// Project description
     pub description: Option<String>,
 
-    /// Project type (Team, Company, Personal)
-    #[serde(rename = "type")]
-    pub project_type: String,
+    /// Project type (Team, Company, Personal)
+    #[serde(rename = "type")]
+    pub project_type: String,
+
+    /// Requirements owner email
+    pub requirements_owner: Option<String>,
+
+    /// Technical lead email
+    pub technical_lead: Option<String>,
+
+    /// Manager email
+    pub manager: Option<String>,
+
+    /// Team assigned to this project
+    pub team: Option<String>,
+
+    /// Start date
+    pub start_date: Option<DateTime<Utc>>,
+
+    /// Due date
+    pub due_date: Option<DateTime<Utc>>,
+
+    /// Jira initiative ticket number (e.g., "PROJ-123")
+    pub jira_initiative: Option<String>,
+
+    /// Whether the project is blocked
+    #[serde(default)]
+    pub blocked: bool,
+
+    /// Why the project is blocked; ignored unless `blocked` is set
+    #[serde(default)]
+    pub blocked_reason: Option<String>,
+
+    /// Budget for the project, in `budget_currency`
+    #[serde(default)]
+    pub budget_amount: Option<f64>,
+
+    /// ISO 4217 currency code of the budget and expenses (e.g., "USD")
+    #[serde(default)]
+    pub budget_currency: Option<String>,
+
+    /// Where the project is in its life, which is its column on the board
+    #[serde(default)]
+    pub status: ProjectStatus,
+
+    /// Lifecycle phase, one of the configured `project_phases` (e.g., Discovery, Build)
+    ///
+    /// Separate from `status`; every change is recorded in the phase
+    /// history (see `ProjectRepository::get_phase_history`).
+    #[serde(default)]
+    pub phase: Option<String>,
+
+    /// Order of the project within its board column, lowest first
+    ///
+    /// `None` until the project is placed with
+    /// `ProjectRepository::reorder_project`; unplaced projects come after
+    /// the placed ones. Project updates leave it alone.
+    #[serde(default)]
+    pub board_position: Option<i64>,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+
+    /// Version bumped by every update, for optimistic concurrency (0 if the client didn't send one)
+    #[serde(default)]
+    pub version: i64,
+
+    /// Values of custom fields by key, filled in by `find_by_id`
+    ///
+    /// Set them with `CustomFieldRepository::set_custom_value`; project
+    /// updates leave them alone.
+    #[serde(default)]
+    pub custom_fields: BTreeMap<String, String>,
+
+    /// Tickets in other trackers, including `jira_initiative`, filled in by `find_by_id`
+    ///
+    /// Manage them with `ExternalRefRepository`; project updates leave them alone.
+    #[serde(default)]
+    pub external_refs: Vec<ExternalRef>,
+}
+
+impl Project {
+    /// Create a new project
+    pub fn new(name: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            slug: crate::utils::slugify(&name),
+            name,
+            description: None,
+            project_type: "Personal".to_string(),
+            requirements_owner: None,
+            technical_lead: None,
+            manager: None,
+            team: None,
+            start_date: None,
+            due_date: None,
+            jira_initiative: None,
+            blocked: false,
+            blocked_reason: None,
+            budget_amount: None,
+            budget_currency: None,
+            status: ProjectStatus::default(),
+            phase: None,
+            board_position: None,
+            created_at: now,
+            updated_at: now,
+            version: 1,
+            custom_fields: BTreeMap::new(),
+            external_refs: Vec::new(),
+        }
+    }
+}
+
+/// Where a project is in its life
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectStatus {
+    Planning,
+    #[default]
+    Active,
+    OnHold,
+    Done,
+}
+
+impl ProjectStatus {
+    /// Every status, in board column order
+    pub const ALL: [ProjectStatus; 4] = [
+        ProjectStatus::Planning,
+        ProjectStatus::Active,
+        ProjectStatus::OnHold,
+        ProjectStatus::Done,
+    ];
+
+    /// Name used in the database and in JSON
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProjectStatus::Planning => "planning",
+            ProjectStatus::Active => "active",
+            ProjectStatus::OnHold => "on_hold",
+            ProjectStatus::Done => "done",
+        }
+    }
+}
+
+impl FromStr for ProjectStatus {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().replace([' ', '-'], "_").as_str() {
+            "planning" => Ok(ProjectStatus::Planning),
+            "active" => Ok(ProjectStatus::Active),
+            "on_hold" => Ok(ProjectStatus::OnHold),
+            "done" => Ok(ProjectStatus::Done),
+            _ => Err(Error::Invalid(format!(
+                "Invalid project status '{}': expected planning, active, on_hold or done",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for ProjectStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A board column: the projects with one status, in board order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoardColumn {
+    /// Status of the projects in the column
+    pub status: ProjectStatus,
+
+    /// The projects, ordered by `board_position`
+    pub projects: Vec<Project>,
+}
+
+/// A period during which a project was blocked
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockerEntry {
+    /// Project ID
+    pub project_id: Uuid,
+
+    /// Why the project was blocked
+    pub reason: Option<String>,
+
+    /// When the project was blocked
+    pub blocked_at: DateTime<Utc>,
+
+    /// When the blocker was cleared, or `None` if the project is still blocked
+    pub cleared_at: Option<DateTime<Utc>>,
+}
+
+/// A change to a project's phase
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseChange {
+    /// Project ID
+    pub project_id: Uuid,
+
+    /// Phase before the change, or `None` if the project had none
+    pub from_phase: Option<String>,
+
+    /// Phase after the change, or `None` if it was cleared
+    pub to_phase: Option<String>,
+
+    /// Whether the project moved to an earlier phase than the one it left
+    ///
+    /// Going back is allowed, but flagged so reports can point it out.
+    pub backwards: bool,
+
+    /// When the phase changed
+    pub changed_at: DateTime<Utc>,
+}
+
+/// How long a project has spent in one phase
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhaseDuration {
+    /// Phase name
+    pub phase: String,
+
+    /// Whole days spent in the phase, over every time the project was in it
+    pub days: i64,
+
+    /// Whether the project is in this phase now
+    pub current: bool,
+}
+
+/// Money spent on a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectExpense {
+    /// Unique identifier
+    pub id: Uuid,
+
+    /// Project ID
+    pub project_id: Uuid,
+
+    /// What the money was spent on
+    pub description: String,
+
+    /// Amount spent, in the project's budget currency
+    pub amount: f64,
+
+    /// When the expense was incurred
+    pub incurred_at: DateTime<Utc>,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+}
+
+impl ProjectExpense {
+    /// Create a new expense incurred at `incurred_at`
+    pub fn new(project_id: Uuid, description: String, amount: f64, incurred_at: DateTime<Utc>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            project_id,
+            description,
+            amount,
+            incurred_at,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// A project's budget compared with what has been spent
+///
+/// Amounts are in `budget_currency`; expenses are never converted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BudgetStatus {
+    /// Project ID
+    pub project_id: Uuid,
+
+    /// The budget, or `None` if the project has none
+    pub budget_amount: Option<f64>,
+
+    /// Currency code of the budget, returned as stored
+    pub budget_currency: Option<String>,
+
+    /// Sum of all expenses
+    pub total_spent: f64,
+
+    /// Budget left after expenses (negative when over budget), or `None` without a budget
+    pub remaining: Option<f64>,
+}
+
+/// A project with its milestones, resources, stakeholders, retrospective and decisions
+///
+/// This is what a snapshot stores, serialized as JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectBundle {
+    /// The project, with its custom field values
+    pub project: Project,
+
+    /// Milestones in number order
+    pub milestones: Vec<Milestone>,
+
+    /// People assigned to the project
+    #[serde(default)]
+    pub resources: Vec<ProjectResource>,
+
+    /// Stakeholders and their roles
+    #[serde(default)]
+    pub stakeholders: Vec<ProjectStakeholder>,
+
+    /// The project's retrospective, once one has been held
+    #[serde(default)]
+    pub retrospective: Option<Retrospective>,
+
+    /// Decisions, oldest first
+    #[serde(default)]
+    pub decisions: Vec<Decision>,
+}
+
+/// A saved copy of a project's plan at one point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectSnapshot {
+    /// Unique identifier
+    pub id: Uuid,
+
+    /// Project ID
+    pub project_id: Uuid,
+
+    /// Label given when the snapshot was taken, e.g. "Q3 start"
+    pub label: String,
+
+    /// When the snapshot was taken
+    pub created_at: DateTime<Utc>,
+}
+
+/// One field whose value differs between two snapshots
+///
+/// Fields are named as in the JSON form of the record; custom fields are
+/// named `custom_fields.<key>`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    /// Field name, such as `due_date`
+    pub field: String,
+
+    /// Value in the older snapshot (`null` if unset)
+    pub old: serde_json::Value,
+
+    /// Value in the newer snapshot (`null` if unset)
+    pub new: serde_json::Value,
+}
+
+/// A milestone found in both snapshots with different fields
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MilestoneChange {
+    /// Milestone ID
+    pub milestone_id: Uuid,
+
+    /// Milestone name in the newer snapshot
+    pub name: String,
+
+    /// Changed fields, by field name
+    pub changes: Vec<FieldChange>,
+}
+
+/// What changed in a project between two snapshots
+///
+/// Every list is sorted, so comparing the same snapshots always gives the
+/// same result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotDiff {
+    /// The snapshot compared from
+    pub from: ProjectSnapshot,
+
+    /// The snapshot compared to
+    pub to: ProjectSnapshot,
+
+    /// Changed project fields, by field name
+    pub project_changes: Vec<FieldChange>,
+
+    /// Milestones only in `to`, by number
+    pub added_milestones: Vec<Milestone>,
+
+    /// Milestones only in `from`, by number
+    pub removed_milestones: Vec<Milestone>,
+
+    /// Milestones in both with changed fields, by number in `to`
+    pub changed_milestones: Vec<MilestoneChange>,
+}
+
+impl SnapshotDiff {
+    /// Whether nothing changed between the snapshots
+    pub fn is_empty(&self) -> bool {
+        self.project_changes.is_empty()
+            && self.added_milestones.is_empty()
+            && self.removed_milestones.is_empty()
+            && self.changed_milestones.is_empty()
+    }
+}
+
+/// What kind of change an activity feed item records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    NoteAdded,
+    MilestoneCreated,
+    MilestoneCompleted,
+    ResourceAdded,
+    DateChanged,
+    Blocked,
+    Unblocked,
+}
+
+/// One entry in a project's recent activity feed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityItem {
+    /// What happened
+    pub kind: ActivityKind,
+
+    /// One-line description, e.g. "Note added: Kickoff"
+    pub summary: String,
+
+    /// Email of the person the change concerns, when there is one
+    pub actor: Option<String>,
+
+    /// When it happened
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Represents a project stakeholder relationship
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectStakeholder {
+    /// Project ID
+    pub project_id: Uuid,
+
+    /// Stakeholder email
+    pub stakeholder_email: String,
+
+    /// Stakeholder role/relationship to project
+    pub role: Option<String>,
+
+    /// How much the stakeholder can affect the project, from 1 (little) to 5 (a lot)
+    pub influence: Option<i32>,
+
+    /// How much the project affects the stakeholder, from 1 (little) to 5 (a lot)
+    pub interest: Option<i32>,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+}
+
+impl ProjectStakeholder {
+    /// Create a new project stakeholder relationship
+    pub fn new(project_id: Uuid, stakeholder_email: String) -> Self {
+        Self {
+            project_id,
+            stakeholder_email,
+            role: None,
+            influence: None,
+            interest: None,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Represents a project resource (person working on project)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectResource {
+    /// Project ID
+    pub project_id: Uuid,
+
+    /// Person's email address
+    pub person_email: String,
+
+    /// Person's role/responsibilities on this project
+    pub role: Option<String>,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+}
+
+impl ProjectResource {
+    /// Create a new project resource relationship
+    pub fn new(project_id: Uuid, person_email: String) -> Self {
+        Self {
+            project_id,
+            person_email,
+            role: None,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Represents a milestone resource (person working on milestone)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MilestoneResource {
+    /// Milestone ID
+    pub milestone_id: Uuid,
+
+    /// Person's email address
+    pub person_email: String,
+
+    /// Person's role/responsibilities on this milestone
+    pub role: Option<String>,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+}
+
+impl MilestoneResource {
+    /// Create a new milestone resource relationship
+    pub fn new(milestone_id: Uuid, person_email: String) -> Self {
+        Self {
+            milestone_id,
+            person_email,
+            role: None,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// A small sub-task on a milestone's checklist, such as "security review"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChecklistItem {
+    /// Unique identifier
+    pub id: Uuid,
+
+    /// Milestone the item belongs to
+    pub milestone_id: Uuid,
+
+    /// Position in the checklist, starting at 1
+    pub position: i32,
+
+    /// What needs doing
+    pub text: String,
+
+    /// Whether it's done
+    pub checked: bool,
+
+    /// When it was last checked off, if it's checked
+    pub checked_at: Option<DateTime<Utc>>,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+}
+
+/// A milestone left with more resources than `max_resources_per_milestone` allows
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceLimitWarning {
+    /// Milestone ID
+    pub milestone_id: Uuid,
+
+    /// Number of resources on the milestone
+    pub resources: usize,
+
+    /// Most resources the config allows on a milestone
+    pub limit: usize,
+}
+
+impl fmt::Display for ResourceLimitWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Milestone now has {} resources, more than the {} allowed by max_resources_per_milestone",
+            self.resources, self.limit
+        )
+    }
+}
+
+/// How many resource and stakeholder assignments use a role
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoleUsage {
+    /// Role as stored
+    pub role: String,
+
+    /// Number of project resources, milestone resources and stakeholders with the role
+    pub uses: usize,
+}
+
+/// Spellings in use for what is the same role apart from case, spacing and punctuation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoleVariants {
+    /// Spelling new assignments are stored with
+    pub canonical: String,
+
+    /// Every spelling in use, the canonical one included, most used first
+    pub variants: Vec<RoleUsage>,
+}
+
+/// Represents a note attached to a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectNote {
+    /// Unique identifier
+    pub id: Uuid,
+
+    /// Project this note belongs to
+    pub project_id: Uuid,
+
+    /// Note title
+    pub title: String,
+
+    /// Note body/content
+    pub body: String,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+
+    /// Version bumped by every update, for optimistic concurrency (0 if the client didn't send one)
+    #[serde(default)]
+    pub version: i64,
+
+    /// Whether the body can be rewritten or only appended to
+    #[serde(default)]
+    pub kind: NoteKind,
+
+    /// Files attached to the note
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+}
+
+impl ProjectNote {
+    /// Create a new project note
+    pub fn new(project_id: Uuid, title: String, body: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            project_id,
+            title,
+            body,
+            created_at: now,
+            updated_at: now,
+            version: 1,
+            kind: NoteKind::Freeform,
+            attachments: Vec::new(),
+        }
+    }
+}
+
+/// Whether a project note can be edited freely or is an append-only log
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteKind {
+    /// The body can be rewritten
+    #[default]
+    Freeform,
+    /// Entries can only be added to the end, each under a timestamped heading
+    Log,
+}
+
+impl NoteKind {
+    /// Name used in the database and in JSON
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NoteKind::Freeform => "freeform",
+            NoteKind::Log => "log",
+        }
+    }
+}
+
+impl FromStr for NoteKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "freeform" => Ok(NoteKind::Freeform),
+            "log" => Ok(NoteKind::Log),
+            _ => Err(Error::Invalid(format!("Invalid note kind '{}': expected freeform or log", s))),
+        }
+    }
+}
+
+impl fmt::Display for NoteKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Represents a document linked from a project (design doc, PRD, runbook, ...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectDocument {
+    /// Unique identifier
+    pub id: Uuid,
+
+    /// Project this document belongs to
+    pub project_id: Uuid,
+
+    /// Document title
+    pub title: String,
+
+    /// Link to the document (http or https)
+    pub url: String,
+
+    /// Kind of document (e.g., "Design Doc", "PRD")
+    pub doc_type: Option<String>,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+}
+
+impl ProjectDocument {
+    /// Create a new project document
+    pub fn new(project_id: Uuid, title: String, url: String) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            project_id,
+            title,
+            url,
+            doc_type: None,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Where a link stored in the tracker comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkKind {
+    /// A milestone's design document
+    DesignDoc,
+    /// A project document
+    Document,
+    /// A project's Jira initiative
+    JiraInitiative,
+    /// A milestone's Jira epic
+    JiraEpic,
+}
+
+/// A link stored on a project or one of its milestones
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectLink {
+    /// Project the link belongs to
+    pub project_id: Uuid,
+
+    /// Name of the project
+    pub project_name: String,
+
+    /// Where the link comes from
+    pub kind: LinkKind,
+
+    /// What the link is, such as a document title or `Milestone 2: Beta`
+    pub label: String,
+
+    /// The link; the ticket key for Jira links until it is expanded with `jira_url`
+    pub url: String,
+}
+
+/// Outcome of checking a link
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkStatus {
+    /// The server answered, possibly asking to sign in
+    Ok,
+    /// The server answered with an error, such as 404 Not Found
+    Dead,
+    /// There was no answer: the request timed out, failed or was redirected too often
+    Unreachable,
+    /// The link's domain is on the skip list, so it wasn't checked
+    Skipped,
+}
+
+impl LinkStatus {
+    /// Classify an HTTP response status
+    ///
+    /// 401 and 403 count as OK: the page exists but needs signing in, which
+    /// is normal for design docs and Jira.
+    pub fn from_http(code: u16) -> Self {
+        match code {
+            0..=399 | 401 | 403 => LinkStatus::Ok,
+            _ => LinkStatus::Dead,
+        }
+    }
+
+    /// Whether the link is broken: dead or unreachable
+    pub fn is_broken(&self) -> bool {
+        matches!(self, LinkStatus::Dead | LinkStatus::Unreachable)
+    }
+
+    /// Name used in the database and in JSON
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LinkStatus::Ok => "ok",
+            LinkStatus::Dead => "dead",
+            LinkStatus::Unreachable => "unreachable",
+            LinkStatus::Skipped => "skipped",
+        }
+    }
+}
+
+impl FromStr for LinkStatus {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "ok" => Ok(LinkStatus::Ok),
+            "dead" => Ok(LinkStatus::Dead),
+            "unreachable" => Ok(LinkStatus::Unreachable),
+            "skipped" => Ok(LinkStatus::Skipped),
+            _ => Err(Error::Invalid(format!(
+                "Invalid link status '{}': expected ok, dead, unreachable or skipped",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for LinkStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The last check of a URL
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinkCheck {
+    /// URL that was checked
+    pub url: String,
+
+    /// Outcome of the check
+    pub status: LinkStatus,
+
+    /// HTTP status the server answered with, if it answered
+    pub http_status: Option<u16>,
+
+    /// Why the link couldn't be reached
+    pub error: Option<String>,
+
+    /// When the link was checked
+    pub checked_at: DateTime<Utc>,
+}
+
+/// A link along with its last check, if it has been checked
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinkReport {
+    #[serde(flatten)]
+    pub link: ProjectLink,
+
+    /// The last check of the link's URL
+    pub check: Option<LinkCheck>,
+}
+
+/// How severe or how likely a project risk is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RiskLevel {
+    Low,
+    Medium,
+    High,
+}
+
+impl RiskLevel {
+    /// Name used in the database and in JSON
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RiskLevel::Low => "low",
+            RiskLevel::Medium => "medium",
+            RiskLevel::High => "high",
+        }
+    }
+
+    /// Weight used when scoring a risk, from 1 (low) to 3 (high)
+    pub fn weight(&self) -> i32 {
+        match self {
+            RiskLevel::Low => 1,
+            RiskLevel::Medium => 2,
+            RiskLevel::High => 3,
+        }
+    }
+}
+
+impl FromStr for RiskLevel {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "low" => Ok(RiskLevel::Low),
+            "medium" => Ok(RiskLevel::Medium),
+            "high" => Ok(RiskLevel::High),
+            _ => Err(Error::Invalid(format!(
+                "Invalid risk level '{}': expected low, medium or high",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for RiskLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Where a project risk stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RiskStatus {
+    /// Identified, nothing done about it yet
+    Open,
+    /// Mitigation is under way
+    Mitigating,
+    /// No longer a concern
+    Closed,
+}
+
+impl RiskStatus {
+    /// Name used in the database and in JSON
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RiskStatus::Open => "open",
+            RiskStatus::Mitigating => "mitigating",
+            RiskStatus::Closed => "closed",
+        }
+    }
+}
+
+impl FromStr for RiskStatus {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "open" => Ok(RiskStatus::Open),
+            "mitigating" => Ok(RiskStatus::Mitigating),
+            "closed" => Ok(RiskStatus::Closed),
+            _ => Err(Error::Invalid(format!(
+                "Invalid risk status '{}': expected open, mitigating or closed",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for RiskStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Represents a risk tracked in a project's risk register
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectRisk {
+    /// Unique identifier
+    pub id: Uuid,
+
+    /// Project this risk belongs to
+    pub project_id: Uuid,
+
+    /// Short description of the risk
+    pub title: String,
+
+    /// Details of the risk
+    pub description: Option<String>,
+
+    /// How bad it would be if the risk happened
+    pub severity: RiskLevel,
+
+    /// How likely the risk is to happen
+    pub likelihood: RiskLevel,
+
+    /// Plan for reducing the risk
+    pub mitigation: Option<String>,
+
+    /// Email of the person who owns the risk
+    pub owner_email: Option<String>,
+
+    /// Current status
+    pub status: RiskStatus,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ProjectRisk {
+    /// Create a new open risk
+    pub fn new(project_id: Uuid, title: String, severity: RiskLevel, likelihood: RiskLevel) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            project_id,
+            title,
+            description: None,
+            severity,
+            likelihood,
+            mitigation: None,
+            owner_email: None,
+            status: RiskStatus::Open,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Severity weight times likelihood weight, from 1 to 9
+    pub fn score(&self) -> i32 {
+        self.severity.weight() * self.likelihood.weight()
+    }
+
+    /// Whether the risk still needs attention
+    pub fn is_open(&self) -> bool {
+        self.status != RiskStatus::Closed
+    }
+}
+
+/// Where a decision stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DecisionStatus {
+    /// Put forward, not agreed yet
+    Proposed,
+    /// Agreed and in force
+    Accepted,
+    /// Replaced by a later decision, named by `superseded_by`
+    Superseded,
+}
+
+impl DecisionStatus {
+    /// Name used in the database and in JSON
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DecisionStatus::Proposed => "proposed",
+            DecisionStatus::Accepted => "accepted",
+            DecisionStatus::Superseded => "superseded",
+        }
+    }
+}
+
+impl FromStr for DecisionStatus {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "proposed" => Ok(DecisionStatus::Proposed),
+            "accepted" => Ok(DecisionStatus::Accepted),
+            "superseded" => Ok(DecisionStatus::Superseded),
+            _ => Err(Error::Invalid(format!(
+                "Invalid decision status '{}': expected proposed, accepted or superseded",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for DecisionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A decision made on a project, with who signed off on it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Decision {
+    /// Unique identifier
+    pub id: Uuid,
+
+    /// Project the decision was made for
+    pub project_id: Uuid,
+
+    /// Short name, such as "Use the shared API gateway"
+    pub title: String,
+
+    /// The situation and options that led to the decision
+    pub context: Option<String>,
+
+    /// What was decided, or proposed
+    pub decision: String,
+
+    /// When it was agreed
+    pub decided_on: Option<DateTime<Utc>>,
+
+    /// Current status
+    pub status: DecisionStatus,
+
+    /// The decision that replaced this one
+    pub superseded_by: Option<Uuid>,
+
+    /// Emails of the people who approved it
+    #[serde(default)]
+    pub approvers: Vec<String>,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Decision {
+    /// Create a new proposed decision
+    pub fn new(project_id: Uuid, title: String, decision: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            project_id,
+            title,
+            context: None,
+            decision,
+            decided_on: None,
+            status: DecisionStatus::Proposed,
+            superseded_by: None,
+            approvers: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// What an organization is to us
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrganizationKind {
+    /// Sells us goods or services
+    Vendor,
+    /// Buys from us
+    Client,
+    /// Works with us without buying or selling
+    Partner,
+    /// Another part of our own company
+    Internal,
+}
+
+impl OrganizationKind {
+    /// Name used in the database and in JSON
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrganizationKind::Vendor => "vendor",
+            OrganizationKind::Client => "client",
+            OrganizationKind::Partner => "partner",
+            OrganizationKind::Internal => "internal",
+        }
+    }
+}
+
+impl FromStr for OrganizationKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "vendor" => Ok(OrganizationKind::Vendor),
+            "client" => Ok(OrganizationKind::Client),
+            "partner" => Ok(OrganizationKind::Partner),
+            "internal" => Ok(OrganizationKind::Internal),
+            _ => Err(Error::Invalid(format!(
+                "Invalid organization kind '{}': expected vendor, client, partner or internal",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for OrganizationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A company or group outside the team that people can belong to, such as
+/// a vendor, so they can be treated as one stakeholder group
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Organization {
+    /// Unique name
+    pub name: String,
+
+    /// What the organization is to us
+    pub kind: OrganizationKind,
+
+    /// Free-form notes, such as contract terms
+    pub notes: Option<String>,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Organization {
+    /// Create a new vendor organization
+    pub fn new(name: String) -> Self {
+        let now = Utc::now();
+        Self {
+            name,
+            kind: OrganizationKind::Vendor,
+            notes: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// How one of an organization's people is involved in a project
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrganizationInvolvement {
+    /// Person's email address
+    pub email: String,
+
+    /// Person's name
+    pub name: String,
+
+    /// "stakeholder", "resource" or "milestone resource"
+    pub relation: String,
+
+    /// Role on the project or milestone, if one was given
+    pub role: Option<String>,
+}
+
+/// A project an organization's people are involved in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrganizationProject {
+    pub project: Project,
+
+    /// Who from the organization is involved, and how
+    pub involvement: Vec<OrganizationInvolvement>,
+}
+
+/// An organization with its people and the projects they touch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrganizationOverview {
+    pub organization: Organization,
+
+    /// People in the organization, by name
+    pub people: Vec<Person>,
+
+    /// Projects the people are stakeholders or resources on, by name
+    pub projects: Vec<OrganizationProject>,
+}
+
+/// Where a meeting action item stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActionItemStatus {
+    Open,
+    Completed,
+}
+
+impl ActionItemStatus {
+    /// Name used in the database and in JSON
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActionItemStatus::Open => "open",
+            ActionItemStatus::Completed => "completed",
+        }
+    }
+}
+
+impl FromStr for ActionItemStatus {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "open" => Ok(ActionItemStatus::Open),
+            "completed" => Ok(ActionItemStatus::Completed),
+            _ => Err(Error::Invalid(format!(
+                "Invalid action item status '{}': expected open or completed",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for ActionItemStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Represents an action item agreed on in a meeting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionItem {
+    /// Unique identifier
+    pub id: Uuid,
+
+    /// Project this action item belongs to
+    pub project_id: Uuid,
+
+    /// Milestone the action item is for, if any
+    pub milestone_id: Option<Uuid>,
+
+    /// Email of the person who has to do it
+    pub assignee_email: String,
+
+    /// What needs to be done
+    pub description: String,
+
+    /// When it needs to be done by
+    pub due_date: Option<DateTime<Utc>>,
+
+    /// Current status
+    pub status: ActionItemStatus,
+
+    /// Project note the action item came from, if any; cleared when the note is deleted
+    pub source_note_id: Option<Uuid>,
+
+    /// When the action item was completed
+    pub completed_at: Option<DateTime<Utc>>,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ActionItem {
+    /// Create a new open action item
+    pub fn new(project_id: Uuid, assignee_email: String, description: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            project_id,
+            milestone_id: None,
+            assignee_email,
+            description,
+            due_date: None,
+            status: ActionItemStatus::Open,
+            source_note_id: None,
+            completed_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Whether the action item still needs doing
+    pub fn is_open(&self) -> bool {
+        self.status == ActionItemStatus::Open
+    }
+}
+
+/// Represents a note attached to a milestone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MilestoneNote {
+    /// Unique identifier
+    pub id: Uuid,
+
+    /// Milestone this note belongs to
+    pub milestone_id: Uuid,
+
+    /// Note title
+    pub title: String,
+
+    /// Note body/content
+    pub body: String,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+
+    /// Version bumped by every update, for optimistic concurrency (0 if the client didn't send one)
+    #[serde(default)]
+    pub version: i64,
+
+    /// Files attached to the note
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+}
+
+impl MilestoneNote {
+    /// Create a new milestone note
+    pub fn new(milestone_id: Uuid, title: String, body: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            milestone_id,
+            title,
+            body,
+            created_at: now,
+            updated_at: now,
+            version: 1,
+            attachments: Vec::new(),
+        }
+    }
+}
+
+/// Represents a note attached to a stakeholder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StakeholderNote {
+    /// Unique identifier
+    pub id: Uuid,
+
+    /// Project ID
+    pub project_id: Uuid,
+
+    /// Stakeholder email
+    pub stakeholder_email: String,
+
+    /// Note title
+    pub title: String,
+
+    /// Note body/content
+    pub body: String,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+
+    /// Version bumped by every update, for optimistic concurrency (0 if the client didn't send one)
+    #[serde(default)]
+    pub version: i64,
+
+    /// Files attached to the note
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+}
+
+impl StakeholderNote {
+    /// Create a new stakeholder note
+    pub fn new(project_id: Uuid, stakeholder_email: String, title: String, body: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            project_id,
+            stakeholder_email,
+            title,
+            body,
+            created_at: now,
+            updated_at: now,
+            version: 1,
+            attachments: Vec::new(),
+        }
+    }
+}
+
+/// The kind of note an attachment belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteType {
+    Project,
+    Milestone,
+    Stakeholder,
+}
+
+impl NoteType {
+    /// Name used in the database and in JSON
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NoteType::Project => "project",
+            NoteType::Milestone => "milestone",
+            NoteType::Stakeholder => "stakeholder",
+        }
+    }
+
+    /// Table the notes are stored in
+    pub fn table(&self) -> &'static str {
+        match self {
+            NoteType::Project => "project_notes",
+            NoteType::Milestone => "milestone_notes",
+            NoteType::Stakeholder => "stakeholder_notes",
+        }
+    }
+}
+
+impl FromStr for NoteType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "project" => Ok(NoteType::Project),
+            "milestone" => Ok(NoteType::Milestone),
+            "stakeholder" => Ok(NoteType::Stakeholder),
+            _ => Err(Error::Invalid(format!(
+                "Invalid note type '{}': expected project, milestone or stakeholder",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for NoteType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Part of a note's body, for reading a long note a piece at a time
+///
+/// Offsets and lengths count characters, not bytes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NoteSlice {
+    /// Note ID
+    pub id: Uuid,
+
+    /// Kind of note
+    pub note_type: NoteType,
+
+    /// Note title
+    pub title: String,
+
+    /// The requested part of the body
+    pub body: String,
+
+    /// Where `body` starts in the whole body
+    pub offset_chars: usize,
+
+    /// Length of the whole body
+    pub total_chars: usize,
+
+    /// Offset to read the rest of the body from; absent when `body` runs to the end
+    pub next_offset: Option<usize>,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+
+    /// Version number for optimistic locking
+    pub version: i64,
+}
+
+/// A note's title, opening and size, without the rest of the body
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NoteSummary {
+    /// Note ID
+    pub id: Uuid,
+
+    /// Kind of note
+    pub note_type: NoteType,
+
+    /// Note title
+    pub title: String,
+
+    /// The first characters of the body
+    pub excerpt: String,
+
+    /// Length of the whole body in characters
+    pub total_chars: usize,
+
+    /// Number of whitespace-separated words in the body
+    pub word_count: usize,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A file attached to a note
+///
+/// The bytes live in the data directory under `attachments/<sha256>`, so
+/// identical files attached to several notes are stored once.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attachment {
+    /// Unique identifier
+    pub id: Uuid,
+
+    /// Kind of note the file is attached to
+    pub note_type: NoteType,
+
+    /// Note the file is attached to
+    pub note_id: Uuid,
+
+    /// Original file name, without any directories
+    pub filename: String,
+
+    /// MIME type given when the file was uploaded
+    pub mime_type: String,
+
+    /// File size in bytes
+    pub size_bytes: i64,
+
+    /// Hex SHA-256 of the contents, which names the stored file
+    pub sha256: String,
+
+    /// Upload timestamp
+    pub created_at: DateTime<Utc>,
+}
+
+impl Attachment {
+    /// Create a new attachment for a stored file
+    pub fn new(note_type: NoteType, note_id: Uuid, filename: String, mime_type: String, size_bytes: i64, sha256: String) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            note_type,
+            note_id,
+            filename,
+            mime_type,
+            size_bytes,
+            sha256,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// The kind of record a `[[kind:target]]` reference in a note body points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReferenceKind {
+    Project,
+    Person,
+    Milestone,
+}
+
+impl ReferenceKind {
+    /// Name used in reference tokens, in the database and in JSON
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReferenceKind::Project => "project",
+            ReferenceKind::Person => "person",
+            ReferenceKind::Milestone => "milestone",
+        }
+    }
+}
+
+impl fmt::Display for ReferenceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ReferenceKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "project" => Ok(ReferenceKind::Project),
+            "person" => Ok(ReferenceKind::Person),
+            "milestone" => Ok(ReferenceKind::Milestone),
+            _ => Err(Error::Invalid(format!(
+                "Invalid reference kind '{}': expected project, person or milestone",
+                s
+            ))),
+        }
+    }
+}
+
+/// A reference from a note body to a project, person or milestone
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoteLink {
+    /// Kind of note the reference is in
+    pub note_type: NoteType,
+
+    /// Note the reference is in
+    pub note_id: Uuid,
+
+    /// Kind of record referenced
+    pub target_type: ReferenceKind,
+
+    /// Target as written in the note: a UUID, slug or email
+    pub target: String,
+
+    /// UUID or email of the record the reference resolved to, if it did
+    pub target_id: Option<String>,
+
+    /// Whether the reference didn't resolve when the note was saved
+    pub dangling: bool,
+}
+
+/// The record a note reference resolved to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReferenceTarget {
+    pub kind: ReferenceKind,
+
+    /// UUID of the project or milestone, or the person's email
+    pub id: String,
+
+    /// Name to show for the record
+    pub name: String,
+
+    /// Project the record belongs to; a project's own ID, and none for a person
+    pub project_id: Option<Uuid>,
+}
+
+/// A note that references a record
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Backlink {
+    /// Kind of note
+    pub note_type: NoteType,
+
+    /// Note ID
+    pub note_id: Uuid,
+
+    /// Note title
+    pub title: String,
+
+    /// Project the note belongs to, directly or through its milestone
+    pub project_id: Uuid,
+
+    /// Name of that project
+    pub project_name: String,
+
+    /// When the note was last changed
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The point in a milestone's schedule a reminder is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// Due within the next few days
+    Upcoming,
+    /// Due today
+    DueToday,
+    /// Past its due date
+    Overdue,
+}
+
+impl NotificationKind {
+    /// Name used in the database and in JSON
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationKind::Upcoming => "upcoming",
+            NotificationKind::DueToday => "due_today",
+            NotificationKind::Overdue => "overdue",
+        }
+    }
+}
+
+impl FromStr for NotificationKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "upcoming" => Ok(NotificationKind::Upcoming),
+            "due_today" => Ok(NotificationKind::DueToday),
+            "overdue" => Ok(NotificationKind::Overdue),
+            _ => Err(Error::Invalid(format!(
+                "Invalid notification kind '{}': expected upcoming, due_today or overdue",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for NotificationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A milestone with a due date, and the project it belongs to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DueMilestone {
+    /// Milestone ID
+    pub milestone_id: Uuid,
+
+    /// Milestone name
+    pub milestone_name: String,
+
+    /// Project ID
+    pub project_id: Uuid,
+
+    /// Project name
+    pub project_name: String,
+
+    /// Due date
+    pub due_date: DateTime<Utc>,
+}
+
+/// The type of value a custom field holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomFieldType {
+    /// Any text
+    Text,
+    /// A number, stored in its shortest form (`1.50` is stored as `1.5`)
+    Number,
+    /// A date, stored as `YYYY-MM-DD`
+    Date,
+    /// `true` or `false`
+    Bool,
+}
+
+impl CustomFieldType {
+    /// Name used in the database and in JSON
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CustomFieldType::Text => "text",
+            CustomFieldType::Number => "number",
+            CustomFieldType::Date => "date",
+            CustomFieldType::Bool => "bool",
+        }
+    }
+}
+
+impl FromStr for CustomFieldType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "text" => Ok(CustomFieldType::Text),
+            "number" => Ok(CustomFieldType::Number),
+            "date" => Ok(CustomFieldType::Date),
+            "bool" => Ok(CustomFieldType::Bool),
+            _ => Err(Error::Invalid(format!(
+                "Invalid custom field type '{}': expected text, number, date or bool",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for CustomFieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The kind of record a custom field can be set on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomFieldTarget {
+    Project,
+}
+
+impl CustomFieldTarget {
+    /// Name used in the database and in JSON
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CustomFieldTarget::Project => "project",
+        }
+    }
+}
+
+impl FromStr for CustomFieldTarget {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "project" => Ok(CustomFieldTarget::Project),
+            _ => Err(Error::Invalid(format!(
+                "Invalid custom field target '{}': expected project",
+                s
+            ))),
+        }
+    }
+}
+
+/// An extra field defined by the user, such as a cost center or customer name
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomField {
+    /// Identifier used to set and read values, such as `cost_center`
+    pub key: String,
+
+    /// Name shown to people
+    pub label: String,
+
+    /// Type of value the field holds
+    pub field_type: CustomFieldType,
+
+    /// Kind of record the field is set on
+    pub applies_to: CustomFieldTarget,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The kind of record an external ticket reference is attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExternalRefTarget {
+    Project,
+    Milestone,
+}
+
+impl ExternalRefTarget {
+    /// Name used in the database and in JSON
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExternalRefTarget::Project => "project",
+            ExternalRefTarget::Milestone => "milestone",
+        }
+    }
+}
+
+impl FromStr for ExternalRefTarget {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "project" => Ok(ExternalRefTarget::Project),
+            "milestone" => Ok(ExternalRefTarget::Milestone),
+            _ => Err(Error::Invalid(format!(
+                "Invalid external reference target '{}': expected project or milestone",
+                s
+            ))),
+        }
+    }
+}
+
+/// A ticket in another tracker, such as a Jira epic or a GitHub issue, linked
+/// to a project or milestone
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalRef {
+    /// Unique identifier; `None` for a reference read from a project's
+    /// `jira_initiative` or a milestone's `jira_epic`
+    pub id: Option<Uuid>,
+
+    /// Kind of record the reference is attached to
+    pub entity_type: ExternalRefTarget,
+
+    /// ID of the project or milestone
+    pub entity_id: Uuid,
+
+    /// Tracker the ticket lives in, lowercase, such as "jira", "github" or "linear"
+    pub system: String,
+
+    /// Ticket key in that tracker, such as "PROJ-123" or "owner/repo#42"
+    pub key: String,
+
+    /// Link to the ticket, if one was given or the system has a URL template
+    pub url: Option<String>,
+
+    /// When the reference was added; the record's creation time for one
+    /// read from the Jira fields
+    pub created_at: DateTime<Utc>,
+}
+
+/// A note captured before deciding what it belongs to
+///
+/// Inbox notes are triaged later into project, milestone or stakeholder
+/// notes, keeping their ID and timestamps.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InboxNote {
+    /// Unique identifier
+    pub id: Uuid,
+
+    /// Note title
+    pub title: String,
+
+    /// Note body/content
+    pub body: String,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+}
+
+impl InboxNote {
+    /// Create a new inbox note
+    pub fn new(title: String, body: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            title,
+            body,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// A named filter expression, run with `db::query::run_query`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedQuery {
+    /// Name the query is saved under
+    pub name: String,
+
+    /// Filter expression
+    pub expression: String,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A mutating MCP tool call recorded under its idempotency key
+///
+/// Replaying the same key with the same request returns `response` instead
+/// of running the tool again.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct McpOperation {
+    /// Key chosen by the client for the call and its retries
+    pub idempotency_key: String,
+
+    /// Tool that was called
+    pub tool: String,
+
+    /// SHA-256 of the tool name and its arguments, as hex
+    pub request_hash: String,
+
+    /// The tool's result as JSON
+    pub response: String,
+
+    /// When the call was made
+    pub created_at: DateTime<Utc>,
+}
+
+/// What a user of a shared database may do, from least to most
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UserRole {
+    /// Can read everything but change nothing
+    Viewer,
+    /// Can change records, but not delete projects or manage users
+    Editor,
+    /// Can do everything
+    Admin,
+}
+
+impl UserRole {
+    /// Name used in the database and in JSON
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserRole::Viewer => "viewer",
+            UserRole::Editor => "editor",
+            UserRole::Admin => "admin",
+        }
+    }
+}
+
+impl FromStr for UserRole {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "viewer" => Ok(UserRole::Viewer),
+            "editor" => Ok(UserRole::Editor),
+            "admin" => Ok(UserRole::Admin),
+            _ => Err(Error::Invalid(format!("Invalid role '{}': expected viewer, editor or admin", s))),
+        }
+    }
+}
+
+impl fmt::Display for UserRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Someone allowed to use a shared database, identified by their current_user_email
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct User {
+    /// Email, matched without regard to case
+    pub email: String,
+
+    /// What they may do
+    pub role: UserRole,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Position in a newest-first note listing: the creation time and ID of the
+/// last note on the previous page
+///
+/// Notes created in the same instant are ordered by ID, so every note has a
+/// distinct position and pages never overlap or skip notes. Clients see the
+/// cursor as an opaque string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoteCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl NoteCursor {
+    pub fn new(created_at: DateTime<Utc>, id: Uuid) -> Self {
+        Self { created_at, id }
+    }
+}
+
+impl fmt::Display for NoteCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}_{}", self.created_at.timestamp_nanos_opt().unwrap_or_default(), self.id.simple())
+    }
+}
+
+impl FromStr for NoteCursor {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::Invalid(format!("Invalid note cursor '{}'", s));
+        let (nanos, id) = s.split_once('_').ok_or_else(invalid)?;
+        let nanos: i64 = nanos.parse().map_err(|_| invalid())?;
+        let id = Uuid::parse_str(id).map_err(|_| invalid())?;
+        Ok(Self::new(DateTime::from_timestamp_nanos(nanos), id))
+    }
+}
+
+/// What a note is attached to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NoteTarget {
+    /// A project
+    Project { project_id: Uuid },
+
+    /// A milestone
+    Milestone { milestone_id: Uuid },
+
+    /// A stakeholder on a project
+    Stakeholder {
+        project_id: Uuid,
+        stakeholder_email: String,
+    },
+}
+
+/// A note to create as part of a batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewNote {
+    /// What the note is attached to
+    pub target: NoteTarget,
+
+    /// Note title
+    pub title: String,
+
+    /// Note body/content
+    pub body: String,
+}
+
+/// A project together with the collections shown on the dashboard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectDashboard {
+    /// The project itself
+    #[serde(flatten)]
+    pub project: Project,
+
+    /// Project milestones, ordered by number
+    pub milestones: Vec<Milestone>,
+
+    /// People working on the project
+    pub resources: Vec<ProjectResource>,
+
+    /// Project stakeholders
+    pub stakeholders: Vec<ProjectStakeholder>,
+}
+
+/// Progress summary for a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectSummary {
+    /// Project ID
+    pub project_id: Uuid,
+
+    /// Project name
+    pub project_name: String,
+
+    /// Number of milestones in the project
+    pub total_milestones: usize,
+
+    /// Number of milestones considered complete
+    pub completed_milestones: usize,
+
+    /// Whether `completed_milestones` is estimated for any milestone
+    ///
+    /// Milestones don't record a completion status, so a milestone counts as
+    /// complete once its due date has passed, unless checklist progress is
+    /// counted and it has a checklist.
+    pub completion_estimated: bool,
+
+    /// Milestone progress as a percentage of the total, if there are any
+    ///
+    /// When checklist progress is counted, a partly checked milestone counts
+    /// for its share of checked items.
+    pub progress_percent: Option<u8>,
+
+    /// The next milestone that isn't due yet
+    pub next_milestone: Option<NextMilestone>,
+
+    /// Number of project notes written in the last 30 days
+    pub recent_notes: usize,
+
+    /// The project's current phase
+    #[serde(default)]
+    pub phase: Option<String>,
+
+    /// Time spent in each phase, in the order the project first entered them
+    #[serde(default)]
+    pub phase_durations: Vec<PhaseDuration>,
+}
+
+/// Represents a company initiative or OKR that projects contribute to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Initiative {
+    /// Unique identifier
+    pub id: Uuid,
+
+    /// Initiative name
+    pub name: String,
+
+    /// Initiative description
+    pub description: Option<String>,
+
+    /// Quarter the initiative is planned for, as "YYYY-Qn"
+    pub quarter: String,
+
+    /// Email of the person who owns the initiative
+    pub owner_email: Option<String>,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Initiative {
+    /// Create a new initiative
+    pub fn new(name: String, quarter: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            name,
+            description: None,
+            quarter,
+            owner_email: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Parse a quarter such as "2025-Q3" or "2025-q3", returning it as "YYYY-Qn"
+    ///
+    /// Quarters in this form sort chronologically as plain strings.
+    pub fn parse_quarter(input: &str) -> Result<String, Error> {
+        let quarter = input.trim().to_ascii_uppercase();
+        let valid = match quarter.split_once("-Q") {
+            Some((year, n)) => {
+                year.len() == 4
+                    && year.bytes().all(|b| b.is_ascii_digit())
+                    && matches!(n, "1" | "2" | "3" | "4")
+            }
+            None => false,
+        };
+        if !valid {
+            return Err(Error::Invalid(format!(
+                "Invalid quarter '{}': expected YYYY-Qn, e.g. 2025-Q3",
+                input
+            )));
+        }
+        Ok(quarter)
+    }
+}
+
+/// How the projects linked to an initiative are doing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InitiativeProgress {
+    /// The initiative
+    pub initiative: Initiative,
+
+    /// Progress summary of each linked project, ordered by project name
+    pub projects: Vec<ProjectSummary>,
+
+    /// Number of milestones across the linked projects
+    pub total_milestones: usize,
+
+    /// Number of those milestones considered complete (estimated from due dates)
+    pub completed_milestones: usize,
+
+    /// Completed milestones as a percentage of the total, if there are any
+    pub progress_percent: Option<u8>,
+
+    /// Number of open high risks across the linked projects
+    pub open_high_risks: usize,
+}
+
+/// The upcoming milestone shown in a project summary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NextMilestone {
+    /// Milestone ID
+    pub id: Uuid,
+
+    /// Milestone name
+    pub name: String,
+
+    /// Milestone due date
+    pub due_date: DateTime<Utc>,
+}
+
+/// A recorded change to a project or milestone date
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DateChange {
+    /// Kind of record that changed ("project" or "milestone")
+    pub entity_type: String,
+
+    /// ID of the project or milestone
+    pub entity_id: Uuid,
+
+    /// Field that changed ("start_date" or "due_date")
+    pub field: String,
+
+    /// Value before the change
+    pub old_value: Option<DateTime<Utc>>,
+
+    /// Value after the change
+    pub new_value: Option<DateTime<Utc>>,
+
+    /// When the change was made
+    pub changed_at: DateTime<Utc>,
+}
+
+/// How often and how far a milestone's due date has slipped
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MilestoneSlippage {
+    /// Milestone ID
+    pub milestone_id: Uuid,
+
+    /// Milestone number
+    pub number: i32,
+
+    /// Milestone name
+    pub name: String,
+
+    /// Current due date
+    pub due_date: Option<DateTime<Utc>>,
+
+    /// Number of times the due date moved later
+    pub slip_count: usize,
+
+    /// Total days the due date moved later, summed over every slip
+    pub total_slip_days: i64,
+}
+
+/// Estimated against actual effort for one milestone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MilestoneEffort {
+    /// Milestone ID
+    pub milestone_id: Uuid,
+
+    /// Milestone number
+    pub number: i32,
+
+    /// Milestone name
+    pub name: String,
+
+    /// Planned effort in days
+    pub estimated_days: Option<f64>,
+
+    /// Effort actually spent in days
+    pub actual_days: Option<f64>,
+
+    /// Actual minus estimated days, when both are recorded
+    pub variance_days: Option<f64>,
+
+    /// Whether the actual effort is more than the warning ratio times the estimate
+    pub over_estimate: bool,
+}
+
+/// Estimated against actual effort across a project's milestones
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffortSummary {
+    /// Project ID
+    pub project_id: Uuid,
+
+    /// Sum of every milestone's estimate
+    pub total_estimated_days: f64,
+
+    /// Sum of every milestone's actual effort
+    pub total_actual_days: f64,
+
+    /// Sum of the variances of milestones that have both an estimate and an actual
+    pub variance_days: f64,
+
+    /// Milestones, in number order unless sorted by variance
+    pub milestones: Vec<MilestoneEffort>,
+}
+
+impl EffortSummary {
+    /// Put the milestones that overran their estimate the most first, and
+    /// those without both an estimate and an actual last
+    pub fn sort_by_variance(&mut self) {
+        self.milestones.sort_by(|a, b| match (a.variance_days, b.variance_days) {
+            (Some(a), Some(b)) => b.total_cmp(&a),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+    }
+}
+
+/// Milestones due in a calendar quarter, grouped by team and project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarterPlan {
+    /// Year
+    pub year: i32,
+
+    /// Quarter, 1 to 4
+    pub quarter: u32,
+
+    /// Start of the quarter
+    pub start: DateTime<Utc>,
+
+    /// Start of the next quarter
+    pub end: DateTime<Utc>,
+
+    /// Number of milestones in the plan
+    pub milestone_count: usize,
+
+    /// Teams by name, with the milestones that have no team last
+    pub teams: Vec<QuarterTeam>,
+}
+
+/// A team's milestones in a quarter plan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarterTeam {
+    /// Team name, or None for the milestones that have no team
+    pub team: Option<String>,
+
+    /// Number of milestones across the team's projects
+    pub milestone_count: usize,
+
+    /// Projects by name
+    pub projects: Vec<QuarterProject>,
+}
+
+/// A project's milestones in a quarter plan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarterProject {
+    /// Project ID
+    pub project_id: Uuid,
+
+    /// Project name
+    pub project_name: String,
+
+    /// Milestones by due date
+    pub milestones: Vec<Milestone>,
+}
+
+/// A compact reference card for a project
+///
+/// Holds only what's needed to talk about the project: no descriptions,
+/// note bodies or bookkeeping timestamps.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectBrief {
+    /// Project ID
+    pub id: Uuid,
+
+    /// Project name
+    pub name: String,
+
+    /// Short name used in place of the ID
+    pub slug: String,
+
+    /// Project type
+    pub project_type: String,
+
+    /// Board status
+    pub status: ProjectStatus,
+
+    /// Workflow phase
+    pub phase: Option<String>,
+
+    /// Start date
+    pub start_date: Option<DateTime<Utc>>,
+
+    /// Due date
+    pub due_date: Option<DateTime<Utc>>,
+
+    /// Why the project is blocked, if it is
+    pub blocked_reason: Option<String>,
+
+    /// Team responsible for the project
+    pub team: Option<String>,
+
+    /// Manager's email
+    pub manager: Option<String>,
+
+    /// Technical lead's email
+    pub technical_lead: Option<String>,
+
+    /// Requirements owner's email
+    pub requirements_owner: Option<String>,
+
+    /// Stakeholders by email
+    pub stakeholders: Vec<BriefStakeholder>,
+
+    /// Milestones in number order
+    pub milestones: Vec<BriefMilestone>,
+
+    /// Number of milestones the project has, which is more than
+    /// `milestones.len()` when the brief was cut down to fit
+    pub milestone_count: usize,
+
+    /// Risks that aren't closed
+    pub open_risks: usize,
+
+    /// Action items that aren't completed
+    pub open_action_items: usize,
+
+    /// Decisions in force, oldest first
+    #[serde(default)]
+    pub decisions: Vec<BriefDecision>,
+
+    /// The most recent project notes, newest first
+    pub recent_notes: Vec<BriefNote>,
+}
+
+/// A stakeholder line in a project brief
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BriefStakeholder {
+    /// Stakeholder email
+    pub email: String,
+
+    /// Name from the people directory
+    pub name: String,
+
+    /// Role on the project
+    pub role: Option<String>,
+}
+
+/// A milestone line in a project brief
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BriefMilestone {
+    /// Milestone number
+    pub number: i32,
+
+    /// Milestone name
+    pub name: String,
+
+    /// Due date
+    pub due_date: Option<DateTime<Utc>>,
+
+    /// Whether the milestone is complete, which like the progress summaries
+    /// means its due date has passed
+    pub done: bool,
+}
+
+/// An accepted decision in a project brief
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BriefDecision {
+    /// Decision title
+    pub title: String,
+
+    /// When it was agreed
+    pub decided_on: Option<DateTime<Utc>>,
+
+    /// Emails of the people who approved it
+    pub approvers: Vec<String>,
+}
+
+/// A note title in a project brief
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BriefNote {
+    /// Note title
+    pub title: String,
+
+    /// When the note was written
+    pub created_at: DateTime<Utc>,
+}
+
+/// Why something is on a focus list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FocusKind {
+    /// A milestone the user leads is past its due date with work left
+    OverdueMilestone,
+    /// An action item assigned to the user is due soon or overdue
+    ActionItemDue,
+    /// A stakeholder on one of the user's projects hasn't been heard from
+    StakeholderFollowUp,
+    /// A project the user manages is blocked
+    BlockedProject,
+    /// A project the user manages or leads is missing a lead, owner or due date
+    MissingFields,
+}
+
+/// One entry of a user's focus list, most urgent first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FocusItem {
+    /// Why the item is listed
+    pub kind: FocusKind,
+
+    /// Urgency from [`focus_score`](super::project_repo::focus_score); higher comes first
+    pub score: i32,
+
+    /// Project the item belongs to
+    pub project_id: Uuid,
+
+    /// Project name
+    pub project_name: String,
+
+    /// Record to open, as `kind:id`: `project:<id>`, `milestone:<id>`,
+    /// `action_item:<id>` or `person:<email>`
+    pub link: String,
+
+    /// What the item is, such as the milestone or action item
+    pub title: String,
+
+    /// Why it needs attention now
+    pub reason: String,
+
+    /// The date the item is measured against: the due date, or the last
+    /// contact with a stakeholder
+    pub date: Option<DateTime<Utc>>,
+}
+
+/// When someone is expected to be free of their current milestones
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AvailabilityStatus {
+    /// Not assigned to any open milestone
+    Unassigned,
+    /// Their last open milestone is due within the horizon
+    FreesUp,
+    /// Still assigned to a milestone due after the horizon
+    Busy,
+    /// Only assigned to milestones without a due date
+    Unknown,
+}
+
+/// One person's place in an availability forecast
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersonAvailability {
+    /// Person's email
+    pub email: String,
+
+    /// Person's name
+    pub name: String,
+
+    /// Team the person is on
+    pub team: Option<String>,
+
+    /// Which bucket the person falls in
+    pub status: AvailabilityStatus,
+
+    /// Due date of their last open milestone with a due date
+    pub free_on: Option<DateTime<Utc>>,
+
+    /// Open milestones they are assigned to
+    pub open_milestones: usize,
+
+    /// How many of those have no due date
+    pub undated_milestones: usize,
+}
+
+/// The people on a team, in an availability forecast
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TeamAvailability {
+    /// Team name; `None` for people without a team
+    pub team: Option<String>,
+
+    /// Unassigned people first, then by the date they free up, with unknown ones last
+    pub people: Vec<PersonAvailability>,
+}
+
+/// When people roll off their current milestones, by team
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AvailabilityForecast {
+    /// Days ahead the forecast looks
+    pub horizon_days: u32,
+
+    /// End of the horizon
+    pub until: DateTime<Utc>,
+
+    /// People with no open milestones
+    pub unassigned: usize,
+
+    /// People whose last dated milestone is due within the horizon
+    pub freeing_up: usize,
+
+    /// People assigned past the horizon
+    pub busy: usize,
+
+    /// People only assigned to undated milestones
+    pub unknown: usize,
+
+    /// Active people by team, teams by name with people without a team last
+    pub teams: Vec<TeamAvailability>,
+}
+
+/// What to know about a stakeholder before a meeting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StakeholderBrief {
+    /// Project ID
+    pub project_id: Uuid,
+
+    /// Project name
+    pub project_name: String,
+
+    /// The stakeholder's relationship to the project
+    pub stakeholder: ProjectStakeholder,
+
+    /// The stakeholder's person record
+    pub person: Person,
+
+    /// The organization the stakeholder belongs to, if any
+    #[serde(default)]
+    pub organization: Option<Organization>,
 
-    /// Requirements owner email
-    pub requirements_owner: Option<String>,
+    /// Most recent notes about the stakeholder, newest first
+    pub recent_notes: Vec<StakeholderNote>,
 
-    /// Technical lead email
-    pub technical_lead: Option<String>,
+    /// Unchecked task list items (`- [ ] ...`) from any of the stakeholder's notes, newest note first
+    pub open_questions: Vec<String>,
 
-    /// Manager email
-    pub manager: Option<String>,
+    /// Decisions on the project the stakeholder approved, oldest first
+    #[serde(default)]
+    pub approved_decisions: Vec<Decision>,
+}
 
-    /// Team assigned to this project
-    pub team: Option<String>,
+/// A project's stakeholders sorted into the quadrants of an influence/interest grid
+///
+/// Ratings of 4 or 5 count as high. Each quadrant is sorted by influence,
+/// then interest, highest first. Stakeholders missing either rating are
+/// listed under `unrated`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StakeholderMatrix {
+    /// Project ID
+    pub project_id: Uuid,
 
-    /// Start date
-    pub start_date: Option<DateTime<Utc>>,
+    /// Project name
+    pub project_name: String,
 
-    /// Due date
-    pub due_date: Option<DateTime<Utc>>,
+    /// High influence, high interest
+    pub manage_closely: Vec<ProjectStakeholder>,
 
-    /// Jira initiative ticket number (e.g., "PROJ-123")
-    pub jira_initiative: Option<String>,
+    /// High influence, low interest
+    pub keep_satisfied: Vec<ProjectStakeholder>,
 
-    /// Creation timestamp
-    pub created_at: DateTime<Utc>,
+    /// Low influence, high interest
+    pub keep_informed: Vec<ProjectStakeholder>,
 
-    /// Last update timestamp
-    pub updated_at: DateTime<Utc>,
-}
+    /// Low influence, low interest
+    pub monitor: Vec<ProjectStakeholder>,
 
-impl Project {
-    /// Create a new project
-    pub fn new(name: String) -> Self {
-        let now = Utc::now();
-        Self {
-            id: Uuid::new_v4(),
-            name,
-            description: None,
-            project_type: "Personal".to_string(),
-            requirements_owner: None,
-            technical_lead: None,
-            manager: None,
-            team: None,
-            start_date: None,
-            due_date: None,
-            jira_initiative: None,
-            created_at: now,
-            updated_at: now,
-        }
-    }
+    /// Stakeholders without both ratings
+    pub unrated: Vec<ProjectStakeholder>,
 }
 
-/// Represents a project stakeholder relationship
+/// Notes written about a project, and its milestones and stakeholders, over a period
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ProjectStakeholder {
+pub struct NoteActivity {
     /// Project ID
     pub project_id: Uuid,
 
-    /// Stakeholder email
-    pub stakeholder_email: String,
+    /// Project name
+    pub project_name: String,
 
-    /// Stakeholder role/relationship to project
-    pub role: Option<String>,
+    /// Number of notes created in the period
+    pub note_count: usize,
 
-    /// Creation timestamp
-    pub created_at: DateTime<Utc>,
+    /// When the newest of them was created
+    pub last_note_at: DateTime<Utc>,
 }
 
-impl ProjectStakeholder {
-    /// Create a new project stakeholder relationship
-    pub fn new(project_id: Uuid, stakeholder_email: String) -> Self {
-        Self {
-            project_id,
-            stakeholder_email,
-            role: None,
-            created_at: Utc::now(),
-        }
-    }
+/// A person suggested for a milestone, best candidates first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceSuggestion {
+    /// Person's email address
+    pub email: String,
+
+    /// Person's name
+    pub name: String,
+
+    /// Whether they are on the milestone's team (or the project's, if the milestone has none)
+    pub team_match: bool,
+
+    /// Whether they are already a resource on the milestone's project
+    pub on_project: bool,
+
+    /// Whether they have the skill the suggestions were asked for
+    #[serde(default)]
+    pub has_skill: bool,
+
+    /// Their level in that skill, if rated
+    #[serde(default)]
+    pub skill_level: Option<u8>,
+
+    /// Other milestones they are assigned to that overlap this one's dates,
+    /// or that aren't past due yet when this milestone has no dates
+    pub concurrent_milestones: usize,
+
+    /// Why they were suggested, such as "On Platform, already on the project, 1 overlapping milestone"
+    pub reason: String,
 }
 
-/// Represents a project resource (person working on project)
+/// Result of assigning a team's members to a project
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ProjectResource {
+pub struct TeamAssignment {
+    /// Emails of the members added as project resources
+    pub added: Vec<String>,
+
+    /// Emails of the members skipped because they were already assigned or are inactive
+    pub skipped: Vec<String>,
+}
+
+/// A project role (technical lead, manager or requirements owner) held by a person
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectRoleAssignment {
     /// Project ID
     pub project_id: Uuid,
 
-    /// Person's email address
-    pub person_email: String,
+    /// Project name
+    pub project_name: String,
 
-    /// Person's role/responsibilities on this project
-    pub role: Option<String>,
+    /// Role held on the project ("technical_lead", "manager" or "requirements_owner")
+    pub role: String,
+}
 
-    /// Creation timestamp
-    pub created_at: DateTime<Utc>,
+/// A record that refers to a person as its lead, manager or owner
+///
+/// These references keep the person from being deleted until someone else
+/// takes over.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersonReference {
+    /// Kind of record: "person", "team", "project", "milestone", "risk", "action item" or "initiative"
+    pub entity: String,
+
+    /// ID, email or name the record is identified by
+    pub id: String,
+
+    /// Name of the record; milestones are prefixed with their project's name
+    pub name: String,
+
+    /// How the record refers to the person, such as "technical_lead"
+    pub role: String,
 }
 
-impl ProjectResource {
-    /// Create a new project resource relationship
-    pub fn new(project_id: Uuid, person_email: String) -> Self {
-        Self {
-            project_id,
-            person_email,
-            role: None,
-            created_at: Utc::now(),
-        }
+impl fmt::Display for PersonReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} of {} \"{}\"", self.role.replace('_', " "), self.entity, self.name)
     }
 }
 
-/// Represents a milestone resource (person working on milestone)
+/// Result of deactivating a person
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MilestoneResource {
-    /// Milestone ID
-    pub milestone_id: Uuid,
-
-    /// Person's email address
-    pub person_email: String,
+pub struct PersonDeactivation {
+    /// The deactivated person
+    pub person: Person,
 
-    /// Person's role/responsibilities on this milestone
-    pub role: Option<String>,
+    /// Project roles the person still holds and that should be reassigned
+    pub project_roles: Vec<ProjectRoleAssignment>,
 
-    /// Creation timestamp
-    pub created_at: DateTime<Utc>,
+    /// Number of future milestone assignments that were removed
+    pub removed_milestone_assignments: usize,
 }
 
-impl MilestoneResource {
-    /// Create a new milestone resource relationship
-    pub fn new(milestone_id: Uuid, person_email: String) -> Self {
-        Self {
-            milestone_id,
-            person_email,
-            role: None,
-            created_at: Utc::now(),
-        }
-    }
+/// Number of projects sharing a type or team
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupCount {
+    /// Project type or team name, `None` for projects without a team
+    pub name: Option<String>,
+
+    /// Number of projects in the group
+    pub count: usize,
 }
 
-/// Represents a note attached to a project
+/// Portfolio-wide statistics across every project
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ProjectNote {
-    /// Unique identifier
-    pub id: Uuid,
+pub struct PortfolioStats {
+    /// When the statistics were computed
+    pub as_of: DateTime<Utc>,
 
-    /// Project this note belongs to
-    pub project_id: Uuid,
+    /// Total number of projects
+    pub total_projects: usize,
 
-    /// Note title
-    pub title: String,
+    /// Project counts by project type, ordered by type
+    pub projects_by_type: Vec<GroupCount>,
 
-    /// Note body/content
-    pub body: String,
+    /// Project counts by team, ordered by team name with unassigned projects last
+    pub projects_by_team: Vec<GroupCount>,
 
-    /// Creation timestamp
-    pub created_at: DateTime<Utc>,
+    /// Total number of milestones
+    pub total_milestones: usize,
 
-    /// Last update timestamp
-    pub updated_at: DateTime<Utc>,
-}
+    /// Milestones due in the current calendar month (UTC)
+    pub milestones_due_this_month: usize,
 
-impl ProjectNote {
-    /// Create a new project note
-    pub fn new(project_id: Uuid, title: String, body: String) -> Self {
-        let now = Utc::now();
-        Self {
-            id: Uuid::new_v4(),
-            project_id,
-            title,
-            body,
-            created_at: now,
-            updated_at: now,
-        }
-    }
-}
+    /// Milestones due in the current calendar quarter (UTC)
+    pub milestones_due_this_quarter: usize,
 
-/// Represents a note attached to a milestone
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MilestoneNote {
-    /// Unique identifier
-    pub id: Uuid,
+    /// Active people without any project or milestone role or assignment
+    pub unassigned_people: usize,
 
-    /// Milestone this note belongs to
-    pub milestone_id: Uuid,
+    /// Teams with no manager set
+    pub teams_without_manager: usize,
 
-    /// Note title
-    pub title: String,
+    /// Project, milestone and stakeholder notes created in the last 7 days
+    pub notes_last_7_days: usize,
 
-    /// Note body/content
-    pub body: String,
+    /// Average number of milestones per project, 0 when there are no projects
+    pub average_milestones_per_project: f64,
+}
 
-    /// Creation timestamp
-    pub created_at: DateTime<Utc>,
+/// A project that can't finish before another project does
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectDependency {
+    /// The project that waits
+    pub project_id: Uuid,
 
-    /// Last update timestamp
-    pub updated_at: DateTime<Utc>,
+    /// The project it waits for
+    pub depends_on_project_id: Uuid,
+
+    /// Why it depends on the other project
+    pub note: Option<String>,
+
+    /// When the dependency was added
+    pub created_at: DateTime<Utc>,
 }
 
-impl MilestoneNote {
-    /// Create a new milestone note
-    pub fn new(milestone_id: Uuid, title: String, body: String) -> Self {
-        let now = Utc::now();
+impl ProjectDependency {
+    /// Create a new dependency of `project_id` on `depends_on_project_id`
+    pub fn new(project_id: Uuid, depends_on_project_id: Uuid) -> Self {
         Self {
-            id: Uuid::new_v4(),
-            milestone_id,
-            title,
-            body,
-            created_at: now,
-            updated_at: now,
+            project_id,
+            depends_on_project_id,
+            note: None,
+            created_at: Utc::now(),
         }
     }
 }
 
-/// Represents a note attached to a stakeholder
+/// A project in the dependency graph
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct StakeholderNote {
-    /// Unique identifier
-    pub id: Uuid,
-
+pub struct DependencyNode {
     /// Project ID
     pub project_id: Uuid,
 
-    /// Stakeholder email
-    pub stakeholder_email: String,
-
-    /// Note title
-    pub title: String,
-
-    /// Note body/content
-    pub body: String,
+    /// Project name
+    pub project_name: String,
 
-    /// Creation timestamp
-    pub created_at: DateTime<Utc>,
+    /// Whether the project is blocked
+    pub blocked: bool,
 
-    /// Last update timestamp
-    pub updated_at: DateTime<Utc>,
+    /// Whether a project it depends on, directly or further up, is blocked
+    pub upstream_blocked: bool,
 }
 
-impl StakeholderNote {
-    /// Create a new stakeholder note
-    pub fn new(project_id: Uuid, stakeholder_email: String, title: String, body: String) -> Self {
-        let now = Utc::now();
-        Self {
-            id: Uuid::new_v4(),
-            project_id,
-            stakeholder_email,
-            title,
-            body,
-            created_at: now,
-            updated_at: now,
-        }
-    }
+/// Every dependency between projects in the portfolio
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyGraph {
+    /// Projects with at least one dependency or dependent, ordered by name
+    pub nodes: Vec<DependencyNode>,
+
+    /// Dependencies, each pointing from the waiting project to the one it waits for
+    pub edges: Vec<ProjectDependency>,
 }
 
 #[cfg(test)]
@@ -452,6 +3478,7 @@ mod tests {
         assert!(person.team.is_none());
         assert!(person.manager.is_none());
         assert!(person.notes.is_none());
+        assert!(person.active);
         // Check timestamps are set
         assert!(person.created_at < Utc::now() + chrono::Duration::seconds(1));
         assert!(person.updated_at < Utc::now() + chrono::Duration::seconds(1));
@@ -660,6 +3687,95 @@ mod tests {
         assert!(note.updated_at < Utc::now() + chrono::Duration::seconds(1));
     }
 
+    // Project risk model tests
+
+    #[test]
+    fn test_project_risk_new() {
+        let project_id = Uuid::new_v4();
+        let risk = ProjectRisk::new(project_id, "Vendor delay".to_string(), RiskLevel::High, RiskLevel::Medium);
+
+        assert_eq!(risk.project_id, project_id);
+        assert_eq!(risk.status, RiskStatus::Open);
+        assert!(risk.is_open());
+        assert_eq!(risk.score(), 6);
+    }
+
+    #[test]
+    fn test_risk_level_parsing() {
+        assert_eq!("High".parse::<RiskLevel>().unwrap(), RiskLevel::High);
+        assert_eq!(" low ".parse::<RiskLevel>().unwrap(), RiskLevel::Low);
+        assert!(matches!("severe".parse::<RiskLevel>(), Err(Error::Invalid(_))));
+        assert_eq!("mitigating".parse::<RiskStatus>().unwrap(), RiskStatus::Mitigating);
+        assert!(matches!("done".parse::<RiskStatus>(), Err(Error::Invalid(_))));
+    }
+
+    #[test]
+    fn test_project_risk_serialization() {
+        let risk = ProjectRisk::new(Uuid::new_v4(), "Vendor delay".to_string(), RiskLevel::High, RiskLevel::Low);
+
+        let json = serde_json::to_value(&risk).unwrap();
+        assert_eq!(json["severity"], "high");
+        assert_eq!(json["likelihood"], "low");
+        assert_eq!(json["status"], "open");
+
+        let mut invalid = json.clone();
+        invalid["severity"] = serde_json::json!("severe");
+        assert!(serde_json::from_value::<ProjectRisk>(invalid).is_err());
+    }
+
+    // Action item model tests
+
+    #[test]
+    fn test_action_item_new() {
+        let project_id = Uuid::new_v4();
+        let item = ActionItem::new(project_id, "alice@example.com".to_string(), "Send notes".to_string());
+
+        assert_eq!(item.project_id, project_id);
+        assert_eq!(item.status, ActionItemStatus::Open);
+        assert!(item.is_open());
+        assert!(item.completed_at.is_none());
+        assert!(item.milestone_id.is_none());
+        assert!(item.source_note_id.is_none());
+    }
+
+    #[test]
+    fn test_action_item_status_parsing() {
+        assert_eq!("Completed".parse::<ActionItemStatus>().unwrap(), ActionItemStatus::Completed);
+        assert_eq!(" open ".parse::<ActionItemStatus>().unwrap(), ActionItemStatus::Open);
+        assert!(matches!("done".parse::<ActionItemStatus>(), Err(Error::Invalid(_))));
+
+        let item = ActionItem::new(Uuid::new_v4(), "alice@example.com".to_string(), "Send notes".to_string());
+        assert_eq!(serde_json::to_value(&item).unwrap()["status"], "open");
+    }
+
+    // Initiative model tests
+
+    #[test]
+    fn test_initiative_parse_quarter() {
+        assert_eq!(Initiative::parse_quarter("2025-Q3").unwrap(), "2025-Q3");
+        assert_eq!(Initiative::parse_quarter(" 2025-q1 ").unwrap(), "2025-Q1");
+        for invalid in ["2025-Q5", "2025-Q0", "25-Q1", "2025Q1", "Q1-2025", "2025-Q12", ""] {
+            assert!(matches!(Initiative::parse_quarter(invalid), Err(Error::Invalid(_))), "{}", invalid);
+        }
+
+        // Normalized quarters sort chronologically
+        let mut quarters = vec!["2026-Q1", "2025-Q4", "2025-Q2"];
+        quarters.sort();
+        assert_eq!(quarters, vec!["2025-Q2", "2025-Q4", "2026-Q1"]);
+    }
+
+    #[test]
+    fn test_note_cursor_round_trip() {
+        let cursor = NoteCursor::new(Utc::now(), Uuid::new_v4());
+        let encoded = cursor.to_string();
+        assert_eq!(encoded.parse::<NoteCursor>().unwrap(), cursor);
+
+        let bad_time = format!("x_{}", cursor.id.simple());
+        for invalid in ["", "123", "abc_def", bad_time.as_str()] {
+            assert!(matches!(invalid.parse::<NoteCursor>(), Err(Error::Invalid(_))), "{}", invalid);
+        }
+    }
+
     // Serialization tests
 
     #[test]
@@ -0,0 +1,323 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+use super::error::{Error, Result};
+use super::{get_datetime, get_uuid};
+use super::models::{Backlink, NoteLink, NoteType, ReferenceKind, ReferenceTarget};
+use super::project_repo::parse_column;
+use super::begin_or_join;
+use crate::notes::links::{self, NoteRef};
+use rusqlite::{params, Connection, OptionalExtension};
+use uuid::Uuid;
+
+/// Every note with the project it belongs to, as `note_type, note_id,
+/// title, project_id, project_name, updated_at`
+pub(crate) const NOTES_WITH_PROJECT: &str =
+    "SELECT 'project' AS note_type, n.id AS note_id, n.title, p.id AS project_id, p.name AS project_name, n.updated_at
+     FROM project_notes n JOIN projects p ON p.id = n.project_id
+     UNION ALL
+     SELECT 'milestone', n.id, n.title, p.id, p.name, n.updated_at
+     FROM milestone_notes n JOIN milestones m ON m.id = n.milestone_id JOIN projects p ON p.id = m.project_id
+     UNION ALL
+     SELECT 'stakeholder', n.id, n.title, p.id, p.name, n.updated_at
+     FROM stakeholder_notes n JOIN projects p ON p.id = n.project_id";
+
+/// Columns selected for a link, in the order `link_from_row` expects
+const LINK_COLUMNS: &str = "note_type, note_id, target_type, target, target_id, dangling";
+
+/// Map a row selected with `LINK_COLUMNS` to a link
+fn link_from_row(row: &rusqlite::Row) -> rusqlite::Result<NoteLink> {
+    Ok(NoteLink {
+        note_type: parse_column(row, 0)?,
+        note_id: get_uuid(row, 1)?,
+        target_type: parse_column(row, 2)?,
+        target: row.get(3)?,
+        target_id: row.get(4)?,
+        dangling: row.get(5)?,
+    })
+}
+
+/// Repository for the `[[kind:target]]` references in note bodies
+///
+/// The links are derived from the bodies: saving a note replaces its links
+/// with the references in its new body, and deleting a note removes them.
+pub struct NoteLinkRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> NoteLinkRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Find the record a reference points at
+    ///
+    /// Projects are matched by ID or slug, people by email ignoring case,
+    /// and milestones by ID.
+    pub fn resolve(&self, reference: &NoteRef) -> Result<Option<ReferenceTarget>> {
+        let target = reference.target.trim();
+        let resolved = match reference.kind {
+            ReferenceKind::Project => {
+                let sql = match Uuid::parse_str(target) {
+                    Ok(_) => "SELECT id, name FROM projects WHERE id = ?1",
+                    Err(_) => "SELECT id, name FROM projects WHERE slug = ?1",
+                };
+                self.conn
+                    .prepare_cached(sql)?
+                    .query_row(params![target.to_lowercase()], |row| {
+                        let project_id = get_uuid(row, 0)?;
+                        Ok(ReferenceTarget {
+                            kind: ReferenceKind::Project,
+                            project_id: Some(project_id),
+                            id: project_id.to_string(),
+                            name: row.get(1)?,
+                        })
+                    })
+                    .optional()?
+            }
+            ReferenceKind::Person => self
+                .conn
+                .prepare_cached("SELECT email, name FROM people WHERE email = ?1 COLLATE NOCASE")?
+                .query_row(params![target], |row| {
+                    Ok(ReferenceTarget {
+                        kind: ReferenceKind::Person,
+                        id: row.get(0)?,
+                        name: row.get(1)?,
+                        project_id: None,
+                    })
+                })
+                .optional()?,
+            ReferenceKind::Milestone => {
+                let Ok(id) = Uuid::parse_str(target) else {
+                    return Ok(None);
+                };
+                self.conn
+                    .prepare_cached(
+                        "SELECT m.id, m.number, m.name, p.id, p.name
+                         FROM milestones m JOIN projects p ON p.id = m.project_id
+                         WHERE m.id = ?1",
+                    )?
+                    .query_row(params![id.to_string()], |row| {
+                        Ok(ReferenceTarget {
+                            kind: ReferenceKind::Milestone,
+                            id: row.get(0)?,
+                            name: format!("{} #{} {}", row.get::<_, String>(4)?, row.get::<_, i32>(1)?, row.get::<_, String>(2)?),
+                            project_id: Some(get_uuid(row, 3)?),
+                        })
+                    })
+                    .optional()?
+            }
+        };
+        Ok(resolved)
+    }
+
+    /// Replace a note's links with the references in its body
+    ///
+    /// References that don't resolve are kept and marked dangling.
+    pub fn sync_note(&self, note_type: NoteType, note_id: &Uuid, body: &str) -> Result<()> {
+        let tx = begin_or_join(self.conn)?;
+        self.conn
+            .prepare_cached("DELETE FROM note_links WHERE note_type = ?1 AND note_id = ?2")?
+            .execute(params![note_type.as_str(), note_id.to_string()])?;
+
+        let mut insert = self.conn.prepare_cached(
+            "INSERT INTO note_links (note_type, note_id, target_type, target, target_id, dangling)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )?;
+        for reference in links::parse(body) {
+            let target_id = self.resolve(&reference)?.map(|target| target.id);
+            insert.execute(params![
+                note_type.as_str(),
+                note_id.to_string(),
+                reference.kind.as_str(),
+                &reference.target,
+                &target_id,
+                target_id.is_none(),
+            ])?;
+        }
+
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+        Ok(())
+    }
+
+    /// The references in a note, in the order they were written
+    pub fn get_links(&self, note_type: NoteType, note_id: &Uuid) -> Result<Vec<NoteLink>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM note_links WHERE note_type = ?1 AND note_id = ?2 ORDER BY rowid",
+            LINK_COLUMNS
+        ))?;
+        let links = stmt
+            .query_map(params![note_type.as_str(), note_id.to_string()], link_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(links)
+    }
+
+    /// The notes that reference a project, person or milestone, most
+    /// recently changed first
+    ///
+    /// `target` is written the way a reference would be: a project ID or
+    /// slug, an email or a milestone ID. Dangling references written before
+    /// the record existed are included too.
+    pub fn get_backlinks(&self, kind: ReferenceKind, target: &str) -> Result<Vec<Backlink>> {
+        let reference = NoteRef::new(kind, target.trim());
+        let resolved = self.resolve(&reference)?.ok_or_else(|| {
+            let entity = match kind {
+                ReferenceKind::Project => "Project",
+                ReferenceKind::Person => "Person",
+                ReferenceKind::Milestone => "Milestone",
+            };
+            Error::not_found(entity, target)
+        })?;
+        // A project can also have been referenced by its slug
+        let alias = match kind {
+            ReferenceKind::Project => self
+                .conn
+                .prepare_cached("SELECT slug FROM projects WHERE id = ?1")?
+                .query_row(params![&resolved.id], |row| row.get::<_, Option<String>>(0))?
+                .unwrap_or_else(|| resolved.id.clone()),
+            _ => resolved.id.clone(),
+        };
+
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT n.note_type, n.note_id, n.title, n.project_id, n.project_name, n.updated_at
+             FROM ({}) n
+             WHERE EXISTS (
+                 SELECT 1 FROM note_links l
+                 WHERE l.note_type = n.note_type AND l.note_id = n.note_id AND l.target_type = ?1
+                   AND (l.target_id = ?2 OR (l.target_id IS NULL AND lower(l.target) IN (lower(?2), lower(?3))))
+             )
+             ORDER BY n.updated_at DESC, n.note_id",
+            NOTES_WITH_PROJECT
+        ))?;
+        let backlinks = stmt
+            .query_map(params![kind.as_str(), &resolved.id, &alias], |row| {
+                Ok(Backlink {
+                    note_type: parse_column(row, 0)?,
+                    note_id: get_uuid(row, 1)?,
+                    title: row.get(2)?,
+                    project_id: get_uuid(row, 3)?,
+                    project_name: row.get(4)?,
+                    updated_at: get_datetime(row, 5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(backlinks)
+    }
+
+    /// References that don't lead anywhere now
+    ///
+    /// These are the references stored as dangling, and those whose record
+    /// has since been deleted, less any that would resolve if their note was
+    /// saved again.
+    pub fn list_dangling(&self) -> Result<Vec<NoteLink>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM note_links l
+             WHERE l.target_id IS NULL
+                OR (l.target_type = 'project' AND NOT EXISTS (SELECT 1 FROM projects x WHERE x.id = l.target_id))
+                OR (l.target_type = 'milestone' AND NOT EXISTS (SELECT 1 FROM milestones x WHERE x.id = l.target_id))
+                OR (l.target_type = 'person' AND NOT EXISTS (SELECT 1 FROM people x WHERE x.email = l.target_id))
+             ORDER BY l.note_type, l.note_id, l.rowid",
+            LINK_COLUMNS
+        ))?;
+        let candidates = stmt
+            .query_map([], link_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut dangling = Vec::new();
+        for link in candidates {
+            if self.resolve(&NoteRef::new(link.target_type, link.target.clone()))?.is_none() {
+                dangling.push(link);
+            }
+        }
+        Ok(dangling)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{self, Milestone, Person, PersonRepository, Project, ProjectNote, ProjectRepository};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_note_links_and_backlinks() {
+        let conn = setup_test_db();
+        let projects = ProjectRepository::new(&conn);
+        let apollo = Project::new("Apollo".to_string());
+        projects.create(&apollo).unwrap();
+        let gemini = Project::new("Gemini".to_string());
+        projects.create(&gemini).unwrap();
+        let launch = Milestone::new(gemini.id, 1, "Launch".to_string());
+        projects.add_milestone(&launch).unwrap();
+        PersonRepository::new(&conn)
+            .create(&Person::new("alice@example.com".to_string(), "Alice".to_string()))
+            .unwrap();
+
+        // Adding a note records its references, resolved where possible
+        let body = format!(
+            "Blocked on [[project:apollo]] and [[milestone:{}]]; ask [[person:Alice@Example.com]]\n\
+             about [[project:mercury]].\n\n```\n[[project:{}]]\n```",
+            launch.id, gemini.id
+        );
+        let mut note = ProjectNote::new(gemini.id, "Status".to_string(), body);
+        projects.add_project_note(&note).unwrap();
+
+        let links = NoteLinkRepository::new(&conn);
+        let stored = links.get_links(NoteType::Project, &note.id).unwrap();
+        let targets: Vec<_> = stored.iter().map(|l| (l.target.as_str(), l.target_id.as_deref(), l.dangling)).collect();
+        let launch_id = launch.id.to_string();
+        let apollo_id = apollo.id.to_string();
+        assert_eq!(
+            targets,
+            [
+                ("apollo", Some(apollo_id.as_str()), false),
+                (launch_id.as_str(), Some(launch_id.as_str()), false),
+                ("Alice@Example.com", Some("alice@example.com"), false),
+                ("mercury", None, true),
+            ]
+        );
+
+        let backlinks = links.get_backlinks(ReferenceKind::Project, &apollo.id.to_string()).unwrap();
+        assert_eq!(backlinks.len(), 1);
+        assert_eq!(backlinks[0].note_id, note.id);
+        assert_eq!(backlinks[0].project_name, "Gemini");
+        assert_eq!(links.get_backlinks(ReferenceKind::Person, "ALICE@example.com").unwrap().len(), 1);
+        assert_eq!(links.get_backlinks(ReferenceKind::Milestone, &launch.id.to_string()).unwrap().len(), 1);
+        // The reference in the code block doesn't count
+        assert!(links.get_backlinks(ReferenceKind::Project, "gemini").unwrap().is_empty());
+        assert!(matches!(
+            links.get_backlinks(ReferenceKind::Project, "mercury"),
+            Err(Error::NotFound { .. })
+        ));
+
+        // A dangling reference is found once its project exists
+        assert_eq!(links.list_dangling().unwrap().len(), 1);
+        projects.create(&Project::new("Mercury".to_string())).unwrap();
+        assert_eq!(links.get_backlinks(ReferenceKind::Project, "mercury").unwrap().len(), 1);
+        assert!(links.list_dangling().unwrap().is_empty());
+
+        // Deleting a referenced record leaves its references dangling
+        projects.delete_milestone(&launch.id).unwrap();
+        let dangling = links.list_dangling().unwrap();
+        assert_eq!(dangling.len(), 1);
+        assert_eq!(dangling[0].target_type, ReferenceKind::Milestone);
+
+        // Updating the note replaces its links, and deleting it removes them
+        note.body = "Only [[person:alice@example.com]] now".to_string();
+        projects.update_project_note(&note).unwrap();
+        assert_eq!(links.get_links(NoteType::Project, &note.id).unwrap().len(), 1);
+        assert!(links.get_backlinks(ReferenceKind::Project, "apollo").unwrap().is_empty());
+        projects.delete_project_note(&note.id).unwrap();
+        assert!(links.get_links(NoteType::Project, &note.id).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,166 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+use super::error::Result;
+use super::{get_datetime, get_uuid};
+use super::models::{DueMilestone, NotificationKind};
+use crate::utils::dt_to_db;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// A reminder that has already been shown: the milestone, the kind of
+/// reminder, and the due date it was about
+pub type NotifiedMarker = (Uuid, NotificationKind, DateTime<Utc>);
+
+/// Notification repository for milestone reminders and the markers that keep
+/// each one from being shown twice
+pub struct NotificationRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> NotificationRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Milestones due at or after `from` and before `until`, soonest first
+    pub fn due_milestones(
+        &self,
+        from: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<DueMilestone>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT m.id, m.name, p.id, p.name, m.due_date
+             FROM milestones m
+             JOIN projects p ON p.id = m.project_id
+             WHERE m.due_date >= ?1 AND m.due_date < ?2
+             ORDER BY m.due_date, p.name, m.number",
+        )?;
+
+        let due = stmt
+            .query_map(params![dt_to_db(from), dt_to_db(until)], |row| {
+                Ok(DueMilestone {
+                    milestone_id: get_uuid(row, 0)?,
+                    milestone_name: row.get(1)?,
+                    project_id: get_uuid(row, 2)?,
+                    project_name: row.get(3)?,
+                    due_date: get_datetime(row, 4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(due)
+    }
+
+    /// Every reminder that has been shown
+    pub fn notified(&self) -> Result<HashSet<NotifiedMarker>> {
+        let mut stmt = self
+            .conn
+            .prepare_cached("SELECT milestone_id, kind, due_date FROM notified")?;
+
+        let markers = stmt
+            .query_map([], |row| {
+                Ok((
+                    get_uuid(row, 0)?,
+                    super::project_repo::parse_column(row, 1)?,
+                    get_datetime(row, 2)?,
+                ))
+            })?
+            .collect::<Result<HashSet<_>, _>>()?;
+
+        Ok(markers)
+    }
+
+    /// Record that a reminder was shown
+    ///
+    /// A milestone keeps one marker per kind of reminder, so a reminder about
+    /// a new due date replaces the one about the old date.
+    pub fn mark_notified(
+        &self,
+        milestone_id: &Uuid,
+        kind: NotificationKind,
+        due_date: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        self.conn
+            .prepare_cached(
+                "INSERT INTO notified (milestone_id, kind, due_date, notified_at)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(milestone_id, kind) DO UPDATE SET
+                     due_date = excluded.due_date,
+                     notified_at = excluded.notified_at",
+            )?
+            .execute(params![
+                milestone_id.to_string(),
+                kind.as_str(),
+                dt_to_db(due_date),
+                dt_to_db(now)
+            ])?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{schema, Milestone, Project, ProjectRepository};
+    use chrono::{Duration, TimeZone};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+        schema::initialize_schema(&conn).unwrap();
+        schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_due_milestones_and_markers() {
+        let conn = setup_test_db();
+        let projects = ProjectRepository::new(&conn);
+        let project = Project::new("Apollo".to_string());
+        projects.create(&project).unwrap();
+
+        let due = Utc.with_ymd_and_hms(2025, 3, 14, 17, 0, 0).unwrap();
+        let mut milestone = Milestone::new(project.id, 1, "Launch".to_string());
+        milestone.due_date = Some(due);
+        projects.add_milestone(&milestone).unwrap();
+        let mut later = Milestone::new(project.id, 2, "Review".to_string());
+        later.due_date = Some(due + Duration::days(30));
+        projects.add_milestone(&later).unwrap();
+
+        let repo = NotificationRepository::new(&conn);
+        let found = repo
+            .due_milestones(due - Duration::days(1), due + Duration::days(1))
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].milestone_id, milestone.id);
+        assert_eq!(found[0].project_name, "Apollo");
+        assert_eq!(found[0].due_date, due);
+
+        assert!(repo.notified().unwrap().is_empty());
+        repo.mark_notified(&milestone.id, NotificationKind::Upcoming, due, due)
+            .unwrap();
+        repo.mark_notified(
+            &milestone.id,
+            NotificationKind::Upcoming,
+            later.due_date.unwrap(),
+            due,
+        )
+        .unwrap();
+        let markers = repo.notified().unwrap();
+        assert_eq!(markers.len(), 1);
+        assert!(markers.contains(&(
+            milestone.id,
+            NotificationKind::Upcoming,
+            later.due_date.unwrap()
+        )));
+
+        // Markers go away with their milestone
+        projects.delete_milestone(&milestone.id).unwrap();
+        assert!(repo.notified().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,253 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+use super::error::{Error, Result};
+use super::models::{ActionItem, OneOnOne};
+use super::{begin_or_join, get_datetime, get_uuid, ProjectRepository};
+use crate::utils::dt_to_db;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use uuid::Uuid;
+
+/// Columns selected for a 1:1, in the order `one_on_one_from_row` expects
+const ONE_ON_ONE_COLUMNS: &str = "id, person_email, occurred_at, agenda, notes, followups, created_at, updated_at";
+
+/// Map a row selected with `ONE_ON_ONE_COLUMNS` to a 1:1
+fn one_on_one_from_row(row: &rusqlite::Row) -> rusqlite::Result<OneOnOne> {
+    Ok(OneOnOne {
+        id: get_uuid(row, 0)?,
+        person_email: row.get(1)?,
+        occurred_at: get_datetime(row, 2)?,
+        agenda: row.get(3)?,
+        notes: row.get(4)?,
+        followups: row.get(5)?,
+        created_at: get_datetime(row, 6)?,
+        updated_at: get_datetime(row, 7)?,
+    })
+}
+
+/// Blank text is stored as NULL so "no agenda" has one representation
+fn non_blank(text: &Option<String>) -> Option<&str> {
+    text.as_deref().filter(|text| !text.trim().is_empty())
+}
+
+/// Repository for the records of 1:1 meetings with people
+pub struct OneOnOneRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> OneOnOneRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Record a 1:1
+    pub fn create(&self, one_on_one: &OneOnOne) -> Result<()> {
+        self.conn
+            .prepare_cached(
+                "INSERT INTO one_on_ones (id, person_email, occurred_at, agenda, notes, followups, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?
+            .execute(params![
+                one_on_one.id.to_string(),
+                &one_on_one.person_email,
+                dt_to_db(one_on_one.occurred_at),
+                non_blank(&one_on_one.agenda),
+                non_blank(&one_on_one.notes),
+                non_blank(&one_on_one.followups),
+                dt_to_db(one_on_one.created_at),
+                dt_to_db(one_on_one.updated_at),
+            ])
+            .map_err(|e| Error::from(e).with_field("person_email"))?;
+        log::debug!("Logged 1:1 {} with {}", one_on_one.id, one_on_one.person_email);
+        Ok(())
+    }
+
+    /// Find a 1:1 by ID
+    pub fn find_by_id(&self, id: &Uuid) -> Result<Option<OneOnOne>> {
+        let one_on_one = self
+            .conn
+            .prepare_cached(&format!("SELECT {} FROM one_on_ones WHERE id = ?1", ONE_ON_ONE_COLUMNS))?
+            .query_row(params![id.to_string()], one_on_one_from_row)
+            .optional()?;
+        Ok(one_on_one)
+    }
+
+    /// List a person's 1:1s, most recent first
+    pub fn list_one_on_ones(&self, person_email: &str) -> Result<Vec<OneOnOne>> {
+        self.list_between(person_email, None, None)
+    }
+
+    /// List a person's 1:1s that took place on or after `since` and before
+    /// `before`, most recent first
+    ///
+    /// Either end can be left open.
+    pub fn list_between(
+        &self,
+        person_email: &str,
+        since: Option<DateTime<Utc>>,
+        before: Option<DateTime<Utc>>,
+    ) -> Result<Vec<OneOnOne>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM one_on_ones
+             WHERE person_email = ?1
+               AND (?2 IS NULL OR occurred_at >= ?2)
+               AND (?3 IS NULL OR occurred_at < ?3)
+             ORDER BY occurred_at DESC, id DESC",
+            ONE_ON_ONE_COLUMNS
+        ))?;
+        let one_on_ones = stmt
+            .query_map(
+                params![person_email, since.map(dt_to_db), before.map(dt_to_db)],
+                one_on_one_from_row,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(one_on_ones)
+    }
+
+    /// The most recent 1:1 with a person, if there has been one
+    pub fn latest_one_on_one(&self, person_email: &str) -> Result<Option<OneOnOne>> {
+        let one_on_one = self
+            .conn
+            .prepare_cached(&format!(
+                "SELECT {} FROM one_on_ones WHERE person_email = ?1
+                 ORDER BY occurred_at DESC, id DESC LIMIT 1",
+                ONE_ON_ONE_COLUMNS
+            ))?
+            .query_row(params![person_email], one_on_one_from_row)
+            .optional()?;
+        Ok(one_on_one)
+    }
+
+    /// How many 1:1s have been recorded with a person
+    pub fn count_for_person(&self, person_email: &str) -> Result<usize> {
+        let count = self
+            .conn
+            .prepare_cached("SELECT COUNT(*) FROM one_on_ones WHERE person_email = ?1")?
+            .query_row(params![person_email], |row| row.get(0))?;
+        Ok(count)
+    }
+
+    /// Update when a 1:1 took place and what was recorded for it
+    pub fn update(&self, one_on_one: &OneOnOne) -> Result<()> {
+        let rows = self
+            .conn
+            .prepare_cached(
+                "UPDATE one_on_ones SET occurred_at = ?1, agenda = ?2, notes = ?3, followups = ?4, updated_at = ?5
+                 WHERE id = ?6",
+            )?
+            .execute(params![
+                dt_to_db(one_on_one.occurred_at),
+                non_blank(&one_on_one.agenda),
+                non_blank(&one_on_one.notes),
+                non_blank(&one_on_one.followups),
+                dt_to_db(one_on_one.updated_at),
+                one_on_one.id.to_string(),
+            ])?;
+
+        if rows == 0 {
+            return Err(Error::not_found("1:1", one_on_one.id));
+        }
+        log::debug!("Updated 1:1: {}", one_on_one.id);
+        Ok(())
+    }
+
+    /// Delete a 1:1
+    pub fn delete(&self, id: &Uuid) -> Result<()> {
+        let rows = self
+            .conn
+            .prepare_cached("DELETE FROM one_on_ones WHERE id = ?1")?
+            .execute(params![id.to_string()])?;
+
+        if rows == 0 {
+            return Err(Error::not_found("1:1", id));
+        }
+        log::debug!("Deleted 1:1: {}", id);
+        Ok(())
+    }
+
+    /// Turn each of a 1:1's follow-ups into an action item for the person
+    ///
+    /// Action items belong to a project, so the caller picks which one. The
+    /// items are created together or not at all, and are returned in the
+    /// order the follow-ups were written.
+    pub fn create_followup_action_items(&self, one_on_one: &OneOnOne, project_id: &Uuid) -> Result<Vec<ActionItem>> {
+        let projects = ProjectRepository::new(self.conn);
+        let tx = begin_or_join(self.conn)?;
+        let mut items = Vec::new();
+        for followup in one_on_one.followup_items() {
+            let item = ActionItem::new(*project_id, one_on_one.person_email.clone(), followup);
+            projects.create_action_item(&item)?;
+            items.push(item);
+        }
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{self, Person, PersonRepository, Project};
+    use chrono::{Duration, TimeZone};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_one_on_one_crud_and_order() {
+        let conn = setup_test_db();
+        let people = PersonRepository::new(&conn);
+        people
+            .create(&Person::new("alice@example.com".to_string(), "Alice".to_string()))
+            .unwrap();
+
+        let repo = OneOnOneRepository::new(&conn);
+        let first = Utc.with_ymd_and_hms(2025, 3, 3, 15, 0, 0).unwrap();
+        let mut week1 = OneOnOne::new("alice@example.com".to_string(), first);
+        week1.agenda = Some("Promotion packet".to_string());
+        week1.notes = Some("  ".to_string());
+        let mut week2 = OneOnOne::new("alice@example.com".to_string(), first + Duration::weeks(1));
+        week2.followups = Some("- Share the launch plan\n\n* Book the offsite\n".to_string());
+        repo.create(&week2).unwrap();
+        repo.create(&week1).unwrap();
+        assert!(matches!(
+            repo.create(&OneOnOne::new("nobody@example.com".to_string(), first)),
+            Err(Error::ForeignKeyViolation { .. })
+        ));
+
+        // Most recent first, blank text stored as nothing
+        let listed = repo.list_one_on_ones("alice@example.com").unwrap();
+        assert_eq!(listed.iter().map(|o| o.id).collect::<Vec<_>>(), [week2.id, week1.id]);
+        assert_eq!(listed[1].notes, None);
+        assert_eq!(repo.latest_one_on_one("alice@example.com").unwrap().unwrap().id, week2.id);
+        assert!(repo.latest_one_on_one("bob@example.com").unwrap().is_none());
+        let before_week2 = repo.list_between("alice@example.com", None, Some(week2.occurred_at)).unwrap();
+        assert_eq!(before_week2.len(), 1);
+        assert_eq!(repo.list_between("alice@example.com", Some(first), None).unwrap().len(), 2);
+        assert_eq!(repo.count_for_person("alice@example.com").unwrap(), 2);
+
+        week1.notes = Some("Wants to lead the next launch".to_string());
+        repo.update(&week1).unwrap();
+        assert_eq!(repo.find_by_id(&week1.id).unwrap().unwrap().notes, week1.notes);
+
+        // Follow-ups become action items on the chosen project
+        let project = Project::new("Apollo".to_string());
+        ProjectRepository::new(&conn).create(&project).unwrap();
+        let items = repo.create_followup_action_items(&week2, &project.id).unwrap();
+        let descriptions: Vec<_> = items.iter().map(|item| item.description.as_str()).collect();
+        assert_eq!(descriptions, ["Share the launch plan", "Book the offsite"]);
+        assert!(items.iter().all(|item| item.assignee_email == "alice@example.com"));
+
+        repo.delete(&week1.id).unwrap();
+        assert!(matches!(repo.delete(&week1.id), Err(Error::NotFound { .. })));
+    }
+}
@@ -0,0 +1,156 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Journal of MCP tool calls made with an idempotency key
+//!
+//! An assistant that loses its connection halfway through a series of
+//! changes can't tell which of them were made. Calling each tool with an
+//! `idempotency_key` lets it simply retry: the first call's result is stored
+//! here in the same transaction as its changes, and a retry with the same
+//! key and arguments gets that result back instead of changing anything.
+
+use super::error::{Error, Result};
+use super::get_datetime;
+use super::models::McpOperation;
+use crate::utils::dt_to_db;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Columns selected for an operation, in the order `operation_from_row` expects
+const OPERATION_COLUMNS: &str = "idempotency_key, tool, request_hash, response, created_at";
+
+/// Map a row selected with `OPERATION_COLUMNS` to an operation
+fn operation_from_row(row: &rusqlite::Row) -> rusqlite::Result<McpOperation> {
+    Ok(McpOperation {
+        idempotency_key: row.get(0)?,
+        tool: row.get(1)?,
+        request_hash: row.get(2)?,
+        response: row.get(3)?,
+        created_at: get_datetime(row, 4)?,
+    })
+}
+
+/// Operation repository for the MCP idempotency journal
+pub struct OperationRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> OperationRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Find the operation recorded under a key
+    pub fn find(&self, idempotency_key: &str) -> Result<Option<McpOperation>> {
+        let operation = self
+            .conn
+            .prepare_cached(&format!(
+                "SELECT {} FROM mcp_operations WHERE idempotency_key = ?1",
+                OPERATION_COLUMNS
+            ))?
+            .query_row(params![idempotency_key], operation_from_row)
+            .optional()?;
+        Ok(operation)
+    }
+
+    /// The stored response for a replayed call, or `None` if the key is unused
+    ///
+    /// Fails with a conflict when the key was used for a different tool or
+    /// different arguments, since returning that call's result would be wrong.
+    pub fn replay(&self, idempotency_key: &str, tool: &str, request_hash: &str) -> Result<Option<String>> {
+        let Some(operation) = self.find(idempotency_key)? else {
+            return Ok(None);
+        };
+        if operation.tool != tool || operation.request_hash != request_hash {
+            return Err(Error::Conflict(format!(
+                "Idempotency key '{}' was already used for a different {} call; use a new key for a new request",
+                idempotency_key, operation.tool
+            )));
+        }
+        log::debug!("Replaying {} for idempotency key {}", tool, idempotency_key);
+        Ok(Some(operation.response))
+    }
+
+    /// Record a call's response under its key
+    pub fn record(&self, operation: &McpOperation) -> Result<()> {
+        self.conn
+            .prepare_cached(
+                "INSERT INTO mcp_operations (idempotency_key, tool, request_hash, response, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?
+            .execute(params![
+                &operation.idempotency_key,
+                &operation.tool,
+                &operation.request_hash,
+                &operation.response,
+                dt_to_db(operation.created_at),
+            ])
+            .map_err(|e| {
+                Error::from(e).with_conflict(format!(
+                    "Idempotency key '{}' is already in use",
+                    operation.idempotency_key
+                ))
+            })?;
+        Ok(())
+    }
+
+    /// Forget operations recorded before `before`, so their keys can be reused
+    ///
+    /// Returns the number forgotten.
+    pub fn purge_before(&self, before: DateTime<Utc>) -> Result<usize> {
+        let purged = self
+            .conn
+            .prepare_cached("DELETE FROM mcp_operations WHERE created_at < ?1")?
+            .execute(params![dt_to_db(before)])?;
+        if purged > 0 {
+            log::debug!("Purged {} expired MCP operations", purged);
+        }
+        Ok(purged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::schema;
+    use chrono::Duration;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        schema::initialize_schema(&conn).unwrap();
+        schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_replay_and_expiry() {
+        let conn = setup_test_db();
+        let repo = OperationRepository::new(&conn);
+        assert_eq!(repo.replay("k1", "create_project", "abc").unwrap(), None);
+
+        let mut operation = McpOperation {
+            idempotency_key: "k1".to_string(),
+            tool: "create_project".to_string(),
+            request_hash: "abc".to_string(),
+            response: r#"{"content":[]}"#.to_string(),
+            created_at: Utc::now() - Duration::hours(2),
+        };
+        repo.record(&operation).unwrap();
+        assert!(matches!(repo.record(&operation), Err(Error::Conflict(_))));
+
+        assert_eq!(
+            repo.replay("k1", "create_project", "abc").unwrap().as_deref(),
+            Some(r#"{"content":[]}"#)
+        );
+        assert!(matches!(repo.replay("k1", "create_project", "def"), Err(Error::Conflict(_))));
+        assert!(matches!(repo.replay("k1", "update_project", "abc"), Err(Error::Conflict(_))));
+
+        operation.idempotency_key = "k2".to_string();
+        operation.created_at = Utc::now();
+        repo.record(&operation).unwrap();
+        assert_eq!(repo.purge_before(Utc::now() - Duration::hours(1)).unwrap(), 1);
+        assert_eq!(repo.replay("k1", "create_project", "def").unwrap(), None);
+        assert!(repo.find("k2").unwrap().is_some());
+    }
+}
@@ -0,0 +1,323 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Organizations people belong to, such as vendors and clients
+//!
+//! A person can belong to one organization through `people.organization`.
+//! Grouping people this way answers questions like "which projects is this
+//! vendor involved in?", going through the stakeholder, project resource and
+//! milestone resource links of the organization's people. Deleting an
+//! organization leaves its people in place without one.
+
+use super::error::{Error, Result};
+use super::get_datetime;
+use super::models::{Organization, OrganizationInvolvement, OrganizationOverview, OrganizationProject, Person};
+use super::person_repo::{person_from_row, PersonRepository, PERSON_COLUMNS};
+use super::project_repo::{parse_column, project_from_row, PROJECT_COLUMNS};
+use crate::utils::dt_to_db;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Columns selected for an organization, in the order `organization_from_row` expects
+const ORGANIZATION_COLUMNS: &str = "name, kind, notes, created_at, updated_at";
+
+/// Map a row selected with `ORGANIZATION_COLUMNS` to an organization
+fn organization_from_row(row: &rusqlite::Row) -> rusqlite::Result<Organization> {
+    Ok(Organization {
+        name: row.get(0)?,
+        kind: parse_column(row, 1)?,
+        notes: row.get(2)?,
+        created_at: get_datetime(row, 3)?,
+        updated_at: get_datetime(row, 4)?,
+    })
+}
+
+/// How the people of organization `?1` are linked to projects, one row per
+/// project, person and link
+const INVOLVEMENT: &str = "SELECT s.project_id, pe.email, pe.name, 'stakeholder' AS relation, s.role
+     FROM project_stakeholders s JOIN people pe ON pe.email = s.stakeholder_email
+     WHERE pe.organization = ?1
+     UNION
+     SELECT r.project_id, pe.email, pe.name, 'resource', r.role
+     FROM project_resources r JOIN people pe ON pe.email = r.person_email
+     WHERE pe.organization = ?1
+     UNION
+     SELECT m.project_id, pe.email, pe.name, 'milestone resource', mr.role
+     FROM milestone_resources mr
+     JOIN milestones m ON m.id = mr.milestone_id
+     JOIN people pe ON pe.email = mr.person_email
+     WHERE pe.organization = ?1";
+
+/// Organization repository for database operations
+pub struct OrganizationRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> OrganizationRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Create a new organization
+    pub fn create(&self, organization: &Organization) -> Result<()> {
+        let name = organization.name.trim();
+        if name.is_empty() {
+            return Err(Error::Invalid("Organization name cannot be empty".to_string()));
+        }
+        self.conn
+            .prepare_cached(
+                "INSERT INTO organizations (name, kind, notes, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?
+            .execute(params![
+                name,
+                organization.kind.as_str(),
+                &organization.notes,
+                dt_to_db(organization.created_at),
+                dt_to_db(organization.updated_at),
+            ])
+            .map_err(|e| Error::from(e).with_conflict(format!("An organization named {} already exists", name)))?;
+        log::debug!("Created organization: {}", name);
+        Ok(())
+    }
+
+    /// Find an organization by name
+    pub fn find_by_name(&self, name: &str) -> Result<Option<Organization>> {
+        let organization = self
+            .conn
+            .prepare_cached(&format!(
+                "SELECT {} FROM organizations WHERE name = ?1",
+                ORGANIZATION_COLUMNS
+            ))?
+            .query_row(params![name.trim()], organization_from_row)
+            .optional()?;
+        Ok(organization)
+    }
+
+    /// List all organizations by name
+    pub fn list_all(&self) -> Result<Vec<Organization>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM organizations ORDER BY name COLLATE NOCASE",
+            ORGANIZATION_COLUMNS
+        ))?;
+        let organizations = stmt
+            .query_map([], organization_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(organizations)
+    }
+
+    /// Update an organization's kind and notes
+    pub fn update(&self, organization: &Organization) -> Result<()> {
+        let rows = self
+            .conn
+            .prepare_cached("UPDATE organizations SET kind = ?1, notes = ?2, updated_at = ?3 WHERE name = ?4")?
+            .execute(params![
+                organization.kind.as_str(),
+                &organization.notes,
+                dt_to_db(Utc::now()),
+                organization.name.trim(),
+            ])?;
+        if rows == 0 {
+            return Err(Error::not_found("Organization", organization.name.trim()));
+        }
+        log::debug!("Updated organization: {}", organization.name);
+        Ok(())
+    }
+
+    /// Delete an organization
+    ///
+    /// Its people are kept and no longer belong to an organization.
+    pub fn delete(&self, name: &str) -> Result<()> {
+        let rows = self
+            .conn
+            .prepare_cached("DELETE FROM organizations WHERE name = ?1")?
+            .execute(params![name.trim()])?;
+        if rows == 0 {
+            return Err(Error::not_found("Organization", name.trim()));
+        }
+        log::debug!("Deleted organization {}", name);
+        Ok(())
+    }
+
+    /// The people who belong to an organization, by name
+    pub fn get_people_for_org(&self, name: &str) -> Result<Vec<Person>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM people WHERE organization = ?1 ORDER BY name, email",
+            PERSON_COLUMNS
+        ))?;
+        let mut people = stmt
+            .query_map(params![name.trim()], person_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        PersonRepository::new(self.conn).load_skills(&mut people)?;
+        Ok(people)
+    }
+
+    /// The projects an organization's people are stakeholders or resources
+    /// on, by name, with who is involved and how
+    ///
+    /// Someone who is a resource on several milestones of a project is listed
+    /// once per distinct role.
+    pub fn get_projects_for_org(&self, name: &str) -> Result<Vec<OrganizationProject>> {
+        let name = name.trim();
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT project_id, email, name, relation, role FROM ({})
+             ORDER BY name, email, relation, role",
+            INVOLVEMENT
+        ))?;
+        let mut involvement: HashMap<String, Vec<OrganizationInvolvement>> = HashMap::new();
+        let rows = stmt.query_map(params![name], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                OrganizationInvolvement {
+                    email: row.get(1)?,
+                    name: row.get(2)?,
+                    relation: row.get(3)?,
+                    role: row.get(4)?,
+                },
+            ))
+        })?;
+        for row in rows {
+            let (project_id, person) = row?;
+            involvement.entry(project_id).or_default().push(person);
+        }
+
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {}
+             FROM projects
+             WHERE id IN (SELECT project_id FROM ({}))
+             ORDER BY name",
+            PROJECT_COLUMNS, INVOLVEMENT
+        ))?;
+        let projects = stmt
+            .query_map(params![name], project_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(projects
+            .into_iter()
+            .map(|project| OrganizationProject {
+                involvement: involvement.remove(&project.id.to_string()).unwrap_or_default(),
+                project,
+            })
+            .collect())
+    }
+
+    /// IDs of the projects an organization's people are involved in, for
+    /// filtering project lists
+    pub fn get_project_ids_for_org(&self, name: &str) -> Result<Vec<Uuid>> {
+        Ok(self
+            .get_projects_for_org(name)?
+            .into_iter()
+            .map(|p| p.project.id)
+            .collect())
+    }
+
+    /// An organization with its people and the projects they're involved in
+    pub fn get_overview(&self, name: &str) -> Result<OrganizationOverview> {
+        let organization = self
+            .find_by_name(name)?
+            .ok_or_else(|| Error::not_found("Organization", name.trim()))?;
+        Ok(OrganizationOverview {
+            people: self.get_people_for_org(&organization.name)?,
+            projects: self.get_projects_for_org(&organization.name)?,
+            organization,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{self, Milestone, MilestoneResource, OrganizationKind, Project, ProjectRepository, ProjectResource, ProjectStakeholder};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_organization_overview() {
+        let conn = setup_test_db();
+        let repo = OrganizationRepository::new(&conn);
+        let mut acme = Organization::new("Acme".to_string());
+        acme.notes = Some("Support contract".to_string());
+        repo.create(&acme).unwrap();
+        assert!(matches!(repo.create(&acme), Err(Error::Conflict(_))));
+        assert!(matches!(repo.create(&Organization::new(" ".to_string())), Err(Error::Invalid(_))));
+
+        let people = PersonRepository::new(&conn);
+        let mut ada = Person::new("ada@acme.com".to_string(), "Ada".to_string());
+        ada.organization = Some("Acme".to_string());
+        people.create(&ada).unwrap();
+        let mut bob = Person::new("bob@acme.com".to_string(), "Bob".to_string());
+        bob.organization = Some("Initech".to_string());
+        assert!(matches!(people.create(&bob), Err(Error::ForeignKeyViolation { ref field }) if field == "organization"));
+        bob.organization = Some("Acme".to_string());
+        people.create(&bob).unwrap();
+        people
+            .create(&Person::new("cy@example.com".to_string(), "Cy".to_string()))
+            .unwrap();
+
+        let projects = ProjectRepository::new(&conn);
+        let apollo = Project::new("Apollo".to_string());
+        projects.create(&apollo).unwrap();
+        let gemini = Project::new("Gemini".to_string());
+        projects.create(&gemini).unwrap();
+        let mercury = Project::new("Mercury".to_string());
+        projects.create(&mercury).unwrap();
+        let mut stakeholder = ProjectStakeholder::new(apollo.id, "ada@acme.com".to_string());
+        stakeholder.role = Some("Sponsor".to_string());
+        projects.add_stakeholder(&apollo.id, &stakeholder).unwrap();
+        projects
+            .add_project_resource(&apollo.id, &ProjectResource::new(apollo.id, "bob@acme.com".to_string()))
+            .unwrap();
+        let milestone = Milestone::new(gemini.id, 1, "Design".to_string());
+        projects.add_milestone(&milestone).unwrap();
+        projects
+            .add_milestone_resource(&milestone.id, &MilestoneResource::new(milestone.id, "bob@acme.com".to_string()))
+            .unwrap();
+        projects
+            .add_project_resource(&mercury.id, &ProjectResource::new(mercury.id, "cy@example.com".to_string()))
+            .unwrap();
+
+        let overview = repo.get_overview("Acme").unwrap();
+        assert_eq!(overview.organization, acme);
+        let names: Vec<_> = overview.people.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Ada", "Bob"]);
+        let names: Vec<_> = overview.projects.iter().map(|p| p.project.name.as_str()).collect();
+        assert_eq!(names, ["Apollo", "Gemini"]);
+        assert_eq!(
+            overview.projects[0].involvement,
+            [
+                OrganizationInvolvement {
+                    email: "ada@acme.com".to_string(),
+                    name: "Ada".to_string(),
+                    relation: "stakeholder".to_string(),
+                    role: Some("Sponsor".to_string()),
+                },
+                OrganizationInvolvement {
+                    email: "bob@acme.com".to_string(),
+                    name: "Bob".to_string(),
+                    relation: "resource".to_string(),
+                    role: None,
+                },
+            ]
+        );
+        assert_eq!(overview.projects[1].involvement[0].relation, "milestone resource");
+        assert!(matches!(repo.get_overview("Initech"), Err(Error::NotFound { .. })));
+
+        acme.kind = OrganizationKind::Client;
+        repo.update(&acme).unwrap();
+        assert_eq!(repo.find_by_name("Acme").unwrap().unwrap().kind, OrganizationKind::Client);
+
+        // Deleting the organization keeps its people
+        repo.delete("Acme").unwrap();
+        assert!(matches!(repo.delete("Acme"), Err(Error::NotFound { .. })));
+        assert_eq!(people.find_by_email("ada@acme.com").unwrap().unwrap().organization, None);
+        assert!(repo.list_all().unwrap().is_empty());
+    }
+}
@@ -2,147 +2,960 @@
 //
 // SPDX-License-Identifier: MIT
 
-use super::models::Person;
-use anyhow::Result;
-use chrono::Utc;
+use super::error::{Error, Result};
+use super::one_on_one_repo::OneOnOneRepository;
+use super::{begin_or_join, get_datetime, get_opt_datetime, get_uuid, like_prefix};
+use super::models::{AvailabilityForecast, AvailabilityStatus, Person, PersonAvailability, PersonDeactivation, PersonMatch, PersonNote, PersonReference, PersonSkill, PersonSuggestion, ProjectRoleAssignment, TeamAvailability};
+use super::sync::{self, SyncEntity};
+use crate::utils::{dt_to_db, email_domain_allowed, name_similarity};
+use chrono::{DateTime, Duration, Utc};
 use rusqlite::{params, Connection, OptionalExtension};
+use uuid::Uuid;
+
+/// Columns selected for a person, in the order `person_from_row` expects
+pub(super) const PERSON_COLUMNS: &str =
+    "email, name, team, manager, notes, created_at, updated_at, active, version, avatar_path, external, organization";
+
+/// Number of recent notes included with a person unless asked otherwise
+pub const DEFAULT_RECENT_NOTE_LIMIT: usize = 5;
+
+/// Days an availability forecast looks ahead unless asked otherwise
+pub const DEFAULT_FORECAST_DAYS: u32 = 90;
+
+/// Every column that holds a person's email, with whether its table is versioned
+const PERSON_REFERENCES: &[(&str, &str, bool)] = &[
+    ("people", "manager", true),
+    ("teams", "manager", false),
+    ("team_members", "person_email", false),
+    ("projects", "requirements_owner", true),
+    ("projects", "technical_lead", true),
+    ("projects", "manager", true),
+    ("milestones", "technical_lead", true),
+    ("project_stakeholders", "stakeholder_email", false),
+    ("stakeholder_notes", "stakeholder_email", true),
+    ("project_resources", "person_email", false),
+    ("milestone_resources", "person_email", false),
+    ("project_risks", "owner_email", false),
+    ("action_items", "assignee_email", false),
+    ("initiatives", "owner_email", false),
+    ("person_notes", "person_email", true),
+    ("one_on_ones", "person_email", false),
+    ("person_skills", "person_email", false),
+    ("decision_approvers", "approver_email", false),
+];
+
+/// Map a row selected with `PERSON_COLUMNS` to a person
+pub(super) fn person_from_row(row: &rusqlite::Row) -> rusqlite::Result<Person> {
+    Ok(Person {
+        email: row.get(0)?,
+        name: row.get(1)?,
+        team: row.get(2)?,
+        manager: row.get(3)?,
+        notes: row.get(4)?,
+        created_at: get_datetime(row, 5)?,
+        updated_at: get_datetime(row, 6)?,
+        active: row.get(7)?,
+        version: row.get(8)?,
+        avatar_path: row.get(9)?,
+        external: row.get(10)?,
+        organization: row.get(11)?,
+        skills: Vec::new(),
+    })
+}
+
+/// Columns selected for a person note, in the order `person_note_from_row` expects
+const PERSON_NOTE_COLUMNS: &str = "id, person_email, title, body, created_at, updated_at, version";
+
+/// Map a row selected with `PERSON_NOTE_COLUMNS` to a person note
+fn person_note_from_row(row: &rusqlite::Row) -> rusqlite::Result<PersonNote> {
+    Ok(PersonNote {
+        id: get_uuid(row, 0)?,
+        person_email: row.get(1)?,
+        title: row.get(2)?,
+        body: row.get(3)?,
+        created_at: get_datetime(row, 4)?,
+        updated_at: get_datetime(row, 5)?,
+        version: row.get(6)?,
+    })
+}
+
+/// Canonical form of a skill name: trimmed and lowercase
+fn canonical_skill(skill: &str) -> Result<String> {
+    let skill = skill.trim().to_lowercase();
+    if skill.is_empty() {
+        return Err(Error::Invalid("Skill name can't be empty".to_string()));
+    }
+    Ok(skill)
+}
+
+/// Refuse skill levels outside 1-5
+fn check_skill_level(level: Option<u8>) -> Result<()> {
+    match level {
+        Some(level) if !(1..=5).contains(&level) => Err(Error::Invalid(format!(
+            "Invalid skill level {}: expected 1 to 5",
+            level
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Map a `skill, level, created_at` row to a skill
+fn skill_from_row(row: &rusqlite::Row) -> rusqlite::Result<PersonSkill> {
+    Ok(PersonSkill {
+        skill: row.get(0)?,
+        level: row.get(1)?,
+        created_at: get_datetime(row, 2)?,
+    })
+}
 
 /// Person repository for database operations
 pub struct PersonRepository<'a> {
     conn: &'a Connection,
+    duplicate_threshold: Option<f64>,
+    allowed_domains: &'a [String],
 }
 
 impl<'a> PersonRepository<'a> {
     pub fn new(conn: &'a Connection) -> Self {
-        Self { conn }
+        Self {
+            conn,
+            duplicate_threshold: None,
+            allowed_domains: &[],
+        }
+    }
+
+    /// Refuse to create people whose name is at least `threshold` similar to an existing person's
+    ///
+    /// `create` then returns [`Error::PossibleDuplicate`] listing the matches
+    /// instead of adding the person. Repositories built without this create
+    /// people unconditionally, which is how callers force a create.
+    pub fn with_duplicate_check(mut self, threshold: f64) -> Self {
+        self.duplicate_threshold = Some(threshold);
+        self
+    }
+
+    /// Only accept email addresses in these domains or their subdomains
+    ///
+    /// `create` and `change_email` then return [`Error::DomainNotAllowed`]
+    /// for other addresses unless the person is marked external, and
+    /// `update` refuses to clear `external` on such a person. An empty list,
+    /// the default, allows every address.
+    pub fn with_allowed_domains(mut self, domains: &'a [String]) -> Self {
+        self.allowed_domains = domains;
+        self
+    }
+
+    /// Refuse an address outside the allowed domains
+    fn check_domain(&self, email: &str) -> Result<()> {
+        if email_domain_allowed(email, self.allowed_domains) {
+            return Ok(());
+        }
+        Err(Error::DomainNotAllowed {
+            email: email.to_string(),
+            allowed: self.allowed_domains.to_vec(),
+        })
     }
 
     /// Create a new person
     pub fn create(&self, person: &Person) -> Result<()> {
-        self.conn.execute(
-            "INSERT INTO people (email, name, team, manager, notes, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![
-                &person.email,
-                &person.name,
-                &person.team,
-                &person.manager,
-                &person.notes,
-                person.created_at.to_rfc3339(),
-                person.updated_at.to_rfc3339(),
-            ],
+        if let Some(threshold) = self.duplicate_threshold {
+            let matches: Vec<_> = self
+                .find_similar(&person.name, threshold)?
+                .into_iter()
+                .filter(|m| m.email != person.email)
+                .collect();
+            if !matches.is_empty() {
+                return Err(Error::PossibleDuplicate {
+                    name: person.name.clone(),
+                    matches,
+                });
+            }
+        }
+        if !person.external {
+            self.check_domain(&person.email)?;
+        }
+        self.check_manager_chain(&person.email, person.manager.as_deref())?;
+        self.check_organization(person.organization.as_deref())?;
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT INTO people (email, name, team, manager, notes, created_at, updated_at, active, external, organization)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
         )?;
+        stmt.execute(params![
+            &person.email,
+            &person.name,
+            &person.team,
+            &person.manager,
+            &person.notes,
+            dt_to_db(person.created_at),
+            dt_to_db(person.updated_at),
+            person.active,
+            person.external,
+            &person.organization,
+        ])
+        .map_err(|e| {
+            Error::from(e)
+                .with_conflict(format!("A person with email {} already exists", person.email))
+                .with_field("manager")
+        })?;
         log::debug!("Created person: {}", person.email);
         Ok(())
     }
 
     /// Find a person by email
     pub fn find_by_email(&self, email: &str) -> Result<Option<Person>> {
-        let person = self
-            .conn
-            .query_row(
-                "SELECT email, name, team, manager, notes, created_at, updated_at
-                 FROM people WHERE email = ?1",
-                params![email],
-                |row| {
-                    Ok(Person {
-                        email: row.get(0)?,
-                        name: row.get(1)?,
-                        team: row.get(2)?,
-                        manager: row.get(3)?,
-                        notes: row.get(4)?,
-                        created_at: row.get(5)?,
-                        updated_at: row.get(6)?,
-                    })
-                },
-            )
-            .optional()?;
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM people WHERE email = ?1",
+            PERSON_COLUMNS
+        ))?;
+        let mut person = stmt.query_row(params![email], person_from_row).optional()?;
+        if let Some(person) = person.as_mut() {
+            person.skills = self.get_skills(&person.email)?;
+        }
         Ok(person)
     }
 
-    /// List all people
-    pub fn list_all(&self) -> Result<Vec<Person>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT email, name, team, manager, notes, created_at, updated_at
-             FROM people ORDER BY name",
-        )?;
+    /// Find people, active or not, whose name is at least `threshold` similar to `name`
+    ///
+    /// Names are compared with [`name_similarity`], ignoring case, accents
+    /// and punctuation. The most similar people come first.
+    pub fn find_similar(&self, name: &str, threshold: f64) -> Result<Vec<PersonMatch>> {
+        let mut stmt = self.conn.prepare_cached("SELECT email, name FROM people")?;
+        let mut matches = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+            .filter_map(|row| match row {
+                Ok((email, existing)) => {
+                    let similarity = name_similarity(name, &existing);
+                    (similarity >= threshold).then(|| {
+                        Ok(PersonMatch {
+                            email,
+                            name: existing,
+                            similarity,
+                        })
+                    })
+                }
+                Err(e) => Some(Err(e)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        matches.sort_by(|a, b| {
+            b.similarity
+                .total_cmp(&a.similarity)
+                .then_with(|| a.email.cmp(&b.email))
+        });
+        Ok(matches)
+    }
 
-        let people = stmt
-            .query_map([], |row| {
-                Ok(Person {
-                    email: row.get(0)?,
-                    name: row.get(1)?,
-                    team: row.get(2)?,
-                    manager: row.get(3)?,
-                    notes: row.get(4)?,
-                    created_at: row.get(5)?,
-                    updated_at: row.get(6)?,
-                })
-            })?
+    /// List people, excluding inactive people unless `include_inactive` is set
+    pub fn list_all(&self, include_inactive: bool) -> Result<Vec<Person>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM people WHERE (?1 OR active) ORDER BY name",
+            PERSON_COLUMNS
+        ))?;
+
+        let mut people = stmt
+            .query_map(params![include_inactive], person_from_row)?
             .collect::<Result<Vec<_>, _>>()?;
+        self.load_skills(&mut people)?;
+
+        Ok(people)
+    }
+
+    /// List a page of people sorted by name, excluding inactive people unless `include_inactive` is set
+    pub fn list_page(&self, include_inactive: bool, limit: usize, offset: usize) -> Result<Vec<Person>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM people WHERE (?1 OR active) ORDER BY name, email LIMIT ?2 OFFSET ?3",
+            PERSON_COLUMNS
+        ))?;
+
+        let mut people = stmt
+            .query_map(params![include_inactive, limit as i64, offset as i64], person_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        self.load_skills(&mut people)?;
 
         Ok(people)
     }
 
     /// Search people by name (for autocomplete)
-    pub fn search_by_name(&self, query: &str) -> Result<Vec<Person>> {
+    ///
+    /// Inactive people are excluded unless `include_inactive` is set.
+    pub fn search_by_name(&self, query: &str, include_inactive: bool) -> Result<Vec<Person>> {
         let search_pattern = format!("%{}%", query);
-        let mut stmt = self.conn.prepare(
-            "SELECT email, name, team, manager, notes, created_at, updated_at
-             FROM people WHERE name LIKE ?1 ORDER BY name LIMIT 20",
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM people WHERE name LIKE ?1 AND (?2 OR active) ORDER BY name LIMIT 20",
+            PERSON_COLUMNS
+        ))?;
+
+        let mut people = stmt
+            .query_map(params![search_pattern, include_inactive], person_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        self.load_skills(&mut people)?;
+
+        Ok(people)
+    }
+
+    /// Suggest active people whose name or email starts with `prefix`, ignoring case
+    ///
+    /// Unlike `search_by_name` this can use the name and email indexes, so it
+    /// stays fast on every keystroke.
+    pub fn autocomplete(&self, prefix: &str, limit: usize) -> Result<Vec<PersonSuggestion>> {
+        let pattern = like_prefix(prefix);
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT email, name, team FROM people
+             WHERE (name LIKE ?1 ESCAPE '\\' OR email LIKE ?1 ESCAPE '\\') AND active
+             LIMIT ?2",
         )?;
 
-        let people = stmt
-            .query_map(params![search_pattern], |row| {
-                Ok(Person {
+        let mut suggestions = stmt
+            .query_map(params![pattern, limit as i64], |row| {
+                Ok(PersonSuggestion {
                     email: row.get(0)?,
                     name: row.get(1)?,
                     team: row.get(2)?,
-                    manager: row.get(3)?,
-                    notes: row.get(4)?,
-                    created_at: row.get(5)?,
-                    updated_at: row.get(6)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
+        suggestions.sort_by_key(|a| a.name.to_lowercase());
 
-        Ok(people)
+        Ok(suggestions)
     }
 
     /// Update a person
+    ///
+    /// People already outside the allowed domains can still be updated, but
+    /// not have `external` cleared.
     pub fn update(&self, person: &Person) -> Result<()> {
-        let rows = self.conn.execute(
-            "UPDATE people SET name = ?1, team = ?2, manager = ?3, notes = ?4, updated_at = ?5
-             WHERE email = ?6",
-            params![
+        if !person.external && !email_domain_allowed(&person.email, self.allowed_domains) {
+            let was_external = self.find_by_email(&person.email)?.is_some_and(|p| p.external);
+            if was_external {
+                self.check_domain(&person.email)?;
+            }
+        }
+        self.check_manager_chain(&person.email, person.manager.as_deref())?;
+        self.check_organization(person.organization.as_deref())?;
+        let mut stmt = self.conn.prepare_cached(
+            "UPDATE people SET name = ?1, team = ?2, manager = ?3, notes = ?4, external = ?5, organization = ?6,
+                              updated_at = ?7, version = version + 1
+             WHERE email = ?8 AND version = ?9",
+        )?;
+        let rows = stmt
+            .execute(params![
                 &person.name,
                 &person.team,
                 &person.manager,
                 &person.notes,
-                Utc::now().to_rfc3339(),
+                person.external,
+                &person.organization,
+                dt_to_db(Utc::now()),
                 &person.email,
-            ],
-        )?;
+                person.version,
+            ])
+            .map_err(|e| Error::from(e).with_field("manager"))?;
 
         if rows == 0 {
-            anyhow::bail!("Person not found: {}", person.email);
+            return Err(match self.find_by_email(&person.email)? {
+                Some(current) => {
+                    Error::version_conflict("Person", &person.email, person.version, current.version, &current)
+                }
+                None => Error::not_found("Person", &person.email),
+            });
         }
 
         log::debug!("Updated person: {}", person.email);
         Ok(())
     }
 
+    /// Make sure an organization given for a person exists
+    ///
+    /// Checked up front because the database can't say which of a person's
+    /// references was missing.
+    fn check_organization(&self, organization: Option<&str>) -> Result<()> {
+        let Some(organization) = organization else {
+            return Ok(());
+        };
+        let exists = self
+            .conn
+            .prepare_cached("SELECT 1 FROM organizations WHERE name = ?1")?
+            .exists(params![organization])?;
+        if !exists {
+            return Err(Error::ForeignKeyViolation {
+                field: "organization".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Make sure giving `email` the manager `manager` doesn't create a reporting cycle
+    ///
+    /// Walks up the management chain from `manager`. The walk takes at most
+    /// one step per person, so a cycle that already exists further up can't
+    /// make it loop forever.
+    fn check_manager_chain(&self, email: &str, manager: Option<&str>) -> Result<()> {
+        let Some(manager) = manager else {
+            return Ok(());
+        };
+        let mut chain = vec![email.to_string(), manager.to_string()];
+        if manager == email {
+            return Err(Error::CycleDetected { relation: "manager", chain });
+        }
+
+        let people: i64 = self.conn.query_row("SELECT COUNT(*) FROM people", [], |row| row.get(0))?;
+        let mut stmt = self.conn.prepare_cached("SELECT manager FROM people WHERE email = ?1")?;
+        for _ in 0..people {
+            let current = &chain[chain.len() - 1];
+            let next: Option<String> = stmt.query_row(params![current], |row| row.get(0)).optional()?.flatten();
+            let Some(next) = next else {
+                return Ok(());
+            };
+            // Reaching someone already on the chain means a cycle above that doesn't include this person
+            let seen = chain[1..].contains(&next);
+            chain.push(next);
+            if chain[chain.len() - 1] == email {
+                return Err(Error::CycleDetected { relation: "manager", chain });
+            }
+            if seen {
+                return Ok(());
+            }
+        }
+        Ok(())
+    }
+
+    /// Set or clear a person's avatar path and return the previous one
+    ///
+    /// The avatar isn't part of `update`, so editing a person's details
+    /// never drops their avatar.
+    pub fn set_avatar_path(&self, email: &str, avatar_path: Option<&str>) -> Result<Option<String>> {
+        let tx = begin_or_join(self.conn)?;
+        let previous = self
+            .find_by_email(email)?
+            .ok_or_else(|| Error::not_found("Person", email))?
+            .avatar_path;
+        self.conn
+            .prepare_cached("UPDATE people SET avatar_path = ?1, updated_at = ?2 WHERE email = ?3")?
+            .execute(params![avatar_path, dt_to_db(Utc::now()), email])?;
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+
+        log::debug!("Set avatar of {}: {:?}", email, avatar_path);
+        Ok(previous)
+    }
+
+    /// Avatar paths in use by any person
+    pub fn avatar_paths(&self) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare_cached("SELECT avatar_path FROM people WHERE avatar_path IS NOT NULL")?;
+        let paths = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(paths)
+    }
+
+    /// Find the records that keep a person from being deleted
+    ///
+    /// These are the people and teams they manage, the projects and
+    /// milestones they lead, the risks, action items and initiatives they
+    /// own, and the decisions they approved. Team memberships, resource assignments, stakeholder roles
+    /// and person notes are deleted with the person instead.
+    pub fn restricted_references(&self, email: &str) -> Result<Vec<PersonReference>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT 'person', email, name, 'manager' FROM people WHERE manager = ?1
+             UNION ALL
+             SELECT 'team', name, name, 'manager' FROM teams WHERE manager = ?1
+             UNION ALL
+             SELECT 'project', id, name, 'requirements_owner' FROM projects WHERE requirements_owner = ?1
+             UNION ALL
+             SELECT 'project', id, name, 'technical_lead' FROM projects WHERE technical_lead = ?1
+             UNION ALL
+             SELECT 'project', id, name, 'manager' FROM projects WHERE manager = ?1
+             UNION ALL
+             SELECT 'milestone', m.id, p.name || ': ' || m.name, 'technical_lead'
+             FROM milestones m JOIN projects p ON p.id = m.project_id WHERE m.technical_lead = ?1
+             UNION ALL
+             SELECT 'risk', id, title, 'owner' FROM project_risks WHERE owner_email = ?1
+             UNION ALL
+             SELECT 'action item', id, description, 'assignee' FROM action_items WHERE assignee_email = ?1
+             UNION ALL
+             SELECT 'initiative', id, name, 'owner' FROM initiatives WHERE owner_email = ?1
+             UNION ALL
+             SELECT 'decision', d.id, d.title, 'approver'
+             FROM decisions d JOIN decision_approvers a ON a.decision_id = d.id WHERE a.approver_email = ?1",
+        )?;
+
+        let references = stmt
+            .query_map(params![email], |row| {
+                Ok(PersonReference {
+                    entity: row.get(0)?,
+                    id: row.get(1)?,
+                    name: row.get(2)?,
+                    role: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(references)
+    }
+
     /// Delete a person
-    pub fn delete(&self, email: &str) -> Result<()> {
-        let rows = self.conn.execute("DELETE FROM people WHERE email = ?1", params![email])?;
+    ///
+    /// Fails with a conflict listing the records that still refer to the
+    /// person (see `restricted_references`). Records of 1:1s with the person
+    /// also block the delete unless `delete_one_on_ones` is set, in which case
+    /// they are deleted with the person.
+    pub fn delete(&self, email: &str, delete_one_on_ones: bool) -> Result<()> {
+        let tx = begin_or_join(self.conn)?;
+
+        let references = self.restricted_references(email)?;
+        if !references.is_empty() {
+            let references: Vec<String> = references.iter().map(ToString::to_string).collect();
+            return Err(Error::Conflict(format!(
+                "Person {} is still {}; reassign them or deactivate the person instead",
+                email,
+                references.join(", ")
+            )));
+        }
+
+        let one_on_ones = OneOnOneRepository::new(self.conn).count_for_person(email)?;
+        if one_on_ones > 0 {
+            if !delete_one_on_ones {
+                return Err(Error::Conflict(format!(
+                    "Person {} has {} recorded 1:1{}; delete them along with the person or deactivate the person instead",
+                    email,
+                    one_on_ones,
+                    if one_on_ones == 1 { "" } else { "s" }
+                )));
+            }
+            self.conn
+                .prepare_cached("DELETE FROM one_on_ones WHERE person_email = ?1")?
+                .execute(params![email])?;
+        }
+
+        let rows = self
+            .conn
+            .prepare_cached("DELETE FROM people WHERE email = ?1")?
+            .execute(params![email])
+            .map_err(|e| match Error::from(e) {
+                Error::ForeignKeyViolation { .. } => Error::Conflict(format!(
+                    "Person {} is still referenced by projects or other people; reassign them or deactivate the person instead",
+                    email
+                )),
+                other => other,
+            })?;
 
         if rows == 0 {
-            anyhow::bail!("Person not found: {}", email);
+            return Err(Error::not_found("Person", email));
+        }
+        sync::record_deletion(self.conn, SyncEntity::Person, email)?;
+        if let Some(tx) = tx {
+            tx.commit()?;
         }
 
         log::debug!("Deleted person: {}", email);
         Ok(())
     }
+
+    /// Change a person's email address, keeping everything that refers to them
+    ///
+    /// The person row and every reference to it are updated in one
+    /// transaction, with foreign key checks deferred until it commits.
+    /// Changing to an address that another person already has is a conflict.
+    /// An address outside the allowed domains is refused unless the person
+    /// is external.
+    pub fn change_email(&self, old_email: &str, new_email: &str) -> Result<Person> {
+        if new_email.trim().is_empty() {
+            return Err(Error::Invalid("The new email address can't be empty".to_string()));
+        }
+        if old_email == new_email {
+            return self
+                .find_by_email(old_email)?
+                .ok_or_else(|| Error::not_found("Person", old_email));
+        }
+
+        let person = self
+            .find_by_email(old_email)?
+            .ok_or_else(|| Error::not_found("Person", old_email))?;
+        if !person.external {
+            self.check_domain(new_email)?;
+        }
+
+        let tx = begin_or_join(self.conn)?;
+        // Resets itself when the transaction ends
+        self.conn.pragma_update(None, "defer_foreign_keys", true)?;
+
+        if self.find_by_email(new_email)?.is_some() {
+            return Err(Error::Conflict(format!(
+                "A person with email {} already exists; merging two people isn't supported",
+                new_email
+            )));
+        }
+
+        let rows = self
+            .conn
+            .prepare_cached("UPDATE people SET email = ?2, updated_at = ?3, version = version + 1 WHERE email = ?1")?
+            .execute(params![old_email, new_email, dt_to_db(Utc::now())])?;
+        if rows == 0 {
+            return Err(Error::not_found("Person", old_email));
+        }
+
+        for (table, column, versioned) in PERSON_REFERENCES {
+            let bump = if *versioned { ", version = version + 1" } else { "" };
+            self.conn.execute(
+                &format!("UPDATE {table} SET {column} = ?2{bump} WHERE {column} = ?1"),
+                params![old_email, new_email],
+            )?;
+        }
+
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+
+        log::debug!("Changed email of person {} to {}", old_email, new_email);
+        self.find_by_email(new_email)?
+            .ok_or_else(|| Error::not_found("Person", new_email))
+    }
+
+    /// Mark a person as inactive
+    ///
+    /// The person is kept so existing references stay valid. The result lists
+    /// the projects where they are still technical lead, manager or
+    /// requirements owner. When `remove_future_assignments` is set, they are
+    /// also removed from milestones that are due in the future.
+    pub fn deactivate(&self, email: &str, remove_future_assignments: bool) -> Result<PersonDeactivation> {
+        let tx = begin_or_join(self.conn)?;
+
+        self.set_active(email, false)?;
+
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, name, 'technical_lead' FROM projects WHERE technical_lead = ?1
+             UNION ALL
+             SELECT id, name, 'manager' FROM projects WHERE manager = ?1
+             UNION ALL
+             SELECT id, name, 'requirements_owner' FROM projects WHERE requirements_owner = ?1
+             ORDER BY 2, 3",
+        )?;
+        let project_roles = stmt
+            .query_map(params![email], |row| {
+                Ok(ProjectRoleAssignment {
+                    project_id: get_uuid(row, 0)?,
+                    project_name: row.get(1)?,
+                    role: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let removed_milestone_assignments = if remove_future_assignments {
+            self.conn
+                .prepare_cached(
+                    "DELETE FROM milestone_resources
+                     WHERE person_email = ?1
+                       AND milestone_id IN (SELECT id FROM milestones WHERE due_date > ?2)",
+                )?
+                .execute(params![email, dt_to_db(Utc::now())])?
+        } else {
+            0
+        };
+
+        let person = self
+            .find_by_email(email)?
+            .ok_or_else(|| Error::not_found("Person", email))?;
+
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+
+        log::debug!(
+            "Deactivated person: {} ({} project roles, {} milestone assignments removed)",
+            email,
+            project_roles.len(),
+            removed_milestone_assignments
+        );
+        Ok(PersonDeactivation {
+            person,
+            project_roles,
+            removed_milestone_assignments,
+        })
+    }
+
+    /// Mark a previously deactivated person as active again
+    pub fn reactivate(&self, email: &str) -> Result<Person> {
+        self.set_active(email, true)?;
+        log::debug!("Reactivated person: {}", email);
+        self.find_by_email(email)?
+            .ok_or_else(|| Error::not_found("Person", email))
+    }
+
+    // Person skills
+
+    /// Record that a person has a skill, optionally rated 1-5
+    ///
+    /// Skill names are stored lowercase, so "Kubernetes" and "kubernetes"
+    /// are the same skill. Adding a skill the person already has is not an
+    /// error: a new level replaces the old one, and no level keeps it.
+    pub fn add_skill(&self, email: &str, skill: &str, level: Option<u8>) -> Result<PersonSkill> {
+        let skill = canonical_skill(skill)?;
+        check_skill_level(level)?;
+        if self.find_by_email(email)?.is_none() {
+            return Err(Error::not_found("Person", email));
+        }
+
+        self.conn
+            .prepare_cached(
+                "INSERT INTO person_skills (person_email, skill, level, created_at)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (person_email, skill) DO UPDATE SET level = COALESCE(excluded.level, level)",
+            )?
+            .execute(params![email, &skill, level, dt_to_db(Utc::now())])?;
+        log::debug!("Added skill {} for person {}", skill, email);
+
+        let added = self
+            .conn
+            .prepare_cached("SELECT skill, level, created_at FROM person_skills WHERE person_email = ?1 AND skill = ?2")?
+            .query_row(params![email, &skill], skill_from_row)?;
+        Ok(added)
+    }
+
+    /// Remove a skill from a person
+    pub fn remove_skill(&self, email: &str, skill: &str) -> Result<()> {
+        let skill = canonical_skill(skill)?;
+        let rows = self
+            .conn
+            .prepare_cached("DELETE FROM person_skills WHERE person_email = ?1 AND skill = ?2")?
+            .execute(params![email, &skill])?;
+
+        if rows == 0 {
+            return Err(Error::not_found("Skill", format!("{} for {}", skill, email)));
+        }
+        log::debug!("Removed skill {} from person {}", skill, email);
+        Ok(())
+    }
+
+    /// A person's skills, sorted by name
+    pub fn get_skills(&self, email: &str) -> Result<Vec<PersonSkill>> {
+        let skills = self
+            .conn
+            .prepare_cached("SELECT skill, level, created_at FROM person_skills WHERE person_email = ?1 ORDER BY skill")?
+            .query_map(params![email], skill_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(skills)
+    }
+
+    /// Fill in the skills of each person
+    pub fn load_skills(&self, people: &mut [Person]) -> Result<()> {
+        for person in people {
+            person.skills = self.get_skills(&person.email)?;
+        }
+        Ok(())
+    }
+
+    /// Find active people with a skill, the most proficient first
+    ///
+    /// With `min_level`, only people rated at least that level are included,
+    /// which leaves out people whose level isn't rated. Otherwise unrated
+    /// people come after the rated ones.
+    pub fn find_people_by_skill(&self, skill: &str, min_level: Option<u8>) -> Result<Vec<Person>> {
+        let skill = canonical_skill(skill)?;
+        check_skill_level(min_level)?;
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM people
+             JOIN (SELECT person_email, level FROM person_skills WHERE skill = ?1) ps ON ps.person_email = email
+             WHERE active AND (?2 IS NULL OR ps.level >= ?2)
+             ORDER BY ps.level DESC NULLS LAST, name, email",
+            PERSON_COLUMNS
+        ))?;
+
+        let mut people = stmt
+            .query_map(params![&skill, min_level], person_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        self.load_skills(&mut people)?;
+
+        Ok(people)
+    }
+
+    /// Forecast when active people free up from the milestones they are
+    /// assigned to as resources, grouped by team
+    ///
+    /// A milestone stays open while its project isn't done and, when it has
+    /// a checklist, until every item is checked; without one it counts as
+    /// done once its due date has passed, as in project summaries. Someone
+    /// is expected to be free on the due date of their last open dated
+    /// milestone, whether or not they also have undated ones. People whose
+    /// open milestones are all undated are `unknown` rather than free.
+    pub fn get_availability_forecast(&self, horizon_days: u32, now: DateTime<Utc>) -> Result<AvailabilityForecast> {
+        let until = now + Duration::days(horizon_days.into());
+        let mut stmt = self.conn.prepare_cached(
+            "WITH checklists AS (
+                 SELECT milestone_id, COUNT(*) AS total, SUM(checked) AS checked
+                 FROM milestone_checklist_items GROUP BY milestone_id
+             ),
+             open_assignments AS (
+                 SELECT r.person_email, m.due_date
+                 FROM milestone_resources r
+                 JOIN milestones m ON m.id = r.milestone_id
+                 JOIN projects p ON p.id = m.project_id
+                 LEFT JOIN checklists c ON c.milestone_id = m.id
+                 WHERE p.status <> 'done'
+                   AND CASE WHEN c.total > 0 THEN c.checked < c.total
+                            ELSE m.due_date IS NULL OR m.due_date >= ?1 END
+             )
+             SELECT pe.email, pe.name, NULLIF(pe.team, ''), COUNT(a.person_email), COUNT(a.due_date), MAX(a.due_date)
+             FROM people pe
+             LEFT JOIN open_assignments a ON a.person_email = pe.email
+             WHERE pe.active
+             GROUP BY pe.email
+             ORDER BY NULLIF(pe.team, '') IS NULL, NULLIF(pe.team, ''), pe.name, pe.email",
+        )?;
+        let people = stmt
+            .query_map(params![dt_to_db(now)], |row| {
+                let open_milestones: usize = row.get(3)?;
+                let dated: usize = row.get(4)?;
+                let free_on = get_opt_datetime(row, 5)?;
+                let status = match free_on {
+                    _ if open_milestones == 0 => AvailabilityStatus::Unassigned,
+                    Some(date) if date <= until => AvailabilityStatus::FreesUp,
+                    Some(_) => AvailabilityStatus::Busy,
+                    None => AvailabilityStatus::Unknown,
+                };
+                Ok(PersonAvailability {
+                    email: row.get(0)?,
+                    name: row.get(1)?,
+                    team: row.get(2)?,
+                    status,
+                    free_on,
+                    open_milestones,
+                    undated_milestones: open_milestones - dated,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut forecast = AvailabilityForecast {
+            horizon_days,
+            until,
+            unassigned: 0,
+            freeing_up: 0,
+            busy: 0,
+            unknown: 0,
+            teams: Vec::new(),
+        };
+        // Rows arrive sorted by team, so each team is a run of rows
+        for person in people {
+            match person.status {
+                AvailabilityStatus::Unassigned => forecast.unassigned += 1,
+                AvailabilityStatus::FreesUp => forecast.freeing_up += 1,
+                AvailabilityStatus::Busy => forecast.busy += 1,
+                AvailabilityStatus::Unknown => forecast.unknown += 1,
+            }
+            if forecast.teams.last().map(|t| &t.team) != Some(&person.team) {
+                forecast.teams.push(TeamAvailability {
+                    team: person.team.clone(),
+                    people: Vec::new(),
+                });
+            }
+            forecast.teams.last_mut().unwrap().people.push(person);
+        }
+        for team in &mut forecast.teams {
+            team.people.sort_by_key(|p| (p.status == AvailabilityStatus::Unknown, p.free_on));
+        }
+
+        Ok(forecast)
+    }
+
+    // Person notes
+
+    /// List a person's notes, newest first, keeping at most `limit` when given
+    pub fn list_notes(&self, email: &str, limit: Option<usize>) -> Result<Vec<PersonNote>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM person_notes WHERE person_email = ?1
+             ORDER BY created_at DESC, id DESC LIMIT ?2",
+            PERSON_NOTE_COLUMNS
+        ))?;
+        let limit = limit.map_or(-1, |limit| limit as i64);
+
+        let notes = stmt
+            .query_map(params![email, limit], person_note_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(notes)
+    }
+
+    /// Find a person note by ID
+    pub fn find_note_by_id(&self, id: &Uuid) -> Result<Option<PersonNote>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM person_notes WHERE id = ?1",
+            PERSON_NOTE_COLUMNS
+        ))?;
+        let note = stmt.query_row(params![id.to_string()], person_note_from_row).optional()?;
+        Ok(note)
+    }
+
+    /// Add a note about a person
+    pub fn add_note(&self, note: &PersonNote) -> Result<()> {
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT INTO person_notes (id, person_email, title, body, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )?;
+        stmt.execute(params![
+            note.id.to_string(),
+            &note.person_email,
+            &note.title,
+            &note.body,
+            dt_to_db(note.created_at),
+            dt_to_db(note.updated_at),
+        ])
+        .map_err(|e| Error::from(e).with_field("person_email"))?;
+        log::debug!("Added note {} for person {}", note.id, note.person_email);
+        Ok(())
+    }
+
+    /// Update a person note's title and body
+    pub fn update_note(&self, note: &PersonNote) -> Result<()> {
+        let mut stmt = self.conn.prepare_cached(
+            "UPDATE person_notes SET title = ?1, body = ?2, updated_at = ?3, version = version + 1
+             WHERE id = ?4 AND version = ?5",
+        )?;
+        let rows = stmt.execute(params![
+            &note.title,
+            &note.body,
+            dt_to_db(note.updated_at),
+            note.id.to_string(),
+            note.version,
+        ])?;
+
+        if rows == 0 {
+            return Err(match self.find_note_by_id(&note.id)? {
+                Some(current) => Error::version_conflict("Person note", note.id, note.version, current.version, &current),
+                None => Error::not_found("Person note", note.id),
+            });
+        }
+
+        log::debug!("Updated person note: {}", note.id);
+        Ok(())
+    }
+
+    /// Delete a person note
+    pub fn delete_note(&self, id: &Uuid) -> Result<()> {
+        let rows = self
+            .conn
+            .prepare_cached("DELETE FROM person_notes WHERE id = ?1")?
+            .execute(params![id.to_string()])?;
+
+        if rows == 0 {
+            return Err(Error::not_found("Person note", id));
+        }
+        sync::record_deletion(self.conn, SyncEntity::PersonNote, &id.to_string())?;
+
+        Ok(())
+    }
+
+    fn set_active(&self, email: &str, active: bool) -> Result<()> {
+        let rows = self
+            .conn
+            .prepare_cached("UPDATE people SET active = ?1, updated_at = ?2, version = version + 1 WHERE email = ?3")?
+            .execute(params![active, dt_to_db(Utc::now()), email])?;
+
+        if rows == 0 {
+            return Err(Error::not_found("Person", email));
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -181,6 +994,55 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn test_create_possible_duplicate() {
+        let conn = setup_test_db();
+        let repo = PersonRepository::new(&conn).with_duplicate_check(0.9);
+        repo.create(&Person::new("jose@example.com".to_string(), "José García".to_string()))
+            .unwrap();
+        repo.create(&Person::new("john@example.com".to_string(), "John Smith".to_string()))
+            .unwrap();
+
+        // Accents and case are ignored
+        let err = repo
+            .create(&Person::new("jgarcia@example.com".to_string(), "jose garcia".to_string()))
+            .unwrap_err();
+        match err {
+            Error::PossibleDuplicate { name, matches } => {
+                assert_eq!(name, "jose garcia");
+                assert_eq!(matches.len(), 1);
+                assert_eq!(matches[0].email, "jose@example.com");
+                assert_eq!(matches[0].similarity, 1.0);
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+
+        // So are transposed and missing letters
+        for name in ["Jhon Smith", "Jon Smith"] {
+            let err = repo
+                .create(&Person::new("jsmith@example.com".to_string(), name.to_string()))
+                .unwrap_err();
+            assert!(
+                err.to_string().contains("John Smith <john@example.com>"),
+                "{}",
+                err
+            );
+        }
+        assert!(repo.find_by_email("jsmith@example.com").unwrap().is_none());
+
+        // Different names and forced creates go through
+        repo.create(&Person::new("jane@example.com".to_string(), "Jane Doe".to_string()))
+            .unwrap();
+        PersonRepository::new(&conn)
+            .create(&Person::new("jsmith@example.com".to_string(), "Jon Smith".to_string()))
+            .unwrap();
+
+        let similar = repo.find_similar("John Smyth", 0.9).unwrap();
+        let emails: Vec<_> = similar.iter().map(|m| m.email.as_str()).collect();
+        assert_eq!(emails, ["john@example.com", "jsmith@example.com"]);
+        assert!(similar[0].similarity > similar[1].similarity);
+    }
+
     #[test]
     fn test_create_person_with_all_fields() {
         let conn = setup_test_db();
@@ -209,7 +1071,7 @@ mod tests {
         repo.create(&Person::new("charlie@example.com".to_string(), "Charlie Brown".to_string()))
             .unwrap();
 
-        let people = repo.list_all().unwrap();
+        let people = repo.list_all(false).unwrap();
         assert_eq!(people.len(), 3);
         // Should be sorted by name
         assert_eq!(people[0].name, "Alice Smith");
@@ -217,12 +1079,28 @@ mod tests {
         assert_eq!(people[2].name, "Charlie Brown");
     }
 
+    #[test]
+    fn test_list_page_people() {
+        let conn = setup_test_db();
+        let repo = PersonRepository::new(&conn);
+
+        for (email, name) in [("carol@example.com", "Carol"), ("alice@example.com", "Alice"), ("bob@example.com", "Bob")] {
+            repo.create(&Person::new(email.to_string(), name.to_string())).unwrap();
+        }
+        conn.execute("UPDATE people SET active = 0 WHERE email = 'bob@example.com'", []).unwrap();
+
+        let names = |people: Vec<Person>| people.into_iter().map(|p| p.name).collect::<Vec<_>>();
+        assert_eq!(names(repo.list_page(false, 10, 0).unwrap()), vec!["Alice", "Carol"]);
+        assert_eq!(names(repo.list_page(true, 2, 1).unwrap()), vec!["Bob", "Carol"]);
+        assert!(repo.list_page(true, 2, 3).unwrap().is_empty());
+    }
+
     #[test]
     fn test_list_all_people_empty() {
         let conn = setup_test_db();
         let repo = PersonRepository::new(&conn);
 
-        let people = repo.list_all().unwrap();
+        let people = repo.list_all(false).unwrap();
         assert_eq!(people.len(), 0);
     }
 
@@ -244,6 +1122,20 @@ mod tests {
         assert_eq!(found.notes, Some("Promoted to manager".to_string()));
     }
 
+    #[test]
+    fn test_update_person_version_conflict() {
+        let conn = setup_test_db();
+        let repo = PersonRepository::new(&conn);
+        let mut person = Person::new("alice@example.com".to_string(), "Alice Smith".to_string());
+        repo.create(&person).unwrap();
+
+        // Deactivating bumps the version, so an edit based on the old row conflicts
+        repo.set_active("alice@example.com", false).unwrap();
+        person.name = "Alice Johnson".to_string();
+        let result = repo.update(&person);
+        assert!(matches!(result.unwrap_err(), Error::VersionConflict { entity: "Person", expected: 1, actual: 2, .. }));
+    }
+
     #[test]
     fn test_update_nonexistent_person() {
         let conn = setup_test_db();
@@ -252,7 +1144,88 @@ mod tests {
 
         let result = repo.update(&person);
         assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("not found"));
+        assert!(matches!(result.unwrap_err(), Error::NotFound { entity: "Person", .. }));
+    }
+
+    /// Load a person, give them `manager` and save them
+    fn set_manager(repo: &PersonRepository, email: &str, manager: &str) -> Result<()> {
+        let mut person = repo.find_by_email(email).unwrap().unwrap();
+        person.manager = Some(manager.to_string());
+        repo.update(&person)
+    }
+
+    #[test]
+    fn test_self_manager_rejected() {
+        let conn = setup_test_db();
+        let repo = PersonRepository::new(&conn);
+        repo.create(&Person::new("alice@example.com".to_string(), "Alice".to_string())).unwrap();
+
+        let err = set_manager(&repo, "alice@example.com", "alice@example.com").unwrap_err();
+        assert!(matches!(&err, Error::CycleDetected { chain, .. } if chain == &["alice@example.com", "alice@example.com"]));
+        assert_eq!(err.to_string(), "Circular manager reference: alice@example.com -> alice@example.com");
+
+        let mut bob = Person::new("bob@example.com".to_string(), "Bob".to_string());
+        bob.manager = Some("bob@example.com".to_string());
+        assert!(matches!(repo.create(&bob).unwrap_err(), Error::CycleDetected { .. }));
+    }
+
+    #[test]
+    fn test_two_person_manager_cycle_rejected() {
+        let conn = setup_test_db();
+        let repo = PersonRepository::new(&conn);
+        repo.create(&Person::new("alice@example.com".to_string(), "Alice".to_string())).unwrap();
+        repo.create(&Person::new("bob@example.com".to_string(), "Bob".to_string())).unwrap();
+
+        set_manager(&repo, "alice@example.com", "bob@example.com").unwrap();
+        let err = set_manager(&repo, "bob@example.com", "alice@example.com").unwrap_err();
+        assert!(matches!(&err, Error::CycleDetected { chain, .. }
+            if chain == &["bob@example.com", "alice@example.com", "bob@example.com"]));
+        assert!(repo.find_by_email("bob@example.com").unwrap().unwrap().manager.is_none());
+    }
+
+    #[test]
+    fn test_long_manager_chain() {
+        let conn = setup_test_db();
+        let repo = PersonRepository::new(&conn);
+        let emails: Vec<String> = (0..20).map(|i| format!("p{:02}@example.com", i)).collect();
+        for email in &emails {
+            repo.create(&Person::new(email.clone(), email.clone())).unwrap();
+        }
+        // p00 reports to p01, who reports to p02, and so on up to p19
+        for pair in emails.windows(2) {
+            set_manager(&repo, &pair[0], &pair[1]).unwrap();
+        }
+
+        let err = set_manager(&repo, &emails[19], &emails[0]).unwrap_err();
+        match err {
+            Error::CycleDetected { chain, .. } => {
+                assert_eq!(chain.len(), 21);
+                assert_eq!(chain.first(), Some(&emails[19]));
+                assert_eq!(chain[1..20], emails[..19]);
+                assert_eq!(chain.last(), Some(&emails[19]));
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+
+        // Re-pointing someone further up the chain is fine
+        set_manager(&repo, &emails[0], &emails[19]).unwrap();
+    }
+
+    #[test]
+    fn test_existing_cycle_above_does_not_loop() {
+        let conn = setup_test_db();
+        let repo = PersonRepository::new(&conn);
+        for email in ["alice@example.com", "bob@example.com", "carol@example.com"] {
+            repo.create(&Person::new(email.to_string(), email.to_string())).unwrap();
+        }
+        // A cycle written before validation existed
+        conn.execute_batch(
+            "UPDATE people SET manager = 'bob@example.com' WHERE email = 'alice@example.com';
+             UPDATE people SET manager = 'alice@example.com' WHERE email = 'bob@example.com';",
+        )
+        .unwrap();
+
+        set_manager(&repo, "carol@example.com", "alice@example.com").unwrap();
     }
 
     #[test]
@@ -262,7 +1235,7 @@ mod tests {
         let person = Person::new("alice@example.com".to_string(), "Alice Smith".to_string());
         repo.create(&person).unwrap();
 
-        repo.delete("alice@example.com").unwrap();
+        repo.delete("alice@example.com", false).unwrap();
 
         let found = repo.find_by_email("alice@example.com").unwrap();
         assert!(found.is_none());
@@ -273,9 +1246,9 @@ mod tests {
         let conn = setup_test_db();
         let repo = PersonRepository::new(&conn);
 
-        let result = repo.delete("nonexistent@example.com");
+        let result = repo.delete("nonexistent@example.com", false);
         assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("not found"));
+        assert!(matches!(result.unwrap_err(), Error::NotFound { entity: "Person", .. }));
     }
 
     // Search tests
@@ -292,7 +1265,7 @@ mod tests {
         repo.create(&Person::new("charlie@example.com".to_string(), "Charlie Brown".to_string()))
             .unwrap();
 
-        let results = repo.search_by_name("Alice").unwrap();
+        let results = repo.search_by_name("Alice", false).unwrap();
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].name, "Alice Smith");
     }
@@ -309,7 +1282,7 @@ mod tests {
         repo.create(&Person::new("bob@example.com".to_string(), "Bob Alice".to_string()))
             .unwrap();
 
-        let results = repo.search_by_name("Ali").unwrap();
+        let results = repo.search_by_name("Ali", false).unwrap();
         assert_eq!(results.len(), 3); // Matches Alice, Alicia, and "Bob Alice"
     }
 
@@ -321,7 +1294,7 @@ mod tests {
         repo.create(&Person::new("alice@example.com".to_string(), "Alice Smith".to_string()))
             .unwrap();
 
-        let results = repo.search_by_name("alice").unwrap();
+        let results = repo.search_by_name("alice", false).unwrap();
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].name, "Alice Smith");
     }
@@ -334,7 +1307,7 @@ mod tests {
         repo.create(&Person::new("alice@example.com".to_string(), "Alice Smith".to_string()))
             .unwrap();
 
-        let results = repo.search_by_name("Bob").unwrap();
+        let results = repo.search_by_name("Bob", false).unwrap();
         assert_eq!(results.len(), 0);
     }
 
@@ -352,7 +1325,7 @@ mod tests {
             repo.create(&person).unwrap();
         }
 
-        let results = repo.search_by_name("Test Person").unwrap();
+        let results = repo.search_by_name("Test Person", false).unwrap();
         // Should limit to 20 results
         assert_eq!(results.len(), 20);
     }
@@ -387,6 +1360,628 @@ mod tests {
 
         let person2 = Person::new("alice@example.com".to_string(), "Alice Jones".to_string());
         let result = repo.create(&person2);
+        assert!(matches!(result.unwrap_err(), Error::Conflict(_)));
+    }
+
+    #[test]
+    fn test_create_person_with_unknown_manager_fails() {
+        let conn = setup_test_db();
+        let repo = PersonRepository::new(&conn);
+
+        let mut person = Person::new("alice@example.com".to_string(), "Alice Smith".to_string());
+        person.manager = Some("nobody@example.com".to_string());
+        let result = repo.create(&person);
+        assert!(matches!(result.unwrap_err(), Error::ForeignKeyViolation { field } if field == "manager"));
+    }
+
+    #[test]
+    fn test_delete_referenced_person_is_conflict() {
+        let conn = setup_test_db();
+        let repo = PersonRepository::new(&conn);
+
+        repo.create(&Person::new("manager@example.com".to_string(), "Manager".to_string()))
+            .unwrap();
+        let mut employee = Person::new("employee@example.com".to_string(), "Employee".to_string());
+        employee.manager = Some("manager@example.com".to_string());
+        repo.create(&employee).unwrap();
+
+        let result = repo.delete("manager@example.com", false);
+        assert!(matches!(result.unwrap_err(), Error::Conflict(_)));
+    }
+
+    #[test]
+    fn test_delete_person_lists_restricted_references() {
+        use crate::db::{Milestone, Project, ProjectRepository, ProjectStakeholder, StakeholderNote, Team, TeamRepository};
+
+        let conn = setup_test_db();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+        let repo = PersonRepository::new(&conn);
+        let project_repo = ProjectRepository::new(&conn);
+        repo.create(&Person::new("alice@example.com".to_string(), "Alice".to_string())).unwrap();
+        repo.create(&Person::new("bob@example.com".to_string(), "Bob".to_string())).unwrap();
+
+        let mut project = Project::new("Apollo".to_string());
+        project.technical_lead = Some("alice@example.com".to_string());
+        project_repo.create(&project).unwrap();
+        let mut milestone = Milestone::new(project.id, 1, "Launch".to_string());
+        milestone.technical_lead = Some("alice@example.com".to_string());
+        project_repo.add_milestone(&milestone).unwrap();
+        let mut team = Team::new("Platform".to_string());
+        team.manager = Some("alice@example.com".to_string());
+        TeamRepository::new(&conn).create(&team).unwrap();
+
+        let references = repo.restricted_references("alice@example.com").unwrap();
+        assert_eq!(
+            references.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "manager of team \"Platform\"",
+                "technical lead of project \"Apollo\"",
+                "technical lead of milestone \"Apollo: Launch\"",
+            ]
+        );
+        assert_eq!(references[1].id, project.id.to_string());
+        assert_eq!(references[2].id, milestone.id.to_string());
+
+        match repo.delete("alice@example.com", false).unwrap_err() {
+            Error::Conflict(message) => assert_eq!(
+                message,
+                "Person alice@example.com is still manager of team \"Platform\", technical lead of project \"Apollo\", \
+                 technical lead of milestone \"Apollo: Launch\"; reassign them or deactivate the person instead"
+            ),
+            err => panic!("Expected a conflict, got {:?}", err),
+        }
+        assert!(repo.find_by_email("alice@example.com").unwrap().is_some());
+
+        // Being a stakeholder doesn't keep a person, and their stakeholder notes go with them
+        project_repo
+            .add_stakeholder(&project.id, &ProjectStakeholder::new(project.id, "bob@example.com".to_string()))
+            .unwrap();
+        project_repo
+            .add_stakeholder_note(&StakeholderNote::new(
+                project.id,
+                "bob@example.com".to_string(),
+                "Kickoff".to_string(),
+                "Agreed on scope".to_string(),
+            ))
+            .unwrap();
+        assert!(repo.restricted_references("bob@example.com").unwrap().is_empty());
+        repo.delete("bob@example.com", false).unwrap();
+        assert!(project_repo.get_stakeholders(&project.id).unwrap().is_empty());
+        assert!(project_repo.get_stakeholder_notes(&project.id, "bob@example.com").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_allowed_email_domains() {
+        let conn = setup_test_db();
+        let allowed = vec!["company.com".to_string()];
+        let repo = PersonRepository::new(&conn).with_allowed_domains(&allowed);
+
+        repo.create(&Person::new("alice@company.com".to_string(), "Alice".to_string()))
+            .unwrap();
+        repo.create(&Person::new("bob@corp.company.com".to_string(), "Bob".to_string()))
+            .unwrap();
+        match repo.create(&Person::new("carl@gmail.com".to_string(), "Carl".to_string())) {
+            Err(Error::DomainNotAllowed { email, allowed }) => {
+                assert_eq!(email, "carl@gmail.com");
+                assert_eq!(allowed, ["company.com"]);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        // Sanctioned exceptions skip the check, and keep their flag
+        let mut carl = Person::new("carl@consulting.io".to_string(), "Carl".to_string());
+        carl.external = true;
+        repo.create(&carl).unwrap();
+        let mut carl = repo.find_by_email("carl@consulting.io").unwrap().unwrap();
+        assert!(carl.external);
+        carl.name = "Carl Consultant".to_string();
+        repo.update(&carl).unwrap();
+        carl.version += 1;
+
+        // Clearing the flag would put him outside the allow-list
+        carl.external = false;
+        assert!(matches!(repo.update(&carl), Err(Error::DomainNotAllowed { .. })));
+
+        assert!(matches!(
+            repo.change_email("alice@company.com", "alice@gmail.com"),
+            Err(Error::DomainNotAllowed { .. })
+        ));
+        repo.change_email("alice@company.com", "alice@eu.company.com").unwrap();
+        repo.change_email("carl@consulting.io", "carl@other.io").unwrap();
+
+        // Without an allow-list anyone can be added
+        PersonRepository::new(&conn)
+            .create(&Person::new("dana@gmail.com".to_string(), "Dana".to_string()))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_delete_with_one_on_ones() {
+        let conn = setup_test_db();
+        let repo = PersonRepository::new(&conn);
+        repo.create(&Person::new("alice@example.com".to_string(), "Alice Smith".to_string()))
+            .unwrap();
+        let one_on_ones = OneOnOneRepository::new(&conn);
+        one_on_ones
+            .create(&crate::db::OneOnOne::new("alice@example.com".to_string(), Utc::now()))
+            .unwrap();
+
+        // 1:1 records are kept unless the caller asks for them to go too
+        match repo.delete("alice@example.com", false).unwrap_err() {
+            Error::Conflict(message) => assert!(message.contains("1 recorded 1:1;"), "{}", message),
+            other => panic!("Expected a conflict, got {:?}", other),
+        }
+        assert!(repo.find_by_email("alice@example.com").unwrap().is_some());
+
+        repo.delete("alice@example.com", true).unwrap();
+        assert!(repo.find_by_email("alice@example.com").unwrap().is_none());
+        assert_eq!(one_on_ones.count_for_person("alice@example.com").unwrap(), 0);
+    }
+
+    // Active status tests
+
+    #[test]
+    fn test_deactivate_hides_person_from_lists() {
+        let conn = setup_test_db();
+        let repo = PersonRepository::new(&conn);
+
+        repo.create(&Person::new("alice@example.com".to_string(), "Alice Smith".to_string()))
+            .unwrap();
+        repo.create(&Person::new("alicia@example.com".to_string(), "Alicia Jones".to_string()))
+            .unwrap();
+
+        let result = repo.deactivate("alice@example.com", false).unwrap();
+        assert!(!result.person.active);
+        assert!(result.project_roles.is_empty());
+        assert_eq!(result.removed_milestone_assignments, 0);
+
+        let people = repo.list_all(false).unwrap();
+        assert_eq!(people.len(), 1);
+        assert_eq!(people[0].email, "alicia@example.com");
+        assert_eq!(repo.list_all(true).unwrap().len(), 2);
+
+        assert_eq!(repo.search_by_name("Ali", false).unwrap().len(), 1);
+        assert_eq!(repo.search_by_name("Ali", true).unwrap().len(), 2);
+
+        // Still reachable directly, so existing references keep working
+        let found = repo.find_by_email("alice@example.com").unwrap().unwrap();
+        assert!(!found.active);
+    }
+
+    #[test]
+    fn test_reactivate_person() {
+        let conn = setup_test_db();
+        let repo = PersonRepository::new(&conn);
+        repo.create(&Person::new("alice@example.com".to_string(), "Alice Smith".to_string()))
+            .unwrap();
+
+        repo.deactivate("alice@example.com", false).unwrap();
+        let person = repo.reactivate("alice@example.com").unwrap();
+        assert!(person.active);
+        assert_eq!(repo.list_all(false).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_deactivate_nonexistent_person() {
+        let conn = setup_test_db();
+        let repo = PersonRepository::new(&conn);
+
+        let result = repo.deactivate("nonexistent@example.com", false);
+        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), Error::NotFound { entity: "Person", .. }));
+
+        let result = repo.reactivate("nonexistent@example.com");
         assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), Error::NotFound { entity: "Person", .. }));
+    }
+
+    #[test]
+    fn test_deactivate_reports_roles_and_removes_future_assignments() {
+        use crate::db::{Milestone, MilestoneResource, Project, ProjectRepository};
+
+        let conn = setup_test_db();
+        let repo = PersonRepository::new(&conn);
+        let project_repo = ProjectRepository::new(&conn);
+        repo.create(&Person::new("alice@example.com".to_string(), "Alice Smith".to_string()))
+            .unwrap();
+
+        let mut project = Project::new("Apollo".to_string());
+        project.technical_lead = Some("alice@example.com".to_string());
+        project.manager = Some("alice@example.com".to_string());
+        project_repo.create(&project).unwrap();
+
+        let mut past = Milestone::new(project.id, 1, "Past".to_string());
+        past.due_date = Some(Utc::now() - chrono::Duration::days(30));
+        let mut future = Milestone::new(project.id, 2, "Future".to_string());
+        future.due_date = Some(Utc::now() + chrono::Duration::days(30));
+        project_repo.add_milestone(&past).unwrap();
+        project_repo.add_milestone(&future).unwrap();
+        for milestone in [&past, &future] {
+            project_repo
+                .add_milestone_resource(
+                    &milestone.id,
+                    &MilestoneResource::new(milestone.id, "alice@example.com".to_string()),
+                )
+                .unwrap();
+        }
+
+        let result = repo.deactivate("alice@example.com", true).unwrap();
+
+        let roles: Vec<&str> = result.project_roles.iter().map(|r| r.role.as_str()).collect();
+        assert_eq!(roles, vec!["manager", "technical_lead"]);
+        assert!(result.project_roles.iter().all(|r| r.project_id == project.id));
+        assert_eq!(result.removed_milestone_assignments, 1);
+
+        assert_eq!(project_repo.get_milestone_resources(&past.id).unwrap().len(), 1);
+        assert!(project_repo.get_milestone_resources(&future.id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_set_avatar_path() {
+        let conn = setup_test_db();
+        let repo = PersonRepository::new(&conn);
+        let mut person = Person::new("alice@example.com".to_string(), "Alice Smith".to_string());
+        repo.create(&person).unwrap();
+
+        assert_eq!(repo.set_avatar_path(&person.email, Some("avatars/a.png")).unwrap(), None);
+        assert_eq!(repo.avatar_paths().unwrap(), vec!["avatars/a.png".to_string()]);
+
+        // Updating other fields keeps the avatar
+        person.name = "Alice Jones".to_string();
+        repo.update(&person).unwrap();
+        let found = repo.find_by_email(&person.email).unwrap().unwrap();
+        assert_eq!(found.avatar_path.as_deref(), Some("avatars/a.png"));
+
+        assert_eq!(
+            repo.set_avatar_path(&person.email, None).unwrap().as_deref(),
+            Some("avatars/a.png")
+        );
+        assert!(repo.avatar_paths().unwrap().is_empty());
+        assert!(matches!(
+            repo.set_avatar_path("nobody@example.com", None).unwrap_err(),
+            Error::NotFound { entity: "Person", .. }
+        ));
+    }
+
+    #[test]
+    fn test_change_email_updates_every_reference() {
+        use crate::db::{
+            ActionItem, Decision, DecisionRepository, Initiative, InitiativeRepository, Milestone, MilestoneResource,
+            Project, ProjectRepository, ProjectResource, ProjectRisk, ProjectStakeholder, RiskLevel, StakeholderNote,
+            Team, TeamRepository,
+        };
+
+        let conn = setup_test_db();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+        let repo = PersonRepository::new(&conn);
+        let project_repo = ProjectRepository::new(&conn);
+        let team_repo = TeamRepository::new(&conn);
+
+        let old = "alice@example.com";
+        let new = "alice.jones@example.com";
+        repo.create(&Person::new(old.to_string(), "Alice Smith".to_string())).unwrap();
+        let mut report = Person::new("bob@example.com".to_string(), "Bob Jones".to_string());
+        report.manager = Some(old.to_string());
+        repo.create(&report).unwrap();
+
+        let mut team = Team::new("Platform".to_string());
+        team.manager = Some(old.to_string());
+        team_repo.create(&team).unwrap();
+        team_repo.add_member("Platform", old).unwrap();
+
+        let mut project = Project::new("Apollo".to_string());
+        project.requirements_owner = Some(old.to_string());
+        project.technical_lead = Some(old.to_string());
+        project.manager = Some(old.to_string());
+        project_repo.create(&project).unwrap();
+
+        let mut milestone = Milestone::new(project.id, 1, "Launch".to_string());
+        milestone.technical_lead = Some(old.to_string());
+        project_repo.add_milestone(&milestone).unwrap();
+        project_repo
+            .add_milestone_resource(&milestone.id, &MilestoneResource::new(milestone.id, old.to_string()))
+            .unwrap();
+        project_repo
+            .add_project_resource(&project.id, &ProjectResource::new(project.id, old.to_string()))
+            .unwrap();
+        project_repo
+            .add_stakeholder(&project.id, &ProjectStakeholder::new(project.id, old.to_string()))
+            .unwrap();
+        project_repo
+            .add_stakeholder_note(&StakeholderNote::new(
+                project.id,
+                old.to_string(),
+                "Kickoff".to_string(),
+                "Agreed on scope".to_string(),
+            ))
+            .unwrap();
+
+        repo.add_note(&PersonNote::new(old.to_string(), "1:1".to_string(), "Wants to lead the launch".to_string()))
+            .unwrap();
+
+        let mut risk = ProjectRisk::new(project.id, "Vendor delay".to_string(), RiskLevel::High, RiskLevel::Low);
+        risk.owner_email = Some(old.to_string());
+        project_repo.add_project_risk(&risk).unwrap();
+        project_repo
+            .create_action_item(&ActionItem::new(project.id, old.to_string(), "Send notes".to_string()))
+            .unwrap();
+        let mut initiative = Initiative::new("Growth".to_string(), "2025-Q3".to_string());
+        initiative.owner_email = Some(old.to_string());
+        InitiativeRepository::new(&conn).create(&initiative).unwrap();
+        OneOnOneRepository::new(&conn)
+            .create(&crate::db::OneOnOne::new(old.to_string(), Utc::now()))
+            .unwrap();
+        repo.add_skill(old, "kubernetes", Some(3)).unwrap();
+        let mut decision = Decision::new(project.id, "Scope".to_string(), "Ship the MVP first".to_string());
+        decision.approvers = vec![old.to_string()];
+        DecisionRepository::new(&conn).create(&decision).unwrap();
+
+        let changed = repo.change_email(old, new).unwrap();
+        assert_eq!(changed.email, new);
+        assert_eq!(changed.name, "Alice Smith");
+        assert!(repo.find_by_email(old).unwrap().is_none());
+
+        for (table, column, _) in PERSON_REFERENCES {
+            let count = |email: &str| -> i64 {
+                conn.query_row(
+                    &format!("SELECT COUNT(*) FROM {table} WHERE {column} = ?1"),
+                    params![email],
+                    |row| row.get(0),
+                )
+                .unwrap()
+            };
+            assert_eq!(count(old), 0, "{}.{} still refers to the old email", table, column);
+            assert!(count(new) > 0, "{}.{} wasn't updated", table, column);
+        }
+
+        let violations: i64 = conn
+            .query_row("SELECT COUNT(*) FROM pragma_foreign_key_check", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(violations, 0);
+    }
+
+    // Person skill tests
+
+    #[test]
+    fn test_person_skills() {
+        let conn = setup_test_db();
+        let repo = PersonRepository::new(&conn);
+        repo.create(&Person::new("alice@example.com".to_string(), "Alice".to_string()))
+            .unwrap();
+        repo.create(&Person::new("bob@example.com".to_string(), "Bob".to_string()))
+            .unwrap();
+        repo.create(&Person::new("carol@example.com".to_string(), "Carol".to_string()))
+            .unwrap();
+
+        let added = repo.add_skill("alice@example.com", "  Kubernetes ", Some(3)).unwrap();
+        assert_eq!(added.skill, "kubernetes");
+        assert_eq!(added.level, Some(3));
+        repo.add_skill("alice@example.com", "Go", None).unwrap();
+        repo.add_skill("bob@example.com", "kubernetes", Some(5)).unwrap();
+        repo.add_skill("carol@example.com", "KUBERNETES", None).unwrap();
+
+        // Adding again is idempotent: no level keeps the old one, a new level replaces it
+        let again = repo.add_skill("alice@example.com", "kubernetes", None).unwrap();
+        assert_eq!(again, added);
+        assert_eq!(repo.add_skill("alice@example.com", "Kubernetes", Some(4)).unwrap().level, Some(4));
+
+        let alice = repo.find_by_email("alice@example.com").unwrap().unwrap();
+        let skills: Vec<_> = alice.skills.iter().map(|s| (s.skill.as_str(), s.level)).collect();
+        assert_eq!(skills, vec![("go", None), ("kubernetes", Some(4))]);
+        let json = serde_json::to_value(&alice).unwrap();
+        assert_eq!(json["skills"][1]["skill"], "kubernetes");
+
+        let found: Vec<_> = repo
+            .find_people_by_skill("Kubernetes", None)
+            .unwrap()
+            .into_iter()
+            .map(|p| p.email)
+            .collect();
+        assert_eq!(found, ["bob@example.com", "alice@example.com", "carol@example.com"]);
+        let experts = repo.find_people_by_skill("kubernetes", Some(5)).unwrap();
+        assert_eq!(experts.len(), 1);
+        assert_eq!(experts[0].email, "bob@example.com");
+        repo.deactivate("bob@example.com", false).unwrap();
+        assert!(repo.find_people_by_skill("kubernetes", Some(5)).unwrap().is_empty());
+
+        // Unknown people and skills, bad levels and blank names
+        assert!(matches!(
+            repo.add_skill("nobody@example.com", "go", None).unwrap_err(),
+            Error::NotFound { entity: "Person", .. }
+        ));
+        for level in [0, 6] {
+            assert!(matches!(
+                repo.add_skill("alice@example.com", "rust", Some(level)).unwrap_err(),
+                Error::Invalid(_)
+            ));
+        }
+        assert!(matches!(repo.find_people_by_skill("go", Some(9)).unwrap_err(), Error::Invalid(_)));
+        assert!(matches!(repo.add_skill("alice@example.com", "  ", None).unwrap_err(), Error::Invalid(_)));
+
+        repo.remove_skill("alice@example.com", "GO").unwrap();
+        assert!(matches!(
+            repo.remove_skill("alice@example.com", "go").unwrap_err(),
+            Error::NotFound { .. }
+        ));
+        assert_eq!(repo.get_skills("alice@example.com").unwrap().len(), 1);
+
+        // Skills go with the person
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+        repo.delete("carol@example.com", false).unwrap();
+        let left: i64 = conn
+            .query_row("SELECT COUNT(*) FROM person_skills WHERE person_email = 'carol@example.com'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(left, 0);
+    }
+
+    // Person note tests
+
+    #[test]
+    fn test_availability_forecast() {
+        let conn = setup_test_db();
+        let people = PersonRepository::new(&conn);
+        let projects = db::ProjectRepository::new(&conn);
+        let now: DateTime<Utc> = "2025-06-01T00:00:00Z".parse().unwrap();
+
+        for (email, team) in [
+            ("alice", Some("Platform")),
+            ("bob", Some("Platform")),
+            ("carol", Some("Platform")),
+            ("frank", Some("Platform")),
+            ("dave", Some("Mobile")),
+            ("gina", Some("Mobile")),
+            ("erin", None),
+        ] {
+            let mut person = Person::new(format!("{}@example.com", email), email.to_string());
+            person.team = team.map(str::to_string);
+            people.create(&person).unwrap();
+        }
+
+        let apollo = db::Project::new("Apollo".to_string());
+        projects.create(&apollo).unwrap();
+        let mut finished = db::Project::new("Finished".to_string());
+        finished.status = db::ProjectStatus::Done;
+        projects.create(&finished).unwrap();
+
+        let milestone = |project: &db::Project, number: i32, due_in_days: Option<i64>| {
+            let mut milestone = db::Milestone::new(project.id, number, format!("M{}", number));
+            milestone.due_date = due_in_days.map(|days| now + Duration::days(days));
+            projects.add_milestone(&milestone).unwrap();
+            milestone.id
+        };
+        let soon = milestone(&apollo, 1, Some(30));
+        let late = milestone(&apollo, 2, Some(200));
+        let undated = milestone(&apollo, 3, None);
+        let past = milestone(&apollo, 4, Some(-10));
+        let overdue = milestone(&apollo, 5, Some(-5));
+        let done_project = milestone(&finished, 1, Some(300));
+        let checked = milestone(&apollo, 6, Some(60));
+
+        // A past milestone is open while its checklist isn't done, and a
+        // checked-off one is done before its due date
+        let checklists = db::ChecklistRepository::new(&conn);
+        checklists.add_item(&overdue, "Sign-off").unwrap();
+        let item = checklists.add_item(&checked, "Sign-off").unwrap();
+        checklists.toggle_item(&item.id, Some(true)).unwrap();
+
+        for (email, milestones) in [
+            ("alice", vec![soon, late]),
+            ("bob", vec![soon, undated]),
+            ("dave", vec![undated]),
+            ("gina", vec![overdue]),
+            ("erin", vec![past, done_project, checked]),
+            ("frank", vec![soon]),
+        ] {
+            for id in milestones {
+                let resource = db::MilestoneResource::new(id, format!("{}@example.com", email));
+                projects.add_milestone_resource(&id, &resource).unwrap();
+            }
+        }
+        people.deactivate("frank@example.com", false).unwrap();
+
+        let forecast = people.get_availability_forecast(120, now).unwrap();
+        assert_eq!(forecast.until, now + Duration::days(120));
+        assert_eq!(
+            (forecast.unassigned, forecast.freeing_up, forecast.busy, forecast.unknown),
+            (2, 2, 1, 1)
+        );
+        let teams: Vec<_> = forecast
+            .teams
+            .iter()
+            .map(|team| {
+                let people: Vec<_> = team
+                    .people
+                    .iter()
+                    .map(|p| (p.name.as_str(), p.status, p.free_on.map(|date| (date - now).num_days())))
+                    .collect();
+                (team.team.as_deref(), people)
+            })
+            .collect();
+        assert_eq!(
+            teams,
+            [
+                (
+                    Some("Mobile"),
+                    vec![("gina", AvailabilityStatus::FreesUp, Some(-5)), ("dave", AvailabilityStatus::Unknown, None)]
+                ),
+                (
+                    Some("Platform"),
+                    vec![
+                        ("carol", AvailabilityStatus::Unassigned, None),
+                        ("bob", AvailabilityStatus::FreesUp, Some(30)),
+                        ("alice", AvailabilityStatus::Busy, Some(200)),
+                    ]
+                ),
+                (None, vec![("erin", AvailabilityStatus::Unassigned, None)]),
+            ]
+        );
+        let bob = &forecast.teams[1].people[1];
+        assert_eq!((bob.open_milestones, bob.undated_milestones), (2, 1));
+
+        // A longer horizon frees up everyone with a dated milestone
+        let forecast = people.get_availability_forecast(365, now).unwrap();
+        assert_eq!((forecast.freeing_up, forecast.busy), (3, 0));
+    }
+
+    #[test]
+    fn test_person_notes_crud() {
+        let conn = setup_test_db();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+        let repo = PersonRepository::new(&conn);
+        repo.create(&Person::new("alice@example.com".to_string(), "Alice Smith".to_string()))
+            .unwrap();
+
+        let mut first = PersonNote::new("alice@example.com".to_string(), "Intro".to_string(), "Met at kickoff".to_string());
+        first.created_at -= chrono::Duration::days(1);
+        repo.add_note(&first).unwrap();
+        let second = PersonNote::new("alice@example.com".to_string(), "1:1".to_string(), "Wants more ownership".to_string());
+        repo.add_note(&second).unwrap();
+
+        let notes = repo.list_notes("alice@example.com", None).unwrap();
+        assert_eq!(notes.iter().map(|n| n.id).collect::<Vec<_>>(), vec![second.id, first.id]);
+        assert_eq!(repo.list_notes("alice@example.com", Some(1)).unwrap().len(), 1);
+
+        let mut note = repo.find_note_by_id(&first.id).unwrap().unwrap();
+        note.body = "Met at the kickoff meeting".to_string();
+        repo.update_note(&note).unwrap();
+        let updated = repo.find_note_by_id(&first.id).unwrap().unwrap();
+        assert_eq!(updated.body, "Met at the kickoff meeting");
+        assert_eq!(updated.version, 2);
+        assert!(matches!(repo.update_note(&note).unwrap_err(), Error::VersionConflict { .. }));
+
+        repo.delete_note(&first.id).unwrap();
+        assert!(matches!(repo.delete_note(&first.id).unwrap_err(), Error::NotFound { .. }));
+
+        // Notes need a person and go with them
+        let orphan = PersonNote::new("nobody@example.com".to_string(), "Lost".to_string(), String::new());
+        match repo.add_note(&orphan).unwrap_err() {
+            Error::ForeignKeyViolation { field } => assert_eq!(field, "person_email"),
+            err => panic!("Expected a foreign key violation, got {:?}", err),
+        }
+        repo.delete("alice@example.com", false).unwrap();
+        assert!(repo.find_note_by_id(&second.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_change_email_rejects_existing_and_missing() {
+        let conn = setup_test_db();
+        let repo = PersonRepository::new(&conn);
+        repo.create(&Person::new("alice@example.com".to_string(), "Alice Smith".to_string()))
+            .unwrap();
+        repo.create(&Person::new("bob@example.com".to_string(), "Bob Jones".to_string()))
+            .unwrap();
+
+        let err = repo.change_email("alice@example.com", "bob@example.com").unwrap_err();
+        assert!(matches!(err, Error::Conflict(_)));
+        assert!(repo.find_by_email("alice@example.com").unwrap().is_some());
+
+        let err = repo.change_email("nobody@example.com", "new@example.com").unwrap_err();
+        assert!(matches!(err, Error::NotFound { entity: "Person", .. }));
+        assert!(repo.find_by_email("new@example.com").unwrap().is_none());
+
+        assert!(matches!(
+            repo.change_email("alice@example.com", " ").unwrap_err(),
+            Error::Invalid(_)
+        ));
     }
 }
@@ -2,118 +2,1203 @@
 //
 // SPDX-License-Identifier: MIT
 
-use super::models::{Milestone, MilestoneNote, MilestoneResource, Project, ProjectNote, ProjectResource, ProjectStakeholder, StakeholderNote};
-use anyhow::Result;
-use chrono::Utc;
-use rusqlite::{params, Connection, OptionalExtension};
+use super::error::{Error, Result};
+use super::{begin_or_join, get_datetime, get_opt_datetime, get_opt_uuid, get_uuid};
+use super::models::{ActionItem, ActionItemStatus, ActivityItem, ActivityKind, BlockerEntry, BoardColumn, BriefDecision, BriefMilestone, BriefNote, BriefStakeholder, DateChange, BudgetStatus, EffortSummary, ExternalRefTarget, FieldChange, FocusItem, FocusKind, Initiative, Milestone, MilestoneChange, MilestoneEffort, MilestoneNote, MilestoneResource, MilestoneSlippage, NewNote, NextMilestone, NoteActivity, NoteCursor, NoteKind, NoteSlice, NoteSummary, NoteTarget, NoteType, PhaseChange, PhaseDuration, Project, ProjectBrief, ProjectBundle, ProjectDashboard, ProjectDocument, ProjectExpense, ProjectNote, ProjectResource, ProjectRisk, ProjectSnapshot, ProjectStakeholder, ProjectStatus, ProjectSummary, QuarterPlan, QuarterProject, QuarterTeam, ResourceLimitWarning, ResourceSuggestion, RiskStatus, SnapshotDiff, StakeholderBrief, StakeholderMatrix, StakeholderNote, TeamAssignment};
+use super::attachment_repo::AttachmentRepository;
+use super::checklist_repo::ChecklistRepository;
+use super::custom_field_repo::CustomFieldRepository;
+use super::decision_repo::DecisionRepository;
+use super::external_ref_repo::{merge_legacy_jira, ExternalRefRepository};
+use super::initiative_repo::InitiativeRepository;
+use super::note_link_repo::NoteLinkRepository;
+use super::organization_repo::OrganizationRepository;
+use super::person_repo::PersonRepository;
+use super::retrospective_repo::RetrospectiveRepository;
+use super::role_repo::RoleRepository;
+use super::sync::{self, SyncEntity};
+use super::team_repo::TeamRepository;
+use super::watcher_repo::WatcherRepository;
+use crate::notes;
+use crate::utils::{dt_to_db, is_http_url, is_valid_slug, slugify, start_of_local_day};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use chrono_tz::Tz;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Transaction, TransactionBehavior};
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
 use uuid::Uuid;
 
+/// Space left between neighbouring board positions, so most moves only renumber the moved project
+const BOARD_GAP: i64 = 1024;
+
+/// Stored start and due dates of a project or milestone
+type StoredDates = (Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+/// Columns selected for a project, in the order `project_from_row` expects
+pub(super) const PROJECT_COLUMNS: &str =
+    "id, name, description, type, requirements_owner, technical_lead, manager, team, start_date, due_date, \
+     jira_initiative, created_at, updated_at, version, blocked, blocked_reason, budget_amount, budget_currency, \
+     slug, status, board_position, phase";
+
+/// [`PROJECT_COLUMNS`] qualified with the `p` alias, for queries that join other tables
+pub(super) const PROJECT_COLUMNS_P: &str =
+    "p.id, p.name, p.description, p.type, p.requirements_owner, p.technical_lead, p.manager, p.team, \
+     p.start_date, p.due_date, p.jira_initiative, p.created_at, p.updated_at, p.version, p.blocked, \
+     p.blocked_reason, p.budget_amount, p.budget_currency, p.slug, p.status, p.board_position, p.phase";
+
+/// Map a row selected with `PROJECT_COLUMNS` to a project
+pub(super) fn project_from_row(row: &rusqlite::Row) -> rusqlite::Result<Project> {
+    Ok(Project {
+        id: get_uuid(row, 0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        project_type: row.get(3)?,
+        requirements_owner: row.get(4)?,
+        technical_lead: row.get(5)?,
+        manager: row.get(6)?,
+        team: row.get(7)?,
+        start_date: get_opt_datetime(row, 8)?,
+        due_date: get_opt_datetime(row, 9)?,
+        jira_initiative: row.get(10)?,
+        created_at: get_datetime(row, 11)?,
+        updated_at: get_datetime(row, 12)?,
+        version: row.get(13)?,
+        blocked: row.get(14)?,
+        blocked_reason: row.get(15)?,
+        budget_amount: row.get(16)?,
+        budget_currency: row.get(17)?,
+        slug: row.get(18)?,
+        status: parse_column(row, 19)?,
+        board_position: row.get(20)?,
+        phase: row.get(21)?,
+        custom_fields: BTreeMap::new(),
+        external_refs: Vec::new(),
+    })
+}
+
+/// First moment of a calendar quarter and of the quarter after it, at
+/// midnight in `tz`
+pub fn quarter_bounds(year: i32, quarter: u32, tz: Tz) -> Result<(DateTime<Utc>, DateTime<Utc>)> {
+    if !(1..=4).contains(&quarter) {
+        return Err(Error::Invalid(format!("Quarter must be 1 to 4, not {}", quarter)));
+    }
+    let first_month = (quarter - 1) * 3 + 1;
+    let start_date = NaiveDate::from_ymd_opt(year, first_month, 1)
+        .ok_or_else(|| Error::Invalid(format!("Year out of range: {}", year)))?;
+    let end_date = if quarter == 4 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(year, first_month + 3, 1)
+    }
+    .ok_or_else(|| Error::Invalid(format!("Year out of range: {}", year)))?;
+    Ok((start_of_local_day(start_date, tz), start_of_local_day(end_date, tz)))
+}
+
+/// The reason to store for a project, which is only kept while it is blocked
+fn blocked_reason(project: &Project) -> Option<&str> {
+    project.blocked_reason.as_deref().filter(|_| project.blocked)
+}
+
+/// A project's blocker state as stored: whether it is blocked, and why
+fn blocker_state(project: &Project) -> (bool, Option<&str>) {
+    (project.blocked, blocked_reason(project))
+}
+
+/// Map a blocker_history row to a blocker entry
+fn blocker_entry_from_row(row: &rusqlite::Row) -> rusqlite::Result<BlockerEntry> {
+    Ok(BlockerEntry {
+        project_id: get_uuid(row, 0)?,
+        reason: row.get(1)?,
+        blocked_at: get_datetime(row, 2)?,
+        cleared_at: get_opt_datetime(row, 3)?,
+    })
+}
+
+/// Map a row of `phase_history` to a phase change
+fn phase_change_from_row(row: &rusqlite::Row) -> rusqlite::Result<PhaseChange> {
+    Ok(PhaseChange {
+        project_id: get_uuid(row, 0)?,
+        from_phase: row.get(1)?,
+        to_phase: row.get(2)?,
+        backwards: row.get(3)?,
+        changed_at: get_datetime(row, 4)?,
+    })
+}
+
+/// Add up the time spent in each phase from a project's phase history, oldest change first
+///
+/// A phase lasts from the change that entered it until the next change, or
+/// until `now` for the current phase. Phases are listed in the order they
+/// were first entered.
+pub fn phase_durations(history: &[PhaseChange], now: DateTime<Utc>) -> Vec<PhaseDuration> {
+    let mut totals: Vec<(String, Duration)> = Vec::new();
+    for (i, change) in history.iter().enumerate() {
+        let Some(phase) = &change.to_phase else { continue };
+        let end = history.get(i + 1).map_or(now, |next| next.changed_at);
+        let spent = (end - change.changed_at).max(Duration::zero());
+        match totals.iter_mut().find(|(p, _)| p == phase) {
+            Some((_, total)) => *total += spent,
+            None => totals.push((phase.clone(), spent)),
+        }
+    }
+    let current = history.last().and_then(|change| change.to_phase.as_deref());
+    totals
+        .into_iter()
+        .map(|(phase, total)| PhaseDuration {
+            current: current == Some(phase.as_str()),
+            days: total.num_days(),
+            phase,
+        })
+        .collect()
+}
+
+/// Urgency of a focus list item; higher comes first
+///
+/// Each kind starts from a base weight, from overdue milestones down to
+/// missing fields. `days_late` is how many days past the point the item
+/// needed attention it is, negative while that is still ahead: each day late
+/// adds two points, up to 30 days, and each day ahead takes two off, up to 10.
+pub fn focus_score(kind: FocusKind, days_late: i64) -> i32 {
+    let base = match kind {
+        FocusKind::OverdueMilestone => 60,
+        FocusKind::ActionItemDue => 50,
+        FocusKind::BlockedProject => 40,
+        FocusKind::StakeholderFollowUp => 20,
+        FocusKind::MissingFields => 10,
+    };
+    base + 2 * days_late.clamp(-10, 30) as i32
+}
+
+/// Describe a day relative to today, such as "today", "2 days ago" or "in 3 days"
+fn relative_days(days_late: i64) -> String {
+    match days_late {
+        0 => "today".to_string(),
+        1 => "1 day ago".to_string(),
+        -1 => "in 1 day".to_string(),
+        days if days > 0 => format!("{} days ago", days),
+        days => format!("in {} days", -days),
+    }
+}
+
+/// Map a row selected with the milestone column list to a milestone
+fn milestone_from_row(row: &rusqlite::Row) -> rusqlite::Result<Milestone> {
+    Ok(Milestone {
+        id: get_uuid(row, 0)?,
+        project_id: get_uuid(row, 1)?,
+        number: row.get(2)?,
+        name: row.get(3)?,
+        description: row.get(4)?,
+        technical_lead: row.get(5)?,
+        team: row.get(6)?,
+        design_doc_url: row.get(7)?,
+        start_date: get_opt_datetime(row, 8)?,
+        due_date: get_opt_datetime(row, 9)?,
+        jira_epic: row.get(10)?,
+        created_at: get_datetime(row, 11)?,
+        updated_at: get_datetime(row, 12)?,
+        recurrence_rule: row.get(13)?,
+        series_id: get_opt_uuid(row, 14)?,
+        series_index: row.get(15)?,
+        version: row.get(16)?,
+        estimated_days: row.get(17)?,
+        actual_days: row.get(18)?,
+        checklist: Vec::new(),
+        checklist_completion: None,
+        external_refs: Vec::new(),
+    })
+}
+
+/// Map a row selected with the project_notes column list to a project note
+fn project_note_from_row(row: &rusqlite::Row) -> rusqlite::Result<ProjectNote> {
+    Ok(ProjectNote {
+        id: get_uuid(row, 0)?,
+        project_id: get_uuid(row, 1)?,
+        title: row.get(2)?,
+        body: row.get(3)?,
+        created_at: get_datetime(row, 4)?,
+        updated_at: get_datetime(row, 5)?,
+        version: row.get(6)?,
+        kind: row.get::<_, String>(7)?.parse().unwrap_or_default(),
+        attachments: Vec::new(),
+    })
+}
+
+/// Map a row selected with the project_documents column list to a document
+fn project_document_from_row(row: &rusqlite::Row) -> rusqlite::Result<ProjectDocument> {
+    Ok(ProjectDocument {
+        id: get_uuid(row, 0)?,
+        project_id: get_uuid(row, 1)?,
+        title: row.get(2)?,
+        url: row.get(3)?,
+        doc_type: row.get(4)?,
+        created_at: get_datetime(row, 5)?,
+    })
+}
+
+/// Only link to documents over http(s), so links are safe to open from the UI
+fn validate_document_url(url: &str) -> Result<()> {
+    if !is_http_url(url) {
+        return Err(Error::Invalid(format!(
+            "Invalid document URL '{}': only http and https links are allowed",
+            url
+        )));
+    }
+    Ok(())
+}
+
+/// Map a project_expenses row to an expense
+fn project_expense_from_row(row: &rusqlite::Row) -> rusqlite::Result<ProjectExpense> {
+    Ok(ProjectExpense {
+        id: get_uuid(row, 0)?,
+        project_id: get_uuid(row, 1)?,
+        description: row.get(2)?,
+        amount: row.get(3)?,
+        incurred_at: get_datetime(row, 4)?,
+        created_at: get_datetime(row, 5)?,
+    })
+}
+
+/// Reject an amount of money that is negative or not a number
+fn validate_amount(field: &str, amount: f64) -> Result<()> {
+    if !amount.is_finite() || amount < 0.0 {
+        return Err(Error::Invalid(format!(
+            "Invalid {} {}: must be zero or more",
+            field, amount
+        )));
+    }
+    Ok(())
+}
+
+/// Check a project's budget: a non-negative amount and a three-letter currency code
+///
+/// The currency is kept as given; amounts are never converted between currencies.
+fn validate_budget(project: &Project) -> Result<()> {
+    if let Some(amount) = project.budget_amount {
+        validate_amount("budget amount", amount)?;
+    }
+    if let Some(currency) = &project.budget_currency {
+        if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(Error::Invalid(format!(
+                "Invalid budget currency '{}': expected a three-letter code such as USD",
+                currency
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Map a project_snapshots row selected without its data to a snapshot
+fn project_snapshot_from_row(row: &rusqlite::Row) -> rusqlite::Result<ProjectSnapshot> {
+    Ok(ProjectSnapshot {
+        id: get_uuid(row, 0)?,
+        project_id: get_uuid(row, 1)?,
+        label: row.get(2)?,
+        created_at: get_datetime(row, 3)?,
+    })
+}
+
+/// Fields left out when comparing snapshots: identity and bookkeeping, not the plan
+const SNAPSHOT_IGNORED_FIELDS: &[&str] = &["id", "project_id", "created_at", "updated_at", "version"];
+
+/// Compare the JSON forms of two records field by field, in field name order
+///
+/// Nested objects such as custom field values are compared key by key and
+/// reported as `parent.key`.
+fn field_changes<T: serde::Serialize>(old: &T, new: &T) -> Result<Vec<FieldChange>> {
+    fn flatten(prefix: &str, value: serde_json::Value, fields: &mut BTreeMap<String, serde_json::Value>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, value) in map {
+                    if prefix.is_empty() && SNAPSHOT_IGNORED_FIELDS.contains(&key.as_str()) {
+                        continue;
+                    }
+                    let name = if prefix.is_empty() { key } else { format!("{}.{}", prefix, key) };
+                    flatten(&name, value, fields);
+                }
+            }
+            value => {
+                fields.insert(prefix.to_string(), value);
+            }
+        }
+    }
+
+    let mut old_fields = BTreeMap::new();
+    flatten("", serde_json::to_value(old).map_err(anyhow::Error::from)?, &mut old_fields);
+    let mut new_fields = BTreeMap::new();
+    flatten("", serde_json::to_value(new).map_err(anyhow::Error::from)?, &mut new_fields);
+
+    let names: std::collections::BTreeSet<&String> = old_fields.keys().chain(new_fields.keys()).collect();
+    Ok(names
+        .into_iter()
+        .filter_map(|name| {
+            let old = old_fields.get(name).cloned().unwrap_or(serde_json::Value::Null);
+            let new = new_fields.get(name).cloned().unwrap_or(serde_json::Value::Null);
+            (old != new).then(|| FieldChange { field: name.clone(), old, new })
+        })
+        .collect())
+}
+
+/// Sort milestones by number, then name and ID, so diffs come out the same every time
+fn sort_milestones(milestones: &mut [Milestone]) {
+    milestones.sort_by(|a, b| (a.number, &a.name, a.id).cmp(&(b.number, &b.name, b.id)));
+}
+
+/// Columns selected for a risk, in the order `project_risk_from_row` expects
+const RISK_COLUMNS: &str = "id, project_id, title, description, severity, likelihood, mitigation, owner_email, status, created_at, updated_at";
+
+/// Map a row selected with `RISK_COLUMNS` to a project risk
+fn project_risk_from_row(row: &rusqlite::Row) -> rusqlite::Result<ProjectRisk> {
+    Ok(ProjectRisk {
+        id: get_uuid(row, 0)?,
+        project_id: get_uuid(row, 1)?,
+        title: row.get(2)?,
+        description: row.get(3)?,
+        severity: parse_column(row, 4)?,
+        likelihood: parse_column(row, 5)?,
+        mitigation: row.get(6)?,
+        owner_email: row.get(7)?,
+        status: parse_column(row, 8)?,
+        created_at: get_datetime(row, 9)?,
+        updated_at: get_datetime(row, 10)?,
+    })
+}
+
+/// Read a text column holding one of the risk enums
+pub(super) fn parse_column<T: FromStr<Err = Error>>(row: &rusqlite::Row, idx: usize) -> rusqlite::Result<T> {
+    row.get::<_, String>(idx)?.parse().map_err(|e: Error| {
+        rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+/// Map a row selected with the project_stakeholders column list to a stakeholder
+fn stakeholder_from_row(row: &rusqlite::Row) -> rusqlite::Result<ProjectStakeholder> {
+    Ok(ProjectStakeholder {
+        project_id: get_uuid(row, 0)?,
+        stakeholder_email: row.get(1)?,
+        role: row.get(2)?,
+        influence: row.get(3)?,
+        interest: row.get(4)?,
+        created_at: get_datetime(row, 5)?,
+    })
+}
+
+/// Influence and interest are rated from 1 to 5 when given
+fn validate_stakeholder_ratings(stakeholder: &ProjectStakeholder) -> Result<()> {
+    for (field, rating) in [("influence", stakeholder.influence), ("interest", stakeholder.interest)] {
+        if let Some(rating) = rating {
+            if !(1..=5).contains(&rating) {
+                return Err(Error::Invalid(format!(
+                    "Invalid {} {}: must be from 1 to 5",
+                    field, rating
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A risk needs a title to be listed anywhere
+fn validate_risk(risk: &ProjectRisk) -> Result<()> {
+    if risk.title.trim().is_empty() {
+        return Err(Error::Invalid("Risk title cannot be empty".to_string()));
+    }
+    Ok(())
+}
+
+/// A milestone can't start after it is due
+fn validate_milestone_dates(milestone: &Milestone) -> Result<()> {
+    if let (Some(start_date), Some(due_date)) = (milestone.start_date, milestone.due_date) {
+        if start_date > due_date {
+            return Err(Error::Invalid(format!(
+                "Milestone start date {} is after its due date {}",
+                start_date.format("%Y-%m-%d"),
+                due_date.format("%Y-%m-%d")
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Reject negative or non-finite effort
+fn validate_milestone_effort(milestone: &Milestone) -> Result<()> {
+    for (field, days) in [("estimated_days", milestone.estimated_days), ("actual_days", milestone.actual_days)] {
+        if let Some(days) = days {
+            if !days.is_finite() || days < 0.0 {
+                return Err(Error::Invalid(format!("Milestone {} must be a non-negative number of days, got {}", field, days)));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Columns selected for an action item, in the order `action_item_from_row` expects
+const ACTION_ITEM_COLUMNS: &str = "id, project_id, milestone_id, assignee_email, description, due_date, status, source_note_id, completed_at, created_at, updated_at";
+
+/// Map a row selected with `ACTION_ITEM_COLUMNS` to an action item
+fn action_item_from_row(row: &rusqlite::Row) -> rusqlite::Result<ActionItem> {
+    Ok(ActionItem {
+        id: get_uuid(row, 0)?,
+        project_id: get_uuid(row, 1)?,
+        milestone_id: get_opt_uuid(row, 2)?,
+        assignee_email: row.get(3)?,
+        description: row.get(4)?,
+        due_date: get_opt_datetime(row, 5)?,
+        status: parse_column(row, 6)?,
+        source_note_id: get_opt_uuid(row, 7)?,
+        completed_at: get_opt_datetime(row, 8)?,
+        created_at: get_datetime(row, 9)?,
+        updated_at: get_datetime(row, 10)?,
+    })
+}
+
+/// Bind values for a note page query: the cursor's timestamp and ID, or NULLs for the first page
+fn note_cursor_params(cursor: Option<NoteCursor>) -> (Option<String>, Option<String>) {
+    match cursor {
+        Some(cursor) => (Some(dt_to_db(cursor.created_at)), Some(cursor.id.to_string())),
+        None => (None, None),
+    }
+}
+
+/// Map a row selected with the milestone_notes column list to a milestone note
+fn milestone_note_from_row(row: &rusqlite::Row) -> rusqlite::Result<MilestoneNote> {
+    Ok(MilestoneNote {
+        id: get_uuid(row, 0)?,
+        milestone_id: get_uuid(row, 1)?,
+        title: row.get(2)?,
+        body: row.get(3)?,
+        created_at: get_datetime(row, 4)?,
+        updated_at: get_datetime(row, 5)?,
+        version: row.get(6)?,
+        attachments: Vec::new(),
+    })
+}
+
+/// Map a row selected with the stakeholder_notes column list to a stakeholder note
+fn stakeholder_note_from_row(row: &rusqlite::Row) -> rusqlite::Result<StakeholderNote> {
+    Ok(StakeholderNote {
+        id: get_uuid(row, 0)?,
+        project_id: get_uuid(row, 1)?,
+        stakeholder_email: row.get(2)?,
+        title: row.get(3)?,
+        body: row.get(4)?,
+        created_at: get_datetime(row, 5)?,
+        updated_at: get_datetime(row, 6)?,
+        version: row.get(7)?,
+        attachments: Vec::new(),
+    })
+}
+
+/// Summary query shared by `get_project_summary` and `list_project_summaries`
+///
+/// `?1` is the current time, `?2` the start of the recent notes window, `?3`
+/// an optional project ID to restrict the results to and `?4` whether
+/// checklists count towards progress. Each milestone's progress is the share
+/// of its checklist that is checked when that's counted and it has one, and
+/// otherwise 1 once its due date has passed.
+const PROJECT_SUMMARY_SQL: &str = "
+    WITH next_milestones AS (
+        SELECT project_id, id, name, due_date,
+               ROW_NUMBER() OVER (PARTITION BY project_id ORDER BY due_date, number) AS position
+        FROM milestones WHERE due_date >= ?1
+    ),
+    checklists AS (
+        SELECT milestone_id, COUNT(*) AS total, SUM(checked) AS checked
+        FROM milestone_checklist_items GROUP BY milestone_id
+    ),
+    milestone_progress AS (
+        SELECT m.project_id,
+               CASE WHEN ?4 AND c.total > 0 THEN CAST(c.checked AS REAL) / c.total
+                    WHEN m.due_date < ?1 THEN 1.0
+                    ELSE 0.0 END AS done,
+               NOT (?4 AND c.total > 0) AS estimated
+        FROM milestones m LEFT JOIN checklists c ON c.milestone_id = m.id
+    )
+    SELECT p.id, p.name,
+           (SELECT COUNT(*) FROM milestone_progress mp WHERE mp.project_id = p.id),
+           (SELECT COUNT(*) FROM milestone_progress mp WHERE mp.project_id = p.id AND mp.done >= 1.0),
+           (SELECT COUNT(*) FROM project_notes n WHERE n.project_id = p.id AND n.created_at >= ?2),
+           nm.id, nm.name, nm.due_date,
+           (SELECT COALESCE(SUM(mp.done), 0.0) FROM milestone_progress mp WHERE mp.project_id = p.id),
+           (SELECT COUNT(*) FROM milestone_progress mp WHERE mp.project_id = p.id AND mp.estimated),
+           p.phase
+    FROM projects p
+    LEFT JOIN next_milestones nm ON nm.project_id = p.id AND nm.position = 1
+    WHERE ?3 IS NULL OR p.id = ?3
+    ORDER BY p.name";
+
+/// Map a row selected with `PROJECT_SUMMARY_SQL` to a project summary
+///
+/// Phase durations come from the phase history and are filled in afterwards.
+fn project_summary_from_row(row: &rusqlite::Row) -> rusqlite::Result<ProjectSummary> {
+    let total_milestones: usize = row.get(2)?;
+    let completed_milestones: usize = row.get(3)?;
+    let progress: f64 = row.get(8)?;
+    let estimated: usize = row.get(9)?;
+    let next_milestone = match get_opt_uuid(row, 5)? {
+        Some(id) => Some(NextMilestone {
+            id,
+            name: row.get(6)?,
+            due_date: get_datetime(row, 7)?,
+        }),
+        None => None,
+    };
+
+    Ok(ProjectSummary {
+        project_id: get_uuid(row, 0)?,
+        project_name: row.get(1)?,
+        total_milestones,
+        completed_milestones,
+        completion_estimated: estimated > 0,
+        progress_percent: (total_milestones > 0)
+            .then(|| (progress * 100.0 / total_milestones as f64).floor() as u8),
+        next_milestone,
+        recent_notes: row.get(4)?,
+        phase: row.get(10)?,
+        phase_durations: Vec::new(),
+    })
+}
+
+/// Shortest ID prefix accepted in place of a full project or milestone ID
+pub const MIN_ID_PREFIX_LEN: usize = 8;
+
+/// Lowest influence or interest rating that counts as high in a stakeholder matrix
+pub const HIGH_RATING: i32 = 4;
+
+/// Number of recent notes included in a stakeholder brief unless asked otherwise
+pub const DEFAULT_BRIEF_NOTE_LIMIT: usize = 5;
+
+/// Number of note titles in a project brief
+pub const BRIEF_NOTE_COUNT: usize = 3;
+
+/// Number of items in a project's activity feed unless asked otherwise
+pub const DEFAULT_ACTIVITY_LIMIT: usize = 50;
+
+/// Number of notes in a page of a note listing unless asked otherwise
+pub const DEFAULT_NOTE_PAGE_SIZE: usize = 50;
+
+/// Length of the excerpt in a note summary unless asked otherwise
+pub const DEFAULT_NOTE_EXCERPT_CHARS: usize = 280;
+
+/// Days ahead an action item's due date puts it on the focus list
+pub const FOCUS_DUE_SOON_DAYS: i64 = 3;
+
+/// Days an overdue milestone stays on the focus list
+pub const FOCUS_OVERDUE_DAYS: i64 = 14;
+
+/// Days without a stakeholder note before a stakeholder is listed for follow-up
+pub const DEFAULT_STAKEHOLDER_CONTACT_DAYS: u32 = 30;
+
+/// Risks scoring above this (severity weight times likelihood weight) are high risks
+pub const HIGH_RISK_THRESHOLD: i32 = 4;
+
 /// Project repository for database operations
 pub struct ProjectRepository<'a> {
     conn: &'a Connection,
+    project_types: Option<&'a [String]>,
+    roles: &'a [String],
+    allow_new_roles: bool,
+    max_resources_per_milestone: Option<usize>,
+    hard_resource_limit: bool,
+    checklist_progress: bool,
+    phases: &'a [String],
+    stakeholder_contact_days: u32,
 }
 
 impl<'a> ProjectRepository<'a> {
     pub fn new(conn: &'a Connection) -> Self {
-        Self { conn }
+        Self {
+            conn,
+            project_types: None,
+            roles: &[],
+            allow_new_roles: true,
+            max_resources_per_milestone: None,
+            hard_resource_limit: false,
+            checklist_progress: false,
+            phases: &[],
+            stakeholder_contact_days: DEFAULT_STAKEHOLDER_CONTACT_DAYS,
+        }
+    }
+
+    /// The connection the repository uses, for reading related records
+    pub(crate) fn conn(&self) -> &'a Connection {
+        self.conn
+    }
+
+    /// Only accept these project types when creating or updating projects
+    ///
+    /// An empty list accepts any type, as does a repository created without one.
+    pub fn with_project_types(mut self, project_types: &'a [String]) -> Self {
+        self.project_types = Some(project_types);
+        self
+    }
+
+    /// Store resource and stakeholder roles under these canonical roles
+    ///
+    /// Other roles are rejected unless `allow_new` is set. See
+    /// [`RoleRepository::with_roles`].
+    pub fn with_roles(mut self, roles: &'a [String], allow_new: bool) -> Self {
+        self.roles = roles;
+        self.allow_new_roles = allow_new;
+        self
+    }
+
+    /// Limit the number of resources on a milestone
+    ///
+    /// Going over `max` is allowed with a warning, or refused when `hard` is
+    /// set. `None` allows any number.
+    pub fn with_resource_limit(mut self, max: Option<usize>, hard: bool) -> Self {
+        self.max_resources_per_milestone = max;
+        self.hard_resource_limit = hard;
+        self
+    }
+
+    /// Count checked checklist items towards milestone progress in summaries
+    ///
+    /// A milestone with a checklist is then as far along as its checklist,
+    /// and complete once every item is checked, whatever its due date.
+    pub fn with_checklist_progress(mut self, enabled: bool) -> Self {
+        self.checklist_progress = enabled;
+        self
+    }
+
+    /// Only accept these phases, in this order, when setting a project's phase
+    ///
+    /// Moving to an earlier phase in the list is flagged in the phase
+    /// history. An empty list accepts any phase, as does a repository created
+    /// without one.
+    pub fn with_phases(mut self, phases: &'a [String]) -> Self {
+        self.phases = phases;
+        self
+    }
+
+    /// List stakeholders on the focus list after this many days without a note
+    ///
+    /// 0 leaves stakeholders off the focus list.
+    pub fn with_stakeholder_contact_days(mut self, days: u32) -> Self {
+        self.stakeholder_contact_days = days;
+        self
+    }
+
+    /// Map a resource or stakeholder role to the canonical spelling it is stored with
+    pub fn normalize_role(&self, role: Option<&str>) -> Result<Option<String>> {
+        RoleRepository::new(self.conn)
+            .with_roles(self.roles, self.allow_new_roles)
+            .normalize(role)
+    }
+
+    /// Reject a project whose type isn't one of the configured types
+    fn validate_project_type(&self, project: &Project) -> Result<()> {
+        match self.project_types {
+            Some(types) if !types.is_empty() && !types.contains(&project.project_type) => {
+                Err(Error::Invalid(format!(
+                    "Unknown project type '{}': expected one of {}",
+                    project.project_type,
+                    types.join(", ")
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Map a phase to the configured spelling it is stored with
+    ///
+    /// Phases are matched ignoring case, and rejected if they aren't
+    /// configured, unless no phases are.
+    fn canonical_phase(&self, phase: &str) -> Result<String> {
+        let phase = phase.trim();
+        if phase.is_empty() {
+            return Err(Error::Invalid("Project phase cannot be empty".to_string()));
+        }
+        if self.phases.is_empty() {
+            return Ok(phase.to_string());
+        }
+        self.phases
+            .iter()
+            .find(|p| p.eq_ignore_ascii_case(phase))
+            .cloned()
+            .ok_or_else(|| {
+                Error::Invalid(format!(
+                    "Unknown project phase '{}': expected one of {}",
+                    phase,
+                    self.phases.join(", ")
+                ))
+            })
     }
 
     /// Create a new project
     pub fn create(&self, project: &Project) -> Result<()> {
-        self.conn.execute(
+        self.validate_project_type(project)?;
+        validate_budget(project)?;
+        let phase = project.phase.as_deref().map(|p| self.canonical_phase(p)).transpose()?;
+
+        let mut stmt = self.conn.prepare_cached(
             "INSERT INTO projects (id, name, description, type, requirements_owner, technical_lead,
-                                  manager, team, start_date, due_date, jira_initiative, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
-            params![
-                project.id.to_string(),
-                &project.name,
-                &project.description,
-                &project.project_type,
-                &project.requirements_owner,
-                &project.technical_lead,
-                &project.manager,
-                &project.team,
-                project.start_date.map(|d| d.to_rfc3339()),
-                project.due_date.map(|d| d.to_rfc3339()),
-                &project.jira_initiative,
-                project.created_at.to_rfc3339(),
-                project.updated_at.to_rfc3339(),
-            ],
+                                  manager, team, start_date, due_date, jira_initiative, created_at, updated_at,
+                                  blocked, blocked_reason, budget_amount, budget_currency, slug, status, board_position,
+                                  phase)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21)",
         )?;
+        let tx = begin_or_join(self.conn)?;
+        // Keep a valid slug the caller chose, otherwise derive one from the name
+        let base = if is_valid_slug(&project.slug) {
+            project.slug.clone()
+        } else {
+            slugify(&project.name)
+        };
+        let slug = self.unique_slug(&base, &project.id)?;
+        stmt.execute(params![
+            project.id.to_string(),
+            &project.name,
+            &project.description,
+            &project.project_type,
+            &project.requirements_owner,
+            &project.technical_lead,
+            &project.manager,
+            &project.team,
+            project.start_date.map(dt_to_db),
+            project.due_date.map(dt_to_db),
+            &project.jira_initiative,
+            dt_to_db(project.created_at),
+            dt_to_db(project.updated_at),
+            project.blocked,
+            blocked_reason(project),
+            project.budget_amount,
+            &project.budget_currency,
+            &slug,
+            project.status.as_str(),
+            project.board_position,
+            &phase,
+        ])
+        .map_err(|e| {
+            let e = Error::from(e).with_field("requirements_owner, technical_lead or manager");
+            self.name_conflict(e, project)
+                .with_conflict(format!("A project with ID {} already exists", project.id))
+        })?;
+        self.record_blocker_change(&project.id, (false, None), blocker_state(project))?;
+        self.record_phase_change(&project.id, None, phase.as_deref())?;
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
         log::debug!("Created project: {} ({})", project.name, project.id);
         Ok(())
     }
 
+    /// Report a conflict caused by the unique name policy as `AlreadyExists`
+    ///
+    /// Other errors, and conflicts with the project itself, are returned unchanged.
+    fn name_conflict(&self, e: Error, project: &Project) -> Error {
+        if let Error::Conflict(_) = e {
+            if let Ok(Some(existing)) = self.find_by_name_exact(&project.name) {
+                if existing.id != project.id {
+                    return Error::AlreadyExists {
+                        entity: "Project",
+                        name: existing.name,
+                        id: existing.id.to_string(),
+                    };
+                }
+            }
+        }
+        e
+    }
+
+    /// Find the oldest project with the given name, ignoring ASCII case
+    pub fn find_by_name_exact(&self, name: &str) -> Result<Option<Project>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM projects WHERE name = ?1 COLLATE NOCASE ORDER BY created_at LIMIT 1",
+            PROJECT_COLUMNS
+        ))?;
+        let project = stmt.query_row(params![name], project_from_row).optional()?;
+        Ok(project)
+    }
+
     /// Find a project by ID
     pub fn find_by_id(&self, id: &Uuid) -> Result<Option<Project>> {
-        let project = self
-            .conn
-            .query_row(
-                "SELECT id, name, description, type, requirements_owner, technical_lead, manager, team,
-                        start_date, due_date, jira_initiative, created_at, updated_at
-                 FROM projects WHERE id = ?1",
-                params![id.to_string()],
-                |row| {
-                    Ok(Project {
-                        id: Uuid::parse_str(&row.get::<_, String>(0)?).unwrap(),
-                        name: row.get(1)?,
-                        description: row.get(2)?,
-                        project_type: row.get(3)?,
-                        requirements_owner: row.get(4)?,
-                        technical_lead: row.get(5)?,
-                        manager: row.get(6)?,
-                        team: row.get(7)?,
-                        start_date: row.get(8)?,
-                        due_date: row.get(9)?,
-                        jira_initiative: row.get(10)?,
-                        created_at: row.get(11)?,
-                        updated_at: row.get(12)?,
-                    })
-                },
-            )
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM projects WHERE id = ?1",
+            PROJECT_COLUMNS
+        ))?;
+        let mut project = stmt
+            .query_row(params![id.to_string()], project_from_row)
             .optional()?;
+        if let Some(project) = project.as_mut() {
+            project.custom_fields = CustomFieldRepository::new(self.conn).get_custom_values(id)?;
+            let refs = ExternalRefRepository::new(self.conn).list_stored(ExternalRefTarget::Project, id)?;
+            project.external_refs = merge_legacy_jira(
+                refs,
+                ExternalRefTarget::Project,
+                project.id,
+                project.jira_initiative.as_deref(),
+                project.created_at,
+            );
+        }
         Ok(project)
     }
 
     /// List all projects
     pub fn list_all(&self) -> Result<Vec<Project>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, name, description, type, requirements_owner, technical_lead, manager, team,
-                    start_date, due_date, jira_initiative, created_at, updated_at
-             FROM projects ORDER BY name",
-        )?;
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM projects ORDER BY name",
+            PROJECT_COLUMNS
+        ))?;
 
         let projects = stmt
-            .query_map([], |row| {
-                Ok(Project {
-                    id: Uuid::parse_str(&row.get::<_, String>(0)?).unwrap(),
-                    name: row.get(1)?,
-                    description: row.get(2)?,
-                    project_type: row.get(3)?,
-                    requirements_owner: row.get(4)?,
-                    technical_lead: row.get(5)?,
-                    manager: row.get(6)?,
-                    team: row.get(7)?,
-                    start_date: row.get(8)?,
-                    due_date: row.get(9)?,
-                    jira_initiative: row.get(10)?,
-                    created_at: row.get(11)?,
-                    updated_at: row.get(12)?,
+            .query_map([], project_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(projects)
+    }
+
+    /// List projects created at or after `since`, newest first
+    pub fn list_created_since(&self, since: DateTime<Utc>) -> Result<Vec<Project>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM projects WHERE created_at >= ?1 ORDER BY created_at DESC, name",
+            PROJECT_COLUMNS
+        ))?;
+
+        let projects = stmt
+            .query_map(params![dt_to_db(since)], project_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(projects)
+    }
+
+    /// Projects with the most notes created at or after `since`
+    ///
+    /// Project, milestone and stakeholder notes all count toward their
+    /// project. Busiest projects come first, at most `limit` of them.
+    pub fn note_activity_since(&self, since: DateTime<Utc>, limit: usize) -> Result<Vec<NoteActivity>> {
+        let mut stmt = self.conn.prepare_cached(
+            "WITH notes AS (
+                 SELECT project_id, created_at FROM project_notes WHERE created_at >= ?1
+                 UNION ALL
+                 SELECT m.project_id, mn.created_at
+                 FROM milestone_notes mn JOIN milestones m ON m.id = mn.milestone_id
+                 WHERE mn.created_at >= ?1
+                 UNION ALL
+                 SELECT project_id, created_at FROM stakeholder_notes WHERE created_at >= ?1
+             )
+             SELECT p.id, p.name, COUNT(*), MAX(n.created_at)
+             FROM notes n JOIN projects p ON p.id = n.project_id
+             GROUP BY p.id
+             ORDER BY COUNT(*) DESC, MAX(n.created_at) DESC, p.name
+             LIMIT ?2",
+        )?;
+
+        let activity = stmt
+            .query_map(params![dt_to_db(since), limit as i64], |row| {
+                Ok(NoteActivity {
+                    project_id: get_uuid(row, 0)?,
+                    project_name: row.get(1)?,
+                    note_count: row.get(2)?,
+                    last_note_at: get_datetime(row, 3)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
 
+        Ok(activity)
+    }
+
+    /// Resolve a project ID or a unique prefix of one
+    ///
+    /// Full IDs are returned without a lookup. Prefixes must be at least
+    /// [`MIN_ID_PREFIX_LEN`] characters and match exactly one project.
+    pub fn resolve_id_prefix(&self, prefix: &str) -> Result<Uuid> {
+        self.resolve_prefix("projects", "Project", prefix)
+    }
+
+    /// Resolve a project reference: a full ID, a slug, or a unique ID prefix
+    ///
+    /// Slugs are matched before prefixes, so a slug that happens to look like
+    /// hex digits still finds its project.
+    pub fn resolve_project_ref(&self, reference: &str) -> Result<Uuid> {
+        let reference = reference.trim();
+        if let Ok(id) = Uuid::parse_str(reference) {
+            return Ok(id);
+        }
+        if let Some(project) = self.find_by_slug(reference)? {
+            return Ok(project.id);
+        }
+        match self.resolve_id_prefix(reference) {
+            Err(Error::Other(_)) => Err(Error::not_found("Project", reference)),
+            result => result,
+        }
+    }
+
+    /// Find a project by its slug, ignoring case
+    pub fn find_by_slug(&self, slug: &str) -> Result<Option<Project>> {
+        let id: Option<String> = self
+            .conn
+            .prepare_cached("SELECT id FROM projects WHERE slug = ?1")?
+            .query_row(params![slug.to_lowercase()], |row| row.get(0))
+            .optional()?;
+        match id {
+            Some(id) => self.find_by_id(&Uuid::parse_str(&id).map_err(anyhow::Error::from)?),
+            None => Ok(None),
+        }
+    }
+
+    /// Change a project's slug
+    ///
+    /// With `None` the slug is regenerated from the project's current name,
+    /// adding a numeric suffix if another project already uses it. An explicit
+    /// slug must be valid and unused. Renaming a project keeps its slug, so
+    /// call this after a rename to pick up the new name. Returns the new slug.
+    pub fn set_slug(&self, id: &Uuid, slug: Option<&str>) -> Result<String> {
+        let project = self
+            .find_by_id(id)?
+            .ok_or_else(|| Error::not_found("Project", id))?;
+        let slug = match slug {
+            Some(slug) => {
+                let slug = slug.trim().to_lowercase();
+                if !is_valid_slug(&slug) {
+                    return Err(Error::Invalid(format!(
+                        "Invalid slug '{}': use lowercase letters, digits and single hyphens, up to {} characters",
+                        slug,
+                        crate::utils::MAX_SLUG_LEN
+                    )));
+                }
+                if let Some(existing) = self.find_by_slug(&slug)?.filter(|p| p.id != *id) {
+                    return Err(Error::AlreadyExists {
+                        entity: "Project slug",
+                        name: slug,
+                        id: existing.id.to_string(),
+                    });
+                }
+                slug
+            }
+            None => self.unique_slug(&slugify(&project.name), id)?,
+        };
+
+        self.conn.execute(
+            "UPDATE projects SET slug = ?1, updated_at = ?2, version = version + 1 WHERE id = ?3",
+            params![slug, dt_to_db(Utc::now()), id.to_string()],
+        )?;
+        log::debug!("Set slug of project {} to {}", id, slug);
+        Ok(slug)
+    }
+
+    /// Return `base`, or `base-2`, `base-3`, ... if another project already uses it
+    fn unique_slug(&self, base: &str, id: &Uuid) -> Result<String> {
+        let mut stmt = self
+            .conn
+            .prepare_cached("SELECT 1 FROM projects WHERE slug = ?1 AND id <> ?2")?;
+        let mut candidate = base.to_string();
+        let mut suffix = 2;
+        while stmt.exists(params![candidate, id.to_string()])? {
+            let tail = format!("-{}", suffix);
+            let head = base[..base.len().min(crate::utils::MAX_SLUG_LEN - tail.len())].trim_end_matches('-');
+            candidate = format!("{}{}", head, tail);
+            suffix += 1;
+        }
+        Ok(candidate)
+    }
+
+    /// Resolve a milestone ID or a unique prefix of one
+    pub fn resolve_milestone_id_prefix(&self, prefix: &str) -> Result<Uuid> {
+        self.resolve_prefix("milestones", "Milestone", prefix)
+    }
+
+    fn resolve_prefix(&self, table: &str, entity: &'static str, prefix: &str) -> Result<Uuid> {
+        if let Ok(id) = Uuid::parse_str(prefix) {
+            return Ok(id);
+        }
+
+        let prefix = prefix.to_ascii_lowercase();
+        if prefix.len() < MIN_ID_PREFIX_LEN
+            || !prefix.chars().all(|c| c.is_ascii_hexdigit() || c == '-')
+        {
+            return Err(Error::Other(anyhow::anyhow!(
+                "Invalid {} ID '{}': use the full ID or at least {} characters of it",
+                entity.to_lowercase(),
+                prefix,
+                MIN_ID_PREFIX_LEN
+            )));
+        }
+
+        // The prefix is hex digits and dashes, so it can't contain LIKE wildcards
+        let mut stmt = self
+            .conn
+            .prepare_cached(&format!("SELECT id FROM {} WHERE id LIKE ?1 || '%' ORDER BY id", table))?;
+        let ids = stmt
+            .query_map(params![prefix], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match ids.as_slice() {
+            [] => Err(Error::not_found(entity, prefix)),
+            [id] => Ok(Uuid::parse_str(id).map_err(anyhow::Error::from)?),
+            _ => Err(Error::AmbiguousId {
+                entity,
+                prefix,
+                matches: ids.len(),
+            }),
+        }
+    }
+
+    /// List a page of projects in creation order
+    ///
+    /// The order doesn't change when projects are renamed, so offsets stay
+    /// valid between calls unless projects are added or deleted.
+    pub fn list_page(&self, limit: usize, offset: usize) -> Result<Vec<Project>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM projects ORDER BY created_at, id LIMIT ?1 OFFSET ?2",
+            PROJECT_COLUMNS
+        ))?;
+
+        let projects = stmt
+            .query_map(params![limit as i64, offset as i64], project_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
         Ok(projects)
     }
 
+    /// Load every project along with its milestones, resources and stakeholders.
+    ///
+    /// Runs one query per collection (grouped with `project_id IN (...)`) instead of
+    /// one query per project, so the statement count does not grow with the number
+    /// of projects.
+    pub fn get_dashboard(&self) -> Result<Vec<ProjectDashboard>> {
+        let projects = self.list_all()?;
+        if projects.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids: Vec<String> = projects.iter().map(|p| p.id.to_string()).collect();
+        let placeholders = vec!["?"; ids.len()].join(", ");
+
+        let mut milestones: HashMap<Uuid, Vec<Milestone>> = HashMap::new();
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT id, project_id, number, name, description, technical_lead, team,
+                    design_doc_url, start_date, due_date, jira_epic, created_at, updated_at,
+                    recurrence_rule, series_id, series_index, version, estimated_days, actual_days
+             FROM milestones WHERE project_id IN ({}) ORDER BY project_id, number",
+            placeholders
+        ))?;
+        let rows = stmt.query_map(params_from_iter(ids.iter()), milestone_from_row)?;
+        for milestone in rows {
+            let milestone = milestone?;
+            milestones.entry(milestone.project_id).or_default().push(milestone);
+        }
+
+        let mut resources: HashMap<Uuid, Vec<ProjectResource>> = HashMap::new();
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT project_id, person_email, role, created_at
+             FROM project_resources WHERE project_id IN ({})",
+            placeholders
+        ))?;
+        let rows = stmt.query_map(params_from_iter(ids.iter()), |row| {
+            Ok(ProjectResource {
+                project_id: get_uuid(row, 0)?,
+                person_email: row.get(1)?,
+                role: row.get(2)?,
+                created_at: get_datetime(row, 3)?,
+            })
+        })?;
+        for resource in rows {
+            let resource = resource?;
+            resources.entry(resource.project_id).or_default().push(resource);
+        }
+
+        let mut stakeholders: HashMap<Uuid, Vec<ProjectStakeholder>> = HashMap::new();
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT project_id, stakeholder_email, role, influence, interest, created_at
+             FROM project_stakeholders WHERE project_id IN ({})",
+            placeholders
+        ))?;
+        let rows = stmt.query_map(params_from_iter(ids.iter()), stakeholder_from_row)?;
+        for stakeholder in rows {
+            let stakeholder = stakeholder?;
+            stakeholders.entry(stakeholder.project_id).or_default().push(stakeholder);
+        }
+
+        let dashboard = projects
+            .into_iter()
+            .map(|project| ProjectDashboard {
+                milestones: milestones.remove(&project.id).unwrap_or_default(),
+                resources: resources.remove(&project.id).unwrap_or_default(),
+                stakeholders: stakeholders.remove(&project.id).unwrap_or_default(),
+                project,
+            })
+            .collect();
+
+        Ok(dashboard)
+    }
+
+    /// Get the progress summary for a project
+    pub fn get_project_summary(&self, id: &Uuid) -> Result<Option<ProjectSummary>> {
+        let now = Utc::now();
+        let summary = self
+            .conn
+            .prepare_cached(PROJECT_SUMMARY_SQL)?
+            .query_row(
+                params![dt_to_db(now), dt_to_db(now - Duration::days(30)), id.to_string(), self.checklist_progress],
+                project_summary_from_row,
+            )
+            .optional()?;
+        let Some(mut summary) = summary else { return Ok(None) };
+        summary.phase_durations = phase_durations(&self.get_phase_history(id)?, now);
+        Ok(Some(summary))
+    }
+
+    /// Get the progress summary for every project, ordered by project name
+    ///
+    /// Runs two queries regardless of the number of projects: one for the
+    /// summaries and one for every project's phase history.
+    pub fn list_project_summaries(&self) -> Result<Vec<ProjectSummary>> {
+        let now = Utc::now();
+        let mut stmt = self.conn.prepare_cached(PROJECT_SUMMARY_SQL)?;
+        let mut summaries = stmt
+            .query_map(
+                params![dt_to_db(now), dt_to_db(now - Duration::days(30)), None::<String>, self.checklist_progress],
+                project_summary_from_row,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut histories: HashMap<Uuid, Vec<PhaseChange>> = HashMap::new();
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT project_id, from_phase, to_phase, backwards, changed_at
+             FROM phase_history ORDER BY project_id, changed_at, id",
+        )?;
+        for change in stmt.query_map([], phase_change_from_row)? {
+            let change = change?;
+            histories.entry(change.project_id).or_default().push(change);
+        }
+        for summary in &mut summaries {
+            if let Some(history) = histories.get(&summary.project_id) {
+                summary.phase_durations = phase_durations(history, now);
+            }
+        }
+        Ok(summaries)
+    }
+
     /// Update a project
+    ///
+    /// Changing the status moves the project to the end of its new board column.
     pub fn update(&self, project: &Project) -> Result<()> {
-        let rows = self.conn.execute(
+        self.validate_project_type(project)?;
+        validate_budget(project)?;
+
+        let tx = begin_or_join(self.conn)?;
+        let previous = self.stored_dates("projects", &project.id)?;
+        let previous_blocker = self.stored_blocker(&project.id)?;
+        let previous_phase = self.stored_phase(&project.id)?;
+        // A phase that is no longer configured can be kept, but not moved to
+        let phase = match (&project.phase, &previous_phase) {
+            (Some(phase), Some(Some(stored))) if phase == stored => Some(phase.clone()),
+            (Some(phase), _) => Some(self.canonical_phase(phase)?),
+            (None, _) => None,
+        };
+
+        let mut stmt = self.conn.prepare_cached(
             "UPDATE projects SET name = ?1, description = ?2, type = ?3, requirements_owner = ?4,
                                 technical_lead = ?5, manager = ?6, team = ?7, start_date = ?8, due_date = ?9,
-                                jira_initiative = ?10, updated_at = ?11
-             WHERE id = ?12",
-            params![
+                                jira_initiative = ?10, blocked = ?14, blocked_reason = ?15,
+                                budget_amount = ?16, budget_currency = ?17,
+                                board_position = CASE WHEN status = ?18 THEN board_position END, status = ?18,
+                                phase = ?19, updated_at = ?11, version = version + 1
+             WHERE id = ?12 AND version = ?13",
+        )?;
+        let rows = stmt
+            .execute(params![
                 &project.name,
                 &project.description,
                 &project.project_type,
@@ -121,162 +1206,607 @@ impl<'a> ProjectRepository<'a> {
                 &project.technical_lead,
                 &project.manager,
                 &project.team,
-                project.start_date.map(|d| d.to_rfc3339()),
-                project.due_date.map(|d| d.to_rfc3339()),
+                project.start_date.map(dt_to_db),
+                project.due_date.map(dt_to_db),
                 &project.jira_initiative,
-                Utc::now().to_rfc3339(),
+                dt_to_db(Utc::now()),
                 project.id.to_string(),
-            ],
-        )?;
+                project.version,
+                project.blocked,
+                blocked_reason(project),
+                project.budget_amount,
+                &project.budget_currency,
+                project.status.as_str(),
+                &phase,
+            ])
+            .map_err(|e| {
+                let e = Error::from(e).with_field("requirements_owner, technical_lead or manager");
+                self.name_conflict(e, project)
+            })?;
 
         if rows == 0 {
-            anyhow::bail!("Project not found: {}", project.id);
+            return Err(match self.find_by_id(&project.id)? {
+                Some(current) => Error::version_conflict("Project", project.id, project.version, current.version, &current),
+                None => Error::not_found("Project", project.id),
+            });
+        }
+
+        if let Some((start_date, due_date)) = previous {
+            self.record_date_change("project", &project.id, "start_date", start_date, project.start_date)?;
+            self.record_date_change("project", &project.id, "due_date", due_date, project.due_date)?;
+        }
+        if let Some((blocked, reason)) = &previous_blocker {
+            self.record_blocker_change(&project.id, (*blocked, reason.as_deref()), blocker_state(project))?;
+        }
+        if let Some(stored) = &previous_phase {
+            self.record_phase_change(&project.id, stored.as_deref(), phase.as_deref())?;
+        }
+        if let Some(tx) = tx {
+            tx.commit()?;
         }
 
         log::debug!("Updated project: {}", project.id);
         Ok(())
     }
 
+    /// Change the type of every project of type `old` to `new`
+    ///
+    /// Returns the number of projects changed. The configured types aren't
+    /// checked, so this also works while the configuration is being changed.
+    pub fn rename_project_type(&self, old: &str, new: &str) -> Result<usize> {
+        if new.trim().is_empty() {
+            return Err(Error::Invalid("Project type cannot be empty".to_string()));
+        }
+
+        let rows = self
+            .conn
+            .prepare_cached("UPDATE projects SET type = ?1, updated_at = ?2, version = version + 1 WHERE type = ?3")?
+            .execute(params![new, dt_to_db(Utc::now()), old])?;
+
+        log::debug!("Renamed project type {} to {} on {} projects", old, new, rows);
+        Ok(rows)
+    }
+
+    /// List the distinct project types used by existing projects
+    pub fn list_project_types_in_use(&self) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare_cached("SELECT DISTINCT type FROM projects ORDER BY type")?;
+        let types = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(types)
+    }
+
     /// Add stakeholder to project
     pub fn add_stakeholder(&self, project_id: &Uuid, stakeholder: &ProjectStakeholder) -> Result<()> {
-        self.conn.execute(
-            "INSERT INTO project_stakeholders (project_id, stakeholder_email, role, created_at)
-             VALUES (?1, ?2, ?3, ?4)",
-            params![
-                project_id.to_string(),
-                &stakeholder.stakeholder_email,
-                &stakeholder.role,
-                stakeholder.created_at.to_rfc3339(),
-            ],
+        validate_stakeholder_ratings(stakeholder)?;
+        let role = self.normalize_role(stakeholder.role.as_deref())?;
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT INTO project_stakeholders (project_id, stakeholder_email, role, influence, interest, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
         )?;
+        stmt.execute(params![
+            project_id.to_string(),
+            &stakeholder.stakeholder_email,
+            role,
+            stakeholder.influence,
+            stakeholder.interest,
+            dt_to_db(stakeholder.created_at),
+        ])
+        .map_err(|e| {
+            Error::from(e)
+                .with_conflict(format!("{} is already a stakeholder on this project", stakeholder.stakeholder_email))
+                .with_field("project or stakeholder_email")
+        })?;
         Ok(())
     }
 
     /// Get project stakeholders
     pub fn get_stakeholders(&self, project_id: &Uuid) -> Result<Vec<ProjectStakeholder>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT project_id, stakeholder_email, role, created_at
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT project_id, stakeholder_email, role, influence, interest, created_at
              FROM project_stakeholders WHERE project_id = ?1",
         )?;
 
         let stakeholders = stmt
-            .query_map(params![project_id.to_string()], |row| {
-                Ok(ProjectStakeholder {
-                    project_id: Uuid::parse_str(&row.get::<_, String>(0)?).unwrap(),
-                    stakeholder_email: row.get(1)?,
-                    role: row.get(2)?,
-                    created_at: row.get(3)?,
-                })
-            })?
+            .query_map(params![project_id.to_string()], stakeholder_from_row)?
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(stakeholders)
     }
 
-    /// Get project milestones
+    /// Get project milestones, with their checklists
     pub fn get_milestones(&self, project_id: &Uuid) -> Result<Vec<Milestone>> {
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare_cached(
             "SELECT id, project_id, number, name, description, technical_lead, team,
-                    design_doc_url, start_date, due_date, jira_epic, created_at, updated_at
+                    design_doc_url, start_date, due_date, jira_epic, created_at, updated_at,
+                    recurrence_rule, series_id, series_index, version, estimated_days, actual_days
              FROM milestones WHERE project_id = ?1 ORDER BY number",
         )?;
 
-        let milestones = stmt
-            .query_map(params![project_id.to_string()], |row| {
-                Ok(Milestone {
-                    id: Uuid::parse_str(&row.get::<_, String>(0)?).unwrap(),
-                    project_id: Uuid::parse_str(&row.get::<_, String>(1)?).unwrap(),
-                    number: row.get(2)?,
-                    name: row.get(3)?,
-                    description: row.get(4)?,
-                    technical_lead: row.get(5)?,
-                    team: row.get(6)?,
-                    design_doc_url: row.get(7)?,
-                    start_date: row.get(8)?,
-                    due_date: row.get(9)?,
-                    jira_epic: row.get(10)?,
-                    created_at: row.get(11)?,
-                    updated_at: row.get(12)?,
-                })
-            })?
+        let mut milestones = stmt
+            .query_map(params![project_id.to_string()], milestone_from_row)?
             .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(milestones)
+        let mut checklists = ChecklistRepository::new(self.conn).list_for_project(project_id)?;
+        let mut refs = ExternalRefRepository::new(self.conn).list_stored_for_milestones(project_id)?;
+        for milestone in &mut milestones {
+            milestone.set_checklist(checklists.remove(&milestone.id).unwrap_or_default());
+            milestone.external_refs = merge_legacy_jira(
+                refs.remove(&milestone.id).unwrap_or_default(),
+                ExternalRefTarget::Milestone,
+                milestone.id,
+                milestone.jira_epic.as_deref(),
+                milestone.created_at,
+            );
+        }
+
+        Ok(milestones)
+    }
+
+    /// Find a milestone by ID, with its checklist
+    pub fn find_milestone_by_id(&self, id: &Uuid) -> Result<Option<Milestone>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, project_id, number, name, description, technical_lead, team,
+                    design_doc_url, start_date, due_date, jira_epic, created_at, updated_at,
+                    recurrence_rule, series_id, series_index, version, estimated_days, actual_days
+             FROM milestones WHERE id = ?1",
+        )?;
+        let mut milestone = stmt
+            .query_row(params![id.to_string()], milestone_from_row)
+            .optional()?;
+        if let Some(milestone) = &mut milestone {
+            milestone.set_checklist(ChecklistRepository::new(self.conn).list_items(id)?);
+            let refs = ExternalRefRepository::new(self.conn).list_stored(ExternalRefTarget::Milestone, id)?;
+            milestone.external_refs = merge_legacy_jira(
+                refs,
+                ExternalRefTarget::Milestone,
+                milestone.id,
+                milestone.jira_epic.as_deref(),
+                milestone.created_at,
+            );
+        }
+        Ok(milestone)
     }
 
     /// Add milestone to project
     pub fn add_milestone(&self, milestone: &Milestone) -> Result<()> {
-        self.conn.execute(
+        validate_milestone_dates(milestone)?;
+        validate_milestone_effort(milestone)?;
+        let mut stmt = self.conn.prepare_cached(
             "INSERT INTO milestones (id, project_id, number, name, description, technical_lead, team,
-                                    design_doc_url, start_date, due_date, jira_epic, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
-            params![
-                milestone.id.to_string(),
-                milestone.project_id.to_string(),
-                milestone.number,
-                &milestone.name,
-                &milestone.description,
-                &milestone.technical_lead,
-                &milestone.team,
-                &milestone.design_doc_url,
-                milestone.start_date.map(|d| d.to_rfc3339()),
-                milestone.due_date.map(|d| d.to_rfc3339()),
-                &milestone.jira_epic,
-                milestone.created_at.to_rfc3339(),
-                milestone.updated_at.to_rfc3339(),
-            ],
+                                    design_doc_url, start_date, due_date, jira_epic, created_at, updated_at,
+                                    recurrence_rule, series_id, series_index, estimated_days, actual_days)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)",
         )?;
+        stmt.execute(params![
+            milestone.id.to_string(),
+            milestone.project_id.to_string(),
+            milestone.number,
+            &milestone.name,
+            &milestone.description,
+            &milestone.technical_lead,
+            &milestone.team,
+            &milestone.design_doc_url,
+            milestone.start_date.map(dt_to_db),
+            milestone.due_date.map(dt_to_db),
+            &milestone.jira_epic,
+            dt_to_db(milestone.created_at),
+            dt_to_db(milestone.updated_at),
+            &milestone.recurrence_rule,
+            milestone.series_id.map(|id| id.to_string()),
+            milestone.series_index,
+            milestone.estimated_days,
+            milestone.actual_days,
+        ])
+        .map_err(|e| {
+            Error::from(e)
+                .with_conflict(format!("Milestone number {} already exists in this project", milestone.number))
+                .with_field("project or technical_lead")
+        })?;
         Ok(())
     }
 
     /// Update a milestone
     pub fn update_milestone(&self, milestone: &Milestone) -> Result<()> {
-        let rows = self.conn.execute(
+        validate_milestone_dates(milestone)?;
+        validate_milestone_effort(milestone)?;
+        let tx = begin_or_join(self.conn)?;
+        let previous = self.stored_dates("milestones", &milestone.id)?;
+
+        let mut stmt = self.conn.prepare_cached(
             "UPDATE milestones SET number = ?1, name = ?2, description = ?3, technical_lead = ?4,
-                                   team = ?5, design_doc_url = ?6, start_date = ?7, due_date = ?8, jira_epic = ?9, updated_at = ?10
-             WHERE id = ?11",
-            params![
+                                   team = ?5, design_doc_url = ?6, start_date = ?7, due_date = ?8, jira_epic = ?9,
+                                   recurrence_rule = ?10, estimated_days = ?11, actual_days = ?12,
+                                   updated_at = ?13, version = version + 1
+             WHERE id = ?14 AND version = ?15",
+        )?;
+        let rows = stmt
+            .execute(params![
                 milestone.number,
                 &milestone.name,
                 &milestone.description,
                 &milestone.technical_lead,
                 &milestone.team,
                 &milestone.design_doc_url,
-                milestone.start_date.map(|d| d.to_rfc3339()),
-                milestone.due_date.map(|d| d.to_rfc3339()),
+                milestone.start_date.map(dt_to_db),
+                milestone.due_date.map(dt_to_db),
                 &milestone.jira_epic,
-                Utc::now().to_rfc3339(),
+                &milestone.recurrence_rule,
+                milestone.estimated_days,
+                milestone.actual_days,
+                dt_to_db(Utc::now()),
                 milestone.id.to_string(),
-            ],
-        )?;
+                milestone.version,
+            ])
+            .map_err(|e| {
+                Error::from(e)
+                    .with_conflict(format!("Milestone number {} already exists in this project", milestone.number))
+                    .with_field("technical_lead")
+            })?;
 
         if rows == 0 {
-            anyhow::bail!("Milestone not found: {}", milestone.id);
+            return Err(match self.find_milestone_by_id(&milestone.id)? {
+                Some(current) => {
+                    Error::version_conflict("Milestone", milestone.id, milestone.version, current.version, &current)
+                }
+                None => Error::not_found("Milestone", milestone.id),
+            });
+        }
+
+        if let Some((start_date, due_date)) = previous {
+            self.record_date_change("milestone", &milestone.id, "start_date", start_date, milestone.start_date)?;
+            self.record_date_change("milestone", &milestone.id, "due_date", due_date, milestone.due_date)?;
+        }
+        if let Some(tx) = tx {
+            tx.commit()?;
         }
 
         log::debug!("Updated milestone: {}", milestone.id);
         Ok(())
     }
 
+    /// Check a milestone's dates against its project
+    ///
+    /// These are warnings rather than errors: a milestone due after its
+    /// project usually means the project's due date needs to move.
+    pub fn milestone_date_warnings(&self, milestone: &Milestone) -> Result<Vec<String>> {
+        let mut warnings = Vec::new();
+        let project = self.find_by_id(&milestone.project_id)?;
+        if let (Some(due_date), Some(project_due_date)) = (milestone.due_date, project.and_then(|p| p.due_date)) {
+            if due_date > project_due_date {
+                warnings.push(format!(
+                    "Milestone '{}' is due {}, after the project's due date {}",
+                    milestone.name,
+                    due_date.format("%Y-%m-%d"),
+                    project_due_date.format("%Y-%m-%d")
+                ));
+            }
+        }
+        Ok(warnings)
+    }
+
+    /// Suggest a project due date: the latest due date of its milestones
+    pub fn suggest_project_due_date(&self, project_id: &Uuid) -> Result<Option<DateTime<Utc>>> {
+        let milestones = self.get_milestones(project_id)?;
+        Ok(milestones.iter().filter_map(|m| m.due_date).max())
+    }
+
+    /// Read the stored start and due dates of a project or milestone
+    fn stored_dates(
+        &self,
+        table: &str,
+        id: &Uuid,
+    ) -> Result<Option<StoredDates>> {
+        let mut stmt = self
+            .conn
+            .prepare_cached(&format!("SELECT start_date, due_date FROM {} WHERE id = ?1", table))?;
+        let dates = stmt
+            .query_row(params![id.to_string()], |row| {
+                Ok((get_opt_datetime(row, 0)?, get_opt_datetime(row, 1)?))
+            })
+            .optional()?;
+        Ok(dates)
+    }
+
+    /// Add a date_history row, unless the date didn't change
+    fn record_date_change(
+        &self,
+        entity_type: &str,
+        entity_id: &Uuid,
+        field: &str,
+        old_value: Option<DateTime<Utc>>,
+        new_value: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        if old_value == new_value {
+            return Ok(());
+        }
+
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT INTO date_history (entity_type, entity_id, field, old_value, new_value, changed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )?;
+        stmt.execute(params![
+            entity_type,
+            entity_id.to_string(),
+            field,
+            old_value.map(dt_to_db),
+            new_value.map(dt_to_db),
+            dt_to_db(Utc::now()),
+        ])?;
+        Ok(())
+    }
+
+    /// Get the recorded start and due date changes of a project or milestone, oldest first
+    pub fn get_date_history(&self, entity_id: &Uuid) -> Result<Vec<DateChange>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT entity_type, entity_id, field, old_value, new_value, changed_at
+             FROM date_history WHERE entity_id = ?1 ORDER BY changed_at, id",
+        )?;
+
+        let history = stmt
+            .query_map(params![entity_id.to_string()], |row| {
+                Ok(DateChange {
+                    entity_type: row.get(0)?,
+                    entity_id: get_uuid(row, 1)?,
+                    field: row.get(2)?,
+                    old_value: get_opt_datetime(row, 3)?,
+                    new_value: get_opt_datetime(row, 4)?,
+                    changed_at: get_datetime(row, 5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(history)
+    }
+
+    /// Plan for a calendar quarter: the milestones due in it, by team and project
+    ///
+    /// Quarter boundaries are midnight in `tz`. A milestone belongs to its own
+    /// team, or its project's team if it has none; milestones with neither
+    /// are grouped last, under no team. With `team`, only that team's
+    /// milestones are included.
+    pub fn get_quarter_plan(&self, year: i32, quarter: u32, tz: Tz, team: Option<&str>) -> Result<QuarterPlan> {
+        let (start, end) = quarter_bounds(year, quarter, tz)?;
+
+        let mut stmt = self.conn.prepare_cached(
+            "WITH planned AS (
+                 SELECT m.id, m.project_id, m.number, m.name, m.description, m.technical_lead, m.team,
+                        m.design_doc_url, m.start_date, m.due_date, m.jira_epic, m.created_at, m.updated_at,
+                        m.recurrence_rule, m.series_id, m.series_index, m.version, m.estimated_days, m.actual_days,
+                        p.name AS project_name,
+                        COALESCE(NULLIF(m.team, ''), NULLIF(p.team, '')) AS plan_team
+                 FROM milestones m
+                 JOIN projects p ON p.id = m.project_id
+                 WHERE m.due_date >= ?1 AND m.due_date < ?2
+             )
+             SELECT * FROM planned
+             WHERE ?3 IS NULL OR plan_team = ?3
+             ORDER BY plan_team IS NULL, plan_team, project_name, project_id, due_date, number",
+        )?;
+        let rows = stmt
+            .query_map(params![dt_to_db(start), dt_to_db(end), team], |row| {
+                Ok((milestone_from_row(row)?, row.get::<_, String>(19)?, row.get::<_, Option<String>>(20)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Rows arrive sorted by team, then project, so each group is a run of rows
+        let milestone_count = rows.len();
+        let mut teams: Vec<QuarterTeam> = Vec::new();
+        for (milestone, project_name, plan_team) in rows {
+            if teams.last().map(|t| &t.team) != Some(&plan_team) {
+                teams.push(QuarterTeam {
+                    team: plan_team,
+                    milestone_count: 0,
+                    projects: Vec::new(),
+                });
+            }
+            let bucket = teams.last_mut().unwrap();
+            bucket.milestone_count += 1;
+            if bucket.projects.last().map(|p| p.project_id) != Some(milestone.project_id) {
+                bucket.projects.push(QuarterProject {
+                    project_id: milestone.project_id,
+                    project_name,
+                    milestones: Vec::new(),
+                });
+            }
+            bucket.projects.last_mut().unwrap().milestones.push(milestone);
+        }
+
+        Ok(QuarterPlan {
+            year,
+            quarter,
+            start,
+            end,
+            milestone_count,
+            teams,
+        })
+    }
+
+    /// Summarize how often each milestone of a project slipped
+    ///
+    /// A slip is a due date change to a later date. Milestones are returned in
+    /// number order, including those that never slipped.
+    pub fn get_slippage_report(&self, project_id: &Uuid) -> Result<Vec<MilestoneSlippage>> {
+        if self.find_by_id(project_id)?.is_none() {
+            return Err(Error::not_found("Project", project_id));
+        }
+
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT h.entity_id, h.old_value, h.new_value
+             FROM date_history h
+             JOIN milestones m ON m.id = h.entity_id
+             WHERE m.project_id = ?1 AND h.entity_type = 'milestone' AND h.field = 'due_date'
+             ORDER BY h.changed_at, h.id",
+        )?;
+        let changes = stmt
+            .query_map(params![project_id.to_string()], |row| {
+                Ok((row.get::<_, String>(0)?, get_opt_datetime(row, 1)?, get_opt_datetime(row, 2)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut slips: HashMap<String, (usize, Duration)> = HashMap::new();
+        for (milestone_id, old_value, new_value) in changes {
+            if let (Some(old_value), Some(new_value)) = (old_value, new_value) {
+                if new_value > old_value {
+                    let slip = slips.entry(milestone_id).or_insert((0, Duration::zero()));
+                    slip.0 += 1;
+                    slip.1 += new_value - old_value;
+                }
+            }
+        }
+
+        let report = self
+            .get_milestones(project_id)?
+            .into_iter()
+            .map(|milestone| {
+                let (slip_count, slipped) = slips
+                    .get(&milestone.id.to_string())
+                    .copied()
+                    .unwrap_or((0, Duration::zero()));
+                MilestoneSlippage {
+                    milestone_id: milestone.id,
+                    number: milestone.number,
+                    name: milestone.name,
+                    due_date: milestone.due_date,
+                    slip_count,
+                    total_slip_days: slipped.num_days(),
+                }
+            })
+            .collect();
+
+        Ok(report)
+    }
+
+    /// Compare estimated and actual effort across a project's milestones
+    ///
+    /// Milestones are returned in number order, flagged when their actual
+    /// effort is more than `warning_ratio` times the estimate; call
+    /// [`EffortSummary::sort_by_variance`] to list the worst overruns first.
+    pub fn get_effort_summary(&self, project_id: &Uuid, warning_ratio: f64) -> Result<EffortSummary> {
+        if self.find_by_id(project_id)?.is_none() {
+            return Err(Error::not_found("Project", project_id));
+        }
+
+        let milestones: Vec<MilestoneEffort> = self
+            .get_milestones(project_id)?
+            .into_iter()
+            .map(|milestone| MilestoneEffort {
+                milestone_id: milestone.id,
+                number: milestone.number,
+                over_estimate: milestone.exceeds_estimate(warning_ratio),
+                variance_days: milestone.actual_days.zip(milestone.estimated_days).map(|(a, e)| a - e),
+                estimated_days: milestone.estimated_days,
+                actual_days: milestone.actual_days,
+                name: milestone.name,
+            })
+            .collect();
+
+        Ok(EffortSummary {
+            project_id: *project_id,
+            total_estimated_days: milestones.iter().filter_map(|m| m.estimated_days).sum(),
+            total_actual_days: milestones.iter().filter_map(|m| m.actual_days).sum(),
+            variance_days: milestones.iter().filter_map(|m| m.variance_days).sum(),
+            milestones,
+        })
+    }
+
     /// Delete a milestone
     pub fn delete_milestone(&self, id: &Uuid) -> Result<()> {
-        let rows = self.conn.execute("DELETE FROM milestones WHERE id = ?1", params![id.to_string()])?;
+        let rows = self
+            .conn
+            .prepare_cached("DELETE FROM milestones WHERE id = ?1")?
+            .execute(params![id.to_string()])?;
 
         if rows == 0 {
-            anyhow::bail!("Milestone not found: {}", id);
+            return Err(Error::not_found("Milestone", id));
         }
+        sync::record_deletion(self.conn, SyncEntity::Milestone, &id.to_string())?;
 
         log::debug!("Deleted milestone: {}", id);
         Ok(())
     }
 
+    /// Get every milestone that defines a recurring series
+    pub fn get_recurring_milestones(&self) -> Result<Vec<Milestone>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, project_id, number, name, description, technical_lead, team,
+                    design_doc_url, start_date, due_date, jira_epic, created_at, updated_at,
+                    recurrence_rule, series_id, series_index, version, estimated_days, actual_days
+             FROM milestones WHERE recurrence_rule IS NOT NULL AND series_id IS NULL",
+        )?;
+
+        let milestones = stmt
+            .query_map([], milestone_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(milestones)
+    }
+
+    /// Get the occurrence indexes already materialized for a series
+    pub fn get_series_indexes(&self, series_id: &Uuid) -> Result<Vec<i32>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT series_index FROM milestones
+             WHERE series_id = ?1 AND series_index IS NOT NULL ORDER BY series_index",
+        )?;
+
+        let indexes = stmt
+            .query_map(params![series_id.to_string()], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(indexes)
+    }
+
+    /// Get the next unused milestone number for a project
+    pub fn next_milestone_number(&self, project_id: &Uuid) -> Result<i32> {
+        let number = self
+            .conn
+            .prepare_cached("SELECT COALESCE(MAX(number), 0) + 1 FROM milestones WHERE project_id = ?1")?
+            .query_row(params![project_id.to_string()], |row| row.get(0))?;
+        Ok(number)
+    }
+
+    /// Delete a recurring series
+    ///
+    /// When `keep_occurrences` is true the materialized occurrences are kept as
+    /// standalone milestones; otherwise they are deleted along with the series.
+    pub fn delete_milestone_series(&self, id: &Uuid, keep_occurrences: bool) -> Result<()> {
+        if keep_occurrences {
+            self.conn
+                .prepare_cached(
+                    "UPDATE milestones SET series_id = NULL, series_index = NULL, updated_at = ?1, version = version + 1
+                     WHERE series_id = ?2",
+                )?
+                .execute(params![dt_to_db(Utc::now()), id.to_string()])?;
+        } else {
+            let occurrences: Vec<String> = self
+                .conn
+                .prepare_cached("SELECT id FROM milestones WHERE series_id = ?1")?
+                .query_map(params![id.to_string()], |row| row.get(0))?
+                .collect::<Result<_, _>>()?;
+            for occurrence in occurrences {
+                self.conn
+                    .prepare_cached("DELETE FROM milestones WHERE id = ?1")?
+                    .execute(params![occurrence])?;
+                sync::record_deletion(self.conn, SyncEntity::Milestone, &occurrence)?;
+            }
+        }
+
+        self.delete_milestone(id)
+    }
+
     /// Delete a project (cascades to milestones and stakeholders)
     pub fn delete(&self, id: &Uuid) -> Result<()> {
-        let rows = self.conn.execute("DELETE FROM projects WHERE id = ?1", params![id.to_string()])?;
+        let rows = self
+            .conn
+            .prepare_cached("DELETE FROM projects WHERE id = ?1")?
+            .execute(params![id.to_string()])?;
 
         if rows == 0 {
-            anyhow::bail!("Project not found: {}", id);
+            return Err(Error::not_found("Project", id));
         }
+        sync::record_deletion(self.conn, SyncEntity::Project, &id.to_string())?;
 
         log::debug!("Deleted project: {}", id);
         Ok(())
@@ -284,17 +1814,22 @@ impl<'a> ProjectRepository<'a> {
 
     /// Update a stakeholder
     pub fn update_stakeholder(&self, project_id: &Uuid, stakeholder: &ProjectStakeholder) -> Result<()> {
-        let rows = self.conn.execute(
-            "UPDATE project_stakeholders SET role = ?1 WHERE project_id = ?2 AND stakeholder_email = ?3",
-            params![
-                &stakeholder.role,
-                project_id.to_string(),
-                &stakeholder.stakeholder_email,
-            ],
+        validate_stakeholder_ratings(stakeholder)?;
+        let role = self.normalize_role(stakeholder.role.as_deref())?;
+        let mut stmt = self.conn.prepare_cached(
+            "UPDATE project_stakeholders SET role = ?1, influence = ?2, interest = ?3
+             WHERE project_id = ?4 AND stakeholder_email = ?5",
         )?;
+        let rows = stmt.execute(params![
+            role,
+            stakeholder.influence,
+            stakeholder.interest,
+            project_id.to_string(),
+            &stakeholder.stakeholder_email,
+        ])?;
 
         if rows == 0 {
-            anyhow::bail!("Stakeholder not found");
+            return Err(Error::not_found("Stakeholder", &stakeholder.stakeholder_email));
         }
 
         Ok(())
@@ -302,13 +1837,13 @@ impl<'a> ProjectRepository<'a> {
 
     /// Remove stakeholder from project
     pub fn remove_stakeholder(&self, project_id: &Uuid, stakeholder_email: &str) -> Result<()> {
-        let rows = self.conn.execute(
+        let mut stmt = self.conn.prepare_cached(
             "DELETE FROM project_stakeholders WHERE project_id = ?1 AND stakeholder_email = ?2",
-            params![project_id.to_string(), stakeholder_email],
         )?;
+        let rows = stmt.execute(params![project_id.to_string(), stakeholder_email])?;
 
         if rows == 0 {
-            anyhow::bail!("Stakeholder not found");
+            return Err(Error::not_found("Stakeholder", stakeholder_email));
         }
 
         Ok(())
@@ -318,22 +1853,91 @@ impl<'a> ProjectRepository<'a> {
 
     /// Add resource to project
     pub fn add_project_resource(&self, project_id: &Uuid, resource: &ProjectResource) -> Result<()> {
-        self.conn.execute(
+        let role = self.normalize_role(resource.role.as_deref())?;
+        let mut stmt = self.conn.prepare_cached(
             "INSERT INTO project_resources (project_id, person_email, role, created_at)
              VALUES (?1, ?2, ?3, ?4)",
-            params![
-                project_id.to_string(),
-                &resource.person_email,
-                &resource.role,
-                resource.created_at.to_rfc3339(),
-            ],
         )?;
+        stmt.execute(params![
+            project_id.to_string(),
+            &resource.person_email,
+            role,
+            dt_to_db(resource.created_at),
+        ])
+        .map_err(|e| {
+            Error::from(e)
+                .with_conflict(format!("{} is already a resource on this project", resource.person_email))
+                .with_field("project or person_email")
+        })?;
         Ok(())
     }
 
+    /// Add every active member of a team as a project resource
+    ///
+    /// Members who are already assigned to the project, or who are inactive,
+    /// are skipped. All resources are added in a single transaction.
+    pub fn add_team_as_resources(
+        &self,
+        project_id: &Uuid,
+        team_name: &str,
+        default_role: Option<&str>,
+    ) -> Result<TeamAssignment> {
+        let tx = begin_or_join(self.conn)?;
+
+        if self.find_by_id(project_id)?.is_none() {
+            return Err(Error::not_found("Project", project_id));
+        }
+
+        let team_repo = TeamRepository::new(self.conn);
+        if team_repo.find_by_name(team_name)?.is_none() {
+            return Err(Error::not_found("Team", team_name));
+        }
+
+        let members = team_repo.get_members(team_name)?;
+        if members.is_empty() {
+            return Err(Error::Conflict(format!("Team {} has no members to assign", team_name)));
+        }
+        let default_role = self.normalize_role(default_role)?;
+
+        let existing: Vec<String> = self
+            .get_project_resources(project_id)?
+            .into_iter()
+            .map(|r| r.person_email)
+            .collect();
+
+        let mut assignment = TeamAssignment {
+            added: Vec::new(),
+            skipped: Vec::new(),
+        };
+        for member in members {
+            if !member.active || existing.contains(&member.email) {
+                assignment.skipped.push(member.email);
+                continue;
+            }
+
+            let mut resource = ProjectResource::new(*project_id, member.email.clone());
+            resource.role = default_role.clone();
+            self.add_project_resource(project_id, &resource)?;
+            assignment.added.push(member.email);
+        }
+
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+
+        log::debug!(
+            "Assigned team {} to project {}: {} added, {} skipped",
+            team_name,
+            project_id,
+            assignment.added.len(),
+            assignment.skipped.len()
+        );
+        Ok(assignment)
+    }
+
     /// Get project resources
     pub fn get_project_resources(&self, project_id: &Uuid) -> Result<Vec<ProjectResource>> {
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare_cached(
             "SELECT project_id, person_email, role, created_at
              FROM project_resources WHERE project_id = ?1",
         )?;
@@ -341,10 +1945,10 @@ impl<'a> ProjectRepository<'a> {
         let resources = stmt
             .query_map(params![project_id.to_string()], |row| {
                 Ok(ProjectResource {
-                    project_id: Uuid::parse_str(&row.get::<_, String>(0)?).unwrap(),
+                    project_id: get_uuid(row, 0)?,
                     person_email: row.get(1)?,
                     role: row.get(2)?,
-                    created_at: row.get(3)?,
+                    created_at: get_datetime(row, 3)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -354,17 +1958,18 @@ impl<'a> ProjectRepository<'a> {
 
     /// Update a project resource
     pub fn update_project_resource(&self, project_id: &Uuid, resource: &ProjectResource) -> Result<()> {
-        let rows = self.conn.execute(
+        let role = self.normalize_role(resource.role.as_deref())?;
+        let mut stmt = self.conn.prepare_cached(
             "UPDATE project_resources SET role = ?1 WHERE project_id = ?2 AND person_email = ?3",
-            params![
-                &resource.role,
-                project_id.to_string(),
-                &resource.person_email,
-            ],
         )?;
+        let rows = stmt.execute(params![
+            role,
+            project_id.to_string(),
+            &resource.person_email,
+        ])?;
 
         if rows == 0 {
-            anyhow::bail!("Project resource not found");
+            return Err(Error::not_found("Project resource", &resource.person_email));
         }
 
         Ok(())
@@ -372,36 +1977,100 @@ impl<'a> ProjectRepository<'a> {
 
     /// Remove resource from project
     pub fn remove_project_resource(&self, project_id: &Uuid, person_email: &str) -> Result<()> {
-        let rows = self.conn.execute(
+        let mut stmt = self.conn.prepare_cached(
             "DELETE FROM project_resources WHERE project_id = ?1 AND person_email = ?2",
-            params![project_id.to_string(), person_email],
         )?;
+        let rows = stmt.execute(params![project_id.to_string(), person_email])?;
 
         if rows == 0 {
-            anyhow::bail!("Project resource not found");
+            return Err(Error::not_found("Project resource", person_email));
         }
 
         Ok(())
     }
 
     /// Add resource to milestone
-    pub fn add_milestone_resource(&self, milestone_id: &Uuid, resource: &MilestoneResource) -> Result<()> {
-        self.conn.execute(
+    ///
+    /// Returns a warning when the milestone ends up with more resources than
+    /// the limit set with [`Self::with_resource_limit`]; with a hard limit the
+    /// resource isn't added and an error is returned instead.
+    pub fn add_milestone_resource(
+        &self,
+        milestone_id: &Uuid,
+        resource: &MilestoneResource,
+    ) -> Result<Option<ResourceLimitWarning>> {
+        let role = self.normalize_role(resource.role.as_deref())?;
+        if let (Some(limit), true) = (self.max_resources_per_milestone, self.hard_resource_limit) {
+            let resources = self.count_milestone_resources(milestone_id)?;
+            if resources >= limit {
+                return Err(Error::Invalid(format!(
+                    "Milestone already has {} resources, the most allowed by max_resources_per_milestone",
+                    resources
+                )));
+            }
+        }
+        let mut stmt = self.conn.prepare_cached(
             "INSERT INTO milestone_resources (milestone_id, person_email, role, created_at)
              VALUES (?1, ?2, ?3, ?4)",
-            params![
-                milestone_id.to_string(),
-                &resource.person_email,
-                &resource.role,
-                resource.created_at.to_rfc3339(),
-            ],
         )?;
-        Ok(())
+        stmt.execute(params![
+            milestone_id.to_string(),
+            &resource.person_email,
+            role,
+            dt_to_db(resource.created_at),
+        ])
+        .map_err(|e| {
+            Error::from(e)
+                .with_conflict(format!("{} is already a resource on this milestone", resource.person_email))
+                .with_field("milestone or person_email")
+        })?;
+
+        let Some(limit) = self.max_resources_per_milestone else {
+            return Ok(None);
+        };
+        let resources = self.count_milestone_resources(milestone_id)?;
+        Ok((resources > limit).then_some(ResourceLimitWarning {
+            milestone_id: *milestone_id,
+            resources,
+            limit,
+        }))
+    }
+
+    fn count_milestone_resources(&self, milestone_id: &Uuid) -> Result<usize> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM milestone_resources WHERE milestone_id = ?1",
+            params![milestone_id.to_string()],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    /// Number of resources on each of a project's milestones, including milestones with none
+    pub fn get_resource_counts(&self, project_id: &Uuid) -> Result<BTreeMap<Uuid, usize>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT m.id, COUNT(mr.person_email)
+             FROM milestones m
+             LEFT JOIN milestone_resources mr ON mr.milestone_id = m.id
+             WHERE m.project_id = ?1
+             GROUP BY m.id",
+        )?;
+        let rows = stmt
+            .query_map(params![project_id.to_string()], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        rows.into_iter()
+            .map(|(id, count)| {
+                let id = Uuid::parse_str(&id).map_err(anyhow::Error::from)?;
+                Ok((id, count as usize))
+            })
+            .collect()
     }
 
     /// Get milestone resources
     pub fn get_milestone_resources(&self, milestone_id: &Uuid) -> Result<Vec<MilestoneResource>> {
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare_cached(
             "SELECT milestone_id, person_email, role, created_at
              FROM milestone_resources WHERE milestone_id = ?1",
         )?;
@@ -409,10 +2078,10 @@ impl<'a> ProjectRepository<'a> {
         let resources = stmt
             .query_map(params![milestone_id.to_string()], |row| {
                 Ok(MilestoneResource {
-                    milestone_id: Uuid::parse_str(&row.get::<_, String>(0)?).unwrap(),
+                    milestone_id: get_uuid(row, 0)?,
                     person_email: row.get(1)?,
                     role: row.get(2)?,
-                    created_at: row.get(3)?,
+                    created_at: get_datetime(row, 3)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -422,17 +2091,18 @@ impl<'a> ProjectRepository<'a> {
 
     /// Update a milestone resource
     pub fn update_milestone_resource(&self, milestone_id: &Uuid, resource: &MilestoneResource) -> Result<()> {
-        let rows = self.conn.execute(
+        let role = self.normalize_role(resource.role.as_deref())?;
+        let mut stmt = self.conn.prepare_cached(
             "UPDATE milestone_resources SET role = ?1 WHERE milestone_id = ?2 AND person_email = ?3",
-            params![
-                &resource.role,
-                milestone_id.to_string(),
-                &resource.person_email,
-            ],
         )?;
+        let rows = stmt.execute(params![
+            role,
+            milestone_id.to_string(),
+            &resource.person_email,
+        ])?;
 
         if rows == 0 {
-            anyhow::bail!("Milestone resource not found");
+            return Err(Error::not_found("Milestone resource", &resource.person_email));
         }
 
         Ok(())
@@ -440,572 +2110,4312 @@ impl<'a> ProjectRepository<'a> {
 
     /// Remove resource from milestone
     pub fn remove_milestone_resource(&self, milestone_id: &Uuid, person_email: &str) -> Result<()> {
-        let rows = self.conn.execute(
+        let mut stmt = self.conn.prepare_cached(
             "DELETE FROM milestone_resources WHERE milestone_id = ?1 AND person_email = ?2",
-            params![milestone_id.to_string(), person_email],
         )?;
+        let rows = stmt.execute(params![milestone_id.to_string(), person_email])?;
 
         if rows == 0 {
-            anyhow::bail!("Milestone resource not found");
+            return Err(Error::not_found("Milestone resource", person_email));
         }
 
         Ok(())
     }
 
+    /// Suggest people to assign to a milestone, best candidates first
+    ///
+    /// Candidates are active members of the milestone's team (or its
+    /// project's team) and the project's resources; when there is neither,
+    /// everyone active is considered. People already on the milestone are
+    /// left out. Team members come first, then whoever has the fewest other
+    /// milestones overlapping this one's dates, then people already on the
+    /// project. A milestone without dates is compared on open milestones
+    /// instead.
+    ///
+    /// When a `skill` is given, active people with that skill are candidates
+    /// too, and they rank above everyone else, the most proficient first.
+    pub fn suggest_milestone_resources(
+        &self,
+        milestone_id: &Uuid,
+        skill: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<ResourceSuggestion>> {
+        if self.find_milestone_by_id(milestone_id)?.is_none() {
+            return Err(Error::not_found("Milestone", milestone_id));
+        }
+        let skill = skill.map(|skill| skill.trim().to_lowercase()).filter(|skill| !skill.is_empty());
+
+        let mut stmt = self.conn.prepare_cached(
+            "WITH target AS (
+                 SELECT m.id, m.project_id,
+                        COALESCE(NULLIF(m.team, ''), NULLIF(p.team, '')) AS team,
+                        COALESCE(m.start_date, m.due_date) AS window_start,
+                        COALESCE(m.due_date, m.start_date) AS window_end
+                 FROM milestones m JOIN projects p ON p.id = m.project_id
+                 WHERE m.id = ?1
+             ),
+             candidates AS (
+                 SELECT tm.person_email AS email FROM team_members tm JOIN target t ON tm.team_name = t.team
+                 UNION
+                 SELECT pe.email FROM people pe JOIN target t ON pe.team = t.team
+                 UNION
+                 SELECT pr.person_email FROM project_resources pr JOIN target t ON pr.project_id = t.project_id
+                 UNION
+                 SELECT pe.email FROM people pe, target t
+                 WHERE t.team IS NULL
+                   AND NOT EXISTS (SELECT 1 FROM project_resources pr WHERE pr.project_id = t.project_id)
+                 UNION
+                 SELECT ps.person_email FROM person_skills ps WHERE ps.skill = ?3
+             )
+             SELECT pe.email, pe.name, t.team,
+                    EXISTS (SELECT 1 FROM team_members tm WHERE tm.team_name = t.team AND tm.person_email = pe.email)
+                        OR COALESCE(pe.team = t.team, 0),
+                    EXISTS (SELECT 1 FROM project_resources pr
+                            WHERE pr.project_id = t.project_id AND pr.person_email = pe.email),
+                    (SELECT COUNT(*) FROM milestone_resources mr JOIN milestones o ON o.id = mr.milestone_id
+                     WHERE mr.person_email = pe.email AND o.id <> t.id
+                       AND CASE WHEN t.window_start IS NULL
+                                THEN o.due_date IS NULL OR o.due_date >= ?2
+                                ELSE COALESCE(o.start_date, o.due_date) <= t.window_end
+                                     AND COALESCE(o.due_date, o.start_date) >= t.window_start
+                           END),
+                    t.window_start IS NOT NULL,
+                    ps.person_email IS NOT NULL, ps.level
+             FROM target t
+             JOIN candidates c
+             JOIN people pe ON pe.email = c.email
+             LEFT JOIN person_skills ps ON ps.person_email = pe.email AND ps.skill = ?3
+             WHERE pe.active = 1
+               AND NOT EXISTS (SELECT 1 FROM milestone_resources mr
+                               WHERE mr.milestone_id = t.id AND mr.person_email = pe.email)",
+        )?;
+
+        let mut candidates = stmt
+            .query_map(params![milestone_id.to_string(), dt_to_db(Utc::now()), skill], |row| {
+                let team: Option<String> = row.get(2)?;
+                let team_match: bool = row.get(3)?;
+                let on_project: bool = row.get(4)?;
+                let load: usize = row.get(5)?;
+                let has_window: bool = row.get(6)?;
+                let has_skill: bool = row.get(7)?;
+                let skill_level: Option<u8> = row.get(8)?;
+
+                let mut parts = Vec::new();
+                if let (true, Some(skill)) = (has_skill, skill.as_deref()) {
+                    parts.push(match skill_level {
+                        Some(level) => format!("knows {} (level {})", skill, level),
+                        None => format!("knows {}", skill),
+                    });
+                }
+                if let (true, Some(team)) = (team_match, team) {
+                    parts.push(format!("On {}", team));
+                }
+                if on_project {
+                    parts.push("already on the project".to_string());
+                }
+                let noun = if has_window { "overlapping milestone" } else { "open milestone" };
+                parts.push(match load {
+                    0 => format!("no {}s", noun),
+                    1 => format!("1 {}", noun),
+                    n => format!("{} {}s", n, noun),
+                });
+                let mut reason = parts.join(", ");
+                if let Some(first) = reason.get(..1) {
+                    reason = first.to_uppercase() + &reason[1..];
+                }
+
+                Ok(ResourceSuggestion {
+                    email: row.get(0)?,
+                    name: row.get(1)?,
+                    team_match,
+                    on_project,
+                    has_skill,
+                    skill_level,
+                    concurrent_milestones: load,
+                    reason,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        candidates.sort_by(|a, b| {
+            let rank = |s: &ResourceSuggestion| {
+                (
+                    !s.has_skill,
+                    std::cmp::Reverse(s.skill_level),
+                    !s.team_match,
+                    s.concurrent_milestones,
+                    !s.on_project,
+                    s.name.clone(),
+                )
+            };
+            rank(a).cmp(&rank(b))
+        });
+        candidates.truncate(limit);
+        Ok(candidates)
+    }
+
     // Project Notes
 
     /// Get notes for a project
     pub fn get_project_notes(&self, project_id: &Uuid) -> Result<Vec<ProjectNote>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, project_id, title, body, created_at, updated_at
-             FROM project_notes WHERE project_id = ?1 ORDER BY created_at DESC",
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, project_id, title, body, created_at, updated_at, version, kind
+             FROM project_notes WHERE project_id = ?1 ORDER BY created_at DESC, id DESC",
         )?;
 
-        let notes = stmt
-            .query_map(params![project_id.to_string()], |row| {
-                Ok(ProjectNote {
-                    id: Uuid::parse_str(&row.get::<_, String>(0)?).unwrap(),
-                    project_id: Uuid::parse_str(&row.get::<_, String>(1)?).unwrap(),
-                    title: row.get(2)?,
-                    body: row.get(3)?,
-                    created_at: row.get(4)?,
-                    updated_at: row.get(5)?,
-                })
-            })?
+        let mut notes = stmt
+            .query_map(params![project_id.to_string()], project_note_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        AttachmentRepository::new(self.conn).load_for_notes(&mut notes)?;
+
+        Ok(notes)
+    }
+
+    /// Get up to `limit` of a project's notes, newest first, starting after `cursor`
+    pub fn get_project_notes_page(
+        &self,
+        project_id: &Uuid,
+        cursor: Option<NoteCursor>,
+        limit: usize,
+    ) -> Result<Vec<ProjectNote>> {
+        let (created_at, id) = note_cursor_params(cursor);
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, project_id, title, body, created_at, updated_at, version, kind
+             FROM project_notes
+             WHERE project_id = ?1 AND (?2 IS NULL OR created_at < ?2 OR (created_at = ?2 AND id < ?3))
+             ORDER BY created_at DESC, id DESC LIMIT ?4",
+        )?;
+
+        let mut notes = stmt
+            .query_map(params![project_id.to_string(), created_at, id, limit as i64], project_note_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        AttachmentRepository::new(self.conn).load_for_notes(&mut notes)?;
+
+        Ok(notes)
+    }
+
+    /// List a page of notes across all projects in creation order
+    pub fn list_project_notes_page(&self, limit: usize, offset: usize) -> Result<Vec<ProjectNote>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, project_id, title, body, created_at, updated_at, version, kind
+             FROM project_notes ORDER BY created_at, id LIMIT ?1 OFFSET ?2",
+        )?;
+
+        let mut notes = stmt
+            .query_map(params![limit as i64, offset as i64], project_note_from_row)?
             .collect::<Result<Vec<_>, _>>()?;
 
+        AttachmentRepository::new(self.conn).load_for_notes(&mut notes)?;
+
         Ok(notes)
     }
 
+    /// Find a project note by ID
+    pub fn find_project_note_by_id(&self, id: &Uuid) -> Result<Option<ProjectNote>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, project_id, title, body, created_at, updated_at, version, kind
+             FROM project_notes WHERE id = ?1",
+        )?;
+        let mut note = stmt
+            .query_row(params![id.to_string()], project_note_from_row)
+            .optional()?;
+        if let Some(note) = note.as_mut() {
+            AttachmentRepository::new(self.conn).load_for_notes(std::slice::from_mut(note))?;
+        }
+        Ok(note)
+    }
+
     /// Add note to project
     pub fn add_project_note(&self, note: &ProjectNote) -> Result<()> {
-        self.conn.execute(
-            "INSERT INTO project_notes (id, project_id, title, body, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            params![
-                note.id.to_string(),
-                note.project_id.to_string(),
-                &note.title,
-                &note.body,
-                note.created_at.to_rfc3339(),
-                note.updated_at.to_rfc3339(),
-            ],
+        let tx = begin_or_join(self.conn)?;
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT INTO project_notes (id, project_id, title, body, created_at, updated_at, kind)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
         )?;
+        stmt.execute(params![
+            note.id.to_string(),
+            note.project_id.to_string(),
+            &note.title,
+            &note.body,
+            dt_to_db(note.created_at),
+            dt_to_db(note.updated_at),
+            note.kind.as_str(),
+        ])
+        .map_err(|e| Error::from(e).with_field("project"))?;
+        NoteLinkRepository::new(self.conn).sync_note(NoteType::Project, &note.id, &note.body)?;
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
         Ok(())
     }
 
     /// Update a project note
+    ///
+    /// Log notes keep their body: only their title can change here, and
+    /// entries are added with [`append_note_entry`](Self::append_note_entry).
     pub fn update_project_note(&self, note: &ProjectNote) -> Result<()> {
-        let rows = self.conn.execute(
-            "UPDATE project_notes SET title = ?1, body = ?2, updated_at = ?3
-             WHERE id = ?4",
-            params![
-                &note.title,
-                &note.body,
-                note.updated_at.to_rfc3339(),
-                note.id.to_string(),
-            ],
+        let tx = begin_or_join(self.conn)?;
+        let mut stmt = self.conn.prepare_cached(
+            "UPDATE project_notes SET title = ?1, body = ?2, updated_at = ?3, version = version + 1
+             WHERE id = ?4 AND version = ?5 AND (kind <> 'log' OR body = ?2)",
         )?;
+        let rows = stmt.execute(params![
+            &note.title,
+            &note.body,
+            dt_to_db(note.updated_at),
+            note.id.to_string(),
+            note.version,
+        ])?;
 
         if rows == 0 {
-            anyhow::bail!("Project note not found: {}", note.id);
+            return Err(match self.find_project_note_by_id(&note.id)? {
+                Some(current) if current.kind == NoteKind::Log && current.version == note.version => Error::Invalid(format!(
+                    "Project note {} is a log; add to it with append_to_note instead of replacing its body",
+                    note.id
+                )),
+                Some(current) => Error::version_conflict("Project note", note.id, note.version, current.version, &current),
+                None => Error::not_found("Project note", note.id),
+            });
+        }
+
+        NoteLinkRepository::new(self.conn).sync_note(NoteType::Project, &note.id, &note.body)?;
+        if let Some(tx) = tx {
+            tx.commit()?;
         }
 
         log::debug!("Updated project note: {}", note.id);
         Ok(())
     }
 
+    /// Add an entry to the end of a project note under a heading with the
+    /// time and author, such as `## 2025-03-02 14:05 UTC — alice@example.com`
+    ///
+    /// The entry is added in a single statement, so entries appended at the
+    /// same time from different connections all land, in the order they
+    /// were committed. Works on log and freeform notes alike.
+    pub fn append_note_entry(&self, note_id: &Uuid, text: &str, author: Option<&str>) -> Result<ProjectNote> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(Error::Invalid("Note entries can't be empty".to_string()));
+        }
+        let now = Utc::now();
+        let stamp = now.format("%Y-%m-%d %H:%M UTC");
+        let entry = match author.map(str::trim).filter(|author| !author.is_empty()) {
+            Some(author) => format!("## {} — {}\n{}", stamp, author, text),
+            None => format!("## {}\n{}", stamp, text),
+        };
+
+        let tx = begin_or_join(self.conn)?;
+        let rows = self
+            .conn
+            .prepare_cached(
+                "UPDATE project_notes
+                 SET body = CASE WHEN body = '' THEN ?1 ELSE body || ?2 || ?1 END,
+                     updated_at = ?3, version = version + 1
+                 WHERE id = ?4",
+            )?
+            .execute(params![entry, "\n\n", dt_to_db(now), note_id.to_string()])?;
+        if rows == 0 {
+            return Err(Error::not_found("Project note", note_id));
+        }
+        let note = self
+            .find_project_note_by_id(note_id)?
+            .ok_or_else(|| Error::not_found("Project note", note_id))?;
+        NoteLinkRepository::new(self.conn).sync_note(NoteType::Project, note_id, &note.body)?;
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+
+        log::debug!("Appended to project note: {}", note_id);
+        Ok(note)
+    }
+
     /// Delete project note
     pub fn delete_project_note(&self, id: &Uuid) -> Result<()> {
-        let rows = self.conn.execute("DELETE FROM project_notes WHERE id = ?1", params![id.to_string()])?;
+        let rows = self
+            .conn
+            .prepare_cached("DELETE FROM project_notes WHERE id = ?1")?
+            .execute(params![id.to_string()])?;
 
         if rows == 0 {
-            anyhow::bail!("Note not found: {}", id);
+            return Err(Error::not_found("Project note", id));
         }
+        sync::record_deletion(self.conn, SyncEntity::ProjectNote, &id.to_string())?;
 
         Ok(())
     }
 
-    // Milestone Notes
+    // Project Documents
 
-    /// Get notes for a milestone
-    pub fn get_milestone_notes(&self, milestone_id: &Uuid) -> Result<Vec<MilestoneNote>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, milestone_id, title, body, created_at, updated_at
-             FROM milestone_notes WHERE milestone_id = ?1 ORDER BY created_at DESC",
+    /// Get documents linked from a project
+    pub fn get_project_documents(&self, project_id: &Uuid) -> Result<Vec<ProjectDocument>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, project_id, title, url, doc_type, created_at
+             FROM project_documents WHERE project_id = ?1 ORDER BY created_at, title",
         )?;
 
-        let notes = stmt
-            .query_map(params![milestone_id.to_string()], |row| {
-                Ok(MilestoneNote {
-                    id: Uuid::parse_str(&row.get::<_, String>(0)?).unwrap(),
-                    milestone_id: Uuid::parse_str(&row.get::<_, String>(1)?).unwrap(),
-                    title: row.get(2)?,
-                    body: row.get(3)?,
-                    created_at: row.get(4)?,
-                    updated_at: row.get(5)?,
-                })
-            })?
+        let documents = stmt
+            .query_map(params![project_id.to_string()], project_document_from_row)?
             .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(notes)
+        Ok(documents)
     }
 
-    /// Add note to milestone
-    pub fn add_milestone_note(&self, note: &MilestoneNote) -> Result<()> {
-        self.conn.execute(
-            "INSERT INTO milestone_notes (id, milestone_id, title, body, created_at, updated_at)
+    /// Find a project document by ID
+    pub fn find_project_document_by_id(&self, id: &Uuid) -> Result<Option<ProjectDocument>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, project_id, title, url, doc_type, created_at
+             FROM project_documents WHERE id = ?1",
+        )?;
+        let document = stmt
+            .query_row(params![id.to_string()], project_document_from_row)
+            .optional()?;
+        Ok(document)
+    }
+
+    /// Add a document to a project
+    pub fn add_project_document(&self, document: &ProjectDocument) -> Result<()> {
+        validate_document_url(&document.url)?;
+
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT INTO project_documents (id, project_id, title, url, doc_type, created_at)
              VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            params![
-                note.id.to_string(),
-                note.milestone_id.to_string(),
-                &note.title,
-                &note.body,
-                note.created_at.to_rfc3339(),
-                note.updated_at.to_rfc3339(),
-            ],
         )?;
+        stmt.execute(params![
+            document.id.to_string(),
+            document.project_id.to_string(),
+            &document.title,
+            &document.url,
+            &document.doc_type,
+            dt_to_db(document.created_at),
+        ])
+        .map_err(|e| Error::from(e).with_field("project"))?;
         Ok(())
     }
 
-    /// Update a milestone note
-    pub fn update_milestone_note(&self, note: &MilestoneNote) -> Result<()> {
-        let rows = self.conn.execute(
-            "UPDATE milestone_notes SET title = ?1, body = ?2, updated_at = ?3
+    /// Update a project document's title, URL and type
+    pub fn update_project_document(&self, document: &ProjectDocument) -> Result<()> {
+        validate_document_url(&document.url)?;
+
+        let mut stmt = self.conn.prepare_cached(
+            "UPDATE project_documents SET title = ?1, url = ?2, doc_type = ?3
              WHERE id = ?4",
-            params![
-                &note.title,
-                &note.body,
-                note.updated_at.to_rfc3339(),
-                note.id.to_string(),
-            ],
         )?;
+        let rows = stmt.execute(params![
+            &document.title,
+            &document.url,
+            &document.doc_type,
+            document.id.to_string(),
+        ])?;
 
         if rows == 0 {
-            anyhow::bail!("Milestone note not found: {}", note.id);
+            return Err(Error::not_found("Project document", document.id));
         }
 
-        log::debug!("Updated milestone note: {}", note.id);
+        log::debug!("Updated project document: {}", document.id);
         Ok(())
     }
 
-    /// Delete milestone note
-    pub fn delete_milestone_note(&self, id: &Uuid) -> Result<()> {
-        let rows = self.conn.execute("DELETE FROM milestone_notes WHERE id = ?1", params![id.to_string()])?;
+    /// Remove a document from a project
+    pub fn delete_project_document(&self, id: &Uuid) -> Result<()> {
+        let rows = self
+            .conn
+            .prepare_cached("DELETE FROM project_documents WHERE id = ?1")?
+            .execute(params![id.to_string()])?;
 
         if rows == 0 {
-            anyhow::bail!("Note not found: {}", id);
+            return Err(Error::not_found("Project document", id));
         }
 
         Ok(())
     }
 
-    // Stakeholder Notes
+    // Project Budget
 
-    /// Get notes for a stakeholder
-    pub fn get_stakeholder_notes(&self, project_id: &Uuid, stakeholder_email: &str) -> Result<Vec<StakeholderNote>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, project_id, stakeholder_email, title, body, created_at, updated_at
-             FROM stakeholder_notes WHERE project_id = ?1 AND stakeholder_email = ?2 ORDER BY created_at DESC",
-        )?;
-
-        let notes = stmt
-            .query_map(params![project_id.to_string(), stakeholder_email], |row| {
-                Ok(StakeholderNote {
-                    id: Uuid::parse_str(&row.get::<_, String>(0)?).unwrap(),
-                    project_id: Uuid::parse_str(&row.get::<_, String>(1)?).unwrap(),
-                    stakeholder_email: row.get(2)?,
-                    title: row.get(3)?,
-                    body: row.get(4)?,
-                    created_at: row.get(5)?,
-                    updated_at: row.get(6)?,
-                })
-            })?
+    /// Get a project's expenses, oldest first
+    pub fn list_expenses(&self, project_id: &Uuid) -> Result<Vec<ProjectExpense>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, project_id, description, amount, incurred_at, created_at
+             FROM project_expenses WHERE project_id = ?1 ORDER BY incurred_at, created_at",
+        )?;
+
+        let expenses = stmt
+            .query_map(params![project_id.to_string()], project_expense_from_row)?
             .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(notes)
+        Ok(expenses)
     }
 
-    /// Add note to stakeholder
-    pub fn add_stakeholder_note(&self, note: &StakeholderNote) -> Result<()> {
-        self.conn.execute(
-            "INSERT INTO stakeholder_notes (id, project_id, stakeholder_email, title, body, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![
-                note.id.to_string(),
-                note.project_id.to_string(),
-                &note.stakeholder_email,
-                &note.title,
-                &note.body,
-                note.created_at.to_rfc3339(),
-                note.updated_at.to_rfc3339(),
-            ],
+    /// Find an expense by ID
+    pub fn find_expense_by_id(&self, id: &Uuid) -> Result<Option<ProjectExpense>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, project_id, description, amount, incurred_at, created_at
+             FROM project_expenses WHERE id = ?1",
+        )?;
+        let expense = stmt
+            .query_row(params![id.to_string()], project_expense_from_row)
+            .optional()?;
+        Ok(expense)
+    }
+
+    /// Record money spent on a project
+    pub fn add_expense(&self, expense: &ProjectExpense) -> Result<()> {
+        validate_amount("expense amount", expense.amount)?;
+
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT INTO project_expenses (id, project_id, description, amount, incurred_at, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
         )?;
+        stmt.execute(params![
+            expense.id.to_string(),
+            expense.project_id.to_string(),
+            &expense.description,
+            expense.amount,
+            dt_to_db(expense.incurred_at),
+            dt_to_db(expense.created_at),
+        ])
+        .map_err(|e| Error::from(e).with_field("project"))?;
         Ok(())
     }
 
-    /// Update a stakeholder note
-    pub fn update_stakeholder_note(&self, note: &StakeholderNote) -> Result<()> {
-        let rows = self.conn.execute(
-            "UPDATE stakeholder_notes SET title = ?1, body = ?2, updated_at = ?3
+    /// Update an expense's description, amount and date
+    pub fn update_expense(&self, expense: &ProjectExpense) -> Result<()> {
+        validate_amount("expense amount", expense.amount)?;
+
+        let mut stmt = self.conn.prepare_cached(
+            "UPDATE project_expenses SET description = ?1, amount = ?2, incurred_at = ?3
              WHERE id = ?4",
-            params![
-                &note.title,
-                &note.body,
-                note.updated_at.to_rfc3339(),
-                note.id.to_string(),
-            ],
         )?;
+        let rows = stmt.execute(params![
+            &expense.description,
+            expense.amount,
+            dt_to_db(expense.incurred_at),
+            expense.id.to_string(),
+        ])?;
+
+        if rows == 0 {
+            return Err(Error::not_found("Expense", expense.id));
+        }
+
+        log::debug!("Updated expense: {}", expense.id);
+        Ok(())
+    }
+
+    /// Delete an expense
+    pub fn delete_expense(&self, id: &Uuid) -> Result<()> {
+        let rows = self
+            .conn
+            .prepare_cached("DELETE FROM project_expenses WHERE id = ?1")?
+            .execute(params![id.to_string()])?;
 
         if rows == 0 {
-            anyhow::bail!("Stakeholder note not found: {}", note.id);
+            return Err(Error::not_found("Expense", id));
         }
 
-        log::debug!("Updated stakeholder note: {}", note.id);
-        Ok(())
+        Ok(())
+    }
+
+    /// Compare a project's budget with the sum of its expenses
+    ///
+    /// The currency is returned as stored; no conversion is done.
+    pub fn get_budget_status(&self, project_id: &Uuid) -> Result<BudgetStatus> {
+        let project = self
+            .find_by_id(project_id)?
+            .ok_or_else(|| Error::not_found("Project", project_id))?;
+
+        let total_spent: f64 = self
+            .conn
+            .prepare_cached("SELECT COALESCE(SUM(amount), 0.0) FROM project_expenses WHERE project_id = ?1")?
+            .query_row(params![project_id.to_string()], |row| row.get(0))?;
+
+        Ok(BudgetStatus {
+            project_id: *project_id,
+            remaining: project.budget_amount.map(|budget| budget - total_spent),
+            budget_amount: project.budget_amount,
+            budget_currency: project.budget_currency,
+            total_spent,
+        })
+    }
+
+    // Project Snapshots
+
+    /// Gather a project with its milestones, resources, stakeholders, retrospective and decisions
+    pub fn get_project_bundle(&self, project_id: &Uuid) -> Result<ProjectBundle> {
+        let project = self
+            .find_by_id(project_id)?
+            .ok_or_else(|| Error::not_found("Project", project_id))?;
+        Ok(ProjectBundle {
+            milestones: self.get_milestones(project_id)?,
+            resources: self.get_project_resources(project_id)?,
+            stakeholders: self.get_stakeholders(project_id)?,
+            retrospective: RetrospectiveRepository::new(self.conn).find_by_project(project_id)?,
+            decisions: DecisionRepository::new(self.conn).list_decisions(project_id)?,
+            project,
+        })
+    }
+
+    /// Gather a compact reference card for a project
+    ///
+    /// Runs five queries however large the project is: the project with its
+    /// open risk and action item counts, its stakeholders, its milestones, its
+    /// accepted decisions and its latest note titles. A milestone counts as done once its due date
+    /// has passed, as in the progress summaries.
+    pub fn get_brief(&self, project_id: &Uuid) -> Result<ProjectBrief> {
+        let id = project_id.to_string();
+        let (project, open_risks, open_action_items) = self
+            .conn
+            .prepare_cached(&format!(
+                "SELECT {},
+                        (SELECT COUNT(*) FROM project_risks r WHERE r.project_id = p.id AND r.status <> 'closed'),
+                        (SELECT COUNT(*) FROM action_items a WHERE a.project_id = p.id AND a.status = 'open')
+                 FROM projects p WHERE id = ?1",
+                PROJECT_COLUMNS
+            ))?
+            .query_row(params![id], |row| {
+                Ok((project_from_row(row)?, row.get::<_, usize>(22)?, row.get::<_, usize>(23)?))
+            })
+            .optional()?
+            .ok_or_else(|| Error::not_found("Project", project_id))?;
+
+        let stakeholders = self
+            .conn
+            .prepare_cached(
+                "SELECT s.stakeholder_email, COALESCE(p.name, s.stakeholder_email), s.role
+                 FROM project_stakeholders s LEFT JOIN people p ON p.email = s.stakeholder_email
+                 WHERE s.project_id = ?1 ORDER BY s.stakeholder_email",
+            )?
+            .query_map(params![id], |row| {
+                Ok(BriefStakeholder {
+                    email: row.get(0)?,
+                    name: row.get(1)?,
+                    role: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let now = Utc::now();
+        let milestones = self
+            .conn
+            .prepare_cached("SELECT number, name, due_date FROM milestones WHERE project_id = ?1 ORDER BY number")?
+            .query_map(params![id], |row| {
+                let due_date = get_opt_datetime(row, 2)?;
+                Ok(BriefMilestone {
+                    number: row.get(0)?,
+                    name: row.get(1)?,
+                    done: due_date.is_some_and(|due| due < now),
+                    due_date,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let decisions = self
+            .conn
+            .prepare_cached(
+                "SELECT d.title, d.decided_on,
+                        (SELECT group_concat(approver_email, ',') FROM
+                            (SELECT approver_email FROM decision_approvers
+                             WHERE decision_id = d.id ORDER BY approver_email))
+                 FROM decisions d WHERE d.project_id = ?1 AND d.status = 'accepted'
+                 ORDER BY d.decided_on IS NULL, d.decided_on, d.created_at, d.id",
+            )?
+            .query_map(params![id], |row| {
+                let approvers: Option<String> = row.get(2)?;
+                Ok(BriefDecision {
+                    title: row.get(0)?,
+                    decided_on: get_opt_datetime(row, 1)?,
+                    approvers: approvers
+                        .map(|a| a.split(',').map(str::to_string).collect())
+                        .unwrap_or_default(),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let recent_notes = self
+            .conn
+            .prepare_cached(
+                "SELECT title, created_at FROM project_notes WHERE project_id = ?1
+                 ORDER BY created_at DESC, id DESC LIMIT ?2",
+            )?
+            .query_map(params![id, BRIEF_NOTE_COUNT], |row| {
+                Ok(BriefNote {
+                    title: row.get(0)?,
+                    created_at: get_datetime(row, 1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ProjectBrief {
+            id: project.id,
+            name: project.name,
+            slug: project.slug,
+            project_type: project.project_type,
+            status: project.status,
+            phase: project.phase,
+            start_date: project.start_date,
+            due_date: project.due_date,
+            blocked_reason: project.blocked.then(|| project.blocked_reason.unwrap_or_default()),
+            team: project.team,
+            manager: project.manager,
+            technical_lead: project.technical_lead,
+            requirements_owner: project.requirements_owner,
+            stakeholders,
+            milestone_count: milestones.len(),
+            milestones,
+            open_risks,
+            open_action_items,
+            decisions,
+            recent_notes,
+        })
+    }
+
+    /// Rank what a user should look at today, most urgent first
+    ///
+    /// Lists milestones they lead that came due in the last
+    /// [`FOCUS_OVERDUE_DAYS`] days with checklist items left, their open
+    /// action items due within [`FOCUS_DUE_SOON_DAYS`] days or overdue,
+    /// stakeholders on projects they watch, manage or lead who have gone
+    /// without a note for the configured number of days, blocked projects
+    /// they manage, and projects they manage or lead that are missing a
+    /// technical lead, requirements owner or due date. Finished projects are
+    /// left out. Sources whose table doesn't exist in this database are
+    /// skipped. Items are ranked by [`focus_score`].
+    pub fn get_focus_list(&self, email: &str, now: DateTime<Utc>) -> Result<Vec<FocusItem>> {
+        let email = email.trim();
+        let days_late = |date: DateTime<Utc>| (now - date).num_days();
+        let mut items = Vec::new();
+
+        // A milestone without a lead is led by its project's technical lead
+        let checklist_left = if self.table_exists("milestone_checklist_items")? {
+            "AND (SELECT MIN(c.checked) FROM milestone_checklist_items c WHERE c.milestone_id = m.id) IS NOT 1"
+        } else {
+            ""
+        };
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT m.id, m.number, m.name, m.due_date, p.id, p.name
+             FROM milestones m JOIN projects p ON p.id = m.project_id
+             WHERE COALESCE(m.technical_lead, p.technical_lead) = ?1 COLLATE NOCASE
+               AND p.status <> 'done' AND m.due_date >= ?2 AND m.due_date < ?3 {}",
+            checklist_left
+        ))?;
+        let rows = stmt.query_map(
+            params![email, dt_to_db(now - Duration::days(FOCUS_OVERDUE_DAYS)), dt_to_db(now)],
+            |row| {
+                Ok((
+                    get_uuid(row, 0)?,
+                    row.get::<_, i32>(1)?,
+                    row.get::<_, String>(2)?,
+                    get_datetime(row, 3)?,
+                    get_uuid(row, 4)?,
+                    row.get::<_, String>(5)?,
+                ))
+            },
+        )?;
+        for row in rows {
+            let (id, number, name, due, project_id, project_name) = row?;
+            let late = days_late(due);
+            items.push(FocusItem {
+                kind: FocusKind::OverdueMilestone,
+                score: focus_score(FocusKind::OverdueMilestone, late),
+                project_id,
+                project_name,
+                link: format!("milestone:{}", id),
+                title: format!("#{} {}", number, name),
+                reason: format!("Was due {}", relative_days(late)),
+                date: Some(due),
+            });
+        }
+
+        if self.table_exists("action_items")? {
+            let mut stmt = self.conn.prepare_cached(
+                "SELECT a.id, a.description, a.due_date, p.id, p.name
+                 FROM action_items a JOIN projects p ON p.id = a.project_id
+                 WHERE a.assignee_email = ?1 COLLATE NOCASE AND a.status = 'open' AND a.due_date < ?2",
+            )?;
+            let rows = stmt.query_map(
+                params![email, dt_to_db(now + Duration::days(FOCUS_DUE_SOON_DAYS))],
+                |row| {
+                    Ok((
+                        get_uuid(row, 0)?,
+                        row.get::<_, String>(1)?,
+                        get_datetime(row, 2)?,
+                        get_uuid(row, 3)?,
+                        row.get::<_, String>(4)?,
+                    ))
+                },
+            )?;
+            for row in rows {
+                let (id, description, due, project_id, project_name) = row?;
+                let late = days_late(due);
+                let reason = if due < now {
+                    format!("Was due {}", relative_days(late))
+                } else {
+                    format!("Due {}", relative_days(late))
+                };
+                items.push(FocusItem {
+                    kind: FocusKind::ActionItemDue,
+                    score: focus_score(FocusKind::ActionItemDue, late),
+                    project_id,
+                    project_name,
+                    link: format!("action_item:{}", id),
+                    title: description,
+                    reason,
+                    date: Some(due),
+                });
+            }
+        }
+
+        if self.stakeholder_contact_days > 0 {
+            // Without stakeholder notes, the last contact is when they were added
+            let last_contact = if self.table_exists("stakeholder_notes")? {
+                "COALESCE((SELECT MAX(n.created_at) FROM stakeholder_notes n
+                           WHERE n.project_id = s.project_id AND n.stakeholder_email = s.stakeholder_email),
+                          s.created_at)"
+            } else {
+                "s.created_at"
+            };
+            let mut stmt = self.conn.prepare_cached(&format!(
+                "SELECT s.project_id, p.name, s.stakeholder_email, COALESCE(pe.name, s.stakeholder_email), {}
+                 FROM project_stakeholders s
+                 JOIN projects p ON p.id = s.project_id
+                 LEFT JOIN people pe ON pe.email = s.stakeholder_email
+                 WHERE p.status <> 'done'",
+                last_contact
+            ))?;
+            let relevant = WatcherRepository::new(self.conn).relevant_project_ids(email)?;
+            let threshold = i64::from(self.stakeholder_contact_days);
+            let rows = stmt.query_map([], |row| {
+                Ok((
+                    get_uuid(row, 0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    get_datetime(row, 4)?,
+                ))
+            })?;
+            for row in rows {
+                let (project_id, project_name, stakeholder_email, name, contacted) = row?;
+                let since = days_late(contacted);
+                if since < threshold || !relevant.contains(&project_id) {
+                    continue;
+                }
+                items.push(FocusItem {
+                    kind: FocusKind::StakeholderFollowUp,
+                    score: focus_score(FocusKind::StakeholderFollowUp, since - threshold),
+                    project_id,
+                    project_name,
+                    link: format!("person:{}", stakeholder_email),
+                    title: name,
+                    reason: format!("No stakeholder note in {} days", since),
+                    date: Some(contacted),
+                });
+            }
+        }
+
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, name, blocked, blocked_reason, manager = ?1 COLLATE NOCASE,
+                    technical_lead IS NULL, requirements_owner IS NULL, due_date IS NULL
+             FROM projects
+             WHERE status <> 'done'
+               AND (manager = ?1 COLLATE NOCASE OR technical_lead = ?1 COLLATE NOCASE)",
+        )?;
+        let rows = stmt.query_map(params![email], |row| {
+            Ok((
+                get_uuid(row, 0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, bool>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, bool>(4)?,
+                [
+                    (row.get::<_, bool>(5)?, "technical lead"),
+                    (row.get::<_, bool>(6)?, "requirements owner"),
+                    (row.get::<_, bool>(7)?, "due date"),
+                ],
+            ))
+        })?;
+        for row in rows {
+            let (id, name, blocked, blocked_reason, manages, fields) = row?;
+            if blocked && manages {
+                items.push(FocusItem {
+                    kind: FocusKind::BlockedProject,
+                    score: focus_score(FocusKind::BlockedProject, 0),
+                    project_id: id,
+                    project_name: name.clone(),
+                    link: format!("project:{}", id),
+                    title: name.clone(),
+                    reason: blocked_reason.map_or_else(|| "Blocked".to_string(), |reason| format!("Blocked: {}", reason)),
+                    date: None,
+                });
+            }
+            let missing: Vec<&str> = fields.iter().filter(|(missing, _)| *missing).map(|(_, field)| *field).collect();
+            if !missing.is_empty() {
+                items.push(FocusItem {
+                    kind: FocusKind::MissingFields,
+                    score: focus_score(FocusKind::MissingFields, 0),
+                    project_id: id,
+                    project_name: name.clone(),
+                    link: format!("project:{}", id),
+                    title: name,
+                    reason: format!("Missing {}", missing.join(", ")),
+                    date: None,
+                });
+            }
+        }
+
+        items.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.date.is_none().cmp(&b.date.is_none()))
+                .then_with(|| a.date.cmp(&b.date))
+                .then_with(|| a.title.cmp(&b.title))
+        });
+        Ok(items)
+    }
+
+    /// Save a copy of a project's current plan under a label
+    pub fn create_snapshot(&self, project_id: &Uuid, label: &str) -> Result<ProjectSnapshot> {
+        let label = label.trim();
+        if label.is_empty() {
+            return Err(Error::Invalid("Snapshot label cannot be empty".to_string()));
+        }
+        let bundle = self.get_project_bundle(project_id)?;
+        let data = serde_json::to_string(&bundle).map_err(anyhow::Error::from)?;
+
+        let snapshot = ProjectSnapshot {
+            id: Uuid::new_v4(),
+            project_id: *project_id,
+            label: label.to_string(),
+            created_at: Utc::now(),
+        };
+        self.conn
+            .prepare_cached(
+                "INSERT INTO project_snapshots (id, project_id, label, data, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?
+            .execute(params![
+                snapshot.id.to_string(),
+                project_id.to_string(),
+                &snapshot.label,
+                data,
+                dt_to_db(snapshot.created_at),
+            ])?;
+        log::debug!("Created snapshot {} of project {}", snapshot.id, project_id);
+        Ok(snapshot)
+    }
+
+    /// List a project's snapshots, oldest first
+    pub fn list_snapshots(&self, project_id: &Uuid) -> Result<Vec<ProjectSnapshot>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, project_id, label, created_at
+             FROM project_snapshots WHERE project_id = ?1 ORDER BY created_at, id",
+        )?;
+
+        let snapshots = stmt
+            .query_map(params![project_id.to_string()], project_snapshot_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(snapshots)
+    }
+
+    /// Load a snapshot with the project bundle it saved
+    pub fn get_snapshot(&self, id: &Uuid) -> Result<(ProjectSnapshot, ProjectBundle)> {
+        let (snapshot, data) = self
+            .conn
+            .prepare_cached(
+                "SELECT id, project_id, label, created_at, data FROM project_snapshots WHERE id = ?1",
+            )?
+            .query_row(params![id.to_string()], |row| {
+                Ok((project_snapshot_from_row(row)?, row.get::<_, String>(4)?))
+            })
+            .optional()?
+            .ok_or_else(|| Error::not_found("Snapshot", id))?;
+        let bundle = serde_json::from_str(&data)
+            .map_err(|e| anyhow::anyhow!("Snapshot {} can't be read: {}", id, e))?;
+        Ok((snapshot, bundle))
+    }
+
+    /// Delete a snapshot
+    pub fn delete_snapshot(&self, id: &Uuid) -> Result<()> {
+        let rows = self
+            .conn
+            .prepare_cached("DELETE FROM project_snapshots WHERE id = ?1")?
+            .execute(params![id.to_string()])?;
+
+        if rows == 0 {
+            return Err(Error::not_found("Snapshot", id));
+        }
+
+        log::debug!("Deleted snapshot: {}", id);
+        Ok(())
+    }
+
+    /// Resolve a snapshot ID or a unique prefix of one
+    pub fn resolve_snapshot_id_prefix(&self, prefix: &str) -> Result<Uuid> {
+        self.resolve_prefix("project_snapshots", "Snapshot", prefix)
+    }
+
+    /// Compare two snapshots of the same project
+    ///
+    /// Milestones are matched by ID, so a renamed milestone shows up as
+    /// changed rather than removed and added. Timestamps and versions are
+    /// left out; everything else in the project and its milestones is
+    /// compared.
+    pub fn compare_snapshots(&self, from: &Uuid, to: &Uuid) -> Result<SnapshotDiff> {
+        let (from, old) = self.get_snapshot(from)?;
+        let (to, new) = self.get_snapshot(to)?;
+        if from.project_id != to.project_id {
+            return Err(Error::Invalid(format!(
+                "Snapshots {} and {} are of different projects",
+                from.id, to.id
+            )));
+        }
+
+        let project_changes = field_changes(&old.project, &new.project)?;
+
+        let old_milestones: HashMap<Uuid, &Milestone> = old.milestones.iter().map(|m| (m.id, m)).collect();
+        let new_ids: std::collections::HashSet<Uuid> = new.milestones.iter().map(|m| m.id).collect();
+
+        let mut removed_milestones: Vec<Milestone> = old
+            .milestones
+            .iter()
+            .filter(|m| !new_ids.contains(&m.id))
+            .cloned()
+            .collect();
+        sort_milestones(&mut removed_milestones);
+
+        let mut current = new.milestones.clone();
+        sort_milestones(&mut current);
+        let mut added_milestones = Vec::new();
+        let mut changed_milestones = Vec::new();
+        for milestone in current {
+            match old_milestones.get(&milestone.id) {
+                None => added_milestones.push(milestone),
+                Some(previous) => {
+                    let changes = field_changes(*previous, &milestone)?;
+                    if !changes.is_empty() {
+                        changed_milestones.push(MilestoneChange {
+                            milestone_id: milestone.id,
+                            name: milestone.name.clone(),
+                            changes,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(SnapshotDiff {
+            from,
+            to,
+            project_changes,
+            added_milestones,
+            removed_milestones,
+            changed_milestones,
+        })
+    }
+
+    // Project Risks
+
+    /// Get every risk in a project's register, open risks first
+    pub fn get_project_risks(&self, project_id: &Uuid) -> Result<Vec<ProjectRisk>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM project_risks WHERE project_id = ?1
+             ORDER BY status = 'closed', created_at, title",
+            RISK_COLUMNS
+        ))?;
+
+        let risks = stmt
+            .query_map(params![project_id.to_string()], project_risk_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(risks)
+    }
+
+    /// Get a project's risks that are not closed, highest score first
+    pub fn list_open_risks(&self, project_id: &Uuid) -> Result<Vec<ProjectRisk>> {
+        let mut risks: Vec<_> = self
+            .get_project_risks(project_id)?
+            .into_iter()
+            .filter(ProjectRisk::is_open)
+            .collect();
+        risks.sort_by_key(|risk| std::cmp::Reverse(risk.score()));
+        Ok(risks)
+    }
+
+    /// Get open risks across all projects that score above `HIGH_RISK_THRESHOLD`, highest first
+    pub fn list_high_risks(&self) -> Result<Vec<ProjectRisk>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM project_risks WHERE status <> 'closed' ORDER BY created_at, title",
+            RISK_COLUMNS
+        ))?;
+
+        let mut risks = stmt
+            .query_map([], project_risk_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        risks.retain(|risk| risk.score() > HIGH_RISK_THRESHOLD);
+        risks.sort_by_key(|risk| std::cmp::Reverse(risk.score()));
+
+        Ok(risks)
+    }
+
+    /// Find a project risk by ID
+    pub fn find_project_risk_by_id(&self, id: &Uuid) -> Result<Option<ProjectRisk>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM project_risks WHERE id = ?1",
+            RISK_COLUMNS
+        ))?;
+        let risk = stmt
+            .query_row(params![id.to_string()], project_risk_from_row)
+            .optional()?;
+        Ok(risk)
+    }
+
+    /// Add a risk to a project's register
+    pub fn add_project_risk(&self, risk: &ProjectRisk) -> Result<()> {
+        validate_risk(risk)?;
+
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT INTO project_risks (id, project_id, title, description, severity, likelihood,
+                                        mitigation, owner_email, status, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        )?;
+        stmt.execute(params![
+            risk.id.to_string(),
+            risk.project_id.to_string(),
+            &risk.title,
+            &risk.description,
+            risk.severity.as_str(),
+            risk.likelihood.as_str(),
+            &risk.mitigation,
+            &risk.owner_email,
+            risk.status.as_str(),
+            dt_to_db(risk.created_at),
+            dt_to_db(risk.updated_at),
+        ])
+        .map_err(|e| Error::from(e).with_field("project or owner_email"))?;
+        log::debug!("Added project risk: {} ({})", risk.title, risk.id);
+        Ok(())
+    }
+
+    /// Update a project risk
+    pub fn update_project_risk(&self, risk: &ProjectRisk) -> Result<()> {
+        validate_risk(risk)?;
+
+        let mut stmt = self.conn.prepare_cached(
+            "UPDATE project_risks
+             SET title = ?1, description = ?2, severity = ?3, likelihood = ?4, mitigation = ?5,
+                 owner_email = ?6, status = ?7, updated_at = ?8
+             WHERE id = ?9",
+        )?;
+        let rows = stmt
+            .execute(params![
+                &risk.title,
+                &risk.description,
+                risk.severity.as_str(),
+                risk.likelihood.as_str(),
+                &risk.mitigation,
+                &risk.owner_email,
+                risk.status.as_str(),
+                dt_to_db(risk.updated_at),
+                risk.id.to_string(),
+            ])
+            .map_err(|e| Error::from(e).with_field("owner_email"))?;
+
+        if rows == 0 {
+            return Err(Error::not_found("Project risk", risk.id));
+        }
+
+        log::debug!("Updated project risk: {}", risk.id);
+        Ok(())
+    }
+
+    /// Mark a project risk as closed
+    pub fn close_project_risk(&self, id: &Uuid) -> Result<()> {
+        let rows = self
+            .conn
+            .prepare_cached("UPDATE project_risks SET status = ?1, updated_at = ?2 WHERE id = ?3")?
+            .execute(params![RiskStatus::Closed.as_str(), dt_to_db(Utc::now()), id.to_string()])?;
+
+        if rows == 0 {
+            return Err(Error::not_found("Project risk", id));
+        }
+
+        Ok(())
+    }
+
+    /// Remove a risk from a project's register
+    pub fn delete_project_risk(&self, id: &Uuid) -> Result<()> {
+        let rows = self
+            .conn
+            .prepare_cached("DELETE FROM project_risks WHERE id = ?1")?
+            .execute(params![id.to_string()])?;
+
+        if rows == 0 {
+            return Err(Error::not_found("Project risk", id));
+        }
+
+        Ok(())
+    }
+
+    // Blockers
+
+    /// Read a project's stored blocked flag and reason, or `None` if the project doesn't exist
+    fn stored_blocker(&self, id: &Uuid) -> Result<Option<(bool, Option<String>)>> {
+        let state = self
+            .conn
+            .prepare_cached("SELECT blocked, blocked_reason FROM projects WHERE id = ?1")?
+            .query_row(params![id.to_string()], |row| Ok((row.get(0)?, row.get(1)?)))
+            .optional()?;
+        Ok(state)
+    }
+
+    /// Keep blocker_history in step with a change to a project's blocked flag and reason
+    ///
+    /// Clearing a blocker closes the open entry, and blocking opens a new one.
+    /// A new reason on a project that stays blocked does both.
+    fn record_blocker_change(
+        &self,
+        project_id: &Uuid,
+        before: (bool, Option<&str>),
+        after: (bool, Option<&str>),
+    ) -> Result<()> {
+        if before == after {
+            return Ok(());
+        }
+
+        let now = dt_to_db(Utc::now());
+        if before.0 {
+            self.conn
+                .prepare_cached("UPDATE blocker_history SET cleared_at = ?1 WHERE project_id = ?2 AND cleared_at IS NULL")?
+                .execute(params![&now, project_id.to_string()])?;
+        }
+        if let (true, reason) = after {
+            self.conn
+                .prepare_cached("INSERT INTO blocker_history (project_id, reason, blocked_at) VALUES (?1, ?2, ?3)")?
+                .execute(params![project_id.to_string(), reason, &now])?;
+        }
+        Ok(())
+    }
+
+    /// Mark a project as blocked and return it
+    ///
+    /// Blocking a project that is already blocked replaces the reason.
+    pub fn set_blocked(&self, id: &Uuid, reason: &str) -> Result<Project> {
+        self.write_blocker(id, Some(reason))
+    }
+
+    /// Clear a project's blocker and return it
+    pub fn clear_blocked(&self, id: &Uuid) -> Result<Project> {
+        self.write_blocker(id, None)
+    }
+
+    /// Set or clear a project's blocker, recording the change in blocker_history
+    fn write_blocker(&self, id: &Uuid, reason: Option<&str>) -> Result<Project> {
+        let tx = begin_or_join(self.conn)?;
+        let (blocked, previous_reason) = self.stored_blocker(id)?.ok_or_else(|| Error::not_found("Project", id))?;
+
+        let before = (blocked, previous_reason.as_deref());
+        let after = (reason.is_some(), reason);
+        if before != after {
+            self.conn
+                .prepare_cached(
+                    "UPDATE projects SET blocked = ?1, blocked_reason = ?2, updated_at = ?3, version = version + 1
+                     WHERE id = ?4",
+                )?
+                .execute(params![after.0, reason, dt_to_db(Utc::now()), id.to_string()])?;
+            self.record_blocker_change(id, before, after)?;
+        }
+        let project = self.find_by_id(id)?.ok_or_else(|| Error::not_found("Project", id))?;
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+
+        log::debug!("Set blocker on project {}: {:?}", id, reason);
+        Ok(project)
+    }
+
+    /// List the blocked projects, ordered by name
+    pub fn list_blocked(&self) -> Result<Vec<Project>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM projects WHERE blocked ORDER BY name",
+            PROJECT_COLUMNS
+        ))?;
+
+        let projects = stmt
+            .query_map([], project_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(projects)
+    }
+
+    /// List projects as board columns, one per status in [`ProjectStatus::ALL`] order
+    ///
+    /// Every status has a column, even if it is empty. Each column is ordered
+    /// by board position, followed by projects that were never placed, by name.
+    pub fn get_board(&self) -> Result<Vec<BoardColumn>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM projects ORDER BY board_position IS NULL, board_position, name",
+            PROJECT_COLUMNS
+        ))?;
+
+        let mut columns: Vec<BoardColumn> = ProjectStatus::ALL
+            .iter()
+            .map(|&status| BoardColumn {
+                status,
+                projects: Vec::new(),
+            })
+            .collect();
+        for project in stmt.query_map([], project_from_row)? {
+            let project = project?;
+            if let Some(column) = columns.iter_mut().find(|c| c.status == project.status) {
+                column.projects.push(project);
+            }
+        }
+
+        Ok(columns)
+    }
+
+    /// Move a project to `position` (counting from 0) in the board column for `status`
+    ///
+    /// Positions past the end of the column put the project last. The project
+    /// is numbered between its new neighbours, and the rest of the column is
+    /// only renumbered when there is no room left between them. Changing the
+    /// status counts as an update to the project; moving it within its column
+    /// only changes board positions. Returns the moved project.
+    pub fn reorder_project(&self, id: &Uuid, status: ProjectStatus, position: usize) -> Result<Project> {
+        // Take the write lock up front so concurrent moves see each other's numbering,
+        // unless the caller's transaction already covers the move
+        let tx = if self.conn.is_autocommit() {
+            Some(Transaction::new_unchecked(self.conn, TransactionBehavior::Immediate)?)
+        } else {
+            None
+        };
+        let current = self
+            .find_by_id(id)?
+            .ok_or_else(|| Error::not_found("Project", id))?;
+
+        // The rest of the target column, in board order
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, board_position FROM projects WHERE status = ?1 AND id != ?2
+             ORDER BY board_position IS NULL, board_position, name",
+        )?;
+        let mut column = stmt
+            .query_map(params![status.as_str(), id.to_string()], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Option<i64>>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let position = position.min(column.len());
+        let before = position.checked_sub(1).map(|i| column[i].1);
+        let after = column.get(position).map(|(_, p)| *p);
+        let slot = match (before, after) {
+            // Unplaced projects sort last, so the project can't go after one without renumbering
+            (Some(None), _) => None,
+            (None, None | Some(None)) => Some(BOARD_GAP),
+            (None, Some(Some(next))) => next.checked_sub(BOARD_GAP),
+            (Some(Some(prev)), None | Some(None)) => prev.checked_add(BOARD_GAP),
+            (Some(Some(prev)), Some(Some(next))) => (next - prev > 1).then(|| prev + (next - prev) / 2),
+        };
+
+        let board_position = match slot {
+            Some(slot) => slot,
+            None => {
+                column.insert(position, (id.to_string(), None));
+                let mut renumber = self
+                    .conn
+                    .prepare_cached("UPDATE projects SET board_position = ?1 WHERE id = ?2")?;
+                for (i, (other, stored)) in column.iter().enumerate() {
+                    let renumbered = (i as i64 + 1) * BOARD_GAP;
+                    if other != &id.to_string() && *stored != Some(renumbered) {
+                        renumber.execute(params![renumbered, other])?;
+                    }
+                }
+                (position as i64 + 1) * BOARD_GAP
+            }
+        };
+
+        if status == current.status {
+            self.conn
+                .prepare_cached("UPDATE projects SET board_position = ?1 WHERE id = ?2")?
+                .execute(params![board_position, id.to_string()])?;
+        } else {
+            self.conn
+                .prepare_cached(
+                    "UPDATE projects SET status = ?1, board_position = ?2, updated_at = ?3, version = version + 1
+                     WHERE id = ?4",
+                )?
+                .execute(params![status.as_str(), board_position, dt_to_db(Utc::now()), id.to_string()])?;
+        }
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+
+        log::debug!("Moved project {} to {} position {}", id, status, position);
+        self.find_by_id(id)?
+            .ok_or_else(|| Error::not_found("Project", id))
+    }
+
+    /// Get a project's blocker history, oldest first
+    pub fn get_blocker_history(&self, project_id: &Uuid) -> Result<Vec<BlockerEntry>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT project_id, reason, blocked_at, cleared_at
+             FROM blocker_history WHERE project_id = ?1 ORDER BY blocked_at, id",
+        )?;
+
+        let entries = stmt
+            .query_map(params![project_id.to_string()], blocker_entry_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(entries)
+    }
+
+    // Phases
+
+    /// Read a project's stored phase, or `None` if the project doesn't exist
+    fn stored_phase(&self, id: &Uuid) -> Result<Option<Option<String>>> {
+        let phase = self
+            .conn
+            .prepare_cached("SELECT phase FROM projects WHERE id = ?1")?
+            .query_row(params![id.to_string()], |row| row.get(0))
+            .optional()?;
+        Ok(phase)
+    }
+
+    /// Record a change of a project's phase in phase_history
+    ///
+    /// Nothing is recorded if the phase didn't change. A move to a phase
+    /// earlier in the configured order is flagged as backwards.
+    fn record_phase_change(&self, project_id: &Uuid, from: Option<&str>, to: Option<&str>) -> Result<()> {
+        if from == to {
+            return Ok(());
+        }
+
+        let position = |phase: Option<&str>| phase.and_then(|phase| self.phases.iter().position(|p| p == phase));
+        let backwards = matches!((position(from), position(to)), (Some(from), Some(to)) if to < from);
+        self.conn
+            .prepare_cached(
+                "INSERT INTO phase_history (project_id, from_phase, to_phase, backwards, changed_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?
+            .execute(params![project_id.to_string(), from, to, backwards, dt_to_db(Utc::now())])?;
+        Ok(())
+    }
+
+    /// Move a project to a phase and return it
+    ///
+    /// The change is recorded in the phase history; setting the phase the
+    /// project is already in changes nothing.
+    pub fn set_phase(&self, id: &Uuid, phase: &str) -> Result<Project> {
+        let phase = self.canonical_phase(phase)?;
+        let tx = begin_or_join(self.conn)?;
+        let previous = self.stored_phase(id)?.ok_or_else(|| Error::not_found("Project", id))?;
+
+        if previous.as_deref() != Some(phase.as_str()) {
+            self.conn
+                .prepare_cached("UPDATE projects SET phase = ?1, updated_at = ?2, version = version + 1 WHERE id = ?3")?
+                .execute(params![&phase, dt_to_db(Utc::now()), id.to_string()])?;
+            self.record_phase_change(id, previous.as_deref(), Some(&phase))?;
+        }
+        let project = self.find_by_id(id)?.ok_or_else(|| Error::not_found("Project", id))?;
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+
+        log::debug!("Moved project {} to phase {}", id, phase);
+        Ok(project)
+    }
+
+    /// Get a project's phase changes, oldest first
+    pub fn get_phase_history(&self, project_id: &Uuid) -> Result<Vec<PhaseChange>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT project_id, from_phase, to_phase, backwards, changed_at
+             FROM phase_history WHERE project_id = ?1 ORDER BY changed_at, id",
+        )?;
+
+        let history = stmt
+            .query_map(params![project_id.to_string()], phase_change_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(history)
+    }
+
+    /// Get the time a project has spent in each phase, in the order it first entered them
+    pub fn get_phase_durations(&self, project_id: &Uuid) -> Result<Vec<PhaseDuration>> {
+        Ok(phase_durations(&self.get_phase_history(project_id)?, Utc::now()))
+    }
+
+    /// List the projects in a phase, matched ignoring case, ordered by name
+    pub fn list_by_phase(&self, phase: &str) -> Result<Vec<Project>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM projects WHERE phase = ?1 COLLATE NOCASE ORDER BY name",
+            PROJECT_COLUMNS
+        ))?;
+
+        let projects = stmt
+            .query_map(params![phase.trim()], project_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(projects)
+    }
+
+    /// Get a project's recent activity, newest first
+    ///
+    /// Combines notes, milestones, resources, date changes and blockers.
+    /// Each source reads at most `limit` rows and only note titles, never
+    /// bodies, so the cost doesn't grow with the project's history. Sources
+    /// whose table doesn't exist in this database are skipped. Removed
+    /// resources aren't recorded anywhere, so they don't appear.
+    pub fn get_activity_feed(&self, project_id: &Uuid, limit: usize) -> Result<Vec<ActivityItem>> {
+        let id = project_id.to_string();
+        let limit_param = i64::try_from(limit).unwrap_or(i64::MAX);
+        let mut items = Vec::new();
+
+        let mut add = |table: &str,
+                       sql: &str,
+                       params: &[&dyn rusqlite::ToSql],
+                       map: &dyn Fn(&rusqlite::Row) -> rusqlite::Result<Vec<ActivityItem>>|
+         -> Result<()> {
+            if !self.table_exists(table)? {
+                return Ok(());
+            }
+            let mut stmt = self.conn.prepare_cached(sql)?;
+            let rows = stmt.query_map(params, |row| map(row))?;
+            for row in rows {
+                items.extend(row?);
+            }
+            Ok(())
+        };
+
+        add(
+            "project_notes",
+            "SELECT title, created_at FROM project_notes WHERE project_id = ?1 ORDER BY created_at DESC LIMIT ?2",
+            params![id, limit_param],
+            &|row| {
+                Ok(vec![ActivityItem {
+                    kind: ActivityKind::NoteAdded,
+                    summary: format!("Note added: {}", row.get::<_, String>(0)?),
+                    actor: None,
+                    timestamp: get_datetime(row, 1)?,
+                }])
+            },
+        )?;
+        add(
+            "milestone_notes",
+            "SELECT n.title, m.name, n.created_at FROM milestone_notes n
+             JOIN milestones m ON m.id = n.milestone_id
+             WHERE m.project_id = ?1 ORDER BY n.created_at DESC LIMIT ?2",
+            params![id, limit_param],
+            &|row| {
+                Ok(vec![ActivityItem {
+                    kind: ActivityKind::NoteAdded,
+                    summary: format!("Note added to {}: {}", row.get::<_, String>(1)?, row.get::<_, String>(0)?),
+                    actor: None,
+                    timestamp: get_datetime(row, 2)?,
+                }])
+            },
+        )?;
+        add(
+            "stakeholder_notes",
+            "SELECT title, stakeholder_email, created_at FROM stakeholder_notes
+             WHERE project_id = ?1 ORDER BY created_at DESC LIMIT ?2",
+            params![id, limit_param],
+            &|row| {
+                Ok(vec![ActivityItem {
+                    kind: ActivityKind::NoteAdded,
+                    summary: format!("Stakeholder note added: {}", row.get::<_, String>(0)?),
+                    actor: Some(row.get(1)?),
+                    timestamp: get_datetime(row, 2)?,
+                }])
+            },
+        )?;
+        add(
+            "milestones",
+            "SELECT name, created_at FROM milestones WHERE project_id = ?1 ORDER BY created_at DESC LIMIT ?2",
+            params![id, limit_param],
+            &|row| {
+                Ok(vec![ActivityItem {
+                    kind: ActivityKind::MilestoneCreated,
+                    summary: format!("Milestone created: {}", row.get::<_, String>(0)?),
+                    actor: None,
+                    timestamp: get_datetime(row, 1)?,
+                }])
+            },
+        )?;
+        // Milestones count as completed once their due date has passed, as in the summaries
+        add(
+            "milestones",
+            "SELECT name, due_date FROM milestones WHERE project_id = ?1 AND due_date < ?3
+             ORDER BY due_date DESC LIMIT ?2",
+            params![id, limit_param, dt_to_db(Utc::now())],
+            &|row| {
+                Ok(vec![ActivityItem {
+                    kind: ActivityKind::MilestoneCompleted,
+                    summary: format!("Milestone completed: {}", row.get::<_, String>(0)?),
+                    actor: None,
+                    timestamp: get_datetime(row, 1)?,
+                }])
+            },
+        )?;
+        add(
+            "project_resources",
+            "SELECT person_email, role, created_at FROM project_resources
+             WHERE project_id = ?1 ORDER BY created_at DESC LIMIT ?2",
+            params![id, limit_param],
+            &|row| {
+                let email: String = row.get(0)?;
+                let summary = match row.get::<_, Option<String>>(1)? {
+                    Some(role) => format!("{} added as {}", email, role),
+                    None => format!("{} added to the project", email),
+                };
+                Ok(vec![ActivityItem {
+                    kind: ActivityKind::ResourceAdded,
+                    summary,
+                    actor: Some(email),
+                    timestamp: get_datetime(row, 2)?,
+                }])
+            },
+        )?;
+        add(
+            "milestone_resources",
+            "SELECT r.person_email, m.name, r.created_at FROM milestone_resources r
+             JOIN milestones m ON m.id = r.milestone_id
+             WHERE m.project_id = ?1 ORDER BY r.created_at DESC LIMIT ?2",
+            params![id, limit_param],
+            &|row| {
+                let email: String = row.get(0)?;
+                Ok(vec![ActivityItem {
+                    kind: ActivityKind::ResourceAdded,
+                    summary: format!("{} added to {}", email, row.get::<_, String>(1)?),
+                    actor: Some(email),
+                    timestamp: get_datetime(row, 2)?,
+                }])
+            },
+        )?;
+        add(
+            "date_history",
+            "SELECT m.name, h.field, h.old_value, h.new_value, h.changed_at FROM date_history h
+             LEFT JOIN milestones m ON h.entity_type = 'milestone' AND m.id = h.entity_id
+             WHERE (h.entity_type = 'project' AND h.entity_id = ?1) OR m.project_id = ?1
+             ORDER BY h.changed_at DESC LIMIT ?2",
+            params![id, limit_param],
+            &|row| {
+                let subject = match row.get::<_, Option<String>>(0)? {
+                    Some(milestone) => format!("{} {}", milestone, row.get::<_, String>(1)?.replace('_', " ")),
+                    None => format!("Project {}", row.get::<_, String>(1)?.replace('_', " ")),
+                };
+                let date = |value: Option<DateTime<Utc>>| {
+                    value.map_or("none".to_string(), |d| d.format("%Y-%m-%d").to_string())
+                };
+                Ok(vec![ActivityItem {
+                    kind: ActivityKind::DateChanged,
+                    summary: format!(
+                        "{} moved from {} to {}",
+                        subject,
+                        date(get_opt_datetime(row, 2)?),
+                        date(get_opt_datetime(row, 3)?)
+                    ),
+                    actor: None,
+                    timestamp: get_datetime(row, 4)?,
+                }])
+            },
+        )?;
+        add(
+            "blocker_history",
+            "SELECT reason, blocked_at, cleared_at FROM blocker_history
+             WHERE project_id = ?1 ORDER BY blocked_at DESC LIMIT ?2",
+            params![id, limit_param],
+            &|row| {
+                let reason: Option<String> = row.get(0)?;
+                let mut events = vec![ActivityItem {
+                    kind: ActivityKind::Blocked,
+                    summary: match reason {
+                        Some(reason) => format!("Blocked: {}", reason),
+                        None => "Blocked".to_string(),
+                    },
+                    actor: None,
+                    timestamp: get_datetime(row, 1)?,
+                }];
+                if let Some(cleared_at) = get_opt_datetime(row, 2)? {
+                    events.push(ActivityItem {
+                        kind: ActivityKind::Unblocked,
+                        summary: "Unblocked".to_string(),
+                        actor: None,
+                        timestamp: cleared_at,
+                    });
+                }
+                Ok(events)
+            },
+        )?;
+
+        items.sort_by_key(|item| std::cmp::Reverse(item.timestamp));
+        items.truncate(limit);
+        Ok(items)
+    }
+
+    /// Whether a table exists, for features that older databases may not have
+    fn table_exists(&self, table: &str) -> Result<bool> {
+        let exists = self
+            .conn
+            .prepare_cached("SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = ?1")?
+            .query_row(params![table], |row| row.get(0))?;
+        Ok(exists)
+    }
+
+    // Initiatives
+
+    /// Link a project to an initiative, replacing any initiative it was linked to
+    pub fn set_initiative(&self, project_id: &Uuid, initiative_id: &Uuid) -> Result<()> {
+        self.conn
+            .prepare_cached(
+                "INSERT INTO project_initiatives (project_id, initiative_id, created_at)
+                 VALUES (?1, ?2, ?3)
+                 ON CONFLICT (project_id) DO UPDATE SET initiative_id = excluded.initiative_id,
+                                                        created_at = excluded.created_at",
+            )?
+            .execute(params![project_id.to_string(), initiative_id.to_string(), dt_to_db(Utc::now())])
+            .map_err(|e| Error::from(e).with_field("project or initiative"))?;
+        log::debug!("Linked project {} to initiative {}", project_id, initiative_id);
+        Ok(())
+    }
+
+    /// Unlink a project from its initiative, if it has one
+    pub fn clear_initiative(&self, project_id: &Uuid) -> Result<()> {
+        self.conn
+            .prepare_cached("DELETE FROM project_initiatives WHERE project_id = ?1")?
+            .execute(params![project_id.to_string()])?;
+        Ok(())
+    }
+
+    /// Get the initiative a project is linked to
+    pub fn get_initiative(&self, project_id: &Uuid) -> Result<Option<Initiative>> {
+        let initiative_id = self
+            .conn
+            .prepare_cached("SELECT initiative_id FROM project_initiatives WHERE project_id = ?1")?
+            .query_row(params![project_id.to_string()], |row| get_uuid(row, 0))
+            .optional()?;
+
+        match initiative_id {
+            Some(id) => InitiativeRepository::new(self.conn).find_by_id(&id),
+            None => Ok(None),
+        }
+    }
+
+    // Action Items
+
+    /// Get a project's action items, open items first, each by due date
+    pub fn list_action_items(&self, project_id: &Uuid) -> Result<Vec<ActionItem>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM action_items WHERE project_id = ?1
+             ORDER BY status = 'completed', due_date IS NULL, due_date, created_at",
+            ACTION_ITEM_COLUMNS
+        ))?;
+
+        let items = stmt
+            .query_map(params![project_id.to_string()], action_item_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(items)
+    }
+
+    /// Get the open action items assigned to a person across all projects, soonest due first
+    pub fn list_open_action_items_for(&self, email: &str) -> Result<Vec<ActionItem>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM action_items WHERE assignee_email = ?1 AND status = 'open'
+             ORDER BY due_date IS NULL, due_date, created_at",
+            ACTION_ITEM_COLUMNS
+        ))?;
+
+        let items = stmt
+            .query_map(params![email], action_item_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(items)
+    }
+
+    /// Find an action item by ID
+    pub fn find_action_item_by_id(&self, id: &Uuid) -> Result<Option<ActionItem>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM action_items WHERE id = ?1",
+            ACTION_ITEM_COLUMNS
+        ))?;
+        let item = stmt
+            .query_row(params![id.to_string()], action_item_from_row)
+            .optional()?;
+        Ok(item)
+    }
+
+    /// An action item needs a description, and its milestone and source note must be in its project
+    fn validate_action_item(&self, item: &ActionItem) -> Result<()> {
+        if item.description.trim().is_empty() {
+            return Err(Error::Invalid("Action item description cannot be empty".to_string()));
+        }
+
+        if let Some(milestone_id) = &item.milestone_id {
+            match self.find_milestone_by_id(milestone_id)? {
+                Some(milestone) if milestone.project_id == item.project_id => {}
+                Some(_) => {
+                    return Err(Error::Invalid(format!(
+                        "Milestone {} is not part of project {}",
+                        milestone_id, item.project_id
+                    )))
+                }
+                None => return Err(Error::not_found("Milestone", milestone_id)),
+            }
+        }
+
+        if let Some(note_id) = &item.source_note_id {
+            match self.find_project_note_by_id(note_id)? {
+                Some(note) if note.project_id == item.project_id => {}
+                Some(_) => {
+                    return Err(Error::Invalid(format!(
+                        "Note {} is not part of project {}",
+                        note_id, item.project_id
+                    )))
+                }
+                None => return Err(Error::not_found("Project note", note_id)),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Add an action item to a project
+    pub fn create_action_item(&self, item: &ActionItem) -> Result<()> {
+        self.validate_action_item(item)?;
+
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT INTO action_items (id, project_id, milestone_id, assignee_email, description, due_date,
+                                       status, source_note_id, completed_at, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        )?;
+        stmt.execute(params![
+            item.id.to_string(),
+            item.project_id.to_string(),
+            item.milestone_id.map(|id| id.to_string()),
+            &item.assignee_email,
+            &item.description,
+            item.due_date.map(dt_to_db),
+            item.status.as_str(),
+            item.source_note_id.map(|id| id.to_string()),
+            item.completed_at.map(dt_to_db),
+            dt_to_db(item.created_at),
+            dt_to_db(item.updated_at),
+        ])
+        .map_err(|e| Error::from(e).with_field("project or assignee_email"))?;
+        log::debug!("Created action item: {}", item.id);
+        Ok(())
+    }
+
+    /// Update an action item
+    ///
+    /// Moving an item to or from completed sets or clears its completion time.
+    pub fn update_action_item(&self, item: &ActionItem) -> Result<()> {
+        self.validate_action_item(item)?;
+
+        let completed_at = match item.status {
+            ActionItemStatus::Completed => Some(item.completed_at.unwrap_or(item.updated_at)),
+            ActionItemStatus::Open => None,
+        };
+
+        let mut stmt = self.conn.prepare_cached(
+            "UPDATE action_items
+             SET milestone_id = ?1, assignee_email = ?2, description = ?3, due_date = ?4, status = ?5,
+                 source_note_id = ?6, completed_at = ?7, updated_at = ?8
+             WHERE id = ?9",
+        )?;
+        let rows = stmt
+            .execute(params![
+                item.milestone_id.map(|id| id.to_string()),
+                &item.assignee_email,
+                &item.description,
+                item.due_date.map(dt_to_db),
+                item.status.as_str(),
+                item.source_note_id.map(|id| id.to_string()),
+                completed_at.map(dt_to_db),
+                dt_to_db(item.updated_at),
+                item.id.to_string(),
+            ])
+            .map_err(|e| Error::from(e).with_field("assignee_email"))?;
+
+        if rows == 0 {
+            return Err(Error::not_found("Action item", item.id));
+        }
+
+        log::debug!("Updated action item: {}", item.id);
+        Ok(())
+    }
+
+    /// Mark an action item as completed now and return it
+    ///
+    /// Completing an item that is already completed keeps its original completion time.
+    pub fn complete_action_item(&self, id: &Uuid) -> Result<ActionItem> {
+        let now = dt_to_db(Utc::now());
+        self.conn
+            .prepare_cached(
+                "UPDATE action_items SET status = ?1, completed_at = ?2, updated_at = ?2
+                 WHERE id = ?3 AND status <> ?1",
+            )?
+            .execute(params![ActionItemStatus::Completed.as_str(), now, id.to_string()])?;
+
+        self.find_action_item_by_id(id)?
+            .ok_or_else(|| Error::not_found("Action item", id))
+    }
+
+    /// Delete an action item
+    pub fn delete_action_item(&self, id: &Uuid) -> Result<()> {
+        let rows = self
+            .conn
+            .prepare_cached("DELETE FROM action_items WHERE id = ?1")?
+            .execute(params![id.to_string()])?;
+
+        if rows == 0 {
+            return Err(Error::not_found("Action item", id));
+        }
+
+        Ok(())
+    }
+
+    // Milestone Notes
+
+    /// Get notes for a milestone
+    pub fn get_milestone_notes(&self, milestone_id: &Uuid) -> Result<Vec<MilestoneNote>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, milestone_id, title, body, created_at, updated_at, version
+             FROM milestone_notes WHERE milestone_id = ?1 ORDER BY created_at DESC, id DESC",
+        )?;
+
+        let mut notes = stmt
+            .query_map(params![milestone_id.to_string()], milestone_note_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        AttachmentRepository::new(self.conn).load_for_notes(&mut notes)?;
+
+        Ok(notes)
+    }
+
+    /// Get up to `limit` of a milestone's notes, newest first, starting after `cursor`
+    pub fn get_milestone_notes_page(
+        &self,
+        milestone_id: &Uuid,
+        cursor: Option<NoteCursor>,
+        limit: usize,
+    ) -> Result<Vec<MilestoneNote>> {
+        let (created_at, id) = note_cursor_params(cursor);
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, milestone_id, title, body, created_at, updated_at, version
+             FROM milestone_notes
+             WHERE milestone_id = ?1 AND (?2 IS NULL OR created_at < ?2 OR (created_at = ?2 AND id < ?3))
+             ORDER BY created_at DESC, id DESC LIMIT ?4",
+        )?;
+
+        let mut notes = stmt
+            .query_map(params![milestone_id.to_string(), created_at, id, limit as i64], milestone_note_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        AttachmentRepository::new(self.conn).load_for_notes(&mut notes)?;
+
+        Ok(notes)
+    }
+
+    /// Find a milestone note by ID
+    pub fn find_milestone_note_by_id(&self, id: &Uuid) -> Result<Option<MilestoneNote>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, milestone_id, title, body, created_at, updated_at, version
+             FROM milestone_notes WHERE id = ?1",
+        )?;
+        let mut note = stmt
+            .query_row(params![id.to_string()], milestone_note_from_row)
+            .optional()?;
+        if let Some(note) = note.as_mut() {
+            AttachmentRepository::new(self.conn).load_for_notes(std::slice::from_mut(note))?;
+        }
+        Ok(note)
+    }
+
+    /// Add note to milestone
+    pub fn add_milestone_note(&self, note: &MilestoneNote) -> Result<()> {
+        let tx = begin_or_join(self.conn)?;
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT INTO milestone_notes (id, milestone_id, title, body, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )?;
+        stmt.execute(params![
+            note.id.to_string(),
+            note.milestone_id.to_string(),
+            &note.title,
+            &note.body,
+            dt_to_db(note.created_at),
+            dt_to_db(note.updated_at),
+        ])
+        .map_err(|e| Error::from(e).with_field("milestone"))?;
+        NoteLinkRepository::new(self.conn).sync_note(NoteType::Milestone, &note.id, &note.body)?;
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+        Ok(())
+    }
+
+    /// Update a milestone note
+    pub fn update_milestone_note(&self, note: &MilestoneNote) -> Result<()> {
+        let tx = begin_or_join(self.conn)?;
+        let mut stmt = self.conn.prepare_cached(
+            "UPDATE milestone_notes SET title = ?1, body = ?2, updated_at = ?3, version = version + 1
+             WHERE id = ?4 AND version = ?5",
+        )?;
+        let rows = stmt.execute(params![
+            &note.title,
+            &note.body,
+            dt_to_db(note.updated_at),
+            note.id.to_string(),
+            note.version,
+        ])?;
+
+        if rows == 0 {
+            return Err(match self.find_milestone_note_by_id(&note.id)? {
+                Some(current) => Error::version_conflict("Milestone note", note.id, note.version, current.version, &current),
+                None => Error::not_found("Milestone note", note.id),
+            });
+        }
+
+        NoteLinkRepository::new(self.conn).sync_note(NoteType::Milestone, &note.id, &note.body)?;
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+
+        log::debug!("Updated milestone note: {}", note.id);
+        Ok(())
+    }
+
+    /// Delete milestone note
+    pub fn delete_milestone_note(&self, id: &Uuid) -> Result<()> {
+        let rows = self
+            .conn
+            .prepare_cached("DELETE FROM milestone_notes WHERE id = ?1")?
+            .execute(params![id.to_string()])?;
+
+        if rows == 0 {
+            return Err(Error::not_found("Milestone note", id));
+        }
+        sync::record_deletion(self.conn, SyncEntity::MilestoneNote, &id.to_string())?;
+
+        Ok(())
+    }
+
+    // Stakeholder Notes
+
+    /// Get notes for a stakeholder
+    pub fn get_stakeholder_notes(&self, project_id: &Uuid, stakeholder_email: &str) -> Result<Vec<StakeholderNote>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, project_id, stakeholder_email, title, body, created_at, updated_at, version
+             FROM stakeholder_notes WHERE project_id = ?1 AND stakeholder_email = ?2 ORDER BY created_at DESC, id DESC",
+        )?;
+
+        let mut notes = stmt
+            .query_map(params![project_id.to_string(), stakeholder_email], stakeholder_note_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        AttachmentRepository::new(self.conn).load_for_notes(&mut notes)?;
+
+        Ok(notes)
+    }
+
+    /// Get up to `limit` of a stakeholder's notes on a project, newest first, starting after `cursor`
+    pub fn get_stakeholder_notes_page(
+        &self,
+        project_id: &Uuid,
+        stakeholder_email: &str,
+        cursor: Option<NoteCursor>,
+        limit: usize,
+    ) -> Result<Vec<StakeholderNote>> {
+        let (created_at, id) = note_cursor_params(cursor);
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, project_id, stakeholder_email, title, body, created_at, updated_at, version
+             FROM stakeholder_notes
+             WHERE project_id = ?1 AND stakeholder_email = ?2
+               AND (?3 IS NULL OR created_at < ?3 OR (created_at = ?3 AND id < ?4))
+             ORDER BY created_at DESC, id DESC LIMIT ?5",
+        )?;
+
+        let mut notes = stmt
+            .query_map(
+                params![project_id.to_string(), stakeholder_email, created_at, id, limit as i64],
+                stakeholder_note_from_row,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        AttachmentRepository::new(self.conn).load_for_notes(&mut notes)?;
+
+        Ok(notes)
+    }
+
+    /// Gather a stakeholder's role, person record, organization, recent
+    /// notes, open questions and the decisions they approved
+    ///
+    /// `note_limit` caps the number of recent notes. Open questions are taken
+    /// from all of the stakeholder's notes.
+    pub fn get_stakeholder_brief(
+        &self,
+        project_id: &Uuid,
+        stakeholder_email: &str,
+        note_limit: usize,
+    ) -> Result<StakeholderBrief> {
+        let project = self
+            .find_by_id(project_id)?
+            .ok_or_else(|| Error::not_found("Project", project_id))?;
+        let stakeholder = self
+            .get_stakeholders(project_id)?
+            .into_iter()
+            .find(|s| s.stakeholder_email == stakeholder_email)
+            .ok_or_else(|| Error::not_found("Stakeholder", format!("{} on project {}", stakeholder_email, project.name)))?;
+        let person = PersonRepository::new(self.conn)
+            .find_by_email(stakeholder_email)?
+            .ok_or_else(|| Error::not_found("Person", stakeholder_email))?;
+        let organization = match &person.organization {
+            Some(name) => OrganizationRepository::new(self.conn).find_by_name(name)?,
+            None => None,
+        };
+
+        let mut recent_notes = self.get_stakeholder_notes(project_id, stakeholder_email)?;
+        let open_questions = recent_notes.iter().flat_map(|note| notes::open_tasks(&note.body)).collect();
+        recent_notes.truncate(note_limit);
+
+        Ok(StakeholderBrief {
+            project_id: project.id,
+            project_name: project.name,
+            stakeholder,
+            person,
+            organization,
+            recent_notes,
+            open_questions,
+            approved_decisions: DecisionRepository::new(self.conn).approved_by(project_id, stakeholder_email)?,
+        })
+    }
+
+    /// Sort a project's stakeholders into the quadrants of an influence/interest grid
+    ///
+    /// See [`StakeholderMatrix`] for how ratings map to quadrants.
+    pub fn get_stakeholder_matrix(&self, project_id: &Uuid) -> Result<StakeholderMatrix> {
+        let project = self
+            .find_by_id(project_id)?
+            .ok_or_else(|| Error::not_found("Project", project_id))?;
+        let mut stakeholders = self.get_stakeholders(project_id)?;
+        stakeholders.sort_by(|a, b| {
+            (b.influence, b.interest)
+                .cmp(&(a.influence, a.interest))
+                .then_with(|| a.stakeholder_email.cmp(&b.stakeholder_email))
+        });
+
+        let mut matrix = StakeholderMatrix {
+            project_id: project.id,
+            project_name: project.name,
+            manage_closely: Vec::new(),
+            keep_satisfied: Vec::new(),
+            keep_informed: Vec::new(),
+            monitor: Vec::new(),
+            unrated: Vec::new(),
+        };
+        for stakeholder in stakeholders {
+            let quadrant = match (stakeholder.influence, stakeholder.interest) {
+                (Some(influence), Some(interest)) => match (influence >= HIGH_RATING, interest >= HIGH_RATING) {
+                    (true, true) => &mut matrix.manage_closely,
+                    (true, false) => &mut matrix.keep_satisfied,
+                    (false, true) => &mut matrix.keep_informed,
+                    (false, false) => &mut matrix.monitor,
+                },
+                _ => &mut matrix.unrated,
+            };
+            quadrant.push(stakeholder);
+        }
+        Ok(matrix)
+    }
+
+    /// Find a stakeholder note by ID
+    pub fn find_stakeholder_note_by_id(&self, id: &Uuid) -> Result<Option<StakeholderNote>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, project_id, stakeholder_email, title, body, created_at, updated_at, version
+             FROM stakeholder_notes WHERE id = ?1",
+        )?;
+        let mut note = stmt
+            .query_row(params![id.to_string()], stakeholder_note_from_row)
+            .optional()?;
+        if let Some(note) = note.as_mut() {
+            AttachmentRepository::new(self.conn).load_for_notes(std::slice::from_mut(note))?;
+        }
+        Ok(note)
+    }
+
+    /// Add note to stakeholder
+    pub fn add_stakeholder_note(&self, note: &StakeholderNote) -> Result<()> {
+        let tx = begin_or_join(self.conn)?;
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT INTO stakeholder_notes (id, project_id, stakeholder_email, title, body, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )?;
+        stmt.execute(params![
+            note.id.to_string(),
+            note.project_id.to_string(),
+            &note.stakeholder_email,
+            &note.title,
+            &note.body,
+            dt_to_db(note.created_at),
+            dt_to_db(note.updated_at),
+        ])
+        .map_err(|e| Error::from(e).with_field("stakeholder"))?;
+        NoteLinkRepository::new(self.conn).sync_note(NoteType::Stakeholder, &note.id, &note.body)?;
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+        Ok(())
+    }
+
+    /// Update a stakeholder note
+    pub fn update_stakeholder_note(&self, note: &StakeholderNote) -> Result<()> {
+        let tx = begin_or_join(self.conn)?;
+        let mut stmt = self.conn.prepare_cached(
+            "UPDATE stakeholder_notes SET title = ?1, body = ?2, updated_at = ?3, version = version + 1
+             WHERE id = ?4 AND version = ?5",
+        )?;
+        let rows = stmt.execute(params![
+            &note.title,
+            &note.body,
+            dt_to_db(note.updated_at),
+            note.id.to_string(),
+            note.version,
+        ])?;
+
+        if rows == 0 {
+            return Err(match self.find_stakeholder_note_by_id(&note.id)? {
+                Some(current) => Error::version_conflict("Stakeholder note", note.id, note.version, current.version, &current),
+                None => Error::not_found("Stakeholder note", note.id),
+            });
+        }
+
+        NoteLinkRepository::new(self.conn).sync_note(NoteType::Stakeholder, &note.id, &note.body)?;
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+
+        log::debug!("Updated stakeholder note: {}", note.id);
+        Ok(())
+    }
+
+    /// Delete stakeholder note
+    pub fn delete_stakeholder_note(&self, id: &Uuid) -> Result<()> {
+        let rows = self
+            .conn
+            .prepare_cached("DELETE FROM stakeholder_notes WHERE id = ?1")?
+            .execute(params![id.to_string()])?;
+
+        if rows == 0 {
+            return Err(Error::not_found("Stakeholder note", id));
+        }
+
+        Ok(())
+    }
+
+    // Note batches and moves
+
+    /// Create notes on any mix of projects, milestones and stakeholders
+    ///
+    /// The notes are created in a single transaction, so if one fails none are
+    /// created. Returns the new note IDs in the order given.
+    pub fn add_notes_batch(&self, notes: &[NewNote]) -> Result<Vec<Uuid>> {
+        let tx = begin_or_join(self.conn)?;
+
+        let mut ids = Vec::with_capacity(notes.len());
+        for note in notes {
+            let (title, body) = (note.title.clone(), note.body.clone());
+            let id = match &note.target {
+                NoteTarget::Project { project_id } => {
+                    let note = ProjectNote::new(*project_id, title, body);
+                    self.add_project_note(&note)?;
+                    note.id
+                }
+                NoteTarget::Milestone { milestone_id } => {
+                    let note = MilestoneNote::new(*milestone_id, title, body);
+                    self.add_milestone_note(&note)?;
+                    note.id
+                }
+                NoteTarget::Stakeholder { project_id, stakeholder_email } => {
+                    let note = StakeholderNote::new(*project_id, stakeholder_email.clone(), title, body);
+                    self.add_stakeholder_note(&note)?;
+                    note.id
+                }
+            };
+            ids.push(id);
+        }
+
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+        Ok(ids)
+    }
+
+    /// Move a note between a project and its milestones
+    ///
+    /// Project and milestone notes can move to the project or to any milestone
+    /// of the same project, keeping their ID and timestamps. Moves to another
+    /// project, and moves to or from stakeholders, are rejected.
+    pub fn move_note(&self, note_id: &Uuid, target: &NoteTarget) -> Result<()> {
+        let (current, project_id, title, body, created_at, updated_at) =
+            if let Some(note) = self.find_project_note_by_id(note_id)? {
+                if note.kind == NoteKind::Log && matches!(target, NoteTarget::Milestone { .. }) {
+                    return Err(Error::Invalid("Log notes can't be moved to a milestone".to_string()));
+                }
+                let current = NoteTarget::Project { project_id: note.project_id };
+                (current, note.project_id, note.title, note.body, note.created_at, note.updated_at)
+            } else if let Some(note) = self.find_milestone_note_by_id(note_id)? {
+                let milestone = self
+                    .find_milestone_by_id(&note.milestone_id)?
+                    .ok_or_else(|| Error::not_found("Milestone", note.milestone_id))?;
+                let current = NoteTarget::Milestone { milestone_id: note.milestone_id };
+                (current, milestone.project_id, note.title, note.body, note.created_at, note.updated_at)
+            } else if self.find_stakeholder_note_by_id(note_id)?.is_some() {
+                return Err(Error::Invalid("Stakeholder notes can't be moved".to_string()));
+            } else {
+                return Err(Error::not_found("Note", note_id));
+            };
+
+        let target_project_id = match target {
+            NoteTarget::Project { project_id } => *project_id,
+            NoteTarget::Milestone { milestone_id } => {
+                self.find_milestone_by_id(milestone_id)?
+                    .ok_or_else(|| Error::not_found("Milestone", milestone_id))?
+                    .project_id
+            }
+            NoteTarget::Stakeholder { .. } => {
+                return Err(Error::Invalid(
+                    "Notes can only be moved to a project or one of its milestones".to_string(),
+                ));
+            }
+        };
+        if target_project_id != project_id {
+            return Err(Error::Invalid("Notes can't be moved to a different project".to_string()));
+        }
+        if *target == current {
+            return Ok(());
+        }
+
+        let tx = begin_or_join(self.conn)?;
+        // Retag the attachments first so deleting the old note doesn't remove them
+        let (from_type, to_type) = match current {
+            NoteTarget::Milestone { .. } => (NoteType::Milestone, NoteType::Project),
+            _ => (NoteType::Project, NoteType::Milestone),
+        };
+        self.conn
+            .prepare_cached("UPDATE attachments SET note_type = ?1 WHERE note_type = ?2 AND note_id = ?3")?
+            .execute(params![to_type.as_str(), from_type.as_str(), note_id.to_string()])?;
+        match current {
+            NoteTarget::Milestone { .. } => self.delete_milestone_note(note_id)?,
+            _ => self.delete_project_note(note_id)?,
+        }
+        match *target {
+            NoteTarget::Milestone { milestone_id } => self.add_milestone_note(&MilestoneNote {
+                id: *note_id,
+                milestone_id,
+                title,
+                body,
+                created_at,
+                updated_at,
+                version: 1,
+                attachments: Vec::new(),
+            })?,
+            _ => self.add_project_note(&ProjectNote {
+                id: *note_id,
+                project_id,
+                title,
+                body,
+                created_at,
+                updated_at,
+                version: 1,
+                kind: NoteKind::Freeform,
+                attachments: Vec::new(),
+            })?,
+        }
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+
+        log::debug!("Moved note {} to {:?}", note_id, target);
+        Ok(())
+    }
+
+    /// Get a project, milestone or stakeholder note by ID, with its whole body
+    pub fn get_note_by_id(&self, note_type: NoteType, id: &Uuid) -> Result<Option<NoteSlice>> {
+        self.get_note_slice(note_type, id, 0, None)
+    }
+
+    /// Get part of a note's body: up to `max_chars` characters from
+    /// `offset_chars`, or everything from there when there is no limit
+    ///
+    /// The body is cut by SQLite, so only the requested part is read out of
+    /// the database.
+    pub fn get_note_slice(
+        &self,
+        note_type: NoteType,
+        id: &Uuid,
+        offset_chars: usize,
+        max_chars: Option<usize>,
+    ) -> Result<Option<NoteSlice>> {
+        let sql = format!(
+            "SELECT id, title, substr(body, ?2 + 1, COALESCE(?3, length(body))), length(body),
+                    created_at, updated_at, version
+             FROM {} WHERE id = ?1",
+            note_type.table()
+        );
+        let slice = self
+            .conn
+            .prepare_cached(&sql)?
+            .query_row(
+                params![id.to_string(), offset_chars as i64, max_chars.map(|max| max as i64)],
+                |row| {
+                    let body: String = row.get(2)?;
+                    let total_chars = row.get::<_, i64>(3)? as usize;
+                    let end = offset_chars + body.chars().count();
+                    Ok(NoteSlice {
+                        id: get_uuid(row, 0)?,
+                        note_type,
+                        title: row.get(1)?,
+                        body,
+                        offset_chars,
+                        total_chars,
+                        next_offset: (end < total_chars).then_some(end),
+                        created_at: get_datetime(row, 4)?,
+                        updated_at: get_datetime(row, 5)?,
+                        version: row.get(6)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(slice)
+    }
+
+    /// Summarize a note with its title, the first `excerpt_chars` characters
+    /// of the body and its size
+    ///
+    /// Words are counted on SQLite's copy of the body, which is never copied
+    /// into a `String`.
+    pub fn get_note_summary(&self, note_type: NoteType, id: &Uuid, excerpt_chars: usize) -> Result<Option<NoteSummary>> {
+        let sql = format!(
+            "SELECT id, title, substr(body, 1, ?2), length(body), body, created_at, updated_at
+             FROM {} WHERE id = ?1",
+            note_type.table()
+        );
+        let summary = self
+            .conn
+            .prepare_cached(&sql)?
+            .query_row(params![id.to_string(), excerpt_chars as i64], |row| {
+                Ok(NoteSummary {
+                    id: get_uuid(row, 0)?,
+                    note_type,
+                    title: row.get(1)?,
+                    excerpt: row.get(2)?,
+                    total_chars: row.get::<_, i64>(3)? as usize,
+                    word_count: row.get_ref(4)?.as_str()?.split_whitespace().count(),
+                    created_at: get_datetime(row, 5)?,
+                    updated_at: get_datetime(row, 6)?,
+                })
+            })
+            .optional()?;
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use crate::db::{Person, RiskLevel};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    // Project CRUD tests
+
+    #[test]
+    fn test_create_and_find_project() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let project = Project::new("Test Project".to_string());
+
+        repo.create(&project).unwrap();
+
+        let found = repo.find_by_id(&project.id).unwrap().unwrap();
+        assert_eq!(found.name, "Test Project");
+        assert_eq!(found.id, project.id);
+    }
+
+    #[test]
+    fn test_find_nonexistent_project() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let fake_id = Uuid::new_v4();
+
+        let result = repo.find_by_id(&fake_id).unwrap();
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_list_all_projects() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+
+        let project1 = Project::new("Alpha Project".to_string());
+        let project2 = Project::new("Beta Project".to_string());
+        repo.create(&project1).unwrap();
+        repo.create(&project2).unwrap();
+
+        let projects = repo.list_all().unwrap();
+        assert_eq!(projects.len(), 2);
+        // Should be sorted by name
+        assert_eq!(projects[0].name, "Alpha Project");
+        assert_eq!(projects[1].name, "Beta Project");
+    }
+
+    #[test]
+    fn test_update_project() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let mut project = Project::new("Original Name".to_string());
+        repo.create(&project).unwrap();
+
+        project.name = "Updated Name".to_string();
+        project.description = Some("New description".to_string());
+        repo.update(&project).unwrap();
+
+        let found = repo.find_by_id(&project.id).unwrap().unwrap();
+        assert_eq!(found.name, "Updated Name");
+        assert_eq!(found.description, Some("New description".to_string()));
+    }
+
+    #[test]
+    fn test_update_nonexistent_project() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let project = Project::new("Test Project".to_string());
+
+        let result = repo.update(&project);
+        assert!(matches!(result.unwrap_err(), Error::NotFound { entity: "Project", .. }));
+    }
+
+    #[test]
+    fn test_update_project_version_conflict() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+
+        // Two clients read the same version
+        let mut first = repo.find_by_id(&project.id).unwrap().unwrap();
+        let mut second = first.clone();
+        assert_eq!(first.version, 1);
+
+        first.description = Some("Written by the agent".to_string());
+        repo.update(&first).unwrap();
+        assert_eq!(repo.find_by_id(&project.id).unwrap().unwrap().version, 2);
+
+        // The stale write is rejected and carries the current row
+        second.description = Some("Stale edit".to_string());
+        match repo.update(&second).unwrap_err() {
+            Error::VersionConflict { entity, expected, actual, current, .. } => {
+                assert_eq!(entity, "Project");
+                assert_eq!(expected, 1);
+                assert_eq!(actual, 2);
+                assert_eq!(current["description"], "Written by the agent");
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+
+        // Retrying against the current version succeeds
+        second.version = 2;
+        repo.update(&second).unwrap();
+        let found = repo.find_by_id(&project.id).unwrap().unwrap();
+        assert_eq!(found.description, Some("Stale edit".to_string()));
+        assert_eq!(found.version, 3);
+    }
+
+    #[test]
+    fn test_project_type_validation() {
+        let conn = setup_test_db();
+        let types = vec!["Personal".to_string(), "Team".to_string()];
+        let repo = ProjectRepository::new(&conn).with_project_types(&types);
+
+        let mut project = Project::new("Test Project".to_string());
+        project.project_type = "Company".to_string();
+        match repo.create(&project).unwrap_err() {
+            Error::Invalid(message) => assert!(message.contains("Personal, Team"), "{}", message),
+            other => panic!("unexpected error: {}", other),
+        }
+
+        project.project_type = "Team".to_string();
+        repo.create(&project).unwrap();
+        project.project_type = "Company".to_string();
+        assert!(matches!(repo.update(&project).unwrap_err(), Error::Invalid(_)));
+
+        // Without configured types any type is accepted
+        ProjectRepository::new(&conn).update(&project).unwrap();
+        let empty: Vec<String> = Vec::new();
+        project.version += 1;
+        project.project_type = "Anything".to_string();
+        ProjectRepository::new(&conn).with_project_types(&empty).update(&project).unwrap();
+    }
+
+    #[test]
+    fn test_rename_project_type() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+
+        for (name, project_type) in [("Alpha", "Company"), ("Beta", "Company"), ("Gamma", "Team")] {
+            let mut project = Project::new(name.to_string());
+            project.project_type = project_type.to_string();
+            repo.create(&project).unwrap();
+        }
+        assert_eq!(repo.list_project_types_in_use().unwrap(), vec!["Company", "Team"]);
+
+        assert_eq!(repo.rename_project_type("Company", "Org-wide").unwrap(), 2);
+        assert_eq!(repo.list_project_types_in_use().unwrap(), vec!["Org-wide", "Team"]);
+        let alpha = repo.find_by_name_exact("Alpha").unwrap().unwrap();
+        assert_eq!(alpha.project_type, "Org-wide");
+        assert_eq!(alpha.version, 2);
+
+        assert_eq!(repo.rename_project_type("Company", "Org-wide").unwrap(), 0);
+        assert!(matches!(repo.rename_project_type("Team", " ").unwrap_err(), Error::Invalid(_)));
+    }
+
+    #[test]
+    fn test_delete_project() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+
+        repo.delete(&project.id).unwrap();
+
+        let found = repo.find_by_id(&project.id).unwrap();
+        assert!(found.is_none());
+    }
+
+    #[test]
+    fn test_delete_nonexistent_project() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let fake_id = Uuid::new_v4();
+
+        let result = repo.delete(&fake_id);
+        assert!(matches!(result.unwrap_err(), Error::NotFound { entity: "Project", .. }));
+    }
+
+    // Milestone tests
+
+    #[test]
+    fn test_add_milestone() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+
+        let milestone = Milestone::new(project.id, 1, "Milestone 1".to_string());
+        repo.add_milestone(&milestone).unwrap();
+
+        let milestones = repo.get_milestones(&project.id).unwrap();
+        assert_eq!(milestones.len(), 1);
+        assert_eq!(milestones[0].name, "Milestone 1");
+        assert_eq!(milestones[0].number, 1);
+    }
+
+    #[test]
+    fn test_get_milestones_empty() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+
+        let milestones = repo.get_milestones(&project.id).unwrap();
+        assert_eq!(milestones.len(), 0);
+    }
+
+    #[test]
+    fn test_update_milestone() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+
+        let mut milestone = Milestone::new(project.id, 1, "Original Name".to_string());
+        repo.add_milestone(&milestone).unwrap();
+
+        milestone.name = "Updated Name".to_string();
+        milestone.description = Some("New description".to_string());
+        repo.update_milestone(&milestone).unwrap();
+
+        let milestones = repo.get_milestones(&project.id).unwrap();
+        assert_eq!(milestones[0].name, "Updated Name");
+        assert_eq!(milestones[0].description, Some("New description".to_string()));
+    }
+
+    #[test]
+    fn test_update_nonexistent_milestone() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+
+        let milestone = Milestone::new(project.id, 1, "Test Milestone".to_string());
+        let result = repo.update_milestone(&milestone);
+        assert!(matches!(result.unwrap_err(), Error::NotFound { entity: "Milestone", .. }));
+    }
+
+    #[test]
+    fn test_delete_milestone() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+
+        let milestone = Milestone::new(project.id, 1, "Milestone 1".to_string());
+        repo.add_milestone(&milestone).unwrap();
+
+        repo.delete_milestone(&milestone.id).unwrap();
+
+        let milestones = repo.get_milestones(&project.id).unwrap();
+        assert_eq!(milestones.len(), 0);
+    }
+
+    #[test]
+    fn test_delete_nonexistent_milestone() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let fake_id = Uuid::new_v4();
+
+        let result = repo.delete_milestone(&fake_id);
+        assert!(matches!(result.unwrap_err(), Error::NotFound { entity: "Milestone", .. }));
+    }
+
+    #[test]
+    fn test_add_duplicate_milestone_number_is_conflict() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+
+        repo.add_milestone(&Milestone::new(project.id, 1, "First".to_string())).unwrap();
+        let result = repo.add_milestone(&Milestone::new(project.id, 1, "Second".to_string()));
+        assert!(matches!(result.unwrap_err(), Error::Conflict(_)));
+    }
+
+    #[test]
+    fn test_add_milestone_to_missing_project_is_foreign_key_violation() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+
+        let result = repo.add_milestone(&Milestone::new(Uuid::new_v4(), 1, "Orphan".to_string()));
+        assert!(matches!(result.unwrap_err(), Error::ForeignKeyViolation { .. }));
+    }
+
+    #[test]
+    fn test_create_project_with_unknown_person_is_foreign_key_violation() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+
+        let mut project = Project::new("Test Project".to_string());
+        project.technical_lead = Some("nobody@example.com".to_string());
+        let result = repo.create(&project);
+        assert!(matches!(result.unwrap_err(), Error::ForeignKeyViolation { .. }));
+    }
+
+    #[test]
+    fn test_delete_project_cascades_to_milestones() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+
+        let milestone = Milestone::new(project.id, 1, "Milestone 1".to_string());
+        repo.add_milestone(&milestone).unwrap();
+
+        repo.delete(&project.id).unwrap();
+
+        // Milestones should be deleted via cascade
+        let milestones = repo.get_milestones(&project.id).unwrap();
+        assert_eq!(milestones.len(), 0);
+    }
+
+    // Stakeholder tests
+
+    #[test]
+    fn test_add_stakeholder() {
+        let conn = setup_test_db();
+        let person_repo = crate::db::PersonRepository::new(&conn);
+        let repo = ProjectRepository::new(&conn);
+
+        let person = crate::db::Person::new("alice@example.com".to_string(), "Alice".to_string());
+        person_repo.create(&person).unwrap();
+
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+
+        let mut stakeholder = ProjectStakeholder::new(project.id, "alice@example.com".to_string());
+        stakeholder.role = Some("Lead".to_string());
+        repo.add_stakeholder(&project.id, &stakeholder).unwrap();
+
+        let stakeholders = repo.get_stakeholders(&project.id).unwrap();
+        assert_eq!(stakeholders.len(), 1);
+        assert_eq!(stakeholders[0].stakeholder_email, "alice@example.com");
+        assert_eq!(stakeholders[0].role, Some("Lead".to_string()));
+    }
+
+    #[test]
+    fn test_update_stakeholder() {
+        let conn = setup_test_db();
+        let person_repo = crate::db::PersonRepository::new(&conn);
+        let repo = ProjectRepository::new(&conn);
+
+        let person = crate::db::Person::new("alice@example.com".to_string(), "Alice".to_string());
+        person_repo.create(&person).unwrap();
+
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+
+        let mut stakeholder = ProjectStakeholder::new(project.id, "alice@example.com".to_string());
+        stakeholder.role = Some("Member".to_string());
+        repo.add_stakeholder(&project.id, &stakeholder).unwrap();
+
+        stakeholder.role = Some("Lead".to_string());
+        repo.update_stakeholder(&project.id, &stakeholder).unwrap();
+
+        let stakeholders = repo.get_stakeholders(&project.id).unwrap();
+        assert_eq!(stakeholders[0].role, Some("Lead".to_string()));
+    }
+
+    #[test]
+    fn test_stakeholder_matrix() {
+        let conn = setup_test_db();
+        let person_repo = crate::db::PersonRepository::new(&conn);
+        let repo = ProjectRepository::new(&conn);
+
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+
+        let ratings = [
+            ("ceo@example.com", Some(5), Some(4)),
+            ("cfo@example.com", Some(4), Some(2)),
+            ("user@example.com", Some(2), Some(5)),
+            ("legal@example.com", Some(1), Some(1)),
+            ("new@example.com", Some(5), None),
+            ("vp@example.com", Some(4), Some(5)),
+        ];
+        for (email, influence, interest) in ratings {
+            person_repo.create(&Person::new(email.to_string(), email.to_string())).unwrap();
+            let mut stakeholder = ProjectStakeholder::new(project.id, email.to_string());
+            stakeholder.influence = influence;
+            stakeholder.interest = interest;
+            repo.add_stakeholder(&project.id, &stakeholder).unwrap();
+        }
+
+        let matrix = repo.get_stakeholder_matrix(&project.id).unwrap();
+        let emails = |stakeholders: &[ProjectStakeholder]| {
+            stakeholders.iter().map(|s| s.stakeholder_email.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(emails(&matrix.manage_closely), ["ceo@example.com", "vp@example.com"]);
+        assert_eq!(emails(&matrix.keep_satisfied), ["cfo@example.com"]);
+        assert_eq!(emails(&matrix.keep_informed), ["user@example.com"]);
+        assert_eq!(emails(&matrix.monitor), ["legal@example.com"]);
+        assert_eq!(emails(&matrix.unrated), ["new@example.com"]);
+
+        // Ratings are from 1 to 5
+        let mut stakeholder = repo.get_stakeholders(&project.id).unwrap().remove(0);
+        stakeholder.influence = Some(6);
+        let err = repo.update_stakeholder(&project.id, &stakeholder).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid influence 6: must be from 1 to 5");
+        stakeholder.influence = None;
+        stakeholder.interest = Some(0);
+        assert!(matches!(repo.update_stakeholder(&project.id, &stakeholder), Err(Error::Invalid(_))));
+
+        assert!(matches!(
+            repo.get_stakeholder_matrix(&Uuid::new_v4()),
+            Err(Error::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_remove_stakeholder() {
+        let conn = setup_test_db();
+        let person_repo = crate::db::PersonRepository::new(&conn);
+        let repo = ProjectRepository::new(&conn);
+
+        let person = crate::db::Person::new("alice@example.com".to_string(), "Alice".to_string());
+        person_repo.create(&person).unwrap();
+
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+
+        let stakeholder = ProjectStakeholder::new(project.id, "alice@example.com".to_string());
+        repo.add_stakeholder(&project.id, &stakeholder).unwrap();
+
+        repo.remove_stakeholder(&project.id, "alice@example.com").unwrap();
+
+        let stakeholders = repo.get_stakeholders(&project.id).unwrap();
+        assert_eq!(stakeholders.len(), 0);
+    }
+
+    // Project Notes tests
+
+    #[test]
+    fn test_add_project_note() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+
+        let note = ProjectNote::new(project.id, "Test Note".to_string(), "Note body".to_string());
+        repo.add_project_note(&note).unwrap();
+
+        let notes = repo.get_project_notes(&project.id).unwrap();
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].title, "Test Note");
+        assert_eq!(notes[0].body, "Note body");
+    }
+
+    #[test]
+    fn test_update_project_note() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+
+        let mut note = ProjectNote::new(project.id, "Original Title".to_string(), "Original body".to_string());
+        repo.add_project_note(&note).unwrap();
+
+        note.title = "Updated Title".to_string();
+        note.body = "Updated body".to_string();
+        repo.update_project_note(&note).unwrap();
+
+        let notes = repo.get_project_notes(&project.id).unwrap();
+        assert_eq!(notes[0].title, "Updated Title");
+        assert_eq!(notes[0].body, "Updated body");
+    }
+
+    #[test]
+    fn test_update_note_version_conflict() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+        let mut note = ProjectNote::new(project.id, "Status".to_string(), "On track".to_string());
+        repo.add_project_note(&note).unwrap();
+
+        note.body = "At risk".to_string();
+        repo.update_project_note(&note).unwrap();
+        let err = repo.update_project_note(&note).unwrap_err();
+        assert!(matches!(err, Error::VersionConflict { entity: "Project note", actual: 2, .. }));
+
+        let missing = ProjectNote::new(project.id, "Missing".to_string(), String::new());
+        let err = repo.update_project_note(&missing).unwrap_err();
+        assert!(matches!(err, Error::NotFound { .. }));
+    }
+
+    #[test]
+    fn test_log_notes_only_take_appends() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+        let mut note = ProjectNote::new(project.id, "Decision log".to_string(), String::new());
+        note.kind = NoteKind::Log;
+        repo.add_project_note(&note).unwrap();
+
+        let appended = repo.append_note_entry(&note.id, "Use Postgres", Some("alice@example.com")).unwrap();
+        assert!(appended.body.starts_with("## "), "{}", appended.body);
+        assert!(appended.body.ends_with(" UTC — alice@example.com\nUse Postgres"), "{}", appended.body);
+        assert_eq!(appended.version, 2);
+        let appended = repo.append_note_entry(&note.id, "Ship in May\n", None).unwrap();
+        assert!(appended.body.contains("Use Postgres\n\n## "), "{}", appended.body);
+        assert!(appended.body.ends_with(" UTC\nShip in May"), "{}", appended.body);
+
+        // The body can't be replaced, but the title can change
+        let mut edit = appended.clone();
+        edit.body = "Rewritten".to_string();
+        assert!(matches!(repo.update_project_note(&edit).unwrap_err(), Error::Invalid(_)));
+        let mut edit = appended.clone();
+        edit.title = "Decisions".to_string();
+        repo.update_project_note(&edit).unwrap();
+        let found = repo.find_project_note_by_id(&note.id).unwrap().unwrap();
+        assert_eq!((found.title.as_str(), found.kind), ("Decisions", NoteKind::Log));
+        assert_eq!(found.body, appended.body);
+
+        assert!(matches!(repo.append_note_entry(&note.id, "  ", None).unwrap_err(), Error::Invalid(_)));
+        assert!(matches!(repo.append_note_entry(&Uuid::new_v4(), "Lost", None).unwrap_err(), Error::NotFound { .. }));
+    }
+
+    #[test]
+    fn test_concurrent_note_appends() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.db");
+        let conn = db::open_database(&path).unwrap();
+        let project = Project::new("Test Project".to_string());
+        ProjectRepository::new(&conn).create(&project).unwrap();
+        let mut note = ProjectNote::new(project.id, "Decision log".to_string(), String::new());
+        note.kind = NoteKind::Log;
+        ProjectRepository::new(&conn).add_project_note(&note).unwrap();
+
+        // Entries land in the order they commit, whichever connection wrote them
+        let other = db::open_database(&path).unwrap();
+        ProjectRepository::new(&conn).append_note_entry(&note.id, "one", None).unwrap();
+        ProjectRepository::new(&other).append_note_entry(&note.id, "two", None).unwrap();
+        ProjectRepository::new(&conn).append_note_entry(&note.id, "three", None).unwrap();
+        let body = ProjectRepository::new(&conn).find_project_note_by_id(&note.id).unwrap().unwrap().body;
+        let entries: Vec<_> = body.lines().filter(|line| !line.is_empty() && !line.starts_with("## ")).collect();
+        assert_eq!(entries, ["one", "two", "three"]);
+
+        let workers: Vec<_> = ["a", "b"]
+            .into_iter()
+            .map(|worker| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    let conn = db::open_database(&path).unwrap();
+                    let repo = ProjectRepository::new(&conn);
+                    for i in 0..20 {
+                        repo.append_note_entry(&note.id, &format!("{}{}", worker, i), Some(worker)).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        // Every entry from both connections is there, each connection's in order
+        let note = ProjectRepository::new(&conn).find_project_note_by_id(&note.id).unwrap().unwrap();
+        assert_eq!(note.version, 1 + 3 + 40);
+        let entries: Vec<_> = note.body.lines().filter(|line| !line.is_empty() && !line.starts_with("## ")).collect();
+        assert_eq!(entries.len(), 3 + 40);
+        for worker in ["a", "b"] {
+            let own: Vec<_> = entries.iter().filter(|entry| entry.starts_with(worker)).copied().collect();
+            let expected: Vec<_> = (0..20).map(|i| format!("{}{}", worker, i)).collect();
+            assert_eq!(own, expected);
+        }
+    }
+
+    #[test]
+    fn test_delete_project_note() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+
+        let note = ProjectNote::new(project.id, "Test Note".to_string(), "Note body".to_string());
+        repo.add_project_note(&note).unwrap();
+
+        repo.delete_project_note(&note.id).unwrap();
+
+        let notes = repo.get_project_notes(&project.id).unwrap();
+        assert_eq!(notes.len(), 0);
+    }
+
+    #[test]
+    fn test_project_notes_page_with_identical_timestamps() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+
+        let created_at = Utc::now();
+        for i in 0..5 {
+            let mut note = ProjectNote::new(project.id, format!("Note {}", i), String::new());
+            note.created_at = created_at;
+            repo.add_project_note(&note).unwrap();
+        }
+
+        // Notes created in the same instant are ordered by ID, every time
+        let all = repo.get_project_notes(&project.id).unwrap();
+        let ids: Vec<Uuid> = all.iter().map(|n| n.id).collect();
+        let mut expected = ids.clone();
+        expected.sort_by_key(|id| std::cmp::Reverse(id.to_string()));
+        assert_eq!(ids, expected);
+        assert_eq!(repo.get_project_notes(&project.id).unwrap().iter().map(|n| n.id).collect::<Vec<_>>(), ids);
+
+        // Paging visits every note exactly once, in the same order
+        let mut paged = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = repo.get_project_notes_page(&project.id, cursor, 2).unwrap();
+            paged.extend(page.iter().map(|n| n.id));
+            match page.last() {
+                Some(last) if page.len() == 2 => cursor = Some(NoteCursor::new(last.created_at, last.id)),
+                _ => break,
+            }
+        }
+        assert_eq!(paged, ids);
+
+        // A cursor survives being passed around as a string
+        let cursor: NoteCursor = NoteCursor::new(all[2].created_at, all[2].id).to_string().parse().unwrap();
+        let page = repo.get_project_notes_page(&project.id, Some(cursor), 10).unwrap();
+        assert_eq!(page.iter().map(|n| n.id).collect::<Vec<_>>(), ids[3..]);
+    }
+
+    #[test]
+    fn test_milestone_and_stakeholder_notes_page() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        crate::db::PersonRepository::new(&conn)
+            .create(&Person::new("alice@example.com".to_string(), "Alice".to_string()))
+            .unwrap();
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+        let milestone = Milestone::new(project.id, 1, "Milestone 1".to_string());
+        repo.add_milestone(&milestone).unwrap();
+        repo.add_stakeholder(&project.id, &ProjectStakeholder::new(project.id, "alice@example.com".to_string()))
+            .unwrap();
+
+        let created_at = Utc::now();
+        for i in 0..3 {
+            let mut note = MilestoneNote::new(milestone.id, format!("Note {}", i), String::new());
+            note.created_at = created_at;
+            repo.add_milestone_note(&note).unwrap();
+            let mut note = StakeholderNote::new(project.id, "alice@example.com".to_string(), format!("Note {}", i), String::new());
+            note.created_at = created_at;
+            repo.add_stakeholder_note(&note).unwrap();
+        }
+
+        let all = repo.get_milestone_notes(&milestone.id).unwrap();
+        let first = repo.get_milestone_notes_page(&milestone.id, None, 2).unwrap();
+        let cursor = NoteCursor::new(first[1].created_at, first[1].id);
+        let rest = repo.get_milestone_notes_page(&milestone.id, Some(cursor), 2).unwrap();
+        assert_eq!(first.iter().chain(&rest).map(|n| n.id).collect::<Vec<_>>(), all.iter().map(|n| n.id).collect::<Vec<_>>());
+        assert_eq!(rest.len(), 1);
+
+        let all = repo.get_stakeholder_notes(&project.id, "alice@example.com").unwrap();
+        let first = repo.get_stakeholder_notes_page(&project.id, "alice@example.com", None, 2).unwrap();
+        let cursor = NoteCursor::new(first[1].created_at, first[1].id);
+        let rest = repo.get_stakeholder_notes_page(&project.id, "alice@example.com", Some(cursor), 2).unwrap();
+        assert_eq!(first.iter().chain(&rest).map(|n| n.id).collect::<Vec<_>>(), all.iter().map(|n| n.id).collect::<Vec<_>>());
+        assert_eq!(rest.len(), 1);
+    }
+
+    #[test]
+    fn test_date_history() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let start = Utc::now();
+
+        let mut project = Project::new("Test Project".to_string());
+        project.due_date = Some(start + Duration::days(30));
+        repo.create(&project).unwrap();
+        let mut milestone = Milestone::new(project.id, 1, "Launch".to_string());
+        milestone.due_date = Some(start + Duration::days(10));
+        repo.add_milestone(&milestone).unwrap();
+
+        // Updates that don't touch dates don't write history
+        project.description = Some("Updated".to_string());
+        repo.update(&project).unwrap();
+        project.version += 1;
+        milestone.name = "Public launch".to_string();
+        repo.update_milestone(&milestone).unwrap();
+        milestone.version += 1;
+        assert!(repo.get_date_history(&project.id).unwrap().is_empty());
+        assert!(repo.get_date_history(&milestone.id).unwrap().is_empty());
+
+        milestone.start_date = Some(start);
+        milestone.due_date = Some(start + Duration::days(17));
+        repo.update_milestone(&milestone).unwrap();
+        let history = repo.get_date_history(&milestone.id).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].entity_type, "milestone");
+        let due_change = history.iter().find(|c| c.field == "due_date").unwrap();
+        assert_eq!(due_change.old_value, Some(start + Duration::days(10)));
+        assert_eq!(due_change.new_value, milestone.due_date);
+        let start_change = history.iter().find(|c| c.field == "start_date").unwrap();
+        assert!(start_change.old_value.is_none());
+
+        project.due_date = None;
+        repo.update(&project).unwrap();
+        let history = repo.get_date_history(&project.id).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].field, "due_date");
+        assert!(history[0].new_value.is_none());
+
+        // A failed update records nothing
+        let missing = Milestone::new(project.id, 2, "Missing".to_string());
+        assert!(repo.update_milestone(&missing).is_err());
+        assert!(repo.get_date_history(&missing.id).unwrap().is_empty());
     }
 
-    /// Delete stakeholder note
-    pub fn delete_stakeholder_note(&self, id: &Uuid) -> Result<()> {
-        let rows = self.conn.execute("DELETE FROM stakeholder_notes WHERE id = ?1", params![id.to_string()])?;
+    #[test]
+    fn test_milestone_date_validation() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let start = Utc::now();
+        let mut project = Project::new("Apollo".to_string());
+        project.due_date = Some(start + Duration::days(30));
+        repo.create(&project).unwrap();
 
-        if rows == 0 {
-            anyhow::bail!("Note not found: {}", id);
-        }
+        // A milestone can't start after it is due
+        let mut milestone = Milestone::new(project.id, 1, "Launch".to_string());
+        milestone.start_date = Some(start + Duration::days(5));
+        milestone.due_date = Some(start);
+        assert!(matches!(repo.add_milestone(&milestone).unwrap_err(), Error::Invalid(_)));
+        assert!(repo.find_milestone_by_id(&milestone.id).unwrap().is_none());
 
-        Ok(())
+        milestone.due_date = Some(start + Duration::days(10));
+        repo.add_milestone(&milestone).unwrap();
+        assert!(repo.milestone_date_warnings(&milestone).unwrap().is_empty());
+
+        milestone.start_date = Some(start + Duration::days(11));
+        assert!(matches!(repo.update_milestone(&milestone).unwrap_err(), Error::Invalid(_)));
+        assert_eq!(repo.find_milestone_by_id(&milestone.id).unwrap().unwrap().version, 1);
+
+        // Being due after the project is only a warning
+        milestone.start_date = Some(start);
+        milestone.due_date = Some(start + Duration::days(45));
+        repo.update_milestone(&milestone).unwrap();
+        let warnings = repo.milestone_date_warnings(&milestone).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("after the project's due date"));
+
+        let mut other = Milestone::new(project.id, 2, "Review".to_string());
+        other.due_date = Some(start + Duration::days(20));
+        repo.add_milestone(&other).unwrap();
+        repo.add_milestone(&Milestone::new(project.id, 3, "Undated".to_string())).unwrap();
+        assert_eq!(repo.suggest_project_due_date(&project.id).unwrap(), milestone.due_date);
+
+        let empty = Project::new("Gemini".to_string());
+        repo.create(&empty).unwrap();
+        assert!(repo.suggest_project_due_date(&empty.id).unwrap().is_none());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::db;
+    #[test]
+    fn test_activity_feed() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let mut project = Project::new("Apollo".to_string());
+        repo.create(&project).unwrap();
+        db::PersonRepository::new(&conn)
+            .create(&Person::new("alice@example.com".to_string(), "Alice".to_string()))
+            .unwrap();
 
-    fn setup_test_db() -> Connection {
-        let conn = Connection::open_in_memory().unwrap();
-        db::schema::initialize_schema(&conn).unwrap();
-        db::schema::apply_migrations(&conn).unwrap();
-        conn
+        let start = Utc::now() - Duration::days(10);
+        let mut note = ProjectNote::new(project.id, "Kickoff".to_string(), "A long body".to_string());
+        note.created_at = start;
+        repo.add_project_note(&note).unwrap();
+        let mut milestone = Milestone::new(project.id, 1, "Design".to_string());
+        milestone.created_at = start + Duration::days(1);
+        milestone.due_date = Some(start + Duration::days(5));
+        repo.add_milestone(&milestone).unwrap();
+        let resource = ProjectResource {
+            project_id: project.id,
+            person_email: "alice@example.com".to_string(),
+            role: Some("Developer".to_string()),
+            created_at: start + Duration::days(2),
+        };
+        repo.add_project_resource(&project.id, &resource).unwrap();
+        project.due_date = Some(start + Duration::days(30));
+        repo.update(&project).unwrap();
+        project.version += 1;
+        repo.set_blocked(&project.id, "Waiting on vendor").unwrap();
+
+        let feed = repo.get_activity_feed(&project.id, 50).unwrap();
+        let kinds: Vec<ActivityKind> = feed.iter().map(|item| item.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ActivityKind::Blocked,
+                ActivityKind::DateChanged,
+                ActivityKind::MilestoneCompleted,
+                ActivityKind::ResourceAdded,
+                ActivityKind::MilestoneCreated,
+                ActivityKind::NoteAdded,
+            ]
+        );
+        assert_eq!(feed[3].summary, "alice@example.com added as Developer");
+        assert_eq!(feed[3].actor.as_deref(), Some("alice@example.com"));
+        assert_eq!(feed[5].summary, "Note added: Kickoff");
+
+        let feed = repo.get_activity_feed(&project.id, 2).unwrap();
+        assert_eq!(feed.len(), 2);
+        assert_eq!(feed[0].kind, ActivityKind::Blocked);
+
+        // Databases without the optional history tables still get a feed
+        conn.execute_batch("DROP TABLE blocker_history; DROP TABLE date_history;").unwrap();
+        let feed = repo.get_activity_feed(&project.id, 50).unwrap();
+        assert_eq!(feed.len(), 4);
+        assert_eq!(feed[0].kind, ActivityKind::MilestoneCompleted);
     }
 
-    // Project CRUD tests
+    fn board_column(repo: &ProjectRepository, status: ProjectStatus) -> Vec<(String, Option<i64>)> {
+        repo.get_board()
+            .unwrap()
+            .into_iter()
+            .find(|c| c.status == status)
+            .unwrap()
+            .projects
+            .into_iter()
+            .map(|p| (p.name, p.board_position))
+            .collect()
+    }
+
+    fn board_names(repo: &ProjectRepository, status: ProjectStatus) -> Vec<String> {
+        board_column(repo, status).into_iter().map(|(name, _)| name).collect()
+    }
 
     #[test]
-    fn test_create_and_find_project() {
+    fn test_board_reorder_within_column() {
         let conn = setup_test_db();
         let repo = ProjectRepository::new(&conn);
-        let project = Project::new("Test Project".to_string());
+        let mut ids = HashMap::new();
+        for name in ["A", "B", "C", "D"] {
+            let project = Project::new(name.to_string());
+            repo.create(&project).unwrap();
+            ids.insert(name, project.id);
+        }
 
-        repo.create(&project).unwrap();
+        // Every status has a column, and unplaced projects are ordered by name
+        let board = repo.get_board().unwrap();
+        assert_eq!(
+            board.iter().map(|c| c.status).collect::<Vec<_>>(),
+            ProjectStatus::ALL
+        );
+        assert_eq!(board_names(&repo, ProjectStatus::Active), ["A", "B", "C", "D"]);
+
+        let moved = repo.reorder_project(&ids["D"], ProjectStatus::Active, 0).unwrap();
+        assert_eq!(moved.board_position, Some(BOARD_GAP));
+        assert_eq!(moved.version, 1);
+        repo.reorder_project(&ids["C"], ProjectStatus::Active, 1).unwrap();
+        assert_eq!(board_names(&repo, ProjectStatus::Active), ["D", "C", "A", "B"]);
+
+        // Moving between two placed projects only numbers the moved one
+        repo.reorder_project(&ids["B"], ProjectStatus::Active, 1).unwrap();
+        assert_eq!(
+            board_column(&repo, ProjectStatus::Active),
+            [
+                ("D".to_string(), Some(BOARD_GAP)),
+                ("B".to_string(), Some(BOARD_GAP + BOARD_GAP / 2)),
+                ("C".to_string(), Some(2 * BOARD_GAP)),
+                ("A".to_string(), None),
+            ]
+        );
 
-        let found = repo.find_by_id(&project.id).unwrap().unwrap();
-        assert_eq!(found.name, "Test Project");
-        assert_eq!(found.id, project.id);
+        // Positions past the end put the project last
+        repo.reorder_project(&ids["D"], ProjectStatus::Active, 99).unwrap();
+        assert_eq!(board_names(&repo, ProjectStatus::Active), ["B", "C", "A", "D"]);
+        assert_eq!(
+            board_column(&repo, ProjectStatus::Active),
+            [
+                ("B".to_string(), Some(BOARD_GAP)),
+                ("C".to_string(), Some(2 * BOARD_GAP)),
+                ("A".to_string(), Some(3 * BOARD_GAP)),
+                ("D".to_string(), Some(4 * BOARD_GAP)),
+            ]
+        );
+
+        let missing = repo.reorder_project(&Uuid::new_v4(), ProjectStatus::Active, 0);
+        assert!(matches!(missing, Err(Error::NotFound { .. })));
     }
 
     #[test]
-    fn test_find_nonexistent_project() {
+    fn test_board_reorder_across_columns() {
         let conn = setup_test_db();
         let repo = ProjectRepository::new(&conn);
-        let fake_id = Uuid::new_v4();
-
-        let result = repo.find_by_id(&fake_id).unwrap();
-        assert!(result.is_none());
+        let apollo = Project::new("Apollo".to_string());
+        let gemini = Project::new("Gemini".to_string());
+        repo.create(&apollo).unwrap();
+        repo.create(&gemini).unwrap();
+
+        let moved = repo.reorder_project(&apollo.id, ProjectStatus::Done, 0).unwrap();
+        assert_eq!(moved.status, ProjectStatus::Done);
+        assert_eq!(moved.version, 2);
+        assert_eq!(board_names(&repo, ProjectStatus::Active), ["Gemini"]);
+        assert_eq!(board_names(&repo, ProjectStatus::Done), ["Apollo"]);
+
+        repo.reorder_project(&gemini.id, ProjectStatus::Done, 0).unwrap();
+        assert_eq!(board_names(&repo, ProjectStatus::Done), ["Gemini", "Apollo"]);
+        assert!(board_names(&repo, ProjectStatus::Active).is_empty());
+
+        // Changing the status with an update sends the project to the end of its new column
+        let mut gemini = repo.find_by_id(&gemini.id).unwrap().unwrap();
+        gemini.status = ProjectStatus::OnHold;
+        repo.update(&gemini).unwrap();
+        assert_eq!(
+            board_column(&repo, ProjectStatus::OnHold),
+            [("Gemini".to_string(), None)]
+        );
+        // Other updates keep its place
+        let mut apollo = repo.find_by_id(&apollo.id).unwrap().unwrap();
+        apollo.description = Some("Moon".to_string());
+        repo.update(&apollo).unwrap();
+        assert_eq!(
+            repo.find_by_id(&apollo.id).unwrap().unwrap().board_position,
+            apollo.board_position
+        );
     }
 
     #[test]
-    fn test_list_all_projects() {
+    fn test_board_renumbers_when_out_of_room() {
         let conn = setup_test_db();
         let repo = ProjectRepository::new(&conn);
+        let mut ids = Vec::new();
+        for (name, position) in [("A", Some(1)), ("B", Some(2)), ("C", None)] {
+            let mut project = Project::new(name.to_string());
+            project.board_position = position;
+            repo.create(&project).unwrap();
+            ids.push(project.id);
+        }
 
-        let project1 = Project::new("Alpha Project".to_string());
-        let project2 = Project::new("Beta Project".to_string());
-        repo.create(&project1).unwrap();
-        repo.create(&project2).unwrap();
+        repo.reorder_project(&ids[2], ProjectStatus::Active, 1).unwrap();
+        assert_eq!(
+            board_column(&repo, ProjectStatus::Active),
+            [
+                ("A".to_string(), Some(BOARD_GAP)),
+                ("C".to_string(), Some(2 * BOARD_GAP)),
+                ("B".to_string(), Some(3 * BOARD_GAP)),
+            ]
+        );
+    }
 
-        let projects = repo.list_all().unwrap();
-        assert_eq!(projects.len(), 2);
-        // Should be sorted by name
-        assert_eq!(projects[0].name, "Alpha Project");
-        assert_eq!(projects[1].name, "Beta Project");
+    #[test]
+    fn test_concurrent_board_reorders() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("board.db");
+        let conn = db::open_database(&path).unwrap();
+        let ids: Vec<Uuid> = (0..6)
+            .map(|i| {
+                let project = Project::new(format!("Project {}", i));
+                ProjectRepository::new(&conn).create(&project).unwrap();
+                project.id
+            })
+            .collect();
+
+        let workers: Vec<_> = (0..3)
+            .map(|worker| {
+                let path = path.clone();
+                let ids = ids.clone();
+                std::thread::spawn(move || {
+                    let conn = db::open_database(&path).unwrap();
+                    let repo = ProjectRepository::new(&conn);
+                    for i in 0..30 {
+                        let id = &ids[(i * 5 + worker) % ids.len()];
+                        let status = ProjectStatus::ALL[(i + worker) % 2];
+                        repo.reorder_project(id, status, (i * 7) % 4).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        // Every project is in exactly one column, and no two share a position
+        let repo = ProjectRepository::new(&conn);
+        let mut seen = Vec::new();
+        for column in repo.get_board().unwrap() {
+            let positions: Vec<_> = column.projects.iter().map(|p| p.board_position).collect();
+            let mut sorted = positions.clone();
+            sorted.sort();
+            sorted.dedup();
+            assert_eq!(positions, sorted, "{:?}", column.status);
+            assert!(positions.iter().all(Option::is_some), "{:?}", column.status);
+            seen.extend(column.projects.into_iter().map(|p| p.id));
+        }
+        seen.sort();
+        let mut expected = ids.clone();
+        expected.sort();
+        assert_eq!(seen, expected);
     }
 
     #[test]
-    fn test_update_project() {
+    fn test_blocker_history() {
         let conn = setup_test_db();
         let repo = ProjectRepository::new(&conn);
-        let mut project = Project::new("Original Name".to_string());
+        let project = Project::new("Apollo".to_string());
         repo.create(&project).unwrap();
-
-        project.name = "Updated Name".to_string();
-        project.description = Some("New description".to_string());
+        repo.create(&Project::new("Gemini".to_string())).unwrap();
+        assert!(repo.list_blocked().unwrap().is_empty());
+
+        let blocked = repo.set_blocked(&project.id, "Waiting on vendor").unwrap();
+        assert!(blocked.blocked);
+        assert_eq!(blocked.blocked_reason.as_deref(), Some("Waiting on vendor"));
+        assert_eq!(blocked.version, project.version + 1);
+        let listed = repo.list_blocked().unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, project.id);
+
+        // Blocking again for the same reason changes nothing; a new reason starts a new entry
+        repo.set_blocked(&project.id, "Waiting on vendor").unwrap();
+        assert_eq!(repo.get_blocker_history(&project.id).unwrap().len(), 1);
+        repo.set_blocked(&project.id, "Security review").unwrap();
+        let history = repo.get_blocker_history(&project.id).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].reason.as_deref(), Some("Waiting on vendor"));
+        assert!(history[0].cleared_at.is_some());
+        assert!(history[1].cleared_at.is_none());
+
+        let cleared = repo.clear_blocked(&project.id).unwrap();
+        assert!(!cleared.blocked);
+        assert!(cleared.blocked_reason.is_none());
+        assert!(repo.list_blocked().unwrap().is_empty());
+        let history = repo.get_blocker_history(&project.id).unwrap();
+        assert!(history.iter().all(|entry| entry.cleared_at.is_some()));
+
+        // Updates go through the same history
+        let mut project = cleared;
+        project.blocked = true;
+        project.blocked_reason = Some("Budget freeze".to_string());
         repo.update(&project).unwrap();
+        let history = repo.get_blocker_history(&project.id).unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[2].reason.as_deref(), Some("Budget freeze"));
+
+        assert!(matches!(
+            repo.set_blocked(&Uuid::new_v4(), "Missing").unwrap_err(),
+            Error::NotFound { entity: "Project", .. }
+        ));
+    }
+
+    #[test]
+    fn test_phase_history() {
+        let conn = setup_test_db();
+        let phases: Vec<String> = ["Discovery", "Design", "Build"].iter().map(|p| p.to_string()).collect();
+        let repo = ProjectRepository::new(&conn).with_phases(&phases);
 
+        let mut project = Project::new("Apollo".to_string());
+        project.phase = Some("discovery".to_string());
+        repo.create(&project).unwrap();
+        repo.create(&Project::new("Gemini".to_string())).unwrap();
         let found = repo.find_by_id(&project.id).unwrap().unwrap();
-        assert_eq!(found.name, "Updated Name");
-        assert_eq!(found.description, Some("New description".to_string()));
+        assert_eq!(found.phase.as_deref(), Some("Discovery"));
+
+        let built = repo.set_phase(&project.id, "Build").unwrap();
+        assert_eq!(built.phase.as_deref(), Some("Build"));
+        assert_eq!(built.version, project.version + 1);
+        repo.set_phase(&project.id, "build").unwrap();
+        match repo.set_phase(&project.id, "Launch").unwrap_err() {
+            Error::Invalid(message) => assert!(message.contains("Discovery, Design, Build"), "{}", message),
+            other => panic!("unexpected error: {}", other),
+        }
+
+        // Going back is allowed but flagged, and updates are recorded too
+        let mut project = repo.set_phase(&project.id, "Design").unwrap();
+        project.phase = Some("Build".to_string());
+        repo.update(&project).unwrap();
+        let history = repo.get_phase_history(&project.id).unwrap();
+        let moves: Vec<_> = history
+            .iter()
+            .map(|c| (c.from_phase.as_deref(), c.to_phase.as_deref(), c.backwards))
+            .collect();
+        assert_eq!(
+            moves,
+            [
+                (None, Some("Discovery"), false),
+                (Some("Discovery"), Some("Build"), false),
+                (Some("Build"), Some("Design"), true),
+                (Some("Design"), Some("Build"), false),
+            ]
+        );
+
+        let in_build = repo.list_by_phase("BUILD").unwrap();
+        assert_eq!(in_build.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), ["Apollo"]);
+        let durations = repo.get_phase_durations(&project.id).unwrap();
+        assert_eq!(durations.iter().map(|d| d.phase.as_str()).collect::<Vec<_>>(), ["Discovery", "Build", "Design"]);
+        assert!(durations.iter().all(|d| d.current == (d.phase == "Build")));
+        let summary = repo.get_project_summary(&project.id).unwrap().unwrap();
+        assert_eq!(summary.phase.as_deref(), Some("Build"));
+        assert_eq!(summary.phase_durations, durations);
+
+        assert!(matches!(
+            repo.set_phase(&Uuid::new_v4(), "Build").unwrap_err(),
+            Error::NotFound { entity: "Project", .. }
+        ));
     }
 
     #[test]
-    fn test_update_nonexistent_project() {
+    fn test_phase_durations() {
+        use chrono::TimeZone;
+        let project_id = Uuid::new_v4();
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let change = |days: i64, to: Option<&str>| PhaseChange {
+            project_id,
+            from_phase: None,
+            to_phase: to.map(str::to_string),
+            backwards: false,
+            changed_at: start + Duration::days(days),
+        };
+        let history = [
+            change(0, Some("Design")),
+            change(10, Some("Build")),
+            change(40, Some("Design")),
+            change(45, Some("Build")),
+            change(50, None),
+        ];
+
+        let durations = phase_durations(&history, start + Duration::days(100));
+        assert_eq!(
+            durations,
+            [
+                PhaseDuration { phase: "Design".to_string(), days: 15, current: false },
+                PhaseDuration { phase: "Build".to_string(), days: 35, current: false },
+            ]
+        );
+        let current = phase_durations(&history[..4], start + Duration::days(60));
+        assert_eq!(current[1], PhaseDuration { phase: "Build".to_string(), days: 45, current: true });
+    }
+
+    #[test]
+    fn test_slippage_report() {
         let conn = setup_test_db();
         let repo = ProjectRepository::new(&conn);
+        let start = Utc::now();
+
         let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+        let mut launch = Milestone::new(project.id, 1, "Launch".to_string());
+        launch.due_date = Some(start + Duration::days(10));
+        repo.add_milestone(&launch).unwrap();
+        let beta = Milestone::new(project.id, 2, "Beta".to_string());
+        repo.add_milestone(&beta).unwrap();
+
+        // Two slips of 7 and 3 days, and a pull-in that isn't a slip
+        for days in [17, 20, 15] {
+            launch.due_date = Some(start + Duration::days(days));
+            repo.update_milestone(&launch).unwrap();
+            launch.version += 1;
+        }
 
-        let result = repo.update(&project);
-        assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("not found"));
+        let report = repo.get_slippage_report(&project.id).unwrap();
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].milestone_id, launch.id);
+        assert_eq!(report[0].slip_count, 2);
+        assert_eq!(report[0].total_slip_days, 10);
+        assert_eq!(report[0].due_date, launch.due_date);
+        assert_eq!(report[1].slip_count, 0);
+        assert_eq!(report[1].total_slip_days, 0);
+
+        assert!(matches!(
+            repo.get_slippage_report(&Uuid::new_v4()).unwrap_err(),
+            Error::NotFound { .. }
+        ));
     }
 
     #[test]
-    fn test_delete_project() {
+    fn test_effort_summary() {
         let conn = setup_test_db();
         let repo = ProjectRepository::new(&conn);
         let project = Project::new("Test Project".to_string());
         repo.create(&project).unwrap();
 
-        repo.delete(&project.id).unwrap();
-
-        let found = repo.find_by_id(&project.id).unwrap();
-        assert!(found.is_none());
+        let mut design = Milestone::new(project.id, 1, "Design".to_string());
+        design.estimated_days = Some(5.0);
+        design.actual_days = Some(4.0);
+        repo.add_milestone(&design).unwrap();
+        let mut build = Milestone::new(project.id, 2, "Build".to_string());
+        build.estimated_days = Some(10.0);
+        build.actual_days = Some(16.0);
+        repo.add_milestone(&build).unwrap();
+        let mut launch = Milestone::new(project.id, 3, "Launch".to_string());
+        launch.estimated_days = Some(2.0);
+        repo.add_milestone(&launch).unwrap();
+
+        let stored = repo.find_milestone_by_id(&build.id).unwrap().unwrap();
+        assert_eq!(stored.estimated_days, Some(10.0));
+        assert_eq!(stored.actual_days, Some(16.0));
+        assert!(stored.effort_warning(1.5).is_some());
+        assert!(stored.effort_warning(2.0).is_none());
+
+        let mut summary = repo.get_effort_summary(&project.id, 1.5).unwrap();
+        assert_eq!(summary.total_estimated_days, 17.0);
+        assert_eq!(summary.total_actual_days, 20.0);
+        assert_eq!(summary.variance_days, 5.0);
+        assert_eq!(summary.milestones[0].variance_days, Some(-1.0));
+        assert_eq!(summary.milestones[2].variance_days, None);
+        assert!(summary.milestones[1].over_estimate);
+        assert!(!summary.milestones[0].over_estimate);
+
+        summary.sort_by_variance();
+        let order: Vec<&str> = summary.milestones.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(order, vec!["Build", "Design", "Launch"]);
+
+        launch.actual_days = Some(-1.0);
+        assert!(matches!(repo.update_milestone(&launch).unwrap_err(), Error::Invalid(_)));
+        assert!(matches!(
+            repo.get_effort_summary(&Uuid::new_v4(), 1.5).unwrap_err(),
+            Error::NotFound { .. }
+        ));
     }
 
     #[test]
-    fn test_delete_nonexistent_project() {
+    fn test_quarter_plan() {
+        use chrono::TimeZone;
+
         let conn = setup_test_db();
         let repo = ProjectRepository::new(&conn);
-        let fake_id = Uuid::new_v4();
-
-        let result = repo.delete(&fake_id);
-        assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("not found"));
+        let tz = chrono_tz::America::New_York;
+        let due = |month: u32, day: u32| Some(tz.with_ymd_and_hms(2025, month, day, 12, 0, 0).unwrap().with_timezone(&Utc));
+
+        let mut apollo = Project::new("Apollo".to_string());
+        apollo.team = Some("Platform".to_string());
+        repo.create(&apollo).unwrap();
+        let gemini = Project::new("Gemini".to_string());
+        repo.create(&gemini).unwrap();
+
+        let mut launch = Milestone::new(apollo.id, 1, "Launch".to_string());
+        launch.due_date = due(8, 15);
+        repo.add_milestone(&launch).unwrap();
+        let mut review = Milestone::new(apollo.id, 2, "Review".to_string());
+        review.due_date = due(7, 1);
+        review.team = Some("Design".to_string());
+        repo.add_milestone(&review).unwrap();
+        let mut orbit = Milestone::new(gemini.id, 1, "Orbit".to_string());
+        orbit.due_date = due(9, 30);
+        repo.add_milestone(&orbit).unwrap();
+        let mut later = Milestone::new(gemini.id, 2, "Later".to_string());
+        later.due_date = due(10, 1);
+        repo.add_milestone(&later).unwrap();
+
+        // Late on September 30 in New York is already October in UTC
+        let mut edge = Milestone::new(gemini.id, 3, "Edge".to_string());
+        edge.due_date = Some(tz.with_ymd_and_hms(2025, 9, 30, 22, 0, 0).unwrap().with_timezone(&Utc));
+        repo.add_milestone(&edge).unwrap();
+
+        let plan = repo.get_quarter_plan(2025, 3, tz, None).unwrap();
+        assert_eq!(plan.milestone_count, 4);
+        let teams: Vec<_> = plan.teams.iter().map(|t| (t.team.as_deref(), t.milestone_count)).collect();
+        assert_eq!(teams, vec![(Some("Design"), 1), (Some("Platform"), 1), (None, 2)]);
+        assert_eq!(plan.teams[1].projects[0].project_name, "Apollo");
+        assert_eq!(plan.teams[1].projects[0].milestones[0].id, launch.id);
+        let names: Vec<_> = plan.teams[2].projects[0].milestones.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["Orbit", "Edge"]);
+
+        let plan = repo.get_quarter_plan(2025, 4, tz, None).unwrap();
+        assert_eq!(plan.milestone_count, 1);
+        assert_eq!(plan.teams[0].projects[0].milestones[0].id, later.id);
+
+        let plan = repo.get_quarter_plan(2025, 3, tz, Some("Platform")).unwrap();
+        assert_eq!(plan.milestone_count, 1);
+        assert_eq!(plan.teams[0].team.as_deref(), Some("Platform"));
+
+        assert!(matches!(repo.get_quarter_plan(2025, 5, tz, None).unwrap_err(), Error::Invalid(_)));
     }
 
-    // Milestone tests
-
     #[test]
-    fn test_add_milestone() {
+    fn test_add_notes_batch() {
         let conn = setup_test_db();
+        let person_repo = crate::db::PersonRepository::new(&conn);
         let repo = ProjectRepository::new(&conn);
+
+        person_repo
+            .create(&crate::db::Person::new("alice@example.com".to_string(), "Alice".to_string()))
+            .unwrap();
         let project = Project::new("Test Project".to_string());
         repo.create(&project).unwrap();
-
-        let milestone = Milestone::new(project.id, 1, "Milestone 1".to_string());
+        let milestone = Milestone::new(project.id, 1, "Launch".to_string());
         repo.add_milestone(&milestone).unwrap();
-
-        let milestones = repo.get_milestones(&project.id).unwrap();
-        assert_eq!(milestones.len(), 1);
-        assert_eq!(milestones[0].name, "Milestone 1");
-        assert_eq!(milestones[0].number, 1);
+        repo.add_stakeholder(&project.id, &ProjectStakeholder::new(project.id, "alice@example.com".to_string()))
+            .unwrap();
+
+        let note = |target, title: &str| NewNote {
+            target,
+            title: title.to_string(),
+            body: "Minutes".to_string(),
+        };
+        let ids = repo
+            .add_notes_batch(&[
+                note(NoteTarget::Project { project_id: project.id }, "Decisions"),
+                note(NoteTarget::Milestone { milestone_id: milestone.id }, "Launch plan"),
+                note(
+                    NoteTarget::Stakeholder {
+                        project_id: project.id,
+                        stakeholder_email: "alice@example.com".to_string(),
+                    },
+                    "Alice's concerns",
+                ),
+            ])
+            .unwrap();
+        assert_eq!(ids.len(), 3);
+        assert_eq!(repo.find_project_note_by_id(&ids[0]).unwrap().unwrap().title, "Decisions");
+        assert_eq!(repo.find_milestone_note_by_id(&ids[1]).unwrap().unwrap().title, "Launch plan");
+        assert_eq!(repo.find_stakeholder_note_by_id(&ids[2]).unwrap().unwrap().title, "Alice's concerns");
+
+        // One bad target rolls back the whole batch
+        let result = repo.add_notes_batch(&[
+            note(NoteTarget::Project { project_id: project.id }, "Kept?"),
+            note(NoteTarget::Milestone { milestone_id: Uuid::new_v4() }, "Orphan"),
+        ]);
+        assert!(matches!(result.unwrap_err(), Error::ForeignKeyViolation { .. }));
+        assert_eq!(repo.get_project_notes(&project.id).unwrap().len(), 1);
     }
 
     #[test]
-    fn test_get_milestones_empty() {
+    fn test_move_note() {
         let conn = setup_test_db();
         let repo = ProjectRepository::new(&conn);
+
         let project = Project::new("Test Project".to_string());
         repo.create(&project).unwrap();
+        let launch = Milestone::new(project.id, 1, "Launch".to_string());
+        repo.add_milestone(&launch).unwrap();
+        let beta = Milestone::new(project.id, 2, "Beta".to_string());
+        repo.add_milestone(&beta).unwrap();
+
+        let mut note = ProjectNote::new(project.id, "Minutes".to_string(), "Body".to_string());
+        note.created_at = Utc::now() - Duration::days(3);
+        note.updated_at = Utc::now() - Duration::days(2);
+        repo.add_project_note(&note).unwrap();
 
-        let milestones = repo.get_milestones(&project.id).unwrap();
-        assert_eq!(milestones.len(), 0);
+        // Project to milestone keeps the ID and timestamps
+        repo.move_note(&note.id, &NoteTarget::Milestone { milestone_id: launch.id }).unwrap();
+        assert!(repo.find_project_note_by_id(&note.id).unwrap().is_none());
+        let moved = repo.find_milestone_note_by_id(&note.id).unwrap().unwrap();
+        assert_eq!(moved.milestone_id, launch.id);
+        assert_eq!(moved.created_at, note.created_at);
+        assert_eq!(moved.updated_at, note.updated_at);
+
+        // Milestone to milestone, then back to the project
+        repo.move_note(&note.id, &NoteTarget::Milestone { milestone_id: beta.id }).unwrap();
+        assert_eq!(repo.find_milestone_note_by_id(&note.id).unwrap().unwrap().milestone_id, beta.id);
+        repo.move_note(&note.id, &NoteTarget::Project { project_id: project.id }).unwrap();
+        let moved = repo.find_project_note_by_id(&note.id).unwrap().unwrap();
+        assert_eq!(moved.created_at, note.created_at);
+        assert!(repo.find_milestone_note_by_id(&note.id).unwrap().is_none());
     }
 
     #[test]
-    fn test_update_milestone() {
+    fn test_move_note_rejections() {
         let conn = setup_test_db();
         let repo = ProjectRepository::new(&conn);
+
         let project = Project::new("Test Project".to_string());
         repo.create(&project).unwrap();
+        let other = Project::new("Other Project".to_string());
+        repo.create(&other).unwrap();
+        let other_milestone = Milestone::new(other.id, 1, "Elsewhere".to_string());
+        repo.add_milestone(&other_milestone).unwrap();
 
-        let mut milestone = Milestone::new(project.id, 1, "Original Name".to_string());
-        repo.add_milestone(&milestone).unwrap();
+        let note = ProjectNote::new(project.id, "Minutes".to_string(), "Body".to_string());
+        repo.add_project_note(&note).unwrap();
 
-        milestone.name = "Updated Name".to_string();
-        milestone.description = Some("New description".to_string());
-        repo.update_milestone(&milestone).unwrap();
+        // Cross-project moves, to the project or one of its milestones
+        for target in [
+            NoteTarget::Project { project_id: other.id },
+            NoteTarget::Milestone { milestone_id: other_milestone.id },
+        ] {
+            assert!(matches!(repo.move_note(&note.id, &target).unwrap_err(), Error::Invalid(_)));
+        }
 
-        let milestones = repo.get_milestones(&project.id).unwrap();
-        assert_eq!(milestones[0].name, "Updated Name");
-        assert_eq!(milestones[0].description, Some("New description".to_string()));
+        let stakeholder = NoteTarget::Stakeholder {
+            project_id: project.id,
+            stakeholder_email: "alice@example.com".to_string(),
+        };
+        assert!(matches!(repo.move_note(&note.id, &stakeholder).unwrap_err(), Error::Invalid(_)));
+        assert!(matches!(
+            repo.move_note(&note.id, &NoteTarget::Milestone { milestone_id: Uuid::new_v4() }).unwrap_err(),
+            Error::NotFound { .. }
+        ));
+        assert!(matches!(
+            repo.move_note(&Uuid::new_v4(), &NoteTarget::Project { project_id: project.id }).unwrap_err(),
+            Error::NotFound { .. }
+        ));
+
+        // Milestone notes can't be logs, so log notes stay with the project
+        let milestone = Milestone::new(project.id, 1, "Kickoff".to_string());
+        repo.add_milestone(&milestone).unwrap();
+        let mut log = ProjectNote::new(project.id, "Decision log".to_string(), String::new());
+        log.kind = NoteKind::Log;
+        repo.add_project_note(&log).unwrap();
+        let target = NoteTarget::Milestone { milestone_id: milestone.id };
+        assert!(matches!(repo.move_note(&log.id, &target).unwrap_err(), Error::Invalid(_)));
+
+        // The note is still where it was
+        assert_eq!(repo.find_project_note_by_id(&note.id).unwrap().unwrap().project_id, project.id);
     }
 
     #[test]
-    fn test_update_nonexistent_milestone() {
+    fn test_note_slices_and_summary() {
         let conn = setup_test_db();
         let repo = ProjectRepository::new(&conn);
+
         let project = Project::new("Test Project".to_string());
         repo.create(&project).unwrap();
+        let launch = Milestone::new(project.id, 1, "Launch".to_string());
+        repo.add_milestone(&launch).unwrap();
+        let note = MilestoneNote::new(launch.id, "Minutes".to_string(), "Grüße aus Köln, zwei Wörter".to_string());
+        repo.add_milestone_note(&note).unwrap();
 
-        let milestone = Milestone::new(project.id, 1, "Test Milestone".to_string());
-        let result = repo.update_milestone(&milestone);
-        assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("not found"));
+        let whole = repo.get_note_by_id(NoteType::Milestone, &note.id).unwrap().unwrap();
+        assert_eq!(whole.body, note.body);
+        assert_eq!(whole.total_chars, 27);
+        assert_eq!(whole.next_offset, None);
+        assert!(repo.get_note_by_id(NoteType::Project, &note.id).unwrap().is_none());
+
+        // Offsets count characters, so multi-byte text is never split
+        let first = repo.get_note_slice(NoteType::Milestone, &note.id, 0, Some(5)).unwrap().unwrap();
+        assert_eq!(first.body, "Grüße");
+        assert_eq!(first.next_offset, Some(5));
+        let rest = repo
+            .get_note_slice(NoteType::Milestone, &note.id, first.next_offset.unwrap(), Some(100))
+            .unwrap()
+            .unwrap();
+        assert_eq!(rest.body, " aus Köln, zwei Wörter");
+        assert_eq!(rest.next_offset, None);
+        let past_end = repo.get_note_slice(NoteType::Milestone, &note.id, 50, Some(5)).unwrap().unwrap();
+        assert_eq!(past_end.body, "");
+        assert_eq!(past_end.next_offset, None);
+
+        let summary = repo.get_note_summary(NoteType::Milestone, &note.id, 9).unwrap().unwrap();
+        assert_eq!(summary.title, "Minutes");
+        assert_eq!(summary.excerpt, "Grüße aus");
+        assert_eq!(summary.total_chars, 27);
+        assert_eq!(summary.word_count, 5);
+        assert!(repo.get_note_summary(NoteType::Stakeholder, &note.id, 9).unwrap().is_none());
     }
 
     #[test]
-    fn test_delete_milestone() {
+    fn test_project_documents() {
         let conn = setup_test_db();
         let repo = ProjectRepository::new(&conn);
         let project = Project::new("Test Project".to_string());
         repo.create(&project).unwrap();
 
-        let milestone = Milestone::new(project.id, 1, "Milestone 1".to_string());
-        repo.add_milestone(&milestone).unwrap();
-
-        repo.delete_milestone(&milestone.id).unwrap();
-
-        let milestones = repo.get_milestones(&project.id).unwrap();
-        assert_eq!(milestones.len(), 0);
+        let mut document = ProjectDocument::new(
+            project.id,
+            "Storage design".to_string(),
+            "https://docs.example.com/storage".to_string(),
+        );
+        document.doc_type = Some("Design Doc".to_string());
+        repo.add_project_document(&document).unwrap();
+        let runbook = ProjectDocument::new(
+            project.id,
+            "Runbook".to_string(),
+            "http://wiki.internal/runbook".to_string(),
+        );
+        repo.add_project_document(&runbook).unwrap();
+
+        let documents = repo.get_project_documents(&project.id).unwrap();
+        assert_eq!(documents.len(), 2);
+        assert_eq!(documents[0].doc_type.as_deref(), Some("Design Doc"));
+
+        document.title = "Storage design v2".to_string();
+        document.url = "https://docs.example.com/storage-v2".to_string();
+        repo.update_project_document(&document).unwrap();
+        let found = repo.find_project_document_by_id(&document.id).unwrap().unwrap();
+        assert_eq!(found.title, "Storage design v2");
+        assert_eq!(found.url, "https://docs.example.com/storage-v2");
+
+        repo.delete_project_document(&runbook.id).unwrap();
+        assert_eq!(repo.get_project_documents(&project.id).unwrap().len(), 1);
+        assert!(matches!(
+            repo.delete_project_document(&runbook.id).unwrap_err(),
+            Error::NotFound { .. }
+        ));
+
+        // Deleting the project removes its documents
+        repo.delete(&project.id).unwrap();
+        assert!(repo.find_project_document_by_id(&document.id).unwrap().is_none());
     }
 
     #[test]
-    fn test_delete_nonexistent_milestone() {
+    fn test_project_document_url_validation() {
         let conn = setup_test_db();
         let repo = ProjectRepository::new(&conn);
-        let fake_id = Uuid::new_v4();
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
 
-        let result = repo.delete_milestone(&fake_id);
-        assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("not found"));
+        for url in ["ftp://files.example.com/spec.pdf", "javascript:alert(1)", "docs/spec.md"] {
+            let document = ProjectDocument::new(project.id, "Spec".to_string(), url.to_string());
+            assert!(matches!(repo.add_project_document(&document).unwrap_err(), Error::Invalid(_)));
+        }
+
+        let mut document = ProjectDocument::new(
+            project.id,
+            "Spec".to_string(),
+            "https://docs.example.com/spec".to_string(),
+        );
+        repo.add_project_document(&document).unwrap();
+        document.url = "file:///home/me/spec.md".to_string();
+        assert!(matches!(repo.update_project_document(&document).unwrap_err(), Error::Invalid(_)));
+
+        let orphan = ProjectDocument::new(
+            Uuid::new_v4(),
+            "Orphan".to_string(),
+            "https://docs.example.com/orphan".to_string(),
+        );
+        assert!(matches!(
+            repo.add_project_document(&orphan).unwrap_err(),
+            Error::ForeignKeyViolation { .. }
+        ));
     }
 
     #[test]
-    fn test_delete_project_cascades_to_milestones() {
+    fn test_project_budget_and_expenses() {
         let conn = setup_test_db();
         let repo = ProjectRepository::new(&conn);
-        let project = Project::new("Test Project".to_string());
+        let mut project = Project::new("Test Project".to_string());
         repo.create(&project).unwrap();
 
-        let milestone = Milestone::new(project.id, 1, "Milestone 1".to_string());
-        repo.add_milestone(&milestone).unwrap();
+        // No budget yet: spending is still totalled, but nothing remains
+        let status = repo.get_budget_status(&project.id).unwrap();
+        assert_eq!(status.budget_amount, None);
+        assert_eq!(status.total_spent, 0.0);
+        assert_eq!(status.remaining, None);
 
-        repo.delete(&project.id).unwrap();
+        project.budget_amount = Some(1000.0);
+        project.budget_currency = Some("eur".to_string());
+        repo.update(&project).unwrap();
 
-        // Milestones should be deleted via cascade
-        let milestones = repo.get_milestones(&project.id).unwrap();
-        assert_eq!(milestones.len(), 0);
+        let now = Utc::now();
+        let licenses = ProjectExpense::new(project.id, "Licenses".to_string(), 250.0, now - Duration::days(2));
+        repo.add_expense(&licenses).unwrap();
+        let mut travel = ProjectExpense::new(project.id, "Travel".to_string(), 100.0, now);
+        repo.add_expense(&travel).unwrap();
+
+        let expenses = repo.list_expenses(&project.id).unwrap();
+        assert_eq!(expenses.iter().map(|e| e.description.as_str()).collect::<Vec<_>>(), ["Licenses", "Travel"]);
+
+        travel.amount = 150.5;
+        repo.update_expense(&travel).unwrap();
+        assert_eq!(repo.find_expense_by_id(&travel.id).unwrap().unwrap().amount, 150.5);
+
+        let status = repo.get_budget_status(&project.id).unwrap();
+        assert_eq!(status.budget_amount, Some(1000.0));
+        assert_eq!(status.budget_currency.as_deref(), Some("eur"));
+        assert_eq!(status.total_spent, 400.5);
+        assert_eq!(status.remaining, Some(599.5));
+
+        repo.delete_expense(&licenses.id).unwrap();
+        assert!(matches!(repo.delete_expense(&licenses.id).unwrap_err(), Error::NotFound { .. }));
+        assert!(matches!(
+            repo.get_budget_status(&Uuid::new_v4()).unwrap_err(),
+            Error::NotFound { .. }
+        ));
+
+        // Deleting the project removes its expenses
+        repo.delete(&project.id).unwrap();
+        assert!(repo.find_expense_by_id(&travel.id).unwrap().is_none());
     }
 
-    // Stakeholder tests
-
     #[test]
-    fn test_add_stakeholder() {
+    fn test_project_budget_validation() {
         let conn = setup_test_db();
-        let person_repo = crate::db::PersonRepository::new(&conn);
         let repo = ProjectRepository::new(&conn);
 
-        let person = crate::db::Person::new("alice@example.com".to_string(), "Alice".to_string());
-        person_repo.create(&person).unwrap();
+        for (amount, currency) in [(Some(-1.0), None), (Some(f64::NAN), None), (None, Some("US")), (None, Some("US$1"))] {
+            let mut project = Project::new("Test Project".to_string());
+            project.budget_amount = amount;
+            project.budget_currency = currency.map(str::to_string);
+            assert!(matches!(repo.create(&project).unwrap_err(), Error::Invalid(_)), "{:?} {:?}", amount, currency);
+        }
 
         let project = Project::new("Test Project".to_string());
         repo.create(&project).unwrap();
-
-        let mut stakeholder = ProjectStakeholder::new(project.id, "alice@example.com".to_string());
-        stakeholder.role = Some("Lead".to_string());
-        repo.add_stakeholder(&project.id, &stakeholder).unwrap();
-
-        let stakeholders = repo.get_stakeholders(&project.id).unwrap();
-        assert_eq!(stakeholders.len(), 1);
-        assert_eq!(stakeholders[0].stakeholder_email, "alice@example.com");
-        assert_eq!(stakeholders[0].role, Some("Lead".to_string()));
+        let expense = ProjectExpense::new(project.id, "Refund".to_string(), -5.0, Utc::now());
+        assert!(matches!(repo.add_expense(&expense).unwrap_err(), Error::Invalid(_)));
+
+        let orphan = ProjectExpense::new(Uuid::new_v4(), "Orphan".to_string(), 5.0, Utc::now());
+        assert!(matches!(
+            repo.add_expense(&orphan).unwrap_err(),
+            Error::ForeignKeyViolation { .. }
+        ));
     }
 
+    // Project Snapshots tests
+
     #[test]
-    fn test_update_stakeholder() {
+    fn test_project_snapshots() {
+        use chrono::TimeZone;
         let conn = setup_test_db();
-        let person_repo = crate::db::PersonRepository::new(&conn);
         let repo = ProjectRepository::new(&conn);
+        let mut project = Project::new("Apollo".to_string());
+        project.due_date = Some(Utc.with_ymd_and_hms(2025, 9, 30, 0, 0, 0).unwrap());
+        repo.create(&project).unwrap();
+        let mut design = Milestone::new(project.id, 1, "Design".to_string());
+        repo.add_milestone(&design).unwrap();
+        let build = Milestone::new(project.id, 2, "Build".to_string());
+        repo.add_milestone(&build).unwrap();
+
+        let start = repo.create_snapshot(&project.id, " Q3 start ").unwrap();
+        assert_eq!(start.label, "Q3 start");
+        assert!(matches!(repo.create_snapshot(&project.id, " ").unwrap_err(), Error::Invalid(_)));
+        assert!(matches!(repo.create_snapshot(&Uuid::new_v4(), "x").unwrap_err(), Error::NotFound { .. }));
+
+        // Slip the project, rename a milestone, drop one and add another
+        let mut project = repo.find_by_id(&project.id).unwrap().unwrap();
+        project.due_date = Some(Utc.with_ymd_and_hms(2025, 10, 31, 0, 0, 0).unwrap());
+        project.description = Some("Moon landing".to_string());
+        repo.update(&project).unwrap();
+        design = repo.find_milestone_by_id(&design.id).unwrap().unwrap();
+        design.name = "Design review".to_string();
+        repo.update_milestone(&design).unwrap();
+        repo.delete_milestone(&build.id).unwrap();
+        let launch = Milestone::new(project.id, 3, "Launch".to_string());
+        repo.add_milestone(&launch).unwrap();
+
+        let now = repo.create_snapshot(&project.id, "Q3 end").unwrap();
+        assert_eq!(
+            repo.list_snapshots(&project.id).unwrap().iter().map(|s| s.label.as_str()).collect::<Vec<_>>(),
+            ["Q3 start", "Q3 end"]
+        );
 
-        let person = crate::db::Person::new("alice@example.com".to_string(), "Alice".to_string());
-        person_repo.create(&person).unwrap();
+        let diff = repo.compare_snapshots(&start.id, &now.id).unwrap();
+        assert_eq!(diff.from, start);
+        assert_eq!(
+            diff.project_changes,
+            vec![
+                FieldChange {
+                    field: "description".to_string(),
+                    old: serde_json::Value::Null,
+                    new: serde_json::json!("Moon landing"),
+                },
+                FieldChange {
+                    field: "due_date".to_string(),
+                    old: serde_json::json!("2025-09-30T00:00:00Z"),
+                    new: serde_json::json!("2025-10-31T00:00:00Z"),
+                },
+            ]
+        );
+        assert_eq!(diff.added_milestones.iter().map(|m| m.id).collect::<Vec<_>>(), [launch.id]);
+        assert_eq!(diff.removed_milestones.iter().map(|m| m.id).collect::<Vec<_>>(), [build.id]);
+        assert_eq!(diff.changed_milestones.len(), 1);
+        assert_eq!(diff.changed_milestones[0].name, "Design review");
+        assert_eq!(
+            diff.changed_milestones[0].changes,
+            vec![FieldChange {
+                field: "name".to_string(),
+                old: serde_json::json!("Design"),
+                new: serde_json::json!("Design review"),
+            }]
+        );
 
-        let project = Project::new("Test Project".to_string());
-        repo.create(&project).unwrap();
+        // The same comparison gives the same result, and a snapshot matches itself
+        assert_eq!(
+            serde_json::to_string(&repo.compare_snapshots(&start.id, &now.id).unwrap()).unwrap(),
+            serde_json::to_string(&diff).unwrap()
+        );
+        assert!(repo.compare_snapshots(&now.id, &now.id).unwrap().is_empty());
 
-        let mut stakeholder = ProjectStakeholder::new(project.id, "alice@example.com".to_string());
-        stakeholder.role = Some("Member".to_string());
-        repo.add_stakeholder(&project.id, &stakeholder).unwrap();
+        // Snapshots of different projects can't be compared
+        let other = Project::new("Gemini".to_string());
+        repo.create(&other).unwrap();
+        let other_snapshot = repo.create_snapshot(&other.id, "Q3 start").unwrap();
+        assert!(matches!(
+            repo.compare_snapshots(&start.id, &other_snapshot.id).unwrap_err(),
+            Error::Invalid(_)
+        ));
 
-        stakeholder.role = Some("Lead".to_string());
-        repo.update_stakeholder(&project.id, &stakeholder).unwrap();
+        repo.delete_snapshot(&start.id).unwrap();
+        assert!(matches!(repo.get_snapshot(&start.id).unwrap_err(), Error::NotFound { .. }));
 
-        let stakeholders = repo.get_stakeholders(&project.id).unwrap();
-        assert_eq!(stakeholders[0].role, Some("Lead".to_string()));
+        // Deleting the project removes its snapshots
+        repo.delete(&project.id).unwrap();
+        assert!(repo.list_snapshots(&project.id).unwrap().is_empty());
     }
 
+    // Project Risks tests
+
     #[test]
-    fn test_remove_stakeholder() {
+    fn test_project_risks() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let people = PersonRepository::new(&conn);
+        people.create(&Person::new("alice@example.com".to_string(), "Alice".to_string())).unwrap();
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+
+        let mut vendor = ProjectRisk::new(project.id, "Vendor delay".to_string(), RiskLevel::Medium, RiskLevel::Low);
+        vendor.owner_email = Some("alice@example.com".to_string());
+        repo.add_project_risk(&vendor).unwrap();
+        let staffing = ProjectRisk::new(project.id, "Staffing".to_string(), RiskLevel::High, RiskLevel::High);
+        repo.add_project_risk(&staffing).unwrap();
+
+        // Open risks come back highest score first
+        let open = repo.list_open_risks(&project.id).unwrap();
+        assert_eq!(open.iter().map(|r| r.title.as_str()).collect::<Vec<_>>(), vec!["Staffing", "Vendor delay"]);
+
+        vendor.status = RiskStatus::Mitigating;
+        vendor.mitigation = Some("Second supplier".to_string());
+        repo.update_project_risk(&vendor).unwrap();
+        let found = repo.find_project_risk_by_id(&vendor.id).unwrap().unwrap();
+        assert_eq!(found.status, RiskStatus::Mitigating);
+        assert_eq!(found.mitigation.as_deref(), Some("Second supplier"));
+        assert_eq!(found.owner_email.as_deref(), Some("alice@example.com"));
+
+        repo.close_project_risk(&staffing.id).unwrap();
+        let open = repo.list_open_risks(&project.id).unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].id, vendor.id);
+
+        // Closed risks stay in the register, after the open ones
+        let all = repo.get_project_risks(&project.id).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[1].status, RiskStatus::Closed);
+
+        assert!(matches!(repo.close_project_risk(&Uuid::new_v4()).unwrap_err(), Error::NotFound { .. }));
+        repo.delete_project_risk(&vendor.id).unwrap();
+        assert!(matches!(repo.delete_project_risk(&vendor.id).unwrap_err(), Error::NotFound { .. }));
+
+        // Deleting the project removes its risks
+        repo.delete(&project.id).unwrap();
+        assert!(repo.find_project_risk_by_id(&staffing.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_list_high_risks() {
         let conn = setup_test_db();
-        let person_repo = crate::db::PersonRepository::new(&conn);
         let repo = ProjectRepository::new(&conn);
+        let alpha = Project::new("Alpha".to_string());
+        repo.create(&alpha).unwrap();
+        let beta = Project::new("Beta".to_string());
+        repo.create(&beta).unwrap();
+
+        for (project, title, severity, likelihood) in [
+            (&alpha, "Medium", RiskLevel::Medium, RiskLevel::Medium),
+            (&alpha, "Likely outage", RiskLevel::High, RiskLevel::Medium),
+            (&beta, "Critical", RiskLevel::High, RiskLevel::High),
+        ] {
+            repo.add_project_risk(&ProjectRisk::new(project.id, title.to_string(), severity, likelihood)).unwrap();
+        }
+        let mut closed = ProjectRisk::new(beta.id, "Closed".to_string(), RiskLevel::High, RiskLevel::High);
+        closed.status = RiskStatus::Closed;
+        repo.add_project_risk(&closed).unwrap();
 
-        let person = crate::db::Person::new("alice@example.com".to_string(), "Alice".to_string());
-        person_repo.create(&person).unwrap();
+        let high = repo.list_high_risks().unwrap();
+        assert_eq!(high.iter().map(|r| r.title.as_str()).collect::<Vec<_>>(), vec!["Critical", "Likely outage"]);
+    }
 
+    #[test]
+    fn test_project_risk_validation() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
         let project = Project::new("Test Project".to_string());
         repo.create(&project).unwrap();
 
-        let stakeholder = ProjectStakeholder::new(project.id, "alice@example.com".to_string());
-        repo.add_stakeholder(&project.id, &stakeholder).unwrap();
+        let untitled = ProjectRisk::new(project.id, "  ".to_string(), RiskLevel::Low, RiskLevel::Low);
+        assert!(matches!(repo.add_project_risk(&untitled).unwrap_err(), Error::Invalid(_)));
 
-        repo.remove_stakeholder(&project.id, "alice@example.com").unwrap();
+        let mut unowned = ProjectRisk::new(project.id, "Unowned".to_string(), RiskLevel::Low, RiskLevel::Low);
+        unowned.owner_email = Some("nobody@example.com".to_string());
+        assert!(matches!(repo.add_project_risk(&unowned).unwrap_err(), Error::ForeignKeyViolation { .. }));
 
-        let stakeholders = repo.get_stakeholders(&project.id).unwrap();
-        assert_eq!(stakeholders.len(), 0);
+        // The table rejects levels the enums don't know about
+        assert!(conn
+            .execute(
+                "INSERT INTO project_risks (id, project_id, title, severity, likelihood, created_at, updated_at)
+                 VALUES (?1, ?2, 'Raw', 'severe', 'low', '2025-01-01T00:00:00Z', '2025-01-01T00:00:00Z')",
+                params![Uuid::new_v4().to_string(), project.id.to_string()],
+            )
+            .is_err());
     }
 
-    // Project Notes tests
+    // Action Items tests
 
     #[test]
-    fn test_add_project_note() {
+    fn test_action_items() {
         let conn = setup_test_db();
         let repo = ProjectRepository::new(&conn);
+        let people = PersonRepository::new(&conn);
+        people.create(&Person::new("alice@example.com".to_string(), "Alice".to_string())).unwrap();
+        people.create(&Person::new("bob@example.com".to_string(), "Bob".to_string())).unwrap();
         let project = Project::new("Test Project".to_string());
         repo.create(&project).unwrap();
+        let milestone = Milestone::new(project.id, 1, "Beta".to_string());
+        repo.add_milestone(&milestone).unwrap();
 
-        let note = ProjectNote::new(project.id, "Test Note".to_string(), "Note body".to_string());
-        repo.add_project_note(&note).unwrap();
-
-        let notes = repo.get_project_notes(&project.id).unwrap();
-        assert_eq!(notes.len(), 1);
-        assert_eq!(notes[0].title, "Test Note");
-        assert_eq!(notes[0].body, "Note body");
+        let mut later = ActionItem::new(project.id, "alice@example.com".to_string(), "Book venue".to_string());
+        later.due_date = Some(Utc::now() + Duration::days(7));
+        later.milestone_id = Some(milestone.id);
+        repo.create_action_item(&later).unwrap();
+        let mut sooner = ActionItem::new(project.id, "alice@example.com".to_string(), "Send agenda".to_string());
+        sooner.due_date = Some(Utc::now() + Duration::days(1));
+        repo.create_action_item(&sooner).unwrap();
+        let undated = ActionItem::new(project.id, "bob@example.com".to_string(), "Review budget".to_string());
+        repo.create_action_item(&undated).unwrap();
+
+        let mine = repo.list_open_action_items_for("alice@example.com").unwrap();
+        assert_eq!(mine.iter().map(|i| i.description.as_str()).collect::<Vec<_>>(), vec!["Send agenda", "Book venue"]);
+        assert_eq!(mine[1].milestone_id, Some(milestone.id));
+
+        // Completing stamps the status and time, and drops the item from open lists
+        let completed = repo.complete_action_item(&sooner.id).unwrap();
+        assert_eq!(completed.status, ActionItemStatus::Completed);
+        let completed_at = completed.completed_at.unwrap();
+        assert_eq!(repo.complete_action_item(&sooner.id).unwrap().completed_at, Some(completed_at));
+        assert_eq!(repo.list_open_action_items_for("alice@example.com").unwrap().len(), 1);
+
+        // Completed items come after open ones
+        let all = repo.list_action_items(&project.id).unwrap();
+        assert_eq!(all.iter().map(|i| i.id).collect::<Vec<_>>(), vec![later.id, undated.id, sooner.id]);
+
+        // Reopening clears the completion time
+        let mut reopened = repo.find_action_item_by_id(&sooner.id).unwrap().unwrap();
+        reopened.status = ActionItemStatus::Open;
+        reopened.assignee_email = "bob@example.com".to_string();
+        repo.update_action_item(&reopened).unwrap();
+        let found = repo.find_action_item_by_id(&sooner.id).unwrap().unwrap();
+        assert!(found.is_open());
+        assert!(found.completed_at.is_none());
+        assert_eq!(repo.list_open_action_items_for("bob@example.com").unwrap().len(), 2);
+
+        assert!(matches!(repo.complete_action_item(&Uuid::new_v4()).unwrap_err(), Error::NotFound { .. }));
+        repo.delete_action_item(&undated.id).unwrap();
+        assert!(matches!(repo.delete_action_item(&undated.id).unwrap_err(), Error::NotFound { .. }));
+
+        // Deleting the milestone keeps the item; deleting the project removes it
+        repo.delete_milestone(&milestone.id).unwrap();
+        assert!(repo.find_action_item_by_id(&later.id).unwrap().unwrap().milestone_id.is_none());
+        repo.delete(&project.id).unwrap();
+        assert!(repo.find_action_item_by_id(&later.id).unwrap().is_none());
     }
 
     #[test]
-    fn test_update_project_note() {
+    fn test_action_item_survives_source_note() {
         let conn = setup_test_db();
         let repo = ProjectRepository::new(&conn);
+        PersonRepository::new(&conn)
+            .create(&Person::new("alice@example.com".to_string(), "Alice".to_string()))
+            .unwrap();
         let project = Project::new("Test Project".to_string());
         repo.create(&project).unwrap();
-
-        let mut note = ProjectNote::new(project.id, "Original Title".to_string(), "Original body".to_string());
+        let note = ProjectNote::new(project.id, "Standup".to_string(), "Alice to send agenda".to_string());
         repo.add_project_note(&note).unwrap();
 
-        note.title = "Updated Title".to_string();
-        note.body = "Updated body".to_string();
-        repo.update_project_note(&note).unwrap();
+        let mut item = ActionItem::new(project.id, "alice@example.com".to_string(), "Send agenda".to_string());
+        item.source_note_id = Some(note.id);
+        repo.create_action_item(&item).unwrap();
 
-        let notes = repo.get_project_notes(&project.id).unwrap();
-        assert_eq!(notes[0].title, "Updated Title");
-        assert_eq!(notes[0].body, "Updated body");
+        repo.delete_project_note(&note.id).unwrap();
+        let found = repo.find_action_item_by_id(&item.id).unwrap().unwrap();
+        assert!(found.source_note_id.is_none());
+        assert_eq!(found.description, "Send agenda");
     }
 
     #[test]
-    fn test_delete_project_note() {
+    fn test_action_item_validation() {
         let conn = setup_test_db();
         let repo = ProjectRepository::new(&conn);
+        PersonRepository::new(&conn)
+            .create(&Person::new("alice@example.com".to_string(), "Alice".to_string()))
+            .unwrap();
         let project = Project::new("Test Project".to_string());
         repo.create(&project).unwrap();
-
-        let note = ProjectNote::new(project.id, "Test Note".to_string(), "Note body".to_string());
-        repo.add_project_note(&note).unwrap();
-
-        repo.delete_project_note(&note.id).unwrap();
-
-        let notes = repo.get_project_notes(&project.id).unwrap();
-        assert_eq!(notes.len(), 0);
+        let other = Project::new("Other Project".to_string());
+        repo.create(&other).unwrap();
+        let other_milestone = Milestone::new(other.id, 1, "Launch".to_string());
+        repo.add_milestone(&other_milestone).unwrap();
+
+        let blank = ActionItem::new(project.id, "alice@example.com".to_string(), " ".to_string());
+        assert!(matches!(repo.create_action_item(&blank).unwrap_err(), Error::Invalid(_)));
+
+        let unassigned = ActionItem::new(project.id, "nobody@example.com".to_string(), "Follow up".to_string());
+        assert!(matches!(
+            repo.create_action_item(&unassigned).unwrap_err(),
+            Error::ForeignKeyViolation { .. }
+        ));
+
+        let mut misplaced = ActionItem::new(project.id, "alice@example.com".to_string(), "Follow up".to_string());
+        misplaced.milestone_id = Some(other_milestone.id);
+        assert!(matches!(repo.create_action_item(&misplaced).unwrap_err(), Error::Invalid(_)));
+        misplaced.milestone_id = Some(Uuid::new_v4());
+        assert!(matches!(repo.create_action_item(&misplaced).unwrap_err(), Error::NotFound { .. }));
     }
 
     // Milestone Notes tests
@@ -1127,6 +6537,50 @@ mod tests {
         assert_eq!(notes[0].title, "Updated Title");
     }
 
+    #[test]
+    fn test_get_stakeholder_brief() {
+        let conn = setup_test_db();
+        let person_repo = crate::db::PersonRepository::new(&conn);
+        let repo = ProjectRepository::new(&conn);
+
+        for email in ["alice@example.com", "bob@example.com"] {
+            person_repo
+                .create(&crate::db::Person::new(email.to_string(), email.to_string()))
+                .unwrap();
+        }
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+        let mut stakeholder = ProjectStakeholder::new(project.id, "alice@example.com".to_string());
+        stakeholder.role = Some("Sponsor".to_string());
+        repo.add_stakeholder(&project.id, &stakeholder).unwrap();
+
+        let start = Utc::now();
+        for (days, body) in [(3, "- [ ] Budget for Q3?"), (2, "Kickoff went well"), (1, "- [x] Deck\n- [ ] Launch date?")] {
+            let mut note = StakeholderNote::new(
+                project.id,
+                "alice@example.com".to_string(),
+                format!("Day {}", days),
+                body.to_string(),
+            );
+            note.created_at = start - Duration::days(days);
+            repo.add_stakeholder_note(&note).unwrap();
+        }
+
+        let brief = repo.get_stakeholder_brief(&project.id, "alice@example.com", 2).unwrap();
+        assert_eq!(brief.project_name, "Test Project");
+        assert_eq!(brief.stakeholder.role, Some("Sponsor".to_string()));
+        assert_eq!(brief.person.email, "alice@example.com");
+        let titles: Vec<_> = brief.recent_notes.iter().map(|n| n.title.as_str()).collect();
+        assert_eq!(titles, vec!["Day 1", "Day 2"]);
+        assert_eq!(brief.open_questions, vec!["Launch date?", "Budget for Q3?"]);
+
+        // Someone who isn't a stakeholder gets an error, not an empty brief
+        let err = repo.get_stakeholder_brief(&project.id, "bob@example.com", 2).unwrap_err();
+        assert!(matches!(err, Error::NotFound { entity: "Stakeholder", .. }));
+        let err = repo.get_stakeholder_brief(&Uuid::new_v4(), "alice@example.com", 2).unwrap_err();
+        assert!(matches!(err, Error::NotFound { entity: "Project", .. }));
+    }
+
     #[test]
     fn test_delete_stakeholder_note() {
         let conn = setup_test_db();
@@ -1272,7 +6726,7 @@ mod tests {
         let resource = ProjectResource::new(project.id, "alice@example.com".to_string());
         repo.add_project_resource(&project.id, &resource).unwrap();
 
-        person_repo.delete("alice@example.com").unwrap();
+        person_repo.delete("alice@example.com", false).unwrap();
 
         // Resources should be deleted via cascade
         let resources = repo.get_project_resources(&project.id).unwrap();
@@ -1306,6 +6760,60 @@ mod tests {
         assert_eq!(resources[0].role, Some("Developer".to_string()));
     }
 
+    #[test]
+    fn test_milestone_resource_limit() {
+        let conn = setup_test_db();
+        let person_repo = crate::db::PersonRepository::new(&conn);
+        for name in ["alice", "bob", "carol", "dave"] {
+            person_repo
+                .create(&crate::db::Person::new(format!("{}@example.com", name), name.to_string()))
+                .unwrap();
+        }
+        let repo = ProjectRepository::new(&conn).with_resource_limit(Some(2), false);
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+        let milestone = Milestone::new(project.id, 1, "Milestone 1".to_string());
+        repo.add_milestone(&milestone).unwrap();
+        let empty = Milestone::new(project.id, 2, "Milestone 2".to_string());
+        repo.add_milestone(&empty).unwrap();
+        let add = |repo: &ProjectRepository, name: &str| {
+            repo.add_milestone_resource(
+                &milestone.id,
+                &MilestoneResource::new(milestone.id, format!("{}@example.com", name)),
+            )
+        };
+
+        // Reaching the limit is fine; going over it warns but still adds
+        assert_eq!(add(&repo, "alice").unwrap(), None);
+        assert_eq!(add(&repo, "bob").unwrap(), None);
+        let warning = add(&repo, "carol").unwrap().unwrap();
+        assert_eq!(
+            warning,
+            ResourceLimitWarning {
+                milestone_id: milestone.id,
+                resources: 3,
+                limit: 2
+            }
+        );
+        assert_eq!(
+            warning.to_string(),
+            "Milestone now has 3 resources, more than the 2 allowed by max_resources_per_milestone"
+        );
+
+        // A hard limit refuses resources once the milestone is full
+        let hard = ProjectRepository::new(&conn).with_resource_limit(Some(3), true);
+        let err = add(&hard, "dave").unwrap_err();
+        assert!(matches!(err, Error::Invalid(_)), "{:?}", err);
+        assert_eq!(repo.get_milestone_resources(&milestone.id).unwrap().len(), 3);
+
+        // Without a limit anything goes
+        assert_eq!(add(&ProjectRepository::new(&conn), "dave").unwrap(), None);
+
+        let counts = repo.get_resource_counts(&project.id).unwrap();
+        assert_eq!(counts, BTreeMap::from([(milestone.id, 4), (empty.id, 0)]));
+        assert!(repo.get_resource_counts(&Uuid::new_v4()).unwrap().is_empty());
+    }
+
     #[test]
     fn test_get_milestone_resources_empty() {
         let conn = setup_test_db();
@@ -1413,10 +6921,873 @@ mod tests {
         let resource = MilestoneResource::new(milestone.id, "alice@example.com".to_string());
         repo.add_milestone_resource(&milestone.id, &resource).unwrap();
 
-        person_repo.delete("alice@example.com").unwrap();
+        person_repo.delete("alice@example.com", false).unwrap();
 
         // Resources should be deleted via cascade
         let resources = repo.get_milestone_resources(&milestone.id).unwrap();
         assert_eq!(resources.len(), 0);
     }
+
+    // Dashboard tests
+
+    #[test]
+    fn test_get_dashboard() {
+        let conn = setup_test_db();
+        let person_repo = crate::db::PersonRepository::new(&conn);
+        let repo = ProjectRepository::new(&conn);
+
+        person_repo
+            .create(&crate::db::Person::new("alice@example.com".to_string(), "Alice".to_string()))
+            .unwrap();
+
+        let project = Project::new("Alpha".to_string());
+        repo.create(&project).unwrap();
+        repo.add_milestone(&Milestone::new(project.id, 2, "Second".to_string())).unwrap();
+        repo.add_milestone(&Milestone::new(project.id, 1, "First".to_string())).unwrap();
+        repo.add_project_resource(&project.id, &ProjectResource::new(project.id, "alice@example.com".to_string()))
+            .unwrap();
+        repo.add_stakeholder(&project.id, &ProjectStakeholder::new(project.id, "alice@example.com".to_string()))
+            .unwrap();
+
+        let empty = Project::new("Beta".to_string());
+        repo.create(&empty).unwrap();
+
+        let dashboard = repo.get_dashboard().unwrap();
+        assert_eq!(dashboard.len(), 2);
+
+        assert_eq!(dashboard[0].project.name, "Alpha");
+        assert_eq!(dashboard[0].milestones.len(), 2);
+        assert_eq!(dashboard[0].milestones[0].name, "First");
+        assert_eq!(dashboard[0].resources.len(), 1);
+        assert_eq!(dashboard[0].stakeholders.len(), 1);
+
+        assert_eq!(dashboard[1].project.name, "Beta");
+        assert!(dashboard[1].milestones.is_empty());
+        assert!(dashboard[1].resources.is_empty());
+        assert!(dashboard[1].stakeholders.is_empty());
+    }
+
+    #[test]
+    fn test_get_dashboard_empty() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+
+        let dashboard = repo.get_dashboard().unwrap();
+        assert!(dashboard.is_empty());
+    }
+
+    #[test]
+    fn test_resolve_id_prefix() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+
+        let mut first = Project::new("First".to_string());
+        first.id = Uuid::parse_str("0123abcd-0000-4000-8000-000000000001").unwrap();
+        repo.create(&first).unwrap();
+        let mut second = Project::new("Second".to_string());
+        second.id = Uuid::parse_str("0123abcd-1111-4000-8000-000000000002").unwrap();
+        repo.create(&second).unwrap();
+
+        // Full IDs and unambiguous prefixes, in either case
+        assert_eq!(repo.resolve_id_prefix(&first.id.to_string()).unwrap(), first.id);
+        assert_eq!(repo.resolve_id_prefix("0123abcd-0").unwrap(), first.id);
+        assert_eq!(repo.resolve_id_prefix("0123ABCD-1111").unwrap(), second.id);
+
+        match repo.resolve_id_prefix("0123abcd").unwrap_err() {
+            Error::AmbiguousId { entity, matches, .. } => {
+                assert_eq!(entity, "Project");
+                assert_eq!(matches, 2);
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+        assert!(matches!(repo.resolve_id_prefix("ffffffff").unwrap_err(), Error::NotFound { .. }));
+        assert!(matches!(repo.resolve_id_prefix("0123abc").unwrap_err(), Error::Other(_)));
+        assert!(matches!(repo.resolve_id_prefix("0123abc%").unwrap_err(), Error::Other(_)));
+
+        // Milestones resolve against their own table
+        let milestone = Milestone::new(first.id, 1, "Launch".to_string());
+        repo.add_milestone(&milestone).unwrap();
+        let prefix = &milestone.id.to_string()[..MIN_ID_PREFIX_LEN];
+        assert_eq!(repo.resolve_milestone_id_prefix(prefix).unwrap(), milestone.id);
+        assert!(matches!(
+            repo.resolve_milestone_id_prefix("0123abcd").unwrap_err(),
+            Error::NotFound { .. }
+        ));
+    }
+
+    #[test]
+    fn test_project_slugs() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+
+        // Collisions get a numeric suffix
+        let first = Project::new("My Project".to_string());
+        repo.create(&first).unwrap();
+        let second = Project::new("My Project".to_string());
+        repo.create(&second).unwrap();
+        let third = Project::new("my project!".to_string());
+        repo.create(&third).unwrap();
+        assert_eq!(repo.find_by_id(&first.id).unwrap().unwrap().slug, "my-project");
+        assert_eq!(repo.find_by_id(&second.id).unwrap().unwrap().slug, "my-project-2");
+        assert_eq!(repo.find_by_id(&third.id).unwrap().unwrap().slug, "my-project-3");
+
+        // Unicode names are transliterated, and names with nothing usable still get a slug
+        let accented = Project::new("Café Über Straße".to_string());
+        repo.create(&accented).unwrap();
+        assert_eq!(repo.find_by_id(&accented.id).unwrap().unwrap().slug, "cafe-uber-strasse");
+        let symbols = Project::new("日本語 ✨".to_string());
+        repo.create(&symbols).unwrap();
+        assert_eq!(repo.find_by_id(&symbols.id).unwrap().unwrap().slug, "project");
+
+        // Renaming keeps the slug until it is regenerated explicitly
+        let mut renamed = repo.find_by_id(&first.id).unwrap().unwrap();
+        renamed.name = "Apollo Migration".to_string();
+        repo.update(&renamed).unwrap();
+        assert_eq!(repo.find_by_id(&first.id).unwrap().unwrap().slug, "my-project");
+        assert_eq!(repo.set_slug(&first.id, None).unwrap(), "apollo-migration");
+        assert_eq!(repo.find_by_id(&first.id).unwrap().unwrap().slug, "apollo-migration");
+
+        // Explicit slugs are validated and must be unused
+        assert_eq!(repo.set_slug(&second.id, Some("Apollo-2")).unwrap(), "apollo-2");
+        assert!(matches!(
+            repo.set_slug(&third.id, Some("apollo-migration")).unwrap_err(),
+            Error::AlreadyExists { .. }
+        ));
+        assert!(matches!(repo.set_slug(&third.id, Some("not a slug")).unwrap_err(), Error::Invalid(_)));
+        assert!(matches!(repo.set_slug(&Uuid::new_v4(), None).unwrap_err(), Error::NotFound { .. }));
+    }
+
+    #[test]
+    fn test_resolve_project_ref() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+
+        let mut project = Project::new("Apollo Migration".to_string());
+        project.id = Uuid::parse_str("0123abcd-0000-4000-8000-000000000001").unwrap();
+        repo.create(&project).unwrap();
+        let mut hex = Project::new("Other".to_string());
+        hex.slug = "deadbeef".to_string();
+        repo.create(&hex).unwrap();
+
+        assert_eq!(repo.resolve_project_ref(&project.id.to_string()).unwrap(), project.id);
+        assert_eq!(repo.resolve_project_ref("0123abcd").unwrap(), project.id);
+        assert_eq!(repo.resolve_project_ref("apollo-migration").unwrap(), project.id);
+        assert_eq!(repo.resolve_project_ref("Apollo-Migration").unwrap(), project.id);
+        // Slugs win over ID prefixes
+        assert_eq!(repo.resolve_project_ref("deadbeef").unwrap(), hex.id);
+
+        assert!(matches!(repo.resolve_project_ref("apollo").unwrap_err(), Error::NotFound { .. }));
+        assert!(matches!(repo.resolve_project_ref("ffffffff").unwrap_err(), Error::NotFound { .. }));
+    }
+
+    #[test]
+    fn test_find_by_name_exact() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+
+        let project = Project::new("Migration".to_string());
+        repo.create(&project).unwrap();
+        repo.create(&Project::new("Migration 2".to_string())).unwrap();
+
+        assert_eq!(repo.find_by_name_exact("migration").unwrap().unwrap().id, project.id);
+        assert!(repo.find_by_name_exact("Migr").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_duplicate_names_allowed_without_policy() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+
+        repo.create(&Project::new("Migration".to_string())).unwrap();
+        repo.create(&Project::new("Migration".to_string())).unwrap();
+        assert_eq!(repo.list_all().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_unique_name_policy() {
+        let conn = setup_test_db();
+        db::schema::set_unique_project_names(&conn, true).unwrap();
+        let repo = ProjectRepository::new(&conn);
+
+        let original = Project::new("Migration".to_string());
+        repo.create(&original).unwrap();
+
+        // A retry with a new ID is rejected and points at the original
+        let err = repo.create(&Project::new("MIGRATION".to_string())).unwrap_err();
+        match err {
+            Error::AlreadyExists { entity, id, .. } => {
+                assert_eq!(entity, "Project");
+                assert_eq!(id, original.id.to_string());
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+
+        // Creating the same project twice is still an ID conflict
+        assert!(matches!(repo.create(&original).unwrap_err(), Error::Conflict(_)));
+
+        // Renaming onto an existing name is rejected too
+        let mut other = Project::new("Other".to_string());
+        repo.create(&other).unwrap();
+        other.name = "migration".to_string();
+        assert!(matches!(repo.update(&other).unwrap_err(), Error::AlreadyExists { .. }));
+
+        // Updating a project without renaming it is fine
+        let mut original = original;
+        original.description = Some("Move to the new cluster".to_string());
+        repo.update(&original).unwrap();
+        assert_eq!(repo.list_all().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_list_page() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+
+        let mut ids = Vec::new();
+        for name in ["Zulu", "Alpha", "Mike"] {
+            let project = Project::new(name.to_string());
+            repo.create(&project).unwrap();
+            ids.push(project.id);
+        }
+
+        // Creation order, not name order
+        let first: Vec<Uuid> = repo.list_page(2, 0).unwrap().iter().map(|p| p.id).collect();
+        assert_eq!(first, ids[..2]);
+        let rest: Vec<Uuid> = repo.list_page(2, 2).unwrap().iter().map(|p| p.id).collect();
+        assert_eq!(rest, ids[2..]);
+        assert!(repo.list_page(2, 4).unwrap().is_empty());
+    }
+
+    static STATEMENT_COUNT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+    fn count_statement(_sql: &str) {
+        STATEMENT_COUNT.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    #[test]
+    fn test_get_dashboard_statement_count() {
+        let mut conn = setup_test_db();
+
+        {
+            let repo = ProjectRepository::new(&conn);
+            for i in 0..100 {
+                let project = Project::new(format!("Project {:03}", i));
+                repo.create(&project).unwrap();
+                for n in 1..=10 {
+                    repo.add_milestone(&Milestone::new(project.id, n, format!("Milestone {}", n)))
+                        .unwrap();
+                }
+            }
+        }
+
+        STATEMENT_COUNT.store(0, std::sync::atomic::Ordering::SeqCst);
+        conn.trace(Some(count_statement));
+        let dashboard = ProjectRepository::new(&conn).get_dashboard().unwrap();
+        conn.trace(None);
+
+        assert_eq!(dashboard.len(), 100);
+        assert!(dashboard.iter().all(|d| d.milestones.len() == 10));
+
+        let statements = STATEMENT_COUNT.load(std::sync::atomic::Ordering::SeqCst);
+        assert!(statements < 10, "dashboard ran {} statements", statements);
+    }
+
+    #[test]
+    fn test_project_summary() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+
+        let project = Project::new("Apollo".to_string());
+        repo.create(&project).unwrap();
+
+        let mut done = Milestone::new(project.id, 1, "Done".to_string());
+        done.due_date = Some(Utc::now() - Duration::days(10));
+        repo.add_milestone(&done).unwrap();
+        let mut later = Milestone::new(project.id, 2, "Later".to_string());
+        later.due_date = Some(Utc::now() + Duration::days(60));
+        repo.add_milestone(&later).unwrap();
+        let mut next = Milestone::new(project.id, 3, "Next".to_string());
+        next.due_date = Some(Utc::now() + Duration::days(5));
+        repo.add_milestone(&next).unwrap();
+        repo.add_milestone(&Milestone::new(project.id, 4, "Unscheduled".to_string())).unwrap();
+
+        let recent = ProjectNote::new(project.id, "Recent".to_string(), String::new());
+        repo.add_project_note(&recent).unwrap();
+        let old = ProjectNote::new(project.id, "Old".to_string(), String::new());
+        repo.add_project_note(&old).unwrap();
+        conn.execute(
+            "UPDATE project_notes SET created_at = ?1 WHERE id = ?2",
+            params![dt_to_db(Utc::now() - Duration::days(45)), old.id.to_string()],
+        )
+        .unwrap();
+
+        let summary = repo.get_project_summary(&project.id).unwrap().unwrap();
+        assert_eq!(summary.project_name, "Apollo");
+        assert_eq!(summary.total_milestones, 4);
+        assert_eq!(summary.completed_milestones, 1);
+        assert!(summary.completion_estimated);
+        assert_eq!(summary.progress_percent, Some(25));
+        let next_milestone = summary.next_milestone.unwrap();
+        assert_eq!(next_milestone.id, next.id);
+        assert_eq!(next_milestone.name, "Next");
+        assert_eq!(summary.recent_notes, 1);
+
+        assert!(repo.get_project_summary(&Uuid::new_v4()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_project_summary_checklist_progress() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let checklists = ChecklistRepository::new(&conn);
+
+        let project = Project::new("Apollo".to_string());
+        repo.create(&project).unwrap();
+        let mut overdue = Milestone::new(project.id, 1, "Overdue".to_string());
+        overdue.due_date = Some(Utc::now() - Duration::days(10));
+        repo.add_milestone(&overdue).unwrap();
+        let mut upcoming = Milestone::new(project.id, 2, "Upcoming".to_string());
+        upcoming.due_date = Some(Utc::now() + Duration::days(10));
+        repo.add_milestone(&upcoming).unwrap();
+
+        // The overdue milestone's checklist is half done; the upcoming one's is done
+        let signed_off = checklists.add_item(&overdue.id, "Design signed off").unwrap();
+        checklists.add_item(&overdue.id, "Security review").unwrap();
+        checklists.toggle_item(&signed_off.id, Some(true)).unwrap();
+        let runbook = checklists.add_item(&upcoming.id, "Runbook written").unwrap();
+        checklists.toggle_item(&runbook.id, Some(true)).unwrap();
+
+        // Without checklist progress, only due dates count
+        let summary = repo.get_project_summary(&project.id).unwrap().unwrap();
+        assert_eq!(summary.completed_milestones, 1);
+        assert_eq!(summary.progress_percent, Some(50));
+        assert!(summary.completion_estimated);
+
+        let summary = ProjectRepository::new(&conn)
+            .with_checklist_progress(true)
+            .get_project_summary(&project.id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(summary.completed_milestones, 1);
+        assert_eq!(summary.progress_percent, Some(75));
+        assert!(!summary.completion_estimated);
+    }
+
+    #[test]
+    fn test_list_project_summaries() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+
+        let beta = Project::new("Beta".to_string());
+        repo.create(&beta).unwrap();
+        let alpha = Project::new("Alpha".to_string());
+        repo.create(&alpha).unwrap();
+        let mut milestone = Milestone::new(alpha.id, 1, "Launch".to_string());
+        milestone.due_date = Some(Utc::now() + Duration::days(1));
+        repo.add_milestone(&milestone).unwrap();
+
+        let summaries = repo.list_project_summaries().unwrap();
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].project_name, "Alpha");
+        assert_eq!(summaries[0].progress_percent, Some(0));
+        assert_eq!(summaries[0].next_milestone.as_ref().unwrap().name, "Launch");
+        assert_eq!(summaries[1].project_name, "Beta");
+        assert_eq!(summaries[1].total_milestones, 0);
+        assert_eq!(summaries[1].progress_percent, None);
+        assert!(summaries[1].next_milestone.is_none());
+    }
+
+    static SUMMARY_STATEMENT_COUNT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+    fn count_summary_statement(_sql: &str) {
+        SUMMARY_STATEMENT_COUNT.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    #[test]
+    fn test_list_project_summaries_statement_count() {
+        let mut conn = setup_test_db();
+
+        {
+            let repo = ProjectRepository::new(&conn);
+            for i in 0..50 {
+                let project = Project::new(format!("Project {:03}", i));
+                repo.create(&project).unwrap();
+                for n in 1..=5 {
+                    repo.add_milestone(&Milestone::new(project.id, n, format!("Milestone {}", n)))
+                        .unwrap();
+                }
+            }
+        }
+
+        SUMMARY_STATEMENT_COUNT.store(0, std::sync::atomic::Ordering::SeqCst);
+        conn.trace(Some(count_summary_statement));
+        let summaries = ProjectRepository::new(&conn).list_project_summaries().unwrap();
+        conn.trace(None);
+
+        assert_eq!(summaries.len(), 50);
+        assert!(summaries.iter().all(|s| s.total_milestones == 5));
+
+        let statements = SUMMARY_STATEMENT_COUNT.load(std::sync::atomic::Ordering::SeqCst);
+        assert!(statements <= 2, "summaries ran {} statements", statements);
+    }
+
+    static BRIEF_STATEMENT_COUNT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+    fn count_brief_statement(_sql: &str) {
+        BRIEF_STATEMENT_COUNT.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    #[test]
+    fn test_get_brief_statement_count() {
+        let mut conn = setup_test_db();
+        let project = Project::new("Apollo".to_string());
+        {
+            let repo = ProjectRepository::new(&conn);
+            repo.create(&project).unwrap();
+            for n in 1..=20 {
+                repo.add_milestone(&Milestone::new(project.id, n, format!("Milestone {}", n))).unwrap();
+                repo.add_project_note(&ProjectNote::new(project.id, format!("Note {}", n), "Body".to_string()))
+                    .unwrap();
+            }
+        }
+
+        BRIEF_STATEMENT_COUNT.store(0, std::sync::atomic::Ordering::SeqCst);
+        conn.trace(Some(count_brief_statement));
+        let brief = ProjectRepository::new(&conn).get_brief(&project.id).unwrap();
+        conn.trace(None);
+
+        assert_eq!(brief.milestones.len(), 20);
+        assert_eq!(brief.milestone_count, 20);
+        assert_eq!(brief.recent_notes.len(), BRIEF_NOTE_COUNT);
+        let statements = BRIEF_STATEMENT_COUNT.load(std::sync::atomic::Ordering::SeqCst);
+        assert!(statements <= 5, "brief ran {} statements", statements);
+
+        assert!(matches!(
+            ProjectRepository::new(&conn).get_brief(&Uuid::new_v4()),
+            Err(Error::NotFound { .. })
+        ));
+    }
+
+    // Focus list tests
+
+    #[test]
+    fn test_focus_score() {
+        // Kinds keep their order when equally late
+        assert!(focus_score(FocusKind::OverdueMilestone, 0) > focus_score(FocusKind::ActionItemDue, 0));
+        assert!(focus_score(FocusKind::ActionItemDue, 0) > focus_score(FocusKind::BlockedProject, 0));
+        assert!(focus_score(FocusKind::BlockedProject, 0) > focus_score(FocusKind::StakeholderFollowUp, 0));
+        assert!(focus_score(FocusKind::StakeholderFollowUp, 0) > focus_score(FocusKind::MissingFields, 0));
+
+        // Lateness moves items up, and far enough past another kind
+        assert_eq!(focus_score(FocusKind::ActionItemDue, 2), 54);
+        assert_eq!(focus_score(FocusKind::ActionItemDue, -2), 46);
+        assert!(focus_score(FocusKind::ActionItemDue, 10) > focus_score(FocusKind::OverdueMilestone, 0));
+        assert_eq!(focus_score(FocusKind::MissingFields, 365), focus_score(FocusKind::MissingFields, 30));
+        assert_eq!(focus_score(FocusKind::MissingFields, -365), focus_score(FocusKind::MissingFields, -10));
+    }
+
+    #[test]
+    fn test_get_focus_list() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let people = PersonRepository::new(&conn);
+        for (email, name) in [("alice@example.com", "Alice"), ("bob@example.com", "Bob"), ("carol@example.com", "Carol")] {
+            people.create(&Person::new(email.to_string(), name.to_string())).unwrap();
+        }
+        let now = Utc::now();
+
+        // Alice manages and leads Apollo, which has everything filled in
+        let mut apollo = Project::new("Apollo".to_string());
+        apollo.manager = Some("alice@example.com".to_string());
+        apollo.technical_lead = Some("alice@example.com".to_string());
+        apollo.requirements_owner = Some("bob@example.com".to_string());
+        apollo.due_date = Some(now + Duration::days(90));
+        repo.create(&apollo).unwrap();
+        let mut overdue = Milestone::new(apollo.id, 1, "Launch".to_string());
+        overdue.due_date = Some(now - Duration::days(2));
+        repo.add_milestone(&overdue).unwrap();
+        // Done milestones and ones long past aren't listed
+        let mut checked = Milestone::new(apollo.id, 2, "Checked".to_string());
+        checked.due_date = Some(now - Duration::days(1));
+        repo.add_milestone(&checked).unwrap();
+        let checklist = crate::db::ChecklistRepository::new(&conn);
+        let item = checklist.add_item(&checked.id, "Ship").unwrap();
+        checklist.toggle_item(&item.id, Some(true)).unwrap();
+        let mut old = Milestone::new(apollo.id, 3, "Old".to_string());
+        old.due_date = Some(now - Duration::days(FOCUS_OVERDUE_DAYS + 1));
+        repo.add_milestone(&old).unwrap();
+        // Bob leads this one
+        let mut bobs = Milestone::new(apollo.id, 4, "Bob's".to_string());
+        bobs.technical_lead = Some("bob@example.com".to_string());
+        bobs.due_date = Some(now - Duration::days(1));
+        repo.add_milestone(&bobs).unwrap();
+
+        let mut soon = ActionItem::new(apollo.id, "alice@example.com".to_string(), "Send agenda".to_string());
+        soon.due_date = Some(now + Duration::hours(36));
+        repo.create_action_item(&soon).unwrap();
+        let mut later = ActionItem::new(apollo.id, "alice@example.com".to_string(), "Book venue".to_string());
+        later.due_date = Some(now + Duration::days(FOCUS_DUE_SOON_DAYS + 1));
+        repo.create_action_item(&later).unwrap();
+
+        // Carol was added over a month ago and Bob was written about recently
+        let mut carol = ProjectStakeholder::new(apollo.id, "carol@example.com".to_string());
+        carol.created_at = now - Duration::days(35);
+        repo.add_stakeholder(&apollo.id, &carol).unwrap();
+        let mut bob = ProjectStakeholder::new(apollo.id, "bob@example.com".to_string());
+        bob.created_at = now - Duration::days(45);
+        repo.add_stakeholder(&apollo.id, &bob).unwrap();
+        repo.add_stakeholder_note(&StakeholderNote::new(
+            apollo.id,
+            "bob@example.com".to_string(),
+            "Catch-up".to_string(),
+            String::new(),
+        ))
+        .unwrap();
+
+        // Gemini is blocked and has no technical lead or due date
+        let mut gemini = Project::new("Gemini".to_string());
+        gemini.manager = Some("alice@example.com".to_string());
+        gemini.blocked = true;
+        gemini.blocked_reason = Some("Waiting on legal".to_string());
+        repo.create(&gemini).unwrap();
+
+        // Finished projects are left out
+        let mut mercury = Project::new("Mercury".to_string());
+        mercury.manager = Some("alice@example.com".to_string());
+        mercury.status = ProjectStatus::Done;
+        repo.create(&mercury).unwrap();
+
+        let focus = repo.get_focus_list(" Alice@Example.com ", now).unwrap();
+        let summary: Vec<(FocusKind, &str, &str)> =
+            focus.iter().map(|item| (item.kind, item.title.as_str(), item.reason.as_str())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (FocusKind::OverdueMilestone, "#1 Launch", "Was due 2 days ago"),
+                (FocusKind::ActionItemDue, "Send agenda", "Due in 1 day"),
+                (FocusKind::BlockedProject, "Gemini", "Blocked: Waiting on legal"),
+                (FocusKind::StakeholderFollowUp, "Carol", "No stakeholder note in 35 days"),
+                (FocusKind::MissingFields, "Gemini", "Missing technical lead, requirements owner, due date"),
+            ]
+        );
+        assert_eq!(focus[0].link, format!("milestone:{}", overdue.id));
+        assert_eq!(focus[0].score, 64);
+        assert_eq!(focus[1].link, format!("action_item:{}", soon.id));
+        assert_eq!(focus[2].link, format!("project:{}", gemini.id));
+        assert_eq!(focus[3].link, "person:carol@example.com");
+        assert_eq!(focus[3].project_name, "Apollo");
+
+        // Bob only sees the milestone he leads
+        let bobs_focus = repo.get_focus_list("bob@example.com", now).unwrap();
+        assert_eq!(bobs_focus.len(), 1);
+        assert_eq!(bobs_focus[0].title, "#4 Bob's");
+
+        // Stakeholder follow-ups can be turned off
+        let quiet = ProjectRepository::new(&conn).with_stakeholder_contact_days(0);
+        let focus = quiet.get_focus_list("alice@example.com", now).unwrap();
+        assert!(focus.iter().all(|item| item.kind != FocusKind::StakeholderFollowUp));
+
+        // Older databases without the optional tables still get a list; the
+        // checked milestone is overdue and Bob's last contact is when he was added
+        conn.execute_batch("DROP TABLE action_items; DROP TABLE stakeholder_notes; DROP TABLE milestone_checklist_items")
+            .unwrap();
+        let focus = repo.get_focus_list("alice@example.com", now).unwrap();
+        let summary: Vec<(FocusKind, &str)> = focus.iter().map(|item| (item.kind, item.title.as_str())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (FocusKind::OverdueMilestone, "#1 Launch"),
+                (FocusKind::OverdueMilestone, "#2 Checked"),
+                (FocusKind::StakeholderFollowUp, "Bob"),
+                (FocusKind::BlockedProject, "Gemini"),
+                (FocusKind::StakeholderFollowUp, "Carol"),
+                (FocusKind::MissingFields, "Gemini"),
+            ]
+        );
+    }
+
+    // Timestamp format tests
+
+    #[test]
+    fn test_read_timestamps_in_both_formats() {
+        use chrono::TimeZone;
+
+        let conn = setup_test_db();
+        let project_id = Uuid::new_v4();
+        let milestone_id = Uuid::new_v4();
+
+        // Rows written with SQLite's datetime format and with RFC3339
+        conn.execute(
+            "INSERT INTO projects (id, name, slug, due_date, created_at, updated_at)
+             VALUES (?1, 'Legacy', 'legacy', '2025-03-01 00:00:00', '2025-01-15 10:30:00', '2025-01-15T10:30:00Z')",
+            params![project_id.to_string()],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO milestones (id, project_id, number, name, due_date, created_at, updated_at)
+             VALUES (?1, ?2, 1, 'M1', '2025-02-01T12:00:00+02:00', '2025-01-15 10:30:00.500', '2025-01-15T10:30:00+00:00')",
+            params![milestone_id.to_string(), project_id.to_string()],
+        )
+        .unwrap();
+
+        let repo = ProjectRepository::new(&conn);
+        let expected = Utc.with_ymd_and_hms(2025, 1, 15, 10, 30, 0).unwrap();
+
+        let project = repo.find_by_id(&project_id).unwrap().unwrap();
+        assert_eq!(project.created_at, expected);
+        assert_eq!(project.updated_at, expected);
+        assert_eq!(project.due_date, Some(Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap()));
+
+        let milestone = repo.find_milestone_by_id(&milestone_id).unwrap().unwrap();
+        assert_eq!(milestone.created_at, expected + chrono::Duration::milliseconds(500));
+        assert_eq!(milestone.updated_at, expected);
+        assert_eq!(milestone.due_date, Some(Utc.with_ymd_and_hms(2025, 2, 1, 10, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_write_timestamps_as_rfc3339() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+
+        let mut project = Project::new("Test Project".to_string());
+        project.due_date = Some(Utc::now());
+        repo.create(&project).unwrap();
+
+        let (due_date, created_at): (String, String) = conn
+            .query_row(
+                "SELECT due_date, created_at FROM projects WHERE id = ?1",
+                params![project.id.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(due_date, dt_to_db(project.due_date.unwrap()));
+        assert_eq!(created_at, dt_to_db(project.created_at));
+
+        let found = repo.find_by_id(&project.id).unwrap().unwrap();
+        assert_eq!(found.due_date, project.due_date);
+        assert_eq!(found.created_at, project.created_at);
+    }
+
+    // Team assignment tests
+
+    fn setup_team(conn: &Connection, members: &[&str]) {
+        let person_repo = crate::db::PersonRepository::new(conn);
+        let team_repo = crate::db::TeamRepository::new(conn);
+        team_repo.create(&crate::db::Team::new("Engineering".to_string())).unwrap();
+        for email in members {
+            person_repo
+                .create(&crate::db::Person::new(email.to_string(), email.to_string()))
+                .unwrap();
+            team_repo.add_member("Engineering", email).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_add_team_as_resources() {
+        let conn = setup_test_db();
+        setup_team(&conn, &["alice@example.com", "bob@example.com", "carol@example.com"]);
+        let repo = ProjectRepository::new(&conn);
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+
+        // Bob is already assigned and Carol has left
+        repo.add_project_resource(&project.id, &ProjectResource::new(project.id, "bob@example.com".to_string()))
+            .unwrap();
+        crate::db::PersonRepository::new(&conn)
+            .deactivate("carol@example.com", false)
+            .unwrap();
+
+        let result = repo
+            .add_team_as_resources(&project.id, "Engineering", Some("Developer"))
+            .unwrap();
+        assert_eq!(result.added, vec!["alice@example.com"]);
+        assert_eq!(result.skipped, vec!["bob@example.com", "carol@example.com"]);
+
+        let resources = repo.get_project_resources(&project.id).unwrap();
+        assert_eq!(resources.len(), 2);
+        let alice = resources.iter().find(|r| r.person_email == "alice@example.com").unwrap();
+        assert_eq!(alice.role.as_deref(), Some("Developer"));
+    }
+
+    #[test]
+    fn test_add_empty_team_as_resources_fails() {
+        let conn = setup_test_db();
+        setup_team(&conn, &[]);
+        let repo = ProjectRepository::new(&conn);
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+
+        let result = repo.add_team_as_resources(&project.id, "Engineering", None);
+        assert!(matches!(result.unwrap_err(), Error::Conflict(_)));
+    }
+
+    #[test]
+    fn test_add_missing_team_as_resources_fails() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let project = Project::new("Test Project".to_string());
+        repo.create(&project).unwrap();
+
+        let result = repo.add_team_as_resources(&project.id, "Nonexistent", None);
+        assert!(matches!(result.unwrap_err(), Error::NotFound { entity: "Team", .. }));
+
+        let result = repo.add_team_as_resources(&Uuid::new_v4(), "Nonexistent", None);
+        assert!(matches!(result.unwrap_err(), Error::NotFound { entity: "Project", .. }));
+    }
+
+    #[test]
+    fn test_suggest_milestone_resources() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let person_repo = db::PersonRepository::new(&conn);
+        let team_repo = db::TeamRepository::new(&conn);
+        let days = |n: i64| Some(Utc::now() + Duration::days(n));
+
+        for (email, name) in [
+            ("alice@example.com", "Alice"),
+            ("bob@example.com", "Bob"),
+            ("carol@example.com", "Carol"),
+            ("dave@example.com", "Dave"),
+            ("erin@example.com", "Erin"),
+        ] {
+            person_repo.create(&Person::new(email.to_string(), name.to_string())).unwrap();
+        }
+        team_repo.create(&db::Team::new("Platform".to_string())).unwrap();
+        for email in ["alice@example.com", "bob@example.com", "carol@example.com", "erin@example.com"] {
+            team_repo.add_member("Platform", email).unwrap();
+        }
+        person_repo.deactivate("carol@example.com", false).unwrap();
+
+        let project = Project::new("Apollo".to_string());
+        repo.create(&project).unwrap();
+        repo.add_project_resource(&project.id, &ProjectResource::new(project.id, "dave@example.com".to_string()))
+            .unwrap();
+        repo.add_project_resource(&project.id, &ProjectResource::new(project.id, "bob@example.com".to_string()))
+            .unwrap();
+
+        let mut target = Milestone::new(project.id, 1, "Launch".to_string());
+        target.team = Some("Platform".to_string());
+        target.start_date = days(30);
+        target.due_date = days(60);
+        repo.add_milestone(&target).unwrap();
+        let mut overlapping = Milestone::new(project.id, 2, "Beta".to_string());
+        overlapping.due_date = days(45);
+        repo.add_milestone(&overlapping).unwrap();
+        let mut later = Milestone::new(project.id, 3, "GA".to_string());
+        later.start_date = days(90);
+        later.due_date = days(120);
+        repo.add_milestone(&later).unwrap();
+
+        let assign = |milestone: &Milestone, email: &str| {
+            repo.add_milestone_resource(&milestone.id, &MilestoneResource::new(milestone.id, email.to_string()))
+                .unwrap();
+        };
+        assign(&overlapping, "alice@example.com");
+        assign(&later, "bob@example.com");
+        assign(&target, "erin@example.com");
+
+        // Carol is inactive and Erin is already on the milestone
+        let suggestions = repo.suggest_milestone_resources(&target.id, None, 10).unwrap();
+        let ranked: Vec<_> = suggestions
+            .iter()
+            .map(|s| (s.email.as_str(), s.team_match, s.on_project, s.concurrent_milestones))
+            .collect();
+        assert_eq!(
+            ranked,
+            vec![
+                ("bob@example.com", true, true, 0),
+                ("alice@example.com", true, false, 1),
+                ("dave@example.com", false, true, 0),
+            ]
+        );
+        assert_eq!(suggestions[0].reason, "On Platform, already on the project, no overlapping milestones");
+        assert_eq!(suggestions[1].reason, "On Platform, 1 overlapping milestone");
+        assert_eq!(suggestions[2].reason, "Already on the project, no overlapping milestones");
+        assert_eq!(repo.suggest_milestone_resources(&target.id, None, 1).unwrap().len(), 1);
+
+        // No team and no dates: the project's team, then total open assignments
+        let mut solo = Project::new("Solo".to_string());
+        solo.team = Some("Platform".to_string());
+        repo.create(&solo).unwrap();
+        let undated = Milestone::new(solo.id, 1, "Someday".to_string());
+        repo.add_milestone(&undated).unwrap();
+        let suggestions = repo.suggest_milestone_resources(&undated.id, None, 10).unwrap();
+        let ranked: Vec<_> = suggestions.iter().map(|s| (s.email.as_str(), s.concurrent_milestones)).collect();
+        assert_eq!(
+            ranked,
+            vec![("alice@example.com", 1), ("bob@example.com", 1), ("erin@example.com", 1)]
+        );
+        assert_eq!(suggestions[0].reason, "On Platform, 1 open milestone");
+
+        // Nobody to go on: everyone active is a candidate
+        let loose = Project::new("Loose".to_string());
+        repo.create(&loose).unwrap();
+        let orphan = Milestone::new(loose.id, 1, "Whenever".to_string());
+        repo.add_milestone(&orphan).unwrap();
+        let suggestions = repo.suggest_milestone_resources(&orphan.id, None, 10).unwrap();
+        assert_eq!(suggestions.len(), 4);
+        assert_eq!(suggestions[0].email, "dave@example.com");
+
+        // Asking for a skill brings in people from elsewhere and ranks by level
+        person_repo.create(&Person::new("frank@example.com".to_string(), "Frank".to_string())).unwrap();
+        person_repo.add_skill("frank@example.com", "kubernetes", Some(2)).unwrap();
+        person_repo.add_skill("dave@example.com", "kubernetes", Some(4)).unwrap();
+        person_repo.add_skill("alice@example.com", "kubernetes", None).unwrap();
+        let suggestions = repo.suggest_milestone_resources(&target.id, Some("Kubernetes"), 10).unwrap();
+        let ranked: Vec<_> = suggestions.iter().map(|s| (s.email.as_str(), s.skill_level)).collect();
+        assert_eq!(
+            ranked,
+            vec![
+                ("dave@example.com", Some(4)),
+                ("frank@example.com", Some(2)),
+                ("alice@example.com", None),
+                ("bob@example.com", None),
+            ]
+        );
+        assert_eq!(suggestions[0].reason, "Knows kubernetes (level 4), already on the project, no overlapping milestones");
+        assert!(!suggestions[3].has_skill);
+
+        assert!(matches!(
+            repo.suggest_milestone_resources(&Uuid::new_v4(), None, 10).unwrap_err(),
+            Error::NotFound { entity: "Milestone", .. }
+        ));
+    }
+
+    #[test]
+    fn test_created_since_and_note_activity() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let now = Utc::now();
+
+        let mut old = Project::new("Old".to_string());
+        old.created_at = now - Duration::days(30);
+        repo.create(&old).unwrap();
+        let new = Project::new("New".to_string());
+        repo.create(&new).unwrap();
+
+        let created: Vec<_> = repo
+            .list_created_since(now - Duration::days(7))
+            .unwrap()
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(created, vec!["New"]);
+
+        let milestone = Milestone::new(old.id, 1, "Launch".to_string());
+        repo.add_milestone(&milestone).unwrap();
+        repo.add_project_note(&ProjectNote::new(old.id, "Status".to_string(), "Fine".to_string()))
+            .unwrap();
+        repo.add_milestone_note(&MilestoneNote::new(milestone.id, "Risk".to_string(), "Late".to_string()))
+            .unwrap();
+        repo.add_project_note(&ProjectNote::new(new.id, "Kickoff".to_string(), "Started".to_string()))
+            .unwrap();
+        let mut stale = ProjectNote::new(new.id, "Ancient".to_string(), "Long ago".to_string());
+        stale.created_at = now - Duration::days(30);
+        repo.add_project_note(&stale).unwrap();
+
+        let activity = repo.note_activity_since(now - Duration::days(7), 10).unwrap();
+        let counts: Vec<_> = activity.iter().map(|a| (a.project_name.as_str(), a.note_count)).collect();
+        assert_eq!(counts, vec![("Old", 2), ("New", 1)]);
+        assert_eq!(repo.note_activity_since(now - Duration::days(7), 1).unwrap().len(), 1);
+    }
 }
@@ -0,0 +1,910 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! A small filter language for finding projects
+//!
+//! Expressions compare fields of a project, or of any one of its milestones,
+//! with literal values:
+//!
+//! ```text
+//! type = "Team" AND team = "Platform"
+//!     AND milestone.due_date < 2025-04-01 AND milestone.technical_lead IS NULL
+//! ```
+//!
+//! Supported operators are `=`, `!=`, `<`, `<=`, `>`, `>=`, `IS NULL`,
+//! `IS NOT NULL` and `IN (...)`, combined with `AND`, `OR`, `NOT` and
+//! parentheses. Strings are quoted with `"` or `'` and use `\` to escape
+//! quotes; dates are written `YYYY-MM-DD` and compare by calendar day in UTC.
+//! Text comparisons ignore case. A comparison never matches a missing value;
+//! use `IS NULL` for that. All milestone conditions in an expression must
+//! hold for the same milestone.
+//!
+//! Expressions are parsed into an [`Expr`] and compiled to SQL in which every
+//! column comes from a fixed list of [`FIELDS`] and every value is a bound
+//! parameter, so no input can change the shape of the query.
+
+use super::error::{Error, Result};
+use super::models::Project;
+use super::project_repo::{project_from_row, PROJECT_COLUMNS_P};
+use chrono::NaiveDate;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::Connection;
+use std::fmt;
+
+/// Longest expression accepted, in bytes
+pub const MAX_QUERY_LENGTH: usize = 4096;
+
+/// Deepest nesting of parentheses and `NOT` accepted
+const MAX_DEPTH: usize = 32;
+
+/// The type of values a field holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    Text,
+    Date,
+    Integer,
+    Bool,
+}
+
+/// A field that expressions may refer to
+#[derive(Debug, PartialEq, Eq)]
+pub struct Field {
+    /// Name used in expressions
+    pub name: &'static str,
+    /// Type of the field's values
+    pub kind: FieldKind,
+    /// SQL column the field reads from
+    column: &'static str,
+}
+
+impl Field {
+    fn is_milestone(&self) -> bool {
+        self.name.starts_with("milestone.")
+    }
+}
+
+/// Every field expressions may refer to; nothing else can reach the SQL
+pub const FIELDS: &[Field] = &[
+    Field {
+        name: "name",
+        kind: FieldKind::Text,
+        column: "p.name",
+    },
+    Field {
+        name: "type",
+        kind: FieldKind::Text,
+        column: "p.type",
+    },
+    Field {
+        name: "team",
+        kind: FieldKind::Text,
+        column: "p.team",
+    },
+    Field {
+        name: "manager",
+        kind: FieldKind::Text,
+        column: "p.manager",
+    },
+    Field {
+        name: "technical_lead",
+        kind: FieldKind::Text,
+        column: "p.technical_lead",
+    },
+    Field {
+        name: "requirements_owner",
+        kind: FieldKind::Text,
+        column: "p.requirements_owner",
+    },
+    Field {
+        name: "jira_initiative",
+        kind: FieldKind::Text,
+        column: "p.jira_initiative",
+    },
+    Field {
+        name: "start_date",
+        kind: FieldKind::Date,
+        column: "p.start_date",
+    },
+    Field {
+        name: "due_date",
+        kind: FieldKind::Date,
+        column: "p.due_date",
+    },
+    Field {
+        name: "blocked",
+        kind: FieldKind::Bool,
+        column: "p.blocked",
+    },
+    Field {
+        name: "milestone.name",
+        kind: FieldKind::Text,
+        column: "m.name",
+    },
+    Field {
+        name: "milestone.number",
+        kind: FieldKind::Integer,
+        column: "m.number",
+    },
+    Field {
+        name: "milestone.team",
+        kind: FieldKind::Text,
+        column: "m.team",
+    },
+    Field {
+        name: "milestone.technical_lead",
+        kind: FieldKind::Text,
+        column: "m.technical_lead",
+    },
+    Field {
+        name: "milestone.jira_epic",
+        kind: FieldKind::Text,
+        column: "m.jira_epic",
+    },
+    Field {
+        name: "milestone.start_date",
+        kind: FieldKind::Date,
+        column: "m.start_date",
+    },
+    Field {
+        name: "milestone.due_date",
+        kind: FieldKind::Date,
+        column: "m.due_date",
+    },
+];
+
+/// Look up a field by name, accepting an optional `project.` prefix
+pub fn find_field(name: &str) -> Option<&'static Field> {
+    let name = name.to_lowercase();
+    let name = name.strip_prefix("project.").unwrap_or(&name);
+    FIELDS.iter().find(|field| field.name == name)
+}
+
+/// A literal value in an expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Text(String),
+    Date(NaiveDate),
+    Integer(i64),
+    Bool(bool),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Text(s) => write!(f, "{:?}", s),
+            Value::Date(d) => write!(f, "{}", d.format("%Y-%m-%d")),
+            Value::Integer(n) => write!(f, "{}", n),
+            Value::Bool(b) => write!(f, "{}", b),
+        }
+    }
+}
+
+/// A comparison operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CompareOp {
+    fn sql(self) -> &'static str {
+        match self {
+            CompareOp::Eq => "=",
+            CompareOp::Ne => "!=",
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+        }
+    }
+}
+
+/// A parsed and type-checked filter expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare {
+        field: &'static Field,
+        op: CompareOp,
+        value: Value,
+    },
+    IsNull {
+        field: &'static Field,
+        negated: bool,
+    },
+    In {
+        field: &'static Field,
+        values: Vec<Value>,
+    },
+}
+
+/// SQL compiled from an expression, with its parameters in order
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompiledQuery {
+    pub sql: String,
+    pub params: Vec<SqlValue>,
+}
+
+/// Parse an expression, checking field names and value types
+pub fn parse(input: &str) -> Result<Expr> {
+    if input.len() > MAX_QUERY_LENGTH {
+        return Err(Error::Invalid(format!(
+            "Query is too long: {} bytes, the limit is {}",
+            input.len(),
+            MAX_QUERY_LENGTH
+        )));
+    }
+    let tokens = tokenize(input)?;
+    if tokens.is_empty() {
+        return Err(Error::Invalid("Query is empty".to_string()));
+    }
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        depth: 0,
+    };
+    let expr = parser.or_expr()?;
+    if let Some((token, at)) = parser.tokens.get(parser.pos) {
+        return Err(Error::Invalid(format!(
+            "Unexpected {} at position {}",
+            token, at
+        )));
+    }
+    Ok(expr)
+}
+
+impl Expr {
+    /// Whether any condition refers to a milestone field
+    pub fn uses_milestones(&self) -> bool {
+        match self {
+            Expr::And(a, b) | Expr::Or(a, b) => a.uses_milestones() || b.uses_milestones(),
+            Expr::Not(e) => e.uses_milestones(),
+            Expr::Compare { field, .. } | Expr::IsNull { field, .. } | Expr::In { field, .. } => {
+                field.is_milestone()
+            }
+        }
+    }
+
+    /// Compile to a `SELECT` returning the matching projects, ordered by name
+    pub fn compile(&self) -> CompiledQuery {
+        let mut params = Vec::new();
+        let mut condition = String::new();
+        self.write_sql(&mut condition, &mut params);
+
+        let join = if self.uses_milestones() {
+            " LEFT JOIN milestones m ON m.project_id = p.id"
+        } else {
+            ""
+        };
+        let sql = format!(
+            "SELECT DISTINCT {} FROM projects p{} WHERE {} ORDER BY p.name",
+            PROJECT_COLUMNS_P, join, condition
+        );
+        CompiledQuery { sql, params }
+    }
+
+    fn write_sql(&self, sql: &mut String, params: &mut Vec<SqlValue>) {
+        match self {
+            Expr::And(a, b) | Expr::Or(a, b) => {
+                let op = if matches!(self, Expr::And(..)) {
+                    " AND "
+                } else {
+                    " OR "
+                };
+                sql.push('(');
+                a.write_sql(sql, params);
+                sql.push_str(op);
+                b.write_sql(sql, params);
+                sql.push(')');
+            }
+            Expr::Not(e) => {
+                sql.push_str("NOT (");
+                e.write_sql(sql, params);
+                sql.push(')');
+            }
+            Expr::Compare { field, op, value } => {
+                sql.push_str(&column_sql(field));
+                sql.push(' ');
+                sql.push_str(op.sql());
+                sql.push_str(" ?");
+                sql.push_str(collation(field));
+                params.push(sql_value(value));
+            }
+            Expr::IsNull { field, negated } => {
+                if *negated {
+                    sql.push_str(&format!("{} IS NOT NULL", field.column));
+                } else if field.is_milestone() {
+                    // Projects without milestones don't have a milestone missing a value
+                    sql.push_str(&format!("(m.id IS NOT NULL AND {} IS NULL)", field.column));
+                } else {
+                    sql.push_str(&format!("{} IS NULL", field.column));
+                }
+            }
+            Expr::In { field, values } => {
+                sql.push_str(&column_sql(field));
+                sql.push_str(" IN (");
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        sql.push_str(", ");
+                    }
+                    sql.push('?');
+                    sql.push_str(collation(field));
+                    params.push(sql_value(value));
+                }
+                sql.push(')');
+            }
+        }
+    }
+}
+
+/// The SQL a field is compared through; dates compare by their day
+fn column_sql(field: &Field) -> String {
+    match field.kind {
+        FieldKind::Date => format!("substr({}, 1, 10)", field.column),
+        _ => field.column.to_string(),
+    }
+}
+
+fn collation(field: &Field) -> &'static str {
+    if field.kind == FieldKind::Text {
+        " COLLATE NOCASE"
+    } else {
+        ""
+    }
+}
+
+fn sql_value(value: &Value) -> SqlValue {
+    match value {
+        Value::Text(s) => SqlValue::Text(s.clone()),
+        Value::Date(d) => SqlValue::Text(d.format("%Y-%m-%d").to_string()),
+        Value::Integer(n) => SqlValue::Integer(*n),
+        Value::Bool(b) => SqlValue::Integer(i64::from(*b)),
+    }
+}
+
+/// Parse an expression and return the projects it matches
+pub fn run_query(conn: &Connection, expression: &str) -> Result<Vec<Project>> {
+    let compiled = parse(expression)?.compile();
+    let mut stmt = conn.prepare(&compiled.sql)?;
+    let projects = stmt
+        .query_map(
+            rusqlite::params_from_iter(compiled.params),
+            project_from_row,
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    log::debug!("Query {:?} matched {} projects", expression, projects.len());
+    Ok(projects)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Number(i64),
+    Date(NaiveDate),
+    Op(CompareOp),
+    LParen,
+    RParen,
+    Comma,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(s) => write!(f, "'{}'", s),
+            Token::Str(s) => write!(f, "string {:?}", s),
+            Token::Number(n) => write!(f, "number {}", n),
+            Token::Date(d) => write!(f, "date {}", d),
+            Token::Op(op) => write!(f, "'{}'", op.sql()),
+            Token::LParen => f.write_str("'('"),
+            Token::RParen => f.write_str("')'"),
+            Token::Comma => f.write_str("','"),
+        }
+    }
+}
+
+/// Split an expression into tokens, each with its character position
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let token = match c {
+            '(' => {
+                i += 1;
+                Token::LParen
+            }
+            ')' => {
+                i += 1;
+                Token::RParen
+            }
+            ',' => {
+                i += 1;
+                Token::Comma
+            }
+            '=' => {
+                i += 1;
+                Token::Op(CompareOp::Eq)
+            }
+            '!' if chars.get(i + 1) == Some(&'=') => {
+                i += 2;
+                Token::Op(CompareOp::Ne)
+            }
+            '<' => match chars.get(i + 1) {
+                Some('=') => {
+                    i += 2;
+                    Token::Op(CompareOp::Le)
+                }
+                Some('>') => {
+                    i += 2;
+                    Token::Op(CompareOp::Ne)
+                }
+                _ => {
+                    i += 1;
+                    Token::Op(CompareOp::Lt)
+                }
+            },
+            '>' => {
+                if chars.get(i + 1) == Some(&'=') {
+                    i += 2;
+                    Token::Op(CompareOp::Ge)
+                } else {
+                    i += 1;
+                    Token::Op(CompareOp::Gt)
+                }
+            }
+            '"' | '\'' => {
+                let quote = c;
+                let mut value = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => {
+                            return Err(Error::Invalid(format!(
+                                "Unterminated string starting at position {}",
+                                start
+                            )))
+                        }
+                        Some('\\') => {
+                            match chars.get(i + 1) {
+                                Some(&escaped) if escaped == quote || escaped == '\\' => {
+                                    value.push(escaped)
+                                }
+                                _ => return Err(Error::Invalid(format!(
+                                    "Invalid escape at position {}: only \\{} and \\\\ are allowed",
+                                    i, quote
+                                ))),
+                            }
+                            i += 2;
+                        }
+                        Some(&ch) if ch == quote => {
+                            i += 1;
+                            break;
+                        }
+                        Some(&ch) => {
+                            value.push(ch);
+                            i += 1;
+                        }
+                    }
+                }
+                Token::Str(value)
+            }
+            c if c.is_ascii_digit() => {
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '-') {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                if text.contains('-') {
+                    let date = NaiveDate::parse_from_str(&text, "%Y-%m-%d").map_err(|_| {
+                        Error::Invalid(format!(
+                            "Invalid date '{}' at position {}: expected YYYY-MM-DD",
+                            text, start
+                        ))
+                    })?;
+                    Token::Date(date)
+                } else {
+                    let number = text.parse().map_err(|_| {
+                        Error::Invalid(format!("Invalid number '{}' at position {}", text, start))
+                    })?;
+                    Token::Number(number)
+                }
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                while i < chars.len()
+                    && (chars[i].is_ascii_alphanumeric() || chars[i] == '_' || chars[i] == '.')
+                {
+                    i += 1;
+                }
+                Token::Ident(chars[start..i].iter().collect())
+            }
+            other => {
+                return Err(Error::Invalid(format!(
+                    "Unexpected character '{}' at position {}",
+                    other, start
+                )))
+            }
+        };
+        tokens.push((token, start));
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    /// Position of the next token, or the end of the input
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .or_else(|| self.tokens.last())
+            .map_or(0, |(_, at)| *at)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(token, _)| token.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(s)) if s.eq_ignore_ascii_case(keyword))
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<()> {
+        if self.peek_keyword(keyword) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("expected {}", keyword)))
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> Error {
+        match self.tokens.get(self.pos) {
+            Some((token, at)) => Error::Invalid(format!(
+                "Unexpected {} at position {}: {}",
+                token, at, expected
+            )),
+            None => Error::Invalid(format!("Unexpected end of query: {}", expected)),
+        }
+    }
+
+    fn or_expr(&mut self) -> Result<Expr> {
+        let mut expr = self.and_expr()?;
+        while self.peek_keyword("OR") {
+            self.pos += 1;
+            expr = Expr::Or(Box::new(expr), Box::new(self.and_expr()?));
+        }
+        Ok(expr)
+    }
+
+    fn and_expr(&mut self) -> Result<Expr> {
+        let mut expr = self.unary()?;
+        while self.peek_keyword("AND") {
+            self.pos += 1;
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(Error::Invalid(format!(
+                "Query is nested too deeply, the limit is {} levels",
+                MAX_DEPTH
+            )));
+        }
+        let expr = if self.peek_keyword("NOT") {
+            self.pos += 1;
+            Expr::Not(Box::new(self.unary()?))
+        } else if self.peek() == Some(&Token::LParen) {
+            self.pos += 1;
+            let expr = self.or_expr()?;
+            if self.next() != Some(Token::RParen) {
+                self.pos -= 1;
+                return Err(self.unexpected("expected ')'"));
+            }
+            expr
+        } else {
+            self.condition()?
+        };
+        self.depth -= 1;
+        Ok(expr)
+    }
+
+    fn condition(&mut self) -> Result<Expr> {
+        let at = self.position();
+        let field = match self.next() {
+            Some(Token::Ident(name)) => find_field(&name).ok_or_else(|| {
+                Error::Invalid(format!(
+                    "Unknown field '{}' at position {}: expected one of {}",
+                    name,
+                    at,
+                    FIELDS.iter().map(|f| f.name).collect::<Vec<_>>().join(", ")
+                ))
+            })?,
+            _ => {
+                self.pos -= 1;
+                return Err(self.unexpected("expected a field name"));
+            }
+        };
+
+        if self.peek_keyword("IS") {
+            self.pos += 1;
+            let negated = self.peek_keyword("NOT");
+            if negated {
+                self.pos += 1;
+            }
+            self.expect_keyword("NULL")?;
+            return Ok(Expr::IsNull { field, negated });
+        }
+
+        if self.peek_keyword("IN") {
+            self.pos += 1;
+            if self.next() != Some(Token::LParen) {
+                self.pos -= 1;
+                return Err(self.unexpected("expected '(' after IN"));
+            }
+            let mut values = vec![self.value(field)?];
+            loop {
+                match self.next() {
+                    Some(Token::Comma) => values.push(self.value(field)?),
+                    Some(Token::RParen) => break,
+                    _ => {
+                        self.pos -= 1;
+                        return Err(self.unexpected("expected ',' or ')'"));
+                    }
+                }
+            }
+            return Ok(Expr::In { field, values });
+        }
+
+        let op = match self.next() {
+            Some(Token::Op(op)) => op,
+            _ => {
+                self.pos -= 1;
+                return Err(self.unexpected("expected a comparison, IS or IN"));
+            }
+        };
+        if field.kind == FieldKind::Bool && !matches!(op, CompareOp::Eq | CompareOp::Ne) {
+            return Err(Error::Invalid(format!(
+                "{} can only be compared with = or !=",
+                field.name
+            )));
+        }
+        let value = self.value(field)?;
+        Ok(Expr::Compare { field, op, value })
+    }
+
+    /// Read a literal and check that it suits `field`
+    fn value(&mut self, field: &Field) -> Result<Value> {
+        let at = self.position();
+        let token = self.next();
+        let value = match (field.kind, token) {
+            (FieldKind::Text, Some(Token::Str(s))) => Value::Text(s),
+            (FieldKind::Date, Some(Token::Date(d))) => Value::Date(d),
+            (FieldKind::Date, Some(Token::Str(s))) => {
+                Value::Date(NaiveDate::parse_from_str(&s, "%Y-%m-%d").map_err(|_| {
+                    Error::Invalid(format!(
+                        "Invalid date {:?} at position {}: expected YYYY-MM-DD",
+                        s, at
+                    ))
+                })?)
+            }
+            (FieldKind::Integer, Some(Token::Number(n))) => Value::Integer(n),
+            (FieldKind::Bool, Some(Token::Ident(s))) if s.eq_ignore_ascii_case("true") => {
+                Value::Bool(true)
+            }
+            (FieldKind::Bool, Some(Token::Ident(s))) if s.eq_ignore_ascii_case("false") => {
+                Value::Bool(false)
+            }
+            (kind, Some(_)) => {
+                self.pos -= 1;
+                let expected = match kind {
+                    FieldKind::Text => "a quoted string",
+                    FieldKind::Date => "a date (YYYY-MM-DD)",
+                    FieldKind::Integer => "a number",
+                    FieldKind::Bool => "true or false",
+                };
+                return Err(self.unexpected(&format!("{} expects {}", field.name, expected)));
+            }
+            (_, None) => {
+                return Err(Error::Invalid(format!(
+                    "Unexpected end of query: expected a value for {}",
+                    field.name
+                )))
+            }
+        };
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::{Milestone, Team};
+    use crate::db::{schema, ProjectRepository, TeamRepository};
+    use chrono::{TimeZone, Utc};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        schema::initialize_schema(&conn).unwrap();
+        schema::apply_migrations(&conn).unwrap();
+
+        TeamRepository::new(&conn)
+            .create(&Team::new("Platform".to_string()))
+            .unwrap();
+        let repo = ProjectRepository::new(&conn);
+        for (name, due) in [("Alpha", Some(1)), ("Beta", Some(5)), ("Gamma", None)] {
+            let mut project = Project::new(name.to_string());
+            project.project_type = "Team".to_string();
+            project.team = Some("Platform".to_string());
+            repo.create(&project).unwrap();
+            if let Some(month) = due {
+                let mut milestone = Milestone::new(project.id, 1, format!("{} M1", name));
+                milestone.due_date = Some(Utc.with_ymd_and_hms(2025, month, 15, 12, 0, 0).unwrap());
+                repo.add_milestone(&milestone).unwrap();
+            }
+        }
+        conn
+    }
+
+    fn names(projects: Vec<Project>) -> Vec<String> {
+        projects.into_iter().map(|p| p.name).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let expr = parse(
+            r#"type = "Team" AND team = 'Platform' AND milestone.due_date < 2025-04-01 AND milestone.technical_lead IS NULL"#,
+        )
+        .unwrap();
+        assert!(expr.uses_milestones());
+        let compiled = expr.compile();
+        assert_eq!(compiled.params.len(), 3);
+        assert!(compiled.sql.contains("LEFT JOIN milestones m"));
+
+        let expr = parse(
+            "NOT (blocked = true OR name IN (\"a\", \"b\")) and project.due_date >= \"2025-01-01\"",
+        )
+        .unwrap();
+        assert!(!expr.uses_milestones());
+        assert_eq!(expr.compile().params.len(), 4);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for bad in [
+            "",
+            "name",
+            "name =",
+            "name = Platform",
+            "due_date < \"soon\"",
+            "due_date < 2025-13-01",
+            "milestone.number = \"1\"",
+            "blocked > true",
+            "(name = \"a\"",
+            "name = \"a\" name = \"b\"",
+            "name IS \"a\"",
+        ] {
+            assert!(matches!(parse(bad), Err(Error::Invalid(_))), "{}", bad);
+        }
+
+        let deep = format!("{}name = \"a\"{}", "(".repeat(100), ")".repeat(100));
+        assert!(matches!(parse(&deep), Err(Error::Invalid(_))));
+        assert!(matches!(
+            parse(&format!("name = \"{}\"", "a".repeat(MAX_QUERY_LENGTH))),
+            Err(Error::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_unknown_fields() {
+        for field in [
+            "password",
+            "p.name",
+            "sqlite_master.sql",
+            "milestone.project_id",
+            "name;",
+            "description",
+        ] {
+            let err = parse(&format!("{} = \"x\"", field)).unwrap_err();
+            assert!(matches!(err, Error::Invalid(_)), "{}", field);
+        }
+        let err = parse("secret = \"x\"").unwrap_err().to_string();
+        assert!(err.contains("Unknown field 'secret'"));
+    }
+
+    #[test]
+    fn test_injection_attempts() {
+        let conn = setup_test_db();
+        let attacks = [
+            r#"name = "x\" OR 1=1 --""#,
+            r#"name = 'x\' OR \'1\'=\'1'"#,
+            r#"name = "Alpha'; DROP TABLE projects; --""#,
+            r#"name = "\\\" OR name != \"""#,
+            r#"name IN ("x"); DELETE FROM projects; --")"#,
+        ];
+        for attack in attacks {
+            match parse(attack) {
+                Ok(expr) => {
+                    let compiled = expr.compile();
+                    assert!(!compiled.sql.contains("DROP"));
+                    assert!(!compiled.sql.contains("1=1"));
+                    assert!(run_query(&conn, attack).unwrap().len() < 3, "{}", attack);
+                }
+                Err(e) => assert!(matches!(e, Error::Invalid(_)), "{}", attack),
+            }
+        }
+        // The quoted text is one value, not SQL
+        assert_eq!(
+            parse(r#"name = "x\" OR 1=1 --""#).unwrap().compile().params,
+            vec![SqlValue::Text("x\" OR 1=1 --".to_string())]
+        );
+        for attack in [
+            "name = \"a\"; DROP TABLE projects",
+            "name = \"a\" -- comment",
+            "name = \"a\" /* */",
+            "name = 'unterminated",
+            "name = \"bad \\n escape\"",
+        ] {
+            assert!(
+                matches!(parse(attack), Err(Error::Invalid(_))),
+                "{}",
+                attack
+            );
+        }
+        assert_eq!(ProjectRepository::new(&conn).list_all().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_run_query() {
+        let conn = setup_test_db();
+
+        let found = run_query(
+            &conn,
+            "type = \"team\" AND team = \"Platform\" AND milestone.due_date < 2025-04-01 AND milestone.technical_lead IS NULL",
+        )
+        .unwrap();
+        assert_eq!(names(found), vec!["Alpha"]);
+
+        // Milestone conditions don't match projects without milestones
+        let found = run_query(&conn, "milestone.technical_lead IS NULL").unwrap();
+        assert_eq!(names(found), vec!["Alpha", "Beta"]);
+
+        let found = run_query(&conn, "due_date IS NULL AND NOT name = \"Beta\"").unwrap();
+        assert_eq!(names(found), vec!["Alpha", "Gamma"]);
+
+        let found = run_query(
+            &conn,
+            "milestone.due_date = 2025-05-15 OR name IN ('gamma')",
+        )
+        .unwrap();
+        assert_eq!(names(found), vec!["Beta", "Gamma"]);
+
+        let found = run_query(&conn, "blocked = false AND milestone.number >= 1").unwrap();
+        assert_eq!(names(found), vec!["Alpha", "Beta"]);
+    }
+}
@@ -0,0 +1,205 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+use super::error::{Error, Result};
+use super::{get_datetime, get_uuid};
+use super::models::{Project, Retrospective};
+use super::project_repo::{project_from_row, PROJECT_COLUMNS};
+use crate::utils::dt_to_db;
+use rusqlite::{params, Connection, OptionalExtension};
+use uuid::Uuid;
+
+/// Columns selected for a retrospective, in the order `retrospective_from_row` expects
+const RETROSPECTIVE_COLUMNS: &str =
+    "id, project_id, went_well, needs_improvement, action_summary, completed_on, created_at, updated_at";
+
+/// Map a row selected with `RETROSPECTIVE_COLUMNS` to a retrospective
+fn retrospective_from_row(row: &rusqlite::Row) -> rusqlite::Result<Retrospective> {
+    Ok(Retrospective {
+        id: get_uuid(row, 0)?,
+        project_id: get_uuid(row, 1)?,
+        went_well: row.get(2)?,
+        needs_improvement: row.get(3)?,
+        action_summary: row.get(4)?,
+        completed_on: get_datetime(row, 5)?,
+        created_at: get_datetime(row, 6)?,
+        updated_at: get_datetime(row, 7)?,
+    })
+}
+
+/// Blank text is stored as NULL so "nothing recorded" has one representation
+fn non_blank(text: &Option<String>) -> Option<&str> {
+    text.as_deref().filter(|text| !text.trim().is_empty())
+}
+
+/// Repository for the retrospectives of finished projects
+pub struct RetrospectiveRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> RetrospectiveRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Save a project's retrospective, replacing the one it already has
+    ///
+    /// A project has one retrospective, so saving again updates it in place:
+    /// the original ID and creation time are kept. Returns the retrospective
+    /// as stored.
+    pub fn save(&self, retrospective: &Retrospective) -> Result<Retrospective> {
+        self.conn
+            .prepare_cached(
+                "INSERT INTO retrospectives
+                     (id, project_id, went_well, needs_improvement, action_summary, completed_on, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                 ON CONFLICT (project_id) DO UPDATE SET
+                     went_well = excluded.went_well,
+                     needs_improvement = excluded.needs_improvement,
+                     action_summary = excluded.action_summary,
+                     completed_on = excluded.completed_on,
+                     updated_at = excluded.updated_at",
+            )?
+            .execute(params![
+                retrospective.id.to_string(),
+                retrospective.project_id.to_string(),
+                non_blank(&retrospective.went_well),
+                non_blank(&retrospective.needs_improvement),
+                non_blank(&retrospective.action_summary),
+                dt_to_db(retrospective.completed_on),
+                dt_to_db(retrospective.created_at),
+                dt_to_db(retrospective.updated_at),
+            ])
+            .map_err(|e| Error::from(e).with_field("project_id"))?;
+        log::debug!("Saved retrospective for project {}", retrospective.project_id);
+        self.find_by_project(&retrospective.project_id)?
+            .ok_or_else(|| Error::not_found("Retrospective", retrospective.project_id))
+    }
+
+    /// Find a project's retrospective
+    pub fn find_by_project(&self, project_id: &Uuid) -> Result<Option<Retrospective>> {
+        let retrospective = self
+            .conn
+            .prepare_cached(&format!(
+                "SELECT {} FROM retrospectives WHERE project_id = ?1",
+                RETROSPECTIVE_COLUMNS
+            ))?
+            .query_row(params![project_id.to_string()], retrospective_from_row)
+            .optional()?;
+        Ok(retrospective)
+    }
+
+    /// Delete a project's retrospective
+    pub fn delete(&self, project_id: &Uuid) -> Result<()> {
+        let rows = self
+            .conn
+            .prepare_cached("DELETE FROM retrospectives WHERE project_id = ?1")?
+            .execute(params![project_id.to_string()])?;
+
+        if rows == 0 {
+            return Err(Error::not_found("Retrospective", project_id));
+        }
+        log::debug!("Deleted retrospective for project {}", project_id);
+        Ok(())
+    }
+
+    /// Completed projects that don't have a retrospective yet, by name
+    pub fn list_projects_missing(&self) -> Result<Vec<Project>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {}
+             FROM projects p
+             WHERE status = 'done'
+               AND NOT EXISTS (SELECT 1 FROM retrospectives r WHERE r.project_id = p.id)
+             ORDER BY name, id",
+            PROJECT_COLUMNS
+        ))?;
+        let projects = stmt
+            .query_map([], project_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(projects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{self, ProjectRepository, ProjectStatus};
+    use chrono::{TimeZone, Utc};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_save_retrospective_upserts() {
+        let conn = setup_test_db();
+        let projects = ProjectRepository::new(&conn);
+        let project = Project::new("Apollo".to_string());
+        projects.create(&project).unwrap();
+
+        let repo = RetrospectiveRepository::new(&conn);
+        assert!(repo.find_by_project(&project.id).unwrap().is_none());
+
+        let held = Utc.with_ymd_and_hms(2025, 6, 30, 0, 0, 0).unwrap();
+        let mut first = Retrospective::new(project.id, held);
+        first.went_well = Some("Shipped on time".to_string());
+        first.needs_improvement = Some("  ".to_string());
+        let saved = repo.save(&first).unwrap();
+        assert_eq!(saved.id, first.id);
+        assert_eq!(saved.went_well.as_deref(), Some("Shipped on time"));
+        assert_eq!(saved.needs_improvement, None);
+
+        // Saving again for the same project updates the existing retrospective
+        let mut second = Retrospective::new(project.id, held + chrono::Duration::days(1));
+        second.needs_improvement = Some("Earlier load testing".to_string());
+        second.action_summary = Some("Load test before the beta".to_string());
+        let updated = repo.save(&second).unwrap();
+        assert_eq!(updated.id, first.id);
+        assert_eq!(updated.created_at, saved.created_at);
+        assert_eq!(updated.went_well, None);
+        assert_eq!(updated.needs_improvement.as_deref(), Some("Earlier load testing"));
+        assert_eq!(updated.completed_on, second.completed_on);
+        assert_eq!(repo.find_by_project(&project.id).unwrap(), Some(updated));
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM retrospectives", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+
+        // It travels with the project bundle
+        let bundle = projects.get_project_bundle(&project.id).unwrap();
+        assert_eq!(bundle.retrospective.map(|r| r.id), Some(first.id));
+
+        assert!(matches!(
+            repo.save(&Retrospective::new(Uuid::new_v4(), held)),
+            Err(Error::ForeignKeyViolation { .. })
+        ));
+
+        repo.delete(&project.id).unwrap();
+        assert!(matches!(repo.delete(&project.id), Err(Error::NotFound { .. })));
+    }
+
+    #[test]
+    fn test_projects_missing_retrospective() {
+        let conn = setup_test_db();
+        let projects = ProjectRepository::new(&conn);
+        let mut apollo = Project::new("Apollo".to_string());
+        apollo.status = ProjectStatus::Done;
+        projects.create(&apollo).unwrap();
+        let gemini = Project::new("Gemini".to_string());
+        projects.create(&gemini).unwrap();
+
+        let repo = RetrospectiveRepository::new(&conn);
+        let missing = repo.list_projects_missing().unwrap();
+        assert_eq!(missing.iter().map(|p| p.id).collect::<Vec<_>>(), [apollo.id]);
+
+        // Saving a retrospective clears the reminder
+        repo.save(&Retrospective::new(gemini.id, Utc::now())).unwrap();
+        repo.save(&Retrospective::new(apollo.id, Utc::now())).unwrap();
+        assert!(repo.list_projects_missing().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,325 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+use super::begin_or_join;
+use super::error::{Error, Result};
+use super::models::{RoleUsage, RoleVariants};
+use crate::utils::{dt_to_db, normalize_name};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+
+/// Roles stored on project resources, milestone resources and stakeholders, with their uses
+const ROLES_IN_USE_SQL: &str = "
+    SELECT role, COUNT(*) FROM (
+        SELECT role FROM project_resources
+        UNION ALL SELECT role FROM milestone_resources
+        UNION ALL SELECT role FROM project_stakeholders
+    )
+    WHERE role IS NOT NULL
+    GROUP BY role
+    ORDER BY COUNT(*) DESC, role";
+
+/// Tables with a role column
+const ROLE_TABLES: [&str; 3] = ["project_resources", "milestone_resources", "project_stakeholders"];
+
+/// Key that spellings of the same role share, ignoring case, spacing and punctuation
+pub fn role_key(role: &str) -> String {
+    normalize_name(role)
+}
+
+/// Trim a role and collapse runs of whitespace, or `None` if nothing is left
+fn clean_role(role: &str) -> Option<String> {
+    let cleaned = role.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!cleaned.is_empty()).then_some(cleaned)
+}
+
+/// Role repository: the canonical spelling of each role
+///
+/// Roles are written in many ways ("Tech Lead", "tech-lead"), so writes go
+/// through [`normalize`](Self::normalize), which maps every spelling to the
+/// canonical one stored in the `roles` table.
+pub struct RoleRepository<'a> {
+    conn: &'a Connection,
+    known_roles: &'a [String],
+    allow_new: bool,
+}
+
+impl<'a> RoleRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        Self {
+            conn,
+            known_roles: &[],
+            allow_new: true,
+        }
+    }
+
+    /// Accept the configured roles, and other roles only when `allow_new` is set
+    ///
+    /// Configured roles become canonical the first time they're used. A
+    /// repository created without them accepts any role.
+    pub fn with_roles(mut self, known_roles: &'a [String], allow_new: bool) -> Self {
+        self.known_roles = known_roles;
+        self.allow_new = allow_new;
+        self
+    }
+
+    /// Find the canonical spelling stored for a role
+    pub fn find_canonical(&self, role: &str) -> Result<Option<String>> {
+        let mut stmt = self.conn.prepare_cached("SELECT name FROM roles WHERE key = ?1")?;
+        let name = stmt.query_row(params![role_key(role)], |row| row.get(0)).optional()?;
+        Ok(name)
+    }
+
+    /// Map a role to its canonical spelling, adding it as a new role if needed
+    ///
+    /// A blank role is no role. An unknown role is an error unless new
+    /// roles are allowed, in which case its trimmed spelling becomes canonical.
+    pub fn normalize(&self, role: Option<&str>) -> Result<Option<String>> {
+        let Some(cleaned) = role.and_then(clean_role) else {
+            return Ok(None);
+        };
+        if let Some(name) = self.find_canonical(&cleaned)? {
+            return Ok(Some(name));
+        }
+
+        let key = role_key(&cleaned);
+        let name = match self.known_roles.iter().find(|known| role_key(known) == key) {
+            Some(known) => clean_role(known).unwrap_or(cleaned),
+            None if self.allow_new => cleaned,
+            None => {
+                return Err(Error::Invalid(format!(
+                    "Unknown role '{}': expected one of {}",
+                    cleaned,
+                    self.list_roles()?.join(", ")
+                )))
+            }
+        };
+        self.conn.execute(
+            "INSERT INTO roles (name, key, created_at) VALUES (?1, ?2, ?3)",
+            params![&name, &key, dt_to_db(Utc::now())],
+        )?;
+        log::debug!("Added role: {}", name);
+        Ok(Some(name))
+    }
+
+    /// List the canonical roles and the configured ones not used yet, by name
+    pub fn list_roles(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare_cached("SELECT name FROM roles")?;
+        let mut roles: Vec<String> = stmt.query_map([], |row| row.get(0))?.collect::<rusqlite::Result<_>>()?;
+        for known in self.known_roles.iter().filter_map(|known| clean_role(known)) {
+            if !roles.iter().any(|role| role_key(role) == role_key(&known)) {
+                roles.push(known);
+            }
+        }
+        roles.sort_by_key(|role| role.to_lowercase());
+        Ok(roles)
+    }
+
+    /// List the roles stored on resources and stakeholders, most used first
+    ///
+    /// Each spelling is listed separately, so variants of one role show up
+    /// side by side.
+    pub fn list_roles_in_use(&self) -> Result<Vec<RoleUsage>> {
+        let mut stmt = self.conn.prepare_cached(ROLES_IN_USE_SQL)?;
+        let roles = stmt
+            .query_map([], |row| {
+                Ok(RoleUsage {
+                    role: row.get(0)?,
+                    uses: row.get::<_, i64>(1)? as usize,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(roles)
+    }
+
+    /// Find roles in use under more than one spelling
+    ///
+    /// Nothing is changed; [`merge_roles`](Self::merge_roles) cleans them up.
+    pub fn find_variants(&self) -> Result<Vec<RoleVariants>> {
+        let mut groups: Vec<(String, Vec<RoleUsage>)> = Vec::new();
+        for usage in self.list_roles_in_use()? {
+            let key = role_key(&usage.role);
+            match groups.iter_mut().find(|(group_key, _)| *group_key == key) {
+                Some((_, variants)) => variants.push(usage),
+                None => groups.push((key, vec![usage])),
+            }
+        }
+
+        let mut found = Vec::new();
+        for (_, variants) in groups.into_iter().filter(|(_, variants)| variants.len() > 1) {
+            let canonical = match self.find_canonical(&variants[0].role)? {
+                Some(name) => name,
+                None => variants[0].role.clone(),
+            };
+            found.push(RoleVariants { canonical, variants });
+        }
+        found.sort_by_key(|group| group.canonical.to_lowercase());
+        Ok(found)
+    }
+
+    /// Suggest canonical roles matching what has been typed, most used first
+    ///
+    /// A role matches when one of its words starts with `prefix`, ignoring
+    /// case and punctuation. An empty prefix matches every role.
+    pub fn autocomplete(&self, prefix: &str, limit: usize) -> Result<Vec<String>> {
+        let prefix = role_key(prefix);
+        let mut uses: HashMap<String, usize> = HashMap::new();
+        for usage in self.list_roles_in_use()? {
+            *uses.entry(role_key(&usage.role)).or_default() += usage.uses;
+        }
+
+        let mut matches: Vec<(usize, String)> = self
+            .list_roles()?
+            .into_iter()
+            .filter(|role| {
+                let key = role_key(role);
+                key.starts_with(&prefix) || key.contains(&format!(" {}", prefix))
+            })
+            .map(|role| (uses.get(&role_key(&role)).copied().unwrap_or(0), role))
+            .collect();
+        matches.sort_by(|(a_uses, a), (b_uses, b)| b_uses.cmp(a_uses).then_with(|| a.to_lowercase().cmp(&b.to_lowercase())));
+        Ok(matches.into_iter().take(limit).map(|(_, role)| role).collect())
+    }
+
+    /// Replace a role with another on every resource and stakeholder
+    ///
+    /// Every spelling of `old` is replaced with the canonical spelling of
+    /// `new`, and `old` stops being a role of its own. Returns the number of
+    /// assignments changed. Fails if `old` is neither a role nor in use.
+    pub fn merge_roles(&self, old: &str, new: &str) -> Result<usize> {
+        let tx = begin_or_join(self.conn)?;
+
+        let old_key = role_key(old);
+        let known: bool = self
+            .conn
+            .query_row("SELECT EXISTS(SELECT 1 FROM roles WHERE key = ?1)", params![&old_key], |row| row.get(0))?;
+        let in_use = self.list_roles_in_use()?.iter().any(|usage| role_key(&usage.role) == old_key);
+        if !known && !in_use {
+            return Err(Error::not_found("Role", old));
+        }
+
+        let Some(new) = self.normalize(Some(new))? else {
+            return Err(Error::Invalid("The role to merge into can't be blank".to_string()));
+        };
+        let new_key = role_key(&new);
+
+        let mut changed = 0;
+        for table in ROLE_TABLES {
+            let roles: Vec<String> = self
+                .conn
+                .prepare(&format!("SELECT DISTINCT role FROM {} WHERE role IS NOT NULL", table))?
+                .query_map([], |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()?;
+            for role in roles.into_iter().filter(|role| role_key(role) == old_key && *role != new) {
+                changed += self.conn.execute(
+                    &format!("UPDATE {} SET role = ?1 WHERE role = ?2", table),
+                    params![&new, &role],
+                )?;
+            }
+        }
+        if old_key != new_key {
+            self.conn.execute("DELETE FROM roles WHERE key = ?1", params![&old_key])?;
+        }
+
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+        log::debug!("Merged role {} into {}: {} assignments changed", old, new, changed);
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{self, Milestone, Person, PersonRepository, Project, ProjectRepository, ProjectResource, ProjectStakeholder};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_normalize() {
+        let conn = setup_test_db();
+        let known = vec!["Technical Lead".to_string(), "Developer".to_string()];
+        let repo = RoleRepository::new(&conn).with_roles(&known, false);
+
+        assert_eq!(repo.normalize(Some("  technical-lead ")).unwrap().as_deref(), Some("Technical Lead"));
+        assert_eq!(repo.normalize(Some("TECHNICAL  LEAD")).unwrap().as_deref(), Some("Technical Lead"));
+        assert_eq!(repo.normalize(Some("  ")).unwrap(), None);
+        assert_eq!(repo.normalize(None).unwrap(), None);
+        assert!(matches!(repo.normalize(Some("Tech Lead")), Err(Error::Invalid(_))));
+
+        // New roles become canonical with the first spelling used
+        let repo = RoleRepository::new(&conn).with_roles(&known, true);
+        assert_eq!(repo.normalize(Some("Tech  Lead")).unwrap().as_deref(), Some("Tech Lead"));
+        assert_eq!(repo.normalize(Some("tech_lead")).unwrap().as_deref(), Some("Tech Lead"));
+        assert_eq!(repo.list_roles().unwrap(), ["Developer", "Tech Lead", "Technical Lead"]);
+    }
+
+    #[test]
+    fn test_variants_autocomplete_and_merge() {
+        let conn = setup_test_db();
+        let people = PersonRepository::new(&conn);
+        let projects = ProjectRepository::new(&conn);
+        let project = Project::new("Apollo".to_string());
+        projects.create(&project).unwrap();
+        let milestone = Milestone::new(project.id, 1, "Beta".to_string());
+        projects.add_milestone(&milestone).unwrap();
+
+        // Variants written before roles were normalized
+        for (email, role) in [("a@example.com", "Tech Lead"), ("b@example.com", "tech-lead"), ("c@example.com", "TL")] {
+            people.create(&Person::new(email.to_string(), email.to_string())).unwrap();
+            conn.execute(
+                "INSERT INTO project_resources (project_id, person_email, role, created_at) VALUES (?1, ?2, ?3, ?4)",
+                params![project.id.to_string(), email, role, dt_to_db(Utc::now())],
+            )
+            .unwrap();
+        }
+        let mut stakeholder = ProjectStakeholder::new(project.id, "c@example.com".to_string());
+        stakeholder.role = Some("Sponsor".to_string());
+        projects.add_stakeholder(&project.id, &stakeholder).unwrap();
+
+        // The migration makes the most used spelling canonical
+        let repo = RoleRepository::new(&conn);
+        repo.normalize(Some("Tech Lead")).unwrap();
+        let variants = repo.find_variants().unwrap();
+        assert_eq!(variants.len(), 1);
+        assert_eq!(variants[0].canonical, "Tech Lead");
+        assert_eq!(
+            variants[0].variants.iter().map(|v| v.role.as_str()).collect::<Vec<_>>(),
+            ["Tech Lead", "tech-lead"]
+        );
+
+        // Writes use the canonical spelling
+        let mut resource = ProjectResource::new(project.id, "b@example.com".to_string());
+        resource.role = Some("TECH LEAD".to_string());
+        projects.update_project_resource(&project.id, &resource).unwrap();
+        let mut milestone_resource = db::MilestoneResource::new(milestone.id, "a@example.com".to_string());
+        milestone_resource.role = Some("sponsor".to_string());
+        projects.add_milestone_resource(&milestone.id, &milestone_resource).unwrap();
+        assert_eq!(projects.get_milestone_resources(&milestone.id).unwrap()[0].role.as_deref(), Some("Sponsor"));
+        assert!(repo.find_variants().unwrap().is_empty());
+
+        assert_eq!(repo.autocomplete("le", 10).unwrap(), ["Tech Lead"]);
+        assert_eq!(repo.autocomplete("", 5).unwrap(), ["Sponsor", "Tech Lead"]);
+
+        assert_eq!(repo.merge_roles("tl", "Technical Lead").unwrap(), 1);
+        assert_eq!(repo.merge_roles("Tech Lead", "Technical Lead").unwrap(), 2);
+        let in_use = repo.list_roles_in_use().unwrap();
+        assert_eq!(
+            in_use,
+            [
+                RoleUsage { role: "Technical Lead".to_string(), uses: 3 },
+                RoleUsage { role: "Sponsor".to_string(), uses: 2 },
+            ]
+        );
+        assert_eq!(repo.list_roles().unwrap(), ["Sponsor", "Technical Lead"]);
+        assert!(matches!(repo.merge_roles("Tech Lead", "Technical Lead"), Err(Error::NotFound { .. })));
+    }
+}
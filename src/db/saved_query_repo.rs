@@ -0,0 +1,159 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+use super::error::{Error, Result};
+use super::get_datetime;
+use super::models::{Project, SavedQuery};
+use super::query;
+use crate::utils::dt_to_db;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Columns selected for a saved query, in the order `saved_query_from_row` expects
+const SAVED_QUERY_COLUMNS: &str = "name, expression, created_at, updated_at";
+
+/// Map a row selected with `SAVED_QUERY_COLUMNS` to a saved query
+fn saved_query_from_row(row: &rusqlite::Row) -> rusqlite::Result<SavedQuery> {
+    Ok(SavedQuery {
+        name: row.get(0)?,
+        expression: row.get(1)?,
+        created_at: get_datetime(row, 2)?,
+        updated_at: get_datetime(row, 3)?,
+    })
+}
+
+/// Saved query repository for named filter expressions
+pub struct SavedQueryRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> SavedQueryRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Save an expression under a name, replacing any query already saved with it
+    ///
+    /// The expression is parsed first, so only valid queries are ever saved.
+    pub fn save(&self, name: &str, expression: &str) -> Result<SavedQuery> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(Error::Invalid(
+                "Saved query name cannot be empty".to_string(),
+            ));
+        }
+        query::parse(expression)?;
+
+        let now = dt_to_db(Utc::now());
+        self.conn
+            .prepare_cached(
+                "INSERT INTO saved_queries (name, expression, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?3)
+                 ON CONFLICT(name) DO UPDATE SET expression = excluded.expression, updated_at = excluded.updated_at",
+            )?
+            .execute(params![name, expression, now])?;
+        log::debug!("Saved query: {}", name);
+
+        self.find_by_name(name)?
+            .ok_or_else(|| Error::not_found("Saved query", name))
+    }
+
+    /// Find a saved query by name
+    pub fn find_by_name(&self, name: &str) -> Result<Option<SavedQuery>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM saved_queries WHERE name = ?1",
+            SAVED_QUERY_COLUMNS
+        ))?;
+        let saved = stmt
+            .query_row(params![name.trim()], saved_query_from_row)
+            .optional()?;
+        Ok(saved)
+    }
+
+    /// List saved queries by name
+    pub fn list_all(&self) -> Result<Vec<SavedQuery>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM saved_queries ORDER BY name",
+            SAVED_QUERY_COLUMNS
+        ))?;
+
+        let saved = stmt
+            .query_map([], saved_query_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(saved)
+    }
+
+    /// Delete a saved query
+    pub fn delete(&self, name: &str) -> Result<()> {
+        let rows = self
+            .conn
+            .prepare_cached("DELETE FROM saved_queries WHERE name = ?1")?
+            .execute(params![name.trim()])?;
+
+        if rows == 0 {
+            return Err(Error::not_found("Saved query", name));
+        }
+
+        Ok(())
+    }
+
+    /// Run a saved query and return the projects it matches
+    pub fn run(&self, name: &str) -> Result<Vec<Project>> {
+        let saved = self
+            .find_by_name(name)?
+            .ok_or_else(|| Error::not_found("Saved query", name))?;
+        query::run_query(self.conn, &saved.expression)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{schema, ProjectRepository};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        schema::initialize_schema(&conn).unwrap();
+        schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_saved_queries() {
+        let conn = setup_test_db();
+        let repo = SavedQueryRepository::new(&conn);
+        ProjectRepository::new(&conn)
+            .create(&Project::new("Launch".to_string()))
+            .unwrap();
+
+        let saved = repo.save("  unblocked ", "blocked = false").unwrap();
+        assert_eq!(saved.name, "unblocked");
+        assert_eq!(repo.run("unblocked").unwrap().len(), 1);
+
+        // Saving again replaces the expression but keeps the creation time
+        let updated = repo.save("unblocked", "blocked = true").unwrap();
+        assert_eq!(updated.created_at, saved.created_at);
+        assert!(repo.run("unblocked").unwrap().is_empty());
+        assert_eq!(repo.list_all().unwrap().len(), 1);
+
+        // Invalid expressions and names are never saved
+        assert!(matches!(
+            repo.save("bad", "secret = \"x\""),
+            Err(Error::Invalid(_))
+        ));
+        assert!(matches!(
+            repo.save(" ", "blocked = true"),
+            Err(Error::Invalid(_))
+        ));
+        assert!(repo.find_by_name("bad").unwrap().is_none());
+
+        repo.delete("unblocked").unwrap();
+        assert!(matches!(
+            repo.delete("unblocked"),
+            Err(Error::NotFound { .. })
+        ));
+        assert!(matches!(repo.run("unblocked"), Err(Error::NotFound { .. })));
+    }
+}
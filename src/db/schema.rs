@@ -2,8 +2,10 @@
 //
 // SPDX-License-Identifier: MIT
 
-use anyhow::Result;
-use rusqlite::Connection;
+use crate::utils::{dt_from_db, dt_to_db};
+use anyhow::{bail, Result};
+use rusqlite::{params, types::Value, Connection};
+use serde::Serialize;
 
 /// Initialize the database schema
 pub fn initialize_schema(conn: &Connection) -> Result<()> {
@@ -170,6 +172,20 @@ pub fn initialize_schema(conn: &Connection) -> Result<()> {
         [],
     )?;
 
+    // Create project_documents table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS project_documents (
+            id TEXT PRIMARY KEY NOT NULL,
+            project_id TEXT NOT NULL,
+            title TEXT NOT NULL,
+            url TEXT NOT NULL,
+            doc_type TEXT,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
     // Create milestone_notes table
     conn.execute(
         "CREATE TABLE IF NOT EXISTS milestone_notes (
@@ -199,6 +215,20 @@ pub fn initialize_schema(conn: &Connection) -> Result<()> {
         [],
     )?;
 
+    // Create date_history table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS date_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            entity_type TEXT NOT NULL,
+            entity_id TEXT NOT NULL,
+            field TEXT NOT NULL,
+            old_value TEXT,
+            new_value TEXT,
+            changed_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
     // Create schema_version table for migrations
     conn.execute(
         "CREATE TABLE IF NOT EXISTS schema_version (
@@ -229,9 +259,77 @@ pub fn get_schema_version(conn: &Connection) -> Result<i32> {
     Ok(version)
 }
 
+/// Highest schema version this build knows how to migrate to and use
+pub const SUPPORTED_SCHEMA_VERSION: i32 = 53;
+
+/// A database's schema version alongside the newest one this build supports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct SchemaInfo {
+    /// Version the database is at
+    pub current_version: i32,
+
+    /// Highest version this build supports
+    pub supported_version: i32,
+}
+
+impl SchemaInfo {
+    fn new(current_version: i32) -> Self {
+        Self {
+            current_version,
+            supported_version: SUPPORTED_SCHEMA_VERSION,
+        }
+    }
+
+    /// Whether the database was written by a newer build
+    pub fn is_newer_than_supported(&self) -> bool {
+        self.current_version > self.supported_version
+    }
+
+    /// Whether opening the database will apply migrations
+    pub fn has_pending_migrations(&self) -> bool {
+        self.current_version < self.supported_version
+    }
+}
+
+/// Get the schema version of an initialized database alongside the supported version
+pub fn get_schema_info(conn: &Connection) -> Result<SchemaInfo> {
+    Ok(SchemaInfo::new(get_schema_version(conn)?))
+}
+
+/// Get the schema version of a database without initializing it
+///
+/// Returns `None` for a database that has never been initialized.
+pub fn read_schema_version(conn: &Connection) -> Result<Option<i32>> {
+    let initialized: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'schema_version'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !initialized {
+        return Ok(None);
+    }
+    Ok(Some(get_schema_version(conn)?))
+}
+
+/// Refuse a database written by a newer build
+///
+/// An older build would otherwise work until a query touched a column or
+/// table it doesn't know about.
+pub fn ensure_supported(version: i32) -> Result<()> {
+    if version > SUPPORTED_SCHEMA_VERSION {
+        bail!(
+            "Database schema version {} is newer than this build supports (up to {}); upgrade Project Tracker to open it",
+            version,
+            SUPPORTED_SCHEMA_VERSION
+        );
+    }
+    Ok(())
+}
+
 /// Apply migrations to bring database schema up to date
 pub fn apply_migrations(conn: &Connection) -> Result<()> {
     let current_version = get_schema_version(conn)?;
+    ensure_supported(current_version)?;
 
     // Migration to version 2: Add type field to projects
     if current_version < 2 {
@@ -478,160 +576,1868 @@ pub fn apply_migrations(conn: &Connection) -> Result<()> {
         )?;
     }
 
-    log::info!("Database migrations complete");
-    Ok(())
-}
+    // Migration to version 7: Add recurrence columns to milestones
+    if current_version < 7 {
+        log::info!("Applying migration to version 7: Adding recurrence columns to milestones");
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use rusqlite::Connection;
+        // Check and add recurrence_rule to milestones
+        let has_recurrence_rule: bool = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('milestones') WHERE name='recurrence_rule'",
+                [],
+                |row| {
+                    let count: i32 = row.get(0)?;
+                    Ok(count > 0)
+                },
+            )?;
 
-    // Schema initialization tests
+        if !has_recurrence_rule {
+            conn.execute(
+                "ALTER TABLE milestones ADD COLUMN recurrence_rule TEXT",
+                [],
+            )?;
+        }
 
-    #[test]
-    fn test_initialize_schema() {
-        let conn = Connection::open_in_memory().unwrap();
-        initialize_schema(&conn).unwrap();
+        // Check and add series_id to milestones. Materialized occurrences point
+        // back at the milestone that defines the series, and are kept (detached)
+        // if that milestone is deleted.
+        let has_series_id: bool = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('milestones') WHERE name='series_id'",
+                [],
+                |row| {
+                    let count: i32 = row.get(0)?;
+                    Ok(count > 0)
+                },
+            )?;
 
-        // Verify tables exist
-        let tables: Vec<String> = conn
-            .prepare("SELECT name FROM sqlite_master WHERE type='table' ORDER BY name")
-            .unwrap()
-            .query_map([], |row| row.get(0))
-            .unwrap()
-            .collect::<Result<Vec<_>, _>>()
-            .unwrap();
+        if !has_series_id {
+            conn.execute(
+                "ALTER TABLE milestones ADD COLUMN series_id TEXT REFERENCES milestones(id) ON DELETE SET NULL",
+                [],
+            )?;
+        }
 
-        assert!(tables.contains(&"people".to_string()));
-        assert!(tables.contains(&"teams".to_string()));
-        assert!(tables.contains(&"team_members".to_string()));
-        assert!(tables.contains(&"projects".to_string()));
-        assert!(tables.contains(&"milestones".to_string()));
-        assert!(tables.contains(&"project_stakeholders".to_string()));
-        assert!(tables.contains(&"project_resources".to_string()));
-        assert!(tables.contains(&"milestone_resources".to_string()));
-        assert!(tables.contains(&"project_notes".to_string()));
-        assert!(tables.contains(&"milestone_notes".to_string()));
-        assert!(tables.contains(&"stakeholder_notes".to_string()));
-        assert!(tables.contains(&"schema_version".to_string()));
-    }
+        // Check and add series_index to milestones
+        let has_series_index: bool = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('milestones') WHERE name='series_index'",
+                [],
+                |row| {
+                    let count: i32 = row.get(0)?;
+                    Ok(count > 0)
+                },
+            )?;
 
-    #[test]
-    fn test_initialize_schema_idempotent() {
-        let conn = Connection::open_in_memory().unwrap();
+        if !has_series_index {
+            conn.execute(
+                "ALTER TABLE milestones ADD COLUMN series_index INTEGER",
+                [],
+            )?;
+        }
 
-        // Initialize twice - should not error
-        initialize_schema(&conn).unwrap();
-        initialize_schema(&conn).unwrap();
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_milestones_series_id ON milestones(series_id)",
+            [],
+        )?;
 
-        let version = get_schema_version(&conn).unwrap();
-        assert_eq!(version, 1);
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (7, datetime('now'))",
+            [],
+        )?;
     }
 
-    #[test]
-    fn test_schema_version() {
-        let conn = Connection::open_in_memory().unwrap();
-        initialize_schema(&conn).unwrap();
+    // Migration to version 8: Normalize stored timestamps to RFC3339
+    if current_version < 8 {
+        log::info!("Applying migration to version 8: Normalizing stored timestamps to RFC3339");
 
-        let version = get_schema_version(&conn).unwrap();
-        assert_eq!(version, 1);
-    }
+        normalize_datetime_columns(conn)?;
 
-    // Migration tests
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (8, datetime('now'))",
+            [],
+        )?;
+    }
 
-    #[test]
-    fn test_apply_migrations() {
-        let conn = Connection::open_in_memory().unwrap();
-        initialize_schema(&conn).unwrap();
+    // Migration to version 9: Add active flag to people
+    if current_version < 9 {
+        log::info!("Applying migration to version 9: Adding active column to people");
 
-        // Initial version should be 1
-        let version = get_schema_version(&conn).unwrap();
-        assert_eq!(version, 1);
+        // Check and add active to people
+        let has_active: bool = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('people') WHERE name='active'",
+                [],
+                |row| {
+                    let count: i32 = row.get(0)?;
+                    Ok(count > 0)
+                },
+            )?;
 
-        // Apply migrations
-        apply_migrations(&conn).unwrap();
+        if !has_active {
+            conn.execute(
+                "ALTER TABLE people ADD COLUMN active INTEGER NOT NULL DEFAULT 1",
+                [],
+            )?;
+        }
 
-        // Should now be at version 6 (latest)
-        let version = get_schema_version(&conn).unwrap();
-        assert_eq!(version, 6);
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (9, datetime('now'))",
+            [],
+        )?;
     }
 
-    #[test]
-    fn test_apply_migrations_idempotent() {
-        let conn = Connection::open_in_memory().unwrap();
-        initialize_schema(&conn).unwrap();
+    if current_version < 10 {
+        log::info!("Applying migration to version 10: Adding case-insensitive project name index");
 
-        // Apply migrations twice - should not error
-        apply_migrations(&conn).unwrap();
-        apply_migrations(&conn).unwrap();
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_projects_name_nocase ON projects(name COLLATE NOCASE)",
+            [],
+        )?;
 
-        let version = get_schema_version(&conn).unwrap();
-        assert_eq!(version, 6);
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (10, datetime('now'))",
+            [],
+        )?;
     }
 
-    #[test]
-    fn test_migration_to_version_2_adds_type_column() {
-        let conn = Connection::open_in_memory().unwrap();
-        initialize_schema(&conn).unwrap();
+    // Migration to version 11: Add project_documents table
+    if current_version < 11 {
+        log::info!("Applying migration to version 11: Adding project documents table");
 
-        // Apply migrations
-        apply_migrations(&conn).unwrap();
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS project_documents (
+                id TEXT PRIMARY KEY NOT NULL,
+                project_id TEXT NOT NULL,
+                title TEXT NOT NULL,
+                url TEXT NOT NULL,
+                doc_type TEXT,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
 
-        // Verify type column exists in projects table
-        let columns: Vec<String> = conn
-            .prepare("PRAGMA table_info(projects)")
-            .unwrap()
-            .query_map([], |row| row.get(1))
-            .unwrap()
-            .collect::<Result<Vec<_>, _>>()
-            .unwrap();
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_project_documents_project_id ON project_documents(project_id)",
+            [],
+        )?;
 
-        assert!(columns.contains(&"type".to_string()));
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (11, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 12: Add date_history table
+    if current_version < 12 {
+        log::info!("Applying migration to version 12: Adding date history table");
 
-        // Verify default value works
-        let project_id = uuid::Uuid::new_v4();
         conn.execute(
-            "INSERT INTO projects (id, name, created_at, updated_at) VALUES (?1, 'Test', datetime('now'), datetime('now'))",
-            [project_id.to_string()],
-        ).unwrap();
+            "CREATE TABLE IF NOT EXISTS date_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                entity_type TEXT NOT NULL,
+                entity_id TEXT NOT NULL,
+                field TEXT NOT NULL,
+                old_value TEXT,
+                new_value TEXT,
+                changed_at TEXT NOT NULL
+            )",
+            [],
+        )?;
 
-        let project_type: String = conn
-            .query_row(
-                "SELECT type FROM projects WHERE id = ?1",
-                [project_id.to_string()],
-                |row| row.get(0),
-            )
-            .unwrap();
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_date_history_entity_id ON date_history(entity_id)",
+            [],
+        )?;
 
-        assert_eq!(project_type, "Personal");
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (12, datetime('now'))",
+            [],
+        )?;
     }
 
-    #[test]
-    fn test_migration_to_version_3_adds_updated_at_to_notes() {
-        let conn = Connection::open_in_memory().unwrap();
-        initialize_schema(&conn).unwrap();
-        apply_migrations(&conn).unwrap();
-
-        // Verify updated_at column exists in project_notes table
-        let columns: Vec<String> = conn
-            .prepare("PRAGMA table_info(project_notes)")
-            .unwrap()
-            .query_map([], |row| row.get(1))
-            .unwrap()
-            .collect::<Result<Vec<_>, _>>()
-            .unwrap();
+    // Migration to version 13: Add version columns for optimistic concurrency control
+    if current_version < 13 {
+        log::info!("Applying migration to version 13: Adding version columns");
 
-        assert!(columns.contains(&"updated_at".to_string()));
+        for table in ["projects", "milestones", "people", "project_notes", "milestone_notes", "stakeholder_notes"] {
+            let has_version: bool = conn.query_row(
+                &format!("SELECT COUNT(*) FROM pragma_table_info('{}') WHERE name='version'", table),
+                [],
+                |row| {
+                    let count: i32 = row.get(0)?;
+                    Ok(count > 0)
+                },
+            )?;
 
-        // Verify updated_at column exists in milestone_notes table
-        let columns: Vec<String> = conn
-            .prepare("PRAGMA table_info(milestone_notes)")
-            .unwrap()
-            .query_map([], |row| row.get(1))
-            .unwrap()
-            .collect::<Result<Vec<_>, _>>()
-            .unwrap();
+            if !has_version {
+                conn.execute(
+                    &format!("ALTER TABLE {} ADD COLUMN version INTEGER NOT NULL DEFAULT 1", table),
+                    [],
+                )?;
+            }
+        }
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (13, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 14: Add project_risks table
+    if current_version < 14 {
+        log::info!("Applying migration to version 14: Adding project risks table");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS project_risks (
+                id TEXT PRIMARY KEY NOT NULL,
+                project_id TEXT NOT NULL,
+                title TEXT NOT NULL,
+                description TEXT,
+                severity TEXT NOT NULL CHECK (severity IN ('low', 'medium', 'high')),
+                likelihood TEXT NOT NULL CHECK (likelihood IN ('low', 'medium', 'high')),
+                mitigation TEXT,
+                owner_email TEXT,
+                status TEXT NOT NULL DEFAULT 'open' CHECK (status IN ('open', 'mitigating', 'closed')),
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+                FOREIGN KEY (owner_email) REFERENCES people(email)
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_project_risks_project_id ON project_risks(project_id)",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (14, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 15: Add case-insensitive indexes for autocomplete
+    if current_version < 15 {
+        log::info!("Applying migration to version 15: Adding autocomplete indexes");
+
+        // LIKE is case-insensitive, so prefix searches can only use NOCASE indexes
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_people_name_nocase ON people(name COLLATE NOCASE)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_people_email_nocase ON people(email COLLATE NOCASE)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_teams_name_nocase ON teams(name COLLATE NOCASE)",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (15, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 16: Add action_items table
+    if current_version < 16 {
+        log::info!("Applying migration to version 16: Adding action items table");
+
+        // Action items outlive the milestone or note they came from
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS action_items (
+                id TEXT PRIMARY KEY NOT NULL,
+                project_id TEXT NOT NULL,
+                milestone_id TEXT,
+                assignee_email TEXT NOT NULL,
+                description TEXT NOT NULL,
+                due_date TEXT,
+                status TEXT NOT NULL DEFAULT 'open' CHECK (status IN ('open', 'completed')),
+                source_note_id TEXT,
+                completed_at TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+                FOREIGN KEY (milestone_id) REFERENCES milestones(id) ON DELETE SET NULL,
+                FOREIGN KEY (assignee_email) REFERENCES people(email),
+                FOREIGN KEY (source_note_id) REFERENCES project_notes(id) ON DELETE SET NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_action_items_project_id ON action_items(project_id)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_action_items_assignee ON action_items(assignee_email, status)",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (16, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 17: Add initiatives and the projects linked to them
+    if current_version < 17 {
+        log::info!("Applying migration to version 17: Adding initiatives tables");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS initiatives (
+                id TEXT PRIMARY KEY NOT NULL,
+                name TEXT NOT NULL,
+                description TEXT,
+                quarter TEXT NOT NULL CHECK (quarter GLOB '[0-9][0-9][0-9][0-9]-Q[1-4]'),
+                owner_email TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (owner_email) REFERENCES people(email)
+            )",
+            [],
+        )?;
+
+        // A project contributes to at most one initiative
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS project_initiatives (
+                project_id TEXT PRIMARY KEY NOT NULL,
+                initiative_id TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+                FOREIGN KEY (initiative_id) REFERENCES initiatives(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_initiatives_quarter ON initiatives(quarter)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_project_initiatives_initiative_id ON project_initiatives(initiative_id)",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (17, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 18: Add the inbox for notes not yet attached to anything
+    if current_version < 18 {
+        log::info!("Applying migration to version 18: Adding inbox_notes table");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS inbox_notes (
+                id TEXT PRIMARY KEY NOT NULL,
+                title TEXT NOT NULL,
+                body TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_inbox_notes_created_at ON inbox_notes(created_at)",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (18, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 19: Add parent teams so teams can form a hierarchy
+    if current_version < 19 {
+        log::info!("Applying migration to version 19: Adding parent_team to teams");
+
+        conn.execute(
+            "ALTER TABLE teams ADD COLUMN parent_team TEXT REFERENCES teams(name) ON UPDATE CASCADE",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_teams_parent_team ON teams(parent_team)",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (19, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 20: Add blocked status to projects and the blocker history
+    if current_version < 20 {
+        log::info!("Applying migration to version 20: Adding blocked status and blocker_history table");
+
+        conn.execute(
+            "ALTER TABLE projects ADD COLUMN blocked INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+
+        conn.execute(
+            "ALTER TABLE projects ADD COLUMN blocked_reason TEXT",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS blocker_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                project_id TEXT NOT NULL,
+                reason TEXT,
+                blocked_at TEXT NOT NULL,
+                cleared_at TEXT,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_blocker_history_project_id ON blocker_history(project_id)",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (20, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 21: Add avatar paths to people
+    if current_version < 21 {
+        log::info!("Applying migration to version 21: Adding avatar_path to people");
+
+        conn.execute(
+            "ALTER TABLE people ADD COLUMN avatar_path TEXT",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (21, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 22: Add saved queries
+    if current_version < 22 {
+        log::info!("Applying migration to version 22: Adding saved_queries table");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS saved_queries (
+                name TEXT PRIMARY KEY NOT NULL,
+                expression TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (22, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 23: Add note attachments
+    if current_version < 23 {
+        log::info!("Applying migration to version 23: Adding attachments table");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS attachments (
+                id TEXT PRIMARY KEY NOT NULL,
+                note_type TEXT NOT NULL CHECK (note_type IN ('project', 'milestone', 'stakeholder')),
+                note_id TEXT NOT NULL,
+                filename TEXT NOT NULL,
+                mime_type TEXT NOT NULL,
+                size_bytes INTEGER NOT NULL,
+                sha256 TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_attachments_note ON attachments(note_type, note_id)",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_attachments_sha256 ON attachments(sha256)",
+            [],
+        )?;
+
+        // Attachments can belong to any kind of note, so they can't have a
+        // foreign key; triggers remove them with their note instead
+        for (table, note_type) in [
+            ("project_notes", "project"),
+            ("milestone_notes", "milestone"),
+            ("stakeholder_notes", "stakeholder"),
+        ] {
+            conn.execute(
+                &format!(
+                    "CREATE TRIGGER IF NOT EXISTS {0}_delete_attachments
+                     AFTER DELETE ON {0}
+                     BEGIN
+                         DELETE FROM attachments WHERE note_type = '{1}' AND note_id = OLD.id;
+                     END",
+                    table, note_type
+                ),
+                [],
+            )?;
+        }
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (23, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 24: Track which milestone reminders have been shown
+    if current_version < 24 {
+        log::info!("Applying migration to version 24: Adding notified table");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS notified (
+                milestone_id TEXT NOT NULL,
+                kind TEXT NOT NULL CHECK (kind IN ('upcoming', 'due_today', 'overdue')),
+                due_date TEXT NOT NULL,
+                notified_at TEXT NOT NULL,
+                PRIMARY KEY (milestone_id, kind),
+                FOREIGN KEY (milestone_id) REFERENCES milestones(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (24, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 25: Add custom project fields
+    if current_version < 25 {
+        log::info!("Applying migration to version 25: Adding custom field tables");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS custom_fields (
+                key TEXT PRIMARY KEY NOT NULL,
+                label TEXT NOT NULL,
+                field_type TEXT NOT NULL CHECK (field_type IN ('text', 'number', 'date', 'bool')),
+                applies_to TEXT NOT NULL DEFAULT 'project' CHECK (applies_to IN ('project')),
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS project_custom_values (
+                project_id TEXT NOT NULL,
+                key TEXT NOT NULL,
+                value TEXT NOT NULL,
+                PRIMARY KEY (project_id, key),
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+                FOREIGN KEY (key) REFERENCES custom_fields(key) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_project_custom_values_key ON project_custom_values(key)",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (25, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 26: Track dependencies between projects
+    if current_version < 26 {
+        log::info!("Applying migration to version 26: Adding project_dependencies table");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS project_dependencies (
+                project_id TEXT NOT NULL,
+                depends_on_project_id TEXT NOT NULL,
+                note TEXT,
+                created_at TEXT NOT NULL,
+                PRIMARY KEY (project_id, depends_on_project_id),
+                CHECK (project_id <> depends_on_project_id),
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+                FOREIGN KEY (depends_on_project_id) REFERENCES projects(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_project_dependencies_depends_on
+             ON project_dependencies(depends_on_project_id)",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (26, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 27: Track estimated and actual effort on milestones
+    if current_version < 27 {
+        log::info!("Applying migration to version 27: Adding effort columns to milestones");
+
+        conn.execute(
+            "ALTER TABLE milestones ADD COLUMN estimated_days REAL CHECK (estimated_days >= 0)",
+            [],
+        )?;
+
+        conn.execute(
+            "ALTER TABLE milestones ADD COLUMN actual_days REAL CHECK (actual_days >= 0)",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (27, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 28: Person notes as timestamped entries
+    if current_version < 28 {
+        log::info!("Applying migration to version 28: Adding person_notes table");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS person_notes (
+                id TEXT PRIMARY KEY NOT NULL,
+                person_email TEXT NOT NULL,
+                title TEXT NOT NULL,
+                body TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                version INTEGER NOT NULL DEFAULT 1,
+                FOREIGN KEY (person_email) REFERENCES people(email) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_person_notes_person ON person_notes(person_email, created_at)",
+            [],
+        )?;
+
+        // Turn each existing notes blob into the person's first note; the
+        // column itself is left in place so older records stay readable
+        let mut stmt = conn.prepare(
+            "SELECT email, notes, updated_at FROM people
+             WHERE notes IS NOT NULL AND trim(notes) <> ''
+               AND NOT EXISTS (SELECT 1 FROM person_notes WHERE person_email = people.email)",
+        )?;
+        let blobs = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        for (email, notes, updated_at) in blobs {
+            conn.execute(
+                "INSERT INTO person_notes (id, person_email, title, body, created_at, updated_at)
+                 VALUES (?1, ?2, 'Imported note', ?3, ?4, ?4)",
+                params![uuid::Uuid::new_v4().to_string(), email, notes, updated_at],
+            )?;
+        }
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (28, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 29: Explicit ON DELETE rules for references to people
+    if current_version < 29 {
+        log::info!("Applying migration to version 29: Adding explicit ON DELETE rules");
+
+        // People who lead, manage or own something can't be deleted until
+        // someone else takes over. Being a stakeholder is a link like a team
+        // membership or resource assignment, so it goes with the person.
+        rebuild_tables(
+            conn,
+            &[
+                ("people", &[("FOREIGN KEY (manager) REFERENCES people(email)", "RESTRICT")]),
+                (
+                    "teams",
+                    &[
+                        ("FOREIGN KEY (manager) REFERENCES people(email)", "RESTRICT"),
+                        ("REFERENCES teams(name) ON UPDATE CASCADE", "RESTRICT"),
+                    ],
+                ),
+                (
+                    "projects",
+                    &[
+                        ("FOREIGN KEY (requirements_owner) REFERENCES people(email)", "RESTRICT"),
+                        ("FOREIGN KEY (technical_lead) REFERENCES people(email)", "RESTRICT"),
+                        ("FOREIGN KEY (manager) REFERENCES people(email)", "RESTRICT"),
+                    ],
+                ),
+                ("milestones", &[("FOREIGN KEY (technical_lead) REFERENCES people(email)", "RESTRICT")]),
+                ("project_stakeholders", &[("FOREIGN KEY (stakeholder_email) REFERENCES people(email)", "CASCADE")]),
+                ("project_risks", &[("FOREIGN KEY (owner_email) REFERENCES people(email)", "RESTRICT")]),
+                ("action_items", &[("FOREIGN KEY (assignee_email) REFERENCES people(email)", "RESTRICT")]),
+                ("initiatives", &[("FOREIGN KEY (owner_email) REFERENCES people(email)", "RESTRICT")]),
+            ],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (29, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 30: Project budgets and expenses
+    if current_version < 30 {
+        log::info!("Applying migration to version 30: Adding project budgets and expenses");
+
+        conn.execute(
+            "ALTER TABLE projects ADD COLUMN budget_amount REAL CHECK (budget_amount >= 0)",
+            [],
+        )?;
+
+        conn.execute("ALTER TABLE projects ADD COLUMN budget_currency TEXT", [])?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS project_expenses (
+                id TEXT PRIMARY KEY NOT NULL,
+                project_id TEXT NOT NULL,
+                description TEXT NOT NULL,
+                amount REAL NOT NULL CHECK (amount >= 0),
+                incurred_at TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_project_expenses_project ON project_expenses(project_id, incurred_at)",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (30, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 31: Slugs for projects
+    if current_version < 31 {
+        log::info!("Applying migration to version 31: Adding project slugs");
+
+        conn.execute("ALTER TABLE projects ADD COLUMN slug TEXT", [])?;
+
+        // Oldest projects get the plain slug; later ones with the same name get a suffix
+        let projects = conn
+            .prepare("SELECT id, name FROM projects ORDER BY created_at, id")?
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        let mut taken = std::collections::HashSet::new();
+        for (id, name) in projects {
+            let base = crate::utils::slugify(&name);
+            let slug = (1..)
+                .map(|n| if n == 1 { base.clone() } else { format!("{}-{}", base, n) })
+                .find(|slug| !taken.contains(slug))
+                .unwrap();
+            conn.execute("UPDATE projects SET slug = ?1 WHERE id = ?2", params![slug, id])?;
+            taken.insert(slug);
+        }
+
+        conn.execute(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_projects_slug ON projects(slug)",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (31, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 32: Project snapshots
+    if current_version < 32 {
+        log::info!("Applying migration to version 32: Adding project snapshots");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS project_snapshots (
+                id TEXT PRIMARY KEY NOT NULL,
+                project_id TEXT NOT NULL,
+                label TEXT NOT NULL,
+                data TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_project_snapshots_project ON project_snapshots(project_id, created_at)",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (32, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 33: Tombstones for sync
+    if current_version < 33 {
+        log::info!("Applying migration to version 33: Adding deleted row tombstones");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS deleted_rows (
+                entity TEXT NOT NULL,
+                key TEXT NOT NULL,
+                deleted_at TEXT NOT NULL,
+                PRIMARY KEY (entity, key)
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_deleted_rows_deleted_at ON deleted_rows(deleted_at)",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (33, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 34: Project status and board order
+    if current_version < 34 {
+        log::info!("Applying migration to version 34: Adding project status and board positions");
+
+        conn.execute(
+            "ALTER TABLE projects ADD COLUMN status TEXT NOT NULL DEFAULT 'active'
+                CHECK (status IN ('planning', 'active', 'on_hold', 'done'))",
+            [],
+        )?;
+
+        conn.execute("ALTER TABLE projects ADD COLUMN board_position INTEGER", [])?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_projects_board ON projects(status, board_position)",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (34, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 35: Link check results
+    if current_version < 35 {
+        log::info!("Applying migration to version 35: Adding link check results");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS link_checks (
+                url TEXT PRIMARY KEY NOT NULL,
+                status TEXT NOT NULL CHECK (status IN ('ok', 'dead', 'unreachable', 'skipped')),
+                http_status INTEGER,
+                error TEXT,
+                checked_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (35, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 36: Canonical roles
+    if current_version < 36 {
+        log::info!("Applying migration to version 36: Adding canonical roles");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS roles (
+                name TEXT PRIMARY KEY NOT NULL,
+                key TEXT NOT NULL UNIQUE,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // The most used spelling of each role becomes canonical. Other
+        // spellings are left alone and reported, to be merged by hand.
+        let in_use = conn
+            .prepare(
+                "SELECT role, COUNT(*) FROM (
+                    SELECT role FROM project_resources
+                    UNION ALL SELECT role FROM milestone_resources
+                    UNION ALL SELECT role FROM project_stakeholders
+                 )
+                 WHERE TRIM(COALESCE(role, '')) != ''
+                 GROUP BY role
+                 ORDER BY COUNT(*) DESC, role",
+            )?
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        let mut roles: Vec<(String, Vec<String>)> = Vec::new();
+        for (role, _) in in_use {
+            let key = super::role_repo::role_key(&role);
+            match roles.iter_mut().find(|(existing, _)| *existing == key) {
+                Some((_, spellings)) => spellings.push(role),
+                None => roles.push((key, vec![role])),
+            }
+        }
+        for (key, spellings) in &roles {
+            let canonical = spellings[0].split_whitespace().collect::<Vec<_>>().join(" ");
+            conn.execute(
+                "INSERT OR IGNORE INTO roles (name, key, created_at) VALUES (?1, ?2, ?3)",
+                params![canonical, key, crate::utils::dt_to_db(chrono::Utc::now())],
+            )?;
+            if spellings.len() > 1 {
+                log::warn!(
+                    "Role '{}' is also written as {}; merge them with `track roles merge`",
+                    canonical,
+                    spellings[1..].iter().map(|s| format!("'{}'", s)).collect::<Vec<_>>().join(", ")
+                );
+            }
+        }
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (36, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 37: Stakeholder influence and interest ratings
+    if current_version < 37 {
+        log::info!("Applying migration to version 37: Adding influence and interest to project_stakeholders");
+
+        conn.execute(
+            "ALTER TABLE project_stakeholders ADD COLUMN influence INTEGER CHECK (influence BETWEEN 1 AND 5)",
+            [],
+        )?;
+
+        conn.execute(
+            "ALTER TABLE project_stakeholders ADD COLUMN interest INTEGER CHECK (interest BETWEEN 1 AND 5)",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (37, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 38: Milestone checklists
+    if current_version < 38 {
+        log::info!("Applying migration to version 38: Adding milestone checklist items");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS milestone_checklist_items (
+                id TEXT PRIMARY KEY NOT NULL,
+                milestone_id TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                text TEXT NOT NULL,
+                checked INTEGER NOT NULL DEFAULT 0,
+                checked_at TEXT,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (milestone_id) REFERENCES milestones(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_milestone_checklist_items_milestone
+             ON milestone_checklist_items(milestone_id, position)",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (38, datetime('now'))",
+            [],
+        )?;
+    }
+
+    if current_version < 39 {
+        log::info!("Applying migration to version 39: Adding sync bundle history");
+
+        // One row per sync bundle exported from or imported into this
+        // database. Bundles carry the table with them, so the newest row
+        // says which bundle both sides last agreed on.
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS sync_bundles (
+                seq INTEGER PRIMARY KEY,
+                bundle_id TEXT NOT NULL,
+                direction TEXT NOT NULL CHECK (direction IN ('export', 'import')),
+                parent_id TEXT,
+                checksum TEXT NOT NULL,
+                host TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (39, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 40: 1:1 meeting records
+    if current_version < 40 {
+        log::info!("Applying migration to version 40: Adding one_on_ones table");
+
+        // A manager's record of their 1:1s with someone is kept deliberately,
+        // so deleting the person is refused while any exist instead of
+        // silently taking them along (see `PersonRepository::delete`)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS one_on_ones (
+                id TEXT PRIMARY KEY NOT NULL,
+                person_email TEXT NOT NULL,
+                occurred_at TEXT NOT NULL,
+                agenda TEXT,
+                notes TEXT,
+                followups TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (person_email) REFERENCES people(email) ON DELETE RESTRICT
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_one_on_ones_person ON one_on_ones(person_email, occurred_at)",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (40, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 41: Sanctioned exceptions to the email domain allow-list
+    if current_version < 41 {
+        log::info!("Applying migration to version 41: Adding external to people");
+
+        conn.execute(
+            "ALTER TABLE people ADD COLUMN external INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (41, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 42: Skills for staffing searches
+    if current_version < 42 {
+        log::info!("Applying migration to version 42: Adding person_skills table");
+
+        // Skill names are stored lowercase (see `PersonRepository::add_skill`),
+        // so the primary key keeps one entry per skill regardless of case
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS person_skills (
+                person_email TEXT NOT NULL,
+                skill TEXT NOT NULL,
+                level INTEGER CHECK (level BETWEEN 1 AND 5),
+                created_at TEXT NOT NULL,
+                PRIMARY KEY (person_email, skill),
+                FOREIGN KEY (person_email) REFERENCES people(email) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_person_skills_skill ON person_skills(skill, level)",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (42, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 43: Project phases and the phase history
+    if current_version < 43 {
+        log::info!("Applying migration to version 43: Adding project phase and phase_history table");
+
+        conn.execute(
+            "ALTER TABLE projects ADD COLUMN phase TEXT",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS phase_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                project_id TEXT NOT NULL,
+                from_phase TEXT,
+                to_phase TEXT,
+                backwards INTEGER NOT NULL DEFAULT 0,
+                changed_at TEXT NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_phase_history_project_id ON phase_history(project_id)",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (43, datetime('now'))",
+            [],
+        )?;
+    }
+
+    if current_version < 44 {
+        log::info!("Applying migration to version 44: Adding note_links table");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS note_links (
+                note_type TEXT NOT NULL CHECK (note_type IN ('project', 'milestone', 'stakeholder')),
+                note_id TEXT NOT NULL,
+                target_type TEXT NOT NULL CHECK (target_type IN ('project', 'person', 'milestone')),
+                target TEXT NOT NULL,
+                target_id TEXT,
+                dangling INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (note_type, note_id, target_type, target)
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_note_links_target ON note_links(target_type, target_id)",
+            [],
+        )?;
+
+        // Like attachments, links belong to any kind of note, so triggers
+        // remove them with their note
+        for (table, note_type) in [
+            ("project_notes", "project"),
+            ("milestone_notes", "milestone"),
+            ("stakeholder_notes", "stakeholder"),
+        ] {
+            conn.execute(
+                &format!(
+                    "CREATE TRIGGER IF NOT EXISTS {0}_delete_links
+                     AFTER DELETE ON {0}
+                     BEGIN
+                         DELETE FROM note_links WHERE note_type = '{1}' AND note_id = OLD.id;
+                     END",
+                    table, note_type
+                ),
+                [],
+            )?;
+        }
+
+        // Record the references in notes written before links were tracked
+        let links = super::note_link_repo::NoteLinkRepository::new(conn);
+        for note_type in [super::NoteType::Project, super::NoteType::Milestone, super::NoteType::Stakeholder] {
+            let notes: Vec<(String, String)> = conn
+                .prepare(&format!("SELECT id, body FROM {}", note_type.table()))?
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<rusqlite::Result<_>>()?;
+            for (id, body) in notes {
+                // Links refer to notes by UUID, so other IDs are skipped
+                if let Ok(id) = uuid::Uuid::parse_str(&id) {
+                    links.sync_note(note_type, &id, &body)?;
+                }
+            }
+        }
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (44, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 45: Retrospectives of finished projects
+    if current_version < 45 {
+        log::info!("Applying migration to version 45: Adding retrospectives table");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS retrospectives (
+                id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL UNIQUE,
+                went_well TEXT,
+                needs_improvement TEXT,
+                action_summary TEXT,
+                completed_on TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (45, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 46: People watching projects
+    if current_version < 46 {
+        log::info!("Applying migration to version 46: Adding project_watchers table");
+
+        // Watchers don't have to be people in the directory, so the email
+        // isn't a foreign key
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS project_watchers (
+                project_id TEXT NOT NULL,
+                watcher_email TEXT NOT NULL COLLATE NOCASE,
+                created_at TEXT NOT NULL,
+                PRIMARY KEY (project_id, watcher_email),
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_project_watchers_email ON project_watchers(watcher_email)",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (46, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 47: Log of automatic backups
+    if current_version < 47 {
+        log::info!("Applying migration to version 47: Adding backup_log table");
+
+        // One row per automatic backup attempt, failed ones included
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS backup_log (
+                id INTEGER PRIMARY KEY,
+                started_at TEXT NOT NULL,
+                path TEXT,
+                size_bytes INTEGER,
+                pruned INTEGER NOT NULL DEFAULT 0,
+                error TEXT
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (47, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 48: Tickets in trackers other than Jira
+    if current_version < 48 {
+        log::info!("Applying migration to version 48: Adding external_refs table");
+
+        // jira_initiative and jira_epic stay where they are while readers
+        // merge them in, so nothing is moved here
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS external_refs (
+                id TEXT PRIMARY KEY NOT NULL,
+                entity_type TEXT NOT NULL CHECK (entity_type IN ('project', 'milestone')),
+                entity_id TEXT NOT NULL,
+                system TEXT NOT NULL,
+                key TEXT NOT NULL COLLATE NOCASE,
+                url TEXT,
+                created_at TEXT NOT NULL,
+                UNIQUE (entity_type, entity_id, system, key)
+            )",
+            [],
+        )?;
+
+        // References point at projects or milestones, so like note links
+        // they are removed with their record by triggers
+        for (table, entity_type) in [("projects", "project"), ("milestones", "milestone")] {
+            conn.execute(
+                &format!(
+                    "CREATE TRIGGER IF NOT EXISTS {0}_delete_external_refs
+                     AFTER DELETE ON {0}
+                     BEGIN
+                         DELETE FROM external_refs WHERE entity_type = '{1}' AND entity_id = OLD.id;
+                     END",
+                    table, entity_type
+                ),
+                [],
+            )?;
+        }
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (48, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 49: Append-only log notes
+    if current_version < 49 {
+        log::info!("Applying migration to version 49: Adding kind to project_notes");
+
+        conn.execute(
+            "ALTER TABLE project_notes ADD COLUMN kind TEXT NOT NULL DEFAULT 'freeform'
+                CHECK (kind IN ('freeform', 'log'))",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (49, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 50: Permission tiers for shared databases
+    if current_version < 50 {
+        log::info!("Applying migration to version 50: Adding users table");
+
+        // Nobody is listed in existing databases, which leaves permissions off
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS users (
+                email TEXT PRIMARY KEY NOT NULL COLLATE NOCASE,
+                role TEXT NOT NULL CHECK (role IN ('viewer', 'editor', 'admin')),
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (50, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 51: Decision records
+    if current_version < 51 {
+        log::info!("Applying migration to version 51: Adding decisions and decision_approvers tables");
+
+        // Deleting a decision that replaced another leaves the older one
+        // without a successor; the repository marks it accepted again
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS decisions (
+                id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                title TEXT NOT NULL,
+                context TEXT,
+                decision TEXT NOT NULL,
+                decided_on TEXT,
+                status TEXT NOT NULL DEFAULT 'proposed' CHECK (status IN ('proposed', 'accepted', 'superseded')),
+                superseded_by TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+                FOREIGN KEY (superseded_by) REFERENCES decisions(id) ON DELETE SET NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_decisions_project_id ON decisions(project_id)",
+            [],
+        )?;
+
+        // Sign-offs are a record of who agreed, so like risk owners they
+        // keep the person from being deleted
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS decision_approvers (
+                decision_id TEXT NOT NULL,
+                approver_email TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                PRIMARY KEY (decision_id, approver_email),
+                FOREIGN KEY (decision_id) REFERENCES decisions(id) ON DELETE CASCADE,
+                FOREIGN KEY (approver_email) REFERENCES people(email) ON DELETE RESTRICT
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_decision_approvers_email ON decision_approvers(approver_email)",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (51, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 52: MCP operation journal
+    if current_version < 52 {
+        log::info!("Applying migration to version 52: Adding mcp_operations table");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS mcp_operations (
+                idempotency_key TEXT PRIMARY KEY,
+                tool TEXT NOT NULL,
+                request_hash TEXT NOT NULL,
+                response TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_mcp_operations_created_at ON mcp_operations(created_at)",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (52, datetime('now'))",
+            [],
+        )?;
+    }
+
+    // Migration to version 53: Organizations
+    if current_version < 53 {
+        log::info!("Applying migration to version 53: Adding organizations table and people.organization");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS organizations (
+                name TEXT PRIMARY KEY,
+                kind TEXT NOT NULL DEFAULT 'vendor' CHECK (kind IN ('vendor', 'client', 'partner', 'internal')),
+                notes TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Deleting an organization leaves its people in place, unaffiliated
+        conn.execute(
+            "ALTER TABLE people ADD COLUMN organization TEXT REFERENCES organizations(name) ON DELETE SET NULL ON UPDATE CASCADE",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_people_organization ON people(organization)",
+            [],
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, applied_at)
+             VALUES (53, datetime('now'))",
+            [],
+        )?;
+    }
+
+    log::info!("Database migrations complete");
+    Ok(())
+}
+
+/// Turn the unique project name policy on or off
+///
+/// When enabled, a unique index on `projects(name COLLATE NOCASE)` makes
+/// SQLite reject projects whose name matches an existing one, ignoring ASCII
+/// case. Enabling fails if existing projects already share a name.
+pub fn set_unique_project_names(conn: &Connection, enabled: bool) -> Result<()> {
+    if !enabled {
+        conn.execute("DROP INDEX IF EXISTS idx_projects_name_unique", [])?;
+        return Ok(());
+    }
+
+    let duplicates: Vec<String> = conn
+        .prepare(
+            "SELECT MIN(name) FROM projects GROUP BY name COLLATE NOCASE HAVING COUNT(*) > 1 ORDER BY 1",
+        )?
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    if !duplicates.is_empty() {
+        bail!(
+            "Cannot enforce unique project names, these names are used more than once: {}",
+            duplicates.join(", ")
+        );
+    }
+
+    conn.execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_projects_name_unique ON projects(name COLLATE NOCASE)",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Rebuild tables to add `ON DELETE` actions to their foreign keys
+///
+/// SQLite can't alter a foreign key, so each table is copied into a new one
+/// whose definition has the action appended after the given foreign key
+/// text, then swapped in with its indexes and triggers. Foreign key
+/// enforcement is turned off while the tables are swapped, so dropping the
+/// old tables doesn't cascade.
+fn rebuild_tables(conn: &Connection, tables: &[(&str, &[(&str, &str)])]) -> Result<()> {
+    let foreign_keys: bool = conn.query_row("PRAGMA foreign_keys", [], |row| row.get(0))?;
+    conn.pragma_update(None, "foreign_keys", false)?;
+
+    let result = (|| -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        for (table, actions) in tables {
+            rebuild_table(&tx, table, actions)?;
+        }
+
+        // Rebuilding doesn't change any rows, so these were already there
+        let violations: i64 = tx.query_row("SELECT COUNT(*) FROM pragma_foreign_key_check", [], |row| row.get(0))?;
+        if violations > 0 {
+            log::warn!("Database has {} rows that refer to records that no longer exist", violations);
+        }
+
+        tx.commit()?;
+        Ok(())
+    })();
+
+    conn.pragma_update(None, "foreign_keys", foreign_keys)?;
+    result
+}
+
+fn rebuild_table(conn: &Connection, table: &str, actions: &[(&str, &str)]) -> Result<()> {
+    let mut sql: String = conn.query_row(
+        "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        params![table],
+        |row| row.get(0),
+    )?;
+    for (foreign_key, action) in actions {
+        if sql.matches(foreign_key).count() != 1 {
+            bail!("Unexpected definition of table {}, can't find: {}", table, foreign_key);
+        }
+        sql = sql.replace(foreign_key, &format!("{} ON DELETE {}", foreign_key, action));
+    }
+
+    // Indexes and triggers are dropped with the old table
+    let dependents: Vec<String> = conn
+        .prepare("SELECT sql FROM sqlite_master WHERE tbl_name = ?1 AND type IN ('index', 'trigger') AND sql IS NOT NULL")?
+        .query_map(params![table], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let rebuilt = format!("{}_rebuilt", table);
+    let columns = &sql[sql.find('(').unwrap_or(0)..];
+    conn.execute(&format!("CREATE TABLE {} {}", rebuilt, columns), [])?;
+    conn.execute(&format!("INSERT INTO {} SELECT * FROM {}", rebuilt, table), [])?;
+    conn.execute(&format!("DROP TABLE {}", table), [])?;
+    conn.execute(&format!("ALTER TABLE {} RENAME TO {}", rebuilt, table), [])?;
+    for dependent in dependents {
+        conn.execute(&dependent, [])?;
+    }
+
+    Ok(())
+}
+
+/// Date/time columns that are stored as RFC3339 text
+const DATETIME_COLUMNS: &[(&str, &str)] = &[
+    ("people", "created_at"),
+    ("people", "updated_at"),
+    ("teams", "created_at"),
+    ("teams", "updated_at"),
+    ("team_members", "created_at"),
+    ("projects", "start_date"),
+    ("projects", "due_date"),
+    ("projects", "created_at"),
+    ("projects", "updated_at"),
+    ("milestones", "start_date"),
+    ("milestones", "due_date"),
+    ("milestones", "created_at"),
+    ("milestones", "updated_at"),
+    ("project_stakeholders", "created_at"),
+    ("project_resources", "created_at"),
+    ("milestone_resources", "created_at"),
+    ("project_notes", "created_at"),
+    ("project_notes", "updated_at"),
+    ("project_documents", "created_at"),
+    ("date_history", "old_value"),
+    ("date_history", "new_value"),
+    ("date_history", "changed_at"),
+    ("milestone_notes", "created_at"),
+    ("milestone_notes", "updated_at"),
+    ("stakeholder_notes", "created_at"),
+    ("stakeholder_notes", "updated_at"),
+];
+
+/// Rewrite date/time values that are not already in RFC3339 form
+///
+/// Older rows were written with SQLite's `datetime('now')` format
+/// (`YYYY-MM-DD HH:MM:SS`). Values that can't be parsed are left as they
+/// are and logged.
+fn normalize_datetime_columns(conn: &Connection) -> Result<()> {
+    for (table, column) in DATETIME_COLUMNS {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT rowid, {column} FROM {table} WHERE {column} IS NOT NULL"
+        ))?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, Value>(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut update = conn.prepare(&format!(
+            "UPDATE {table} SET {column} = ?1 WHERE rowid = ?2"
+        ))?;
+        let mut rewritten = 0;
+        for (rowid, value) in rows {
+            let Value::Text(value) = value else {
+                log::warn!("Leaving non-text {}.{} value in row {}", table, column, rowid);
+                continue;
+            };
+            match dt_from_db(&value) {
+                Ok(dt) => {
+                    let normalized = dt_to_db(dt);
+                    if normalized != value {
+                        update.execute(params![normalized, rowid])?;
+                        rewritten += 1;
+                    }
+                }
+                Err(e) => {
+                    log::warn!("Leaving {}.{} value {:?} in row {}: {}", table, column, value, rowid, e);
+                }
+            }
+        }
+
+        if rewritten > 0 {
+            log::info!("Normalized {} {}.{} values", rewritten, table, column);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    // Schema initialization tests
+
+    #[test]
+    fn test_initialize_schema() {
+        let conn = Connection::open_in_memory().unwrap();
+        initialize_schema(&conn).unwrap();
+
+        // Verify tables exist
+        let tables: Vec<String> = conn
+            .prepare("SELECT name FROM sqlite_master WHERE type='table' ORDER BY name")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert!(tables.contains(&"people".to_string()));
+        assert!(tables.contains(&"teams".to_string()));
+        assert!(tables.contains(&"team_members".to_string()));
+        assert!(tables.contains(&"projects".to_string()));
+        assert!(tables.contains(&"milestones".to_string()));
+        assert!(tables.contains(&"project_stakeholders".to_string()));
+        assert!(tables.contains(&"project_resources".to_string()));
+        assert!(tables.contains(&"milestone_resources".to_string()));
+        assert!(tables.contains(&"project_notes".to_string()));
+        assert!(tables.contains(&"project_documents".to_string()));
+        assert!(tables.contains(&"date_history".to_string()));
+        assert!(tables.contains(&"milestone_notes".to_string()));
+        assert!(tables.contains(&"stakeholder_notes".to_string()));
+        assert!(tables.contains(&"schema_version".to_string()));
+    }
+
+    #[test]
+    fn test_initialize_schema_idempotent() {
+        let conn = Connection::open_in_memory().unwrap();
+
+        // Initialize twice - should not error
+        initialize_schema(&conn).unwrap();
+        initialize_schema(&conn).unwrap();
+
+        let version = get_schema_version(&conn).unwrap();
+        assert_eq!(version, 1);
+    }
+
+    #[test]
+    fn test_schema_version() {
+        let conn = Connection::open_in_memory().unwrap();
+        initialize_schema(&conn).unwrap();
+
+        let version = get_schema_version(&conn).unwrap();
+        assert_eq!(version, 1);
+    }
+
+    // Migration tests
+
+    #[test]
+    fn test_apply_migrations() {
+        let conn = Connection::open_in_memory().unwrap();
+        initialize_schema(&conn).unwrap();
+
+        // Initial version should be 1
+        let version = get_schema_version(&conn).unwrap();
+        assert_eq!(version, 1);
+
+        // Apply migrations
+        apply_migrations(&conn).unwrap();
+
+        // Should now be at version 37 (latest)
+        let version = get_schema_version(&conn).unwrap();
+        assert_eq!(version, 53);
+    }
+
+    #[test]
+    fn test_supported_schema_version_matches_migrations() {
+        let conn = Connection::open_in_memory().unwrap();
+        assert_eq!(read_schema_version(&conn).unwrap(), None);
+
+        initialize_schema(&conn).unwrap();
+        assert_eq!(read_schema_version(&conn).unwrap(), Some(1));
+        assert!(get_schema_info(&conn).unwrap().has_pending_migrations());
+
+        apply_migrations(&conn).unwrap();
+        let info = get_schema_info(&conn).unwrap();
+        assert_eq!(info.current_version, SUPPORTED_SCHEMA_VERSION);
+        assert!(!info.has_pending_migrations());
+        assert!(!info.is_newer_than_supported());
+    }
+
+    #[test]
+    fn test_apply_migrations_refuses_newer_schema() {
+        let conn = Connection::open_in_memory().unwrap();
+        initialize_schema(&conn).unwrap();
+        apply_migrations(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO schema_version (version, applied_at) VALUES (?1, datetime('now'))",
+            params![SUPPORTED_SCHEMA_VERSION + 1],
+        )
+        .unwrap();
+
+        assert!(get_schema_info(&conn).unwrap().is_newer_than_supported());
+        let err = apply_migrations(&conn).unwrap_err();
+        assert!(err.to_string().contains("upgrade Project Tracker"));
+    }
+
+    #[test]
+    fn test_apply_migrations_idempotent() {
+        let conn = Connection::open_in_memory().unwrap();
+        initialize_schema(&conn).unwrap();
+
+        // Apply migrations twice - should not error
+        apply_migrations(&conn).unwrap();
+        apply_migrations(&conn).unwrap();
+
+        let version = get_schema_version(&conn).unwrap();
+        assert_eq!(version, 53);
+    }
+
+    #[test]
+    fn test_migration_to_version_2_adds_type_column() {
+        let conn = Connection::open_in_memory().unwrap();
+        initialize_schema(&conn).unwrap();
+
+        // Apply migrations
+        apply_migrations(&conn).unwrap();
+
+        // Verify type column exists in projects table
+        let columns: Vec<String> = conn
+            .prepare("PRAGMA table_info(projects)")
+            .unwrap()
+            .query_map([], |row| row.get(1))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert!(columns.contains(&"type".to_string()));
+
+        // Verify default value works
+        let project_id = uuid::Uuid::new_v4();
+        conn.execute(
+            "INSERT INTO projects (id, name, created_at, updated_at) VALUES (?1, 'Test', datetime('now'), datetime('now'))",
+            [project_id.to_string()],
+        ).unwrap();
+
+        let project_type: String = conn
+            .query_row(
+                "SELECT type FROM projects WHERE id = ?1",
+                [project_id.to_string()],
+                |row| row.get(0),
+            )
+            .unwrap();
+
+        assert_eq!(project_type, "Personal");
+    }
+
+    #[test]
+    fn test_migration_to_version_3_adds_updated_at_to_notes() {
+        let conn = Connection::open_in_memory().unwrap();
+        initialize_schema(&conn).unwrap();
+        apply_migrations(&conn).unwrap();
+
+        // Verify updated_at column exists in project_notes table
+        let columns: Vec<String> = conn
+            .prepare("PRAGMA table_info(project_notes)")
+            .unwrap()
+            .query_map([], |row| row.get(1))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert!(columns.contains(&"updated_at".to_string()));
+
+        // Verify updated_at column exists in milestone_notes table
+        let columns: Vec<String> = conn
+            .prepare("PRAGMA table_info(milestone_notes)")
+            .unwrap()
+            .query_map([], |row| row.get(1))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
 
         assert!(columns.contains(&"updated_at".to_string()));
 
@@ -647,6 +2453,129 @@ mod tests {
         assert!(columns.contains(&"updated_at".to_string()));
     }
 
+    #[test]
+    fn test_migration_to_version_8_normalizes_timestamps() {
+        let conn = Connection::open_in_memory().unwrap();
+        initialize_schema(&conn).unwrap();
+
+        // Rows written by older versions using SQLite's datetime format
+        conn.execute(
+            "INSERT INTO people (email, name, created_at, updated_at)
+             VALUES ('alice@example.com', 'Alice', '2025-01-15 10:30:00', '2025-01-15T10:30:00+00:00')",
+            [],
+        )
+        .unwrap();
+        let project_id = uuid::Uuid::new_v4().to_string();
+        conn.execute(
+            "INSERT INTO projects (id, name, due_date, created_at, updated_at)
+             VALUES (?1, 'Test', 'not a date', '2025-02-01 08:00:00', '2025-02-01 09:15:30.250')",
+            [&project_id],
+        )
+        .unwrap();
+
+        apply_migrations(&conn).unwrap();
+
+        let (created_at, updated_at): (String, String) = conn
+            .query_row(
+                "SELECT created_at, updated_at FROM people WHERE email = 'alice@example.com'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(created_at, "2025-01-15T10:30:00+00:00");
+        assert_eq!(updated_at, "2025-01-15T10:30:00+00:00");
+
+        let (due_date, created_at, updated_at): (String, String, String) = conn
+            .query_row(
+                "SELECT due_date, created_at, updated_at FROM projects WHERE id = ?1",
+                [&project_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        // Unparseable values are left alone
+        assert_eq!(due_date, "not a date");
+        assert_eq!(created_at, "2025-02-01T08:00:00+00:00");
+        assert_eq!(updated_at, "2025-02-01T09:15:30.250+00:00");
+    }
+
+    #[test]
+    fn test_migration_to_version_28_imports_person_notes() {
+        let conn = Connection::open_in_memory().unwrap();
+        initialize_schema(&conn).unwrap();
+
+        conn.execute(
+            "INSERT INTO people (email, name, notes, created_at, updated_at) VALUES
+             ('alice@example.com', 'Alice', 'Prefers email', '2025-01-15T10:30:00+00:00', '2025-03-01T09:00:00+00:00'),
+             ('bob@example.com', 'Bob', '  ', '2025-01-15T10:30:00+00:00', '2025-01-15T10:30:00+00:00'),
+             ('carol@example.com', 'Carol', NULL, '2025-01-15T10:30:00+00:00', '2025-01-15T10:30:00+00:00')",
+            [],
+        )
+        .unwrap();
+
+        apply_migrations(&conn).unwrap();
+
+        let notes: Vec<(String, String, String, String)> = conn
+            .prepare("SELECT person_email, title, body, created_at FROM person_notes")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(
+            notes,
+            vec![(
+                "alice@example.com".to_string(),
+                "Imported note".to_string(),
+                "Prefers email".to_string(),
+                "2025-03-01T09:00:00+00:00".to_string(),
+            )]
+        );
+
+        // The old column is still readable
+        let old: Option<String> = conn
+            .query_row("SELECT notes FROM people WHERE email = 'alice@example.com'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(old.as_deref(), Some("Prefers email"));
+    }
+
+    #[test]
+    fn test_migration_to_version_31_backfills_project_slugs() {
+        let conn = Connection::open_in_memory().unwrap();
+        initialize_schema(&conn).unwrap();
+
+        conn.execute(
+            "INSERT INTO projects (id, name, created_at, updated_at) VALUES
+             ('b', 'My Project', '2025-02-01T00:00:00+00:00', '2025-02-01T00:00:00+00:00'),
+             ('a', 'My Project', '2025-01-01T00:00:00+00:00', '2025-01-01T00:00:00+00:00'),
+             ('c', 'Ünïcødé ✨', '2025-03-01T00:00:00+00:00', '2025-03-01T00:00:00+00:00')",
+            [],
+        )
+        .unwrap();
+
+        apply_migrations(&conn).unwrap();
+
+        let slugs: Vec<(String, String)> = conn
+            .prepare("SELECT id, slug FROM projects ORDER BY id")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(
+            slugs,
+            vec![
+                ("a".to_string(), "my-project".to_string()),
+                ("b".to_string(), "my-project-2".to_string()),
+                ("c".to_string(), "unicode".to_string()),
+            ]
+        );
+
+        // The index keeps slugs unique
+        assert!(conn
+            .execute("UPDATE projects SET slug = 'my-project' WHERE id = 'c'", [])
+            .is_err());
+    }
+
     // Foreign key tests
 
     #[test]
@@ -696,6 +2625,324 @@ mod tests {
         assert_eq!(count, 0);
     }
 
+    /// Tables checked by the cascade tests, with their row counts in `cascade_fixture`
+    const CASCADE_TABLES: &[(&str, i64)] = &[
+        ("people", 2),
+        ("person_notes", 1),
+        ("teams", 2),
+        ("team_members", 1),
+        ("projects", 2),
+        ("project_dependencies", 1),
+        ("project_stakeholders", 1),
+        ("stakeholder_notes", 1),
+        ("project_resources", 1),
+        ("project_notes", 1),
+        ("project_documents", 1),
+        ("project_risks", 1),
+        ("project_expenses", 1),
+        ("project_snapshots", 1),
+        ("blocker_history", 1),
+        ("phase_history", 1),
+        ("retrospectives", 1),
+        ("project_watchers", 1),
+        ("external_refs", 2),
+        ("decisions", 2),
+        ("decision_approvers", 1),
+        ("milestones", 2),
+        ("milestone_resources", 1),
+        ("milestone_notes", 1),
+        ("notified", 1),
+        ("action_items", 1),
+        ("attachments", 1),
+        ("note_links", 1),
+        ("initiatives", 1),
+        ("project_initiatives", 1),
+        ("custom_fields", 1),
+        ("project_custom_values", 1),
+        ("organizations", 1),
+    ];
+
+    /// A database with one row in each table that refers to another
+    ///
+    /// `lead@example.com` manages, leads or owns one of everything, and
+    /// `member@example.com` is linked to project `p` and milestone `m1` the
+    /// way a team member, resource and stakeholder are.
+    fn cascade_fixture() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+        initialize_schema(&conn).unwrap();
+        apply_migrations(&conn).unwrap();
+
+        conn.execute_batch(
+            "INSERT INTO organizations (name, kind, created_at, updated_at)
+             VALUES ('Acme', 'vendor', 'now', 'now');
+             INSERT INTO people (email, name, created_at, updated_at)
+             VALUES ('lead@example.com', 'Lead', 'now', 'now');
+             INSERT INTO people (email, name, manager, organization, created_at, updated_at)
+             VALUES ('member@example.com', 'Member', 'lead@example.com', 'Acme', 'now', 'now');
+             INSERT INTO person_notes (id, person_email, title, body, created_at, updated_at)
+             VALUES ('pn', 'member@example.com', '1:1', '', 'now', 'now');
+             INSERT INTO teams (name, manager, created_at, updated_at)
+             VALUES ('Platform', 'lead@example.com', 'now', 'now');
+             INSERT INTO teams (name, parent_team, created_at, updated_at)
+             VALUES ('Infra', 'Platform', 'now', 'now');
+             INSERT INTO team_members (team_name, person_email, created_at)
+             VALUES ('Platform', 'member@example.com', 'now');
+
+             INSERT INTO projects (id, name, requirements_owner, technical_lead, manager, created_at, updated_at)
+             VALUES ('p', 'Apollo', 'lead@example.com', 'lead@example.com', 'lead@example.com', 'now', 'now');
+             INSERT INTO projects (id, name, created_at, updated_at) VALUES ('q', 'Gemini', 'now', 'now');
+             INSERT INTO project_dependencies (project_id, depends_on_project_id, created_at)
+             VALUES ('q', 'p', 'now');
+             INSERT INTO project_stakeholders (project_id, stakeholder_email, created_at)
+             VALUES ('p', 'member@example.com', 'now');
+             INSERT INTO stakeholder_notes (id, project_id, stakeholder_email, title, body, created_at, updated_at)
+             VALUES ('sn', 'p', 'member@example.com', 'Kickoff', '', 'now', 'now');
+             INSERT INTO project_resources (project_id, person_email, created_at)
+             VALUES ('p', 'member@example.com', 'now');
+             INSERT INTO project_notes (id, project_id, title, body, created_at, updated_at)
+             VALUES ('n', 'p', 'Status', '', 'now', 'now');
+             INSERT INTO project_documents (id, project_id, title, url, created_at)
+             VALUES ('d', 'p', 'Design', 'https://example.com', 'now');
+             INSERT INTO project_risks (id, project_id, title, severity, likelihood, owner_email, created_at, updated_at)
+             VALUES ('r', 'p', 'Vendor', 'high', 'low', 'lead@example.com', 'now', 'now');
+             INSERT INTO project_expenses (id, project_id, description, amount, incurred_at, created_at)
+             VALUES ('e', 'p', 'Licenses', 100.0, 'now', 'now');
+             INSERT INTO project_snapshots (id, project_id, label, data, created_at)
+             VALUES ('s', 'p', 'Q3 start', '{}', 'now');
+             INSERT INTO blocker_history (project_id, blocked_at) VALUES ('p', 'now');
+             INSERT INTO phase_history (project_id, to_phase, changed_at) VALUES ('p', 'Build', 'now');
+             INSERT INTO retrospectives (id, project_id, completed_on, created_at, updated_at)
+             VALUES ('rt', 'p', 'now', 'now', 'now');
+             INSERT INTO project_watchers (project_id, watcher_email, created_at)
+             VALUES ('p', 'member@example.com', 'now');
+             INSERT INTO decisions (id, project_id, title, decision, status, created_at, updated_at)
+             VALUES ('dc2', 'p', 'Gateway', 'Use the shared gateway', 'accepted', 'now', 'now');
+             INSERT INTO decisions (id, project_id, title, decision, status, superseded_by, created_at, updated_at)
+             VALUES ('dc1', 'p', 'Gateway', 'Run our own gateway', 'superseded', 'dc2', 'now', 'now');
+             INSERT INTO decision_approvers (decision_id, approver_email, created_at)
+             VALUES ('dc2', 'lead@example.com', 'now');
+
+             INSERT INTO milestones (id, project_id, number, name, technical_lead, created_at, updated_at)
+             VALUES ('m1', 'p', 1, 'Design', 'lead@example.com', 'now', 'now');
+             INSERT INTO milestones (id, project_id, number, name, series_id, created_at, updated_at)
+             VALUES ('m2', 'p', 2, 'Design again', 'm1', 'now', 'now');
+             INSERT INTO milestone_resources (milestone_id, person_email, created_at)
+             VALUES ('m1', 'member@example.com', 'now');
+             INSERT INTO milestone_notes (id, milestone_id, title, body, created_at, updated_at)
+             VALUES ('mn', 'm1', 'Review', '', 'now', 'now');
+             INSERT INTO notified (milestone_id, kind, due_date, notified_at)
+             VALUES ('m1', 'upcoming', 'now', 'now');
+
+             INSERT INTO action_items (id, project_id, milestone_id, assignee_email, description, source_note_id, created_at, updated_at)
+             VALUES ('a', 'p', 'm1', 'lead@example.com', 'Follow up', 'n', 'now', 'now');
+             INSERT INTO attachments (id, note_type, note_id, filename, mime_type, size_bytes, sha256, created_at)
+             VALUES ('f', 'project', 'n', 'plan.pdf', 'application/pdf', 1, 'abc', 'now');
+             INSERT INTO note_links (note_type, note_id, target_type, target, target_id)
+             VALUES ('project', 'n', 'project', 'q', 'q');
+             INSERT INTO initiatives (id, name, quarter, owner_email, created_at, updated_at)
+             VALUES ('i', 'Growth', '2025-Q3', 'lead@example.com', 'now', 'now');
+             INSERT INTO project_initiatives (project_id, initiative_id, created_at) VALUES ('p', 'i', 'now');
+             INSERT INTO custom_fields (key, label, field_type, created_at, updated_at)
+             VALUES ('cost', 'Cost', 'number', 'now', 'now');
+             INSERT INTO project_custom_values (project_id, key, value) VALUES ('p', 'cost', '5');
+             INSERT INTO external_refs (id, entity_type, entity_id, system, key, created_at)
+             VALUES ('x1', 'project', 'p', 'github', 'acme/app#1', 'now');
+             INSERT INTO external_refs (id, entity_type, entity_id, system, key, created_at)
+             VALUES ('x2', 'milestone', 'm1', 'linear', 'ENG-1', 'now');",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn row_counts(conn: &Connection) -> Vec<(&'static str, i64)> {
+        CASCADE_TABLES
+            .iter()
+            .map(|(table, _)| {
+                let count = conn
+                    .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
+                    .unwrap();
+                (*table, count)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_delete_cascade_matrix() {
+        // Each delete with the rows it changes, or None if it must be refused
+        type Case<'a> = (&'a str, Option<&'a [(&'a str, i64)]>);
+        let cases: &[Case] = &[
+            (
+                "DELETE FROM projects WHERE id = 'p'",
+                Some(&[
+                    ("projects", 1),
+                    ("project_dependencies", 0),
+                    ("project_stakeholders", 0),
+                    ("stakeholder_notes", 0),
+                    ("project_resources", 0),
+                    ("project_notes", 0),
+                    ("project_documents", 0),
+                    ("project_risks", 0),
+                    ("project_expenses", 0),
+                    ("project_snapshots", 0),
+                    ("blocker_history", 0),
+                    ("phase_history", 0),
+                    ("retrospectives", 0),
+                    ("project_watchers", 0),
+                    ("milestones", 0),
+                    ("milestone_resources", 0),
+                    ("milestone_notes", 0),
+                    ("notified", 0),
+                    ("action_items", 0),
+                    ("attachments", 0),
+                    ("note_links", 0),
+                    ("project_initiatives", 0),
+                    ("project_custom_values", 0),
+                    ("external_refs", 0),
+                    ("decisions", 0),
+                    ("decision_approvers", 0),
+                ]),
+            ),
+            ("DELETE FROM projects WHERE id = 'q'", Some(&[("projects", 1), ("project_dependencies", 0)])),
+            (
+                "DELETE FROM milestones WHERE id = 'm1'",
+                Some(&[
+                    ("milestones", 1),
+                    ("milestone_resources", 0),
+                    ("milestone_notes", 0),
+                    ("notified", 0),
+                    ("external_refs", 1),
+                ]),
+            ),
+            (
+                "DELETE FROM people WHERE email = 'member@example.com'",
+                Some(&[
+                    ("people", 1),
+                    ("person_notes", 0),
+                    ("team_members", 0),
+                    ("project_stakeholders", 0),
+                    ("stakeholder_notes", 0),
+                    ("project_resources", 0),
+                    ("milestone_resources", 0),
+                ]),
+            ),
+            ("DELETE FROM people WHERE email = 'lead@example.com'", None),
+            ("DELETE FROM teams WHERE name = 'Infra'", Some(&[("teams", 1)])),
+            ("DELETE FROM teams WHERE name = 'Platform'", None),
+            ("DELETE FROM project_notes WHERE id = 'n'", Some(&[("project_notes", 0), ("attachments", 0), ("note_links", 0)])),
+            ("DELETE FROM initiatives WHERE id = 'i'", Some(&[("initiatives", 0), ("project_initiatives", 0)])),
+            ("DELETE FROM custom_fields WHERE key = 'cost'", Some(&[("custom_fields", 0), ("project_custom_values", 0)])),
+            ("DELETE FROM decisions WHERE id = 'dc2'", Some(&[("decisions", 1), ("decision_approvers", 0)])),
+            ("DELETE FROM organizations WHERE name = 'Acme'", Some(&[("organizations", 0)])),
+        ];
+
+        for (sql, changes) in cases {
+            let conn = cascade_fixture();
+            let result = conn.execute(sql, []);
+
+            let mut expected = CASCADE_TABLES.to_vec();
+            match changes {
+                Some(changes) => {
+                    result.unwrap_or_else(|e| panic!("{}: {}", sql, e));
+                    for (table, count) in changes.iter() {
+                        expected.iter_mut().find(|(t, _)| t == table).unwrap().1 = *count;
+                    }
+                }
+                None => assert!(result.is_err(), "{} should be refused", sql),
+            }
+            assert_eq!(row_counts(&conn), expected, "{}", sql);
+        }
+    }
+
+    #[test]
+    fn test_delete_sets_optional_references_to_null() {
+        let conn = cascade_fixture();
+        conn.execute("DELETE FROM milestones WHERE id = 'm1'", []).unwrap();
+        conn.execute("DELETE FROM project_notes WHERE id = 'n'", []).unwrap();
+
+        let (milestone_id, source_note_id): (Option<String>, Option<String>) = conn
+            .query_row("SELECT milestone_id, source_note_id FROM action_items WHERE id = 'a'", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(milestone_id, None);
+        assert_eq!(source_note_id, None);
+        let series_id: Option<String> = conn
+            .query_row("SELECT series_id FROM milestones WHERE id = 'm2'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(series_id, None);
+
+        conn.execute("DELETE FROM decisions WHERE id = 'dc2'", []).unwrap();
+        let superseded_by: Option<String> = conn
+            .query_row("SELECT superseded_by FROM decisions WHERE id = 'dc1'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(superseded_by, None);
+
+        conn.execute("DELETE FROM organizations WHERE name = 'Acme'", []).unwrap();
+        let organization: Option<String> = conn
+            .query_row("SELECT organization FROM people WHERE email = 'member@example.com'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(organization, None);
+    }
+
+    #[test]
+    fn test_migration_to_version_29_keeps_rows_and_indexes() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+        initialize_schema(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO people (email, name, created_at, updated_at)
+             VALUES ('lead@example.com', 'Lead', 'now', 'now');
+             INSERT INTO people (email, name, manager, created_at, updated_at)
+             VALUES ('member@example.com', 'Member', 'lead@example.com', 'now', 'now');
+             INSERT INTO teams (name, manager, created_at, updated_at)
+             VALUES ('Platform', 'lead@example.com', 'now', 'now');
+             INSERT INTO projects (id, name, technical_lead, created_at, updated_at)
+             VALUES ('p', 'Apollo', 'lead@example.com', 'now', 'now');
+             INSERT INTO milestones (id, project_id, number, name, technical_lead, created_at, updated_at)
+             VALUES ('m1', 'p', 1, 'Design', 'lead@example.com', 'now', 'now');
+             INSERT INTO project_stakeholders (project_id, stakeholder_email, created_at)
+             VALUES ('p', 'member@example.com', 'now');
+             INSERT INTO stakeholder_notes (id, project_id, stakeholder_email, title, body, created_at, updated_at)
+             VALUES ('sn', 'p', 'member@example.com', 'Kickoff', '', 'now', 'now');",
+        )
+        .unwrap();
+
+        apply_migrations(&conn).unwrap();
+
+        let count = |table: &str| -> i64 {
+            conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
+                .unwrap()
+        };
+        for (table, expected) in [("people", 2), ("teams", 1), ("projects", 1), ("milestones", 1), ("stakeholder_notes", 1)] {
+            assert_eq!(count(table), expected, "{}", table);
+        }
+        let indexes: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'index'
+                 AND name IN ('idx_people_name', 'idx_teams_name', 'idx_projects_name', 'idx_milestones_due_date')",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(indexes, 4);
+        let foreign_keys: bool = conn.query_row("PRAGMA foreign_keys", [], |row| row.get(0)).unwrap();
+        assert!(foreign_keys);
+
+        // Leads are kept, and stakeholder roles go with the person
+        assert!(conn.execute("DELETE FROM people WHERE email = 'lead@example.com'", []).is_err());
+        conn.execute("DELETE FROM people WHERE email = 'member@example.com'", []).unwrap();
+        assert_eq!(count("project_stakeholders"), 0);
+        assert_eq!(count("stakeholder_notes"), 0);
+
+        // A table that doesn't look as expected is left alone
+        let err = rebuild_tables(&conn, &[("people", &[("REFERENCES nobody(id)", "CASCADE")])]).unwrap_err();
+        assert!(err.to_string().contains("Unexpected definition of table people"));
+        assert_eq!(count("people"), 1);
+        let foreign_keys: bool = conn.query_row("PRAGMA foreign_keys", [], |row| row.get(0)).unwrap();
+        assert!(foreign_keys);
+    }
+
     // Index tests
 
     #[test]
@@ -716,4 +2963,34 @@ mod tests {
         assert!(indexes.contains(&"idx_projects_name".to_string()));
         assert!(indexes.contains(&"idx_milestones_due_date".to_string()));
     }
+
+    #[test]
+    fn test_set_unique_project_names() {
+        let conn = Connection::open_in_memory().unwrap();
+        initialize_schema(&conn).unwrap();
+        apply_migrations(&conn).unwrap();
+
+        let insert = |id: &str, name: &str| {
+            conn.execute(
+                "INSERT INTO projects (id, name, type, created_at, updated_at)
+                 VALUES (?1, ?2, 'Team', datetime('now'), datetime('now'))",
+                params![id, name],
+            )
+        };
+
+        // Duplicates are allowed until the policy is turned on
+        insert("1", "Migration").unwrap();
+        insert("2", "migration").unwrap();
+        let err = set_unique_project_names(&conn, true).unwrap_err();
+        assert!(err.to_string().contains("Migration"));
+
+        conn.execute("DELETE FROM projects WHERE id = '2'", []).unwrap();
+        set_unique_project_names(&conn, true).unwrap();
+        set_unique_project_names(&conn, true).unwrap();
+        assert!(insert("3", "MIGRATION").is_err());
+        insert("4", "Migration 2").unwrap();
+
+        set_unique_project_names(&conn, false).unwrap();
+        insert("5", "MIGRATION").unwrap();
+    }
 }
@@ -0,0 +1,243 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Portfolio-wide statistics computed with aggregate queries
+
+use super::error::Result;
+use super::models::{GroupCount, PortfolioStats};
+use crate::utils::dt_to_db;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc};
+use rusqlite::{params, Connection};
+
+const TOTALS_SQL: &str = "
+    SELECT
+        (SELECT COUNT(*) FROM projects),
+        (SELECT COUNT(*) FROM milestones),
+        (SELECT COUNT(*) FROM milestones WHERE due_date >= ?1 AND due_date < ?2),
+        (SELECT COUNT(*) FROM milestones WHERE due_date >= ?3 AND due_date < ?4),
+        (SELECT COUNT(*) FROM people p
+         WHERE p.active = 1
+           AND NOT EXISTS (SELECT 1 FROM project_resources r WHERE r.person_email = p.email)
+           AND NOT EXISTS (SELECT 1 FROM milestone_resources r WHERE r.person_email = p.email)
+           AND NOT EXISTS (SELECT 1 FROM milestones m WHERE m.technical_lead = p.email)
+           AND NOT EXISTS (
+               SELECT 1 FROM projects pr
+               WHERE p.email IN (pr.requirements_owner, pr.technical_lead, pr.manager)
+           )),
+        (SELECT COUNT(*) FROM teams WHERE manager IS NULL OR manager = ''),
+        (SELECT COUNT(*) FROM (
+            SELECT created_at FROM project_notes WHERE created_at >= ?5
+            UNION ALL
+            SELECT created_at FROM milestone_notes WHERE created_at >= ?5
+            UNION ALL
+            SELECT created_at FROM stakeholder_notes WHERE created_at >= ?5
+        )),
+        (SELECT COALESCE(AVG(milestone_count), 0.0) FROM (
+            SELECT COUNT(m.id) AS milestone_count
+            FROM projects pr
+            LEFT JOIN milestones m ON m.project_id = pr.id
+            GROUP BY pr.id
+        ))";
+
+/// Compute portfolio statistics as of now
+pub fn portfolio(conn: &Connection) -> Result<PortfolioStats> {
+    portfolio_at(conn, Utc::now())
+}
+
+/// Compute portfolio statistics as of the given time
+///
+/// Month and quarter boundaries are calendar boundaries in UTC.
+pub fn portfolio_at(conn: &Connection, now: DateTime<Utc>) -> Result<PortfolioStats> {
+    let this_month_start = month_start(now.year(), now.month());
+    let next_month_start = month_start_after(now.year(), now.month(), 1);
+    let quarter_month = (now.month() - 1) / 3 * 3 + 1;
+    let quarter_start = month_start(now.year(), quarter_month);
+    let next_quarter_start = month_start_after(now.year(), quarter_month, 3);
+
+    let mut stats = conn.prepare_cached(TOTALS_SQL)?.query_row(
+        params![
+            dt_to_db(this_month_start),
+            dt_to_db(next_month_start),
+            dt_to_db(quarter_start),
+            dt_to_db(next_quarter_start),
+            dt_to_db(now - Duration::days(7)),
+        ],
+        |row| {
+            Ok(PortfolioStats {
+                as_of: now,
+                total_projects: row.get(0)?,
+                projects_by_type: Vec::new(),
+                projects_by_team: Vec::new(),
+                total_milestones: row.get(1)?,
+                milestones_due_this_month: row.get(2)?,
+                milestones_due_this_quarter: row.get(3)?,
+                unassigned_people: row.get(4)?,
+                teams_without_manager: row.get(5)?,
+                notes_last_7_days: row.get(6)?,
+                average_milestones_per_project: row.get(7)?,
+            })
+        },
+    )?;
+
+    stats.projects_by_type = group_counts(
+        conn,
+        "SELECT type, COUNT(*) FROM projects GROUP BY type ORDER BY type",
+    )?;
+    stats.projects_by_team = group_counts(
+        conn,
+        "SELECT team, COUNT(*) FROM projects GROUP BY team ORDER BY team IS NULL, team",
+    )?;
+
+    Ok(stats)
+}
+
+/// Run a `SELECT name, COUNT(*) ... GROUP BY name` query
+fn group_counts(conn: &Connection, sql: &str) -> Result<Vec<GroupCount>> {
+    let mut stmt = conn.prepare_cached(sql)?;
+    let counts = stmt
+        .query_map([], |row| {
+            Ok(GroupCount {
+                name: row.get(0)?,
+                count: row.get(1)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(counts)
+}
+
+/// Midnight UTC on the first day of the given month
+fn month_start(year: i32, month: u32) -> DateTime<Utc> {
+    let date = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
+    Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+}
+
+/// Midnight UTC on the first day of the month `months` after the given one
+fn month_start_after(year: i32, month: u32, months: u32) -> DateTime<Utc> {
+    let index = month - 1 + months;
+    month_start(year + (index / 12) as i32, index % 12 + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{
+        self, Milestone, Person, PersonRepository, Project, ProjectNote, ProjectRepository,
+        ProjectResource, Team, TeamRepository,
+    };
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn at(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_portfolio_empty() {
+        let conn = setup_test_db();
+        let now = at("2025-05-15T12:00:00Z");
+
+        let stats = portfolio_at(&conn, now).unwrap();
+        assert_eq!(stats.as_of, now);
+        assert_eq!(stats.total_projects, 0);
+        assert!(stats.projects_by_type.is_empty());
+        assert!(stats.projects_by_team.is_empty());
+        assert_eq!(stats.average_milestones_per_project, 0.0);
+    }
+
+    #[test]
+    fn test_portfolio() {
+        let conn = setup_test_db();
+        let people = PersonRepository::new(&conn);
+        let teams = TeamRepository::new(&conn);
+        let repo = ProjectRepository::new(&conn);
+        let now = at("2025-05-15T12:00:00Z");
+
+        for email in [
+            "alice@example.com",
+            "bob@example.com",
+            "carol@example.com",
+            "dave@example.com",
+        ] {
+            people
+                .create(&Person::new(email.to_string(), email.to_string()))
+                .unwrap();
+        }
+
+        let mut platform = Team::new("Platform".to_string());
+        platform.manager = Some("alice@example.com".to_string());
+        teams.create(&platform).unwrap();
+        teams.create(&Team::new("Mobile".to_string())).unwrap();
+
+        let mut alpha = Project::new("Alpha".to_string());
+        alpha.project_type = "Team".to_string();
+        alpha.team = Some("Platform".to_string());
+        alpha.manager = Some("alice@example.com".to_string());
+        repo.create(&alpha).unwrap();
+        let beta = Project::new("Beta".to_string());
+        repo.create(&beta).unwrap();
+
+        repo.add_project_resource(
+            &alpha.id,
+            &ProjectResource::new(alpha.id, "bob@example.com".to_string()),
+        )
+        .unwrap();
+
+        // One milestone due this month, one later this quarter, one next quarter
+        for (number, due) in [
+            (1, "2025-05-20T00:00:00Z"),
+            (2, "2025-06-30T00:00:00Z"),
+            (3, "2025-07-01T00:00:00Z"),
+        ] {
+            let mut milestone = Milestone::new(alpha.id, number, format!("M{}", number));
+            milestone.due_date = Some(at(due));
+            if number == 1 {
+                milestone.technical_lead = Some("carol@example.com".to_string());
+            }
+            repo.add_milestone(&milestone).unwrap();
+        }
+
+        let mut recent = ProjectNote::new(alpha.id, "Recent".to_string(), "body".to_string());
+        recent.created_at = now - Duration::days(2);
+        repo.add_project_note(&recent).unwrap();
+        let mut old = ProjectNote::new(alpha.id, "Old".to_string(), "body".to_string());
+        old.created_at = now - Duration::days(30);
+        repo.add_project_note(&old).unwrap();
+
+        let stats = portfolio_at(&conn, now).unwrap();
+        assert_eq!(stats.total_projects, 2);
+        assert_eq!(stats.total_milestones, 3);
+        assert_eq!(stats.milestones_due_this_month, 1);
+        assert_eq!(stats.milestones_due_this_quarter, 2);
+        assert_eq!(stats.unassigned_people, 1);
+        assert_eq!(stats.teams_without_manager, 1);
+        assert_eq!(stats.notes_last_7_days, 1);
+        assert_eq!(stats.average_milestones_per_project, 1.5);
+
+        let by_type: Vec<_> = stats
+            .projects_by_type
+            .iter()
+            .map(|g| (g.name.as_deref(), g.count))
+            .collect();
+        assert_eq!(by_type, vec![(Some("Personal"), 1), (Some("Team"), 1)]);
+        let by_team: Vec<_> = stats
+            .projects_by_team
+            .iter()
+            .map(|g| (g.name.as_deref(), g.count))
+            .collect();
+        assert_eq!(by_team, vec![(Some("Platform"), 1), (None, 1)]);
+    }
+
+    #[test]
+    fn test_month_start_after_wraps_year() {
+        assert_eq!(month_start_after(2025, 12, 1), at("2026-01-01T00:00:00Z"));
+        assert_eq!(month_start_after(2025, 10, 3), at("2026-01-01T00:00:00Z"));
+    }
+}
@@ -0,0 +1,1082 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Change sets for syncing with offline clients
+//!
+//! A client asks for everything changed since its last sync with
+//! [`get_changes_since`] and pushes its own edits back with
+//! [`apply_changes`]. Changes are found by `updated_at`; deletes are found
+//! through the `deleted_rows` tombstones the repositories write when they
+//! delete people, teams, projects, milestones and project, milestone or
+//! person notes. Rows deleted by a cascade get no tombstone of their own:
+//! deleting the parent deletes them on the client too.
+//!
+//! Pushed rows are written as sent, keeping their `updated_at`, so both
+//! sides end up with the same timestamps and the next pull doesn't send the
+//! same rows back. Attachments and avatars are files and aren't synced.
+
+use super::error::{Error, Result};
+use super::models::{Milestone, MilestoneNote, NoteType, Person, PersonNote, Project, ProjectNote, Team};
+use super::{NoteLinkRepository, PersonRepository, ProjectRepository, TeamRepository};
+use crate::utils::{dt_from_db, dt_to_db};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension, ToSql};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A kind of record that is synced
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncEntity {
+    Person,
+    Team,
+    Project,
+    Milestone,
+    ProjectNote,
+    MilestoneNote,
+    PersonNote,
+}
+
+impl SyncEntity {
+    /// Every synced entity, parents before children
+    pub const ALL: [SyncEntity; 7] = [
+        SyncEntity::Person,
+        SyncEntity::Team,
+        SyncEntity::Project,
+        SyncEntity::Milestone,
+        SyncEntity::ProjectNote,
+        SyncEntity::MilestoneNote,
+        SyncEntity::PersonNote,
+    ];
+
+    /// Name stored in `deleted_rows.entity`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncEntity::Person => "person",
+            SyncEntity::Team => "team",
+            SyncEntity::Project => "project",
+            SyncEntity::Milestone => "milestone",
+            SyncEntity::ProjectNote => "project_note",
+            SyncEntity::MilestoneNote => "milestone_note",
+            SyncEntity::PersonNote => "person_note",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|entity| entity.as_str() == s)
+    }
+
+    fn table(&self) -> &'static str {
+        match self {
+            SyncEntity::Person => "people",
+            SyncEntity::Team => "teams",
+            SyncEntity::Project => "projects",
+            SyncEntity::Milestone => "milestones",
+            SyncEntity::ProjectNote => "project_notes",
+            SyncEntity::MilestoneNote => "milestone_notes",
+            SyncEntity::PersonNote => "person_notes",
+        }
+    }
+
+    fn key_column(&self) -> &'static str {
+        match self {
+            SyncEntity::Person => "email",
+            SyncEntity::Team => "name",
+            _ => "id",
+        }
+    }
+
+    /// Columns written when a pushed row is stored, key first
+    fn columns(&self) -> &'static [&'static str] {
+        match self {
+            SyncEntity::Person => &[
+                "email",
+                "name",
+                "team",
+                "manager",
+                "notes",
+                "active",
+                "external",
+                "created_at",
+                "updated_at",
+            ],
+            SyncEntity::Team => &[
+                "name",
+                "description",
+                "manager",
+                "parent_team",
+                "created_at",
+                "updated_at",
+            ],
+            SyncEntity::Project => &[
+                "id",
+                "name",
+                "slug",
+                "description",
+                "type",
+                "requirements_owner",
+                "technical_lead",
+                "manager",
+                "team",
+                "start_date",
+                "due_date",
+                "jira_initiative",
+                "blocked",
+                "blocked_reason",
+                "budget_amount",
+                "budget_currency",
+                "status",
+                "phase",
+                "board_position",
+                "created_at",
+                "updated_at",
+            ],
+            SyncEntity::Milestone => &[
+                "id",
+                "project_id",
+                "number",
+                "name",
+                "description",
+                "technical_lead",
+                "team",
+                "design_doc_url",
+                "start_date",
+                "due_date",
+                "jira_epic",
+                "recurrence_rule",
+                "series_id",
+                "series_index",
+                "estimated_days",
+                "actual_days",
+                "created_at",
+                "updated_at",
+            ],
+            SyncEntity::ProjectNote => &[
+                "id",
+                "project_id",
+                "title",
+                "body",
+                "created_at",
+                "updated_at",
+                "kind",
+            ],
+            SyncEntity::MilestoneNote => &[
+                "id",
+                "milestone_id",
+                "title",
+                "body",
+                "created_at",
+                "updated_at",
+            ],
+            SyncEntity::PersonNote => &[
+                "id",
+                "person_email",
+                "title",
+                "body",
+                "created_at",
+                "updated_at",
+            ],
+        }
+    }
+
+    /// Whether the table has a `version` column for optimistic concurrency
+    fn versioned(&self) -> bool {
+        !matches!(self, SyncEntity::Team)
+    }
+
+    /// `INSERT` that updates the existing row when the key is already taken
+    fn upsert_sql(&self) -> String {
+        let columns = self.columns();
+        let placeholders: Vec<String> = (1..=columns.len()).map(|i| format!("?{}", i)).collect();
+        let mut updates: Vec<String> = columns[1..]
+            .iter()
+            .map(|column| format!("{0} = excluded.{0}", column))
+            .collect();
+        if self.versioned() {
+            updates.push("version = version + 1".to_string());
+        }
+        format!(
+            "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT({}) DO UPDATE SET {}",
+            self.table(),
+            columns.join(", "),
+            placeholders.join(", "),
+            self.key_column(),
+            updates.join(", ")
+        )
+    }
+}
+
+/// A deleted row
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tombstone {
+    pub entity: SyncEntity,
+    /// Email, team name or UUID of the deleted row
+    pub key: String,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// Rows created, updated or deleted after `since`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChangeSet {
+    /// When the sender last synced; rows changed after this were changed on the sender
+    pub since: Option<DateTime<Utc>>,
+    /// When the changes were read; pass this as `since` on the next pull
+    #[serde(default)]
+    pub until: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub people: Vec<Person>,
+    #[serde(default)]
+    pub teams: Vec<Team>,
+    #[serde(default)]
+    pub projects: Vec<Project>,
+    #[serde(default)]
+    pub milestones: Vec<Milestone>,
+    #[serde(default)]
+    pub project_notes: Vec<ProjectNote>,
+    #[serde(default)]
+    pub milestone_notes: Vec<MilestoneNote>,
+    #[serde(default)]
+    pub person_notes: Vec<PersonNote>,
+    #[serde(default)]
+    pub deleted: Vec<Tombstone>,
+}
+
+impl ChangeSet {
+    /// Number of changed and deleted rows
+    pub fn len(&self) -> usize {
+        self.people.len()
+            + self.teams.len()
+            + self.projects.len()
+            + self.milestones.len()
+            + self.project_notes.len()
+            + self.milestone_notes.len()
+            + self.person_notes.len()
+            + self.deleted.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// How [`apply_changes`] settles a row changed on both sides
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// Keep whichever change has the later `updated_at` (or `deleted_at`)
+    #[default]
+    LastWriteWins,
+    /// Keep the local row if it changed after the sender's `since`
+    ServerWins,
+}
+
+/// A pushed row that wasn't applied because the local row won
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedChange {
+    pub entity: SyncEntity,
+    pub key: String,
+}
+
+/// What [`apply_changes`] did
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApplyReport {
+    /// Rows created or updated
+    pub applied: usize,
+    /// Rows deleted
+    pub deleted: usize,
+    /// Changes that lost to a local change
+    pub skipped: Vec<SkippedChange>,
+}
+
+/// Record that a row was deleted, for clients that sync deletes
+pub(crate) fn record_deletion(conn: &Connection, entity: SyncEntity, key: &str) -> Result<()> {
+    record_deletion_at(conn, entity, key, Utc::now())
+}
+
+fn record_deletion_at(
+    conn: &Connection,
+    entity: SyncEntity,
+    key: &str,
+    at: DateTime<Utc>,
+) -> Result<()> {
+    conn.prepare_cached(
+        "INSERT OR REPLACE INTO deleted_rows (entity, key, deleted_at) VALUES (?1, ?2, ?3)",
+    )?
+    .execute(params![entity.as_str(), key, dt_to_db(at)])?;
+    Ok(())
+}
+
+/// Delete tombstones recorded before `before`, returning how many were removed
+///
+/// Clients that last synced before `before` can no longer learn about those
+/// deletes and have to download everything again.
+pub fn prune_tombstones(conn: &Connection, before: DateTime<Utc>) -> Result<usize> {
+    let rows = conn
+        .prepare_cached("DELETE FROM deleted_rows WHERE julianday(deleted_at) < julianday(?1)")?
+        .execute(params![dt_to_db(before)])?;
+    if rows > 0 {
+        log::debug!("Pruned {} tombstones", rows);
+    }
+    Ok(rows)
+}
+
+/// Keys of the rows of `entity` whose `updated_at` is after `since`
+///
+/// SQLite compares timestamps to the millisecond, so the query includes
+/// the millisecond of `since` and the exact comparison is left to the caller.
+fn changed_keys(
+    conn: &Connection,
+    entity: SyncEntity,
+    since: DateTime<Utc>,
+) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT {0} FROM {1} WHERE julianday(updated_at) >= julianday(?1) ORDER BY updated_at, {0}",
+        entity.key_column(),
+        entity.table()
+    ))?;
+    let keys = stmt
+        .query_map(params![dt_to_db(since)], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(keys)
+}
+
+fn parse_id(key: &str) -> Result<Uuid> {
+    Uuid::parse_str(key).map_err(|e| Error::Invalid(format!("Invalid ID {}: {}", key, e)))
+}
+
+/// Load the rows of `entity` changed after `since` with `find`
+fn load_changed<T>(
+    conn: &Connection,
+    entity: SyncEntity,
+    since: DateTime<Utc>,
+    updated_at: impl Fn(&T) -> DateTime<Utc>,
+    find: impl Fn(&str) -> Result<Option<T>>,
+) -> Result<Vec<T>> {
+    let mut rows = Vec::new();
+    for key in changed_keys(conn, entity, since)? {
+        if let Some(row) = find(&key)? {
+            if updated_at(&row) > since {
+                rows.push(row);
+            }
+        }
+    }
+    Ok(rows)
+}
+
+/// Collect every synced row created, updated or deleted after `since`
+pub fn get_changes_since(conn: &Connection, since: DateTime<Utc>) -> Result<ChangeSet> {
+    let until = Utc::now();
+    let people = PersonRepository::new(conn);
+    let teams = TeamRepository::new(conn);
+    let projects = ProjectRepository::new(conn);
+
+    let mut changes = ChangeSet {
+        since: Some(since),
+        until: Some(until),
+        people: load_changed(
+            conn,
+            SyncEntity::Person,
+            since,
+            |p: &Person| p.updated_at,
+            |key| people.find_by_email(key),
+        )?,
+        teams: load_changed(
+            conn,
+            SyncEntity::Team,
+            since,
+            |t: &Team| t.updated_at,
+            |key| teams.find_by_name(key),
+        )?,
+        projects: load_changed(
+            conn,
+            SyncEntity::Project,
+            since,
+            |p: &Project| p.updated_at,
+            |key| projects.find_by_id(&parse_id(key)?),
+        )?,
+        milestones: load_changed(
+            conn,
+            SyncEntity::Milestone,
+            since,
+            |m: &Milestone| m.updated_at,
+            |key| projects.find_milestone_by_id(&parse_id(key)?),
+        )?,
+        project_notes: load_changed(
+            conn,
+            SyncEntity::ProjectNote,
+            since,
+            |n: &ProjectNote| n.updated_at,
+            |key| projects.find_project_note_by_id(&parse_id(key)?),
+        )?,
+        milestone_notes: load_changed(
+            conn,
+            SyncEntity::MilestoneNote,
+            since,
+            |n: &MilestoneNote| n.updated_at,
+            |key| projects.find_milestone_note_by_id(&parse_id(key)?),
+        )?,
+        person_notes: load_changed(
+            conn,
+            SyncEntity::PersonNote,
+            since,
+            |n: &PersonNote| n.updated_at,
+            |key| people.find_note_by_id(&parse_id(key)?),
+        )?,
+        deleted: Vec::new(),
+    };
+
+    let mut stmt = conn.prepare_cached(
+        "SELECT entity, key, deleted_at FROM deleted_rows
+         WHERE julianday(deleted_at) >= julianday(?1) ORDER BY deleted_at, entity, key",
+    )?;
+    let tombstones = stmt
+        .query_map(params![dt_to_db(since)], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    for (entity, key, deleted_at) in tombstones {
+        let Some(entity) = SyncEntity::parse(&entity) else {
+            continue;
+        };
+        let deleted_at = dt_from_db(&deleted_at)?;
+        // A row created again after it was deleted isn't deleted any more
+        if deleted_at > since && local_updated_at(conn, entity, &key)?.is_none() {
+            changes.deleted.push(Tombstone {
+                entity,
+                key,
+                deleted_at,
+            });
+        }
+    }
+
+    // Moving a note deletes it from one table and adds it to the other with
+    // its old timestamps, so send the note along with the delete
+    let moved: Vec<(SyncEntity, Uuid)> = changes
+        .deleted
+        .iter()
+        .filter(|t| {
+            matches!(
+                t.entity,
+                SyncEntity::ProjectNote | SyncEntity::MilestoneNote
+            )
+        })
+        .map(|t| Ok((t.entity, parse_id(&t.key)?)))
+        .collect::<Result<_>>()?;
+    for (entity, id) in moved {
+        if entity == SyncEntity::ProjectNote && !changes.milestone_notes.iter().any(|n| n.id == id)
+        {
+            changes
+                .milestone_notes
+                .extend(projects.find_milestone_note_by_id(&id)?);
+        } else if entity == SyncEntity::MilestoneNote
+            && !changes.project_notes.iter().any(|n| n.id == id)
+        {
+            changes
+                .project_notes
+                .extend(projects.find_project_note_by_id(&id)?);
+        }
+    }
+
+    // Notes are listed with their attachments, which stay on this machine
+    for note in &mut changes.project_notes {
+        note.attachments.clear();
+    }
+    for note in &mut changes.milestone_notes {
+        note.attachments.clear();
+    }
+
+    Ok(changes)
+}
+
+fn local_updated_at(
+    conn: &Connection,
+    entity: SyncEntity,
+    key: &str,
+) -> Result<Option<DateTime<Utc>>> {
+    let value: Option<String> = conn
+        .prepare_cached(&format!(
+            "SELECT updated_at FROM {} WHERE {} = ?1",
+            entity.table(),
+            entity.key_column()
+        ))?
+        .query_row(params![key], |row| row.get(0))
+        .optional()?;
+    Ok(value.map(|value| dt_from_db(&value)).transpose()?)
+}
+
+fn local_deleted_at(
+    conn: &Connection,
+    entity: SyncEntity,
+    key: &str,
+) -> Result<Option<DateTime<Utc>>> {
+    let value: Option<String> = conn
+        .prepare_cached("SELECT deleted_at FROM deleted_rows WHERE entity = ?1 AND key = ?2")?
+        .query_row(params![entity.as_str(), key], |row| row.get(0))
+        .optional()?;
+    Ok(value.map(|value| dt_from_db(&value)).transpose()?)
+}
+
+/// Applies one change set inside a transaction
+struct Applier<'a> {
+    conn: &'a Connection,
+    since: Option<DateTime<Utc>>,
+    policy: ConflictPolicy,
+    report: ApplyReport,
+}
+
+impl Applier<'_> {
+    /// Whether a change made at `changed_at` beats the local row
+    fn wins(&self, entity: SyncEntity, key: &str, changed_at: DateTime<Utc>) -> Result<bool> {
+        let local = match local_updated_at(self.conn, entity, key)? {
+            Some(updated_at) => Some(updated_at),
+            None => local_deleted_at(self.conn, entity, key)?,
+        };
+        Ok(match (local, self.policy) {
+            (None, _) => true,
+            (Some(local), ConflictPolicy::LastWriteWins) => changed_at > local,
+            (Some(local), ConflictPolicy::ServerWins) => {
+                self.since.is_some_and(|since| local <= since)
+            }
+        })
+    }
+
+    fn upsert(
+        &mut self,
+        entity: SyncEntity,
+        key: &str,
+        updated_at: DateTime<Utc>,
+        values: &[&dyn ToSql],
+    ) -> Result<()> {
+        if !self.wins(entity, key, updated_at)? {
+            self.report.skipped.push(SkippedChange {
+                entity,
+                key: key.to_string(),
+            });
+            return Ok(());
+        }
+        self.conn
+            .prepare_cached(&entity.upsert_sql())?
+            .execute(values)
+            .map_err(|e| {
+                Error::from(e).with_conflict(format!("Couldn't store {} {}", entity.as_str(), key))
+            })?;
+        self.conn
+            .prepare_cached("DELETE FROM deleted_rows WHERE entity = ?1 AND key = ?2")?
+            .execute(params![entity.as_str(), key])?;
+        self.report.applied += 1;
+        Ok(())
+    }
+
+    fn delete(&mut self, tombstone: &Tombstone) -> Result<()> {
+        let entity = tombstone.entity;
+        if local_updated_at(self.conn, entity, &tombstone.key)?.is_none() {
+            return Ok(());
+        }
+        if !self.wins(entity, &tombstone.key, tombstone.deleted_at)? {
+            self.report.skipped.push(SkippedChange {
+                entity,
+                key: tombstone.key.clone(),
+            });
+            return Ok(());
+        }
+        self.conn
+            .prepare_cached(&format!(
+                "DELETE FROM {} WHERE {} = ?1",
+                entity.table(),
+                entity.key_column()
+            ))?
+            .execute(params![tombstone.key])
+            .map_err(|e| match Error::from(e) {
+                Error::ForeignKeyViolation { .. } => Error::Conflict(format!(
+                    "Can't delete {} {}: other records still refer to it",
+                    entity.as_str(),
+                    tombstone.key
+                )),
+                other => other,
+            })?;
+        record_deletion_at(self.conn, entity, &tombstone.key, tombstone.deleted_at)?;
+        self.report.deleted += 1;
+        Ok(())
+    }
+}
+
+/// Apply a change set pushed by a client
+///
+/// Rows are created or updated with the values and timestamps sent, and
+/// tombstones delete the local row. When a row also changed locally,
+/// `policy` decides which change is kept; the ones dropped are listed in
+/// the report. Foreign keys are checked when the whole set has been
+/// applied, so rows may arrive in any order. Either every change is
+/// applied or, on an error, none are.
+pub fn apply_changes(
+    conn: &Connection,
+    changes: &ChangeSet,
+    policy: ConflictPolicy,
+) -> Result<ApplyReport> {
+    let tx = conn.unchecked_transaction()?;
+    conn.pragma_update(None, "defer_foreign_keys", true)?;
+
+    let mut applier = Applier {
+        conn,
+        since: changes.since,
+        policy,
+        report: ApplyReport::default(),
+    };
+    let dt = dt_to_db;
+    let opt_dt = |dt: Option<DateTime<Utc>>| dt.map(dt_to_db);
+
+    for p in &changes.people {
+        applier.upsert(
+            SyncEntity::Person,
+            &p.email,
+            p.updated_at,
+            params![
+                p.email,
+                p.name,
+                p.team,
+                p.manager,
+                p.notes,
+                p.active,
+                p.external,
+                dt(p.created_at),
+                dt(p.updated_at),
+            ],
+        )?;
+    }
+    for t in &changes.teams {
+        applier.upsert(
+            SyncEntity::Team,
+            &t.name,
+            t.updated_at,
+            params![
+                t.name,
+                t.description,
+                t.manager,
+                t.parent_team,
+                dt(t.created_at),
+                dt(t.updated_at),
+            ],
+        )?;
+    }
+    for p in &changes.projects {
+        applier.upsert(
+            SyncEntity::Project,
+            &p.id.to_string(),
+            p.updated_at,
+            params![
+                p.id.to_string(),
+                p.name,
+                p.slug,
+                p.description,
+                p.project_type,
+                p.requirements_owner,
+                p.technical_lead,
+                p.manager,
+                p.team,
+                opt_dt(p.start_date),
+                opt_dt(p.due_date),
+                p.jira_initiative,
+                p.blocked,
+                p.blocked_reason,
+                p.budget_amount,
+                p.budget_currency,
+                p.status.as_str(),
+                p.phase,
+                p.board_position,
+                dt(p.created_at),
+                dt(p.updated_at),
+            ],
+        )?;
+    }
+    for m in &changes.milestones {
+        applier.upsert(
+            SyncEntity::Milestone,
+            &m.id.to_string(),
+            m.updated_at,
+            params![
+                m.id.to_string(),
+                m.project_id.to_string(),
+                m.number,
+                m.name,
+                m.description,
+                m.technical_lead,
+                m.team,
+                m.design_doc_url,
+                opt_dt(m.start_date),
+                opt_dt(m.due_date),
+                m.jira_epic,
+                m.recurrence_rule,
+                m.series_id.map(|id| id.to_string()),
+                m.series_index,
+                m.estimated_days,
+                m.actual_days,
+                dt(m.created_at),
+                dt(m.updated_at),
+            ],
+        )?;
+    }
+    for n in &changes.project_notes {
+        applier.upsert(
+            SyncEntity::ProjectNote,
+            &n.id.to_string(),
+            n.updated_at,
+            params![
+                n.id.to_string(),
+                n.project_id.to_string(),
+                n.title,
+                n.body,
+                dt(n.created_at),
+                dt(n.updated_at),
+                n.kind.as_str(),
+            ],
+        )?;
+    }
+    for n in &changes.milestone_notes {
+        applier.upsert(
+            SyncEntity::MilestoneNote,
+            &n.id.to_string(),
+            n.updated_at,
+            params![
+                n.id.to_string(),
+                n.milestone_id.to_string(),
+                n.title,
+                n.body,
+                dt(n.created_at),
+                dt(n.updated_at),
+            ],
+        )?;
+    }
+    for n in &changes.person_notes {
+        applier.upsert(
+            SyncEntity::PersonNote,
+            &n.id.to_string(),
+            n.updated_at,
+            params![
+                n.id.to_string(),
+                n.person_email,
+                n.title,
+                n.body,
+                dt(n.created_at),
+                dt(n.updated_at),
+            ],
+        )?;
+    }
+
+    // Links are derived from note bodies, so refresh them for the notes that changed
+    {
+        let links = NoteLinkRepository::new(conn);
+        let applied = |entity: SyncEntity, id: &Uuid| {
+            !applier.report.skipped.iter().any(|s| s.entity == entity && s.key == id.to_string())
+        };
+        for n in changes.project_notes.iter().filter(|n| applied(SyncEntity::ProjectNote, &n.id)) {
+            links.sync_note(NoteType::Project, &n.id, &n.body)?;
+        }
+        for n in changes.milestone_notes.iter().filter(|n| applied(SyncEntity::MilestoneNote, &n.id)) {
+            links.sync_note(NoteType::Milestone, &n.id, &n.body)?;
+        }
+    }
+
+    // Notes and milestones go before the projects and people they refer to
+    let mut deleted: Vec<&Tombstone> = changes.deleted.iter().collect();
+    deleted.sort_by_key(|t| std::cmp::Reverse(SyncEntity::ALL.iter().position(|e| *e == t.entity)));
+    for tombstone in deleted {
+        applier.delete(tombstone)?;
+    }
+
+    tx.commit().map_err(|e| match Error::from(e) {
+        Error::ForeignKeyViolation { .. } => {
+            Error::Invalid("The changes refer to records that don't exist".to_string())
+        }
+        other => other,
+    })?;
+
+    log::debug!(
+        "Applied sync changes: {} stored, {} deleted, {} skipped",
+        applier.report.applied,
+        applier.report.deleted,
+        applier.report.skipped.len()
+    );
+    Ok(applier.report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{self, SubteamPolicy};
+    use chrono::TimeZone;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn epoch() -> DateTime<Utc> {
+        Utc.timestamp_opt(0, 0).unwrap()
+    }
+
+    /// Everything in the database, without versions, which each side keeps for itself
+    fn dump(conn: &Connection) -> serde_json::Value {
+        let mut changes = get_changes_since(conn, epoch()).unwrap();
+        changes.since = None;
+        changes.until = None;
+        let mut value = serde_json::to_value(changes).unwrap();
+        for rows in value.as_object_mut().unwrap().values_mut() {
+            for row in rows.as_array_mut().into_iter().flatten() {
+                row.as_object_mut().unwrap().remove("version");
+            }
+        }
+        value
+    }
+
+    /// Push the changes made on `from` since `since` to `to`, returning the report
+    fn push(from: &Connection, to: &Connection, since: DateTime<Utc>) -> ApplyReport {
+        let changes = get_changes_since(from, since).unwrap();
+        apply_changes(to, &changes, ConflictPolicy::LastWriteWins).unwrap()
+    }
+
+    #[test]
+    fn test_get_changes_since() {
+        let conn = setup_test_db();
+        let people = PersonRepository::new(&conn);
+        let projects = ProjectRepository::new(&conn);
+        people
+            .create(&Person::new(
+                "alice@example.com".to_string(),
+                "Alice".to_string(),
+            ))
+            .unwrap();
+        let apollo = Project::new("Apollo".to_string());
+        projects.create(&apollo).unwrap();
+        let gemini = Project::new("Gemini".to_string());
+        projects.create(&gemini).unwrap();
+
+        let all = get_changes_since(&conn, epoch()).unwrap();
+        assert_eq!(all.people.len(), 1);
+        assert_eq!(all.projects.len(), 2);
+        assert!(all.deleted.is_empty());
+
+        let since = all.until.unwrap();
+        assert!(get_changes_since(&conn, since).unwrap().is_empty());
+
+        let mut apollo = projects.find_by_id(&apollo.id).unwrap().unwrap();
+        apollo.description = Some("To the moon".to_string());
+        projects.update(&apollo).unwrap();
+        projects.delete(&gemini.id).unwrap();
+
+        let changes = get_changes_since(&conn, since).unwrap();
+        assert!(changes.people.is_empty());
+        assert_eq!(changes.projects.len(), 1);
+        assert_eq!(
+            changes.projects[0].description.as_deref(),
+            Some("To the moon")
+        );
+        assert_eq!(changes.deleted.len(), 1);
+        assert_eq!(changes.deleted[0].entity, SyncEntity::Project);
+        assert_eq!(changes.deleted[0].key, gemini.id.to_string());
+
+        // A row created again after its delete isn't reported as deleted
+        people.delete("alice@example.com", false).unwrap();
+        people
+            .create(&Person::new(
+                "alice@example.com".to_string(),
+                "Alice".to_string(),
+            ))
+            .unwrap();
+        let changes = get_changes_since(&conn, since).unwrap();
+        assert_eq!(changes.people.len(), 1);
+        assert_eq!(changes.deleted.len(), 1);
+
+        assert_eq!(prune_tombstones(&conn, since).unwrap(), 0);
+        let later = Utc::now() + chrono::Duration::seconds(1);
+        assert_eq!(prune_tombstones(&conn, later).unwrap(), 2);
+        assert!(get_changes_since(&conn, since).unwrap().deleted.is_empty());
+    }
+
+    #[test]
+    fn test_two_databases_converge() {
+        let server = setup_test_db();
+        let phone = setup_test_db();
+
+        let people = PersonRepository::new(&server);
+        let mut bob = Person::new("bob@example.com".to_string(), "Bob".to_string());
+        people.create(&bob).unwrap();
+        // Alice reports to Bob but is listed first, which only works because keys are checked at the end
+        let mut alice = Person::new("alice@example.com".to_string(), "Alice".to_string());
+        alice.manager = Some(bob.email.clone());
+        alice.updated_at = bob.updated_at - chrono::Duration::seconds(1);
+        people.create(&alice).unwrap();
+        db::TeamRepository::new(&server)
+            .create(&Team::new("Platform".to_string()))
+            .unwrap();
+        let projects = ProjectRepository::new(&server);
+        let mut project = Project::new("Apollo".to_string());
+        project.technical_lead = Some(alice.email.clone());
+        projects.create(&project).unwrap();
+        let milestone = Milestone::new(project.id, 1, "Design".to_string());
+        projects.add_milestone(&milestone).unwrap();
+        let note = MilestoneNote::new(milestone.id, "Kickoff".to_string(), "Notes".to_string());
+        projects.add_milestone_note(&note).unwrap();
+        let agenda = ProjectNote::new(project.id, "Agenda".to_string(), "Scope".to_string());
+        projects.add_project_note(&agenda).unwrap();
+
+        // First sync downloads everything
+        let first = get_changes_since(&server, epoch()).unwrap();
+        let report = apply_changes(&phone, &first, ConflictPolicy::LastWriteWins).unwrap();
+        assert_eq!(report.applied, 7);
+        assert_eq!(dump(&server), dump(&phone));
+        let last_sync = first.until.unwrap();
+
+        // Both sides change things while apart
+        let phone_projects = ProjectRepository::new(&phone);
+        let mut on_phone = phone_projects.find_by_id(&project.id).unwrap().unwrap();
+        on_phone.blocked = true;
+        on_phone.blocked_reason = Some("Waiting on legal".to_string());
+        phone_projects.update(&on_phone).unwrap();
+        phone_projects
+            .add_project_note(&ProjectNote::new(
+                project.id,
+                "Call".to_string(),
+                "From the road".to_string(),
+            ))
+            .unwrap();
+        phone_projects.delete_milestone_note(&note.id).unwrap();
+
+        bob = people.find_by_email(&bob.email).unwrap().unwrap();
+        bob.name = "Robert".to_string();
+        people.update(&bob).unwrap();
+        projects
+            .move_note(
+                &agenda.id,
+                &db::NoteTarget::Milestone {
+                    milestone_id: milestone.id,
+                },
+            )
+            .unwrap();
+        db::TeamRepository::new(&server)
+            .delete("Platform", SubteamPolicy::Refuse)
+            .unwrap();
+
+        // Push from the phone, then pull what the server changed
+        let push_report = push(&phone, &server, last_sync);
+        assert_eq!(push_report.applied, 2);
+        assert_eq!(push_report.deleted, 1);
+        assert!(push_report.skipped.is_empty());
+        push(&server, &phone, last_sync);
+
+        assert_eq!(dump(&server), dump(&phone));
+        let synced = projects.find_by_id(&project.id).unwrap().unwrap();
+        assert!(synced.blocked);
+        assert!(projects
+            .find_milestone_note_by_id(&note.id)
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            PersonRepository::new(&phone)
+                .find_by_email(&bob.email)
+                .unwrap()
+                .unwrap()
+                .name,
+            "Robert"
+        );
+        assert!(db::TeamRepository::new(&phone)
+            .find_by_name("Platform")
+            .unwrap()
+            .is_none());
+        assert!(phone_projects
+            .find_milestone_note_by_id(&agenda.id)
+            .unwrap()
+            .is_some());
+
+        // Syncing again changes nothing
+        let since = Utc::now();
+        assert!(get_changes_since(&server, since).unwrap().is_empty());
+        assert!(get_changes_since(&phone, since).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_conflict_policies() {
+        let server = setup_test_db();
+        let phone = setup_test_db();
+        let project = Project::new("Apollo".to_string());
+        ProjectRepository::new(&server).create(&project).unwrap();
+        let first = get_changes_since(&server, epoch()).unwrap();
+        apply_changes(&phone, &first, ConflictPolicy::LastWriteWins).unwrap();
+        let last_sync = first.until.unwrap();
+
+        let rename = |conn: &Connection, name: &str| {
+            let repo = ProjectRepository::new(conn);
+            let mut project = repo.find_by_id(&project.id).unwrap().unwrap();
+            project.name = name.to_string();
+            repo.update(&project).unwrap();
+        };
+        let name = |conn: &Connection| {
+            ProjectRepository::new(conn)
+                .find_by_id(&project.id)
+                .unwrap()
+                .unwrap()
+                .name
+        };
+
+        // The phone's edit is older than the server's
+        rename(&phone, "Apollo (phone)");
+        rename(&server, "Apollo (server)");
+        let changes = get_changes_since(&phone, last_sync).unwrap();
+
+        let report = apply_changes(&server, &changes, ConflictPolicy::LastWriteWins).unwrap();
+        assert_eq!(report.applied, 0);
+        assert_eq!(
+            report.skipped,
+            vec![SkippedChange {
+                entity: SyncEntity::Project,
+                key: project.id.to_string()
+            }]
+        );
+        assert_eq!(name(&server), "Apollo (server)");
+
+        // A newer edit wins under last-write-wins but not when the server wins
+        rename(&phone, "Apollo (phone again)");
+        let changes = get_changes_since(&phone, last_sync).unwrap();
+        let report = apply_changes(&server, &changes, ConflictPolicy::ServerWins).unwrap();
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(name(&server), "Apollo (server)");
+
+        let report = apply_changes(&server, &changes, ConflictPolicy::LastWriteWins).unwrap();
+        assert_eq!(report.applied, 1);
+        assert_eq!(name(&server), "Apollo (phone again)");
+
+        // Server-wins still takes changes to rows the server left alone
+        let later = Utc::now();
+        rename(&phone, "Apollo (final)");
+        let changes = get_changes_since(&phone, later).unwrap();
+        let report = apply_changes(&server, &changes, ConflictPolicy::ServerWins).unwrap();
+        assert_eq!(report.applied, 1);
+        assert_eq!(name(&server), "Apollo (final)");
+    }
+
+    #[test]
+    fn test_apply_changes_is_all_or_nothing() {
+        let conn = setup_test_db();
+        let mut project = Project::new("Apollo".to_string());
+        project.technical_lead = Some("nobody@example.com".to_string());
+        let changes = ChangeSet {
+            projects: vec![project.clone()],
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            apply_changes(&conn, &changes, ConflictPolicy::LastWriteWins),
+            Err(Error::Invalid(_))
+        ));
+        assert!(ProjectRepository::new(&conn)
+            .find_by_id(&project.id)
+            .unwrap()
+            .is_none());
+    }
+}
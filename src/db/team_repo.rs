@@ -2,11 +2,30 @@
 //
 // SPDX-License-Identifier: MIT
 
-use super::models::{Team, Person};
-use anyhow::{anyhow, Result};
+use super::error::{Error, Result};
+use super::{begin_or_join, get_datetime, like_prefix};
+use super::person_repo::{person_from_row, PersonRepository};
+use super::models::{Team, Person, SubteamPolicy, TeamTreeNode};
+use super::sync::{self, SyncEntity};
+use crate::utils::dt_to_db;
 use chrono::Utc;
 use rusqlite::{params, Connection, OptionalExtension};
 
+/// Columns selected for a team, in the order `team_from_row` expects
+const TEAM_COLUMNS: &str = "name, description, manager, parent_team, created_at, updated_at";
+
+/// Map a row selected with `TEAM_COLUMNS` to a team
+fn team_from_row(row: &rusqlite::Row) -> rusqlite::Result<Team> {
+    Ok(Team {
+        name: row.get(0)?,
+        description: row.get(1)?,
+        manager: row.get(2)?,
+        parent_team: row.get(3)?,
+        created_at: get_datetime(row, 4)?,
+        updated_at: get_datetime(row, 5)?,
+    })
+}
+
 /// Team repository for database operations
 pub struct TeamRepository<'a> {
     conn: &'a Connection,
@@ -19,60 +38,62 @@ impl<'a> TeamRepository<'a> {
 
     /// Create a new team
     pub fn create(&self, team: &Team) -> Result<()> {
-        self.conn.execute(
-            "INSERT INTO teams (name, description, manager, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5)",
-            params![
-                &team.name,
-                &team.description,
-                &team.manager,
-                team.created_at.to_rfc3339(),
-                team.updated_at.to_rfc3339(),
-            ],
+        self.check_parent_chain(&team.name, team.parent_team.as_deref())?;
+
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT INTO teams (name, description, manager, parent_team, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
         )?;
+        stmt.execute(params![
+            &team.name,
+            &team.description,
+            &team.manager,
+            &team.parent_team,
+            dt_to_db(team.created_at),
+            dt_to_db(team.updated_at),
+        ])
+        .map_err(|e| {
+            Error::from(e)
+                .with_conflict(format!("A team named {} already exists", team.name))
+                .with_field("manager")
+        })?;
         log::debug!("Created team: {}", team.name);
         Ok(())
     }
 
     /// Find a team by name
     pub fn find_by_name(&self, name: &str) -> Result<Option<Team>> {
-        let team = self
-            .conn
-            .query_row(
-                "SELECT name, description, manager, created_at, updated_at
-                 FROM teams WHERE name = ?1",
-                params![name],
-                |row| {
-                    Ok(Team {
-                        name: row.get(0)?,
-                        description: row.get(1)?,
-                        manager: row.get(2)?,
-                        created_at: row.get(3)?,
-                        updated_at: row.get(4)?,
-                    })
-                },
-            )
-            .optional()?;
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM teams WHERE name = ?1",
+            TEAM_COLUMNS
+        ))?;
+        let team = stmt.query_row(params![name], team_from_row).optional()?;
         Ok(team)
     }
 
     /// List all teams
     pub fn list_all(&self) -> Result<Vec<Team>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT name, description, manager, created_at, updated_at
-             FROM teams ORDER BY name",
-        )?;
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM teams ORDER BY name",
+            TEAM_COLUMNS
+        ))?;
 
         let teams = stmt
-            .query_map([], |row| {
-                Ok(Team {
-                    name: row.get(0)?,
-                    description: row.get(1)?,
-                    manager: row.get(2)?,
-                    created_at: row.get(3)?,
-                    updated_at: row.get(4)?,
-                })
-            })?
+            .query_map([], team_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(teams)
+    }
+
+    /// List a page of teams sorted by name
+    pub fn list_page(&self, limit: usize, offset: usize) -> Result<Vec<Team>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM teams ORDER BY name LIMIT ?1 OFFSET ?2",
+            TEAM_COLUMNS
+        ))?;
+
+        let teams = stmt
+            .query_map(params![limit as i64, offset as i64], team_from_row)?
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(teams)
@@ -81,91 +102,223 @@ impl<'a> TeamRepository<'a> {
     /// Search teams by name (for autocomplete)
     pub fn search_by_name(&self, query: &str) -> Result<Vec<Team>> {
         let search_pattern = format!("%{}%", query);
-        let mut stmt = self.conn.prepare(
-            "SELECT name, description, manager, created_at, updated_at
-             FROM teams WHERE name LIKE ?1 ORDER BY name LIMIT 20",
-        )?;
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM teams WHERE name LIKE ?1 ORDER BY name LIMIT 20",
+            TEAM_COLUMNS
+        ))?;
 
         let teams = stmt
-            .query_map(params![search_pattern], |row| {
-                Ok(Team {
-                    name: row.get(0)?,
-                    description: row.get(1)?,
-                    manager: row.get(2)?,
-                    created_at: row.get(3)?,
-                    updated_at: row.get(4)?,
-                })
-            })?
+            .query_map(params![search_pattern], team_from_row)?
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(teams)
     }
 
+    /// Suggest names of teams that start with `prefix`, ignoring case
+    pub fn autocomplete(&self, prefix: &str, limit: usize) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT name FROM teams WHERE name LIKE ?1 ESCAPE '\\' ORDER BY name COLLATE NOCASE LIMIT ?2",
+        )?;
+
+        let names = stmt
+            .query_map(params![like_prefix(prefix), limit as i64], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(names)
+    }
+
     /// Update a team
     pub fn update(&self, team: &Team) -> Result<()> {
-        let rows = self.conn.execute(
-            "UPDATE teams SET description = ?1, manager = ?2, updated_at = ?3
-             WHERE name = ?4",
-            params![
+        self.check_parent_chain(&team.name, team.parent_team.as_deref())?;
+
+        let mut stmt = self.conn.prepare_cached(
+            "UPDATE teams SET description = ?1, manager = ?2, parent_team = ?3, updated_at = ?4
+             WHERE name = ?5",
+        )?;
+        let rows = stmt
+            .execute(params![
                 &team.description,
                 &team.manager,
-                Utc::now().to_rfc3339(),
+                &team.parent_team,
+                dt_to_db(Utc::now()),
                 &team.name,
-            ],
-        )?;
+            ])
+            .map_err(|e| Error::from(e).with_field("manager"))?;
 
         if rows == 0 {
-            return Err(anyhow!("Team not found: {}", team.name));
+            return Err(Error::not_found("Team", &team.name));
         }
 
         log::debug!("Updated team: {}", team.name);
         Ok(())
     }
 
+    /// Make sure giving `name` the parent team `parent` doesn't create a cycle
+    ///
+    /// Walks up the hierarchy from `parent`, stopping if it reaches a team
+    /// already on the chain, so a cycle that already exists further up
+    /// can't make it loop forever.
+    fn check_parent_chain(&self, name: &str, parent: Option<&str>) -> Result<()> {
+        let Some(parent) = parent else {
+            return Ok(());
+        };
+        let mut chain = vec![name.to_string(), parent.to_string()];
+        if parent == name {
+            return Err(Error::CycleDetected { relation: "parent team", chain });
+        }
+
+        let mut stmt = self.conn.prepare_cached("SELECT parent_team FROM teams WHERE name = ?1")?;
+        loop {
+            let current = &chain[chain.len() - 1];
+            let next: Option<Option<String>> = stmt.query_row(params![current], |row| row.get(0)).optional()?;
+            let next = match next {
+                Some(Some(next)) => next,
+                None if chain.len() == 2 => {
+                    return Err(Error::ForeignKeyViolation { field: "parent_team".to_string() });
+                }
+                _ => return Ok(()),
+            };
+            // Reaching a team already on the chain means a cycle above that doesn't include this team
+            let seen = chain[1..].contains(&next);
+            chain.push(next);
+            if chain[chain.len() - 1] == name {
+                return Err(Error::CycleDetected { relation: "parent team", chain });
+            }
+            if seen {
+                return Ok(());
+            }
+        }
+    }
+
     /// Delete a team
-    pub fn delete(&self, name: &str) -> Result<()> {
-        let rows = self.conn.execute("DELETE FROM teams WHERE name = ?1", params![name])?;
+    ///
+    /// `subteams` decides what happens when other teams still have this one
+    /// as their parent: refuse the delete, or make them top-level teams.
+    pub fn delete(&self, name: &str, subteams: SubteamPolicy) -> Result<()> {
+        let children = self.get_subteams(name)?;
+
+        let tx = begin_or_join(self.conn)?;
+        if !children.is_empty() {
+            match subteams {
+                SubteamPolicy::Refuse => {
+                    let names: Vec<&str> = children.iter().map(|t| t.name.as_str()).collect();
+                    return Err(Error::Conflict(format!(
+                        "Team {} still has sub-teams: {}",
+                        name,
+                        names.join(", ")
+                    )));
+                }
+                SubteamPolicy::Detach => {
+                    self.conn
+                        .prepare_cached("UPDATE teams SET parent_team = NULL, updated_at = ?1 WHERE parent_team = ?2")?
+                        .execute(params![dt_to_db(Utc::now()), name])?;
+                }
+            }
+        }
+
+        let rows = self
+            .conn
+            .prepare_cached("DELETE FROM teams WHERE name = ?1")?
+            .execute(params![name])?;
 
         if rows == 0 {
-            return Err(anyhow!("Team not found: {}", name));
+            return Err(Error::not_found("Team", name));
+        }
+        sync::record_deletion(self.conn, SyncEntity::Team, name)?;
+        if let Some(tx) = tx {
+            tx.commit()?;
         }
 
         log::debug!("Deleted team: {}", name);
         Ok(())
     }
 
+    /// Get the teams whose parent is `name`, sorted by name
+    pub fn get_subteams(&self, name: &str) -> Result<Vec<Team>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM teams WHERE parent_team = ?1 ORDER BY name",
+            TEAM_COLUMNS
+        ))?;
+
+        let teams = stmt
+            .query_map(params![name], team_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(teams)
+    }
+
+    /// Get every team arranged as a tree under the top-level teams
+    ///
+    /// Teams caught in a parent cycle, which only data written by older
+    /// versions can have, have no top-level ancestor and are left out.
+    pub fn get_team_tree(&self) -> Result<Vec<TeamTreeNode>> {
+        // Paths join names with the unit separator, which sorts before any
+        // printable character, so ordering by path lists each team right
+        // before its sub-teams
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "WITH RECURSIVE tree(name, depth, path) AS (
+                 SELECT name, 0, name || char(31) FROM teams WHERE parent_team IS NULL
+                 UNION ALL
+                 SELECT t.name, tree.depth + 1, tree.path || t.name || char(31)
+                 FROM teams t INNER JOIN tree ON t.parent_team = tree.name
+                 WHERE instr(char(31) || tree.path, char(31) || t.name || char(31)) = 0
+             )
+             SELECT {}, depth FROM tree INNER JOIN teams USING (name) ORDER BY path",
+            TEAM_COLUMNS
+        ))?;
+
+        let rows = stmt
+            .query_map([], |row| Ok((team_from_row(row)?, row.get::<_, i64>(6)? as usize)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Rows come in depth-first order, so the stack holds the current team's ancestors
+        let mut roots = Vec::new();
+        let mut stack: Vec<TeamTreeNode> = Vec::new();
+        for (team, depth) in rows {
+            while stack.len() > depth {
+                close_tree_node(&mut stack, &mut roots);
+            }
+            stack.push(TeamTreeNode { team, subteams: Vec::new() });
+        }
+        while !stack.is_empty() {
+            close_tree_node(&mut stack, &mut roots);
+        }
+
+        Ok(roots)
+    }
+
     /// Add a member to a team
     pub fn add_member(&self, team_name: &str, person_email: &str) -> Result<()> {
         // Verify team exists
         if self.find_by_name(team_name)?.is_none() {
-            return Err(anyhow!("Team not found: {}", team_name));
+            return Err(Error::not_found("Team", team_name));
         }
 
         // Verify person exists
         let person_exists: bool = self
             .conn
-            .query_row(
-                "SELECT COUNT(*) FROM people WHERE email = ?1",
-                params![person_email],
-                |row| {
-                    let count: i32 = row.get(0)?;
-                    Ok(count > 0)
-                },
-            )?;
+            .prepare_cached("SELECT COUNT(*) FROM people WHERE email = ?1")?
+            .query_row(params![person_email], |row| {
+                let count: i32 = row.get(0)?;
+                Ok(count > 0)
+            })?;
 
         if !person_exists {
-            return Err(anyhow!("Person not found: {}", person_email));
+            return Err(Error::not_found("Person", person_email));
         }
 
-        self.conn.execute(
+        let mut stmt = self.conn.prepare_cached(
             "INSERT INTO team_members (team_name, person_email, created_at)
              VALUES (?1, ?2, ?3)",
-            params![
-                team_name,
-                person_email,
-                Utc::now().to_rfc3339(),
-            ],
         )?;
+        stmt.execute(params![
+            team_name,
+            person_email,
+            dt_to_db(Utc::now()),
+        ])
+        .map_err(|e| {
+            Error::from(e).with_conflict(format!("{} is already a member of team {}", person_email, team_name))
+        })?;
 
         log::debug!("Added {} to team {}", person_email, team_name);
         Ok(())
@@ -173,16 +326,15 @@ impl<'a> TeamRepository<'a> {
 
     /// Remove a member from a team
     pub fn remove_member(&self, team_name: &str, person_email: &str) -> Result<()> {
-        let rows = self.conn.execute(
+        let mut stmt = self.conn.prepare_cached(
             "DELETE FROM team_members WHERE team_name = ?1 AND person_email = ?2",
-            params![team_name, person_email],
         )?;
+        let rows = stmt.execute(params![team_name, person_email])?;
 
         if rows == 0 {
-            return Err(anyhow!(
-                "Team member not found: {} in team {}",
-                person_email,
-                team_name
+            return Err(Error::not_found(
+                "Team member",
+                format!("{} in team {}", person_email, team_name),
             ));
         }
 
@@ -192,36 +344,63 @@ impl<'a> TeamRepository<'a> {
 
     /// Get all members of a team
     pub fn get_members(&self, team_name: &str) -> Result<Vec<Person>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT p.email, p.name, p.team, p.manager, p.notes, p.created_at, p.updated_at
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT p.email, p.name, p.team, p.manager, p.notes, p.created_at, p.updated_at, p.active, p.version, p.avatar_path, p.external, p.organization
              FROM people p
              INNER JOIN team_members tm ON p.email = tm.person_email
              WHERE tm.team_name = ?1
              ORDER BY p.name",
         )?;
 
-        let members = stmt
-            .query_map(params![team_name], |row| {
-                Ok(Person {
-                    email: row.get(0)?,
-                    name: row.get(1)?,
-                    team: row.get(2)?,
-                    manager: row.get(3)?,
-                    notes: row.get(4)?,
-                    created_at: row.get(5)?,
-                    updated_at: row.get(6)?,
-                })
-            })?
+        let mut members = stmt
+            .query_map(params![team_name], person_from_row)?
             .collect::<Result<Vec<_>, _>>()?;
+        PersonRepository::new(self.conn).load_skills(&mut members)?;
+
+        Ok(members)
+    }
+
+    /// Get the members of a team and all of its sub-teams, each listed once
+    pub fn get_members_recursive(&self, team_name: &str) -> Result<Vec<Person>> {
+        // UNION drops teams already visited, so a parent cycle can't recurse forever
+        let mut stmt = self.conn.prepare_cached(
+            "WITH RECURSIVE subtree(name) AS (
+                 SELECT ?1
+                 UNION
+                 SELECT t.name FROM teams t INNER JOIN subtree s ON t.parent_team = s.name
+             )
+             SELECT p.email, p.name, p.team, p.manager, p.notes, p.created_at, p.updated_at, p.active, p.version, p.avatar_path, p.external, p.organization
+             FROM people p
+             WHERE p.email IN (
+                 SELECT tm.person_email FROM team_members tm
+                 INNER JOIN subtree s ON tm.team_name = s.name
+             )
+             ORDER BY p.name",
+        )?;
+
+        let mut members = stmt
+            .query_map(params![team_name], person_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        PersonRepository::new(self.conn).load_skills(&mut members)?;
 
         Ok(members)
     }
 }
 
+/// Pop the top of `stack` and attach it to its parent, or to `roots` if it has none
+fn close_tree_node(stack: &mut Vec<TeamTreeNode>, roots: &mut Vec<TeamTreeNode>) {
+    if let Some(node) = stack.pop() {
+        match stack.last_mut() {
+            Some(parent) => parent.subteams.push(node),
+            None => roots.push(node),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db::{open_database, Person};
+    use crate::db::{open_database, Error, Person};
     use tempfile::tempdir;
 
     fn setup_test_db() -> (tempfile::TempDir, Connection) {
@@ -254,7 +433,7 @@ mod tests {
 
         // Try to create duplicate
         let result = repo.create(&team);
-        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), Error::Conflict(_)));
     }
 
     #[test]
@@ -286,6 +465,9 @@ mod tests {
         assert_eq!(teams[0].name, "Design");
         assert_eq!(teams[1].name, "Engineering");
         assert_eq!(teams[2].name, "Product");
+
+        let page = repo.list_page(2, 1).unwrap();
+        assert_eq!(page.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(), vec!["Engineering", "Product"]);
     }
 
     #[test]
@@ -331,7 +513,7 @@ mod tests {
 
         let team = Team::new("Nonexistent".to_string());
         let result = repo.update(&team);
-        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), Error::NotFound { entity: "Team", .. }));
     }
 
     #[test]
@@ -342,7 +524,7 @@ mod tests {
         let team = Team::new("Engineering".to_string());
         repo.create(&team).unwrap();
 
-        repo.delete("Engineering").unwrap();
+        repo.delete("Engineering", SubteamPolicy::Refuse).unwrap();
 
         let found = repo.find_by_name("Engineering").unwrap();
         assert!(found.is_none());
@@ -353,8 +535,8 @@ mod tests {
         let (_dir, conn) = setup_test_db();
         let repo = TeamRepository::new(&conn);
 
-        let result = repo.delete("Nonexistent");
-        assert!(result.is_err());
+        let result = repo.delete("Nonexistent", SubteamPolicy::Refuse);
+        assert!(matches!(result.unwrap_err(), Error::NotFound { entity: "Team", .. }));
     }
 
     #[test]
@@ -389,7 +571,7 @@ mod tests {
         person_repo.create(&person).unwrap();
 
         let result = team_repo.add_member("Nonexistent", "test@example.com");
-        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), Error::NotFound { entity: "Team", .. }));
     }
 
     #[test]
@@ -401,7 +583,7 @@ mod tests {
         team_repo.create(&team).unwrap();
 
         let result = team_repo.add_member("Engineering", "nonexistent@example.com");
-        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), Error::NotFound { entity: "Person", .. }));
     }
 
     #[test]
@@ -435,7 +617,7 @@ mod tests {
         team_repo.create(&team).unwrap();
 
         let result = team_repo.remove_member("Engineering", "nonexistent@example.com");
-        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), Error::NotFound { entity: "Team member", .. }));
     }
 
     #[test]
@@ -480,7 +662,7 @@ mod tests {
         team_repo.add_member("Engineering", "test@example.com").unwrap();
 
         // Delete team
-        team_repo.delete("Engineering").unwrap();
+        team_repo.delete("Engineering", SubteamPolicy::Refuse).unwrap();
 
         // Verify team_members were deleted (check by querying directly)
         let count: i32 = conn
@@ -496,4 +678,111 @@ mod tests {
         let person_found = person_repo.find_by_email("test@example.com").unwrap();
         assert!(person_found.is_some());
     }
+
+    /// Create `name` under `parent`
+    fn create_subteam(repo: &TeamRepository, name: &str, parent: Option<&str>) {
+        let mut team = Team::new(name.to_string());
+        team.parent_team = parent.map(str::to_string);
+        repo.create(&team).unwrap();
+    }
+
+    #[test]
+    fn test_team_tree() {
+        let (_dir, conn) = setup_test_db();
+        let repo = TeamRepository::new(&conn);
+
+        create_subteam(&repo, "Platform", None);
+        create_subteam(&repo, "Storage", Some("Platform"));
+        create_subteam(&repo, "Compute", Some("Platform"));
+        create_subteam(&repo, "Block", Some("Storage"));
+        create_subteam(&repo, "Design", None);
+        create_subteam(&repo, "Platform Ops", None);
+
+        let subteams = repo.get_subteams("Platform").unwrap();
+        assert_eq!(subteams.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(), vec!["Compute", "Storage"]);
+        assert_eq!(subteams[1].parent_team.as_deref(), Some("Platform"));
+
+        let tree = repo.get_team_tree().unwrap();
+        assert_eq!(tree.iter().map(|n| n.team.name.as_str()).collect::<Vec<_>>(), vec!["Design", "Platform", "Platform Ops"]);
+        let platform = &tree[1];
+        assert_eq!(
+            platform.subteams.iter().map(|n| n.team.name.as_str()).collect::<Vec<_>>(),
+            vec!["Compute", "Storage"]
+        );
+        assert!(platform.subteams[0].subteams.is_empty());
+        assert_eq!(platform.subteams[1].subteams[0].team.name, "Block");
+        assert!(tree[2].subteams.is_empty());
+    }
+
+    #[test]
+    fn test_parent_team_cycles_are_rejected() {
+        let (_dir, conn) = setup_test_db();
+        let repo = TeamRepository::new(&conn);
+
+        create_subteam(&repo, "Platform", None);
+        create_subteam(&repo, "Storage", Some("Platform"));
+        create_subteam(&repo, "Block", Some("Storage"));
+
+        let mut platform = repo.find_by_name("Platform").unwrap().unwrap();
+        platform.parent_team = Some("Block".to_string());
+        let err = repo.update(&platform).unwrap_err();
+        assert_eq!(err.to_string(), "Circular parent team reference: Platform -> Block -> Storage -> Platform");
+
+        platform.parent_team = Some("Platform".to_string());
+        assert!(matches!(repo.update(&platform).unwrap_err(), Error::CycleDetected { relation: "parent team", .. }));
+
+        platform.parent_team = Some("Nonexistent".to_string());
+        assert!(matches!(repo.update(&platform).unwrap_err(), Error::ForeignKeyViolation { field } if field == "parent_team"));
+        assert_eq!(repo.find_by_name("Platform").unwrap().unwrap().parent_team, None);
+    }
+
+    #[test]
+    fn test_get_members_recursive() {
+        let (_dir, conn) = setup_test_db();
+        let repo = TeamRepository::new(&conn);
+        let person_repo = crate::db::PersonRepository::new(&conn);
+
+        create_subteam(&repo, "Platform", None);
+        create_subteam(&repo, "Storage", Some("Platform"));
+        create_subteam(&repo, "Block", Some("Storage"));
+        create_subteam(&repo, "Design", None);
+        for (email, name) in [("alice@example.com", "Alice"), ("bob@example.com", "Bob"), ("carol@example.com", "Carol"), ("dave@example.com", "Dave")] {
+            person_repo.create(&Person::new(email.to_string(), name.to_string())).unwrap();
+        }
+        repo.add_member("Platform", "alice@example.com").unwrap();
+        repo.add_member("Storage", "bob@example.com").unwrap();
+        repo.add_member("Block", "carol@example.com").unwrap();
+        repo.add_member("Block", "alice@example.com").unwrap();
+        repo.add_member("Design", "dave@example.com").unwrap();
+
+        let names = |members: Vec<Person>| members.into_iter().map(|p| p.name).collect::<Vec<_>>();
+        assert_eq!(names(repo.get_members_recursive("Platform").unwrap()), vec!["Alice", "Bob", "Carol"]);
+        assert_eq!(names(repo.get_members_recursive("Storage").unwrap()), vec!["Alice", "Bob", "Carol"]);
+        assert_eq!(names(repo.get_members("Storage").unwrap()), vec!["Bob"]);
+
+        // A cycle written directly, as the repository would refuse it
+        conn.execute("UPDATE teams SET parent_team = 'Block' WHERE name = 'Platform'", []).unwrap();
+        assert_eq!(names(repo.get_members_recursive("Storage").unwrap()), vec!["Alice", "Bob", "Carol"]);
+        let tree = repo.get_team_tree().unwrap();
+        assert_eq!(tree.iter().map(|n| n.team.name.as_str()).collect::<Vec<_>>(), vec!["Design"]);
+    }
+
+    #[test]
+    fn test_delete_team_with_subteams() {
+        let (_dir, conn) = setup_test_db();
+        let repo = TeamRepository::new(&conn);
+
+        create_subteam(&repo, "Platform", None);
+        create_subteam(&repo, "Storage", Some("Platform"));
+        create_subteam(&repo, "Compute", Some("Platform"));
+
+        let err = repo.delete("Platform", SubteamPolicy::Refuse).unwrap_err();
+        assert_eq!(err.to_string(), "Team Platform still has sub-teams: Compute, Storage");
+        assert!(repo.find_by_name("Platform").unwrap().is_some());
+
+        repo.delete("Platform", SubteamPolicy::Detach).unwrap();
+        assert!(repo.find_by_name("Platform").unwrap().is_none());
+        assert_eq!(repo.find_by_name("Storage").unwrap().unwrap().parent_team, None);
+        assert_eq!(repo.get_team_tree().unwrap().len(), 2);
+    }
 }
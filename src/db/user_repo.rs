@@ -0,0 +1,270 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Users of a shared database and their roles
+//!
+//! Permissions are off while nobody is listed, so a database used by one
+//! person behaves as it always has. Once the first user is added, everyone
+//! is checked against the list: people who aren't on it, and anyone without
+//! a current_user_email, can only read. Only admins can change the list.
+
+use super::error::{Error, Result};
+use super::models::{User, UserRole};
+use super::project_repo::parse_column;
+use super::{begin_or_join, get_datetime};
+use crate::utils::dt_to_db;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Columns selected for a user, in the order `user_from_row` expects
+const USER_COLUMNS: &str = "email, role, created_at, updated_at";
+
+/// Map a row selected with `USER_COLUMNS` to a user
+fn user_from_row(row: &rusqlite::Row) -> rusqlite::Result<User> {
+    Ok(User {
+        email: row.get(0)?,
+        role: parse_column(row, 1)?,
+        created_at: get_datetime(row, 2)?,
+        updated_at: get_datetime(row, 3)?,
+    })
+}
+
+/// User repository for the roles people have in a shared database
+pub struct UserRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> UserRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// List users by email
+    pub fn list_all(&self) -> Result<Vec<User>> {
+        let mut stmt = self
+            .conn
+            .prepare_cached(&format!("SELECT {} FROM users ORDER BY email", USER_COLUMNS))?;
+        let users = stmt.query_map([], user_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(users)
+    }
+
+    /// Find a user by email, ignoring case
+    pub fn find_by_email(&self, email: &str) -> Result<Option<User>> {
+        let mut stmt = self
+            .conn
+            .prepare_cached(&format!("SELECT {} FROM users WHERE email = ?1", USER_COLUMNS))?;
+        let user = stmt.query_row(params![email.trim()], user_from_row).optional()?;
+        Ok(user)
+    }
+
+    /// Whether anyone is listed, which turns permissions on
+    pub fn permissions_enabled(&self) -> Result<bool> {
+        let enabled = self
+            .conn
+            .prepare_cached("SELECT EXISTS (SELECT 1 FROM users)")?
+            .query_row([], |row| row.get(0))?;
+        Ok(enabled)
+    }
+
+    /// The role someone acts with
+    ///
+    /// Everyone is an admin while nobody is listed. After that, people who
+    /// aren't listed, and a missing email, are viewers.
+    pub fn effective_role(&self, email: Option<&str>) -> Result<UserRole> {
+        if !self.permissions_enabled()? {
+            return Ok(UserRole::Admin);
+        }
+        let Some(email) = email else {
+            return Ok(UserRole::Viewer);
+        };
+        Ok(self.find_by_email(email)?.map_or(UserRole::Viewer, |user| user.role))
+    }
+
+    /// Add a user or change their role; only admins can
+    ///
+    /// The first user added must be an admin, and the last admin can't be
+    /// demoted, so there is always someone who can manage the list.
+    pub fn set_role(&self, acting: Option<&str>, email: &str, role: UserRole) -> Result<User> {
+        let email = email.trim();
+        if !email.contains('@') {
+            return Err(Error::Invalid(format!("User email must be an email address, got '{}'", email)));
+        }
+
+        let tx = begin_or_join(self.conn)?;
+        self.require_admin(acting, "set_user_role")?;
+        let existing = self.find_by_email(email)?;
+        if role != UserRole::Admin {
+            if !self.permissions_enabled()? {
+                return Err(Error::Invalid(
+                    "The first user must be an admin, so someone can manage users".to_string(),
+                ));
+            }
+            if existing.as_ref().is_some_and(|user| user.role == UserRole::Admin) && self.admin_count()? == 1 {
+                return Err(Error::Invalid(format!(
+                    "{} is the last admin; make someone else an admin first",
+                    email
+                )));
+            }
+        }
+
+        let now = dt_to_db(Utc::now());
+        self.conn
+            .prepare_cached(
+                "INSERT INTO users (email, role, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?3)
+                 ON CONFLICT(email) DO UPDATE SET role = excluded.role, updated_at = excluded.updated_at",
+            )?
+            .execute(params![email, role.as_str(), now])?;
+        let user = self
+            .find_by_email(email)?
+            .ok_or_else(|| Error::not_found("User", email))?;
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+        log::info!("Set role of {} to {}", user.email, user.role);
+
+        Ok(user)
+    }
+
+    /// Remove a user; only admins can
+    ///
+    /// The last admin can only be removed when they are the last user,
+    /// which turns permissions off again.
+    pub fn remove(&self, acting: Option<&str>, email: &str) -> Result<()> {
+        let tx = begin_or_join(self.conn)?;
+        self.require_admin(acting, "remove_user")?;
+        let user = self
+            .find_by_email(email)?
+            .ok_or_else(|| Error::not_found("User", email.trim()))?;
+        if user.role == UserRole::Admin && self.admin_count()? == 1 && self.list_all()?.len() > 1 {
+            return Err(Error::Invalid(format!(
+                "{} is the last admin; make someone else an admin first",
+                user.email
+            )));
+        }
+
+        self.conn
+            .prepare_cached("DELETE FROM users WHERE email = ?1")?
+            .execute(params![user.email])?;
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+        log::info!("Removed user {}", user.email);
+
+        Ok(())
+    }
+
+    /// Fail unless `acting` is an admin
+    fn require_admin(&self, acting: Option<&str>, operation: &str) -> Result<()> {
+        let role = self.effective_role(acting)?;
+        if role < UserRole::Admin {
+            return Err(Error::PermissionDenied {
+                operation: operation.to_string(),
+                user: acting.map(str::to_string),
+                role,
+                required: UserRole::Admin,
+            });
+        }
+        Ok(())
+    }
+
+    fn admin_count(&self) -> Result<i64> {
+        let count = self
+            .conn
+            .prepare_cached("SELECT COUNT(*) FROM users WHERE role = 'admin'")?
+            .query_row([], |row| row.get(0))?;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::schema;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        schema::initialize_schema(&conn).unwrap();
+        schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_everyone_is_admin_until_users_are_added() {
+        let conn = setup_test_db();
+        let repo = UserRepository::new(&conn);
+        assert!(!repo.permissions_enabled().unwrap());
+        assert_eq!(repo.effective_role(None).unwrap(), UserRole::Admin);
+        assert_eq!(repo.effective_role(Some("kim@example.com")).unwrap(), UserRole::Admin);
+
+        // The first user must be an admin
+        assert!(matches!(
+            repo.set_role(None, "lee@example.com", UserRole::Editor),
+            Err(Error::Invalid(_))
+        ));
+        repo.set_role(None, "Ada@Example.com", UserRole::Admin).unwrap();
+
+        assert!(repo.permissions_enabled().unwrap());
+        assert_eq!(repo.effective_role(Some("ada@example.com")).unwrap(), UserRole::Admin);
+        assert_eq!(repo.effective_role(Some("kim@example.com")).unwrap(), UserRole::Viewer);
+        assert_eq!(repo.effective_role(None).unwrap(), UserRole::Viewer);
+    }
+
+    #[test]
+    fn test_only_admins_manage_users() {
+        let conn = setup_test_db();
+        let repo = UserRepository::new(&conn);
+        let admin = Some("ada@example.com");
+        repo.set_role(None, "ada@example.com", UserRole::Admin).unwrap();
+        repo.set_role(admin, "lee@example.com", UserRole::Editor).unwrap();
+        repo.set_role(admin, "kim@example.com", UserRole::Viewer).unwrap();
+
+        for acting in [Some("lee@example.com"), Some("kim@example.com"), Some("nobody@example.com"), None] {
+            assert!(matches!(
+                repo.set_role(acting, "kim@example.com", UserRole::Admin),
+                Err(Error::PermissionDenied { required: UserRole::Admin, .. })
+            ));
+            assert!(matches!(
+                repo.remove(acting, "kim@example.com"),
+                Err(Error::PermissionDenied { .. })
+            ));
+        }
+
+        let promoted = repo.set_role(admin, "KIM@example.com", UserRole::Editor).unwrap();
+        assert_eq!(promoted.email, "kim@example.com");
+        assert_eq!(promoted.role, UserRole::Editor);
+        repo.remove(admin, "kim@example.com").unwrap();
+        assert_eq!(
+            repo.list_all().unwrap().iter().map(|u| u.email.as_str()).collect::<Vec<_>>(),
+            ["ada@example.com", "lee@example.com"]
+        );
+        assert!(matches!(repo.remove(admin, "kim@example.com"), Err(Error::NotFound { .. })));
+    }
+
+    #[test]
+    fn test_last_admin_stays() {
+        let conn = setup_test_db();
+        let repo = UserRepository::new(&conn);
+        let admin = Some("ada@example.com");
+        repo.set_role(None, "ada@example.com", UserRole::Admin).unwrap();
+        repo.set_role(admin, "lee@example.com", UserRole::Editor).unwrap();
+
+        assert!(matches!(
+            repo.set_role(admin, "ada@example.com", UserRole::Editor),
+            Err(Error::Invalid(_))
+        ));
+        assert!(matches!(repo.remove(admin, "ada@example.com"), Err(Error::Invalid(_))));
+
+        // Once someone else is an admin, the first can step down
+        repo.set_role(admin, "lee@example.com", UserRole::Admin).unwrap();
+        repo.set_role(admin, "ada@example.com", UserRole::Viewer).unwrap();
+        assert_eq!(repo.effective_role(admin).unwrap(), UserRole::Viewer);
+
+        // Removing the only user turns permissions off again
+        let lee = Some("lee@example.com");
+        repo.remove(lee, "ada@example.com").unwrap();
+        repo.remove(lee, "lee@example.com").unwrap();
+        assert!(!repo.permissions_enabled().unwrap());
+    }
+}
@@ -0,0 +1,205 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+use super::error::{Error, Result};
+use super::models::{Project, ProjectWatcher};
+use super::project_repo::{project_from_row, PROJECT_COLUMNS};
+use super::{get_datetime, get_uuid};
+use crate::utils::dt_to_db;
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// Trim a watcher's email and check that it looks like one
+fn watcher_email(email: &str) -> Result<&str> {
+    let email = email.trim();
+    if !email.contains('@') {
+        return Err(Error::Invalid(format!("'{}' is not an email address", email)));
+    }
+    Ok(email)
+}
+
+/// Repository for the people watching projects
+///
+/// Emails are compared ignoring case. Watchers don't have to be in the
+/// people directory, so anyone sharing the database can follow projects.
+pub struct WatcherRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> WatcherRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Start watching a project; watching it again changes nothing
+    pub fn watch(&self, project_id: &Uuid, email: &str) -> Result<()> {
+        let email = watcher_email(email)?;
+        self.conn
+            .prepare_cached(
+                "INSERT INTO project_watchers (project_id, watcher_email, created_at)
+                 VALUES (?1, ?2, ?3)
+                 ON CONFLICT (project_id, watcher_email) DO NOTHING",
+            )?
+            .execute(params![project_id.to_string(), email, dt_to_db(Utc::now())])
+            .map_err(|e| Error::from(e).with_field("project_id"))?;
+        log::debug!("{} is watching project {}", email, project_id);
+        Ok(())
+    }
+
+    /// Stop watching a project
+    pub fn unwatch(&self, project_id: &Uuid, email: &str) -> Result<()> {
+        let email = email.trim();
+        let rows = self
+            .conn
+            .prepare_cached("DELETE FROM project_watchers WHERE project_id = ?1 AND watcher_email = ?2")?
+            .execute(params![project_id.to_string(), email])?;
+
+        if rows == 0 {
+            return Err(Error::not_found(
+                "Project watcher",
+                format!("{} on project {}", email, project_id),
+            ));
+        }
+        log::debug!("{} stopped watching project {}", email, project_id);
+        Ok(())
+    }
+
+    /// The people watching a project, by email
+    pub fn list_watchers(&self, project_id: &Uuid) -> Result<Vec<ProjectWatcher>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT project_id, watcher_email, created_at FROM project_watchers
+             WHERE project_id = ?1 ORDER BY watcher_email",
+        )?;
+        let watchers = stmt
+            .query_map(params![project_id.to_string()], |row| {
+                Ok(ProjectWatcher {
+                    project_id: get_uuid(row, 0)?,
+                    watcher_email: row.get(1)?,
+                    created_at: get_datetime(row, 2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(watchers)
+    }
+
+    /// The projects someone is watching, by name
+    pub fn list_watched_projects(&self, email: &str) -> Result<Vec<Project>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {}
+             FROM projects p
+             WHERE EXISTS (SELECT 1 FROM project_watchers w
+                           WHERE w.project_id = p.id AND w.watcher_email = ?1)
+             ORDER BY name",
+            PROJECT_COLUMNS
+        ))?;
+        let projects = stmt
+            .query_map(params![email.trim()], project_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(projects)
+    }
+
+    /// IDs of the projects that concern someone: the ones they watch, manage
+    /// or are the technical lead of
+    pub fn relevant_project_ids(&self, email: &str) -> Result<HashSet<Uuid>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT project_id FROM project_watchers WHERE watcher_email = ?1
+             UNION
+             SELECT id FROM projects
+             WHERE manager = ?1 COLLATE NOCASE OR technical_lead = ?1 COLLATE NOCASE",
+        )?;
+        let ids = stmt
+            .query_map(params![email.trim()], |row| get_uuid(row, 0))?
+            .collect::<Result<_, _>>()?;
+        Ok(ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{self, Person, PersonRepository, ProjectRepository};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn create_project(conn: &Connection, name: &str) -> Project {
+        let project = Project::new(name.to_string());
+        ProjectRepository::new(conn).create(&project).unwrap();
+        project
+    }
+
+    #[test]
+    fn test_watch_and_unwatch() {
+        let conn = setup_test_db();
+        let apollo = create_project(&conn, "Apollo");
+        let gemini = create_project(&conn, "Gemini");
+        let repo = WatcherRepository::new(&conn);
+
+        repo.watch(&apollo.id, "alice@example.com").unwrap();
+        repo.watch(&apollo.id, " Alice@Example.com ").unwrap();
+        repo.watch(&apollo.id, "bob@example.com").unwrap();
+        repo.watch(&gemini.id, "alice@example.com").unwrap();
+        let emails = |id| -> Vec<String> {
+            repo.list_watchers(id).unwrap().into_iter().map(|w| w.watcher_email).collect()
+        };
+        assert_eq!(emails(&apollo.id), ["alice@example.com", "bob@example.com"]);
+
+        let names = |email| -> Vec<String> {
+            repo.list_watched_projects(email).unwrap().into_iter().map(|p| p.name).collect()
+        };
+        assert_eq!(names("ALICE@example.com"), ["Apollo", "Gemini"]);
+        assert_eq!(names("bob@example.com"), ["Apollo"]);
+
+        // Unwatching only removes that person's row
+        repo.unwatch(&apollo.id, "alice@example.com").unwrap();
+        assert_eq!(emails(&apollo.id), ["bob@example.com"]);
+        assert_eq!(names("alice@example.com"), ["Gemini"]);
+        assert!(matches!(
+            repo.unwatch(&apollo.id, "alice@example.com"),
+            Err(Error::NotFound { .. })
+        ));
+
+        assert!(matches!(repo.watch(&apollo.id, "alice"), Err(Error::Invalid(_))));
+        assert!(matches!(
+            repo.watch(&Uuid::new_v4(), "alice@example.com"),
+            Err(Error::ForeignKeyViolation { .. })
+        ));
+    }
+
+    #[test]
+    fn test_relevant_project_ids() {
+        let conn = setup_test_db();
+        PersonRepository::new(&conn)
+            .create(&Person::new("alice@example.com".to_string(), "Alice".to_string()))
+            .unwrap();
+        let watched = create_project(&conn, "Watched");
+        let mut managed = Project::new("Managed".to_string());
+        managed.manager = Some("alice@example.com".to_string());
+        let mut led = Project::new("Led".to_string());
+        led.technical_lead = Some("alice@example.com".to_string());
+        let other = create_project(&conn, "Other");
+        let projects = ProjectRepository::new(&conn);
+        projects.create(&managed).unwrap();
+        projects.create(&led).unwrap();
+
+        let repo = WatcherRepository::new(&conn);
+        repo.watch(&watched.id, "alice@example.com").unwrap();
+        repo.watch(&other.id, "bob@example.com").unwrap();
+
+        let relevant = repo.relevant_project_ids("Alice@Example.com").unwrap();
+        assert_eq!(relevant, HashSet::from([watched.id, managed.id, led.id]));
+        assert_eq!(
+            repo.relevant_project_ids("bob@example.com").unwrap(),
+            HashSet::from([other.id])
+        );
+        assert!(repo.relevant_project_ids("carol@example.com").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,589 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Graphviz and Mermaid diagrams of the organization and the portfolio
+//!
+//! [`build`] gathers a [`Diagram`] from the database: the org chart from each
+//! person's manager, team membership, or the projects with their
+//! dependencies. [`Diagram::render`] writes it as Graphviz DOT or Mermaid.
+//!
+//! Node ids are derived from what the node stands for, so the same person or
+//! project keeps its id between runs and across diagrams. Labels are escaped
+//! for each format, since names can contain quotes, newlines and markup.
+
+use crate::db::{
+    self, DependencyRepository, PersonRepository, Project, ProjectRepository, ProjectStatus,
+    TeamRepository,
+};
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Write as _};
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// What a diagram shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagramKind {
+    /// Everyone active, under their manager
+    Org,
+    /// Teams, their sub-teams and their members
+    Teams,
+    /// Projects and the projects they depend on, or their milestones when
+    /// there are no dependencies
+    Projects,
+}
+
+impl DiagramKind {
+    /// Name used on the command line and in tool arguments
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiagramKind::Org => "org",
+            DiagramKind::Teams => "teams",
+            DiagramKind::Projects => "projects",
+        }
+    }
+}
+
+impl FromStr for DiagramKind {
+    type Err = db::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "org" => Ok(DiagramKind::Org),
+            "teams" => Ok(DiagramKind::Teams),
+            "projects" | "dependencies" => Ok(DiagramKind::Projects),
+            _ => Err(db::Error::Invalid(format!(
+                "Invalid diagram '{}': expected org, teams or projects",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for DiagramKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Text format of a rendered diagram
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiagramFormat {
+    /// Graphviz DOT
+    Dot,
+    /// Mermaid flowchart
+    #[default]
+    Mermaid,
+}
+
+impl DiagramFormat {
+    /// Name used on the command line and in tool arguments
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiagramFormat::Dot => "dot",
+            DiagramFormat::Mermaid => "mermaid",
+        }
+    }
+}
+
+impl FromStr for DiagramFormat {
+    type Err = db::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "dot" | "graphviz" => Ok(DiagramFormat::Dot),
+            "mermaid" => Ok(DiagramFormat::Mermaid),
+            _ => Err(db::Error::Invalid(format!(
+                "Invalid diagram format '{}': expected dot or mermaid",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for DiagramFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How a node is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NodeStyle {
+    #[default]
+    Normal,
+    /// Past its due date and not done, drawn in red
+    Overdue,
+    /// Done, or not in the tracker, drawn in grey
+    Muted,
+}
+
+/// A box in a diagram
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    /// Identifier used in the rendered text; letters, digits and underscores only
+    pub id: String,
+    /// Text shown in the box
+    pub label: String,
+    pub style: NodeStyle,
+}
+
+/// An arrow between two nodes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    pub label: Option<String>,
+}
+
+/// Nodes and edges ready to render
+#[derive(Debug, Clone, Default)]
+pub struct Diagram {
+    /// Lay the diagram out left to right instead of top down
+    pub left_to_right: bool,
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+/// Build a diagram from the database, as of `now`
+pub fn build(conn: &Connection, kind: DiagramKind, now: DateTime<Utc>) -> db::Result<Diagram> {
+    match kind {
+        DiagramKind::Org => org_chart(conn),
+        DiagramKind::Teams => team_membership(conn),
+        DiagramKind::Projects => project_graph(conn, now),
+    }
+}
+
+/// Everyone active, with an arrow from each manager to their reports
+///
+/// Managers who are no longer active are still shown, in grey.
+pub fn org_chart(conn: &Connection) -> db::Result<Diagram> {
+    let people = PersonRepository::new(conn).list_all(true)?;
+    let names: HashMap<&str, &str> = people.iter().map(|p| (p.email.as_str(), p.name.as_str())).collect();
+    let mut diagram = Diagram::default();
+    let mut shown: HashSet<&str> = HashSet::new();
+    for person in people.iter().filter(|p| p.active) {
+        diagram.add_node(person_id(&person.email), &person.name, NodeStyle::Normal);
+        shown.insert(&person.email);
+    }
+    for person in people.iter().filter(|p| p.active) {
+        let Some(manager) = person.manager.as_deref() else {
+            continue;
+        };
+        if shown.insert(manager) {
+            let name = names.get(manager).copied().unwrap_or(manager);
+            diagram.add_node(person_id(manager), name, NodeStyle::Muted);
+        }
+        diagram.add_edge(person_id(manager), person_id(&person.email), None);
+    }
+    Ok(diagram)
+}
+
+/// Every team, with arrows to its sub-teams and its active members
+pub fn team_membership(conn: &Connection) -> db::Result<Diagram> {
+    let repo = TeamRepository::new(conn);
+    let teams = repo.list_all()?;
+    let names: HashSet<&str> = teams.iter().map(|t| t.name.as_str()).collect();
+    let mut diagram = Diagram::default();
+    let mut people: HashSet<String> = HashSet::new();
+    for team in &teams {
+        diagram.add_node(team_id(&team.name), &team.name, NodeStyle::Normal);
+    }
+    for team in &teams {
+        if let Some(parent) = team.parent_team.as_deref().filter(|p| names.contains(p)) {
+            diagram.add_edge(team_id(parent), team_id(&team.name), None);
+        }
+        for member in repo.get_members(&team.name)?.into_iter().filter(|p| p.active) {
+            if people.insert(member.email.clone()) {
+                diagram.add_node(person_id(&member.email), &member.name, NodeStyle::Normal);
+            }
+            diagram.add_edge(team_id(&team.name), person_id(&member.email), None);
+        }
+    }
+    Ok(diagram)
+}
+
+/// Projects and what they depend on, or projects and their milestones
+///
+/// When any project depends on another, the diagram shows the projects with
+/// dependencies, with an arrow from each project to the one it waits for.
+/// Otherwise it shows every project with its milestones. Projects and
+/// milestones past their due date are drawn in red until the project is done.
+pub fn project_graph(conn: &Connection, now: DateTime<Utc>) -> db::Result<Diagram> {
+    let repo = ProjectRepository::new(conn);
+    let projects = repo.list_all()?;
+    let graph = DependencyRepository::new(conn).get_dependency_graph()?;
+    let mut diagram = Diagram {
+        left_to_right: true,
+        ..Diagram::default()
+    };
+
+    if !graph.edges.is_empty() {
+        let by_id: HashMap<Uuid, &Project> = projects.iter().map(|p| (p.id, p)).collect();
+        for node in &graph.nodes {
+            let style = by_id
+                .get(&node.project_id)
+                .map_or(NodeStyle::Normal, |p| project_style(p, now));
+            diagram.add_node(project_id(&node.project_id), &node.project_name, style);
+        }
+        for edge in &graph.edges {
+            diagram.add_edge(
+                project_id(&edge.project_id),
+                project_id(&edge.depends_on_project_id),
+                Some("depends on"),
+            );
+        }
+        return Ok(diagram);
+    }
+
+    for project in &projects {
+        let style = project_style(project, now);
+        diagram.add_node(project_id(&project.id), &project.name, style);
+        for milestone in repo.get_milestones(&project.id)? {
+            let style = match style {
+                NodeStyle::Muted => NodeStyle::Muted,
+                _ if is_past_due(milestone.due_date, now) => NodeStyle::Overdue,
+                _ => NodeStyle::Normal,
+            };
+            let label = format!("Milestone {}: {}", milestone.number, milestone.name);
+            diagram.add_node(milestone_id(&milestone.id), &label, style);
+            diagram.add_edge(project_id(&project.id), milestone_id(&milestone.id), None);
+        }
+    }
+    Ok(diagram)
+}
+
+fn project_style(project: &Project, now: DateTime<Utc>) -> NodeStyle {
+    if project.status == ProjectStatus::Done {
+        NodeStyle::Muted
+    } else if is_past_due(project.due_date, now) {
+        NodeStyle::Overdue
+    } else {
+        NodeStyle::Normal
+    }
+}
+
+fn is_past_due(due_date: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    due_date.is_some_and(|due| due < now)
+}
+
+/// Node id of a person, from their email
+pub fn person_id(email: &str) -> String {
+    format!("person_{:016x}", fnv1a(email))
+}
+
+/// Node id of a team, from its name
+pub fn team_id(name: &str) -> String {
+    format!("team_{:016x}", fnv1a(name))
+}
+
+/// Node id of a project
+pub fn project_id(id: &Uuid) -> String {
+    format!("project_{}", id.simple())
+}
+
+/// Node id of a milestone
+pub fn milestone_id(id: &Uuid) -> String {
+    format!("milestone_{}", id.simple())
+}
+
+/// 64-bit FNV-1a hash, which unlike `DefaultHasher` is the same in every build
+fn fnv1a(s: &str) -> u64 {
+    s.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+impl Diagram {
+    fn add_node(&mut self, id: String, label: &str, style: NodeStyle) {
+        self.nodes.push(Node {
+            id,
+            label: label.to_string(),
+            style,
+        });
+    }
+
+    fn add_edge(&mut self, from: String, to: String, label: Option<&str>) {
+        self.edges.push(Edge {
+            from,
+            to,
+            label: label.map(str::to_string),
+        });
+    }
+
+    /// Render the diagram in a format
+    pub fn render(&self, format: DiagramFormat) -> String {
+        match format {
+            DiagramFormat::Dot => self.to_dot(),
+            DiagramFormat::Mermaid => self.to_mermaid(),
+        }
+    }
+
+    /// Render the diagram as a Graphviz digraph
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph {\n");
+        let _ = writeln!(out, "    rankdir={};", if self.left_to_right { "LR" } else { "TB" });
+        out.push_str("    node [shape=box, style=\"rounded,filled\", fillcolor=\"#ffffff\", fontname=\"Helvetica\"];\n");
+        out.push_str("    edge [fontname=\"Helvetica\", fontsize=10];\n");
+        for node in &self.nodes {
+            let style = match node.style {
+                NodeStyle::Normal => "",
+                NodeStyle::Overdue => ", color=\"#cc0000\", fillcolor=\"#ffdddd\", fontcolor=\"#990000\"",
+                NodeStyle::Muted => ", color=\"#999999\", fontcolor=\"#777777\"",
+            };
+            let _ = writeln!(out, "    {} [label=\"{}\"{}];", node.id, escape_dot(&node.label), style);
+        }
+        for edge in &self.edges {
+            match &edge.label {
+                Some(label) => {
+                    let _ = writeln!(out, "    {} -> {} [label=\"{}\"];", edge.from, edge.to, escape_dot(label));
+                }
+                None => {
+                    let _ = writeln!(out, "    {} -> {};", edge.from, edge.to);
+                }
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Render the diagram as a Mermaid flowchart
+    pub fn to_mermaid(&self) -> String {
+        let mut out = format!("flowchart {}\n", if self.left_to_right { "LR" } else { "TD" });
+        for node in &self.nodes {
+            let _ = writeln!(out, "    {}[\"{}\"]", node.id, escape_mermaid(&node.label));
+        }
+        for edge in &self.edges {
+            match &edge.label {
+                Some(label) => {
+                    let _ = writeln!(out, "    {} -->|\"{}\"| {}", edge.from, escape_mermaid(label), edge.to);
+                }
+                None => {
+                    let _ = writeln!(out, "    {} --> {}", edge.from, edge.to);
+                }
+            }
+        }
+        for (style, class, definition) in [
+            (NodeStyle::Overdue, "overdue", "fill:#ffdddd,stroke:#cc0000,color:#990000"),
+            (NodeStyle::Muted, "muted", "fill:#ffffff,stroke:#999999,color:#777777"),
+        ] {
+            let ids: Vec<&str> = self
+                .nodes
+                .iter()
+                .filter(|n| n.style == style)
+                .map(|n| n.id.as_str())
+                .collect();
+            if !ids.is_empty() {
+                let _ = writeln!(out, "    classDef {} {}", class, definition);
+                let _ = writeln!(out, "    class {} {}", ids.join(","), class);
+            }
+        }
+        out
+    }
+}
+
+/// Escape text for a double-quoted DOT string
+///
+/// Newlines become line breaks in the label; other control characters become spaces.
+pub fn escape_dot(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            c if c.is_control() => out.push(' '),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Escape text for a double-quoted Mermaid label
+///
+/// Characters Mermaid or HTML would interpret are written as entity codes,
+/// and newlines become line breaks.
+pub fn escape_mermaid(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("#quot;"),
+            '#' => out.push_str("#35;"),
+            '&' => out.push_str("#amp;"),
+            '<' => out.push_str("#lt;"),
+            '>' => out.push_str("#gt;"),
+            '|' => out.push_str("#124;"),
+            '`' => out.push_str("#96;"),
+            '\n' => out.push_str("<br/>"),
+            '\r' => {}
+            c if c.is_control() => out.push(' '),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Milestone, Person, ProjectDependency, Team};
+    use chrono::{Duration, TimeZone};
+
+    const HOSTILE: &str = "Robert \"Bobby\" <b>Tables</b>\r\n#1 | `x` \\n; end";
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn person(email: &str, name: &str, manager: Option<&str>) -> Person {
+        let mut person = Person::new(email.to_string(), name.to_string());
+        person.manager = manager.map(str::to_string);
+        person
+    }
+
+    #[test]
+    fn test_escaping() {
+        assert_eq!(
+            escape_dot(HOSTILE),
+            "Robert \\\"Bobby\\\" <b>Tables</b>\\n#1 | `x` \\\\n; end"
+        );
+        assert_eq!(
+            escape_mermaid(HOSTILE),
+            "Robert #quot;Bobby#quot; #lt;b#gt;Tables#lt;/b#gt;<br/>#35;1 #124; #96;x#96; \\n; end"
+        );
+        assert_eq!(escape_mermaid("#quot;"), "#35;quot;");
+        assert_eq!(escape_dot("tab\there\u{7}"), "tab here ");
+    }
+
+    #[test]
+    fn test_org_chart() {
+        let conn = setup_test_db();
+        let people = PersonRepository::new(&conn);
+        let mut gone = person("gone@example.com", "Gone", None);
+        gone.active = false;
+        people.create(&gone).unwrap();
+        people.create(&person("boss@example.com", HOSTILE, None)).unwrap();
+        people
+            .create(&person("dev@example.com", "Dev", Some("boss@example.com")))
+            .unwrap();
+        people
+            .create(&person("new@example.com", "New", Some("gone@example.com")))
+            .unwrap();
+
+        let diagram = build(&conn, DiagramKind::Org, Utc::now()).unwrap();
+        assert_eq!(diagram.nodes.len(), 4);
+        let gone = diagram.nodes.iter().find(|n| n.label == "Gone").unwrap();
+        assert_eq!(gone.style, NodeStyle::Muted);
+        assert_eq!(gone.id, person_id("gone@example.com"));
+
+        // Ids are stable and never contain anything from the label
+        let boss = person_id("boss@example.com");
+        assert_eq!(boss, "person_".to_string() + &format!("{:016x}", fnv1a("boss@example.com")));
+        assert_eq!(diagram.edges[0].from, boss);
+
+        // Every line is still one statement, however hostile the names
+        let mermaid = diagram.to_mermaid();
+        assert!(mermaid.contains(&format!("    {}[\"{}\"]\n", boss, escape_mermaid(HOSTILE))));
+        assert_eq!(mermaid.lines().count(), 1 + 4 + 2 + 2);
+        for line in mermaid.lines().skip(1) {
+            assert_eq!(line.matches('"').count() % 2, 0, "{}", line);
+        }
+        let dot = diagram.to_dot();
+        assert!(dot.contains(&format!("    {} [label=\"{}\"];\n", boss, escape_dot(HOSTILE))));
+        assert_eq!(dot.lines().count(), 4 + 4 + 2 + 1);
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_team_membership() {
+        let conn = setup_test_db();
+        let teams = TeamRepository::new(&conn);
+        let people = PersonRepository::new(&conn);
+        teams.create(&Team::new("Platform".to_string())).unwrap();
+        let mut infra = Team::new("Infra \"Core\"".to_string());
+        infra.parent_team = Some("Platform".to_string());
+        teams.create(&infra).unwrap();
+        people.create(&person("a@example.com", "Ada", None)).unwrap();
+        let mut gone = person("b@example.com", "Bob", None);
+        gone.active = false;
+        people.create(&gone).unwrap();
+        teams.add_member("Infra \"Core\"", "a@example.com").unwrap();
+        teams.add_member("Infra \"Core\"", "b@example.com").unwrap();
+
+        let diagram = build(&conn, DiagramKind::Teams, Utc::now()).unwrap();
+        assert_eq!(diagram.nodes.len(), 3);
+        assert_eq!(
+            diagram.edges.iter().map(|e| (e.from.clone(), e.to.clone())).collect::<Vec<_>>(),
+            [
+                (team_id("Platform"), team_id("Infra \"Core\"")),
+                (team_id("Infra \"Core\""), person_id("a@example.com")),
+            ]
+        );
+        assert!(diagram.to_dot().contains("[label=\"Infra \\\"Core\\\"\"]"));
+    }
+
+    #[test]
+    fn test_project_graph() {
+        let conn = setup_test_db();
+        let projects = ProjectRepository::new(&conn);
+        let now = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let mut apollo = Project::new("Apollo\nLaunch".to_string());
+        apollo.due_date = Some(now - Duration::days(1));
+        projects.create(&apollo).unwrap();
+        let mut gemini = Project::new("Gemini".to_string());
+        gemini.due_date = Some(now - Duration::days(1));
+        gemini.status = ProjectStatus::Done;
+        projects.create(&gemini).unwrap();
+        let mut beta = Milestone::new(gemini.id, 1, "Beta".to_string());
+        beta.due_date = Some(now - Duration::days(3));
+        projects.add_milestone(&beta).unwrap();
+        let mut review = Milestone::new(apollo.id, 1, "Review".to_string());
+        review.due_date = Some(now + Duration::days(3));
+        projects.add_milestone(&review).unwrap();
+
+        // Without dependencies, projects contain their milestones
+        let diagram = build(&conn, DiagramKind::Projects, now).unwrap();
+        let styles: Vec<_> = diagram.nodes.iter().map(|n| (n.label.as_str(), n.style)).collect();
+        assert_eq!(
+            styles,
+            [
+                ("Apollo\nLaunch", NodeStyle::Overdue),
+                ("Milestone 1: Review", NodeStyle::Normal),
+                ("Gemini", NodeStyle::Muted),
+                ("Milestone 1: Beta", NodeStyle::Muted),
+            ]
+        );
+        let mermaid = diagram.to_mermaid();
+        assert!(mermaid.starts_with("flowchart LR\n"));
+        assert!(mermaid.contains("[\"Apollo<br/>Launch\"]"));
+        assert!(mermaid.contains(&format!("    class {} overdue\n", project_id(&apollo.id))));
+
+        // Once there are dependencies, they are what's shown
+        DependencyRepository::new(&conn)
+            .add(&ProjectDependency::new(apollo.id, gemini.id))
+            .unwrap();
+        let diagram = build(&conn, DiagramKind::Projects, now).unwrap();
+        assert_eq!(diagram.nodes.len(), 2);
+        assert_eq!(diagram.edges.len(), 1);
+        let dot = diagram.to_dot();
+        assert!(dot.contains(&format!(
+            "    {} -> {} [label=\"depends on\"];\n",
+            project_id(&apollo.id),
+            project_id(&gemini.id)
+        )));
+        assert!(dot.contains("[label=\"Apollo\\nLaunch\", color=\"#cc0000\""));
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("Org".parse::<DiagramKind>().unwrap(), DiagramKind::Org);
+        assert_eq!("graphviz".parse::<DiagramFormat>().unwrap(), DiagramFormat::Dot);
+        assert!("pie".parse::<DiagramKind>().is_err());
+    }
+}
@@ -0,0 +1,648 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Weekly digest of what is due, overdue, new and busy
+//!
+//! Completed projects without a retrospective are listed as a reminder until
+//! one is saved. A digest composed for a user only covers the projects they
+//! watch, manage or lead.
+//!
+//! [`compose`] gathers the digest from the database, and [`Digest::render`]
+//! turns it into markdown or simple HTML in a [`Locale`]. Delivering it goes through a
+//! [`DigestSink`]: [`WriterSink`] writes it to stdout or a file, and
+//! [`SmtpSink`] emails it using the `[digest.smtp]` config section.
+
+use crate::config::SmtpConfig;
+use crate::i18n::Locale;
+use crate::secrets::SecretStore;
+use crate::db::{
+    self, DueMilestone, NoteActivity, NotificationRepository, Project, ProjectRepository,
+    RetrospectiveRepository, WatcherRepository,
+};
+use crate::utils::start_of_local_day;
+use anyhow::Context;
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use rusqlite::Connection;
+use std::fmt::Write as _;
+use std::io::Write;
+
+/// How far back a digest looks when no start is given
+pub const DEFAULT_LOOKBACK_DAYS: i64 = 7;
+
+/// How far ahead a digest lists milestones that are coming due
+pub const DUE_AHEAD_DAYS: i64 = 7;
+
+/// Most projects listed under note activity
+pub const MAX_NOTE_ACTIVITY: usize = 10;
+
+/// A digest of the portfolio over a period
+#[derive(Debug, Clone)]
+pub struct Digest {
+    /// Start of the period covered
+    pub since: DateTime<Utc>,
+
+    /// When the digest was composed
+    pub generated_at: DateTime<Utc>,
+
+    /// Time zone dates are shown in
+    pub tz: Tz,
+
+    /// Milestones due from today through the next [`DUE_AHEAD_DAYS`] days
+    pub due_this_week: Vec<DueMilestone>,
+
+    /// Milestones whose due date passed during the period
+    pub overdue: Vec<DueMilestone>,
+
+    /// Projects created during the period
+    pub new_projects: Vec<Project>,
+
+    /// Projects with the most notes written during the period
+    pub note_activity: Vec<NoteActivity>,
+
+    /// Completed projects that don't have a retrospective yet
+    pub missing_retrospectives: Vec<Project>,
+}
+
+/// Output format of a rendered digest
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestFormat {
+    Markdown,
+    Html,
+}
+
+/// Compose a digest of everything since `since`, as of `now`
+///
+/// Days start at midnight in `tz`: "due this week" begins today, and
+/// milestones due earlier today or before count as overdue. With a `user`
+/// email, only the projects that user watches, manages or leads are covered.
+pub fn compose(
+    conn: &Connection,
+    since: DateTime<Utc>,
+    now: DateTime<Utc>,
+    tz: Tz,
+    user: Option<&str>,
+) -> db::Result<Digest> {
+    let today = start_of_local_day(now.with_timezone(&tz).date_naive(), tz);
+    let notifications = NotificationRepository::new(conn);
+    let projects = ProjectRepository::new(conn);
+
+    let mut digest = Digest {
+        since,
+        generated_at: now,
+        tz,
+        due_this_week: notifications
+            .due_milestones(today, today + Duration::days(DUE_AHEAD_DAYS))?,
+        overdue: notifications.due_milestones(since.min(today), today)?,
+        new_projects: projects.list_created_since(since)?,
+        note_activity: projects.note_activity_since(since, MAX_NOTE_ACTIVITY)?,
+        missing_retrospectives: RetrospectiveRepository::new(conn).list_projects_missing()?,
+    };
+    if let Some(email) = user {
+        let relevant = WatcherRepository::new(conn).relevant_project_ids(email)?;
+        digest.due_this_week.retain(|m| relevant.contains(&m.project_id));
+        digest.overdue.retain(|m| relevant.contains(&m.project_id));
+        digest.new_projects.retain(|p| relevant.contains(&p.id));
+        digest.note_activity.retain(|a| relevant.contains(&a.project_id));
+        digest.missing_retrospectives.retain(|p| relevant.contains(&p.id));
+    }
+    Ok(digest)
+}
+
+impl Digest {
+    /// Email subject, such as "Project digest for 2025-07-07"
+    pub fn subject(&self, locale: Locale) -> String {
+        locale.format("digest.subject", &[("date", &self.date(self.generated_at, locale))])
+    }
+
+    /// Whether there is nothing to report
+    pub fn is_empty(&self) -> bool {
+        self.due_this_week.is_empty()
+            && self.overdue.is_empty()
+            && self.new_projects.is_empty()
+            && self.note_activity.is_empty()
+            && self.missing_retrospectives.is_empty()
+    }
+
+    /// Render the digest in a format
+    pub fn render(&self, format: DigestFormat, locale: Locale) -> String {
+        match format {
+            DigestFormat::Markdown => self.to_markdown(locale),
+            DigestFormat::Html => self.to_html(locale),
+        }
+    }
+
+    /// Render the digest as markdown
+    pub fn to_markdown(&self, locale: Locale) -> String {
+        let mut out = format!("# {}\n", self.subject(locale));
+        for (title, items) in self.sections(locale) {
+            let _ = write!(out, "\n## {} ({})\n\n", title, items.len());
+            if items.is_empty() {
+                let _ = writeln!(out, "{}", locale.text("digest.nothing_to_report"));
+            }
+            for item in items {
+                let _ = writeln!(out, "- {}", item);
+            }
+        }
+        out
+    }
+
+    /// Render the digest as a simple HTML page
+    pub fn to_html(&self, locale: Locale) -> String {
+        let subject = escape_html(&self.subject(locale));
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html lang=\"{1}\">\n<head><meta charset=\"utf-8\"><title>{0}</title></head>\n<body>\n<h1>{0}</h1>\n",
+            subject, locale
+        );
+        for (title, items) in self.sections(locale) {
+            let _ = writeln!(out, "<h2>{} ({})</h2>", escape_html(&title), items.len());
+            if items.is_empty() {
+                let _ = writeln!(out, "<p>{}</p>", escape_html(locale.text("digest.nothing_to_report")));
+                continue;
+            }
+            out.push_str("<ul>\n");
+            for item in items {
+                let _ = writeln!(out, "<li>{}</li>", escape_html(&item));
+            }
+            out.push_str("</ul>\n");
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+
+    /// Section titles with one line of plain text per entry
+    ///
+    /// The retrospective reminder is only included when a project needs one.
+    fn sections(&self, locale: Locale) -> Vec<(String, Vec<String>)> {
+        let milestone = |m: &DueMilestone| {
+            format!(
+                "{} {}: {}",
+                self.date(m.due_date, locale),
+                m.project_name,
+                m.milestone_name
+            )
+        };
+        let mut sections = vec![
+            (
+                locale.text("digest.due_this_week").to_string(),
+                self.due_this_week.iter().map(milestone).collect(),
+            ),
+            (
+                locale.format("digest.overdue_since", &[("date", &self.date(self.since, locale))]),
+                self.overdue.iter().map(milestone).collect(),
+            ),
+            (
+                locale.text("digest.new_projects").to_string(),
+                self.new_projects
+                    .iter()
+                    .map(|p| {
+                        locale.format(
+                            "digest.new_project_item",
+                            &[
+                                ("name", &p.name),
+                                ("type", &p.project_type),
+                                ("date", &self.date(p.created_at, locale)),
+                            ],
+                        )
+                    })
+                    .collect(),
+            ),
+            (
+                locale.text("digest.note_activity").to_string(),
+                self.note_activity
+                    .iter()
+                    .map(|a| {
+                        locale.plural(
+                            "digest.note_activity_item",
+                            a.note_count,
+                            &[
+                                ("project", &a.project_name),
+                                ("date", &self.date(a.last_note_at, locale)),
+                            ],
+                        )
+                    })
+                    .collect(),
+            ),
+        ];
+        if !self.missing_retrospectives.is_empty() {
+            sections.push((
+                locale.text("digest.missing_retrospectives").to_string(),
+                self.missing_retrospectives.iter().map(|p| p.name.clone()).collect(),
+            ));
+        }
+        sections
+    }
+
+    fn date(&self, dt: DateTime<Utc>, locale: Locale) -> String {
+        locale.date(dt, self.tz)
+    }
+}
+
+/// Somewhere a digest can be delivered
+pub trait DigestSink {
+    /// Deliver a digest
+    fn deliver(&mut self, digest: &Digest) -> crate::Result<()>;
+}
+
+/// Writes the rendered digest to stdout, a file or any other writer
+pub struct WriterSink<W: Write> {
+    writer: W,
+    format: DigestFormat,
+    locale: Locale,
+}
+
+impl<W: Write> WriterSink<W> {
+    pub fn new(writer: W, format: DigestFormat, locale: Locale) -> Self {
+        Self {
+            writer,
+            format,
+            locale,
+        }
+    }
+}
+
+impl<W: Write> DigestSink for WriterSink<W> {
+    fn deliver(&mut self, digest: &Digest) -> crate::Result<()> {
+        self.writer
+            .write_all(digest.render(self.format, self.locale).as_bytes())?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Emails the digest through an SMTP server, as markdown text with an HTML alternative
+pub struct SmtpSink {
+    transport: SmtpTransport,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+    locale: Locale,
+}
+
+impl SmtpSink {
+    /// Connect settings from the `[digest.smtp]` config section, writing digests in `locale`
+    ///
+    /// With a `username`, the password is read from the environment variable
+    /// named by `password_env` when it is set, and otherwise from `secrets`.
+    pub fn from_config(config: &SmtpConfig, secrets: &SecretStore, locale: Locale) -> crate::Result<Self> {
+        let mut builder = SmtpTransport::starttls_relay(&config.host)
+            .with_context(|| format!("Invalid SMTP host {}", config.host))?
+            .port(config.port);
+        if let Some(username) = &config.username {
+            let password = smtp_password(config, secrets, username)?;
+            builder = builder.credentials(Credentials::new(username.clone(), password));
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from: parse_mailbox(&config.from)?,
+            to: config
+                .to
+                .iter()
+                .map(|to| parse_mailbox(to))
+                .collect::<crate::Result<_>>()?,
+            locale,
+        })
+    }
+}
+
+fn smtp_password(config: &SmtpConfig, secrets: &SecretStore, username: &str) -> crate::Result<String> {
+    if let Ok(password) = std::env::var(&config.password_env) {
+        return Ok(password);
+    }
+    secrets
+        .get(&config.password_secret)
+        .with_context(|| format!("Failed to read the SMTP password from the {}", secrets.location()))?
+        .with_context(|| {
+            format!(
+                "No SMTP password for {}; store it with `track auth set-key {}` or set {}",
+                username, config.password_secret, config.password_env
+            )
+        })
+}
+
+impl DigestSink for SmtpSink {
+    fn deliver(&mut self, digest: &Digest) -> crate::Result<()> {
+        let message = build_message(digest, self.locale, &self.from, &self.to)?;
+        self.transport
+            .send(&message)
+            .context("Failed to send the digest")?;
+        log::info!("Sent digest to {} recipient(s)", self.to.len());
+        Ok(())
+    }
+}
+
+/// Build the email for a digest
+pub fn build_message(
+    digest: &Digest,
+    locale: Locale,
+    from: &Mailbox,
+    to: &[Mailbox],
+) -> crate::Result<Message> {
+    let mut builder = Message::builder()
+        .from(from.clone())
+        .subject(digest.subject(locale));
+    for recipient in to {
+        builder = builder.to(recipient.clone());
+    }
+    let message = builder.multipart(MultiPart::alternative_plain_html(
+        digest.to_markdown(locale),
+        digest.to_html(locale),
+    ))?;
+    Ok(message)
+}
+
+/// Escape text for use in HTML element content
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn parse_mailbox(address: &str) -> crate::Result<Mailbox> {
+    address
+        .parse()
+        .with_context(|| format!("Invalid email address '{}'", address))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Milestone, NoteActivity};
+    use chrono::TimeZone;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    /// Keeps delivered digests so tests can look at them
+    #[derive(Default)]
+    struct RecordingSink {
+        delivered: Vec<String>,
+    }
+
+    impl DigestSink for RecordingSink {
+        fn deliver(&mut self, digest: &Digest) -> crate::Result<()> {
+            self.delivered.push(digest.to_markdown(Locale::En));
+            Ok(())
+        }
+    }
+
+    fn sample_digest(conn: &Connection, now: DateTime<Utc>) -> Digest {
+        let repo = ProjectRepository::new(conn);
+        let project = Project::new("Apollo <Moon>".to_string());
+        repo.create(&project).unwrap();
+
+        let mut launch = Milestone::new(project.id, 1, "Launch".to_string());
+        launch.due_date = Some(now + Duration::days(3));
+        repo.add_milestone(&launch).unwrap();
+        let mut review = Milestone::new(project.id, 2, "Review".to_string());
+        review.due_date = Some(now - Duration::days(2));
+        repo.add_milestone(&review).unwrap();
+        let mut ancient = Milestone::new(project.id, 3, "Ancient".to_string());
+        ancient.due_date = Some(now - Duration::days(60));
+        repo.add_milestone(&ancient).unwrap();
+        repo.add_project_note(&db::ProjectNote::new(
+            project.id,
+            "Status".to_string(),
+            "Fine".to_string(),
+        ))
+        .unwrap();
+
+        compose(
+            conn,
+            now - Duration::days(DEFAULT_LOOKBACK_DAYS),
+            now,
+            Tz::UTC,
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_compose_digest() {
+        let conn = setup_test_db();
+        let now = Utc::now();
+        let digest = sample_digest(&conn, now);
+
+        assert!(!digest.is_empty());
+        assert_eq!(digest.due_this_week.len(), 1);
+        assert_eq!(digest.due_this_week[0].milestone_name, "Launch");
+        assert_eq!(digest.overdue.len(), 1);
+        assert_eq!(digest.overdue[0].milestone_name, "Review");
+        assert_eq!(digest.new_projects.len(), 1);
+        assert_eq!(digest.note_activity[0].note_count, 1);
+
+        let mut sink = RecordingSink::default();
+        sink.deliver(&digest).unwrap();
+        let markdown = &sink.delivered[0];
+        assert!(markdown.starts_with(&format!(
+            "# Project digest for {}",
+            crate::utils::format_local_date(now, Tz::UTC)
+        )));
+        assert!(markdown.contains("## Due this week (1)"));
+        assert!(markdown.contains(": Launch"));
+        assert!(!markdown.contains("Ancient"));
+        assert!(markdown.contains("- Apollo <Moon>: 1 note, latest"));
+    }
+
+    #[test]
+    fn test_empty_digest() {
+        let conn = setup_test_db();
+        let now = Utc::now();
+        let digest = compose(&conn, now - Duration::days(7), now, Tz::UTC, None).unwrap();
+        assert!(digest.is_empty());
+        assert_eq!(
+            digest.to_markdown(Locale::En).matches("Nothing to report.").count(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_missing_retrospective_reminder() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let mut project = Project::new("Apollo".to_string());
+        project.created_at = Utc::now() - Duration::days(90);
+        repo.create(&project).unwrap();
+        let now = Utc::now();
+        let since = now - Duration::days(DEFAULT_LOOKBACK_DAYS);
+        assert!(compose(&conn, since, now, Tz::UTC, None).unwrap().is_empty());
+
+        project.status = db::ProjectStatus::Done;
+        repo.update(&project).unwrap();
+        let digest = compose(&conn, since, now, Tz::UTC, None).unwrap();
+        assert!(!digest.is_empty());
+        assert!(digest
+            .to_markdown(Locale::En)
+            .contains("## Completed projects without a retrospective (1)\n\n- Apollo\n"));
+
+        db::RetrospectiveRepository::new(&conn)
+            .save(&db::Retrospective::new(project.id, now))
+            .unwrap();
+        let digest = compose(&conn, since, now, Tz::UTC, None).unwrap();
+        assert!(digest.missing_retrospectives.is_empty());
+        assert!(!digest.to_markdown(Locale::En).contains("retrospective"));
+    }
+
+    #[test]
+    fn test_digest_for_user() {
+        let conn = setup_test_db();
+        let now = Utc::now();
+        let repo = ProjectRepository::new(&conn);
+        db::PersonRepository::new(&conn)
+            .create(&db::Person::new("alice@example.com".to_string(), "Alice".to_string()))
+            .unwrap();
+        let mut projects = Vec::new();
+        for name in ["Watched", "Managed", "Other"] {
+            let mut project = Project::new(name.to_string());
+            if name == "Managed" {
+                project.manager = Some("alice@example.com".to_string());
+            }
+            repo.create(&project).unwrap();
+            let mut milestone = Milestone::new(project.id, 1, format!("{} launch", name));
+            milestone.due_date = Some(now + Duration::days(2));
+            repo.add_milestone(&milestone).unwrap();
+            projects.push(project);
+        }
+        WatcherRepository::new(&conn).watch(&projects[0].id, "alice@example.com").unwrap();
+        WatcherRepository::new(&conn).watch(&projects[2].id, "bob@example.com").unwrap();
+        let since = now - Duration::days(DEFAULT_LOOKBACK_DAYS);
+
+        let everything = compose(&conn, since, now, Tz::UTC, None).unwrap();
+        assert_eq!(everything.due_this_week.len(), 3);
+        assert_eq!(everything.new_projects.len(), 3);
+
+        let alice = compose(&conn, since, now, Tz::UTC, Some("alice@example.com")).unwrap();
+        let due: Vec<_> = alice.due_this_week.iter().map(|m| m.milestone_name.as_str()).collect();
+        assert_eq!(due, ["Managed launch", "Watched launch"]);
+        let mut names: Vec<_> = alice.new_projects.iter().map(|p| p.name.as_str()).collect();
+        names.sort_unstable();
+        assert_eq!(names, ["Managed", "Watched"]);
+
+        let carol = compose(&conn, since, now, Tz::UTC, Some("carol@example.com")).unwrap();
+        assert!(carol.is_empty());
+    }
+
+    #[test]
+    fn test_html_and_writer_sink() {
+        let conn = setup_test_db();
+        let digest = sample_digest(&conn, Utc::now());
+
+        let html = digest.to_html(Locale::En);
+        assert!(html.contains("<h2>Due this week (1)</h2>"));
+        assert!(html.contains("Apollo &lt;Moon&gt;"));
+        assert!(!html.contains("<Moon>"));
+
+        let mut out = Vec::new();
+        WriterSink::new(&mut out, DigestFormat::Html, Locale::En)
+            .deliver(&digest)
+            .unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), html);
+    }
+
+    #[test]
+    fn test_build_message() {
+        let conn = setup_test_db();
+        let digest = sample_digest(&conn, Utc::now());
+        let from = parse_mailbox("Tracker <tracker@example.com>").unwrap();
+        let to = vec![
+            parse_mailbox("me@example.com").unwrap(),
+            parse_mailbox("boss@example.com").unwrap(),
+        ];
+
+        let message =
+            String::from_utf8(build_message(&digest, Locale::En, &from, &to).unwrap().formatted()).unwrap();
+        assert!(message.contains(&format!("Subject: {}", digest.subject(Locale::En))));
+        assert!(message.contains("me@example.com"));
+        assert!(message.contains("boss@example.com"));
+        assert!(message.contains("multipart/alternative"));
+        assert!(message.contains("text/html"));
+
+        assert!(parse_mailbox("not an address").is_err());
+    }
+
+    #[test]
+    fn test_render_golden() {
+        let at = |day, hour| Utc.with_ymd_and_hms(2025, 7, day, hour, 0, 0).unwrap();
+        let mut project = Project::new("Apollo".to_string());
+        project.project_type = "Team".to_string();
+        project.created_at = at(3, 9);
+        let due = |name: &str, date| DueMilestone {
+            milestone_id: uuid::Uuid::nil(),
+            milestone_name: name.to_string(),
+            project_id: project.id,
+            project_name: "Apollo".to_string(),
+            due_date: date,
+        };
+        let activity = |name: &str, count| NoteActivity {
+            project_id: project.id,
+            project_name: name.to_string(),
+            note_count: count,
+            last_note_at: at(6, 17),
+        };
+        let berlin: Tz = "Europe/Berlin".parse().unwrap();
+        let digest = Digest {
+            since: at(1, 0),
+            generated_at: at(7, 6),
+            tz: berlin,
+            due_this_week: vec![due("Launch", at(9, 23))],
+            overdue: vec![due("Review", at(4, 12))],
+            new_projects: vec![project.clone()],
+            note_activity: vec![activity("Apollo", 3), activity("Gemini", 1)],
+            missing_retrospectives: vec![Project::new("Mercury".to_string())],
+        };
+
+        assert_eq!(
+            digest.to_markdown(Locale::En),
+            include_str!("../../tests/fixtures/digest.en.md")
+        );
+        assert_eq!(
+            digest.to_markdown(Locale::De),
+            include_str!("../../tests/fixtures/digest.de.md")
+        );
+        assert!(digest.to_html(Locale::De).contains("<html lang=\"de\">"));
+    }
+
+    #[test]
+    fn test_smtp_password_from_secret_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let secrets = SecretStore::file(
+            dir.path().join("secrets.enc"),
+            Some("passphrase".to_string()),
+            "TEST_PASSPHRASE",
+        );
+        let config: SmtpConfig = toml::from_str(
+            r#"
+            host = "smtp.company.com"
+            from = "tracker@company.com"
+            to = ["me@company.com"]
+            username = "tracker"
+            password_env = "PROJECT_TRACKER_TEST_SMTP_PASSWORD_UNSET"
+            "#,
+        )
+        .unwrap();
+
+        let err = smtp_password(&config, &secrets, "tracker").unwrap_err();
+        assert!(err.to_string().contains("track auth set-key smtp.password"));
+
+        secrets.set("smtp.password", "hunter2").unwrap();
+        assert_eq!(smtp_password(&config, &secrets, "tracker").unwrap(), "hunter2");
+        SmtpSink::from_config(&config, &secrets, Locale::En).unwrap();
+    }
+}
@@ -0,0 +1,551 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Demo data for screenshots, demos and trying things out
+//!
+//! [`seed_demo_data`] fills a database with teams, people, projects,
+//! milestones, resources, stakeholders and notes. Everything comes from a
+//! small seeded random number generator, so the same seed and day always
+//! produce the same data. Dates are spread around the day it runs, so the
+//! data always has work that is overdue, due soon and further out.
+//! [`seed_people`] adds many people at once, for timing things like
+//! autocomplete against a large directory.
+
+use crate::db::{
+    self, Milestone, MilestoneNote, MilestoneResource, Person, PersonRepository, Project, ProjectNote,
+    ProjectRepository, ProjectResource, ProjectStakeholder, ProjectStatus, StakeholderNote, Team, TeamRepository,
+};
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// Seed used when none is given
+pub const DEFAULT_SEED: u64 = 42;
+
+const TEAM_NAMES: [&str; 8] = ["Platform", "Payments", "Mobile", "Data", "Growth", "Security", "Infrastructure", "Design Systems"];
+
+const FIRST_NAMES: [&str; 32] = [
+    "Ada", "Ben", "Carmen", "Dev", "Elena", "Farid", "Grace", "Hiro", "Ines", "Jonas", "Kemi", "Liam", "Mei", "Noah",
+    "Olga", "Priya", "Quinn", "Rosa", "Sam", "Tariq", "Uma", "Victor", "Wen", "Ximena", "Yusuf", "Zoe", "Aiko",
+    "Bruno", "Chloe", "Diego", "Esther", "Felix",
+];
+
+const LAST_NAMES: [&str; 32] = [
+    "Abbott", "Bauer", "Castillo", "Dubois", "Eriksen", "Fischer", "Garcia", "Haddad", "Ito", "Jensen", "Kowalski",
+    "Lindqvist", "Moreau", "Nakamura", "Okafor", "Patel", "Quintero", "Rossi", "Schmidt", "Tanaka", "Usman", "Varga",
+    "Weber", "Xu", "Yilmaz", "Zhang", "Andersen", "Brennan", "Costa", "Delgado", "Evans", "Fontaine",
+];
+
+const PROJECT_ADJECTIVES: [&str; 16] = [
+    "Unified", "Faster", "Self-Serve", "Global", "Realtime", "Modern", "Secure", "Automated", "Next", "Simpler",
+    "Reliable", "Shared", "Mobile", "Smarter", "Open", "Quiet",
+];
+
+const PROJECT_NOUNS: [&str; 16] = [
+    "Checkout", "Onboarding", "Search", "Billing", "Deploys", "Analytics", "Login", "Notifications", "Pipeline",
+    "Dashboards", "Storage", "Reporting", "Alerts", "Permissions", "Catalog", "Support Tools",
+];
+
+const PROJECT_TYPES: [&str; 3] = ["Team", "Company", "Personal"];
+
+const MILESTONE_NAMES: [&str; 8] = [
+    "Discovery", "Design review", "Prototype", "Alpha", "Beta", "Migration", "Launch", "Cleanup",
+];
+
+const RESOURCE_ROLES: [&str; 4] = ["Developer", "Developer", "Designer", "QA"];
+
+const STAKEHOLDER_ROLES: [&str; 3] = ["Sponsor", "Product Owner", "Reviewer"];
+
+const NOTE_TITLES: [&str; 6] = ["Weekly status", "Kickoff", "Risks", "Planning", "Retro", "Decision log"];
+
+const NOTE_BODIES: [&str; 6] = [
+    "On track. The team finished the last open review items this week.",
+    "Scope agreed with stakeholders; stretch goals moved to a follow-up.",
+    "Waiting on a dependency from another team, which may push the next milestone.",
+    "Demo went well. A few usability issues to fix before the next phase.",
+    "Load testing found a slow query; a fix is in review.",
+    "Hiring for the open role is still in progress, so the plan assumes the current team.",
+];
+
+const FOLLOW_UPS: [&str; 4] = [
+    "confirm the launch date",
+    "review the rollout plan",
+    "share the updated estimates",
+    "sign off on the design",
+];
+
+/// Number of rows of each kind that were created
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SeedSummary {
+    pub teams: usize,
+    pub people: usize,
+    pub projects: usize,
+    pub milestones: usize,
+    pub resources: usize,
+    pub stakeholders: usize,
+    pub notes: usize,
+}
+
+/// SplitMix64: tiny, fast and the same on every platform and release
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `low..high`
+    fn range(&mut self, low: i64, high: i64) -> i64 {
+        low + (self.next_u64() % (high - low) as u64) as i64
+    }
+
+    fn chance(&mut self, percent: u64) -> bool {
+        self.next_u64() % 100 < percent
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.next_u64() as usize % items.len()]
+    }
+
+    /// Pick `count` different items, or all of them if there are fewer
+    fn sample<'a, T>(&mut self, items: &'a [T], count: usize) -> Vec<&'a T> {
+        let mut indexes: Vec<usize> = (0..items.len()).collect();
+        for i in 0..count.min(items.len()) {
+            let j = i + self.next_u64() as usize % (items.len() - i);
+            indexes.swap(i, j);
+        }
+        indexes.into_iter().take(count).map(|i| &items[i]).collect()
+    }
+
+    fn uuid(&mut self) -> Uuid {
+        let bytes = ((self.next_u64() as u128) << 64 | self.next_u64() as u128).to_be_bytes();
+        uuid::Builder::from_random_bytes(bytes).into_uuid()
+    }
+}
+
+/// Whether the database has no people, teams or projects yet
+pub fn is_empty(conn: &Connection) -> db::Result<bool> {
+    let rows: i64 = conn.query_row(
+        "SELECT (SELECT COUNT(*) FROM people) + (SELECT COUNT(*) FROM teams) + (SELECT COUNT(*) FROM projects)",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(rows == 0)
+}
+
+/// Fill the database with demo data, with dates around today
+///
+/// See [`seed_demo_data_on`].
+pub fn seed_demo_data(conn: &Connection, seed: u64) -> db::Result<SeedSummary> {
+    seed_demo_data_on(conn, seed, Utc::now().date_naive())
+}
+
+/// Fill the database with demo data, with dates around `today`
+///
+/// Creates 8 teams, 60 people (a director, a manager for each team and
+/// their reports) and 25 projects with milestones, resources, stakeholders
+/// and notes. The same seed and day produce the same data. People, teams
+/// and projects that already exist are left alone, so seeding twice with the
+/// same seed adds nothing. Everything is created in a single transaction.
+pub fn seed_demo_data_on(conn: &Connection, seed: u64, today: NaiveDate) -> db::Result<SeedSummary> {
+    let mut rng = Rng(seed);
+    let mut summary = SeedSummary::default();
+    let day = |offset: i64| DateTime::from_naive_utc_and_offset(today.and_time(NaiveTime::MIN), Utc) + Duration::days(offset);
+
+    let tx = conn.unchecked_transaction()?;
+    let people_repo = PersonRepository::new(conn);
+    let team_repo = TeamRepository::new(conn);
+    let project_repo = ProjectRepository::new(conn);
+
+    // People: a director, one manager per team, and everyone else split across the teams
+    let mut names = HashSet::new();
+    let mut people: Vec<Person> = Vec::with_capacity(60);
+    while people.len() < 60 {
+        let (first, last) = (*rng.pick(&FIRST_NAMES), *rng.pick(&LAST_NAMES));
+        if !names.insert((first, last)) {
+            continue;
+        }
+        let email = format!("{}.{}@example.com", first, last).to_lowercase();
+        let mut person = Person::new(email, format!("{} {}", first, last));
+        let joined = day(-rng.range(200, 1500));
+        person.created_at = joined;
+        person.updated_at = joined;
+        people.push(person);
+    }
+    // Managers come before their reports, so each manager exists when their reports are created
+    let director = people[0].email.clone();
+    let managers: Vec<String> = people[1..=TEAM_NAMES.len()].iter().map(|p| p.email.clone()).collect();
+    for (i, person) in people.iter_mut().enumerate().skip(1) {
+        let team = (i - 1) % TEAM_NAMES.len();
+        person.team = Some(TEAM_NAMES[team].to_string());
+        person.manager = Some(if i <= TEAM_NAMES.len() {
+            director.clone()
+        } else {
+            managers[team].clone()
+        });
+    }
+    for person in &people {
+        if people_repo.find_by_email(&person.email)?.is_none() {
+            people_repo.create(person)?;
+            summary.people += 1;
+        }
+    }
+
+    for (i, name) in TEAM_NAMES.iter().enumerate() {
+        if team_repo.find_by_name(name)?.is_some() {
+            continue;
+        }
+        let mut team = Team::new(name.to_string());
+        team.description = Some(format!("Owns {} work across the company", name.to_lowercase()));
+        team.manager = Some(managers[i].clone());
+        team.created_at = day(-1500);
+        team.updated_at = team.created_at;
+        team_repo.create(&team)?;
+        summary.teams += 1;
+        for person in people.iter().filter(|p| p.team.as_deref() == Some(name)) {
+            team_repo.add_member(name, &person.email)?;
+        }
+    }
+
+    // Projects, each with its own milestones, people and notes. Each is
+    // generated in full before it's written, so skipping one that already
+    // exists doesn't change the ones after it.
+    let mut project_names = HashSet::new();
+    while project_names.len() < 25 {
+        let name = format!("{} {}", rng.pick(&PROJECT_ADJECTIVES), rng.pick(&PROJECT_NOUNS));
+        if !project_names.insert(name.clone()) {
+            continue;
+        }
+        let plan = plan_project(&mut rng, name, &people, &managers, &day);
+        if project_repo.find_by_id(&plan.project.id)?.is_some() {
+            continue;
+        }
+
+        project_repo.create(&plan.project)?;
+        for milestone in &plan.milestones {
+            project_repo.add_milestone(milestone)?;
+        }
+        for resource in &plan.resources {
+            project_repo.add_project_resource(&resource.project_id, resource)?;
+        }
+        for resource in &plan.milestone_resources {
+            project_repo.add_milestone_resource(&resource.milestone_id, resource)?;
+        }
+        for stakeholder in &plan.stakeholders {
+            project_repo.add_stakeholder(&stakeholder.project_id, stakeholder)?;
+        }
+        for note in &plan.project_notes {
+            project_repo.add_project_note(note)?;
+        }
+        for note in &plan.milestone_notes {
+            project_repo.add_milestone_note(note)?;
+        }
+        for note in &plan.stakeholder_notes {
+            project_repo.add_stakeholder_note(note)?;
+        }
+
+        summary.projects += 1;
+        summary.milestones += plan.milestones.len();
+        summary.resources += plan.resources.len() + plan.milestone_resources.len();
+        summary.stakeholders += plan.stakeholders.len();
+        summary.notes += plan.project_notes.len() + plan.milestone_notes.len() + plan.stakeholder_notes.len();
+    }
+
+    tx.commit()?;
+    log::info!(
+        "Seeded demo data: {} teams, {} people, {} projects, {} milestones",
+        summary.teams,
+        summary.people,
+        summary.projects,
+        summary.milestones
+    );
+    Ok(summary)
+}
+
+/// A generated project with everything that belongs to it
+struct ProjectPlan {
+    project: Project,
+    milestones: Vec<Milestone>,
+    resources: Vec<ProjectResource>,
+    milestone_resources: Vec<MilestoneResource>,
+    stakeholders: Vec<ProjectStakeholder>,
+    project_notes: Vec<ProjectNote>,
+    milestone_notes: Vec<MilestoneNote>,
+    stakeholder_notes: Vec<StakeholderNote>,
+}
+
+/// Generate a project run by one of the teams, starting up to 8 months ago
+fn plan_project(
+    rng: &mut Rng,
+    name: String,
+    people: &[Person],
+    managers: &[String],
+    day: &impl Fn(i64) -> DateTime<Utc>,
+) -> ProjectPlan {
+    let team_index = rng.range(0, TEAM_NAMES.len() as i64) as usize;
+    let team = TEAM_NAMES[team_index];
+    let members: Vec<&Person> = people.iter().filter(|p| p.team.as_deref() == Some(team)).collect();
+    let start = rng.range(-240, 60);
+    let length = rng.range(60, 300);
+
+    let mut project = Project::new(name);
+    project.id = rng.uuid();
+    project.description = Some(format!("{} for the {} team's customers.", project.name, team));
+    project.project_type = rng.pick(&PROJECT_TYPES).to_string();
+    project.team = Some(team.to_string());
+    project.manager = Some(managers[team_index].clone());
+    project.technical_lead = Some(rng.pick(&members).email.clone());
+    project.requirements_owner = Some(rng.pick(people).email.clone());
+    project.start_date = Some(day(start));
+    project.due_date = Some(day(start + length));
+    project.jira_initiative = rng.chance(60).then(|| format!("{}-{}", team[..3].to_uppercase(), rng.range(100, 999)));
+    project.status = if start + length < 0 {
+        ProjectStatus::Done
+    } else if start > 0 {
+        ProjectStatus::Planning
+    } else if rng.chance(15) {
+        ProjectStatus::OnHold
+    } else {
+        ProjectStatus::Active
+    };
+    if project.status == ProjectStatus::Active && rng.chance(15) {
+        project.blocked = true;
+        project.blocked_reason = Some("Waiting on a security review".to_string());
+    }
+    if rng.chance(40) {
+        project.budget_amount = Some(rng.range(20, 500) as f64 * 1000.0);
+        project.budget_currency = Some("USD".to_string());
+    }
+    project.created_at = day(start - rng.range(5, 30));
+    project.updated_at = project.created_at;
+
+    // Milestones split the project's dates evenly
+    let count = rng.range(2, 6);
+    let mut milestones = Vec::new();
+    for number in 1..=count {
+        let mut milestone = Milestone::new(project.id, number as i32, MILESTONE_NAMES[number as usize - 1].to_string());
+        milestone.id = rng.uuid();
+        milestone.team = Some(team.to_string());
+        milestone.technical_lead = Some(rng.pick(&members).email.clone());
+        milestone.start_date = Some(day(start + length * (number - 1) / count));
+        milestone.due_date = Some(day(start + length * number / count));
+        milestone.estimated_days = Some((length / count) as f64);
+        if rng.chance(50) {
+            milestone.design_doc_url = Some(format!("https://docs.example.com/{}/{}", project.slug, number));
+        }
+        milestone.created_at = project.created_at;
+        milestone.updated_at = project.created_at;
+        milestones.push(milestone);
+    }
+
+    let size = rng.range(2, 6) as usize;
+    let assigned = rng.sample(&members, size);
+    let mut resources = Vec::new();
+    for person in &assigned {
+        let mut resource = ProjectResource::new(project.id, person.email.clone());
+        resource.role = Some(rng.pick(&RESOURCE_ROLES).to_string());
+        resource.created_at = project.created_at;
+        resources.push(resource);
+    }
+    let mut milestone_resources = Vec::new();
+    for milestone in &milestones {
+        for person in rng.sample(&assigned, 2) {
+            let mut resource = MilestoneResource::new(milestone.id, person.email.clone());
+            resource.role = Some(rng.pick(&RESOURCE_ROLES).to_string());
+            resource.created_at = project.created_at;
+            milestone_resources.push(resource);
+        }
+    }
+
+    let outsiders: Vec<&Person> = people.iter().filter(|p| p.team.as_deref() != Some(team)).collect();
+    let count = rng.range(1, 4) as usize;
+    let mut stakeholders = Vec::new();
+    for person in rng.sample(&outsiders, count) {
+        let mut stakeholder = ProjectStakeholder::new(project.id, person.email.clone());
+        stakeholder.role = Some(rng.pick(&STAKEHOLDER_ROLES).to_string());
+        // Most stakeholders have been rated; the rest show up as unrated
+        if rng.chance(80) {
+            stakeholder.influence = Some(rng.range(1, 6) as i32);
+            stakeholder.interest = Some(rng.range(1, 6) as i32);
+        }
+        stakeholder.created_at = project.created_at;
+        stakeholders.push(stakeholder);
+    }
+
+    // Notes fall between the project's creation and today, during working hours
+    let first_day = start - 5;
+    let last_day = (start + length).min(0).max(first_day + 1);
+    let note_time = |rng: &mut Rng| day(rng.range(first_day, last_day)) + Duration::minutes(rng.range(9 * 60, 18 * 60));
+    let mut project_notes = Vec::new();
+    for _ in 0..rng.range(1, 5) {
+        let created = note_time(rng);
+        let mut note = ProjectNote::new(project.id, rng.pick(&NOTE_TITLES).to_string(), note_body(rng, people));
+        note.id = rng.uuid();
+        note.created_at = created;
+        note.updated_at = created;
+        project_notes.push(note);
+    }
+    let mut milestone_notes = Vec::new();
+    for milestone in &milestones {
+        if !rng.chance(40) {
+            continue;
+        }
+        let created = note_time(rng);
+        let mut note = MilestoneNote::new(milestone.id, format!("{} notes", milestone.name), note_body(rng, people));
+        note.id = rng.uuid();
+        note.created_at = created;
+        note.updated_at = created;
+        milestone_notes.push(note);
+    }
+    let mut stakeholder_notes = Vec::new();
+    if let Some(stakeholder) = stakeholders.first() {
+        let created = note_time(rng);
+        let email = stakeholder.stakeholder_email.clone();
+        let mut note = StakeholderNote::new(project.id, email, "1:1".to_string(), note_body(rng, people));
+        note.id = rng.uuid();
+        note.created_at = created;
+        note.updated_at = created;
+        stakeholder_notes.push(note);
+    }
+
+    ProjectPlan {
+        project,
+        milestones,
+        resources,
+        milestone_resources,
+        stakeholders,
+        project_notes,
+        milestone_notes,
+        stakeholder_notes,
+    }
+}
+
+/// Add `count` people with made-up names and no team, for load testing
+///
+/// Emails are numbered so they never collide; names repeat once the name
+/// lists run out. The same seed produces the same people. Returns how many
+/// were created, in a single transaction.
+pub fn seed_people(conn: &Connection, count: usize, seed: u64) -> db::Result<usize> {
+    let mut rng = Rng(seed);
+    let tx = conn.unchecked_transaction()?;
+    let repo = PersonRepository::new(conn);
+    for i in 0..count {
+        let (first, last) = (*rng.pick(&FIRST_NAMES), *rng.pick(&LAST_NAMES));
+        let email = format!("{}.{}.{}@example.com", first, last, i).to_lowercase();
+        repo.create(&Person::new(email, format!("{} {}", first, last)))?;
+    }
+    tx.commit()?;
+    Ok(count)
+}
+
+/// A status update, sometimes with a follow-up task for someone
+fn note_body(rng: &mut Rng, people: &[Person]) -> String {
+    let mut body = rng.pick(&NOTE_BODIES).to_string();
+    if rng.chance(50) {
+        let person = rng.pick(people);
+        body.push_str(&format!("\n\n- [ ] Ask {} to {}", person.name, rng.pick(&FOLLOW_UPS)));
+    }
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    /// Every row of the seeded tables, leaving out timestamps set when rows are written
+    fn dump(conn: &Connection) -> Vec<String> {
+        let queries = [
+            "SELECT email, name, team, manager, created_at FROM people ORDER BY email",
+            "SELECT name, description, manager, created_at FROM teams ORDER BY name",
+            "SELECT team_name, person_email FROM team_members ORDER BY 1, 2",
+            "SELECT id, name, slug, type, team, manager, technical_lead, requirements_owner, start_date, due_date,
+                    jira_initiative, status, blocked, budget_amount, created_at FROM projects ORDER BY id",
+            "SELECT id, project_id, number, name, technical_lead, start_date, due_date, design_doc_url FROM milestones ORDER BY id",
+            "SELECT project_id, person_email, role, created_at FROM project_resources ORDER BY 1, 2",
+            "SELECT milestone_id, person_email, role FROM milestone_resources ORDER BY 1, 2",
+            "SELECT project_id, stakeholder_email, role, influence, interest FROM project_stakeholders ORDER BY 1, 2",
+            "SELECT id, project_id, title, body, created_at FROM project_notes ORDER BY id",
+            "SELECT id, milestone_id, title, body, created_at FROM milestone_notes ORDER BY id",
+            "SELECT id, stakeholder_email, title, body, created_at FROM stakeholder_notes ORDER BY id",
+        ];
+        let mut rows = Vec::new();
+        for query in queries {
+            let mut stmt = conn.prepare(query).unwrap();
+            let columns = stmt.column_count();
+            let mut result = stmt.query([]).unwrap();
+            while let Some(row) = result.next().unwrap() {
+                let values: Vec<String> = (0..columns)
+                    .map(|i| format!("{:?}", row.get::<_, rusqlite::types::Value>(i).unwrap()))
+                    .collect();
+                rows.push(values.join("|"));
+            }
+        }
+        rows
+    }
+
+    #[test]
+    fn test_seed_is_deterministic() {
+        let today = NaiveDate::from_ymd_opt(2025, 6, 2).unwrap();
+        let first = setup_test_db();
+        let second = setup_test_db();
+        let other = setup_test_db();
+
+        let summary = seed_demo_data_on(&first, 42, today).unwrap();
+        assert_eq!(seed_demo_data_on(&second, 42, today).unwrap(), summary);
+        seed_demo_data_on(&other, 7, today).unwrap();
+
+        assert_eq!((summary.teams, summary.people, summary.projects), (8, 60, 25));
+        assert!(summary.milestones >= 50 && summary.notes >= 25);
+        assert_eq!(dump(&first), dump(&second));
+        assert_ne!(dump(&first), dump(&other));
+        assert!(!is_empty(&first).unwrap());
+    }
+
+    #[test]
+    fn test_seed_data_is_consistent() {
+        let conn = setup_test_db();
+        assert!(is_empty(&conn).unwrap());
+        seed_demo_data_on(&conn, DEFAULT_SEED, NaiveDate::from_ymd_opt(2025, 6, 2).unwrap()).unwrap();
+
+        // Every manager chain ends at the director
+        let people = PersonRepository::new(&conn).list_all(true).unwrap();
+        let directors: Vec<_> = people.iter().filter(|p| p.manager.is_none()).collect();
+        assert_eq!(directors.len(), 1);
+
+        // Milestones fall within their project, one after another
+        let repo = ProjectRepository::new(&conn);
+        for project in repo.list_all().unwrap() {
+            let milestones = repo.get_milestones(&project.id).unwrap();
+            assert!(milestones.len() >= 2);
+            assert_eq!(milestones.first().unwrap().start_date, project.start_date);
+            assert_eq!(milestones.last().unwrap().due_date, project.due_date);
+        }
+
+        // Seeding again with the same seed adds nothing
+        let again = seed_demo_data_on(&conn, DEFAULT_SEED, NaiveDate::from_ymd_opt(2025, 6, 2).unwrap()).unwrap();
+        assert_eq!(again, SeedSummary::default());
+    }
+
+    #[test]
+    fn test_seed_people() {
+        let conn = setup_test_db();
+        assert_eq!(seed_people(&conn, 200, DEFAULT_SEED).unwrap(), 200);
+        let people = PersonRepository::new(&conn).list_all(true).unwrap();
+        assert_eq!(people.len(), 200);
+        assert!(people.iter().all(|p| p.team.is_none() && p.email.ends_with("@example.com")));
+    }
+}
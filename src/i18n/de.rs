@@ -0,0 +1,85 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! German text
+
+pub(super) const CATALOG: &[(&str, &str)] = &[
+    // Weekly digest
+    ("digest.subject", "Projektübersicht vom {date}"),
+    ("digest.due_this_week", "Diese Woche fällig"),
+    ("digest.overdue_since", "Überfällig seit {date}"),
+    ("digest.new_projects", "Neue Projekte"),
+    ("digest.note_activity", "Notizaktivität"),
+    ("digest.nothing_to_report", "Nichts zu berichten."),
+    ("digest.new_project_item", "{name} ({type}, angelegt am {date})"),
+    ("digest.note_activity_item.one", "{project}: {count} Notiz, zuletzt am {date}"),
+    ("digest.note_activity_item.other", "{project}: {count} Notizen, zuletzt am {date}"),
+    ("digest.missing_retrospectives", "Abgeschlossene Projekte ohne Retrospektive"),
+    // Desktop notifications
+    ("notification.due_tomorrow", "Meilenstein morgen fällig"),
+    ("notification.due_in_days", "Meilenstein in {days} Tagen fällig"),
+    ("notification.due_today", "Meilenstein heute fällig"),
+    ("notification.overdue", "Meilenstein überfällig"),
+    ("notification.is_due", "{name} ist am {date} fällig"),
+    ("notification.was_due", "{name} war am {date} fällig"),
+    // CLI tables
+    ("table.id", "ID"),
+    ("table.slug", "KÜRZEL"),
+    ("table.name", "NAME"),
+    ("table.type", "TYP"),
+    ("table.status", "STATUS"),
+    ("table.team", "TEAM"),
+    ("table.due", "FÄLLIG"),
+    ("table.email", "E-MAIL"),
+    ("table.manager", "FÜHRUNGSKRAFT"),
+    ("table.parent", "ÜBERGEORDNET"),
+    ("table.role", "ROLLE"),
+    ("table.label", "BEZEICHNUNG"),
+    ("table.created", "ERSTELLT"),
+    ("table.no_projects", "Keine Projekte gefunden"),
+    ("table.no_people", "Keine Personen gefunden"),
+    ("table.no_teams", "Keine Teams gefunden"),
+    ("table.no_resources", "Keine Ressourcen gefunden"),
+    ("table.no_snapshots", "Keine Snapshots gefunden"),
+    // Portfolio report
+    ("report.as_of", "Portfolio, Stand {date}"),
+    ("report.projects", "Projekte"),
+    ("report.by_type", "Typ: {name}"),
+    ("report.by_team", "Team: {name}"),
+    ("report.no_type", "(keiner)"),
+    ("report.no_team", "(kein Team)"),
+    ("report.milestones", "Meilensteine"),
+    ("report.due_this_month", "diesen Monat fällig"),
+    ("report.due_this_quarter", "dieses Quartal fällig"),
+    ("report.milestones_per_project", "Meilensteine pro Projekt"),
+    ("report.unassigned_people", "Personen ohne Zuordnung"),
+    ("report.teams_without_manager", "Teams ohne Führungskraft"),
+    ("report.recent_notes", "Notizen der letzten 7 Tage"),
+    // Team quarterly review
+    ("review.title", "{team}: Rückblick Q{quarter} {year}"),
+    ("review.period", "{start} bis {end}"),
+    ("review.no_activity", "Keine Aktivität in diesem Quartal."),
+    ("review.none", "Keine."),
+    ("review.projects_delivered", "Abgeschlossene Projekte"),
+    ("review.delivered_item", "{name} (fällig am {date})"),
+    ("review.retrospectives", "Retrospektiven"),
+    ("review.went_well", "Lief gut: {text}"),
+    ("review.needs_improvement", "Verbesserungsbedarf: {text}"),
+    ("review.actions", "Maßnahmen: {text}"),
+    ("review.no_retrospective", "noch keine Retrospektive"),
+    ("review.milestones", "Meilensteine"),
+    ("review.milestones_summary", "{due} fällig, {completed} erledigt"),
+    ("review.completed", "erledigt"),
+    ("review.slipped", "Verschobene Meilensteine"),
+    ("review.slipped_item", "{project}: {name} verschoben vom {from} auf den {to} (+{days} Tage)"),
+    ("review.headcount", "Personalstand"),
+    ("review.members.one", "{count} Mitglied"),
+    ("review.members.other", "{count} Mitglieder"),
+    ("review.joined", "Neu: {name} ({date})"),
+    ("review.left", "Ausgeschieden: {name} ({date})"),
+    ("review.risks", "Wichtige Risiken"),
+    ("review.risk.open", "offen"),
+    ("review.risk.mitigating", "in Behandlung"),
+    ("review.risk.closed", "geschlossen"),
+];
@@ -0,0 +1,85 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! English text, which every other catalog falls back to
+
+pub(super) const CATALOG: &[(&str, &str)] = &[
+    // Weekly digest
+    ("digest.subject", "Project digest for {date}"),
+    ("digest.due_this_week", "Due this week"),
+    ("digest.overdue_since", "Overdue since {date}"),
+    ("digest.new_projects", "New projects"),
+    ("digest.note_activity", "Note activity"),
+    ("digest.nothing_to_report", "Nothing to report."),
+    ("digest.new_project_item", "{name} ({type}, created {date})"),
+    ("digest.note_activity_item.one", "{project}: {count} note, latest {date}"),
+    ("digest.note_activity_item.other", "{project}: {count} notes, latest {date}"),
+    ("digest.missing_retrospectives", "Completed projects without a retrospective"),
+    // Desktop notifications
+    ("notification.due_tomorrow", "Milestone due tomorrow"),
+    ("notification.due_in_days", "Milestone due in {days} days"),
+    ("notification.due_today", "Milestone due today"),
+    ("notification.overdue", "Milestone overdue"),
+    ("notification.is_due", "{name} is due {date}"),
+    ("notification.was_due", "{name} was due {date}"),
+    // CLI tables
+    ("table.id", "ID"),
+    ("table.slug", "SLUG"),
+    ("table.name", "NAME"),
+    ("table.type", "TYPE"),
+    ("table.status", "STATUS"),
+    ("table.team", "TEAM"),
+    ("table.due", "DUE"),
+    ("table.email", "EMAIL"),
+    ("table.manager", "MANAGER"),
+    ("table.parent", "PARENT"),
+    ("table.role", "ROLE"),
+    ("table.label", "LABEL"),
+    ("table.created", "CREATED"),
+    ("table.no_projects", "No projects found"),
+    ("table.no_people", "No people found"),
+    ("table.no_teams", "No teams found"),
+    ("table.no_resources", "No resources found"),
+    ("table.no_snapshots", "No snapshots found"),
+    // Portfolio report
+    ("report.as_of", "Portfolio as of {date}"),
+    ("report.projects", "Projects"),
+    ("report.by_type", "type: {name}"),
+    ("report.by_team", "team: {name}"),
+    ("report.no_type", "(none)"),
+    ("report.no_team", "(no team)"),
+    ("report.milestones", "Milestones"),
+    ("report.due_this_month", "due this month"),
+    ("report.due_this_quarter", "due this quarter"),
+    ("report.milestones_per_project", "Milestones per project"),
+    ("report.unassigned_people", "People without assignments"),
+    ("report.teams_without_manager", "Teams without a manager"),
+    ("report.recent_notes", "Notes in the last 7 days"),
+    // Team quarterly review
+    ("review.title", "{team}: Q{quarter} {year} review"),
+    ("review.period", "{start} to {end}"),
+    ("review.no_activity", "No activity this quarter."),
+    ("review.none", "None."),
+    ("review.projects_delivered", "Projects delivered"),
+    ("review.delivered_item", "{name} (due {date})"),
+    ("review.retrospectives", "Retrospectives"),
+    ("review.went_well", "Went well: {text}"),
+    ("review.needs_improvement", "Needs improvement: {text}"),
+    ("review.actions", "Actions: {text}"),
+    ("review.no_retrospective", "no retrospective yet"),
+    ("review.milestones", "Milestones"),
+    ("review.milestones_summary", "{due} due, {completed} completed"),
+    ("review.completed", "completed"),
+    ("review.slipped", "Slipped milestones"),
+    ("review.slipped_item", "{project}: {name} moved from {from} to {to} (+{days} days)"),
+    ("review.headcount", "Headcount"),
+    ("review.members.one", "{count} member"),
+    ("review.members.other", "{count} members"),
+    ("review.joined", "Joined: {name} ({date})"),
+    ("review.left", "Left: {name} ({date})"),
+    ("review.risks", "Notable risks"),
+    ("review.risk.open", "open"),
+    ("review.risk.mitigating", "mitigating"),
+    ("review.risk.closed", "closed"),
+];
@@ -0,0 +1,250 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Translated text, dates and numbers for people-facing output
+//!
+//! The digest, desktop notifications, CLI tables, the portfolio report and
+//! team reviews are written in the configured [`Locale`]. Text is looked up
+//! by key in a catalog per language, with `{name}` placeholders filled in by
+//! [`Locale::format`]. A key missing from a catalog falls back to English and
+//! logs a warning. Rendering code takes the locale as a parameter instead of
+//! reading the config, so it stays easy to test.
+//!
+//! Only presentation changes: timestamps are still stored as UTC RFC 3339,
+//! and the MCP tools and JSON output stay in English.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+mod de;
+mod en;
+
+/// A language for people-facing output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+}
+
+impl Locale {
+    /// Every supported locale
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::De];
+
+    /// Language code used in the config file
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::De => "de",
+        }
+    }
+
+    fn catalog(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Locale::En => en::CATALOG,
+            Locale::De => de::CATALOG,
+        }
+    }
+
+    /// The text for `key`, in English if this locale doesn't have it
+    pub fn text(&self, key: &str) -> &'static str {
+        self.text_from(self.catalog(), key)
+    }
+
+    fn text_from(&self, catalog: &'static [(&'static str, &'static str)], key: &str) -> &'static str {
+        if let Some(text) = lookup(catalog, key) {
+            return text;
+        }
+        if *self != Locale::En {
+            log::warn!("No {} text for '{}'; using English", self, key);
+        }
+        lookup(en::CATALOG, key).unwrap_or_else(|| {
+            log::warn!("No text for '{}'", key);
+            ""
+        })
+    }
+
+    /// The text for `key` with each `{name}` replaced by its value from `args`
+    pub fn format(&self, key: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+        fill(self.text(key), args)
+    }
+
+    /// The `key.one` or `key.other` text for `count`, formatted with `args` and `{count}`
+    pub fn plural(&self, key: &str, count: usize, args: &[(&str, &dyn fmt::Display)]) -> String {
+        let form = if count == 1 { "one" } else { "other" };
+        let mut all: Vec<(&str, &dyn fmt::Display)> = vec![("count", &count)];
+        all.extend_from_slice(args);
+        fill(self.text(&format!("{}.{}", key, form)), &all)
+    }
+
+    /// A stored timestamp as a date in `tz`, such as 2025-07-07 or 07.07.2025
+    pub fn date(&self, dt: DateTime<Utc>, tz: Tz) -> String {
+        self.naive_date(dt.with_timezone(&tz).date_naive())
+    }
+
+    /// A calendar date, such as 2025-07-07 or 07.07.2025
+    pub fn naive_date(&self, date: NaiveDate) -> String {
+        match self {
+            Locale::En => date.format("%Y-%m-%d").to_string(),
+            Locale::De => date.format("%d.%m.%Y").to_string(),
+        }
+    }
+
+    /// A stored timestamp as a date and time in `tz`, with the zone's abbreviation
+    pub fn datetime(&self, dt: DateTime<Utc>, tz: Tz) -> String {
+        let local = dt.with_timezone(&tz);
+        match self {
+            Locale::En => local.format("%Y-%m-%d %H:%M %Z").to_string(),
+            Locale::De => local.format("%d.%m.%Y %H:%M %Z").to_string(),
+        }
+    }
+
+    /// A number with `decimals` places and grouped thousands, such as 1,234.5 or 1.234,5
+    pub fn number(&self, value: f64, decimals: usize) -> String {
+        let (group, point) = match self {
+            Locale::En => (',', '.'),
+            Locale::De => ('.', ','),
+        };
+        let formatted = format!("{:.*}", decimals, value.abs());
+        let (whole, fraction) = match formatted.split_once('.') {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (formatted.as_str(), None),
+        };
+
+        let mut out = String::new();
+        if value.is_sign_negative() && formatted.chars().any(|c| c.is_ascii_digit() && c != '0') {
+            out.push('-');
+        }
+        for (i, digit) in whole.chars().enumerate() {
+            if i > 0 && (whole.len() - i) % 3 == 0 {
+                out.push(group);
+            }
+            out.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            out.push(point);
+            out.push_str(fraction);
+        }
+        out
+    }
+}
+
+impl FromStr for Locale {
+    type Err = crate::db::Error;
+
+    /// Parse a language code, ignoring case and any region, so `de-AT` is German
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let language = s.trim().split(['-', '_']).next().unwrap_or_default();
+        match language.to_ascii_lowercase().as_str() {
+            "en" => Ok(Locale::En),
+            "de" => Ok(Locale::De),
+            _ => Err(crate::db::Error::Invalid(format!(
+                "Unsupported locale '{}': expected en or de",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+fn lookup(catalog: &'static [(&'static str, &'static str)], key: &str) -> Option<&'static str> {
+    catalog.iter().find(|(k, _)| *k == key).map(|(_, text)| *text)
+}
+
+/// Replace each `{name}` in `template` with its value from `args`
+///
+/// Placeholders without a value are left as they are.
+fn fill(template: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let value = after
+            .find('}')
+            .and_then(|end| args.iter().find(|(name, _)| *name == &after[..end]).map(|arg| (end, arg.1)));
+        match value {
+            Some((end, value)) => {
+                out.push_str(&value.to_string());
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_catalogs_match() {
+        let keys = |catalog: &'static [(&'static str, &'static str)]| {
+            catalog.iter().map(|(k, _)| *k).collect::<HashSet<_>>()
+        };
+        for catalog in [en::CATALOG, de::CATALOG] {
+            assert_eq!(keys(catalog).len(), catalog.len(), "duplicate keys");
+        }
+        assert_eq!(keys(en::CATALOG), keys(de::CATALOG));
+    }
+
+    #[test]
+    fn test_text_and_fallback() {
+        assert_eq!(Locale::De.text("digest.new_projects"), "Neue Projekte");
+        assert_eq!(Locale::En.text("digest.new_projects"), "New projects");
+        assert_eq!(Locale::De.text_from(&[], "digest.new_projects"), "New projects");
+        assert_eq!(Locale::De.text("no.such.key"), "");
+        assert_eq!(
+            Locale::De.format("digest.subject", &[("date", &"07.07.2025")]),
+            "Projektübersicht vom 07.07.2025"
+        );
+        assert_eq!(
+            Locale::En.plural("digest.note_activity_item", 1, &[("project", &"Apollo"), ("date", &"x")]),
+            "Apollo: 1 note, latest x"
+        );
+        assert_eq!(
+            Locale::De.plural("digest.note_activity_item", 2, &[("project", &"Apollo"), ("date", &"x")]),
+            "Apollo: 2 Notizen, zuletzt am x"
+        );
+        assert_eq!(fill("{a} {b} {", &[("a", &1)]), "1 {b} {");
+    }
+
+    #[test]
+    fn test_dates_and_numbers() {
+        let dt = Utc.with_ymd_and_hms(2025, 3, 31, 22, 30, 0).unwrap();
+        let tokyo: Tz = "Asia/Tokyo".parse().unwrap();
+        assert_eq!(Locale::En.date(dt, Tz::UTC), "2025-03-31");
+        assert_eq!(Locale::De.date(dt, tokyo), "01.04.2025");
+        assert_eq!(Locale::De.datetime(dt, Tz::UTC), "31.03.2025 22:30 UTC");
+
+        assert_eq!(Locale::En.number(1234567.891, 2), "1,234,567.89");
+        assert_eq!(Locale::De.number(1234567.891, 2), "1.234.567,89");
+        assert_eq!(Locale::De.number(-999.0, 0), "-999");
+        assert_eq!(Locale::En.number(-0.01, 1), "0.0");
+        assert_eq!(Locale::De.number(2.5, 1), "2,5");
+    }
+
+    #[test]
+    fn test_parse_locale() {
+        assert_eq!("de-AT".parse::<Locale>().unwrap(), Locale::De);
+        assert_eq!("EN_us".parse::<Locale>().unwrap(), Locale::En);
+        assert!("fr".parse::<Locale>().is_err());
+    }
+}
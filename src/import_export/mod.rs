@@ -0,0 +1,392 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Import people from outside sources, and export the portfolio
+//!
+//! The workbook export lives in [`workbook`], on top of the writer in
+//! [`xlsx`].
+//!
+//! A directory export (Google Workspace, LDAP and similar) is a JSON array of
+//! entries with `displayName`, `primaryEmail`, `managerEmail` and
+//! `department`. Importing the same file twice changes nothing the second
+//! time: people are matched by email, and existing people only have the
+//! fields listed in [`DirectoryImportOptions::update_fields`] overwritten.
+
+use crate::db::{Error, Person, PersonRepository, Result, Team, TeamRepository};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::Read;
+use std::str::FromStr;
+
+pub mod workbook;
+pub mod xlsx;
+
+pub use workbook::{export_workbook, WorkbookSheet};
+
+/// One person in a directory export
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryEntry {
+    /// Full name
+    pub display_name: String,
+    /// Email address, which identifies the person
+    pub primary_email: String,
+    /// Manager's email address
+    #[serde(default)]
+    pub manager_email: Option<String>,
+    /// Department, imported as the person's team
+    #[serde(default)]
+    pub department: Option<String>,
+}
+
+/// A field of an existing person that an import may overwrite
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DirectoryField {
+    /// The name, from `displayName`
+    Name,
+    /// The team, from `department`
+    Team,
+    /// The manager, from `managerEmail`
+    Manager,
+}
+
+impl fmt::Display for DirectoryField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DirectoryField::Name => "name",
+            DirectoryField::Team => "team",
+            DirectoryField::Manager => "manager",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for DirectoryField {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "name" => Ok(DirectoryField::Name),
+            "team" => Ok(DirectoryField::Team),
+            "manager" => Ok(DirectoryField::Manager),
+            other => Err(Error::Invalid(format!(
+                "Unknown directory field '{}': expected name, team or manager",
+                other
+            ))),
+        }
+    }
+}
+
+/// How a directory import treats teams and existing people
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DirectoryImportOptions {
+    /// Create a team for each department that doesn't have one yet
+    #[serde(default)]
+    pub create_teams: bool,
+    /// Fields of existing people the import overwrites; other fields are left alone
+    #[serde(default)]
+    pub update_fields: Vec<DirectoryField>,
+}
+
+/// What an import did
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportReport {
+    /// People that didn't exist before
+    pub created: usize,
+    /// Existing people with at least one field changed
+    pub updated: usize,
+    /// Entries that were unchanged or couldn't be imported
+    pub skipped: usize,
+    /// Problems that didn't stop the import, such as managers missing from the file
+    pub warnings: Vec<String>,
+}
+
+/// What happened to one entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+/// Import people from a directory JSON export
+///
+/// Runs in two passes so managers can appear after their reports: the first
+/// creates or updates everyone's name and team, the second links managers.
+/// A manager who is neither in the file nor already known is left unset
+/// with a warning. Either the whole file is imported or nothing is.
+pub fn import_directory_json(
+    conn: &Connection,
+    reader: impl Read,
+    opts: &DirectoryImportOptions,
+) -> Result<ImportReport> {
+    let entries: Vec<DirectoryEntry> = serde_json::from_reader(reader)
+        .map_err(|e| Error::Invalid(format!("Invalid directory JSON: {}", e)))?;
+
+    let people = PersonRepository::new(conn);
+    let teams = TeamRepository::new(conn);
+    let mut report = ImportReport::default();
+    let mut outcomes: HashMap<String, Outcome> = HashMap::new();
+    let mut imported: Vec<&DirectoryEntry> = Vec::new();
+    let mut known_teams: HashSet<String> = HashSet::new();
+
+    let tx = conn.unchecked_transaction()?;
+
+    for (index, entry) in entries.iter().enumerate() {
+        let email = entry.primary_email.trim();
+        if email.is_empty() {
+            report.warnings.push(format!(
+                "Entry {} has no primaryEmail and was skipped",
+                index + 1
+            ));
+            report.skipped += 1;
+            continue;
+        }
+        if outcomes.contains_key(email) {
+            report.warnings.push(format!(
+                "{} appears more than once; only the first entry was imported",
+                email
+            ));
+            report.skipped += 1;
+            continue;
+        }
+
+        let team = match non_empty(entry.department.as_deref()) {
+            Some(department) => {
+                resolve_team(&teams, department, opts, &mut known_teams, &mut report)?
+            }
+            None => None,
+        };
+        let name = entry.display_name.trim();
+
+        let outcome = match people.find_by_email(email)? {
+            None => {
+                let mut person = Person::new(email.to_string(), name.to_string());
+                person.team = team;
+                people.create(&person)?;
+                Outcome::Created
+            }
+            Some(mut person) => {
+                let mut changed = false;
+                if opts.update_fields.contains(&DirectoryField::Name)
+                    && !name.is_empty()
+                    && person.name != name
+                {
+                    person.name = name.to_string();
+                    changed = true;
+                }
+                if opts.update_fields.contains(&DirectoryField::Team) && person.team != team {
+                    person.team = team;
+                    changed = true;
+                }
+                if changed {
+                    people.update(&person)?;
+                    Outcome::Updated
+                } else {
+                    Outcome::Unchanged
+                }
+            }
+        };
+        outcomes.insert(email.to_string(), outcome);
+        imported.push(entry);
+    }
+
+    for entry in imported {
+        let email = entry.primary_email.trim();
+        let outcome = outcomes[email];
+        if outcome != Outcome::Created && !opts.update_fields.contains(&DirectoryField::Manager) {
+            continue;
+        }
+
+        let manager = non_empty(entry.manager_email.as_deref());
+        if let Some(manager) = manager {
+            if people.find_by_email(manager)?.is_none() {
+                report.warnings.push(format!(
+                    "Manager {} of {} was not found; the manager was not set",
+                    manager, email
+                ));
+                continue;
+            }
+        }
+
+        let Some(mut person) = people.find_by_email(email)? else {
+            continue;
+        };
+        if person.manager.as_deref() == manager {
+            continue;
+        }
+        person.manager = manager.map(str::to_string);
+        match people.update(&person) {
+            Ok(()) => {
+                if outcome == Outcome::Unchanged {
+                    outcomes.insert(email.to_string(), Outcome::Updated);
+                }
+            }
+            Err(e @ Error::CycleDetected { .. }) => {
+                report
+                    .warnings
+                    .push(format!("{}; the manager of {} was not changed", e, email));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    tx.commit()?;
+
+    for outcome in outcomes.values() {
+        match outcome {
+            Outcome::Created => report.created += 1,
+            Outcome::Updated => report.updated += 1,
+            Outcome::Unchanged => report.skipped += 1,
+        }
+    }
+    log::debug!(
+        "Imported directory: {} created, {} updated, {} skipped",
+        report.created,
+        report.updated,
+        report.skipped
+    );
+    Ok(report)
+}
+
+/// The team a department maps to, creating it if the options allow
+fn resolve_team(
+    teams: &TeamRepository,
+    department: &str,
+    opts: &DirectoryImportOptions,
+    known_teams: &mut HashSet<String>,
+    report: &mut ImportReport,
+) -> Result<Option<String>> {
+    if known_teams.contains(department) || teams.find_by_name(department)?.is_some() {
+        known_teams.insert(department.to_string());
+        return Ok(Some(department.to_string()));
+    }
+    if opts.create_teams {
+        teams.create(&Team::new(department.to_string()))?;
+        known_teams.insert(department.to_string());
+        return Ok(Some(department.to_string()));
+    }
+    // Warn once per department
+    let warning = format!(
+        "Team {} does not exist; its people were imported without a team",
+        department
+    );
+    if !report.warnings.contains(&warning) {
+        report.warnings.push(warning);
+    }
+    Ok(None)
+}
+
+/// A trimmed value, or `None` if it is missing or blank
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+
+    const DIRECTORY: &str = include_str!("../../tests/fixtures/directory.json");
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_import_directory() {
+        let conn = setup_test_db();
+        let opts = DirectoryImportOptions {
+            create_teams: true,
+            update_fields: Vec::new(),
+        };
+
+        let report = import_directory_json(&conn, DIRECTORY.as_bytes(), &opts).unwrap();
+        assert_eq!(report.created, 4);
+        assert_eq!(report.updated, 0);
+        assert_eq!(report.skipped, 0);
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].contains("nobody@example.com"));
+
+        let people = PersonRepository::new(&conn);
+        // Bob's manager comes later in the file
+        let bob = people.find_by_email("bob@example.com").unwrap().unwrap();
+        assert_eq!(bob.manager.as_deref(), Some("carol@example.com"));
+        assert_eq!(bob.team.as_deref(), Some("Forschung & Entwicklung"));
+        let dave = people.find_by_email("dave@example.com").unwrap().unwrap();
+        assert!(dave.manager.is_none());
+        assert_eq!(dave.team.as_deref(), Some("Ingénierie 製品"));
+        assert!(TeamRepository::new(&conn)
+            .find_by_name("Ingénierie 製品")
+            .unwrap()
+            .is_some());
+
+        // A second import changes nothing
+        let report = import_directory_json(&conn, DIRECTORY.as_bytes(), &opts).unwrap();
+        assert_eq!((report.created, report.updated, report.skipped), (0, 0, 4));
+    }
+
+    #[test]
+    fn test_import_directory_updates_listed_fields() {
+        let conn = setup_test_db();
+        let people = PersonRepository::new(&conn);
+        let mut alice = Person::new("alice@example.com".to_string(), "Al".to_string());
+        alice.team = Some("Old Team".to_string());
+        people.create(&alice).unwrap();
+
+        // Without create_teams, unknown departments leave the team unset
+        let opts = DirectoryImportOptions {
+            create_teams: false,
+            update_fields: vec![DirectoryField::Name],
+        };
+        let report = import_directory_json(&conn, DIRECTORY.as_bytes(), &opts).unwrap();
+        assert_eq!((report.created, report.updated), (3, 1));
+        assert!(report
+            .warnings
+            .iter()
+            .any(|w| w.contains("Team Forschung & Entwicklung does not exist")));
+
+        let alice = people.find_by_email("alice@example.com").unwrap().unwrap();
+        assert_eq!(alice.name, "Alice Example");
+        assert_eq!(alice.team.as_deref(), Some("Old Team"));
+        assert!(alice.manager.is_none());
+
+        let opts = DirectoryImportOptions {
+            create_teams: false,
+            update_fields: vec![DirectoryField::Manager],
+        };
+        let report = import_directory_json(&conn, DIRECTORY.as_bytes(), &opts).unwrap();
+        assert_eq!((report.created, report.updated, report.skipped), (0, 1, 3));
+        let alice = people.find_by_email("alice@example.com").unwrap().unwrap();
+        assert_eq!(alice.manager.as_deref(), Some("carol@example.com"));
+    }
+
+    #[test]
+    fn test_import_directory_rejects_bad_json() {
+        let conn = setup_test_db();
+        let err = import_directory_json(
+            &conn,
+            "{\"people\": []}".as_bytes(),
+            &DirectoryImportOptions::default(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::Invalid(_)));
+        assert!(PersonRepository::new(&conn)
+            .list_all(true)
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(
+            "Manager".parse::<DirectoryField>().unwrap(),
+            DirectoryField::Manager
+        );
+        assert!("email".parse::<DirectoryField>().is_err());
+    }
+}
@@ -0,0 +1,287 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Export the portfolio to an Excel workbook
+//!
+//! [`export_workbook`] writes an `.xlsx` file with a sheet each for
+//! projects, milestones, people, teams, assignments (who works on which
+//! project or milestone, and in what role) and stakeholders, plus a
+//! `Metadata` sheet recording when it was exported and from which schema
+//! version. Rows are read from one snapshot of the database and written out
+//! as they are read.
+
+use super::xlsx::{Cell, Sheet, Workbook};
+use crate::db::{get_datetime, get_opt_datetime, schema};
+use crate::Result;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use rusqlite::{Connection, Row};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufWriter, Seek, Write};
+use std::path::Path;
+
+/// A sheet of an exported workbook and how many rows it has
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WorkbookSheet {
+    pub name: String,
+    /// Rows below the header
+    pub rows: usize,
+}
+
+/// Write the portfolio to an `.xlsx` file at `path`, replacing any file there
+///
+/// Dates and timestamps are shown in `tz`. Returns the sheets written, in
+/// order. The file only appears once the whole workbook has been written.
+pub fn export_workbook(conn: &Connection, path: &Path, tz: Tz) -> Result<Vec<WorkbookSheet>> {
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".partial");
+    let partial = Path::new(&partial);
+
+    let written = File::create(partial)
+        .with_context(|| format!("Failed to create {}", partial.display()))
+        .and_then(|file| write_workbook(conn, BufWriter::new(file), tz))
+        .and_then(|(out, sheets)| {
+            out.into_inner()
+                .map_err(|e| e.into_error())
+                .and_then(|file| file.sync_all())
+                .with_context(|| format!("Failed to write {}", partial.display()))?;
+            std::fs::rename(partial, path).with_context(|| format!("Failed to write {}", path.display()))?;
+            Ok(sheets)
+        });
+    if written.is_err() {
+        let _ = std::fs::remove_file(partial);
+    }
+    let sheets = written?;
+    log::info!("Exported workbook to {}", path.display());
+    Ok(sheets)
+}
+
+/// Write the workbook to `out`, returning it with the sheets written
+fn write_workbook<W: Write + Seek>(conn: &Connection, out: W, tz: Tz) -> Result<(W, Vec<WorkbookSheet>)> {
+    // A read transaction, so every sheet sees the same data
+    let _snapshot = conn.unchecked_transaction()?;
+    let mut workbook = Workbook::new(out);
+    let mut sheets = Vec::new();
+    let date = |row: &Row, idx| -> rusqlite::Result<Cell> {
+        Ok(get_opt_datetime(row, idx)?.map(|dt| dt.with_timezone(&tz).date_naive()).into())
+    };
+    let timestamp = |row: &Row, idx| -> rusqlite::Result<Cell> {
+        Ok(local_timestamp(get_datetime(row, idx)?, tz))
+    };
+    let text = |row: &Row, idx| -> rusqlite::Result<Cell> { Ok(row.get::<_, Option<String>>(idx)?.into()) };
+    let number = |row: &Row, idx| -> rusqlite::Result<Cell> { Ok(row.get::<_, Option<f64>>(idx)?.into()) };
+    let flag = |row: &Row, idx| -> rusqlite::Result<Cell> { Ok(row.get::<_, bool>(idx)?.into()) };
+
+    let mut add = |name: &str, headers: &[&str], sql: &str, to_cells: &dyn Fn(&Row) -> rusqlite::Result<Vec<Cell>>| {
+        let rows = workbook.sheet(name, headers, |sheet| write_rows(conn, sheet, sql, to_cells))?;
+        sheets.push(WorkbookSheet { name: name.to_string(), rows });
+        Result::Ok(())
+    };
+
+    add(
+        "Projects",
+        &[
+            "ID", "Slug", "Name", "Type", "Status", "Phase", "Team", "Manager", "Technical Lead",
+            "Requirements Owner", "Start Date", "Due Date", "Blocked", "Budget", "Currency", "Jira Initiative",
+            "Created", "Updated",
+        ],
+        "SELECT id, slug, name, type, status, phase, team, manager, technical_lead, requirements_owner,
+                start_date, due_date, blocked, budget_amount, budget_currency, jira_initiative, created_at, updated_at
+         FROM projects
+         ORDER BY name COLLATE NOCASE, id",
+        &|row| {
+            Ok(vec![
+                text(row, 0)?, text(row, 1)?, text(row, 2)?, text(row, 3)?, text(row, 4)?, text(row, 5)?,
+                text(row, 6)?, text(row, 7)?, text(row, 8)?, text(row, 9)?, date(row, 10)?, date(row, 11)?,
+                flag(row, 12)?, number(row, 13)?, text(row, 14)?, text(row, 15)?, timestamp(row, 16)?,
+                timestamp(row, 17)?,
+            ])
+        },
+    )?;
+    add(
+        "Milestones",
+        &[
+            "ID", "Project", "Number", "Name", "Team", "Technical Lead", "Start Date", "Due Date", "Estimated Days",
+            "Actual Days", "Jira Epic", "Design Doc",
+        ],
+        "SELECT m.id, p.name, m.number, m.name, m.team, m.technical_lead, m.start_date, m.due_date,
+                m.estimated_days, m.actual_days, m.jira_epic, m.design_doc_url
+         FROM milestones m
+         JOIN projects p ON p.id = m.project_id
+         ORDER BY p.name COLLATE NOCASE, p.id, m.number",
+        &|row| {
+            Ok(vec![
+                text(row, 0)?, text(row, 1)?, number(row, 2)?, text(row, 3)?, text(row, 4)?, text(row, 5)?,
+                date(row, 6)?, date(row, 7)?, number(row, 8)?, number(row, 9)?, text(row, 10)?, text(row, 11)?,
+            ])
+        },
+    )?;
+    add(
+        "People",
+        &["Email", "Name", "Team", "Organization", "Manager", "Active", "External", "Created", "Updated"],
+        "SELECT email, name, team, organization, manager, active, external, created_at, updated_at
+         FROM people
+         ORDER BY name COLLATE NOCASE, email",
+        &|row| {
+            Ok(vec![
+                text(row, 0)?, text(row, 1)?, text(row, 2)?, text(row, 3)?, text(row, 4)?, flag(row, 5)?,
+                flag(row, 6)?, timestamp(row, 7)?, timestamp(row, 8)?,
+            ])
+        },
+    )?;
+    add(
+        "Teams",
+        &["Name", "Parent Team", "Manager", "Members", "Description"],
+        "SELECT t.name, t.parent_team, t.manager,
+                (SELECT COUNT(*) FROM team_members tm WHERE tm.team_name = t.name), t.description
+         FROM teams t
+         ORDER BY t.name COLLATE NOCASE",
+        &|row| Ok(vec![text(row, 0)?, text(row, 1)?, text(row, 2)?, number(row, 3)?, text(row, 4)?]),
+    )?;
+    add(
+        "Assignments",
+        &["Email", "Person", "Project", "Milestone Number", "Milestone", "Role"],
+        "SELECT r.person_email AS email, pe.name, p.name AS project, NULL AS number, NULL, r.role
+         FROM project_resources r
+         JOIN projects p ON p.id = r.project_id
+         LEFT JOIN people pe ON pe.email = r.person_email
+         UNION ALL
+         SELECT r.person_email, pe.name, p.name, m.number, m.name, r.role
+         FROM milestone_resources r
+         JOIN milestones m ON m.id = r.milestone_id
+         JOIN projects p ON p.id = m.project_id
+         LEFT JOIN people pe ON pe.email = r.person_email
+         ORDER BY email, project COLLATE NOCASE, number",
+        &|row| {
+            Ok(vec![text(row, 0)?, text(row, 1)?, text(row, 2)?, number(row, 3)?, text(row, 4)?, text(row, 5)?])
+        },
+    )?;
+    add(
+        "Stakeholders",
+        &["Project", "Email", "Name", "Role", "Influence", "Interest"],
+        "SELECT p.name, s.stakeholder_email, pe.name, s.role, s.influence, s.interest
+         FROM project_stakeholders s
+         JOIN projects p ON p.id = s.project_id
+         LEFT JOIN people pe ON pe.email = s.stakeholder_email
+         ORDER BY p.name COLLATE NOCASE, s.stakeholder_email",
+        &|row| {
+            Ok(vec![text(row, 0)?, text(row, 1)?, text(row, 2)?, text(row, 3)?, number(row, 4)?, number(row, 5)?])
+        },
+    )?;
+
+    let schema_version = schema::get_schema_version(conn)?;
+    let rows = workbook.sheet("Metadata", &["Field", "Value"], |sheet| {
+        sheet.row([Cell::from("Exported At"), local_timestamp(Utc::now(), tz)])?;
+        sheet.row([Cell::from("Time Zone"), Cell::from(tz.name())])?;
+        sheet.row([Cell::from("Schema Version"), Cell::from(i64::from(schema_version))])?;
+        sheet.row([Cell::from("Application Version"), Cell::from(env!("CARGO_PKG_VERSION"))])?;
+        Ok(())
+    })?;
+    sheets.push(WorkbookSheet { name: "Metadata".to_string(), rows });
+
+    Ok((workbook.finish()?, sheets))
+}
+
+/// Write a row to the sheet for each row `sql` returns, one at a time
+fn write_rows<W: Write + Seek>(
+    conn: &Connection,
+    sheet: &mut Sheet<'_, W>,
+    sql: &str,
+    to_cells: &dyn Fn(&Row) -> rusqlite::Result<Vec<Cell>>,
+) -> Result<()> {
+    let mut stmt = conn.prepare(sql)?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        sheet.row(to_cells(row)?)?;
+    }
+    Ok(())
+}
+
+fn local_timestamp(dt: DateTime<Utc>, tz: Tz) -> Cell {
+    Cell::DateTime(dt.with_timezone(&tz).naive_local())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{
+        Milestone, MilestoneResource, Person, PersonRepository, Project, ProjectRepository, ProjectResource, ProjectStakeholder,
+        Team, TeamRepository,
+    };
+    use chrono::TimeZone;
+    use crate::import_export::xlsx::read_entries;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        schema::initialize_schema(&conn).unwrap();
+        schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_export_workbook() {
+        let conn = setup_test_db();
+        TeamRepository::new(&conn).create(&Team::new("Platform".to_string())).unwrap();
+        let people = PersonRepository::new(&conn);
+        people.create(&Person::new("ada@example.com".to_string(), "Ada".to_string())).unwrap();
+        people.create(&Person::new("bob@example.com".to_string(), "Bob & Co".to_string())).unwrap();
+
+        let repo = ProjectRepository::new(&conn);
+        let mut project = Project::new("Apollo".to_string());
+        project.due_date = Some(Utc.with_ymd_and_hms(2025, 3, 31, 0, 0, 0).unwrap());
+        repo.create(&project).unwrap();
+        let milestone = Milestone::new(project.id, 1, "Launch".to_string());
+        repo.add_milestone(&milestone).unwrap();
+        let mut resource = ProjectResource::new(project.id, "ada@example.com".to_string());
+        resource.role = Some("Lead".to_string());
+        repo.add_project_resource(&project.id, &resource).unwrap();
+        let resource = MilestoneResource::new(milestone.id, "bob@example.com".to_string());
+        repo.add_milestone_resource(&milestone.id, &resource).unwrap();
+        let stakeholder = ProjectStakeholder::new(project.id, "bob@example.com".to_string());
+        repo.add_stakeholder(&project.id, &stakeholder).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("portfolio.xlsx");
+        let sheets = export_workbook(&conn, &path, chrono_tz::UTC).unwrap();
+        let counts: Vec<(&str, usize)> = sheets.iter().map(|s| (s.name.as_str(), s.rows)).collect();
+        assert_eq!(
+            counts,
+            [
+                ("Projects", 1),
+                ("Milestones", 1),
+                ("People", 2),
+                ("Teams", 1),
+                ("Assignments", 2),
+                ("Stakeholders", 1),
+                ("Metadata", 4)
+            ]
+        );
+        assert!(!dir.path().join("portfolio.xlsx.partial").exists());
+
+        let entries = read_entries(&std::fs::read(&path).unwrap());
+        let workbook = &entries["xl/workbook.xml"];
+        for (i, name) in ["Projects", "Milestones", "People", "Teams", "Assignments", "Stakeholders", "Metadata"]
+            .iter()
+            .enumerate()
+        {
+            assert!(workbook.contains(&format!(r#"<sheet name="{}" sheetId="{}""#, name, i + 1)), "{}", name);
+        }
+
+        // The project's due date is a date cell: 2025-03-31 is serial 45747
+        let projects = &entries["xl/worksheets/sheet1.xml"];
+        assert!(projects.contains(r#"<t xml:space="preserve">Apollo</t>"#));
+        assert!(projects.contains(r#"<c r="L2" s="2"><v>45747</v></c>"#));
+        assert_eq!(projects.matches("<row ").count(), 2);
+
+        let assignments = &entries["xl/worksheets/sheet5.xml"];
+        assert!(assignments.contains(r#"<c r="F2" t="inlineStr"><is><t xml:space="preserve">Lead</t></is></c>"#));
+        assert!(assignments.contains("Bob &amp; Co"));
+        assert!(assignments.contains(r#"<c r="D3"><v>1</v></c>"#));
+
+        let metadata = &entries["xl/worksheets/sheet7.xml"];
+        assert!(metadata.contains(&format!("<v>{}</v>", schema::SUPPORTED_SCHEMA_VERSION)));
+    }
+}
@@ -0,0 +1,548 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Minimal XLSX writer
+//!
+//! Writes just enough of the Office Open XML spreadsheet format for Excel,
+//! LibreOffice and Google Sheets to open the file: one worksheet per
+//! [`Workbook::sheet`] call with a bold, frozen header row, text written as
+//! inline strings, and dates and timestamps stored as date serials with a
+//! date format. Rows go straight into the file as they are written, so a
+//! sheet never has to fit in memory.
+//!
+//! The package is a zip archive whose entries are stored uncompressed; each
+//! entry's header is patched with its size and CRC once it is complete,
+//! which is why the output has to be seekable.
+
+use crate::Result;
+use chrono::{NaiveDate, NaiveDateTime};
+use std::fmt::Write as _;
+use std::io::{self, Seek, SeekFrom, Write};
+
+/// Most characters Excel keeps in one cell; longer text is cut short
+pub const MAX_CELL_CHARS: usize = 32_767;
+
+/// Style indexes into the `cellXfs` list in [`STYLES`]
+const STYLE_HEADER: u8 = 1;
+const STYLE_DATE: u8 = 2;
+const STYLE_DATE_TIME: u8 = 3;
+
+const STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><numFmts count="2"><numFmt numFmtId="164" formatCode="yyyy-mm-dd"/><numFmt numFmtId="165" formatCode="yyyy-mm-dd hh:mm"/></numFmts><fonts count="2"><font><sz val="11"/><name val="Calibri"/></font><font><b/><sz val="11"/><name val="Calibri"/></font></fonts><fills count="2"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill></fills><borders count="1"><border><left/><right/><top/><bottom/><diagonal/></border></borders><cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs><cellXfs count="4"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/><xf numFmtId="0" fontId="1" fillId="0" borderId="0" xfId="0" applyFont="1"/><xf numFmtId="164" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/><xf numFmtId="165" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/></cellXfs><cellStyles count="1"><cellStyle name="Normal" xfId="0" builtinId="0"/></cellStyles></styleSheet>"#;
+
+const ROOT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#;
+
+/// One cell of a row
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Empty,
+    Text(String),
+    Number(f64),
+    Bool(bool),
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+impl From<&str> for Cell {
+    fn from(value: &str) -> Self {
+        Cell::Text(value.to_string())
+    }
+}
+
+impl From<String> for Cell {
+    fn from(value: String) -> Self {
+        Cell::Text(value)
+    }
+}
+
+impl<T: Into<Cell>> From<Option<T>> for Cell {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Cell::Empty, Into::into)
+    }
+}
+
+impl From<f64> for Cell {
+    fn from(value: f64) -> Self {
+        Cell::Number(value)
+    }
+}
+
+impl From<i64> for Cell {
+    fn from(value: i64) -> Self {
+        Cell::Number(value as f64)
+    }
+}
+
+impl From<bool> for Cell {
+    fn from(value: bool) -> Self {
+        Cell::Bool(value)
+    }
+}
+
+impl From<NaiveDate> for Cell {
+    fn from(value: NaiveDate) -> Self {
+        Cell::Date(value)
+    }
+}
+
+impl From<NaiveDateTime> for Cell {
+    fn from(value: NaiveDateTime) -> Self {
+        Cell::DateTime(value)
+    }
+}
+
+/// An XLSX file being written
+///
+/// Call [`Workbook::finish`] once every sheet is written; until then the
+/// file isn't a valid workbook.
+pub struct Workbook<W: Write + Seek> {
+    zip: ZipWriter<W>,
+    sheets: Vec<String>,
+}
+
+impl<W: Write + Seek> Workbook<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            zip: ZipWriter::new(out),
+            sheets: Vec::new(),
+        }
+    }
+
+    /// Write a sheet with a header row, then the rows `write_rows` adds
+    ///
+    /// Returns the number of rows added, not counting the header.
+    pub fn sheet<F>(&mut self, name: &str, headers: &[&str], write_rows: F) -> Result<usize>
+    where
+        F: FnOnce(&mut Sheet<'_, W>) -> Result<()>,
+    {
+        if self.sheets.iter().any(|sheet| sheet.eq_ignore_ascii_case(name)) {
+            anyhow::bail!("The workbook already has a sheet named {}", name);
+        }
+        self.sheets.push(name.to_string());
+        self.zip.start_file(&format!("xl/worksheets/sheet{}.xml", self.sheets.len()))?;
+
+        let mut xml = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheetViews><sheetView workbookViewId="0"><pane ySplit="1" topLeftCell="A2" activePane="bottomLeft" state="frozen"/></sheetView></sheetViews><cols>"#,
+        );
+        for (i, header) in headers.iter().enumerate() {
+            let width = (header.chars().count() + 4).max(12);
+            let _ = write!(xml, r#"<col min="{0}" max="{0}" width="{1}" customWidth="1"/>"#, i + 1, width);
+        }
+        xml.push_str("</cols><sheetData>");
+        self.zip.write_all(xml.as_bytes())?;
+
+        let mut sheet = Sheet { zip: &mut self.zip, rows: 0 };
+        sheet.write_row(headers.iter().map(|header| Cell::from(*header)), Some(STYLE_HEADER))?;
+        write_rows(&mut sheet)?;
+        let rows = sheet.rows - 1;
+
+        self.zip.write_all(b"</sheetData></worksheet>")?;
+        self.zip.finish_file()?;
+        Ok(rows as usize)
+    }
+
+    /// Write the parts that tie the sheets together and close the archive
+    pub fn finish(mut self) -> Result<W> {
+        let mut workbook = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets>"#,
+        );
+        let mut rels = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+        );
+        let mut types = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/>"#,
+        );
+        for (i, name) in self.sheets.iter().enumerate() {
+            let n = i + 1;
+            let _ = write!(workbook, r#"<sheet name="{}" sheetId="{}" r:id="rId{}"/>"#, escape(name), n, n);
+            let _ = write!(
+                rels,
+                r#"<Relationship Id="rId{0}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet{0}.xml"/>"#,
+                n
+            );
+            let _ = write!(
+                types,
+                r#"<Override PartName="/xl/worksheets/sheet{}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#,
+                n
+            );
+        }
+        workbook.push_str("</sheets></workbook>");
+        let _ = write!(
+            rels,
+            r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/></Relationships>"#,
+            self.sheets.len() + 1
+        );
+        types.push_str("</Types>");
+
+        for (name, contents) in [
+            ("xl/workbook.xml", workbook.as_str()),
+            ("xl/_rels/workbook.xml.rels", rels.as_str()),
+            ("xl/styles.xml", STYLES),
+            ("_rels/.rels", ROOT_RELS),
+            ("[Content_Types].xml", types.as_str()),
+        ] {
+            self.zip.start_file(name)?;
+            self.zip.write_all(contents.as_bytes())?;
+            self.zip.finish_file()?;
+        }
+        Ok(self.zip.finish()?)
+    }
+}
+
+/// A sheet being written by [`Workbook::sheet`]
+pub struct Sheet<'a, W: Write + Seek> {
+    zip: &'a mut ZipWriter<W>,
+    /// Rows written so far, header included
+    rows: u32,
+}
+
+impl<W: Write + Seek> Sheet<'_, W> {
+    /// Add a row below the ones already written
+    pub fn row<I: IntoIterator<Item = Cell>>(&mut self, cells: I) -> Result<()> {
+        self.write_row(cells, None)
+    }
+
+    fn write_row<I: IntoIterator<Item = Cell>>(&mut self, cells: I, style: Option<u8>) -> Result<()> {
+        self.rows += 1;
+        let row = self.rows;
+        let mut xml = format!(r#"<row r="{}">"#, row);
+        for (i, cell) in cells.into_iter().enumerate() {
+            let reference = format!("{}{}", column_name(i), row);
+            let style = |default: u8| match style.unwrap_or(default) {
+                0 => String::new(),
+                style => format!(r#" s="{}""#, style),
+            };
+            match cell {
+                Cell::Empty => {}
+                Cell::Text(text) => {
+                    let text: String = text.chars().take(MAX_CELL_CHARS).collect();
+                    let _ = write!(
+                        xml,
+                        r#"<c r="{}"{} t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+                        reference,
+                        style(0),
+                        escape(&text)
+                    );
+                }
+                Cell::Number(value) if value.is_finite() => {
+                    let _ = write!(xml, r#"<c r="{}"{}><v>{}</v></c>"#, reference, style(0), value);
+                }
+                Cell::Number(_) => {}
+                Cell::Bool(value) => {
+                    let _ = write!(xml, r#"<c r="{}"{} t="b"><v>{}</v></c>"#, reference, style(0), u8::from(value));
+                }
+                Cell::Date(date) => {
+                    let serial = date_serial(date.and_hms_opt(0, 0, 0).unwrap_or_default());
+                    let _ = write!(xml, r#"<c r="{}"{}><v>{}</v></c>"#, reference, style(STYLE_DATE), serial);
+                }
+                Cell::DateTime(date_time) => {
+                    let serial = date_serial(date_time);
+                    let _ = write!(xml, r#"<c r="{}"{}><v>{}</v></c>"#, reference, style(STYLE_DATE_TIME), serial);
+                }
+            }
+        }
+        xml.push_str("</row>");
+        self.zip.write_all(xml.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Spreadsheet column name for a zero-based index: A, B, ..., Z, AA, AB, ...
+fn column_name(index: usize) -> String {
+    let mut name = Vec::new();
+    let mut n = index + 1;
+    while n > 0 {
+        let rem = (n - 1) % 26;
+        name.push(b'A' + rem as u8);
+        n = (n - 1) / 26;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+/// Days since 1899-12-30, the date serial spreadsheets store dates as
+fn date_serial(value: NaiveDateTime) -> f64 {
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30)
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .unwrap_or_default();
+    let seconds = (value - epoch).num_seconds() as f64;
+    seconds / 86_400.0
+}
+
+/// Escape text for XML, dropping control characters XML can't hold
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Writes a zip archive of uncompressed entries, one at a time
+struct ZipWriter<W: Write + Seek> {
+    out: W,
+    /// Bytes written so far
+    position: u64,
+    entries: Vec<ZipEntry>,
+    /// The entry being written, if any
+    current: Option<ZipEntry>,
+}
+
+struct ZipEntry {
+    name: String,
+    offset: u32,
+    crc: u32,
+    size: u32,
+}
+
+/// Version 2.0, the first with stored entries in folders
+const ZIP_VERSION: u16 = 20;
+/// Names are UTF-8
+const ZIP_FLAGS: u16 = 1 << 11;
+/// 1980-01-01, the earliest date a zip entry can carry
+const ZIP_DATE: u16 = (1 << 5) | 1;
+
+impl<W: Write + Seek> ZipWriter<W> {
+    fn new(out: W) -> Self {
+        Self {
+            out,
+            position: 0,
+            entries: Vec::new(),
+            current: None,
+        }
+    }
+
+    fn offset(&self) -> io::Result<u32> {
+        u32::try_from(self.position)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Workbook is larger than 4 GB"))
+    }
+
+    fn put(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.out.write_all(bytes)?;
+        self.position += bytes.len() as u64;
+        Ok(())
+    }
+
+    /// Start an entry; its CRC and size are filled in by `finish_file`
+    fn start_file(&mut self, name: &str) -> io::Result<()> {
+        let entry = ZipEntry {
+            name: name.to_string(),
+            offset: self.offset()?,
+            crc: !0,
+            size: 0,
+        };
+        let mut header = Vec::with_capacity(30 + name.len());
+        header.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        header.extend_from_slice(&ZIP_VERSION.to_le_bytes());
+        header.extend_from_slice(&ZIP_FLAGS.to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes()); // stored
+        header.extend_from_slice(&0u16.to_le_bytes()); // time
+        header.extend_from_slice(&ZIP_DATE.to_le_bytes());
+        header.extend_from_slice(&[0; 12]); // CRC and sizes, patched later
+        header.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes()); // extra field length
+        header.extend_from_slice(name.as_bytes());
+        self.put(&header)?;
+        self.current = Some(entry);
+        Ok(())
+    }
+
+    /// Fill in the current entry's CRC and size
+    fn finish_file(&mut self) -> io::Result<()> {
+        let Some(mut entry) = self.current.take() else {
+            return Ok(());
+        };
+        entry.crc = !entry.crc;
+        let mut patch = Vec::with_capacity(12);
+        patch.extend_from_slice(&entry.crc.to_le_bytes());
+        patch.extend_from_slice(&entry.size.to_le_bytes());
+        patch.extend_from_slice(&entry.size.to_le_bytes());
+        self.out.seek(SeekFrom::Start(u64::from(entry.offset) + 14))?;
+        self.out.write_all(&patch)?;
+        self.out.seek(SeekFrom::Start(self.position))?;
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Write the central directory and return the output
+    fn finish(mut self) -> io::Result<W> {
+        self.finish_file()?;
+        let directory_offset = self.offset()?;
+        let entries = std::mem::take(&mut self.entries);
+        for entry in &entries {
+            let mut header = Vec::with_capacity(46 + entry.name.len());
+            header.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+            header.extend_from_slice(&ZIP_VERSION.to_le_bytes()); // made by
+            header.extend_from_slice(&ZIP_VERSION.to_le_bytes()); // needed
+            header.extend_from_slice(&ZIP_FLAGS.to_le_bytes());
+            header.extend_from_slice(&0u16.to_le_bytes()); // stored
+            header.extend_from_slice(&0u16.to_le_bytes()); // time
+            header.extend_from_slice(&ZIP_DATE.to_le_bytes());
+            header.extend_from_slice(&entry.crc.to_le_bytes());
+            header.extend_from_slice(&entry.size.to_le_bytes());
+            header.extend_from_slice(&entry.size.to_le_bytes());
+            header.extend_from_slice(&(entry.name.len() as u16).to_le_bytes());
+            header.extend_from_slice(&[0; 12]); // extra, comment, disk, attributes
+            header.extend_from_slice(&entry.offset.to_le_bytes());
+            header.extend_from_slice(entry.name.as_bytes());
+            self.put(&header)?;
+        }
+        let directory_size = self.offset()? - directory_offset;
+        let count = u16::try_from(entries.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Too many entries for a zip file"))?;
+        let mut end = Vec::with_capacity(22);
+        end.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        end.extend_from_slice(&[0; 4]); // disk numbers
+        end.extend_from_slice(&count.to_le_bytes());
+        end.extend_from_slice(&count.to_le_bytes());
+        end.extend_from_slice(&directory_size.to_le_bytes());
+        end.extend_from_slice(&directory_offset.to_le_bytes());
+        end.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        self.put(&end)?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+impl<W: Write + Seek> Write for ZipWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let Some(entry) = self.current.as_mut() else {
+            return Err(io::Error::other("No zip entry has been started"));
+        };
+        entry.size = u32::try_from(u64::from(entry.size) + buf.len() as u64)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Sheet is larger than 4 GB"))?;
+        entry.crc = crc32_update(entry.crc, buf);
+        self.out.write_all(buf)?;
+        self.position += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Lookup table for the CRC-32 zip uses (polynomial 0xEDB88320)
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Continue a CRC-32 (kept inverted, starting from `!0`) over more bytes
+fn crc32_update(mut crc: u32, bytes: &[u8]) -> u32 {
+    for &byte in bytes {
+        crc = CRC_TABLE[((crc ^ u32::from(byte)) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    crc
+}
+
+/// Read back every entry of a written workbook with the `zip` crate
+///
+/// An independent reader, so a mistake in [`ZipWriter`] can't be mirrored by
+/// the test that checks it. Reading an entry to the end verifies its CRC.
+#[cfg(test)]
+pub(crate) fn read_entries(bytes: &[u8]) -> std::collections::BTreeMap<String, String> {
+    use std::io::Read;
+
+    let mut archive = zip::ZipArchive::new(io::Cursor::new(bytes)).unwrap();
+    let mut entries = std::collections::BTreeMap::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).unwrap();
+        let mut data = String::new();
+        entry.read_to_string(&mut data).unwrap();
+        entries.insert(entry.name().to_string(), data);
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(!crc32_update(!0, b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_column_name_and_date_serial() {
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(25), "Z");
+        assert_eq!(column_name(26), "AA");
+        assert_eq!(column_name(701), "ZZ");
+        assert_eq!(column_name(702), "AAA");
+
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        assert_eq!(date_serial(date.and_hms_opt(0, 0, 0).unwrap()), 45658.0);
+        assert_eq!(date_serial(date.and_hms_opt(12, 0, 0).unwrap()), 45658.5);
+    }
+
+    #[test]
+    fn test_workbook() {
+        let mut workbook = Workbook::new(Cursor::new(Vec::new()));
+        let rows = workbook
+            .sheet("People", &["Name", "Joined", "Active", "Score"], |sheet| {
+                sheet.row([
+                    Cell::from("Ada & <Co>"),
+                    Cell::from(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()),
+                    Cell::from(true),
+                    Cell::from(Some(1.5)),
+                ])?;
+                sheet.row([Cell::from("Bell\u{7}"), Cell::Empty, Cell::from(false), Cell::from(None::<f64>)])?;
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(rows, 2);
+        assert!(workbook.sheet("people", &["Name"], |_| Ok(())).is_err());
+        let bytes = workbook.finish().unwrap().into_inner();
+
+        let entries = read_entries(&bytes);
+        assert_eq!(
+            entries.keys().map(String::as_str).collect::<Vec<_>>(),
+            [
+                "[Content_Types].xml",
+                "_rels/.rels",
+                "xl/_rels/workbook.xml.rels",
+                "xl/styles.xml",
+                "xl/workbook.xml",
+                "xl/worksheets/sheet1.xml"
+            ]
+        );
+        assert!(entries["xl/workbook.xml"].contains(r#"<sheet name="People" sheetId="1" r:id="rId1"/>"#));
+        let sheet = &entries["xl/worksheets/sheet1.xml"];
+        assert!(sheet.contains(r#"state="frozen""#));
+        assert!(sheet.contains(r#"<c r="A1" s="1" t="inlineStr"><is><t xml:space="preserve">Name</t></is></c>"#));
+        assert!(sheet.contains("Ada &amp; &lt;Co&gt;"));
+        assert!(sheet.contains(r#"<c r="B2" s="2"><v>45658</v></c>"#));
+        assert!(sheet.contains(r#"<c r="C2" t="b"><v>1</v></c>"#));
+        assert!(sheet.contains(r#"<c r="D2"><v>1.5</v></c>"#));
+        assert!(sheet.contains(">Bell</t>"));
+        assert!(!sheet.contains("B3"));
+    }
+}
@@ -10,9 +10,21 @@
 pub mod config;
 pub mod core;
 pub mod db;
+pub mod diagram;
+pub mod digest;
+pub mod fixtures;
+pub mod i18n;
+pub mod import_export;
+pub mod linkcheck;
 pub mod mcp;
+pub mod notes;
+pub mod notifications;
+pub mod report;
+pub mod secrets;
+pub mod service;
 pub mod storage;
 pub mod utils;
+pub mod webhook;
 
 // Re-export commonly used types
 pub use config::Config;
@@ -0,0 +1,319 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Checking the links stored in the tracker for dead ones
+//!
+//! Design docs, project documents and Jira tickets move and disappear.
+//! [`check_urls`] asks each URL's server whether it is still there, with a
+//! bounded number of requests in flight. Requests go through a [`LinkProbe`],
+//! so tests can answer them without a network; [`HttpProbe`] is the real one.
+//! The results are stored with `LinkCheckRepository::save_checks`.
+
+use crate::config::LinkCheckConfig;
+use crate::db::{LinkCheck, LinkStatus};
+use crate::utils::is_http_url;
+use chrono::Utc;
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinSet;
+
+/// What a server said when asked for a URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Probe {
+    /// The HTTP status it answered with, after following redirects
+    Status(u16),
+    /// Why there was no answer
+    Failed(String),
+}
+
+/// Asks a server for a URL
+pub trait LinkProbe: Send + Sync + 'static {
+    /// Request `url` and report the answer
+    fn probe(&self, url: &str) -> impl Future<Output = Probe> + Send;
+}
+
+/// Checks links over HTTP, with the timeout and redirect limit from the config
+pub struct HttpProbe {
+    client: reqwest::Client,
+}
+
+impl HttpProbe {
+    pub fn new(config: &LinkCheckConfig) -> crate::Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .redirect(reqwest::redirect::Policy::limited(config.max_redirects))
+            .user_agent(concat!("project-tracker/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+        Ok(Self { client })
+    }
+}
+
+impl LinkProbe for HttpProbe {
+    async fn probe(&self, url: &str) -> Probe {
+        // HEAD avoids downloading the page, but not every server supports it
+        match self.client.head(url).send().await {
+            Ok(response) if !matches!(response.status().as_u16(), 405 | 501) => {
+                return Probe::Status(response.status().as_u16())
+            }
+            Ok(_) => {}
+            Err(e) => return Probe::Failed(describe_error(&e)),
+        }
+        match self.client.get(url).send().await {
+            Ok(response) => Probe::Status(response.status().as_u16()),
+            Err(e) => Probe::Failed(describe_error(&e)),
+        }
+    }
+}
+
+/// Describe a failed request, including the underlying cause
+fn describe_error(e: &reqwest::Error) -> String {
+    if e.is_timeout() {
+        return "Timed out".to_string();
+    }
+    if e.is_redirect() {
+        return "Too many redirects".to_string();
+    }
+    let mut description = e.to_string();
+    let mut source = std::error::Error::source(e);
+    while let Some(cause) = source {
+        description.push_str(": ");
+        description.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    description
+}
+
+/// Whether a URL's host is one of `domains` or a subdomain of one
+pub fn is_skipped(url: &str, domains: &[String]) -> bool {
+    let Some(host) = url::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+    else {
+        return false;
+    };
+    domains.iter().any(|domain| {
+        let domain = domain.trim().trim_start_matches('.').to_ascii_lowercase();
+        !domain.is_empty() && (host == domain || host.ends_with(&format!(".{}", domain)))
+    })
+}
+
+/// Check each URL once, calling `progress` with every result as it comes in
+///
+/// Duplicate URLs are checked once. At most `concurrency` requests are in
+/// flight at a time. URLs on skipped domains aren't requested, and values that
+/// aren't `http` or `https` URLs count as unreachable. `progress` gets the
+/// result along with the number of URLs checked so far and the total.
+/// Results are returned in the order the URLs were given.
+pub async fn check_urls<P: LinkProbe>(
+    probe: Arc<P>,
+    urls: impl IntoIterator<Item = String>,
+    config: &LinkCheckConfig,
+    mut progress: impl FnMut(&LinkCheck, usize, usize),
+) -> Vec<LinkCheck> {
+    let mut seen = HashSet::new();
+    let urls: Vec<String> = urls.into_iter().filter(|url| seen.insert(url.clone())).collect();
+    let total = urls.len();
+
+    let mut checks: Vec<Option<LinkCheck>> = vec![None; total];
+    let mut pending = urls.into_iter().enumerate();
+    let mut running = JoinSet::new();
+    let mut checked = 0;
+    loop {
+        while running.len() < config.concurrency.max(1) {
+            let Some((i, url)) = pending.next() else {
+                break;
+            };
+            let check = if is_skipped(&url, &config.skip_domains) {
+                Some(result(url, LinkStatus::Skipped, None, None))
+            } else if !is_http_url(&url) {
+                let error = Some("Not an http or https URL".to_string());
+                Some(result(url, LinkStatus::Unreachable, None, error))
+            } else {
+                let probe = Arc::clone(&probe);
+                running.spawn(async move {
+                    let answer = probe.probe(&url).await;
+                    (i, url, answer)
+                });
+                None
+            };
+            if let Some(check) = check {
+                checked += 1;
+                progress(&check, checked, total);
+                checks[i] = Some(check);
+            }
+        }
+
+        let Some(joined) = running.join_next().await else {
+            break;
+        };
+        let (i, url, answer) = match joined {
+            Ok(done) => done,
+            Err(e) => std::panic::resume_unwind(e.into_panic()),
+        };
+        let check = match answer {
+            Probe::Status(code) => result(url, LinkStatus::from_http(code), Some(code), None),
+            Probe::Failed(error) => result(url, LinkStatus::Unreachable, None, Some(error)),
+        };
+        log::debug!("Checked {}: {}", check.url, check.status);
+        checked += 1;
+        progress(&check, checked, total);
+        checks[i] = Some(check);
+    }
+
+    checks.into_iter().flatten().collect()
+}
+
+fn result(url: String, status: LinkStatus, http_status: Option<u16>, error: Option<String>) -> LinkCheck {
+    LinkCheck {
+        url,
+        status,
+        http_status,
+        error,
+        checked_at: Utc::now(),
+    }
+}
+
+/// Number of checks that found a dead or unreachable link
+pub fn count_broken(checks: &[LinkCheck]) -> usize {
+    checks.iter().filter(|check| check.status.is_broken()).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    /// Answers from a table, recording the requests and how many ran at once
+    #[derive(Default)]
+    struct FakeProbe {
+        answers: HashMap<String, Probe>,
+        requests: Mutex<Vec<String>>,
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    impl LinkProbe for FakeProbe {
+        async fn probe(&self, url: &str) -> Probe {
+            self.requests.lock().unwrap().push(url.to_string());
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            self.answers
+                .get(url)
+                .cloned()
+                .unwrap_or(Probe::Status(200))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_urls() {
+        let probe = Arc::new(FakeProbe {
+            answers: HashMap::from([
+                ("https://docs.example.com/gone".to_string(), Probe::Status(404)),
+                ("https://docs.example.com/private".to_string(), Probe::Status(403)),
+                ("https://down.example.com/".to_string(), Probe::Failed("Timed out".to_string())),
+            ]),
+            ..FakeProbe::default()
+        });
+        let config = LinkCheckConfig {
+            skip_domains: vec!["intranet.corp".to_string()],
+            ..LinkCheckConfig::default()
+        };
+        let urls = [
+            "https://docs.example.com/ok",
+            "https://docs.example.com/gone",
+            "https://docs.example.com/private",
+            "https://down.example.com/",
+            "https://wiki.intranet.corp/page",
+            "PROJ-123",
+            "https://docs.example.com/ok",
+        ];
+
+        let mut seen = Vec::new();
+        let checks = check_urls(
+            Arc::clone(&probe),
+            urls.iter().map(|u| u.to_string()),
+            &config,
+            |check, checked, total| seen.push((check.url.clone(), checked, total)),
+        )
+        .await;
+
+        let statuses: Vec<_> = checks.iter().map(|c| (c.url.as_str(), c.status, c.http_status)).collect();
+        assert_eq!(
+            statuses,
+            [
+                ("https://docs.example.com/ok", LinkStatus::Ok, Some(200)),
+                ("https://docs.example.com/gone", LinkStatus::Dead, Some(404)),
+                ("https://docs.example.com/private", LinkStatus::Ok, Some(403)),
+                ("https://down.example.com/", LinkStatus::Unreachable, None),
+                ("https://wiki.intranet.corp/page", LinkStatus::Skipped, None),
+                ("PROJ-123", LinkStatus::Unreachable, None),
+            ]
+        );
+        assert_eq!(checks[3].error.as_deref(), Some("Timed out"));
+        assert_eq!(count_broken(&checks), 3);
+
+        // Skipped and invalid links aren't requested, and duplicates are requested once
+        let requests = probe.requests.lock().unwrap().clone();
+        assert_eq!(requests.len(), 4);
+        assert!(!requests.iter().any(|url| url.contains("intranet")));
+
+        assert_eq!(seen.len(), 6);
+        assert_eq!(seen.last().map(|(_, checked, total)| (*checked, *total)), Some((6, 6)));
+    }
+
+    #[tokio::test]
+    async fn test_check_urls_bounds_concurrency() {
+        let probe = Arc::new(FakeProbe::default());
+        let config = LinkCheckConfig {
+            concurrency: 3,
+            ..LinkCheckConfig::default()
+        };
+        let urls = (0..20).map(|i| format!("https://docs.example.com/{}", i));
+
+        let checks = check_urls(Arc::clone(&probe), urls, &config, |_, _, _| {}).await;
+        assert_eq!(checks.len(), 20);
+        assert_eq!(checks[7].url, "https://docs.example.com/7");
+        assert_eq!(probe.max_in_flight.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_is_skipped() {
+        let domains = vec!["corp.example.com".to_string(), ".Intranet".to_string()];
+        assert!(is_skipped("https://corp.example.com/a", &domains));
+        assert!(is_skipped("https://wiki.corp.example.com/a", &domains));
+        assert!(is_skipped("http://HOST.intranet/a", &domains));
+        assert!(!is_skipped("https://notcorp.example.com/a", &domains));
+        assert!(!is_skipped("https://example.com/corp.example.com", &domains));
+        assert!(!is_skipped("not a url", &domains));
+    }
+
+    #[tokio::test]
+    async fn test_http_probe() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("HEAD", "/doc").with_status(200).create_async().await;
+        // Servers that don't support HEAD are asked again with GET
+        server.mock("HEAD", "/no-head").with_status(405).create_async().await;
+        server.mock("GET", "/no-head").with_status(404).create_async().await;
+        server
+            .mock("HEAD", "/loop")
+            .with_status(302)
+            .with_header("location", "/loop")
+            .create_async()
+            .await;
+
+        let probe = HttpProbe::new(&LinkCheckConfig::default()).unwrap();
+        assert_eq!(probe.probe(&format!("{}/doc", server.url())).await, Probe::Status(200));
+        assert_eq!(probe.probe(&format!("{}/no-head", server.url())).await, Probe::Status(404));
+        assert_eq!(
+            probe.probe(&format!("{}/loop", server.url())).await,
+            Probe::Failed("Too many redirects".to_string())
+        );
+    }
+}
@@ -6,7 +6,8 @@
 //!
 //! Command-line interface for Project Tracker.
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
+use cli::render::OutputFormat;
 use project_tracker::{Config, Result};
 use std::path::PathBuf;
 
@@ -22,6 +23,14 @@ struct Cli {
     #[arg(short, long, global = true)]
     config: Option<PathBuf>,
 
+    /// Workspace to use instead of the configured default
+    #[arg(long, global = true)]
+    workspace: Option<String>,
+
+    /// Output format for project, people, team, snapshot, stats, query, hygiene, plan, today, forecast and report commands
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Table)]
+    output: OutputFormat,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -43,24 +52,121 @@ enum Commands {
         #[command(subcommand)]
         action: cli::TeamAction,
     },
+    /// Manage webhooks
+    Webhooks {
+        #[command(subcommand)]
+        action: cli::WebhookAction,
+    },
+    /// Draw the org chart, teams or project dependencies as Mermaid or Graphviz
+    Diagram(cli::DiagramArgs),
+    /// Generate or email the weekly digest
+    Digest {
+        #[command(subcommand)]
+        action: cli::DigestAction,
+    },
+    /// Store passwords and keys in the keychain or an encrypted file
+    Auth {
+        #[command(subcommand)]
+        action: cli::AuthAction,
+    },
+    /// Work with project stakeholders
+    Stakeholders {
+        #[command(subcommand)]
+        action: cli::StakeholderAction,
+    },
+    /// List and merge resource and stakeholder roles
+    Roles {
+        #[command(subcommand)]
+        action: cli::RoleAction,
+    },
+    /// Save and compare snapshots of a project's plan
+    Snapshots {
+        #[command(subcommand)]
+        action: cli::SnapshotAction,
+    },
+    /// Change configuration together with the data that depends on it
+    Config {
+        #[command(subcommand)]
+        action: cli::ConfigAction,
+    },
+    /// Inspect the database
+    Db {
+        #[command(subcommand)]
+        action: cli::DbAction,
+    },
+    /// Developer tools
+    Dev {
+        #[command(subcommand)]
+        action: cli::DevAction,
+    },
+    /// Print short IDs and names for scripts and fzf
+    Ids {
+        #[command(subcommand)]
+        action: cli::IdsAction,
+    },
+    /// Print a shell completion script
+    Completions {
+        /// Shell to generate completions for
+        shell: clap_complete::Shell,
+    },
+    /// Find projects with a filter expression, or manage saved queries
+    Query(cli::QueryArgs),
+    /// Show portfolio statistics
+    Stats,
+    /// List missing managers, leads and due dates
+    Hygiene(cli::HygieneArgs),
+    /// Check design docs, documents and Jira links for dead ones
+    Linkcheck(cli::LinkcheckArgs),
+    /// Show milestones due in a calendar quarter, grouped by team and project
+    Plan(cli::PlanArgs),
+    /// Show what needs attention today, most urgent first
+    Today(cli::TodayArgs),
+    /// Show when people free up from their milestones, by team
+    Forecast(cli::ForecastArgs),
     /// Generate reports
     Report {
-        /// Output format (markdown, text, json)
-        #[arg(short, long, default_value = "markdown")]
-        format: String,
+        #[command(subcommand)]
+        action: cli::ReportAction,
+    },
+    /// Export the portfolio to other formats
+    Export {
+        #[command(subcommand)]
+        action: cli::ExportAction,
     },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
+    let format = cli.output;
+
+    match run(cli).await {
+        Err(e) if format == OutputFormat::Json => {
+            cli::render::print_json_error(&e);
+            std::process::exit(1);
+        }
+        result => result,
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    let format = cli.output;
+
+    // Completions don't need configuration or a database
+    if let Commands::Completions { shell } = cli.command {
+        clap_complete::generate(shell, &mut Cli::command(), "track", &mut std::io::stdout());
+        return Ok(());
+    }
 
     // Load configuration
-    let config = if let Some(config_path) = &cli.config {
+    let mut config = if let Some(config_path) = &cli.config {
         Config::load(config_path)?
     } else {
         Config::load_or_default()?
     };
+    if let Some(workspace) = &cli.workspace {
+        config.use_workspace(workspace)?;
+    }
 
     // Initialize logging with configured level
     env_logger::Builder::from_env(
@@ -76,16 +182,85 @@ async fn main() -> Result<()> {
                 .unwrap_or_else(|_| "unknown".to_string())
             )
     );
-    log::debug!("Data directory: {}", config.data_dir);
+    log::debug!("Workspace: {}", config.workspace());
+    log::debug!("Data directory: {}", config.data_dir_path()?.display());
 
     // Ensure data directory exists
     config.ensure_data_dir()?;
 
+    // Warn about a data directory in a cloud-synced folder, and in lock mode
+    // stay out of one another machine has open. Config and auth commands
+    // don't touch the database.
+    if !matches!(cli.command, Commands::Config { .. } | Commands::Auth { .. }) {
+        let storage = project_tracker::Storage::new(config.data_dir_path()?)?;
+        if let Some(warning) = storage.check_sync_safety(&config.sync_safety)? {
+            eprintln!("Warning: {}", warning);
+        }
+    }
+
     match cli.command {
-        Commands::Projects { action } => cli::handle_projects(action, &config).await?,
-        Commands::People { action } => cli::handle_people(action, &config).await?,
-        Commands::Teams { action } => cli::handle_teams(action, &config).await?,
-        Commands::Report { format } => cli::handle_report(&format, &config).await?,
+        Commands::Projects { action } => {
+            cli::handle_projects(action, &config).await?.print(format, config.tz(), config.locale())?
+        }
+        Commands::People { action } => {
+            cli::handle_people(action, &config).await?.print(format, config.tz(), config.locale())?
+        }
+        Commands::Teams { action } => {
+            cli::handle_teams(action, &config).await?.print(format, config.tz(), config.locale())?
+        }
+        Commands::Webhooks { action } => cli::handle_webhooks(action, &config).await?,
+        Commands::Diagram(args) => cli::handle_diagram(args, &config).await?,
+        Commands::Digest { action } => cli::handle_digest(action, &config).await?,
+        Commands::Auth { action } => cli::handle_auth(action, &config).await?,
+        Commands::Stakeholders { action } => cli::handle_stakeholders(action, &config).await?,
+        Commands::Roles { action } => cli::handle_roles(action, &config).await?,
+        Commands::Snapshots { action } => {
+            cli::handle_snapshots(action, &config).await?.print(format, config.tz(), config.locale())?
+        }
+        Commands::Config { action } => {
+            let config_path = match &cli.config {
+                Some(path) => path.clone(),
+                None => Config::default_path()?,
+            };
+            cli::handle_config(action, &config, &config_path).await?
+        }
+        Commands::Db { action } => cli::handle_db(action, &config).await?,
+        Commands::Dev { action } => cli::handle_dev(action, &config).await?,
+        Commands::Ids { action } => cli::handle_ids(action, &config).await?,
+        Commands::Completions { .. } => unreachable!("handled before loading configuration"),
+        Commands::Query(mut args) => {
+            args.json |= format == OutputFormat::Json;
+            cli::handle_query(args, &config).await?
+        }
+        Commands::Stats => cli::handle_stats(format, &config).await?,
+        Commands::Hygiene(mut args) => {
+            args.json |= format == OutputFormat::Json;
+            cli::handle_hygiene(args, &config).await?
+        }
+        Commands::Linkcheck(mut args) => {
+            args.json |= format == OutputFormat::Json;
+            cli::handle_linkcheck(args, &config).await?
+        }
+        Commands::Plan(mut args) => {
+            args.json |= format == OutputFormat::Json;
+            cli::handle_plan(args, &config).await?
+        }
+        Commands::Today(mut args) => {
+            args.json |= format == OutputFormat::Json;
+            cli::handle_today(args, &config).await?
+        }
+        Commands::Forecast(mut args) => {
+            args.json |= format == OutputFormat::Json;
+            cli::handle_forecast(args, &config).await?
+        }
+        Commands::Report { mut action } => {
+            let cli::ReportAction::TeamReview { json, .. } = &mut action;
+            *json |= format == OutputFormat::Json;
+            cli::handle_report(action, &config).await?
+        }
+        Commands::Export { action } => {
+            cli::handle_export(action, &config).await?.print(format, config.tz(), config.locale())?
+        }
     }
 
     Ok(())
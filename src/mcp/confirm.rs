@@ -0,0 +1,193 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Confirmation tokens for destructive tool calls
+//!
+//! When `mcp_require_confirmation` is on, a delete tool called without a
+//! token doesn't delete anything. It describes what would go and hands out
+//! a token; calling the tool again with the same arguments and that token
+//! performs the delete. Tokens are single use, expire after
+//! [`CONFIRMATION_TTL`] and only live in memory.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// How long a confirmation token stays valid
+pub const CONFIRMATION_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Why a token couldn't be redeemed
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ConfirmError {
+    #[error("Unknown confirmation token; call the tool without one to get a new token")]
+    Unknown,
+
+    #[error("Confirmation token expired; call the tool without one to get a new token")]
+    Expired,
+
+    #[error("Confirmation token was issued for a different call")]
+    Mismatch,
+}
+
+struct Pending {
+    action: String,
+    expires: Instant,
+}
+
+/// Tokens handed out and not yet redeemed
+pub struct Confirmations {
+    ttl: Duration,
+    pending: Mutex<HashMap<String, Pending>>,
+}
+
+impl Confirmations {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// How long tokens stay valid
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Issue a token for `action`, a description of the exact call, such as
+    /// `delete_project:<id>`
+    pub fn issue(&self, action: &str) -> String {
+        self.issue_at(action, Instant::now())
+    }
+
+    fn issue_at(&self, action: &str, now: Instant) -> String {
+        let token = Uuid::new_v4().to_string();
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        // Expired tokens can never be redeemed, so drop them as we go
+        pending.retain(|_, p| p.expires > now);
+        pending.insert(
+            token.clone(),
+            Pending {
+                action: action.to_string(),
+                expires: now + self.ttl,
+            },
+        );
+        token
+    }
+
+    /// Use up `token`, which must have been issued for `action` and not
+    /// have expired
+    pub fn redeem(&self, token: &str, action: &str) -> Result<(), ConfirmError> {
+        self.redeem_at(token, action, Instant::now())
+    }
+
+    fn redeem_at(&self, token: &str, action: &str, now: Instant) -> Result<(), ConfirmError> {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = pending.get(token).ok_or(ConfirmError::Unknown)?;
+        if entry.action != action {
+            // Keep the token: it's still good for the call it was issued for
+            return Err(ConfirmError::Mismatch);
+        }
+        let expires = entry.expires;
+        pending.remove(token);
+        if now >= expires {
+            return Err(ConfirmError::Expired);
+        }
+        Ok(())
+    }
+}
+
+impl Default for Confirmations {
+    fn default() -> Self {
+        Self::new(CONFIRMATION_TTL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redeem_once() {
+        let confirmations = Confirmations::default();
+        let token = confirmations.issue("delete_project:1");
+        assert_eq!(confirmations.redeem(&token, "delete_project:1"), Ok(()));
+        assert_eq!(
+            confirmations.redeem(&token, "delete_project:1"),
+            Err(ConfirmError::Unknown)
+        );
+    }
+
+    #[test]
+    fn test_redeem_wrong_token() {
+        let confirmations = Confirmations::default();
+        let token = confirmations.issue("delete_project:1");
+        assert_eq!(
+            confirmations.redeem("not-a-token", "delete_project:1"),
+            Err(ConfirmError::Unknown)
+        );
+        assert_eq!(
+            confirmations.redeem(&token, "delete_project:2"),
+            Err(ConfirmError::Mismatch)
+        );
+        assert_eq!(confirmations.redeem(&token, "delete_project:1"), Ok(()));
+    }
+
+    #[test]
+    fn test_redeem_expired() {
+        let confirmations = Confirmations::new(Duration::from_secs(60));
+        let start = Instant::now();
+        let token = confirmations.issue_at("delete_team:Platform", start);
+        assert_eq!(
+            confirmations.redeem_at(
+                &token,
+                "delete_team:Platform",
+                start + Duration::from_secs(60)
+            ),
+            Err(ConfirmError::Expired)
+        );
+
+        let token = confirmations.issue_at("delete_team:Platform", start);
+        assert_eq!(
+            confirmations.redeem_at(
+                &token,
+                "delete_team:Platform",
+                start + Duration::from_secs(59)
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_issue_drops_expired_tokens() {
+        let confirmations = Confirmations::new(Duration::from_secs(60));
+        let start = Instant::now();
+        confirmations.issue_at("delete_person:a@example.com", start);
+        confirmations.issue_at(
+            "delete_person:b@example.com",
+            start + Duration::from_secs(120),
+        );
+        assert_eq!(confirmations.pending.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_poisoned_tokens_still_work() {
+        let confirmations = Confirmations::new(Duration::from_secs(60));
+        let token = confirmations.issue("delete_team:Platform");
+
+        // A call that panicked while holding the tokens doesn't break confirmations
+        std::thread::scope(|scope| {
+            let _ = scope
+                .spawn(|| {
+                    let _pending = confirmations.pending.lock().unwrap();
+                    panic!("tool call failed");
+                })
+                .join();
+        });
+        assert!(confirmations.pending.is_poisoned());
+        assert_eq!(confirmations.redeem(&token, "delete_team:Platform"), Ok(()));
+        let token = confirmations.issue("delete_team:Platform");
+        assert_eq!(confirmations.redeem(&token, "delete_team:Platform"), Ok(()));
+    }
+}
@@ -0,0 +1,303 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Short handles for records in tool results
+//!
+//! Models copying a UUID from one tool call to the next sometimes change a
+//! character or two. List and get tools therefore give each project,
+//! milestone and note they return a handle such as `P1`, `M3` or `N7`, and
+//! any ID argument accepts a handle instead of the UUID. Handles are numbered
+//! per kind in the order records are first returned, stay the same for the
+//! rest of the session, and belong to that session alone: another session
+//! has its own numbering and rejects them. They only live in memory and are
+//! replaced by their UUIDs before a tool runs, so they are never stored.
+
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// The kind of record a handle stands for, which is its letter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HandleKind {
+    Project,
+    Milestone,
+    Note,
+}
+
+impl HandleKind {
+    const ALL: [HandleKind; 3] = [HandleKind::Project, HandleKind::Milestone, HandleKind::Note];
+
+    /// Letter that starts the kind's handles
+    pub fn letter(&self) -> char {
+        match self {
+            HandleKind::Project => 'P',
+            HandleKind::Milestone => 'M',
+            HandleKind::Note => 'N',
+        }
+    }
+
+    fn from_letter(letter: char) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.letter() == letter)
+    }
+
+    /// The kind an argument's handle must be, from the argument's name, such
+    /// as projects for `depends_on_project_id`; `None` when any kind will do
+    fn for_argument(name: &str) -> Option<Self> {
+        if name.ends_with("project_id") || name.ends_with("project_ids") {
+            Some(HandleKind::Project)
+        } else if name.ends_with("milestone_id") || name.ends_with("milestone_ids") {
+            Some(HandleKind::Milestone)
+        } else if name.ends_with("note_id") || name.ends_with("note_ids") {
+            Some(HandleKind::Note)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for HandleKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            HandleKind::Project => "project",
+            HandleKind::Milestone => "milestone",
+            HandleKind::Note => "note",
+        })
+    }
+}
+
+/// Why a handle in the arguments couldn't be replaced
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum HandleError {
+    #[error("Unknown handle {0}: handles only work in the session that listed them; list the records again or use the full ID")]
+    Unknown(String),
+
+    #[error("Handle {handle} in {argument} is a {found}, not a {expected}")]
+    WrongKind {
+        handle: String,
+        argument: String,
+        found: HandleKind,
+        expected: HandleKind,
+    },
+}
+
+/// Parse a handle such as `P12`; anything else, including slugs, is `None`
+fn parse_handle(value: &str) -> Option<(HandleKind, u32)> {
+    let mut chars = value.chars();
+    let kind = HandleKind::from_letter(chars.next()?)?;
+    let digits = chars.as_str();
+    if digits.is_empty() || digits.starts_with('0') || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some((kind, digits.parse().ok()?))
+}
+
+/// Whether an argument holds record IDs: `id` and names ending in `_id` or `_ids`
+fn is_id_argument(name: &str) -> bool {
+    name == "id" || name.ends_with("_id") || name.ends_with("_ids")
+}
+
+#[derive(Default)]
+struct HandleTable {
+    by_id: HashMap<(HandleKind, Uuid), u32>,
+    by_handle: HashMap<(HandleKind, u32), Uuid>,
+    next: HashMap<HandleKind, u32>,
+}
+
+/// The handles handed out in one session
+#[derive(Default)]
+pub struct Handles {
+    table: Mutex<HandleTable>,
+}
+
+impl Handles {
+    /// The handle for a record, handing out the next one the first time
+    pub fn handle(&self, kind: HandleKind, id: Uuid) -> String {
+        let mut table = self.table.lock().unwrap_or_else(|e| e.into_inner());
+        let number = match table.by_id.get(&(kind, id)) {
+            Some(number) => *number,
+            None => {
+                let next = table.next.entry(kind).or_insert(0);
+                *next += 1;
+                let number = *next;
+                table.by_id.insert((kind, id), number);
+                table.by_handle.insert((kind, number), id);
+                number
+            }
+        };
+        format!("{}{}", kind.letter(), number)
+    }
+
+    /// The record a handle stands for, if this session handed it out
+    pub fn resolve(&self, handle: &str) -> Option<(HandleKind, Uuid)> {
+        let (kind, number) = parse_handle(handle)?;
+        let table = self.table.lock().unwrap_or_else(|e| e.into_inner());
+        table.by_handle.get(&(kind, number)).map(|id| (kind, *id))
+    }
+
+    /// Give each record in a tool result a `handle` field and return the
+    /// legend lines, such as `P1 = <id> Apollo`
+    ///
+    /// `value` is a record with an `id`, a list of them, or an object with
+    /// lists of them, such as a page of notes.
+    pub fn annotate(&self, kind: HandleKind, value: &mut Value) -> Vec<String> {
+        let mut legend = Vec::new();
+        match value {
+            Value::Array(records) => {
+                for record in records {
+                    if let Value::Object(record) = record {
+                        self.annotate_record(kind, record, &mut legend);
+                    }
+                }
+            }
+            Value::Object(object) if object.contains_key("id") => self.annotate_record(kind, object, &mut legend),
+            Value::Object(object) => {
+                for records in object.values_mut().filter(|v| v.is_array()) {
+                    legend.extend(self.annotate(kind, records));
+                }
+            }
+            _ => {}
+        }
+        legend
+    }
+
+    fn annotate_record(&self, kind: HandleKind, record: &mut Map<String, Value>, legend: &mut Vec<String>) {
+        let Some(id) = record.get("id").and_then(Value::as_str).and_then(|id| Uuid::parse_str(id).ok()) else {
+            return;
+        };
+        let handle = self.handle(kind, id);
+        let label = ["name", "title"]
+            .iter()
+            .find_map(|field| record.get(*field).and_then(Value::as_str));
+        legend.push(match label {
+            Some(label) => format!("{} = {} {}", handle, id, label),
+            None => format!("{} = {}", handle, id),
+        });
+        record.insert("handle".to_string(), Value::String(handle));
+    }
+
+    /// Replace the handles in a tool call's ID arguments with their UUIDs
+    ///
+    /// Looks through nested objects and lists, so note targets and batches
+    /// are covered. Values that aren't handles, such as UUIDs and slugs, are
+    /// left alone.
+    pub fn resolve_arguments(&self, arguments: &mut Map<String, Value>) -> Result<(), HandleError> {
+        for (name, value) in arguments.iter_mut() {
+            self.resolve_value(name, value)?;
+        }
+        Ok(())
+    }
+
+    fn resolve_value(&self, name: &str, value: &mut Value) -> Result<(), HandleError> {
+        match value {
+            Value::String(text) if is_id_argument(name) && parse_handle(text).is_some() => {
+                let (found, id) = self.resolve(text).ok_or_else(|| HandleError::Unknown(text.clone()))?;
+                if let Some(expected) = HandleKind::for_argument(name).filter(|expected| *expected != found) {
+                    return Err(HandleError::WrongKind {
+                        handle: text.clone(),
+                        argument: name.to_string(),
+                        found,
+                        expected,
+                    });
+                }
+                *text = id.to_string();
+            }
+            Value::Array(values) => {
+                for value in values {
+                    self.resolve_value(name, value)?;
+                }
+            }
+            Value::Object(object) => self.resolve_arguments(object)?,
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_handles_are_stable_per_kind() {
+        let handles = Handles::default();
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        assert_eq!(handles.handle(HandleKind::Project, a), "P1");
+        assert_eq!(handles.handle(HandleKind::Project, b), "P2");
+        assert_eq!(handles.handle(HandleKind::Project, a), "P1");
+        assert_eq!(handles.handle(HandleKind::Milestone, a), "M1");
+        assert_eq!(handles.resolve("P2"), Some((HandleKind::Project, b)));
+        assert_eq!(handles.resolve("P3"), None);
+        assert_eq!(handles.resolve("p1"), None);
+        assert_eq!(handles.resolve("P01"), None);
+    }
+
+    #[test]
+    fn test_poisoned_table_still_works() {
+        let handles = Handles::default();
+        let id = Uuid::new_v4();
+        assert_eq!(handles.handle(HandleKind::Project, id), "P1");
+
+        // A call that panicked while holding the table doesn't break the session
+        std::thread::scope(|scope| {
+            let _ = scope
+                .spawn(|| {
+                    let _table = handles.table.lock().unwrap();
+                    panic!("tool call failed");
+                })
+                .join();
+        });
+        assert!(handles.table.is_poisoned());
+        assert_eq!(handles.resolve("P1"), Some((HandleKind::Project, id)));
+        assert_eq!(handles.handle(HandleKind::Project, Uuid::new_v4()), "P2");
+    }
+
+    #[test]
+    fn test_annotate_and_resolve_arguments() {
+        let handles = Handles::default();
+        let (apollo, gemini, note) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut projects = json!([{"id": apollo, "name": "Apollo"}, {"id": gemini, "name": "Gemini"}]);
+        let legend = handles.annotate(HandleKind::Project, &mut projects);
+        assert_eq!(projects[1]["handle"], "P2");
+        assert_eq!(legend, [format!("P1 = {} Apollo", apollo), format!("P2 = {} Gemini", gemini)]);
+
+        let mut page = json!({"notes": [{"id": note, "title": "Status"}], "next_cursor": null});
+        handles.annotate(HandleKind::Note, &mut page);
+        assert_eq!(page["notes"][0]["handle"], "N1");
+
+        let mut arguments = json!({
+            "project_id": "P2",
+            "target": {"project_id": "P1"},
+            "note_ids": ["N1"],
+            "name": "P1",
+            "slug": "p1",
+            "id": apollo,
+        });
+        handles.resolve_arguments(arguments.as_object_mut().unwrap()).unwrap();
+        assert_eq!(
+            arguments,
+            json!({
+                "project_id": gemini,
+                "target": {"project_id": apollo},
+                "note_ids": [note],
+                "name": "P1",
+                "slug": "p1",
+                "id": apollo,
+            })
+        );
+
+        let mut unknown = json!({"id": "P9"});
+        assert_eq!(
+            handles.resolve_arguments(unknown.as_object_mut().unwrap()),
+            Err(HandleError::Unknown("P9".to_string()))
+        );
+        let mut wrong = json!({"milestone_id": "P1"});
+        assert!(matches!(
+            handles.resolve_arguments(wrong.as_object_mut().unwrap()),
+            Err(HandleError::WrongKind { expected: HandleKind::Milestone, .. })
+        ));
+    }
+}
@@ -0,0 +1,199 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Health and metrics endpoints for the MCP HTTP server
+//!
+//! These are served next to the SSE transport so the server can be
+//! monitored: `GET /healthz` reports whether the database answers, and
+//! `GET /metrics` exposes tool call metrics unless disabled in the config.
+//! The read-only REST API from [`super::rest`] is served alongside them.
+
+use super::server::ProjectTrackerServer;
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use serde::Serialize;
+
+/// Body of the `/healthz` response
+#[derive(Debug, Clone, Serialize)]
+pub struct Health {
+    /// `ok`, or `degraded` when the database doesn't answer
+    pub status: &'static str,
+    pub db_ok: bool,
+    /// Schema version of the database, if it could be read
+    pub schema_version: Option<i32>,
+    pub uptime_seconds: u64,
+}
+
+/// Routes for `/healthz`, the REST API and, when enabled, `/metrics`
+pub fn routes(server: ProjectTrackerServer) -> Router {
+    let mut router = Router::new().route("/healthz", get(healthz));
+    if server.metrics_enabled() {
+        router = router.route("/metrics", get(metrics));
+    }
+    router
+        .with_state(server.clone())
+        .merge(super::rest::routes(server))
+}
+
+async fn healthz(State(server): State<ProjectTrackerServer>) -> impl IntoResponse {
+    let health = server.health().await;
+    let status = if health.db_ok {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(health))
+}
+
+async fn metrics(State(server): State<ProjectTrackerServer>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        server.metrics().render(),
+    )
+}
+
+#[cfg(test)]
+pub(super) mod testing {
+    use super::*;
+    use crate::{db, Config};
+    use rusqlite::Connection;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    pub fn test_server(config: Config) -> ProjectTrackerServer {
+        let conn = Connection::open_in_memory().unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        ProjectTrackerServer::new(config, conn)
+    }
+
+    /// Serve the routes for `server` on a local port and return its address
+    pub async fn spawn_http(server: ProjectTrackerServer) -> std::net::SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, routes(server)).await });
+        addr
+    }
+
+    /// Send a GET request and return the status code and body
+    pub async fn get(addr: std::net::SocketAddr, path: &str) -> (u16, String) {
+        request(addr, "GET", path, &[], None).await
+    }
+
+    /// Send a request with extra headers and an optional JSON body, and
+    /// return the status code and body
+    pub async fn request(
+        addr: std::net::SocketAddr,
+        method: &str,
+        path: &str,
+        headers: &[(&str, &str)],
+        body: Option<&str>,
+    ) -> (u16, String) {
+        let response = exchange(addr, method, path, headers, body).await;
+        let status = response[9..12].parse().unwrap();
+        let body = response
+            .split_once("\r\n\r\n")
+            .map(|(_, body)| body.to_string())
+            .unwrap_or_default();
+        (status, body)
+    }
+
+    /// Send a request and return the whole response, headers included
+    pub async fn exchange(
+        addr: std::net::SocketAddr,
+        method: &str,
+        path: &str,
+        headers: &[(&str, &str)],
+        body: Option<&str>,
+    ) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let mut request = format!(
+            "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n",
+            method, path
+        );
+        for (name, value) in headers {
+            request.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        if let Some(body) = body {
+            request.push_str(&format!(
+                "Content-Type: application/json\r\nContent-Length: {}\r\n",
+                body.len()
+            ));
+        }
+        request.push_str("\r\n");
+        request.push_str(body.unwrap_or_default());
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::{get, spawn_http, test_server};
+    use crate::{db, Config};
+    use rmcp::{model::CallToolRequestParam, ServiceExt};
+
+    #[tokio::test]
+    async fn test_healthz() {
+        let addr = spawn_http(test_server(Config::default())).await;
+
+        let (status, body) = get(addr, "/healthz").await;
+        assert_eq!(status, 200);
+        let health: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(health["status"], "ok");
+        assert_eq!(health["db_ok"], true);
+        assert_eq!(
+            health["schema_version"],
+            db::schema::SUPPORTED_SCHEMA_VERSION
+        );
+        assert!(health["uptime_seconds"].is_u64());
+    }
+
+    #[tokio::test]
+    async fn test_metrics_count_tool_calls() {
+        let server = test_server(Config::default());
+        let addr = spawn_http(server.clone()).await;
+
+        let (status, body) = get(addr, "/metrics").await;
+        assert_eq!(status, 200);
+        assert!(!body.contains("track_mcp_tool_calls_total{tool=\"list_projects\"}"));
+
+        let (server_transport, client_transport) = tokio::io::duplex(4096);
+        tokio::spawn(async move {
+            let service = server.serve(server_transport).await?;
+            service.waiting().await?;
+            anyhow::Ok(())
+        });
+        let client = ().serve(client_transport).await.unwrap();
+        client
+            .call_tool(CallToolRequestParam {
+                name: "list_projects".into(),
+                arguments: None,
+            })
+            .await
+            .unwrap();
+
+        let (_, body) = get(addr, "/metrics").await;
+        assert!(body.contains("track_mcp_tool_calls_total{tool=\"list_projects\"} 1\n"));
+        assert!(body.contains("track_mcp_tool_errors_total{tool=\"list_projects\"} 0\n"));
+        assert!(body.contains("track_mcp_tool_duration_seconds_count{tool=\"list_projects\"} 1\n"));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_can_be_disabled() {
+        let mut config = Config::default();
+        config.mcp_metrics_enabled = false;
+        let addr = spawn_http(test_server(config)).await;
+
+        assert_eq!(get(addr, "/metrics").await.0, 404);
+        assert_eq!(get(addr, "/healthz").await.0, 200);
+    }
+}
@@ -0,0 +1,370 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Idempotency keys for tools that change data
+//!
+//! Every tool that isn't a read accepts an optional `idempotency_key`. The
+//! key is taken out of the arguments before the tool sees them, and the
+//! call's result is recorded under it (see [`OperationRepository`]) in the
+//! same transaction as the tool's changes. Calling again with the same key
+//! and arguments returns the recorded result without running the tool, so
+//! an assistant can retry a call it lost the answer to. Reusing a key for a
+//! different call is refused. Keys are forgotten after
+//! `mcp_idempotency_ttl_hours`.
+
+use super::writer::WriteContext;
+use crate::db::{self, McpOperation, OperationRepository};
+use chrono::{Duration, Utc};
+use rmcp::model::{CallToolResult, JsonObject, Tool};
+use rmcp::ErrorData as McpError;
+use rusqlite::Connection;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Arc;
+
+/// Name of the argument carrying the key
+pub const IDEMPOTENCY_KEY: &str = "idempotency_key";
+
+tokio::task_local! {
+    /// The keyed call a tool is running for, until its write takes it
+    static CURRENT: RefCell<Option<Operation>>;
+}
+
+/// A tool call made with an idempotency key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Operation {
+    pub key: String,
+    pub tool: String,
+    pub request_hash: String,
+}
+
+impl Operation {
+    /// Take the idempotency key out of a call's arguments
+    ///
+    /// Returns `None` when the call has no key. The hash covers the
+    /// arguments that are left.
+    pub fn from_arguments(tool: &str, arguments: Option<&mut JsonObject>) -> Result<Option<Self>, McpError> {
+        let Some(arguments) = arguments else {
+            return Ok(None);
+        };
+        let key = match arguments.remove(IDEMPOTENCY_KEY) {
+            None | Some(Value::Null) => return Ok(None),
+            Some(Value::String(key)) if !key.trim().is_empty() => key.trim().to_string(),
+            Some(_) => {
+                return Err(McpError::invalid_params(
+                    "idempotency_key must be a non-empty string",
+                    None,
+                ))
+            }
+        };
+        Ok(Some(Self {
+            key,
+            tool: tool.to_string(),
+            request_hash: request_hash(tool, arguments),
+        }))
+    }
+
+    /// Run `future`, a call of the tool, on behalf of this operation
+    ///
+    /// The tool's write picks the operation up with [`Operation::take`] and
+    /// records its result with [`Operation::run`]. A call that never writes
+    /// changes nothing, so nothing is recorded for it.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT.scope(RefCell::new(Some(self)), future).await
+    }
+
+    /// The operation the current tool call is running for, if it hasn't
+    /// been taken yet
+    ///
+    /// Only the first write of a call is journaled.
+    pub fn take() -> Option<Self> {
+        CURRENT.try_with(|current| current.borrow_mut().take()).ok().flatten()
+    }
+
+    /// The result recorded for an earlier call with this key
+    ///
+    /// Keys older than `ttl_hours` are forgotten first.
+    pub fn replay(&self, conn: &Connection, ttl_hours: u32) -> Result<Option<CallToolResult>, McpError> {
+        let repo = OperationRepository::new(conn);
+        repo.purge_before(Utc::now() - Duration::hours(i64::from(ttl_hours)))
+            .map_err(|e| journal_error(self, e))?;
+        let Some(response) = repo
+            .replay(&self.key, &self.tool, &self.request_hash)
+            .map_err(|e| journal_error(self, e))?
+        else {
+            return Ok(None);
+        };
+        serde_json::from_str(&response).map(Some).map_err(|e| {
+            McpError::internal_error(
+                "Failed to read the recorded result",
+                Some(serde_json::json!({"error": e.to_string(), "idempotency_key": self.key})),
+            )
+        })
+    }
+
+    /// Run `job` and record its result, in one transaction
+    ///
+    /// A result already recorded for the key is returned instead of running
+    /// `job`. Calls that fail or return an error result aren't recorded, so a
+    /// retry runs them again, and their changes are rolled back. Webhook
+    /// events `job` emits through `context` are held until the transaction
+    /// commits, and dropped if it doesn't.
+    pub fn run<F>(self, conn: &Connection, context: &WriteContext, job: F) -> Result<CallToolResult, McpError>
+    where
+        F: FnOnce(&Connection, &WriteContext) -> Result<CallToolResult, McpError>,
+    {
+        let tx = db::begin_or_join(conn).map_err(|e| journal_error(&self, e.into()))?;
+        if let Some(result) = self.replay(conn, context.config.mcp_idempotency_ttl_hours)? {
+            return Ok(result);
+        }
+
+        let (webhooks, events) = context.webhooks.hold();
+        let held = WriteContext {
+            config: context.config.clone(),
+            webhooks,
+        };
+        let result = job(conn, &held)?;
+        if result.is_error == Some(true) {
+            return Ok(result);
+        }
+        self.record(conn, &result)?;
+        if let Some(tx) = tx {
+            tx.commit().map_err(|e| journal_error(&self, e.into()))?;
+        }
+        events.release();
+        Ok(result)
+    }
+
+    /// Record a call's result under the key
+    fn record(&self, conn: &Connection, result: &CallToolResult) -> Result<(), McpError> {
+        let response = serde_json::to_string(result).map_err(|e| {
+            McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()})))
+        })?;
+        OperationRepository::new(conn)
+            .record(&McpOperation {
+                idempotency_key: self.key.clone(),
+                tool: self.tool.clone(),
+                request_hash: self.request_hash.clone(),
+                response,
+                created_at: Utc::now(),
+            })
+            .map_err(|e| journal_error(self, e))
+    }
+}
+
+/// Add the `idempotency_key` argument to a tool's input schema
+pub fn add_parameter(tool: &mut Tool) {
+    let mut schema = (*tool.input_schema).clone();
+    let properties = schema
+        .entry("properties")
+        .or_insert_with(|| Value::Object(Default::default()));
+    if let Value::Object(properties) = properties {
+        properties.insert(
+            IDEMPOTENCY_KEY.to_string(),
+            serde_json::json!({
+                "type": "string",
+                "description": "Key for retrying this call safely: a retry with the same key and arguments returns the first call's result instead of running again"
+            }),
+        );
+    }
+    tool.input_schema = Arc::new(schema);
+}
+
+/// SHA-256 of a tool name and its arguments, as hex
+///
+/// Object keys are sorted first, so the order the client sent them in
+/// doesn't matter.
+fn request_hash(tool: &str, arguments: &JsonObject) -> String {
+    let mut canonical = String::new();
+    write_canonical(&Value::Object(arguments.clone()), &mut canonical);
+    let mut hasher = Sha256::new();
+    hasher.update(tool.as_bytes());
+    hasher.update([0]);
+    hasher.update(canonical.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            out.push('{');
+            let sorted: BTreeMap<&String, &Value> = map.iter().collect();
+            for (i, (key, value)) in sorted.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(value, out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+/// Describe a journal error, naming the key when it was reused
+fn journal_error(operation: &Operation, e: db::Error) -> McpError {
+    match e {
+        db::Error::Conflict(message) => McpError::invalid_params(
+            message,
+            Some(serde_json::json!({
+                "error": "idempotency_conflict",
+                "idempotency_key": operation.key,
+                "tool": operation.tool,
+            })),
+        ),
+        e => McpError::internal_error(
+            "Failed to use the operation journal",
+            Some(serde_json::json!({"error": e.to_string()})),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::db::Project;
+    use crate::service::ProjectService;
+    use crate::webhook::{self, WebhookDispatcher};
+    use rmcp::model::Content;
+
+    fn arguments(value: serde_json::Value) -> JsonObject {
+        value.as_object().cloned().unwrap()
+    }
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn operation(key: &str) -> Operation {
+        Operation {
+            key: key.to_string(),
+            tool: "create_project".to_string(),
+            request_hash: "hash".to_string(),
+        }
+    }
+
+    fn project_count(conn: &Connection) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM projects", [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn test_run_sends_events_after_commit() {
+        let conn = setup_test_db();
+        let (webhooks, mut receiver) = WebhookDispatcher::capture();
+        let context = WriteContext {
+            config: Arc::new(Config::default()),
+            webhooks,
+        };
+
+        let result = operation("k1")
+            .run(&conn, &context, |conn, context| {
+                ProjectService::new(conn, &context.webhooks)
+                    .create_project(&Project::new("Apollo".to_string()))
+                    .unwrap();
+                // Held until the call is recorded and committed
+                assert!(receiver.try_recv().is_err());
+                Ok(CallToolResult::success(vec![Content::text("Created")]))
+            })
+            .unwrap();
+        assert_ne!(result.is_error, Some(true));
+        assert_eq!(receiver.try_recv().unwrap().event, webhook::PROJECT_CREATED);
+
+        // A retry replays the result without running the job or sending anything
+        operation("k1")
+            .run(&conn, &context, |_, _| panic!("the job ran again"))
+            .unwrap();
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(project_count(&conn), 1);
+    }
+
+    #[test]
+    fn test_failed_record_sends_no_events() {
+        let conn = setup_test_db();
+        let (webhooks, mut receiver) = WebhookDispatcher::capture();
+        let context = WriteContext {
+            config: Arc::new(Config::default()),
+            webhooks,
+        };
+
+        let result = operation("k1").run(&conn, &context, |conn, context| {
+            ProjectService::new(conn, &context.webhooks)
+                .create_project(&Project::new("Apollo".to_string()))
+                .unwrap();
+            // Recording the result fails without the journal table
+            conn.execute("DROP TABLE mcp_operations", []).unwrap();
+            Ok(CallToolResult::success(vec![Content::text("Created")]))
+        });
+        assert!(result.is_err());
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(project_count(&conn), 0);
+    }
+
+    #[test]
+    fn test_error_result_is_rolled_back() {
+        let conn = setup_test_db();
+        let (webhooks, mut receiver) = WebhookDispatcher::capture();
+        let context = WriteContext {
+            config: Arc::new(Config::default()),
+            webhooks,
+        };
+
+        let result = operation("k1")
+            .run(&conn, &context, |conn, context| {
+                ProjectService::new(conn, &context.webhooks)
+                    .create_project(&Project::new("Apollo".to_string()))
+                    .unwrap();
+                Ok(CallToolResult::error(vec![Content::text("Refused")]))
+            })
+            .unwrap();
+        assert_eq!(result.is_error, Some(true));
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(project_count(&conn), 0);
+        let replayed = operation("k1").replay(&conn, 24).unwrap();
+        assert!(replayed.is_none());
+    }
+
+    #[test]
+    fn test_from_arguments() {
+        let mut args = arguments(serde_json::json!({"name": "Apollo", "idempotency_key": " k1 "}));
+        let operation = Operation::from_arguments("create_project", Some(&mut args)).unwrap().unwrap();
+        assert_eq!(operation.key, "k1");
+        assert!(!args.contains_key(IDEMPOTENCY_KEY));
+
+        // Argument order doesn't change the hash, but the tool and values do
+        let mut reordered = arguments(serde_json::json!({"idempotency_key": "k1", "name": "Apollo"}));
+        let same = Operation::from_arguments("create_project", Some(&mut reordered)).unwrap().unwrap();
+        assert_eq!(same.request_hash, operation.request_hash);
+        let mut other = arguments(serde_json::json!({"name": "Gemini", "idempotency_key": "k1"}));
+        let other = Operation::from_arguments("create_project", Some(&mut other)).unwrap().unwrap();
+        assert_ne!(other.request_hash, operation.request_hash);
+        let mut args = arguments(serde_json::json!({"name": "Apollo", "idempotency_key": "k1"}));
+        let other_tool = Operation::from_arguments("create_team", Some(&mut args)).unwrap().unwrap();
+        assert_ne!(other_tool.request_hash, operation.request_hash);
+
+        let mut args = arguments(serde_json::json!({"name": "Apollo"}));
+        assert_eq!(Operation::from_arguments("create_project", Some(&mut args)).unwrap(), None);
+        assert_eq!(Operation::from_arguments("create_project", None).unwrap(), None);
+        let mut args = arguments(serde_json::json!({"idempotency_key": 7}));
+        assert!(Operation::from_arguments("create_project", Some(&mut args)).is_err());
+    }
+}
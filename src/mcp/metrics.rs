@@ -0,0 +1,173 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Tool call metrics for the MCP server
+//!
+//! Every tool call is counted by tool name, along with failed calls and a
+//! latency histogram. The totals are rendered in the Prometheus text format
+//! for the `/metrics` endpoint of the HTTP server.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Upper bounds of the latency histogram buckets, in seconds
+pub const LATENCY_BUCKETS: [f64; 10] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0];
+
+/// Totals for one tool
+#[derive(Default)]
+struct ToolStats {
+    calls: u64,
+    errors: u64,
+    /// Calls in each latency bucket; the last one counts calls slower than every bound
+    buckets: [u64; LATENCY_BUCKETS.len() + 1],
+    total_seconds: f64,
+}
+
+/// Counters and latency histograms for tool calls, shared by every session
+pub struct Metrics {
+    started_at: Instant,
+    tools: Mutex<BTreeMap<String, ToolStats>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            tools: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Time since the server started
+    pub fn uptime(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+
+    /// Record a finished tool call
+    pub fn record_tool_call(&self, tool: &str, elapsed: Duration, failed: bool) {
+        let seconds = elapsed.as_secs_f64();
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|&bound| seconds <= bound)
+            .unwrap_or(LATENCY_BUCKETS.len());
+
+        let mut tools = self.tools.lock().unwrap_or_else(|e| e.into_inner());
+        let stats = tools.entry(tool.to_string()).or_default();
+        stats.calls += 1;
+        if failed {
+            stats.errors += 1;
+        }
+        stats.buckets[bucket] += 1;
+        stats.total_seconds += seconds;
+    }
+
+    /// Number of calls recorded for `tool`
+    pub fn tool_calls(&self, tool: &str) -> u64 {
+        let tools = self.tools.lock().unwrap_or_else(|e| e.into_inner());
+        tools.get(tool).map_or(0, |stats| stats.calls)
+    }
+
+    /// Render every metric in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let tools = self.tools.lock().unwrap_or_else(|e| e.into_inner());
+        // Writing to a String can't fail
+        let mut out = String::new();
+
+        out.push_str("# HELP track_mcp_uptime_seconds Seconds since the MCP server started\n");
+        out.push_str("# TYPE track_mcp_uptime_seconds gauge\n");
+        let _ = writeln!(out, "track_mcp_uptime_seconds {}", self.uptime().as_secs());
+
+        out.push_str("# HELP track_mcp_tool_calls_total Tool calls by tool name\n");
+        out.push_str("# TYPE track_mcp_tool_calls_total counter\n");
+        for (tool, stats) in tools.iter() {
+            let _ = writeln!(
+                out,
+                "track_mcp_tool_calls_total{{tool=\"{}\"}} {}",
+                tool, stats.calls
+            );
+        }
+
+        out.push_str("# HELP track_mcp_tool_errors_total Tool calls that failed, by tool name\n");
+        out.push_str("# TYPE track_mcp_tool_errors_total counter\n");
+        for (tool, stats) in tools.iter() {
+            let _ = writeln!(
+                out,
+                "track_mcp_tool_errors_total{{tool=\"{}\"}} {}",
+                tool, stats.errors
+            );
+        }
+
+        out.push_str("# HELP track_mcp_tool_duration_seconds Tool call latency by tool name\n");
+        out.push_str("# TYPE track_mcp_tool_duration_seconds histogram\n");
+        for (tool, stats) in tools.iter() {
+            let mut cumulative = 0;
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(&stats.buckets) {
+                cumulative += count;
+                let _ = writeln!(
+                    out,
+                    "track_mcp_tool_duration_seconds_bucket{{tool=\"{}\",le=\"{}\"}} {}",
+                    tool, bound, cumulative
+                );
+            }
+            let _ = writeln!(
+                out,
+                "track_mcp_tool_duration_seconds_bucket{{tool=\"{}\",le=\"+Inf\"}} {}",
+                tool, stats.calls
+            );
+            let _ = writeln!(
+                out,
+                "track_mcp_tool_duration_seconds_sum{{tool=\"{}\"}} {}",
+                tool, stats.total_seconds
+            );
+            let _ = writeln!(
+                out,
+                "track_mcp_tool_duration_seconds_count{{tool=\"{}\"}} {}",
+                tool, stats.calls
+            );
+        }
+
+        out
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_metrics() {
+        let metrics = Metrics::new();
+        metrics.record_tool_call("list_projects", Duration::from_millis(2), false);
+        metrics.record_tool_call("list_projects", Duration::from_millis(30), true);
+        metrics.record_tool_call("get_project", Duration::from_secs(10), false);
+
+        assert_eq!(metrics.tool_calls("list_projects"), 2);
+        assert_eq!(metrics.tool_calls("delete_project"), 0);
+
+        let text = metrics.render();
+        assert!(text.contains("track_mcp_tool_calls_total{tool=\"list_projects\"} 2\n"));
+        assert!(text.contains("track_mcp_tool_errors_total{tool=\"list_projects\"} 1\n"));
+        assert!(text.contains("track_mcp_tool_errors_total{tool=\"get_project\"} 0\n"));
+        // Buckets are cumulative
+        assert!(text.contains(
+            "track_mcp_tool_duration_seconds_bucket{tool=\"list_projects\",le=\"0.005\"} 1\n"
+        ));
+        assert!(text.contains(
+            "track_mcp_tool_duration_seconds_bucket{tool=\"list_projects\",le=\"0.05\"} 2\n"
+        ));
+        assert!(text
+            .contains("track_mcp_tool_duration_seconds_bucket{tool=\"get_project\",le=\"5\"} 0\n"));
+        assert!(text.contains(
+            "track_mcp_tool_duration_seconds_bucket{tool=\"get_project\",le=\"+Inf\"} 1\n"
+        ));
+        assert!(text.contains("track_mcp_tool_duration_seconds_count{tool=\"get_project\"} 1\n"));
+    }
+}
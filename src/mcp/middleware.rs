@@ -0,0 +1,263 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Request logging and rate limiting for the MCP HTTP server
+//!
+//! Every request is logged at debug level with its method, path, status,
+//! duration and, for MCP messages, the SSE session it belongs to. Requests
+//! are rate limited per client IP address with a token bucket sized by
+//! `mcp_rate_limit_per_minute` and `mcp_rate_limit_burst`, so a client stuck
+//! in a loop gets `429 Too Many Requests` instead of keeping the server busy.
+//! `/healthz` is never limited.
+
+use super::server::ProjectTrackerServer;
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    Json, Router,
+};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How often buckets of clients that went quiet are dropped
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Requests allowed per client
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Sustained requests per minute; 0 turns limiting off
+    pub per_minute: u32,
+    /// Requests a client can make at once after being idle
+    pub burst: u32,
+}
+
+impl RateLimit {
+    /// Tokens added to a bucket per second
+    fn per_second(&self) -> f64 {
+        f64::from(self.per_minute) / 60.0
+    }
+
+    /// How long an empty bucket takes to fill up
+    fn refill_time(&self) -> Duration {
+        Duration::from_secs_f64(f64::from(self.burst) / self.per_second())
+    }
+}
+
+/// A client's token bucket
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+#[derive(Debug, Default)]
+struct Buckets {
+    by_client: HashMap<IpAddr, Bucket>,
+    last_sweep: Option<Instant>,
+}
+
+/// Token buckets for the clients that made requests recently
+///
+/// A bucket that has had time to fill up is no different from a new one, so
+/// buckets idle that long are dropped and the map only holds active clients.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimiter {
+    /// Take a token from `client`'s bucket at `now`
+    ///
+    /// Returns how long until a token is available when the bucket is empty.
+    pub fn check(&self, client: IpAddr, limit: RateLimit, now: Instant) -> Result<(), Duration> {
+        if limit.per_minute == 0 {
+            return Ok(());
+        }
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        if buckets.last_sweep.is_none_or(|last| now.saturating_duration_since(last) >= SWEEP_INTERVAL) {
+            let refill_time = limit.refill_time();
+            buckets
+                .by_client
+                .retain(|_, bucket| now.saturating_duration_since(bucket.updated) < refill_time);
+            buckets.last_sweep = Some(now);
+        }
+
+        let capacity = f64::from(limit.burst.max(1));
+        let bucket = buckets.by_client.entry(client).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * limit.per_second()).min(capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / limit.per_second()))
+        }
+    }
+
+    /// Number of clients with a bucket
+    pub fn tracked_clients(&self) -> usize {
+        self.buckets.lock().unwrap_or_else(|e| e.into_inner()).by_client.len()
+    }
+}
+
+/// Add request logging and per-client rate limiting to `router`
+///
+/// Serve the router with `into_make_service_with_connect_info::<SocketAddr>()`
+/// so clients can be told apart; otherwise every request shares one bucket.
+pub fn apply(router: Router, server: ProjectTrackerServer) -> Router {
+    let limiter = Arc::new(RateLimiter::default());
+    router
+        .layer(middleware::from_fn_with_state((server, limiter), rate_limit))
+        .layer(middleware::from_fn(log_request))
+}
+
+/// IP address of the client that sent a request
+fn client_ip(request: &Request) -> IpAddr {
+    request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED), |info| info.0.ip())
+}
+
+async fn rate_limit(
+    State((server, limiter)): State<(ProjectTrackerServer, Arc<RateLimiter>)>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if request.uri().path() == "/healthz" {
+        return next.run(request).await;
+    }
+    let client = client_ip(&request);
+    match limiter.check(client, server.rate_limit(), Instant::now()) {
+        Ok(()) => next.run(request).await,
+        Err(wait) => {
+            log::warn!("Rate limited {} {} from {}", request.method(), request.uri().path(), client);
+            let retry_after = wait.as_secs_f64().ceil().max(1.0) as u64;
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, retry_after.to_string())],
+                Json(serde_json::json!({"error": "Too many requests"})),
+            )
+                .into_response()
+        }
+    }
+}
+
+async fn log_request(request: Request, next: Next) -> Response {
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let session = request
+        .uri()
+        .query()
+        .and_then(|query| query.split('&').find_map(|pair| pair.strip_prefix("sessionId=")))
+        .unwrap_or("-")
+        .to_string();
+    let client = client_ip(&request);
+    let started = Instant::now();
+
+    let response = next.run(request).await;
+    log::debug!(
+        "method={} path={} status={} duration_ms={} session={} client={}",
+        method,
+        path,
+        response.status().as_u16(),
+        started.elapsed().as_millis(),
+        session,
+        client
+    );
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::http::{self, testing::{exchange, test_server}};
+    use crate::Config;
+
+    const LIMIT: RateLimit = RateLimit {
+        per_minute: 60,
+        burst: 3,
+    };
+
+    #[test]
+    fn test_bucket_limits_and_refills() {
+        let limiter = RateLimiter::default();
+        let alice: IpAddr = "10.0.0.1".parse().unwrap();
+        let bob: IpAddr = "10.0.0.2".parse().unwrap();
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert_eq!(limiter.check(alice, LIMIT, start), Ok(()));
+        }
+        assert_eq!(limiter.check(alice, LIMIT, start), Err(Duration::from_secs(1)));
+        // Other clients have their own bucket
+        assert_eq!(limiter.check(bob, LIMIT, start), Ok(()));
+
+        // One token comes back each second, up to the burst size
+        let later = start + Duration::from_millis(1500);
+        assert_eq!(limiter.check(alice, LIMIT, later), Ok(()));
+        assert_eq!(limiter.check(alice, LIMIT, later), Err(Duration::from_millis(500)));
+        let much_later = later + Duration::from_secs(30);
+        for _ in 0..3 {
+            assert_eq!(limiter.check(alice, LIMIT, much_later), Ok(()));
+        }
+        assert!(limiter.check(alice, LIMIT, much_later).is_err());
+
+        // A limit of 0 turns limiting off
+        let off = RateLimit { per_minute: 0, burst: 0 };
+        assert!((0..100).all(|_| limiter.check(alice, off, much_later).is_ok()));
+    }
+
+    #[test]
+    fn test_idle_buckets_expire() {
+        let limiter = RateLimiter::default();
+        let start = Instant::now();
+        for i in 0..50u8 {
+            limiter.check(IpAddr::from([10, 0, 0, i]), LIMIT, start).unwrap();
+        }
+        assert_eq!(limiter.tracked_clients(), 50);
+
+        // Buckets that have had time to fill up are dropped on the next sweep
+        let later = start + SWEEP_INTERVAL;
+        limiter.check(IpAddr::from([10, 0, 1, 0]), LIMIT, later).unwrap();
+        assert_eq!(limiter.tracked_clients(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_http_requests_are_rate_limited() {
+        let mut config = Config::default();
+        config.mcp_rate_limit_per_minute = 60;
+        config.mcp_rate_limit_burst = 3;
+        let server = test_server(config);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router = apply(http::routes(server.clone()), server);
+        tokio::spawn(async move {
+            axum::serve(listener, router.into_make_service_with_connect_info::<SocketAddr>()).await
+        });
+
+        for _ in 0..3 {
+            assert!(exchange(addr, "GET", "/api/v1/projects", &[], None).await.starts_with("HTTP/1.1 200"));
+        }
+        let limited = exchange(addr, "GET", "/api/v1/projects", &[], None).await;
+        assert!(limited.starts_with("HTTP/1.1 429"), "{}", limited);
+        assert!(limited.to_ascii_lowercase().contains("retry-after: 1\r\n"), "{}", limited);
+
+        // Health checks are never limited
+        assert!(exchange(addr, "GET", "/healthz", &[], None).await.starts_with("HTTP/1.1 200"));
+
+        // A token comes back after a second
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        assert!(exchange(addr, "GET", "/api/v1/projects", &[], None).await.starts_with("HTTP/1.1 200"));
+    }
+}
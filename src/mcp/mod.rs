@@ -7,6 +7,17 @@
 //! This module provides shared MCP server functionality that can be used
 //! with different transports (stdio, HTTP/SSE).
 
+pub mod confirm;
+pub mod handles;
+pub mod http;
+pub mod journal;
+pub mod metrics;
+pub mod middleware;
+pub mod prompts;
+pub mod resources;
+pub mod rest;
 pub mod server;
+pub mod sse;
+pub mod writer;
 
 pub use server::ProjectTrackerServer;
@@ -0,0 +1,258 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! MCP prompts
+//!
+//! Prompts are canned instructions for common workflows that a client such
+//! as Claude Desktop offers to the user. Each one fills its arguments into a
+//! template telling the model which tools to call and what to produce:
+//!
+//! - `weekly_status` - summarize what changed since a date
+//! - `new_project_intake` - walk through creating a project with its milestones and stakeholders
+//! - `stakeholder_prep` - prepare for a meeting with a project stakeholder
+
+use anyhow::{anyhow, bail};
+use chrono::NaiveDate;
+use rmcp::model::{
+    GetPromptResult, JsonObject, Prompt, PromptArgument, PromptMessage, PromptMessageRole,
+};
+
+const WEEKLY_STATUS: &str = "\
+Write a weekly status summary of what happened in my projects {period}.
+
+1. Call get_project_summaries for progress on every project and the next milestone of each.
+2. Call list_blocked_projects, and list_risks without a project_id for the open high risks.
+3. For each project that had activity, call get_project_activity and keep what happened {period}.
+4. Call get_quarter_plan for the current quarter to see what is due next.
+
+Then write the summary in markdown with these sections: Highlights, Milestones completed, \
+Coming up in the next two weeks, Blocked and at risk, and Slipped dates. Keep each project \
+to one or two lines, name people by name, and leave out projects with nothing to report.";
+
+const NEW_PROJECT_INTAKE: &str = "\
+Help me set up a new project in Project Tracker. Ask me one question at a time and wait for \
+my answer before moving on.
+
+1. Ask for the project's name, description, type, team, manager, technical lead, requirements \
+owner and due date. Check people with search_people and teams with search_teams, and offer \
+create_person or create_team for anyone or anything that doesn't exist yet.
+2. Create the project with create_project and show me what was saved.
+3. Ask for the milestones in order, each with a name, due date and technical lead, and create \
+them with create_milestone. Mention any warnings the tool returns.
+4. Ask who the stakeholders are and their roles, and add them with add_project_stakeholder.
+5. Ask whether to assign people or a whole team, using add_project_resource or assign_team_to_project.
+6. Ask about any documents to link (add_project_document) and risks to record (add_risk).
+
+Finish with a short summary of everything created, including the project ID.";
+
+const STAKEHOLDER_PREP: &str = "\
+Prepare me for a meeting with {email} about the project {project}.
+
+1. If {project} isn't a project ID, find the project with list_projects.
+2. Call generate_stakeholder_brief with the project ID and stakeholder_email {email}.
+3. Call get_project_summaries with the project ID, and list_risks and list_action_items for the project.
+
+Then write a one-page brief: who they are and their role on the project, where the project \
+stands, what we discussed recently, their open questions, action items that involve them, and \
+three to five talking points for the meeting, starting with anything they are waiting on.";
+
+/// Every prompt the server offers
+pub fn list() -> Vec<Prompt> {
+    vec![
+        Prompt::new(
+            "weekly_status",
+            Some("Summarize progress, blockers and upcoming milestones across all projects"),
+            Some(vec![argument(
+                "since",
+                "Start of the period to cover, as YYYY-MM-DD (defaults to the last 7 days)",
+                false,
+            )]),
+        ),
+        Prompt::new(
+            "new_project_intake",
+            Some("Walk through creating a project with its milestones, stakeholders and resources"),
+            None,
+        ),
+        Prompt::new(
+            "stakeholder_prep",
+            Some("Prepare for a meeting with a project stakeholder"),
+            Some(vec![
+                argument("project", "Project name or ID", true),
+                argument("email", "Stakeholder email", true),
+            ]),
+        ),
+    ]
+}
+
+/// Fill `arguments` into the prompt called `name`
+pub fn get(name: &str, arguments: Option<&JsonObject>) -> anyhow::Result<GetPromptResult> {
+    let arg = |key: &str| -> anyhow::Result<Option<String>> {
+        match arguments.and_then(|args| args.get(key)) {
+            None | Some(serde_json::Value::Null) => Ok(None),
+            Some(serde_json::Value::String(value)) if value.trim().is_empty() => Ok(None),
+            Some(serde_json::Value::String(value)) => Ok(Some(value.trim().to_string())),
+            Some(value) => bail!("Argument '{}' must be a string, got {}", key, value),
+        }
+    };
+    let required =
+        |key: &str| arg(key)?.ok_or_else(|| anyhow!("Missing required argument '{}'", key));
+
+    let (description, text) = match name {
+        "weekly_status" => {
+            let period = match arg("since")? {
+                Some(since) => {
+                    NaiveDate::parse_from_str(&since, "%Y-%m-%d").map_err(|e| {
+                        anyhow!("Invalid since date '{}', expected YYYY-MM-DD: {}", since, e)
+                    })?;
+                    format!("since {}", since)
+                }
+                None => "in the last 7 days".to_string(),
+            };
+            (
+                "Weekly status summary",
+                fill(WEEKLY_STATUS, &[("period", &period)]),
+            )
+        }
+        "new_project_intake" => ("New project intake", NEW_PROJECT_INTAKE.to_string()),
+        "stakeholder_prep" => {
+            let project = required("project")?;
+            let email = required("email")?;
+            (
+                "Stakeholder meeting prep",
+                fill(
+                    STAKEHOLDER_PREP,
+                    &[("project", &project), ("email", &email)],
+                ),
+            )
+        }
+        _ => bail!("Unknown prompt: {}", name),
+    };
+
+    Ok(GetPromptResult {
+        description: Some(description.to_string()),
+        messages: vec![PromptMessage::new_text(PromptMessageRole::User, text)],
+    })
+}
+
+fn argument(name: &str, description: &str, required: bool) -> PromptArgument {
+    PromptArgument {
+        name: name.to_string(),
+        title: None,
+        description: Some(description.to_string()),
+        required: Some(required),
+    }
+}
+
+/// Replace each `{key}` in `template` with its value
+fn fill(template: &str, values: &[(&str, &str)]) -> String {
+    values
+        .iter()
+        .fold(template.to_string(), |text, (key, value)| {
+            text.replace(&format!("{{{}}}", key), value)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::PromptMessageContent;
+
+    fn text(result: &GetPromptResult) -> &str {
+        match &result.messages[0].content {
+            PromptMessageContent::Text { text } => text,
+            content => panic!("Expected text, got {:?}", content),
+        }
+    }
+
+    fn args(value: serde_json::Value) -> JsonObject {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn test_list_matches_get() {
+        let prompts = list();
+        assert_eq!(prompts.len(), 3);
+        for prompt in prompts {
+            let required: Vec<_> = prompt
+                .arguments
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|a| a.required == Some(true))
+                .map(|a| (a.name, serde_json::json!("x")))
+                .collect();
+            let arguments: JsonObject = required.into_iter().collect();
+            assert!(
+                get(&prompt.name, Some(&arguments)).is_ok(),
+                "{}",
+                prompt.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_fill() {
+        assert_eq!(
+            fill("{a} and {b}, then {a}", &[("a", "one"), ("b", "two")]),
+            "one and two, then one"
+        );
+        assert_eq!(fill("{unknown}", &[("a", "one")]), "{unknown}");
+    }
+
+    #[test]
+    fn test_weekly_status() {
+        let result = get("weekly_status", None).unwrap();
+        assert!(text(&result).starts_with(
+            "Write a weekly status summary of what happened in my projects in the last 7 days."
+        ));
+
+        let result = get(
+            "weekly_status",
+            Some(&args(serde_json::json!({"since": "2025-06-02"}))),
+        )
+        .unwrap();
+        assert!(text(&result).contains("in my projects since 2025-06-02."));
+        assert!(text(&result).contains("keep what happened since 2025-06-02."));
+        assert!(!text(&result).contains('{'));
+
+        let err = get(
+            "weekly_status",
+            Some(&args(serde_json::json!({"since": "last week"}))),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("expected YYYY-MM-DD"));
+    }
+
+    #[test]
+    fn test_stakeholder_prep() {
+        let arguments =
+            args(serde_json::json!({"project": "Apollo", "email": " alice@example.com "}));
+        let result = get("stakeholder_prep", Some(&arguments)).unwrap();
+        assert_eq!(result.messages[0].role, PromptMessageRole::User);
+        assert!(text(&result).starts_with(
+            "Prepare me for a meeting with alice@example.com about the project Apollo."
+        ));
+        assert!(text(&result).contains("stakeholder_email alice@example.com."));
+
+        let err = get(
+            "stakeholder_prep",
+            Some(&args(serde_json::json!({"project": "Apollo"}))),
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Missing required argument 'email'");
+        let err = get(
+            "stakeholder_prep",
+            Some(&args(serde_json::json!({"project": 7, "email": "a@b.c"}))),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("must be a string"));
+    }
+
+    #[test]
+    fn test_unknown_prompt() {
+        assert_eq!(
+            get("nope", None).unwrap_err().to_string(),
+            "Unknown prompt: nope"
+        );
+    }
+}
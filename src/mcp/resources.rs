@@ -0,0 +1,1487 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! MCP resources
+//!
+//! Projects and their notes are exposed as read-only resources:
+//!
+//! - `project://{project_id}` - the project with its external tickets, its milestones, stakeholders, stakeholder matrix, documents, open risks, phases, effort, retrospective and decisions as markdown
+//! - `project://{project_id}/notes/{note_id}` - a project note as markdown, with its `[[kind:target]]` references as links
+//!
+//! Resource lists are paginated. Projects are listed first, then notes, both in
+//! creation order so that cursors stay valid while records are edited.
+
+use crate::db::{
+    self, BlockerEntry, BudgetStatus, Decision, DecisionRepository, DecisionStatus, EffortSummary, ExternalRef, Milestone, MilestoneSlippage, PhaseChange, Project,
+    ProjectBrief, ProjectDocument, ProjectNote, ProjectRepository, ProjectRisk, ProjectStakeholder, ProjectStatus, RiskStatus,
+    NoteLinkRepository, Retrospective, RetrospectiveRepository, StakeholderBrief, StakeholderMatrix,
+};
+use crate::db::project_repo::phase_durations;
+use crate::notes::links as note_links;
+use crate::utils::format_local_date;
+use anyhow::{anyhow, bail};
+use chrono::Utc;
+use chrono_tz::Tz;
+use rmcp::model::{AnnotateAble, RawResource, RawResourceTemplate, Resource, ResourceTemplate};
+use std::fmt::{self, Write};
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Maximum number of resources returned per page
+pub const PAGE_SIZE: usize = 100;
+
+/// MIME type of every resource
+pub const MIME_TYPE: &str = "text/markdown";
+
+const SCHEME: &str = "project://";
+
+/// A parsed resource URI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceUri {
+    /// `project://{project_id}`
+    Project(Uuid),
+    /// `project://{project_id}/notes/{note_id}`
+    ProjectNote { project_id: Uuid, note_id: Uuid },
+}
+
+impl fmt::Display for ResourceUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResourceUri::Project(id) => write!(f, "{}{}", SCHEME, id),
+            ResourceUri::ProjectNote {
+                project_id,
+                note_id,
+            } => {
+                write!(f, "{}{}/notes/{}", SCHEME, project_id, note_id)
+            }
+        }
+    }
+}
+
+impl FromStr for ResourceUri {
+    type Err = anyhow::Error;
+
+    fn from_str(uri: &str) -> Result<Self, Self::Err> {
+        let path = uri
+            .strip_prefix(SCHEME)
+            .ok_or_else(|| anyhow!("Unsupported resource URI: {}", uri))?;
+        let parse_id = |id: &str| {
+            Uuid::parse_str(id).map_err(|e| anyhow!("Invalid UUID '{}' in resource URI: {}", id, e))
+        };
+
+        match path.split('/').collect::<Vec<_>>().as_slice() {
+            [project_id] => Ok(ResourceUri::Project(parse_id(project_id)?)),
+            [project_id, "notes", note_id] => Ok(ResourceUri::ProjectNote {
+                project_id: parse_id(project_id)?,
+                note_id: parse_id(note_id)?,
+            }),
+            _ => bail!("Unknown resource URI: {}", uri),
+        }
+    }
+}
+
+/// Position in the resource list, sent to clients as an opaque cursor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cursor {
+    /// Offset into the project list
+    Projects(usize),
+    /// Offset into the note list, once every project has been listed
+    Notes(usize),
+}
+
+impl Default for Cursor {
+    fn default() -> Self {
+        Cursor::Projects(0)
+    }
+}
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cursor::Projects(offset) => write!(f, "projects:{}", offset),
+            Cursor::Notes(offset) => write!(f, "notes:{}", offset),
+        }
+    }
+}
+
+impl FromStr for Cursor {
+    type Err = anyhow::Error;
+
+    fn from_str(cursor: &str) -> Result<Self, Self::Err> {
+        let invalid = || anyhow!("Invalid cursor: {}", cursor);
+        let (kind, offset) = cursor.split_once(':').ok_or_else(invalid)?;
+        let offset = offset.parse().map_err(|_| invalid())?;
+        match kind {
+            "projects" => Ok(Cursor::Projects(offset)),
+            "notes" => Ok(Cursor::Notes(offset)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Templates describing the resource URIs
+pub fn templates() -> Vec<ResourceTemplate> {
+    vec![
+        RawResourceTemplate {
+            uri_template: format!("{}{{project_id}}", SCHEME),
+            name: "project".to_string(),
+            title: Some("Project".to_string()),
+            description: Some("A project with its milestones and stakeholders".to_string()),
+            mime_type: Some(MIME_TYPE.to_string()),
+        }
+        .no_annotation(),
+        RawResourceTemplate {
+            uri_template: format!("{}{{project_id}}/notes/{{note_id}}", SCHEME),
+            name: "project_note".to_string(),
+            title: Some("Project note".to_string()),
+            description: Some("A note attached to a project".to_string()),
+            mime_type: Some(MIME_TYPE.to_string()),
+        }
+        .no_annotation(),
+    ]
+}
+
+/// List one page of resources starting at `cursor`
+///
+/// Returns the resources and the cursor for the next page, if there is one.
+pub fn list(
+    repo: &ProjectRepository,
+    cursor: Cursor,
+    page_size: usize,
+) -> db::Result<(Vec<Resource>, Option<Cursor>)> {
+    let mut resources = Vec::new();
+
+    let note_offset = match cursor {
+        Cursor::Projects(offset) => {
+            let projects = repo.list_page(page_size + 1, offset)?;
+            if projects.len() > page_size {
+                resources.extend(projects.iter().take(page_size).map(project_resource));
+                return Ok((resources, Some(Cursor::Projects(offset + page_size))));
+            }
+            resources.extend(projects.iter().map(project_resource));
+            0
+        }
+        Cursor::Notes(offset) => offset,
+    };
+
+    let remaining = page_size - resources.len();
+    let notes = repo.list_project_notes_page(remaining + 1, note_offset)?;
+    let next = (notes.len() > remaining).then_some(Cursor::Notes(note_offset + remaining));
+    resources.extend(notes.iter().take(remaining).map(note_resource));
+
+    Ok((resources, next))
+}
+
+/// Read a resource as markdown, showing dates in `tz` and flagging
+/// milestones whose effort exceeds their estimate by more than `effort_warning_ratio`
+pub fn read(
+    repo: &ProjectRepository,
+    uri: &ResourceUri,
+    tz: Tz,
+    effort_warning_ratio: f64,
+) -> db::Result<String> {
+    match *uri {
+        ResourceUri::Project(id) => {
+            let project = repo
+                .find_by_id(&id)?
+                .ok_or_else(|| db::Error::not_found("Project", id))?;
+            let milestones = repo.get_milestones(&id)?;
+            let stakeholders = repo.get_stakeholders(&id)?;
+            let documents = repo.get_project_documents(&id)?;
+            let slippage = repo.get_slippage_report(&id)?;
+            let risks = repo.list_open_risks(&id)?;
+            let blockers = repo.get_blocker_history(&id)?;
+            let phases = repo.get_phase_history(&id)?;
+            let effort = repo.get_effort_summary(&id, effort_warning_ratio)?;
+            let budget = repo.get_budget_status(&id)?;
+            let matrix = repo.get_stakeholder_matrix(&id)?;
+            let retrospective = RetrospectiveRepository::new(repo.conn()).find_by_project(&id)?;
+            let decisions = DecisionRepository::new(repo.conn()).list_decisions(&id)?;
+            let mut text = render_project(
+                &project,
+                &milestones,
+                &stakeholders,
+                &documents,
+                &slippage,
+                &risks,
+                &budget,
+                tz,
+            );
+            text.push_str(&render_stakeholder_matrix(&matrix));
+            text.push_str(&render_phase_history(&phases, tz));
+            text.push_str(&render_blocker_history(&blockers, tz));
+            text.push_str(&render_effort_summary(&effort));
+            if retrospective.is_some() || project.status == ProjectStatus::Done {
+                text.push_str(&render_retrospective(retrospective.as_ref(), tz));
+            }
+            if !decisions.is_empty() {
+                text.push_str(&render_decisions(&decisions, tz));
+            }
+            Ok(text)
+        }
+        ResourceUri::ProjectNote {
+            project_id,
+            note_id,
+        } => {
+            let note = repo
+                .find_project_note_by_id(&note_id)?
+                .filter(|note| note.project_id == project_id)
+                .ok_or_else(|| db::Error::not_found("Project note", note_id))?;
+            let mut note = note;
+            note.body = link_references(&NoteLinkRepository::new(repo.conn()), &note.body)?;
+            Ok(render_note(&note))
+        }
+    }
+}
+
+fn project_resource(project: &Project) -> Resource {
+    let mut resource = RawResource::new(
+        ResourceUri::Project(project.id).to_string(),
+        project.name.clone(),
+    );
+    resource.description = project.description.clone();
+    resource.mime_type = Some(MIME_TYPE.to_string());
+    resource.no_annotation()
+}
+
+fn note_resource(note: &ProjectNote) -> Resource {
+    let uri = ResourceUri::ProjectNote {
+        project_id: note.project_id,
+        note_id: note.id,
+    };
+    let mut resource = RawResource::new(uri.to_string(), note.title.clone());
+    resource.mime_type = Some(MIME_TYPE.to_string());
+    resource.no_annotation()
+}
+
+/// Describe a budget and its spend, such as `5000.00 USD (1200.00 spent, 3800.00 remaining)`,
+/// or `None` if there is no budget
+fn render_budget(budget: &BudgetStatus) -> Option<String> {
+    let (amount, remaining) = budget.budget_amount.zip(budget.remaining)?;
+    let currency = budget
+        .budget_currency
+        .as_deref()
+        .map_or(String::new(), |c| format!(" {}", c));
+    let left = if remaining < 0.0 {
+        format!("{:.2} over budget", -remaining)
+    } else {
+        format!("{:.2} remaining", remaining)
+    };
+    Some(format!(
+        "{:.2}{} ({:.2} spent, {})",
+        amount, currency, budget.total_spent, left
+    ))
+}
+
+/// Render a project, its milestones, stakeholders, documents and open risks as markdown, showing dates in `tz`
+///
+/// A budget line is included when the project has a budget.
+#[allow(clippy::too_many_arguments)]
+pub fn render_project(
+    project: &Project,
+    milestones: &[Milestone],
+    stakeholders: &[ProjectStakeholder],
+    documents: &[ProjectDocument],
+    slippage: &[MilestoneSlippage],
+    risks: &[ProjectRisk],
+    budget: &BudgetStatus,
+    tz: Tz,
+) -> String {
+    let format_date = |date| format_local_date(date, tz);
+    let mut out = format!("# {}\n\n", project.name);
+    if let Some(description) = &project.description {
+        let _ = write!(out, "{}\n\n", description);
+    }
+
+    let _ = writeln!(out, "- **Type:** {}", project.project_type);
+    if let Some(phase) = &project.phase {
+        let _ = writeln!(out, "- **Phase:** {}", phase);
+    }
+    if project.blocked {
+        let reason = project
+            .blocked_reason
+            .as_deref()
+            .unwrap_or("no reason given");
+        let _ = writeln!(out, "- **Blocked:** {}", reason);
+    }
+    let fields = [
+        ("Requirements owner", project.requirements_owner.clone()),
+        ("Technical lead", project.technical_lead.clone()),
+        ("Manager", project.manager.clone()),
+        ("Team", project.team.clone()),
+        ("Start date", project.start_date.map(format_date)),
+        ("Due date", project.due_date.map(format_date)),
+        ("Tickets", render_external_refs(&project.external_refs)),
+    ];
+    for (label, value) in fields {
+        if let Some(value) = value {
+            let _ = writeln!(out, "- **{}:** {}", label, value);
+        }
+    }
+    if let Some(line) = render_budget(budget) {
+        let _ = writeln!(out, "- **Budget:** {}", line);
+    }
+
+    out.push_str("\n## Milestones\n\n");
+    if milestones.is_empty() {
+        out.push_str("No milestones.\n");
+    }
+    for milestone in milestones {
+        let _ = write!(out, "{}. {}", milestone.number, milestone.name);
+        if let Some(due_date) = milestone.due_date {
+            let _ = write!(out, " (due {})", format_date(due_date));
+        }
+        if let Some(lead) = &milestone.technical_lead {
+            let _ = write!(out, " - {}", lead);
+        }
+        let slipped = slippage
+            .iter()
+            .find(|s| s.milestone_id == milestone.id && s.slip_count > 0);
+        if let Some(slipped) = slipped {
+            let _ = write!(
+                out,
+                " - slipped {}, total {}",
+                plural(slipped.slip_count as i64, "time"),
+                plural(slipped.total_slip_days, "day")
+            );
+        }
+        if let Some(tickets) = render_external_refs(&milestone.external_refs) {
+            let _ = write!(out, " - {}", tickets);
+        }
+        out.push('\n');
+    }
+
+    out.push_str("\n## Stakeholders\n\n");
+    if stakeholders.is_empty() {
+        out.push_str("No stakeholders.\n");
+    }
+    for stakeholder in stakeholders {
+        match &stakeholder.role {
+            Some(role) => {
+                let _ = writeln!(out, "- {} ({})", stakeholder.stakeholder_email, role);
+            }
+            None => {
+                let _ = writeln!(out, "- {}", stakeholder.stakeholder_email);
+            }
+        }
+    }
+
+    out.push_str("\n## Documents\n\n");
+    if documents.is_empty() {
+        out.push_str("No documents.\n");
+    }
+    for document in documents {
+        let _ = write!(out, "- [{}]({})", document.title, document.url);
+        if let Some(doc_type) = &document.doc_type {
+            let _ = write!(out, " ({})", doc_type);
+        }
+        out.push('\n');
+    }
+
+    out.push_str("\n## Open risks\n\n");
+    if risks.is_empty() {
+        out.push_str("No open risks.\n");
+    }
+    for risk in risks {
+        let _ = write!(
+            out,
+            "- {} (severity {}, likelihood {}",
+            risk.title, risk.severity, risk.likelihood
+        );
+        if risk.status == RiskStatus::Mitigating {
+            out.push_str(", mitigating");
+        }
+        out.push(')');
+        if let Some(owner) = &risk.owner_email {
+            let _ = write!(out, " - {}", owner);
+        }
+        out.push('\n');
+        if let Some(mitigation) = &risk.mitigation {
+            let _ = writeln!(out, "  - Mitigation: {}", mitigation);
+        }
+    }
+
+    out
+}
+
+/// Render the time a project has spent in each phase and its phase changes
+/// as a markdown section, showing dates in `tz`
+///
+/// Moves back to an earlier phase are marked.
+pub fn render_phase_history(history: &[PhaseChange], tz: Tz) -> String {
+    let mut out = String::from("\n## Phases\n\n");
+    if history.is_empty() {
+        out.push_str("No phase set.\n");
+        return out;
+    }
+    for duration in phase_durations(history, Utc::now()) {
+        let current = if duration.current { " (current)" } else { "" };
+        let _ = writeln!(out, "- {}: {}{}", duration.phase, plural(duration.days, "day"), current);
+    }
+    out.push_str("\nChanges:\n\n");
+    for change in history {
+        let _ = write!(out, "- {}: ", format_local_date(change.changed_at, tz));
+        let _ = match (&change.from_phase, &change.to_phase) {
+            (Some(from), Some(to)) => write!(out, "{} to {}", from, to),
+            (None, Some(to)) => write!(out, "Entered {}", to),
+            (Some(from), None) => write!(out, "Left {}", from),
+            (None, None) => Ok(()),
+        };
+        if change.backwards {
+            out.push_str(" (moved back)");
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Render a project's blocker history as a markdown section, showing dates in `tz`
+pub fn render_blocker_history(history: &[BlockerEntry], tz: Tz) -> String {
+    let mut out = String::from("\n## Blocker history\n\n");
+    if history.is_empty() {
+        out.push_str("Never blocked.\n");
+    }
+    for entry in history {
+        let reason = entry.reason.as_deref().unwrap_or("no reason given");
+        let blocked_at = format_local_date(entry.blocked_at, tz);
+        let _ = match entry.cleared_at {
+            Some(cleared_at) => writeln!(
+                out,
+                "- {} to {}: {}",
+                blocked_at,
+                format_local_date(cleared_at, tz),
+                reason
+            ),
+            None => writeln!(out, "- Since {}: {}", blocked_at, reason),
+        };
+    }
+    out
+}
+
+/// Render estimated against actual effort as a markdown section, listing
+/// only milestones with an estimate or actual recorded
+pub fn render_effort_summary(summary: &EffortSummary) -> String {
+    let mut out = String::from("\n## Effort\n\n");
+    let recorded: Vec<_> = summary
+        .milestones
+        .iter()
+        .filter(|m| m.estimated_days.is_some() || m.actual_days.is_some())
+        .collect();
+    if recorded.is_empty() {
+        out.push_str("No effort recorded.\n");
+        return out;
+    }
+
+    let _ = writeln!(
+        out,
+        "Estimated {} days, actual {} days, variance {:+} days\n",
+        summary.total_estimated_days, summary.total_actual_days, summary.variance_days
+    );
+    let days = |days: Option<f64>| days.map_or("-".to_string(), |d| d.to_string());
+    for milestone in recorded {
+        let _ = write!(
+            out,
+            "- {}. {}: estimated {}, actual {}",
+            milestone.number,
+            milestone.name,
+            days(milestone.estimated_days),
+            days(milestone.actual_days)
+        );
+        if let Some(variance) = milestone.variance_days {
+            let _ = write!(out, " ({:+})", variance);
+        }
+        if milestone.over_estimate {
+            out.push_str(" - over estimate");
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Render a project's retrospective as a markdown section, showing dates in `tz`
+///
+/// `None` renders a reminder that a retrospective is still to be held.
+pub fn render_retrospective(retrospective: Option<&Retrospective>, tz: Tz) -> String {
+    let mut out = String::from("\n## Retrospective\n\n");
+    let Some(retrospective) = retrospective else {
+        out.push_str("No retrospective yet.\n");
+        return out;
+    };
+    let _ = writeln!(out, "Held {}", format_local_date(retrospective.completed_on, tz));
+    let parts = [
+        ("What went well", &retrospective.went_well),
+        ("What needs improvement", &retrospective.needs_improvement),
+        ("Actions", &retrospective.action_summary),
+    ];
+    for (title, text) in parts {
+        if let Some(text) = text {
+            let _ = write!(out, "\n### {}\n\n{}\n", title, text.trim_end());
+        }
+    }
+    out
+}
+
+/// The anchor a decision's heading has in its project's resource
+fn decision_anchor(decision: &Decision) -> String {
+    format!("decision-{}", decision.id)
+}
+
+/// Render a project's decisions as a markdown section, showing dates in `tz`
+///
+/// Each decision gets an anchor so stakeholder briefs can link to it, and
+/// superseded decisions link to the one that replaced them.
+pub fn render_decisions(decisions: &[Decision], tz: Tz) -> String {
+    let mut out = String::from("\n## Decisions\n");
+    for decision in decisions {
+        let _ = write!(
+            out,
+            "\n<a id=\"{}\"></a>\n### {} ({})\n\n",
+            decision_anchor(decision),
+            decision.title,
+            decision.status
+        );
+        if let Some(decided_on) = decision.decided_on {
+            let _ = writeln!(out, "- **Decided:** {}", format_local_date(decided_on, tz));
+        }
+        if !decision.approvers.is_empty() {
+            let _ = writeln!(out, "- **Approved by:** {}", decision.approvers.join(", "));
+        }
+        if let Some(successor) = decision
+            .superseded_by
+            .and_then(|id| decisions.iter().find(|d| d.id == id))
+        {
+            let _ = writeln!(
+                out,
+                "- **Superseded by:** [{}](#{})",
+                successor.title,
+                decision_anchor(successor)
+            );
+        }
+        if let Some(context) = decision.context.as_deref().filter(|c| !c.trim().is_empty()) {
+            let _ = write!(out, "\n{}\n", context.trim_end());
+        }
+        let _ = write!(out, "\n**Decision:** {}\n", decision.decision.trim_end());
+    }
+    out
+}
+
+/// Render a stakeholder matrix as a markdown section with one table row per stakeholder
+pub fn render_stakeholder_matrix(matrix: &StakeholderMatrix) -> String {
+    let mut out = String::from("\n## Stakeholder matrix\n\n");
+    let quadrants = [
+        ("Manage closely", &matrix.manage_closely),
+        ("Keep satisfied", &matrix.keep_satisfied),
+        ("Keep informed", &matrix.keep_informed),
+        ("Monitor", &matrix.monitor),
+        ("Unrated", &matrix.unrated),
+    ];
+    if quadrants.iter().all(|(_, stakeholders)| stakeholders.is_empty()) {
+        out.push_str("No stakeholders.\n");
+        return out;
+    }
+
+    out.push_str("| Quadrant | Stakeholder | Role | Influence | Interest |\n");
+    out.push_str("| --- | --- | --- | --- | --- |\n");
+    let rating = |rating: Option<i32>| rating.map_or("-".to_string(), |r| r.to_string());
+    for (label, stakeholders) in quadrants {
+        for stakeholder in stakeholders {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} | {} |",
+                label,
+                stakeholder.stakeholder_email,
+                stakeholder.role.as_deref().unwrap_or("-"),
+                rating(stakeholder.influence),
+                rating(stakeholder.interest)
+            );
+        }
+    }
+    out
+}
+
+/// List tickets in other trackers as "system key", linked when they have a URL
+fn render_external_refs(refs: &[ExternalRef]) -> Option<String> {
+    if refs.is_empty() {
+        return None;
+    }
+    let tickets: Vec<String> = refs
+        .iter()
+        .map(|r| match &r.url {
+            Some(url) => format!("[{} {}]({})", r.system, r.key, url),
+            None => format!("{} {}", r.system, r.key),
+        })
+        .collect();
+    Some(tickets.join(", "))
+}
+
+/// Format a count with a singular or plural noun, e.g. "1 day" or "3 days"
+fn plural(count: i64, noun: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
+/// Turn the `[[kind:target]]` references in a note body into markdown links
+///
+/// Projects and milestones link to the project's resource and people to
+/// their email. References that don't resolve are left as written.
+fn link_references(links: &NoteLinkRepository, body: &str) -> db::Result<String> {
+    let mut targets = Vec::new();
+    for reference in note_links::parse(body) {
+        if let Some(target) = links.resolve(&reference)? {
+            targets.push((reference, target));
+        }
+    }
+    Ok(note_links::replace(body, |reference| {
+        let (_, target) = targets
+            .iter()
+            .find(|(r, _)| r.kind == reference.kind && r.target.eq_ignore_ascii_case(&reference.target))?;
+        let url = match target.project_id {
+            Some(project_id) => ResourceUri::Project(project_id).to_string(),
+            None => format!("mailto:{}", target.id),
+        };
+        Some(format!("[{}]({})", target.name, url))
+    }))
+}
+
+/// Render a project note as markdown
+pub fn render_note(note: &ProjectNote) -> String {
+    format!("# {}\n\n{}\n", note.title, note.body)
+}
+
+/// Render a stakeholder brief as a markdown section
+pub fn render_stakeholder_brief(brief: &StakeholderBrief, tz: Tz) -> String {
+    let person = &brief.person;
+    let mut out = format!("## {} ({})\n\n", person.name, person.email);
+
+    let _ = writeln!(out, "- **Project:** {}", brief.project_name);
+    let fields = [
+        ("Role", brief.stakeholder.role.clone()),
+        (
+            "Organization",
+            brief.organization.as_ref().map(|o| format!("{} ({})", o.name, o.kind)),
+        ),
+        ("Team", person.team.clone()),
+        ("Manager", person.manager.clone()),
+        (
+            "Stakeholder since",
+            Some(format_local_date(brief.stakeholder.created_at, tz)),
+        ),
+    ];
+    for (label, value) in fields {
+        if let Some(value) = value {
+            let _ = writeln!(out, "- **{}:** {}", label, value);
+        }
+    }
+
+    out.push_str("\n### Open questions\n\n");
+    if brief.open_questions.is_empty() {
+        out.push_str("No open questions.\n");
+    }
+    for question in &brief.open_questions {
+        let _ = writeln!(out, "- {}", question);
+    }
+
+    if !brief.approved_decisions.is_empty() {
+        out.push_str("\n### Decisions approved\n\n");
+    }
+    for decision in &brief.approved_decisions {
+        let status = match decision.status {
+            DecisionStatus::Superseded => " (superseded)",
+            _ => "",
+        };
+        let _ = writeln!(
+            out,
+            "- [{}]({}#{}){}",
+            decision.title,
+            ResourceUri::Project(brief.project_id),
+            decision_anchor(decision),
+            status
+        );
+    }
+
+    out.push_str("\n### Recent notes\n");
+    if brief.recent_notes.is_empty() {
+        out.push_str("\nNo notes.\n");
+    }
+    for note in &brief.recent_notes {
+        let _ = write!(
+            out,
+            "\n#### {} ({})\n\n{}\n",
+            note.title,
+            format_local_date(note.created_at, tz),
+            note.body.trim_end()
+        );
+    }
+
+    out
+}
+
+/// Longest project brief returned unless `brief_max_chars` says otherwise
+pub const DEFAULT_BRIEF_MAX_CHARS: usize = 4000;
+
+/// Smallest `brief_max_chars` allowed, which still fits a project's header lines
+pub const MIN_BRIEF_MAX_CHARS: usize = 200;
+
+/// How a project brief is written out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BriefFormat {
+    /// Plain text, one fact per line
+    #[default]
+    Text,
+    /// Compact JSON of the [`ProjectBrief`]
+    Json,
+}
+
+impl FromStr for BriefFormat {
+    type Err = db::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "text" => Ok(BriefFormat::Text),
+            "json" => Ok(BriefFormat::Json),
+            _ => Err(db::Error::Invalid(format!(
+                "Invalid brief format '{}': expected text or json",
+                s
+            ))),
+        }
+    }
+}
+
+/// Render a project brief in at most `max_chars` characters
+///
+/// A brief that is too long loses its note titles first, oldest first, then
+/// its milestones from the last one back, then its decisions, oldest first.
+/// Text that still doesn't fit is cut off; JSON drops the stakeholders
+/// instead, so it always parses.
+pub fn render_project_brief(brief: &ProjectBrief, format: BriefFormat, tz: Tz, max_chars: usize) -> String {
+    let render = |brief: &ProjectBrief| match format {
+        BriefFormat::Text => render_brief_text(brief, tz),
+        BriefFormat::Json => serde_json::to_string(brief).unwrap_or_default(),
+    };
+    let mut brief = brief.clone();
+    loop {
+        let out = render(&brief);
+        if out.chars().count() <= max_chars {
+            return out;
+        }
+        if brief.recent_notes.pop().is_some() || brief.milestones.pop().is_some() {
+            continue;
+        }
+        if !brief.decisions.is_empty() {
+            brief.decisions.remove(0);
+            continue;
+        }
+        return match format {
+            BriefFormat::Text => {
+                let mut cut: String = out.chars().take(max_chars.saturating_sub(4)).collect();
+                cut.push_str("\n...");
+                cut
+            }
+            BriefFormat::Json => {
+                brief.stakeholders.clear();
+                render(&brief)
+            }
+        };
+    }
+}
+
+/// Render a project brief as plain text, leaving out anything that isn't set
+fn render_brief_text(brief: &ProjectBrief, tz: Tz) -> String {
+    let mut out = format!("# {} [{}]\n", brief.name, brief.slug);
+
+    let join = |fields: &[(&str, Option<String>)]| {
+        fields
+            .iter()
+            .filter_map(|(label, value)| value.as_ref().map(|value| format!("{}: {}", label, value)))
+            .collect::<Vec<_>>()
+            .join(" | ")
+    };
+    let lines = [
+        join(&[
+            ("Type", Some(brief.project_type.clone())),
+            ("Status", Some(brief.status.as_str().to_string())),
+            ("Phase", brief.phase.clone()),
+        ]),
+        join(&[
+            ("Start", brief.start_date.map(|d| format_local_date(d, tz))),
+            ("Due", brief.due_date.map(|d| format_local_date(d, tz))),
+        ]),
+        join(&[("Blocked", brief.blocked_reason.clone())]),
+        join(&[
+            ("Team", brief.team.clone()),
+            ("Manager", brief.manager.clone()),
+            ("Tech lead", brief.technical_lead.clone()),
+            ("Requirements owner", brief.requirements_owner.clone()),
+        ]),
+    ];
+    for line in lines.iter().filter(|line| !line.is_empty()) {
+        let _ = writeln!(out, "{}", line);
+    }
+
+    if !brief.stakeholders.is_empty() {
+        out.push_str("Stakeholders:\n");
+    }
+    for stakeholder in &brief.stakeholders {
+        let _ = write!(out, "- {} <{}>", stakeholder.name, stakeholder.email);
+        if let Some(role) = &stakeholder.role {
+            let _ = write!(out, ": {}", role);
+        }
+        out.push('\n');
+    }
+
+    if brief.milestones.len() < brief.milestone_count {
+        let _ = writeln!(out, "Milestones ({} of {}):", brief.milestones.len(), brief.milestone_count);
+    } else if !brief.milestones.is_empty() {
+        out.push_str("Milestones:\n");
+    }
+    for milestone in &brief.milestones {
+        let due = match milestone.due_date {
+            Some(due) => format!("due {}", format_local_date(due, tz)),
+            None => "no due date".to_string(),
+        };
+        let status = if milestone.done { "done" } else { "open" };
+        let _ = writeln!(out, "{}. {} | {} | {}", milestone.number, milestone.name, due, status);
+    }
+
+    let _ = writeln!(
+        out,
+        "Open risks: {} | Open action items: {}",
+        brief.open_risks, brief.open_action_items
+    );
+
+    if !brief.decisions.is_empty() {
+        out.push_str("Decisions:\n");
+    }
+    for decision in &brief.decisions {
+        let _ = write!(out, "- {}", decision.title);
+        if let Some(decided_on) = decision.decided_on {
+            let _ = write!(out, " ({})", format_local_date(decided_on, tz));
+        }
+        if !decision.approvers.is_empty() {
+            let _ = write!(out, " approved by {}", decision.approvers.join(", "));
+        }
+        out.push('\n');
+    }
+
+    if !brief.recent_notes.is_empty() {
+        out.push_str("Recent notes:\n");
+    }
+    for note in &brief.recent_notes {
+        let _ = writeln!(out, "- {} ({})", note.title, format_local_date(note.created_at, tz));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{ActionItem, Person, PersonRepository, RiskLevel};
+    use chrono::TimeZone;
+    use rusqlite::Connection;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_parse_resource_uri() {
+        let project_id = Uuid::new_v4();
+        let note_id = Uuid::new_v4();
+
+        let uri: ResourceUri = format!("project://{}", project_id).parse().unwrap();
+        assert_eq!(uri, ResourceUri::Project(project_id));
+        assert_eq!(uri.to_string(), format!("project://{}", project_id));
+
+        let uri: ResourceUri = format!("project://{}/notes/{}", project_id, note_id)
+            .parse()
+            .unwrap();
+        assert_eq!(
+            uri,
+            ResourceUri::ProjectNote {
+                project_id,
+                note_id
+            }
+        );
+        assert_eq!(
+            uri.to_string(),
+            format!("project://{}/notes/{}", project_id, note_id)
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_resource_uri() {
+        let id = Uuid::new_v4();
+        assert!("project://not-a-uuid".parse::<ResourceUri>().is_err());
+        assert!(format!("project://{}/notes/not-a-uuid", id)
+            .parse::<ResourceUri>()
+            .is_err());
+        assert!(format!("project://{}/milestones/{}", id, id)
+            .parse::<ResourceUri>()
+            .is_err());
+        assert!(format!("file://{}", id).parse::<ResourceUri>().is_err());
+        assert!("project://".parse::<ResourceUri>().is_err());
+    }
+
+    #[test]
+    fn test_parse_cursor() {
+        assert_eq!(
+            "projects:100".parse::<Cursor>().unwrap(),
+            Cursor::Projects(100)
+        );
+        assert_eq!("notes:5".parse::<Cursor>().unwrap(), Cursor::Notes(5));
+        assert_eq!(
+            Cursor::Notes(5).to_string().parse::<Cursor>().unwrap(),
+            Cursor::Notes(5)
+        );
+        assert!("milestones:1".parse::<Cursor>().is_err());
+        assert!("projects:-1".parse::<Cursor>().is_err());
+        assert!("projects".parse::<Cursor>().is_err());
+    }
+
+    #[test]
+    fn test_list_pages() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+
+        let mut expected = Vec::new();
+        let mut project_ids = Vec::new();
+        for i in 0..3 {
+            let project = Project::new(format!("Project {}", i));
+            repo.create(&project).unwrap();
+            expected.push(ResourceUri::Project(project.id).to_string());
+            project_ids.push(project.id);
+        }
+        let project_id = project_ids[0];
+        for i in 0..2 {
+            let note = ProjectNote::new(project_id, format!("Note {}", i), String::new());
+            repo.add_project_note(&note).unwrap();
+            expected.push(
+                ResourceUri::ProjectNote {
+                    project_id,
+                    note_id: note.id,
+                }
+                .to_string(),
+            );
+        }
+
+        let mut uris = Vec::new();
+        let mut cursor = Some(Cursor::default());
+        let mut pages = 0;
+        while let Some(current) = cursor {
+            let (resources, next) = list(&repo, current, 2).unwrap();
+            assert!(resources.len() <= 2);
+            uris.extend(resources.into_iter().map(|r| r.raw.uri));
+            cursor = next;
+            pages += 1;
+        }
+
+        assert_eq!(pages, 3);
+        assert_eq!(uris, expected);
+    }
+
+    #[test]
+    fn test_read_resources() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+
+        let mut project = Project::new("Apollo".to_string());
+        project.description = Some("Moon landing".to_string());
+        project.jira_initiative = Some("PROJ-1".to_string());
+        repo.create(&project).unwrap();
+        let liftoff = Milestone::new(project.id, 1, "Liftoff".to_string());
+        repo.add_milestone(&liftoff).unwrap();
+        let refs = db::ExternalRefRepository::new(&conn);
+        refs.add(db::ExternalRefTarget::Project, &project.id, "github", "acme/apollo#4", Some("https://github.com/acme/apollo/issues/4"))
+            .unwrap();
+        refs.add(db::ExternalRefTarget::Milestone, &liftoff.id, "linear", "ENG-7", None).unwrap();
+        let note = ProjectNote::new(
+            project.id,
+            "Status".to_string(),
+            "All systems go".to_string(),
+        );
+        repo.add_project_note(&note).unwrap();
+
+        let text = read(&repo, &ResourceUri::Project(project.id), Tz::UTC, 1.25).unwrap();
+        assert!(text.starts_with("# Apollo\n\nMoon landing\n"));
+        assert!(text.contains("- **Tickets:** jira PROJ-1, [github acme/apollo#4](https://github.com/acme/apollo/issues/4)\n"));
+        assert!(text.contains("1. Liftoff - linear ENG-7\n"));
+        assert!(text.contains("No stakeholders."));
+        assert!(text.contains("No documents."));
+        assert!(text.contains("No open risks."));
+        assert!(text.contains("## Blocker history\n\nNever blocked.\n"));
+        assert!(text.ends_with("## Effort\n\nNo effort recorded.\n"));
+
+        repo.set_blocked(&project.id, "Waiting on vendor").unwrap();
+        let text = read(&repo, &ResourceUri::Project(project.id), Tz::UTC, 1.25).unwrap();
+        assert!(text.contains("- **Blocked:** Waiting on vendor\n"));
+        let today = format_local_date(chrono::Utc::now(), Tz::UTC);
+        assert!(text.contains(&format!("- Since {}: Waiting on vendor\n", today)));
+        repo.clear_blocked(&project.id).unwrap();
+        let text = read(&repo, &ResourceUri::Project(project.id), Tz::UTC, 1.25).unwrap();
+        assert!(!text.contains("**Blocked:**"));
+        assert!(text.contains(&format!("- {} to {}: Waiting on vendor\n", today, today)));
+
+        // Phases show how long each lasted, and moves back are marked
+        assert!(text.contains("## Phases\n\nNo phase set.\n"));
+        let phases = vec!["Design".to_string(), "Build".to_string()];
+        let phased = ProjectRepository::new(&conn).with_phases(&phases);
+        phased.set_phase(&project.id, "Build").unwrap();
+        phased.set_phase(&project.id, "Design").unwrap();
+        let text = read(&repo, &ResourceUri::Project(project.id), Tz::UTC, 1.25).unwrap();
+        assert!(text.contains("- **Phase:** Design\n"));
+        assert!(text.contains("## Phases\n\n- Build: 0 days\n- Design: 0 days (current)\n"));
+        assert!(text.contains(&format!("- {}: Build to Design (moved back)\n", today)));
+
+        // The budget line only shows once a budget is set
+        assert!(!text.contains("**Budget:**"));
+        let mut budgeted = repo.find_by_id(&project.id).unwrap().unwrap();
+        budgeted.budget_amount = Some(1000.0);
+        budgeted.budget_currency = Some("USD".to_string());
+        repo.update(&budgeted).unwrap();
+        repo.add_expense(&db::ProjectExpense::new(
+            project.id,
+            "Fuel".to_string(),
+            1250.5,
+            chrono::Utc::now(),
+        ))
+        .unwrap();
+        let text = read(&repo, &ResourceUri::Project(project.id), Tz::UTC, 1.25).unwrap();
+        assert!(text.contains("- **Budget:** 1000.00 USD (1250.50 spent, 250.50 over budget)\n"));
+
+        let mut document = ProjectDocument::new(
+            project.id,
+            "Flight plan".to_string(),
+            "https://docs.example.com/flight-plan".to_string(),
+        );
+        document.doc_type = Some("Design Doc".to_string());
+        repo.add_project_document(&document).unwrap();
+        let text = read(&repo, &ResourceUri::Project(project.id), Tz::UTC, 1.25).unwrap();
+        assert!(text.contains(
+            "## Documents\n\n- [Flight plan](https://docs.example.com/flight-plan) (Design Doc)\n"
+        ));
+
+        // Only open risks are listed
+        let mut risk = ProjectRisk::new(
+            project.id,
+            "Weather".to_string(),
+            db::RiskLevel::High,
+            db::RiskLevel::Medium,
+        );
+        risk.status = RiskStatus::Mitigating;
+        risk.mitigation = Some("Backup launch window".to_string());
+        repo.add_project_risk(&risk).unwrap();
+        let mut closed = ProjectRisk::new(
+            project.id,
+            "Fuel leak".to_string(),
+            db::RiskLevel::High,
+            db::RiskLevel::Low,
+        );
+        closed.status = RiskStatus::Closed;
+        repo.add_project_risk(&closed).unwrap();
+        let text = read(&repo, &ResourceUri::Project(project.id), Tz::UTC, 1.25).unwrap();
+        assert!(text.contains(
+            "## Open risks\n\n- Weather (severity high, likelihood medium, mitigating)\n  - Mitigation: Backup launch window\n"
+        ));
+        assert!(!text.contains("Fuel leak"));
+
+        // Dates are shown in the requested zone
+        let mut milestone = repo.get_milestones(&project.id).unwrap().remove(0);
+        milestone.due_date =
+            Some(chrono::TimeZone::with_ymd_and_hms(&chrono::Utc, 2025, 3, 31, 15, 0, 0).unwrap());
+        repo.update_milestone(&milestone).unwrap();
+        milestone.version += 1;
+        let text = read(&repo, &ResourceUri::Project(project.id), Tz::UTC, 1.25).unwrap();
+        assert!(text.contains("1. Liftoff (due 2025-03-31)"));
+        let text = read(
+            &repo,
+            &ResourceUri::Project(project.id),
+            chrono_tz::Asia::Tokyo,
+            1.25,
+        )
+        .unwrap();
+        assert!(text.contains("1. Liftoff (due 2025-04-01)"));
+
+        // Slipped due dates are called out
+        milestone.due_date =
+            Some(chrono::TimeZone::with_ymd_and_hms(&chrono::Utc, 2025, 4, 7, 15, 0, 0).unwrap());
+        repo.update_milestone(&milestone).unwrap();
+        let text = read(&repo, &ResourceUri::Project(project.id), Tz::UTC, 1.25).unwrap();
+        assert!(text.contains("1. Liftoff (due 2025-04-07) - slipped 1 time, total 7 days"));
+
+        // A completed project shows its retrospective, or that it still needs one
+        assert!(!text.contains("## Retrospective"));
+        let mut done = repo.find_by_id(&project.id).unwrap().unwrap();
+        done.status = db::ProjectStatus::Done;
+        repo.update(&done).unwrap();
+        let text = read(&repo, &ResourceUri::Project(project.id), Tz::UTC, 1.25).unwrap();
+        assert!(text.ends_with("## Retrospective\n\nNo retrospective yet.\n"));
+        let mut retrospective = db::Retrospective::new(
+            project.id,
+            chrono::TimeZone::with_ymd_and_hms(&chrono::Utc, 2025, 4, 30, 15, 0, 0).unwrap(),
+        );
+        retrospective.went_well = Some("Clear launch checklist".to_string());
+        retrospective.action_summary = Some("- Book the pad earlier\n".to_string());
+        RetrospectiveRepository::new(&conn).save(&retrospective).unwrap();
+        let text = read(&repo, &ResourceUri::Project(project.id), Tz::UTC, 1.25).unwrap();
+        assert!(text.ends_with(
+            "## Retrospective\n\nHeld 2025-04-30\n\n\
+             ### What went well\n\nClear launch checklist\n\n\
+             ### Actions\n\n- Book the pad earlier\n"
+        ));
+
+        let text = read(
+            &repo,
+            &ResourceUri::ProjectNote {
+                project_id: project.id,
+                note_id: note.id,
+            },
+            Tz::UTC,
+            1.25,
+        )
+        .unwrap();
+        assert_eq!(text, "# Status\n\nAll systems go\n");
+
+        // References become links, and ones that don't resolve stay as written
+        PersonRepository::new(&conn)
+            .create(&Person::new("alice@example.com".to_string(), "Alice".to_string()))
+            .unwrap();
+        let linked = ProjectNote::new(
+            project.id,
+            "Links".to_string(),
+            "See [[project:apollo]], ask [[person:alice@example.com]] about [[project:mercury]]\n\n`[[project:apollo]]`".to_string(),
+        );
+        repo.add_project_note(&linked).unwrap();
+        let text = read(
+            &repo,
+            &ResourceUri::ProjectNote {
+                project_id: project.id,
+                note_id: linked.id,
+            },
+            Tz::UTC,
+            1.25,
+        )
+        .unwrap();
+        assert_eq!(
+            text,
+            format!(
+                "# Links\n\nSee [Apollo](project://{}), ask [Alice](mailto:alice@example.com) about [[project:mercury]]\n\n`[[project:apollo]]`\n",
+                project.id
+            )
+        );
+
+        // A note only resolves under its own project
+        let err = read(
+            &repo,
+            &ResourceUri::ProjectNote {
+                project_id: Uuid::new_v4(),
+                note_id: note.id,
+            },
+            Tz::UTC,
+            1.25,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            db::Error::NotFound {
+                entity: "Project note",
+                ..
+            }
+        ));
+
+        let err = read(&repo, &ResourceUri::Project(Uuid::new_v4()), Tz::UTC, 1.25).unwrap_err();
+        assert!(matches!(
+            err,
+            db::Error::NotFound {
+                entity: "Project",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_render_effort_summary() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let project = Project::new("Apollo".to_string());
+        repo.create(&project).unwrap();
+        let mut design = Milestone::new(project.id, 1, "Design".to_string());
+        design.estimated_days = Some(4.0);
+        design.actual_days = Some(6.5);
+        repo.add_milestone(&design).unwrap();
+        let mut launch = Milestone::new(project.id, 2, "Launch".to_string());
+        launch.estimated_days = Some(2.0);
+        repo.add_milestone(&launch).unwrap();
+        repo.add_milestone(&Milestone::new(project.id, 3, "Retro".to_string()))
+            .unwrap();
+
+        let summary = repo.get_effort_summary(&project.id, 1.25).unwrap();
+        assert_eq!(
+            render_effort_summary(&summary),
+            "\n## Effort\n\nEstimated 6 days, actual 6.5 days, variance +2.5 days\n\n\
+             - 1. Design: estimated 4, actual 6.5 (+2.5) - over estimate\n\
+             - 2. Launch: estimated 2, actual -\n"
+        );
+    }
+
+    #[test]
+    fn test_render_stakeholder_matrix() {
+        let project_id = Uuid::new_v4();
+        let stakeholder = |email: &str, role: Option<&str>, influence, interest| {
+            let mut stakeholder = ProjectStakeholder::new(project_id, email.to_string());
+            stakeholder.role = role.map(str::to_string);
+            stakeholder.influence = influence;
+            stakeholder.interest = interest;
+            stakeholder
+        };
+        let mut matrix = StakeholderMatrix {
+            project_id,
+            project_name: "Apollo".to_string(),
+            manage_closely: vec![],
+            keep_satisfied: vec![],
+            keep_informed: vec![],
+            monitor: vec![],
+            unrated: vec![],
+        };
+        assert_eq!(render_stakeholder_matrix(&matrix), "\n## Stakeholder matrix\n\nNo stakeholders.\n");
+
+        matrix.manage_closely = vec![stakeholder("ceo@example.com", Some("Sponsor"), Some(5), Some(4))];
+        matrix.monitor = vec![stakeholder("legal@example.com", None, Some(1), Some(2))];
+        matrix.unrated = vec![stakeholder("new@example.com", None, Some(3), None)];
+        assert_eq!(
+            render_stakeholder_matrix(&matrix),
+            "\n## Stakeholder matrix\n\n\
+             | Quadrant | Stakeholder | Role | Influence | Interest |\n\
+             | --- | --- | --- | --- | --- |\n\
+             | Manage closely | ceo@example.com | Sponsor | 5 | 4 |\n\
+             | Monitor | legal@example.com | - | 1 | 2 |\n\
+             | Unrated | new@example.com | - | 3 | - |\n"
+        );
+    }
+
+    #[test]
+    fn test_render_stakeholder_brief() {
+        let project_id = Uuid::new_v4();
+        let mut person =
+            db::Person::new("alice@example.com".to_string(), "Alice Smith".to_string());
+        person.team = Some("Finance".to_string());
+        let mut stakeholder = ProjectStakeholder::new(project_id, person.email.clone());
+        stakeholder.role = Some("Sponsor".to_string());
+        let mut note = db::StakeholderNote::new(
+            project_id,
+            person.email.clone(),
+            "Budget review".to_string(),
+            "- [ ] Q3 budget?\n".to_string(),
+        );
+        note.created_at =
+            chrono::TimeZone::with_ymd_and_hms(&chrono::Utc, 2025, 3, 31, 15, 0, 0).unwrap();
+        let brief = StakeholderBrief {
+            project_id,
+            project_name: "Apollo".to_string(),
+            stakeholder,
+            person,
+            organization: Some(db::Organization::new("Acme".to_string())),
+            recent_notes: vec![note],
+            open_questions: vec!["Q3 budget?".to_string()],
+            approved_decisions: vec![db::Decision::new(
+                project_id,
+                "Fund Q3".to_string(),
+                "Fund Q3 from the reserve".to_string(),
+            )],
+        };
+
+        let text = render_stakeholder_brief(&brief, chrono_tz::Asia::Tokyo);
+        assert!(text.starts_with("## Alice Smith (alice@example.com)\n\n- **Project:** Apollo\n"));
+        assert!(text.contains("- **Role:** Sponsor\n- **Organization:** Acme (vendor)\n- **Team:** Finance\n"));
+        assert!(text.contains("### Open questions\n\n- Q3 budget?\n"));
+        assert!(text.contains("#### Budget review (2025-04-01)\n\n- [ ] Q3 budget?\n"));
+        assert!(text.contains(&format!(
+            "### Decisions approved\n\n- [Fund Q3](project://{}#decision-{})\n",
+            project_id, brief.approved_decisions[0].id
+        )));
+    }
+
+    #[test]
+    fn test_render_decisions() {
+        let project_id = Uuid::new_v4();
+        let mut old = Decision::new(project_id, "Own gateway".to_string(), "Run our own gateway".to_string());
+        old.status = DecisionStatus::Superseded;
+        let mut new = Decision::new(project_id, "Shared gateway".to_string(), "Use the shared gateway".to_string());
+        new.status = DecisionStatus::Accepted;
+        new.decided_on = Some(Utc.with_ymd_and_hms(2025, 2, 3, 20, 0, 0).unwrap());
+        new.context = Some("Running our own costs a team.\n".to_string());
+        new.approvers = vec!["alice@example.com".to_string()];
+        old.superseded_by = Some(new.id);
+
+        let text = render_decisions(&[old.clone(), new.clone()], chrono_tz::Asia::Tokyo);
+        assert_eq!(
+            text,
+            format!(
+                "\n## Decisions\n\
+                 \n<a id=\"decision-{old}\"></a>\n### Own gateway (superseded)\n\n\
+                 - **Superseded by:** [Shared gateway](#decision-{new})\n\
+                 \n**Decision:** Run our own gateway\n\
+                 \n<a id=\"decision-{new}\"></a>\n### Shared gateway (accepted)\n\n\
+                 - **Decided:** 2025-02-04\n\
+                 - **Approved by:** alice@example.com\n\
+                 \nRunning our own costs a team.\n\
+                 \n**Decision:** Use the shared gateway\n",
+                old = old.id,
+                new = new.id
+            )
+        );
+    }
+
+    /// A project with a bit of everything, for the brief snapshots
+    fn brief_fixture(conn: &Connection) -> ProjectBrief {
+        let people = PersonRepository::new(conn);
+        for (email, name) in [("alice@example.com", "Alice Smith"), ("bob@example.com", "Bob Jones")] {
+            people.create(&Person::new(email.to_string(), name.to_string())).unwrap();
+        }
+        let repo = ProjectRepository::new(conn);
+        let date = |y, m, d| Utc.with_ymd_and_hms(y, m, d, 12, 0, 0).unwrap();
+
+        let mut project = Project::new("Apollo".to_string());
+        project.id = Uuid::parse_str("6f1c2a0e-8d4b-4c3e-9a7f-0b1d2e3f4a5b").unwrap();
+        project.project_type = "Team".to_string();
+        project.phase = Some("Build".to_string());
+        project.start_date = Some(date(2020, 1, 6));
+        project.due_date = Some(date(2099, 9, 30));
+        project.manager = Some("alice@example.com".to_string());
+        project.technical_lead = Some("bob@example.com".to_string());
+        repo.create(&project).unwrap();
+
+        let mut sponsor = ProjectStakeholder::new(project.id, "alice@example.com".to_string());
+        sponsor.role = Some("Sponsor".to_string());
+        repo.add_stakeholder(&project.id, &sponsor).unwrap();
+        repo.add_stakeholder(&project.id, &ProjectStakeholder::new(project.id, "bob@example.com".to_string()))
+            .unwrap();
+
+        for (number, name, due) in [(1, "Kickoff", Some(date(2020, 1, 15))), (2, "Beta", Some(date(2099, 3, 1))), (3, "Launch", None)] {
+            let mut milestone = Milestone::new(project.id, number, name.to_string());
+            milestone.due_date = due;
+            repo.add_milestone(&milestone).unwrap();
+        }
+
+        repo.add_project_risk(&ProjectRisk::new(project.id, "Vendor delay".to_string(), RiskLevel::High, RiskLevel::Low))
+            .unwrap();
+        let mut closed = ProjectRisk::new(project.id, "Old outage".to_string(), RiskLevel::Low, RiskLevel::Low);
+        closed.status = RiskStatus::Closed;
+        repo.add_project_risk(&closed).unwrap();
+        repo.create_action_item(&ActionItem::new(project.id, "bob@example.com".to_string(), "Book the pad".to_string()))
+            .unwrap();
+
+        let decisions = DecisionRepository::new(conn);
+        let mut gateway = Decision::new(project.id, "Own gateway".to_string(), "Run our own gateway".to_string());
+        gateway.status = DecisionStatus::Accepted;
+        gateway.decided_on = Some(date(2025, 1, 10));
+        decisions.create(&gateway).unwrap();
+        let mut shared = Decision::new(project.id, "Shared gateway".to_string(), "Use the shared gateway".to_string());
+        shared.decided_on = Some(date(2025, 2, 3));
+        shared.approvers = vec!["bob@example.com".to_string(), "alice@example.com".to_string()];
+        decisions.create(&shared).unwrap();
+        decisions.supersede_decision(&gateway.id, &shared.id).unwrap();
+        decisions
+            .create(&Decision::new(project.id, "Vendor".to_string(), "Pick a vendor".to_string()))
+            .unwrap();
+
+        for (day, title) in [(1, "Planning"), (2, "Kickoff notes"), (3, "Weekly status"), (4, "Risks")] {
+            let mut note = ProjectNote::new(project.id, title.to_string(), "Long body the brief leaves out".to_string());
+            note.created_at = date(2025, 3, day);
+            note.updated_at = note.created_at;
+            repo.add_project_note(&note).unwrap();
+        }
+
+        repo.get_brief(&project.id).unwrap()
+    }
+
+    #[test]
+    fn test_render_project_brief() {
+        let conn = setup_test_db();
+        let brief = brief_fixture(&conn);
+
+        let text = render_project_brief(&brief, BriefFormat::Text, Tz::UTC, DEFAULT_BRIEF_MAX_CHARS);
+        assert_eq!(
+            text,
+            "# Apollo [apollo]\n\
+             Type: Team | Status: active | Phase: Build\n\
+             Start: 2020-01-06 | Due: 2099-09-30\n\
+             Manager: alice@example.com | Tech lead: bob@example.com\n\
+             Stakeholders:\n\
+             - Alice Smith <alice@example.com>: Sponsor\n\
+             - Bob Jones <bob@example.com>\n\
+             Milestones:\n\
+             1. Kickoff | due 2020-01-15 | done\n\
+             2. Beta | due 2099-03-01 | open\n\
+             3. Launch | no due date | open\n\
+             Open risks: 1 | Open action items: 1\n\
+             Decisions:\n\
+             - Shared gateway (2025-02-03) approved by alice@example.com, bob@example.com\n\
+             Recent notes:\n\
+             - Risks (2025-03-04)\n\
+             - Weekly status (2025-03-03)\n\
+             - Kickoff notes (2025-03-02)\n"
+        );
+
+        let json = render_project_brief(&brief, BriefFormat::Json, Tz::UTC, DEFAULT_BRIEF_MAX_CHARS);
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"id":"6f1c2a0e-8d4b-4c3e-9a7f-0b1d2e3f4a5b","name":"Apollo","slug":"apollo","project_type":"Team","#,
+                r#""status":"active","phase":"Build","start_date":"2020-01-06T12:00:00Z","due_date":"2099-09-30T12:00:00Z","#,
+                r#""blocked_reason":null,"team":null,"manager":"alice@example.com","technical_lead":"bob@example.com","#,
+                r#""requirements_owner":null,"stakeholders":[{"email":"alice@example.com","name":"Alice Smith","role":"Sponsor"},"#,
+                r#"{"email":"bob@example.com","name":"Bob Jones","role":null}],"milestones":["#,
+                r#"{"number":1,"name":"Kickoff","due_date":"2020-01-15T12:00:00Z","done":true},"#,
+                r#"{"number":2,"name":"Beta","due_date":"2099-03-01T12:00:00Z","done":false},"#,
+                r#"{"number":3,"name":"Launch","due_date":null,"done":false}],"milestone_count":3,"#,
+                r#""open_risks":1,"open_action_items":1,"decisions":[{"title":"Shared gateway","#,
+                r#""decided_on":"2025-02-03T12:00:00Z","approvers":["alice@example.com","bob@example.com"]}],"#,
+                r#""recent_notes":[{"title":"Risks","created_at":"2025-03-04T12:00:00Z"},"#,
+                r#"{"title":"Weekly status","created_at":"2025-03-03T12:00:00Z"},"#,
+                r#"{"title":"Kickoff notes","created_at":"2025-03-02T12:00:00Z"}]}"#,
+            )
+        );
+        assert_eq!(serde_json::from_str::<ProjectBrief>(&json).unwrap(), brief);
+    }
+
+    #[test]
+    fn test_render_project_brief_within_budget() {
+        let conn = setup_test_db();
+        let brief = brief_fixture(&conn);
+        let full = render_project_brief(&brief, BriefFormat::Text, Tz::UTC, DEFAULT_BRIEF_MAX_CHARS);
+
+        // Note titles go first, oldest first
+        let text = render_project_brief(&brief, BriefFormat::Text, Tz::UTC, full.len() - 10);
+        assert!(text.ends_with("Recent notes:\n- Risks (2025-03-04)\n- Weekly status (2025-03-03)\n"));
+
+        // Then milestones from the end
+        let text = render_project_brief(&brief, BriefFormat::Text, Tz::UTC, 460);
+        assert!(text.chars().count() <= 460);
+        assert!(text.contains("Milestones (2 of 3):\n1. Kickoff | due 2020-01-15 | done\n2. Beta"));
+        assert!(!text.contains("Recent notes"));
+        assert!(text.ends_with("Decisions:\n- Shared gateway (2025-02-03) approved by alice@example.com, bob@example.com\n"));
+
+        // Text that still doesn't fit is cut off
+        let text = render_project_brief(&brief, BriefFormat::Text, Tz::UTC, MIN_BRIEF_MAX_CHARS);
+        assert_eq!(text.chars().count(), MIN_BRIEF_MAX_CHARS);
+        assert!(text.starts_with("# Apollo [apollo]\n") && text.ends_with("\n..."));
+
+        // JSON stays valid
+        let json = render_project_brief(&brief, BriefFormat::Json, Tz::UTC, 500);
+        assert!(json.chars().count() <= 500);
+        let cut: ProjectBrief = serde_json::from_str(&json).unwrap();
+        assert!(cut.recent_notes.is_empty());
+        assert!(cut.milestones.len() < cut.milestone_count);
+
+        assert!("JSON".parse::<BriefFormat>().is_ok());
+        assert!("markdown".parse::<BriefFormat>().is_err());
+    }
+}
@@ -0,0 +1,399 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! JSON REST API for the MCP HTTP server
+//!
+//! Dashboards that can't speak MCP can read projects, milestones, people
+//! and teams under `/api/v1`. Responses are the same models the MCP tools
+//! return, and lists take `limit` and `offset` query parameters.
+//!
+//! When `sync_token` is configured, offline clients can also exchange
+//! change sets with `GET` and `POST /api/v1/sync` (see [`crate::db::sync`]),
+//! sending the token as a bearer token.
+
+use super::server::ProjectTrackerServer;
+use crate::db::sync::{self, ApplyReport, ChangeSet, ConflictPolicy};
+use crate::db::{self, Milestone, Person, Project, Team};
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+/// Page size used when a request has no `limit`
+pub const DEFAULT_LIMIT: usize = 100;
+
+/// Largest `limit` a request may ask for
+pub const MAX_LIMIT: usize = 1000;
+
+/// `limit` and `offset` query parameters for list endpoints
+#[derive(Debug, Default, Deserialize)]
+pub struct PageParams {
+    limit: Option<usize>,
+    offset: Option<usize>,
+}
+
+impl PageParams {
+    fn limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT)
+    }
+
+    fn offset(&self) -> usize {
+        self.offset.unwrap_or(0)
+    }
+}
+
+/// Query parameters for `/people`
+#[derive(Debug, Default, Deserialize)]
+pub struct PeopleParams {
+    limit: Option<usize>,
+    offset: Option<usize>,
+    /// Also list deactivated people
+    #[serde(default)]
+    include_inactive: bool,
+}
+
+/// Query parameters for `GET /sync`
+#[derive(Debug, Default, Deserialize)]
+pub struct PullParams {
+    /// Time of the last sync, usually the `until` it returned; everything is sent without it
+    since: Option<DateTime<Utc>>,
+}
+
+/// Query parameters for `POST /sync`
+#[derive(Debug, Default, Deserialize)]
+pub struct PushParams {
+    #[serde(default)]
+    policy: ConflictPolicy,
+}
+
+/// An error response, sent as `{"error": message}`
+#[derive(Debug)]
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn bad_request(message: String) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            message,
+        }
+    }
+
+    fn unauthorized() -> Self {
+        Self {
+            status: StatusCode::UNAUTHORIZED,
+            message: "Missing or wrong bearer token".to_string(),
+        }
+    }
+}
+
+impl From<db::Error> for ApiError {
+    fn from(e: db::Error) -> Self {
+        let status = match e {
+            db::Error::NotFound { .. } => StatusCode::NOT_FOUND,
+            db::Error::Invalid(_) | db::Error::AmbiguousId { .. } => StatusCode::BAD_REQUEST,
+            db::Error::Conflict(_) => StatusCode::CONFLICT,
+            db::Error::PermissionDenied { .. } => StatusCode::FORBIDDEN,
+            db::Error::Busy => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self {
+            status,
+            message: e.to_string(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (
+            self.status,
+            Json(serde_json::json!({"error": self.message})),
+        )
+            .into_response()
+    }
+}
+
+type ApiResult<T> = std::result::Result<Json<T>, ApiError>;
+
+/// Routes for the REST API under `/api/v1`
+pub fn routes(server: ProjectTrackerServer) -> Router {
+    let mut router = Router::new()
+        .route("/api/v1/projects", get(list_projects))
+        .route("/api/v1/projects/{id}", get(get_project))
+        .route("/api/v1/projects/{id}/milestones", get(list_milestones))
+        .route("/api/v1/people", get(list_people))
+        .route("/api/v1/teams", get(list_teams));
+    if server.sync_token().is_some() {
+        router = router.route("/api/v1/sync", get(pull_changes).post(push_changes));
+    }
+    router.with_state(server)
+}
+
+/// Check the request's bearer token against `sync_token`
+fn authorize(server: &ProjectTrackerServer, headers: &HeaderMap) -> std::result::Result<(), ApiError> {
+    let expected = server.sync_token().ok_or_else(ApiError::unauthorized)?;
+    let given = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or_else(ApiError::unauthorized)?;
+    // Compare every byte so the time taken doesn't reveal how much matched
+    let matches = given.len() == expected.len()
+        && given.bytes().zip(expected.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0;
+    if matches {
+        Ok(())
+    } else {
+        Err(ApiError::unauthorized())
+    }
+}
+
+/// Parse a project ID from the path
+fn project_id(id: &str) -> std::result::Result<Uuid, ApiError> {
+    Uuid::parse_str(id).map_err(|_| ApiError::bad_request(format!("Invalid project ID: {}", id)))
+}
+
+async fn list_projects(
+    State(server): State<ProjectTrackerServer>,
+    Query(page): Query<PageParams>,
+) -> ApiResult<Vec<Project>> {
+    let db = server.db().await;
+    let projects = db::ProjectRepository::new(&db).list_page(page.limit(), page.offset())?;
+    Ok(Json(projects))
+}
+
+async fn get_project(
+    State(server): State<ProjectTrackerServer>,
+    Path(id): Path<String>,
+) -> ApiResult<Project> {
+    let id = project_id(&id)?;
+    let db = server.db().await;
+    let project = db::ProjectRepository::new(&db)
+        .find_by_id(&id)?
+        .ok_or_else(|| db::Error::not_found("Project", id))?;
+    Ok(Json(project))
+}
+
+async fn list_milestones(
+    State(server): State<ProjectTrackerServer>,
+    Path(id): Path<String>,
+    Query(page): Query<PageParams>,
+) -> ApiResult<Vec<Milestone>> {
+    let id = project_id(&id)?;
+    let db = server.db().await;
+    let repo = db::ProjectRepository::new(&db);
+    if repo.find_by_id(&id)?.is_none() {
+        return Err(db::Error::not_found("Project", id).into());
+    }
+    let milestones = repo
+        .get_milestones(&id)?
+        .into_iter()
+        .skip(page.offset())
+        .take(page.limit())
+        .collect();
+    Ok(Json(milestones))
+}
+
+async fn list_people(
+    State(server): State<ProjectTrackerServer>,
+    Query(params): Query<PeopleParams>,
+) -> ApiResult<Vec<Person>> {
+    let page = PageParams {
+        limit: params.limit,
+        offset: params.offset,
+    };
+    let db = server.db().await;
+    let people = db::PersonRepository::new(&db).list_page(
+        params.include_inactive,
+        page.limit(),
+        page.offset(),
+    )?;
+    Ok(Json(people))
+}
+
+async fn list_teams(
+    State(server): State<ProjectTrackerServer>,
+    Query(page): Query<PageParams>,
+) -> ApiResult<Vec<Team>> {
+    let db = server.db().await;
+    let teams = db::TeamRepository::new(&db).list_page(page.limit(), page.offset())?;
+    Ok(Json(teams))
+}
+
+async fn pull_changes(
+    State(server): State<ProjectTrackerServer>,
+    headers: HeaderMap,
+    Query(params): Query<PullParams>,
+) -> ApiResult<ChangeSet> {
+    authorize(&server, &headers)?;
+    let oldest = Utc::now() - Duration::days(i64::from(server.sync_tombstone_days()));
+    let since = match params.since {
+        Some(since) if since < oldest => {
+            return Err(ApiError {
+                status: StatusCode::GONE,
+                message: format!(
+                    "Deletes before {} are no longer known; sync again without since to download everything",
+                    oldest.to_rfc3339()
+                ),
+            });
+        }
+        Some(since) => since,
+        None => Utc.timestamp_opt(0, 0).unwrap(),
+    };
+
+    let db = server.db().await;
+    sync::prune_tombstones(&db, oldest)?;
+    Ok(Json(sync::get_changes_since(&db, since)?))
+}
+
+async fn push_changes(
+    State(server): State<ProjectTrackerServer>,
+    headers: HeaderMap,
+    Query(params): Query<PushParams>,
+    Json(changes): Json<ChangeSet>,
+) -> ApiResult<ApplyReport> {
+    authorize(&server, &headers)?;
+    let db = server.db().await;
+    Ok(Json(sync::apply_changes(&db, &changes, params.policy)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::{self, Milestone, Person, Project, Team};
+    use crate::mcp::http::testing::{get, request, spawn_http, test_server};
+    use crate::Config;
+
+    #[tokio::test]
+    async fn test_rest_api() {
+        let server = test_server(Config::default());
+        let project = Project::new("Apollo".to_string());
+        {
+            let db = server.db().await;
+            let projects = db::ProjectRepository::new(&db);
+            projects.create(&project).unwrap();
+            projects
+                .create(&Project::new("Gemini".to_string()))
+                .unwrap();
+            for number in 1..=3 {
+                let milestone = Milestone::new(project.id, number, format!("Phase {}", number));
+                projects.add_milestone(&milestone).unwrap();
+            }
+            db::PersonRepository::new(&db)
+                .create(&Person::new(
+                    "alice@example.com".to_string(),
+                    "Alice".to_string(),
+                ))
+                .unwrap();
+            db::TeamRepository::new(&db)
+                .create(&Team::new("Platform".to_string()))
+                .unwrap();
+        }
+        let addr = spawn_http(server).await;
+        let json = |body: &str| serde_json::from_str::<serde_json::Value>(body).unwrap();
+
+        let (status, body) = get(addr, "/api/v1/projects").await;
+        assert_eq!(status, 200);
+        assert_eq!(json(&body).as_array().unwrap().len(), 2);
+        let (_, body) = get(addr, "/api/v1/projects?limit=1&offset=1").await;
+        assert_eq!(json(&body)[0]["name"], "Gemini");
+
+        let (status, body) = get(addr, &format!("/api/v1/projects/{}", project.id)).await;
+        assert_eq!(status, 200);
+        assert_eq!(json(&body)["id"], project.id.to_string());
+        assert_eq!(json(&body)["name"], "Apollo");
+
+        let path = format!("/api/v1/projects/{}/milestones?offset=1", project.id);
+        let (status, body) = get(addr, &path).await;
+        assert_eq!(status, 200);
+        let milestones = json(&body);
+        assert_eq!(milestones.as_array().unwrap().len(), 2);
+        assert_eq!(milestones[0]["name"], "Phase 2");
+
+        let (status, body) = get(addr, "/api/v1/people").await;
+        assert_eq!(status, 200);
+        assert_eq!(json(&body)[0]["email"], "alice@example.com");
+
+        let (status, body) = get(addr, "/api/v1/teams?limit=10").await;
+        assert_eq!(status, 200);
+        assert_eq!(json(&body)[0]["name"], "Platform");
+    }
+
+    #[tokio::test]
+    async fn test_rest_api_errors() {
+        let addr = spawn_http(test_server(Config::default())).await;
+        let missing = uuid::Uuid::new_v4();
+
+        let (status, body) = get(addr, &format!("/api/v1/projects/{}", missing)).await;
+        assert_eq!(status, 404);
+        let error: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert!(error["error"].as_str().unwrap().contains("not found"));
+
+        let path = format!("/api/v1/projects/{}/milestones", missing);
+        assert_eq!(get(addr, &path).await.0, 404);
+        assert_eq!(get(addr, "/api/v1/projects/not-a-uuid").await.0, 400);
+        assert_eq!(get(addr, "/api/v1/projects?limit=many").await.0, 400);
+    }
+
+    #[tokio::test]
+    async fn test_sync_api() {
+        let mut config = Config::default();
+        config.sync_token = Some("secret".to_string());
+        let server = test_server(config);
+        {
+            let db = server.db().await;
+            db::ProjectRepository::new(&db)
+                .create(&Project::new("Apollo".to_string()))
+                .unwrap();
+        }
+        let addr = spawn_http(server.clone()).await;
+        let auth = [("Authorization", "Bearer secret")];
+
+        assert_eq!(get(addr, "/api/v1/sync").await.0, 401);
+        let wrong = [("Authorization", "Bearer guess")];
+        assert_eq!(request(addr, "GET", "/api/v1/sync", &wrong, None).await.0, 401);
+
+        let (status, body) = request(addr, "GET", "/api/v1/sync", &auth, None).await;
+        assert_eq!(status, 200);
+        let changes: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(changes["projects"][0]["name"], "Apollo");
+        let until = changes["until"].as_str().unwrap().to_string();
+
+        let path = format!("/api/v1/sync?since={}", until.replace('+', "%2B"));
+        let (status, body) = request(addr, "GET", &path, &auth, None).await;
+        assert_eq!(status, 200);
+        let changes: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert!(changes["projects"].as_array().unwrap().is_empty());
+
+        let stale = "/api/v1/sync?since=2000-01-01T00:00:00Z";
+        assert_eq!(request(addr, "GET", stale, &auth, None).await.0, 410);
+
+        let person = Person::new("alice@example.com".to_string(), "Alice".to_string());
+        let push = serde_json::json!({"since": until, "people": [person]}).to_string();
+        let (status, body) =
+            request(addr, "POST", "/api/v1/sync?policy=server_wins", &auth, Some(&push)).await;
+        assert_eq!(status, 200, "{}", body);
+        let report: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(report["applied"], 1);
+        let db = server.db().await;
+        assert!(db::PersonRepository::new(&db)
+            .find_by_email("alice@example.com")
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_sync_api_is_off_without_a_token() {
+        let addr = spawn_http(test_server(Config::default())).await;
+        let auth = [("Authorization", "Bearer secret")];
+        assert_eq!(request(addr, "GET", "/api/v1/sync", &auth, None).await.0, 404);
+    }
+}
@@ -7,17 +7,28 @@
 //! This module provides the core MCP server functionality that can be used
 //! with different transports (stdio, HTTP/SSE).
 
-use crate::{db, Config};
+use super::confirm::Confirmations;
+use super::handles::{HandleKind, Handles};
+use super::http::Health;
+use super::journal;
+use super::metrics::Metrics;
+use super::middleware::RateLimit;
+use super::prompts;
+use super::resources::{self, ResourceUri};
+use super::writer::{WriteContext, WriteQueue};
+use crate::{core::recurrence::RecurrenceRule, db, diagram::{self, DiagramFormat, DiagramKind}, notes, service::{auth, ProjectService}, utils, webhook::WebhookDispatcher, config::SharedConfig, Config, Storage};
 use anyhow::Result;
 use rmcp::{
     ErrorData as McpError, RoleServer, ServerHandler,
-    handler::server::{router::tool::ToolRouter, wrapper::Parameters},
+    handler::server::{router::tool::ToolRouter, tool::ToolCallContext, wrapper::Parameters},
     model::*,
     service::RequestContext,
-    tool, tool_handler, tool_router,
+    tool, tool_router,
 };
-use rusqlite::{Connection, OptionalExtension};
-use std::sync::Arc;
+use rusqlite::Connection;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
@@ -25,17 +36,85 @@ use uuid::Uuid;
 #[derive(Clone)]
 pub struct ProjectTrackerServer {
     db: Arc<Mutex<Connection>>,
-    _config: Arc<Config>,
+    config: SharedConfig,
+    writes: WriteQueue,
+    workspace: Arc<RwLock<String>>,
+    metrics: Arc<Metrics>,
+    confirmations: Arc<Confirmations>,
+    handles: Arc<Handles>,
+    sessions: Arc<AtomicUsize>,
     tool_router: ToolRouter<Self>,
 }
 
 // Request/Response types for tools
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 struct GetProjectRequest {
-    /// Project UUID
+    /// Project ID or slug
     id: String,
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GetProjectBriefRequest {
+    /// Project ID or slug
+    id: String,
+    /// Output format: text (default) or json
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GetFocusListRequest {
+    /// Whose focus list to build; defaults to the configured current_user_email
+    #[serde(skip_serializing_if = "Option::is_none")]
+    email: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GetProjectSummariesRequest {
+    /// Project ID or slug (omit to summarize every project)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    project_id: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GetSlippageReportRequest {
+    /// Project ID or slug
+    project_id: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GetEffortSummaryRequest {
+    /// Project ID or slug
+    project_id: String,
+    /// List the milestones that overran their estimate the most first
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sort_by_variance: Option<bool>,
+    /// Maximum number of milestones to return, e.g. 5 with sort_by_variance for the worst offenders
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GetQuarterPlanRequest {
+    /// Year, e.g. 2025
+    year: i32,
+    /// Calendar quarter, 1 to 4
+    quarter: u32,
+    /// Only include this team's milestones
+    #[serde(skip_serializing_if = "Option::is_none")]
+    team: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GenerateTeamReviewRequest {
+    /// Team name
+    team: String,
+    /// Year, e.g. 2025
+    year: i32,
+    /// Calendar quarter, 1 to 4
+    quarter: u32,
+}
+
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 struct CreateProjectRequest {
     /// Project name
@@ -43,24 +122,66 @@ struct CreateProjectRequest {
     /// Project description
     #[serde(skip_serializing_if = "Option::is_none")]
     description: Option<String>,
-    /// Project type (Personal, Team, Company)
+    /// Project type, one of the configured project_types (e.g., Personal, Team, Company)
     #[serde(skip_serializing_if = "Option::is_none")]
     project_type: Option<String>,
-    /// JIRA initiative ID
+    /// JIRA initiative ID (older field, listed as a jira external reference; prefer add_external_ref)
     #[serde(skip_serializing_if = "Option::is_none")]
     jira_initiative: Option<String>,
+    /// Budget amount, in budget_currency (zero or more)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    budget_amount: Option<f64>,
+    /// Three-letter currency code of the budget (e.g., USD)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    budget_currency: Option<String>,
+    /// Status: planning, active, on_hold or done (default: active)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<String>,
+    /// Phase, one of the configured project_phases (e.g., Discovery, Build)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    phase: Option<String>,
+    /// Don't add the milestones, notes and stakeholders configured for the project type (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    skip_bootstrap: Option<bool>,
+    /// What to do when unique project names are enforced and the name is taken
+    /// (default: error)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    if_exists: Option<IfExists>,
+}
+
+/// How `create_project` handles a name that is already taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum IfExists {
+    /// Return the existing project, so retrying a create is safe
+    ReturnExisting,
+    /// Fail with an error that includes the existing project's ID
+    Error,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct ListPeopleRequest {
+    /// Include inactive (offboarded) people
+    #[serde(skip_serializing_if = "Option::is_none")]
+    include_inactive: Option<bool>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 struct SearchPeopleRequest {
     /// Search query
     query: String,
+    /// Include inactive (offboarded) people
+    #[serde(skip_serializing_if = "Option::is_none")]
+    include_inactive: Option<bool>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 struct GetPersonRequest {
     /// Person email
     email: String,
+    /// Number of recent notes to include (default 5)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note_limit: Option<usize>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
@@ -72,6 +193,15 @@ struct CreatePersonRequest {
     /// Team name
     #[serde(skip_serializing_if = "Option::is_none")]
     team: Option<String>,
+    /// Create the person even if their name closely matches an existing person's
+    #[serde(default)]
+    force: bool,
+    /// Mark the person as a sanctioned exception to the allowed email domains
+    #[serde(default)]
+    external: bool,
+    /// Name of the organization the person works for, such as a vendor; it must already exist
+    #[serde(skip_serializing_if = "Option::is_none")]
+    organization: Option<String>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
@@ -96,6 +226,9 @@ struct CreateTeamRequest {
     /// Manager email
     #[serde(skip_serializing_if = "Option::is_none")]
     manager: Option<String>,
+    /// Name of the parent team, for a sub-team
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent_team: Option<String>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
@@ -110,17 +243,143 @@ struct TeamMemberRequest {
 struct GetTeamMembersRequest {
     /// Team name
     team_name: String,
+    /// Also include the members of all sub-teams (default false)
+    #[serde(default)]
+    include_subteams: bool,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 struct GetMilestonesRequest {
-    /// Project UUID
+    /// Project ID or slug
+    project_id: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GetProjectActivityRequest {
+    /// Project ID or slug
+    project_id: String,
+    /// Maximum number of items to return (default 50)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct SuggestProjectDueDateRequest {
+    /// Project ID or slug
+    project_id: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct RunQueryRequest {
+    /// Filter expression, e.g. `team = "Platform" AND milestone.due_date < 2025-04-01 AND milestone.technical_lead IS NULL`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expression: Option<String>,
+    /// Name of a saved query to run instead of an expression
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct SaveQueryRequest {
+    /// Name to save the query under; an existing query with this name is replaced
+    name: String,
+    /// Filter expression
+    expression: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct DefineCustomFieldRequest {
+    /// Key used to set and read values: lowercase letters, digits and underscores, such as "cost_center"
+    key: String,
+    /// Name shown to people
+    label: String,
+    /// Type of value: text, number, date (YYYY-MM-DD) or bool
+    field_type: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct DeleteCustomFieldRequest {
+    /// Key of the custom field
+    key: String,
+    /// Also delete the field's values on projects; without this, fields with values aren't deleted
+    #[serde(default)]
+    force: bool,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct SetProjectCustomFieldRequest {
+    /// Project ID or slug
     project_id: String,
+    /// Key of the custom field (see list_custom_fields)
+    key: String,
+    /// New value, checked against the field's type; omit or leave blank to clear it
+    value: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct DeleteSavedQueryRequest {
+    /// Name of the saved query
+    name: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct SetUserRoleRequest {
+    /// Email of the user to add or change
+    email: String,
+    /// Role to give them: viewer, editor or admin
+    role: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct RemoveUserRequest {
+    /// Email of the user to remove
+    email: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct ListAttachmentsRequest {
+    /// Kind of note: project, milestone or stakeholder
+    note_type: String,
+    /// Note UUID
+    note_id: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GetBacklinksRequest {
+    /// Kind of record: project, person or milestone
+    kind: String,
+    /// Project ID or slug, person email, or milestone ID
+    target: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GetNoteRequest {
+    /// Kind of note: project, milestone or stakeholder
+    note_type: String,
+    /// Note UUID
+    id: String,
+    /// Character offset to start reading the body from (defaults to 0)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset_chars: Option<usize>,
+    /// Return at most this many characters of the body (defaults to the rest of it)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_chars: Option<usize>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GetNoteSummaryRequest {
+    /// Kind of note: project, milestone or stakeholder
+    note_type: String,
+    /// Note UUID
+    id: String,
+    /// Length of the excerpt in characters (defaults to 280)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    excerpt_chars: Option<usize>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 struct AddProjectResourceRequest {
-    /// Project UUID
+    /// Project ID or slug
     project_id: String,
     /// Person email
     person_email: String,
@@ -129,15 +388,26 @@ struct AddProjectResourceRequest {
     role: Option<String>,
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct AssignTeamToProjectRequest {
+    /// Project ID or slug
+    project_id: String,
+    /// Team name
+    team_name: String,
+    /// Role given to each added member
+    #[serde(skip_serializing_if = "Option::is_none")]
+    role: Option<String>,
+}
+
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 struct GetProjectResourcesRequest {
-    /// Project UUID
+    /// Project ID or slug
     project_id: String,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 struct RemoveProjectResourceRequest {
-    /// Project UUID
+    /// Project ID or slug
     project_id: String,
     /// Person email
     person_email: String,
@@ -160,6 +430,53 @@ struct GetMilestoneResourcesRequest {
     milestone_id: String,
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct AddPersonSkillRequest {
+    /// Person email
+    person_email: String,
+    /// Skill name, such as "kubernetes" (stored lowercase)
+    skill: String,
+    /// Proficiency from 1 (beginner) to 5 (expert)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    level: Option<u8>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct RemovePersonSkillRequest {
+    /// Person email
+    person_email: String,
+    /// Skill name
+    skill: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct FindPeopleBySkillRequest {
+    /// Skill name, ignoring case
+    skill: String,
+    /// Only include people rated at least this level (1-5)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_level: Option<u8>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GetAvailabilityForecastRequest {
+    /// Days ahead to look (default 90)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    horizon_days: Option<u32>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct SuggestResourcesRequest {
+    /// Milestone UUID
+    milestone_id: String,
+    /// Rank people with this skill first, the most proficient first
+    #[serde(skip_serializing_if = "Option::is_none")]
+    skill: Option<String>,
+    /// Maximum number of people to suggest (default 5)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,
+}
+
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 struct RemoveMilestoneResourceRequest {
     /// Milestone UUID
@@ -168,6 +485,35 @@ struct RemoveMilestoneResourceRequest {
     person_email: String,
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct AddChecklistItemRequest {
+    /// Milestone UUID
+    milestone_id: String,
+    /// What needs doing, e.g. "Security review"
+    text: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct ToggleChecklistItemRequest {
+    /// Checklist item UUID
+    id: String,
+    /// Check (true) or uncheck (false) the item; flips it when omitted
+    #[serde(default)]
+    checked: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct ListChecklistItemsRequest {
+    /// Milestone UUID
+    milestone_id: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct RemoveChecklistItemRequest {
+    /// Checklist item UUID
+    id: String,
+}
+
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 struct UpdatePersonRequest {
     /// Person email
@@ -180,15 +526,58 @@ struct UpdatePersonRequest {
     /// Manager email
     #[serde(skip_serializing_if = "Option::is_none")]
     manager: Option<String>,
-    /// Notes
+    /// Deprecated: replaces the old single notes field, which is left unchanged when omitted. Use create_person_note instead
     #[serde(skip_serializing_if = "Option::is_none")]
     notes: Option<String>,
+    /// Mark or unmark the person as a sanctioned exception to the allowed email domains; left unchanged when omitted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    external: Option<bool>,
+    /// Name of the organization the person works for; left unchanged when omitted, an empty string clears it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    organization: Option<String>,
+    /// Version of the record the update is based on; the update fails with a conflict if it has changed since
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<i64>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 struct DeletePersonRequest {
     /// Person email
     email: String,
+    /// Also delete the records of 1:1s with the person; without this, a person with 1:1s can't be deleted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    delete_one_on_ones: Option<bool>,
+    /// Token from an earlier call, when the server asks for deletes to be confirmed
+    confirmation_token: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct SwitchWorkspaceRequest {
+    /// Workspace name, as configured under [workspaces]
+    name: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct DeactivatePersonRequest {
+    /// Person email
+    email: String,
+    /// Also remove the person from milestones that are due in the future
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remove_future_assignments: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct ChangePersonEmailRequest {
+    /// Current email
+    old_email: String,
+    /// New email
+    new_email: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct ReactivatePersonRequest {
+    /// Person email
+    email: String,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
@@ -201,24 +590,32 @@ struct UpdateTeamRequest {
     /// Manager email
     #[serde(skip_serializing_if = "Option::is_none")]
     manager: Option<String>,
+    /// Name of the parent team; leave out to make this a top-level team
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent_team: Option<String>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 struct DeleteTeamRequest {
     /// Team name
     name: String,
+    /// Make the team's sub-teams top-level teams instead of refusing to delete it (default false)
+    #[serde(default)]
+    detach_subteams: bool,
+    /// Token from an earlier call, when the server asks for deletes to be confirmed
+    confirmation_token: Option<String>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 struct UpdateProjectRequest {
-    /// Project UUID
+    /// Project ID or slug
     id: String,
     /// Project name
     name: String,
     /// Project description
     #[serde(skip_serializing_if = "Option::is_none")]
     description: Option<String>,
-    /// Project type (Personal, Team, Company)
+    /// Project type, one of the configured project_types (e.g., Personal, Team, Company)
     #[serde(skip_serializing_if = "Option::is_none")]
     project_type: Option<String>,
     /// Requirements owner email
@@ -233,128 +630,378 @@ struct UpdateProjectRequest {
     /// Team name
     #[serde(skip_serializing_if = "Option::is_none")]
     team: Option<String>,
-    /// Start date (RFC3339)
+    /// Start date (RFC3339, or YYYY-MM-DD in the configured time zone)
     #[serde(skip_serializing_if = "Option::is_none")]
     start_date: Option<String>,
-    /// Due date (RFC3339)
+    /// Due date (RFC3339, or YYYY-MM-DD in the configured time zone)
     #[serde(skip_serializing_if = "Option::is_none")]
     due_date: Option<String>,
-    /// JIRA initiative ID
+    /// JIRA initiative ID (older field, listed as a jira external reference; prefer add_external_ref)
     #[serde(skip_serializing_if = "Option::is_none")]
     jira_initiative: Option<String>,
+    /// Budget amount, in budget_currency (zero or more)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    budget_amount: Option<f64>,
+    /// Three-letter currency code of the budget (e.g., USD)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    budget_currency: Option<String>,
+    /// Status: planning, active, on_hold or done (unchanged if omitted)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<String>,
+    /// Phase, one of the configured project_phases (unchanged if omitted); the change is kept in the phase history
+    #[serde(skip_serializing_if = "Option::is_none")]
+    phase: Option<String>,
+    /// Version of the record the update is based on; the update fails with a conflict if it has changed since
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<i64>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 struct DeleteProjectRequest {
-    /// Project UUID
+    /// Project ID or slug
     id: String,
+    /// Token from an earlier call, when the server asks for deletes to be confirmed
+    confirmation_token: Option<String>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
-struct GetMilestoneRequest {
-    /// Milestone UUID
-    id: String,
+struct BlockProjectRequest {
+    /// Project ID or slug
+    project_id: String,
+    /// Why the project is blocked; replaces the reason if it is already blocked
+    reason: String,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
-struct CreateMilestoneRequest {
-    /// Project UUID
+struct UnblockProjectRequest {
+    /// Project ID or slug
     project_id: String,
-    /// Milestone number (for ordering)
-    number: i32,
-    /// Milestone name
-    name: String,
-    /// Milestone description
-    #[serde(skip_serializing_if = "Option::is_none")]
-    description: Option<String>,
-    /// Technical lead email
-    #[serde(skip_serializing_if = "Option::is_none")]
-    technical_lead: Option<String>,
-    /// Team name
-    #[serde(skip_serializing_if = "Option::is_none")]
-    team: Option<String>,
-    /// Design doc URL
-    #[serde(skip_serializing_if = "Option::is_none")]
-    design_doc_url: Option<String>,
-    /// Start date (RFC3339)
-    #[serde(skip_serializing_if = "Option::is_none")]
-    start_date: Option<String>,
-    /// Due date (RFC3339)
-    #[serde(skip_serializing_if = "Option::is_none")]
-    due_date: Option<String>,
-    /// JIRA epic ID
-    #[serde(skip_serializing_if = "Option::is_none")]
-    jira_epic: Option<String>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
-struct UpdateMilestoneRequest {
-    /// Milestone UUID
-    id: String,
-    /// Milestone number (for ordering)
-    number: i32,
-    /// Milestone name
-    name: String,
-    /// Milestone description
-    #[serde(skip_serializing_if = "Option::is_none")]
-    description: Option<String>,
-    /// Technical lead email
-    #[serde(skip_serializing_if = "Option::is_none")]
-    technical_lead: Option<String>,
-    /// Team name
-    #[serde(skip_serializing_if = "Option::is_none")]
-    team: Option<String>,
-    /// Design doc URL
-    #[serde(skip_serializing_if = "Option::is_none")]
-    design_doc_url: Option<String>,
-    /// Start date (RFC3339)
-    #[serde(skip_serializing_if = "Option::is_none")]
-    start_date: Option<String>,
-    /// Due date (RFC3339)
-    #[serde(skip_serializing_if = "Option::is_none")]
-    due_date: Option<String>,
-    /// JIRA epic ID
-    #[serde(skip_serializing_if = "Option::is_none")]
-    jira_epic: Option<String>,
+struct SetProjectPhaseRequest {
+    /// Project ID or slug
+    project_id: String,
+    /// Phase, one of the configured project_phases (matched ignoring case)
+    phase: String,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
-struct DeleteMilestoneRequest {
-    /// Milestone UUID
-    id: String,
+struct GetPhaseHistoryRequest {
+    /// Project ID or slug
+    project_id: String,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
-struct AddProjectStakeholderRequest {
-    /// Project UUID
+struct SaveRetrospectiveRequest {
+    /// Project ID or slug
     project_id: String,
-    /// Stakeholder email
-    stakeholder_email: String,
-    /// Stakeholder role
+    /// What went well
     #[serde(skip_serializing_if = "Option::is_none")]
-    role: Option<String>,
+    went_well: Option<String>,
+    /// What needs improvement
+    #[serde(skip_serializing_if = "Option::is_none")]
+    needs_improvement: Option<String>,
+    /// Actions agreed for future projects
+    #[serde(skip_serializing_if = "Option::is_none")]
+    action_summary: Option<String>,
+    /// When the retrospective was held (YYYY-MM-DD or RFC3339); defaults to the saved date, or now
+    #[serde(skip_serializing_if = "Option::is_none")]
+    completed_on: Option<String>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
-struct GetProjectStakeholdersRequest {
-    /// Project UUID
+struct GetRetrospectiveRequest {
+    /// Project ID or slug
     project_id: String,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
-struct UpdateProjectStakeholderRequest {
-    /// Project UUID
+struct RecordDecisionRequest {
+    /// Project ID or slug
     project_id: String,
-    /// Stakeholder email
+    /// Short name for the decision
+    title: String,
+    /// What was decided, or is proposed
+    decision: String,
+    /// The situation and options that led to it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    context: Option<String>,
+    /// proposed or accepted (default: accepted)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<String>,
+    /// When it was agreed (YYYY-MM-DD or RFC3339); accepted decisions default to now
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decided_on: Option<String>,
+    /// Emails of the people who approved it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    approvers: Option<Vec<String>>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct ListDecisionsRequest {
+    /// Project ID or slug
+    project_id: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct SupersedeDecisionRequest {
+    /// UUID of the decision being replaced
+    old_id: String,
+    /// UUID of the decision replacing it, on the same project
+    new_id: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct ListProjectsRequest {
+    /// Only list the projects watched by the configured current_user_email
+    #[serde(skip_serializing_if = "Option::is_none")]
+    only_watched: Option<bool>,
+    /// Only list the projects whose stakeholders or resources include people from this organization
+    #[serde(skip_serializing_if = "Option::is_none")]
+    organization: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct CreateOrganizationRequest {
+    /// Unique organization name
+    name: String,
+    /// vendor, client, partner or internal (default: vendor)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kind: Option<String>,
+    /// Free-form notes, such as contract terms
+    #[serde(skip_serializing_if = "Option::is_none")]
+    notes: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GetOrganizationOverviewRequest {
+    /// Organization name
+    name: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct WatchProjectRequest {
+    /// Project ID or slug
+    project_id: String,
+    /// Email of the watcher; defaults to the configured current_user_email
+    #[serde(skip_serializing_if = "Option::is_none")]
+    email: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct UnwatchProjectRequest {
+    /// Project ID or slug
+    project_id: String,
+    /// Email of the watcher; defaults to the configured current_user_email
+    #[serde(skip_serializing_if = "Option::is_none")]
+    email: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct AddExternalRefRequest {
+    /// Kind of record: project or milestone
+    entity_type: String,
+    /// Project ID or slug, or milestone ID
+    entity_id: String,
+    /// Tracker the ticket is in, such as jira, github or linear
+    system: String,
+    /// Ticket key, such as PROJ-123 or 42
+    key: String,
+    /// Link to the ticket; built from the system's URL template when left out
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct ListExternalRefsRequest {
+    /// Kind of record: project or milestone
+    entity_type: String,
+    /// Project ID or slug, or milestone ID
+    entity_id: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct RemoveExternalRefRequest {
+    /// Kind of record: project or milestone
+    entity_type: String,
+    /// Project ID or slug, or milestone ID
+    entity_id: String,
+    /// Tracker the ticket is in
+    system: String,
+    /// Ticket key
+    key: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct ListProjectsByPhaseRequest {
+    /// Phase name (matched ignoring case)
+    phase: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct SetProjectSlugRequest {
+    /// Project ID or slug
+    project_id: String,
+    /// New slug, such as `apollo-migration`; omit to make one from the project's current name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    slug: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GetMilestoneRequest {
+    /// Milestone UUID
+    id: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct CreateMilestoneRequest {
+    /// Project ID or slug
+    project_id: String,
+    /// Milestone number (for ordering)
+    number: i32,
+    /// Milestone name
+    name: String,
+    /// Milestone description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// Technical lead email
+    #[serde(skip_serializing_if = "Option::is_none")]
+    technical_lead: Option<String>,
+    /// Team name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    team: Option<String>,
+    /// Design doc URL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    design_doc_url: Option<String>,
+    /// Start date (RFC3339, or YYYY-MM-DD in the configured time zone)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_date: Option<String>,
+    /// Due date (RFC3339, or YYYY-MM-DD in the configured time zone)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    due_date: Option<String>,
+    /// JIRA epic ID (older field, listed as a jira external reference; prefer add_external_ref)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    jira_epic: Option<String>,
+    /// Recurrence rule (FREQ=WEEKLY|MONTHLY with optional INTERVAL and COUNT or UNTIL, e.g. "FREQ=MONTHLY;COUNT=12"); requires due_date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recurrence_rule: Option<String>,
+    /// Planned effort in days (non-negative)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    estimated_days: Option<f64>,
+    /// Effort actually spent in days (non-negative)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    actual_days: Option<f64>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct UpdateMilestoneRequest {
+    /// Milestone UUID
+    id: String,
+    /// Milestone number (for ordering)
+    number: i32,
+    /// Milestone name
+    name: String,
+    /// Milestone description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// Technical lead email
+    #[serde(skip_serializing_if = "Option::is_none")]
+    technical_lead: Option<String>,
+    /// Team name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    team: Option<String>,
+    /// Design doc URL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    design_doc_url: Option<String>,
+    /// Start date (RFC3339, or YYYY-MM-DD in the configured time zone)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_date: Option<String>,
+    /// Due date (RFC3339, or YYYY-MM-DD in the configured time zone)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    due_date: Option<String>,
+    /// JIRA epic ID (older field, listed as a jira external reference; prefer add_external_ref)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    jira_epic: Option<String>,
+    /// Recurrence rule (FREQ=WEEKLY|MONTHLY with optional INTERVAL and COUNT or UNTIL, e.g. "FREQ=MONTHLY;COUNT=12"); requires due_date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recurrence_rule: Option<String>,
+    /// Planned effort in days (non-negative)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    estimated_days: Option<f64>,
+    /// Effort actually spent in days (non-negative)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    actual_days: Option<f64>,
+    /// Version of the record the update is based on; the update fails with a conflict if it has changed since
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<i64>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct DeleteMilestoneRequest {
+    /// Milestone UUID
+    id: String,
+    /// For a recurring series: true keeps already materialized occurrences as standalone milestones, false deletes them too
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keep_occurrences: Option<bool>,
+    /// Token from an earlier call, when the server asks for deletes to be confirmed
+    confirmation_token: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct AddProjectStakeholderRequest {
+    /// Project ID or slug
+    project_id: String,
+    /// Stakeholder email
+    stakeholder_email: String,
+    /// Stakeholder role
+    #[serde(skip_serializing_if = "Option::is_none")]
+    role: Option<String>,
+    /// How much the stakeholder can affect the project, from 1 (little) to 5 (a lot)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    influence: Option<i32>,
+    /// How much the project affects the stakeholder, from 1 (little) to 5 (a lot)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    interest: Option<i32>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GetProjectStakeholdersRequest {
+    /// Project ID or slug
+    project_id: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GenerateStakeholderBriefRequest {
+    /// Project ID or slug
+    project_id: String,
+    /// Stakeholder email
+    stakeholder_email: String,
+    /// Number of recent notes to include (default 5)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note_limit: Option<usize>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct UpdateProjectStakeholderRequest {
+    /// Project ID or slug
+    project_id: String,
+    /// Stakeholder email
     stakeholder_email: String,
     /// Stakeholder role
     #[serde(skip_serializing_if = "Option::is_none")]
     role: Option<String>,
+    /// How much the stakeholder can affect the project, from 1 (little) to 5 (a lot)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    influence: Option<i32>,
+    /// How much the project affects the stakeholder, from 1 (little) to 5 (a lot)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    interest: Option<i32>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 struct RemoveProjectStakeholderRequest {
-    /// Project UUID
+    /// Project ID or slug
     project_id: String,
     /// Stakeholder email
     stakeholder_email: String,
@@ -362,7 +1009,7 @@ struct RemoveProjectStakeholderRequest {
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 struct UpdateProjectResourceRequest {
-    /// Project UUID
+    /// Project ID or slug
     project_id: String,
     /// Person email
     person_email: String,
@@ -384,18 +1031,33 @@ struct UpdateMilestoneResourceRequest {
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 struct CreateProjectNoteRequest {
-    /// Project UUID
+    /// Project ID or slug
     project_id: String,
     /// Note title
     title: String,
     /// Note body
     body: String,
+    /// freeform (default), or log for an append-only note such as a decision log
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kind: Option<String>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 struct GetProjectNotesRequest {
-    /// Project UUID
+    /// Project ID or slug
     project_id: String,
+    /// Include a sanitized HTML rendering of each note body as `body_html`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rendered: Option<bool>,
+    /// Return at most this many notes, as `{notes, next_cursor}` instead of a plain list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,
+    /// `next_cursor` from the previous page, to continue a paged listing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cursor: Option<String>,
+    /// Cut each body to at most this many characters, adding `total_chars` and `next_offset` so the rest can be read with get_note
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_chars: Option<usize>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
@@ -406,6 +1068,20 @@ struct UpdateProjectNoteRequest {
     title: String,
     /// Note body
     body: String,
+    /// Version of the record the update is based on; the update fails with a conflict if it has changed since
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<i64>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct AppendToNoteRequest {
+    /// Project note UUID
+    note_id: String,
+    /// Entry to add to the end of the note
+    text: String,
+    /// Who the entry is from; defaults to current_user_email from the config
+    #[serde(skip_serializing_if = "Option::is_none")]
+    author: Option<String>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
@@ -414,10 +1090,33 @@ struct DeleteProjectNoteRequest {
     id: String,
 }
 
+/// What a note is attached to
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
-struct CreateMilestoneNoteRequest {
-    /// Milestone UUID
-    milestone_id: String,
+#[serde(tag = "type", rename_all = "snake_case")]
+enum NoteTargetParam {
+    /// A project
+    Project {
+        /// Project ID or slug
+        project_id: String,
+    },
+    /// A milestone
+    Milestone {
+        /// Milestone UUID
+        milestone_id: String,
+    },
+    /// A stakeholder on a project
+    Stakeholder {
+        /// Project ID or slug
+        project_id: String,
+        /// Stakeholder email
+        stakeholder_email: String,
+    },
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct NoteSpec {
+    /// What to attach the note to
+    target: NoteTargetParam,
     /// Note title
     title: String,
     /// Note body
@@ -425,15 +1124,21 @@ struct CreateMilestoneNoteRequest {
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
-struct GetMilestoneNotesRequest {
-    /// Milestone UUID
-    milestone_id: String,
+struct CreateNotesBatchRequest {
+    /// Notes to create
+    notes: Vec<NoteSpec>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
-struct UpdateMilestoneNoteRequest {
-    /// Note UUID
-    id: String,
+struct MoveNoteRequest {
+    /// Project or milestone note UUID
+    note_id: String,
+    /// The project, or a milestone of the same project, to move the note to
+    target: NoteTargetParam,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct CreateInboxNoteRequest {
     /// Note title
     title: String,
     /// Note body
@@ -441,1096 +1146,6585 @@ struct UpdateMilestoneNoteRequest {
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
-struct DeleteMilestoneNoteRequest {
-    /// Note UUID
-    id: String,
+struct TriageInboxNoteRequest {
+    /// Inbox note UUID
+    note_id: String,
+    /// The project, milestone or stakeholder to file the note under
+    target: NoteTargetParam,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
-struct CreateStakeholderNoteRequest {
-    /// Project UUID
+struct AddProjectDocumentRequest {
+    /// Project ID or slug
     project_id: String,
-    /// Stakeholder email
-    stakeholder_email: String,
-    /// Note title
+    /// Document title
     title: String,
-    /// Note body
-    body: String,
+    /// Document URL (http or https)
+    url: String,
+    /// Document type (e.g., Design Doc, PRD, RFC, Runbook; see document_types in the config)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    doc_type: Option<String>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
-struct GetStakeholderNotesRequest {
-    /// Project UUID
+struct ListProjectDocumentsRequest {
+    /// Project ID or slug
     project_id: String,
-    /// Stakeholder email
-    stakeholder_email: String,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
-struct UpdateStakeholderNoteRequest {
-    /// Note UUID
+struct RemoveProjectDocumentRequest {
+    /// Document UUID
     id: String,
-    /// Note title
-    title: String,
-    /// Note body
-    body: String,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
-struct DeleteStakeholderNoteRequest {
-    /// Note UUID
-    id: String,
+struct AddExpenseRequest {
+    /// Project ID or slug
+    project_id: String,
+    /// What the money was spent on
+    description: String,
+    /// Amount spent, in the project's budget currency (zero or more)
+    amount: f64,
+    /// When it was spent (RFC3339, or YYYY-MM-DD in the configured time zone; default now)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    incurred_at: Option<String>,
 }
 
-#[tool_router]
-impl ProjectTrackerServer {
-    pub fn new(config: Config, db: Connection) -> Self {
-        Self {
-            db: Arc::new(Mutex::new(db)),
-            _config: Arc::new(config),
-            tool_router: Self::tool_router(),
-        }
-    }
-
-    // Project tools
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct ListExpensesRequest {
+    /// Project ID or slug
+    project_id: String,
+}
 
-    #[tool(description = "List all projects")]
-    async fn list_projects(&self) -> Result<CallToolResult, McpError> {
-        let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        let projects = repo.list_all().map_err(|e| {
-            McpError::internal_error("Failed to list projects", Some(serde_json::json!({"error": e.to_string()})))
-        })?;
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GetBudgetStatusRequest {
+    /// Project ID or slug
+    project_id: String,
+}
 
-        let json = serde_json::to_string_pretty(&projects)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct CreateSnapshotRequest {
+    /// Project ID or slug
+    project_id: String,
+    /// Label for the snapshot, such as "Q3 start"
+    label: String,
+}
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
-    }
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct ListSnapshotsRequest {
+    /// Project ID or slug
+    project_id: String,
+}
 
-    #[tool(description = "Get a project by ID")]
-    async fn get_project(&self, Parameters(req): Parameters<GetProjectRequest>) -> Result<CallToolResult, McpError> {
-        let uuid = Uuid::parse_str(&req.id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct CompareSnapshotsRequest {
+    /// UUID of the older snapshot
+    from_snapshot_id: String,
+    /// UUID of the newer snapshot
+    to_snapshot_id: String,
+}
 
-        let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        let project = repo.find_by_id(&uuid)
-            .map_err(|e| McpError::internal_error("Database error", Some(serde_json::json!({"error": e.to_string()}))))?;
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct AddRiskRequest {
+    /// Project ID or slug
+    project_id: String,
+    /// Short description of the risk
+    title: String,
+    /// Details of the risk
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// How bad it would be: low, medium or high
+    severity: String,
+    /// How likely it is: low, medium or high
+    likelihood: String,
+    /// Plan for reducing the risk
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mitigation: Option<String>,
+    /// Email of the person who owns the risk
+    #[serde(skip_serializing_if = "Option::is_none")]
+    owner_email: Option<String>,
+}
 
-        let json = serde_json::to_string_pretty(&project)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct UpdateRiskRequest {
+    /// Risk UUID
+    id: String,
+    /// Short description of the risk
+    title: String,
+    /// Details of the risk
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// How bad it would be: low, medium or high
+    severity: String,
+    /// How likely it is: low, medium or high
+    likelihood: String,
+    /// Plan for reducing the risk
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mitigation: Option<String>,
+    /// Email of the person who owns the risk
+    #[serde(skip_serializing_if = "Option::is_none")]
+    owner_email: Option<String>,
+    /// Status: open, mitigating or closed (unchanged if omitted)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct ListRisksRequest {
+    /// Project ID or slug; without one, open high risks across all projects are listed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    project_id: Option<String>,
+    /// Leave out closed risks (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    open_only: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct CloseRiskRequest {
+    /// Risk UUID
+    id: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct CreateActionItemRequest {
+    /// Project ID or slug
+    project_id: String,
+    /// Email of the person who has to do it
+    assignee_email: String,
+    /// What needs to be done
+    description: String,
+    /// Milestone UUID the action item is for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    milestone_id: Option<String>,
+    /// Due date (RFC3339, or YYYY-MM-DD in the configured time zone)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    due_date: Option<String>,
+    /// UUID of the project note the action item came from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_note_id: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct CompleteActionItemRequest {
+    /// Action item UUID
+    id: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct ListActionItemsRequest {
+    /// Project ID or slug
+    project_id: String,
+    /// Leave out completed action items (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    open_only: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GenerateDiagramRequest {
+    /// What to draw: org (people under their managers), teams (teams, sub-teams and members) or projects (project dependencies, or projects and their milestones when there are none)
+    kind: String,
+    /// Text format: mermaid (default) or dot
+    format: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct MyActionItemsRequest {
+    /// Email of the assignee
+    email: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct CreateInitiativeRequest {
+    /// Initiative name
+    name: String,
+    /// Quarter the initiative is planned for, as YYYY-Qn (e.g. 2025-Q3)
+    quarter: String,
+    /// Initiative description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// Email of the person who owns the initiative
+    #[serde(skip_serializing_if = "Option::is_none")]
+    owner_email: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct LinkProjectToInitiativeRequest {
+    /// Project ID or slug
+    project_id: String,
+    /// Initiative UUID; replaces any initiative the project was linked to. Omit to unlink the project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    initiative_id: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct ListInitiativesRequest {
+    /// Only list initiatives for this quarter (YYYY-Qn)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    quarter: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GetInitiativeProgressRequest {
+    /// Initiative UUID
+    id: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct ProjectDependencyRequest {
+    /// ID or slug of the project that waits
+    project_id: String,
+    /// ID or slug of the project it waits for
+    depends_on_project_id: String,
+    /// Why it depends on the other project (only used when adding)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct ListProjectDependenciesRequest {
+    /// Project ID or slug
+    project_id: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct CreateMilestoneNoteRequest {
+    /// Milestone UUID
+    milestone_id: String,
+    /// Note title
+    title: String,
+    /// Note body
+    body: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GetMilestoneNotesRequest {
+    /// Milestone UUID
+    milestone_id: String,
+    /// Include a sanitized HTML rendering of each note body as `body_html`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rendered: Option<bool>,
+    /// Return at most this many notes, as `{notes, next_cursor}` instead of a plain list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,
+    /// `next_cursor` from the previous page, to continue a paged listing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cursor: Option<String>,
+    /// Cut each body to at most this many characters, adding `total_chars` and `next_offset` so the rest can be read with get_note
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_chars: Option<usize>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct UpdateMilestoneNoteRequest {
+    /// Note UUID
+    id: String,
+    /// Note title
+    title: String,
+    /// Note body
+    body: String,
+    /// Version of the record the update is based on; the update fails with a conflict if it has changed since
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<i64>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct DeleteMilestoneNoteRequest {
+    /// Note UUID
+    id: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct CreateStakeholderNoteRequest {
+    /// Project ID or slug
+    project_id: String,
+    /// Stakeholder email
+    stakeholder_email: String,
+    /// Note title
+    title: String,
+    /// Note body
+    body: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GetStakeholderNotesRequest {
+    /// Project ID or slug
+    project_id: String,
+    /// Stakeholder email
+    stakeholder_email: String,
+    /// Include a sanitized HTML rendering of each note body as `body_html`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rendered: Option<bool>,
+    /// Return at most this many notes, as `{notes, next_cursor}` instead of a plain list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,
+    /// `next_cursor` from the previous page, to continue a paged listing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cursor: Option<String>,
+    /// Cut each body to at most this many characters, adding `total_chars` and `next_offset` so the rest can be read with get_note
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_chars: Option<usize>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct UpdateStakeholderNoteRequest {
+    /// Note UUID
+    id: String,
+    /// Note title
+    title: String,
+    /// Note body
+    body: String,
+    /// Version of the record the update is based on; the update fails with a conflict if it has changed since
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<i64>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct DeleteStakeholderNoteRequest {
+    /// Note UUID
+    id: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct CreatePersonNoteRequest {
+    /// Person email
+    person_email: String,
+    /// Note title
+    title: String,
+    /// Note body
+    body: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct ListPersonNotesRequest {
+    /// Person email
+    person_email: String,
+    /// Include a sanitized HTML rendering of each note body as `body_html`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rendered: Option<bool>,
+    /// Return at most this many of the newest notes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,
+    /// Cut each body to at most this many characters, adding `total_chars` and `next_offset` so callers know it was cut
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_chars: Option<usize>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct UpdatePersonNoteRequest {
+    /// Note UUID
+    id: String,
+    /// Note title
+    title: String,
+    /// Note body
+    body: String,
+    /// Version of the record the update is based on; the update fails with a conflict if it has changed since
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<i64>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct DeletePersonNoteRequest {
+    /// Note UUID
+    id: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct LogOneOnOneRequest {
+    /// Email of the person the 1:1 was with
+    person_email: String,
+    /// When it took place (YYYY-MM-DD or RFC3339); defaults to now
+    #[serde(skip_serializing_if = "Option::is_none")]
+    occurred_at: Option<String>,
+    /// Topics planned for the meeting
+    #[serde(skip_serializing_if = "Option::is_none")]
+    agenda: Option<String>,
+    /// What was discussed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    notes: Option<String>,
+    /// Follow-ups agreed in the meeting, one per line
+    #[serde(skip_serializing_if = "Option::is_none")]
+    followups: Option<String>,
+    /// Project ID or slug; when given, each follow-up also becomes an action item for the person on this project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    action_item_project: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct ListOneOnOnesRequest {
+    /// Person email
+    person_email: String,
+    /// Only 1:1s on or after this date (YYYY-MM-DD or RFC3339)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    since: Option<String>,
+    /// Only 1:1s before this date (YYYY-MM-DD or RFC3339)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    before: Option<String>,
+}
+
+/// Convert a database error into an MCP error
+///
+/// Missing records, conflicts and invalid references are caused by the
+/// request, so they are reported as invalid parameters with a message saying
+/// what to fix. Anything else is an internal error.
+fn db_error(context: &'static str, e: db::Error) -> McpError {
+    let data = Some(serde_json::json!({"error": e.to_string()}));
+    match e {
+        db::Error::NotFound { .. }
+        | db::Error::Conflict(_)
+        | db::Error::AmbiguousId { .. }
+        | db::Error::Invalid(_)
+        | db::Error::CycleDetected { .. }
+        | db::Error::ForeignKeyViolation { .. } => {
+            McpError::invalid_params(e.to_string(), data)
+        }
+        db::Error::AlreadyExists { ref id, .. } => McpError::invalid_params(
+            e.to_string(),
+            Some(serde_json::json!({"error": e.to_string(), "existing_id": id})),
+        ),
+        db::Error::VersionConflict { ref current, .. } => McpError::invalid_params(
+            e.to_string(),
+            Some(serde_json::json!({"error": e.to_string(), "current": current})),
+        ),
+        db::Error::PossibleDuplicate { ref matches, .. } => McpError::invalid_params(
+            e.to_string(),
+            Some(serde_json::json!({"error": e.to_string(), "possible_duplicates": matches})),
+        ),
+        db::Error::DomainNotAllowed { ref allowed, .. } => McpError::invalid_params(
+            e.to_string(),
+            Some(serde_json::json!({"error": e.to_string(), "allowed_domains": allowed})),
+        ),
+        db::Error::PermissionDenied { ref operation, ref user, role, required } => McpError::invalid_request(
+            e.to_string(),
+            Some(serde_json::json!({
+                "error": "permission_denied",
+                "operation": operation,
+                "user": user,
+                "role": role,
+                "required": required,
+            })),
+        ),
+        db::Error::Busy => McpError::internal_error(e.to_string(), data),
+        db::Error::Other(_) => McpError::internal_error(context, data),
+    }
+}
+
+/// Parse the `cursor` of a paged note listing
+fn note_cursor(cursor: Option<&str>) -> Result<Option<db::NoteCursor>, McpError> {
+    cursor
+        .map(|cursor| cursor.parse().map_err(|e| db_error("Invalid cursor", e)))
+        .transpose()
+}
+
+/// Pick the version an update is checked against
+///
+/// Without a version from the caller the update is based on the stored
+/// version, so it overwrites whatever is there, unless the configuration
+/// requires versions.
+fn expected_version(config: &Config, requested: Option<i64>, stored: i64) -> Result<i64, McpError> {
+    match requested {
+        Some(version) => Ok(version),
+        None if config.allow_unversioned_updates => Ok(stored),
+        None => Err(McpError::invalid_params(
+            "version is required: fetch the record and pass its version",
+            None,
+        )),
+    }
+}
+
+/// Tool result content: the JSON result, followed by one text item per warning
+fn with_warnings(json: String, warnings: Vec<String>) -> Vec<Content> {
+    std::iter::once(Content::text(json))
+        .chain(warnings.into_iter().map(|w| Content::text(format!("Warning: {}", w))))
+        .collect()
+}
+
+#[tool_router]
+impl ProjectTrackerServer {
+    /// Create the server
+    ///
+    /// Must be called from within a tokio runtime, which runs the webhook
+    /// delivery workers and the database writer.
+    ///
+    /// Settings are read from `config` as each call is handled, so a
+    /// [`SharedConfig`] that is reloaded takes effect without a restart.
+    /// Webhooks and whether `switch_workspace` is offered are fixed when the
+    /// server is created.
+    pub fn new(config: impl Into<SharedConfig>, db: Connection) -> Self {
+        let db = Arc::new(Mutex::new(db));
+        let config = config.into();
+        let current = config.get();
+        let writes = WriteQueue::spawn(db.clone(), config.clone(), WebhookDispatcher::spawn(&current.webhooks));
+        let mut tool_router = Self::tool_router();
+        if !current.mcp_workspace_switching {
+            tool_router.remove_route("switch_workspace");
+        }
+        Self {
+            db,
+            workspace: Arc::new(RwLock::new(current.workspace().to_string())),
+            config,
+            writes,
+            metrics: Arc::new(Metrics::new()),
+            confirmations: Arc::new(Confirmations::default()),
+            handles: Arc::new(Handles::default()),
+            sessions: Arc::new(AtomicUsize::new(0)),
+            tool_router,
+        }
+    }
+
+    /// A copy of the server for another session, sharing everything but
+    /// the short handles it hands out
+    pub fn new_session(&self) -> Self {
+        Self {
+            handles: Arc::new(Handles::default()),
+            ..self.clone()
+        }
+    }
+
+    /// The configuration as it is now
+    fn config(&self) -> Arc<Config> {
+        self.config.get()
+    }
+
+    /// The email to watch projects as: the one given, or the configured
+    /// current_user_email
+    fn watcher_email(&self, email: Option<String>) -> Result<String, McpError> {
+        email.or_else(|| self.config().current_user_email.clone()).ok_or_else(|| {
+            McpError::invalid_params("No email given and current_user_email is not set in the configuration", None)
+        })
+    }
+
+    /// Check that current_user_email's role allows a tool, see [`auth`]
+    async fn authorize(&self, tool: &str) -> Result<(), McpError> {
+        let db = self.db.lock().await;
+        auth::authorize_user(&db, self.config().current_user_email.as_deref(), tool)
+            .map_err(|e| db_error("Failed to check permissions", e))
+    }
+
+    /// The line of the instructions saying what current_user_email may do
+    fn role_instructions(&self, db: &Connection) -> Option<String> {
+        let users = db::UserRepository::new(db);
+        if !users.permissions_enabled().ok()? {
+            return Some("Role: admin (no users are listed, so permissions are off)".to_string());
+        }
+        let email = self.config().current_user_email.clone();
+        let role = users.effective_role(email.as_deref()).ok()?;
+        let allowed = match role {
+            db::UserRole::Viewer => "only list, get and other read tools; everything else returns permission_denied",
+            db::UserRole::Editor => "every tool except delete_project, set_user_role and remove_user",
+            db::UserRole::Admin => "every tool",
+        };
+        Some(format!(
+            "Role: {} as {}, which allows {}",
+            role,
+            email.as_deref().unwrap_or("a user without current_user_email"),
+            allowed
+        ))
+    }
+
+    /// Name of the workspace whose database the server is using
+    pub fn workspace(&self) -> String {
+        self.workspace.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Name the workspace `db` was opened for, when it isn't the configured one
+    pub fn for_workspace(self, name: &str) -> Self {
+        *self.workspace.write().unwrap_or_else(|e| e.into_inner()) = name.to_string();
+        self
+    }
+
+    /// Close the database and open another workspace's, for every session
+    ///
+    /// Waiting for the lock lets running calls finish; writes queued behind
+    /// the switch then run against the new database. Nothing changes if the
+    /// new database can't be opened.
+    pub async fn use_workspace(&self, name: &str) -> Result<()> {
+        let path = self.config().database_path_for(name)?;
+        let mut db = self.db.lock().await;
+        let conn = db::open_database(&path)?;
+        if let Err(e) = db::schema::set_unique_project_names(&conn, self.config().enforce_unique_project_names) {
+            log::warn!("Failed to apply the unique project name policy: {:#}", e);
+        }
+        *db = conn;
+        *self.workspace.write().unwrap_or_else(|e| e.into_inner()) = name.to_string();
+        log::info!("Switched to workspace {} ({})", name, path.display());
+        Ok(())
+    }
+
+    /// Run a tool's reads and writes on the single writer, after any writes
+    /// submitted before it
+    ///
+    /// A call made with an idempotency key has its result journaled in the
+    /// same transaction as the job's changes.
+    async fn write<F>(&self, job: F) -> Result<CallToolResult, McpError>
+    where
+        F: FnOnce(&Connection, &WriteContext) -> Result<CallToolResult, McpError> + Send + 'static,
+    {
+        match journal::Operation::take() {
+            Some(operation) => {
+                self.writes
+                    .submit(move |db, ctx| operation.run(db, ctx, job))
+                    .await?
+            }
+            None => self.writes.submit(job).await?,
+        }
+    }
+
+    /// Resolve a project ID, slug or unique ID prefix
+    ///
+    /// Full IDs are returned without taking the database lock.
+    async fn project_ref(&self, reference: &str) -> Result<Uuid, McpError> {
+        if let Ok(id) = Uuid::parse_str(reference) {
+            return Ok(id);
+        }
+        let db = self.db.lock().await;
+        db::ProjectRepository::new(&db)
+            .resolve_project_ref(reference)
+            .map_err(|e| db_error("Unknown project", e))
+    }
+
+    /// Resolve the record an external reference is attached to, accepting project slugs
+    async fn external_ref_target(&self, entity_type: &str, entity_id: &str) -> Result<(db::ExternalRefTarget, Uuid), McpError> {
+        let target = entity_type.parse::<db::ExternalRefTarget>()
+            .map_err(|e| db_error("Invalid entity type", e))?;
+        let id = match target {
+            db::ExternalRefTarget::Project => self.project_ref(entity_id).await?,
+            db::ExternalRefTarget::Milestone => Uuid::parse_str(entity_id)
+                .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?,
+        };
+        Ok((target, id))
+    }
+
+    /// Resolve the projects of a dependency request
+    async fn dependency_ids(&self, req: &ProjectDependencyRequest) -> Result<(Uuid, Uuid), McpError> {
+        Ok((self.project_ref(&req.project_id).await?, self.project_ref(&req.depends_on_project_id).await?))
+    }
+
+    /// Resolve a note target, accepting project slugs
+    async fn note_target(&self, target: NoteTargetParam) -> Result<db::NoteTarget, McpError> {
+        Ok(match target {
+            NoteTargetParam::Project { project_id } => db::NoteTarget::Project { project_id: self.project_ref(&project_id).await? },
+            NoteTargetParam::Milestone { milestone_id } => db::NoteTarget::Milestone {
+                milestone_id: Uuid::parse_str(&milestone_id)
+                    .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?,
+            },
+            NoteTargetParam::Stakeholder { project_id, stakeholder_email } => db::NoteTarget::Stakeholder {
+                project_id: self.project_ref(&project_id).await?,
+                stakeholder_email,
+            },
+        })
+    }
+
+    /// Check a delete against `mcp_require_confirmation`
+    ///
+    /// Returns `None` when the delete can go ahead: confirmation is off, or
+    /// `token` was issued for this `action`. Otherwise returns the result to
+    /// send back instead, which describes what would be deleted and carries
+    /// a new token.
+    async fn confirm_delete<F>(&self, action: String, token: Option<&str>, impact: F) -> Result<Option<CallToolResult>, McpError>
+    where
+        F: FnOnce(&Connection) -> db::Result<db::deletion::DeletionImpact>,
+    {
+        if !self.config().mcp_require_confirmation {
+            return Ok(None);
+        }
+        if let Some(token) = token {
+            self.confirmations.redeem(token, &action)
+                .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+            return Ok(None);
+        }
+
+        let impact = {
+            let db = self.db.lock().await;
+            impact(&db).map_err(|e| db_error("Failed to look up what would be deleted", e))?
+        };
+        let json = serde_json::to_string_pretty(&serde_json::json!({
+            "confirmation_required": true,
+            "confirmation_token": self.confirmations.issue(&action),
+            "expires_in_seconds": self.confirmations.ttl().as_secs(),
+            "summary": impact.summary(),
+            "impact": impact,
+        }))
+        .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(Some(CallToolResult::success(vec![Content::text(json)])))
+    }
+
+    /// Serialize a tool result, giving each record in it this session's
+    /// short handle and listing the handles after it
+    fn with_handles<T: serde::Serialize>(&self, kind: HandleKind, value: &T) -> Result<CallToolResult, McpError> {
+        let mut value = serde_json::to_value(value)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let legend = self.handles.annotate(kind, &mut value);
+        let json = serde_json::to_string_pretty(&value)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        let mut content = vec![Content::text(json)];
+        if !legend.is_empty() {
+            content.push(Content::text(format!(
+                "Handles for this session, accepted in place of IDs:\n{}",
+                legend.join("\n")
+            )));
+        }
+        Ok(CallToolResult::success(content))
+    }
+
+    /// Tool call metrics, shared by every session
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Number of SSE sessions currently connected
+    pub fn active_sessions(&self) -> usize {
+        self.sessions.load(Ordering::Relaxed)
+    }
+
+    /// Count the SSE sessions connected to this server
+    pub(crate) fn session_counter(&self) -> Arc<AtomicUsize> {
+        self.sessions.clone()
+    }
+
+    /// Lock the shared database connection, for the HTTP endpoints
+    pub(crate) async fn db(&self) -> tokio::sync::MutexGuard<'_, Connection> {
+        self.db.lock().await
+    }
+
+    /// Whether the HTTP server exposes `/metrics`
+    pub fn metrics_enabled(&self) -> bool {
+        self.config().mcp_metrics_enabled
+    }
+
+    /// Requests each client may make to the HTTP server
+    pub fn rate_limit(&self) -> RateLimit {
+        let config = self.config();
+        RateLimit {
+            per_minute: config.mcp_rate_limit_per_minute,
+            burst: config.mcp_rate_limit_burst,
+        }
+    }
+
+    /// Bearer token for `/api/v1/sync`, if syncing is turned on
+    pub fn sync_token(&self) -> Option<String> {
+        self.config().sync_token.clone()
+    }
+
+    /// Days deletes are kept for syncing clients
+    pub fn sync_tombstone_days(&self) -> u32 {
+        self.config().sync_tombstone_days
+    }
+
+    /// Check that the database answers and report the schema version and uptime
+    pub async fn health(&self) -> Health {
+        let db = self.db.lock().await;
+        let db_ok = db.query_row("SELECT 1", [], |row| row.get::<_, i32>(0)).is_ok();
+        let schema_version = db::schema::read_schema_version(&db).ok().flatten();
+        Health {
+            status: if db_ok { "ok" } else { "degraded" },
+            db_ok,
+            schema_version,
+            uptime_seconds: self.metrics.uptime().as_secs(),
+        }
+    }
+
+    // Project tools
+
+    #[tool(description = "List all projects, or with only_watched the ones the configured current_user_email watches. With organization, only the projects that organization's people are stakeholders or resources on")]
+    async fn list_projects(&self, Parameters(req): Parameters<ListProjectsRequest>) -> Result<CallToolResult, McpError> {
+        let email = if req.only_watched.unwrap_or(false) {
+            Some(self.watcher_email(None)?)
+        } else {
+            None
+        };
+        let db = self.db.lock().await;
+        let mut projects = match email {
+            Some(email) => db::WatcherRepository::new(&db).list_watched_projects(&email),
+            None => db::ProjectRepository::new(&db).list_all(),
+        }
+        .map_err(|e| db_error("Failed to list projects", e))?;
+        if let Some(organization) = req.organization {
+            let ids = db::OrganizationRepository::new(&db).get_project_ids_for_org(&organization)
+                .map_err(|e| db_error("Failed to list projects", e))?;
+            projects.retain(|p| ids.contains(&p.id));
+        }
+
+        self.with_handles(HandleKind::Project, &projects)
+    }
+
+    #[tool(description = "Get a project by ID")]
+    async fn get_project(&self, Parameters(req): Parameters<GetProjectRequest>) -> Result<CallToolResult, McpError> {
+        let uuid = self.project_ref(&req.id).await?;
+
+        let db = self.db.lock().await;
+        let repo = db::ProjectRepository::new(&db);
+        let project = repo.find_by_id(&uuid)
+            .map_err(|e| db_error("Database error", e))?;
+
+        self.with_handles(HandleKind::Project, &project)
+    }
+
+    #[tool(description = "Compact reference card for a project in one call: name, type, status, phase, dates, team, leads, stakeholders with roles, one line per milestone, open risk and action item counts and the three latest note titles. Prefer it to get_project and the list tools for getting oriented. Output is plain text, or compact JSON with format json, and is cut to fit brief_max_chars from the config by dropping note titles, then milestones.")]
+    async fn get_project_brief(&self, Parameters(req): Parameters<GetProjectBriefRequest>) -> Result<CallToolResult, McpError> {
+        let uuid = self.project_ref(&req.id).await?;
+        let format = match req.format.as_deref() {
+            Some(format) => format.parse::<resources::BriefFormat>().map_err(|e| db_error("Invalid brief format", e))?,
+            None => resources::BriefFormat::default(),
+        };
+        let config = self.config();
+
+        let db = self.db.lock().await;
+        let brief = db::ProjectRepository::new(&db).get_brief(&uuid)
+            .map_err(|e| db_error("Failed to build project brief", e))?;
+
+        let text = resources::render_project_brief(&brief, format, config.tz(), config.brief_max_chars);
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
+    #[tool(description = "What someone should look at today, most urgent first: overdue milestones they lead, their action items due within three days or overdue, stakeholders on their projects without a note in stakeholder_contact_days days, blocked projects they manage and projects they manage or lead that are missing a lead, owner or due date. Each item has a kind, score, reason and a link such as milestone:<id> or person:<email> naming the record to open. Use it to brief the user on their day")]
+    async fn get_focus_list(&self, Parameters(req): Parameters<GetFocusListRequest>) -> Result<CallToolResult, McpError> {
+        let email = self.watcher_email(req.email)?;
+        let contact_days = self.config().stakeholder_contact_days;
+
+        let db = self.db.lock().await;
+        let focus = db::ProjectRepository::new(&db)
+            .with_stakeholder_contact_days(contact_days)
+            .get_focus_list(&email, chrono::Utc::now())
+            .map_err(|e| db_error("Failed to build focus list", e))?;
+
+        let json = serde_json::to_string_pretty(&focus)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Summarize project progress: milestone counts, percent complete, the next upcoming milestone and recent note activity. Completion is estimated from due dates that have passed, or from checklists when checklist_progress is enabled in the config.")]
+    async fn get_project_summaries(&self, Parameters(req): Parameters<GetProjectSummariesRequest>) -> Result<CallToolResult, McpError> {
+        let checklist_progress = self.config().checklist_progress;
+        let db = self.db.lock().await;
+        let repo = db::ProjectRepository::new(&db).with_checklist_progress(checklist_progress);
+
+        let summaries = match req.project_id {
+            Some(id) => {
+                let uuid = repo.resolve_project_ref(&id)
+                    .map_err(|e| db_error("Unknown project", e))?;
+                let summary = repo.get_project_summary(&uuid)
+                    .map_err(|e| db_error("Database error", e))?
+                    .ok_or_else(|| db_error("Database error", db::Error::not_found("Project", uuid)))?;
+                vec![summary]
+            }
+            None => repo.list_project_summaries()
+                .map_err(|e| db_error("Failed to summarize projects", e))?,
+        };
+
+        let json = serde_json::to_string_pretty(&summaries)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Quarter plan: milestones due in a calendar quarter (in the configured time zone), grouped by team and then project, with counts per team. A milestone without a team counts for its project's team; those with neither are in the last group, whose team is null")]
+    async fn get_quarter_plan(&self, Parameters(req): Parameters<GetQuarterPlanRequest>) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let repo = db::ProjectRepository::new(&db);
+        let plan = repo.get_quarter_plan(req.year, req.quarter, self.config().tz(), req.team.as_deref())
+            .map_err(|e| db_error("Failed to build quarter plan", e))?;
+
+        let json = serde_json::to_string_pretty(&plan)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Quarterly review of a team: projects delivered, milestones due and completed, milestones that slipped, people who joined or left and notable (high severity) risks. A team with nothing to report gets a short \"no activity\" review. Returns the review as JSON followed by a markdown rendering.")]
+    async fn generate_team_review(&self, Parameters(req): Parameters<GenerateTeamReviewRequest>) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let review = crate::report::team_quarterly_review(&db, &req.team, req.year, req.quarter, self.config().tz())
+            .map_err(|e| db_error("Failed to build team review", e))?;
+
+        let json = serde_json::to_string_pretty(&review)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let markdown = review.to_markdown(self.config().locale());
+
+        Ok(CallToolResult::success(vec![Content::text(json), Content::text(markdown)]))
+    }
+
+    #[tool(description = "Report how many times, and by how many days, each milestone of a project slipped")]
+    async fn get_slippage_report(&self, Parameters(req): Parameters<GetSlippageReportRequest>) -> Result<CallToolResult, McpError> {
+        let uuid = self.project_ref(&req.project_id).await?;
+
+        let db = self.db.lock().await;
+        let repo = db::ProjectRepository::new(&db);
+        let report = repo.get_slippage_report(&uuid)
+            .map_err(|e| db_error("Failed to build slippage report", e))?;
+
+        let json = serde_json::to_string_pretty(&report)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Compare estimated and actual days across a project's milestones: totals, variance (actual minus estimated) per milestone, and which milestones overran their estimate by more than the configured effort_warning_ratio")]
+    async fn get_effort_summary(&self, Parameters(req): Parameters<GetEffortSummaryRequest>) -> Result<CallToolResult, McpError> {
+        let uuid = self.project_ref(&req.project_id).await?;
+
+        let db = self.db.lock().await;
+        let repo = db::ProjectRepository::new(&db);
+        let mut summary = repo.get_effort_summary(&uuid, self.config().effort_warning_ratio)
+            .map_err(|e| db_error("Failed to build effort summary", e))?;
+        if req.sort_by_variance.unwrap_or(false) {
+            summary.sort_by_variance();
+        }
+        if let Some(limit) = req.limit {
+            summary.milestones.truncate(limit);
+        }
+
+        let json = serde_json::to_string_pretty(&summary)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Portfolio overview: projects by type and team, milestones due this month and quarter, unassigned people, teams without a manager, notes written in the last 7 days and average milestones per project")]
+    async fn get_portfolio_stats(&self) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let stats = db::stats::portfolio(&db)
+            .map_err(|e| db_error("Failed to compute portfolio statistics", e))?;
+
+        let json = serde_json::to_string_pretty(&stats)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Data hygiene report: active people without a manager or team, teams without a manager, projects without a technical lead, requirements owner or due date, milestones without a due date or technical lead, stakeholders whose email isn't a person, people outside the allowed email domains who aren't marked external, and completed projects without a retrospective. Each entry has the IDs needed to fix it with the update tools")]
+    async fn get_hygiene_report(&self) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let report = db::hygiene::find_gaps_with(&db, &self.config().hygiene.checks, &self.config().allowed_email_domains)
+            .map_err(|e| db_error("Failed to check the data", e))?;
+
+        let json = serde_json::to_string_pretty(&report)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Find projects with a filter expression or a saved query. Fields: name, type, team, manager, technical_lead, requirements_owner, jira_initiative, start_date, due_date, blocked, and milestone.name, milestone.number, milestone.team, milestone.technical_lead, milestone.jira_epic, milestone.start_date, milestone.due_date. Operators: = != < <= > >= IS [NOT] NULL, IN (...), AND, OR, NOT and parentheses. Strings are quoted, dates are YYYY-MM-DD, and all milestone conditions apply to the same milestone")]
+    async fn run_query(&self, Parameters(req): Parameters<RunQueryRequest>) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let projects = match (req.expression, req.name) {
+            (Some(expression), None) => db::query::run_query(&db, &expression),
+            (None, Some(name)) => db::SavedQueryRepository::new(&db).run(&name),
+            _ => return Err(McpError::invalid_params("Provide either an expression or the name of a saved query", None)),
+        }
+        .map_err(|e| db_error("Failed to run query", e))?;
+
+        let json = serde_json::to_string_pretty(&projects)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Save a filter expression under a name so it can be run later with run_query")]
+    async fn save_query(&self, Parameters(req): Parameters<SaveQueryRequest>) -> Result<CallToolResult, McpError> {
+        self.write(move |db, _| {
+            let saved = db::SavedQueryRepository::new(db).save(&req.name, &req.expression)
+                .map_err(|e| db_error("Failed to save query", e))?;
+
+            let json = serde_json::to_string_pretty(&saved)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "List saved queries by name")]
+    async fn list_saved_queries(&self) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let saved = db::SavedQueryRepository::new(&db).list_all()
+            .map_err(|e| db_error("Failed to list saved queries", e))?;
+
+        let json = serde_json::to_string_pretty(&saved)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Delete a saved query")]
+    async fn delete_saved_query(&self, Parameters(req): Parameters<DeleteSavedQueryRequest>) -> Result<CallToolResult, McpError> {
+        self.write(move |db, _| {
+            db::SavedQueryRepository::new(db).delete(&req.name)
+                .map_err(|e| db_error("Failed to delete saved query", e))?;
+
+            Ok(CallToolResult::success(vec![Content::text(format!("Deleted saved query {}", req.name.trim()))]))
+        }).await
+    }
+
+    #[tool(description = "List the users of a shared database and their roles; while nobody is listed, everyone is an admin")]
+    async fn list_users(&self) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let users = db::UserRepository::new(&db).list_all()
+            .map_err(|e| db_error("Failed to list users", e))?;
+
+        let json = serde_json::to_string_pretty(&users)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Add a user or change their role (viewer, editor or admin); admins only, and the first user must be an admin")]
+    async fn set_user_role(&self, Parameters(req): Parameters<SetUserRoleRequest>) -> Result<CallToolResult, McpError> {
+        let role = req.role.parse::<db::UserRole>()
+            .map_err(|e| db_error("Invalid role", e))?;
+        self.write(move |db, ctx| {
+            let user = db::UserRepository::new(db)
+                .set_role(ctx.config.current_user_email.as_deref(), &req.email, role)
+                .map_err(|e| db_error("Failed to set user role", e))?;
+
+            let json = serde_json::to_string_pretty(&user)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Remove a user; admins only. Removing the last user turns permissions off")]
+    async fn remove_user(&self, Parameters(req): Parameters<RemoveUserRequest>) -> Result<CallToolResult, McpError> {
+        self.write(move |db, ctx| {
+            db::UserRepository::new(db)
+                .remove(ctx.config.current_user_email.as_deref(), &req.email)
+                .map_err(|e| db_error("Failed to remove user", e))?;
+
+            Ok(CallToolResult::success(vec![Content::text(format!("Removed user {}", req.email.trim()))]))
+        }).await
+    }
+
+    #[tool(description = "List the custom fields that can be set on projects, with their keys, labels and types")]
+    async fn list_custom_fields(&self) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let fields = db::CustomFieldRepository::new(&db).list_custom_fields()
+            .map_err(|e| db_error("Failed to list custom fields", e))?;
+
+        let json = serde_json::to_string_pretty(&fields)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Define a custom project field, or change the label of an existing one. A field's type can only change while no project has a value for it")]
+    async fn define_custom_field(&self, Parameters(req): Parameters<DefineCustomFieldRequest>) -> Result<CallToolResult, McpError> {
+        let field_type: db::CustomFieldType = req.field_type.parse()
+            .map_err(|e| db_error("Invalid field type", e))?;
+
+        self.write(move |db, _| {
+            let field = db::CustomFieldRepository::new(db).define_custom_field(&req.key, &req.label, field_type)
+                .map_err(|e| db_error("Failed to define custom field", e))?;
+
+            let json = serde_json::to_string_pretty(&field)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Delete a custom project field. Fields that projects have values for are only deleted with force, which deletes the values too")]
+    async fn delete_custom_field(&self, Parameters(req): Parameters<DeleteCustomFieldRequest>) -> Result<CallToolResult, McpError> {
+        self.write(move |db, _| {
+            let removed = db::CustomFieldRepository::new(db).delete_custom_field(&req.key, req.force)
+                .map_err(|e| db_error("Failed to delete custom field", e))?;
+
+            Ok(CallToolResult::success(vec![Content::text(format!(
+                "Deleted custom field {} and {} value(s)",
+                req.key.trim(),
+                removed
+            ))]))
+        }).await
+    }
+
+    #[tool(description = "Set or clear a project's value for a custom field. Returns all of the project's custom field values")]
+    async fn set_project_custom_field(&self, Parameters(req): Parameters<SetProjectCustomFieldRequest>) -> Result<CallToolResult, McpError> {
+        let uuid = self.project_ref(&req.project_id).await?;
+
+        self.write(move |db, _| {
+            let repo = db::CustomFieldRepository::new(db);
+            repo.set_custom_value(&uuid, &req.key, req.value.as_deref())
+                .map_err(|e| db_error("Failed to set custom field", e))?;
+            let values = repo.get_custom_values(&uuid)
+                .map_err(|e| db_error("Failed to load custom fields", e))?;
+
+            let json = serde_json::to_string_pretty(&values)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Create a new project. Projects of a type with a bootstrap rule in the configuration also get its milestones, notes and stakeholders, unless skip_bootstrap is set")]
+    async fn create_project(&self, Parameters(req): Parameters<CreateProjectRequest>) -> Result<CallToolResult, McpError> {
+        let mut project = db::Project::new(req.name);
+
+        if let Some(desc) = req.description {
+            project.description = Some(desc);
+        }
+        if let Some(ptype) = req.project_type {
+            project.project_type = ptype;
+        }
+        if let Some(jira) = req.jira_initiative {
+            project.jira_initiative = Some(jira);
+        }
+        project.budget_amount = req.budget_amount;
+        project.budget_currency = req.budget_currency;
+        if let Some(status) = req.status {
+            project.status = status.parse().map_err(|e| db_error("Invalid status", e))?;
+        }
+        project.phase = req.phase;
+
+        self.write(move |db, ctx| {
+            let mut service = ProjectService::new(db, &ctx.webhooks)
+                .with_project_types(&ctx.config.project_types)
+                .with_phases(&ctx.config.project_phases)
+                .with_creator(ctx.config.current_user_email.as_deref());
+            if !req.skip_bootstrap.unwrap_or(false) {
+                service = service.with_bootstrap(&ctx.config.bootstrap);
+            }
+            match service.create_project(&project) {
+                Ok(()) => {
+                    // The stored slug carries a suffix if another project had the same one,
+                    // and the phase takes its configured spelling
+                    if let Some(created) = db::ProjectRepository::new(db).find_by_id(&project.id)
+                        .map_err(|e| db_error("Database error", e))?
+                    {
+                        project.slug = created.slug;
+                        project.phase = created.phase;
+                    }
+                }
+                Err(db::Error::AlreadyExists { ref id, .. }) if req.if_exists == Some(IfExists::ReturnExisting) => {
+                    let existing_id = Uuid::parse_str(id)
+                        .map_err(|e| McpError::internal_error("Invalid project ID", Some(serde_json::json!({"error": e.to_string()}))))?;
+                    project = db::ProjectRepository::new(db).find_by_id(&existing_id)
+                        .map_err(|e| db_error("Database error", e))?
+                        .ok_or_else(|| db_error("Database error", db::Error::not_found("Project", existing_id)))?;
+                }
+                Err(e) => return Err(db_error("Failed to create project", e)),
+            }
+
+            let json = serde_json::to_string_pretty(&project)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Update a project")]
+    async fn update_project(&self, Parameters(req): Parameters<UpdateProjectRequest>) -> Result<CallToolResult, McpError> {
+        let uuid = self.project_ref(&req.id).await?;
+
+        self.write(move |db, ctx| {
+            let repo = db::ProjectRepository::new(db);
+
+            // Fetch existing project first
+            let mut project = repo.find_by_id(&uuid)
+                .map_err(|e| db_error("Database error", e))?
+                .ok_or_else(|| db_error("Database error", db::Error::not_found("Project", uuid)))?;
+
+            project.version = expected_version(&ctx.config, req.version, project.version)?;
+
+            // Update fields
+            project.name = req.name;
+            project.description = req.description;
+            if let Some(ptype) = req.project_type {
+                project.project_type = ptype;
+            }
+            project.requirements_owner = req.requirements_owner;
+            project.technical_lead = req.technical_lead;
+            project.manager = req.manager;
+            project.team = req.team;
+            project.jira_initiative = req.jira_initiative;
+            project.budget_amount = req.budget_amount;
+            project.budget_currency = req.budget_currency;
+            if let Some(status) = req.status {
+                project.status = status.parse().map_err(|e| db_error("Invalid status", e))?;
+            }
+            if let Some(phase) = req.phase {
+                project.phase = Some(phase);
+            }
+
+            // Parse dates if provided
+            if let Some(start_date_str) = req.start_date {
+                project.start_date = Some(utils::parse_user_date(&start_date_str, ctx.config.tz())
+                    .map_err(|e| McpError::invalid_params("Invalid start_date format", Some(serde_json::json!({"error": e.to_string()}))))?);
+            }
+            if let Some(due_date_str) = req.due_date {
+                project.due_date = Some(utils::parse_user_date(&due_date_str, ctx.config.tz())
+                    .map_err(|e| McpError::invalid_params("Invalid due_date format", Some(serde_json::json!({"error": e.to_string()}))))?);
+            }
+
+            ProjectService::new(db, &ctx.webhooks)
+                .with_project_types(&ctx.config.project_types)
+                .with_phases(&ctx.config.project_phases)
+                .update_project(&project)
+                .map_err(|e| db_error("Failed to update project", e))?;
+            let project = repo.find_by_id(&uuid)
+                .map_err(|e| db_error("Database error", e))?
+                .ok_or_else(|| db_error("Database error", db::Error::not_found("Project", uuid)))?;
+
+            let json = serde_json::to_string_pretty(&project)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Delete a project")]
+    async fn delete_project(&self, Parameters(req): Parameters<DeleteProjectRequest>) -> Result<CallToolResult, McpError> {
+        let uuid = self.project_ref(&req.id).await?;
+        let action = format!("delete_project:{}", uuid);
+        if let Some(pending) = self.confirm_delete(action, req.confirmation_token.as_deref(), |db| db::deletion::project_impact(db, &uuid)).await? {
+            return Ok(pending);
+        }
+
+        self.write(move |db, ctx| {
+            let service = ProjectService::new(db, &ctx.webhooks);
+            service.delete_project(&uuid)
+                .map_err(|e| db_error("Failed to delete project", e))?;
+
+            Ok(CallToolResult::success(vec![Content::text(format!("Deleted project {}", req.id))]))
+        }).await
+    }
+
+    #[tool(description = "Mark a project as blocked, with the reason. The change is kept in the project's blocker history")]
+    async fn block_project(&self, Parameters(req): Parameters<BlockProjectRequest>) -> Result<CallToolResult, McpError> {
+        let uuid = self.project_ref(&req.project_id).await?;
+        if req.reason.trim().is_empty() {
+            return Err(McpError::invalid_params("A reason is required to block a project", None));
+        }
+
+        self.write(move |db, ctx| {
+            let project = ProjectService::new(db, &ctx.webhooks)
+                .block_project(&uuid, req.reason.trim())
+                .map_err(|e| db_error("Failed to block project", e))?;
+
+            let json = serde_json::to_string_pretty(&project)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Clear a project's blocker")]
+    async fn unblock_project(&self, Parameters(req): Parameters<UnblockProjectRequest>) -> Result<CallToolResult, McpError> {
+        let uuid = self.project_ref(&req.project_id).await?;
+
+        self.write(move |db, ctx| {
+            let project = ProjectService::new(db, &ctx.webhooks)
+                .unblock_project(&uuid)
+                .map_err(|e| db_error("Failed to unblock project", e))?;
+
+            let json = serde_json::to_string_pretty(&project)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Move a project to a phase, one of the configured project_phases such as Discovery, Design, Build, Rollout or Hypercare. Phase is separate from status. The change is kept in the project's phase history; moving back to an earlier phase is allowed but flagged")]
+    async fn set_project_phase(&self, Parameters(req): Parameters<SetProjectPhaseRequest>) -> Result<CallToolResult, McpError> {
+        let uuid = self.project_ref(&req.project_id).await?;
+
+        self.write(move |db, ctx| {
+            let project = ProjectService::new(db, &ctx.webhooks)
+                .with_phases(&ctx.config.project_phases)
+                .set_project_phase(&uuid, &req.phase)
+                .map_err(|e| db_error("Failed to set project phase", e))?;
+
+            let json = serde_json::to_string_pretty(&project)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Get a project's phase history, oldest first: each change with from_phase, to_phase, changed_at and whether it was a move backwards, plus the days spent in each phase")]
+    async fn get_phase_history(&self, Parameters(req): Parameters<GetPhaseHistoryRequest>) -> Result<CallToolResult, McpError> {
+        let uuid = self.project_ref(&req.project_id).await?;
+        let db = self.db.lock().await;
+        let repo = db::ProjectRepository::new(&db);
+        let history = repo.get_phase_history(&uuid).map_err(|e| db_error("Failed to get phase history", e))?;
+        let durations = db::project_repo::phase_durations(&history, chrono::Utc::now());
+
+        let json = serde_json::to_string_pretty(&serde_json::json!({"history": history, "durations": durations}))
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Save the retrospective of a finished project: what went well, what needs improvement and the actions agreed. A project has one retrospective; saving again updates it, and fields left out keep their saved value. Completed projects without one are flagged by get_hygiene_report")]
+    async fn save_retrospective(&self, Parameters(req): Parameters<SaveRetrospectiveRequest>) -> Result<CallToolResult, McpError> {
+        let uuid = self.project_ref(&req.project_id).await?;
+        let completed_on = req.completed_on
+            .map(|completed_on| utils::parse_user_date(&completed_on, self.config().tz()))
+            .transpose()
+            .map_err(|e| McpError::invalid_params("Invalid completed_on format", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        self.write(move |db, _| {
+            let repo = db::RetrospectiveRepository::new(db);
+            let mut retrospective = repo.find_by_project(&uuid)
+                .map_err(|e| db_error("Database error", e))?
+                .unwrap_or_else(|| db::Retrospective::new(uuid, chrono::Utc::now()));
+            if let Some(completed_on) = completed_on {
+                retrospective.completed_on = completed_on;
+            }
+            if req.went_well.is_some() {
+                retrospective.went_well = req.went_well;
+            }
+            if req.needs_improvement.is_some() {
+                retrospective.needs_improvement = req.needs_improvement;
+            }
+            if req.action_summary.is_some() {
+                retrospective.action_summary = req.action_summary;
+            }
+            retrospective.updated_at = chrono::Utc::now();
+            let retrospective = repo.save(&retrospective)
+                .map_err(|e| db_error("Failed to save retrospective", e))?;
+
+            let json = serde_json::to_string_pretty(&retrospective)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Get a project's retrospective")]
+    async fn get_retrospective(&self, Parameters(req): Parameters<GetRetrospectiveRequest>) -> Result<CallToolResult, McpError> {
+        let uuid = self.project_ref(&req.project_id).await?;
+        let db = self.db.lock().await;
+        let retrospective = db::RetrospectiveRepository::new(&db).find_by_project(&uuid)
+            .map_err(|e| db_error("Failed to get retrospective", e))?
+            .ok_or_else(|| McpError::invalid_params("No retrospective saved for this project", Some(serde_json::json!({"project_id": req.project_id}))))?;
+
+        let json = serde_json::to_string_pretty(&retrospective)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    // Decision tools
+
+    #[tool(description = "Record a decision made on a project, with the context behind it and who approved it. Decisions are accepted unless status is proposed. Returns the decision")]
+    async fn record_decision(&self, Parameters(req): Parameters<RecordDecisionRequest>) -> Result<CallToolResult, McpError> {
+        let uuid = self.project_ref(&req.project_id).await?;
+        let status = match req.status.as_deref() {
+            Some(status) => status.parse::<db::DecisionStatus>()
+                .map_err(|e| McpError::invalid_params(e.to_string(), None))?,
+            None => db::DecisionStatus::Accepted,
+        };
+        let decided_on = req.decided_on
+            .map(|decided_on| utils::parse_user_date(&decided_on, self.config().tz()))
+            .transpose()
+            .map_err(|e| McpError::invalid_params("Invalid decided_on format", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        let mut decision = db::Decision::new(uuid, req.title, req.decision);
+        decision.context = req.context;
+        decision.status = status;
+        decision.decided_on = decided_on.or((status == db::DecisionStatus::Accepted).then(chrono::Utc::now));
+        decision.approvers = req.approvers.unwrap_or_default();
+
+        self.write(move |db, _| {
+            let repo = db::DecisionRepository::new(db);
+            repo.create(&decision)
+                .map_err(|e| db_error("Failed to record decision", e))?;
+            let decision = repo.find_by_id(&decision.id)
+                .map_err(|e| db_error("Database error", e))?
+                .ok_or_else(|| McpError::internal_error("Decision not found after creation", None))?;
+
+            let json = serde_json::to_string_pretty(&decision)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "List a project's decisions, oldest first, including superseded ones with the ID of the decision that replaced them")]
+    async fn list_decisions(&self, Parameters(req): Parameters<ListDecisionsRequest>) -> Result<CallToolResult, McpError> {
+        let uuid = self.project_ref(&req.project_id).await?;
+        let db = self.db.lock().await;
+        let decisions = db::DecisionRepository::new(&db).list_decisions(&uuid)
+            .map_err(|e| db_error("Failed to list decisions", e))?;
+
+        let json = serde_json::to_string_pretty(&decisions)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Replace a decision with a later one on the same project. The old decision is marked superseded and links to the new one; a proposed replacement is accepted. Returns the old decision")]
+    async fn supersede_decision(&self, Parameters(req): Parameters<SupersedeDecisionRequest>) -> Result<CallToolResult, McpError> {
+        let parse = |id: &str| Uuid::parse_str(id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string(), "id": id}))));
+        let old_uuid = parse(&req.old_id)?;
+        let new_uuid = parse(&req.new_id)?;
+
+        self.write(move |db, _| {
+            let old = db::DecisionRepository::new(db).supersede_decision(&old_uuid, &new_uuid)
+                .map_err(|e| db_error("Failed to supersede decision", e))?;
+
+            let json = serde_json::to_string_pretty(&old)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    // Organization tools
+
+    #[tool(description = "Create an organization, such as a vendor or client, that people can belong to. Set a person's organization with create_person or update_person. Returns the organization")]
+    async fn create_organization(&self, Parameters(req): Parameters<CreateOrganizationRequest>) -> Result<CallToolResult, McpError> {
+        let mut organization = db::Organization::new(req.name.trim().to_string());
+        if let Some(kind) = req.kind.as_deref() {
+            organization.kind = kind.parse::<db::OrganizationKind>()
+                .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+        }
+        organization.notes = req.notes;
+
+        self.write(move |db, _| {
+            db::OrganizationRepository::new(db).create(&organization)
+                .map_err(|e| db_error("Failed to create organization", e))?;
+
+            let json = serde_json::to_string_pretty(&organization)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "List all organizations by name")]
+    async fn list_organizations(&self) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let organizations = db::OrganizationRepository::new(&db).list_all()
+            .map_err(|e| db_error("Failed to list organizations", e))?;
+
+        let json = serde_json::to_string_pretty(&organizations)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "An organization with its people and every project they are stakeholders, project resources or milestone resources on, listing who is involved in each project and how. Use it to answer which projects a vendor or client touches")]
+    async fn get_organization_overview(&self, Parameters(req): Parameters<GetOrganizationOverviewRequest>) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let overview = db::OrganizationRepository::new(&db).get_overview(&req.name)
+            .map_err(|e| db_error("Failed to get organization overview", e))?;
+
+        let json = serde_json::to_string_pretty(&overview)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Watch a project so it appears in the watcher's digest, reminders and watched project list. Returns the project's watchers")]
+    async fn watch_project(&self, Parameters(req): Parameters<WatchProjectRequest>) -> Result<CallToolResult, McpError> {
+        let uuid = self.project_ref(&req.project_id).await?;
+        let email = self.watcher_email(req.email)?;
+
+        self.write(move |db, _| {
+            let repo = db::WatcherRepository::new(db);
+            repo.watch(&uuid, &email).map_err(|e| db_error("Failed to watch project", e))?;
+            let watchers = repo.list_watchers(&uuid).map_err(|e| db_error("Database error", e))?;
+
+            let json = serde_json::to_string_pretty(&watchers)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Stop watching a project. Other people's watches are kept. Returns the project's remaining watchers")]
+    async fn unwatch_project(&self, Parameters(req): Parameters<UnwatchProjectRequest>) -> Result<CallToolResult, McpError> {
+        let uuid = self.project_ref(&req.project_id).await?;
+        let email = self.watcher_email(req.email)?;
+
+        self.write(move |db, _| {
+            let repo = db::WatcherRepository::new(db);
+            repo.unwatch(&uuid, &email).map_err(|e| db_error("Failed to unwatch project", e))?;
+            let watchers = repo.list_watchers(&uuid).map_err(|e| db_error("Database error", e))?;
+
+            let json = serde_json::to_string_pretty(&watchers)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Link a project or milestone to a ticket in another tracker, such as a GitHub issue or Linear ticket. Without a url, the link is built from the system's template in external_ref_urls (jira uses jira_url). Adding a key the record already has only updates its url. Returns the record's references")]
+    async fn add_external_ref(&self, Parameters(req): Parameters<AddExternalRefRequest>) -> Result<CallToolResult, McpError> {
+        let (target, id) = self.external_ref_target(&req.entity_type, &req.entity_id).await?;
+
+        self.write(move |db, ctx| {
+            let templates = ctx.config.external_ref_url_templates();
+            let repo = db::ExternalRefRepository::new(db).with_url_templates(&templates);
+            repo.add(target, &id, &req.system, &req.key, req.url.as_deref())
+                .map_err(|e| db_error("Failed to add external reference", e))?;
+            let refs = repo.get_external_refs(target, &id).map_err(|e| db_error("Database error", e))?;
+
+            let json = serde_json::to_string_pretty(&refs)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "List the tickets in other trackers linked to a project or milestone. A project's jira_initiative and a milestone's jira_epic are included as jira references without an id")]
+    async fn list_external_refs(&self, Parameters(req): Parameters<ListExternalRefsRequest>) -> Result<CallToolResult, McpError> {
+        let (target, id) = self.external_ref_target(&req.entity_type, &req.entity_id).await?;
+        let templates = self.config().external_ref_url_templates();
+
+        let db = self.db.lock().await;
+        let refs = db::ExternalRefRepository::new(&db).with_url_templates(&templates)
+            .get_external_refs(target, &id)
+            .map_err(|e| db_error("Failed to list external references", e))?;
+
+        let json = serde_json::to_string_pretty(&refs)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Unlink a project or milestone from a ticket in another tracker. Removing a jira key that is the project's jira_initiative or the milestone's jira_epic clears that field. Returns the record's remaining references")]
+    async fn remove_external_ref(&self, Parameters(req): Parameters<RemoveExternalRefRequest>) -> Result<CallToolResult, McpError> {
+        let (target, id) = self.external_ref_target(&req.entity_type, &req.entity_id).await?;
+
+        self.write(move |db, ctx| {
+            let templates = ctx.config.external_ref_url_templates();
+            let repo = db::ExternalRefRepository::new(db).with_url_templates(&templates);
+            repo.remove(target, &id, &req.system, &req.key)
+                .map_err(|e| db_error("Failed to remove external reference", e))?;
+            let refs = repo.get_external_refs(target, &id).map_err(|e| db_error("Database error", e))?;
+
+            let json = serde_json::to_string_pretty(&refs)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "List the projects in a phase, ordered by name")]
+    async fn list_projects_by_phase(&self, Parameters(req): Parameters<ListProjectsByPhaseRequest>) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let repo = db::ProjectRepository::new(&db);
+        let projects = repo.list_by_phase(&req.phase).map_err(|e| db_error("Failed to list projects by phase", e))?;
+
+        self.with_handles(HandleKind::Project, &projects)
+    }
+
+    #[tool(description = "Change a project's slug, the short name that can be used instead of its ID. Renaming a project keeps its slug; omit `slug` to make a new one from the current name")]
+    async fn set_project_slug(&self, Parameters(req): Parameters<SetProjectSlugRequest>) -> Result<CallToolResult, McpError> {
+        let uuid = self.project_ref(&req.project_id).await?;
+
+        self.write(move |db, ctx| {
+            let slug = ProjectService::new(db, &ctx.webhooks)
+                .set_slug(&uuid, req.slug.as_deref())
+                .map_err(|e| db_error("Failed to set project slug", e))?;
+
+            Ok(CallToolResult::success(vec![Content::text(
+                serde_json::json!({"project_id": uuid, "slug": slug}).to_string(),
+            )]))
+        }).await
+    }
+
+    #[tool(description = "List the blocked projects and why each is blocked")]
+    async fn list_blocked_projects(&self) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let repo = db::ProjectRepository::new(&db);
+        let projects = repo.list_blocked().map_err(|e| db_error("Failed to list blocked projects", e))?;
+
+        self.with_handles(HandleKind::Project, &projects)
+    }
+
+    #[tool(description = "Get the project board: one column per status (planning, active, on_hold, done), each listing its projects in board order")]
+    async fn get_board(&self) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let repo = db::ProjectRepository::new(&db);
+        let board = repo.get_board().map_err(|e| db_error("Failed to get the board", e))?;
+
+        let json = serde_json::to_string_pretty(&board)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    // People tools
+
+    #[tool(description = "List all people (inactive people are excluded unless include_inactive is true)")]
+    async fn list_people(&self, Parameters(req): Parameters<ListPeopleRequest>) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let repo = db::PersonRepository::new(&db);
+        let people = repo.list_all(req.include_inactive.unwrap_or(false))
+            .map_err(|e| db_error("Failed to list people", e))?;
+
+        let json = serde_json::to_string_pretty(&people)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Search people by name (inactive people are excluded unless include_inactive is true)")]
+    async fn search_people(&self, Parameters(req): Parameters<SearchPeopleRequest>) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let repo = db::PersonRepository::new(&db);
+        let people = repo.search_by_name(&req.query, req.include_inactive.unwrap_or(false))
+            .map_err(|e| db_error("Search failed", e))?;
+
+        let json = serde_json::to_string_pretty(&people)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Get a person by email, with their most recent notes as recent_notes")]
+    async fn get_person(&self, Parameters(req): Parameters<GetPersonRequest>) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let repo = db::PersonRepository::new(&db);
+        let person = repo.find_by_email(&req.email)
+            .map_err(|e| db_error("Database error", e))?;
+        let person = match person {
+            Some(person) => {
+                let note_limit = req.note_limit.unwrap_or(db::person_repo::DEFAULT_RECENT_NOTE_LIMIT);
+                let recent_notes = repo.list_notes(&person.email, Some(note_limit))
+                    .map_err(|e| db_error("Failed to list notes", e))?;
+                let mut value = serde_json::to_value(&person)
+                    .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+                value["recent_notes"] = serde_json::json!(recent_notes);
+                value
+            }
+            None => serde_json::Value::Null,
+        };
+
+        let json = serde_json::to_string_pretty(&person)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Create a new person. If their name closely matches someone who already exists, nothing is created and the error lists the matches under possible_duplicates; check whether the person is already in the tracker, and pass force to create them anyway")]
+    async fn create_person(&self, Parameters(req): Parameters<CreatePersonRequest>) -> Result<CallToolResult, McpError> {
+        let mut person = db::Person::new(req.email, req.name);
+
+        if let Some(team) = req.team {
+            person.team = Some(team);
+        }
+        person.external = req.external;
+        person.organization = req.organization;
+
+        self.write(move |db, ctx| {
+            let mut repo = db::PersonRepository::new(db).with_allowed_domains(&ctx.config.allowed_email_domains);
+            if !req.force {
+                repo = repo.with_duplicate_check(ctx.config.person_duplicate_threshold);
+            }
+            repo.create(&person)
+                .map_err(|e| db_error("Failed to create person", e))?;
+
+            let json = serde_json::to_string_pretty(&person)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Update a person")]
+    async fn update_person(&self, Parameters(req): Parameters<UpdatePersonRequest>) -> Result<CallToolResult, McpError> {
+        self.write(move |db, ctx| {
+            let repo = db::PersonRepository::new(db).with_allowed_domains(&ctx.config.allowed_email_domains);
+
+            // Fetch existing person first
+            let mut person = repo.find_by_email(&req.email)
+                .map_err(|e| db_error("Database error", e))?
+                .ok_or_else(|| db_error("Database error", db::Error::not_found("Person", &req.email)))?;
+
+            person.version = expected_version(&ctx.config, req.version, person.version)?;
+
+            // Update fields
+            person.name = req.name;
+            person.team = req.team;
+            person.manager = req.manager;
+            if let Some(external) = req.external {
+                person.external = external;
+            }
+            if let Some(organization) = req.organization {
+                person.organization = Some(organization).filter(|o| !o.trim().is_empty());
+            }
+            let mut warnings = Vec::new();
+            if let Some(notes) = req.notes {
+                person.notes = Some(notes);
+                warnings.push("The notes field is deprecated; add timestamped notes with create_person_note instead".to_string());
+            }
+
+            repo.update(&person)
+                .map_err(|e| db_error("Failed to update person", e))?;
+            person.version += 1;
+
+            let json = serde_json::to_string_pretty(&person)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(with_warnings(json, warnings)))
+        }).await
+    }
+
+    #[tool(description = "Delete a person")]
+    async fn delete_person(&self, Parameters(req): Parameters<DeletePersonRequest>) -> Result<CallToolResult, McpError> {
+        let action = format!("delete_person:{}", req.email);
+        if let Some(pending) = self.confirm_delete(action, req.confirmation_token.as_deref(), |db| db::deletion::person_impact(db, &req.email)).await? {
+            return Ok(pending);
+        }
+        let data_dir = self.config().workspace_dir(&self.workspace());
+        self.write(move |db, _| {
+            let repo = db::PersonRepository::new(db);
+            let person = repo.find_by_email(&req.email)
+                .map_err(|e| db_error("Database error", e))?;
+            repo.delete(&req.email, req.delete_one_on_ones.unwrap_or(false))
+                .map_err(|e| db_error("Failed to delete person", e))?;
+
+            if let Some(avatar_path) = person.and_then(|p| p.avatar_path) {
+                let removed = data_dir
+                    .and_then(Storage::new)
+                    .and_then(|storage| storage.remove_avatar(&avatar_path));
+                if let Err(e) = removed {
+                    log::warn!("Failed to remove avatar {} of {}: {:#}", avatar_path, req.email, e);
+                }
+            }
+
+            Ok(CallToolResult::success(vec![Content::text(format!("Deleted person {}", req.email))]))
+        }).await
+    }
+
+    #[tool(description = "Deactivate a person who has left, hiding them from lists and search. Returns the projects where they are still technical lead, manager or requirements owner so they can be reassigned")]
+    async fn deactivate_person(&self, Parameters(req): Parameters<DeactivatePersonRequest>) -> Result<CallToolResult, McpError> {
+        self.write(move |db, _| {
+            let repo = db::PersonRepository::new(db);
+            let result = repo.deactivate(&req.email, req.remove_future_assignments.unwrap_or(false))
+                .map_err(|e| db_error("Failed to deactivate person", e))?;
+
+            let json = serde_json::to_string_pretty(&result)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Change a person's email address, updating every project, milestone, team, note and assignment that refers to them. Fails if someone already has the new address, or if it is outside the allowed email domains and the person isn't external")]
+    async fn change_person_email(&self, Parameters(req): Parameters<ChangePersonEmailRequest>) -> Result<CallToolResult, McpError> {
+        self.write(move |db, ctx| {
+            let repo = db::PersonRepository::new(db).with_allowed_domains(&ctx.config.allowed_email_domains);
+            let person = repo.change_email(&req.old_email, &req.new_email)
+                .map_err(|e| db_error("Failed to change email", e))?;
+
+            let json = serde_json::to_string_pretty(&person)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Reactivate a previously deactivated person")]
+    async fn reactivate_person(&self, Parameters(req): Parameters<ReactivatePersonRequest>) -> Result<CallToolResult, McpError> {
+        self.write(move |db, _| {
+            let repo = db::PersonRepository::new(db);
+            let person = repo.reactivate(&req.email)
+                .map_err(|e| db_error("Failed to reactivate person", e))?;
+
+            let json = serde_json::to_string_pretty(&person)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    // Person Skill tools
+
+    #[tool(description = "Record that a person has a skill, optionally with a 1-5 level. Adding a skill again is not an error: a new level replaces the old one")]
+    async fn add_person_skill(&self, Parameters(req): Parameters<AddPersonSkillRequest>) -> Result<CallToolResult, McpError> {
+        self.write(move |db, _| {
+            let repo = db::PersonRepository::new(db);
+            let skill = repo.add_skill(&req.person_email, &req.skill, req.level)
+                .map_err(|e| db_error("Failed to add skill", e))?;
+
+            let json = serde_json::to_string_pretty(&skill)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Remove a skill from a person")]
+    async fn remove_person_skill(&self, Parameters(req): Parameters<RemovePersonSkillRequest>) -> Result<CallToolResult, McpError> {
+        self.write(move |db, _| {
+            let repo = db::PersonRepository::new(db);
+            repo.remove_skill(&req.person_email, &req.skill)
+                .map_err(|e| db_error("Failed to remove skill", e))?;
+
+            Ok(CallToolResult::success(vec![Content::text(format!("Removed skill {} from {}", req.skill, req.person_email))]))
+        }).await
+    }
+
+    #[tool(description = "Find active people with a skill, the most proficient first. With min_level, people whose level isn't rated are left out")]
+    async fn find_people_by_skill(&self, Parameters(req): Parameters<FindPeopleBySkillRequest>) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let repo = db::PersonRepository::new(&db);
+        let people = repo.find_people_by_skill(&req.skill, req.min_level)
+            .map_err(|e| db_error("Failed to find people", e))?;
+
+        let json = serde_json::to_string_pretty(&people)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "When active people free up from the milestones they are assigned to, grouped by team: unassigned people, people whose last open milestone is due within horizon_days (with the date), people busy past it, and people only on undated milestones (unknown). Use it to staff upcoming work")]
+    async fn get_availability_forecast(&self, Parameters(req): Parameters<GetAvailabilityForecastRequest>) -> Result<CallToolResult, McpError> {
+        let horizon_days = req.horizon_days.unwrap_or(db::person_repo::DEFAULT_FORECAST_DAYS);
+
+        let db = self.db.lock().await;
+        let forecast = db::PersonRepository::new(&db)
+            .get_availability_forecast(horizon_days, chrono::Utc::now())
+            .map_err(|e| db_error("Failed to forecast availability", e))?;
+
+        let json = serde_json::to_string_pretty(&forecast)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    // Person Note tools
+
+    #[tool(description = "Create a timestamped note about a person")]
+    async fn create_person_note(&self, Parameters(req): Parameters<CreatePersonNoteRequest>) -> Result<CallToolResult, McpError> {
+        let note = db::PersonNote::new(req.person_email, req.title, req.body);
+
+        self.write(move |db, _| {
+            let repo = db::PersonRepository::new(db);
+            repo.add_note(&note)
+                .map_err(|e| db_error("Failed to create note", e))?;
+
+            let json = serde_json::to_string_pretty(&note)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "List notes about a person, newest first")]
+    async fn list_person_notes(&self, Parameters(req): Parameters<ListPersonNotesRequest>) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let repo = db::PersonRepository::new(&db);
+        let notes = repo.list_notes(&req.person_email, req.limit)
+            .map_err(|e| db_error("Failed to list notes", e))?;
+
+        let json = serde_json::to_string_pretty(&notes::with_html(notes, req.rendered.unwrap_or(false), req.max_chars))
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Update a person note")]
+    async fn update_person_note(&self, Parameters(req): Parameters<UpdatePersonNoteRequest>) -> Result<CallToolResult, McpError> {
+        let note_uuid = Uuid::parse_str(&req.id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        self.write(move |db, ctx| {
+            let repo = db::PersonRepository::new(db);
+            let mut note = repo.find_note_by_id(&note_uuid)
+                .map_err(|e| db_error("Database error", e))?
+                .ok_or_else(|| db_error("Database error", db::Error::not_found("Person note", note_uuid)))?;
+
+            note.version = expected_version(&ctx.config, req.version, note.version)?;
+            note.title = req.title;
+            note.body = req.body;
+            note.updated_at = chrono::Utc::now();
+
+            repo.update_note(&note)
+                .map_err(|e| db_error("Failed to update note", e))?;
+            note.version += 1;
+
+            let json = serde_json::to_string_pretty(&note)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Delete a person note")]
+    async fn delete_person_note(&self, Parameters(req): Parameters<DeletePersonNoteRequest>) -> Result<CallToolResult, McpError> {
+        let note_uuid = Uuid::parse_str(&req.id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        self.write(move |db, _| {
+            let repo = db::PersonRepository::new(db);
+            repo.delete_note(&note_uuid)
+                .map_err(|e| db_error("Failed to delete note", e))?;
+
+            Ok(CallToolResult::success(vec![Content::text(format!("Deleted note {}", req.id))]))
+        }).await
+    }
+
+    // 1:1 tools
+
+    #[tool(description = "Record a 1:1 with a person: agenda, discussion notes and follow-ups. Follow-ups can also be turned into action items for the person on a project")]
+    async fn log_one_on_one(&self, Parameters(req): Parameters<LogOneOnOneRequest>) -> Result<CallToolResult, McpError> {
+        let occurred_at = match req.occurred_at {
+            Some(occurred_at) => utils::parse_user_date(&occurred_at, self.config().tz())
+                .map_err(|e| McpError::invalid_params("Invalid occurred_at format", Some(serde_json::json!({"error": e.to_string()}))))?,
+            None => chrono::Utc::now(),
+        };
+        let project_uuid = match req.action_item_project {
+            Some(project) => Some(self.project_ref(&project).await?),
+            None => None,
+        };
+
+        let mut one_on_one = db::OneOnOne::new(req.person_email, occurred_at);
+        one_on_one.agenda = req.agenda;
+        one_on_one.notes = req.notes;
+        one_on_one.followups = req.followups;
+
+        self.write(move |db, _| {
+            let repo = db::OneOnOneRepository::new(db);
+            let tx = db::begin_or_join(db)
+                .map_err(|e| db_error("Database error", e.into()))?;
+            repo.create(&one_on_one)
+                .map_err(|e| db_error("Failed to log 1:1", e))?;
+            let action_items = match project_uuid {
+                Some(project_uuid) => repo.create_followup_action_items(&one_on_one, &project_uuid)
+                    .map_err(|e| db_error("Failed to create action items", e))?,
+                None => Vec::new(),
+            };
+            if let Some(tx) = tx {
+                tx.commit()
+                    .map_err(|e| db_error("Database error", e.into()))?;
+            }
+            let one_on_one = repo.find_by_id(&one_on_one.id)
+                .map_err(|e| db_error("Database error", e))?;
+
+            let json = serde_json::to_string_pretty(&serde_json::json!({
+                "one_on_one": one_on_one,
+                "action_items": action_items,
+            }))
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "List the 1:1s recorded with a person, most recent first, optionally only those in a date range")]
+    async fn list_one_on_ones(&self, Parameters(req): Parameters<ListOneOnOnesRequest>) -> Result<CallToolResult, McpError> {
+        let tz = self.config().tz();
+        let since = req.since
+            .map(|since| utils::parse_user_date(&since, tz))
+            .transpose()
+            .map_err(|e| McpError::invalid_params("Invalid since format", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let before = req.before
+            .map(|before| utils::parse_user_date(&before, tz))
+            .transpose()
+            .map_err(|e| McpError::invalid_params("Invalid before format", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        let db = self.db.lock().await;
+        let one_on_ones = db::OneOnOneRepository::new(&db)
+            .list_between(&req.person_email, since, before)
+            .map_err(|e| db_error("Failed to list 1:1s", e))?;
+
+        let json = serde_json::to_string_pretty(&one_on_ones)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    // Team tools
+
+    #[tool(description = "List all teams")]
+    async fn list_teams(&self) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let repo = db::TeamRepository::new(&db);
+        let teams = repo.list_all()
+            .map_err(|e| db_error("Failed to list teams", e))?;
+
+        let json = serde_json::to_string_pretty(&teams)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Search teams by name")]
+    async fn search_teams(&self, Parameters(req): Parameters<SearchTeamsRequest>) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let repo = db::TeamRepository::new(&db);
+        let teams = repo.search_by_name(&req.query)
+            .map_err(|e| db_error("Search failed", e))?;
+
+        let json = serde_json::to_string_pretty(&teams)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Get a team by name")]
+    async fn get_team(&self, Parameters(req): Parameters<GetTeamRequest>) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let repo = db::TeamRepository::new(&db);
+        let team = repo.find_by_name(&req.name)
+            .map_err(|e| db_error("Database error", e))?;
+
+        let json = serde_json::to_string_pretty(&team)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Create a new team")]
+    async fn create_team(&self, Parameters(req): Parameters<CreateTeamRequest>) -> Result<CallToolResult, McpError> {
+        let mut team = db::Team::new(req.name);
+
+        if let Some(desc) = req.description {
+            team.description = Some(desc);
+        }
+        if let Some(manager) = req.manager {
+            team.manager = Some(manager);
+        }
+        team.parent_team = req.parent_team;
+
+        self.write(move |db, _| {
+            let repo = db::TeamRepository::new(db);
+            repo.create(&team)
+                .map_err(|e| db_error("Failed to create team", e))?;
+
+            let json = serde_json::to_string_pretty(&team)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Update a team")]
+    async fn update_team(&self, Parameters(req): Parameters<UpdateTeamRequest>) -> Result<CallToolResult, McpError> {
+        self.write(move |db, _| {
+            let repo = db::TeamRepository::new(db);
+
+            // Fetch existing team first
+            let mut team = repo.find_by_name(&req.name)
+                .map_err(|e| db_error("Database error", e))?
+                .ok_or_else(|| db_error("Database error", db::Error::not_found("Team", &req.name)))?;
+
+            // Update fields
+            team.description = req.description;
+            team.manager = req.manager;
+            team.parent_team = req.parent_team;
+
+            repo.update(&team)
+                .map_err(|e| db_error("Failed to update team", e))?;
+
+            let json = serde_json::to_string_pretty(&team)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Delete a team. Fails if it has sub-teams unless detach_subteams is true")]
+    async fn delete_team(&self, Parameters(req): Parameters<DeleteTeamRequest>) -> Result<CallToolResult, McpError> {
+        let action = format!("delete_team:{}:{}", req.name, req.detach_subteams);
+        if let Some(pending) = self.confirm_delete(action, req.confirmation_token.as_deref(), |db| db::deletion::team_impact(db, &req.name)).await? {
+            return Ok(pending);
+        }
+        let subteams = if req.detach_subteams {
+            db::SubteamPolicy::Detach
+        } else {
+            db::SubteamPolicy::Refuse
+        };
+        self.write(move |db, _| {
+            let repo = db::TeamRepository::new(db);
+            repo.delete(&req.name, subteams)
+                .map_err(|e| db_error("Failed to delete team", e))?;
+
+            Ok(CallToolResult::success(vec![Content::text(format!("Deleted team {}", req.name))]))
+        }).await
+    }
+
+    #[tool(description = "Add a member to a team")]
+    async fn add_team_member(&self, Parameters(req): Parameters<TeamMemberRequest>) -> Result<CallToolResult, McpError> {
+        self.write(move |db, _| {
+            let repo = db::TeamRepository::new(db);
+            repo.add_member(&req.team_name, &req.person_email)
+                .map_err(|e| db_error("Failed to add team member", e))?;
+
+            Ok(CallToolResult::success(vec![Content::text(format!("Added {} to team {}", req.person_email, req.team_name))]))
+        }).await
+    }
+
+    #[tool(description = "Remove a member from a team")]
+    async fn remove_team_member(&self, Parameters(req): Parameters<TeamMemberRequest>) -> Result<CallToolResult, McpError> {
+        self.write(move |db, _| {
+            let repo = db::TeamRepository::new(db);
+            repo.remove_member(&req.team_name, &req.person_email)
+                .map_err(|e| db_error("Failed to remove team member", e))?;
+
+            Ok(CallToolResult::success(vec![Content::text(format!("Removed {} from team {}", req.person_email, req.team_name))]))
+        }).await
+    }
+
+    #[tool(description = "Get all members of a team, optionally including the members of its sub-teams")]
+    async fn get_team_members(&self, Parameters(req): Parameters<GetTeamMembersRequest>) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let repo = db::TeamRepository::new(&db);
+        let members = if req.include_subteams {
+            repo.get_members_recursive(&req.team_name)
+        } else {
+            repo.get_members(&req.team_name)
+        }
+        .map_err(|e| db_error("Failed to get team members", e))?;
+
+        let json = serde_json::to_string_pretty(&members)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Get all teams as a tree, with each top-level team holding its sub-teams")]
+    async fn get_team_tree(&self) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let repo = db::TeamRepository::new(&db);
+        let tree = repo.get_team_tree()
+            .map_err(|e| db_error("Failed to get team tree", e))?;
+
+        let json = serde_json::to_string_pretty(&tree)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    // Milestone tools
+
+    #[tool(description = "List milestones for a project, each with its checklist and checklist_completion (0.0 to 1.0, null without a checklist)")]
+    async fn list_milestones(&self, Parameters(req): Parameters<GetMilestonesRequest>) -> Result<CallToolResult, McpError> {
+        let uuid = self.project_ref(&req.project_id).await?;
+
+        let db = self.db.lock().await;
+        let repo = db::ProjectRepository::new(&db);
+        let milestones = repo.get_milestones(&uuid)
+            .map_err(|e| db_error("Failed to list milestones", e))?;
+
+        self.with_handles(HandleKind::Milestone, &milestones)
+    }
+
+    #[tool(description = "Get a milestone by ID")]
+    async fn get_milestone(&self, Parameters(req): Parameters<GetMilestoneRequest>) -> Result<CallToolResult, McpError> {
+        let milestone_uuid = Uuid::parse_str(&req.id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        let db = self.db.lock().await;
+        let repo = db::ProjectRepository::new(&db);
+        let milestone = repo.find_milestone_by_id(&milestone_uuid)
+            .map_err(|e| db_error("Database error", e))?
+            .ok_or_else(|| db_error("Database error", db::Error::not_found("Milestone", milestone_uuid)))?;
+
+        self.with_handles(HandleKind::Milestone, &milestone)
+    }
+
+    #[tool(description = "Create a new milestone")]
+    async fn create_milestone(&self, Parameters(req): Parameters<CreateMilestoneRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
+
+        let mut milestone = db::Milestone::new(project_uuid, req.number, req.name);
+        milestone.description = req.description;
+        milestone.technical_lead = req.technical_lead;
+        milestone.team = req.team;
+        milestone.design_doc_url = req.design_doc_url;
+        milestone.jira_epic = req.jira_epic;
+        milestone.recurrence_rule = req.recurrence_rule;
+        milestone.estimated_days = req.estimated_days;
+        milestone.actual_days = req.actual_days;
+
+        // Parse dates if provided
+        if let Some(start_date_str) = req.start_date {
+            milestone.start_date = Some(utils::parse_user_date(&start_date_str, self.config().tz())
+                .map_err(|e| McpError::invalid_params("Invalid start_date format", Some(serde_json::json!({"error": e.to_string()}))))?);
+        }
+        if let Some(due_date_str) = req.due_date {
+            milestone.due_date = Some(utils::parse_user_date(&due_date_str, self.config().tz())
+                .map_err(|e| McpError::invalid_params("Invalid due_date format", Some(serde_json::json!({"error": e.to_string()}))))?);
+        }
+
+        if let Some(rule) = &milestone.recurrence_rule {
+            rule.parse::<RecurrenceRule>()
+                .map_err(|e| McpError::invalid_params("Invalid recurrence_rule", Some(serde_json::json!({"error": e.to_string()}))))?;
+            if milestone.due_date.is_none() {
+                return Err(McpError::invalid_params("recurrence_rule requires a due_date", None));
+            }
+        }
+
+        self.write(move |db, ctx| {
+            let service = ProjectService::new(db, &ctx.webhooks);
+            service.add_milestone(&milestone)
+                .map_err(|e| db_error("Failed to create milestone", e))?;
+            let mut warnings = db::ProjectRepository::new(db).milestone_date_warnings(&milestone)
+                .map_err(|e| db_error("Failed to check milestone dates", e))?;
+            warnings.extend(milestone.effort_warning(ctx.config.effort_warning_ratio));
+
+            let json = serde_json::to_string_pretty(&milestone)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(with_warnings(json, warnings)))
+        }).await
+    }
+
+    #[tool(description = "Update a milestone")]
+    async fn update_milestone(&self, Parameters(req): Parameters<UpdateMilestoneRequest>) -> Result<CallToolResult, McpError> {
+        let milestone_uuid = Uuid::parse_str(&req.id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        self.write(move |db, ctx| {
+
+            // Fetch existing milestone first
+            let repo = db::ProjectRepository::new(db);
+            let mut milestone = repo.find_milestone_by_id(&milestone_uuid)
+                .map_err(|e| db_error("Database error", e))?
+                .ok_or_else(|| db_error("Database error", db::Error::not_found("Milestone", milestone_uuid)))?;
+
+            milestone.version = expected_version(&ctx.config, req.version, milestone.version)?;
+
+            // Update fields
+            milestone.number = req.number;
+            milestone.name = req.name;
+            milestone.description = req.description;
+            milestone.technical_lead = req.technical_lead;
+            milestone.team = req.team;
+            milestone.design_doc_url = req.design_doc_url;
+            milestone.jira_epic = req.jira_epic;
+            milestone.recurrence_rule = req.recurrence_rule;
+            milestone.estimated_days = req.estimated_days;
+            milestone.actual_days = req.actual_days;
+
+            // Parse dates if provided
+            if let Some(start_date_str) = req.start_date {
+                milestone.start_date = Some(utils::parse_user_date(&start_date_str, ctx.config.tz())
+                    .map_err(|e| McpError::invalid_params("Invalid start_date format", Some(serde_json::json!({"error": e.to_string()}))))?);
+            }
+            if let Some(due_date_str) = req.due_date {
+                milestone.due_date = Some(utils::parse_user_date(&due_date_str, ctx.config.tz())
+                    .map_err(|e| McpError::invalid_params("Invalid due_date format", Some(serde_json::json!({"error": e.to_string()}))))?);
+            }
+
+            if let Some(rule) = &milestone.recurrence_rule {
+                rule.parse::<RecurrenceRule>()
+                    .map_err(|e| McpError::invalid_params("Invalid recurrence_rule", Some(serde_json::json!({"error": e.to_string()}))))?;
+                if milestone.due_date.is_none() {
+                    return Err(McpError::invalid_params("recurrence_rule requires a due_date", None));
+                }
+            }
+
+            ProjectService::new(db, &ctx.webhooks)
+                .update_milestone(&milestone)
+                .map_err(|e| db_error("Failed to update milestone", e))?;
+            milestone.version += 1;
+            let mut warnings = repo.milestone_date_warnings(&milestone)
+                .map_err(|e| db_error("Failed to check milestone dates", e))?;
+            warnings.extend(milestone.effort_warning(ctx.config.effort_warning_ratio));
+
+            let json = serde_json::to_string_pretty(&milestone)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(with_warnings(json, warnings)))
+        }).await
+    }
+
+    #[tool(description = "Recent activity on a project, newest first: notes added, milestones created and completed, resources added, date changes and blockers")]
+    async fn get_project_activity(&self, Parameters(req): Parameters<GetProjectActivityRequest>) -> Result<CallToolResult, McpError> {
+        let uuid = self.project_ref(&req.project_id).await?;
+
+        let db = self.db.lock().await;
+        let repo = db::ProjectRepository::new(&db);
+        if repo.find_by_id(&uuid).map_err(|e| db_error("Database error", e))?.is_none() {
+            return Err(db_error("Database error", db::Error::not_found("Project", uuid)));
+        }
+        let limit = req.limit.unwrap_or(db::project_repo::DEFAULT_ACTIVITY_LIMIT);
+        let activity = repo.get_activity_feed(&uuid, limit)
+            .map_err(|e| db_error("Failed to load project activity", e))?;
+
+        let json = serde_json::to_string_pretty(&activity)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Suggest a due date for a project: the latest due date of its milestones")]
+    async fn suggest_project_due_date(&self, Parameters(req): Parameters<SuggestProjectDueDateRequest>) -> Result<CallToolResult, McpError> {
+        let uuid = self.project_ref(&req.project_id).await?;
+
+        let db = self.db.lock().await;
+        let repo = db::ProjectRepository::new(&db);
+        let project = repo.find_by_id(&uuid)
+            .map_err(|e| db_error("Database error", e))?
+            .ok_or_else(|| db_error("Database error", db::Error::not_found("Project", uuid)))?;
+        let suggested = repo.suggest_project_due_date(&uuid)
+            .map_err(|e| db_error("Failed to suggest a due date", e))?;
+
+        let json = serde_json::to_string_pretty(&serde_json::json!({
+            "project_id": project.id,
+            "due_date": project.due_date,
+            "suggested_due_date": suggested,
+        }))
+        .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Delete a milestone")]
+    async fn delete_milestone(&self, Parameters(req): Parameters<DeleteMilestoneRequest>) -> Result<CallToolResult, McpError> {
+        let milestone_uuid = Uuid::parse_str(&req.id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let action = format!("delete_milestone:{}:{:?}", milestone_uuid, req.keep_occurrences);
+        let with_occurrences = req.keep_occurrences == Some(false);
+        if let Some(pending) = self.confirm_delete(action, req.confirmation_token.as_deref(), |db| db::deletion::milestone_impact(db, &milestone_uuid, with_occurrences)).await? {
+            return Ok(pending);
+        }
+
+        self.write(move |db, ctx| {
+            let service = ProjectService::new(db, &ctx.webhooks);
+            let result = match req.keep_occurrences {
+                Some(keep) => service.delete_milestone_series(&milestone_uuid, keep),
+                None => service.delete_milestone(&milestone_uuid),
+            };
+            result
+                .map_err(|e| db_error("Failed to delete milestone", e))?;
+
+            Ok(CallToolResult::success(vec![Content::text(format!("Deleted milestone {}", req.id))]))
+        }).await
+    }
+
+    // Project Stakeholder tools
+
+    #[tool(description = "Add a stakeholder to a project, optionally rating their influence and interest from 1 to 5. Returns the stakeholder as stored, with the role under its canonical spelling (\"tech-lead\" is stored as an existing \"Tech Lead\")")]
+    async fn add_project_stakeholder(&self, Parameters(req): Parameters<AddProjectStakeholderRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
+
+        let mut stakeholder = db::ProjectStakeholder {
+            project_id: project_uuid,
+            stakeholder_email: req.stakeholder_email.clone(),
+            role: req.role,
+            influence: req.influence,
+            interest: req.interest,
+            created_at: chrono::Utc::now(),
+        };
+
+        self.write(move |db, ctx| {
+            let repo = db::ProjectRepository::new(db).with_roles(&ctx.config.roles, ctx.config.allow_new_roles);
+            stakeholder.role = repo.normalize_role(stakeholder.role.as_deref())
+                .map_err(|e| db_error("Failed to add stakeholder", e))?;
+            repo.add_stakeholder(&project_uuid, &stakeholder)
+                .map_err(|e| db_error("Failed to add stakeholder", e))?;
+
+            let json = serde_json::to_string_pretty(&stakeholder)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "List stakeholders for a project")]
+    async fn list_project_stakeholders(&self, Parameters(req): Parameters<GetProjectStakeholdersRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
+
+        let db = self.db.lock().await;
+        let repo = db::ProjectRepository::new(&db);
+        let stakeholders = repo.get_stakeholders(&project_uuid)
+            .map_err(|e| db_error("Failed to list stakeholders", e))?;
+
+        let json = serde_json::to_string_pretty(&stakeholders)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Brief on a project stakeholder for meeting prep: their role, person record, recent notes and open questions (unchecked task list items in their notes). Returns the brief as JSON followed by a markdown rendering.")]
+    async fn generate_stakeholder_brief(&self, Parameters(req): Parameters<GenerateStakeholderBriefRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
+
+        let db = self.db.lock().await;
+        let repo = db::ProjectRepository::new(&db);
+        let note_limit = req.note_limit.unwrap_or(db::project_repo::DEFAULT_BRIEF_NOTE_LIMIT);
+        let brief = repo.get_stakeholder_brief(&project_uuid, &req.stakeholder_email, note_limit)
+            .map_err(|e| db_error("Failed to build stakeholder brief", e))?;
+
+        let json = serde_json::to_string_pretty(&brief)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let markdown = resources::render_stakeholder_brief(&brief, self.config().tz());
+
+        Ok(CallToolResult::success(vec![Content::text(json), Content::text(markdown)]))
+    }
+
+    #[tool(description = "Sort a project's stakeholders into an influence/interest grid: manage_closely (high influence, high interest), keep_satisfied (high influence), keep_informed (high interest) and monitor, where ratings of 4 or 5 are high. Stakeholders missing a rating are listed under unrated. Returns the matrix as JSON followed by a markdown table.")]
+    async fn get_stakeholder_matrix(&self, Parameters(req): Parameters<GetProjectStakeholdersRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
+
+        let db = self.db.lock().await;
+        let repo = db::ProjectRepository::new(&db);
+        let matrix = repo.get_stakeholder_matrix(&project_uuid)
+            .map_err(|e| db_error("Failed to build stakeholder matrix", e))?;
+
+        let json = serde_json::to_string_pretty(&matrix)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let markdown = resources::render_stakeholder_matrix(&matrix);
+
+        Ok(CallToolResult::success(vec![Content::text(json), Content::text(markdown)]))
+    }
+
+    #[tool(description = "Update a project stakeholder's role and influence and interest ratings; omitted values are cleared. Returns the stakeholder as stored, with the role under its canonical spelling")]
+    async fn update_project_stakeholder(&self, Parameters(req): Parameters<UpdateProjectStakeholderRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
+
+        let mut stakeholder = db::ProjectStakeholder {
+            project_id: project_uuid,
+            stakeholder_email: req.stakeholder_email.clone(),
+            role: req.role,
+            influence: req.influence,
+            interest: req.interest,
+            created_at: chrono::Utc::now(), // This will be ignored by update
+        };
+
+        self.write(move |db, ctx| {
+            let repo = db::ProjectRepository::new(db).with_roles(&ctx.config.roles, ctx.config.allow_new_roles);
+            stakeholder.role = repo.normalize_role(stakeholder.role.as_deref())
+                .map_err(|e| db_error("Failed to update stakeholder", e))?;
+            repo.update_stakeholder(&project_uuid, &stakeholder)
+                .map_err(|e| db_error("Failed to update stakeholder", e))?;
+
+            let json = serde_json::to_string_pretty(&stakeholder)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Remove a stakeholder from a project")]
+    async fn remove_project_stakeholder(&self, Parameters(req): Parameters<RemoveProjectStakeholderRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
+
+        self.write(move |db, _| {
+            let repo = db::ProjectRepository::new(db);
+            repo.remove_stakeholder(&project_uuid, &req.stakeholder_email)
+                .map_err(|e| db_error("Failed to remove stakeholder", e))?;
+
+            Ok(CallToolResult::success(vec![Content::text(format!("Removed stakeholder {} from project {}", req.stakeholder_email, req.project_id))]))
+        }).await
+    }
+
+    // Project Resource tools
+
+    #[tool(description = "Add a resource to a project. Returns the resource as stored, with the role under its canonical spelling (\"tech-lead\" is stored as an existing \"Tech Lead\")")]
+    async fn add_project_resource(&self, Parameters(req): Parameters<AddProjectResourceRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
+
+        let mut resource = db::ProjectResource {
+            project_id: project_uuid,
+            person_email: req.person_email.clone(),
+            role: req.role,
+            created_at: chrono::Utc::now(),
+        };
+
+        self.write(move |db, ctx| {
+            let repo = db::ProjectRepository::new(db).with_roles(&ctx.config.roles, ctx.config.allow_new_roles);
+            resource.role = repo.normalize_role(resource.role.as_deref())
+                .map_err(|e| db_error("Failed to add resource", e))?;
+            repo.add_project_resource(&project_uuid, &resource)
+                .map_err(|e| db_error("Failed to add resource", e))?;
+
+            let json = serde_json::to_string_pretty(&resource)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Add every active member of a team as a project resource. Returns which members were added and which were skipped because they were already assigned or are inactive")]
+    async fn assign_team_to_project(&self, Parameters(req): Parameters<AssignTeamToProjectRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
+
+        self.write(move |db, ctx| {
+            let repo = db::ProjectRepository::new(db).with_roles(&ctx.config.roles, ctx.config.allow_new_roles);
+            let result = repo.add_team_as_resources(&project_uuid, &req.team_name, req.role.as_deref())
+                .map_err(|e| db_error("Failed to assign team", e))?;
+
+            let json = serde_json::to_string_pretty(&result)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "List resources for a project")]
+    async fn list_project_resources(&self, Parameters(req): Parameters<GetProjectResourcesRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
+
+        let db = self.db.lock().await;
+        let repo = db::ProjectRepository::new(&db);
+        let resources = repo.get_project_resources(&project_uuid)
+            .map_err(|e| db_error("Failed to list resources", e))?;
+
+        let json = serde_json::to_string_pretty(&resources)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Update a project resource. Returns the resource as stored, with the role under its canonical spelling")]
+    async fn update_project_resource(&self, Parameters(req): Parameters<UpdateProjectResourceRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
+
+        let mut resource = db::ProjectResource {
+            project_id: project_uuid,
+            person_email: req.person_email.clone(),
+            role: req.role,
+            created_at: chrono::Utc::now(), // This will be ignored by update
+        };
+
+        self.write(move |db, ctx| {
+            let repo = db::ProjectRepository::new(db).with_roles(&ctx.config.roles, ctx.config.allow_new_roles);
+            resource.role = repo.normalize_role(resource.role.as_deref())
+                .map_err(|e| db_error("Failed to update resource", e))?;
+            repo.update_project_resource(&project_uuid, &resource)
+                .map_err(|e| db_error("Failed to update resource", e))?;
+
+            let json = serde_json::to_string_pretty(&resource)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Remove a resource from a project")]
+    async fn remove_project_resource(&self, Parameters(req): Parameters<RemoveProjectResourceRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
+
+        self.write(move |db, _| {
+            let repo = db::ProjectRepository::new(db);
+            repo.remove_project_resource(&project_uuid, &req.person_email)
+                .map_err(|e| db_error("Failed to remove resource", e))?;
+
+            Ok(CallToolResult::success(vec![Content::text(format!("Removed resource {} from project {}", req.person_email, req.project_id))]))
+        }).await
+    }
+
+    // Milestone Resource tools
+
+    #[tool(description = "Add a resource to a milestone. Returns the resource as stored, with the role under its canonical spelling, and a warning when the milestone now has more resources than max_resources_per_milestone allows")]
+    async fn add_milestone_resource(&self, Parameters(req): Parameters<AddMilestoneResourceRequest>) -> Result<CallToolResult, McpError> {
+        let milestone_uuid = Uuid::parse_str(&req.milestone_id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        let mut resource = db::MilestoneResource {
+            milestone_id: milestone_uuid,
+            person_email: req.person_email.clone(),
+            role: req.role,
+            created_at: chrono::Utc::now(),
+        };
+
+        self.write(move |db, ctx| {
+            let repo = db::ProjectRepository::new(db)
+                .with_roles(&ctx.config.roles, ctx.config.allow_new_roles)
+                .with_resource_limit(ctx.config.max_resources_per_milestone, ctx.config.max_resources_hard_limit);
+            resource.role = repo.normalize_role(resource.role.as_deref())
+                .map_err(|e| db_error("Failed to add resource", e))?;
+            let warning = repo.add_milestone_resource(&milestone_uuid, &resource)
+                .map_err(|e| db_error("Failed to add resource", e))?;
+
+            let json = serde_json::to_string_pretty(&resource)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(with_warnings(json, warning.iter().map(ToString::to_string).collect())))
+        }).await
+    }
+
+    #[tool(description = "List resources for a milestone")]
+    async fn list_milestone_resources(&self, Parameters(req): Parameters<GetMilestoneResourcesRequest>) -> Result<CallToolResult, McpError> {
+        let milestone_uuid = Uuid::parse_str(&req.milestone_id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        let db = self.db.lock().await;
+        let repo = db::ProjectRepository::new(&db);
+        let resources = repo.get_milestone_resources(&milestone_uuid)
+            .map_err(|e| db_error("Failed to list resources", e))?;
+
+        let json = serde_json::to_string_pretty(&resources)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Suggest people to assign to a milestone: members of its team (or its project's team) and project resources, ranked by how few other milestones they have overlapping its dates, each with the reason they were suggested. With skill, people who have it are included and ranked first by level")]
+    async fn suggest_resources(&self, Parameters(req): Parameters<SuggestResourcesRequest>) -> Result<CallToolResult, McpError> {
+        let milestone_uuid = Uuid::parse_str(&req.milestone_id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        let db = self.db.lock().await;
+        let repo = db::ProjectRepository::new(&db);
+        let suggestions = repo.suggest_milestone_resources(&milestone_uuid, req.skill.as_deref(), req.limit.unwrap_or(5))
+            .map_err(|e| db_error("Failed to suggest resources", e))?;
+
+        let json = serde_json::to_string_pretty(&suggestions)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Update a milestone resource. Returns the resource as stored, with the role under its canonical spelling")]
+    async fn update_milestone_resource(&self, Parameters(req): Parameters<UpdateMilestoneResourceRequest>) -> Result<CallToolResult, McpError> {
+        let milestone_uuid = Uuid::parse_str(&req.milestone_id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        let mut resource = db::MilestoneResource {
+            milestone_id: milestone_uuid,
+            person_email: req.person_email.clone(),
+            role: req.role,
+            created_at: chrono::Utc::now(), // This will be ignored by update
+        };
+
+        self.write(move |db, ctx| {
+            let repo = db::ProjectRepository::new(db).with_roles(&ctx.config.roles, ctx.config.allow_new_roles);
+            resource.role = repo.normalize_role(resource.role.as_deref())
+                .map_err(|e| db_error("Failed to update resource", e))?;
+            repo.update_milestone_resource(&milestone_uuid, &resource)
+                .map_err(|e| db_error("Failed to update resource", e))?;
+
+            let json = serde_json::to_string_pretty(&resource)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Remove a resource from a milestone")]
+    async fn remove_milestone_resource(&self, Parameters(req): Parameters<RemoveMilestoneResourceRequest>) -> Result<CallToolResult, McpError> {
+        let milestone_uuid = Uuid::parse_str(&req.milestone_id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        self.write(move |db, _| {
+            let repo = db::ProjectRepository::new(db);
+            repo.remove_milestone_resource(&milestone_uuid, &req.person_email)
+                .map_err(|e| db_error("Failed to remove resource", e))?;
+
+            Ok(CallToolResult::success(vec![Content::text(format!("Removed resource {} from milestone {}", req.person_email, req.milestone_id))]))
+        }).await
+    }
+
+    // Project Note tools
+
+    // Milestone checklist tools
+
+    #[tool(description = "Add an item to the end of a milestone's checklist of small sub-tasks, such as \"security review\". Returns the item with its position")]
+    async fn add_checklist_item(&self, Parameters(req): Parameters<AddChecklistItemRequest>) -> Result<CallToolResult, McpError> {
+        let milestone_uuid = Uuid::parse_str(&req.milestone_id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        self.write(move |db, _| {
+            let item = db::ChecklistRepository::new(db).add_item(&milestone_uuid, &req.text)
+                .map_err(|e| db_error("Failed to add checklist item", e))?;
+
+            let json = serde_json::to_string_pretty(&item)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Check or uncheck a milestone checklist item; without checked it flips. Returns the item as stored")]
+    async fn toggle_checklist_item(&self, Parameters(req): Parameters<ToggleChecklistItemRequest>) -> Result<CallToolResult, McpError> {
+        let item_uuid = Uuid::parse_str(&req.id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        self.write(move |db, _| {
+            let item = db::ChecklistRepository::new(db).toggle_item(&item_uuid, req.checked)
+                .map_err(|e| db_error("Failed to update checklist item", e))?;
+
+            let json = serde_json::to_string_pretty(&item)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "List a milestone's checklist in order")]
+    async fn list_checklist_items(&self, Parameters(req): Parameters<ListChecklistItemsRequest>) -> Result<CallToolResult, McpError> {
+        let milestone_uuid = Uuid::parse_str(&req.milestone_id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        let db = self.db.lock().await;
+        let items = db::ChecklistRepository::new(&db).list_items(&milestone_uuid)
+            .map_err(|e| db_error("Failed to list checklist items", e))?;
+
+        let json = serde_json::to_string_pretty(&items)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Remove an item from a milestone's checklist; the items after it move up")]
+    async fn remove_checklist_item(&self, Parameters(req): Parameters<RemoveChecklistItemRequest>) -> Result<CallToolResult, McpError> {
+        let item_uuid = Uuid::parse_str(&req.id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        self.write(move |db, _| {
+            db::ChecklistRepository::new(db).remove_item(&item_uuid)
+                .map_err(|e| db_error("Failed to remove checklist item", e))?;
+
+            Ok(CallToolResult::success(vec![Content::text(format!("Removed checklist item {}", item_uuid))]))
+        }).await
+    }
+
+    #[tool(description = "Create a note for a project")]
+    async fn create_project_note(&self, Parameters(req): Parameters<CreateProjectNoteRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
+
+        let mut note = db::ProjectNote::new(project_uuid, req.title, req.body);
+        if let Some(kind) = req.kind.as_deref() {
+            note.kind = kind.parse().map_err(|e| db_error("Invalid note kind", e))?;
+        }
+
+        self.write(move |db, _| {
+            let repo = db::ProjectRepository::new(db);
+            repo.add_project_note(&note)
+                .map_err(|e| db_error("Failed to create note", e))?;
+
+            let json = serde_json::to_string_pretty(&note)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "List notes for a project, newest first. Pass limit to get one page at a time as {notes, next_cursor}, then pass next_cursor back as cursor for the next page")]
+    async fn list_project_notes(&self, Parameters(req): Parameters<GetProjectNotesRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
+
+        let db = self.db.lock().await;
+        let repo = db::ProjectRepository::new(&db);
+        let rendered = req.rendered.unwrap_or(false);
+        let notes = if req.limit.is_some() || req.cursor.is_some() {
+            let cursor = note_cursor(req.cursor.as_deref())?;
+            let limit = req.limit.unwrap_or(db::project_repo::DEFAULT_NOTE_PAGE_SIZE);
+            let notes = repo.get_project_notes_page(&project_uuid, cursor, limit)
+                .map_err(|e| db_error("Failed to list notes", e))?;
+            serde_json::to_value(notes::page_with_html(notes, limit, rendered, req.max_chars))
+        } else {
+            let notes = repo.get_project_notes(&project_uuid)
+                .map_err(|e| db_error("Failed to list notes", e))?;
+            serde_json::to_value(notes::with_html(notes, rendered, req.max_chars))
+        }
+        .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        self.with_handles(HandleKind::Note, &notes)
+    }
+
+    #[tool(description = "Add a timestamped entry to the end of a project note, under a heading with the time and author. The only way to add to a log note, such as a project's decision log")]
+    async fn append_to_note(&self, Parameters(req): Parameters<AppendToNoteRequest>) -> Result<CallToolResult, McpError> {
+        let note_uuid = Uuid::parse_str(&req.note_id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let author = req.author.or_else(|| self.config().current_user_email.clone());
+
+        self.write(move |db, _| {
+            let note = db::ProjectRepository::new(db)
+                .append_note_entry(&note_uuid, &req.text, author.as_deref())
+                .map_err(|e| db_error("Failed to append to note", e))?;
+
+            let json = serde_json::to_string_pretty(&note)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Update a project note. The body of a log note can't be replaced; add to it with append_to_note")]
+    async fn update_project_note(&self, Parameters(req): Parameters<UpdateProjectNoteRequest>) -> Result<CallToolResult, McpError> {
+        let note_uuid = Uuid::parse_str(&req.id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        self.write(move |db, ctx| {
+
+            // Fetch existing note first
+            let repo = db::ProjectRepository::new(db);
+            let mut note = repo.find_project_note_by_id(&note_uuid)
+                .map_err(|e| db_error("Database error", e))?
+                .ok_or_else(|| db_error("Database error", db::Error::not_found("Project note", note_uuid)))?;
+
+            note.version = expected_version(&ctx.config, req.version, note.version)?;
+
+            // Update fields
+            note.title = req.title;
+            note.body = req.body;
+            note.updated_at = chrono::Utc::now();
+
+            repo.update_project_note(&note)
+                .map_err(|e| db_error("Failed to update note", e))?;
+            note.version += 1;
+
+            let json = serde_json::to_string_pretty(&note)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Delete a project note")]
+    async fn delete_project_note(&self, Parameters(req): Parameters<DeleteProjectNoteRequest>) -> Result<CallToolResult, McpError> {
+        let note_uuid = Uuid::parse_str(&req.id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        self.write(move |db, _| {
+            let repo = db::ProjectRepository::new(db);
+            repo.delete_project_note(&note_uuid)
+                .map_err(|e| db_error("Failed to delete note", e))?;
+
+            Ok(CallToolResult::success(vec![Content::text(format!("Deleted note {}", req.id))]))
+        }).await
+    }
+
+    // Note batch and move tools
+
+    #[tool(description = "Create several notes on projects, milestones and stakeholders at once; either all are created or none")]
+    async fn create_notes_batch(&self, Parameters(req): Parameters<CreateNotesBatchRequest>) -> Result<CallToolResult, McpError> {
+        let mut notes = Vec::with_capacity(req.notes.len());
+        for spec in req.notes {
+            notes.push(db::NewNote { target: self.note_target(spec.target).await?, title: spec.title, body: spec.body });
+        }
+
+        self.write(move |db, _| {
+            let repo = db::ProjectRepository::new(db);
+            let ids = repo.add_notes_batch(&notes)
+                .map_err(|e| db_error("Failed to create notes", e))?;
+
+            let json = serde_json::to_string_pretty(&ids)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+    #[tool(description = "Move a project or milestone note to its project or another milestone of the same project")]
+    async fn move_note(&self, Parameters(req): Parameters<MoveNoteRequest>) -> Result<CallToolResult, McpError> {
+        let note_uuid = Uuid::parse_str(&req.note_id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let target = self.note_target(req.target).await?;
+
+        self.write(move |db, _| {
+            let repo = db::ProjectRepository::new(db);
+            repo.move_note(&note_uuid, &target)
+                .map_err(|e| db_error("Failed to move note", e))?;
+
+            Ok(CallToolResult::success(vec![Content::text(format!("Moved note {}", req.note_id))]))
+        }).await
     }
 
-    #[tool(description = "Create a new project")]
-    async fn create_project(&self, Parameters(req): Parameters<CreateProjectRequest>) -> Result<CallToolResult, McpError> {
-        let mut project = db::Project::new(req.name);
+    // Inbox tools
 
-        if let Some(desc) = req.description {
-            project.description = Some(desc);
-        }
-        if let Some(ptype) = req.project_type {
-            project.project_type = ptype;
-        }
-        if let Some(jira) = req.jira_initiative {
-            project.jira_initiative = Some(jira);
-        }
+    #[tool(description = "Capture a note in the inbox, before deciding which project, milestone or stakeholder it belongs to")]
+    async fn create_inbox_note(&self, Parameters(req): Parameters<CreateInboxNoteRequest>) -> Result<CallToolResult, McpError> {
+        let note = db::InboxNote::new(req.title, req.body);
+
+        self.write(move |db, _| {
+            let repo = db::InboxRepository::new(db);
+            repo.create(&note)
+                .map_err(|e| db_error("Failed to create inbox note", e))?;
+
+            let json = serde_json::to_string_pretty(&note)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "List the files attached to a note, with their file names, MIME types and sizes. File contents are only available in the desktop app")]
+    async fn list_attachments(&self, Parameters(req): Parameters<ListAttachmentsRequest>) -> Result<CallToolResult, McpError> {
+        let note_type: db::NoteType = req.note_type.parse()
+            .map_err(|e| db_error("Invalid note type", e))?;
+        let uuid = Uuid::parse_str(&req.note_id)
+            .map_err(|e| McpError::invalid_params("Invalid note UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
 
         let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        repo.create(&project)
-            .map_err(|e| McpError::internal_error("Failed to create project", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let attachments = db::AttachmentRepository::new(&db).list_attachments(note_type, &uuid)
+            .map_err(|e| db_error("Failed to list attachments", e))?;
 
-        let json = serde_json::to_string_pretty(&project)
+        let json = serde_json::to_string_pretty(&attachments)
             .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
 
         Ok(CallToolResult::success(vec![Content::text(json)]))
     }
 
-    #[tool(description = "Update a project")]
-    async fn update_project(&self, Parameters(req): Parameters<UpdateProjectRequest>) -> Result<CallToolResult, McpError> {
+    #[tool(description = "List the project, milestone and stakeholder notes that reference a record with a [[project:<id or slug>]], [[person:<email>]] or [[milestone:<id>]] token, most recently changed first")]
+    async fn get_backlinks(&self, Parameters(req): Parameters<GetBacklinksRequest>) -> Result<CallToolResult, McpError> {
+        let kind: db::ReferenceKind = req.kind.parse()
+            .map_err(|e| db_error("Invalid reference kind", e))?;
+
+        let db = self.db.lock().await;
+        let backlinks = db::NoteLinkRepository::new(&db).get_backlinks(kind, &req.target)
+            .map_err(|e| db_error("Failed to get backlinks", e))?;
+
+        let json = serde_json::to_string_pretty(&backlinks)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Get a project, milestone or stakeholder note. Pass offset_chars and max_chars to read a long body a piece at a time: the result has total_chars, and next_offset until the end is reached")]
+    async fn get_note(&self, Parameters(req): Parameters<GetNoteRequest>) -> Result<CallToolResult, McpError> {
+        let note_type: db::NoteType = req.note_type.parse()
+            .map_err(|e| db_error("Invalid note type", e))?;
         let uuid = Uuid::parse_str(&req.id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+            .map_err(|e| McpError::invalid_params("Invalid note UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
 
         let db = self.db.lock().await;
         let repo = db::ProjectRepository::new(&db);
+        let note = repo.get_note_slice(note_type, &uuid, req.offset_chars.unwrap_or(0), req.max_chars)
+            .map_err(|e| db_error("Failed to get note", e))?
+            .ok_or_else(|| McpError::invalid_params("Note not found", Some(serde_json::json!({"id": req.id}))))?;
 
-        // Fetch existing project first
-        let mut project = repo.find_by_id(&uuid)
-            .map_err(|e| McpError::internal_error("Database error", Some(serde_json::json!({"error": e.to_string()}))))?
-            .ok_or_else(|| McpError::invalid_params("Project not found", None))?;
+        self.with_handles(HandleKind::Note, &note)
+    }
 
-        // Update fields
-        project.name = req.name;
-        project.description = req.description;
-        if let Some(ptype) = req.project_type {
-            project.project_type = ptype;
-        }
-        project.requirements_owner = req.requirements_owner;
-        project.technical_lead = req.technical_lead;
-        project.manager = req.manager;
-        project.team = req.team;
-        project.jira_initiative = req.jira_initiative;
+    #[tool(description = "Summarize a project, milestone or stakeholder note with its title, the start of the body, its length and word count, without returning the whole body")]
+    async fn get_note_summary(&self, Parameters(req): Parameters<GetNoteSummaryRequest>) -> Result<CallToolResult, McpError> {
+        let note_type: db::NoteType = req.note_type.parse()
+            .map_err(|e| db_error("Invalid note type", e))?;
+        let uuid = Uuid::parse_str(&req.id)
+            .map_err(|e| McpError::invalid_params("Invalid note UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
 
-        // Parse dates if provided
-        if let Some(start_date_str) = req.start_date {
-            project.start_date = Some(chrono::DateTime::parse_from_rfc3339(&start_date_str)
-                .map_err(|e| McpError::invalid_params("Invalid start_date format", Some(serde_json::json!({"error": e.to_string()}))))?
-                .with_timezone(&chrono::Utc));
-        }
-        if let Some(due_date_str) = req.due_date {
-            project.due_date = Some(chrono::DateTime::parse_from_rfc3339(&due_date_str)
-                .map_err(|e| McpError::invalid_params("Invalid due_date format", Some(serde_json::json!({"error": e.to_string()}))))?
-                .with_timezone(&chrono::Utc));
-        }
+        let db = self.db.lock().await;
+        let repo = db::ProjectRepository::new(&db);
+        let excerpt_chars = req.excerpt_chars.unwrap_or(db::project_repo::DEFAULT_NOTE_EXCERPT_CHARS);
+        let summary = repo.get_note_summary(note_type, &uuid, excerpt_chars)
+            .map_err(|e| db_error("Failed to summarize note", e))?
+            .ok_or_else(|| McpError::invalid_params("Note not found", Some(serde_json::json!({"id": req.id}))))?;
+
+        let json = serde_json::to_string_pretty(&summary)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
 
-        repo.update(&project)
-            .map_err(|e| McpError::internal_error("Failed to update project", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tool(description = "List the notes in the inbox that haven't been filed yet, newest first")]
+    async fn list_inbox_notes(&self) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let repo = db::InboxRepository::new(&db);
+        let notes = repo.list_all()
+            .map_err(|e| db_error("Failed to list inbox notes", e))?;
 
-        let json = serde_json::to_string_pretty(&project)
+        let json = serde_json::to_string_pretty(&notes)
             .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
 
         Ok(CallToolResult::success(vec![Content::text(json)]))
     }
 
-    #[tool(description = "Delete a project")]
-    async fn delete_project(&self, Parameters(req): Parameters<DeleteProjectRequest>) -> Result<CallToolResult, McpError> {
-        let uuid = Uuid::parse_str(&req.id)
+    #[tool(description = "File an inbox note under a project, milestone or stakeholder, keeping its ID and timestamps")]
+    async fn triage_inbox_note(&self, Parameters(req): Parameters<TriageInboxNoteRequest>) -> Result<CallToolResult, McpError> {
+        let note_uuid = Uuid::parse_str(&req.note_id)
             .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let target = self.note_target(req.target).await?;
 
-        let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        repo.delete(&uuid)
-            .map_err(|e| McpError::internal_error("Failed to delete project", Some(serde_json::json!({"error": e.to_string()}))))?;
+        self.write(move |db, _| {
+            let repo = db::InboxRepository::new(db);
+            repo.triage(&note_uuid, &target)
+                .map_err(|e| db_error("Failed to triage inbox note", e))?;
 
-        Ok(CallToolResult::success(vec![Content::text(format!("Deleted project {}", req.id))]))
+            Ok(CallToolResult::success(vec![Content::text(format!("Filed inbox note {}", req.note_id))]))
+        }).await
     }
 
-    // People tools
+    // Project Document tools
+
+    #[tool(description = "Link a document (design doc, PRD, runbook, ...) to a project")]
+    async fn add_project_document(&self, Parameters(req): Parameters<AddProjectDocumentRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
+
+        let mut document = db::ProjectDocument::new(project_uuid, req.title, req.url);
+        document.doc_type = req.doc_type;
+
+        self.write(move |db, _| {
+            let repo = db::ProjectRepository::new(db);
+            repo.add_project_document(&document)
+                .map_err(|e| db_error("Failed to add document", e))?;
+
+            let json = serde_json::to_string_pretty(&document)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "List documents linked to a project")]
+    async fn list_project_documents(&self, Parameters(req): Parameters<ListProjectDocumentsRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
 
-    #[tool(description = "List all people")]
-    async fn list_people(&self) -> Result<CallToolResult, McpError> {
         let db = self.db.lock().await;
-        let repo = db::PersonRepository::new(&db);
-        let people = repo.list_all()
-            .map_err(|e| McpError::internal_error("Failed to list people", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let repo = db::ProjectRepository::new(&db);
+        let documents = repo.get_project_documents(&project_uuid)
+            .map_err(|e| db_error("Failed to list documents", e))?;
 
-        let json = serde_json::to_string_pretty(&people)
+        let json = serde_json::to_string_pretty(&documents)
             .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
 
         Ok(CallToolResult::success(vec![Content::text(json)]))
     }
 
-    #[tool(description = "Search people by name")]
-    async fn search_people(&self, Parameters(req): Parameters<SearchPeopleRequest>) -> Result<CallToolResult, McpError> {
+    #[tool(description = "Remove a document from a project")]
+    async fn remove_project_document(&self, Parameters(req): Parameters<RemoveProjectDocumentRequest>) -> Result<CallToolResult, McpError> {
+        let document_uuid = Uuid::parse_str(&req.id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        self.write(move |db, _| {
+            let repo = db::ProjectRepository::new(db);
+            repo.delete_project_document(&document_uuid)
+                .map_err(|e| db_error("Failed to remove document", e))?;
+
+            Ok(CallToolResult::success(vec![Content::text(format!("Removed document {}", req.id))]))
+        }).await
+    }
+
+    // Project Budget tools
+
+    #[tool(description = "Record money spent on a project, in the project's budget currency")]
+    async fn add_expense(&self, Parameters(req): Parameters<AddExpenseRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
+
+        self.write(move |db, ctx| {
+            let incurred_at = match req.incurred_at {
+                Some(date) => utils::parse_user_date(&date, ctx.config.tz())
+                    .map_err(|e| McpError::invalid_params("Invalid incurred_at format", Some(serde_json::json!({"error": e.to_string()}))))?,
+                None => chrono::Utc::now(),
+            };
+            let expense = db::ProjectExpense::new(project_uuid, req.description, req.amount, incurred_at);
+
+            let repo = db::ProjectRepository::new(db);
+            repo.add_expense(&expense)
+                .map_err(|e| db_error("Failed to add expense", e))?;
+
+            let json = serde_json::to_string_pretty(&expense)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "List a project's expenses, oldest first")]
+    async fn list_expenses(&self, Parameters(req): Parameters<ListExpensesRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
+
         let db = self.db.lock().await;
-        let repo = db::PersonRepository::new(&db);
-        let people = repo.search_by_name(&req.query)
-            .map_err(|e| McpError::internal_error("Search failed", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let repo = db::ProjectRepository::new(&db);
+        let expenses = repo.list_expenses(&project_uuid)
+            .map_err(|e| db_error("Failed to list expenses", e))?;
 
-        let json = serde_json::to_string_pretty(&people)
+        let json = serde_json::to_string_pretty(&expenses)
             .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
 
         Ok(CallToolResult::success(vec![Content::text(json)]))
     }
 
-    #[tool(description = "Get a person by email")]
-    async fn get_person(&self, Parameters(req): Parameters<GetPersonRequest>) -> Result<CallToolResult, McpError> {
+    #[tool(description = "Get a project's budget, total spend and remaining budget. The currency is returned as stored; nothing is converted")]
+    async fn get_budget_status(&self, Parameters(req): Parameters<GetBudgetStatusRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
+
         let db = self.db.lock().await;
-        let repo = db::PersonRepository::new(&db);
-        let person = repo.find_by_email(&req.email)
-            .map_err(|e| McpError::internal_error("Database error", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let repo = db::ProjectRepository::new(&db);
+        let status = repo.get_budget_status(&project_uuid)
+            .map_err(|e| db_error("Failed to get budget status", e))?;
 
-        let json = serde_json::to_string_pretty(&person)
+        let json = serde_json::to_string_pretty(&status)
             .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
 
         Ok(CallToolResult::success(vec![Content::text(json)]))
     }
 
-    #[tool(description = "Create a new person")]
-    async fn create_person(&self, Parameters(req): Parameters<CreatePersonRequest>) -> Result<CallToolResult, McpError> {
-        let mut person = db::Person::new(req.email, req.name);
+    // Project Snapshot tools
 
-        if let Some(team) = req.team {
-            person.team = Some(team);
-        }
+    #[tool(description = "Save a labelled copy of a project's current plan (the project, its milestones, resources and stakeholders) to compare against later")]
+    async fn create_snapshot(&self, Parameters(req): Parameters<CreateSnapshotRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
+
+        self.write(move |db, _| {
+            let snapshot = db::ProjectRepository::new(db)
+                .create_snapshot(&project_uuid, &req.label)
+                .map_err(|e| db_error("Failed to create snapshot", e))?;
+
+            let json = serde_json::to_string_pretty(&snapshot)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "List a project's snapshots, oldest first")]
+    async fn list_snapshots(&self, Parameters(req): Parameters<ListSnapshotsRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
 
         let db = self.db.lock().await;
-        let repo = db::PersonRepository::new(&db);
-        repo.create(&person)
-            .map_err(|e| McpError::internal_error("Failed to create person", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let repo = db::ProjectRepository::new(&db);
+        let snapshots = repo.list_snapshots(&project_uuid)
+            .map_err(|e| db_error("Failed to list snapshots", e))?;
 
-        let json = serde_json::to_string_pretty(&person)
+        let json = serde_json::to_string_pretty(&snapshots)
             .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
 
         Ok(CallToolResult::success(vec![Content::text(json)]))
     }
 
-    #[tool(description = "Update a person")]
-    async fn update_person(&self, Parameters(req): Parameters<UpdatePersonRequest>) -> Result<CallToolResult, McpError> {
+    #[tool(description = "Compare two snapshots of a project: changed project fields with old and new values, and added, removed and changed milestones")]
+    async fn compare_snapshots(&self, Parameters(req): Parameters<CompareSnapshotsRequest>) -> Result<CallToolResult, McpError> {
+        let parse = |id: &str| {
+            Uuid::parse_str(id)
+                .map_err(|e| McpError::invalid_params("Invalid snapshot UUID", Some(serde_json::json!({"error": e.to_string()}))))
+        };
+        let from = parse(&req.from_snapshot_id)?;
+        let to = parse(&req.to_snapshot_id)?;
+
         let db = self.db.lock().await;
-        let repo = db::PersonRepository::new(&db);
+        let repo = db::ProjectRepository::new(&db);
+        let diff = repo.compare_snapshots(&from, &to)
+            .map_err(|e| db_error("Failed to compare snapshots", e))?;
+
+        let json = serde_json::to_string_pretty(&diff)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
 
-        // Fetch existing person first
-        let mut person = repo.find_by_email(&req.email)
-            .map_err(|e| McpError::internal_error("Database error", Some(serde_json::json!({"error": e.to_string()}))))?
-            .ok_or_else(|| McpError::invalid_params("Person not found", None))?;
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
 
-        // Update fields
-        person.name = req.name;
-        person.team = req.team;
-        person.manager = req.manager;
-        person.notes = req.notes;
+    // Project Risk tools
 
-        repo.update(&person)
-            .map_err(|e| McpError::internal_error("Failed to update person", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tool(description = "Add a risk to a project's risk register")]
+    async fn add_risk(&self, Parameters(req): Parameters<AddRiskRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
+        let severity = req.severity.parse::<db::RiskLevel>().map_err(|e| db_error("Invalid severity", e))?;
+        let likelihood = req.likelihood.parse::<db::RiskLevel>().map_err(|e| db_error("Invalid likelihood", e))?;
 
-        let json = serde_json::to_string_pretty(&person)
+        let mut risk = db::ProjectRisk::new(project_uuid, req.title, severity, likelihood);
+        risk.description = req.description;
+        risk.mitigation = req.mitigation;
+        risk.owner_email = req.owner_email;
+
+        self.write(move |db, _| {
+            let repo = db::ProjectRepository::new(db);
+            repo.add_project_risk(&risk)
+                .map_err(|e| db_error("Failed to add risk", e))?;
+
+            let json = serde_json::to_string_pretty(&risk)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Update a project risk")]
+    async fn update_risk(&self, Parameters(req): Parameters<UpdateRiskRequest>) -> Result<CallToolResult, McpError> {
+        let risk_uuid = Uuid::parse_str(&req.id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        self.write(move |db, _| {
+            let repo = db::ProjectRepository::new(db);
+
+            // Fetch existing risk first
+            let mut risk = repo.find_project_risk_by_id(&risk_uuid)
+                .map_err(|e| db_error("Database error", e))?
+                .ok_or_else(|| db_error("Database error", db::Error::not_found("Project risk", risk_uuid)))?;
+
+            // Update fields
+            risk.title = req.title;
+            risk.description = req.description;
+            risk.severity = req.severity.parse::<db::RiskLevel>().map_err(|e| db_error("Invalid severity", e))?;
+            risk.likelihood = req.likelihood.parse::<db::RiskLevel>().map_err(|e| db_error("Invalid likelihood", e))?;
+            risk.mitigation = req.mitigation;
+            risk.owner_email = req.owner_email;
+            if let Some(status) = req.status {
+                risk.status = status.parse::<db::RiskStatus>().map_err(|e| db_error("Invalid status", e))?;
+            }
+            risk.updated_at = chrono::Utc::now();
+
+            repo.update_project_risk(&risk)
+                .map_err(|e| db_error("Failed to update risk", e))?;
+
+            let json = serde_json::to_string_pretty(&risk)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "List a project's risks, or open high risks (severity x likelihood above 4, each weighted 1-3) across all projects when no project_id is given")]
+    async fn list_risks(&self, Parameters(req): Parameters<ListRisksRequest>) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let repo = db::ProjectRepository::new(&db);
+
+        let risks = match req.project_id {
+            Some(project_id) => {
+                let project_uuid = repo.resolve_project_ref(&project_id)
+                    .map_err(|e| db_error("Unknown project", e))?;
+                if req.open_only.unwrap_or(false) {
+                    repo.list_open_risks(&project_uuid)
+                } else {
+                    repo.get_project_risks(&project_uuid)
+                }
+            }
+            None => repo.list_high_risks(),
+        }
+        .map_err(|e| db_error("Failed to list risks", e))?;
+
+        let json = serde_json::to_string_pretty(&risks)
             .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
 
         Ok(CallToolResult::success(vec![Content::text(json)]))
     }
 
-    #[tool(description = "Delete a person")]
-    async fn delete_person(&self, Parameters(req): Parameters<DeletePersonRequest>) -> Result<CallToolResult, McpError> {
+    #[tool(description = "Close a project risk; it stays in the register")]
+    async fn close_risk(&self, Parameters(req): Parameters<CloseRiskRequest>) -> Result<CallToolResult, McpError> {
+        let risk_uuid = Uuid::parse_str(&req.id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        self.write(move |db, _| {
+            let repo = db::ProjectRepository::new(db);
+            repo.close_project_risk(&risk_uuid)
+                .map_err(|e| db_error("Failed to close risk", e))?;
+
+            Ok(CallToolResult::success(vec![Content::text(format!("Closed risk {}", req.id))]))
+        }).await
+    }
+
+    // Action Item tools
+
+    #[tool(description = "Record a meeting action item for a person on a project, optionally tied to a milestone and the note it came from")]
+    async fn create_action_item(&self, Parameters(req): Parameters<CreateActionItemRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
+
+        let mut item = db::ActionItem::new(project_uuid, req.assignee_email, req.description);
+        if let Some(milestone_id) = req.milestone_id {
+            item.milestone_id = Some(Uuid::parse_str(&milestone_id)
+                .map_err(|e| McpError::invalid_params("Invalid milestone UUID", Some(serde_json::json!({"error": e.to_string()}))))?);
+        }
+        if let Some(note_id) = req.source_note_id {
+            item.source_note_id = Some(Uuid::parse_str(&note_id)
+                .map_err(|e| McpError::invalid_params("Invalid note UUID", Some(serde_json::json!({"error": e.to_string()}))))?);
+        }
+        if let Some(due_date_str) = req.due_date {
+            item.due_date = Some(utils::parse_user_date(&due_date_str, self.config().tz())
+                .map_err(|e| McpError::invalid_params("Invalid due_date format", Some(serde_json::json!({"error": e.to_string()}))))?);
+        }
+
+        self.write(move |db, _| {
+            let repo = db::ProjectRepository::new(db);
+            repo.create_action_item(&item)
+                .map_err(|e| db_error("Failed to create action item", e))?;
+
+            let json = serde_json::to_string_pretty(&item)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Mark an action item as completed, recording when it was completed")]
+    async fn complete_action_item(&self, Parameters(req): Parameters<CompleteActionItemRequest>) -> Result<CallToolResult, McpError> {
+        let item_uuid = Uuid::parse_str(&req.id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        self.write(move |db, _| {
+            let repo = db::ProjectRepository::new(db);
+            let item = repo.complete_action_item(&item_uuid)
+                .map_err(|e| db_error("Failed to complete action item", e))?;
+
+            let json = serde_json::to_string_pretty(&item)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "List a project's action items, open ones first")]
+    async fn list_action_items(&self, Parameters(req): Parameters<ListActionItemsRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
+
         let db = self.db.lock().await;
-        let repo = db::PersonRepository::new(&db);
-        repo.delete(&req.email)
-            .map_err(|e| McpError::internal_error("Failed to delete person", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let repo = db::ProjectRepository::new(&db);
+        let mut items = repo.list_action_items(&project_uuid)
+            .map_err(|e| db_error("Failed to list action items", e))?;
+        if req.open_only.unwrap_or(false) {
+            items.retain(db::ActionItem::is_open);
+        }
+
+        let json = serde_json::to_string_pretty(&items)
+            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    // Initiative tools
+
+    #[tool(description = "Create an initiative (company goal or OKR) for a quarter")]
+    async fn create_initiative(&self, Parameters(req): Parameters<CreateInitiativeRequest>) -> Result<CallToolResult, McpError> {
+        let quarter = db::Initiative::parse_quarter(&req.quarter)
+            .map_err(|e| db_error("Invalid quarter", e))?;
 
-        Ok(CallToolResult::success(vec![Content::text(format!("Deleted person {}", req.email))]))
+        let mut initiative = db::Initiative::new(req.name, quarter);
+        initiative.description = req.description;
+        initiative.owner_email = req.owner_email;
+
+        self.write(move |db, _| {
+            let repo = db::InitiativeRepository::new(db);
+            repo.create(&initiative)
+                .map_err(|e| db_error("Failed to create initiative", e))?;
+
+            let json = serde_json::to_string_pretty(&initiative)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
     }
 
-    // Team tools
+    #[tool(description = "Link a project to an initiative, replacing its previous initiative, or unlink it when no initiative_id is given")]
+    async fn link_project_to_initiative(&self, Parameters(req): Parameters<LinkProjectToInitiativeRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
+
+        self.write(move |db, _| {
+            let repo = db::ProjectRepository::new(db);
+            match req.initiative_id {
+                Some(initiative_id) => {
+                    let initiative_uuid = Uuid::parse_str(&initiative_id)
+                        .map_err(|e| McpError::invalid_params("Invalid initiative UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+                    repo.set_initiative(&project_uuid, &initiative_uuid)
+                        .map_err(|e| db_error("Failed to link project", e))?;
+                    Ok(CallToolResult::success(vec![Content::text(format!(
+                        "Linked project {} to initiative {}",
+                        req.project_id, initiative_id
+                    ))]))
+                }
+                None => {
+                    repo.clear_initiative(&project_uuid)
+                        .map_err(|e| db_error("Failed to unlink project", e))?;
+                    Ok(CallToolResult::success(vec![Content::text(format!(
+                        "Unlinked project {} from its initiative",
+                        req.project_id
+                    ))]))
+                }
+            }
+        }).await
+    }
 
-    #[tool(description = "List all teams")]
-    async fn list_teams(&self) -> Result<CallToolResult, McpError> {
+    #[tool(description = "List initiatives ordered by quarter, optionally only those for one quarter")]
+    async fn list_initiatives(&self, Parameters(req): Parameters<ListInitiativesRequest>) -> Result<CallToolResult, McpError> {
         let db = self.db.lock().await;
-        let repo = db::TeamRepository::new(&db);
-        let teams = repo.list_all()
-            .map_err(|e| McpError::internal_error("Failed to list teams", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let repo = db::InitiativeRepository::new(&db);
+        let initiatives = match req.quarter {
+            Some(quarter) => repo.list_by_quarter(&quarter),
+            None => repo.list_all(),
+        }
+        .map_err(|e| db_error("Failed to list initiatives", e))?;
 
-        let json = serde_json::to_string_pretty(&teams)
+        let json = serde_json::to_string_pretty(&initiatives)
             .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
 
         Ok(CallToolResult::success(vec![Content::text(json)]))
     }
 
-    #[tool(description = "Search teams by name")]
-    async fn search_teams(&self, Parameters(req): Parameters<SearchTeamsRequest>) -> Result<CallToolResult, McpError> {
+    #[tool(description = "Get an initiative's progress: each linked project's milestone completion, the combined completion percentage and the number of open high risks")]
+    async fn get_initiative_progress(&self, Parameters(req): Parameters<GetInitiativeProgressRequest>) -> Result<CallToolResult, McpError> {
+        let initiative_uuid = Uuid::parse_str(&req.id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        let checklist_progress = self.config().checklist_progress;
         let db = self.db.lock().await;
-        let repo = db::TeamRepository::new(&db);
-        let teams = repo.search_by_name(&req.query)
-            .map_err(|e| McpError::internal_error("Search failed", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let repo = db::InitiativeRepository::new(&db).with_checklist_progress(checklist_progress);
+        let progress = repo.get_progress(&initiative_uuid)
+            .map_err(|e| db_error("Failed to get initiative progress", e))?
+            .ok_or_else(|| db_error("Database error", db::Error::not_found("Initiative", initiative_uuid)))?;
 
-        let json = serde_json::to_string_pretty(&teams)
+        let json = serde_json::to_string_pretty(&progress)
             .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
 
         Ok(CallToolResult::success(vec![Content::text(json)]))
     }
 
-    #[tool(description = "Get a team by name")]
-    async fn get_team(&self, Parameters(req): Parameters<GetTeamRequest>) -> Result<CallToolResult, McpError> {
+    // Project dependency tools
+
+    #[tool(description = "Record that a project can't finish before another project does. Fails if the other project already depends on this one, directly or through other projects")]
+    async fn add_project_dependency(&self, Parameters(req): Parameters<ProjectDependencyRequest>) -> Result<CallToolResult, McpError> {
+        let (project_uuid, depends_on_uuid) = self.dependency_ids(&req).await?;
+        let mut dependency = db::ProjectDependency::new(project_uuid, depends_on_uuid);
+        dependency.note = req.note;
+
+        self.write(move |db, _| {
+            db::DependencyRepository::new(db).add(&dependency)
+                .map_err(|e| db_error("Failed to add dependency", e))?;
+
+            let json = serde_json::to_string_pretty(&dependency)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "Remove a dependency between two projects")]
+    async fn remove_project_dependency(&self, Parameters(req): Parameters<ProjectDependencyRequest>) -> Result<CallToolResult, McpError> {
+        let (project_uuid, depends_on_uuid) = self.dependency_ids(&req).await?;
+
+        self.write(move |db, _| {
+            db::DependencyRepository::new(db).remove(&project_uuid, &depends_on_uuid)
+                .map_err(|e| db_error("Failed to remove dependency", e))?;
+
+            Ok(CallToolResult::success(vec![Content::text(format!(
+                "Project {} no longer depends on {}",
+                req.project_id, req.depends_on_project_id
+            ))]))
+        }).await
+    }
+
+    #[tool(description = "List a project's dependencies: the projects it depends on, the projects that depend on it directly, and every project waiting on it directly or through other projects")]
+    async fn list_project_dependencies(&self, Parameters(req): Parameters<ListProjectDependenciesRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
+
         let db = self.db.lock().await;
-        let repo = db::TeamRepository::new(&db);
-        let team = repo.find_by_name(&req.name)
-            .map_err(|e| McpError::internal_error("Database error", Some(serde_json::json!({"error": e.to_string()}))))?;
+        db::ProjectRepository::new(&db).find_by_id(&project_uuid)
+            .map_err(|e| db_error("Database error", e))?
+            .ok_or_else(|| db_error("Database error", db::Error::not_found("Project", project_uuid)))?;
+        let repo = db::DependencyRepository::new(&db);
+        let depends_on = repo.list_dependencies(&project_uuid)
+            .map_err(|e| db_error("Failed to list dependencies", e))?;
+        let dependents = repo.list_dependents(&project_uuid)
+            .map_err(|e| db_error("Failed to list dependencies", e))?;
+        let blocked = repo.find_projects_blocked_by(&project_uuid)
+            .map_err(|e| db_error("Failed to list dependencies", e))?;
+
+        let json = serde_json::to_string_pretty(&serde_json::json!({
+            "depends_on": depends_on,
+            "dependents": dependents,
+            "waiting_projects": blocked
+                .iter()
+                .map(|p| serde_json::json!({"id": p.id, "name": p.name}))
+                .collect::<Vec<_>>(),
+        }))
+        .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
 
-        let json = serde_json::to_string_pretty(&team)
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Get every dependency between projects as a graph: nodes are projects, flagged when they are blocked or depend on a blocked project, and edges point from the waiting project to the one it waits for")]
+    async fn get_dependency_graph(&self) -> Result<CallToolResult, McpError> {
+        let db = self.db.lock().await;
+        let graph = db::DependencyRepository::new(&db).get_dependency_graph()
+            .map_err(|e| db_error("Failed to get dependency graph", e))?;
+
+        let json = serde_json::to_string_pretty(&graph)
             .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
 
         Ok(CallToolResult::success(vec![Content::text(json)]))
     }
 
-    #[tool(description = "Create a new team")]
-    async fn create_team(&self, Parameters(req): Parameters<CreateTeamRequest>) -> Result<CallToolResult, McpError> {
-        let mut team = db::Team::new(req.name);
+    #[tool(description = "Draw the org chart, team membership or project dependencies as Mermaid or Graphviz DOT text, ready to put in a code block. Overdue projects and milestones are red")]
+    async fn generate_diagram(&self, Parameters(req): Parameters<GenerateDiagramRequest>) -> Result<CallToolResult, McpError> {
+        let kind = req.kind.parse::<DiagramKind>().map_err(|e| db_error("Invalid diagram", e))?;
+        let format = match req.format.as_deref() {
+            Some(format) => format.parse::<DiagramFormat>().map_err(|e| db_error("Invalid diagram format", e))?,
+            None => DiagramFormat::default(),
+        };
 
-        if let Some(desc) = req.description {
-            team.description = Some(desc);
-        }
-        if let Some(manager) = req.manager {
-            team.manager = Some(manager);
-        }
+        let db = self.db.lock().await;
+        let diagram = diagram::build(&db, kind, chrono::Utc::now())
+            .map_err(|e| db_error("Failed to build diagram", e))?;
+
+        Ok(CallToolResult::success(vec![Content::text(diagram.render(format))]))
+    }
 
+    #[tool(description = "List the open action items assigned to a person across all projects, soonest due first")]
+    async fn my_action_items(&self, Parameters(req): Parameters<MyActionItemsRequest>) -> Result<CallToolResult, McpError> {
         let db = self.db.lock().await;
-        let repo = db::TeamRepository::new(&db);
-        repo.create(&team)
-            .map_err(|e| McpError::internal_error("Failed to create team", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let repo = db::ProjectRepository::new(&db);
+        let items = repo.list_open_action_items_for(&req.email)
+            .map_err(|e| db_error("Failed to list action items", e))?;
 
-        let json = serde_json::to_string_pretty(&team)
+        let json = serde_json::to_string_pretty(&items)
             .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
 
         Ok(CallToolResult::success(vec![Content::text(json)]))
     }
 
-    #[tool(description = "Update a team")]
-    async fn update_team(&self, Parameters(req): Parameters<UpdateTeamRequest>) -> Result<CallToolResult, McpError> {
+    // Milestone Note tools
+
+    #[tool(description = "Create a note for a milestone")]
+    async fn create_milestone_note(&self, Parameters(req): Parameters<CreateMilestoneNoteRequest>) -> Result<CallToolResult, McpError> {
+        let milestone_uuid = Uuid::parse_str(&req.milestone_id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        let note = db::MilestoneNote::new(milestone_uuid, req.title, req.body);
+
+        self.write(move |db, _| {
+            let repo = db::ProjectRepository::new(db);
+            repo.add_milestone_note(&note)
+                .map_err(|e| db_error("Failed to create note", e))?;
+
+            let json = serde_json::to_string_pretty(&note)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
+
+    #[tool(description = "List notes for a milestone, newest first. Pass limit to get one page at a time as {notes, next_cursor}, then pass next_cursor back as cursor for the next page")]
+    async fn list_milestone_notes(&self, Parameters(req): Parameters<GetMilestoneNotesRequest>) -> Result<CallToolResult, McpError> {
+        let milestone_uuid = Uuid::parse_str(&req.milestone_id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+
         let db = self.db.lock().await;
-        let repo = db::TeamRepository::new(&db);
+        let repo = db::ProjectRepository::new(&db);
+        let rendered = req.rendered.unwrap_or(false);
+        let notes = if req.limit.is_some() || req.cursor.is_some() {
+            let cursor = note_cursor(req.cursor.as_deref())?;
+            let limit = req.limit.unwrap_or(db::project_repo::DEFAULT_NOTE_PAGE_SIZE);
+            let notes = repo.get_milestone_notes_page(&milestone_uuid, cursor, limit)
+                .map_err(|e| db_error("Failed to list notes", e))?;
+            serde_json::to_value(notes::page_with_html(notes, limit, rendered, req.max_chars))
+        } else {
+            let notes = repo.get_milestone_notes(&milestone_uuid)
+                .map_err(|e| db_error("Failed to list notes", e))?;
+            serde_json::to_value(notes::with_html(notes, rendered, req.max_chars))
+        }
+        .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        self.with_handles(HandleKind::Note, &notes)
+    }
+
+    #[tool(description = "Update a milestone note")]
+    async fn update_milestone_note(&self, Parameters(req): Parameters<UpdateMilestoneNoteRequest>) -> Result<CallToolResult, McpError> {
+        let note_uuid = Uuid::parse_str(&req.id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+        self.write(move |db, ctx| {
+
+            // Fetch existing note first
+            let repo = db::ProjectRepository::new(db);
+            let mut note = repo.find_milestone_note_by_id(&note_uuid)
+                .map_err(|e| db_error("Database error", e))?
+                .ok_or_else(|| db_error("Database error", db::Error::not_found("Milestone note", note_uuid)))?;
+
+            note.version = expected_version(&ctx.config, req.version, note.version)?;
+
+            // Update fields
+            note.title = req.title;
+            note.body = req.body;
+            note.updated_at = chrono::Utc::now();
+
+            repo.update_milestone_note(&note)
+                .map_err(|e| db_error("Failed to update note", e))?;
+            note.version += 1;
+
+            let json = serde_json::to_string_pretty(&note)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
+    }
 
-        // Fetch existing team first
-        let mut team = repo.find_by_name(&req.name)
-            .map_err(|e| McpError::internal_error("Database error", Some(serde_json::json!({"error": e.to_string()}))))?
-            .ok_or_else(|| McpError::invalid_params("Team not found", None))?;
+    #[tool(description = "Delete a milestone note")]
+    async fn delete_milestone_note(&self, Parameters(req): Parameters<DeleteMilestoneNoteRequest>) -> Result<CallToolResult, McpError> {
+        let note_uuid = Uuid::parse_str(&req.id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
 
-        // Update fields
-        team.description = req.description;
-        team.manager = req.manager;
+        self.write(move |db, _| {
+            let repo = db::ProjectRepository::new(db);
+            repo.delete_milestone_note(&note_uuid)
+                .map_err(|e| db_error("Failed to delete note", e))?;
 
-        repo.update(&team)
-            .map_err(|e| McpError::internal_error("Failed to update team", Some(serde_json::json!({"error": e.to_string()}))))?;
+            Ok(CallToolResult::success(vec![Content::text(format!("Deleted note {}", req.id))]))
+        }).await
+    }
 
-        let json = serde_json::to_string_pretty(&team)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+    // Stakeholder Note tools
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
-    }
+    #[tool(description = "Create a note for a stakeholder")]
+    async fn create_stakeholder_note(&self, Parameters(req): Parameters<CreateStakeholderNoteRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
 
-    #[tool(description = "Delete a team")]
-    async fn delete_team(&self, Parameters(req): Parameters<DeleteTeamRequest>) -> Result<CallToolResult, McpError> {
-        let db = self.db.lock().await;
-        let repo = db::TeamRepository::new(&db);
-        repo.delete(&req.name)
-            .map_err(|e| McpError::internal_error("Failed to delete team", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let note = db::StakeholderNote::new(project_uuid, req.stakeholder_email, req.title, req.body);
 
-        Ok(CallToolResult::success(vec![Content::text(format!("Deleted team {}", req.name))]))
-    }
+        self.write(move |db, _| {
+            let repo = db::ProjectRepository::new(db);
+            repo.add_stakeholder_note(&note)
+                .map_err(|e| db_error("Failed to create note", e))?;
 
-    #[tool(description = "Add a member to a team")]
-    async fn add_team_member(&self, Parameters(req): Parameters<TeamMemberRequest>) -> Result<CallToolResult, McpError> {
-        let db = self.db.lock().await;
-        let repo = db::TeamRepository::new(&db);
-        repo.add_member(&req.team_name, &req.person_email)
-            .map_err(|e| McpError::internal_error("Failed to add team member", Some(serde_json::json!({"error": e.to_string()}))))?;
+            let json = serde_json::to_string_pretty(&note)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
 
-        Ok(CallToolResult::success(vec![Content::text(format!("Added {} to team {}", req.person_email, req.team_name))]))
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
     }
 
-    #[tool(description = "Remove a member from a team")]
-    async fn remove_team_member(&self, Parameters(req): Parameters<TeamMemberRequest>) -> Result<CallToolResult, McpError> {
+    #[tool(description = "List notes for a stakeholder, newest first. Pass limit to get one page at a time as {notes, next_cursor}, then pass next_cursor back as cursor for the next page")]
+    async fn list_stakeholder_notes(&self, Parameters(req): Parameters<GetStakeholderNotesRequest>) -> Result<CallToolResult, McpError> {
+        let project_uuid = self.project_ref(&req.project_id).await?;
+
         let db = self.db.lock().await;
-        let repo = db::TeamRepository::new(&db);
-        repo.remove_member(&req.team_name, &req.person_email)
-            .map_err(|e| McpError::internal_error("Failed to remove team member", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let repo = db::ProjectRepository::new(&db);
+        let rendered = req.rendered.unwrap_or(false);
+        let notes = if req.limit.is_some() || req.cursor.is_some() {
+            let cursor = note_cursor(req.cursor.as_deref())?;
+            let limit = req.limit.unwrap_or(db::project_repo::DEFAULT_NOTE_PAGE_SIZE);
+            let notes = repo.get_stakeholder_notes_page(&project_uuid, &req.stakeholder_email, cursor, limit)
+                .map_err(|e| db_error("Failed to list notes", e))?;
+            serde_json::to_value(notes::page_with_html(notes, limit, rendered, req.max_chars))
+        } else {
+            let notes = repo.get_stakeholder_notes(&project_uuid, &req.stakeholder_email)
+                .map_err(|e| db_error("Failed to list notes", e))?;
+            serde_json::to_value(notes::with_html(notes, rendered, req.max_chars))
+        }
+        .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
 
-        Ok(CallToolResult::success(vec![Content::text(format!("Removed {} from team {}", req.person_email, req.team_name))]))
+        self.with_handles(HandleKind::Note, &notes)
     }
 
-    #[tool(description = "Get all members of a team")]
-    async fn get_team_members(&self, Parameters(req): Parameters<GetTeamMembersRequest>) -> Result<CallToolResult, McpError> {
-        let db = self.db.lock().await;
-        let repo = db::TeamRepository::new(&db);
-        let members = repo.get_members(&req.team_name)
-            .map_err(|e| McpError::internal_error("Failed to get team members", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tool(description = "Update a stakeholder note")]
+    async fn update_stakeholder_note(&self, Parameters(req): Parameters<UpdateStakeholderNoteRequest>) -> Result<CallToolResult, McpError> {
+        let note_uuid = Uuid::parse_str(&req.id)
+            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
 
-        let json = serde_json::to_string_pretty(&members)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+        self.write(move |db, ctx| {
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
-    }
+            // Fetch existing note first
+            let repo = db::ProjectRepository::new(db);
+            let mut note = repo.find_stakeholder_note_by_id(&note_uuid)
+                .map_err(|e| db_error("Database error", e))?
+                .ok_or_else(|| db_error("Database error", db::Error::not_found("Stakeholder note", note_uuid)))?;
 
-    // Milestone tools
+            note.version = expected_version(&ctx.config, req.version, note.version)?;
 
-    #[tool(description = "List milestones for a project")]
-    async fn list_milestones(&self, Parameters(req): Parameters<GetMilestonesRequest>) -> Result<CallToolResult, McpError> {
-        let uuid = Uuid::parse_str(&req.project_id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+            // Update fields
+            note.title = req.title;
+            note.body = req.body;
+            note.updated_at = chrono::Utc::now();
 
-        let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        let milestones = repo.get_milestones(&uuid)
-            .map_err(|e| McpError::internal_error("Failed to list milestones", Some(serde_json::json!({"error": e.to_string()}))))?;
+            repo.update_stakeholder_note(&note)
+                .map_err(|e| db_error("Failed to update note", e))?;
+            note.version += 1;
 
-        let json = serde_json::to_string_pretty(&milestones)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+            let json = serde_json::to_string_pretty(&note)
+                .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+            Ok(CallToolResult::success(vec![Content::text(json)]))
+        }).await
     }
 
-    #[tool(description = "Get a milestone by ID")]
-    async fn get_milestone(&self, Parameters(req): Parameters<GetMilestoneRequest>) -> Result<CallToolResult, McpError> {
-        let milestone_uuid = Uuid::parse_str(&req.id)
+    #[tool(description = "Delete a stakeholder note")]
+    async fn delete_stakeholder_note(&self, Parameters(req): Parameters<DeleteStakeholderNoteRequest>) -> Result<CallToolResult, McpError> {
+        let note_uuid = Uuid::parse_str(&req.id)
             .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
 
-        let db = self.db.lock().await;
-
-        // Query milestone directly from database
-        let milestone = db.query_row(
-            "SELECT id, project_id, number, name, description, technical_lead, team, design_doc_url, start_date, due_date, jira_epic, created_at, updated_at
-             FROM milestones WHERE id = ?1",
-            rusqlite::params![milestone_uuid.to_string()],
-            |row| {
-                Ok(db::Milestone {
-                    id: Uuid::parse_str(&row.get::<_, String>(0)?).unwrap(),
-                    project_id: Uuid::parse_str(&row.get::<_, String>(1)?).unwrap(),
-                    number: row.get(2)?,
-                    name: row.get(3)?,
-                    description: row.get(4)?,
-                    technical_lead: row.get(5)?,
-                    team: row.get(6)?,
-                    design_doc_url: row.get(7)?,
-                    start_date: row.get(8)?,
-                    due_date: row.get(9)?,
-                    jira_epic: row.get(10)?,
-                    created_at: row.get(11)?,
-                    updated_at: row.get(12)?,
-                })
-            },
-        ).optional()
-            .map_err(|e| McpError::internal_error("Database error", Some(serde_json::json!({"error": e.to_string()}))))?
-            .ok_or_else(|| McpError::invalid_params("Milestone not found", None))?;
-
-        let json = serde_json::to_string_pretty(&milestone)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+        self.write(move |db, _| {
+            let repo = db::ProjectRepository::new(db);
+            repo.delete_stakeholder_note(&note_uuid)
+                .map_err(|e| db_error("Failed to delete note", e))?;
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+            Ok(CallToolResult::success(vec![Content::text(format!("Deleted note {}", req.id))]))
+        }).await
     }
 
-    #[tool(description = "Create a new milestone")]
-    async fn create_milestone(&self, Parameters(req): Parameters<CreateMilestoneRequest>) -> Result<CallToolResult, McpError> {
-        let project_uuid = Uuid::parse_str(&req.project_id)
-            .map_err(|e| McpError::invalid_params("Invalid project UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tool(description = "Switch the server to another workspace, which has its own database. Calls already running finish on the current workspace first.")]
+    async fn switch_workspace(&self, Parameters(req): Parameters<SwitchWorkspaceRequest>) -> Result<CallToolResult, McpError> {
+        self.config().database_path_for(&req.name)
+            .map_err(|e| McpError::invalid_params(e.to_string(), Some(serde_json::json!({"workspaces": self.config().workspace_names()}))))?;
+        self.use_workspace(&req.name).await
+            .map_err(|e| McpError::internal_error("Failed to open workspace", Some(serde_json::json!({"error": format!("{:#}", e)}))))?;
 
-        let mut milestone = db::Milestone::new(project_uuid, req.number, req.name);
-        milestone.description = req.description;
-        milestone.technical_lead = req.technical_lead;
-        milestone.team = req.team;
-        milestone.design_doc_url = req.design_doc_url;
-        milestone.jira_epic = req.jira_epic;
+        Ok(CallToolResult::success(vec![Content::text(format!("Switched to workspace {}", req.name))]))
+    }
+}
 
-        // Parse dates if provided
-        if let Some(start_date_str) = req.start_date {
-            milestone.start_date = Some(chrono::DateTime::parse_from_rfc3339(&start_date_str)
-                .map_err(|e| McpError::invalid_params("Invalid start_date format", Some(serde_json::json!({"error": e.to_string()}))))?
-                .with_timezone(&chrono::Utc));
+impl ProjectTrackerServer {
+    /// The server's description, with the role line when it is known
+    fn server_info(&self, role: Option<String>) -> ServerInfo {
+        let mut workspace = format!("Workspace: {}", self.workspace());
+        if self.tool_router.has_route("switch_workspace") {
+            workspace.push_str(&format!(
+                " (switch_workspace changes it; configured: {})",
+                self.config().workspace_names().join(", ")
+            ));
         }
-        if let Some(due_date_str) = req.due_date {
-            milestone.due_date = Some(chrono::DateTime::parse_from_rfc3339(&due_date_str)
-                .map_err(|e| McpError::invalid_params("Invalid due_date format", Some(serde_json::json!({"error": e.to_string()}))))?
-                .with_timezone(&chrono::Utc));
+        if let Some(role) = role {
+            workspace.push('\n');
+            workspace.push_str(&role);
         }
+        let confirmation = if self.config().mcp_require_confirmation {
+            "\nDeletes need confirming: delete_project, delete_person, delete_team and delete_milestone first \
+            return a summary and a confirmation_token; show the summary to the user, then repeat the call with the token"
+        } else {
+            ""
+        };
 
-        let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        repo.add_milestone(&milestone)
-            .map_err(|e| McpError::internal_error("Failed to create milestone", Some(serde_json::json!({"error": e.to_string()}))))?;
-
-        let json = serde_json::to_string_pretty(&milestone)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
-
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+        ServerInfo {
+            protocol_version: ProtocolVersion::V_2024_11_05,
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_resources()
+                .enable_prompts()
+                .build(),
+            server_info: Implementation::from_build_env(),
+            instructions: Some(format!(
+                "{}\n{}{}",
+                workspace,
+                "Project Tracker MCP Server. Available tools:\n\
+                Projects: list_projects, get_project, get_project_brief, get_project_summaries, get_slippage_report, get_effort_summary, get_portfolio_stats, get_hygiene_report, get_project_activity, create_project, update_project, delete_project, block_project, unblock_project, set_project_phase, get_phase_history, list_projects_by_phase, set_project_slug, list_blocked_projects, get_board\n\
+                Queries: run_query, save_query, list_saved_queries, delete_saved_query\n\
+                Users: list_users, set_user_role, remove_user (viewer, editor and admin roles for a shared database; admins manage them)\n\
+                Custom Fields: list_custom_fields, define_custom_field, delete_custom_field, set_project_custom_field (values appear in get_project)\n\
+                People: list_people, search_people, get_person, create_person, update_person, delete_person, deactivate_person, reactivate_person, change_person_email\n\
+                Person Skills: add_person_skill, remove_person_skill, find_people_by_skill\n\
+                Staffing: get_availability_forecast (when people free up from their milestones, by team)\n\
+                Person Notes: create_person_note, list_person_notes, update_person_note, delete_person_note\n\
+                1:1s: log_one_on_one, list_one_on_ones (delete_person needs delete_one_on_ones for people with 1:1s)\n\
+                Teams: list_teams, search_teams, get_team, create_team, update_team, delete_team, add_team_member, remove_team_member, get_team_members, get_team_tree, generate_team_review\n\
+                Milestones: list_milestones, get_milestone, create_milestone, update_milestone, delete_milestone, suggest_project_due_date, get_quarter_plan\n\
+                Stakeholders: add_project_stakeholder, list_project_stakeholders, generate_stakeholder_brief, get_stakeholder_matrix, update_project_stakeholder, remove_project_stakeholder\n\
+                Project Resources: add_project_resource, assign_team_to_project, list_project_resources, update_project_resource, remove_project_resource\n\
+                Milestone Resources: add_milestone_resource, list_milestone_resources, suggest_resources, update_milestone_resource, remove_milestone_resource\n\
+                Milestone Checklists: add_checklist_item, toggle_checklist_item, list_checklist_items, remove_checklist_item (checklists also appear in list_milestones)\n\
+                Project Notes: create_project_note, list_project_notes, update_project_note, append_to_note, delete_project_note (log notes, such as a decision log, only take append_to_note entries)\n\
+                Notes: create_notes_batch, move_note, get_note, get_note_summary, list_attachments, get_backlinks (notes link records with [[project:slug]], [[person:email]] or [[milestone:id]])\n\
+                Inbox: create_inbox_note, list_inbox_notes, triage_inbox_note\n\
+                Project Documents: add_project_document, list_project_documents, remove_project_document\n\
+                Project Budget: add_expense, list_expenses, get_budget_status (set budget_amount and budget_currency with create_project/update_project)\n\
+                Project Snapshots: create_snapshot, list_snapshots, compare_snapshots\n\
+                Retrospectives: save_retrospective, get_retrospective (completed projects without one appear in get_hygiene_report)\n\
+                Decisions: record_decision, list_decisions, supersede_decision (decisions also appear in project briefs, and stakeholder briefs list the ones the stakeholder approved)\n\
+                Organizations: create_organization, list_organizations, get_organization_overview (vendors, clients and partners people belong to; set a person's organization with create_person or update_person, and filter list_projects by organization)\n\
+                Watching: watch_project, unwatch_project (list_projects with only_watched lists the watched ones; the digest and reminders cover projects current_user_email watches, manages or leads)\n\
+                Focus: get_focus_list (what current_user_email should look at today, most urgent first)\n\
+                External tickets: add_external_ref, list_external_refs, remove_external_ref (GitHub, Linear and other trackers for projects and milestones; jira_initiative and jira_epic are listed as jira references)\n\
+                Project Risks: add_risk, update_risk, list_risks, close_risk\n\
+                Action Items: create_action_item, complete_action_item, list_action_items, my_action_items\n\
+                Initiatives: create_initiative, link_project_to_initiative, list_initiatives, get_initiative_progress\n\
+                Dependencies: add_project_dependency, remove_project_dependency, list_project_dependencies, get_dependency_graph\n\
+                Diagrams: generate_diagram (Mermaid or Graphviz text of the org chart, teams or project dependencies)\n\
+                Milestone Notes: create_milestone_note, list_milestone_notes, update_milestone_note, delete_milestone_note\n\
+                Stakeholder Notes: create_stakeholder_note, list_stakeholder_notes, update_stakeholder_note, delete_stakeholder_note\n\
+                Retries: every tool that changes data takes an optional idempotency_key; retrying with the same key and arguments returns the first result instead of making the change twice\n\
+                Handles: list and get tools give each project, milestone and note a short handle such as P1, M3 or N7 (listed at the end of the result); pass it to any ID argument instead of the UUID. Handles only work in this session\n\
+                Resources: project://{project_id} (project as markdown), project://{project_id}/notes/{note_id} (project note)\n\
+                Prompts: weekly_status, new_project_intake, stakeholder_prep",
+                confirmation
+            )),
+        }
     }
+}
 
-    #[tool(description = "Update a milestone")]
-    async fn update_milestone(&self, Parameters(req): Parameters<UpdateMilestoneRequest>) -> Result<CallToolResult, McpError> {
-        let milestone_uuid = Uuid::parse_str(&req.id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+impl ServerHandler for ProjectTrackerServer {
+    /// Without waiting for the database, so the role is left out while it is busy
+    fn get_info(&self) -> ServerInfo {
+        let role = self.db.try_lock().ok().and_then(|db| self.role_instructions(&db));
+        self.server_info(role)
+    }
 
-        let db = self.db.lock().await;
+    async fn initialize(
+        &self,
+        _request: InitializeRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<InitializeResult, McpError> {
+        let role = {
+            let db = self.db.lock().await;
+            self.role_instructions(&db)
+        };
+        Ok(self.server_info(role))
+    }
 
-        // Fetch existing milestone first - query directly
-        let mut milestone = db.query_row(
-            "SELECT id, project_id, number, name, description, technical_lead, team, design_doc_url, start_date, due_date, jira_epic, created_at, updated_at
-             FROM milestones WHERE id = ?1",
-            rusqlite::params![milestone_uuid.to_string()],
-            |row| {
-                Ok(db::Milestone {
-                    id: Uuid::parse_str(&row.get::<_, String>(0)?).unwrap(),
-                    project_id: Uuid::parse_str(&row.get::<_, String>(1)?).unwrap(),
-                    number: row.get(2)?,
-                    name: row.get(3)?,
-                    description: row.get(4)?,
-                    technical_lead: row.get(5)?,
-                    team: row.get(6)?,
-                    design_doc_url: row.get(7)?,
-                    start_date: row.get(8)?,
-                    due_date: row.get(9)?,
-                    jira_epic: row.get(10)?,
-                    created_at: row.get(11)?,
-                    updated_at: row.get(12)?,
-                })
-            },
-        ).optional()
-            .map_err(|e| McpError::internal_error("Database error", Some(serde_json::json!({"error": e.to_string()}))))?
-            .ok_or_else(|| McpError::invalid_params("Milestone not found", None))?;
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        let tools = self
+            .tool_router
+            .list_all()
+            .into_iter()
+            .map(|mut tool| {
+                if auth::operation(&tool.name) != auth::Operation::Read {
+                    journal::add_parameter(&mut tool);
+                }
+                tool
+            })
+            .collect();
+        Ok(ListToolsResult::with_all_items(tools))
+    }
 
-        // Update fields
-        milestone.number = req.number;
-        milestone.name = req.name;
-        milestone.description = req.description;
-        milestone.technical_lead = req.technical_lead;
-        milestone.team = req.team;
-        milestone.design_doc_url = req.design_doc_url;
-        milestone.jira_epic = req.jira_epic;
+    /// Route the call to its tool, recording it in the metrics
+    async fn call_tool(
+        &self,
+        mut request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        // Unknown names are counted together so callers can't add labels at will
+        let tool = if self.tool_router.has_route(&request.name) {
+            request.name.to_string()
+        } else {
+            "unknown".to_string()
+        };
 
-        // Parse dates if provided
-        if let Some(start_date_str) = req.start_date {
-            milestone.start_date = Some(chrono::DateTime::parse_from_rfc3339(&start_date_str)
-                .map_err(|e| McpError::invalid_params("Invalid start_date format", Some(serde_json::json!({"error": e.to_string()}))))?
-                .with_timezone(&chrono::Utc));
-        }
-        if let Some(due_date_str) = req.due_date {
-            milestone.due_date = Some(chrono::DateTime::parse_from_rfc3339(&due_date_str)
-                .map_err(|e| McpError::invalid_params("Invalid due_date format", Some(serde_json::json!({"error": e.to_string()}))))?
-                .with_timezone(&chrono::Utc));
+        let started = Instant::now();
+        let result = async {
+            // Handles from earlier results are swapped for their IDs before the tool sees them
+            if let Some(arguments) = request.arguments.as_mut() {
+                self.handles
+                    .resolve_arguments(arguments)
+                    .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+            }
+            if tool != "unknown" {
+                self.authorize(&tool).await?;
+            }
+
+            // A retried call gets the first call's result back
+            let operation = if tool != "unknown" && auth::operation(&tool) != auth::Operation::Read {
+                journal::Operation::from_arguments(&tool, request.arguments.as_mut())?
+            } else {
+                None
+            };
+            let Some(operation) = operation else {
+                return self.tool_router.call(ToolCallContext::new(self, request, context)).await;
+            };
+            let check = operation.clone();
+            if let Some(result) = self
+                .writes
+                .submit(move |db, ctx| check.replay(db, ctx.config.mcp_idempotency_ttl_hours))
+                .await??
+            {
+                return Ok(result);
+            }
+            // The tool's write checks the journal again and records the result
+            // in its own transaction, so a retry racing this call replays it.
+            // A call that returns before writing, such as a request for
+            // confirmation, changed nothing and isn't recorded.
+            operation
+                .scope(self.tool_router.call(ToolCallContext::new(self, request, context)))
+                .await
         }
+        .await;
+        let failed = match &result {
+            Ok(result) => result.is_error == Some(true),
+            Err(_) => true,
+        };
+        self.metrics.record_tool_call(&tool, started.elapsed(), failed);
 
-        let repo = db::ProjectRepository::new(&db);
-        repo.update_milestone(&milestone)
-            .map_err(|e| McpError::internal_error("Failed to update milestone", Some(serde_json::json!({"error": e.to_string()}))))?;
+        result
+    }
 
-        let json = serde_json::to_string_pretty(&milestone)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, McpError> {
+        Ok(ListPromptsResult::with_all_items(prompts::list()))
+    }
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, McpError> {
+        prompts::get(&request.name, request.arguments.as_ref())
+            .map_err(|e| McpError::invalid_params(e.to_string(), Some(serde_json::json!({"prompt": request.name}))))
     }
 
-    #[tool(description = "Delete a milestone")]
-    async fn delete_milestone(&self, Parameters(req): Parameters<DeleteMilestoneRequest>) -> Result<CallToolResult, McpError> {
-        let milestone_uuid = Uuid::parse_str(&req.id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+    async fn list_resources(
+        &self,
+        request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        let cursor = match request.and_then(|r| r.cursor) {
+            Some(cursor) => cursor
+                .parse()
+                .map_err(|e: anyhow::Error| McpError::invalid_params(e.to_string(), None))?,
+            None => resources::Cursor::default(),
+        };
 
         let db = self.db.lock().await;
         let repo = db::ProjectRepository::new(&db);
-        repo.delete_milestone(&milestone_uuid)
-            .map_err(|e| McpError::internal_error("Failed to delete milestone", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let (resources, next) = resources::list(&repo, cursor, resources::PAGE_SIZE)
+            .map_err(|e| db_error("Failed to list resources", e))?;
 
-        Ok(CallToolResult::success(vec![Content::text(format!("Deleted milestone {}", req.id))]))
+        Ok(ListResourcesResult {
+            resources,
+            next_cursor: next.map(|cursor| cursor.to_string()),
+        })
     }
 
-    // Project Stakeholder tools
-
-    #[tool(description = "Add a stakeholder to a project")]
-    async fn add_project_stakeholder(&self, Parameters(req): Parameters<AddProjectStakeholderRequest>) -> Result<CallToolResult, McpError> {
-        let project_uuid = Uuid::parse_str(&req.project_id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, McpError> {
+        Ok(ListResourceTemplatesResult {
+            resource_templates: resources::templates(),
+            next_cursor: None,
+        })
+    }
 
-        let stakeholder = db::ProjectStakeholder {
-            project_id: project_uuid,
-            stakeholder_email: req.stakeholder_email.clone(),
-            role: req.role,
-            created_at: chrono::Utc::now(),
-        };
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        let uri: ResourceUri = request.uri.parse()
+            .map_err(|e: anyhow::Error| McpError::invalid_params(e.to_string(), Some(serde_json::json!({"uri": request.uri}))))?;
 
         let db = self.db.lock().await;
         let repo = db::ProjectRepository::new(&db);
-        repo.add_stakeholder(&project_uuid, &stakeholder)
-            .map_err(|e| McpError::internal_error("Failed to add stakeholder", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let text = resources::read(&repo, &uri, self.config().tz(), self.config().effort_warning_ratio).map_err(|e| match e {
+            db::Error::NotFound { .. } => {
+                McpError::resource_not_found(e.to_string(), Some(serde_json::json!({"uri": request.uri})))
+            }
+            e => db_error("Failed to read resource", e),
+        })?;
 
-        let json = serde_json::to_string_pretty(&stakeholder)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+        Ok(ReadResourceResult {
+            contents: vec![ResourceContents::TextResourceContents {
+                uri: request.uri,
+                mime_type: Some(resources::MIME_TYPE.to_string()),
+                text,
+                meta: None,
+            }],
+        })
+    }
+}
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::{service::RunningService, RoleClient, ServiceError, ServiceExt};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
     }
 
-    #[tool(description = "List stakeholders for a project")]
-    async fn list_project_stakeholders(&self, Parameters(req): Parameters<GetProjectStakeholdersRequest>) -> Result<CallToolResult, McpError> {
-        let project_uuid = Uuid::parse_str(&req.project_id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+    /// Start a server for `conn` over an in-memory transport and connect a client to it
+    async fn serve(conn: Connection) -> RunningService<RoleClient, ()> {
+        serve_with(ProjectTrackerServer::new(Config::default(), conn)).await
+    }
 
-        let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        let stakeholders = repo.get_stakeholders(&project_uuid)
-            .map_err(|e| McpError::internal_error("Failed to list stakeholders", Some(serde_json::json!({"error": e.to_string()}))))?;
+    /// Start a session of `server` over an in-memory transport and connect a client to it
+    async fn serve_with(server: ProjectTrackerServer) -> RunningService<RoleClient, ()> {
+        let (server_transport, client_transport) = tokio::io::duplex(4096);
+        tokio::spawn(async move {
+            let service = server.serve(server_transport).await?;
+            service.waiting().await?;
+            anyhow::Ok(())
+        });
 
-        let json = serde_json::to_string_pretty(&stakeholders)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+        ().serve(client_transport).await.unwrap()
+    }
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+    /// Connect to a server with one project that has one note
+    async fn connect() -> (RunningService<RoleClient, ()>, db::Project, db::ProjectNote) {
+        let conn = setup_test_db();
+
+        let repo = db::ProjectRepository::new(&conn);
+        let project = db::Project::new("Apollo".to_string());
+        repo.create(&project).unwrap();
+        let note = db::ProjectNote::new(project.id, "Status".to_string(), "All systems go".to_string());
+        repo.add_project_note(&note).unwrap();
+
+        (serve(conn).await, project, note)
     }
 
-    #[tool(description = "Update a project stakeholder")]
-    async fn update_project_stakeholder(&self, Parameters(req): Parameters<UpdateProjectStakeholderRequest>) -> Result<CallToolResult, McpError> {
-        let project_uuid = Uuid::parse_str(&req.project_id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+    /// Call `create_project` and return the project ID from the result
+    async fn create_project(
+        client: &RunningService<RoleClient, ()>,
+        arguments: serde_json::Value,
+    ) -> Result<String, ServiceError> {
+        let result = client
+            .call_tool(CallToolRequestParam {
+                name: "create_project".into(),
+                arguments: arguments.as_object().cloned(),
+            })
+            .await?;
+        let text = &result.content[0].as_text().unwrap().text;
+        let project: serde_json::Value = serde_json::from_str(text).unwrap();
+        Ok(project["id"].as_str().unwrap().to_string())
+    }
 
-        let stakeholder = db::ProjectStakeholder {
-            project_id: project_uuid,
-            stakeholder_email: req.stakeholder_email.clone(),
-            role: req.role,
-            created_at: chrono::Utc::now(), // This will be ignored by update
-        };
+    fn error_code(err: ServiceError) -> ErrorCode {
+        match err {
+            ServiceError::McpError(e) => e.code,
+            other => panic!("unexpected error: {}", other),
+        }
+    }
 
-        let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        repo.update_stakeholder(&project_uuid, &stakeholder)
-            .map_err(|e| McpError::internal_error("Failed to update stakeholder", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_list_resources() {
+        let (client, project, note) = connect().await;
+
+        let result = client.list_resources(None).await.unwrap();
+        let uris: Vec<String> = result.resources.into_iter().map(|r| r.raw.uri).collect();
+        assert_eq!(
+            uris,
+            vec![
+                format!("project://{}", project.id),
+                format!("project://{}/notes/{}", project.id, note.id),
+            ]
+        );
+        assert!(result.next_cursor.is_none());
+
+        let err = client
+            .list_resources(Some(PaginatedRequestParam { cursor: Some("bogus".to_string()) }))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+    }
 
-        let json = serde_json::to_string_pretty(&stakeholder)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_read_resource() {
+        let (client, project, note) = connect().await;
+
+        let uri = format!("project://{}/notes/{}", project.id, note.id);
+        let result = client
+            .read_resource(ReadResourceRequestParam { uri: uri.clone() })
+            .await
+            .unwrap();
+        match &result.contents[..] {
+            [ResourceContents::TextResourceContents { uri: read_uri, text, .. }] => {
+                assert_eq!(read_uri, &uri);
+                assert_eq!(text, "# Status\n\nAll systems go\n");
+            }
+            other => panic!("unexpected contents: {:?}", other),
+        }
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+        let result = client
+            .read_resource(ReadResourceRequestParam { uri: format!("project://{}", project.id) })
+            .await
+            .unwrap();
+        assert!(matches!(
+            &result.contents[..],
+            [ResourceContents::TextResourceContents { text, .. }] if text.starts_with("# Apollo")
+        ));
     }
 
-    #[tool(description = "Remove a stakeholder from a project")]
-    async fn remove_project_stakeholder(&self, Parameters(req): Parameters<RemoveProjectStakeholderRequest>) -> Result<CallToolResult, McpError> {
-        let project_uuid = Uuid::parse_str(&req.project_id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_read_resource_errors() {
+        let (client, project, _) = connect().await;
+
+        let err = client
+            .read_resource(ReadResourceRequestParam { uri: "project://not-a-uuid".to_string() })
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+
+        let err = client
+            .read_resource(ReadResourceRequestParam {
+                uri: format!("project://{}/notes/12345", project.id),
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+
+        let err = client
+            .read_resource(ReadResourceRequestParam { uri: format!("project://{}", Uuid::new_v4()) })
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::RESOURCE_NOT_FOUND);
+    }
 
-        let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        repo.remove_stakeholder(&project_uuid, &req.stakeholder_email)
-            .map_err(|e| McpError::internal_error("Failed to remove stakeholder", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_prompts() {
+        let client = serve(setup_test_db()).await;
+        assert!(client.peer_info().unwrap().capabilities.prompts.is_some());
+
+        let prompts = client.list_all_prompts().await.unwrap();
+        let names: Vec<&str> = prompts.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["weekly_status", "new_project_intake", "stakeholder_prep"]);
+
+        let result = client
+            .get_prompt(GetPromptRequestParam {
+                name: "stakeholder_prep".to_string(),
+                arguments: serde_json::json!({"project": "Apollo", "email": "alice@example.com"}).as_object().cloned(),
+            })
+            .await
+            .unwrap();
+        assert!(matches!(
+            &result.messages[0].content,
+            PromptMessageContent::Text { text } if text.contains("alice@example.com about the project Apollo")
+        ));
+
+        let err = client
+            .get_prompt(GetPromptRequestParam { name: "stakeholder_prep".to_string(), arguments: None })
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_create_project_unknown_type() {
+        let client = serve(setup_test_db()).await;
+
+        let err = create_project(&client, serde_json::json!({"name": "Apollo", "project_type": "Org-wide"}))
+            .await
+            .unwrap_err();
+        match err {
+            ServiceError::McpError(e) => {
+                assert_eq!(e.code, ErrorCode::INVALID_PARAMS);
+                assert!(e.message.contains("Personal, Team, Company"), "{}", e.message);
+            }
+            other => panic!("unexpected error: {}", other),
+        }
 
-        Ok(CallToolResult::success(vec![Content::text(format!("Removed stakeholder {} from project {}", req.stakeholder_email, req.project_id))]))
+        create_project(&client, serde_json::json!({"name": "Apollo", "project_type": "Team"}))
+            .await
+            .unwrap();
     }
 
-    // Project Resource tools
+    #[tokio::test]
+    async fn test_create_project_bootstrap() {
+        let client = serve(setup_test_db()).await;
+        let milestone_names = |id: String| {
+            let client = &client;
+            async move {
+                let result = client
+                    .call_tool(CallToolRequestParam {
+                        name: "list_milestones".into(),
+                        arguments: serde_json::json!({"project_id": id}).as_object().cloned(),
+                    })
+                    .await
+                    .unwrap();
+                let milestones: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+                milestones
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|m| m["name"].as_str().unwrap().to_string())
+                    .collect::<Vec<_>>()
+            }
+        };
 
-    #[tool(description = "Add a resource to a project")]
-    async fn add_project_resource(&self, Parameters(req): Parameters<AddProjectResourceRequest>) -> Result<CallToolResult, McpError> {
-        let project_uuid = Uuid::parse_str(&req.project_id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+        // The default configuration sets up Team and Company projects
+        let team = create_project(&client, serde_json::json!({"name": "Apollo", "project_type": "Team"}))
+            .await
+            .unwrap();
+        assert_eq!(milestone_names(team).await, ["Kickoff"]);
+
+        let skipped = create_project(
+            &client,
+            serde_json::json!({"name": "Gemini", "project_type": "Company", "skip_bootstrap": true}),
+        )
+        .await
+        .unwrap();
+        assert!(milestone_names(skipped).await.is_empty());
+    }
 
-        let resource = db::ProjectResource {
-            project_id: project_uuid,
-            person_email: req.person_email.clone(),
-            role: req.role,
-            created_at: chrono::Utc::now(),
+    #[tokio::test]
+    async fn test_create_project_if_exists() {
+        let conn = setup_test_db();
+        db::schema::set_unique_project_names(&conn, true).unwrap();
+        let client = serve(conn).await;
+
+        let args = serde_json::json!({"name": "Apollo", "if_exists": "return_existing"});
+        let first = create_project(&client, args.clone()).await.unwrap();
+
+        // Retrying the same create returns the original project
+        let retry = create_project(&client, args).await.unwrap();
+        assert_eq!(retry, first);
+        let retry = create_project(&client, serde_json::json!({"name": "APOLLO", "if_exists": "return_existing"}))
+            .await
+            .unwrap();
+        assert_eq!(retry, first);
+
+        // Without return_existing the duplicate is an error naming the original
+        for args in [
+            serde_json::json!({"name": "Apollo"}),
+            serde_json::json!({"name": "Apollo", "if_exists": "error"}),
+        ] {
+            match create_project(&client, args).await.unwrap_err() {
+                ServiceError::McpError(e) => {
+                    assert_eq!(e.code, ErrorCode::INVALID_PARAMS);
+                    assert_eq!(e.data.unwrap()["existing_id"], first.as_str());
+                }
+                other => panic!("unexpected error: {}", other),
+            }
+        }
+
+        let other = create_project(&client, serde_json::json!({"name": "Gemini", "if_exists": "return_existing"}))
+            .await
+            .unwrap();
+        assert_ne!(other, first);
+    }
+
+    #[tokio::test]
+    async fn test_update_note_version_conflict() {
+        let (client, _, note) = connect().await;
+        let request = |version: Option<i64>| {
+            let mut args = serde_json::json!({"id": note.id.to_string(), "title": "Status", "body": "Holding"});
+            if let Some(version) = version {
+                args["version"] = version.into();
+            }
+            CallToolRequestParam {
+                name: "update_project_note".into(),
+                arguments: args.as_object().cloned(),
+            }
         };
 
-        let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        repo.add_project_resource(&project_uuid, &resource)
-            .map_err(|e| McpError::internal_error("Failed to add resource", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let result = client.call_tool(request(Some(1))).await.unwrap();
+        let updated: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(updated["version"], 2);
+
+        // A write based on the old version is rejected with the current note
+        match client.call_tool(request(Some(1))).await.unwrap_err() {
+            ServiceError::McpError(e) => {
+                assert_eq!(e.code, ErrorCode::INVALID_PARAMS);
+                assert_eq!(e.data.unwrap()["current"]["version"], 2);
+            }
+            other => panic!("unexpected error: {}", other),
+        }
 
-        let json = serde_json::to_string_pretty(&resource)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+        // Unversioned updates still win by default
+        client.call_tool(request(None)).await.unwrap();
+    }
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+    #[tokio::test]
+    async fn test_risk_tools() {
+        let (client, project, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+
+        // Levels are validated on write
+        let err = client
+            .call_tool(call("add_risk", serde_json::json!({
+                "project_id": project.id.to_string(), "title": "Weather", "severity": "severe", "likelihood": "low"
+            })))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+
+        let result = client
+            .call_tool(call("add_risk", serde_json::json!({
+                "project_id": project.id.to_string(), "title": "Weather", "severity": "High", "likelihood": "high"
+            })))
+            .await
+            .unwrap();
+        let risk: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(risk["severity"], "high");
+        let risk_id = risk["id"].as_str().unwrap().to_string();
+
+        // Without a project, high risks across the portfolio are listed
+        let result = client.call_tool(call("list_risks", serde_json::json!({}))).await.unwrap();
+        let risks: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(risks[0]["id"], risk_id.as_str());
+
+        client.call_tool(call("close_risk", serde_json::json!({"id": risk_id}))).await.unwrap();
+        let result = client
+            .call_tool(call("list_risks", serde_json::json!({"project_id": project.id.to_string(), "open_only": true})))
+            .await
+            .unwrap();
+        let risks: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(risks.as_array().unwrap().len(), 0);
     }
 
-    #[tool(description = "List resources for a project")]
-    async fn list_project_resources(&self, Parameters(req): Parameters<GetProjectResourcesRequest>) -> Result<CallToolResult, McpError> {
-        let project_uuid = Uuid::parse_str(&req.project_id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_action_item_tools() {
+        let (client, project, note) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+        client
+            .call_tool(call("create_person", serde_json::json!({"email": "alice@example.com", "name": "Alice"})))
+            .await
+            .unwrap();
+
+        // The assignee has to exist
+        let err = client
+            .call_tool(call("create_action_item", serde_json::json!({
+                "project_id": project.id.to_string(), "assignee_email": "nobody@example.com", "description": "Send agenda"
+            })))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+
+        let result = client
+            .call_tool(call("create_action_item", serde_json::json!({
+                "project_id": project.id.to_string(), "assignee_email": "alice@example.com",
+                "description": "Send agenda", "due_date": "2030-01-15", "source_note_id": note.id.to_string()
+            })))
+            .await
+            .unwrap();
+        let item: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(item["status"], "open");
+        assert_eq!(item["source_note_id"], note.id.to_string());
+        let item_id = item["id"].as_str().unwrap().to_string();
+
+        let result = client
+            .call_tool(call("my_action_items", serde_json::json!({"email": "alice@example.com"})))
+            .await
+            .unwrap();
+        let items: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(items[0]["id"], item_id.as_str());
+
+        let result = client
+            .call_tool(call("complete_action_item", serde_json::json!({"id": item_id})))
+            .await
+            .unwrap();
+        let item: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(item["status"], "completed");
+        assert!(item["completed_at"].is_string());
+
+        let result = client
+            .call_tool(call("list_action_items", serde_json::json!({"project_id": project.id.to_string(), "open_only": true})))
+            .await
+            .unwrap();
+        let items: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(items.as_array().unwrap().len(), 0);
+        let result = client
+            .call_tool(call("list_action_items", serde_json::json!({"project_id": project.id.to_string()})))
+            .await
+            .unwrap();
+        let items: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(items.as_array().unwrap().len(), 1);
+    }
 
-        let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        let resources = repo.get_project_resources(&project_uuid)
-            .map_err(|e| McpError::internal_error("Failed to list resources", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_initiative_tools() {
+        let (client, project, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
 
-        let json = serde_json::to_string_pretty(&resources)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let err = client
+            .call_tool(call("create_initiative", serde_json::json!({"name": "Growth", "quarter": "Q3"})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+
+        let result = client
+            .call_tool(call("create_initiative", serde_json::json!({"name": "Growth", "quarter": "2025-q3"})))
+            .await
+            .unwrap();
+        let initiative: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(initiative["quarter"], "2025-Q3");
+        let initiative_id = initiative["id"].as_str().unwrap().to_string();
+
+        client
+            .call_tool(call("link_project_to_initiative", serde_json::json!({
+                "project_id": project.id.to_string(), "initiative_id": initiative_id
+            })))
+            .await
+            .unwrap();
+
+        let result = client
+            .call_tool(call("list_initiatives", serde_json::json!({"quarter": "2025-Q3"})))
+            .await
+            .unwrap();
+        let initiatives: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(initiatives[0]["id"], initiative_id.as_str());
+
+        let result = client
+            .call_tool(call("get_initiative_progress", serde_json::json!({"id": initiative_id})))
+            .await
+            .unwrap();
+        let progress: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(progress["projects"][0]["project_id"], project.id.to_string());
+
+        // Unlinking leaves the initiative without projects
+        client
+            .call_tool(call("link_project_to_initiative", serde_json::json!({"project_id": project.id.to_string()})))
+            .await
+            .unwrap();
+        let result = client
+            .call_tool(call("get_initiative_progress", serde_json::json!({"id": initiative_id})))
+            .await
+            .unwrap();
+        let progress: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(progress["projects"].as_array().unwrap().len(), 0);
+    }
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+    #[tokio::test]
+    async fn test_list_project_notes_paged() {
+        let (client, project, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+        client
+            .call_tool(call("create_project_note", serde_json::json!({
+                "project_id": project.id.to_string(), "title": "Second", "body": "More"
+            })))
+            .await
+            .unwrap();
+
+        // Without a limit the notes come back as a plain list
+        let result = client
+            .call_tool(call("list_project_notes", serde_json::json!({"project_id": project.id.to_string()})))
+            .await
+            .unwrap();
+        let all: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(all.as_array().unwrap().len(), 2);
+
+        let result = client
+            .call_tool(call("list_project_notes", serde_json::json!({"project_id": project.id.to_string(), "limit": 1})))
+            .await
+            .unwrap();
+        let page: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(page["notes"][0]["id"], all[0]["id"]);
+        let cursor = page["next_cursor"].as_str().unwrap().to_string();
+
+        let result = client
+            .call_tool(call("list_project_notes", serde_json::json!({
+                "project_id": project.id.to_string(), "limit": 1, "cursor": cursor
+            })))
+            .await
+            .unwrap();
+        let page: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(page["notes"][0]["id"], all[1]["id"]);
+
+        let err = client
+            .call_tool(call("list_project_notes", serde_json::json!({
+                "project_id": project.id.to_string(), "cursor": "not-a-cursor"
+            })))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
     }
 
-    #[tool(description = "Update a project resource")]
-    async fn update_project_resource(&self, Parameters(req): Parameters<UpdateProjectResourceRequest>) -> Result<CallToolResult, McpError> {
-        let project_uuid = Uuid::parse_str(&req.project_id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_get_board() {
+        let (client, project, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+        client
+            .call_tool(call("create_project", serde_json::json!({"name": "Gemini", "status": "on hold"})))
+            .await
+            .unwrap();
+        let err = client
+            .call_tool(call("update_project", serde_json::json!({
+                "id": project.id.to_string(), "name": "Apollo", "status": "finished"
+            })))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+
+        let result = client.call_tool(call("get_board", serde_json::json!({}))).await.unwrap();
+        let board: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        let columns: Vec<_> = board.as_array().unwrap().iter().map(|c| c["status"].as_str().unwrap()).collect();
+        assert_eq!(columns, ["planning", "active", "on_hold", "done"]);
+        assert_eq!(board[1]["projects"][0]["name"], "Apollo");
+        assert_eq!(board[2]["projects"][0]["name"], "Gemini");
+    }
 
-        let resource = db::ProjectResource {
-            project_id: project_uuid,
-            person_email: req.person_email.clone(),
-            role: req.role,
-            created_at: chrono::Utc::now(), // This will be ignored by update
+    #[tokio::test]
+    async fn test_create_person_possible_duplicate() {
+        let (client, _, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
         };
+        client
+            .call_tool(call("create_person", serde_json::json!({"email": "jose@example.com", "name": "José García"})))
+            .await
+            .unwrap();
+
+        let err = client
+            .call_tool(call("create_person", serde_json::json!({"email": "jg@example.com", "name": "Jose Garcia"})))
+            .await
+            .unwrap_err();
+        match err {
+            ServiceError::McpError(e) => {
+                assert_eq!(e.code, ErrorCode::INVALID_PARAMS);
+                let matches = &e.data.unwrap()["possible_duplicates"];
+                assert_eq!(matches[0]["email"], "jose@example.com");
+                assert_eq!(matches[0]["similarity"], 1.0);
+            }
+            other => panic!("unexpected error: {}", other),
+        }
 
-        let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        repo.update_project_resource(&project_uuid, &resource)
-            .map_err(|e| McpError::internal_error("Failed to update resource", Some(serde_json::json!({"error": e.to_string()}))))?;
+        client
+            .call_tool(call("create_person", serde_json::json!({
+                "email": "jg@example.com", "name": "Jose Garcia", "force": true
+            })))
+            .await
+            .unwrap();
+    }
 
-        let json = serde_json::to_string_pretty(&resource)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_update_person_manager_cycle() {
+        let (client, _, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+        for (email, name) in [("alice@example.com", "Alice"), ("bob@example.com", "Bob")] {
+            client
+                .call_tool(call("create_person", serde_json::json!({"email": email, "name": name})))
+                .await
+                .unwrap();
+        }
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+        client
+            .call_tool(call("update_person", serde_json::json!({
+                "email": "alice@example.com", "name": "Alice", "manager": "bob@example.com"
+            })))
+            .await
+            .unwrap();
+        let err = client
+            .call_tool(call("update_person", serde_json::json!({
+                "email": "bob@example.com", "name": "Bob", "manager": "alice@example.com"
+            })))
+            .await
+            .unwrap_err();
+        match err {
+            ServiceError::McpError(e) => {
+                assert_eq!(e.code, ErrorCode::INVALID_PARAMS);
+                assert!(e.message.contains("bob@example.com -> alice@example.com -> bob@example.com"), "{}", e.message);
+            }
+            other => panic!("unexpected error: {}", other),
+        }
     }
 
-    #[tool(description = "Remove a resource from a project")]
-    async fn remove_project_resource(&self, Parameters(req): Parameters<RemoveProjectResourceRequest>) -> Result<CallToolResult, McpError> {
-        let project_uuid = Uuid::parse_str(&req.project_id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_inbox_tools() {
+        let (client, project, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
 
-        let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        repo.remove_project_resource(&project_uuid, &req.person_email)
-            .map_err(|e| McpError::internal_error("Failed to remove resource", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let result = client
+            .call_tool(call("create_inbox_note", serde_json::json!({"title": "Idea", "body": "Cache the dashboard"})))
+            .await
+            .unwrap();
+        let note: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        let note_id = note["id"].as_str().unwrap().to_string();
+
+        let result = client.call_tool(call("list_inbox_notes", serde_json::json!({}))).await.unwrap();
+        let inbox: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(inbox[0]["id"], note_id.as_str());
+
+        let err = client
+            .call_tool(call("triage_inbox_note", serde_json::json!({
+                "note_id": note_id, "target": {"type": "project", "project_id": Uuid::new_v4().to_string()}
+            })))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+
+        client
+            .call_tool(call("triage_inbox_note", serde_json::json!({
+                "note_id": note_id, "target": {"type": "project", "project_id": project.id.to_string()}
+            })))
+            .await
+            .unwrap();
+
+        let result = client.call_tool(call("list_inbox_notes", serde_json::json!({}))).await.unwrap();
+        let inbox: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(inbox.as_array().unwrap().len(), 0);
+        let result = client
+            .call_tool(call("list_project_notes", serde_json::json!({"project_id": project.id.to_string()})))
+            .await
+            .unwrap();
+        let notes: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert!(notes.as_array().unwrap().iter().any(|n| n["id"] == note_id.as_str()));
+    }
 
-        Ok(CallToolResult::success(vec![Content::text(format!("Removed resource {} from project {}", req.person_email, req.project_id))]))
+    #[tokio::test]
+    async fn test_team_tree_tools() {
+        let (client, _, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+        client.call_tool(call("create_team", serde_json::json!({"name": "Platform"}))).await.unwrap();
+        client
+            .call_tool(call("create_team", serde_json::json!({"name": "Storage", "parent_team": "Platform"})))
+            .await
+            .unwrap();
+        client
+            .call_tool(call("create_person", serde_json::json!({"email": "alice@example.com", "name": "Alice"})))
+            .await
+            .unwrap();
+        client
+            .call_tool(call("add_team_member", serde_json::json!({"team_name": "Storage", "person_email": "alice@example.com"})))
+            .await
+            .unwrap();
+
+        let result = client.call_tool(call("get_team_tree", serde_json::json!({}))).await.unwrap();
+        let tree: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(tree[0]["name"], "Platform");
+        assert_eq!(tree[0]["subteams"][0]["name"], "Storage");
+        assert_eq!(tree[0]["subteams"][0]["parent_team"], "Platform");
+
+        let result = client
+            .call_tool(call("get_team_members", serde_json::json!({"team_name": "Platform", "include_subteams": true})))
+            .await
+            .unwrap();
+        let members: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(members[0]["email"], "alice@example.com");
+
+        let err = client
+            .call_tool(call("update_team", serde_json::json!({"name": "Platform", "parent_team": "Storage"})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+        let err = client
+            .call_tool(call("delete_team", serde_json::json!({"name": "Platform"})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+        client
+            .call_tool(call("delete_team", serde_json::json!({"name": "Platform", "detach_subteams": true})))
+            .await
+            .unwrap();
     }
 
-    // Milestone Resource tools
+    #[tokio::test]
+    async fn test_block_project_tools() {
+        let (client, project, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
 
-    #[tool(description = "Add a resource to a milestone")]
-    async fn add_milestone_resource(&self, Parameters(req): Parameters<AddMilestoneResourceRequest>) -> Result<CallToolResult, McpError> {
-        let milestone_uuid = Uuid::parse_str(&req.milestone_id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let result = client
+            .call_tool(call("block_project", serde_json::json!({"project_id": project.id, "reason": "Waiting on vendor"})))
+            .await
+            .unwrap();
+        let blocked: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(blocked["blocked"], true);
+        assert_eq!(blocked["blocked_reason"], "Waiting on vendor");
+
+        let result = client.call_tool(call("list_blocked_projects", serde_json::json!({}))).await.unwrap();
+        let listed: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(listed[0]["id"], project.id.to_string());
+
+        let err = client
+            .call_tool(call("block_project", serde_json::json!({"project_id": project.id, "reason": " "})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+
+        client
+            .call_tool(call("unblock_project", serde_json::json!({"project_id": project.id})))
+            .await
+            .unwrap();
+        let result = client.call_tool(call("list_blocked_projects", serde_json::json!({}))).await.unwrap();
+        let listed: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(listed, serde_json::json!([]));
+    }
 
-        let resource = db::MilestoneResource {
-            milestone_id: milestone_uuid,
-            person_email: req.person_email.clone(),
-            role: req.role,
-            created_at: chrono::Utc::now(),
+    #[tokio::test]
+    async fn test_project_phase_tools() {
+        let (client, project, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
         };
 
-        let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        repo.add_milestone_resource(&milestone_uuid, &resource)
-            .map_err(|e| McpError::internal_error("Failed to add resource", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let result = client
+            .call_tool(call("set_project_phase", serde_json::json!({"project_id": project.id, "phase": "build"})))
+            .await
+            .unwrap();
+        let moved: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(moved["phase"], "Build");
+
+        let err = client
+            .call_tool(call("set_project_phase", serde_json::json!({"project_id": project.id, "phase": "Launch"})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+
+        let result = client
+            .call_tool(call("update_project", serde_json::json!({"id": project.id, "name": "Apollo", "phase": "Design"})))
+            .await
+            .unwrap();
+        let updated: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(updated["phase"], "Design");
+
+        let result = client
+            .call_tool(call("get_phase_history", serde_json::json!({"project_id": project.id})))
+            .await
+            .unwrap();
+        let history: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(history["history"][1]["from_phase"], "Build");
+        assert_eq!(history["history"][1]["backwards"], true);
+        assert_eq!(history["durations"][1]["phase"], "Design");
+        assert_eq!(history["durations"][1]["current"], true);
+
+        let result = client
+            .call_tool(call("list_projects_by_phase", serde_json::json!({"phase": "design"})))
+            .await
+            .unwrap();
+        let listed: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(listed[0]["id"], project.id.to_string());
+    }
 
-        let json = serde_json::to_string_pretty(&resource)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_project_slug_tools() {
+        let (client, project, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+        // Slugs work wherever a project ID is accepted
+        let result = client.call_tool(call("get_project", serde_json::json!({"id": "apollo"}))).await.unwrap();
+        let fetched: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(fetched["id"], project.id.to_string());
+        assert_eq!(fetched["slug"], "apollo");
+        client
+            .call_tool(call("block_project", serde_json::json!({"project_id": "apollo", "reason": "Waiting on vendor"})))
+            .await
+            .unwrap();
+
+        let result = client
+            .call_tool(call("set_project_slug", serde_json::json!({"project_id": "apollo", "slug": "apollo-migration"})))
+            .await
+            .unwrap();
+        let set: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(set["slug"], "apollo-migration");
+        let result = client
+            .call_tool(call("list_milestones", serde_json::json!({"project_id": "apollo-migration"})))
+            .await
+            .unwrap();
+        assert!(!result.is_error.unwrap_or(false));
+
+        let err = client
+            .call_tool(call("get_project", serde_json::json!({"id": "apollo"})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
     }
 
-    #[tool(description = "List resources for a milestone")]
-    async fn list_milestone_resources(&self, Parameters(req): Parameters<GetMilestoneResourcesRequest>) -> Result<CallToolResult, McpError> {
-        let milestone_uuid = Uuid::parse_str(&req.milestone_id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
-
-        let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        let resources = repo.get_milestone_resources(&milestone_uuid)
-            .map_err(|e| McpError::internal_error("Failed to list resources", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_snapshot_tools() {
+        let (client, project, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+        let snapshot = |result: CallToolResult| -> serde_json::Value {
+            serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap()
+        };
 
-        let json = serde_json::to_string_pretty(&resources)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let start = snapshot(
+            client
+                .call_tool(call("create_snapshot", serde_json::json!({"project_id": "apollo", "label": "Q3 start"})))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(start["project_id"], project.id.to_string());
+        client
+            .call_tool(call("create_milestone", serde_json::json!({"project_id": "apollo", "number": 1, "name": "Launch"})))
+            .await
+            .unwrap();
+        let end = snapshot(
+            client
+                .call_tool(call("create_snapshot", serde_json::json!({"project_id": "apollo", "label": "Q3 end"})))
+                .await
+                .unwrap(),
+        );
+
+        let listed = snapshot(
+            client
+                .call_tool(call("list_snapshots", serde_json::json!({"project_id": "apollo"})))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(listed.as_array().unwrap().len(), 2);
+
+        let diff = snapshot(
+            client
+                .call_tool(call("compare_snapshots", serde_json::json!({
+                    "from_snapshot_id": start["id"], "to_snapshot_id": end["id"],
+                })))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(diff["added_milestones"][0]["name"], "Launch");
+        assert_eq!(diff["project_changes"], serde_json::json!([]));
+
+        let err = client
+            .call_tool(call("compare_snapshots", serde_json::json!({
+                "from_snapshot_id": start["id"], "to_snapshot_id": Uuid::new_v4(),
+            })))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+    }
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+    #[tokio::test]
+    async fn test_milestone_date_checks() {
+        let (client, project, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+        let project_id = project.id.to_string();
+        client
+            .call_tool(call("update_project", serde_json::json!({
+                "id": project_id, "name": "Apollo", "due_date": "2025-06-30", "version": project.version,
+            })))
+            .await
+            .unwrap();
+
+        let err = client
+            .call_tool(call("create_milestone", serde_json::json!({
+                "project_id": project_id, "number": 1, "name": "Launch",
+                "start_date": "2025-06-10", "due_date": "2025-06-01",
+            })))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+
+        let result = client
+            .call_tool(call("create_milestone", serde_json::json!({
+                "project_id": project_id, "number": 1, "name": "Launch", "due_date": "2025-06-01",
+            })))
+            .await
+            .unwrap();
+        assert_eq!(result.content.len(), 1);
+
+        let result = client
+            .call_tool(call("create_milestone", serde_json::json!({
+                "project_id": project_id, "number": 2, "name": "Rollout", "due_date": "2025-07-15",
+            })))
+            .await
+            .unwrap();
+        assert_eq!(result.content.len(), 2);
+        assert!(result.content[1].as_text().unwrap().text.starts_with("Warning: "));
+
+        let result = client
+            .call_tool(call("suggest_project_due_date", serde_json::json!({"project_id": project_id})))
+            .await
+            .unwrap();
+        let suggestion: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        let suggested = suggestion["suggested_due_date"].as_str().unwrap();
+        assert!(suggested.starts_with("2025-07-1"), "{}", suggested);
     }
 
-    #[tool(description = "Update a milestone resource")]
-    async fn update_milestone_resource(&self, Parameters(req): Parameters<UpdateMilestoneResourceRequest>) -> Result<CallToolResult, McpError> {
-        let milestone_uuid = Uuid::parse_str(&req.milestone_id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_milestone_effort() {
+        let (client, project, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+        let project_id = project.id.to_string();
+
+        let err = client
+            .call_tool(call("create_milestone", serde_json::json!({
+                "project_id": project_id, "number": 1, "name": "Design", "estimated_days": -2.0,
+            })))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+
+        let result = client
+            .call_tool(call("create_milestone", serde_json::json!({
+                "project_id": project_id, "number": 1, "name": "Design", "estimated_days": 5.0, "actual_days": 5.5,
+            })))
+            .await
+            .unwrap();
+        assert_eq!(result.content.len(), 1);
+
+        let result = client
+            .call_tool(call("create_milestone", serde_json::json!({
+                "project_id": project_id, "number": 2, "name": "Build", "estimated_days": 10.0, "actual_days": 20.0,
+            })))
+            .await
+            .unwrap();
+        assert_eq!(result.content.len(), 2);
+        assert!(result.content[1].as_text().unwrap().text.contains("against an estimate of 10"));
+
+        let result = client
+            .call_tool(call("get_effort_summary", serde_json::json!({
+                "project_id": project_id, "sort_by_variance": true, "limit": 1,
+            })))
+            .await
+            .unwrap();
+        let summary: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(summary["total_estimated_days"], 15.0);
+        assert_eq!(summary["total_actual_days"], 25.5);
+        assert_eq!(summary["milestones"].as_array().unwrap().len(), 1);
+        assert_eq!(summary["milestones"][0]["name"], "Build");
+        assert_eq!(summary["milestones"][0]["variance_days"], 10.0);
+        assert_eq!(summary["milestones"][0]["over_estimate"], true);
+    }
 
-        let resource = db::MilestoneResource {
-            milestone_id: milestone_uuid,
-            person_email: req.person_email.clone(),
-            role: req.role,
-            created_at: chrono::Utc::now(), // This will be ignored by update
+    #[tokio::test]
+    async fn test_budget_tools() {
+        let (client, project, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
         };
+        let project_id = project.id.to_string();
+
+        let err = client
+            .call_tool(call("update_project", serde_json::json!({
+                "id": project_id, "name": "Apollo", "budget_amount": 5000.0, "budget_currency": "dollars",
+            })))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+
+        client
+            .call_tool(call("update_project", serde_json::json!({
+                "id": project_id, "name": "Apollo", "budget_amount": 5000.0, "budget_currency": "CHF",
+            })))
+            .await
+            .unwrap();
+
+        let err = client
+            .call_tool(call("add_expense", serde_json::json!({
+                "project_id": project_id, "description": "Refund", "amount": -10.0,
+            })))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+
+        for (description, amount, date) in [("Hardware", 1200.0, "2025-03-01"), ("Training", 300.0, "2025-02-01")] {
+            client
+                .call_tool(call("add_expense", serde_json::json!({
+                    "project_id": project_id, "description": description, "amount": amount, "incurred_at": date,
+                })))
+                .await
+                .unwrap();
+        }
 
-        let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        repo.update_milestone_resource(&milestone_uuid, &resource)
-            .map_err(|e| McpError::internal_error("Failed to update resource", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let result = client
+            .call_tool(call("list_expenses", serde_json::json!({ "project_id": project_id })))
+            .await
+            .unwrap();
+        let expenses: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(expenses[0]["description"], "Training");
+        assert_eq!(expenses[1]["description"], "Hardware");
+
+        let result = client
+            .call_tool(call("get_budget_status", serde_json::json!({ "project_id": project_id })))
+            .await
+            .unwrap();
+        let status: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(status["budget_amount"], 5000.0);
+        assert_eq!(status["budget_currency"], "CHF");
+        assert_eq!(status["total_spent"], 1500.0);
+        assert_eq!(status["remaining"], 3500.0);
+    }
 
-        let json = serde_json::to_string_pretty(&resource)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_get_project_activity() {
+        let (client, project, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+        let result = client
+            .call_tool(call("get_project_activity", serde_json::json!({"project_id": project.id})))
+            .await
+            .unwrap();
+        let activity: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(activity[0]["kind"], "note_added");
+        assert_eq!(activity[0]["summary"], "Note added: Status");
+
+        let err = client
+            .call_tool(call("get_project_activity", serde_json::json!({"project_id": uuid::Uuid::new_v4()})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
     }
 
-    #[tool(description = "Remove a resource from a milestone")]
-    async fn remove_milestone_resource(&self, Parameters(req): Parameters<RemoveMilestoneResourceRequest>) -> Result<CallToolResult, McpError> {
-        let milestone_uuid = Uuid::parse_str(&req.milestone_id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
-
-        let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        repo.remove_milestone_resource(&milestone_uuid, &req.person_email)
-            .map_err(|e| McpError::internal_error("Failed to remove resource", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_run_query() {
+        let (client, project, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+        let expression = format!("name = {:?} AND milestone.due_date IS NULL", project.name);
+
+        client
+            .call_tool(call("save_query", serde_json::json!({"name": "mine", "expression": format!("name = {:?}", project.name)})))
+            .await
+            .unwrap();
+        for args in [serde_json::json!({"name": "mine"}), serde_json::json!({"expression": format!("name = {:?}", project.name)})] {
+            let result = client.call_tool(call("run_query", args)).await.unwrap();
+            let projects: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+            assert_eq!(projects[0]["id"], project.id.to_string());
+        }
 
-        Ok(CallToolResult::success(vec![Content::text(format!("Removed resource {} from milestone {}", req.person_email, req.milestone_id))]))
+        // A project without milestones has no milestone missing a due date
+        let result = client.call_tool(call("run_query", serde_json::json!({"expression": expression}))).await.unwrap();
+        let projects: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(projects.as_array().unwrap().len(), 0);
+
+        let result = client.call_tool(call("list_saved_queries", serde_json::json!({}))).await.unwrap();
+        let saved: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(saved[0]["name"], "mine");
+
+        for args in [
+            serde_json::json!({"expression": "password = \"x\""}),
+            serde_json::json!({"expression": "name = \"x\"; DROP TABLE projects"}),
+            serde_json::json!({"name": "missing"}),
+            serde_json::json!({}),
+        ] {
+            let err = client.call_tool(call("run_query", args)).await.unwrap_err();
+            assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+        }
+        let err = client
+            .call_tool(call("save_query", serde_json::json!({"name": "bad", "expression": "name ="})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+
+        client.call_tool(call("delete_saved_query", serde_json::json!({"name": "mine"}))).await.unwrap();
+        let err = client
+            .call_tool(call("delete_saved_query", serde_json::json!({"name": "mine"})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
     }
 
-    // Project Note tools
+    #[tokio::test]
+    async fn test_get_backlinks() {
+        let (client, project, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
 
-    #[tool(description = "Create a note for a project")]
-    async fn create_project_note(&self, Parameters(req): Parameters<CreateProjectNoteRequest>) -> Result<CallToolResult, McpError> {
-        let project_uuid = Uuid::parse_str(&req.project_id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let other = create_project(&client, serde_json::json!({"name": "Gemini"})).await.unwrap();
+        client
+            .call_tool(call(
+                "create_project_note",
+                serde_json::json!({"project_id": other, "title": "Plan", "body": format!("Needs [[project:{}]] first", project.id)}),
+            ))
+            .await
+            .unwrap();
+
+        let result = client
+            .call_tool(call("get_backlinks", serde_json::json!({"kind": "project", "target": project.id})))
+            .await
+            .unwrap();
+        let backlinks: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(backlinks.as_array().unwrap().len(), 1);
+        assert_eq!(backlinks[0]["title"], "Plan");
+        assert_eq!(backlinks[0]["project_name"], "Gemini");
+
+        let err = client
+            .call_tool(call("get_backlinks", serde_json::json!({"kind": "team", "target": "Platform"})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+        let err = client
+            .call_tool(call("get_backlinks", serde_json::json!({"kind": "person", "target": "nobody@example.com"})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+    }
 
-        let note = db::ProjectNote::new(project_uuid, req.title, req.body);
+    #[tokio::test]
+    async fn test_save_and_get_retrospective() {
+        let (client, project, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
 
-        let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        repo.add_project_note(&note)
-            .map_err(|e| McpError::internal_error("Failed to create note", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let err = client
+            .call_tool(call("get_retrospective", serde_json::json!({"project_id": project.id})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+
+        let result = client
+            .call_tool(call(
+                "save_retrospective",
+                serde_json::json!({"project_id": project.id, "went_well": "Clear scope", "completed_on": "2025-06-30"}),
+            ))
+            .await
+            .unwrap();
+        let saved: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(saved["went_well"], "Clear scope");
+
+        // Saving again updates the same retrospective and keeps fields left out
+        client
+            .call_tool(call(
+                "save_retrospective",
+                serde_json::json!({"project_id": project.id, "action_summary": "Demo weekly"}),
+            ))
+            .await
+            .unwrap();
+        let result = client
+            .call_tool(call("get_retrospective", serde_json::json!({"project_id": project.id})))
+            .await
+            .unwrap();
+        let retrospective: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(retrospective["id"], saved["id"]);
+        assert_eq!(retrospective["went_well"], "Clear scope");
+        assert_eq!(retrospective["action_summary"], "Demo weekly");
+        assert!(retrospective["completed_on"].as_str().unwrap().starts_with("2025-06-30"));
+
+        let err = client
+            .call_tool(call(
+                "save_retrospective",
+                serde_json::json!({"project_id": project.id, "completed_on": "someday"}),
+            ))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+    }
 
-        let json = serde_json::to_string_pretty(&note)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_record_and_supersede_decisions() {
+        let (client, project, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+        let decision = |result: CallToolResult| -> serde_json::Value {
+            serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap()
+        };
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+        let first = decision(
+            client
+                .call_tool(call(
+                    "record_decision",
+                    serde_json::json!({"project_id": project.id, "title": "Gateway", "decision": "Run our own", "decided_on": "2025-01-10"}),
+                ))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(first["status"], "accepted");
+        assert!(first["decided_on"].as_str().unwrap().starts_with("2025-01-10"));
+        let second = decision(
+            client
+                .call_tool(call(
+                    "record_decision",
+                    serde_json::json!({"project_id": project.id, "title": "Gateway", "decision": "Use the shared one", "status": "proposed"}),
+                ))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(second["status"], "proposed");
+        assert!(second["decided_on"].is_null());
+
+        let old = decision(
+            client
+                .call_tool(call(
+                    "supersede_decision",
+                    serde_json::json!({"old_id": first["id"], "new_id": second["id"]}),
+                ))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(old["status"], "superseded");
+        assert_eq!(old["superseded_by"], second["id"]);
+
+        let decisions = decision(
+            client
+                .call_tool(call("list_decisions", serde_json::json!({"project_id": project.id})))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(decisions[1]["id"], second["id"]);
+        assert_eq!(decisions[1]["status"], "accepted");
+
+        for (name, args) in [
+            ("record_decision", serde_json::json!({"project_id": project.id, "title": "X", "decision": "Y", "status": "superseded"})),
+            ("record_decision", serde_json::json!({"project_id": project.id, "title": "X", "decision": "Y", "approvers": ["nobody@example.com"]})),
+            ("supersede_decision", serde_json::json!({"old_id": first["id"], "new_id": second["id"]})),
+            ("supersede_decision", serde_json::json!({"old_id": "nope", "new_id": second["id"]})),
+        ] {
+            let err = client.call_tool(call(name, args)).await.unwrap_err();
+            assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS, "{}", name);
+        }
     }
 
-    #[tool(description = "List notes for a project")]
-    async fn list_project_notes(&self, Parameters(req): Parameters<GetProjectNotesRequest>) -> Result<CallToolResult, McpError> {
-        let project_uuid = Uuid::parse_str(&req.project_id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_organizations() {
+        let (client, project, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+        let json = |result: CallToolResult| -> serde_json::Value {
+            serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap()
+        };
+        create_project(&client, serde_json::json!({"name": "Gemini"})).await.unwrap();
+
+        let acme = json(
+            client
+                .call_tool(call("create_organization", serde_json::json!({"name": "Acme", "kind": "client"})))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(acme["kind"], "client");
+        let person = json(
+            client
+                .call_tool(call(
+                    "create_person",
+                    serde_json::json!({"email": "ada@acme.com", "name": "Ada", "organization": "Acme"}),
+                ))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(person["organization"], "Acme");
+        client
+            .call_tool(call(
+                "add_project_resource",
+                serde_json::json!({"project_id": project.id, "person_email": "ada@acme.com", "role": "Consultant"}),
+            ))
+            .await
+            .unwrap();
+
+        let overview = json(
+            client
+                .call_tool(call("get_organization_overview", serde_json::json!({"name": "Acme"})))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(overview["people"][0]["email"], "ada@acme.com");
+        assert_eq!(overview["projects"][0]["project"]["name"], "Apollo");
+        assert_eq!(overview["projects"][0]["involvement"][0]["role"], "Consultant");
+        let projects = json(
+            client
+                .call_tool(call("list_projects", serde_json::json!({"organization": "Acme"})))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(projects.as_array().unwrap().len(), 1);
+        let organizations = json(client.call_tool(call("list_organizations", serde_json::json!({}))).await.unwrap());
+        assert_eq!(organizations[0]["name"], "Acme");
+
+        // An empty organization clears it; omitting it leaves it alone
+        let person = json(
+            client
+                .call_tool(call("update_person", serde_json::json!({"email": "ada@acme.com", "name": "Ada Lovelace"})))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(person["organization"], "Acme");
+        let person = json(
+            client
+                .call_tool(call("update_person", serde_json::json!({"email": "ada@acme.com", "name": "Ada", "organization": ""})))
+                .await
+                .unwrap(),
+        );
+        assert!(person["organization"].is_null());
+
+        for (name, args) in [
+            ("create_organization", serde_json::json!({"name": "Acme"})),
+            ("create_organization", serde_json::json!({"name": "Initech", "kind": "rival"})),
+            ("create_person", serde_json::json!({"email": "bob@initech.com", "name": "Bob", "organization": "Initech"})),
+            ("get_organization_overview", serde_json::json!({"name": "Initech"})),
+        ] {
+            let err = client.call_tool(call(name, args)).await.unwrap_err();
+            assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS, "{}", name);
+        }
+    }
 
-        let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        let notes = repo.get_project_notes(&project_uuid)
-            .map_err(|e| McpError::internal_error("Failed to list notes", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_idempotency_key_replays_first_result() {
+        let server = ProjectTrackerServer::new(Config::default(), setup_test_db());
+        let client = serve_with(server.clone()).await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+        let text = |result: CallToolResult| result.content[0].as_text().unwrap().text.clone();
+
+        let args = serde_json::json!({"name": "Gemini", "idempotency_key": "create-gemini"});
+        let first = text(client.call_tool(call("create_project", args.clone())).await.unwrap());
+        let second = text(client.call_tool(call("create_project", args)).await.unwrap());
+        assert_eq!(first, second);
+        {
+            let db = server.db.lock().await;
+            let count: i64 = db.query_row("SELECT COUNT(*) FROM projects", [], |row| row.get(0)).unwrap();
+            assert_eq!(count, 1);
+            let count: i64 = db.query_row("SELECT COUNT(*) FROM mcp_operations", [], |row| row.get(0)).unwrap();
+            assert_eq!(count, 1);
+        }
 
-        let json = serde_json::to_string_pretty(&notes)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+        // The same key can't be reused for a different call
+        let err = client
+            .call_tool(call("create_project", serde_json::json!({"name": "Apollo", "idempotency_key": "create-gemini"})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+
+        // Retries racing each other make the change once
+        let args = serde_json::json!({"name": "Mercury", "idempotency_key": "create-mercury"});
+        let (first, second) = tokio::join!(
+            client.call_tool(call("create_project", args.clone())),
+            client.call_tool(call("create_project", args)),
+        );
+        assert_eq!(text(first.unwrap()), text(second.unwrap()));
+        {
+            let db = server.db.lock().await;
+            let count: i64 = db
+                .query_row("SELECT COUNT(*) FROM projects WHERE name = 'Mercury'", [], |row| row.get(0))
+                .unwrap();
+            assert_eq!(count, 1);
+        }
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+        // Calls without a key still run every time
+        let args = serde_json::json!({"name": "Apollo"});
+        client.call_tool(call("create_project", args.clone())).await.unwrap();
+        client.call_tool(call("create_project", args)).await.unwrap();
+        let db = server.db.lock().await;
+        let count: i64 = db.query_row("SELECT COUNT(*) FROM projects", [], |row| row.get(0)).unwrap();
+        assert_eq!(count, 4);
+        drop(db);
+
+        let tools = client.list_all_tools().await.unwrap();
+        let has_key = |name: &str| {
+            let tool = tools.iter().find(|tool| tool.name == name).unwrap();
+            tool.input_schema["properties"].get(journal::IDEMPOTENCY_KEY).is_some()
+        };
+        assert!(has_key("create_project"));
+        assert!(!has_key("list_projects"));
     }
 
-    #[tool(description = "Update a project note")]
-    async fn update_project_note(&self, Parameters(req): Parameters<UpdateProjectNoteRequest>) -> Result<CallToolResult, McpError> {
-        let note_uuid = Uuid::parse_str(&req.id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_watch_and_unwatch_project() {
+        let mut config = Config::default();
+        config.current_user_email = Some("alice@example.com".to_string());
+        let client = serve_with(ProjectTrackerServer::new(config, setup_test_db())).await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+        let names = |result: CallToolResult| -> Vec<String> {
+            let projects: Vec<db::Project> = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+            projects.into_iter().map(|p| p.name).collect()
+        };
 
-        let db = self.db.lock().await;
+        // Projects created with a current user are watched by them
+        let apollo = create_project(&client, serde_json::json!({"name": "Apollo"})).await.unwrap();
+        let result = client.call_tool(call("list_projects", serde_json::json!({"only_watched": true}))).await.unwrap();
+        assert_eq!(names(result), ["Apollo"]);
+
+        let result = client
+            .call_tool(call("watch_project", serde_json::json!({"project_id": apollo, "email": "bob@example.com"})))
+            .await
+            .unwrap();
+        let watchers: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(watchers.as_array().unwrap().len(), 2);
+
+        // Unwatching keeps the other watcher
+        let result = client
+            .call_tool(call("unwatch_project", serde_json::json!({"project_id": apollo})))
+            .await
+            .unwrap();
+        let watchers: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(watchers[0]["watcher_email"], "bob@example.com");
+        assert_eq!(watchers.as_array().unwrap().len(), 1);
+        let result = client.call_tool(call("list_projects", serde_json::json!({"only_watched": true}))).await.unwrap();
+        assert!(names(result).is_empty());
+        let result = client.call_tool(call("list_projects", serde_json::json!({}))).await.unwrap();
+        assert_eq!(names(result), ["Apollo"]);
+
+        let err = client
+            .call_tool(call("unwatch_project", serde_json::json!({"project_id": apollo})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+
+        // Without a current user, an email has to be given
+        let (client, project, _) = connect().await;
+        let err = client
+            .call_tool(call("watch_project", serde_json::json!({"project_id": project.id})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+    }
 
-        // Fetch existing note first
-        let mut note = db.query_row(
-            "SELECT id, project_id, title, body, created_at, updated_at FROM project_notes WHERE id = ?1",
-            rusqlite::params![note_uuid.to_string()],
-            |row| {
-                Ok(db::ProjectNote {
-                    id: Uuid::parse_str(&row.get::<_, String>(0)?).unwrap(),
-                    project_id: Uuid::parse_str(&row.get::<_, String>(1)?).unwrap(),
-                    title: row.get(2)?,
-                    body: row.get(3)?,
-                    created_at: row.get(4)?,
-                    updated_at: row.get(5)?,
-                })
-            },
-        ).optional()
-            .map_err(|e| McpError::internal_error("Database error", Some(serde_json::json!({"error": e.to_string()}))))?
-            .ok_or_else(|| McpError::invalid_params("Note not found", None))?;
+    #[tokio::test]
+    async fn test_get_project_brief() {
+        let (client, project, _) = connect().await;
+        let call = |args: serde_json::Value| CallToolRequestParam {
+            name: "get_project_brief".into(),
+            arguments: args.as_object().cloned(),
+        };
 
-        // Update fields
-        note.title = req.title;
-        note.body = req.body;
-        note.updated_at = chrono::Utc::now();
+        // The slug works as well as the ID
+        let result = client.call_tool(call(serde_json::json!({"id": "apollo"}))).await.unwrap();
+        let text = &result.content[0].as_text().unwrap().text;
+        assert!(text.starts_with("# Apollo [apollo]\n"));
+        assert!(text.contains("Recent notes:\n- Status ("));
+
+        let result = client
+            .call_tool(call(serde_json::json!({"id": project.id, "format": "json"})))
+            .await
+            .unwrap();
+        let brief: db::ProjectBrief = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(brief.id, project.id);
+        assert_eq!(brief.recent_notes[0].title, "Status");
+
+        let err = client
+            .call_tool(call(serde_json::json!({"id": project.id, "format": "yaml"})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+    }
 
-        let repo = db::ProjectRepository::new(&db);
-        repo.update_project_note(&note)
-            .map_err(|e| McpError::internal_error("Failed to update note", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_get_focus_list() {
+        let conn = setup_test_db();
+        db::PersonRepository::new(&conn)
+            .create(&db::Person::new("alice@example.com".to_string(), "Alice".to_string()))
+            .unwrap();
+        let mut apollo = db::Project::new("Apollo".to_string());
+        apollo.manager = Some("alice@example.com".to_string());
+        apollo.blocked = true;
+        apollo.blocked_reason = Some("Waiting on legal".to_string());
+        db::ProjectRepository::new(&conn).create(&apollo).unwrap();
+        let mut config = Config::default();
+        config.current_user_email = Some("alice@example.com".to_string());
+        let client = serve_with(ProjectTrackerServer::new(config, conn)).await;
+        let call = |args: serde_json::Value| CallToolRequestParam {
+            name: "get_focus_list".into(),
+            arguments: args.as_object().cloned(),
+        };
 
-        let json = serde_json::to_string_pretty(&note)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+        // Without an email the configured user's list is built
+        let result = client.call_tool(call(serde_json::json!({}))).await.unwrap();
+        let focus: Vec<db::FocusItem> = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        let kinds: Vec<db::FocusKind> = focus.iter().map(|item| item.kind).collect();
+        assert_eq!(kinds, [db::FocusKind::BlockedProject, db::FocusKind::MissingFields]);
+        assert_eq!(focus[0].link, format!("project:{}", apollo.id));
+        assert_eq!(focus[1].reason, "Missing technical lead, requirements owner, due date");
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+        let result = client.call_tool(call(serde_json::json!({"email": "bob@example.com"}))).await.unwrap();
+        assert_eq!(result.content[0].as_text().unwrap().text, "[]");
     }
 
-    #[tool(description = "Delete a project note")]
-    async fn delete_project_note(&self, Parameters(req): Parameters<DeleteProjectNoteRequest>) -> Result<CallToolResult, McpError> {
-        let note_uuid = Uuid::parse_str(&req.id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_append_to_log_note() {
+        let mut config = Config::default();
+        config.current_user_email = Some("alice@example.com".to_string());
+        let server = ProjectTrackerServer::new(config, setup_test_db());
+        let client = serve_with(server).await;
+        let project_id = create_project(&client, serde_json::json!({"name": "Apollo"})).await.unwrap();
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+        let note = |result: CallToolResult| -> serde_json::Value {
+            serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap()
+        };
 
-        let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        repo.delete_project_note(&note_uuid)
-            .map_err(|e| McpError::internal_error("Failed to delete note", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let created = note(
+            client
+                .call_tool(call(
+                    "create_project_note",
+                    serde_json::json!({"project_id": project_id, "title": "Decision log", "body": "", "kind": "log"}),
+                ))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(created["kind"], "log");
+        let note_id = created["id"].as_str().unwrap();
+
+        let appended = note(
+            client
+                .call_tool(call("append_to_note", serde_json::json!({"note_id": note_id, "text": "Use Postgres"})))
+                .await
+                .unwrap(),
+        );
+        let body = appended["body"].as_str().unwrap();
+        assert!(body.ends_with(" UTC — alice@example.com\nUse Postgres"), "{}", body);
+
+        let err = client
+            .call_tool(call(
+                "update_project_note",
+                serde_json::json!({"id": note_id, "title": "Decision log", "body": "Rewritten"}),
+            ))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+
+        let err = client
+            .call_tool(call(
+                "create_project_note",
+                serde_json::json!({"project_id": project_id, "title": "Minutes", "body": "", "kind": "diary"}),
+            ))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+    }
 
-        Ok(CallToolResult::success(vec![Content::text(format!("Deleted note {}", req.id))]))
+    #[tokio::test]
+    async fn test_external_refs() {
+        let mut config = Config::default();
+        config.jira_url = "https://jira.example.com/browse/".to_string();
+        config.external_ref_urls.insert("github".to_string(), "https://github.com/acme/app/issues/{key}".to_string());
+        let client = serve_with(ProjectTrackerServer::new(config, setup_test_db())).await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+        let refs = |result: CallToolResult| -> Vec<db::ExternalRef> {
+            serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap()
+        };
+        let apollo = create_project(&client, serde_json::json!({"name": "Apollo", "jira_initiative": "PROJ-1"}))
+            .await
+            .unwrap();
+
+        let result = client
+            .call_tool(call("add_external_ref", serde_json::json!({
+                "entity_type": "project", "entity_id": "apollo", "system": "github", "key": "42"
+            })))
+            .await
+            .unwrap();
+        let added = refs(result);
+        assert_eq!(added.len(), 2);
+        assert_eq!((added[0].id, added[0].url.as_deref()), (None, Some("https://jira.example.com/browse/PROJ-1")));
+        assert_eq!(added[1].url.as_deref(), Some("https://github.com/acme/app/issues/42"));
+
+        // get_project carries them too
+        let result = client.call_tool(call("get_project", serde_json::json!({"id": apollo}))).await.unwrap();
+        let project: db::Project = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(project.external_refs.len(), 2);
+
+        let result = client
+            .call_tool(call("remove_external_ref", serde_json::json!({
+                "entity_type": "project", "entity_id": apollo, "system": "jira", "key": "PROJ-1"
+            })))
+            .await
+            .unwrap();
+        assert_eq!(refs(result).iter().map(|r| r.key.as_str()).collect::<Vec<_>>(), ["42"]);
+        let result = client
+            .call_tool(call("list_external_refs", serde_json::json!({"entity_type": "project", "entity_id": apollo})))
+            .await
+            .unwrap();
+        assert_eq!(refs(result).len(), 1);
+
+        let err = client
+            .call_tool(call("list_external_refs", serde_json::json!({"entity_type": "team", "entity_id": apollo})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
     }
 
-    // Milestone Note tools
+    #[tokio::test]
+    async fn test_list_attachments() {
+        let (client, _, note) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
 
-    #[tool(description = "Create a note for a milestone")]
-    async fn create_milestone_note(&self, Parameters(req): Parameters<CreateMilestoneNoteRequest>) -> Result<CallToolResult, McpError> {
-        let milestone_uuid = Uuid::parse_str(&req.milestone_id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let result = client
+            .call_tool(call("list_attachments", serde_json::json!({"note_type": "project", "note_id": note.id})))
+            .await
+            .unwrap();
+        let attachments: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(attachments.as_array().unwrap().len(), 0);
+
+        let err = client
+            .call_tool(call("list_attachments", serde_json::json!({"note_type": "inbox", "note_id": note.id})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+    }
 
-        let note = db::MilestoneNote::new(milestone_uuid, req.title, req.body);
+    #[tokio::test]
+    async fn test_person_skills() {
+        let (client, _, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
 
-        let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        repo.add_milestone_note(&note)
-            .map_err(|e| McpError::internal_error("Failed to create note", Some(serde_json::json!({"error": e.to_string()}))))?;
+        client
+            .call_tool(call("create_person", serde_json::json!({"email": "alice@example.com", "name": "Alice"})))
+            .await
+            .unwrap();
+        let result = client
+            .call_tool(call(
+                "add_person_skill",
+                serde_json::json!({"person_email": "alice@example.com", "skill": "Kubernetes", "level": 4}),
+            ))
+            .await
+            .unwrap();
+        let skill: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(skill["skill"], "kubernetes");
+
+        let result = client
+            .call_tool(call("find_people_by_skill", serde_json::json!({"skill": "KUBERNETES", "min_level": 3})))
+            .await
+            .unwrap();
+        let people: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(people[0]["email"], "alice@example.com");
+        assert_eq!(people[0]["skills"][0]["level"], 4);
+
+        for args in [
+            serde_json::json!({"person_email": "alice@example.com", "skill": "go", "level": 7}),
+            serde_json::json!({"person_email": "nobody@example.com", "skill": "go"}),
+        ] {
+            let err = client.call_tool(call("add_person_skill", args)).await.unwrap_err();
+            assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+        }
+    }
 
-        let json = serde_json::to_string_pretty(&note)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_get_note_in_pieces() {
+        let (client, project, note) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+        let json = |result: CallToolResult| -> serde_json::Value {
+            serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap()
+        };
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+        // "All systems go", read five characters at a time
+        let mut body = String::new();
+        let mut offset = Some(0);
+        while let Some(offset_chars) = offset {
+            let args = serde_json::json!({"note_type": "project", "id": note.id, "offset_chars": offset_chars, "max_chars": 5});
+            let slice = json(client.call_tool(call("get_note", args)).await.unwrap());
+            assert_eq!(slice["total_chars"], 14);
+            body.push_str(slice["body"].as_str().unwrap());
+            offset = slice["next_offset"].as_u64();
+        }
+        assert_eq!(body, note.body);
+
+        let args = serde_json::json!({"note_type": "project", "id": note.id, "excerpt_chars": 3});
+        let summary = json(client.call_tool(call("get_note_summary", args)).await.unwrap());
+        assert_eq!(summary["excerpt"], "All");
+        assert_eq!(summary["word_count"], 3);
+        assert!(summary.get("body").is_none());
+
+        let args = serde_json::json!({"project_id": project.id, "max_chars": 3});
+        let listed = json(client.call_tool(call("list_project_notes", args)).await.unwrap());
+        assert_eq!(listed[0]["body"], "All");
+        assert_eq!(listed[0]["next_offset"], 3);
+
+        let err = client
+            .call_tool(call("get_note", serde_json::json!({"note_type": "milestone", "id": note.id})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
     }
 
-    #[tool(description = "List notes for a milestone")]
-    async fn list_milestone_notes(&self, Parameters(req): Parameters<GetMilestoneNotesRequest>) -> Result<CallToolResult, McpError> {
-        let milestone_uuid = Uuid::parse_str(&req.milestone_id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
-
-        let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        let notes = repo.get_milestone_notes(&milestone_uuid)
-            .map_err(|e| McpError::internal_error("Failed to list notes", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_custom_fields() {
+        let (client, project, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
 
-        let json = serde_json::to_string_pretty(&notes)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+        client
+            .call_tool(call("define_custom_field", serde_json::json!({"key": "cost_center", "label": "Cost Center", "field_type": "number"})))
+            .await
+            .unwrap();
+        let result = client.call_tool(call("list_custom_fields", serde_json::json!({}))).await.unwrap();
+        let fields: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(fields[0]["key"], "cost_center");
+        assert_eq!(fields[0]["field_type"], "number");
+
+        let result = client
+            .call_tool(call(
+                "set_project_custom_field",
+                serde_json::json!({"project_id": project.id.to_string(), "key": "cost_center", "value": "4100"}),
+            ))
+            .await
+            .unwrap();
+        let values: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(values["cost_center"], "4100");
+
+        let result = client.call_tool(call("get_project", serde_json::json!({"id": project.id.to_string()}))).await.unwrap();
+        let found: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(found["custom_fields"]["cost_center"], "4100");
+
+        for (name, args) in [
+            (
+                "set_project_custom_field",
+                serde_json::json!({"project_id": project.id.to_string(), "key": "cost_center", "value": "lots"}),
+            ),
+            (
+                "set_project_custom_field",
+                serde_json::json!({"project_id": project.id.to_string(), "key": "customer", "value": "Acme"}),
+            ),
+            ("define_custom_field", serde_json::json!({"key": "customer", "label": "Customer", "field_type": "string"})),
+            ("delete_custom_field", serde_json::json!({"key": "cost_center"})),
+        ] {
+            let err = client.call_tool(call(name, args)).await.unwrap_err();
+            assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS, "{}", name);
+        }
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+        client
+            .call_tool(call("delete_custom_field", serde_json::json!({"key": "cost_center", "force": true})))
+            .await
+            .unwrap();
+        let result = client.call_tool(call("list_custom_fields", serde_json::json!({}))).await.unwrap();
+        let fields: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(fields.as_array().unwrap().len(), 0);
     }
 
-    #[tool(description = "Update a milestone note")]
-    async fn update_milestone_note(&self, Parameters(req): Parameters<UpdateMilestoneNoteRequest>) -> Result<CallToolResult, McpError> {
-        let note_uuid = Uuid::parse_str(&req.id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
-
-        let db = self.db.lock().await;
-
-        // Fetch existing note first
-        let mut note = db.query_row(
-            "SELECT id, milestone_id, title, body, created_at, updated_at FROM milestone_notes WHERE id = ?1",
-            rusqlite::params![note_uuid.to_string()],
-            |row| {
-                Ok(db::MilestoneNote {
-                    id: Uuid::parse_str(&row.get::<_, String>(0)?).unwrap(),
-                    milestone_id: Uuid::parse_str(&row.get::<_, String>(1)?).unwrap(),
-                    title: row.get(2)?,
-                    body: row.get(3)?,
-                    created_at: row.get(4)?,
-                    updated_at: row.get(5)?,
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_concurrent_updates_do_not_blend() {
+        let conn = setup_test_db();
+        let project = db::Project::new("Apollo".to_string());
+        db::ProjectRepository::new(&conn).create(&project).unwrap();
+
+        // Two sessions on the same server, as the SSE transport sets them up
+        let server = ProjectTrackerServer::new(Config::default(), conn);
+        let clients = [serve_with(server.clone()).await, serve_with(server).await];
+
+        let update = |session: &str| CallToolRequestParam {
+            name: "update_project".into(),
+            arguments: serde_json::json!({
+                "id": project.id.to_string(),
+                "name": format!("Apollo {}", session),
+                "description": format!("Written by {}", session),
+                "team": session,
+            })
+            .as_object()
+            .cloned(),
+        };
+        for _ in 0..20 {
+            let (a, b) = tokio::join!(clients[0].call_tool(update("A")), clients[1].call_tool(update("B")));
+            a.unwrap();
+            b.unwrap();
+
+            let result = clients[0]
+                .call_tool(CallToolRequestParam {
+                    name: "get_project".into(),
+                    arguments: serde_json::json!({"id": project.id.to_string()}).as_object().cloned(),
                 })
-            },
-        ).optional()
-            .map_err(|e| McpError::internal_error("Database error", Some(serde_json::json!({"error": e.to_string()}))))?
-            .ok_or_else(|| McpError::invalid_params("Note not found", None))?;
-
-        // Update fields
-        note.title = req.title;
-        note.body = req.body;
-        note.updated_at = chrono::Utc::now();
+                .await
+                .unwrap();
+            let found: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+            let session = found["team"].as_str().unwrap();
+            assert!(session == "A" || session == "B", "{}", found);
+            assert_eq!(found["name"], format!("Apollo {}", session));
+            assert_eq!(found["description"], format!("Written by {}", session));
+        }
+    }
 
-        let repo = db::ProjectRepository::new(&db);
-        repo.update_milestone_note(&note)
-            .map_err(|e| McpError::internal_error("Failed to update note", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_handles_stand_in_for_ids() {
+        let conn = setup_test_db();
+        let repo = db::ProjectRepository::new(&conn);
+        let apollo = db::Project::new("Apollo".to_string());
+        let gemini = db::Project::new("Gemini".to_string());
+        repo.create(&apollo).unwrap();
+        repo.create(&gemini).unwrap();
+
+        let server = ProjectTrackerServer::new(Config::default(), conn);
+        let client = serve_with(server.new_session()).await;
+        let other = serve_with(server.new_session()).await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
 
-        let json = serde_json::to_string_pretty(&note)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+        let result = client.call_tool(call("list_projects", serde_json::json!({}))).await.unwrap();
+        let projects: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        let gemini_handle = projects
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|p| p["name"] == "Gemini")
+            .map(|p| p["handle"].as_str().unwrap().to_string())
+            .unwrap();
+        let legend = &result.content[1].as_text().unwrap().text;
+        assert!(legend.contains(&format!("{} = {} Gemini", gemini_handle, gemini.id)), "{}", legend);
+
+        // The same record keeps its handle for the rest of the session
+        let result = client.call_tool(call("get_project", serde_json::json!({"id": gemini.id}))).await.unwrap();
+        let found: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(found["handle"], gemini_handle.as_str());
+
+        client
+            .call_tool(call("update_project", serde_json::json!({"id": gemini_handle, "name": "Gemini II"})))
+            .await
+            .unwrap();
+        {
+            let db = server.db().await;
+            let repo = db::ProjectRepository::new(&db);
+            assert_eq!(repo.find_by_id(&gemini.id).unwrap().unwrap().name, "Gemini II");
+            assert_eq!(repo.find_by_id(&apollo.id).unwrap().unwrap().name, "Apollo");
+        }
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+        // Handles belong to the session that listed them
+        let err = other
+            .call_tool(call("update_project", serde_json::json!({"id": gemini_handle, "name": "Gemini III"})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+
+        // Handles never given out, or for another kind of record, are rejected
+        let err = client
+            .call_tool(call("get_milestone", serde_json::json!({"id": "M1"})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+        let err = client
+            .call_tool(call("list_milestone_notes", serde_json::json!({"milestone_id": gemini_handle})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
     }
 
-    #[tool(description = "Delete a milestone note")]
-    async fn delete_milestone_note(&self, Parameters(req): Parameters<DeleteMilestoneNoteRequest>) -> Result<CallToolResult, McpError> {
-        let note_uuid = Uuid::parse_str(&req.id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
-
-        let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        repo.delete_milestone_note(&note_uuid)
-            .map_err(|e| McpError::internal_error("Failed to delete note", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_switch_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.data_dir = dir.path().join("work").to_string_lossy().into_owned();
+        config
+            .workspaces
+            .insert("personal".to_string(), dir.path().join("personal").to_string_lossy().into_owned());
+        config.mcp_workspace_switching = true;
+        let conn = db::open_database(config.database_path().unwrap()).unwrap();
+        let server = ProjectTrackerServer::new(config, conn);
+        let client = serve_with(server.clone()).await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+        let project_names = |result: CallToolResult| {
+            let projects: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+            projects
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|p| p["name"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
 
-        Ok(CallToolResult::success(vec![Content::text(format!("Deleted note {}", req.id))]))
+        assert!(server.get_info().instructions.unwrap().starts_with("Workspace: default (switch_workspace"));
+        create_project(&client, serde_json::json!({"name": "Quarterly planning"})).await.unwrap();
+
+        // A new workspace starts with an empty database
+        client.call_tool(call("switch_workspace", serde_json::json!({"name": "personal"}))).await.unwrap();
+        assert!(dir.path().join("personal/project-tracker.db").exists());
+        assert_eq!(server.workspace(), "personal");
+        assert!(server.get_info().instructions.unwrap().starts_with("Workspace: personal"));
+        let result = client.call_tool(call("list_projects", serde_json::json!({}))).await.unwrap();
+        assert!(project_names(result).is_empty());
+        create_project(&client, serde_json::json!({"name": "Garden"})).await.unwrap();
+
+        // Each workspace keeps its own projects
+        client.call_tool(call("switch_workspace", serde_json::json!({"name": "default"}))).await.unwrap();
+        let result = client.call_tool(call("list_projects", serde_json::json!({}))).await.unwrap();
+        assert_eq!(project_names(result), vec!["Quarterly planning"]);
+        client.call_tool(call("switch_workspace", serde_json::json!({"name": "personal"}))).await.unwrap();
+        let result = client.call_tool(call("list_projects", serde_json::json!({}))).await.unwrap();
+        assert_eq!(project_names(result), vec!["Garden"]);
+
+        let err = client
+            .call_tool(call("switch_workspace", serde_json::json!({"name": "hobby"})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+        assert_eq!(server.workspace(), "personal");
     }
 
-    // Stakeholder Note tools
+    #[tokio::test]
+    async fn test_switch_workspace_disabled() {
+        let server = ProjectTrackerServer::new(Config::default(), setup_test_db());
+        let client = serve_with(server.clone()).await;
+
+        let tools = client.list_all_tools().await.unwrap();
+        assert!(!tools.iter().any(|tool| tool.name == "switch_workspace"));
+        assert!(server.get_info().instructions.unwrap().starts_with("Workspace: default\n"));
+        client
+            .call_tool(CallToolRequestParam {
+                name: "switch_workspace".into(),
+                arguments: serde_json::json!({"name": "default"}).as_object().cloned(),
+            })
+            .await
+            .unwrap_err();
+    }
 
-    #[tool(description = "Create a note for a stakeholder")]
-    async fn create_stakeholder_note(&self, Parameters(req): Parameters<CreateStakeholderNoteRequest>) -> Result<CallToolResult, McpError> {
-        let project_uuid = Uuid::parse_str(&req.project_id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_get_hygiene_report() {
+        let (client, project, _) = connect().await;
+
+        let result = client
+            .call_tool(CallToolRequestParam {
+                name: "get_hygiene_report".into(),
+                arguments: None,
+            })
+            .await
+            .unwrap();
+        let report: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(report["projects_missing_technical_lead"][0]["id"], project.id.to_string());
+        assert_eq!(report["projects_missing_technical_lead"][0]["name"], "Apollo");
+        assert!(report["unknown_stakeholders"].as_array().unwrap().is_empty());
+    }
 
-        let note = db::StakeholderNote::new(project_uuid, req.stakeholder_email, req.title, req.body);
+    #[tokio::test]
+    async fn test_get_quarter_plan() {
+        let (client, project, _) = connect().await;
+        let call = |args: serde_json::Value| CallToolRequestParam {
+            name: "get_quarter_plan".into(),
+            arguments: args.as_object().cloned(),
+        };
+        client
+            .call_tool(CallToolRequestParam {
+                name: "create_milestone".into(),
+                arguments: serde_json::json!({
+                    "project_id": project.id.to_string(), "number": 1, "name": "Launch", "due_date": "2025-08-15"
+                })
+                .as_object()
+                .cloned(),
+            })
+            .await
+            .unwrap();
+
+        let result = client.call_tool(call(serde_json::json!({"year": 2025, "quarter": 3}))).await.unwrap();
+        let plan: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(plan["milestone_count"], 1);
+        assert_eq!(plan["teams"][0]["team"], serde_json::Value::Null);
+        assert_eq!(plan["teams"][0]["projects"][0]["project_name"], "Apollo");
+
+        let err = client.call_tool(call(serde_json::json!({"year": 2025, "quarter": 5}))).await.unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+    }
 
-        let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        repo.add_stakeholder_note(&note)
-            .map_err(|e| McpError::internal_error("Failed to create note", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_generate_team_review() {
+        let (client, _, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+        client.call_tool(call("create_team", serde_json::json!({"name": "Platform"}))).await.unwrap();
+
+        let result = client
+            .call_tool(call("generate_team_review", serde_json::json!({"team": "Platform", "year": 2025, "quarter": 2})))
+            .await
+            .unwrap();
+        let review: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(review["team"], "Platform");
+        assert_eq!(review["start"], "2025-04-01T00:00:00Z");
+        let markdown = &result.content[1].as_text().unwrap().text;
+        assert!(markdown.contains("No activity this quarter."));
+
+        let err = client
+            .call_tool(call("generate_team_review", serde_json::json!({"team": "Nobody", "year": 2025, "quarter": 2})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+    }
 
-        let json = serde_json::to_string_pretty(&note)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_person_notes() {
+        let (client, _, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+        client
+            .call_tool(call("create_person", serde_json::json!({"email": "alice@example.com", "name": "Alice"})))
+            .await
+            .unwrap();
+
+        let mut ids = Vec::new();
+        for title in ["Intro", "1:1", "Review"] {
+            let result = client
+                .call_tool(call("create_person_note", serde_json::json!({
+                    "person_email": "alice@example.com", "title": title, "body": "Wants to lead the **launch**",
+                })))
+                .await
+                .unwrap();
+            let note: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+            ids.push(note["id"].as_str().unwrap().to_string());
+        }
+        let err = client
+            .call_tool(call("create_person_note", serde_json::json!({
+                "person_email": "nobody@example.com", "title": "Lost", "body": "",
+            })))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+
+        let result = client
+            .call_tool(call("update_person_note", serde_json::json!({"id": ids[0], "title": "Intro", "body": "Met at kickoff"})))
+            .await
+            .unwrap();
+        let note: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(note["body"], "Met at kickoff");
+        assert_eq!(note["version"], 2);
+
+        let result = client
+            .call_tool(call("list_person_notes", serde_json::json!({"person_email": "alice@example.com", "rendered": true})))
+            .await
+            .unwrap();
+        let notes: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(notes.as_array().unwrap().len(), 3);
+        assert!(notes[0]["body_html"].as_str().unwrap().contains("<strong>launch</strong>"));
+
+        let result = client
+            .call_tool(call("get_person", serde_json::json!({"email": "alice@example.com", "note_limit": 2})))
+            .await
+            .unwrap();
+        let person: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(person["name"], "Alice");
+        assert_eq!(person["recent_notes"].as_array().unwrap().len(), 2);
+
+        client
+            .call_tool(call("delete_person_note", serde_json::json!({"id": ids[1]})))
+            .await
+            .unwrap();
+        let err = client
+            .call_tool(call("delete_person_note", serde_json::json!({"id": ids[1]})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+
+        // Writing the old notes field still works but warns
+        let result = client
+            .call_tool(call("update_person", serde_json::json!({"email": "alice@example.com", "name": "Alice", "notes": "Old style"})))
+            .await
+            .unwrap();
+        assert_eq!(result.content.len(), 2);
+        assert!(result.content[1].as_text().unwrap().text.contains("create_person_note"));
+    }
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+    #[tokio::test]
+    async fn test_one_on_ones() {
+        let (client, project, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+        client
+            .call_tool(call("create_person", serde_json::json!({"email": "alice@example.com", "name": "Alice"})))
+            .await
+            .unwrap();
+
+        for (date, notes) in [("2025-03-03", "Wants to lead the launch"), ("2025-04-07", "Launch went well")] {
+            client
+                .call_tool(call("log_one_on_one", serde_json::json!({
+                    "person_email": "alice@example.com", "occurred_at": date, "notes": notes,
+                })))
+                .await
+                .unwrap();
+        }
+        let result = client
+            .call_tool(call("log_one_on_one", serde_json::json!({
+                "person_email": "alice@example.com", "occurred_at": "2025-04-14",
+                "followups": "- Draft the retro\n- Pick a mentor", "action_item_project": project.id.to_string(),
+            })))
+            .await
+            .unwrap();
+        let logged: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(logged["one_on_one"]["person_email"], "alice@example.com");
+        assert_eq!(logged["action_items"][1]["description"], "Pick a mentor");
+
+        // Most recent first, and "last month" is a date range
+        let result = client
+            .call_tool(call("list_one_on_ones", serde_json::json!({"person_email": "alice@example.com"})))
+            .await
+            .unwrap();
+        let listed: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(listed.as_array().unwrap().len(), 3);
+        assert_eq!(listed[2]["notes"], "Wants to lead the launch");
+        let result = client
+            .call_tool(call("list_one_on_ones", serde_json::json!({
+                "person_email": "alice@example.com", "since": "2025-03-01", "before": "2025-04-01",
+            })))
+            .await
+            .unwrap();
+        let march: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(march.as_array().unwrap().len(), 1);
+
+        let err = client
+            .call_tool(call("log_one_on_one", serde_json::json!({"person_email": "nobody@example.com"})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+
+        // The 1:1s keep the person until the caller says they can go too
+        client
+            .call_tool(call("create_person", serde_json::json!({"email": "bob@example.com", "name": "Bob"})))
+            .await
+            .unwrap();
+        client
+            .call_tool(call("log_one_on_one", serde_json::json!({"person_email": "bob@example.com", "agenda": "Goodbye"})))
+            .await
+            .unwrap();
+        let err = client
+            .call_tool(call("delete_person", serde_json::json!({"email": "bob@example.com"})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+        client
+            .call_tool(call("delete_person", serde_json::json!({"email": "bob@example.com", "delete_one_on_ones": true})))
+            .await
+            .unwrap();
     }
 
-    #[tool(description = "List notes for a stakeholder")]
-    async fn list_stakeholder_notes(&self, Parameters(req): Parameters<GetStakeholderNotesRequest>) -> Result<CallToolResult, McpError> {
-        let project_uuid = Uuid::parse_str(&req.project_id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_change_person_email() {
+        let (client, _, _) = connect().await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+        for (email, name) in [("alice@example.com", "Alice"), ("bob@example.com", "Bob")] {
+            client
+                .call_tool(call("create_person", serde_json::json!({"email": email, "name": name})))
+                .await
+                .unwrap();
+        }
+        client
+            .call_tool(call("update_person", serde_json::json!({
+                "email": "bob@example.com", "name": "Bob", "manager": "alice@example.com"
+            })))
+            .await
+            .unwrap();
+
+        let result = client
+            .call_tool(call("change_person_email", serde_json::json!({
+                "old_email": "alice@example.com", "new_email": "alice.jones@example.com"
+            })))
+            .await
+            .unwrap();
+        let person: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(person["email"], "alice.jones@example.com");
+
+        let result = client
+            .call_tool(call("get_person", serde_json::json!({"email": "bob@example.com"})))
+            .await
+            .unwrap();
+        let bob: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(bob["manager"], "alice.jones@example.com");
+
+        let err = client
+            .call_tool(call("change_person_email", serde_json::json!({
+                "old_email": "alice.jones@example.com", "new_email": "bob@example.com"
+            })))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+    }
 
-        let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        let notes = repo.get_stakeholder_notes(&project_uuid, &req.stakeholder_email)
-            .map_err(|e| McpError::internal_error("Failed to list notes", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_suggest_resources() {
+        let conn = setup_test_db();
+        let person_repo = db::PersonRepository::new(&conn);
+        let team_repo = db::TeamRepository::new(&conn);
+        let repo = db::ProjectRepository::new(&conn);
+        for (email, name) in [("alice@example.com", "Alice"), ("bob@example.com", "Bob")] {
+            person_repo.create(&db::Person::new(email.to_string(), name.to_string())).unwrap();
+        }
+        team_repo.create(&db::Team::new("Platform".to_string())).unwrap();
+        team_repo.add_member("Platform", "alice@example.com").unwrap();
+        let mut project = db::Project::new("Apollo".to_string());
+        project.team = Some("Platform".to_string());
+        repo.create(&project).unwrap();
+        let milestone = db::Milestone::new(project.id, 1, "Launch".to_string());
+        repo.add_milestone(&milestone).unwrap();
+        let client = serve(conn).await;
+
+        let result = client
+            .call_tool(CallToolRequestParam {
+                name: "suggest_resources".into(),
+                arguments: serde_json::json!({"milestone_id": milestone.id.to_string()}).as_object().cloned(),
+            })
+            .await
+            .unwrap();
+        let suggestions: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(suggestions.as_array().unwrap().len(), 1);
+        assert_eq!(suggestions[0]["email"], "alice@example.com");
+        assert_eq!(suggestions[0]["reason"], "On Platform, no open milestones");
+
+        let err = client
+            .call_tool(CallToolRequestParam {
+                name: "suggest_resources".into(),
+                arguments: serde_json::json!({"milestone_id": Uuid::new_v4().to_string()}).as_object().cloned(),
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+    }
 
-        let json = serde_json::to_string_pretty(&notes)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_resource_roles_are_normalized() {
+        let conn = setup_test_db();
+        db::PersonRepository::new(&conn)
+            .create(&db::Person::new("alice@example.com".to_string(), "Alice".to_string()))
+            .unwrap();
+        let project = db::Project::new("Apollo".to_string());
+        db::ProjectRepository::new(&conn).create(&project).unwrap();
+
+        let mut config = Config::default();
+        config.allow_new_roles = false;
+        let client = serve_with(ProjectTrackerServer::new(config, conn)).await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+        let result = client
+            .call_tool(call("add_project_resource", serde_json::json!({
+                "project_id": project.id.to_string(), "person_email": "alice@example.com", "role": " technical-lead "
+            })))
+            .await
+            .unwrap();
+        let resource: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(resource["role"], "Technical Lead");
+
+        let err = client
+            .call_tool(call("update_project_resource", serde_json::json!({
+                "project_id": project.id.to_string(), "person_email": "alice@example.com", "role": "TL"
+            })))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
     }
 
-    #[tool(description = "Update a stakeholder note")]
-    async fn update_stakeholder_note(&self, Parameters(req): Parameters<UpdateStakeholderNoteRequest>) -> Result<CallToolResult, McpError> {
-        let note_uuid = Uuid::parse_str(&req.id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_milestone_resource_limit_warns() {
+        let conn = setup_test_db();
+        for email in ["alice@example.com", "bob@example.com"] {
+            db::PersonRepository::new(&conn)
+                .create(&db::Person::new(email.to_string(), email.to_string()))
+                .unwrap();
+        }
+        let project = db::Project::new("Apollo".to_string());
+        let milestone = db::Milestone::new(project.id, 1, "Beta".to_string());
+        let repo = db::ProjectRepository::new(&conn);
+        repo.create(&project).unwrap();
+        repo.add_milestone(&milestone).unwrap();
+
+        let mut config = Config::default();
+        config.max_resources_per_milestone = Some(1);
+        let client = serve_with(ProjectTrackerServer::new(config, conn)).await;
+        let add = |email: &str| CallToolRequestParam {
+            name: "add_milestone_resource".into(),
+            arguments: serde_json::json!({"milestone_id": milestone.id.to_string(), "person_email": email})
+                .as_object()
+                .cloned(),
+        };
 
-        let db = self.db.lock().await;
+        let result = client.call_tool(add("alice@example.com")).await.unwrap();
+        assert_eq!(result.content.len(), 1);
+        let result = client.call_tool(add("bob@example.com")).await.unwrap();
+        assert_eq!(
+            result.content[1].as_text().unwrap().text,
+            "Warning: Milestone now has 2 resources, more than the 1 allowed by max_resources_per_milestone"
+        );
+    }
 
-        // Fetch existing note first
-        let mut note = db.query_row(
-            "SELECT id, project_id, stakeholder_email, title, body, created_at, updated_at FROM stakeholder_notes WHERE id = ?1",
-            rusqlite::params![note_uuid.to_string()],
-            |row| {
-                Ok(db::StakeholderNote {
-                    id: Uuid::parse_str(&row.get::<_, String>(0)?).unwrap(),
-                    project_id: Uuid::parse_str(&row.get::<_, String>(1)?).unwrap(),
-                    stakeholder_email: row.get(2)?,
-                    title: row.get(3)?,
-                    body: row.get(4)?,
-                    created_at: row.get(5)?,
-                    updated_at: row.get(6)?,
-                })
-            },
-        ).optional()
-            .map_err(|e| McpError::internal_error("Database error", Some(serde_json::json!({"error": e.to_string()}))))?
-            .ok_or_else(|| McpError::invalid_params("Note not found", None))?;
+    #[tokio::test]
+    async fn test_checklist_tools() {
+        let conn = setup_test_db();
+        let project = db::Project::new("Apollo".to_string());
+        let mut milestone = db::Milestone::new(project.id, 1, "Beta".to_string());
+        milestone.due_date = Some(chrono::Utc::now() + chrono::Duration::days(30));
+        let repo = db::ProjectRepository::new(&conn);
+        repo.create(&project).unwrap();
+        repo.add_milestone(&milestone).unwrap();
+
+        let mut config = Config::default();
+        config.checklist_progress = true;
+        let client = serve_with(ProjectTrackerServer::new(config, conn)).await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+        let json = |result: CallToolResult| -> serde_json::Value {
+            serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap()
+        };
 
-        // Update fields
-        note.title = req.title;
-        note.body = req.body;
-        note.updated_at = chrono::Utc::now();
+        let mut ids = Vec::new();
+        for text in ["Design signed off", "Security review"] {
+            let args = serde_json::json!({"milestone_id": milestone.id.to_string(), "text": text});
+            let item = json(client.call_tool(call("add_checklist_item", args)).await.unwrap());
+            ids.push(item["id"].as_str().unwrap().to_string());
+        }
+        let toggled = json(client.call_tool(call("toggle_checklist_item", serde_json::json!({"id": ids[0]}))).await.unwrap());
+        assert_eq!(toggled["checked"], true);
+
+        let milestones = json(
+            client
+                .call_tool(call("list_milestones", serde_json::json!({"project_id": project.id.to_string()})))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(milestones[0]["checklist"].as_array().unwrap().len(), 2);
+        assert_eq!(milestones[0]["checklist_completion"], 0.5);
+
+        // Half the checklist is done, though the milestone isn't due yet
+        let summaries = json(client.call_tool(call("get_project_summaries", serde_json::json!({}))).await.unwrap());
+        assert_eq!(summaries[0]["progress_percent"], 50);
+
+        client.call_tool(call("remove_checklist_item", serde_json::json!({"id": ids[0]}))).await.unwrap();
+        let items = json(
+            client
+                .call_tool(call("list_checklist_items", serde_json::json!({"milestone_id": milestone.id.to_string()})))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(items[0]["position"], 1);
+        assert_eq!(items[0]["text"], "Security review");
+
+        let err = client
+            .call_tool(call("add_checklist_item", serde_json::json!({"milestone_id": milestone.id.to_string(), "text": " "})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+    }
 
-        let repo = db::ProjectRepository::new(&db);
-        repo.update_stakeholder_note(&note)
-            .map_err(|e| McpError::internal_error("Failed to update note", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_config_changes_apply_without_restart() {
+        let conn = setup_test_db();
+        db::PersonRepository::new(&conn)
+            .create(&db::Person::new("alice@example.com".to_string(), "Alice".to_string()))
+            .unwrap();
+        let project = db::Project::new("Apollo".to_string());
+        db::ProjectRepository::new(&conn).create(&project).unwrap();
+
+        let config = SharedConfig::new(Config::default());
+        let client = serve_with(ProjectTrackerServer::new(config.clone(), conn)).await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+        let set_role = |name: &'static str, role: &str| {
+            call(name, serde_json::json!({
+                "project_id": project.id.to_string(), "person_email": "alice@example.com", "role": role
+            }))
+        };
 
-        let json = serde_json::to_string_pretty(&note)
-            .map_err(|e| McpError::internal_error("Failed to serialize", Some(serde_json::json!({"error": e.to_string()}))))?;
+        client.call_tool(set_role("add_project_resource", "Astronaut")).await.unwrap();
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+        // Turning off new roles applies to the next call
+        let mut changed = Config::default();
+        changed.allow_new_roles = false;
+        config.replace(changed);
+        let err = client.call_tool(set_role("update_project_resource", "Pilot")).await.unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+        client.call_tool(set_role("update_project_resource", "Developer")).await.unwrap();
     }
 
-    #[tool(description = "Delete a stakeholder note")]
-    async fn delete_stakeholder_note(&self, Parameters(req): Parameters<DeleteStakeholderNoteRequest>) -> Result<CallToolResult, McpError> {
-        let note_uuid = Uuid::parse_str(&req.id)
-            .map_err(|e| McpError::invalid_params("Invalid UUID", Some(serde_json::json!({"error": e.to_string()}))))?;
+    #[tokio::test]
+    async fn test_delete_requires_confirmation() {
+        let conn = setup_test_db();
+        let repo = db::ProjectRepository::new(&conn);
+        let project = db::Project::new("Apollo".to_string());
+        repo.create(&project).unwrap();
+        repo.add_milestone(&db::Milestone::new(project.id, 1, "Launch".to_string())).unwrap();
+        let other = db::Project::new("Gemini".to_string());
+        repo.create(&other).unwrap();
+
+        let mut config = Config::default();
+        config.mcp_require_confirmation = true;
+        let server = ProjectTrackerServer::new(config, conn);
+        assert!(server.get_info().instructions.unwrap().contains("Deletes need confirming"));
+        let client = serve_with(server).await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+        let delete = |id: &db::Project, token: Option<&str>| {
+            call("delete_project", serde_json::json!({"id": id.id.to_string(), "confirmation_token": token}))
+        };
+        let project_exists = |name: &'static str| {
+            let client = &client;
+            async move {
+                let result = client.call_tool(call("list_projects", serde_json::json!({}))).await.unwrap();
+                result.content[0].as_text().unwrap().text.contains(name)
+            }
+        };
 
-        let db = self.db.lock().await;
-        let repo = db::ProjectRepository::new(&db);
-        repo.delete_stakeholder_note(&note_uuid)
-            .map_err(|e| McpError::internal_error("Failed to delete note", Some(serde_json::json!({"error": e.to_string()}))))?;
+        // The first call only describes the delete
+        let result = client.call_tool(delete(&project, None)).await.unwrap();
+        let pending: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(pending["confirmation_required"], true);
+        assert_eq!(pending["expires_in_seconds"], 300);
+        assert_eq!(
+            pending["summary"],
+            format!("Delete project \"Apollo\" ({}) with 1 milestones", project.id)
+        );
+        let token = pending["confirmation_token"].as_str().unwrap().to_string();
+        assert!(project_exists("Apollo").await);
+
+        // A made-up token, or one issued for another project, doesn't delete anything
+        let err = client.call_tool(delete(&project, Some("not-a-token"))).await.unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+        let err = client.call_tool(delete(&other, Some(&token))).await.unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+        assert!(project_exists("Gemini").await);
+
+        client.call_tool(delete(&project, Some(&token))).await.unwrap();
+        assert!(!project_exists("Apollo").await);
+
+        // Tokens are single use
+        let err = client.call_tool(delete(&other, Some(&token))).await.unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+
+        // Nothing to describe for a record that doesn't exist
+        let err = client.call_tool(delete(&project, None)).await.unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+    }
 
-        Ok(CallToolResult::success(vec![Content::text(format!("Deleted note {}", req.id))]))
+    #[tokio::test]
+    async fn test_delete_without_confirmation() {
+        let (client, project, _) = connect().await;
+
+        // With mcp_require_confirmation off, the first call deletes and tokens are ignored
+        client
+            .call_tool(CallToolRequestParam {
+                name: "delete_project".into(),
+                arguments: serde_json::json!({"id": project.id.to_string(), "confirmation_token": "ignored"})
+                    .as_object()
+                    .cloned(),
+            })
+            .await
+            .unwrap();
+        let result = client
+            .call_tool(CallToolRequestParam {
+                name: "list_projects".into(),
+                arguments: serde_json::json!({}).as_object().cloned(),
+            })
+            .await
+            .unwrap();
+        assert!(!result.content[0].as_text().unwrap().text.contains("Apollo"));
     }
-}
 
-#[tool_handler]
-impl ServerHandler for ProjectTrackerServer {
-    fn get_info(&self) -> ServerInfo {
-        ServerInfo {
-            protocol_version: ProtocolVersion::V_2024_11_05,
-            capabilities: ServerCapabilities::builder()
-                .enable_tools()
-                .build(),
-            server_info: Implementation::from_build_env(),
-            instructions: Some(
-                "Project Tracker MCP Server. Available tools:\n\
-                Projects: list_projects, get_project, create_project, update_project, delete_project\n\
-                People: list_people, search_people, get_person, create_person, update_person, delete_person\n\
-                Teams: list_teams, search_teams, get_team, create_team, update_team, delete_team, add_team_member, remove_team_member, get_team_members\n\
-                Milestones: list_milestones, get_milestone, create_milestone, update_milestone, delete_milestone\n\
-                Stakeholders: add_project_stakeholder, list_project_stakeholders, update_project_stakeholder, remove_project_stakeholder\n\
-                Project Resources: add_project_resource, list_project_resources, update_project_resource, remove_project_resource\n\
-                Milestone Resources: add_milestone_resource, list_milestone_resources, update_milestone_resource, remove_milestone_resource\n\
-                Project Notes: create_project_note, list_project_notes, update_project_note, delete_project_note\n\
-                Milestone Notes: create_milestone_note, list_milestone_notes, update_milestone_note, delete_milestone_note\n\
-                Stakeholder Notes: create_stakeholder_note, list_stakeholder_notes, update_stakeholder_note, delete_stakeholder_note".to_string()
-            ),
-        }
+    #[tokio::test]
+    async fn test_project_dependencies() {
+        let client = serve(setup_test_db()).await;
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+        let platform = create_project(&client, serde_json::json!({"name": "Platform"})).await.unwrap();
+        let checkout = create_project(&client, serde_json::json!({"name": "Checkout"})).await.unwrap();
+        let launch = create_project(&client, serde_json::json!({"name": "Launch"})).await.unwrap();
+
+        client
+            .call_tool(call(
+                "add_project_dependency",
+                serde_json::json!({"project_id": checkout, "depends_on_project_id": platform, "note": "Needs the new API"}),
+            ))
+            .await
+            .unwrap();
+        client
+            .call_tool(call("add_project_dependency", serde_json::json!({"project_id": launch, "depends_on_project_id": checkout})))
+            .await
+            .unwrap();
+
+        // Platform can't wait on Launch, which already waits on it through Checkout
+        let err = client
+            .call_tool(call("add_project_dependency", serde_json::json!({"project_id": platform, "depends_on_project_id": launch})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+
+        let result = client
+            .call_tool(call("list_project_dependencies", serde_json::json!({"project_id": platform})))
+            .await
+            .unwrap();
+        let listed: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert!(listed["depends_on"].as_array().unwrap().is_empty());
+        assert_eq!(listed["dependents"][0]["note"], "Needs the new API");
+        let waiting: Vec<&str> = listed["waiting_projects"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(waiting, vec!["Checkout", "Launch"]);
+
+        let result = client.call_tool(call("get_dependency_graph", serde_json::json!({}))).await.unwrap();
+        let graph: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(graph["nodes"].as_array().unwrap().len(), 3);
+        assert_eq!(graph["edges"].as_array().unwrap().len(), 2);
+
+        let result = client
+            .call_tool(call("generate_diagram", serde_json::json!({"kind": "projects"})))
+            .await
+            .unwrap();
+        let text = &result.content[0].as_text().unwrap().text;
+        assert!(text.starts_with("flowchart LR\n"));
+        assert_eq!(text.matches("-->|\"depends on\"|").count(), 2);
+        let result = client
+            .call_tool(call("generate_diagram", serde_json::json!({"kind": "org", "format": "dot"})))
+            .await
+            .unwrap();
+        assert!(result.content[0].as_text().unwrap().text.starts_with("digraph {\n"));
+        let err = client
+            .call_tool(call("generate_diagram", serde_json::json!({"kind": "gantt"})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
+
+        client
+            .call_tool(call("remove_project_dependency", serde_json::json!({"project_id": launch, "depends_on_project_id": checkout})))
+            .await
+            .unwrap();
+        let err = client
+            .call_tool(call("remove_project_dependency", serde_json::json!({"project_id": launch, "depends_on_project_id": checkout})))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), ErrorCode::INVALID_PARAMS);
     }
 
-    async fn initialize(
-        &self,
-        _request: InitializeRequestParam,
-        _context: RequestContext<RoleServer>,
-    ) -> Result<InitializeResult, McpError> {
-        Ok(self.get_info())
+    #[tokio::test]
+    async fn test_permission_tiers() {
+        let call = |name: &'static str, args: serde_json::Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: args.as_object().cloned(),
+        };
+
+        for (email, role) in [
+            ("kim@example.com", db::UserRole::Viewer),
+            ("lee@example.com", db::UserRole::Editor),
+            ("ada@example.com", db::UserRole::Admin),
+        ] {
+            let conn = setup_test_db();
+            let users = db::UserRepository::new(&conn);
+            users.set_role(None, "ada@example.com", db::UserRole::Admin).unwrap();
+            users.set_role(Some("ada@example.com"), "lee@example.com", db::UserRole::Editor).unwrap();
+            users.set_role(Some("ada@example.com"), "kim@example.com", db::UserRole::Viewer).unwrap();
+            let project = db::Project::new("Apollo".to_string());
+            db::ProjectRepository::new(&conn).create(&project).unwrap();
+            let mut config = Config::default();
+            config.current_user_email = Some(email.to_string());
+            let client = serve_with(ProjectTrackerServer::new(config, conn)).await;
+
+            let instructions = client.peer_info().unwrap().instructions.clone().unwrap();
+            assert!(instructions.contains(&format!("Role: {} as {}", role, email)), "{}", instructions);
+
+            // Everyone can read
+            client.call_tool(call("list_projects", serde_json::json!({}))).await.unwrap();
+            client.call_tool(call("list_users", serde_json::json!({}))).await.unwrap();
+
+            let operations = [
+                ("create_project", serde_json::json!({"name": "Gemini"}), db::UserRole::Editor),
+                (
+                    "set_user_role",
+                    serde_json::json!({"email": "new@example.com", "role": "viewer"}),
+                    db::UserRole::Admin,
+                ),
+                ("delete_project", serde_json::json!({"id": project.id.to_string()}), db::UserRole::Admin),
+            ];
+            for (tool, args, required) in operations {
+                match client.call_tool(call(tool, args)).await {
+                    Ok(_) => assert!(role >= required, "{} ran {}", role, tool),
+                    Err(ServiceError::McpError(e)) => {
+                        assert!(role < required, "{} refused {}: {}", role, tool, e.message);
+                        assert_eq!(e.code, ErrorCode::INVALID_REQUEST);
+                        let data = e.data.unwrap();
+                        assert_eq!(data["error"], "permission_denied");
+                        assert_eq!(data["role"], role.as_str());
+                        assert_eq!(data["required"], required.as_str());
+                    }
+                    Err(other) => panic!("unexpected error: {}", other),
+                }
+            }
+        }
     }
 }
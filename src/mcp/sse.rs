@@ -0,0 +1,390 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! The MCP HTTP/SSE server, started and stopped on demand
+//!
+//! The desktop app serves MCP over SSE on `127.0.0.1`, next to the health,
+//! metrics and REST endpoints from [`super::http`]. Binding a port can
+//! trigger a security prompt on managed machines, so the server is only
+//! started on launch when `mcp_enabled` is set, and [`SseController`] lets
+//! the app start and stop it later. Bind errors are returned to the caller.
+
+use super::server::ProjectTrackerServer;
+use crate::{config::SharedConfig, db};
+use anyhow::{Context, Result};
+use rmcp::transport::sse_server::{SseServer, SseServerConfig};
+use rmcp::ServiceExt;
+use serde::Serialize;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+/// How long `stop` waits for open connections to close before dropping them
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Whether the MCP HTTP server is running, where, and how many clients it has
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SseStatus {
+    pub running: bool,
+    /// Address the server is listening on, while it runs
+    pub address: Option<String>,
+    /// Number of connected SSE sessions
+    pub active_sessions: usize,
+}
+
+/// A running MCP HTTP/SSE server
+pub struct RunningSseServer {
+    address: SocketAddr,
+    server: ProjectTrackerServer,
+    ct: CancellationToken,
+    task: JoinHandle<()>,
+}
+
+impl RunningSseServer {
+    /// Bind `address` and serve `server` on it
+    ///
+    /// Fails without starting anything if the address can't be bound, such
+    /// as when another program is using the port.
+    pub async fn start(server: ProjectTrackerServer, address: SocketAddr) -> Result<Self> {
+        let listener = tokio::net::TcpListener::bind(address)
+            .await
+            .with_context(|| format!("Failed to bind the MCP HTTP server to {}", address))?;
+        let address = listener.local_addr()?;
+
+        let ct = CancellationToken::new();
+        let (mut sse_server, router) = SseServer::new(SseServerConfig {
+            bind: address,
+            sse_path: "/sse".to_string(),
+            post_path: "/message".to_string(),
+            ct: ct.clone(),
+            sse_keep_alive: Some(Duration::from_secs(30)),
+        });
+        let router = super::middleware::apply(router.merge(super::http::routes(server.clone())), server.clone());
+
+        // Serve each SSE connection as its own session, with its own handles,
+        // counting it while it lasts
+        let sessions = server.clone();
+        let session_ct = ct.clone();
+        tokio::spawn(async move {
+            while let Some(transport) = sse_server.next_transport().await {
+                let service = sessions.new_session();
+                let ct = session_ct.child_token();
+                tokio::spawn(async move {
+                    let _session = SessionGuard::new(service.session_counter());
+                    let running = service.serve_with_ct(transport, ct).await?;
+                    running.waiting().await?;
+                    anyhow::Ok(())
+                });
+            }
+        });
+
+        let shutdown = ct.clone();
+        let task = tokio::spawn(async move {
+            let result = axum::serve(listener, router.into_make_service_with_connect_info::<SocketAddr>())
+                .with_graceful_shutdown(async move { shutdown.cancelled().await })
+                .await;
+            match result {
+                Ok(()) => log::info!("MCP HTTP server stopped"),
+                Err(e) => log::error!("MCP HTTP server error: {}", e),
+            }
+        });
+
+        log::info!("MCP HTTP/SSE server listening on http://{}", address);
+        Ok(Self {
+            address,
+            server,
+            ct,
+            task,
+        })
+    }
+
+    /// Address the server is listening on
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Whether the server is still accepting connections
+    pub fn is_running(&self) -> bool {
+        !self.task.is_finished()
+    }
+
+    /// Number of connected SSE sessions
+    pub fn active_sessions(&self) -> usize {
+        self.server.active_sessions()
+    }
+
+    /// Close every session and stop listening
+    pub async fn stop(self) {
+        self.ct.cancel();
+        let abort = self.task.abort_handle();
+        if tokio::time::timeout(SHUTDOWN_TIMEOUT, self.task)
+            .await
+            .is_err()
+        {
+            abort.abort();
+        }
+    }
+}
+
+/// Counts a session for as long as it is held
+struct SessionGuard(Arc<AtomicUsize>);
+
+impl SessionGuard {
+    fn new(counter: Arc<AtomicUsize>) -> Self {
+        counter.fetch_add(1, Ordering::Relaxed);
+        Self(counter)
+    }
+}
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Starts and stops the desktop app's MCP HTTP server
+///
+/// The server gets its own connection to the database of the app's
+/// workspace, opened when it starts. [`SseController::switch_workspace`]
+/// moves it along when the app switches.
+pub struct SseController {
+    config: SharedConfig,
+    workspace: RwLock<String>,
+    running: Mutex<Option<RunningSseServer>>,
+}
+
+impl SseController {
+    pub fn new(config: impl Into<SharedConfig>, workspace: &str) -> Self {
+        Self {
+            config: config.into(),
+            workspace: RwLock::new(workspace.to_string()),
+            running: Mutex::new(None),
+        }
+    }
+
+    /// Name of the workspace the server uses
+    pub fn workspace(&self) -> String {
+        self.workspace.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Use another workspace's database, switching the server over if it is running
+    ///
+    /// Nothing changes if the running server can't open the new database.
+    pub async fn switch_workspace(&self, name: &str) -> Result<()> {
+        let running = self.running.lock().await;
+        if let Some(server) = running.as_ref().filter(|server| server.is_running()) {
+            server.server.use_workspace(name).await?;
+        }
+        *self.workspace.write().unwrap_or_else(|e| e.into_inner()) = name.to_string();
+        Ok(())
+    }
+
+    /// Start the server on launch, unless `mcp_enabled` is turned off
+    ///
+    /// Returns the address it listens on, or `None` if it is disabled.
+    pub async fn start_if_enabled(&self) -> Result<Option<SocketAddr>> {
+        if !self.config.get().mcp_enabled {
+            log::info!("MCP HTTP server is disabled; start it from the app when needed");
+            return Ok(None);
+        }
+        self.start().await.map(Some)
+    }
+
+    /// Start the server on `mcp_http_port`, or return its address if it is already running
+    pub async fn start(&self) -> Result<SocketAddr> {
+        let mut running = self.running.lock().await;
+        if let Some(server) = running.as_ref().filter(|server| server.is_running()) {
+            return Ok(server.address());
+        }
+
+        let workspace = self.workspace();
+        let conn = db::open_database(self.config.get().database_path_for(&workspace)?)?;
+        let server = ProjectTrackerServer::new(self.config.clone(), conn).for_workspace(&workspace);
+        let address = SocketAddr::from(([127, 0, 0, 1], self.config.get().mcp_http_port));
+        let server = RunningSseServer::start(server, address).await?;
+        let address = server.address();
+        *running = Some(server);
+        Ok(address)
+    }
+
+    /// Stop the server; returns false if it wasn't running
+    pub async fn stop(&self) -> bool {
+        match self.running.lock().await.take() {
+            Some(server) => {
+                server.stop().await;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether the server is running, its address and the number of SSE sessions
+    pub async fn status(&self) -> SseStatus {
+        match self.running.lock().await.as_ref() {
+            Some(server) if server.is_running() => SseStatus {
+                running: true,
+                address: Some(server.address().to_string()),
+                active_sessions: server.active_sessions(),
+            },
+            _ => SseStatus {
+                running: false,
+                address: None,
+                active_sessions: 0,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+    use crate::mcp::http::testing::{get, test_server};
+    use rmcp::model::CallToolRequestParam;
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpStream;
+
+    fn controller(mut config: Config) -> (SseController, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        config.data_dir = dir.path().to_string_lossy().into_owned();
+        let workspace = config.workspace().to_string();
+        let controller = SseController::new(config, &workspace);
+        (controller, dir)
+    }
+
+    /// Names of the projects in a workspace's database
+    fn project_names(config: &Config, workspace: &str) -> Vec<String> {
+        let conn = db::open_database(config.database_path_for(workspace).unwrap()).unwrap();
+        db::ProjectRepository::new(&conn)
+            .list_all()
+            .unwrap()
+            .into_iter()
+            .map(|project| project.name)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_disabled_server_is_not_started() {
+        let mut config = Config::default();
+        config.mcp_enabled = false;
+        config.mcp_http_port = 0;
+        let (controller, _dir) = controller(config);
+
+        assert_eq!(controller.start_if_enabled().await.unwrap(), None);
+        assert!(!controller.status().await.running);
+        assert!(!controller.stop().await);
+
+        // It can still be started on demand
+        let address = controller.start().await.unwrap();
+        assert_eq!(get(address, "/healthz").await.0, 200);
+        let status = controller.status().await;
+        assert!(status.running);
+        assert_eq!(status.address, Some(address.to_string()));
+
+        assert!(controller.stop().await);
+        assert!(!controller.status().await.running);
+    }
+
+    #[tokio::test]
+    async fn test_port_conflict_is_reported() {
+        let taken = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut config = Config::default();
+        config.mcp_http_port = taken.local_addr().unwrap().port();
+        let (controller, _dir) = controller(config);
+
+        let err = controller.start_if_enabled().await.unwrap_err();
+        assert!(
+            format!("{:#}", err).contains("Failed to bind the MCP HTTP server"),
+            "{:#}",
+            err
+        );
+        assert_eq!(
+            controller.status().await,
+            SseStatus {
+                running: false,
+                address: None,
+                active_sessions: 0,
+            }
+        );
+
+        // Once the port is free the server starts
+        drop(taken);
+        controller.start().await.unwrap();
+        assert!(controller.status().await.running);
+        controller.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_switch_workspace_moves_the_server() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.data_dir = dir.path().join("work").to_string_lossy().into_owned();
+        config
+            .workspaces
+            .insert("personal".to_string(), dir.path().join("personal").to_string_lossy().into_owned());
+        config.mcp_http_port = 0;
+        let controller = SseController::new(config.clone(), "default");
+        controller.start().await.unwrap();
+
+        // A session connected before the switch writes to the new workspace
+        let session = controller.running.lock().await.as_ref().unwrap().server.new_session();
+        let (server_transport, client_transport) = tokio::io::duplex(4096);
+        tokio::spawn(async move {
+            let service = session.serve(server_transport).await?;
+            service.waiting().await?;
+            anyhow::Ok(())
+        });
+        let client = ().serve(client_transport).await.unwrap();
+
+        controller.switch_workspace("personal").await.unwrap();
+        assert_eq!(controller.workspace(), "personal");
+        client
+            .call_tool(CallToolRequestParam {
+                name: "create_project".into(),
+                arguments: serde_json::json!({"name": "Garden"}).as_object().cloned(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(project_names(&config, "personal"), ["Garden"]);
+        assert!(project_names(&config, "default").is_empty());
+
+        // A stopped server starts on the workspace it was last switched to
+        controller.stop().await;
+        controller.switch_workspace("default").await.unwrap();
+        controller.start().await.unwrap();
+        assert_eq!(controller.running.lock().await.as_ref().unwrap().server.workspace(), "default");
+        controller.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_sse_sessions_are_counted() {
+        let server = RunningSseServer::start(
+            test_server(Config::default()),
+            "127.0.0.1:0".parse().unwrap(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(server.active_sessions(), 0);
+
+        let mut stream = TcpStream::connect(server.address()).await.unwrap();
+        stream
+            .write_all(b"GET /sse HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        for _ in 0..100 {
+            if server.active_sessions() == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(server.active_sessions(), 1);
+
+        drop(stream);
+        server.stop().await;
+    }
+}
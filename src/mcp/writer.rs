@@ -0,0 +1,175 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Single writer for tool calls that change data
+//!
+//! Every session shares one database connection. Tools that only read lock
+//! it directly; tools that write submit their work to a [`WriteQueue`],
+//! whose thread runs jobs one at a time in the order they were submitted. A
+//! job does its reads and writes in one go, so two sessions updating the
+//! same record can't interleave their fetch-modify-write steps. The queue is
+//! bounded: when it is full, writers wait for room instead of piling up.
+//! Jobs are blocking SQLite calls, so they run on a thread of their own
+//! rather than tying up one of the runtime's workers.
+
+use crate::config::{Config, SharedConfig};
+use crate::webhook::WebhookDispatcher;
+use rmcp::ErrorData as McpError;
+use rusqlite::Connection;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot, Mutex};
+
+/// Number of writes that can be waiting for the writer
+pub const WRITE_QUEUE_CAPACITY: usize = 64;
+
+/// What a write job can use besides the connection
+///
+/// `config` is the configuration as it was when the job started.
+pub struct WriteContext {
+    pub config: Arc<Config>,
+    pub webhooks: WebhookDispatcher,
+}
+
+type Job = Box<dyn FnOnce(&Connection, &WriteContext) + Send>;
+
+/// Handle for submitting writes to the writer thread
+///
+/// Clones share the same queue and thread.
+#[derive(Clone)]
+pub struct WriteQueue {
+    jobs: mpsc::Sender<Job>,
+}
+
+impl WriteQueue {
+    /// Start the writer thread
+    ///
+    /// The thread stops once every handle has been dropped.
+    pub fn spawn(db: Arc<Mutex<Connection>>, config: SharedConfig, webhooks: WebhookDispatcher) -> Self {
+        let (jobs, mut queue) = mpsc::channel::<Job>(WRITE_QUEUE_CAPACITY);
+        let writer = move || {
+            let mut context = WriteContext {
+                config: config.get(),
+                webhooks,
+            };
+            while let Some(job) = queue.blocking_recv() {
+                context.config = config.get();
+                let conn = db.blocking_lock();
+                // A panicking job fails its own call, not every write after it
+                if std::panic::catch_unwind(AssertUnwindSafe(|| job(&conn, &context))).is_err() {
+                    log::error!("A database write panicked");
+                }
+            }
+        };
+        std::thread::Builder::new()
+            .name("mcp-writer".to_string())
+            .spawn(writer)
+            .expect("Failed to start the database writer thread");
+        Self { jobs }
+    }
+
+    /// Run `job` on the writer thread and wait for its result
+    pub async fn submit<T, F>(&self, job: F) -> Result<T, McpError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection, &WriteContext) -> T + Send + 'static,
+    {
+        let (reply, result) = oneshot::channel();
+        let job: Job = Box::new(move |conn, context| {
+            // The caller may have gone away; its result is then dropped
+            let _ = reply.send(job(conn, context));
+        });
+        self.jobs
+            .send(job)
+            .await
+            .map_err(|_| McpError::internal_error("The database writer has stopped", None))?;
+        result
+            .await
+            .map_err(|_| McpError::internal_error("The database write failed", None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_writes_run_in_order() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("CREATE TABLE log (n INTEGER)", []).unwrap();
+        let db = Arc::new(Mutex::new(conn));
+        let queue = WriteQueue::spawn(db.clone(), Config::default().into(), WebhookDispatcher::default());
+
+        let writes = (0..20).map(|n| {
+            let queue = queue.clone();
+            tokio::spawn(async move {
+                queue
+                    .submit(move |conn, _| {
+                        // Read, then write based on what was read
+                        let count: i64 = conn
+                            .query_row("SELECT COUNT(*) FROM log", [], |row| row.get(0))
+                            .unwrap();
+                        std::thread::sleep(std::time::Duration::from_millis(1));
+                        conn.execute("INSERT INTO log (n) VALUES (?1)", [count])
+                            .unwrap();
+                        n
+                    })
+                    .await
+                    .unwrap()
+            })
+        });
+        for write in writes.collect::<Vec<_>>() {
+            write.await.unwrap();
+        }
+
+        // Every job saw all the writes before it
+        let conn = db.lock().await;
+        let mut stmt = conn.prepare("SELECT n FROM log ORDER BY rowid").unwrap();
+        let logged: Vec<i64> = stmt
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(logged, (0..20).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_panicking_write() {
+        let queue = WriteQueue::spawn(
+            Arc::new(Mutex::new(Connection::open_in_memory().unwrap())),
+            Config::default().into(),
+            WebhookDispatcher::default(),
+        );
+
+        let result: Result<(), _> = queue.submit(|_, _| panic!("boom")).await;
+        assert!(result.is_err());
+        assert_eq!(queue.submit(|_, _| 42).await.unwrap(), 42);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_writes_leave_the_runtime_free() {
+        let queue = WriteQueue::spawn(
+            Arc::new(Mutex::new(Connection::open_in_memory().unwrap())),
+            Config::default().into(),
+            WebhookDispatcher::default(),
+        );
+
+        // The runtime has one thread; a write blocking it would stall this test
+        let (finish, finished) = std::sync::mpsc::channel::<()>();
+        let write = tokio::spawn({
+            let queue = queue.clone();
+            async move {
+                queue
+                    .submit(move |_, _| {
+                        finished.recv().unwrap();
+                        std::thread::current().name().map(String::from)
+                    })
+                    .await
+            }
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        finish.send(()).unwrap();
+        assert_eq!(write.await.unwrap().unwrap().as_deref(), Some("mcp-writer"));
+    }
+}
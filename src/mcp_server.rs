@@ -8,7 +8,7 @@
 //! using stdio transport for integration with Claude Desktop and other AI assistants.
 
 use anyhow::Result;
-use project_tracker::{db, mcp::ProjectTrackerServer, Config};
+use project_tracker::{config::SharedConfig, db, mcp::ProjectTrackerServer, Config, Storage};
 use rmcp::ServiceExt;
 
 #[tokio::main]
@@ -24,9 +24,28 @@ async fn main() -> Result<()> {
     let config = Config::load_or_default()?;
     config.ensure_data_dir()?;
 
+    // Stay out of a data directory another machine has open, and while
+    // running, let other machines know this one has it open
+    let storage = Storage::new(config.data_dir_path()?)?;
+    storage.check_sync_safety(&config.sync_safety)?;
+    let _heartbeat = storage.start_heartbeat("track-mcp", &config.sync_safety)?;
+
     // Open database
     let db_path = config.database_path()?;
     let conn = db::open_database(&db_path)?;
+    if let Err(e) = db::schema::set_unique_project_names(&conn, config.enforce_unique_project_names) {
+        log::warn!("Failed to apply the unique project name policy: {:#}", e);
+    }
+
+    // Pick up changes to the config file without a restart
+    let config = SharedConfig::new(config);
+    let _watcher = match Config::default_path().and_then(|path| config.watch(&path, |_| {})) {
+        Ok(watcher) => Some(watcher),
+        Err(e) => {
+            log::warn!("Config changes need a restart to take effect: {:#}", e);
+            None
+        }
+    };
 
     // Create server
     let server = ProjectTrackerServer::new(config, conn);
@@ -0,0 +1,168 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Wiki-style references from note bodies to other records
+//!
+//! A note mentions a project, person or milestone with a token such as
+//! `[[project:apollo]]`, `[[person:alice@example.com]]` or
+//! `[[milestone:<uuid>]]`. Projects can be named by ID or slug, people by
+//! email and milestones by ID. Tokens inside code blocks and inline code are
+//! left alone so a note can show the syntax without making a reference.
+
+use super::parser_options;
+use crate::db::ReferenceKind;
+use pulldown_cmark::{Event, Parser, Tag};
+use std::fmt;
+use std::ops::Range;
+
+/// A reference token found in a note body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteRef {
+    pub kind: ReferenceKind,
+
+    /// Target as written: a UUID, slug or email
+    pub target: String,
+}
+
+impl NoteRef {
+    pub fn new(kind: ReferenceKind, target: impl Into<String>) -> Self {
+        Self {
+            kind,
+            target: target.into(),
+        }
+    }
+
+    /// Whether two references point at the same target, ignoring case
+    fn same_target(&self, other: &NoteRef) -> bool {
+        self.kind == other.kind && self.target.eq_ignore_ascii_case(&other.target)
+    }
+}
+
+impl fmt::Display for NoteRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[[{}:{}]]", self.kind, self.target)
+    }
+}
+
+/// Find the references in a note body
+///
+/// References are returned in the order they first appear. Targets are
+/// compared ignoring case, so mentioning the same record twice returns it
+/// once.
+pub fn parse(body: &str) -> Vec<NoteRef> {
+    let mut refs: Vec<NoteRef> = Vec::new();
+    for (_, reference) in tokens(body) {
+        if !refs.iter().any(|r| r.same_target(&reference)) {
+            refs.push(reference);
+        }
+    }
+    refs
+}
+
+/// Replace each reference token in a note body with the text `f` returns
+///
+/// Tokens `f` returns `None` for are kept as written, as are tokens in code.
+pub fn replace(body: &str, mut f: impl FnMut(&NoteRef) -> Option<String>) -> String {
+    let mut out = String::with_capacity(body.len());
+    let mut copied = 0;
+    for (range, reference) in tokens(body) {
+        if let Some(text) = f(&reference) {
+            out.push_str(&body[copied..range.start]);
+            out.push_str(&text);
+            copied = range.end;
+        }
+    }
+    out.push_str(&body[copied..]);
+    out
+}
+
+/// Every reference token outside code, with its byte range in the body
+fn tokens(body: &str) -> Vec<(Range<usize>, NoteRef)> {
+    let code = code_ranges(body);
+    let mut found = Vec::new();
+    let mut from = 0;
+    while let Some(start) = body[from..].find("[[").map(|i| from + i) {
+        let Some(end) = body[start + 2..].find("]]").map(|i| start + 4 + i) else {
+            break;
+        };
+        match parse_token(&body[start + 2..end - 2]) {
+            Some(reference) => {
+                if !code.iter().any(|range| range.contains(&start)) {
+                    found.push((start..end, reference));
+                }
+                from = end;
+            }
+            // `[[[project:x]]` still holds a token one character on
+            None => from = start + 1,
+        }
+    }
+    found
+}
+
+/// Parse the `kind:target` inside a token's brackets
+fn parse_token(inner: &str) -> Option<NoteRef> {
+    let (kind, target) = inner.split_once(':')?;
+    let invalid = |c: char| c.is_whitespace() || c == '[' || c == ']';
+    if kind.contains(invalid) || target.is_empty() || target.contains(invalid) {
+        return None;
+    }
+    Some(NoteRef::new(kind.parse().ok()?, target))
+}
+
+/// Byte ranges of the code blocks and inline code spans in a body
+fn code_ranges(body: &str) -> Vec<Range<usize>> {
+    Parser::new_ext(body, parser_options())
+        .into_offset_iter()
+        .filter_map(|(event, range)| match event {
+            Event::Start(Tag::CodeBlock(_)) | Event::Code(_) => Some(range),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_references() {
+        let body = "Depends on [[project:apollo]] and [[Person:Alice@Example.com]].\n\
+                    Ships with [[milestone:6f1c2d4e-0000-4000-8000-000000000001]]\n\
+                    Again [[project:Apollo]], at the end of a line: [[person:bob@example.com]]";
+        assert_eq!(
+            parse(body),
+            vec![
+                NoteRef::new(ReferenceKind::Project, "apollo"),
+                NoteRef::new(ReferenceKind::Person, "Alice@Example.com"),
+                NoteRef::new(ReferenceKind::Milestone, "6f1c2d4e-0000-4000-8000-000000000001"),
+                NoteRef::new(ReferenceKind::Person, "bob@example.com"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_skips_code_and_malformed_tokens() {
+        let body = "```\n[[project:fenced]]\n```\n\n\
+                    ~~~markdown\n[[project:tilde]]\n~~~\n\n\
+                    Write `[[project:inline]]` to link.\n\n    [[project:indented]]\n\n\
+                    [[project:]] [[team:platform]] [[project:two words]] [[project:open\n\
+                    [[[project:nested]] [[project:unclosed]";
+        assert_eq!(parse(body), vec![NoteRef::new(ReferenceKind::Project, "nested")]);
+        assert!(parse("").is_empty());
+    }
+
+    #[test]
+    fn test_replace_references() {
+        let body = "See [[project:apollo]] and `[[project:apollo]]`, ask [[person:alice@example.com]]";
+        let replaced = replace(body, |r| match r.kind {
+            ReferenceKind::Project => Some("[Apollo](project://1)".to_string()),
+            _ => None,
+        });
+        assert_eq!(
+            replaced,
+            "See [Apollo](project://1) and `[[project:apollo]]`, ask [[person:alice@example.com]]"
+        );
+        assert_eq!(NoteRef::new(ReferenceKind::Person, "bob@example.com").to_string(), "[[person:bob@example.com]]");
+    }
+}
@@ -0,0 +1,389 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Markdown rendering and link extraction for note bodies
+
+pub mod links;
+
+use crate::db::{MilestoneNote, NoteCursor, PersonNote, ProjectNote, StakeholderNote};
+use pulldown_cmark::{html, Event, Options, Parser, Tag, TagEnd};
+use serde::Serialize;
+use url::Url;
+
+/// Markdown extensions enabled for note bodies
+fn parser_options() -> Options {
+    Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS
+}
+
+/// Render a Markdown note body to sanitized HTML
+///
+/// Raw HTML in the body is passed through the sanitizer, which removes
+/// `script`/`style` elements, event-handler attributes and unsafe URL schemes.
+pub fn render(body: &str) -> String {
+    let parser = Parser::new_ext(body, parser_options());
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, parser);
+    ammonia::clean(&unsafe_html)
+}
+
+/// Extract the HTTP(S) links from a Markdown note body
+///
+/// Finds Markdown links, autolinks and bare URLs in the text. Links are
+/// returned in the order they first appear, without duplicates.
+pub fn extract_links(body: &str) -> Vec<Url> {
+    let mut links: Vec<Url> = Vec::new();
+    let mut push = |candidate: &str| {
+        if let Ok(url) = Url::parse(candidate) {
+            if matches!(url.scheme(), "http" | "https") && !links.contains(&url) {
+                links.push(url);
+            }
+        }
+    };
+
+    for event in Parser::new_ext(body, parser_options()) {
+        match event {
+            Event::Start(Tag::Link { dest_url, .. }) | Event::Start(Tag::Image { dest_url, .. }) => {
+                push(&dest_url);
+            }
+            Event::Text(text) | Event::Code(text) => {
+                for word in text.split_whitespace() {
+                    if word.starts_with("http://") || word.starts_with("https://") {
+                        push(word.trim_end_matches(|c: char| ".,;:!?)]}'\"".contains(c)));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    links
+}
+
+/// Extract the unchecked task list items (`- [ ] ...`) from a Markdown note body
+///
+/// Returns the text of each item in the order it appears. Nested items are
+/// returned separately from their parent.
+pub fn open_tasks(body: &str) -> Vec<String> {
+    let mut tasks = Vec::new();
+    let mut current: Option<String> = None;
+
+    for event in Parser::new_ext(body, parser_options()) {
+        match event {
+            Event::TaskListMarker(checked) => {
+                current = (!checked).then(String::new);
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(task) = current.as_mut() {
+                    task.push_str(&text);
+                }
+            }
+            Event::SoftBreak | Event::HardBreak => {
+                if let Some(task) = current.as_mut() {
+                    task.push(' ');
+                }
+            }
+            Event::Start(Tag::List(_)) | Event::End(TagEnd::Item) => {
+                if let Some(task) = current.take() {
+                    let task = task.trim();
+                    if !task.is_empty() {
+                        tasks.push(task.to_string());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    tasks
+}
+
+/// Note types that carry a Markdown body
+pub trait NoteBody {
+    /// The raw Markdown body of the note
+    fn body(&self) -> &str;
+
+    /// The body, for cutting it short in listings
+    fn body_mut(&mut self) -> &mut String;
+}
+
+impl NoteBody for ProjectNote {
+    fn body(&self) -> &str {
+        &self.body
+    }
+
+    fn body_mut(&mut self) -> &mut String {
+        &mut self.body
+    }
+}
+
+impl NoteBody for MilestoneNote {
+    fn body(&self) -> &str {
+        &self.body
+    }
+
+    fn body_mut(&mut self) -> &mut String {
+        &mut self.body
+    }
+}
+
+impl NoteBody for StakeholderNote {
+    fn body(&self) -> &str {
+        &self.body
+    }
+
+    fn body_mut(&mut self) -> &mut String {
+        &mut self.body
+    }
+}
+
+impl NoteBody for PersonNote {
+    fn body(&self) -> &str {
+        &self.body
+    }
+
+    fn body_mut(&mut self) -> &mut String {
+        &mut self.body
+    }
+}
+
+/// Note types that can be listed a page at a time
+pub trait NotePosition {
+    /// The cursor that continues a listing after this note
+    fn cursor(&self) -> NoteCursor;
+}
+
+impl NotePosition for ProjectNote {
+    fn cursor(&self) -> NoteCursor {
+        NoteCursor::new(self.created_at, self.id)
+    }
+}
+
+impl NotePosition for MilestoneNote {
+    fn cursor(&self) -> NoteCursor {
+        NoteCursor::new(self.created_at, self.id)
+    }
+}
+
+impl NotePosition for StakeholderNote {
+    fn cursor(&self) -> NoteCursor {
+        NoteCursor::new(self.created_at, self.id)
+    }
+}
+
+/// A note with an optional rendered HTML body
+///
+/// Serializes as the underlying note, plus a `body_html` field when rendered
+/// and `total_chars`/`next_offset` fields when the body was cut short.
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderedNote<T> {
+    #[serde(flatten)]
+    pub note: T,
+
+    /// Sanitized HTML rendering of the note body
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body_html: Option<String>,
+
+    /// Length of the whole body in characters, when a limit was given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_chars: Option<usize>,
+
+    /// Character offset the rest of a cut-short body starts at
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<usize>,
+}
+
+/// Cut a body to at most `max_chars` characters, returning its full length
+/// and, if anything was cut, where the rest starts
+fn truncate_chars(body: &mut String, max_chars: usize) -> (usize, Option<usize>) {
+    match body.char_indices().nth(max_chars) {
+        Some((cut, _)) => {
+            let total = max_chars + body[cut..].chars().count();
+            body.truncate(cut);
+            (total, Some(max_chars))
+        }
+        None => (body.chars().count(), None),
+    }
+}
+
+/// Wrap notes for output, rendering their bodies to HTML when requested
+///
+/// With `max_chars`, longer bodies are cut to that many characters before
+/// rendering; the rest can be read with the note's `next_offset`.
+pub fn with_html<T: NoteBody>(notes: Vec<T>, rendered: bool, max_chars: Option<usize>) -> Vec<RenderedNote<T>> {
+    notes
+        .into_iter()
+        .map(|mut note| {
+            let (total_chars, next_offset) = match max_chars {
+                Some(max_chars) => {
+                    let (total, next) = truncate_chars(note.body_mut(), max_chars);
+                    (Some(total), next)
+                }
+                None => (None, None),
+            };
+            let body_html = rendered.then(|| render(note.body()));
+            RenderedNote {
+                note,
+                body_html,
+                total_chars,
+                next_offset,
+            }
+        })
+        .collect()
+}
+
+/// A page of notes and the cursor for the next page
+#[derive(Debug, Clone, Serialize)]
+pub struct NotePage<T> {
+    pub notes: Vec<RenderedNote<T>>,
+
+    /// Pass back to fetch the next page; absent on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Wrap a page of at most `limit` notes for output
+///
+/// A full page may be followed by more notes, so it gets a cursor; a short
+/// page is the last one.
+pub fn page_with_html<T: NoteBody + NotePosition>(
+    notes: Vec<T>,
+    limit: usize,
+    rendered: bool,
+    max_chars: Option<usize>,
+) -> NotePage<T> {
+    let next_cursor = match notes.last() {
+        Some(last) if notes.len() >= limit => Some(last.cursor().to_string()),
+        _ => None,
+    };
+    NotePage {
+        notes: with_html(notes, rendered, max_chars),
+        next_cursor,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_render_markdown() {
+        let html = render("# Title\n\nSome **bold** text");
+        assert!(html.contains("<h1>Title</h1>"));
+        assert!(html.contains("<strong>bold</strong>"));
+    }
+
+    #[test]
+    fn test_render_strips_xss() {
+        let body = "Hello <script>alert('xss')</script>\n\n\
+                    <img src=\"x.png\" onerror=\"alert(1)\">\n\n\
+                    <style>body { display: none; }</style>\n\n\
+                    <a href=\"#\" onclick=\"steal()\">click</a>\n\n\
+                    [link](javascript:alert(1))";
+        let html = render(body);
+
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("alert('xss')"));
+        assert!(!html.contains("<style"));
+        assert!(!html.contains("display: none"));
+        assert!(!html.contains("onerror"));
+        assert!(!html.contains("onclick"));
+        assert!(!html.contains("javascript:"));
+        assert!(html.contains("Hello"));
+        assert!(html.contains("click"));
+    }
+
+    #[test]
+    fn test_extract_links() {
+        let body = "See the [design doc](https://docs.example.com/design) and \
+                    <https://jira.example.com/browse/PROJ-1>.\n\n\
+                    Also https://wiki.example.com/page, and again https://docs.example.com/design";
+        let links = extract_links(body);
+
+        let links: Vec<&str> = links.iter().map(|u| u.as_str()).collect();
+        assert_eq!(
+            links,
+            vec![
+                "https://docs.example.com/design",
+                "https://jira.example.com/browse/PROJ-1",
+                "https://wiki.example.com/page",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_links_ignores_non_http() {
+        let links = extract_links("[mail](mailto:alice@example.com) [js](javascript:alert(1)) plain text");
+        assert!(links.is_empty());
+    }
+
+    #[test]
+    fn test_open_tasks() {
+        let body = "Follow-ups:\n\n\
+                    - [ ] Confirm the `v2` launch date\n\
+                    - [x] Send the deck\n\
+                    - [ ] Who signs off\n  on the budget?\n  \
+                    - [ ] Nested question\n\
+                    - Not a task";
+        assert_eq!(
+            open_tasks(body),
+            vec![
+                "Confirm the v2 launch date",
+                "Who signs off on the budget?",
+                "Nested question",
+            ]
+        );
+        assert!(open_tasks("No tasks here").is_empty());
+    }
+
+    #[test]
+    fn test_with_html() {
+        let note = ProjectNote::new(Uuid::new_v4(), "Title".to_string(), "*hi*".to_string());
+
+        let plain = with_html(vec![note.clone()], false, None);
+        assert!(plain[0].body_html.is_none());
+        let json = serde_json::to_value(&plain[0]).unwrap();
+        assert!(json.get("body_html").is_none());
+        assert_eq!(json["body"], "*hi*");
+
+        let rendered = with_html(vec![note], true, None);
+        assert!(rendered[0].body_html.as_deref().unwrap().contains("<em>hi</em>"));
+    }
+
+    #[test]
+    fn test_with_html_max_chars() {
+        let project_id = Uuid::new_v4();
+        let long = ProjectNote::new(project_id, "Long".to_string(), "**Grüße** aus Köln".to_string());
+        let short = ProjectNote::new(project_id, "Short".to_string(), "ok".to_string());
+
+        let notes = with_html(vec![long, short], true, Some(9));
+        assert_eq!(notes[0].note.body, "**Grüße**");
+        assert_eq!(notes[0].total_chars, Some(18));
+        assert_eq!(notes[0].next_offset, Some(9));
+        assert!(notes[0].body_html.as_deref().unwrap().contains("<strong>Grüße</strong>"));
+
+        // Short bodies report their length but have nothing more to read
+        let json = serde_json::to_value(&notes[1]).unwrap();
+        assert_eq!(json["total_chars"], 2);
+        assert!(json.get("next_offset").is_none());
+    }
+
+    #[test]
+    fn test_page_with_html() {
+        let project_id = Uuid::new_v4();
+        let notes: Vec<ProjectNote> = (0..2)
+            .map(|i| ProjectNote::new(project_id, format!("Note {}", i), "**body**".to_string()))
+            .collect();
+        let last = notes[1].cursor();
+
+        let page = page_with_html(notes.clone(), 2, true, None);
+        assert_eq!(page.notes.len(), 2);
+        assert!(page.notes[0].body_html.as_deref().unwrap().contains("<strong>"));
+        assert_eq!(page.next_cursor, Some(last.to_string()));
+        assert_eq!(page.next_cursor.unwrap().parse::<NoteCursor>().unwrap(), last);
+
+        let page = page_with_html(notes, 3, false, None);
+        assert!(page.notes[0].body_html.is_none());
+        assert!(page.next_cursor.is_none());
+    }
+}
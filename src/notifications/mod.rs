@@ -0,0 +1,357 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Milestone reminders for desktop notifications
+//!
+//! [`plan_notifications`] works out which reminders are due at a given time:
+//! a heads-up in the days before a milestone is due, one on the morning it is
+//! due, and one once it is overdue. Each reminder is shown at most once per
+//! due date; [`mark_shown`] records the ones that were displayed so the next
+//! check skips them. Reminders planned for a user only cover the projects
+//! they watch, manage or lead. Showing the notifications is left to the caller.
+
+use crate::config::Config;
+use crate::db::{DueMilestone, NotificationKind, NotificationRepository, Result, WatcherRepository};
+use crate::i18n::Locale;
+use crate::utils::start_of_local_day;
+use chrono::{DateTime, Duration, NaiveDate, Timelike, Utc};
+use chrono_tz::Tz;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Local hour from which reminders about upcoming and due milestones are shown
+pub const NOTIFY_FROM_HOUR: u32 = 8;
+
+/// Milestones that went overdue longer ago than this are not reminded about,
+/// so old milestones don't all pop up the first time reminders run
+pub const OVERDUE_LOOKBACK_DAYS: i64 = 7;
+
+/// Largest number of days ahead that reminders can start
+pub const MAX_DAYS_BEFORE: u32 = 365;
+
+/// Reminder settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationSettings {
+    /// Whether reminders are shown at all
+    pub enabled: bool,
+
+    /// How many days ahead of a due date to start reminding, 0 for only on the day
+    pub days_before: u32,
+}
+
+impl NotificationSettings {
+    /// The reminder settings in a configuration
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            enabled: config.notifications_enabled,
+            days_before: config.notify_days_before,
+        }
+    }
+
+    /// Copy these settings into a configuration
+    pub fn apply(&self, config: &mut Config) {
+        config.notifications_enabled = self.enabled;
+        config.notify_days_before = self.days_before;
+    }
+}
+
+/// A reminder that should be shown
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedNotification {
+    /// Milestone the reminder is about
+    pub milestone_id: Uuid,
+
+    /// Project the milestone belongs to
+    pub project_id: Uuid,
+
+    /// Where the milestone is in its schedule
+    pub kind: NotificationKind,
+
+    /// The due date the reminder is about
+    pub due_date: DateTime<Utc>,
+
+    /// Notification title
+    pub title: String,
+
+    /// Notification text
+    pub body: String,
+}
+
+/// Work out which reminders to show at `now`, with their text in `locale`
+///
+/// Dates are compared in `now`'s time zone. Reminders that were already shown
+/// for the milestone's current due date are left out, as are milestones of
+/// projects that don't concern `user` when one is given.
+pub fn plan_notifications(
+    conn: &Connection,
+    now: DateTime<Tz>,
+    cfg: &NotificationSettings,
+    locale: Locale,
+    user: Option<&str>,
+) -> Result<Vec<PlannedNotification>> {
+    if !cfg.enabled {
+        return Ok(Vec::new());
+    }
+
+    let tz = now.timezone();
+    let today = now.date_naive();
+    let morning = now.hour() >= NOTIFY_FROM_HOUR;
+    let from = start_of_local_day(today - Duration::days(OVERDUE_LOOKBACK_DAYS), tz);
+    let until = start_of_local_day(
+        today + Duration::days(cfg.days_before.min(MAX_DAYS_BEFORE) as i64 + 1),
+        tz,
+    );
+
+    let relevant = user
+        .map(|email| WatcherRepository::new(conn).relevant_project_ids(email))
+        .transpose()?;
+    let repo = NotificationRepository::new(conn);
+    let shown = repo.notified()?;
+    let mut planned = Vec::new();
+    for milestone in repo.due_milestones(from, until)? {
+        if relevant.as_ref().is_some_and(|ids| !ids.contains(&milestone.project_id)) {
+            continue;
+        }
+        let due = milestone.due_date.with_timezone(&tz).date_naive();
+        let days_until = (due - today).num_days();
+        let kind = match days_until {
+            d if d < 0 => NotificationKind::Overdue,
+            0 => NotificationKind::DueToday,
+            _ => NotificationKind::Upcoming,
+        };
+        if kind != NotificationKind::Overdue && !morning {
+            continue;
+        }
+        if shown.contains(&(milestone.milestone_id, kind, milestone.due_date)) {
+            continue;
+        }
+        planned.push(notification(milestone, kind, days_until, due, locale));
+    }
+
+    Ok(planned)
+}
+
+/// Record that reminders were shown, so they aren't planned again
+pub fn mark_shown(
+    conn: &Connection,
+    notifications: &[PlannedNotification],
+    now: DateTime<Utc>,
+) -> Result<()> {
+    let repo = NotificationRepository::new(conn);
+    for planned in notifications {
+        repo.mark_notified(&planned.milestone_id, planned.kind, planned.due_date, now)?;
+    }
+    Ok(())
+}
+
+fn notification(
+    milestone: DueMilestone,
+    kind: NotificationKind,
+    days_until: i64,
+    due: NaiveDate,
+    locale: Locale,
+) -> PlannedNotification {
+    let name = format!("{}: {}", milestone.project_name, milestone.milestone_name);
+    let date = locale.naive_date(due);
+    let is_due = || locale.format("notification.is_due", &[("name", &name), ("date", &date)]);
+    let (title, body) = match kind {
+        NotificationKind::Upcoming if days_until == 1 => {
+            (locale.text("notification.due_tomorrow").to_string(), is_due())
+        }
+        NotificationKind::Upcoming => (
+            locale.format("notification.due_in_days", &[("days", &days_until)]),
+            is_due(),
+        ),
+        NotificationKind::DueToday => (locale.text("notification.due_today").to_string(), name.clone()),
+        NotificationKind::Overdue => (
+            locale.text("notification.overdue").to_string(),
+            locale.format("notification.was_due", &[("name", &name), ("date", &date)]),
+        ),
+    };
+
+    PlannedNotification {
+        milestone_id: milestone.milestone_id,
+        project_id: milestone.project_id,
+        kind,
+        due_date: milestone.due_date,
+        title,
+        body,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{schema, Milestone, Project, ProjectRepository};
+    use chrono::TimeZone;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        schema::initialize_schema(&conn).unwrap();
+        schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn add_milestone(
+        conn: &Connection,
+        project: &Project,
+        name: &str,
+        due: DateTime<Utc>,
+    ) -> Milestone {
+        let repo = ProjectRepository::new(conn);
+        let number = repo.get_milestones(&project.id).unwrap().len() as i32 + 1;
+        let mut milestone = Milestone::new(project.id, number, name.to_string());
+        milestone.due_date = Some(due);
+        repo.add_milestone(&milestone).unwrap();
+        milestone
+    }
+
+    fn kinds(planned: &[PlannedNotification]) -> Vec<(&str, NotificationKind)> {
+        planned
+            .iter()
+            .map(|p| (p.body.split(" is due").next().unwrap(), p.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_plan_notifications() {
+        let conn = setup_test_db();
+        let project = Project::new("Apollo".to_string());
+        ProjectRepository::new(&conn).create(&project).unwrap();
+
+        // 9am March 14 in Tokyo is midnight UTC
+        let tz = chrono_tz::Asia::Tokyo;
+        let now = tz.with_ymd_and_hms(2025, 3, 14, 9, 0, 0).unwrap();
+        let at = |day: u32, hour: u32| {
+            tz.with_ymd_and_hms(2025, 3, day, hour, 0, 0)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+
+        add_milestone(&conn, &project, "Ancient", at(1, 12));
+        let late = add_milestone(&conn, &project, "Late", at(13, 18));
+        add_milestone(&conn, &project, "Today", at(14, 23));
+        let soon = add_milestone(&conn, &project, "Soon", at(16, 10));
+        add_milestone(&conn, &project, "Later", at(20, 10));
+
+        let cfg = NotificationSettings {
+            enabled: true,
+            days_before: 2,
+        };
+        let planned = plan_notifications(&conn, now, &cfg, Locale::En, None).unwrap();
+        assert_eq!(
+            kinds(&planned),
+            vec![
+                ("Apollo: Late was due 2025-03-13", NotificationKind::Overdue),
+                ("Apollo: Today", NotificationKind::DueToday),
+                ("Apollo: Soon", NotificationKind::Upcoming),
+            ]
+        );
+        assert_eq!(planned[2].title, "Milestone due in 2 days");
+        assert_eq!(planned[0].milestone_id, late.id);
+
+        // The same reminders in German
+        let german = plan_notifications(&conn, now, &cfg, Locale::De, None).unwrap();
+        assert_eq!(german[0].title, "Meilenstein überfällig");
+        assert_eq!(german[0].body, "Apollo: Late war am 13.03.2025 fällig");
+        assert_eq!(german[2].title, "Meilenstein in 2 Tagen fällig");
+
+        // Shown reminders aren't planned again
+        mark_shown(&conn, &planned, now.with_timezone(&Utc)).unwrap();
+        assert!(plan_notifications(&conn, now, &cfg, Locale::En, None).unwrap().is_empty());
+
+        // A new due date gets a new reminder
+        let mut moved = soon.clone();
+        moved.due_date = Some(at(15, 10));
+        ProjectRepository::new(&conn)
+            .update_milestone(&moved)
+            .unwrap();
+        let planned = plan_notifications(&conn, now, &cfg, Locale::En, None).unwrap();
+        assert_eq!(planned.len(), 1);
+        assert_eq!(planned[0].title, "Milestone due tomorrow");
+
+        // The day after, the milestone that was due today is overdue
+        let tomorrow = tz.with_ymd_and_hms(2025, 3, 15, 9, 0, 0).unwrap();
+        mark_shown(&conn, &planned, now.with_timezone(&Utc)).unwrap();
+        let planned = plan_notifications(&conn, tomorrow, &cfg, Locale::En, None).unwrap();
+        assert_eq!(
+            kinds(&planned),
+            vec![
+                (
+                    "Apollo: Today was due 2025-03-14",
+                    NotificationKind::Overdue
+                ),
+                ("Apollo: Soon", NotificationKind::DueToday),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_notifications_waits_for_morning() {
+        let conn = setup_test_db();
+        let project = Project::new("Apollo".to_string());
+        ProjectRepository::new(&conn).create(&project).unwrap();
+
+        let tz = chrono_tz::America::New_York;
+        let early = tz.with_ymd_and_hms(2025, 3, 14, 6, 0, 0).unwrap();
+        let due = |day: u32| {
+            tz.with_ymd_and_hms(2025, 3, day, 17, 0, 0)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+        add_milestone(&conn, &project, "Late", due(13));
+        add_milestone(&conn, &project, "Today", due(14));
+
+        let cfg = NotificationSettings {
+            enabled: true,
+            days_before: 0,
+        };
+        let planned = plan_notifications(&conn, early, &cfg, Locale::En, None).unwrap();
+        assert_eq!(
+            kinds(&planned),
+            vec![("Apollo: Late was due 2025-03-13", NotificationKind::Overdue)]
+        );
+
+        let morning = tz.with_ymd_and_hms(2025, 3, 14, 8, 0, 0).unwrap();
+        let planned = plan_notifications(&conn, morning, &cfg, Locale::En, None).unwrap();
+        assert_eq!(planned.len(), 2);
+
+        let disabled = NotificationSettings {
+            enabled: false,
+            ..cfg
+        };
+        assert!(plan_notifications(&conn, morning, &disabled, Locale::En, None)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_plan_notifications_for_user() {
+        let conn = setup_test_db();
+        let repo = ProjectRepository::new(&conn);
+        let watched = Project::new("Apollo".to_string());
+        repo.create(&watched).unwrap();
+        let other = Project::new("Gemini".to_string());
+        repo.create(&other).unwrap();
+        WatcherRepository::new(&conn).watch(&watched.id, "alice@example.com").unwrap();
+
+        let tz = chrono_tz::UTC;
+        let now = tz.with_ymd_and_hms(2025, 3, 14, 9, 0, 0).unwrap();
+        let due = tz.with_ymd_and_hms(2025, 3, 14, 17, 0, 0).unwrap().with_timezone(&Utc);
+        add_milestone(&conn, &watched, "Launch", due);
+        add_milestone(&conn, &other, "Launch", due);
+
+        let cfg = NotificationSettings {
+            enabled: true,
+            days_before: 0,
+        };
+        assert_eq!(plan_notifications(&conn, now, &cfg, Locale::En, None).unwrap().len(), 2);
+        let planned = plan_notifications(&conn, now, &cfg, Locale::En, Some("alice@example.com")).unwrap();
+        assert_eq!(kinds(&planned), vec![("Apollo: Launch", NotificationKind::DueToday)]);
+        assert!(plan_notifications(&conn, now, &cfg, Locale::En, Some("bob@example.com"))
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,639 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! End-of-quarter review of a team
+//!
+//! [`team_quarterly_review`] gathers what a team delivered in a calendar
+//! quarter from the existing tables, and [`TeamReview::to_markdown`] writes
+//! it up in a [`Locale`]. The review covers:
+//!
+//! - projects delivered: the team's projects marked done whose due date falls
+//!   in the quarter, or that were last updated in it if they have none
+//! - the retrospectives of the delivered projects, or a note that one is
+//!   still to be held
+//! - milestones due in the quarter and how many were completed. Milestones
+//!   don't have a status of their own, so one counts as completed when its
+//!   project is done or every item on its checklist is checked
+//! - milestones whose due date was moved later during the quarter, from the
+//!   date history
+//! - headcount: current members, who joined, and members deactivated in the
+//!   quarter, by when their record last changed
+//! - high-severity risks on the team's projects that were open at some point
+//!   in the quarter
+//!
+//! A milestone belongs to its own team, or its project's team if it has none,
+//! as in the quarter plan.
+
+use crate::db::{
+    self, get_datetime, get_opt_datetime, get_uuid, project_repo::quarter_bounds, Error, Retrospective, RetrospectiveRepository,
+    TeamRepository,
+};
+use crate::i18n::Locale;
+use crate::utils::dt_to_db;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use uuid::Uuid;
+
+/// A team's review of a calendar quarter
+#[derive(Debug, Clone, Serialize)]
+pub struct TeamReview {
+    /// Team name
+    pub team: String,
+
+    /// Calendar year
+    pub year: i32,
+
+    /// Quarter, 1 to 4
+    pub quarter: u32,
+
+    /// First moment of the quarter
+    pub start: DateTime<Utc>,
+
+    /// First moment of the next quarter
+    pub end: DateTime<Utc>,
+
+    /// Time zone quarter boundaries and dates are in
+    #[serde(skip)]
+    pub tz: Tz,
+
+    /// Projects the team delivered in the quarter
+    pub projects_delivered: Vec<ReviewProject>,
+
+    /// Milestones due in the quarter, by due date
+    pub milestones_due: Vec<ReviewMilestone>,
+
+    /// Milestones whose due date moved later during the quarter
+    pub milestones_slipped: Vec<SlippedMilestone>,
+
+    /// Active members now, and who joined or left during the quarter
+    pub headcount: Headcount,
+
+    /// High-severity risks open at some point during the quarter
+    pub risks: Vec<ReviewRisk>,
+}
+
+/// A delivered project
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReviewProject {
+    pub id: Uuid,
+    pub name: String,
+    pub due_date: Option<DateTime<Utc>>,
+    /// The project's retrospective, if one has been held
+    pub retrospective: Option<Retrospective>,
+}
+
+/// A milestone due in the quarter
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReviewMilestone {
+    pub id: Uuid,
+    pub project_name: String,
+    pub name: String,
+    pub due_date: DateTime<Utc>,
+    /// Whether its project is done or its checklist is all checked
+    pub completed: bool,
+}
+
+/// A milestone whose due date moved later during the quarter
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SlippedMilestone {
+    pub id: Uuid,
+    pub project_name: String,
+    pub name: String,
+    /// Due date before the first slip in the quarter
+    pub from: DateTime<Utc>,
+    /// Due date after the last slip in the quarter
+    pub to: DateTime<Utc>,
+    /// Days the due date moved later, summed over every slip in the quarter
+    pub slip_days: i64,
+}
+
+/// Team size and changes to it during the quarter
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Headcount {
+    /// Active members now
+    pub members: usize,
+    /// People added to the team during the quarter
+    pub joined: Vec<MemberChange>,
+    /// Members deactivated during the quarter
+    pub left: Vec<MemberChange>,
+}
+
+/// A person who joined or left the team
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MemberChange {
+    pub email: String,
+    pub name: String,
+    pub at: DateTime<Utc>,
+}
+
+/// A notable risk on one of the team's projects
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReviewRisk {
+    pub id: Uuid,
+    pub project_name: String,
+    pub title: String,
+    pub status: String,
+}
+
+/// Team's projects, by their own team field
+const TEAM_PROJECTS: &str = "SELECT id FROM projects WHERE team = ?1";
+
+/// Review what a team did in a calendar quarter
+///
+/// Quarter boundaries are midnight in `tz`. A team with nothing to report
+/// gets an empty review, which renders as a short "no activity" document;
+/// only an unknown team or quarter is an error.
+pub fn team_quarterly_review(conn: &Connection, team: &str, year: i32, quarter: u32, tz: Tz) -> db::Result<TeamReview> {
+    let (start, end) = quarter_bounds(year, quarter, tz)?;
+    if TeamRepository::new(conn).find_by_name(team)?.is_none() {
+        return Err(Error::not_found("Team", team));
+    }
+    let (start_db, end_db) = (dt_to_db(start), dt_to_db(end));
+    let range = params![team, start_db, end_db];
+
+    let mut projects_delivered = conn
+        .prepare_cached(&format!(
+            "SELECT id, name, due_date FROM projects
+             WHERE id IN ({TEAM_PROJECTS}) AND status = 'done'
+               AND COALESCE(due_date, updated_at) >= ?2 AND COALESCE(due_date, updated_at) < ?3
+             ORDER BY COALESCE(due_date, updated_at), name"
+        ))?
+        .query_map(range, |row| {
+            Ok(ReviewProject {
+                id: get_uuid(row, 0)?,
+                name: row.get(1)?,
+                due_date: get_opt_datetime(row, 2)?,
+                retrospective: None,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    let retrospectives = RetrospectiveRepository::new(conn);
+    for project in &mut projects_delivered {
+        project.retrospective = retrospectives.find_by_project(&project.id)?;
+    }
+
+    let milestones_due = conn
+        .prepare_cached(
+            "SELECT m.id, p.name, m.name, m.due_date,
+                    p.status = 'done' OR (
+                        EXISTS (SELECT 1 FROM milestone_checklist_items c WHERE c.milestone_id = m.id)
+                        AND NOT EXISTS (SELECT 1 FROM milestone_checklist_items c WHERE c.milestone_id = m.id AND NOT c.checked)
+                    )
+             FROM milestones m
+             JOIN projects p ON p.id = m.project_id
+             WHERE COALESCE(NULLIF(m.team, ''), NULLIF(p.team, '')) = ?1
+               AND m.due_date >= ?2 AND m.due_date < ?3
+             ORDER BY m.due_date, p.name, m.number",
+        )?
+        .query_map(range, |row| {
+            Ok(ReviewMilestone {
+                id: get_uuid(row, 0)?,
+                project_name: row.get(1)?,
+                name: row.get(2)?,
+                due_date: get_datetime(row, 3)?,
+                completed: row.get(4)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(TeamReview {
+        team: team.to_string(),
+        year,
+        quarter,
+        start,
+        end,
+        tz,
+        projects_delivered,
+        milestones_due,
+        milestones_slipped: slipped_milestones(conn, team, start, end)?,
+        headcount: headcount(conn, team, start, end)?,
+        risks: conn
+            .prepare_cached(&format!(
+                "SELECT r.id, p.name, r.title, r.status FROM project_risks r
+                 JOIN projects p ON p.id = r.project_id
+                 WHERE r.project_id IN ({TEAM_PROJECTS}) AND r.severity = 'high'
+                   AND r.created_at < ?3 AND (r.status <> 'closed' OR r.updated_at >= ?2)
+                 ORDER BY r.created_at, r.title"
+            ))?
+            .query_map(range, |row| {
+                Ok(ReviewRisk {
+                    id: get_uuid(row, 0)?,
+                    project_name: row.get(1)?,
+                    title: row.get(2)?,
+                    status: row.get(3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?,
+    })
+}
+
+/// Milestones whose due date moved later during the quarter, in the order they first slipped
+fn slipped_milestones(
+    conn: &Connection,
+    team: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> db::Result<Vec<SlippedMilestone>> {
+    let changes = conn
+        .prepare_cached(
+            "SELECT m.id, p.name, m.name, h.old_value, h.new_value
+             FROM date_history h
+             JOIN milestones m ON m.id = h.entity_id
+             JOIN projects p ON p.id = m.project_id
+             WHERE h.entity_type = 'milestone' AND h.field = 'due_date'
+               AND COALESCE(NULLIF(m.team, ''), NULLIF(p.team, '')) = ?1
+               AND h.changed_at >= ?2 AND h.changed_at < ?3
+             ORDER BY h.changed_at, h.id",
+        )?
+        .query_map(params![team, dt_to_db(start), dt_to_db(end)], |row| {
+            Ok((
+                get_uuid(row, 0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                get_opt_datetime(row, 3)?,
+                get_opt_datetime(row, 4)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut slipped: Vec<SlippedMilestone> = Vec::new();
+    let mut index: HashMap<Uuid, usize> = HashMap::new();
+    for (id, project_name, name, old_value, new_value) in changes {
+        let (Some(old_value), Some(new_value)) = (old_value, new_value) else {
+            continue;
+        };
+        if new_value <= old_value {
+            continue;
+        }
+        let slip = *index.entry(id).or_insert_with(|| {
+            slipped.push(SlippedMilestone {
+                id,
+                project_name,
+                name,
+                from: old_value,
+                to: new_value,
+                slip_days: 0,
+            });
+            slipped.len() - 1
+        });
+        slipped[slip].to = new_value;
+        slipped[slip].slip_days += (new_value - old_value).num_days();
+    }
+    Ok(slipped)
+}
+
+fn headcount(conn: &Connection, team: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> db::Result<Headcount> {
+    let members = conn
+        .prepare_cached(
+            "SELECT COUNT(*) FROM team_members tm JOIN people p ON p.email = tm.person_email
+             WHERE tm.team_name = ?1 AND p.active",
+        )?
+        .query_row(params![team], |row| row.get(0))?;
+    let changes = |sql: &str| -> db::Result<Vec<MemberChange>> {
+        let changes = conn
+            .prepare_cached(sql)?
+            .query_map(params![team, dt_to_db(start), dt_to_db(end)], |row| {
+                Ok(MemberChange {
+                    email: row.get(0)?,
+                    name: row.get(1)?,
+                    at: get_datetime(row, 2)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(changes)
+    };
+
+    Ok(Headcount {
+        members,
+        joined: changes(
+            "SELECT p.email, p.name, tm.created_at FROM team_members tm JOIN people p ON p.email = tm.person_email
+             WHERE tm.team_name = ?1 AND tm.created_at >= ?2 AND tm.created_at < ?3
+             ORDER BY tm.created_at, p.name",
+        )?,
+        left: changes(
+            "SELECT p.email, p.name, p.updated_at FROM team_members tm JOIN people p ON p.email = tm.person_email
+             WHERE tm.team_name = ?1 AND NOT p.active AND p.updated_at >= ?2 AND p.updated_at < ?3
+             ORDER BY p.updated_at, p.name",
+        )?,
+    })
+}
+
+impl TeamReview {
+    /// Whether nothing happened in the team during the quarter
+    pub fn is_empty(&self) -> bool {
+        self.projects_delivered.is_empty()
+            && self.milestones_due.is_empty()
+            && self.milestones_slipped.is_empty()
+            && self.headcount.joined.is_empty()
+            && self.headcount.left.is_empty()
+            && self.risks.is_empty()
+    }
+
+    /// Number of milestones due in the quarter that were completed
+    pub fn milestones_completed(&self) -> usize {
+        self.milestones_due.iter().filter(|m| m.completed).count()
+    }
+
+    /// Title, such as "Platform: Q2 2025 review"
+    pub fn title(&self, locale: Locale) -> String {
+        locale.format(
+            "review.title",
+            &[("team", &self.team), ("quarter", &self.quarter), ("year", &self.year)],
+        )
+    }
+
+    /// Render the review as markdown
+    pub fn to_markdown(&self, locale: Locale) -> String {
+        let last_day = self.end.with_timezone(&self.tz).date_naive().pred_opt().unwrap_or_default();
+        let mut out = format!("# {}\n\n", self.title(locale));
+        let _ = writeln!(
+            out,
+            "{}",
+            locale.format(
+                "review.period",
+                &[("start", &self.date(self.start, locale)), ("end", &locale.naive_date(last_day))],
+            )
+        );
+        if self.is_empty() {
+            let _ = write!(out, "\n{}\n", locale.text("review.no_activity"));
+            return out;
+        }
+
+        let date = |dt| self.date(dt, locale);
+        section(
+            &mut out,
+            &format!("{} ({})", locale.text("review.projects_delivered"), self.projects_delivered.len()),
+            self.projects_delivered.iter().map(|p| match p.due_date {
+                Some(due) => locale.format("review.delivered_item", &[("name", &p.name), ("date", &date(due))]),
+                None => p.name.clone(),
+            }),
+            locale,
+        );
+
+        section(
+            &mut out,
+            locale.text("review.retrospectives"),
+            self.projects_delivered.iter().map(|p| match &p.retrospective {
+                Some(retrospective) => {
+                    let mut item = p.name.clone();
+                    let parts = [
+                        ("review.went_well", &retrospective.went_well),
+                        ("review.needs_improvement", &retrospective.needs_improvement),
+                        ("review.actions", &retrospective.action_summary),
+                    ];
+                    for (key, text) in parts {
+                        if let Some(text) = text {
+                            let _ = write!(item, "\n  - {}", locale.format(key, &[("text", &one_line(text))]));
+                        }
+                    }
+                    item
+                }
+                None => format!("{} ({})", p.name, locale.text("review.no_retrospective")),
+            }),
+            locale,
+        );
+
+        let summary = locale.format(
+            "review.milestones_summary",
+            &[("due", &self.milestones_due.len()), ("completed", &self.milestones_completed())],
+        );
+        section(
+            &mut out,
+            &format!("{} ({})", locale.text("review.milestones"), summary),
+            self.milestones_due.iter().map(|m| {
+                let item = format!("{} {}: {}", date(m.due_date), m.project_name, m.name);
+                if m.completed {
+                    format!("{} ({})", item, locale.text("review.completed"))
+                } else {
+                    item
+                }
+            }),
+            locale,
+        );
+
+        section(
+            &mut out,
+            &format!("{} ({})", locale.text("review.slipped"), self.milestones_slipped.len()),
+            self.milestones_slipped.iter().map(|m| {
+                locale.format(
+                    "review.slipped_item",
+                    &[
+                        ("project", &m.project_name),
+                        ("name", &m.name),
+                        ("from", &date(m.from)),
+                        ("to", &date(m.to)),
+                        ("days", &m.slip_days),
+                    ],
+                )
+            }),
+            locale,
+        );
+
+        let member = |key, change: &MemberChange| {
+            locale.format(key, &[("name", &change.name), ("date", &date(change.at))])
+        };
+        section(
+            &mut out,
+            locale.text("review.headcount"),
+            std::iter::once(locale.plural("review.members", self.headcount.members, &[]))
+                .chain(self.headcount.joined.iter().map(|c| member("review.joined", c)))
+                .chain(self.headcount.left.iter().map(|c| member("review.left", c))),
+            locale,
+        );
+
+        section(
+            &mut out,
+            &format!("{} ({})", locale.text("review.risks"), self.risks.len()),
+            self.risks
+                .iter()
+                .map(|r| {
+                    let status = locale.text(&format!("review.risk.{}", r.status));
+                    format!("{}: {} ({})", r.project_name, r.title, status)
+                }),
+            locale,
+        );
+        out
+    }
+
+    fn date(&self, dt: DateTime<Utc>, locale: Locale) -> String {
+        locale.date(dt, self.tz)
+    }
+}
+
+/// Join the lines of free text into one, dropping list markers and blank lines
+fn one_line(text: &str) -> String {
+    text.lines()
+        .map(|line| line.trim().trim_start_matches(['-', '*']).trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Write a markdown section with one bullet per item, or a note that there is none
+fn section(out: &mut String, title: &str, items: impl Iterator<Item = String>, locale: Locale) {
+    let _ = write!(out, "\n## {}\n\n", title);
+    let mut empty = true;
+    for item in items {
+        let _ = writeln!(out, "- {}", item);
+        empty = false;
+    }
+    if empty {
+        let _ = writeln!(out, "{}", locale.text("review.none"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{
+        ChecklistRepository, Milestone, Person, PersonRepository, Project, ProjectRepository, ProjectRisk,
+        ProjectStatus, RiskLevel, RiskStatus, Team,
+    };
+    use chrono::TimeZone;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn at(month: u32, day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, month, day, 12, 0, 0).unwrap()
+    }
+
+    /// Platform's Q2 2025: Apollo ships, Gemini's GA slips, one person joins and one leaves
+    fn fixture_db() -> Connection {
+        let conn = setup_test_db();
+        let people = PersonRepository::new(&conn);
+        let teams = TeamRepository::new(&conn);
+        let projects = ProjectRepository::new(&conn);
+        teams.create(&Team::new("Platform".to_string())).unwrap();
+        teams.create(&Team::new("Mobile".to_string())).unwrap();
+        for (email, name) in [("alice@example.com", "Alice"), ("bob@example.com", "Bob"), ("carol@example.com", "Carol")] {
+            people.create(&Person::new(email.to_string(), name.to_string())).unwrap();
+            teams.add_member("Platform", email).unwrap();
+        }
+        people.deactivate("bob@example.com", false).unwrap();
+        for (sql, value) in [
+            ("UPDATE team_members SET created_at = ?1 WHERE person_email = 'alice@example.com'", at(4, 10)),
+            ("UPDATE team_members SET created_at = ?1 WHERE person_email <> 'alice@example.com'", at(1, 6)),
+            ("UPDATE people SET updated_at = ?1 WHERE email = 'bob@example.com'", at(5, 2)),
+        ] {
+            conn.execute(sql, params![dt_to_db(value)]).unwrap();
+        }
+
+        let mut apollo = Project::new("Apollo".to_string());
+        apollo.team = Some("Platform".to_string());
+        apollo.status = ProjectStatus::Done;
+        apollo.due_date = Some(at(5, 30));
+        projects.create(&apollo).unwrap();
+        let mut launch = Milestone::new(apollo.id, 1, "Launch".to_string());
+        launch.due_date = Some(at(5, 30));
+        projects.add_milestone(&launch).unwrap();
+
+        let mut gemini = Project::new("Gemini".to_string());
+        gemini.team = Some("Platform".to_string());
+        projects.create(&gemini).unwrap();
+        let mut beta = Milestone::new(gemini.id, 1, "Beta".to_string());
+        beta.due_date = Some(at(4, 20));
+        projects.add_milestone(&beta).unwrap();
+        let item = ChecklistRepository::new(&conn).add_item(&beta.id, "Security review").unwrap();
+        ChecklistRepository::new(&conn).toggle_item(&item.id, Some(true)).unwrap();
+        let mut ga = Milestone::new(gemini.id, 2, "GA".to_string());
+        ga.due_date = Some(at(6, 15));
+        projects.add_milestone(&ga).unwrap();
+        conn.execute(
+            "INSERT INTO date_history (entity_type, entity_id, field, old_value, new_value, changed_at)
+             VALUES ('milestone', ?1, 'due_date', ?2, ?3, ?4)",
+            params![ga.id.to_string(), dt_to_db(at(5, 15)), dt_to_db(at(6, 15)), dt_to_db(at(4, 25))],
+        )
+        .unwrap();
+
+        // Another team's milestone, and risks that aren't notable this quarter
+        let mut mercury = Project::new("Mercury".to_string());
+        mercury.team = Some("Mobile".to_string());
+        projects.create(&mercury).unwrap();
+        let mut app = Milestone::new(mercury.id, 1, "App".to_string());
+        app.due_date = Some(at(5, 1));
+        projects.add_milestone(&app).unwrap();
+
+        let mut vendor = ProjectRisk::new(gemini.id, "Vendor delay".to_string(), RiskLevel::High, RiskLevel::Medium);
+        vendor.created_at = at(3, 1);
+        vendor.updated_at = at(3, 1);
+        projects.add_project_risk(&vendor).unwrap();
+        let mut minor = ProjectRisk::new(gemini.id, "Typos".to_string(), RiskLevel::Low, RiskLevel::High);
+        minor.created_at = at(4, 1);
+        projects.add_project_risk(&minor).unwrap();
+        let mut old = ProjectRisk::new(apollo.id, "Old outage".to_string(), RiskLevel::High, RiskLevel::High);
+        old.status = RiskStatus::Closed;
+        old.created_at = at(1, 10);
+        old.updated_at = at(2, 10);
+        projects.add_project_risk(&old).unwrap();
+        conn.execute("UPDATE project_risks SET updated_at = ?1 WHERE title = 'Old outage'", params![dt_to_db(at(2, 10))])
+            .unwrap();
+
+        let mut retrospective = Retrospective::new(apollo.id, at(6, 5));
+        retrospective.went_well = Some("Launch checklist".to_string());
+        retrospective.action_summary = Some("- Start load tests earlier\n- Book the war room\n".to_string());
+        RetrospectiveRepository::new(&conn).save(&retrospective).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_team_quarterly_review() {
+        let conn = fixture_db();
+        let review = team_quarterly_review(&conn, "Platform", 2025, 2, Tz::UTC).unwrap();
+
+        assert_eq!(review.projects_delivered.len(), 1);
+        assert_eq!(review.projects_delivered[0].name, "Apollo");
+        let retrospective = review.projects_delivered[0].retrospective.as_ref().unwrap();
+        assert_eq!(retrospective.went_well.as_deref(), Some("Launch checklist"));
+        let milestones: Vec<_> = review.milestones_due.iter().map(|m| (m.name.as_str(), m.completed)).collect();
+        assert_eq!(milestones, [("Beta", true), ("Launch", true), ("GA", false)]);
+        assert_eq!(review.milestones_completed(), 2);
+        assert_eq!(review.milestones_slipped.len(), 1);
+        assert_eq!(review.milestones_slipped[0].slip_days, 31);
+        assert_eq!(review.headcount.members, 2);
+        assert_eq!(review.headcount.joined[0].name, "Alice");
+        assert_eq!(review.headcount.left[0].name, "Bob");
+        assert_eq!(review.risks.len(), 1);
+        assert_eq!(review.risks[0].title, "Vendor delay");
+
+        assert!(matches!(
+            team_quarterly_review(&conn, "Nobody", 2025, 2, Tz::UTC),
+            Err(Error::NotFound { .. })
+        ));
+        assert!(matches!(team_quarterly_review(&conn, "Platform", 2025, 5, Tz::UTC), Err(Error::Invalid(_))));
+    }
+
+    #[test]
+    fn test_no_activity() {
+        let conn = fixture_db();
+        let review = team_quarterly_review(&conn, "Platform", 2024, 3, Tz::UTC).unwrap();
+        assert!(review.is_empty());
+        assert_eq!(
+            review.to_markdown(Locale::En),
+            "# Platform: Q3 2024 review\n\n2024-07-01 to 2024-09-30\n\nNo activity this quarter.\n"
+        );
+    }
+
+    #[test]
+    fn test_render_golden() {
+        let conn = fixture_db();
+        let review = team_quarterly_review(&conn, "Platform", 2025, 2, Tz::UTC).unwrap();
+        assert_eq!(
+            review.to_markdown(Locale::En),
+            include_str!("../../tests/fixtures/team_review.en.md")
+        );
+        assert_eq!(
+            review.to_markdown(Locale::De),
+            include_str!("../../tests/fixtures/team_review.de.md")
+        );
+    }
+}
@@ -0,0 +1,321 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Passwords and keys kept out of the config file
+//!
+//! A [`SecretStore`] maps names such as `smtp.password` to values. It keeps
+//! them in the operating system's keychain, or, on machines without one, in
+//! a file encrypted with ChaCha20-Poly1305 under a key derived with Argon2id
+//! from a passphrase read from the environment. The `[secrets]` config
+//! section picks the store.
+//!
+//! The keychain holds every secret in a single entry, so the store can list
+//! the names it has without a keychain-specific search.
+
+use crate::config::{Config, SecretsBackend};
+use anyhow::{bail, Context};
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Keychain service the secrets are stored under
+pub const KEYRING_SERVICE: &str = "project-tracker";
+
+/// Keychain user name of the entry holding every secret
+const KEYRING_USER: &str = "secrets";
+
+/// First bytes of an encrypted secrets file, with the format version
+const FILE_MAGIC: &[u8; 8] = b"PTSECRT1";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Named secrets in the keychain or an encrypted file
+pub struct SecretStore {
+    backend: Backend,
+}
+
+enum Backend {
+    Keyring(keyring::Entry),
+    File {
+        path: PathBuf,
+        passphrase: Option<String>,
+        passphrase_env: String,
+    },
+}
+
+impl SecretStore {
+    /// Open the store chosen by the `[secrets]` config section
+    pub fn open(config: &Config) -> crate::Result<Self> {
+        match config.secrets.backend {
+            SecretsBackend::Keyring => Self::keyring(),
+            SecretsBackend::File => {
+                let env = &config.secrets.passphrase_env;
+                Ok(Self::file(
+                    config.secrets_file_path()?,
+                    std::env::var(env).ok(),
+                    env,
+                ))
+            }
+        }
+    }
+
+    /// Store secrets in the operating system's keychain
+    pub fn keyring() -> crate::Result<Self> {
+        let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)
+            .context("Failed to open the keychain")?;
+        Ok(Self { backend: Backend::Keyring(entry) })
+    }
+
+    /// Store secrets in a file encrypted with `passphrase`
+    ///
+    /// `passphrase_env` names where the passphrase should have come from, for
+    /// the error when it is missing.
+    pub fn file(path: PathBuf, passphrase: Option<String>, passphrase_env: &str) -> Self {
+        Self {
+            backend: Backend::File {
+                path,
+                passphrase,
+                passphrase_env: passphrase_env.to_string(),
+            },
+        }
+    }
+
+    /// Where the secrets are kept, for display
+    pub fn location(&self) -> String {
+        match &self.backend {
+            Backend::Keyring(_) => format!("keychain (service \"{}\")", KEYRING_SERVICE),
+            Backend::File { path, .. } => format!("encrypted file {}", path.display()),
+        }
+    }
+
+    /// Look up a secret
+    pub fn get(&self, name: &str) -> crate::Result<Option<String>> {
+        Ok(self.load()?.remove(name))
+    }
+
+    /// Save a secret, replacing any value it had
+    pub fn set(&self, name: &str, value: &str) -> crate::Result<()> {
+        let mut secrets = self.load()?;
+        secrets.insert(name.to_string(), value.to_string());
+        self.save(&secrets)
+    }
+
+    /// Remove a secret; returns whether there was one
+    pub fn delete(&self, name: &str) -> crate::Result<bool> {
+        let mut secrets = self.load()?;
+        if secrets.remove(name).is_none() {
+            return Ok(false);
+        }
+        self.save(&secrets)?;
+        Ok(true)
+    }
+
+    /// Names of the stored secrets, in order
+    pub fn names(&self) -> crate::Result<Vec<String>> {
+        Ok(self.load()?.into_keys().collect())
+    }
+
+    fn load(&self) -> crate::Result<BTreeMap<String, String>> {
+        let json = match &self.backend {
+            Backend::Keyring(entry) => match entry.get_password() {
+                Ok(json) => json,
+                Err(keyring::Error::NoEntry) => return Ok(BTreeMap::new()),
+                Err(e) => return Err(e).context("Failed to read from the keychain"),
+            },
+            Backend::File { path, .. } if !path.exists() => return Ok(BTreeMap::new()),
+            Backend::File { path, .. } => {
+                let data = fs::read(path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                let plain = decrypt(self.passphrase()?, &data)
+                    .with_context(|| format!("Failed to decrypt {}", path.display()))?;
+                String::from_utf8(plain).context("Secrets file is not UTF-8")?
+            }
+        };
+        serde_json::from_str(&json).context("Stored secrets are not a JSON object")
+    }
+
+    fn save(&self, secrets: &BTreeMap<String, String>) -> crate::Result<()> {
+        let json = serde_json::to_string(secrets)?;
+        match &self.backend {
+            Backend::Keyring(entry) => entry
+                .set_password(&json)
+                .context("Failed to write to the keychain"),
+            Backend::File { path, .. } => {
+                let data = encrypt(self.passphrase()?, json.as_bytes())?;
+                write_private(path, &data)
+            }
+        }
+    }
+
+    fn passphrase(&self) -> crate::Result<&str> {
+        match &self.backend {
+            Backend::File { passphrase: Some(passphrase), .. } if !passphrase.is_empty() => {
+                Ok(passphrase)
+            }
+            Backend::File { passphrase_env, .. } => bail!(
+                "Set {} to the passphrase of the secrets file",
+                passphrase_env
+            ),
+            Backend::Keyring(_) => unreachable!("the keychain has no passphrase"),
+        }
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> crate::Result<Key> {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow::anyhow!("Failed to derive the secrets key: {}", e))?;
+    Ok(key)
+}
+
+/// Encrypt `plain` as magic, salt, nonce and ciphertext
+fn encrypt(passphrase: &str, plain: &[u8]) -> crate::Result<Vec<u8>> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plain)
+        .map_err(|_| anyhow::anyhow!("Failed to encrypt the secrets"))?;
+
+    let mut data = Vec::with_capacity(FILE_MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    data.extend_from_slice(FILE_MAGIC);
+    data.extend_from_slice(&salt);
+    data.extend_from_slice(&nonce);
+    data.extend_from_slice(&ciphertext);
+    Ok(data)
+}
+
+fn decrypt(passphrase: &str, data: &[u8]) -> crate::Result<Vec<u8>> {
+    let rest = data
+        .strip_prefix(FILE_MAGIC.as_slice())
+        .context("Not a Project Tracker secrets file")?;
+    if rest.len() < SALT_LEN + NONCE_LEN {
+        bail!("Secrets file is truncated");
+    }
+    let (salt, rest) = rest.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, salt)?);
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow::anyhow!("Wrong passphrase, or the file was changed"))
+}
+
+/// Replace `path` with `data`, readable only by the owner
+fn write_private(path: &Path, data: &[u8]) -> crate::Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+
+    // Write beside the file and rename, so a failed write keeps the old secrets
+    let temp = path.with_extension("tmp");
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(&temp)
+        .with_context(|| format!("Failed to create {}", temp.display()))?;
+    file.write_all(data)?;
+    file.sync_all()?;
+    fs::rename(&temp, path)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_store(dir: &Path, passphrase: &str) -> SecretStore {
+        SecretStore::file(
+            dir.join("secrets.enc"),
+            Some(passphrase.to_string()),
+            "TEST_PASSPHRASE",
+        )
+    }
+
+    #[test]
+    fn test_file_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = file_store(dir.path(), "correct horse");
+
+        assert_eq!(store.get("smtp.password").unwrap(), None);
+        store.set("smtp.password", "hunter2").unwrap();
+        store.set("api.key", "sk-123").unwrap();
+
+        let reopened = file_store(dir.path(), "correct horse");
+        assert_eq!(reopened.get("smtp.password").unwrap().as_deref(), Some("hunter2"));
+        assert_eq!(reopened.names().unwrap(), vec!["api.key", "smtp.password"]);
+
+        assert!(reopened.delete("api.key").unwrap());
+        assert!(!reopened.delete("api.key").unwrap());
+        assert_eq!(store.names().unwrap(), vec!["smtp.password"]);
+    }
+
+    #[test]
+    fn test_file_is_encrypted() {
+        let dir = tempfile::tempdir().unwrap();
+        file_store(dir.path(), "correct horse")
+            .set("smtp.password", "hunter2")
+            .unwrap();
+
+        let data = fs::read(dir.path().join("secrets.enc")).unwrap();
+        assert!(data.starts_with(FILE_MAGIC));
+        assert!(!data.windows(7).any(|w| w == b"hunter2"));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(dir.path().join("secrets.enc")).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_file_wrong_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        file_store(dir.path(), "correct horse")
+            .set("smtp.password", "hunter2")
+            .unwrap();
+
+        let err = file_store(dir.path(), "battery staple").get("smtp.password").unwrap_err();
+        assert!(format!("{:#}", err).contains("Wrong passphrase"));
+    }
+
+    #[test]
+    fn test_file_needs_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SecretStore::file(dir.path().join("secrets.enc"), None, "TEST_PASSPHRASE");
+
+        let err = store.set("smtp.password", "hunter2").unwrap_err();
+        assert!(err.to_string().contains("TEST_PASSPHRASE"));
+    }
+
+    #[test]
+    fn test_keyring_round_trip() {
+        keyring::set_default_credential_builder(keyring::mock::default_credential_builder());
+        let store = SecretStore::keyring().unwrap();
+
+        assert!(store.names().unwrap().is_empty());
+        store.set("smtp.password", "hunter2").unwrap();
+        store.set("api.key", "sk-123").unwrap();
+        assert_eq!(store.get("smtp.password").unwrap().as_deref(), Some("hunter2"));
+        assert_eq!(store.names().unwrap(), vec!["api.key", "smtp.password"]);
+
+        assert!(store.delete("smtp.password").unwrap());
+        assert_eq!(store.get("smtp.password").unwrap(), None);
+    }
+}
@@ -0,0 +1,180 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Permission checks for shared databases
+//!
+//! Every Tauri command and MCP tool is checked by [`authorize`] before it
+//! runs, using the role the configured current_user_email has in the `users`
+//! table (see [`UserRepository::effective_role`]). [`RULES`] decides which
+//! [`Operation`] a command is, and each operation needs a minimum role:
+//! viewers can read, editors can also change records, and only admins can
+//! delete projects or manage users.
+
+use crate::db::{self, UserRepository, UserRole};
+use rusqlite::Connection;
+
+/// What a command does, which decides the role it needs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    /// Reads records, or only affects this computer, such as its configuration
+    Read,
+    /// Adds, changes or removes records
+    Write,
+    /// Deletes a project and everything in it
+    DeleteProject,
+    /// Changes who can use the database
+    ManageUsers,
+}
+
+impl Operation {
+    /// The least role allowed to perform the operation
+    pub fn required_role(&self) -> UserRole {
+        match self {
+            Operation::Read => UserRole::Viewer,
+            Operation::Write => UserRole::Editor,
+            Operation::DeleteProject | Operation::ManageUsers => UserRole::Admin,
+        }
+    }
+}
+
+/// Which operation each command or tool is, by name
+///
+/// The first matching rule wins; a name ending in `*` matches every command
+/// starting with the rest. Commands no rule matches are writes, so a new
+/// command can't be run by viewers until it is listed here.
+pub const RULES: &[(&str, Operation)] = &[
+    ("delete_project", Operation::DeleteProject),
+    ("set_user_role", Operation::ManageUsers),
+    ("remove_user", Operation::ManageUsers),
+    // Commands that only affect this computer
+    ("db_ping", Operation::Read),
+    ("reload_config", Operation::Read),
+    ("switch_workspace", Operation::Read),
+    ("start_mcp_server", Operation::Read),
+    ("stop_mcp_server", Operation::Read),
+    ("save_attachment_to", Operation::Read),
+    ("export_workbook", Operation::Read),
+    ("set_notification_settings", Operation::Read),
+    ("run_query", Operation::Read),
+    ("get_*", Operation::Read),
+    ("list_*", Operation::Read),
+    ("search_*", Operation::Read),
+    ("find_*", Operation::Read),
+    ("suggest_*", Operation::Read),
+    ("autocomplete_*", Operation::Read),
+    ("compare_*", Operation::Read),
+    ("generate_*", Operation::Read),
+    ("preview_*", Operation::Read),
+    ("my_*", Operation::Read),
+];
+
+/// The operation a command or tool is, from [`RULES`]
+pub fn operation(command: &str) -> Operation {
+    RULES
+        .iter()
+        .find(|(pattern, _)| match pattern.strip_suffix('*') {
+            Some(prefix) => command.starts_with(prefix),
+            None => command == *pattern,
+        })
+        .map_or(Operation::Write, |(_, operation)| *operation)
+}
+
+/// Check that `role` may run `command`
+///
+/// `user` is only used to describe who was refused.
+pub fn authorize(role: UserRole, user: Option<&str>, command: &str) -> db::Result<()> {
+    let required = operation(command).required_role();
+    if role < required {
+        log::warn!(
+            "Refused {} for {} with the {} role",
+            command,
+            user.unwrap_or("a user without current_user_email"),
+            role
+        );
+        return Err(db::Error::PermissionDenied {
+            operation: command.to_string(),
+            user: user.map(str::to_string),
+            role,
+            required,
+        });
+    }
+    Ok(())
+}
+
+/// Check that the user with `email` may run `command` against `conn`
+pub fn authorize_user(conn: &Connection, email: Option<&str>, command: &str) -> db::Result<()> {
+    let role = UserRepository::new(conn).effective_role(email)?;
+    authorize(role, email, command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Representative commands and the least role that may run them
+    const MATRIX: &[(&str, UserRole)] = &[
+        ("list_projects", UserRole::Viewer),
+        ("get_project", UserRole::Viewer),
+        ("search_people", UserRole::Viewer),
+        ("run_query", UserRole::Viewer),
+        ("generate_diagram", UserRole::Viewer),
+        ("list_users", UserRole::Viewer),
+        ("switch_workspace", UserRole::Viewer),
+        ("export_workbook", UserRole::Viewer),
+        ("create_project", UserRole::Editor),
+        ("update_milestone", UserRole::Editor),
+        ("append_to_note", UserRole::Editor),
+        ("delete_person", UserRole::Editor),
+        ("log_one_on_one", UserRole::Editor),
+        ("seed_demo_data", UserRole::Editor),
+        ("delete_project", UserRole::Admin),
+        ("set_user_role", UserRole::Admin),
+        ("remove_user", UserRole::Admin),
+    ];
+
+    #[test]
+    fn test_role_matrix() {
+        for role in [UserRole::Viewer, UserRole::Editor, UserRole::Admin] {
+            for (command, required) in MATRIX {
+                let result = authorize(role, Some("kim@example.com"), command);
+                assert_eq!(result.is_ok(), role >= *required, "{} running {}", role, command);
+                if let Err(e) = result {
+                    assert!(matches!(
+                        e,
+                        db::Error::PermissionDenied { role: r, required: q, .. } if r == role && q == *required
+                    ));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_unlisted_commands_are_writes() {
+        assert_eq!(operation("frobnicate"), Operation::Write);
+        // Prefixes only match the start of the name
+        assert_eq!(operation("forget_project"), Operation::Write);
+        assert_eq!(operation("delete_projects"), Operation::Write);
+    }
+
+    #[test]
+    fn test_authorize_user() {
+        let conn = Connection::open_in_memory().unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+
+        // Everyone is an admin until users are added
+        authorize_user(&conn, None, "delete_project").unwrap();
+
+        let users = UserRepository::new(&conn);
+        users.set_role(None, "ada@example.com", UserRole::Admin).unwrap();
+        users.set_role(Some("ada@example.com"), "lee@example.com", UserRole::Editor).unwrap();
+
+        authorize_user(&conn, Some("ada@example.com"), "delete_project").unwrap();
+        authorize_user(&conn, Some("LEE@example.com"), "create_project").unwrap();
+        assert!(authorize_user(&conn, Some("lee@example.com"), "delete_project").is_err());
+        assert!(authorize_user(&conn, Some("kim@example.com"), "create_project").is_err());
+        authorize_user(&conn, None, "list_projects").unwrap();
+        assert!(authorize_user(&conn, None, "create_project").is_err());
+    }
+}
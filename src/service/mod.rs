@@ -0,0 +1,487 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Service layer for mutations that notify external integrations
+//!
+//! The Tauri commands and MCP tools make project and milestone changes
+//! through [`ProjectService`], which writes through the repository and then
+//! emits the matching webhook events. New projects can also be set up with
+//! the milestones, notes and stakeholders configured for their type, and
+//! watched by the person who created them.
+//!
+//! [`auth`] decides which commands and tools the current user's role allows.
+
+pub mod auth;
+
+use crate::config::{BootstrapRule, BootstrapStakeholder};
+use crate::db::{self, Milestone, NoteKind, Project, ProjectNote, ProjectRepository, ProjectStakeholder, WatcherRepository};
+use crate::webhook::{self, WebhookDispatcher, WebhookEvent};
+use rusqlite::Connection;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Project and milestone mutations that emit webhook events
+pub struct ProjectService<'a> {
+    conn: &'a Connection,
+    repo: ProjectRepository<'a>,
+    webhooks: &'a WebhookDispatcher,
+    bootstrap: Option<&'a BTreeMap<String, BootstrapRule>>,
+    creator: Option<&'a str>,
+}
+
+impl<'a> ProjectService<'a> {
+    pub fn new(conn: &'a Connection, webhooks: &'a WebhookDispatcher) -> Self {
+        Self {
+            conn,
+            repo: ProjectRepository::new(conn),
+            webhooks,
+            bootstrap: None,
+            creator: None,
+        }
+    }
+
+    /// Only accept these project types, see [`ProjectRepository::with_project_types`]
+    pub fn with_project_types(mut self, project_types: &'a [String]) -> Self {
+        self.repo = self.repo.with_project_types(project_types);
+        self
+    }
+
+    /// Only accept these phases, see [`ProjectRepository::with_phases`]
+    pub fn with_phases(mut self, phases: &'a [String]) -> Self {
+        self.repo = self.repo.with_phases(phases);
+        self
+    }
+
+    /// Set up new projects with the rule configured for their type
+    ///
+    /// Types without a rule are created as they are, as is every project
+    /// when this isn't called.
+    pub fn with_bootstrap(mut self, rules: &'a BTreeMap<String, BootstrapRule>) -> Self {
+        self.bootstrap = Some(rules);
+        self
+    }
+
+    /// Have new projects watched by `email`, usually the configured
+    /// `current_user_email`; nobody watches them when it is `None`
+    pub fn with_creator(mut self, email: Option<&'a str>) -> Self {
+        self.creator = email;
+        self
+    }
+
+    /// Create a project, and set it up if there is a bootstrap rule for its type
+    ///
+    /// The project, everything the rule adds and the creator's watch are
+    /// written in one transaction, so if any of it fails nothing is created.
+    pub fn create_project(&self, project: &Project) -> db::Result<()> {
+        let rule = self.bootstrap.and_then(|rules| rules.get(&project.project_type));
+        let tx = db::begin_or_join(self.conn)?;
+        self.repo.create(project)?;
+        if let Some(email) = self.creator {
+            WatcherRepository::new(self.conn).watch(&project.id, email)?;
+        }
+        let milestones = match rule {
+            Some(rule) => self.bootstrap_project(project, rule)?,
+            None => Vec::new(),
+        };
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+
+        self.webhooks.emit(WebhookEvent::new(
+            webhook::PROJECT_CREATED,
+            "project",
+            project.id,
+            project,
+        ));
+        for milestone in &milestones {
+            self.webhooks.emit(WebhookEvent::new(
+                webhook::MILESTONE_CREATED,
+                "milestone",
+                milestone.id,
+                milestone,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Add a bootstrap rule's milestones, notes and stakeholders to a new project
+    ///
+    /// Stakeholders are only added for the people the project names, and
+    /// each person only once. Returns the milestones that were added.
+    fn bootstrap_project(&self, project: &Project, rule: &BootstrapRule) -> db::Result<Vec<Milestone>> {
+        let mut milestones = Vec::new();
+        for (number, name) in (1..).zip(&rule.milestones) {
+            let milestone = Milestone::new(project.id, number, name.clone());
+            self.repo.add_milestone(&milestone)?;
+            milestones.push(milestone);
+        }
+        for title in &rule.notes {
+            self.repo
+                .add_project_note(&ProjectNote::new(project.id, title.clone(), String::new()))?;
+        }
+        for title in &rule.log_notes {
+            let mut note = ProjectNote::new(project.id, title.clone(), String::new());
+            note.kind = NoteKind::Log;
+            self.repo.add_project_note(&note)?;
+        }
+
+        let mut added: Vec<&str> = Vec::new();
+        for &field in &rule.stakeholders {
+            let email = match field {
+                BootstrapStakeholder::Manager => &project.manager,
+                BootstrapStakeholder::RequirementsOwner => &project.requirements_owner,
+                BootstrapStakeholder::TechnicalLead => &project.technical_lead,
+            };
+            let Some(email) = email.as_deref().filter(|email| !added.contains(email)) else {
+                continue;
+            };
+            let mut stakeholder = ProjectStakeholder::new(project.id, email.to_string());
+            stakeholder.role = Some(field.role().to_string());
+            self.repo.add_stakeholder(&project.id, &stakeholder)?;
+            added.push(email);
+        }
+
+        log::debug!("Bootstrapped project {} as {}", project.id, project.project_type);
+        Ok(milestones)
+    }
+
+    /// Update a project
+    pub fn update_project(&self, project: &Project) -> db::Result<()> {
+        self.repo.update(project)?;
+        self.webhooks.emit(WebhookEvent::new(
+            webhook::PROJECT_UPDATED,
+            "project",
+            project.id,
+            project,
+        ));
+        Ok(())
+    }
+
+    /// Mark a project as blocked, see [`ProjectRepository::set_blocked`]
+    pub fn block_project(&self, id: &Uuid, reason: &str) -> db::Result<Project> {
+        let project = self.repo.set_blocked(id, reason)?;
+        self.webhooks.emit(WebhookEvent::new(
+            webhook::PROJECT_UPDATED,
+            "project",
+            project.id,
+            &project,
+        ));
+        Ok(project)
+    }
+
+    /// Clear a project's blocker
+    pub fn unblock_project(&self, id: &Uuid) -> db::Result<Project> {
+        let project = self.repo.clear_blocked(id)?;
+        self.webhooks.emit(WebhookEvent::new(
+            webhook::PROJECT_UPDATED,
+            "project",
+            project.id,
+            &project,
+        ));
+        Ok(project)
+    }
+
+    /// Move a project to a phase, see [`ProjectRepository::set_phase`]
+    pub fn set_project_phase(&self, id: &Uuid, phase: &str) -> db::Result<Project> {
+        let project = self.repo.set_phase(id, phase)?;
+        self.webhooks.emit(WebhookEvent::new(
+            webhook::PROJECT_UPDATED,
+            "project",
+            project.id,
+            &project,
+        ));
+        Ok(project)
+    }
+
+    /// Change a project's slug, see [`ProjectRepository::set_slug`]
+    pub fn set_slug(&self, id: &Uuid, slug: Option<&str>) -> db::Result<String> {
+        let slug = self.repo.set_slug(id, slug)?;
+        let project = self
+            .repo
+            .find_by_id(id)?
+            .ok_or_else(|| db::Error::not_found("Project", id))?;
+        self.webhooks.emit(WebhookEvent::new(
+            webhook::PROJECT_UPDATED,
+            "project",
+            project.id,
+            &project,
+        ));
+        Ok(slug)
+    }
+
+    /// Delete a project
+    pub fn delete_project(&self, id: &Uuid) -> db::Result<()> {
+        self.repo.delete(id)?;
+        self.webhooks.emit(WebhookEvent::new(
+            webhook::PROJECT_DELETED,
+            "project",
+            id,
+            serde_json::json!({ "id": id }),
+        ));
+        Ok(())
+    }
+
+    /// Add a milestone to a project
+    pub fn add_milestone(&self, milestone: &Milestone) -> db::Result<()> {
+        self.repo.add_milestone(milestone)?;
+        self.webhooks.emit(WebhookEvent::new(
+            webhook::MILESTONE_CREATED,
+            "milestone",
+            milestone.id,
+            milestone,
+        ));
+        Ok(())
+    }
+
+    /// Update a milestone
+    ///
+    /// Emits `milestone.due_date_changed` as well as `milestone.updated` when
+    /// the due date is different from the stored one.
+    pub fn update_milestone(&self, milestone: &Milestone) -> db::Result<()> {
+        let previous = self.repo.find_milestone_by_id(&milestone.id)?;
+        self.repo.update_milestone(milestone)?;
+
+        self.webhooks.emit(WebhookEvent::new(
+            webhook::MILESTONE_UPDATED,
+            "milestone",
+            milestone.id,
+            milestone,
+        ));
+
+        if let Some(previous) = previous {
+            if previous.due_date != milestone.due_date {
+                self.webhooks.emit(WebhookEvent::new(
+                    webhook::MILESTONE_DUE_DATE_CHANGED,
+                    "milestone",
+                    milestone.id,
+                    serde_json::json!({
+                        "previous_due_date": previous.due_date,
+                        "due_date": milestone.due_date,
+                        "milestone": milestone,
+                    }),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Delete a milestone
+    pub fn delete_milestone(&self, id: &Uuid) -> db::Result<()> {
+        self.repo.delete_milestone(id)?;
+        self.emit_milestone_deleted(id);
+        Ok(())
+    }
+
+    /// Delete a recurring series, keeping or deleting its occurrences
+    pub fn delete_milestone_series(&self, id: &Uuid, keep_occurrences: bool) -> db::Result<()> {
+        self.repo.delete_milestone_series(id, keep_occurrences)?;
+        self.emit_milestone_deleted(id);
+        Ok(())
+    }
+
+    fn emit_milestone_deleted(&self, id: &Uuid) {
+        self.webhooks.emit(WebhookEvent::new(
+            webhook::MILESTONE_DELETED,
+            "milestone",
+            id,
+            serde_json::json!({ "id": id }),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+    use tokio::sync::mpsc::Receiver;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn drain(receiver: &mut Receiver<WebhookEvent>) -> Vec<String> {
+        let mut events = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            events.push(event.event);
+        }
+        events
+    }
+
+    #[test]
+    fn test_project_events() {
+        let conn = setup_test_db();
+        let (webhooks, mut receiver) = WebhookDispatcher::capture();
+        let service = ProjectService::new(&conn, &webhooks);
+
+        let mut project = Project::new("Test Project".to_string());
+        service.create_project(&project).unwrap();
+        project.name = "Renamed".to_string();
+        service.update_project(&project).unwrap();
+        service.block_project(&project.id, "Waiting on vendor").unwrap();
+        service.unblock_project(&project.id).unwrap();
+        assert_eq!(service.set_slug(&project.id, Some("apollo")).unwrap(), "apollo");
+        service.delete_project(&project.id).unwrap();
+
+        assert_eq!(
+            drain(&mut receiver),
+            vec![
+                webhook::PROJECT_CREATED,
+                webhook::PROJECT_UPDATED,
+                webhook::PROJECT_UPDATED,
+                webhook::PROJECT_UPDATED,
+                webhook::PROJECT_UPDATED,
+                webhook::PROJECT_DELETED
+            ]
+        );
+    }
+
+    fn team_rule() -> BTreeMap<String, BootstrapRule> {
+        let rule = BootstrapRule {
+            milestones: vec!["Kickoff".to_string(), "Design complete".to_string()],
+            notes: vec!["Status".to_string()],
+            log_notes: vec!["Decision log".to_string()],
+            stakeholders: vec![BootstrapStakeholder::Manager, BootstrapStakeholder::RequirementsOwner],
+        };
+        BTreeMap::from([("Team".to_string(), rule)])
+    }
+
+    #[test]
+    fn test_bootstrap_project() {
+        let conn = setup_test_db();
+        let (webhooks, mut receiver) = WebhookDispatcher::capture();
+        let rules = team_rule();
+        let service = ProjectService::new(&conn, &webhooks).with_bootstrap(&rules);
+        let repo = ProjectRepository::new(&conn);
+        db::PersonRepository::new(&conn)
+            .create(&db::Person::new("lead@example.com".to_string(), "Lead".to_string()))
+            .unwrap();
+
+        let mut project = Project::new("Apollo".to_string());
+        project.project_type = "Team".to_string();
+        project.manager = Some("lead@example.com".to_string());
+        project.requirements_owner = Some("lead@example.com".to_string());
+        service.create_project(&project).unwrap();
+
+        let milestones: Vec<_> = repo
+            .get_milestones(&project.id)
+            .unwrap()
+            .into_iter()
+            .map(|m| (m.number, m.name))
+            .collect();
+        assert_eq!(milestones, [(1, "Kickoff".to_string()), (2, "Design complete".to_string())]);
+        let mut notes: Vec<_> = repo
+            .get_project_notes(&project.id)
+            .unwrap()
+            .into_iter()
+            .map(|n| (n.title, n.kind))
+            .collect();
+        notes.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(notes, [("Decision log".to_string(), NoteKind::Log), ("Status".to_string(), NoteKind::Freeform)]);
+        // The same person in two roles is one stakeholder
+        let stakeholders = repo.get_stakeholders(&project.id).unwrap();
+        assert_eq!(stakeholders.len(), 1);
+        assert_eq!(stakeholders[0].role.as_deref(), Some("Manager"));
+        assert_eq!(
+            drain(&mut receiver),
+            vec![webhook::PROJECT_CREATED, webhook::MILESTONE_CREATED, webhook::MILESTONE_CREATED]
+        );
+
+        // Other types, and services without rules, create only the project
+        let personal = Project::new("Side project".to_string());
+        service.create_project(&personal).unwrap();
+        let mut skipped = Project::new("Gemini".to_string());
+        skipped.project_type = "Team".to_string();
+        ProjectService::new(&conn, &webhooks).create_project(&skipped).unwrap();
+        for id in [personal.id, skipped.id] {
+            assert!(repo.get_milestones(&id).unwrap().is_empty());
+            assert!(repo.get_project_notes(&id).unwrap().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_failed_bootstrap_creates_nothing() {
+        let conn = setup_test_db();
+        let (webhooks, mut receiver) = WebhookDispatcher::capture();
+        let rules = team_rule();
+        let service = ProjectService::new(&conn, &webhooks).with_bootstrap(&rules);
+        conn.execute_batch(
+            "CREATE TRIGGER no_design BEFORE INSERT ON milestones WHEN NEW.name = 'Design complete'
+             BEGIN SELECT RAISE(ABORT, 'no design milestones'); END;",
+        )
+        .unwrap();
+
+        let mut project = Project::new("Apollo".to_string());
+        project.project_type = "Team".to_string();
+        assert!(service.create_project(&project).is_err());
+
+        let repo = ProjectRepository::new(&conn);
+        assert!(repo.find_by_id(&project.id).unwrap().is_none());
+        assert!(repo.get_milestones(&project.id).unwrap().is_empty());
+        assert!(drain(&mut receiver).is_empty());
+        assert!(conn.is_autocommit());
+    }
+
+    #[test]
+    fn test_creator_watches_new_project() {
+        let conn = setup_test_db();
+        let (webhooks, _receiver) = WebhookDispatcher::capture();
+        let watchers = WatcherRepository::new(&conn);
+
+        let mine = Project::new("Apollo".to_string());
+        ProjectService::new(&conn, &webhooks)
+            .with_creator(Some("alice@example.com"))
+            .create_project(&mine)
+            .unwrap();
+        let emails: Vec<_> = watchers.list_watchers(&mine.id).unwrap().into_iter().map(|w| w.watcher_email).collect();
+        assert_eq!(emails, ["alice@example.com"]);
+
+        let anonymous = Project::new("Gemini".to_string());
+        ProjectService::new(&conn, &webhooks).create_project(&anonymous).unwrap();
+        assert!(watchers.list_watchers(&anonymous.id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_milestone_due_date_change_event() {
+        let conn = setup_test_db();
+        let (webhooks, mut receiver) = WebhookDispatcher::capture();
+        let service = ProjectService::new(&conn, &webhooks);
+
+        let project = Project::new("Test Project".to_string());
+        service.create_project(&project).unwrap();
+        let mut milestone = Milestone::new(project.id, 1, "Launch".to_string());
+        service.add_milestone(&milestone).unwrap();
+        drain(&mut receiver);
+
+        // Renaming doesn't change the due date
+        milestone.name = "Public launch".to_string();
+        service.update_milestone(&milestone).unwrap();
+        assert_eq!(drain(&mut receiver), vec![webhook::MILESTONE_UPDATED]);
+        milestone.version += 1;
+
+        milestone.due_date = Some(Utc::now() + Duration::days(14));
+        service.update_milestone(&milestone).unwrap();
+        let events: Vec<WebhookEvent> = std::iter::from_fn(|| receiver.try_recv().ok()).collect();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].event, webhook::MILESTONE_DUE_DATE_CHANGED);
+        assert_eq!(events[1].entity_id, milestone.id.to_string());
+        assert!(events[1].data["previous_due_date"].is_null());
+        assert!(events[1].data["due_date"].is_string());
+    }
+
+    #[test]
+    fn test_failed_mutation_emits_nothing() {
+        let conn = setup_test_db();
+        let (webhooks, mut receiver) = WebhookDispatcher::capture();
+        let service = ProjectService::new(&conn, &webhooks);
+
+        assert!(service.delete_project(&Uuid::new_v4()).is_err());
+        assert!(service
+            .update_milestone(&Milestone::new(Uuid::new_v4(), 1, "Missing".to_string()))
+            .is_err());
+        assert!(drain(&mut receiver).is_empty());
+    }
+}
@@ -0,0 +1,317 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Daily backups of the database
+//!
+//! [`auto_backup`] copies the database into the `backups` directory under the
+//! data directory at most once per local day, in a file named by the date,
+//! and keeps only the newest `backup_retention_count` of them. Each attempt
+//! is recorded in the `backup_log` table, failed ones included, so a full
+//! disk shows up in [`backup_status`] instead of stopping the caller.
+
+use crate::config::Config;
+use crate::db::get_datetime;
+use crate::utils::dt_to_db;
+use crate::Result;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use rusqlite::{params, Connection, DatabaseName, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Directory under the data directory that holds the daily backups
+pub const BACKUP_DIR: &str = "backups";
+
+/// Backups kept when `backup_retention_count` isn't set
+pub const DEFAULT_BACKUP_RETENTION_COUNT: u32 = 14;
+
+/// Backup settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupSettings {
+    /// Daily backups to keep; 0 turns backups off
+    pub retention_count: u32,
+}
+
+impl BackupSettings {
+    /// The backup settings in a configuration
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            retention_count: config.backup_retention_count,
+        }
+    }
+}
+
+/// One backup attempt from the `backup_log` table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupRecord {
+    /// When the backup was attempted
+    pub started_at: DateTime<Utc>,
+
+    /// The backup file, if it was written
+    pub path: Option<String>,
+
+    /// Size of the backup file in bytes
+    pub size_bytes: Option<u64>,
+
+    /// Older backups removed to stay within the retention count
+    pub pruned: u32,
+
+    /// Why the backup failed
+    pub error: Option<String>,
+}
+
+impl BackupRecord {
+    /// Whether the backup was written
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// The latest backups, for the settings screen
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupStatus {
+    /// Directory the backups are written to
+    pub directory: String,
+
+    /// Daily backups kept; 0 when backups are off
+    pub retention_count: u32,
+
+    /// The most recent attempt, which may have failed
+    pub last_attempt: Option<BackupRecord>,
+
+    /// The most recent backup that was written
+    pub last_success: Option<BackupRecord>,
+}
+
+/// Back up the database if it hasn't been tried yet today
+///
+/// "Today" is `now`'s date in its time zone. Returns the attempt, or `None`
+/// when backups are off or one was already attempted today. A backup that
+/// can't be written, for example because the disk is full, is recorded with
+/// its error and returned rather than failing the call; errors come only from
+/// recording the attempt.
+pub fn auto_backup(
+    conn: &Connection,
+    data_dir: &Path,
+    now: DateTime<Tz>,
+    cfg: &BackupSettings,
+) -> Result<Option<BackupRecord>> {
+    if cfg.retention_count == 0 {
+        return Ok(None);
+    }
+    let tz = now.timezone();
+    let today = now.date_naive();
+    if let Some(last) = last_backup(conn, false)? {
+        if last.started_at.with_timezone(&tz).date_naive() >= today {
+            return Ok(None);
+        }
+    }
+
+    let dir = data_dir.join(BACKUP_DIR);
+    let path = dir.join(format!("tracker-{}.db", today.format("%Y%m%d")));
+    let mut record = BackupRecord {
+        started_at: now.with_timezone(&Utc),
+        path: None,
+        size_bytes: None,
+        pruned: 0,
+        error: None,
+    };
+    match write_backup(conn, &dir, &path) {
+        Ok(size) => {
+            log::info!("Backed up database to {}", path.display());
+            record.path = Some(path.to_string_lossy().to_string());
+            record.size_bytes = Some(size);
+            record.pruned = prune_backups(&dir, cfg.retention_count).unwrap_or_else(|e| {
+                log::warn!("Failed to remove old backups from {}: {:#}", dir.display(), e);
+                0
+            });
+        }
+        Err(e) => {
+            log::warn!("Failed to back up database: {:#}", e);
+            record.error = Some(format!("{:#}", e));
+        }
+    }
+
+    conn.execute(
+        "INSERT INTO backup_log (started_at, path, size_bytes, pruned, error)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            dt_to_db(record.started_at),
+            record.path,
+            record.size_bytes.map(|size| size as i64),
+            record.pruned,
+            record.error,
+        ],
+    )?;
+    Ok(Some(record))
+}
+
+/// The latest backup attempt and the latest backup that was written
+pub fn backup_status(conn: &Connection, data_dir: &Path, cfg: &BackupSettings) -> Result<BackupStatus> {
+    Ok(BackupStatus {
+        directory: data_dir.join(BACKUP_DIR).to_string_lossy().to_string(),
+        retention_count: cfg.retention_count,
+        last_attempt: last_backup(conn, false)?,
+        last_success: last_backup(conn, true)?,
+    })
+}
+
+/// The newest attempt in the log, or the newest successful one
+fn last_backup(conn: &Connection, succeeded: bool) -> Result<Option<BackupRecord>> {
+    let record = conn
+        .prepare_cached(
+            "SELECT started_at, path, size_bytes, pruned, error FROM backup_log
+             WHERE ?1 = 0 OR error IS NULL
+             ORDER BY id DESC LIMIT 1",
+        )?
+        .query_row(params![succeeded], backup_record_from_row)
+        .optional()?;
+    Ok(record)
+}
+
+fn backup_record_from_row(row: &Row) -> rusqlite::Result<BackupRecord> {
+    Ok(BackupRecord {
+        started_at: get_datetime(row, 0)?,
+        path: row.get(1)?,
+        size_bytes: row.get::<_, Option<i64>>(2)?.map(|size| size as u64),
+        pruned: row.get(3)?,
+        error: row.get(4)?,
+    })
+}
+
+/// Copy the database to `path` with SQLite's online backup and return its size
+///
+/// The copy is written next to `path` and renamed, so an interrupted backup
+/// never leaves a partial file under a backup's name.
+fn write_backup(conn: &Connection, dir: &Path, path: &Path) -> Result<u64> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let mut partial = path.as_os_str().to_os_string();
+    partial.push(".partial");
+    let partial = PathBuf::from(partial);
+
+    let written = conn
+        .backup(DatabaseName::Main, &partial, None)
+        .with_context(|| format!("Failed to write backup {}", path.display()))
+        .and_then(|()| Ok(std::fs::rename(&partial, path)?));
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&partial);
+        return Err(e);
+    }
+    Ok(std::fs::metadata(path)?.len())
+}
+
+/// Remove all but the newest `keep` daily backups and return how many were removed
+fn prune_backups(dir: &Path, keep: u32) -> Result<u32> {
+    let mut backups: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.file_name().and_then(|name| name.to_str()).is_some_and(is_backup_name))
+        .collect();
+    // The date in the name sorts oldest first
+    backups.sort();
+
+    let excess = backups.len().saturating_sub(keep as usize);
+    for path in &backups[..excess] {
+        std::fs::remove_file(path).with_context(|| format!("Failed to remove {}", path.display()))?;
+        log::debug!("Removed old backup {}", path.display());
+    }
+    Ok(excess as u32)
+}
+
+/// Whether a file name is a daily backup's, `tracker-YYYYMMDD.db`
+fn is_backup_name(name: &str) -> bool {
+    name.strip_prefix("tracker-")
+        .and_then(|rest| rest.strip_suffix(".db"))
+        .is_some_and(|date| date.len() == 8 && date.bytes().all(|b| b.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{self, Project, ProjectRepository};
+    use chrono::TimeZone;
+    use tempfile::tempdir;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        db::schema::initialize_schema(&conn).unwrap();
+        db::schema::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn backup_names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir.join(BACKUP_DIR))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_auto_backup_once_a_day_with_retention() {
+        let conn = setup_test_db();
+        ProjectRepository::new(&conn).create(&Project::new("Apollo".to_string())).unwrap();
+        let dir = tempdir().unwrap();
+        let cfg = BackupSettings { retention_count: 2 };
+        // 8am March 14 in Tokyo is still March 13 in UTC
+        let tz = chrono_tz::Asia::Tokyo;
+        let day = |day: u32, hour: u32| tz.with_ymd_and_hms(2025, 3, day, hour, 0, 0).unwrap();
+
+        let first = auto_backup(&conn, dir.path(), day(14, 8), &cfg).unwrap().unwrap();
+        assert!(first.succeeded());
+        assert!(first.size_bytes.unwrap() > 0);
+        assert_eq!(backup_names(dir.path()), ["tracker-20250314.db"]);
+        let backup = Connection::open(first.path.as_ref().unwrap()).unwrap();
+        assert_eq!(ProjectRepository::new(&backup).list_all().unwrap().len(), 1);
+
+        // Later the same day nothing happens
+        assert_eq!(auto_backup(&conn, dir.path(), day(14, 23), &cfg).unwrap(), None);
+
+        auto_backup(&conn, dir.path(), day(15, 9), &cfg).unwrap().unwrap();
+        let third = auto_backup(&conn, dir.path(), day(17, 9), &cfg).unwrap().unwrap();
+        assert_eq!(third.pruned, 1);
+        assert_eq!(backup_names(dir.path()), ["tracker-20250315.db", "tracker-20250317.db"]);
+
+        let status = backup_status(&conn, dir.path(), &cfg).unwrap();
+        assert_eq!(status.last_attempt, Some(third.clone()));
+        assert_eq!(status.last_success, Some(third));
+
+        // Turning backups off skips them
+        let off = BackupSettings { retention_count: 0 };
+        assert_eq!(auto_backup(&conn, dir.path(), day(18, 9), &off).unwrap(), None);
+    }
+
+    #[test]
+    fn test_failed_backup_is_recorded() {
+        let conn = setup_test_db();
+        let dir = tempdir().unwrap();
+        let cfg = BackupSettings { retention_count: 14 };
+        let day = |day: u32| Tz::UTC.with_ymd_and_hms(2025, 3, day, 9, 0, 0).unwrap();
+        let written = auto_backup(&conn, dir.path(), day(14), &cfg).unwrap().unwrap();
+
+        // A file where the backup directory should be makes every write fail
+        std::fs::remove_dir_all(dir.path().join(BACKUP_DIR)).unwrap();
+        std::fs::write(dir.path().join(BACKUP_DIR), "not a directory").unwrap();
+        let failed = auto_backup(&conn, dir.path(), day(15), &cfg).unwrap().unwrap();
+        assert!(!failed.succeeded());
+        assert!(failed.error.as_ref().unwrap().contains("Failed to create"));
+        assert_eq!(failed.path, None);
+
+        // A failed attempt still counts for the day
+        assert_eq!(auto_backup(&conn, dir.path(), day(15), &cfg).unwrap(), None);
+
+        let status = backup_status(&conn, dir.path(), &cfg).unwrap();
+        assert_eq!(status.last_attempt, Some(failed));
+        assert_eq!(status.last_success, Some(written));
+    }
+
+    #[test]
+    fn test_is_backup_name() {
+        assert!(is_backup_name("tracker-20250314.db"));
+        assert!(!is_backup_name("tracker-20250314.db.partial"));
+        assert!(!is_backup_name("tracker-2025031.db"));
+        assert!(!is_backup_name("notes-20250314.db"));
+    }
+}
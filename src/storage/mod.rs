@@ -4,25 +4,320 @@
 
 //! Storage layer for persisting data to files
 
-use std::path::PathBuf;
+pub mod backup;
+pub mod sync;
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use anyhow::{bail, Context};
+use sha2::{Digest, Sha256};
 use crate::Result;
 
+pub use backup::{auto_backup, backup_status, BackupRecord, BackupSettings, BackupStatus};
+pub use sync::{cloud_sync_service, HeartbeatGuard, SyncBundle};
+
+/// Directory under the data directory that holds avatar thumbnails
+pub const AVATAR_DIR: &str = "avatars";
+
+/// Largest avatar upload accepted, in bytes
+pub const MAX_AVATAR_BYTES: usize = 5 * 1024 * 1024;
+
+/// Largest width or height of an avatar upload, in pixels
+pub const MAX_AVATAR_DIMENSION: u32 = 4096;
+
+/// Width and height of stored avatar thumbnails, in pixels
+pub const AVATAR_SIZE: u32 = 128;
+
+/// Directory under the data directory that holds note attachments
+pub const ATTACHMENT_DIR: &str = "attachments";
+
+/// Default largest attachment accepted, in bytes
+pub const DEFAULT_MAX_ATTACHMENT_BYTES: u64 = 25 * 1024 * 1024;
+
+/// Default limit on the bytes all attachments may use together
+pub const DEFAULT_MAX_ATTACHMENT_STORAGE_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Image formats accepted for avatars
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+}
+
+impl ImageFormat {
+    /// Recognize an image by its leading magic bytes
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(ImageFormat::Png)
+        } else if bytes.starts_with(&[0xff, 0xd8, 0xff]) {
+            Some(ImageFormat::Jpeg)
+        } else {
+            None
+        }
+    }
+}
+
+/// Decodes and resizes images for avatars
+///
+/// The library doesn't depend on an image crate itself, so the MCP server
+/// and CLI don't pay for one. The desktop app supplies an implementation
+/// with `Storage::with_image_processor`.
+pub trait ImageProcessor: Send + Sync {
+    /// Read an image's width and height, ideally from its header alone
+    fn dimensions(&self, bytes: &[u8], format: ImageFormat) -> Result<(u32, u32)>;
+
+    /// Crop an image to a centered square, scale it to `size` pixels and encode it as PNG
+    fn square_thumbnail(&self, bytes: &[u8], format: ImageFormat, size: u32) -> Result<Vec<u8>>;
+}
+
 /// Storage manager for data persistence
 pub struct Storage {
     data_dir: PathBuf,
+    image_processor: Option<Arc<dyn ImageProcessor>>,
+    max_attachment_bytes: u64,
+    max_attachment_storage_bytes: u64,
 }
 
 impl Storage {
     /// Create a new storage instance
     pub fn new(data_dir: PathBuf) -> Result<Self> {
         std::fs::create_dir_all(&data_dir)?;
-        Ok(Self { data_dir })
+        Ok(Self {
+            data_dir,
+            image_processor: None,
+            max_attachment_bytes: DEFAULT_MAX_ATTACHMENT_BYTES,
+            max_attachment_storage_bytes: DEFAULT_MAX_ATTACHMENT_STORAGE_BYTES,
+        })
+    }
+
+    /// Use `processor` to decode avatar uploads; without one, `save_avatar` fails
+    pub fn with_image_processor(mut self, processor: Arc<dyn ImageProcessor>) -> Self {
+        self.image_processor = Some(processor);
+        self
+    }
+
+    /// Limit the size of each attachment and of all attachments together
+    pub fn with_attachment_limits(mut self, max_file_bytes: u64, max_total_bytes: u64) -> Self {
+        self.max_attachment_bytes = max_file_bytes;
+        self.max_attachment_storage_bytes = max_total_bytes;
+        self
     }
 
     /// Get the data directory path
     pub fn data_dir(&self) -> &PathBuf {
         &self.data_dir
     }
+
+    /// Validate an avatar upload and store it as a square PNG thumbnail
+    ///
+    /// Accepts PNG and JPEG images up to `MAX_AVATAR_BYTES` and
+    /// `MAX_AVATAR_DIMENSION` pixels on a side. The file is named after a
+    /// hash of the email, so a new upload replaces the old one. Returns the
+    /// path relative to the data directory, which is what belongs in the
+    /// person's `avatar_path`.
+    pub fn save_avatar(&self, email: &str, bytes: &[u8]) -> Result<PathBuf> {
+        let Some(processor) = &self.image_processor else {
+            bail!("Avatars are not supported in this build");
+        };
+        if bytes.len() > MAX_AVATAR_BYTES {
+            bail!(
+                "Avatar is too large: {} bytes, the limit is {}",
+                bytes.len(),
+                MAX_AVATAR_BYTES
+            );
+        }
+        let Some(format) = ImageFormat::detect(bytes) else {
+            bail!("Avatar must be a PNG or JPEG image");
+        };
+        let (width, height) = processor.dimensions(bytes, format)?;
+        if width == 0 || height == 0 || width > MAX_AVATAR_DIMENSION || height > MAX_AVATAR_DIMENSION {
+            bail!(
+                "Avatar is {}x{} pixels; images must be at most {}x{}",
+                width,
+                height,
+                MAX_AVATAR_DIMENSION,
+                MAX_AVATAR_DIMENSION
+            );
+        }
+        let thumbnail = processor.square_thumbnail(bytes, format, AVATAR_SIZE)?;
+
+        let hash = hex::encode(Sha256::digest(email.trim().to_lowercase().as_bytes()));
+        let relative = Path::new(AVATAR_DIR).join(format!("{}.png", hash));
+        let path = self.data_dir.join(&relative);
+        std::fs::create_dir_all(self.data_dir.join(AVATAR_DIR))?;
+
+        // Write next to the final file and rename, so readers never see half an image
+        let partial = path.with_extension("png.partial");
+        std::fs::write(&partial, &thumbnail)
+            .with_context(|| format!("Failed to write {}", partial.display()))?;
+        std::fs::rename(&partial, &path)?;
+
+        log::debug!("Saved avatar for {} to {}", email, path.display());
+        Ok(relative)
+    }
+
+    /// Read a stored avatar thumbnail
+    pub fn read_avatar(&self, avatar_path: &str) -> Result<Vec<u8>> {
+        let path = self.avatar_file(avatar_path)?;
+        std::fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))
+    }
+
+    /// Remove a stored avatar thumbnail; a file that is already gone is not an error
+    pub fn remove_avatar(&self, avatar_path: &str) -> Result<()> {
+        let path = self.avatar_file(avatar_path)?;
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e).with_context(|| format!("Failed to remove {}", path.display())),
+        }
+    }
+
+    /// Remove avatar files that no person refers to, and return their paths
+    ///
+    /// `in_use` holds the `avatar_path` of every person that has one.
+    pub fn sweep_avatars(&self, in_use: &[String]) -> Result<Vec<PathBuf>> {
+        let dir = self.data_dir.join(AVATAR_DIR);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let keep: Vec<PathBuf> = in_use
+            .iter()
+            .filter_map(|path| self.avatar_file(path).ok())
+            .collect();
+
+        let mut removed = Vec::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.is_file() && !keep.contains(&path) {
+                std::fs::remove_file(&path)?;
+                removed.push(path);
+            }
+        }
+        removed.sort();
+        Ok(removed)
+    }
+
+    /// Store an attachment's bytes and return their hex SHA-256, which names the file
+    ///
+    /// `stored_bytes` is what attachments already use, as reported by
+    /// `AttachmentRepository::total_size`. A file that is already stored is
+    /// shared rather than written again, so it doesn't count against the
+    /// total limit.
+    pub fn save_attachment(&self, bytes: &[u8], stored_bytes: u64) -> Result<String> {
+        let size = bytes.len() as u64;
+        if size > self.max_attachment_bytes {
+            bail!(
+                "Attachment is too large: {}, the limit is {} per file",
+                format_bytes(size),
+                format_bytes(self.max_attachment_bytes)
+            );
+        }
+
+        let sha256 = hex::encode(Sha256::digest(bytes));
+        let path = self.attachment_file(&sha256)?;
+        if path.exists() {
+            return Ok(sha256);
+        }
+        if stored_bytes.saturating_add(size) > self.max_attachment_storage_bytes {
+            bail!(
+                "Attachment storage is full: {} of {} is used and this file needs {}",
+                format_bytes(stored_bytes),
+                format_bytes(self.max_attachment_storage_bytes),
+                format_bytes(size)
+            );
+        }
+
+        std::fs::create_dir_all(self.data_dir.join(ATTACHMENT_DIR))?;
+        let partial = path.with_extension("partial");
+        std::fs::write(&partial, bytes)
+            .with_context(|| format!("Failed to write {}", partial.display()))?;
+        std::fs::rename(&partial, &path)?;
+
+        log::debug!("Stored attachment {} ({} bytes)", sha256, size);
+        Ok(sha256)
+    }
+
+    /// Read a stored attachment by its hash
+    pub fn read_attachment(&self, sha256: &str) -> Result<Vec<u8>> {
+        let path = self.attachment_file(sha256)?;
+        std::fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))
+    }
+
+    /// Remove a stored attachment; a file that is already gone is not an error
+    ///
+    /// Only call this once no attachment refers to the file any more.
+    pub fn remove_attachment(&self, sha256: &str) -> Result<()> {
+        let path = self.attachment_file(sha256)?;
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e).with_context(|| format!("Failed to remove {}", path.display())),
+        }
+    }
+
+    /// Remove attachment files that no attachment refers to, and return their paths
+    ///
+    /// `in_use` holds the hash of every stored attachment, as reported by
+    /// `AttachmentRepository::stored_hashes`.
+    pub fn sweep_attachments(&self, in_use: &[String]) -> Result<Vec<PathBuf>> {
+        let dir = self.data_dir.join(ATTACHMENT_DIR);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let keep: Vec<PathBuf> = in_use
+            .iter()
+            .filter_map(|sha256| self.attachment_file(sha256).ok())
+            .collect();
+
+        let mut removed = Vec::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.is_file() && !keep.contains(&path) {
+                std::fs::remove_file(&path)?;
+                removed.push(path);
+            }
+        }
+        removed.sort();
+        Ok(removed)
+    }
+
+    /// Resolve an attachment hash to its file, refusing anything but a hex SHA-256
+    fn attachment_file(&self, sha256: &str) -> Result<PathBuf> {
+        if sha256.len() != 64 || !sha256.bytes().all(|b| b.is_ascii_hexdigit()) {
+            bail!("Invalid attachment hash: {}", sha256);
+        }
+        Ok(self.data_dir.join(ATTACHMENT_DIR).join(sha256.to_ascii_lowercase()))
+    }
+
+    /// Resolve an `avatar_path` to a file, refusing anything outside the avatar directory
+    fn avatar_file(&self, avatar_path: &str) -> Result<PathBuf> {
+        let relative = Path::new(avatar_path);
+        let mut components = relative.components();
+        match (components.next(), components.next(), components.next()) {
+            (
+                Some(std::path::Component::Normal(dir)),
+                Some(std::path::Component::Normal(_)),
+                None,
+            ) if dir == AVATAR_DIR => Ok(self.data_dir.join(relative)),
+            _ => bail!("Invalid avatar path: {}", avatar_path),
+        }
+    }
+}
+
+/// Format a byte count for messages, e.g. "25.0 MB"
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["bytes", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} bytes", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
 }
 
 #[cfg(test)]
@@ -30,10 +325,154 @@ mod tests {
     use super::*;
     use tempfile::tempdir;
 
+    /// Reports a fixed size and returns a marker instead of decoding
+    struct FakeProcessor {
+        dimensions: (u32, u32),
+    }
+
+    impl ImageProcessor for FakeProcessor {
+        fn dimensions(&self, _bytes: &[u8], _format: ImageFormat) -> Result<(u32, u32)> {
+            Ok(self.dimensions)
+        }
+
+        fn square_thumbnail(&self, _bytes: &[u8], format: ImageFormat, size: u32) -> Result<Vec<u8>> {
+            Ok(format!("{:?} thumbnail {}", format, size).into_bytes())
+        }
+    }
+
+    fn storage_with(dir: &Path, dimensions: (u32, u32)) -> Storage {
+        Storage::new(dir.to_path_buf())
+            .unwrap()
+            .with_image_processor(Arc::new(FakeProcessor { dimensions }))
+    }
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\nrest of the image";
+
     #[test]
     fn test_storage_creation() {
         let dir = tempdir().unwrap();
         let storage = Storage::new(dir.path().to_path_buf()).unwrap();
         assert_eq!(storage.data_dir(), dir.path());
     }
+
+    #[test]
+    fn test_save_avatar() {
+        let dir = tempdir().unwrap();
+        let storage = storage_with(dir.path(), (640, 480));
+
+        let path = storage.save_avatar("alice@example.com", PNG).unwrap();
+        assert!(path.starts_with(AVATAR_DIR));
+        assert_eq!(path.extension().unwrap(), "png");
+        let path = path.to_str().unwrap();
+        assert_eq!(storage.read_avatar(path).unwrap(), b"Png thumbnail 128");
+
+        // The same person always gets the same file
+        let jpeg = [0xff, 0xd8, 0xff, 0xe0, 0x00];
+        assert_eq!(storage.save_avatar("Alice@example.com", &jpeg).unwrap().to_str().unwrap(), path);
+        assert_eq!(storage.read_avatar(path).unwrap(), b"Jpeg thumbnail 128");
+
+        storage.remove_avatar(path).unwrap();
+        assert!(storage.read_avatar(path).is_err());
+        storage.remove_avatar(path).unwrap();
+    }
+
+    #[test]
+    fn test_save_avatar_validation() {
+        let dir = tempdir().unwrap();
+        let storage = storage_with(dir.path(), (640, 480));
+        assert!(storage.save_avatar("alice@example.com", b"GIF89a").is_err());
+        let mut huge = PNG.to_vec();
+        huge.resize(MAX_AVATAR_BYTES + 1, 0);
+        assert!(storage.save_avatar("alice@example.com", &huge).is_err());
+
+        let storage = storage_with(dir.path(), (MAX_AVATAR_DIMENSION + 1, 10));
+        assert!(storage.save_avatar("alice@example.com", PNG).is_err());
+
+        let storage = Storage::new(dir.path().to_path_buf()).unwrap();
+        assert!(storage.save_avatar("alice@example.com", PNG).is_err());
+        assert!(!dir.path().join(AVATAR_DIR).exists());
+
+        // Paths from the database can't reach outside the avatar directory
+        assert!(storage.read_avatar("../projects.db").is_err());
+        assert!(storage.remove_avatar("avatars/../projects.db").is_err());
+        assert!(storage.remove_avatar("/etc/passwd").is_err());
+    }
+
+    #[test]
+    fn test_sweep_avatars() {
+        let dir = tempdir().unwrap();
+        let storage = storage_with(dir.path(), (64, 64));
+        assert!(storage.sweep_avatars(&[]).unwrap().is_empty());
+
+        let kept = storage.save_avatar("alice@example.com", PNG).unwrap();
+        let orphan = storage.save_avatar("bob@example.com", PNG).unwrap();
+        let in_use = vec![kept.to_str().unwrap().to_string()];
+
+        let removed = storage.sweep_avatars(&in_use).unwrap();
+        assert_eq!(removed, vec![dir.path().join(&orphan)]);
+        assert!(dir.path().join(&kept).exists());
+        assert!(!dir.path().join(&orphan).exists());
+    }
+
+    #[test]
+    fn test_save_attachment() {
+        let dir = tempdir().unwrap();
+        let storage = Storage::new(dir.path().to_path_buf()).unwrap();
+
+        let sha256 = storage.save_attachment(b"hello", 0).unwrap();
+        assert_eq!(sha256.len(), 64);
+        assert_eq!(storage.read_attachment(&sha256).unwrap(), b"hello");
+        assert!(dir.path().join(ATTACHMENT_DIR).join(&sha256).exists());
+
+        // Identical content is stored once
+        assert_eq!(storage.save_attachment(b"hello", 5).unwrap(), sha256);
+        assert_eq!(std::fs::read_dir(dir.path().join(ATTACHMENT_DIR)).unwrap().count(), 1);
+
+        storage.remove_attachment(&sha256).unwrap();
+        assert!(storage.read_attachment(&sha256).is_err());
+        storage.remove_attachment(&sha256).unwrap();
+
+        // Hashes from the database can't reach outside the attachment directory
+        assert!(storage.read_attachment("../projects.db").is_err());
+        assert!(storage.remove_attachment(&"../".repeat(22)).is_err());
+    }
+
+    #[test]
+    fn test_attachment_limits() {
+        let dir = tempdir().unwrap();
+        let storage = Storage::new(dir.path().to_path_buf())
+            .unwrap()
+            .with_attachment_limits(10, 15);
+
+        let err = storage.save_attachment(&[0; 11], 0).unwrap_err();
+        assert!(err.to_string().contains("the limit is 10 bytes per file"), "{}", err);
+
+        let sha256 = storage.save_attachment(&[1; 10], 0).unwrap();
+        let err = storage.save_attachment(&[2; 10], 10).unwrap_err();
+        assert!(err.to_string().contains("storage is full"), "{}", err);
+
+        // A file that is already stored needs no more space
+        assert_eq!(storage.save_attachment(&[1; 10], 10).unwrap(), sha256);
+    }
+
+    #[test]
+    fn test_sweep_attachments() {
+        let dir = tempdir().unwrap();
+        let storage = Storage::new(dir.path().to_path_buf()).unwrap();
+        assert!(storage.sweep_attachments(&[]).unwrap().is_empty());
+
+        let kept = storage.save_attachment(b"kept", 0).unwrap();
+        let orphan = storage.save_attachment(b"orphan", 0).unwrap();
+
+        let removed = storage.sweep_attachments(std::slice::from_ref(&kept)).unwrap();
+        assert_eq!(removed, vec![dir.path().join(ATTACHMENT_DIR).join(&orphan)]);
+        assert_eq!(storage.read_attachment(&kept).unwrap(), b"kept");
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(10), "10 bytes");
+        assert_eq!(format_bytes(DEFAULT_MAX_ATTACHMENT_BYTES), "25.0 MB");
+        assert_eq!(format_bytes(DEFAULT_MAX_ATTACHMENT_STORAGE_BYTES), "1.0 GB");
+    }
 }
@@ -0,0 +1,767 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Safety for a data directory that more than one machine can reach
+//!
+//! SQLite expects one machine at a time to own a database file. Cloud sync
+//! services copy the file whole, so two machines writing between syncs
+//! leaves a conflicted copy at best and a corrupt database at worst. This
+//! module warns when the data directory is inside a synced folder, keeps
+//! heartbeat files so a second machine can tell the database is in use, and
+//! moves data between machines as single-file sync bundles instead.
+
+use super::Storage;
+use crate::config::{SyncSafetyConfig, SyncSafetyMode};
+use crate::db;
+use crate::utils::dt_to_db;
+use crate::Result;
+use anyhow::{bail, Context};
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::types::ValueRef;
+use rusqlite::{params, Connection, DatabaseName, OpenFlags, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+use uuid::Uuid;
+
+/// Directory under the data directory that holds heartbeat files
+pub const HEARTBEAT_DIR: &str = "heartbeats";
+
+/// Environment variable that opens the database despite another machine's live heartbeat
+pub const FORCE_OPEN_ENV_VAR: &str = "PROJECT_TRACKER_FORCE_OPEN";
+
+/// Folder names that sync services create, and the service each belongs to
+///
+/// A folder matches by its exact name or by the name followed by a space,
+/// dash or parenthesis, as in "Dropbox (Personal)", "OneDrive - Company" or
+/// macOS's "Library/CloudStorage/GoogleDrive-me@example.com".
+const CLOUD_FOLDERS: &[(&str, &str)] = &[
+    ("Dropbox", "Dropbox"),
+    ("OneDrive", "OneDrive"),
+    ("Google Drive", "Google Drive"),
+    ("GoogleDrive", "Google Drive"),
+    ("My Drive", "Google Drive"),
+    ("iCloud Drive", "iCloud Drive"),
+    ("iCloudDrive", "iCloud Drive"),
+    ("Mobile Documents", "iCloud Drive"),
+    ("Box", "Box"),
+    ("pCloud Drive", "pCloud"),
+    ("Nextcloud", "Nextcloud"),
+    ("ownCloud", "ownCloud"),
+    ("MEGA", "MEGA"),
+    ("MEGAsync", "MEGA"),
+    ("SynologyDrive", "Synology Drive"),
+];
+
+/// Name the cloud sync service whose folder holds `path`, if any
+///
+/// Recognizes the folders sync services create by default, and a Dropbox
+/// folder anywhere by the `.dropbox` marker file in its root.
+pub fn cloud_sync_service(path: &Path) -> Option<&'static str> {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    for component in path.components() {
+        let std::path::Component::Normal(name) = component else {
+            continue;
+        };
+        let name = name.to_string_lossy();
+        let found = CLOUD_FOLDERS.iter().find(|(folder, _)| {
+            name.strip_prefix(folder)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '-', '(']))
+        });
+        if let Some((_, service)) = found {
+            return Some(service);
+        }
+    }
+    path.ancestors()
+        .any(|dir| dir.join(".dropbox").is_file())
+        .then_some("Dropbox")
+}
+
+/// This machine's host name, as recorded in heartbeats and sync bundles
+pub fn this_host() -> String {
+    hostname::get()
+        .ok()
+        .and_then(|name| name.into_string().ok())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// A running program's claim on a data directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Heartbeat {
+    pub host: String,
+    pub pid: u32,
+    pub program: String,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Heartbeat {
+    /// Whether the heartbeat was updated less than `stale_after` before `now`
+    pub fn is_live(&self, now: DateTime<Utc>, stale_after: Duration) -> bool {
+        now.signed_duration_since(self.updated_at) < stale_after
+    }
+
+    /// File name for the heartbeat, unique to the host and process
+    fn file_name(&self) -> String {
+        let host: String = self
+            .host
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+            .collect();
+        format!("{}-{}.json", host, self.pid)
+    }
+}
+
+/// Write a heartbeat next to its final file and rename it, so readers on
+/// other machines never see half of one
+fn write_heartbeat(path: &Path, heartbeat: &Heartbeat) -> Result<()> {
+    let partial = path.with_extension("json.partial");
+    std::fs::write(&partial, serde_json::to_vec_pretty(heartbeat)?)
+        .with_context(|| format!("Failed to write {}", partial.display()))?;
+    std::fs::rename(&partial, path)?;
+    Ok(())
+}
+
+/// Keeps this process's heartbeat file fresh, and removes it when dropped
+pub struct HeartbeatGuard {
+    path: PathBuf,
+    stop: Option<mpsc::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for HeartbeatGuard {
+    fn drop(&mut self) {
+        // Closing the channel wakes the refresh thread and ends it
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            log::warn!("Failed to remove heartbeat {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// Seconds after which another machine's heartbeat no longer counts
+fn stale_after(settings: &SyncSafetyConfig) -> Duration {
+    Duration::seconds(settings.stale_after_secs as i64)
+}
+
+/// Whether `$PROJECT_TRACKER_FORCE_OPEN` asks to ignore other machines' heartbeats
+fn force_open() -> bool {
+    std::env::var(FORCE_OPEN_ENV_VAR).is_ok_and(|value| !value.is_empty() && value != "0")
+}
+
+impl Storage {
+    /// Check the data directory before opening its database
+    ///
+    /// Returns a warning to show prominently when the directory is inside a
+    /// cloud-synced folder. In lock mode, fails while another machine holds
+    /// a live heartbeat, unless `$PROJECT_TRACKER_FORCE_OPEN` is set.
+    pub fn check_sync_safety(&self, settings: &SyncSafetyConfig) -> Result<Option<String>> {
+        self.check_sync_safety_with(settings, force_open())
+    }
+
+    fn check_sync_safety_with(&self, settings: &SyncSafetyConfig, force: bool) -> Result<Option<String>> {
+        if settings.mode == SyncSafetyMode::Off {
+            return Ok(None);
+        }
+
+        let warning = cloud_sync_service(&self.data_dir).map(|service| {
+            format!(
+                "The data directory {} is inside a {} folder. Opening the database on two machines \
+                 between syncs can corrupt it; set sync_safety.mode = \"lock\" in the config, and \
+                 move data between machines with `track db export-bundle` and `track db import-bundle`.",
+                self.data_dir.display(),
+                service
+            )
+        });
+        if let Some(warning) = &warning {
+            log::warn!("{}", warning);
+        }
+
+        if settings.mode == SyncSafetyMode::Lock {
+            let live = self.live_heartbeats(&this_host(), Utc::now(), stale_after(settings))?;
+            if let Some(other) = live.first() {
+                let message = format!(
+                    "The database in {} is open on {} ({} pid {}, last seen {}). Opening it on \
+                     two machines at once can corrupt it. Close it there, or wait {} seconds for \
+                     its heartbeat to expire if it has stopped; set {}=1 to open it anyway.",
+                    self.data_dir.display(),
+                    other.host,
+                    other.program,
+                    other.pid,
+                    other.updated_at.format("%Y-%m-%d %H:%M:%S UTC"),
+                    settings.stale_after_secs,
+                    FORCE_OPEN_ENV_VAR
+                );
+                if !force {
+                    bail!(message);
+                }
+                log::warn!("Opening anyway because {} is set: {}", FORCE_OPEN_ENV_VAR, message);
+            }
+        }
+        Ok(warning)
+    }
+
+    /// Read every readable heartbeat in the data directory
+    ///
+    /// Files that don't parse, such as a sync service's conflicted copies,
+    /// are skipped.
+    pub fn read_heartbeats(&self) -> Result<Vec<Heartbeat>> {
+        let dir = self.data_dir.join(HEARTBEAT_DIR);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut heartbeats = Vec::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let parsed = std::fs::read(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|bytes| Ok(serde_json::from_slice::<Heartbeat>(&bytes)?));
+            match parsed {
+                Ok(heartbeat) => heartbeats.push(heartbeat),
+                Err(e) => log::warn!("Ignoring unreadable heartbeat {}: {}", path.display(), e),
+            }
+        }
+        Ok(heartbeats)
+    }
+
+    /// Heartbeats from machines other than `host` that were updated within `stale_after` of `now`
+    ///
+    /// Processes on the same machine share the database safely through
+    /// SQLite's own locking, so only other hosts count.
+    pub fn live_heartbeats(&self, host: &str, now: DateTime<Utc>, stale_after: Duration) -> Result<Vec<Heartbeat>> {
+        let mut live: Vec<Heartbeat> = self
+            .read_heartbeats()?
+            .into_iter()
+            .filter(|heartbeat| heartbeat.host != host && heartbeat.is_live(now, stale_after))
+            .collect();
+        live.sort_by_key(|heartbeat| std::cmp::Reverse(heartbeat.updated_at));
+        Ok(live)
+    }
+
+    /// Write this process's heartbeat and keep it fresh until the guard is dropped
+    ///
+    /// Does nothing unless `settings` is in lock mode. Stale heartbeats left
+    /// on this machine by programs that didn't exit cleanly are removed.
+    /// Call [`check_sync_safety`](Self::check_sync_safety) first.
+    pub fn start_heartbeat(&self, program: &str, settings: &SyncSafetyConfig) -> Result<Option<HeartbeatGuard>> {
+        if settings.mode != SyncSafetyMode::Lock {
+            return Ok(None);
+        }
+        let dir = self.data_dir.join(HEARTBEAT_DIR);
+        std::fs::create_dir_all(&dir)?;
+
+        let now = Utc::now();
+        let host = this_host();
+        for old in self.read_heartbeats()? {
+            if old.host == host && !old.is_live(now, stale_after(settings)) {
+                let _ = std::fs::remove_file(dir.join(old.file_name()));
+            }
+        }
+
+        let mut heartbeat = Heartbeat {
+            host,
+            pid: std::process::id(),
+            program: program.to_string(),
+            started_at: now,
+            updated_at: now,
+        };
+        let path = dir.join(heartbeat.file_name());
+        write_heartbeat(&path, &heartbeat)?;
+
+        // Refresh well within the stale limit, so a slow sync doesn't make
+        // a running program look stopped
+        let interval = std::time::Duration::from_secs((settings.stale_after_secs / 4).max(1));
+        let (stop, stopped) = mpsc::channel::<()>();
+        let thread_path = path.clone();
+        let thread = std::thread::spawn(move || {
+            while let Err(mpsc::RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                heartbeat.updated_at = Utc::now();
+                if let Err(e) = write_heartbeat(&thread_path, &heartbeat) {
+                    log::warn!("Failed to refresh heartbeat {}: {:#}", thread_path.display(), e);
+                }
+            }
+        });
+
+        log::info!("Holding heartbeat {}", path.display());
+        Ok(Some(HeartbeatGuard {
+            path,
+            stop: Some(stop),
+            thread: Some(thread),
+        }))
+    }
+
+    /// Write a snapshot of the database to `dest` as a sync bundle
+    ///
+    /// The bundle is an ordinary SQLite database with one more row in its
+    /// `sync_bundles` table describing it: a new ID, the bundle this
+    /// database last exported or imported, and a checksum of the contents.
+    /// The same row is recorded here, so a bundle that comes back can be
+    /// checked against it. An existing file at `dest` is replaced.
+    pub fn export_sync_bundle(&self, conn: &Connection, dest: &Path) -> Result<SyncBundle> {
+        let mut partial = dest.as_os_str().to_os_string();
+        partial.push(".partial");
+        let partial = PathBuf::from(partial);
+        if partial.exists() {
+            std::fs::remove_file(&partial)?;
+        }
+
+        conn.backup(DatabaseName::Main, &partial, None)
+            .with_context(|| format!("Failed to write sync bundle {}", partial.display()))?;
+
+        // Describe the snapshot itself, which can't have changed since it was taken
+        let snapshot = Connection::open(&partial)?;
+        let bundle = SyncBundle {
+            id: Uuid::new_v4(),
+            parent_id: last_sync(&snapshot)?.map(|(last, _)| last.id),
+            checksum: content_checksum(&snapshot)?,
+            host: this_host(),
+            created_at: Utc::now(),
+        };
+        record_sync(&snapshot, &bundle, "export")?;
+        drop(snapshot);
+        record_sync(conn, &bundle, "export")?;
+        std::fs::rename(&partial, dest)
+            .with_context(|| format!("Failed to write sync bundle {}", dest.display()))?;
+
+        log::info!("Exported sync bundle {} to {}", bundle.id, dest.display());
+        Ok(bundle)
+    }
+
+    /// Replace the database with the contents of a sync bundle
+    ///
+    /// Refuses, explaining why, when the bundle doesn't follow on from this
+    /// database: when this database changed since its last export or import,
+    /// when the bundle was exported from a database that last synced a
+    /// different bundle, or when this database was never synced but already
+    /// holds data. `force` imports anyway. Before replacing anything, the
+    /// database is copied to `<name>.pre-import.bak` beside it.
+    ///
+    /// Returns the imported bundle, or `None` when this database already
+    /// matches it.
+    pub fn import_sync_bundle(&self, conn: &mut Connection, src: &Path, force: bool) -> Result<Option<SyncBundle>> {
+        let bundle = read_bundle(conn, src)?;
+        let local_checksum = content_checksum(conn)?;
+        let last = last_sync(conn)?;
+
+        if let Some((last, _)) = &last {
+            if last.id == bundle.id && local_checksum == bundle.checksum {
+                log::info!("Sync bundle {} is already applied", bundle.id);
+                return Ok(None);
+            }
+        }
+        if !force {
+            check_bundle_follows(conn, &bundle, last.as_ref(), &local_checksum)?;
+        }
+
+        if let Some(path) = conn.path().filter(|path| !path.is_empty()) {
+            let backup = PathBuf::from(format!("{}.pre-import.bak", path));
+            if backup.exists() {
+                std::fs::remove_file(&backup)?;
+            }
+            conn.execute("VACUUM INTO ?1", [backup.to_string_lossy().to_string()])
+                .with_context(|| format!("Failed to back up database to {}", backup.display()))?;
+            log::info!("Backed up database to {} before importing", backup.display());
+        }
+
+        conn.flush_prepared_statement_cache();
+        conn.restore(DatabaseName::Main, src, None::<fn(rusqlite::backup::Progress)>)
+            .with_context(|| format!("Failed to import sync bundle {}", src.display()))?;
+        record_sync(
+            conn,
+            &SyncBundle {
+                host: this_host(),
+                created_at: Utc::now(),
+                ..bundle.clone()
+            },
+            "import",
+        )?;
+
+        log::info!("Imported sync bundle {} from {}", bundle.id, src.display());
+        Ok(Some(bundle))
+    }
+}
+
+/// A snapshot of the database for moving it to another machine
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SyncBundle {
+    pub id: Uuid,
+    /// The bundle the exporting database last exported or imported
+    pub parent_id: Option<Uuid>,
+    /// SHA-256 of the bundle's contents, from [`content_checksum`]
+    pub checksum: String,
+    /// Host that exported or imported the bundle
+    pub host: String,
+    pub created_at: DateTime<Utc>,
+}
+
+fn sync_bundle_from_row(row: &rusqlite::Row) -> rusqlite::Result<(SyncBundle, String)> {
+    let id: String = row.get(0)?;
+    let parent_id: Option<String> = row.get(1)?;
+    Ok((
+        SyncBundle {
+            id: Uuid::parse_str(&id).unwrap_or_default(),
+            parent_id: parent_id.and_then(|id| Uuid::parse_str(&id).ok()),
+            checksum: row.get(2)?,
+            host: row.get(3)?,
+            created_at: db::get_datetime(row, 4)?,
+        },
+        row.get(5)?,
+    ))
+}
+
+/// The newest bundle exported from or imported into this database, with its direction
+fn last_sync(conn: &Connection) -> Result<Option<(SyncBundle, String)>> {
+    let last = conn
+        .query_row(
+            "SELECT bundle_id, parent_id, checksum, host, created_at, direction
+             FROM sync_bundles ORDER BY seq DESC LIMIT 1",
+            [],
+            sync_bundle_from_row,
+        )
+        .optional()?;
+    Ok(last)
+}
+
+fn record_sync(conn: &Connection, bundle: &SyncBundle, direction: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO sync_bundles (bundle_id, direction, parent_id, checksum, host, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            bundle.id.to_string(),
+            direction,
+            bundle.parent_id.map(|id| id.to_string()),
+            &bundle.checksum,
+            &bundle.host,
+            dt_to_db(bundle.created_at),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Open a bundle and check that it's an intact export from a compatible database
+fn read_bundle(conn: &Connection, src: &Path) -> Result<SyncBundle> {
+    let source = Connection::open_with_flags(src, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .with_context(|| format!("Failed to open sync bundle {}", src.display()))?;
+    let not_a_bundle = || format!("{} is not a sync bundle; create one with `track db export-bundle`", src.display());
+
+    let version = db::schema::read_schema_version(&source)
+        .ok()
+        .flatten()
+        .with_context(not_a_bundle)?;
+    let local_version = db::schema::get_schema_version(conn)?;
+    if version != local_version {
+        bail!(
+            "The bundle has schema version {} but this database has version {}; run the same \
+             Project Tracker version on both machines before syncing",
+            version,
+            local_version
+        );
+    }
+
+    let bundle = source
+        .query_row(
+            "SELECT bundle_id, parent_id, checksum, host, created_at, direction
+             FROM sync_bundles WHERE direction = 'export' ORDER BY seq DESC LIMIT 1",
+            [],
+            sync_bundle_from_row,
+        )
+        .optional()?
+        .map(|(bundle, _)| bundle)
+        .with_context(not_a_bundle)?;
+    if content_checksum(&source)? != bundle.checksum {
+        bail!(
+            "The contents of {} don't match its checksum; it was changed or damaged after it was exported",
+            src.display()
+        );
+    }
+    Ok(bundle)
+}
+
+/// Refuse a bundle that doesn't follow on from this database
+fn check_bundle_follows(
+    conn: &Connection,
+    bundle: &SyncBundle,
+    last: Option<&(SyncBundle, String)>,
+    local_checksum: &str,
+) -> Result<()> {
+    let exported = format!(
+        "Bundle {} was exported on {} at {}",
+        bundle.id,
+        bundle.host,
+        bundle.created_at.format("%Y-%m-%d %H:%M UTC")
+    );
+    let Some((last, direction)) = last else {
+        if !crate::fixtures::is_empty(conn)? {
+            bail!(
+                "{}, but this database has never been synced and already holds data that the \
+                 import would replace. Export a bundle from it first if the data matters, or \
+                 import with --force to replace it.",
+                exported
+            );
+        }
+        return Ok(());
+    };
+
+    if local_checksum != last.checksum {
+        bail!(
+            "{}, but this database has changed since bundle {} was {}ed here on {}. The two \
+             databases have diverged and importing would lose the changes made here. Copy those \
+             changes across by hand, or import with --force to replace them (a backup is kept).",
+            exported,
+            last.id,
+            direction,
+            last.created_at.format("%Y-%m-%d %H:%M UTC")
+        );
+    }
+    if bundle.parent_id != Some(last.id) {
+        let parent = bundle
+            .parent_id
+            .map_or_else(|| "no earlier bundle".to_string(), |id| format!("bundle {}", id));
+        bail!(
+            "{} from a database that had last synced {}, but this database last synced bundle {}. \
+             Either a newer bundle was skipped or the two databases have diverged. Import the \
+             bundles in order, or import with --force to replace this database (a backup is kept).",
+            exported,
+            parent,
+            last.id
+        );
+    }
+    Ok(())
+}
+
+/// SHA-256 over the contents of every table except the sync, schema and
+/// backup bookkeeping
+///
+/// The backup log is left out because each machine keeps its own backups.
+/// Rows are hashed in a fixed order, so the same data gives the same
+/// checksum however it's stored.
+pub fn content_checksum(conn: &Connection) -> Result<String> {
+    let tables: Vec<String> = conn
+        .prepare(
+            "SELECT name FROM sqlite_master
+             WHERE type = 'table' AND name NOT LIKE 'sqlite_%'
+               AND name NOT IN ('schema_version', 'sync_bundles', 'backup_log')
+               AND sql NOT LIKE 'CREATE VIRTUAL%'
+             ORDER BY name",
+        )?
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let mut hasher = Sha256::new();
+    for table in tables {
+        let columns = conn.prepare(&format!("SELECT * FROM \"{}\"", table))?.column_count();
+        let order = (1..=columns).map(|i| i.to_string()).collect::<Vec<_>>().join(", ");
+        let mut stmt = conn.prepare(&format!("SELECT * FROM \"{}\" ORDER BY {}", table, order))?;
+        hasher.update(table.as_bytes());
+        hasher.update([0]);
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            for i in 0..columns {
+                match row.get_ref(i)? {
+                    ValueRef::Null => hasher.update([0]),
+                    ValueRef::Integer(value) => {
+                        hasher.update([1]);
+                        hasher.update(value.to_le_bytes());
+                    }
+                    ValueRef::Real(value) => {
+                        hasher.update([2]);
+                        hasher.update(value.to_le_bytes());
+                    }
+                    ValueRef::Text(bytes) | ValueRef::Blob(bytes) => {
+                        hasher.update([3]);
+                        hasher.update((bytes.len() as u64).to_le_bytes());
+                        hasher.update(bytes);
+                    }
+                }
+            }
+        }
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Person, PersonRepository};
+    use tempfile::tempdir;
+
+    fn heartbeat(host: &str, pid: u32, updated_at: DateTime<Utc>) -> Heartbeat {
+        Heartbeat {
+            host: host.to_string(),
+            pid,
+            program: "track-mcp".to_string(),
+            started_at: updated_at - Duration::hours(1),
+            updated_at,
+        }
+    }
+
+    fn add_person(conn: &Connection, email: &str) {
+        PersonRepository::new(conn)
+            .create(&Person::new(email.to_string(), email.to_string()))
+            .unwrap();
+    }
+
+    fn person_count(conn: &Connection) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM people", [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn test_cloud_sync_service() {
+        assert_eq!(cloud_sync_service(Path::new("/home/ann/Dropbox/tracker")), Some("Dropbox"));
+        assert_eq!(cloud_sync_service(Path::new("/home/ann/Dropbox (Acme)/tracker")), Some("Dropbox"));
+        assert_eq!(
+            cloud_sync_service(Path::new("/Users/ann/Library/CloudStorage/OneDrive-Personal/tracker")),
+            Some("OneDrive")
+        );
+        assert_eq!(
+            cloud_sync_service(Path::new("/Users/ann/Library/Mobile Documents/com~apple~CloudDocs")),
+            Some("iCloud Drive")
+        );
+        assert_eq!(cloud_sync_service(Path::new("/home/ann/Boxes/tracker")), None);
+
+        let dir = tempdir().unwrap();
+        let data_dir = dir.path().join("shared").join("tracker");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        assert_eq!(cloud_sync_service(&data_dir), None);
+        std::fs::write(dir.path().join("shared").join(".dropbox"), "").unwrap();
+        assert_eq!(cloud_sync_service(&data_dir), Some("Dropbox"));
+    }
+
+    #[test]
+    fn test_stale_and_live_heartbeats() {
+        let dir = tempdir().unwrap();
+        let storage = Storage::new(dir.path().to_path_buf()).unwrap();
+        let heartbeats = dir.path().join(HEARTBEAT_DIR);
+        std::fs::create_dir_all(&heartbeats).unwrap();
+
+        let now = Utc::now();
+        let live = heartbeat("laptop", 10, now - Duration::seconds(30));
+        let stale = heartbeat("desktop", 20, now - Duration::minutes(10));
+        for beat in [&live, &stale] {
+            write_heartbeat(&heartbeats.join(beat.file_name()), beat).unwrap();
+        }
+        std::fs::write(heartbeats.join("laptop-10 (conflicted copy).json"), "{").unwrap();
+
+        let stale_after = Duration::minutes(2);
+        assert_eq!(storage.read_heartbeats().unwrap().len(), 2);
+        assert_eq!(storage.live_heartbeats("workstation", now, stale_after).unwrap(), [live]);
+        // A machine's own heartbeats never block it
+        assert!(storage.live_heartbeats("laptop", now, stale_after).unwrap().is_empty());
+        // Once the laptop stops refreshing, its heartbeat goes stale too
+        let later = now + Duration::minutes(5);
+        assert!(storage.live_heartbeats("workstation", later, stale_after).unwrap().is_empty());
+
+        // Lock mode refuses while another machine's heartbeat is live, unless forced
+        let lock = SyncSafetyConfig {
+            mode: SyncSafetyMode::Lock,
+            ..SyncSafetyConfig::default()
+        };
+        let other = heartbeat("another-machine-entirely", 30, Utc::now());
+        write_heartbeat(&heartbeats.join(other.file_name()), &other).unwrap();
+        let err = storage.check_sync_safety_with(&lock, false).unwrap_err();
+        assert!(err.to_string().contains("another-machine-entirely"), "{}", err);
+        assert!(storage.check_sync_safety_with(&lock, true).is_ok());
+        assert!(storage.check_sync_safety_with(&SyncSafetyConfig::default(), false).is_ok());
+    }
+
+    #[test]
+    fn test_heartbeat_guard() {
+        let dir = tempdir().unwrap();
+        let storage = Storage::new(dir.path().to_path_buf()).unwrap();
+        assert!(storage.start_heartbeat("track", &SyncSafetyConfig::default()).unwrap().is_none());
+
+        let lock = SyncSafetyConfig {
+            mode: SyncSafetyMode::Lock,
+            ..SyncSafetyConfig::default()
+        };
+        let guard = storage.start_heartbeat("track", &lock).unwrap().unwrap();
+        let beats = storage.read_heartbeats().unwrap();
+        assert_eq!(beats.len(), 1);
+        assert_eq!((beats[0].host.as_str(), beats[0].pid), (this_host().as_str(), std::process::id()));
+        drop(guard);
+        assert!(storage.read_heartbeats().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sync_bundle_round_trip() {
+        let dir = tempdir().unwrap();
+        let storage = Storage::new(dir.path().to_path_buf()).unwrap();
+        let laptop = db::open_database(dir.path().join("laptop.db")).unwrap();
+        let mut desktop = db::open_database(dir.path().join("desktop.db")).unwrap();
+        add_person(&laptop, "ann@example.com");
+
+        // A fresh machine takes the first bundle
+        let first = storage.export_sync_bundle(&laptop, &dir.path().join("first.bundle")).unwrap();
+        assert_eq!(first.parent_id, None);
+        let imported = storage.import_sync_bundle(&mut desktop, &dir.path().join("first.bundle"), false).unwrap();
+        assert_eq!(imported, Some(first.clone()));
+        assert_eq!(person_count(&desktop), 1);
+        assert!(dir.path().join("desktop.db.pre-import.bak").exists());
+        assert_eq!(
+            storage.import_sync_bundle(&mut desktop, &dir.path().join("first.bundle"), false).unwrap(),
+            None
+        );
+
+        // Changes made on the desktop come back to the unchanged laptop
+        add_person(&desktop, "bob@example.com");
+        let second = storage.export_sync_bundle(&desktop, &dir.path().join("second.bundle")).unwrap();
+        assert_eq!(second.parent_id, Some(first.id));
+        let mut laptop = laptop;
+        storage.import_sync_bundle(&mut laptop, &dir.path().join("second.bundle"), false).unwrap();
+        assert_eq!(person_count(&laptop), 2);
+        assert_eq!(content_checksum(&laptop).unwrap(), content_checksum(&desktop).unwrap());
+    }
+
+    #[test]
+    fn test_sync_bundle_refuses_diverged_database() {
+        let dir = tempdir().unwrap();
+        let storage = Storage::new(dir.path().to_path_buf()).unwrap();
+        let laptop = db::open_database(dir.path().join("laptop.db")).unwrap();
+        let mut desktop = db::open_database(dir.path().join("desktop.db")).unwrap();
+        add_person(&laptop, "ann@example.com");
+        let bundle = dir.path().join("first.bundle");
+        storage.export_sync_bundle(&laptop, &bundle).unwrap();
+        storage.import_sync_bundle(&mut desktop, &bundle, false).unwrap();
+
+        // Both sides change after the last sync
+        add_person(&laptop, "bob@example.com");
+        add_person(&desktop, "carol@example.com");
+        let second = dir.path().join("second.bundle");
+        storage.export_sync_bundle(&desktop, &second).unwrap();
+        let mut laptop = laptop;
+        let err = storage.import_sync_bundle(&mut laptop, &second, false).unwrap_err();
+        assert!(err.to_string().contains("diverged"), "{}", err);
+        assert_eq!(person_count(&laptop), 2);
+
+        // A bundle that skips one the laptop has already seen is refused too
+        let mut fresh = db::open_database(dir.path().join("fresh.db")).unwrap();
+        storage.import_sync_bundle(&mut fresh, &bundle, false).unwrap();
+        add_person(&laptop, "dave@example.com");
+        storage.import_sync_bundle(&mut fresh, &second, false).unwrap();
+        let third = dir.path().join("third.bundle");
+        storage.export_sync_bundle(&laptop, &third).unwrap();
+        let err = storage.import_sync_bundle(&mut fresh, &third, false).unwrap_err();
+        assert!(err.to_string().contains("last synced"), "{}", err);
+
+        // An unsynced database with data of its own is never replaced silently
+        let mut other = db::open_database(dir.path().join("other.db")).unwrap();
+        add_person(&other, "erin@example.com");
+        let err = storage.import_sync_bundle(&mut other, &bundle, false).unwrap_err();
+        assert!(err.to_string().contains("never been synced"), "{}", err);
+
+        // Forcing replaces the database and keeps a backup
+        storage.import_sync_bundle(&mut laptop, &second, true).unwrap();
+        assert_eq!(person_count(&laptop), 2);
+        let backup = Connection::open(dir.path().join("laptop.db.pre-import.bak")).unwrap();
+        assert_eq!(person_count(&backup), 3);
+    }
+}
@@ -4,6 +4,9 @@
 
 //! Utility functions
 
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, LocalResult, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
 use uuid::Uuid;
 
 /// Generate a unique ID
@@ -11,6 +14,278 @@ pub fn generate_id() -> String {
     Uuid::new_v4().to_string()
 }
 
+/// Format a timestamp for storage in the database (RFC3339)
+pub fn dt_to_db(dt: DateTime<Utc>) -> String {
+    dt.to_rfc3339()
+}
+
+/// Parse a timestamp read from the database
+///
+/// Accepts RFC3339 (what `dt_to_db` writes) as well as SQLite's own
+/// `YYYY-MM-DD HH:MM:SS` format (as produced by `datetime('now')`) and bare
+/// dates. Values without an offset are treated as UTC.
+pub fn dt_from_db(value: &str) -> Result<DateTime<Utc>> {
+    let value = value.trim();
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    if let Ok(dt) = DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f%:z") {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(value, format) {
+            return Ok(Utc.from_utc_datetime(&dt));
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap()));
+    }
+
+    bail!("Unrecognized date/time value: {}", value)
+}
+
+/// Convert a stored UTC timestamp to a time zone for display
+pub fn to_local(dt: DateTime<Utc>, tz: Tz) -> DateTime<Tz> {
+    dt.with_timezone(&tz)
+}
+
+/// Format a stored UTC timestamp as a `YYYY-MM-DD` date in a time zone
+pub fn format_local_date(dt: DateTime<Utc>, tz: Tz) -> String {
+    to_local(dt, tz).format("%Y-%m-%d").to_string()
+}
+
+/// Parse a date entered by a user
+///
+/// RFC3339 timestamps carry their own offset. Plain `YYYY-MM-DD` dates are
+/// taken as the start of that day in `tz`, so they display as the same date
+/// when formatted with [`format_local_date`] in that zone.
+pub fn parse_user_date(value: &str, tz: Tz) -> Result<DateTime<Utc>> {
+    let value = value.trim();
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .with_context(|| format!("Expected an RFC3339 timestamp or a YYYY-MM-DD date: {}", value))?;
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap();
+    let start_of_day = match tz.from_local_datetime(&midnight) {
+        LocalResult::Single(dt) => dt,
+        LocalResult::Ambiguous(earliest, _) => earliest,
+        // A few zones skip midnight when DST starts; the day begins an hour later
+        LocalResult::None => tz
+            .from_local_datetime(&(midnight + Duration::hours(1)))
+            .earliest()
+            .with_context(|| format!("{} has no start of day in {}", value, tz.name()))?,
+    };
+    Ok(start_of_day.with_timezone(&Utc))
+}
+
+/// The first moment of a date in a time zone, in UTC
+pub fn start_of_local_day(date: NaiveDate, tz: Tz) -> DateTime<Utc> {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap();
+    // A few zones skip midnight when DST starts; the day then starts an hour later
+    tz.from_local_datetime(&midnight)
+        .earliest()
+        .or_else(|| tz.from_local_datetime(&(midnight + Duration::hours(1))).earliest())
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&midnight))
+}
+
+/// Check that a value is an absolute `http` or `https` URL
+pub fn is_http_url(value: &str) -> bool {
+    match url::Url::parse(value) {
+        Ok(url) => matches!(url.scheme(), "http" | "https") && url.host().is_some(),
+        Err(_) => false,
+    }
+}
+
+/// Check that an email address belongs to one of the allowed domains
+///
+/// An empty list allows every address. A domain also allows its subdomains,
+/// so `company.com` allows `alice@corp.company.com` but not
+/// `alice@notcompany.com`. Case is ignored.
+pub fn email_domain_allowed(email: &str, allowed: &[String]) -> bool {
+    if allowed.is_empty() {
+        return true;
+    }
+    let Some((_, domain)) = email.rsplit_once('@') else {
+        return false;
+    };
+    let domain = domain.to_lowercase();
+    allowed.iter().any(|allowed| {
+        let allowed = allowed.to_lowercase();
+        domain == allowed || domain.strip_suffix(allowed.as_str()).is_some_and(|sub| sub.ends_with('.'))
+    })
+}
+
+/// Longest slug [`slugify`] produces
+pub const MAX_SLUG_LEN: usize = 60;
+
+/// Turn a name into a short identifier such as `apollo-migration`
+///
+/// Letters are lowercased and accented Latin letters lose their accents;
+/// other characters that aren't ASCII letters or digits separate words. The
+/// result is never empty: a name with nothing to keep becomes `project`.
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars().flat_map(char::to_lowercase) {
+        match transliterate(c) {
+            Some(ascii) => slug.push_str(ascii),
+            None if c.is_ascii_alphanumeric() => slug.push(c),
+            None if !slug.is_empty() && !slug.ends_with('-') => slug.push('-'),
+            None => {}
+        }
+    }
+    slug.truncate(MAX_SLUG_LEN);
+    let slug = slug.trim_matches('-');
+    if slug.is_empty() {
+        "project".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// Check that a value could have been produced by [`slugify`]
+pub fn is_valid_slug(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= MAX_SLUG_LEN
+        && !value.starts_with('-')
+        && !value.ends_with('-')
+        && !value.contains("--")
+        && value.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// Normalize a person's name for comparison
+///
+/// Lowercases, drops accents the way [`slugify`] does, and collapses
+/// punctuation and runs of whitespace into single spaces, so `José  O'Neil`
+/// becomes `jose o neil`.
+pub fn normalize_name(name: &str) -> String {
+    let mut normalized = String::new();
+    for c in name.chars().flat_map(char::to_lowercase) {
+        match transliterate(c) {
+            Some(ascii) => normalized.push_str(ascii),
+            None if c.is_alphanumeric() => normalized.push(c),
+            None if !normalized.is_empty() && !normalized.ends_with(' ') => normalized.push(' '),
+            None => {}
+        }
+    }
+    normalized.trim_end().to_string()
+}
+
+/// How alike two names are, from 0.0 (nothing in common) to 1.0 (the same)
+///
+/// Compares the [normalized](normalize_name) names with the Jaro-Winkler
+/// measure, which tolerates small typos and transposed letters and gives
+/// extra weight to a shared beginning.
+pub fn name_similarity(a: &str, b: &str) -> f64 {
+    jaro_winkler(&normalize_name(a), &normalize_name(b))
+}
+
+/// Jaro-Winkler similarity of two strings
+fn jaro_winkler(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    // Characters match if they are equal and not too far apart
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut a_matched = vec![false; a.len()];
+    let mut b_matched = vec![false; b.len()];
+    let mut matches = 0;
+    for (i, ca) in a.iter().enumerate() {
+        let start = i.saturating_sub(window);
+        let end = (i + window + 1).min(b.len());
+        for j in start..end {
+            if !b_matched[j] && b[j] == *ca {
+                a_matched[i] = true;
+                b_matched[j] = true;
+                matches += 1;
+                break;
+            }
+        }
+    }
+    if matches == 0 {
+        return 0.0;
+    }
+
+    // Half the number of matched characters that appear in a different order
+    let a_order = a.iter().zip(&a_matched).filter(|(_, m)| **m).map(|(c, _)| c);
+    let b_order = b.iter().zip(&b_matched).filter(|(_, m)| **m).map(|(c, _)| c);
+    let transpositions = a_order.zip(b_order).filter(|(x, y)| x != y).count() / 2;
+
+    let m = matches as f64;
+    let jaro = (m / a.len() as f64 + m / b.len() as f64 + (m - transpositions as f64) / m) / 3.0;
+
+    let prefix = a.iter().zip(&b).take(4).take_while(|(x, y)| x == y).count();
+    jaro + prefix as f64 * 0.1 * (1.0 - jaro)
+}
+
+/// ASCII spelling of a lowercase accented Latin letter
+fn transliterate(c: char) -> Option<&'static str> {
+    Some(match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' | 'ě' => "e",
+        'ğ' => "g",
+        'ì' | 'í' | 'î' | 'ï' | 'ī' | 'į' | 'ı' => "i",
+        'ł' | 'ľ' => "l",
+        'ñ' | 'ń' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => "o",
+        'œ' => "oe",
+        'ř' => "r",
+        'ś' | 'š' | 'ş' => "s",
+        'ß' => "ss",
+        'ť' | 'ţ' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' | 'ų' => "u",
+        'ý' | 'ÿ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    })
+}
+
+/// Lock a mutex, waiting at most `timeout` for another holder to release it
+///
+/// Returns `None` if the lock is still held when the timeout runs out. A
+/// mutex poisoned by a panicking holder is recovered rather than treated as
+/// an error, since the data it guards (such as a database connection) is
+/// still usable.
+pub fn lock_with_timeout<T>(
+    mutex: &std::sync::Mutex<T>,
+    timeout: std::time::Duration,
+) -> Option<std::sync::MutexGuard<'_, T>> {
+    use std::sync::TryLockError;
+
+    let deadline = std::time::Instant::now() + timeout;
+    loop {
+        match mutex.try_lock() {
+            Ok(guard) => return Some(guard),
+            Err(TryLockError::Poisoned(poisoned)) => {
+                log::warn!("Recovering a lock poisoned by a panicked thread");
+                mutex.clear_poison();
+                return Some(poisoned.into_inner());
+            }
+            Err(TryLockError::WouldBlock) => {
+                let now = std::time::Instant::now();
+                if now >= deadline {
+                    return None;
+                }
+                std::thread::sleep((deadline - now).min(std::time::Duration::from_millis(5)));
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -22,4 +297,225 @@ mod tests {
         assert_ne!(id1, id2);
         assert!(!id1.is_empty());
     }
+
+    #[test]
+    fn test_dt_round_trip() {
+        let now = Utc::now();
+        assert_eq!(dt_from_db(&dt_to_db(now)).unwrap(), now);
+    }
+
+    #[test]
+    fn test_dt_from_db_formats() {
+        let expected = Utc.with_ymd_and_hms(2025, 3, 14, 15, 9, 26).unwrap();
+
+        for value in [
+            "2025-03-14T15:09:26+00:00",
+            "2025-03-14T15:09:26Z",
+            "2025-03-14T10:09:26-05:00",
+            "2025-03-14 15:09:26",
+            "2025-03-14T15:09:26",
+            "2025-03-14 15:09:26+00:00",
+        ] {
+            assert_eq!(dt_from_db(value).unwrap(), expected, "{}", value);
+        }
+
+        assert_eq!(
+            dt_from_db("2025-03-14 15:09:26.500").unwrap(),
+            expected + chrono::Duration::milliseconds(500)
+        );
+        assert_eq!(
+            dt_from_db("2025-03-14").unwrap(),
+            Utc.with_ymd_and_hms(2025, 3, 14, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_to_local() {
+        // 3pm UTC on March 31 is already April 1 in Tokyo
+        let due = Utc.with_ymd_and_hms(2025, 3, 31, 15, 0, 0).unwrap();
+        assert_eq!(format_local_date(due, Tz::UTC), "2025-03-31");
+        assert_eq!(format_local_date(due, chrono_tz::Asia::Tokyo), "2025-04-01");
+        assert_eq!(to_local(due, chrono_tz::Asia::Tokyo).to_rfc3339(), "2025-04-01T00:00:00+09:00");
+    }
+
+    #[test]
+    fn test_parse_user_date_tokyo() {
+        let tz = chrono_tz::Asia::Tokyo;
+        let due = parse_user_date("2025-03-31", tz).unwrap();
+        assert_eq!(due, Utc.with_ymd_and_hms(2025, 3, 30, 15, 0, 0).unwrap());
+        assert_eq!(format_local_date(due, tz), "2025-03-31");
+
+        // No DST: the offset is the same all year
+        let summer = parse_user_date("2025-07-31", tz).unwrap();
+        assert_eq!(summer, Utc.with_ymd_and_hms(2025, 7, 30, 15, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_parse_user_date_new_york() {
+        let tz = chrono_tz::America::New_York;
+
+        // EST (UTC-5) before the March 9, 2025 change, EDT (UTC-4) after it
+        let winter = parse_user_date("2025-03-08", tz).unwrap();
+        assert_eq!(winter, Utc.with_ymd_and_hms(2025, 3, 8, 5, 0, 0).unwrap());
+        let transition = parse_user_date("2025-03-09", tz).unwrap();
+        assert_eq!(transition, Utc.with_ymd_and_hms(2025, 3, 9, 5, 0, 0).unwrap());
+        let summer = parse_user_date("2025-03-10", tz).unwrap();
+        assert_eq!(summer, Utc.with_ymd_and_hms(2025, 3, 10, 4, 0, 0).unwrap());
+
+        for date in ["2025-03-08", "2025-03-09", "2025-03-10", "2025-11-02", "2025-11-03"] {
+            assert_eq!(format_local_date(parse_user_date(date, tz).unwrap(), tz), date);
+        }
+    }
+
+    #[test]
+    fn test_parse_user_date_keeps_explicit_offsets() {
+        let expected = Utc.with_ymd_and_hms(2025, 3, 14, 15, 9, 26).unwrap();
+        assert_eq!(parse_user_date("2025-03-14T15:09:26Z", chrono_tz::Asia::Tokyo).unwrap(), expected);
+        assert_eq!(
+            parse_user_date("2025-03-14T10:09:26-05:00", chrono_tz::Asia::Tokyo).unwrap(),
+            expected
+        );
+        assert!(parse_user_date("March 14", Tz::UTC).is_err());
+        assert!(parse_user_date("2025-02-30", Tz::UTC).is_err());
+    }
+
+    #[test]
+    fn test_dt_from_db_invalid() {
+        assert!(dt_from_db("").is_err());
+        assert!(dt_from_db("not a date").is_err());
+        assert!(dt_from_db("2025-13-01 00:00:00").is_err());
+    }
+
+    #[test]
+    fn test_is_http_url() {
+        assert!(is_http_url("https://docs.example.com/design/123"));
+        assert!(is_http_url("http://wiki.internal/page?id=7"));
+        assert!(!is_http_url("ftp://files.example.com/doc.pdf"));
+        assert!(!is_http_url("javascript:alert(1)"));
+        assert!(!is_http_url("file:///etc/passwd"));
+        assert!(!is_http_url("docs.example.com/design"));
+    }
+
+    #[test]
+    fn test_email_domain_allowed() {
+        let allowed = vec!["company.com".to_string(), "Partner.org".to_string()];
+        assert!(email_domain_allowed("alice@company.com", &allowed));
+        assert!(email_domain_allowed("alice@Company.COM", &allowed));
+        // Subdomains match by suffix, but only on a label boundary
+        assert!(email_domain_allowed("alice@corp.company.com", &allowed));
+        assert!(email_domain_allowed("bob@eu.partner.org", &allowed));
+        assert!(!email_domain_allowed("mallory@notcompany.com", &allowed));
+        assert!(!email_domain_allowed("consultant@gmail.com", &allowed));
+        assert!(!email_domain_allowed("company.com", &allowed));
+        // An empty list allows everyone
+        assert!(email_domain_allowed("consultant@gmail.com", &[]));
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Apollo Migration"), "apollo-migration");
+        assert_eq!(slugify("  Q3: API -- v2.0!  "), "q3-api-v2-0");
+        assert_eq!(slugify("Crème Brûlée Straße"), "creme-brulee-strasse");
+        assert_eq!(slugify("Ærø Øresund"), "aero-oresund");
+        // Scripts that can't be transliterated are dropped, but the slug is never empty
+        assert_eq!(slugify("東京 Launch"), "launch");
+        assert_eq!(slugify("東京"), "project");
+        assert_eq!(slugify("---"), "project");
+        assert_eq!(slugify(""), "project");
+
+        let long = slugify(&"word ".repeat(30));
+        assert!(long.len() <= MAX_SLUG_LEN);
+        assert!(is_valid_slug(&long), "{}", long);
+    }
+
+    #[test]
+    fn test_is_valid_slug() {
+        assert!(is_valid_slug("apollo-migration"));
+        assert!(is_valid_slug("q3"));
+        assert!(!is_valid_slug(""));
+        assert!(!is_valid_slug("Apollo"));
+        assert!(!is_valid_slug("-apollo"));
+        assert!(!is_valid_slug("apollo--2"));
+        assert!(!is_valid_slug("apollo migration"));
+        assert!(!is_valid_slug("crème"));
+    }
+
+    #[test]
+    fn test_normalize_name() {
+        assert_eq!(normalize_name("José  O'Neil"), "jose o neil");
+        assert_eq!(normalize_name("  Zoë-Ann Smith. "), "zoe ann smith");
+        assert_eq!(normalize_name("東京 Tanaka"), "東京 tanaka");
+        assert_eq!(normalize_name("..."), "");
+    }
+
+    #[test]
+    fn test_name_similarity() {
+        assert_eq!(name_similarity("José García", "Jose Garcia"), 1.0);
+        assert_eq!(name_similarity("jane doe", "Jane  Doe"), 1.0);
+        // Transpositions and dropped letters still score high
+        assert!(name_similarity("John Smith", "Jhon Smith") > 0.9);
+        assert!(name_similarity("John Smith", "Jon Smith") > 0.9);
+        // Different people don't
+        assert!(name_similarity("John Smith", "Maria Lopez") < 0.6);
+        assert!(name_similarity("Alice Chen", "Bob Chen") < 0.9);
+        assert_eq!(name_similarity("", "Bob"), 0.0);
+        // The measure is symmetric
+        assert_eq!(
+            name_similarity("Martha Stewart", "Marhta Stewart"),
+            name_similarity("Marhta Stewart", "Martha Stewart")
+        );
+    }
+
+    #[test]
+    fn test_lock_with_timeout_held_lock() {
+        use std::sync::{mpsc, Arc, Mutex};
+        use std::time::{Duration, Instant};
+
+        let mutex = Arc::new(Mutex::new(0));
+        let (locked_tx, locked_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let holder = {
+            let mutex = Arc::clone(&mutex);
+            std::thread::spawn(move || {
+                let _guard = mutex.lock().unwrap();
+                locked_tx.send(()).unwrap();
+                release_rx.recv().unwrap();
+            })
+        };
+        locked_rx.recv().unwrap();
+
+        let started = Instant::now();
+        assert!(lock_with_timeout(&mutex, Duration::from_millis(100)).is_none());
+        let waited = started.elapsed();
+        assert!(waited >= Duration::from_millis(100));
+        assert!(waited < Duration::from_secs(2), "waited {:?}", waited);
+
+        release_tx.send(()).unwrap();
+        holder.join().unwrap();
+        assert!(lock_with_timeout(&mutex, Duration::from_millis(100)).is_some());
+    }
+
+    #[test]
+    fn test_lock_with_timeout_recovers_poisoned_lock() {
+        use std::sync::{Arc, Mutex};
+        use std::time::Duration;
+
+        let mutex = Arc::new(Mutex::new(1));
+        {
+            let mutex = Arc::clone(&mutex);
+            let result = std::thread::spawn(move || {
+                let mut guard = mutex.lock().unwrap();
+                *guard = 2;
+                panic!("poison the lock");
+            })
+            .join();
+            assert!(result.is_err());
+        }
+        assert!(mutex.is_poisoned());
+
+        let guard = lock_with_timeout(&mutex, Duration::from_millis(100)).unwrap();
+        assert_eq!(*guard, 2);
+        drop(guard);
+        assert!(!mutex.is_poisoned());
+    }
 }
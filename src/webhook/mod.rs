@@ -0,0 +1,552 @@
+// Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+//
+// SPDX-License-Identifier: MIT
+
+//! Webhook notifications for data changes
+//!
+//! Events are queued by a [`WebhookDispatcher`] and delivered in the
+//! background, one worker per configured endpoint. Each endpoint has a bounded
+//! queue: when it fills up (for example because the endpoint is down and
+//! deliveries are being retried) new events for that endpoint are dropped
+//! rather than blocking the write that produced them. Changes made inside a
+//! transaction someone else commits can hold their events back with
+//! [`WebhookDispatcher::hold`], so a rolled back change never sends any.
+
+use crate::config::WebhookConfig;
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Header carrying the HMAC-SHA256 signature of the request body
+pub const SIGNATURE_HEADER: &str = "X-Project-Tracker-Signature";
+
+/// Number of events that can be waiting for each endpoint
+pub const QUEUE_CAPACITY: usize = 100;
+
+/// Sent by `track webhooks test`
+pub const PING: &str = "ping";
+pub const PROJECT_CREATED: &str = "project.created";
+pub const PROJECT_UPDATED: &str = "project.updated";
+pub const PROJECT_DELETED: &str = "project.deleted";
+pub const MILESTONE_CREATED: &str = "milestone.created";
+pub const MILESTONE_UPDATED: &str = "milestone.updated";
+pub const MILESTONE_DELETED: &str = "milestone.deleted";
+/// Sent in addition to `milestone.updated` when the due date changes
+pub const MILESTONE_DUE_DATE_CHANGED: &str = "milestone.due_date_changed";
+
+/// Payload POSTed to webhook endpoints
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookEvent {
+    /// Event name (e.g., "milestone.updated")
+    pub event: String,
+
+    /// Kind of entity that changed ("project", "milestone")
+    pub entity_type: String,
+
+    /// ID of the entity that changed
+    pub entity_id: String,
+
+    /// When the change happened
+    pub timestamp: DateTime<Utc>,
+
+    /// Event details, usually the entity after the change
+    pub data: serde_json::Value,
+}
+
+impl WebhookEvent {
+    /// Create an event for an entity, serializing `data` as its details
+    pub fn new(
+        event: &str,
+        entity_type: &str,
+        entity_id: impl ToString,
+        data: impl Serialize,
+    ) -> Self {
+        Self {
+            event: event.to_string(),
+            entity_type: entity_type.to_string(),
+            entity_id: entity_id.to_string(),
+            timestamp: Utc::now(),
+            data: serde_json::to_value(data).unwrap_or(serde_json::Value::Null),
+        }
+    }
+}
+
+/// Compute the signature header value for a request body
+///
+/// The value is `sha256=` followed by the hex-encoded HMAC-SHA256 of the body,
+/// keyed with the endpoint's secret.
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Check whether an event name matches a configured event pattern
+///
+/// Patterns are an exact event name, `*` for every event, or a prefix such as
+/// `milestone.*`.
+pub fn event_matches(pattern: &str, event: &str) -> bool {
+    match pattern.strip_suffix(".*") {
+        Some(prefix) => event
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('.')),
+        None => pattern == "*" || pattern == event,
+    }
+}
+
+/// How failed deliveries are retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total number of delivery attempts, including the first
+    pub max_attempts: u32,
+
+    /// Delay before the first retry; doubled after each further failure
+    pub initial_backoff: Duration,
+
+    /// Upper bound for the delay between attempts
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that makes a single attempt
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Delay before retrying after the given failed attempt (1-based)
+    ///
+    /// Returns `None` once every attempt has been used.
+    pub fn delay_after(&self, attempt: u32) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        Some(
+            self.initial_backoff
+                .saturating_mul(factor)
+                .min(self.max_backoff),
+        )
+    }
+}
+
+/// POST a payload to an endpoint, retrying failures according to `policy`
+///
+/// Any non-2xx response counts as a failure. The body is signed when a secret
+/// is given.
+pub async fn deliver(
+    client: &reqwest::Client,
+    url: &str,
+    secret: Option<&str>,
+    body: &[u8],
+    policy: &RetryPolicy,
+) -> Result<()> {
+    let mut attempt = 1;
+    loop {
+        let mut request = client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_vec());
+        if let Some(secret) = secret {
+            request = request.header(SIGNATURE_HEADER, sign(secret, body));
+        }
+
+        let error = match request.send().await {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) => format!("HTTP {}", response.status()),
+            Err(e) => e.to_string(),
+        };
+
+        match policy.delay_after(attempt) {
+            Some(delay) => {
+                log::debug!(
+                    "Webhook delivery to {} failed (attempt {}): {}",
+                    url,
+                    attempt,
+                    error
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            None => bail!(
+                "Webhook delivery to {} failed after {} attempts: {}",
+                url,
+                attempt,
+                error
+            ),
+        }
+    }
+}
+
+/// A configured endpoint and the queue feeding its worker
+#[derive(Debug)]
+struct Endpoint {
+    events: Vec<String>,
+    sender: mpsc::Sender<WebhookEvent>,
+}
+
+impl Endpoint {
+    fn wants(&self, event: &str) -> bool {
+        self.events.is_empty()
+            || self
+                .events
+                .iter()
+                .any(|pattern| event_matches(pattern, event))
+    }
+}
+
+/// Queues webhook events for background delivery
+///
+/// Cloning is cheap; clones share the same endpoint queues. A dispatcher with
+/// no endpoints ignores every event.
+#[derive(Debug, Clone, Default)]
+pub struct WebhookDispatcher {
+    endpoints: Arc<Vec<Endpoint>>,
+    /// Where events go instead of the endpoints, for a dispatcher from [`Self::hold`]
+    held: Option<Arc<Mutex<Vec<WebhookEvent>>>>,
+}
+
+/// Events kept back by a dispatcher from [`WebhookDispatcher::hold`]
+///
+/// Dropping them without calling [`HeldEvents::release`] discards them.
+pub struct HeldEvents {
+    dispatcher: WebhookDispatcher,
+    events: Arc<Mutex<Vec<WebhookEvent>>>,
+}
+
+impl HeldEvents {
+    /// Send the kept events on, in the order they were emitted
+    pub fn release(self) {
+        let events = std::mem::take(&mut *self.events.lock().unwrap_or_else(|e| e.into_inner()));
+        for event in events {
+            self.dispatcher.emit(event);
+        }
+    }
+}
+
+impl WebhookDispatcher {
+    /// Create a dispatcher and the delivery workers for its endpoints
+    ///
+    /// Each worker must be spawned on an async runtime; events are only
+    /// delivered while it runs.
+    pub fn new(
+        configs: &[WebhookConfig],
+    ) -> (Self, Vec<impl Future<Output = ()> + Send + 'static>) {
+        Self::with_policy(configs, RetryPolicy::default())
+    }
+
+    /// Create a dispatcher whose workers retry with the given policy
+    pub fn with_policy(
+        configs: &[WebhookConfig],
+        policy: RetryPolicy,
+    ) -> (Self, Vec<impl Future<Output = ()> + Send + 'static>) {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+
+        let mut endpoints = Vec::new();
+        let mut workers = Vec::new();
+        for config in configs {
+            let (sender, receiver) = mpsc::channel(QUEUE_CAPACITY);
+            endpoints.push(Endpoint {
+                events: config.events.clone(),
+                sender,
+            });
+            workers.push(run_worker(client.clone(), config.clone(), policy, receiver));
+        }
+
+        (
+            Self {
+                endpoints: Arc::new(endpoints),
+                held: None,
+            },
+            workers,
+        )
+    }
+
+    /// Create a dispatcher and spawn its workers on the current tokio runtime
+    ///
+    /// Must be called from within a tokio runtime when any webhooks are configured.
+    pub fn spawn(configs: &[WebhookConfig]) -> Self {
+        let (dispatcher, workers) = Self::new(configs);
+        for worker in workers {
+            tokio::spawn(worker);
+        }
+        dispatcher
+    }
+
+    /// Create a dispatcher with a single endpoint whose queue is returned
+    /// instead of being delivered
+    #[cfg(test)]
+    pub(crate) fn capture() -> (Self, mpsc::Receiver<WebhookEvent>) {
+        let (sender, receiver) = mpsc::channel(QUEUE_CAPACITY);
+        let endpoints = vec![Endpoint {
+            events: Vec::new(),
+            sender,
+        }];
+        (
+            Self {
+                endpoints: Arc::new(endpoints),
+                held: None,
+            },
+            receiver,
+        )
+    }
+
+    /// A dispatcher that keeps the events emitted through it until they are released
+    ///
+    /// For changes made in a transaction the caller commits: release the
+    /// events once the commit succeeds, or drop them if it doesn't.
+    pub fn hold(&self) -> (Self, HeldEvents) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let held = Self {
+            endpoints: self.endpoints.clone(),
+            held: Some(events.clone()),
+        };
+        (
+            held,
+            HeldEvents {
+                dispatcher: self.clone(),
+                events,
+            },
+        )
+    }
+
+    /// Queue an event for every endpoint subscribed to it
+    ///
+    /// Never blocks: if an endpoint's queue is full the event is dropped for
+    /// that endpoint and a warning is logged.
+    pub fn emit(&self, event: WebhookEvent) {
+        if let Some(held) = &self.held {
+            held.lock().unwrap_or_else(|e| e.into_inner()).push(event);
+            return;
+        }
+        for endpoint in self.endpoints.iter().filter(|e| e.wants(&event.event)) {
+            if let Err(e) = endpoint.sender.try_send(event.clone()) {
+                log::warn!(
+                    "Dropping webhook event {} for {}: {}",
+                    event.event,
+                    event.entity_id,
+                    e
+                );
+            }
+        }
+    }
+}
+
+/// Deliver queued events to one endpoint until the dispatcher is dropped
+async fn run_worker(
+    client: reqwest::Client,
+    config: WebhookConfig,
+    policy: RetryPolicy,
+    mut receiver: mpsc::Receiver<WebhookEvent>,
+) {
+    while let Some(event) = receiver.recv().await {
+        let body = match serde_json::to_vec(&event) {
+            Ok(body) => body,
+            Err(e) => {
+                log::error!("Failed to serialize webhook event {}: {}", event.event, e);
+                continue;
+            }
+        };
+
+        match deliver(
+            &client,
+            &config.url,
+            config.secret.as_deref(),
+            &body,
+            &policy,
+        )
+        .await
+        {
+            Ok(()) => log::debug!("Delivered webhook event {} to {}", event.event, config.url),
+            Err(e) => log::error!("{}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fast_policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+        }
+    }
+
+    #[test]
+    fn test_sign() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_event_matches() {
+        assert!(event_matches("*", "milestone.updated"));
+        assert!(event_matches("milestone.updated", "milestone.updated"));
+        assert!(event_matches("milestone.*", "milestone.due_date_changed"));
+        assert!(!event_matches("milestone.*", "milestones.updated"));
+        assert!(!event_matches("milestone.*", "project.updated"));
+        assert!(!event_matches("milestone.created", "milestone.updated"));
+    }
+
+    #[test]
+    fn test_retry_policy_backoff() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(350),
+        };
+        assert_eq!(policy.delay_after(1), Some(Duration::from_millis(100)));
+        assert_eq!(policy.delay_after(2), Some(Duration::from_millis(200)));
+        assert_eq!(policy.delay_after(3), Some(Duration::from_millis(350)));
+        assert_eq!(policy.delay_after(4), Some(Duration::from_millis(350)));
+        assert_eq!(policy.delay_after(5), None);
+
+        assert_eq!(RetryPolicy::none().delay_after(1), None);
+    }
+
+    #[tokio::test]
+    async fn test_deliver_signs_body() {
+        let mut server = mockito::Server::new_async().await;
+        let body = br#"{"event":"ping"}"#;
+        let mock = server
+            .mock("POST", "/hook")
+            .match_header(SIGNATURE_HEADER, sign("secret", body).as_str())
+            .match_header("content-type", "application/json")
+            .match_body(mockito::Matcher::Exact(
+                String::from_utf8(body.to_vec()).unwrap(),
+            ))
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let url = format!("{}/hook", server.url());
+        deliver(&client, &url, Some("secret"), body, &fast_policy(3))
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_deliver_retries_until_attempts_exhausted() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/hook")
+            .with_status(500)
+            .expect(3)
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let url = format!("{}/hook", server.url());
+        let result = deliver(&client, &url, None, b"{}", &fast_policy(3)).await;
+
+        assert!(result.is_err());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_dispatcher_delivers_subscribed_events() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/hook")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"event":"milestone.due_date_changed","entity_type":"milestone"}"#.to_string(),
+            ))
+            .with_status(204)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let config = WebhookConfig {
+            url: format!("{}/hook", server.url()),
+            events: vec!["milestone.due_date_changed".to_string()],
+            secret: None,
+        };
+        let (dispatcher, workers) = WebhookDispatcher::with_policy(&[config], fast_policy(1));
+        let handles: Vec<_> = workers.into_iter().map(tokio::spawn).collect();
+
+        dispatcher.emit(WebhookEvent::new(
+            PROJECT_UPDATED,
+            "project",
+            "p1",
+            serde_json::json!({}),
+        ));
+        dispatcher.emit(WebhookEvent::new(
+            MILESTONE_DUE_DATE_CHANGED,
+            "milestone",
+            "m1",
+            serde_json::json!({}),
+        ));
+
+        // Dropping the dispatcher closes the queues, so the workers finish
+        // once everything queued has been delivered
+        drop(dispatcher);
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_held_events() {
+        let (dispatcher, mut receiver) = WebhookDispatcher::capture();
+        let event = |id: &str| WebhookEvent::new(PING, "webhook", id, serde_json::Value::Null);
+
+        let (held, events) = dispatcher.hold();
+        held.emit(event("first"));
+        held.emit(event("second"));
+        assert!(receiver.try_recv().is_err());
+        events.release();
+        assert_eq!(receiver.try_recv().unwrap().entity_id, "first");
+        assert_eq!(receiver.try_recv().unwrap().entity_id, "second");
+
+        // Dropped events are never sent
+        let (held, events) = dispatcher.hold();
+        held.emit(event("dropped"));
+        drop(events);
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_emit_without_endpoints_is_noop() {
+        let dispatcher = WebhookDispatcher::default();
+        dispatcher.emit(WebhookEvent::new(
+            PING,
+            "webhook",
+            "test",
+            serde_json::Value::Null,
+        ));
+    }
+}
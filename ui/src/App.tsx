@@ -6,13 +6,20 @@
 
 import { useState, useEffect } from 'react';
 import {
+  Alert,
   Layout,
   Typography,
   Card,
   Menu,
+  Select,
+  Space,
+  Switch,
+  Tag,
+  Tooltip,
+  message,
   theme,
 } from 'antd';
-import { invoke } from '@tauri-apps/api/core';
+import { CommandError, invoke } from './services/invoke';
 import {
   ProjectOutlined,
   UserOutlined,
@@ -21,6 +28,11 @@ import {
   TeamOutlined,
   FileTextOutlined,
   InfoCircleOutlined,
+  InboxOutlined,
+  AuditOutlined,
+  CalendarOutlined,
+  AimOutlined,
+  LockOutlined,
 } from '@ant-design/icons';
 import { ProjectList } from './components/ProjectList';
 import { ProjectDetail } from './components/ProjectDetail';
@@ -32,38 +44,106 @@ import { TeamList } from './components/TeamList';
 import { TeamDetail } from './components/TeamDetail';
 import { TeamForm } from './components/TeamForm';
 import { DeadlinesList } from './components/DeadlinesList';
+import { InboxPanel } from './components/InboxPanel';
 import { Resources } from './components/Resources';
 import { About } from './components/About';
-import type { Project, Person, Team, Milestone } from './types';
+import { DataHealth } from './components/DataHealth';
+import { QuarterPlanView } from './components/QuarterPlanView';
+import { DigestPreview } from './components/DigestPreview';
+import { TodayPanel } from './components/TodayPanel';
+import { AvailabilityForecastView } from './components/AvailabilityForecastView';
+import { UsersView } from './components/UsersView';
+import { ProjectService } from './services/projectService';
+import type { Project, Person, Team, Milestone, McpStatus } from './types';
 
 const { Header, Content, Sider } = Layout;
 const { Title } = Typography;
 
 type ViewMode = 'list' | 'detail' | 'create' | 'edit';
 
+// How often to check whether the database is busy
+const DB_PING_INTERVAL_MS = 3000;
+
 function App() {
   const [selectedMenu, setSelectedMenu] = useState('1');
   const [viewMode, setViewMode] = useState<ViewMode>('list');
   const [selectedProject, setSelectedProject] = useState<Project | null>(null);
   const [selectedPerson, setSelectedPerson] = useState<Person | null>(null);
   const [selectedTeam, setSelectedTeam] = useState<Team | null>(null);
-  const [mcpPort, setMcpPort] = useState<number | null>(null);
+  const [mcpStatus, setMcpStatus] = useState<McpStatus | null>(null);
+  const [mcpToggling, setMcpToggling] = useState(false);
+  const [dbBusy, setDbBusy] = useState(false);
+  const [workspaces, setWorkspaces] = useState<string[]>([]);
+  const [workspace, setWorkspace] = useState<string | null>(null);
+  const [syncWarning, setSyncWarning] = useState<string | null>(null);
 
   const {
     token: { colorBgContainer },
   } = theme.useToken();
 
-  // Fetch MCP server port on mount
+  // Fetch the MCP server status on mount
   useEffect(() => {
-    const fetchMcpPort = async () => {
+    ProjectService.getMcpStatus()
+      .then(setMcpStatus)
+      .catch((error) => console.error('Failed to fetch MCP status:', error));
+  }, []);
+
+  const handleToggleMcp = async (enabled: boolean) => {
+    setMcpToggling(true);
+    try {
+      setMcpStatus(enabled ? await ProjectService.startMcpServer() : await ProjectService.stopMcpServer());
+    } catch (error) {
+      message.error('Failed to start the MCP server: ' + error);
+    } finally {
+      setMcpToggling(false);
+    }
+  };
+
+  // Fetch the workspaces on mount
+  useEffect(() => {
+    const fetchWorkspaces = async () => {
       try {
-        const port = await invoke<number>('get_mcp_port');
-        setMcpPort(port);
+        setWorkspaces(await ProjectService.listWorkspaces());
+        setWorkspace(await ProjectService.getActiveWorkspace());
       } catch (error) {
-        console.error('Failed to fetch MCP port:', error);
+        console.error('Failed to fetch workspaces:', error);
       }
     };
-    fetchMcpPort();
+    fetchWorkspaces();
+  }, []);
+
+  // Each workspace has its own data directory, which may be in a synced folder
+  useEffect(() => {
+    ProjectService.getSyncWarning()
+      .then(setSyncWarning)
+      .catch((error) => console.error('Failed to check the data directory:', error));
+  }, [workspace]);
+
+  const handleSwitchWorkspace = async (name: string) => {
+    try {
+      setWorkspace(await ProjectService.switchWorkspace(name));
+      setViewMode('list');
+      setSelectedProject(null);
+      setSelectedPerson(null);
+      setSelectedTeam(null);
+    } catch (error) {
+      message.error('Failed to switch workspace: ' + error);
+    }
+  };
+
+  // Poll the database so a long-running command shows up as busy
+  useEffect(() => {
+    const ping = async () => {
+      try {
+        await invoke('db_ping');
+        setDbBusy(false);
+      } catch (error) {
+        setDbBusy(error instanceof CommandError && error.code === 'DB_BUSY');
+      }
+    };
+    ping();
+    const timer = setInterval(ping, DB_PING_INTERVAL_MS);
+    return () => clearInterval(timer);
   }, []);
 
   const handleViewProject = (project: Project) => {
@@ -166,6 +246,18 @@ function App() {
     setViewMode('detail');
   };
 
+  const handleViewPersonFromDataHealth = (person: Person) => {
+    setSelectedMenu('2');
+    setSelectedPerson(person);
+    setViewMode('detail');
+  };
+
+  const handleViewTeamFromDataHealth = (team: Team) => {
+    setSelectedMenu('3');
+    setSelectedTeam(team);
+    setViewMode('detail');
+  };
+
   const handleViewMilestoneFromDeadlines = async (milestone: Milestone) => {
     // Find the project for this milestone and navigate to it
     const { ProjectService } = await import('./services/projectService');
@@ -182,12 +274,17 @@ function App() {
   };
 
   const menuItems = [
+    { key: '11', icon: <AimOutlined />, label: 'Today' },
     { key: '1', icon: <ProjectOutlined />, label: 'Projects' },
     { key: '2', icon: <UserOutlined />, label: 'People' },
     { key: '3', icon: <UsergroupAddOutlined />, label: 'Teams' },
     { key: '4', icon: <ClockCircleOutlined />, label: 'Deadlines' },
+    { key: '10', icon: <CalendarOutlined />, label: 'Planning' },
+    { key: '8', icon: <InboxOutlined />, label: 'Inbox' },
     { key: '5', icon: <TeamOutlined />, label: 'Resources' },
     { key: '6', icon: <FileTextOutlined />, label: 'Reports' },
+    { key: '9', icon: <AuditOutlined />, label: 'Data health' },
+    { key: '12', icon: <LockOutlined />, label: 'Users' },
     { key: '7', icon: <InfoCircleOutlined />, label: 'About' },
   ];
 
@@ -242,6 +339,7 @@ function App() {
               person={selectedPerson}
               onEdit={handleEditFromDetail}
               onBack={handleBackToPersonList}
+              onEmailChanged={setSelectedPerson}
             />
           ) : null;
 
@@ -326,11 +424,52 @@ function App() {
       );
     }
 
+    // Today section
+    if (selectedMenu === '11') {
+      return <TodayPanel onViewProject={handleViewProjectFromDeadlines} />;
+    }
+
+    // Inbox section
+    if (selectedMenu === '8') {
+      return <InboxPanel />;
+    }
+
     // Resources section
     if (selectedMenu === '5') {
       return <Resources />;
     }
 
+    // Planning section
+    if (selectedMenu === '10') {
+      return (
+        <Space direction="vertical" size="large" style={{ width: '100%' }}>
+          <QuarterPlanView onViewProject={handleViewProjectFromDeadlines} />
+          <AvailabilityForecastView />
+        </Space>
+      );
+    }
+
+    // Data health section
+    if (selectedMenu === '9') {
+      return (
+        <DataHealth
+          onViewProject={handleViewProjectFromDeadlines}
+          onViewPerson={handleViewPersonFromDataHealth}
+          onViewTeam={handleViewTeamFromDataHealth}
+        />
+      );
+    }
+
+    // Reports section
+    if (selectedMenu === '6') {
+      return <DigestPreview />;
+    }
+
+    // Users section
+    if (selectedMenu === '12') {
+      return <UsersView />;
+    }
+
     // About section
     if (selectedMenu === '7') {
       return <About />;
@@ -350,11 +489,32 @@ function App() {
         <Title level={3} style={{ color: 'white', margin: 0 }}>
           Project Tracker
         </Title>
-        {mcpPort && (
-          <Typography.Text style={{ color: 'white' }}>
-            MCP: http://127.0.0.1:{mcpPort}/sse
-          </Typography.Text>
-        )}
+        <Space>
+          {dbBusy && <Tag color="warning">Database busy</Tag>}
+          {workspaces.length > 1 && workspace && (
+            <Select
+              value={workspace}
+              onChange={handleSwitchWorkspace}
+              options={workspaces.map((name) => ({ value: name, label: name }))}
+              style={{ minWidth: 140 }}
+            />
+          )}
+          {mcpStatus && (
+            <Tooltip title={mcpStatus.running ? `${mcpStatus.active_sessions} connected session(s)` : 'MCP server is off'}>
+              <Space>
+                <Switch
+                  size="small"
+                  checked={mcpStatus.running}
+                  loading={mcpToggling}
+                  onChange={handleToggleMcp}
+                />
+                <Typography.Text style={{ color: 'white' }}>
+                  {mcpStatus.running ? `MCP: http://${mcpStatus.address}/sse` : 'MCP off'}
+                </Typography.Text>
+              </Space>
+            </Tooltip>
+          )}
+        </Space>
       </Header>
       <Layout>
         <Sider width={200} style={{ background: colorBgContainer }}>
@@ -373,7 +533,10 @@ function App() {
           />
         </Sider>
         <Layout style={{ padding: '24px' }}>
-          <Content>
+          <Content key={workspace ?? ''}>
+            {syncWarning && (
+              <Alert type="warning" showIcon message="Data directory is in a synced folder" description={syncWarning} style={{ marginBottom: 16 }} />
+            )}
             {renderContent()}
           </Content>
         </Layout>
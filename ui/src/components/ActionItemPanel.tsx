@@ -0,0 +1,232 @@
+/**
+ * Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+import { useState, useEffect } from 'react';
+import { Card, Table, Button, Checkbox, Modal, Form, Input, Select, DatePicker, message } from 'antd';
+import { PlusOutlined, DeleteOutlined } from '@ant-design/icons';
+import type { ColumnsType } from 'antd/es/table';
+import { ProjectService } from '../services/projectService';
+import { PersonSelector } from './PersonSelector';
+import type { ActionItem, Milestone, Person } from '../types';
+
+interface ActionItemPanelProps {
+  projectId: string;
+  milestones: Milestone[];
+  people: Person[];
+  timeZone: string;
+}
+
+export const ActionItemPanel: React.FC<ActionItemPanelProps> = ({ projectId, milestones, people, timeZone }) => {
+  const [items, setItems] = useState<ActionItem[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [showModal, setShowModal] = useState(false);
+  const [form] = Form.useForm();
+
+  useEffect(() => {
+    loadItems();
+  }, [projectId]);
+
+  const loadItems = async () => {
+    setLoading(true);
+    try {
+      setItems(await ProjectService.listActionItems(projectId));
+    } catch (error) {
+      message.error('Failed to load action items: ' + error);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const handleToggle = async (item: ActionItem, completed: boolean) => {
+    try {
+      if (completed) {
+        await ProjectService.completeActionItem(item.id);
+      } else {
+        await ProjectService.updateActionItem({ ...item, status: 'open', updated_at: new Date().toISOString() });
+      }
+      await loadItems();
+    } catch (error) {
+      message.error('Failed to update action item: ' + error);
+    }
+  };
+
+  const handleDelete = (item: ActionItem) => {
+    Modal.confirm({
+      title: 'Delete Action Item',
+      content: `Are you sure you want to delete "${item.description}"?`,
+      okText: 'Delete',
+      okType: 'danger',
+      onOk: async () => {
+        try {
+          await ProjectService.deleteActionItem(item.id);
+          message.success('Action item deleted successfully');
+          await loadItems();
+        } catch (error) {
+          message.error('Failed to delete action item: ' + error);
+        }
+      },
+    });
+  };
+
+  const handleSubmit = async (values: any) => {
+    const now = new Date().toISOString();
+    try {
+      await ProjectService.createActionItem({
+        id: crypto.randomUUID(),
+        project_id: projectId,
+        milestone_id: values.milestone_id || undefined,
+        assignee_email: values.assignee_email,
+        description: values.description,
+        due_date: values.due_date ? values.due_date.toISOString() : undefined,
+        status: 'open',
+        created_at: now,
+        updated_at: now,
+      });
+      message.success('Action item added successfully');
+      setShowModal(false);
+      form.resetFields();
+      await loadItems();
+    } catch (error) {
+      message.error('Failed to add action item: ' + error);
+    }
+  };
+
+  const formatDate = (dateString?: string) => {
+    if (!dateString) return '-';
+    return new Date(dateString).toLocaleDateString(undefined, { timeZone });
+  };
+
+  const getPersonName = (email: string) => people.find(p => p.email === email)?.name || email;
+
+  const getMilestoneName = (id?: string) => (id ? milestones.find(m => m.id === id)?.name || '-' : '-');
+
+  const columns: ColumnsType<ActionItem> = [
+    {
+      title: 'Done',
+      key: 'done',
+      width: 60,
+      render: (_, record) => (
+        <Checkbox
+          checked={record.status === 'completed'}
+          onChange={(e) => handleToggle(record, e.target.checked)}
+        />
+      ),
+    },
+    {
+      title: 'Action',
+      dataIndex: 'description',
+      key: 'description',
+      ellipsis: true,
+    },
+    {
+      title: 'Assignee',
+      dataIndex: 'assignee_email',
+      key: 'assignee_email',
+      render: getPersonName,
+    },
+    {
+      title: 'Milestone',
+      dataIndex: 'milestone_id',
+      key: 'milestone_id',
+      render: getMilestoneName,
+    },
+    {
+      title: 'Due',
+      dataIndex: 'due_date',
+      key: 'due_date',
+      width: 120,
+      render: formatDate,
+    },
+    {
+      title: 'Completed',
+      dataIndex: 'completed_at',
+      key: 'completed_at',
+      width: 120,
+      render: formatDate,
+    },
+    {
+      title: 'Actions',
+      key: 'actions',
+      width: 100,
+      render: (_, record) => (
+        <Button
+          type="link"
+          danger
+          size="small"
+          icon={<DeleteOutlined />}
+          onClick={() => handleDelete(record)}
+        >
+          Delete
+        </Button>
+      ),
+    },
+  ];
+
+  return (
+    <Card
+      title="Action Items"
+      style={{ marginBottom: 16 }}
+      extra={
+        <Button
+          type="primary"
+          size="small"
+          icon={<PlusOutlined />}
+          onClick={() => setShowModal(true)}
+        >
+          Add Action Item
+        </Button>
+      }
+    >
+      <Table
+        columns={columns}
+        dataSource={items}
+        rowKey="id"
+        loading={loading}
+        locale={{ emptyText: 'No action items yet' }}
+        pagination={{ pageSize: 10 }}
+      />
+
+      <Modal
+        title="Add Action Item"
+        open={showModal}
+        onCancel={() => setShowModal(false)}
+        onOk={() => form.submit()}
+        okText="Add"
+        destroyOnClose
+      >
+        <Form form={form} layout="vertical" onFinish={handleSubmit}>
+          <Form.Item
+            name="description"
+            label="Action"
+            rules={[{ required: true, message: 'Please describe the action item' }]}
+          >
+            <Input placeholder="What needs to be done" />
+          </Form.Item>
+
+          <Form.Item
+            name="assignee_email"
+            label="Assignee"
+            rules={[{ required: true, message: 'Please select an assignee' }]}
+          >
+            <PersonSelector placeholder="Who will do it" />
+          </Form.Item>
+
+          <Form.Item name="milestone_id" label="Milestone">
+            <Select
+              allowClear
+              placeholder="Select a milestone"
+              options={milestones.map(m => ({ value: m.id, label: m.name }))}
+            />
+          </Form.Item>
+
+          <Form.Item name="due_date" label="Due Date">
+            <DatePicker style={{ width: '100%' }} />
+          </Form.Item>
+        </Form>
+      </Modal>
+    </Card>
+  );
+};
@@ -0,0 +1,132 @@
+/**
+ * Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+import { useState, useEffect } from 'react';
+import { Card, Collapse, Empty, InputNumber, Space, Spin, Table, Tag, Typography, message } from 'antd';
+import type { ColumnsType } from 'antd/es/table';
+import { PersonService } from '../services/personService';
+import { ProjectService } from '../services/projectService';
+import type { AvailabilityForecast, AvailabilityStatus, PersonAvailability } from '../types';
+
+const { Text, Title } = Typography;
+
+const STATUS_TAGS: Record<AvailabilityStatus, { label: string; color: string }> = {
+  unassigned: { label: 'Unassigned', color: 'green' },
+  frees_up: { label: 'Frees up', color: 'blue' },
+  busy: { label: 'Busy', color: 'orange' },
+  unknown: { label: 'Unknown', color: 'default' },
+};
+
+export const AvailabilityForecastView: React.FC = () => {
+  const [horizonDays, setHorizonDays] = useState(90);
+  const [timeZone, setTimeZone] = useState<string>('UTC');
+  const [forecast, setForecast] = useState<AvailabilityForecast | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  useEffect(() => {
+    ProjectService.getTimezone()
+      .then(setTimeZone)
+      .catch((error) => console.error('Failed to load time zone:', error));
+  }, []);
+
+  useEffect(() => {
+    const loadForecast = async () => {
+      setLoading(true);
+      try {
+        setForecast(await PersonService.getAvailabilityForecast(horizonDays));
+      } catch (error) {
+        message.error('Failed to load availability forecast: ' + error);
+      } finally {
+        setLoading(false);
+      }
+    };
+    loadForecast();
+  }, [horizonDays]);
+
+  const formatDate = (dateString?: string) =>
+    dateString ? new Date(dateString).toLocaleDateString(undefined, { timeZone }) : '-';
+
+  const columns: ColumnsType<PersonAvailability> = [
+    {
+      title: 'Name',
+      dataIndex: 'name',
+      key: 'name',
+      render: (name: string, person: PersonAvailability) => (
+        <Space direction="vertical" size={0}>
+          <Text>{name}</Text>
+          <Text type="secondary">{person.email}</Text>
+        </Space>
+      ),
+    },
+    {
+      title: 'Status',
+      dataIndex: 'status',
+      key: 'status',
+      width: 140,
+      render: (status: AvailabilityStatus) => <Tag color={STATUS_TAGS[status].color}>{STATUS_TAGS[status].label}</Tag>,
+    },
+    {
+      title: 'Free on',
+      dataIndex: 'free_on',
+      key: 'free_on',
+      width: 140,
+      render: (freeOn?: string) => formatDate(freeOn),
+    },
+    {
+      title: 'Open milestones',
+      key: 'milestones',
+      width: 160,
+      render: (_, person) =>
+        person.undated_milestones > 0
+          ? `${person.open_milestones} (${person.undated_milestones} undated)`
+          : person.open_milestones,
+    },
+  ];
+
+  return (
+    <Card
+      title={<Title level={4}>Availability</Title>}
+      extra={
+        <Space>
+          <Text>Next</Text>
+          <InputNumber value={horizonDays} min={1} max={3650} onChange={(value) => value && setHorizonDays(value)} />
+          <Text>days</Text>
+        </Space>
+      }
+    >
+      {loading || !forecast ? (
+        <Spin />
+      ) : forecast.teams.length === 0 ? (
+        <Empty description="No active people" />
+      ) : (
+        <>
+          <Space style={{ marginBottom: 16 }} wrap>
+            <Tag color={STATUS_TAGS.unassigned.color}>{forecast.unassigned} unassigned</Tag>
+            <Tag color={STATUS_TAGS.frees_up.color}>{forecast.freeing_up} free up by {formatDate(forecast.until)}</Tag>
+            <Tag color={STATUS_TAGS.busy.color}>{forecast.busy} busy</Tag>
+            <Tag color={STATUS_TAGS.unknown.color}>{forecast.unknown} unknown</Tag>
+          </Space>
+          <Collapse
+            defaultActiveKey={forecast.teams.map((t) => t.team ?? '')}
+            items={forecast.teams.map((t) => ({
+              key: t.team ?? '',
+              label: `${t.team ?? 'No team'} (${t.people.length})`,
+              children: (
+                <Table
+                  columns={columns}
+                  dataSource={t.people}
+                  rowKey="email"
+                  pagination={false}
+                  size="small"
+                />
+              ),
+            }))}
+          />
+        </>
+      )}
+    </Card>
+  );
+};
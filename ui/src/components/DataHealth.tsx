@@ -0,0 +1,199 @@
+/**
+ * Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+import { useState, useEffect } from 'react';
+import { Card, Empty, List, Space, Spin, Tag, Typography, message } from 'antd';
+import { ProjectService } from '../services/projectService';
+import { PersonService } from '../services/personService';
+import { TeamService } from '../services/teamService';
+import type { HygieneReport, Person, Project, Team } from '../types';
+
+const { Link, Title } = Typography;
+
+interface DataHealthProps {
+  onViewProject: (project: Project) => void;
+  onViewPerson: (person: Person) => void;
+  onViewTeam: (team: Team) => void;
+}
+
+interface GapItem {
+  key: string;
+  label: string;
+  open: () => void;
+}
+
+export const DataHealth: React.FC<DataHealthProps> = ({ onViewProject, onViewPerson, onViewTeam }) => {
+  const [report, setReport] = useState<HygieneReport | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  useEffect(() => {
+    loadReport();
+  }, []);
+
+  const loadReport = async () => {
+    setLoading(true);
+    try {
+      setReport(await ProjectService.getHygieneReport());
+    } catch (error) {
+      message.error('Failed to load data health report: ' + error);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const openProject = async (id: string) => {
+    const project = await ProjectService.getProject(id);
+    if (project) {
+      onViewProject(project);
+    }
+  };
+
+  const openPerson = async (email: string) => {
+    const person = await PersonService.getPerson(email);
+    if (person) {
+      onViewPerson(person);
+    }
+  };
+
+  const openTeam = async (name: string) => {
+    const team = await TeamService.getTeam(name);
+    if (team) {
+      onViewTeam(team);
+    }
+  };
+
+  if (loading || !report) {
+    return <Spin />;
+  }
+
+  const sections: { title: string; items: GapItem[] }[] = [
+    {
+      title: 'People without a manager',
+      items: report.people_missing_manager.map((p) => ({
+        key: p.email,
+        label: `${p.name} <${p.email}>`,
+        open: () => openPerson(p.email),
+      })),
+    },
+    {
+      title: 'People without a team',
+      items: report.people_missing_team.map((p) => ({
+        key: p.email,
+        label: `${p.name} <${p.email}>`,
+        open: () => openPerson(p.email),
+      })),
+    },
+    {
+      title: 'Teams without a manager',
+      items: report.teams_missing_manager.map((t) => ({
+        key: t.name,
+        label: t.name,
+        open: () => openTeam(t.name),
+      })),
+    },
+    {
+      title: 'Projects without a technical lead',
+      items: report.projects_missing_technical_lead.map((p) => ({
+        key: p.id,
+        label: p.name,
+        open: () => openProject(p.id),
+      })),
+    },
+    {
+      title: 'Projects without a requirements owner',
+      items: report.projects_missing_requirements_owner.map((p) => ({
+        key: p.id,
+        label: p.name,
+        open: () => openProject(p.id),
+      })),
+    },
+    {
+      title: 'Projects without a due date',
+      items: report.projects_missing_due_date.map((p) => ({
+        key: p.id,
+        label: p.name,
+        open: () => openProject(p.id),
+      })),
+    },
+    {
+      title: 'Milestones without a due date',
+      items: report.milestones_missing_due_date.map((m) => ({
+        key: m.id,
+        label: `${m.project_name}: #${m.number} ${m.name}`,
+        open: () => openProject(m.project_id),
+      })),
+    },
+    {
+      title: 'Milestones without a technical lead',
+      items: report.milestones_missing_lead.map((m) => ({
+        key: m.id,
+        label: `${m.project_name}: #${m.number} ${m.name}`,
+        open: () => openProject(m.project_id),
+      })),
+    },
+    {
+      title: "Stakeholders who aren't people",
+      items: report.unknown_stakeholders.map((s) => ({
+        key: `${s.project_id}/${s.stakeholder_email}`,
+        label: `${s.stakeholder_email} on ${s.project_name}`,
+        open: () => openProject(s.project_id),
+      })),
+    },
+    {
+      title: 'People outside the allowed email domains',
+      items: report.people_outside_allowed_domains.map((p) => ({
+        key: p.email,
+        label: `${p.name} <${p.email}>`,
+        open: () => openPerson(p.email),
+      })),
+    },
+    {
+      title: "Note references that don't lead anywhere",
+      items: report.dangling_references.map((r) => ({
+        key: `${r.note_id}/${r.reference}`,
+        label: `${r.reference} in "${r.note_title}" on ${r.project_name}`,
+        open: () => openProject(r.project_id),
+      })),
+    },
+    {
+      title: 'Completed projects without a retrospective',
+      items: report.projects_missing_retrospective.map((p) => ({
+        key: p.id,
+        label: p.name,
+        open: () => openProject(p.id),
+      })),
+    },
+  ].filter((section) => section.items.length > 0);
+
+  return (
+    <Card title={<Title level={4}>Data health</Title>}>
+      {sections.length === 0 ? (
+        <Empty description="No gaps found" />
+      ) : (
+        <Space direction="vertical" style={{ width: '100%' }}>
+          {sections.map((section) => (
+            <Card
+              key={section.title}
+              size="small"
+              title={section.title}
+              extra={<Tag color="warning">{section.items.length}</Tag>}
+            >
+              <List
+                size="small"
+                dataSource={section.items}
+                renderItem={(item) => (
+                  <List.Item key={item.key}>
+                    <Link onClick={item.open}>{item.label}</Link>
+                  </List.Item>
+                )}
+              />
+            </Card>
+          ))}
+        </Space>
+      )}
+    </Card>
+  );
+};
@@ -5,7 +5,7 @@
  */
 
 import { useState, useEffect } from 'react';
-import { Table, message, Typography, Tag } from 'antd';
+import { Badge, Table, message, Typography, Tag } from 'antd';
 import { ProjectOutlined, FlagOutlined, LinkOutlined } from '@ant-design/icons';
 import type { ColumnsType } from 'antd/es/table';
 import { ProjectService } from '../services/projectService';
@@ -20,6 +20,7 @@ interface DeadlineItem {
   dueDate: string | null;
   ticket: string | null;
   projectId?: string;
+  resources?: number;
 }
 
 interface DeadlinesListProps {
@@ -34,20 +35,22 @@ export const DeadlinesList: React.FC<DeadlinesListProps> = ({
   const [deadlines, setDeadlines] = useState<DeadlineItem[]>([]);
   const [loading, setLoading] = useState(false);
   const [jiraBaseUrl, setJiraBaseUrl] = useState<string>('');
+  const [timeZone, setTimeZone] = useState<string>('UTC');
   const [projects, setProjects] = useState<Project[]>([]);
   const [milestones, setMilestones] = useState<Milestone[]>([]);
 
   useEffect(() => {
     loadDeadlines();
-    loadJiraUrl();
+    loadSettings();
   }, []);
 
-  const loadJiraUrl = async () => {
+  const loadSettings = async () => {
     try {
-      const url = await ProjectService.getJiraUrl();
+      const [url, tz] = await Promise.all([ProjectService.getJiraUrl(), ProjectService.getTimezone()]);
       setJiraBaseUrl(url);
+      setTimeZone(tz);
     } catch (error) {
-      console.error('Failed to load Jira URL:', error);
+      console.error('Failed to load settings:', error);
     }
   };
 
@@ -58,11 +61,16 @@ export const DeadlinesList: React.FC<DeadlinesListProps> = ({
       const projectsData = await ProjectService.listProjects();
       setProjects(projectsData);
 
-      // Load milestones for all projects
+      // Load milestones and their resource counts for all projects
       const allMilestones: Milestone[] = [];
+      const resourceCounts: Record<string, number> = {};
       for (const project of projectsData) {
-        const projectMilestones = await ProjectService.getProjectMilestones(project.id);
+        const [projectMilestones, counts] = await Promise.all([
+          ProjectService.getProjectMilestones(project.id),
+          ProjectService.getResourceCounts(project.id),
+        ]);
         allMilestones.push(...projectMilestones);
+        Object.assign(resourceCounts, counts);
       }
       setMilestones(allMilestones);
 
@@ -92,6 +100,7 @@ export const DeadlinesList: React.FC<DeadlinesListProps> = ({
             dueDate: milestone.due_date,
             ticket: milestone.jira_epic || null,
             projectId: milestone.project_id,
+            resources: resourceCounts[milestone.id] ?? 0,
           });
         }
       }
@@ -113,7 +122,7 @@ export const DeadlinesList: React.FC<DeadlinesListProps> = ({
 
   const formatDate = (dateString: string | null) => {
     if (!dateString) return '-';
-    return new Date(dateString).toLocaleDateString();
+    return new Date(dateString).toLocaleDateString(undefined, { timeZone });
   };
 
   const handleViewItem = (record: DeadlineItem) => {
@@ -180,6 +189,15 @@ export const DeadlinesList: React.FC<DeadlinesListProps> = ({
         </Link>
       ),
     },
+    {
+      title: 'Resources',
+      dataIndex: 'resources',
+      key: 'resources',
+      width: 100,
+      render: (resources?: number) => resources === undefined
+        ? '-'
+        : <Badge count={resources} showZero color={resources === 0 ? 'orange' : 'blue'} />,
+    },
     {
       title: 'Ticket',
       dataIndex: 'ticket',
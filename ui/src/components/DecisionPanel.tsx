@@ -0,0 +1,214 @@
+/**
+ * Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+import { useState, useEffect } from 'react';
+import { Card, Table, Button, Modal, Form, Input, Select, DatePicker, Tag, Typography, message } from 'antd';
+import { PlusOutlined } from '@ant-design/icons';
+import type { ColumnsType } from 'antd/es/table';
+import { ProjectService } from '../services/projectService';
+import type { Decision, DecisionStatus, Person } from '../types';
+
+const { Text } = Typography;
+
+const STATUS_COLORS: Record<DecisionStatus, string> = {
+  proposed: 'blue',
+  accepted: 'green',
+  superseded: 'default',
+};
+
+interface DecisionPanelProps {
+  projectId: string;
+  people: Person[];
+  timeZone: string;
+}
+
+export const DecisionPanel: React.FC<DecisionPanelProps> = ({ projectId, people, timeZone }) => {
+  const [decisions, setDecisions] = useState<Decision[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [showModal, setShowModal] = useState(false);
+  const [form] = Form.useForm();
+
+  useEffect(() => {
+    loadDecisions();
+  }, [projectId]);
+
+  const loadDecisions = async () => {
+    setLoading(true);
+    try {
+      setDecisions(await ProjectService.listDecisions(projectId));
+    } catch (error) {
+      message.error('Failed to load decisions: ' + error);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const handleSubmit = async (values: any) => {
+    const now = new Date().toISOString();
+    const status: DecisionStatus = values.status;
+    try {
+      const decision = await ProjectService.recordDecision({
+        id: crypto.randomUUID(),
+        project_id: projectId,
+        title: values.title,
+        context: values.context || undefined,
+        decision: values.decision,
+        decided_on: values.decided_on ? values.decided_on.toISOString() : status === 'accepted' ? now : undefined,
+        status,
+        approvers: values.approvers || [],
+        created_at: now,
+        updated_at: now,
+      });
+      if (values.supersedes) {
+        await ProjectService.supersedeDecision(values.supersedes, decision.id);
+      }
+      message.success('Decision recorded successfully');
+      setShowModal(false);
+      form.resetFields();
+      await loadDecisions();
+    } catch (error) {
+      message.error('Failed to record decision: ' + error);
+    }
+  };
+
+  const formatDate = (dateString?: string) => {
+    if (!dateString) return '-';
+    return new Date(dateString).toLocaleDateString(undefined, { timeZone });
+  };
+
+  const getPersonName = (email: string) => people.find(p => p.email === email)?.name || email;
+
+  const getDecisionTitle = (id?: string) => decisions.find(d => d.id === id)?.title;
+
+  const columns: ColumnsType<Decision> = [
+    {
+      title: 'Decision',
+      key: 'title',
+      render: (_, record) => (
+        <>
+          <Text strong>{record.title}</Text>
+          <div>{record.decision}</div>
+          {record.context && <Text type="secondary">{record.context}</Text>}
+        </>
+      ),
+    },
+    {
+      title: 'Status',
+      dataIndex: 'status',
+      key: 'status',
+      width: 180,
+      render: (status: DecisionStatus, record) => (
+        <>
+          <Tag color={STATUS_COLORS[status]}>{status}</Tag>
+          {record.superseded_by && <Text type="secondary">by {getDecisionTitle(record.superseded_by)}</Text>}
+        </>
+      ),
+    },
+    {
+      title: 'Approvers',
+      dataIndex: 'approvers',
+      key: 'approvers',
+      render: (approvers: string[]) => (approvers.length > 0 ? approvers.map(getPersonName).join(', ') : '-'),
+    },
+    {
+      title: 'Decided',
+      dataIndex: 'decided_on',
+      key: 'decided_on',
+      width: 120,
+      render: formatDate,
+    },
+  ];
+
+  return (
+    <Card
+      title="Decisions"
+      style={{ marginBottom: 16 }}
+      extra={
+        <Button
+          type="primary"
+          size="small"
+          icon={<PlusOutlined />}
+          onClick={() => setShowModal(true)}
+        >
+          Record Decision
+        </Button>
+      }
+    >
+      <Table
+        columns={columns}
+        dataSource={decisions}
+        rowKey="id"
+        loading={loading}
+        locale={{ emptyText: 'No decisions yet' }}
+        pagination={{ pageSize: 10 }}
+      />
+
+      <Modal
+        title="Record Decision"
+        open={showModal}
+        onCancel={() => setShowModal(false)}
+        onOk={() => form.submit()}
+        okText="Record"
+        destroyOnClose
+      >
+        <Form form={form} layout="vertical" onFinish={handleSubmit} initialValues={{ status: 'accepted' }}>
+          <Form.Item
+            name="title"
+            label="Title"
+            rules={[{ required: true, message: 'Please give the decision a title' }]}
+          >
+            <Input placeholder="Short name for the decision" />
+          </Form.Item>
+
+          <Form.Item
+            name="decision"
+            label="Decision"
+            rules={[{ required: true, message: 'Please describe what was decided' }]}
+          >
+            <Input.TextArea rows={2} placeholder="What was decided" />
+          </Form.Item>
+
+          <Form.Item name="context" label="Context">
+            <Input.TextArea rows={3} placeholder="The situation and options that led to it" />
+          </Form.Item>
+
+          <Form.Item name="status" label="Status">
+            <Select
+              options={[
+                { value: 'accepted', label: 'Accepted' },
+                { value: 'proposed', label: 'Proposed' },
+              ]}
+            />
+          </Form.Item>
+
+          <Form.Item name="decided_on" label="Decided On">
+            <DatePicker style={{ width: '100%' }} />
+          </Form.Item>
+
+          <Form.Item name="approvers" label="Approvers">
+            <Select
+              mode="multiple"
+              allowClear
+              placeholder="Who approved it"
+              optionFilterProp="label"
+              options={people.map(p => ({ value: p.email, label: p.name }))}
+            />
+          </Form.Item>
+
+          <Form.Item name="supersedes" label="Supersedes">
+            <Select
+              allowClear
+              placeholder="The decision this replaces"
+              options={decisions
+                .filter(d => d.status !== 'superseded')
+                .map(d => ({ value: d.id, label: d.title }))}
+            />
+          </Form.Item>
+        </Form>
+      </Modal>
+    </Card>
+  );
+};
@@ -0,0 +1,51 @@
+/**
+ * Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+import { useState } from 'react';
+import { Button, Card, DatePicker, Modal, Space, Typography, message } from 'antd';
+import { EyeOutlined } from '@ant-design/icons';
+import type { Dayjs } from 'dayjs';
+import { ProjectService } from '../services/projectService';
+
+const { Paragraph, Title } = Typography;
+
+export const DigestPreview: React.FC = () => {
+  const [since, setSince] = useState<Dayjs | null>(null);
+  const [html, setHtml] = useState<string | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  const handlePreview = async () => {
+    setLoading(true);
+    try {
+      setHtml(await ProjectService.previewDigest(since?.format('YYYY-MM-DD')));
+    } catch (error) {
+      message.error('Failed to generate digest: ' + error);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  return (
+    <Card title={<Title level={4}>Weekly digest</Title>}>
+      <Paragraph>
+        Milestones due this week, milestones that went overdue, new projects and the projects with the most notes.
+        Use <code>track digest send</code> to email it.
+      </Paragraph>
+      <Space>
+        <DatePicker value={since} onChange={setSince} placeholder="Since (default: 7 days ago)" />
+        <Button type="primary" icon={<EyeOutlined />} loading={loading} onClick={handlePreview}>
+          Preview
+        </Button>
+      </Space>
+
+      <Modal title="Weekly digest" open={html !== null} onCancel={() => setHtml(null)} footer={null} width={800}>
+        {html !== null && (
+          <iframe title="Weekly digest" srcDoc={html} sandbox="" style={{ width: '100%', height: '70vh', border: 'none' }} />
+        )}
+      </Modal>
+    </Card>
+  );
+};
@@ -0,0 +1,204 @@
+/**
+ * Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+import { useState, useEffect } from 'react';
+import { Card, Table, Button, Modal, Form, Input, Select, Space, message } from 'antd';
+import { PlusOutlined, DeleteOutlined, SendOutlined } from '@ant-design/icons';
+import type { ColumnsType } from 'antd/es/table';
+import { NoteService } from '../services/noteService';
+import { ProjectService } from '../services/projectService';
+import type { InboxNote, Milestone, Project } from '../types';
+
+export const InboxPanel: React.FC = () => {
+  const [notes, setNotes] = useState<InboxNote[]>([]);
+  const [projects, setProjects] = useState<Project[]>([]);
+  const [milestones, setMilestones] = useState<Milestone[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [showCapture, setShowCapture] = useState(false);
+  const [triaging, setTriaging] = useState<InboxNote | null>(null);
+  const [captureForm] = Form.useForm();
+  const [triageForm] = Form.useForm();
+
+  useEffect(() => {
+    loadNotes();
+    ProjectService.listProjects()
+      .then(setProjects)
+      .catch((error) => message.error('Failed to load projects: ' + error));
+  }, []);
+
+  const loadNotes = async () => {
+    setLoading(true);
+    try {
+      setNotes(await NoteService.listInboxNotes());
+    } catch (error) {
+      message.error('Failed to load inbox: ' + error);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const handleCapture = async (values: any) => {
+    const now = new Date().toISOString();
+    try {
+      await NoteService.createInboxNote({
+        id: crypto.randomUUID(),
+        title: values.title,
+        body: values.body || '',
+        created_at: now,
+        updated_at: now,
+      });
+      setShowCapture(false);
+      captureForm.resetFields();
+      await loadNotes();
+    } catch (error) {
+      message.error('Failed to capture note: ' + error);
+    }
+  };
+
+  const handleProjectChange = async (projectId: string) => {
+    triageForm.setFieldValue('milestone_id', undefined);
+    try {
+      setMilestones(await ProjectService.getProjectMilestones(projectId));
+    } catch (error) {
+      message.error('Failed to load milestones: ' + error);
+    }
+  };
+
+  const handleTriage = async (values: any) => {
+    if (!triaging) return;
+    const target = values.milestone_id
+      ? { type: 'milestone' as const, milestone_id: values.milestone_id }
+      : { type: 'project' as const, project_id: values.project_id };
+    try {
+      await NoteService.triageInboxNote(triaging.id, target);
+      message.success('Note filed successfully');
+      setTriaging(null);
+      triageForm.resetFields();
+      await loadNotes();
+    } catch (error) {
+      message.error('Failed to file note: ' + error);
+    }
+  };
+
+  const handleDelete = (note: InboxNote) => {
+    Modal.confirm({
+      title: 'Delete Note',
+      content: `Are you sure you want to delete "${note.title}"?`,
+      okText: 'Delete',
+      okType: 'danger',
+      onOk: async () => {
+        try {
+          await NoteService.deleteInboxNote(note.id);
+          await loadNotes();
+        } catch (error) {
+          message.error('Failed to delete note: ' + error);
+        }
+      },
+    });
+  };
+
+  const columns: ColumnsType<InboxNote> = [
+    {
+      title: 'Title',
+      dataIndex: 'title',
+      key: 'title',
+    },
+    {
+      title: 'Note',
+      dataIndex: 'body',
+      key: 'body',
+      ellipsis: true,
+    },
+    {
+      title: 'Captured',
+      dataIndex: 'created_at',
+      key: 'created_at',
+      width: 180,
+      render: (value: string) => new Date(value).toLocaleString(),
+    },
+    {
+      title: 'Actions',
+      key: 'actions',
+      width: 180,
+      render: (_, record) => (
+        <Space>
+          <Button type="link" size="small" icon={<SendOutlined />} onClick={() => setTriaging(record)}>
+            File
+          </Button>
+          <Button type="link" danger size="small" icon={<DeleteOutlined />} onClick={() => handleDelete(record)}>
+            Delete
+          </Button>
+        </Space>
+      ),
+    },
+  ];
+
+  return (
+    <Card
+      title="Inbox"
+      extra={
+        <Button type="primary" icon={<PlusOutlined />} onClick={() => setShowCapture(true)}>
+          Capture Note
+        </Button>
+      }
+    >
+      <Table
+        columns={columns}
+        dataSource={notes}
+        rowKey="id"
+        loading={loading}
+        locale={{ emptyText: 'Inbox is empty' }}
+        pagination={{ pageSize: 20 }}
+      />
+
+      <Modal
+        title="Capture Note"
+        open={showCapture}
+        onCancel={() => setShowCapture(false)}
+        onOk={() => captureForm.submit()}
+        okText="Capture"
+        destroyOnClose
+      >
+        <Form form={captureForm} layout="vertical" onFinish={handleCapture}>
+          <Form.Item name="title" label="Title" rules={[{ required: true, message: 'Please enter a title' }]}>
+            <Input />
+          </Form.Item>
+          <Form.Item name="body" label="Note">
+            <Input.TextArea rows={6} />
+          </Form.Item>
+        </Form>
+      </Modal>
+
+      <Modal
+        title={`File "${triaging?.title ?? ''}"`}
+        open={triaging !== null}
+        onCancel={() => setTriaging(null)}
+        onOk={() => triageForm.submit()}
+        okText="File"
+        destroyOnClose
+      >
+        <Form form={triageForm} layout="vertical" onFinish={handleTriage}>
+          <Form.Item name="project_id" label="Project" rules={[{ required: true, message: 'Please select a project' }]}>
+            <Select
+              showSearch
+              optionFilterProp="label"
+              placeholder="Select a project"
+              options={projects.map(p => ({ value: p.id, label: p.name }))}
+              onChange={handleProjectChange}
+            />
+          </Form.Item>
+          <Form.Item name="milestone_id" label="Milestone">
+            <Select
+              allowClear
+              placeholder="File under the project itself"
+              options={milestones.map(m => ({ value: m.id, label: m.name }))}
+            />
+          </Form.Item>
+        </Form>
+      </Modal>
+    </Card>
+  );
+};
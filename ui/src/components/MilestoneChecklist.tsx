@@ -0,0 +1,104 @@
+/**
+ * Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+import { useState } from 'react';
+import { Button, Checkbox, Input, List, Progress, Space, message } from 'antd';
+import { ArrowDownOutlined, ArrowUpOutlined, DeleteOutlined } from '@ant-design/icons';
+import { MilestoneService } from '../services/milestoneService';
+import type { Milestone } from '../types';
+
+interface MilestoneChecklistProps {
+  milestone: Milestone;
+  onChange: () => void;
+}
+
+export const MilestoneChecklist: React.FC<MilestoneChecklistProps> = ({ milestone, onChange }) => {
+  const [text, setText] = useState('');
+  const [saving, setSaving] = useState(false);
+  const items = milestone.checklist ?? [];
+
+  const run = async (action: () => Promise<unknown>, failure: string) => {
+    setSaving(true);
+    try {
+      await action();
+      onChange();
+    } catch (error) {
+      message.error(`${failure}: ${error}`);
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleAdd = async () => {
+    if (!text.trim()) {
+      return;
+    }
+    await run(() => MilestoneService.addChecklistItem(milestone.id, text), 'Failed to add checklist item');
+    setText('');
+  };
+
+  return (
+    <div>
+      {milestone.checklist_completion != null && (
+        <Progress percent={Math.floor(milestone.checklist_completion * 100)} size="small" style={{ maxWidth: 300 }} />
+      )}
+      <List
+        size="small"
+        dataSource={items}
+        locale={{ emptyText: 'No checklist items' }}
+        renderItem={(item) => (
+          <List.Item
+            actions={[
+              <Button
+                key="up"
+                type="link"
+                size="small"
+                icon={<ArrowUpOutlined />}
+                disabled={saving || item.position === 1}
+                onClick={() => run(() => MilestoneService.moveChecklistItem(item.id, item.position - 1), 'Failed to move checklist item')}
+              />,
+              <Button
+                key="down"
+                type="link"
+                size="small"
+                icon={<ArrowDownOutlined />}
+                disabled={saving || item.position === items.length}
+                onClick={() => run(() => MilestoneService.moveChecklistItem(item.id, item.position + 1), 'Failed to move checklist item')}
+              />,
+              <Button
+                key="remove"
+                type="link"
+                size="small"
+                danger
+                icon={<DeleteOutlined />}
+                disabled={saving}
+                onClick={() => run(() => MilestoneService.removeChecklistItem(item.id), 'Failed to remove checklist item')}
+              />,
+            ]}
+          >
+            <Checkbox
+              checked={item.checked}
+              disabled={saving}
+              onChange={(e) => run(() => MilestoneService.toggleChecklistItem(item.id, e.target.checked), 'Failed to update checklist item')}
+            >
+              {item.text}
+            </Checkbox>
+          </List.Item>
+        )}
+      />
+      <Space.Compact style={{ width: '100%', maxWidth: 500 }}>
+        <Input
+          placeholder="Add a checklist item, e.g. Security review"
+          value={text}
+          onChange={(e) => setText(e.target.value)}
+          onPressEnter={handleAdd}
+          disabled={saving}
+        />
+        <Button onClick={handleAdd} disabled={saving || !text.trim()}>Add</Button>
+      </Space.Compact>
+    </div>
+  );
+};
@@ -60,8 +60,11 @@ export const MilestoneForm: React.FC<MilestoneFormProps> = ({
         start_date: values.start_date ? values.start_date.toISOString() : undefined,
         due_date: values.due_date ? values.due_date.toISOString() : undefined,
         jira_epic: values.jira_epic || undefined,
+        estimated_days: values.estimated_days ?? undefined,
+        actual_days: values.actual_days ?? undefined,
         created_at: milestone?.created_at || new Date().toISOString(),
         updated_at: new Date().toISOString(),
+        version: milestone?.version,
       };
 
       if (isEditing) {
@@ -72,6 +75,9 @@ export const MilestoneForm: React.FC<MilestoneFormProps> = ({
         message.success('Milestone created successfully');
       }
 
+      const warnings = await MilestoneService.getDateWarnings(milestoneData);
+      warnings.forEach((warning) => message.warning(warning));
+
       onSave();
     } catch (error) {
       message.error(`Failed to ${isEditing ? 'update' : 'create'} milestone: ` + error);
@@ -95,6 +101,8 @@ export const MilestoneForm: React.FC<MilestoneFormProps> = ({
         start_date: null,
         due_date: null,
         jira_epic: '',
+        estimated_days: null,
+        actual_days: null,
       }}
     >
       <Form.Item
@@ -151,6 +159,18 @@ export const MilestoneForm: React.FC<MilestoneFormProps> = ({
       <Form.Item
         name="due_date"
         label="Due Date"
+        dependencies={['start_date']}
+        rules={[
+          ({ getFieldValue }) => ({
+            validator(_, value) {
+              const start = getFieldValue('start_date');
+              if (!value || !start || !start.isAfter(value)) {
+                return Promise.resolve();
+              }
+              return Promise.reject(new Error('Due date cannot be before the start date'));
+            },
+          }),
+        ]}
       >
         <DatePicker style={{ width: '100%' }} />
       </Form.Item>
@@ -163,6 +183,15 @@ export const MilestoneForm: React.FC<MilestoneFormProps> = ({
         <Input placeholder="e.g., PROJ-123" />
       </Form.Item>
 
+      <Space style={{ width: '100%' }} size="middle">
+        <Form.Item name="estimated_days" label="Estimated Days">
+          <InputNumber min={0} step={0.5} placeholder="e.g., 5" />
+        </Form.Item>
+        <Form.Item name="actual_days" label="Actual Days">
+          <InputNumber min={0} step={0.5} placeholder="e.g., 6.5" />
+        </Form.Item>
+      </Space>
+
       <Form.Item>
         <Space>
           <Button
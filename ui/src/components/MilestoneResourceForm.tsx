@@ -4,11 +4,15 @@
  * SPDX-License-Identifier: MIT
  */
 
-import { useState } from 'react';
-import { Form, Input, Button, message, Space } from 'antd';
+import { useState, useEffect } from 'react';
+import { Form, Button, Input, List, message, Space, Typography } from 'antd';
 import { SaveOutlined, CloseOutlined } from '@ant-design/icons';
 import { PersonSelector } from './PersonSelector';
-import type { MilestoneResource } from '../types';
+import { RoleInput } from './RoleInput';
+import { ProjectService } from '../services/projectService';
+import type { MilestoneResource, ResourceSuggestion } from '../types';
+
+const { Link, Text } = Typography;
 
 interface MilestoneResourceFormProps {
   milestoneId: string;
@@ -27,9 +31,18 @@ export const MilestoneResourceForm: React.FC<MilestoneResourceFormProps> = ({
 }) => {
   const [form] = Form.useForm();
   const [loading, setLoading] = useState(false);
+  const [suggestions, setSuggestions] = useState<ResourceSuggestion[]>([]);
+  const [skill, setSkill] = useState('');
 
   const isEditing = !!resource;
 
+  useEffect(() => {
+    if (isEditing) return;
+    ProjectService.suggestMilestoneResources(milestoneId, undefined, skill || undefined)
+      .then(setSuggestions)
+      .catch((error) => console.error('Failed to load suggestions:', error));
+  }, [milestoneId, isEditing, skill]);
+
   const handleSubmit = async (values: any) => {
     setLoading(true);
     try {
@@ -69,11 +82,40 @@ export const MilestoneResourceForm: React.FC<MilestoneResourceFormProps> = ({
         <PersonSelector placeholder="Select person" />
       </Form.Item>
 
+      {!isEditing && (
+        <Form.Item label="Skill needed">
+          <Input.Search
+            allowClear
+            placeholder="e.g. kubernetes"
+            onSearch={(value) => setSkill(value.trim())}
+          />
+        </Form.Item>
+      )}
+
+      {suggestions.length > 0 && (
+        <Form.Item label="Suggested">
+          <List
+            size="small"
+            dataSource={suggestions}
+            renderItem={(suggestion) => (
+              <List.Item key={suggestion.email}>
+                <Space direction="vertical" size={0}>
+                  <Link onClick={() => form.setFieldsValue({ person_email: suggestion.email })}>
+                    {suggestion.name}
+                  </Link>
+                  <Text type="secondary">{suggestion.reason}</Text>
+                </Space>
+              </List.Item>
+            )}
+          />
+        </Form.Item>
+      )}
+
       <Form.Item
         name="role"
         label="Role"
       >
-        <Input placeholder="Enter role (e.g., Developer, Designer, PM)" />
+        <RoleInput placeholder="Enter role (e.g., Developer, Designer, PM)" />
       </Form.Item>
 
       <Form.Item>
@@ -0,0 +1,123 @@
+/**
+ * Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+import { useEffect, useState } from 'react';
+import { Button, List, Popconfirm, Typography, Upload, message } from 'antd';
+import { DeleteOutlined, DownloadOutlined, PaperClipOutlined, UploadOutlined } from '@ant-design/icons';
+import { NoteService } from '../services/noteService';
+import type { Attachment, NoteType } from '../types';
+
+const { Text } = Typography;
+
+interface NoteAttachmentsProps {
+  noteType: NoteType;
+  noteId: string;
+}
+
+const formatSize = (bytes: number) => {
+  if (bytes < 1024) return `${bytes} bytes`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+};
+
+export const NoteAttachments: React.FC<NoteAttachmentsProps> = ({ noteType, noteId }) => {
+  const [attachments, setAttachments] = useState<Attachment[]>([]);
+  const [uploading, setUploading] = useState(false);
+
+  const loadAttachments = async () => {
+    try {
+      setAttachments(await NoteService.listAttachments(noteType, noteId));
+    } catch (error) {
+      message.error('Failed to load attachments: ' + error);
+    }
+  };
+
+  useEffect(() => {
+    loadAttachments();
+  }, [noteType, noteId]);
+
+  const handleUpload = async (file: File) => {
+    setUploading(true);
+    try {
+      const buffer = new Uint8Array(await file.arrayBuffer());
+      let binary = '';
+      buffer.forEach((byte) => {
+        binary += String.fromCharCode(byte);
+      });
+      await NoteService.addAttachment(noteType, noteId, file.name, file.type, btoa(binary));
+      await loadAttachments();
+    } catch (error) {
+      message.error('Failed to attach file: ' + error);
+    } finally {
+      setUploading(false);
+    }
+  };
+
+  const handleDownload = async (attachment: Attachment) => {
+    try {
+      const data = await NoteService.getAttachmentData(attachment.id);
+      const link = document.createElement('a');
+      link.href = `data:${attachment.mime_type};base64,${data}`;
+      link.download = attachment.filename;
+      link.click();
+    } catch (error) {
+      message.error('Failed to download attachment: ' + error);
+    }
+  };
+
+  const handleRemove = async (id: string) => {
+    try {
+      await NoteService.removeAttachment(id);
+      await loadAttachments();
+    } catch (error) {
+      message.error('Failed to remove attachment: ' + error);
+    }
+  };
+
+  return (
+    <div>
+      <List
+        size="small"
+        header={<Text strong><PaperClipOutlined /> Attachments</Text>}
+        locale={{ emptyText: 'No attachments' }}
+        dataSource={attachments}
+        renderItem={(attachment) => (
+          <List.Item
+            actions={[
+              <Button
+                key="download"
+                type="link"
+                icon={<DownloadOutlined />}
+                onClick={() => handleDownload(attachment)}
+              />,
+              <Popconfirm
+                key="remove"
+                title="Remove this attachment?"
+                onConfirm={() => handleRemove(attachment.id)}
+              >
+                <Button type="link" danger icon={<DeleteOutlined />} />
+              </Popconfirm>,
+            ]}
+          >
+            <Text>{attachment.filename}</Text>
+            <Text type="secondary" style={{ marginLeft: 8 }}>{formatSize(attachment.size_bytes)}</Text>
+          </List.Item>
+        )}
+      />
+      <Upload
+        showUploadList={false}
+        beforeUpload={(file) => {
+          handleUpload(file);
+          return false;
+        }}
+      >
+        <Button icon={<UploadOutlined />} loading={uploading} style={{ marginTop: 8 }}>
+          Attach File
+        </Button>
+      </Upload>
+    </div>
+  );
+};
@@ -7,6 +7,8 @@
 import { Modal, Button, Space, Typography } from 'antd';
 import { EditOutlined, DeleteOutlined } from '@ant-design/icons';
 import Markdown from 'react-markdown';
+import { NoteAttachments } from './NoteAttachments';
+import type { NoteType } from '../types';
 
 const { Text } = Typography;
 
@@ -20,6 +22,7 @@ interface Note {
 
 interface NoteViewModalProps {
   note: Note | null;
+  noteType?: NoteType;
   open: boolean;
   onClose: () => void;
   onEdit: (note: Note) => void;
@@ -28,6 +31,7 @@ interface NoteViewModalProps {
 
 export const NoteViewModal: React.FC<NoteViewModalProps> = ({
   note,
+  noteType,
   open,
   onClose,
   onEdit,
@@ -85,6 +89,8 @@ export const NoteViewModal: React.FC<NoteViewModalProps> = ({
         }}>
           <Markdown>{note.body}</Markdown>
         </div>
+
+        {noteType && <NoteAttachments noteType={noteType} noteId={note.id} />}
       </Space>
     </Modal>
   );
@@ -0,0 +1,189 @@
+/**
+ * Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+import { useState, useEffect } from 'react';
+import { Button, DatePicker, Descriptions, Form, Input, List, Modal, Select, Space, Typography, message } from 'antd';
+import { DeleteOutlined, EditOutlined, PlusOutlined } from '@ant-design/icons';
+import dayjs from 'dayjs';
+import { PersonService } from '../services/personService';
+import { ProjectService } from '../services/projectService';
+import type { OneOnOne, Person, Project } from '../types';
+
+interface OneOnOnesProps {
+  person: Person;
+}
+
+export const OneOnOnes: React.FC<OneOnOnesProps> = ({ person }) => {
+  const [oneOnOnes, setOneOnOnes] = useState<OneOnOne[]>([]);
+  const [projects, setProjects] = useState<Project[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [editing, setEditing] = useState<OneOnOne | 'new' | null>(null);
+  const [form] = Form.useForm();
+
+  useEffect(() => {
+    loadOneOnOnes();
+  }, [person.email]);
+
+  useEffect(() => {
+    ProjectService.listProjects()
+      .then(setProjects)
+      .catch((error) => message.error('Failed to load projects: ' + error));
+  }, []);
+
+  const loadOneOnOnes = async () => {
+    setLoading(true);
+    try {
+      setOneOnOnes(await PersonService.listOneOnOnes(person.email));
+    } catch (error) {
+      message.error('Failed to load 1:1s: ' + error);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const initialValues = (oneOnOne: OneOnOne | 'new' | null) =>
+    oneOnOne === 'new' || oneOnOne === null
+      ? { occurred_at: dayjs() }
+      : { ...oneOnOne, occurred_at: dayjs(oneOnOne.occurred_at) };
+
+  const handleSave = async (values: any) => {
+    const now = new Date().toISOString();
+    const fields = {
+      occurred_at: values.occurred_at.toISOString(),
+      agenda: values.agenda || undefined,
+      notes: values.notes || undefined,
+      followups: values.followups || undefined,
+    };
+    try {
+      if (editing === 'new') {
+        const logged = await PersonService.logOneOnOne(
+          { id: crypto.randomUUID(), person_email: person.email, created_at: now, updated_at: now, ...fields },
+          values.action_item_project
+        );
+        message.success(
+          logged.action_items.length > 0
+            ? `1:1 logged with ${logged.action_items.length} action items`
+            : '1:1 logged'
+        );
+      } else if (editing) {
+        await PersonService.updateOneOnOne({ ...editing, ...fields });
+        message.success('1:1 updated');
+      }
+      setEditing(null);
+      await loadOneOnOnes();
+    } catch (error) {
+      message.error('Failed to save 1:1: ' + error);
+    }
+  };
+
+  const handleDelete = (oneOnOne: OneOnOne) => {
+    Modal.confirm({
+      title: 'Delete 1:1',
+      content: `Are you sure you want to delete the 1:1 from ${new Date(oneOnOne.occurred_at).toLocaleDateString()}?`,
+      okText: 'Delete',
+      okType: 'danger',
+      onOk: async () => {
+        try {
+          await PersonService.deleteOneOnOne(oneOnOne.id);
+          await loadOneOnOnes();
+        } catch (error) {
+          message.error('Failed to delete 1:1: ' + error);
+        }
+      },
+    });
+  };
+
+  return (
+    <div>
+      <Space style={{ marginBottom: 16 }}>
+        <Button icon={<PlusOutlined />} onClick={() => setEditing('new')}>
+          Log 1:1
+        </Button>
+      </Space>
+
+      <List
+        loading={loading}
+        dataSource={oneOnOnes}
+        locale={{ emptyText: 'No 1:1s recorded' }}
+        renderItem={(oneOnOne) => (
+          <List.Item
+            actions={[
+              <Button key="edit" type="link" size="small" icon={<EditOutlined />} onClick={() => setEditing(oneOnOne)} />,
+              <Button
+                key="delete"
+                type="link"
+                size="small"
+                danger
+                icon={<DeleteOutlined />}
+                onClick={() => handleDelete(oneOnOne)}
+              />,
+            ]}
+          >
+            <div style={{ width: '100%' }}>
+              <Typography.Text strong>{new Date(oneOnOne.occurred_at).toLocaleDateString()}</Typography.Text>
+              <Descriptions column={1} size="small" style={{ marginTop: 8 }}>
+                {oneOnOne.agenda && (
+                  <Descriptions.Item label="Agenda">
+                    <span style={{ whiteSpace: 'pre-wrap' }}>{oneOnOne.agenda}</span>
+                  </Descriptions.Item>
+                )}
+                {oneOnOne.notes && (
+                  <Descriptions.Item label="Notes">
+                    <span style={{ whiteSpace: 'pre-wrap' }}>{oneOnOne.notes}</span>
+                  </Descriptions.Item>
+                )}
+                {oneOnOne.followups && (
+                  <Descriptions.Item label="Follow-ups">
+                    <span style={{ whiteSpace: 'pre-wrap' }}>{oneOnOne.followups}</span>
+                  </Descriptions.Item>
+                )}
+              </Descriptions>
+            </div>
+          </List.Item>
+        )}
+      />
+
+      <Modal
+        title={editing === 'new' ? `Log 1:1 with ${person.name}` : 'Edit 1:1'}
+        open={editing !== null}
+        onCancel={() => setEditing(null)}
+        onOk={() => form.submit()}
+        okText="Save"
+        destroyOnClose
+      >
+        <Form form={form} layout="vertical" initialValues={initialValues(editing)} preserve={false} onFinish={handleSave}>
+          <Form.Item name="occurred_at" label="Date" rules={[{ required: true, message: 'Please pick a date' }]}>
+            <DatePicker style={{ width: '100%' }} />
+          </Form.Item>
+          <Form.Item name="agenda" label="Agenda">
+            <Input.TextArea rows={3} />
+          </Form.Item>
+          <Form.Item name="notes" label="Notes">
+            <Input.TextArea rows={6} />
+          </Form.Item>
+          <Form.Item name="followups" label="Follow-ups" extra="One per line">
+            <Input.TextArea rows={3} />
+          </Form.Item>
+          {editing === 'new' && (
+            <Form.Item
+              name="action_item_project"
+              label="Create action items on project"
+              extra="Each follow-up becomes an action item for this person"
+            >
+              <Select
+                allowClear
+                showSearch
+                optionFilterProp="label"
+                placeholder="Keep follow-ups as text"
+                options={projects.map((p) => ({ value: p.id, label: p.name }))}
+              />
+            </Form.Item>
+          )}
+        </Form>
+      </Modal>
+    </div>
+  );
+};
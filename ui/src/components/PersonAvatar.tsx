@@ -0,0 +1,52 @@
+/**
+ * Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+import { useState, useEffect } from 'react';
+import { Avatar } from 'antd';
+import { PersonService } from '../services/personService';
+import type { Person } from '../types';
+
+interface PersonAvatarProps {
+  person: Person;
+  size?: number;
+}
+
+const initials = (name: string) =>
+  name
+    .split(/\s+/)
+    .filter(Boolean)
+    .slice(0, 2)
+    .map((part) => part[0].toUpperCase())
+    .join('');
+
+/**
+ * A person's avatar thumbnail, or their initials if they have none
+ */
+export const PersonAvatar: React.FC<PersonAvatarProps> = ({ person, size = 32 }) => {
+  const [src, setSrc] = useState<string | null>(null);
+
+  useEffect(() => {
+    setSrc(null);
+    if (!person.avatar_path) return;
+    let cancelled = false;
+    PersonService.getAvatar(person.email)
+      .then((data) => {
+        if (!cancelled && data) setSrc(`data:image/png;base64,${data}`);
+      })
+      .catch(() => {
+        // Fall back to initials
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [person.email, person.avatar_path, person.updated_at]);
+
+  return (
+    <Avatar size={size} src={src ?? undefined}>
+      {initials(person.name)}
+    </Avatar>
+  );
+};
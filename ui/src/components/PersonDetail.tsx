@@ -4,17 +4,128 @@
  * SPDX-License-Identifier: MIT
  */
 
-import { Card, Descriptions, Button, Space } from 'antd';
-import { EditOutlined, ArrowLeftOutlined } from '@ant-design/icons';
-import type { Person } from '../types';
+import { useState, useEffect } from 'react';
+import { Card, Descriptions, Button, Input, Modal, Select, Space, Tabs, Tag, Upload, message } from 'antd';
+import { EditOutlined, ArrowLeftOutlined, UploadOutlined, DeleteOutlined, MailOutlined, PlusOutlined } from '@ant-design/icons';
+import { PersonService } from '../services/personService';
+import { NoteService } from '../services/noteService';
+import { PersonAvatar } from './PersonAvatar';
+import { NoteForm } from './NoteForm';
+import { NoteList } from './NoteList';
+import { OneOnOnes } from './OneOnOnes';
+import type { Person, PersonNote } from '../types';
 
 interface PersonDetailProps {
   person: Person;
   onEdit: () => void;
   onBack: () => void;
+  onEmailChanged: (person: Person) => void;
 }
 
-export const PersonDetail: React.FC<PersonDetailProps> = ({ person, onEdit, onBack }) => {
+export const PersonDetail: React.FC<PersonDetailProps> = ({ person: initialPerson, onEdit, onBack, onEmailChanged }) => {
+  const [person, setPerson] = useState<Person>(initialPerson);
+  const [newEmail, setNewEmail] = useState<string | null>(null);
+  const [notes, setNotes] = useState<PersonNote[]>([]);
+  const [showNoteForm, setShowNoteForm] = useState(false);
+  const [newSkill, setNewSkill] = useState('');
+  const [newSkillLevel, setNewSkillLevel] = useState<number | undefined>(undefined);
+
+  useEffect(() => {
+    setPerson(initialPerson);
+  }, [initialPerson]);
+
+  useEffect(() => {
+    loadNotes();
+  }, [person.email]);
+
+  const loadNotes = async () => {
+    try {
+      setNotes(await NoteService.listPersonNotes(person.email));
+    } catch (error) {
+      message.error('Failed to load notes: ' + error);
+    }
+  };
+
+  const handleSaveNote = async (title: string, body: string) => {
+    try {
+      const now = new Date().toISOString();
+      await NoteService.createPersonNote({
+        id: crypto.randomUUID(),
+        person_email: person.email,
+        title,
+        body,
+        created_at: now,
+        updated_at: now,
+      });
+      message.success('Note added successfully');
+      setShowNoteForm(false);
+      await loadNotes();
+    } catch (error) {
+      message.error('Failed to add note: ' + error);
+    }
+  };
+
+  const handleUploadAvatar = async (file: File) => {
+    try {
+      const buffer = new Uint8Array(await file.arrayBuffer());
+      let binary = '';
+      buffer.forEach((byte) => {
+        binary += String.fromCharCode(byte);
+      });
+      setPerson(await PersonService.setAvatar(person.email, btoa(binary)));
+      message.success('Avatar updated');
+    } catch (error) {
+      message.error('Failed to update avatar: ' + error);
+    }
+  };
+
+  const handleClearAvatar = async () => {
+    try {
+      setPerson(await PersonService.clearAvatar(person.email));
+    } catch (error) {
+      message.error('Failed to remove avatar: ' + error);
+    }
+  };
+
+  const handleChangeEmail = async () => {
+    if (!newEmail) return;
+    try {
+      const updated = await PersonService.changeEmail(person.email, newEmail.trim());
+      setPerson(updated);
+      setNewEmail(null);
+      onEmailChanged(updated);
+      message.success('Email changed');
+    } catch (error) {
+      message.error('Failed to change email: ' + error);
+    }
+  };
+
+  const reloadPerson = async () => {
+    const updated = await PersonService.getPerson(person.email);
+    if (updated) setPerson(updated);
+  };
+
+  const handleAddSkill = async () => {
+    if (!newSkill.trim()) return;
+    try {
+      await PersonService.addSkill(person.email, newSkill.trim(), newSkillLevel);
+      setNewSkill('');
+      setNewSkillLevel(undefined);
+      await reloadPerson();
+    } catch (error) {
+      message.error('Failed to add skill: ' + error);
+    }
+  };
+
+  const handleRemoveSkill = async (skill: string) => {
+    try {
+      await PersonService.removeSkill(person.email, skill);
+      await reloadPerson();
+    } catch (error) {
+      message.error('Failed to remove skill: ' + error);
+    }
+  };
+
   const formatDate = (dateString?: string) => {
     if (!dateString) return '-';
     return new Date(dateString).toLocaleDateString();
@@ -29,15 +140,58 @@ export const PersonDetail: React.FC<PersonDetailProps> = ({ person, onEdit, onBa
         <Button type="primary" icon={<EditOutlined />} onClick={onEdit}>
           Edit Person
         </Button>
+        <Button icon={<MailOutlined />} onClick={() => setNewEmail(person.email)}>
+          Change Email
+        </Button>
       </Space>
 
-      <Card title={`Person: ${person.name}`}>
+      <Modal
+        title="Change Email"
+        open={newEmail !== null}
+        onOk={handleChangeEmail}
+        okButtonProps={{ disabled: !newEmail?.trim() || newEmail.trim() === person.email }}
+        onCancel={() => setNewEmail(null)}
+      >
+        <p>Projects, milestones, teams and notes that refer to {person.name} will use the new address.</p>
+        <Input value={newEmail ?? ''} onChange={(e) => setNewEmail(e.target.value)} />
+      </Modal>
+
+      <Card
+        title={
+          <Space>
+            <PersonAvatar person={person} size={48} />
+            {`Person: ${person.name}`}
+          </Space>
+        }
+        extra={
+          <Space>
+            <Upload
+              accept="image/png,image/jpeg"
+              showUploadList={false}
+              beforeUpload={(file) => {
+                handleUploadAvatar(file);
+                return false;
+              }}
+            >
+              <Button size="small" icon={<UploadOutlined />}>
+                Upload Photo
+              </Button>
+            </Upload>
+            {person.avatar_path && (
+              <Button size="small" icon={<DeleteOutlined />} onClick={handleClearAvatar}>
+                Remove Photo
+              </Button>
+            )}
+          </Space>
+        }
+      >
         <Descriptions column={2} bordered>
           <Descriptions.Item label="Name" span={2}>
             {person.name}
           </Descriptions.Item>
           <Descriptions.Item label="Email" span={2}>
             {person.email}
+            {person.external && <Tag color="orange" style={{ marginLeft: 8 }}>External</Tag>}
           </Descriptions.Item>
           <Descriptions.Item label="Team">
             {person.team || '-'}
@@ -45,9 +199,48 @@ export const PersonDetail: React.FC<PersonDetailProps> = ({ person, onEdit, onBa
           <Descriptions.Item label="Manager">
             {person.manager || '-'}
           </Descriptions.Item>
-          <Descriptions.Item label="Notes" span={2}>
-            {person.notes || '-'}
+          <Descriptions.Item label="Organization" span={2}>
+            {person.organization || '-'}
           </Descriptions.Item>
+          <Descriptions.Item label="Skills" span={2}>
+            <Space wrap>
+              {(person.skills ?? []).map((s) => (
+                <Tag
+                  key={s.skill}
+                  closable
+                  onClose={(e) => {
+                    e.preventDefault();
+                    handleRemoveSkill(s.skill);
+                  }}
+                >
+                  {s.level ? `${s.skill} (${s.level})` : s.skill}
+                </Tag>
+              ))}
+              <Space.Compact size="small">
+                <Input
+                  placeholder="Add skill"
+                  value={newSkill}
+                  onChange={(e) => setNewSkill(e.target.value)}
+                  onPressEnter={handleAddSkill}
+                  style={{ width: 140 }}
+                />
+                <Select
+                  allowClear
+                  placeholder="Level"
+                  value={newSkillLevel}
+                  onChange={setNewSkillLevel}
+                  options={[1, 2, 3, 4, 5].map((level) => ({ value: level, label: level }))}
+                  style={{ width: 80 }}
+                />
+                <Button icon={<PlusOutlined />} disabled={!newSkill.trim()} onClick={handleAddSkill} />
+              </Space.Compact>
+            </Space>
+          </Descriptions.Item>
+          {person.notes && (
+            <Descriptions.Item label="Notes (old)" span={2}>
+              {person.notes}
+            </Descriptions.Item>
+          )}
           <Descriptions.Item label="Created">
             {formatDate(person.created_at)}
           </Descriptions.Item>
@@ -56,6 +249,50 @@ export const PersonDetail: React.FC<PersonDetailProps> = ({ person, onEdit, onBa
           </Descriptions.Item>
         </Descriptions>
       </Card>
+
+      <Card style={{ marginTop: 16 }}>
+        <Tabs
+          items={[
+            {
+              key: 'notes',
+              label: 'Notes',
+              children: (
+                <div>
+                  <Space style={{ marginBottom: 16 }}>
+                    <Button size="small" icon={<PlusOutlined />} onClick={() => setShowNoteForm(true)}>
+                      Add Note
+                    </Button>
+                  </Space>
+                  {showNoteForm ? (
+                    <div style={{ marginBottom: 16 }}>
+                      <NoteForm onSave={handleSaveNote} onCancel={() => setShowNoteForm(false)} />
+                    </div>
+                  ) : null}
+                  <NoteList
+                    notes={notes}
+                    onView={(note) => {
+                      Modal.info({
+                        title: note.title,
+                        content: note.body,
+                        width: 600,
+                      });
+                    }}
+                    onDelete={async (id) => {
+                      await NoteService.deletePersonNote(id);
+                      await loadNotes();
+                    }}
+                  />
+                </div>
+              ),
+            },
+            {
+              key: 'one-on-ones',
+              label: '1:1s',
+              children: <OneOnOnes person={person} />,
+            },
+          ]}
+        />
+      </Card>
     </div>
   );
 };
@@ -5,13 +5,13 @@
  */
 
 import { useState, useEffect } from 'react';
-import { Form, Input, Button, Card, message, Select, Space } from 'antd';
+import { Form, Input, Button, Card, Checkbox, message, Modal, Select, Space } from 'antd';
 import { SaveOutlined, CloseOutlined } from '@ant-design/icons';
 import { PersonService } from '../services/personService';
+import { OrganizationService } from '../services/organizationService';
+import { CommandError } from '../services/invoke';
 import { TeamSelector } from './TeamSelector';
-import type { Person } from '../types';
-
-const { TextArea } = Input;
+import type { Organization, Person } from '../types';
 
 interface PersonFormProps {
   person?: Person;
@@ -24,12 +24,14 @@ export const PersonForm: React.FC<PersonFormProps> = ({ person, onSave, onCancel
   const [form] = Form.useForm();
   const [loading, setLoading] = useState(false);
   const [people, setPeople] = useState<Person[]>([]);
+  const [organizations, setOrganizations] = useState<Organization[]>([]);
   const [defaultDomain, setDefaultDomain] = useState('');
 
   const isEditing = !!person && !!person.email;
 
   useEffect(() => {
     loadPeople();
+    loadOrganizations();
     loadDefaultDomain();
 
     if (person) {
@@ -46,6 +48,14 @@ export const PersonForm: React.FC<PersonFormProps> = ({ person, onSave, onCancel
     }
   };
 
+  const loadOrganizations = async () => {
+    try {
+      setOrganizations(await OrganizationService.listOrganizations());
+    } catch (error) {
+      message.error('Failed to load organizations: ' + error);
+    }
+  };
+
   const loadDefaultDomain = async () => {
     try {
       const domain = await PersonService.getDefaultEmailDomain();
@@ -55,6 +65,18 @@ export const PersonForm: React.FC<PersonFormProps> = ({ person, onSave, onCancel
     }
   };
 
+  // Ask whether a person whose name matches someone else's should be created anyway
+  const confirmDuplicate = (error: CommandError): Promise<boolean> =>
+    new Promise(resolve => {
+      Modal.confirm({
+        title: 'Possible Duplicate',
+        content: error.message,
+        okText: 'Create Anyway',
+        onOk: () => resolve(true),
+        onCancel: () => resolve(false),
+      });
+    });
+
   const handleSubmit = async (values: any) => {
     setLoading(true);
     try {
@@ -62,22 +84,51 @@ export const PersonForm: React.FC<PersonFormProps> = ({ person, onSave, onCancel
         email: values.email,
         name: values.name,
         team: values.team || undefined,
+        organization: values.organization || undefined,
         manager: values.manager || undefined,
-        notes: values.notes || undefined,
+        // The old notes field is read-only now; notes are added from the person's page
+        notes: person?.notes,
+        external: values.external ?? false,
         created_at: person?.created_at || new Date().toISOString(),
         updated_at: new Date().toISOString(),
+        version: person?.version,
       };
 
       if (isEditing) {
         await PersonService.updatePerson(personData);
         message.success('Person updated successfully');
       } else {
-        await PersonService.createPerson(personData);
+        try {
+          await PersonService.createPerson(personData);
+        } catch (error) {
+          if (!(error instanceof CommandError && error.code === 'POSSIBLE_DUPLICATE')) {
+            throw error;
+          }
+          if (!(await confirmDuplicate(error))) {
+            return;
+          }
+          await PersonService.createPerson(personData, true);
+        }
         message.success('Person created successfully');
       }
 
       onSave(personData);
     } catch (error) {
+      // A manager who doesn't exist is shown on the field rather than in a toast
+      if (error instanceof CommandError && error.code === 'FOREIGN_KEY' && error.details?.field === 'manager') {
+        form.setFields([{ name: 'manager', errors: ['No person has this email'] }]);
+        return;
+      }
+      if (error instanceof CommandError && error.code === 'FOREIGN_KEY' && error.details?.field === 'organization') {
+        form.setFields([{ name: 'organization', errors: ['No organization has this name'] }]);
+        return;
+      }
+      // So is an address outside the allowed domains
+      if (error instanceof CommandError && error.code === 'DOMAIN_NOT_ALLOWED') {
+        const allowed = (error.details?.allowed as string[] | undefined)?.join(', ');
+        form.setFields([{ name: 'email', errors: [`Allowed domains: ${allowed}. Mark the person as external for an exception.`] }]);
+        return;
+      }
       message.error(`Failed to ${isEditing ? 'update' : 'create'} person: ` + error);
     } finally {
       setLoading(false);
@@ -101,7 +152,7 @@ export const PersonForm: React.FC<PersonFormProps> = ({ person, onSave, onCancel
         email: '',
         team: '',
         manager: undefined,
-        notes: '',
+        external: false,
       }}
     >
       <Form.Item
@@ -131,6 +182,18 @@ export const PersonForm: React.FC<PersonFormProps> = ({ person, onSave, onCancel
         <TeamSelector placeholder="Select team" />
       </Form.Item>
 
+      <Form.Item
+        name="organization"
+        label="Organization"
+      >
+        <Select
+          showSearch
+          placeholder="Vendor, client or partner they work for"
+          options={organizations.map(o => ({ label: o.name, value: o.name }))}
+          allowClear
+        />
+      </Form.Item>
+
       <Form.Item
         name="manager"
         label="Manager"
@@ -147,10 +210,11 @@ export const PersonForm: React.FC<PersonFormProps> = ({ person, onSave, onCancel
       </Form.Item>
 
       <Form.Item
-        name="notes"
-        label="Notes"
+        name="external"
+        valuePropName="checked"
+        extra="A sanctioned exception to the allowed email domains, such as a consultant"
       >
-        <TextArea rows={4} placeholder="Enter any additional notes" />
+        <Checkbox>External</Checkbox>
       </Form.Item>
 
       <Form.Item>
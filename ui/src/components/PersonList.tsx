@@ -5,11 +5,12 @@
  */
 
 import { useState, useEffect } from 'react';
-import { Table, Button, Space, message, Modal } from 'antd';
-import { PlusOutlined, EditOutlined, DeleteOutlined, EyeOutlined } from '@ant-design/icons';
+import { Table, Button, Space, Tag, message, Modal, Upload, Checkbox } from 'antd';
+import { PlusOutlined, EditOutlined, DeleteOutlined, EyeOutlined, UploadOutlined } from '@ant-design/icons';
 import type { ColumnsType } from 'antd/es/table';
 import { PersonService } from '../services/personService';
-import type { Person } from '../types';
+import { PersonAvatar } from './PersonAvatar';
+import type { DirectoryField, Person } from '../types';
 
 interface PersonListProps {
   onEditPerson: (person: Person) => void;
@@ -24,6 +25,10 @@ export const PersonList: React.FC<PersonListProps> = ({
 }) => {
   const [people, setPeople] = useState<Person[]>([]);
   const [loading, setLoading] = useState(false);
+  const [showImport, setShowImport] = useState(false);
+  const [importFile, setImportFile] = useState<File | null>(null);
+  const [createTeams, setCreateTeams] = useState(true);
+  const [updateFields, setUpdateFields] = useState<DirectoryField[]>([]);
 
   useEffect(() => {
     loadPeople();
@@ -41,6 +46,42 @@ export const PersonList: React.FC<PersonListProps> = ({
     }
   };
 
+  const handleImport = async () => {
+    if (!importFile) {
+      message.error('Please choose a directory file');
+      return;
+    }
+    try {
+      const contents = await importFile.text();
+      const report = await PersonService.importDirectory(contents, {
+        create_teams: createTeams,
+        update_fields: updateFields,
+      });
+      setShowImport(false);
+      setImportFile(null);
+      Modal.info({
+        title: 'Directory Imported',
+        content: (
+          <div>
+            <p>
+              {report.created} created, {report.updated} updated, {report.skipped} skipped
+            </p>
+            {report.warnings.length > 0 && (
+              <ul>
+                {report.warnings.map((warning) => (
+                  <li key={warning}>{warning}</li>
+                ))}
+              </ul>
+            )}
+          </div>
+        ),
+      });
+      loadPeople();
+    } catch (error) {
+      message.error('Failed to import directory: ' + error);
+    }
+  };
+
   const handleDelete = async (person: Person) => {
     Modal.confirm({
       title: 'Delete Person',
@@ -72,6 +113,13 @@ export const PersonList: React.FC<PersonListProps> = ({
       width: 200,
       sorter: (a, b) => a.name.localeCompare(b.name),
       fixed: 'left',
+      render: (name: string, record) => (
+        <Space>
+          <PersonAvatar person={record} size={24} />
+          {name}
+          {record.external && <Tag color="orange">External</Tag>}
+        </Space>
+      ),
     },
     {
       title: 'Email',
@@ -155,7 +203,49 @@ export const PersonList: React.FC<PersonListProps> = ({
         >
           New Person
         </Button>
+        <Button
+          icon={<UploadOutlined />}
+          onClick={() => setShowImport(true)}
+          style={{ marginLeft: 8 }}
+        >
+          Import Directory
+        </Button>
       </div>
+      <Modal
+        title="Import Directory"
+        open={showImport}
+        onCancel={() => setShowImport(false)}
+        onOk={handleImport}
+        okText="Import"
+        destroyOnClose
+      >
+        <Space direction="vertical" style={{ width: '100%' }}>
+          <Upload
+            accept=".json,application/json"
+            maxCount={1}
+            beforeUpload={(file) => {
+              setImportFile(file);
+              return false;
+            }}
+            onRemove={() => setImportFile(null)}
+          >
+            <Button icon={<UploadOutlined />}>Choose JSON File</Button>
+          </Upload>
+          <Checkbox checked={createTeams} onChange={(e) => setCreateTeams(e.target.checked)}>
+            Create teams for new departments
+          </Checkbox>
+          <div>Overwrite for existing people:</div>
+          <Checkbox.Group
+            value={updateFields}
+            onChange={(values) => setUpdateFields(values as DirectoryField[])}
+            options={[
+              { label: 'Name', value: 'name' },
+              { label: 'Team', value: 'team' },
+              { label: 'Manager', value: 'manager' },
+            ]}
+          />
+        </Space>
+      </Modal>
       <Table
         columns={columns}
         dataSource={people}
@@ -5,8 +5,8 @@
  */
 
 import { useState, useEffect } from 'react';
-import { Card, Descriptions, Button, Space, Table, message, Typography, Spin, Modal } from 'antd';
-import { EditOutlined, ArrowLeftOutlined, LinkOutlined, PlusOutlined, DeleteOutlined, EyeOutlined } from '@ant-design/icons';
+import { Alert, Card, Descriptions, Button, Input, Select, Space, Table, Tag, Timeline, message, Typography, Spin, Modal } from 'antd';
+import { EditOutlined, ArrowLeftOutlined, LinkOutlined, PlusOutlined, DeleteOutlined, EyeOutlined, StopOutlined, CheckCircleOutlined } from '@ant-design/icons';
 import type { ColumnsType } from 'antd/es/table';
 import { ProjectService } from '../services/projectService';
 import { MilestoneService } from '../services/milestoneService';
@@ -16,10 +16,13 @@ import { MilestoneForm } from './MilestoneForm';
 import { StakeholderForm } from './StakeholderForm';
 import { ProjectResourceForm } from './ProjectResourceForm';
 import { MilestoneResourceForm } from './MilestoneResourceForm';
+import { MilestoneChecklist } from './MilestoneChecklist';
 import { NoteForm } from './NoteForm';
 import { NoteList } from './NoteList';
 import { NoteViewModal } from './NoteViewModal';
-import type { Project, Milestone, ProjectStakeholder, ProjectResource, MilestoneResource, Person, Note, ProjectNote, MilestoneNote, StakeholderNote } from '../types';
+import { ActionItemPanel } from './ActionItemPanel';
+import { DecisionPanel } from './DecisionPanel';
+import type { ActivityItem, Backlink, BudgetStatus, CustomField, DependencyGraph, LinkReport, PhaseDuration, Project, Milestone, ProjectStakeholder, ProjectResource, MilestoneResource, Person, Note, ProjectNote, MilestoneNote, StakeholderNote } from '../types';
 
 const { Title, Link } = Typography;
 
@@ -38,6 +41,7 @@ export const ProjectDetail: React.FC<ProjectDetailProps> = ({ projectId, onEdit,
   const [people, setPeople] = useState<Person[]>([]);
   const [projectNotes, setProjectNotes] = useState<ProjectNote[]>([]);
   const [jiraBaseUrl, setJiraBaseUrl] = useState<string>('');
+  const [timeZone, setTimeZone] = useState<string>('UTC');
   const [loading, setLoading] = useState(false);
 
   // Milestone modal state
@@ -73,14 +77,90 @@ export const ProjectDetail: React.FC<ProjectDetailProps> = ({ projectId, onEdit,
   const [stakeholderNotes, setStakeholderNotes] = useState<StakeholderNote[]>([]);
   const [showStakeholderNoteForm, setShowStakeholderNoteForm] = useState(false);
 
+  // Blocker modal state
+  const [showBlockModal, setShowBlockModal] = useState(false);
+  const [blockReason, setBlockReason] = useState('');
+  const [suggestedDueDate, setSuggestedDueDate] = useState<string | null>(null);
+  const [activity, setActivity] = useState<ActivityItem[]>([]);
+  const [customFields, setCustomFields] = useState<CustomField[]>([]);
+  const [dependencyGraph, setDependencyGraph] = useState<DependencyGraph | null>(null);
+  const [budget, setBudget] = useState<BudgetStatus | null>(null);
+  const [links, setLinks] = useState<LinkReport[]>([]);
+  const [checkingLinks, setCheckingLinks] = useState(false);
+  const [projectPhases, setProjectPhases] = useState<string[]>([]);
+  const [phaseDurations, setPhaseDurations] = useState<PhaseDuration[]>([]);
+  const [backlinks, setBacklinks] = useState<Backlink[]>([]);
+
   useEffect(() => {
     loadProjectData();
   }, [projectId]);
 
+  const handleBlock = async () => {
+    if (!blockReason.trim()) {
+      message.error('Please enter why the project is blocked');
+      return;
+    }
+    try {
+      setProject(await ProjectService.blockProject(projectId, blockReason));
+      setShowBlockModal(false);
+      setBlockReason('');
+    } catch (error) {
+      message.error('Failed to block project: ' + error);
+    }
+  };
+
+  const handleUnblock = async () => {
+    try {
+      setProject(await ProjectService.unblockProject(projectId));
+    } catch (error) {
+      message.error('Failed to unblock project: ' + error);
+    }
+  };
+
+  const handlePhaseChange = async (phase: string) => {
+    try {
+      setProject(await ProjectService.setProjectPhase(projectId, phase));
+      const summary = await ProjectService.getProjectSummary(projectId);
+      setPhaseDurations(summary?.phase_durations ?? []);
+    } catch (error) {
+      message.error('Failed to change phase: ' + error);
+    }
+  };
+
+  const handleExtendDueDate = async () => {
+    if (!project || !suggestedDueDate) return;
+    try {
+      await ProjectService.updateProject({ ...project, due_date: suggestedDueDate });
+      message.success('Project due date extended');
+      await loadProjectData();
+    } catch (error) {
+      message.error('Failed to extend due date: ' + error);
+    }
+  };
+
+  const handleCheckLinks = async () => {
+    setCheckingLinks(true);
+    try {
+      setLinks(await ProjectService.checkLinks(projectId));
+    } catch (error) {
+      message.error('Failed to check links: ' + error);
+    } finally {
+      setCheckingLinks(false);
+    }
+  };
+
+  const reloadMilestones = async () => {
+    try {
+      setMilestones(await ProjectService.getProjectMilestones(projectId));
+    } catch (error) {
+      message.error('Failed to load milestones: ' + error);
+    }
+  };
+
   const loadProjectData = async () => {
     setLoading(true);
     try {
-      const [projectData, milestonesData, stakeholdersData, resourcesData, peopleData, notesData, jiraUrl] = await Promise.all([
+      const [projectData, milestonesData, stakeholdersData, resourcesData, peopleData, notesData, jiraUrl, tz, suggested, activityData, customFieldsData, graph, budgetData, linksData, phases, summary, backlinksData] = await Promise.all([
         ProjectService.getProject(projectId),
         ProjectService.getProjectMilestones(projectId),
         ProjectService.getProjectStakeholders(projectId),
@@ -88,6 +168,16 @@ export const ProjectDetail: React.FC<ProjectDetailProps> = ({ projectId, onEdit,
         PersonService.listPeople(),
         NoteService.getProjectNotes(projectId),
         ProjectService.getJiraUrl(),
+        ProjectService.getTimezone(),
+        ProjectService.suggestProjectDueDate(projectId),
+        ProjectService.getProjectActivity(projectId, 20),
+        ProjectService.listCustomFields(),
+        ProjectService.getDependencyGraph(),
+        ProjectService.getBudgetStatus(projectId),
+        ProjectService.getProjectLinks(projectId),
+        ProjectService.getProjectPhases(),
+        ProjectService.getProjectSummary(projectId),
+        NoteService.getBacklinks('project', projectId),
       ]);
 
       setProject(projectData);
@@ -97,6 +187,16 @@ export const ProjectDetail: React.FC<ProjectDetailProps> = ({ projectId, onEdit,
       setPeople(peopleData);
       setProjectNotes(notesData);
       setJiraBaseUrl(jiraUrl);
+      setTimeZone(tz);
+      setSuggestedDueDate(suggested);
+      setActivity(activityData);
+      setCustomFields(customFieldsData);
+      setDependencyGraph(graph);
+      setBudget(budgetData);
+      setLinks(linksData);
+      setProjectPhases(phases);
+      setPhaseDurations(summary?.phase_durations ?? []);
+      setBacklinks(backlinksData);
 
       // Load milestone resources for each milestone
       const milestoneResourcesMap = new Map<string, MilestoneResource[]>();
@@ -296,8 +396,12 @@ export const ProjectDetail: React.FC<ProjectDetailProps> = ({ projectId, onEdit,
         await ProjectService.updateMilestoneResource(selectedMilestone.id, resource);
         message.success('Resource updated successfully');
       } else {
-        await ProjectService.addMilestoneResource(selectedMilestone.id, resource);
-        message.success('Resource added successfully');
+        const warning = await ProjectService.addMilestoneResource(selectedMilestone.id, resource);
+        if (warning) {
+          message.warning(`Resource added, but the milestone now has ${warning.resources} resources (limit ${warning.limit})`);
+        } else {
+          message.success('Resource added successfully');
+        }
       }
       setShowMilestoneResourceModal(false);
       setSelectedMilestoneResource(undefined);
@@ -340,8 +444,11 @@ export const ProjectDetail: React.FC<ProjectDetailProps> = ({ projectId, onEdit,
     setShowNoteViewModal(true);
   };
 
+  // Log notes only take new entries, so they open with an empty body to add one
+  const isLogNote = (note?: Note) => (note as ProjectNote | undefined)?.kind === 'log';
+
   const handleEditNote = (note: Note) => {
-    setSelectedNote(note);
+    setSelectedNote(isLogNote(note) ? { ...note, body: '' } : note);
     setShowNoteViewModal(false);
     setShowNoteModal(true);
   };
@@ -351,7 +458,10 @@ export const ProjectDetail: React.FC<ProjectDetailProps> = ({ projectId, onEdit,
       if (noteId) {
         // Editing existing note
         const existingNote = projectNotes.find(n => n.id === noteId);
-        if (existingNote) {
+        if (existingNote?.kind === 'log') {
+          await NoteService.appendToNote(noteId, body);
+          message.success('Entry added successfully');
+        } else if (existingNote) {
           const updatedNote: ProjectNote = {
             ...existingNote,
             title,
@@ -460,7 +570,7 @@ export const ProjectDetail: React.FC<ProjectDetailProps> = ({ projectId, onEdit,
 
   const formatDate = (dateString?: string) => {
     if (!dateString) return '-';
-    return new Date(dateString).toLocaleDateString();
+    return new Date(dateString).toLocaleDateString(undefined, { timeZone });
   };
 
   const renderJiraLink = (ticketNumber?: string) => {
@@ -472,6 +582,11 @@ export const ProjectDetail: React.FC<ProjectDetailProps> = ({ projectId, onEdit,
     );
   };
 
+  // Projects this one depends on, red when they are blocked
+  const dependsOn = (dependencyGraph?.nodes ?? []).filter((node) =>
+    dependencyGraph?.edges.some((e) => e.project_id === projectId && e.depends_on_project_id === node.project_id)
+  );
+
   const milestoneColumns: ColumnsType<Milestone> = [
     {
       title: '#',
@@ -508,6 +623,15 @@ export const ProjectDetail: React.FC<ProjectDetailProps> = ({ projectId, onEdit,
       width: 120,
       render: formatDate,
     },
+    {
+      title: 'Checklist',
+      key: 'checklist',
+      width: 100,
+      render: (_, record) => {
+        const items = record.checklist ?? [];
+        return items.length > 0 ? `${items.filter(item => item.checked).length}/${items.length}` : '-';
+      },
+    },
     {
       title: 'Jira Epic',
       dataIndex: 'jira_epic',
@@ -586,6 +710,18 @@ export const ProjectDetail: React.FC<ProjectDetailProps> = ({ projectId, onEdit,
       key: 'role',
       render: (text) => text || '-',
     },
+    {
+      title: 'Influence',
+      dataIndex: 'influence',
+      key: 'influence',
+      render: (value) => value ?? '-',
+    },
+    {
+      title: 'Interest',
+      dataIndex: 'interest',
+      key: 'interest',
+      render: (value) => value ?? '-',
+    },
     {
       title: 'Actions',
       key: 'actions',
@@ -738,15 +874,80 @@ export const ProjectDetail: React.FC<ProjectDetailProps> = ({ projectId, onEdit,
         <Button type="primary" icon={<EditOutlined />} onClick={onEdit}>
           Edit Project
         </Button>
+        {project.blocked ? (
+          <Button icon={<CheckCircleOutlined />} onClick={handleUnblock}>
+            Unblock
+          </Button>
+        ) : (
+          <Button danger icon={<StopOutlined />} onClick={() => setShowBlockModal(true)}>
+            Mark Blocked
+          </Button>
+        )}
+        <Button icon={<LinkOutlined />} loading={checkingLinks} onClick={handleCheckLinks}>
+          Check Links
+        </Button>
       </Space>
 
       <Title level={2}>{project.name}</Title>
 
+      {project.blocked && (
+        <Alert
+          type="error"
+          showIcon
+          message={`Blocked: ${project.blocked_reason || 'no reason given'}`}
+          style={{ marginBottom: 16 }}
+        />
+      )}
+
+      {project.due_date && suggestedDueDate && new Date(suggestedDueDate) > new Date(project.due_date) && (
+        <Alert
+          type="warning"
+          showIcon
+          message={`Milestones are due after the project's due date, as late as ${formatDate(suggestedDueDate)}`}
+          action={
+            <Button size="small" onClick={handleExtendDueDate}>
+              Extend Project
+            </Button>
+          }
+          style={{ marginBottom: 16 }}
+        />
+      )}
+
+      {links.some((link) => link.check?.status === 'dead' || link.check?.status === 'unreachable') && (
+        <Alert
+          type="warning"
+          showIcon
+          message="Some links were dead when last checked"
+          description={links
+            .filter((link) => link.check?.status === 'dead' || link.check?.status === 'unreachable')
+            .map((link) => `${link.label}: ${link.url} (${link.check?.http_status ?? link.check?.error ?? link.check?.status})`)
+            .join('; ')}
+          style={{ marginBottom: 16 }}
+        />
+      )}
+
       <Card title="Project Details" style={{ marginBottom: 16 }}>
         <Descriptions column={2}>
           <Descriptions.Item label="Description" span={2}>
             {project.description || '-'}
           </Descriptions.Item>
+          <Descriptions.Item label="Phase" span={2}>
+            <Space wrap>
+              <Select
+                size="small"
+                style={{ minWidth: 140 }}
+                placeholder="No phase"
+                value={project.phase}
+                onChange={handlePhaseChange}
+                options={projectPhases.map((phase) => ({ value: phase, label: phase }))}
+              />
+              {phaseDurations.map((duration) => (
+                <Tag key={duration.phase} color={duration.current ? 'blue' : undefined}>
+                  {duration.phase}: {duration.days} {duration.days === 1 ? 'day' : 'days'}
+                </Tag>
+              ))}
+            </Space>
+          </Descriptions.Item>
           <Descriptions.Item label="Requirements Owner">
             {project.requirements_owner || '-'}
           </Descriptions.Item>
@@ -762,6 +963,38 @@ export const ProjectDetail: React.FC<ProjectDetailProps> = ({ projectId, onEdit,
           <Descriptions.Item label="Jira Initiative">
             {renderJiraLink(project.jira_initiative)}
           </Descriptions.Item>
+          {budget?.budget_amount != null && (
+            <Descriptions.Item label="Budget" span={2}>
+              {budget.budget_amount.toFixed(2)} {budget.budget_currency ?? ''}
+              {' '}({budget.total_spent.toFixed(2)} spent,{' '}
+              {(budget.remaining ?? 0) < 0
+                ? <Tag color="red">{(-(budget.remaining ?? 0)).toFixed(2)} over budget</Tag>
+                : `${(budget.remaining ?? 0).toFixed(2)} remaining`})
+            </Descriptions.Item>
+          )}
+          <Descriptions.Item label="Depends On" span={2}>
+            {dependsOn.length > 0
+              ? dependsOn.map((node) => (
+                  <Tag key={node.project_id} color={node.blocked ? 'red' : undefined}>
+                    {node.project_name}
+                  </Tag>
+                ))
+              : '-'}
+          </Descriptions.Item>
+          {backlinks.length > 0 && (
+            <Descriptions.Item label="Referenced In" span={2}>
+              {backlinks.map((link) => (
+                <Tag key={link.note_id}>
+                  {link.project_id === projectId ? link.title : `${link.project_name}: ${link.title}`}
+                </Tag>
+              ))}
+            </Descriptions.Item>
+          )}
+          {customFields.map((field) => (
+            <Descriptions.Item key={field.key} label={field.label}>
+              {project.custom_fields?.[field.key] ?? '-'}
+            </Descriptions.Item>
+          ))}
           <Descriptions.Item label="Created">
             {formatDate(project.created_at)}
           </Descriptions.Item>
@@ -790,7 +1023,12 @@ export const ProjectDetail: React.FC<ProjectDetailProps> = ({ projectId, onEdit,
           dataSource={milestones}
           rowKey="id"
           pagination={false}
-          scroll={{ x: 1100 }}
+          scroll={{ x: 1200 }}
+          expandable={{
+            expandedRowRender: (milestone) => (
+              <MilestoneChecklist milestone={milestone} onChange={reloadMilestones} />
+            ),
+          }}
           locale={{ emptyText: 'No milestones yet' }}
         />
       </Card>
@@ -841,6 +1079,19 @@ export const ProjectDetail: React.FC<ProjectDetailProps> = ({ projectId, onEdit,
         />
       </Card>
 
+      <ActionItemPanel
+        projectId={projectId}
+        milestones={milestones}
+        people={people}
+        timeZone={timeZone}
+      />
+
+      <DecisionPanel
+        projectId={projectId}
+        people={people}
+        timeZone={timeZone}
+      />
+
       <Card
         title="Notes"
         extra={
@@ -862,6 +1113,41 @@ export const ProjectDetail: React.FC<ProjectDetailProps> = ({ projectId, onEdit,
         />
       </Card>
 
+      <Card title="Recent Activity" style={{ marginTop: 16 }}>
+        {activity.length === 0 ? (
+          <Typography.Text type="secondary">No activity yet</Typography.Text>
+        ) : (
+          <Timeline
+            items={activity.map((item) => ({
+              key: `${item.kind}-${item.timestamp}-${item.summary}`,
+              color: item.kind === 'blocked' ? 'red' : item.kind === 'milestone_completed' ? 'green' : 'blue',
+              children: (
+                <>
+                  <div>{item.summary}</div>
+                  <Typography.Text type="secondary">{new Date(item.timestamp).toLocaleString()}</Typography.Text>
+                </>
+              ),
+            }))}
+          />
+        )}
+      </Card>
+
+      <Modal
+        title="Mark Project Blocked"
+        open={showBlockModal}
+        onCancel={() => setShowBlockModal(false)}
+        onOk={handleBlock}
+        okText="Mark Blocked"
+        destroyOnClose
+      >
+        <Input.TextArea
+          rows={3}
+          placeholder="Why is this project blocked?"
+          value={blockReason}
+          onChange={(e) => setBlockReason(e.target.value)}
+        />
+      </Modal>
+
       <Modal
         title={selectedMilestone ? 'Edit Milestone' : 'New Milestone'}
         open={showMilestoneModal}
@@ -931,7 +1217,7 @@ export const ProjectDetail: React.FC<ProjectDetailProps> = ({ projectId, onEdit,
       </Modal>
 
       <Modal
-        title={selectedNote ? 'Edit Note' : 'Add Note'}
+        title={isLogNote(selectedNote) ? 'Add Log Entry' : selectedNote ? 'Edit Note' : 'Add Note'}
         open={showNoteModal}
         onCancel={handleCancelNote}
         footer={null}
@@ -947,6 +1233,7 @@ export const ProjectDetail: React.FC<ProjectDetailProps> = ({ projectId, onEdit,
 
       <NoteViewModal
         note={noteForView}
+        noteType="project"
         open={showNoteViewModal}
         onClose={() => {
           setShowNoteViewModal(false);
@@ -5,7 +5,7 @@
  */
 
 import { useState, useEffect } from 'react';
-import { Form, Input, Button, Card, message, DatePicker, Space, Select } from 'antd';
+import { Form, Input, Button, Card, Checkbox, message, DatePicker, Space, Select, InputNumber } from 'antd';
 import { SaveOutlined, CloseOutlined } from '@ant-design/icons';
 import { ProjectService } from '../services/projectService';
 import { PersonSelector } from './PersonSelector';
@@ -24,9 +24,27 @@ interface ProjectFormProps {
 export const ProjectForm: React.FC<ProjectFormProps> = ({ project, onSave, onCancel }) => {
   const [form] = Form.useForm();
   const [loading, setLoading] = useState(false);
+  const [projectTypes, setProjectTypes] = useState<string[]>(['Personal', 'Team', 'Company']);
+  const [projectPhases, setProjectPhases] = useState<string[]>([]);
 
   const isEditing = !!project;
 
+  useEffect(() => {
+    const loadProjectTypes = () => {
+      ProjectService.getProjectTypes()
+        .then(setProjectTypes)
+        .catch((error) => console.error('Failed to load project types:', error));
+      ProjectService.getProjectPhases()
+        .then(setProjectPhases)
+        .catch((error) => console.error('Failed to load project phases:', error));
+    };
+    loadProjectTypes();
+    const unlisten = ProjectService.onConfigChanged(loadProjectTypes);
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+
   useEffect(() => {
     if (project) {
       form.setFieldsValue({
@@ -43,6 +61,7 @@ export const ProjectForm: React.FC<ProjectFormProps> = ({ project, onSave, onCan
       const projectData: Project = {
         id: project?.id || crypto.randomUUID(),
         name: values.name,
+        slug: project?.slug ?? '',
         description: values.description || undefined,
         type: values.type || 'Personal',
         requirements_owner: values.requirements_owner || undefined,
@@ -52,15 +71,23 @@ export const ProjectForm: React.FC<ProjectFormProps> = ({ project, onSave, onCan
         start_date: values.start_date ? values.start_date.toISOString() : undefined,
         due_date: values.due_date ? values.due_date.toISOString() : undefined,
         jira_initiative: values.jira_initiative || undefined,
+        budget_amount: values.budget_amount ?? undefined,
+        budget_currency: values.budget_currency ? values.budget_currency.toUpperCase() : undefined,
+        status: values.status,
+        phase: values.phase || undefined,
+        board_position: project?.board_position,
+        blocked: project?.blocked,
+        blocked_reason: project?.blocked_reason,
         created_at: project?.created_at || new Date().toISOString(),
         updated_at: new Date().toISOString(),
+        version: project?.version,
       };
 
       if (isEditing) {
         await ProjectService.updateProject(projectData);
         message.success('Project updated successfully');
       } else {
-        await ProjectService.createProject(projectData);
+        await ProjectService.createProject(projectData, values.skip_bootstrap);
         message.success('Project created successfully');
       }
 
@@ -82,6 +109,7 @@ export const ProjectForm: React.FC<ProjectFormProps> = ({ project, onSave, onCan
           name: '',
           description: '',
           type: 'Personal',
+          status: 'active',
           requirements_owner: undefined,
           technical_lead: undefined,
           manager: undefined,
@@ -89,6 +117,8 @@ export const ProjectForm: React.FC<ProjectFormProps> = ({ project, onSave, onCan
           start_date: null,
           due_date: null,
           jira_initiative: '',
+          budget_amount: null,
+          budget_currency: '',
         }}
       >
         <Form.Item
@@ -112,12 +142,46 @@ export const ProjectForm: React.FC<ProjectFormProps> = ({ project, onSave, onCan
           rules={[{ required: true, message: 'Please select a project type' }]}
         >
           <Select placeholder="Select project type">
-            <Select.Option value="Personal">Personal</Select.Option>
-            <Select.Option value="Team">Team</Select.Option>
-            <Select.Option value="Company">Company</Select.Option>
+            {projectTypes.map((projectType) => (
+              <Select.Option key={projectType} value={projectType}>{projectType}</Select.Option>
+            ))}
+          </Select>
+        </Form.Item>
+
+        <Form.Item
+          name="status"
+          label="Status"
+        >
+          <Select>
+            <Select.Option value="planning">Planning</Select.Option>
+            <Select.Option value="active">Active</Select.Option>
+            <Select.Option value="on_hold">On Hold</Select.Option>
+            <Select.Option value="done">Done</Select.Option>
           </Select>
         </Form.Item>
 
+        <Form.Item
+          name="phase"
+          label="Phase"
+          extra="Changes are kept in the project's phase history"
+        >
+          <Select allowClear placeholder="Select phase">
+            {projectPhases.map((phase) => (
+              <Select.Option key={phase} value={phase}>{phase}</Select.Option>
+            ))}
+          </Select>
+        </Form.Item>
+
+        {!isEditing && (
+          <Form.Item
+            name="skip_bootstrap"
+            valuePropName="checked"
+            extra="New projects start with the milestones, notes and stakeholders configured for their type"
+          >
+            <Checkbox>Skip setup for this project type</Checkbox>
+          </Form.Item>
+        )}
+
         <Form.Item
           name="requirements_owner"
           label="Requirements Owner"
@@ -168,6 +232,19 @@ export const ProjectForm: React.FC<ProjectFormProps> = ({ project, onSave, onCan
           <Input placeholder="e.g., PROJ-123" />
         </Form.Item>
 
+        <Space style={{ width: '100%' }} size="middle">
+          <Form.Item name="budget_amount" label="Budget">
+            <InputNumber min={0} step={100} placeholder="e.g., 25000" />
+          </Form.Item>
+          <Form.Item
+            name="budget_currency"
+            label="Currency"
+            rules={[{ pattern: /^[A-Za-z]{3}$/, message: 'Use a three-letter code such as USD' }]}
+          >
+            <Input maxLength={3} placeholder="e.g., USD" />
+          </Form.Item>
+        </Space>
+
         <Form.Item>
           <Space>
             <Button
@@ -5,11 +5,12 @@
  */
 
 import { useState, useEffect } from 'react';
-import { Table, Button, Space, message, Modal, Typography } from 'antd';
-import { PlusOutlined, EyeOutlined, EditOutlined, DeleteOutlined, LinkOutlined } from '@ant-design/icons';
+import { Table, Button, Space, message, Modal, Typography, Progress, Tooltip, Statistic, Alert, Select } from 'antd';
+import { PlusOutlined, EyeOutlined, EditOutlined, DeleteOutlined, LinkOutlined, FileExcelOutlined } from '@ant-design/icons';
 import type { ColumnsType } from 'antd/es/table';
 import { ProjectService } from '../services/projectService';
-import type { Project } from '../types';
+import { OrganizationService } from '../services/organizationService';
+import type { Organization, PortfolioStats, Project, ProjectSummary } from '../types';
 
 const { Link } = Typography;
 
@@ -25,19 +26,42 @@ export const ProjectList: React.FC<ProjectListProps> = ({
   onCreateProject,
 }) => {
   const [projects, setProjects] = useState<Project[]>([]);
+  const [summaries, setSummaries] = useState<Record<string, ProjectSummary>>({});
+  const [stats, setStats] = useState<PortfolioStats | null>(null);
   const [loading, setLoading] = useState(false);
+  const [exporting, setExporting] = useState(false);
+  const [organizations, setOrganizations] = useState<Organization[]>([]);
+  const [organization, setOrganization] = useState<string | undefined>();
   const [jiraBaseUrl, setJiraBaseUrl] = useState<string>('');
+  const [timeZone, setTimeZone] = useState<string>('UTC');
+  const [unconfiguredTypes, setUnconfiguredTypes] = useState<string[]>([]);
 
   useEffect(() => {
-    loadProjects();
-    loadJiraUrl();
+    loadSettings();
+    OrganizationService.listOrganizations()
+      .then(setOrganizations)
+      .catch((error) => console.error('Failed to load organizations:', error));
+    const unlisten = ProjectService.onConfigChanged(loadSettings);
+    return () => {
+      unlisten.then((stop) => stop());
+    };
   }, []);
 
+  useEffect(() => {
+    loadProjects();
+  }, [organization]);
+
   const loadProjects = async () => {
     setLoading(true);
     try {
-      const data = await ProjectService.listProjects();
+      const [data, summaryList, portfolio] = await Promise.all([
+        ProjectService.listProjects(false, organization),
+        ProjectService.listProjectSummaries(),
+        ProjectService.getPortfolioStats(),
+      ]);
       setProjects(data);
+      setStats(portfolio);
+      setSummaries(Object.fromEntries(summaryList.map((s) => [s.project_id, s])));
     } catch (error) {
       message.error('Failed to load projects: ' + error);
     } finally {
@@ -45,12 +69,21 @@ export const ProjectList: React.FC<ProjectListProps> = ({
     }
   };
 
-  const loadJiraUrl = async () => {
+  const loadSettings = async () => {
     try {
-      const url = await ProjectService.getJiraUrl();
+      const [url, tz, configuredTypes, typesInUse] = await Promise.all([
+        ProjectService.getJiraUrl(),
+        ProjectService.getTimezone(),
+        ProjectService.getProjectTypes(),
+        ProjectService.listProjectTypesInUse(),
+      ]);
       setJiraBaseUrl(url);
+      setTimeZone(tz);
+      setUnconfiguredTypes(
+        configuredTypes.length === 0 ? [] : typesInUse.filter((t) => !configuredTypes.includes(t))
+      );
     } catch (error) {
-      console.error('Failed to load Jira URL:', error);
+      console.error('Failed to load settings:', error);
     }
   };
 
@@ -72,9 +105,24 @@ export const ProjectList: React.FC<ProjectListProps> = ({
     });
   };
 
+  const handleExport = async () => {
+    setExporting(true);
+    try {
+      const sheets = await ProjectService.exportWorkbook();
+      if (sheets) {
+        const projectRows = sheets.find((s) => s.name === 'Projects')?.rows ?? 0;
+        message.success(`Exported ${projectRows} projects to Excel`);
+      }
+    } catch (error) {
+      message.error('Failed to export: ' + error);
+    } finally {
+      setExporting(false);
+    }
+  };
+
   const formatDate = (dateString?: string) => {
     if (!dateString) return '-';
-    return new Date(dateString).toLocaleDateString();
+    return new Date(dateString).toLocaleDateString(undefined, { timeZone });
   };
 
   const columns: ColumnsType<Project> = [
@@ -98,6 +146,32 @@ export const ProjectList: React.FC<ProjectListProps> = ({
         return dateA - dateB;
       },
     },
+    {
+      title: 'Progress',
+      key: 'progress',
+      width: 160,
+      render: (_, record) => {
+        const summary = summaries[record.id];
+        if (!summary || summary.progress_percent === undefined || summary.progress_percent === null) return '-';
+        return (
+          <Tooltip
+            title={`${summary.completed_milestones} of ${summary.total_milestones} milestones past their due date`}
+          >
+            <Progress percent={summary.progress_percent} size="small" />
+          </Tooltip>
+        );
+      },
+    },
+    {
+      title: 'Next Milestone',
+      key: 'next_milestone',
+      width: 200,
+      ellipsis: true,
+      render: (_, record) => {
+        const next = summaries[record.id]?.next_milestone;
+        return next ? `${next.name} (${formatDate(next.due_date)})` : '-';
+      },
+    },
     {
       title: 'Jira Initiative',
       dataIndex: 'jira_initiative',
@@ -172,7 +246,28 @@ export const ProjectList: React.FC<ProjectListProps> = ({
 
   return (
     <div>
-      <div style={{ marginBottom: 16 }}>
+      {stats && (
+        <Tooltip title={`As of ${new Date(stats.as_of).toLocaleString(undefined, { timeZone })}`}>
+          <Space size="large" style={{ marginBottom: 16 }}>
+            <Statistic title="Projects" value={stats.total_projects} />
+            <Statistic title="Due This Month" value={stats.milestones_due_this_month} />
+            <Statistic title="Due This Quarter" value={stats.milestones_due_this_quarter} />
+            <Statistic title="Unassigned People" value={stats.unassigned_people} />
+            <Statistic title="Teams Without Manager" value={stats.teams_without_manager} />
+            <Statistic title="Notes (7 days)" value={stats.notes_last_7_days} />
+          </Space>
+        </Tooltip>
+      )}
+      {unconfiguredTypes.length > 0 && (
+        <Alert
+          type="warning"
+          showIcon
+          style={{ marginBottom: 16 }}
+          message={`Some projects use types that aren't in the configuration: ${unconfiguredTypes.join(', ')}`}
+          description="These projects can't be saved until their type is changed. Run `track config rename-type <old> <new>` to rename a type everywhere."
+        />
+      )}
+      <Space style={{ marginBottom: 16 }}>
         <Button
           type="primary"
           icon={<PlusOutlined />}
@@ -180,13 +275,26 @@ export const ProjectList: React.FC<ProjectListProps> = ({
         >
           New Project
         </Button>
-      </div>
+        <Button icon={<FileExcelOutlined />} loading={exporting} onClick={handleExport}>
+          Export to Excel
+        </Button>
+        {organizations.length > 0 && (
+          <Select
+            allowClear
+            placeholder="Filter by organization"
+            style={{ width: 220 }}
+            value={organization}
+            onChange={setOrganization}
+            options={organizations.map((o) => ({ value: o.name, label: o.name }))}
+          />
+        )}
+      </Space>
       <Table
         columns={columns}
         dataSource={projects}
         rowKey="id"
         loading={loading}
-        scroll={{ x: 1400 }}
+        scroll={{ x: 1760 }}
         pagination={{
           showSizeChanger: true,
           showTotal: (total) => `Total ${total} projects`,
@@ -5,9 +5,10 @@
  */
 
 import { useState } from 'react';
-import { Form, Input, Button, message, Space } from 'antd';
+import { Form, Button, message, Space } from 'antd';
 import { SaveOutlined, CloseOutlined } from '@ant-design/icons';
 import { PersonSelector } from './PersonSelector';
+import { RoleInput } from './RoleInput';
 import type { ProjectResource } from '../types';
 
 interface ProjectResourceFormProps {
@@ -73,7 +74,7 @@ export const ProjectResourceForm: React.FC<ProjectResourceFormProps> = ({
         name="role"
         label="Role"
       >
-        <Input placeholder="Enter role (e.g., Developer, Designer, PM)" />
+        <RoleInput placeholder="Enter role (e.g., Developer, Designer, PM)" />
       </Form.Item>
 
       <Form.Item>
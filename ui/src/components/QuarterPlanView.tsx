@@ -0,0 +1,124 @@
+/**
+ * Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+import { useState, useEffect } from 'react';
+import { Card, Collapse, Empty, InputNumber, List, Select, Space, Spin, Tag, Typography, message } from 'antd';
+import { ProjectService } from '../services/projectService';
+import { TeamService } from '../services/teamService';
+import type { Project, QuarterPlan } from '../types';
+
+const { Link, Text, Title } = Typography;
+
+interface QuarterPlanViewProps {
+  onViewProject: (project: Project) => void;
+}
+
+// The calendar quarter a date falls in, 1 to 4
+const quarterOf = (date: Date) => Math.floor(date.getMonth() / 3) + 1;
+
+export const QuarterPlanView: React.FC<QuarterPlanViewProps> = ({ onViewProject }) => {
+  const today = new Date();
+  const [year, setYear] = useState(today.getFullYear());
+  const [quarter, setQuarter] = useState(quarterOf(today));
+  const [team, setTeam] = useState<string | undefined>(undefined);
+  const [teams, setTeams] = useState<string[]>([]);
+  const [timeZone, setTimeZone] = useState<string>('UTC');
+  const [plan, setPlan] = useState<QuarterPlan | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  useEffect(() => {
+    const loadSettings = async () => {
+      try {
+        const [teamList, tz] = await Promise.all([TeamService.listTeams(), ProjectService.getTimezone()]);
+        setTeams(teamList.map((t) => t.name));
+        setTimeZone(tz);
+      } catch (error) {
+        console.error('Failed to load settings:', error);
+      }
+    };
+    loadSettings();
+  }, []);
+
+  useEffect(() => {
+    const loadPlan = async () => {
+      setLoading(true);
+      try {
+        setPlan(await ProjectService.getQuarterPlan(year, quarter, team));
+      } catch (error) {
+        message.error('Failed to load quarter plan: ' + error);
+      } finally {
+        setLoading(false);
+      }
+    };
+    loadPlan();
+  }, [year, quarter, team]);
+
+  const openProject = async (id: string) => {
+    const project = await ProjectService.getProject(id);
+    if (project) {
+      onViewProject(project);
+    }
+  };
+
+  const formatDate = (dateString?: string) =>
+    dateString ? new Date(dateString).toLocaleDateString(undefined, { timeZone }) : '-';
+
+  return (
+    <Card
+      title={<Title level={4}>Planning</Title>}
+      extra={
+        <Space>
+          <InputNumber value={year} min={1970} max={9999} onChange={(value) => value && setYear(value)} />
+          <Select
+            value={quarter}
+            onChange={setQuarter}
+            options={[1, 2, 3, 4].map((q) => ({ value: q, label: `Q${q}` }))}
+          />
+          <Select
+            allowClear
+            placeholder="All teams"
+            value={team}
+            onChange={setTeam}
+            options={teams.map((name) => ({ value: name, label: name }))}
+            style={{ minWidth: 160 }}
+          />
+        </Space>
+      }
+    >
+      {loading || !plan ? (
+        <Spin />
+      ) : plan.teams.length === 0 ? (
+        <Empty description={`No milestones due in Q${plan.quarter} ${plan.year}`} />
+      ) : (
+        <Collapse
+          defaultActiveKey={plan.teams.map((t) => t.team ?? '')}
+          items={plan.teams.map((t) => ({
+            key: t.team ?? '',
+            label: (
+              <Space>
+                <Text strong>{t.team ?? 'Unassigned'}</Text>
+                <Tag>{t.milestone_count}</Tag>
+              </Space>
+            ),
+            children: t.projects.map((p) => (
+              <List
+                key={p.project_id}
+                size="small"
+                header={<Link onClick={() => openProject(p.project_id)}>{p.project_name}</Link>}
+                dataSource={p.milestones}
+                renderItem={(m) => (
+                  <List.Item key={m.id} extra={formatDate(m.due_date)}>
+                    #{m.number} {m.name}
+                  </List.Item>
+                )}
+              />
+            )),
+          }))}
+        />
+      )}
+    </Card>
+  );
+};
@@ -0,0 +1,44 @@
+/**
+ * Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+import { useState, useEffect } from 'react';
+import { AutoComplete } from 'antd';
+import { ProjectService } from '../services/projectService';
+
+interface RoleInputProps {
+  value?: string;
+  onChange?: (value: string) => void;
+  placeholder?: string;
+}
+
+// Free text role input that suggests the roles already in use, so the same
+// role isn't written several ways
+export const RoleInput: React.FC<RoleInputProps> = ({ value, onChange, placeholder }) => {
+  const [options, setOptions] = useState<{ value: string }[]>([]);
+
+  const search = async (query: string) => {
+    try {
+      const roles = await ProjectService.autocompleteRoles(query);
+      setOptions(roles.map((role) => ({ value: role })));
+    } catch (error) {
+      console.error('Failed to load roles:', error);
+    }
+  };
+
+  useEffect(() => {
+    search('');
+  }, []);
+
+  return (
+    <AutoComplete
+      value={value}
+      options={options}
+      onSearch={search}
+      onChange={onChange}
+      placeholder={placeholder}
+    />
+  );
+};
@@ -5,9 +5,10 @@
  */
 
 import { useState } from 'react';
-import { Form, Input, Button, message, Space } from 'antd';
+import { Form, Button, InputNumber, message, Space } from 'antd';
 import { SaveOutlined, CloseOutlined } from '@ant-design/icons';
 import { PersonSelector } from './PersonSelector';
+import { RoleInput } from './RoleInput';
 import type { ProjectStakeholder } from '../types';
 
 interface StakeholderFormProps {
@@ -37,6 +38,8 @@ export const StakeholderForm: React.FC<StakeholderFormProps> = ({
         project_id: projectId,
         stakeholder_email: values.stakeholder_email,
         role: values.role || undefined,
+        influence: values.influence ?? undefined,
+        interest: values.interest ?? undefined,
         created_at: stakeholder?.created_at || new Date().toISOString(),
       };
 
@@ -59,6 +62,8 @@ export const StakeholderForm: React.FC<StakeholderFormProps> = ({
       initialValues={{
         stakeholder_email: stakeholder?.stakeholder_email || undefined,
         role: stakeholder?.role || '',
+        influence: stakeholder?.influence,
+        interest: stakeholder?.interest,
       }}
     >
       <Form.Item
@@ -73,9 +78,27 @@ export const StakeholderForm: React.FC<StakeholderFormProps> = ({
         name="role"
         label="Role"
       >
-        <Input placeholder="Enter stakeholder role (e.g., Sponsor, Reviewer)" />
+        <RoleInput placeholder="Enter stakeholder role (e.g., Sponsor, Reviewer)" />
       </Form.Item>
 
+      <Space size="large">
+        <Form.Item
+          name="influence"
+          label="Influence"
+          tooltip="How much they can affect the project, from 1 (little) to 5 (a lot)"
+        >
+          <InputNumber min={1} max={5} precision={0} placeholder="1-5" />
+        </Form.Item>
+
+        <Form.Item
+          name="interest"
+          label="Interest"
+          tooltip="How much the project affects them, from 1 (little) to 5 (a lot)"
+        >
+          <InputNumber min={1} max={5} precision={0} placeholder="1-5" />
+        </Form.Item>
+      </Space>
+
       <Form.Item>
         <Space>
           <Button
@@ -5,12 +5,13 @@
  */
 
 import { useState, useEffect } from 'react';
-import { Card, Descriptions, Button, Space, Table, message, Modal, Select } from 'antd';
-import { EditOutlined, ArrowLeftOutlined, UserAddOutlined, DeleteOutlined } from '@ant-design/icons';
+import { Card, Descriptions, Button, DatePicker, Space, Table, message, Modal, Select } from 'antd';
+import { EditOutlined, ArrowLeftOutlined, UserAddOutlined, DeleteOutlined, FileTextOutlined } from '@ant-design/icons';
+import dayjs, { type Dayjs } from 'dayjs';
 import type { ColumnsType } from 'antd/es/table';
 import { TeamService } from '../services/teamService';
 import { PersonService } from '../services/personService';
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../services/invoke';
 import type { Team, Person, Project } from '../types';
 
 interface TeamDetailProps {
@@ -27,6 +28,8 @@ export const TeamDetail: React.FC<TeamDetailProps> = ({ team, onEdit, onBack, on
   const [loading, setLoading] = useState(false);
   const [addMemberModalVisible, setAddMemberModalVisible] = useState(false);
   const [selectedPersonEmail, setSelectedPersonEmail] = useState<string | undefined>();
+  const [reviewQuarter, setReviewQuarter] = useState<Dayjs | null>(dayjs());
+  const [review, setReview] = useState<string | null>(null);
 
   useEffect(() => {
     loadMembers();
@@ -101,6 +104,19 @@ export const TeamDetail: React.FC<TeamDetailProps> = ({ team, onEdit, onBack, on
     });
   };
 
+  const handleReview = async () => {
+    if (!reviewQuarter) {
+      message.warning('Please pick a quarter');
+      return;
+    }
+    try {
+      const quarter = Math.floor(reviewQuarter.month() / 3) + 1;
+      setReview(await TeamService.generateTeamReview(team.name, reviewQuarter.year(), quarter));
+    } catch (error) {
+      message.error('Failed to generate review: ' + error);
+    }
+  };
+
   const formatDate = (dateString?: string) => {
     if (!dateString) return '-';
     return new Date(dateString).toLocaleDateString();
@@ -193,6 +209,10 @@ export const TeamDetail: React.FC<TeamDetailProps> = ({ team, onEdit, onBack, on
         <Button type="primary" icon={<EditOutlined />} onClick={onEdit}>
           Edit Team
         </Button>
+        <DatePicker picker="quarter" value={reviewQuarter} onChange={setReviewQuarter} />
+        <Button icon={<FileTextOutlined />} onClick={handleReview}>
+          Quarterly Review
+        </Button>
       </Space>
 
       <Card title={`Team: ${team.name}`} style={{ marginBottom: 16 }}>
@@ -248,6 +268,16 @@ export const TeamDetail: React.FC<TeamDetailProps> = ({ team, onEdit, onBack, on
         />
       </Card>
 
+      <Modal
+        title={`${team.name} quarterly review`}
+        open={review !== null}
+        onCancel={() => setReview(null)}
+        footer={null}
+        width={800}
+      >
+        <pre style={{ whiteSpace: 'pre-wrap', maxHeight: '70vh', overflow: 'auto' }}>{review}</pre>
+      </Modal>
+
       <Modal
         title="Add Team Member"
         open={addMemberModalVisible}
@@ -25,11 +25,15 @@ export const TeamForm: React.FC<TeamFormProps> = ({ team, initialName, onSave, o
   const [form] = Form.useForm();
   const [loading, setLoading] = useState(false);
   const [people, setPeople] = useState<Person[]>([]);
+  const [teams, setTeams] = useState<Team[]>([]);
 
   const isEditing = !!team && !!team.name;
 
   useEffect(() => {
     loadPeople();
+    TeamService.listTeams()
+      .then(setTeams)
+      .catch((error) => message.error('Failed to load teams: ' + error));
 
     if (team) {
       form.setFieldsValue(team);
@@ -54,6 +58,7 @@ export const TeamForm: React.FC<TeamFormProps> = ({ team, initialName, onSave, o
         name: values.name,
         description: values.description || undefined,
         manager: values.manager || undefined,
+        parent_team: values.parent_team || undefined,
         created_at: team?.created_at || new Date().toISOString(),
         updated_at: new Date().toISOString(),
       };
@@ -79,6 +84,10 @@ export const TeamForm: React.FC<TeamFormProps> = ({ team, initialName, onSave, o
     value: p.email,
   }));
 
+  const parentOptions = teams
+    .filter(t => t.name !== team?.name)
+    .map(t => ({ label: t.name, value: t.name }));
+
   const formContent = (
     <Form
       form={form}
@@ -120,6 +129,18 @@ export const TeamForm: React.FC<TeamFormProps> = ({ team, initialName, onSave, o
         />
       </Form.Item>
 
+      <Form.Item
+        name="parent_team"
+        label="Parent Team"
+      >
+        <Select
+          showSearch
+          placeholder="None (top-level team)"
+          options={parentOptions}
+          allowClear
+        />
+      </Form.Item>
+
       <Form.Item>
         <Space>
           <Button
@@ -9,7 +9,16 @@ import { Table, Button, Space, message, Modal } from 'antd';
 import { PlusOutlined, EditOutlined, DeleteOutlined, EyeOutlined } from '@ant-design/icons';
 import type { ColumnsType } from 'antd/es/table';
 import { TeamService } from '../services/teamService';
-import type { Team } from '../types';
+import type { Team, TeamTreeNode } from '../types';
+
+// Rows nest sub-teams under `children`, which the table renders as an expandable tree
+type TeamRow = Team & { children?: TeamRow[] };
+
+const toRows = (nodes: TeamTreeNode[]): TeamRow[] =>
+  nodes.map(({ subteams, ...team }) => ({
+    ...team,
+    children: subteams.length > 0 ? toRows(subteams) : undefined,
+  }));
 
 interface TeamListProps {
   onEditTeam: (team: Team) => void;
@@ -22,7 +31,7 @@ export const TeamList: React.FC<TeamListProps> = ({
   onCreateTeam,
   onViewTeam,
 }) => {
-  const [teams, setTeams] = useState<Team[]>([]);
+  const [teams, setTeams] = useState<TeamRow[]>([]);
   const [loading, setLoading] = useState(false);
 
   useEffect(() => {
@@ -32,8 +41,8 @@ export const TeamList: React.FC<TeamListProps> = ({
   const loadTeams = async () => {
     setLoading(true);
     try {
-      const data = await TeamService.listTeams();
-      setTeams(data);
+      const tree = await TeamService.getTeamTree();
+      setTeams(toRows(tree));
     } catch (error) {
       message.error('Failed to load teams: ' + error);
     } finally {
@@ -41,15 +50,16 @@ export const TeamList: React.FC<TeamListProps> = ({
     }
   };
 
-  const handleDelete = async (team: Team) => {
+  const handleDelete = async (team: TeamRow) => {
+    const subteamNote = team.children ? ' Its sub-teams will become top-level teams.' : '';
     Modal.confirm({
       title: 'Delete Team',
-      content: `Are you sure you want to delete "${team.name}"? This may affect associated projects and milestones.`,
+      content: `Are you sure you want to delete "${team.name}"? This may affect associated projects and milestones.${subteamNote}`,
       okText: 'Delete',
       okType: 'danger',
       onOk: async () => {
         try {
-          await TeamService.deleteTeam(team.name);
+          await TeamService.deleteTeam(team.name, 'detach');
           message.success('Team deleted successfully');
           loadTeams();
         } catch (error) {
@@ -64,7 +74,7 @@ export const TeamList: React.FC<TeamListProps> = ({
     return new Date(dateString).toLocaleDateString();
   };
 
-  const columns: ColumnsType<Team> = [
+  const columns: ColumnsType<TeamRow> = [
     {
       title: 'Name',
       dataIndex: 'name',
@@ -150,7 +160,7 @@ export const TeamList: React.FC<TeamListProps> = ({
         scroll={{ x: 1000 }}
         pagination={{
           showSizeChanger: true,
-          showTotal: (total) => `Total ${total} teams`,
+          showTotal: (total) => `Total ${total} top-level teams`,
         }}
       />
     </div>
@@ -0,0 +1,110 @@
+/**
+ * Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+import { useState, useEffect } from 'react';
+import { Alert, Button, Card, Table, Tag, Typography, message } from 'antd';
+import { ReloadOutlined } from '@ant-design/icons';
+import type { ColumnsType } from 'antd/es/table';
+import { ProjectService } from '../services/projectService';
+import type { FocusItem, FocusKind, Project } from '../types';
+
+const { Link } = Typography;
+
+const KIND_TAGS: Record<FocusKind, { label: string; color: string }> = {
+  overdue_milestone: { label: 'Overdue milestone', color: 'red' },
+  action_item_due: { label: 'Action item', color: 'orange' },
+  blocked_project: { label: 'Blocked', color: 'volcano' },
+  stakeholder_follow_up: { label: 'Follow up', color: 'blue' },
+  missing_fields: { label: 'Missing fields', color: 'default' },
+};
+
+interface TodayPanelProps {
+  onViewProject: (project: Project) => void;
+}
+
+export const TodayPanel: React.FC<TodayPanelProps> = ({ onViewProject }) => {
+  const [items, setItems] = useState<FocusItem[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    loadFocusList();
+  }, []);
+
+  const loadFocusList = async () => {
+    setLoading(true);
+    try {
+      setItems(await ProjectService.getFocusList());
+      setError(null);
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  // Every item belongs to a project, so open the project it links into
+  const handleOpen = async (item: FocusItem) => {
+    try {
+      const project = await ProjectService.getProject(item.project_id);
+      if (project) {
+        onViewProject(project);
+      }
+    } catch (err) {
+      message.error('Failed to load project: ' + err);
+    }
+  };
+
+  const columns: ColumnsType<FocusItem> = [
+    {
+      title: 'Kind',
+      dataIndex: 'kind',
+      key: 'kind',
+      width: 160,
+      render: (kind: FocusKind) => <Tag color={KIND_TAGS[kind].color}>{KIND_TAGS[kind].label}</Tag>,
+    },
+    {
+      title: 'Item',
+      dataIndex: 'title',
+      key: 'title',
+      ellipsis: true,
+      render: (title: string, item: FocusItem) => (
+        <Link onClick={() => handleOpen(item)} style={{ cursor: 'pointer' }}>
+          {title}
+        </Link>
+      ),
+    },
+    {
+      title: 'Project',
+      dataIndex: 'project_name',
+      key: 'project_name',
+      width: 200,
+      ellipsis: true,
+    },
+    {
+      title: 'Why',
+      dataIndex: 'reason',
+      key: 'reason',
+    },
+  ];
+
+  return (
+    <Card
+      title="Today"
+      extra={<Button icon={<ReloadOutlined />} onClick={loadFocusList} loading={loading}>Refresh</Button>}
+    >
+      {error && <Alert type="info" showIcon message={error} style={{ marginBottom: 16 }} />}
+      <Table
+        columns={columns}
+        dataSource={items}
+        rowKey={(item) => `${item.kind}:${item.link}:${item.project_id}`}
+        loading={loading}
+        pagination={false}
+        locale={{ emptyText: 'Nothing needs attention today' }}
+      />
+    </Card>
+  );
+};
@@ -0,0 +1,146 @@
+/**
+ * Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+import { useState, useEffect } from 'react';
+import { Alert, Button, Card, Form, Input, Popconfirm, Select, Space, Table, Tag, Typography, message } from 'antd';
+import type { ColumnsType } from 'antd/es/table';
+import { UserService } from '../services/userService';
+import type { CurrentRole, User, UserRole } from '../types';
+
+const { Text, Title } = Typography;
+
+const ROLE_OPTIONS: { value: UserRole; label: string }[] = [
+  { value: 'viewer', label: 'Viewer' },
+  { value: 'editor', label: 'Editor' },
+  { value: 'admin', label: 'Admin' },
+];
+
+const ROLE_COLORS: Record<UserRole, string> = {
+  viewer: 'default',
+  editor: 'blue',
+  admin: 'gold',
+};
+
+export const UsersView: React.FC = () => {
+  const [current, setCurrent] = useState<CurrentRole | null>(null);
+  const [users, setUsers] = useState<User[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [form] = Form.useForm<{ email: string; role: UserRole }>();
+
+  useEffect(() => {
+    loadUsers();
+  }, []);
+
+  const loadUsers = async () => {
+    setLoading(true);
+    try {
+      const [role, list] = await Promise.all([UserService.getCurrentRole(), UserService.listUsers()]);
+      setCurrent(role);
+      setUsers(list);
+    } catch (error) {
+      message.error('Failed to load users: ' + error);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const setRole = async (email: string, role: UserRole) => {
+    try {
+      await UserService.setUserRole(email, role);
+      await loadUsers();
+    } catch (error) {
+      message.error('Failed to set role: ' + error);
+    }
+  };
+
+  const removeUser = async (email: string) => {
+    try {
+      await UserService.removeUser(email);
+      await loadUsers();
+    } catch (error) {
+      message.error('Failed to remove user: ' + error);
+    }
+  };
+
+  const addUser = async (values: { email: string; role: UserRole }) => {
+    await setRole(values.email, values.role);
+    form.resetFields();
+  };
+
+  const isAdmin = current?.role === 'admin';
+
+  const columns: ColumnsType<User> = [
+    {
+      title: 'Email',
+      dataIndex: 'email',
+      key: 'email',
+    },
+    {
+      title: 'Role',
+      dataIndex: 'role',
+      key: 'role',
+      width: 160,
+      render: (role: UserRole, user: User) =>
+        isAdmin ? (
+          <Select value={role} options={ROLE_OPTIONS} style={{ width: 120 }} onChange={(value) => setRole(user.email, value)} />
+        ) : (
+          <Tag color={ROLE_COLORS[role]}>{role}</Tag>
+        ),
+    },
+    ...(isAdmin
+      ? [
+          {
+            key: 'actions',
+            width: 100,
+            render: (_: unknown, user: User) => (
+              <Popconfirm title={`Remove ${user.email}?`} onConfirm={() => removeUser(user.email)}>
+                <Button danger size="small">
+                  Remove
+                </Button>
+              </Popconfirm>
+            ),
+          },
+        ]
+      : []),
+  ];
+
+  return (
+    <Card title={<Title level={4}>Users</Title>} loading={loading && !current}>
+      <Space direction="vertical" size="middle" style={{ width: '100%' }}>
+        {current && (
+          <Text>
+            You are {current.email ?? 'not identified (set current_user_email)'} with the{' '}
+            <Tag color={ROLE_COLORS[current.role]}>{current.role}</Tag> role
+          </Text>
+        )}
+        {current && !current.permissions_enabled && (
+          <Alert
+            type="info"
+            showIcon
+            message="Permissions are off"
+            description="Nobody is listed, so everyone can do everything. Add yourself as the first admin to turn them on; after that, people who aren't listed can only view."
+          />
+        )}
+        {isAdmin && (
+          <Form form={form} layout="inline" onFinish={addUser} initialValues={{ role: 'viewer' }}>
+            <Form.Item name="email" rules={[{ required: true, type: 'email', message: 'Enter an email address' }]}>
+              <Input placeholder="Email" style={{ width: 260 }} />
+            </Form.Item>
+            <Form.Item name="role">
+              <Select options={ROLE_OPTIONS} style={{ width: 120 }} />
+            </Form.Item>
+            <Form.Item>
+              <Button type="primary" htmlType="submit">
+                Add
+              </Button>
+            </Form.Item>
+          </Form>
+        )}
+        <Table columns={columns} dataSource={users} rowKey="email" pagination={false} size="small" />
+      </Space>
+    </Card>
+  );
+};
@@ -0,0 +1,59 @@
+/**
+ * Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+import { invoke } from './invoke';
+import type { Initiative, InitiativeProgress } from '../types';
+
+export class InitiativeService {
+  /**
+   * List initiatives ordered by quarter, optionally only those for one quarter (YYYY-Qn)
+   */
+  static async listInitiatives(quarter?: string): Promise<Initiative[]> {
+    return await invoke<Initiative[]>('list_initiatives', { quarter });
+  }
+
+  /**
+   * Create a new initiative
+   */
+  static async createInitiative(initiative: Initiative): Promise<Initiative> {
+    return await invoke<Initiative>('create_initiative', { initiative });
+  }
+
+  /**
+   * Update an existing initiative
+   */
+  static async updateInitiative(initiative: Initiative): Promise<void> {
+    await invoke('update_initiative', { initiative });
+  }
+
+  /**
+   * Delete an initiative; its projects are unlinked but kept
+   */
+  static async deleteInitiative(id: string): Promise<void> {
+    await invoke('delete_initiative', { id });
+  }
+
+  /**
+   * Get the milestone completion and open high risks of an initiative's projects
+   */
+  static async getInitiativeProgress(id: string): Promise<InitiativeProgress | null> {
+    return await invoke<InitiativeProgress | null>('get_initiative_progress', { id });
+  }
+
+  /**
+   * Get the initiative a project is linked to
+   */
+  static async getProjectInitiative(projectId: string): Promise<Initiative | null> {
+    return await invoke<Initiative | null>('get_project_initiative', { projectId });
+  }
+
+  /**
+   * Link a project to an initiative, or unlink it when no initiative is given
+   */
+  static async setProjectInitiative(projectId: string, initiativeId?: string): Promise<void> {
+    await invoke('set_project_initiative', { projectId, initiativeId });
+  }
+}
@@ -0,0 +1,61 @@
+/**
+ * Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+import { invoke as tauriInvoke, type InvokeArgs } from '@tauri-apps/api/core';
+import errorCodes from './errorCodes.json';
+
+/**
+ * Codes a backend command can fail with, mapped to what each means. The list
+ * is kept in step with the backend's `error::codes` by a test there.
+ */
+export const ERROR_CODES = errorCodes;
+
+export type ErrorCode = keyof typeof errorCodes;
+
+/**
+ * Error returned by a backend command. `code` identifies the kind of failure,
+ * such as `NOT_FOUND` or `DB_BUSY`; `message` is meant for the user, and
+ * `details` holds the facts behind some codes, such as the `field` of a
+ * `FOREIGN_KEY` error or the `value` of an `INVALID_ID` one.
+ */
+export class CommandError extends Error {
+  code: ErrorCode;
+  details?: Record<string, unknown>;
+
+  constructor(code: ErrorCode, message: string, details?: Record<string, unknown>) {
+    super(message);
+    this.name = 'CommandError';
+    this.code = code;
+    this.details = details;
+  }
+
+  toString(): string {
+    return this.message;
+  }
+}
+
+const isCommandError = (
+  error: unknown,
+): error is { code: ErrorCode; message: string; details?: Record<string, unknown> } =>
+  typeof error === 'object' &&
+  error !== null &&
+  typeof (error as { code?: unknown }).code === 'string' &&
+  (error as { code: string }).code in errorCodes &&
+  typeof (error as { message?: unknown }).message === 'string';
+
+/**
+ * Call a backend command, turning its structured errors into `CommandError`s
+ */
+export async function invoke<T>(command: string, args?: InvokeArgs): Promise<T> {
+  try {
+    return await tauriInvoke<T>(command, args);
+  } catch (error) {
+    if (isCommandError(error)) {
+      throw new CommandError(error.code, error.message, error.details);
+    }
+    throw new CommandError('ERROR', String(error));
+  }
+}
@@ -4,8 +4,8 @@
  * SPDX-License-Identifier: MIT
  */
 
-import { invoke } from '@tauri-apps/api/core';
-import type { Milestone } from '../types';
+import { invoke } from './invoke';
+import type { ChecklistItem, Milestone } from '../types';
 
 export class MilestoneService {
   static async addMilestone(milestone: Milestone): Promise<Milestone> {
@@ -16,7 +16,34 @@ export class MilestoneService {
     await invoke('update_milestone', { milestone });
   }
 
+  static async getDateWarnings(milestone: Milestone): Promise<string[]> {
+    return await invoke<string[]>('get_milestone_date_warnings', { milestone });
+  }
+
   static async deleteMilestone(id: string): Promise<void> {
     await invoke('delete_milestone', { id });
   }
+
+  static async listChecklistItems(milestoneId: string): Promise<ChecklistItem[]> {
+    return await invoke<ChecklistItem[]>('list_checklist_items', { milestoneId });
+  }
+
+  static async addChecklistItem(milestoneId: string, text: string): Promise<ChecklistItem> {
+    return await invoke<ChecklistItem>('add_checklist_item', { milestoneId, text });
+  }
+
+  /**
+   * Check or uncheck an item, flipping it when checked is omitted
+   */
+  static async toggleChecklistItem(id: string, checked?: boolean): Promise<ChecklistItem> {
+    return await invoke<ChecklistItem>('toggle_checklist_item', { id, checked });
+  }
+
+  static async moveChecklistItem(id: string, position: number): Promise<ChecklistItem> {
+    return await invoke<ChecklistItem>('move_checklist_item', { id, position });
+  }
+
+  static async removeChecklistItem(id: string): Promise<void> {
+    await invoke('remove_checklist_item', { id });
+  }
 }
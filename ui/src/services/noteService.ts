@@ -4,8 +4,8 @@
  * SPDX-License-Identifier: MIT
  */
 
-import { invoke } from '@tauri-apps/api/core';
-import type { ProjectNote, MilestoneNote, StakeholderNote } from '../types';
+import { invoke } from './invoke';
+import type { Attachment, Backlink, NoteType, ReferenceKind, ProjectNote, NotePage, MilestoneNote, StakeholderNote, PersonNote, NewNote, NoteTarget, InboxNote } from '../types';
 
 export class NoteService {
   // Project Notes
@@ -13,6 +13,10 @@ export class NoteService {
     return await invoke<ProjectNote[]>('get_project_notes', { projectId });
   }
 
+  static async getProjectNotesPaged(projectId: string, cursor?: string, limit?: number): Promise<NotePage<ProjectNote>> {
+    return await invoke<NotePage<ProjectNote>>('get_project_notes_paged', { projectId, cursor, limit });
+  }
+
   static async addProjectNote(note: ProjectNote): Promise<ProjectNote> {
     return await invoke<ProjectNote>('add_project_note', { note });
   }
@@ -21,6 +25,10 @@ export class NoteService {
     await invoke('update_project_note', { note });
   }
 
+  static async appendToNote(noteId: string, text: string, author?: string): Promise<ProjectNote> {
+    return await invoke<ProjectNote>('append_to_note', { noteId, text, author });
+  }
+
   static async deleteProjectNote(id: string): Promise<void> {
     await invoke('delete_project_note', { id });
   }
@@ -58,4 +66,73 @@ export class NoteService {
   static async deleteStakeholderNote(id: string): Promise<void> {
     await invoke('delete_stakeholder_note', { id });
   }
+
+  // Person Notes
+  static async listPersonNotes(personEmail: string, limit?: number): Promise<PersonNote[]> {
+    return await invoke<PersonNote[]>('list_person_notes', { personEmail, limit });
+  }
+
+  static async createPersonNote(note: PersonNote): Promise<PersonNote> {
+    return await invoke<PersonNote>('create_person_note', { note });
+  }
+
+  static async updatePersonNote(note: PersonNote): Promise<void> {
+    await invoke('update_person_note', { note });
+  }
+
+  static async deletePersonNote(id: string): Promise<void> {
+    await invoke('delete_person_note', { id });
+  }
+
+  // Batches and moves
+  static async createNotesBatch(notes: NewNote[]): Promise<string[]> {
+    return await invoke<string[]>('create_notes_batch', { notes });
+  }
+
+  static async moveNote(noteId: string, target: NoteTarget): Promise<void> {
+    await invoke('move_note', { noteId, target });
+  }
+
+  // Inbox
+  static async listInboxNotes(): Promise<InboxNote[]> {
+    return await invoke<InboxNote[]>('list_inbox_notes');
+  }
+
+  static async createInboxNote(note: InboxNote): Promise<InboxNote> {
+    return await invoke<InboxNote>('create_inbox_note', { note });
+  }
+
+  static async updateInboxNote(note: InboxNote): Promise<void> {
+    await invoke('update_inbox_note', { note });
+  }
+
+  static async deleteInboxNote(id: string): Promise<void> {
+    await invoke('delete_inbox_note', { id });
+  }
+
+  static async triageInboxNote(noteId: string, target: NoteTarget): Promise<void> {
+    await invoke('triage_inbox_note', { noteId, target });
+  }
+
+  // Attachments
+  static async listAttachments(noteType: NoteType, noteId: string): Promise<Attachment[]> {
+    return await invoke<Attachment[]>('list_attachments', { noteType, noteId });
+  }
+
+  static async addAttachment(noteType: NoteType, noteId: string, filename: string, mimeType: string, data: string): Promise<Attachment> {
+    return await invoke<Attachment>('add_attachment', { noteType, noteId, filename, mimeType, data });
+  }
+
+  static async getAttachmentData(id: string): Promise<string> {
+    return await invoke<string>('get_attachment_data', { id });
+  }
+
+  static async removeAttachment(id: string): Promise<void> {
+    await invoke('remove_attachment', { id });
+  }
+
+  // Backlinks
+  static async getBacklinks(kind: ReferenceKind, target: string): Promise<Backlink[]> {
+    return await invoke<Backlink[]>('get_backlinks', { kind, target });
+  }
 }
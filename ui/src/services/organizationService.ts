@@ -0,0 +1,45 @@
+/**
+ * Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+import { invoke } from './invoke';
+import type { Organization, OrganizationOverview } from '../types';
+
+export class OrganizationService {
+  /**
+   * List all organizations
+   */
+  static async listOrganizations(): Promise<Organization[]> {
+    return await invoke<Organization[]>('list_organizations');
+  }
+
+  /**
+   * Create a new organization
+   */
+  static async createOrganization(organization: Organization): Promise<Organization> {
+    return await invoke<Organization>('create_organization', { organization });
+  }
+
+  /**
+   * Update an organization's kind and notes
+   */
+  static async updateOrganization(organization: Organization): Promise<void> {
+    await invoke('update_organization', { organization });
+  }
+
+  /**
+   * Delete an organization; its people are kept without one
+   */
+  static async deleteOrganization(name: string): Promise<void> {
+    await invoke('delete_organization', { name });
+  }
+
+  /**
+   * Get an organization with its people and the projects they're involved in
+   */
+  static async getOrganizationOverview(name: string): Promise<OrganizationOverview> {
+    return await invoke<OrganizationOverview>('get_organization_overview', { name });
+  }
+}
@@ -4,22 +4,29 @@
  * SPDX-License-Identifier: MIT
  */
 
-import { invoke } from '@tauri-apps/api/core';
-import type { Person } from '../types';
+import { invoke } from './invoke';
+import type { AvailabilityForecast, DirectoryImportOptions, ImportReport, LoggedOneOnOne, OneOnOne, Person, PersonDeactivation, PersonSkill, PersonSuggestion } from '../types';
 
 export class PersonService {
   /**
-   * List all people
+   * List all people (inactive people are excluded unless includeInactive is set)
    */
-  static async listPeople(): Promise<Person[]> {
-    return await invoke<Person[]>('list_people');
+  static async listPeople(includeInactive = false): Promise<Person[]> {
+    return await invoke<Person[]>('list_people', { includeInactive });
   }
 
   /**
    * Search people by name (for autocomplete)
    */
-  static async searchPeople(query: string): Promise<Person[]> {
-    return await invoke<Person[]>('search_people', { query });
+  static async searchPeople(query: string, includeInactive = false): Promise<Person[]> {
+    return await invoke<Person[]>('search_people', { query, includeInactive });
+  }
+
+  /**
+   * Suggest active people whose name or email matches a partial query
+   */
+  static async autocompletePeople(query: string, limit?: number): Promise<PersonSuggestion[]> {
+    return await invoke<PersonSuggestion[]>('autocomplete_people', { query, limit });
   }
 
   /**
@@ -31,9 +38,12 @@ export class PersonService {
 
   /**
    * Create a new person
+   *
+   * Fails with a `POSSIBLE_DUPLICATE` error if their name closely matches an
+   * existing person's, unless `force` is set.
    */
-  static async createPerson(person: Person): Promise<Person> {
-    return await invoke<Person>('create_person', { person });
+  static async createPerson(person: Person, force = false): Promise<Person> {
+    return await invoke<Person>('create_person', { person, force });
   }
 
   /**
@@ -45,9 +55,126 @@ export class PersonService {
 
   /**
    * Delete a person
+   *
+   * Fails with a `CONFLICT` error if 1:1s are recorded with them, unless
+   * `deleteOneOnOnes` is set to delete those too.
+   */
+  static async deletePerson(email: string, deleteOneOnOnes = false): Promise<void> {
+    await invoke('delete_person', { email, deleteOneOnOnes });
+  }
+
+  /**
+   * Deactivate a person who has left, returning project roles to reassign
+   */
+  static async deactivatePerson(
+    email: string,
+    removeFutureAssignments = false
+  ): Promise<PersonDeactivation> {
+    return await invoke<PersonDeactivation>('deactivate_person', {
+      email,
+      removeFutureAssignments,
+    });
+  }
+
+  /**
+   * Change a person's email address, updating everything that refers to them
+   */
+  static async changeEmail(oldEmail: string, newEmail: string): Promise<Person> {
+    return await invoke<Person>('change_person_email', { oldEmail, newEmail });
+  }
+
+  /**
+   * Reactivate a deactivated person
+   */
+  static async reactivatePerson(email: string): Promise<Person> {
+    return await invoke<Person>('reactivate_person', { email });
+  }
+
+  /**
+   * Record that a person has a skill, optionally rated 1-5; adding it again updates the level
+   */
+  static async addSkill(personEmail: string, skill: string, level?: number): Promise<PersonSkill> {
+    return await invoke<PersonSkill>('add_person_skill', { personEmail, skill, level });
+  }
+
+  /**
+   * Remove a skill from a person
+   */
+  static async removeSkill(personEmail: string, skill: string): Promise<void> {
+    await invoke('remove_person_skill', { personEmail, skill });
+  }
+
+  /**
+   * Find active people with a skill, the most proficient first
+   */
+  static async findPeopleBySkill(skill: string, minLevel?: number): Promise<Person[]> {
+    return await invoke<Person[]>('find_people_by_skill', { skill, minLevel });
+  }
+
+  /**
+   * When active people free up from their milestones, by team
+   */
+  static async getAvailabilityForecast(horizonDays?: number): Promise<AvailabilityForecast> {
+    return await invoke<AvailabilityForecast>('get_availability_forecast', { horizonDays });
+  }
+
+  /**
+   * Get a person's avatar thumbnail as base64-encoded PNG, or null if they have none
+   */
+  static async getAvatar(email: string): Promise<string | null> {
+    return await invoke<string | null>('get_avatar', { email });
+  }
+
+  /**
+   * Upload a PNG or JPEG avatar, given as base64
+   */
+  static async setAvatar(email: string, data: string): Promise<Person> {
+    return await invoke<Person>('set_avatar', { email, data });
+  }
+
+  /**
+   * Remove a person's avatar
+   */
+  static async clearAvatar(email: string): Promise<Person> {
+    return await invoke<Person>('clear_avatar', { email });
+  }
+
+  /**
+   * List the 1:1s recorded with a person, most recent first
+   */
+  static async listOneOnOnes(personEmail: string): Promise<OneOnOne[]> {
+    return await invoke<OneOnOne[]>('list_one_on_ones', { personEmail });
+  }
+
+  /**
+   * Record a 1:1, turning its follow-ups into action items on a project when one is given
+   */
+  static async logOneOnOne(oneOnOne: OneOnOne, actionItemProject?: string): Promise<LoggedOneOnOne> {
+    return await invoke<LoggedOneOnOne>('log_one_on_one', { oneOnOne, actionItemProject });
+  }
+
+  /**
+   * Update a recorded 1:1
+   */
+  static async updateOneOnOne(oneOnOne: OneOnOne): Promise<void> {
+    await invoke('update_one_on_one', { oneOnOne });
+  }
+
+  /**
+   * Delete a recorded 1:1
+   */
+  static async deleteOneOnOne(id: string): Promise<void> {
+    await invoke('delete_one_on_one', { id });
+  }
+
+  /**
+   * Import people from the contents of a directory JSON export
    */
-  static async deletePerson(email: string): Promise<void> {
-    await invoke('delete_person', { email });
+  static async importDirectory(
+    contents: string,
+    options: DirectoryImportOptions
+  ): Promise<ImportReport> {
+    return await invoke<ImportReport>('import_directory', { contents, options });
   }
 
   /**
@@ -4,15 +4,81 @@
  * SPDX-License-Identifier: MIT
  */
 
-import { invoke } from '@tauri-apps/api/core';
-import type { Project, Milestone, ProjectStakeholder, ProjectResource, MilestoneResource, Person } from '../types';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import { invoke } from './invoke';
+import type { Project, ProjectStatus, ActivityItem, BackupStatus, BlockerEntry, BoardColumn, PhaseChange, DependencyGraph, DiagramFormat, DiagramKind, ProjectDashboard, ProjectDependency, ProjectSummary, ProjectWatcher, PortfolioStats, HygieneReport, LinkReport, QuarterPlan, MilestoneSlippage, EffortSummary, ExternalRef, ExternalRefTarget, FocusItem, Milestone, ProjectStakeholder, StakeholderBrief, StakeholderMatrix, ProjectResource, ProjectDocument, ProjectExpense, BudgetStatus, ProjectSnapshot, SnapshotDiff, ProjectRisk, ActionItem, SchemaInfo, McpStatus, NotificationSettings, CustomField, CustomFieldType, Decision, MilestoneResource, Person, ResourceLimitWarning, ResourceSuggestion, Retrospective, RoleVariants, TeamAssignment, WorkbookSheet } from '../types';
 
 export class ProjectService {
   /**
-   * List all projects
+   * List all projects, or only the ones the configured current user watches
+   * or that an organization's people are involved in
    */
-  static async listProjects(): Promise<Project[]> {
-    return await invoke<Project[]>('list_projects');
+  static async listProjects(onlyWatched?: boolean, organization?: string): Promise<Project[]> {
+    return await invoke<Project[]>('list_projects', { onlyWatched, organization });
+  }
+
+  /**
+   * List all projects with their milestones, resources and stakeholders
+   */
+  static async getDashboard(): Promise<ProjectDashboard[]> {
+    return await invoke<ProjectDashboard[]>('get_dashboard');
+  }
+
+  /**
+   * List progress summaries for all projects
+   */
+  static async listProjectSummaries(): Promise<ProjectSummary[]> {
+    return await invoke<ProjectSummary[]>('list_project_summaries');
+  }
+
+  /**
+   * Get portfolio-wide statistics for the dashboard header
+   */
+  static async getPortfolioStats(): Promise<PortfolioStats> {
+    return await invoke<PortfolioStats>('get_portfolio_stats');
+  }
+
+  /**
+   * Get the people, teams, projects and milestones missing managers, leads or due dates
+   */
+  static async getHygieneReport(): Promise<HygieneReport> {
+    return await invoke<HygieneReport>('get_hygiene_report');
+  }
+
+  /**
+   * Check the links of one project, or of every project, for dead ones.
+   * Progress is sent as `link-check-progress` events while the check runs.
+   */
+  static async checkLinks(projectId?: string): Promise<LinkReport[]> {
+    return await invoke<LinkReport[]>('check_links', { projectId });
+  }
+
+  /**
+   * Get a project's links with their last check, without checking them again
+   */
+  static async getProjectLinks(projectId: string): Promise<LinkReport[]> {
+    return await invoke<LinkReport[]>('get_project_links', { projectId });
+  }
+
+  /**
+   * Render the weekly digest as HTML, covering everything since a YYYY-MM-DD date (default: the last 7 days)
+   */
+  static async previewDigest(since?: string): Promise<string> {
+    return await invoke<string>('preview_digest', { since });
+  }
+
+  /**
+   * Get the milestones due in a calendar quarter, grouped by team and project
+   */
+  static async getQuarterPlan(year: number, quarter: number, team?: string): Promise<QuarterPlan> {
+    return await invoke<QuarterPlan>('get_quarter_plan', { year, quarter, team });
+  }
+
+  /**
+   * Get the progress summary for a single project
+   */
+  static async getProjectSummary(id: string): Promise<ProjectSummary | null> {
+    return await invoke<ProjectSummary | null>('get_project_summary', { id });
   }
 
   /**
@@ -23,10 +89,11 @@ export class ProjectService {
   }
 
   /**
-   * Create a new project
+   * Create a new project, adding the milestones, notes and stakeholders
+   * configured for its type unless skipBootstrap is set
    */
-  static async createProject(project: Project): Promise<Project> {
-    return await invoke<Project>('create_project', { project });
+  static async createProject(project: Project, skipBootstrap?: boolean): Promise<Project> {
+    return await invoke<Project>('create_project', { project, skipBootstrap });
   }
 
   /**
@@ -43,6 +110,219 @@ export class ProjectService {
     await invoke('delete_project', { id });
   }
 
+  /**
+   * Mark a project as blocked, replacing the reason if it is already blocked
+   */
+  static async blockProject(id: string, reason: string): Promise<Project> {
+    return await invoke<Project>('block_project', { id, reason });
+  }
+
+  /**
+   * Clear a project's blocker
+   */
+  static async unblockProject(id: string): Promise<Project> {
+    return await invoke<Project>('unblock_project', { id });
+  }
+
+  /**
+   * Move a project to a phase, recording the change in its phase history
+   */
+  static async setProjectPhase(id: string, phase: string): Promise<Project> {
+    return await invoke<Project>('set_project_phase', { id, phase });
+  }
+
+  /**
+   * Get a project's phase changes, oldest first
+   */
+  static async getPhaseHistory(projectId: string): Promise<PhaseChange[]> {
+    return await invoke<PhaseChange[]>('get_phase_history', { projectId });
+  }
+
+  /**
+   * List the projects in a phase, ordered by name
+   */
+  static async listProjectsByPhase(phase: string): Promise<Project[]> {
+    return await invoke<Project[]>('list_projects_by_phase', { phase });
+  }
+
+  /**
+   * Save a project's retrospective, replacing the one it has
+   */
+  static async saveRetrospective(retrospective: Retrospective): Promise<Retrospective> {
+    return await invoke<Retrospective>('save_retrospective', { retrospective });
+  }
+
+  /**
+   * Get a project's retrospective, or null if none has been saved
+   */
+  static async getRetrospective(projectId: string): Promise<Retrospective | null> {
+    return await invoke<Retrospective | null>('get_retrospective', { projectId });
+  }
+
+  /**
+   * Record a decision with its approvers
+   */
+  static async recordDecision(decision: Decision): Promise<Decision> {
+    return await invoke<Decision>('record_decision', { decision });
+  }
+
+  /**
+   * List a project's decisions, oldest first
+   */
+  static async listDecisions(projectId: string): Promise<Decision[]> {
+    return await invoke<Decision[]>('list_decisions', { projectId });
+  }
+
+  /**
+   * Replace a decision with a later one, returning the old decision
+   */
+  static async supersedeDecision(oldId: string, newId: string): Promise<Decision> {
+    return await invoke<Decision>('supersede_decision', { oldId, newId });
+  }
+
+  /**
+   * Watch a project, as the configured current user unless an email is given
+   */
+  static async watchProject(id: string, email?: string): Promise<ProjectWatcher[]> {
+    return await invoke<ProjectWatcher[]>('watch_project', { id, email });
+  }
+
+  /**
+   * Stop watching a project, as the configured current user unless an email is given
+   */
+  static async unwatchProject(id: string, email?: string): Promise<ProjectWatcher[]> {
+    return await invoke<ProjectWatcher[]>('unwatch_project', { id, email });
+  }
+
+  /**
+   * What needs attention today, most urgent first, for the configured
+   * current user unless an email is given
+   */
+  static async getFocusList(email?: string): Promise<FocusItem[]> {
+    return await invoke<FocusItem[]>('get_focus_list', { email });
+  }
+
+  /**
+   * Link a project or milestone to a ticket in an external tracker and
+   * return its references
+   */
+  static async addExternalRef(entityType: ExternalRefTarget, entityId: string, system: string, key: string, url?: string): Promise<ExternalRef[]> {
+    return await invoke<ExternalRef[]>('add_external_ref', { entityType, entityId, system, key, url });
+  }
+
+  /**
+   * List a project's or milestone's external ticket references
+   */
+  static async listExternalRefs(entityType: ExternalRefTarget, entityId: string): Promise<ExternalRef[]> {
+    return await invoke<ExternalRef[]>('list_external_refs', { entityType, entityId });
+  }
+
+  /**
+   * Remove an external ticket reference and return the ones left
+   */
+  static async removeExternalRef(entityType: ExternalRefTarget, entityId: string, system: string, key: string): Promise<ExternalRef[]> {
+    return await invoke<ExternalRef[]>('remove_external_ref', { entityType, entityId, system, key });
+  }
+
+  /**
+   * Change a project's slug, or make a new one from its name when none is given
+   */
+  static async setProjectSlug(id: string, slug?: string): Promise<string> {
+    return await invoke<string>('set_project_slug', { id, slug });
+  }
+
+  /**
+   * List the blocked projects
+   */
+  static async listBlockedProjects(): Promise<Project[]> {
+    return await invoke<Project[]>('list_blocked_projects');
+  }
+
+  /**
+   * Get the project board, one column per status
+   */
+  static async getBoard(): Promise<BoardColumn[]> {
+    return await invoke<BoardColumn[]>('get_board');
+  }
+
+  /**
+   * Move a project to a position (counting from 0) in a board column
+   */
+  static async reorderProject(id: string, status: ProjectStatus, position: number): Promise<Project> {
+    return await invoke<Project>('reorder_project', { id, status, position });
+  }
+
+  /**
+   * Suggest resource and stakeholder roles that match a partial query, most used first
+   */
+  static async autocompleteRoles(query: string, limit?: number): Promise<string[]> {
+    return await invoke<string[]>('autocomplete_roles', { query, limit });
+  }
+
+  /**
+   * List roles written in more than one way
+   */
+  static async listRoleVariants(): Promise<RoleVariants[]> {
+    return await invoke<RoleVariants[]>('list_role_variants');
+  }
+
+  /**
+   * Replace a role with another on every resource and stakeholder, returning how many changed
+   */
+  static async mergeRoles(oldRole: string, newRole: string): Promise<number> {
+    return await invoke<number>('merge_roles', { old: oldRole, new: newRole });
+  }
+
+  /**
+   * Get a project's blocker history, oldest first
+   */
+  static async getBlockerHistory(projectId: string): Promise<BlockerEntry[]> {
+    return await invoke<BlockerEntry[]>('get_blocker_history', { projectId });
+  }
+
+  /**
+   * List the projects a project depends on
+   */
+  static async listProjectDependencies(projectId: string): Promise<ProjectDependency[]> {
+    return await invoke<ProjectDependency[]>('list_project_dependencies', { projectId });
+  }
+
+  /**
+   * Record that a project depends on another; fails if that would create a cycle
+   */
+  static async addProjectDependency(projectId: string, dependsOnProjectId: string, note?: string): Promise<ProjectDependency> {
+    return await invoke<ProjectDependency>('add_project_dependency', { projectId, dependsOnProjectId, note });
+  }
+
+  /**
+   * Remove a dependency between two projects
+   */
+  static async removeProjectDependency(projectId: string, dependsOnProjectId: string): Promise<void> {
+    await invoke('remove_project_dependency', { projectId, dependsOnProjectId });
+  }
+
+  /**
+   * Get every dependency between projects as nodes and edges
+   */
+  static async getDependencyGraph(): Promise<DependencyGraph> {
+    return await invoke<DependencyGraph>('get_dependency_graph');
+  }
+
+  /**
+   * Draw the org chart, teams or project dependencies as Mermaid or Graphviz text
+   */
+  static async generateDiagram(kind: DiagramKind, format: DiagramFormat = 'mermaid'): Promise<string> {
+    return await invoke<string>('generate_diagram', { kind, format });
+  }
+
+  static async getProjectActivity(projectId: string, limit?: number): Promise<ActivityItem[]> {
+    return await invoke<ActivityItem[]>('get_project_activity', { projectId, limit });
+  }
+
+  static async suggestProjectDueDate(projectId: string): Promise<string | null> {
+    return await invoke<string | null>('suggest_project_due_date', { projectId });
+  }
+
   /**
    * Get milestones for a project
    */
@@ -57,6 +337,20 @@ export class ProjectService {
     return await invoke<ProjectStakeholder[]>('get_project_stakeholders', { projectId });
   }
 
+  /**
+   * Get a meeting prep brief for a stakeholder on a project
+   */
+  static async getStakeholderBrief(projectId: string, stakeholderEmail: string, noteLimit?: number): Promise<StakeholderBrief> {
+    return await invoke<StakeholderBrief>('get_stakeholder_brief', { projectId, stakeholderEmail, noteLimit });
+  }
+
+  /**
+   * Get a project's stakeholders sorted into influence/interest quadrants
+   */
+  static async getStakeholderMatrix(projectId: string): Promise<StakeholderMatrix> {
+    return await invoke<StakeholderMatrix>('get_stakeholder_matrix', { projectId });
+  }
+
   /**
    * Add a milestone to a project
    */
@@ -99,6 +393,28 @@ export class ProjectService {
     return await invoke<Person[]>('search_people', { query });
   }
 
+  /**
+   * Get the database's schema version and the newest one this build supports
+   */
+  static async getSchemaInfo(): Promise<SchemaInfo> {
+    return await invoke<SchemaInfo>('get_schema_info');
+  }
+
+  /**
+   * Reload the config file now; an invalid file is rejected and the current settings are kept
+   */
+  static async reloadConfig(): Promise<void> {
+    await invoke('reload_config');
+  }
+
+  /**
+   * Call `callback` whenever the config file is reloaded, so settings such as
+   * project types can be fetched again. Returns a function that stops listening.
+   */
+  static async onConfigChanged(callback: () => void): Promise<UnlistenFn> {
+    return await listen('config://changed', () => callback());
+  }
+
   /**
    * Get the configured Jira base URL
    */
@@ -106,6 +422,154 @@ export class ProjectService {
     return await invoke<string>('get_jira_url');
   }
 
+  /**
+   * Get the configured project types
+   */
+  static async getProjectTypes(): Promise<string[]> {
+    return await invoke<string[]>('get_project_types');
+  }
+
+  /**
+   * Get the configured project phases, in order
+   */
+  static async getProjectPhases(): Promise<string[]> {
+    return await invoke<string[]>('get_project_phases');
+  }
+
+  /**
+   * List the project types used by existing projects
+   */
+  static async listProjectTypesInUse(): Promise<string[]> {
+    return await invoke<string[]>('list_project_types_in_use');
+  }
+
+  /**
+   * Get the configured IANA time zone used to display dates
+   */
+  static async getTimezone(): Promise<string> {
+    return await invoke<string>('get_timezone');
+  }
+
+  /**
+   * List the custom fields that can be set on projects
+   */
+  static async listCustomFields(): Promise<CustomField[]> {
+    return await invoke<CustomField[]>('list_custom_fields');
+  }
+
+  /**
+   * Define a custom project field, or change the label of an existing one
+   */
+  static async defineCustomField(key: string, label: string, fieldType: CustomFieldType): Promise<CustomField> {
+    return await invoke<CustomField>('define_custom_field', { key, label, fieldType });
+  }
+
+  /**
+   * Delete a custom field; with force, its values on projects are deleted too
+   */
+  static async deleteCustomField(key: string, force?: boolean): Promise<number> {
+    return await invoke<number>('delete_custom_field', { key, force });
+  }
+
+  /**
+   * Get a project's custom field values by key
+   */
+  static async getProjectCustomValues(projectId: string): Promise<Record<string, string>> {
+    return await invoke<Record<string, string>>('get_project_custom_values', { projectId });
+  }
+
+  /**
+   * Set or clear a project's value for a custom field, returning the value as stored
+   */
+  static async setProjectCustomValue(projectId: string, key: string, value?: string): Promise<string | null> {
+    return await invoke<string | null>('set_project_custom_value', { projectId, key, value });
+  }
+
+  /**
+   * Whether the MCP HTTP server is running, its address and connected sessions
+   */
+  static async getMcpStatus(): Promise<McpStatus> {
+    return await invoke<McpStatus>('get_mcp_status');
+  }
+
+  /**
+   * Start the MCP HTTP server; fails if its port can't be bound
+   */
+  static async startMcpServer(): Promise<McpStatus> {
+    return await invoke<McpStatus>('start_mcp_server');
+  }
+
+  /**
+   * Stop the MCP HTTP server
+   */
+  static async stopMcpServer(): Promise<McpStatus> {
+    return await invoke<McpStatus>('stop_mcp_server');
+  }
+
+  /**
+   * List the configured workspaces, the default one first
+   */
+  static async listWorkspaces(): Promise<string[]> {
+    return await invoke<string[]>('list_workspaces');
+  }
+
+  /**
+   * Get the name of the workspace in use
+   */
+  static async getActiveWorkspace(): Promise<string> {
+    return await invoke<string>('get_active_workspace');
+  }
+
+  /**
+   * Get the warning to show while the workspace's data directory is in a cloud-synced folder
+   */
+  static async getSyncWarning(): Promise<string | null> {
+    return await invoke<string | null>('get_sync_warning');
+  }
+
+  /**
+   * Close the current workspace and open another one, returning its name
+   */
+  static async switchWorkspace(name: string): Promise<string> {
+    return await invoke<string>('switch_workspace', { name });
+  }
+
+  /**
+   * Get the settings for milestone reminder notifications
+   */
+  static async getNotificationSettings(): Promise<NotificationSettings> {
+    return await invoke<NotificationSettings>('get_notification_settings');
+  }
+
+  /**
+   * Change the settings for milestone reminder notifications
+   */
+  static async setNotificationSettings(settings: NotificationSettings): Promise<void> {
+    await invoke('set_notification_settings', { settings });
+  }
+
+  /**
+   * Get when the database was last backed up automatically, and whether the latest attempt failed
+   */
+  static async getBackupStatus(): Promise<BackupStatus> {
+    return await invoke<BackupStatus>('get_backup_status');
+  }
+
+  /**
+   * Ask where to save, then export the portfolio to an Excel workbook there.
+   * Returns null if the save dialog was cancelled.
+   */
+  static async exportWorkbook(): Promise<WorkbookSheet[] | null> {
+    return await invoke<WorkbookSheet[] | null>('export_workbook');
+  }
+
+  /**
+   * Get the suggested project document types
+   */
+  static async getDocumentTypes(): Promise<string[]> {
+    return await invoke<string[]>('get_document_types');
+  }
+
   /**
    * Get the default email domain
    */
@@ -135,6 +599,17 @@ export class ProjectService {
     await invoke('add_project_resource', { projectId, resource });
   }
 
+  /**
+   * Add every active member of a team as a project resource
+   */
+  static async assignTeamToProject(
+    projectId: string,
+    teamName: string,
+    role?: string
+  ): Promise<TeamAssignment> {
+    return await invoke<TeamAssignment>('assign_team_to_project', { projectId, teamName, role });
+  }
+
   /**
    * Update a project resource
    */
@@ -157,10 +632,24 @@ export class ProjectService {
   }
 
   /**
-   * Add a resource to a milestone
+   * Suggest people to assign to a milestone, best candidates first
+   */
+  static async suggestMilestoneResources(milestoneId: string, limit?: number, skill?: string): Promise<ResourceSuggestion[]> {
+    return await invoke<ResourceSuggestion[]>('suggest_milestone_resources', { milestoneId, skill, limit });
+  }
+
+  /**
+   * Add a resource to a milestone, returning a warning when it goes over max_resources_per_milestone
+   */
+  static async addMilestoneResource(milestoneId: string, resource: MilestoneResource): Promise<ResourceLimitWarning | null> {
+    return await invoke<ResourceLimitWarning | null>('add_milestone_resource', { milestoneId, resource });
+  }
+
+  /**
+   * Number of resources on each of a project's milestones, keyed by milestone ID
    */
-  static async addMilestoneResource(milestoneId: string, resource: MilestoneResource): Promise<void> {
-    await invoke('add_milestone_resource', { milestoneId, resource });
+  static async getResourceCounts(projectId: string): Promise<Record<string, number>> {
+    return await invoke<Record<string, number>>('get_resource_counts', { projectId });
   }
 
   /**
@@ -176,4 +665,158 @@ export class ProjectService {
   static async removeMilestoneResource(milestoneId: string, personEmail: string): Promise<void> {
     await invoke('remove_milestone_resource', { milestoneId, personEmail });
   }
+
+  /**
+   * List documents linked to a project
+   */
+  static async listProjectDocuments(projectId: string): Promise<ProjectDocument[]> {
+    return await invoke<ProjectDocument[]>('list_project_documents', { projectId });
+  }
+
+  /**
+   * Link a document to a project
+   */
+  static async addProjectDocument(document: ProjectDocument): Promise<ProjectDocument> {
+    return await invoke<ProjectDocument>('add_project_document', { document });
+  }
+
+  /**
+   * Remove a document from a project
+   */
+  static async removeProjectDocument(id: string): Promise<void> {
+    await invoke('remove_project_document', { id });
+  }
+
+  /**
+   * List a project's expenses, oldest first
+   */
+  static async listExpenses(projectId: string): Promise<ProjectExpense[]> {
+    return await invoke<ProjectExpense[]>('list_expenses', { projectId });
+  }
+
+  /**
+   * Record money spent on a project
+   */
+  static async addExpense(expense: ProjectExpense): Promise<ProjectExpense> {
+    return await invoke<ProjectExpense>('add_expense', { expense });
+  }
+
+  /**
+   * Delete an expense
+   */
+  static async deleteExpense(id: string): Promise<void> {
+    await invoke('delete_expense', { id });
+  }
+
+  /**
+   * Get a project's budget, total spend and what remains
+   */
+  static async getBudgetStatus(projectId: string): Promise<BudgetStatus> {
+    return await invoke<BudgetStatus>('get_budget_status', { projectId });
+  }
+
+  /**
+   * Save a labelled copy of a project's current plan
+   */
+  static async createSnapshot(projectId: string, label: string): Promise<ProjectSnapshot> {
+    return await invoke<ProjectSnapshot>('create_snapshot', { projectId, label });
+  }
+
+  /**
+   * List a project's snapshots, oldest first
+   */
+  static async listSnapshots(projectId: string): Promise<ProjectSnapshot[]> {
+    return await invoke<ProjectSnapshot[]>('list_snapshots', { projectId });
+  }
+
+  /**
+   * Compare two snapshots of the same project
+   */
+  static async compareSnapshots(fromSnapshotId: string, toSnapshotId: string): Promise<SnapshotDiff> {
+    return await invoke<SnapshotDiff>('compare_snapshots', { fromSnapshotId, toSnapshotId });
+  }
+
+  /**
+   * List a project's risks, optionally leaving out closed ones
+   */
+  static async listProjectRisks(projectId: string, openOnly?: boolean): Promise<ProjectRisk[]> {
+    return await invoke<ProjectRisk[]>('list_project_risks', { projectId, openOnly });
+  }
+
+  /**
+   * List open high risks across all projects
+   */
+  static async listHighRisks(): Promise<ProjectRisk[]> {
+    return await invoke<ProjectRisk[]>('list_high_risks');
+  }
+
+  /**
+   * Add a risk to a project's risk register
+   */
+  static async addProjectRisk(risk: ProjectRisk): Promise<ProjectRisk> {
+    return await invoke<ProjectRisk>('add_project_risk', { risk });
+  }
+
+  /**
+   * Update a project risk
+   */
+  static async updateProjectRisk(risk: ProjectRisk): Promise<void> {
+    await invoke('update_project_risk', { risk });
+  }
+
+  /**
+   * Close a project risk
+   */
+  static async closeProjectRisk(id: string): Promise<void> {
+    await invoke('close_project_risk', { id });
+  }
+
+  /**
+   * List a project's action items, open ones first
+   */
+  static async listActionItems(projectId: string): Promise<ActionItem[]> {
+    return await invoke<ActionItem[]>('list_action_items', { projectId });
+  }
+
+  /**
+   * Record an action item for a project
+   */
+  static async createActionItem(item: ActionItem): Promise<ActionItem> {
+    return await invoke<ActionItem>('create_action_item', { item });
+  }
+
+  /**
+   * Update an action item
+   */
+  static async updateActionItem(item: ActionItem): Promise<void> {
+    await invoke('update_action_item', { item });
+  }
+
+  /**
+   * Mark an action item as completed
+   */
+  static async completeActionItem(id: string): Promise<ActionItem> {
+    return await invoke<ActionItem>('complete_action_item', { id });
+  }
+
+  /**
+   * Delete an action item
+   */
+  static async deleteActionItem(id: string): Promise<void> {
+    await invoke('delete_action_item', { id });
+  }
+
+  /**
+   * Get how often each milestone of a project slipped
+   */
+  static async getSlippageReport(projectId: string): Promise<MilestoneSlippage[]> {
+    return await invoke<MilestoneSlippage[]>('get_slippage_report', { projectId });
+  }
+
+  /**
+   * Get estimated against actual effort for a project's milestones
+   */
+  static async getEffortSummary(projectId: string, sortByVariance?: boolean): Promise<EffortSummary> {
+    return await invoke<EffortSummary>('get_effort_summary', { projectId, sortByVariance });
+  }
 }
@@ -4,8 +4,8 @@
  * SPDX-License-Identifier: MIT
  */
 
-import { invoke } from '@tauri-apps/api/core';
-import type { Team, Person } from '../types';
+import { invoke } from './invoke';
+import type { Team, Person, SubteamPolicy, TeamTreeNode } from '../types';
 
 export class TeamService {
   /**
@@ -22,6 +22,13 @@ export class TeamService {
     return await invoke<Team[]>('search_teams', { query });
   }
 
+  /**
+   * Suggest team names that match a partial query
+   */
+  static async autocompleteTeams(query: string, limit?: number): Promise<string[]> {
+    return await invoke<string[]>('autocomplete_teams', { query, limit });
+  }
+
   /**
    * Get a single team by name
    */
@@ -44,10 +51,17 @@ export class TeamService {
   }
 
   /**
-   * Delete a team
+   * Delete a team; by default this fails if the team still has sub-teams
+   */
+  static async deleteTeam(name: string, subteams?: SubteamPolicy): Promise<void> {
+    await invoke('delete_team', { name, subteams });
+  }
+
+  /**
+   * Get all teams as a tree of top-level teams and their sub-teams
    */
-  static async deleteTeam(name: string): Promise<void> {
-    await invoke('delete_team', { name });
+  static async getTeamTree(): Promise<TeamTreeNode[]> {
+    return await invoke<TeamTreeNode[]>('get_team_tree');
   }
 
   /**
@@ -65,9 +79,16 @@ export class TeamService {
   }
 
   /**
-   * Get all members of a team
+   * Get all members of a team, optionally including the members of its sub-teams
+   */
+  static async getTeamMembers(teamName: string, includeSubteams?: boolean): Promise<Person[]> {
+    return await invoke<Person[]>('get_team_members', { teamName, includeSubteams });
+  }
+
+  /**
+   * A team's quarterly review as markdown: deliveries, milestones, slips, headcount and risks
    */
-  static async getTeamMembers(teamName: string): Promise<Person[]> {
-    return await invoke<Person[]>('get_team_members', { teamName });
+  static async generateTeamReview(team: string, year: number, quarter: number): Promise<string> {
+    return await invoke<string>('generate_team_review', { team, year, quarter });
   }
 }
@@ -0,0 +1,38 @@
+/**
+ * Copyright 2025 Andrew C. Young <andrew@vaelen.org>
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+import { invoke } from './invoke';
+import type { CurrentRole, User, UserRole } from '../types';
+
+export class UserService {
+  /**
+   * Get the role of the configured current_user_email
+   */
+  static async getCurrentRole(): Promise<CurrentRole> {
+    return await invoke<CurrentRole>('get_current_role');
+  }
+
+  /**
+   * List the users of a shared database and their roles
+   */
+  static async listUsers(): Promise<User[]> {
+    return await invoke<User[]>('list_users');
+  }
+
+  /**
+   * Add a user or change their role; admins only
+   */
+  static async setUserRole(email: string, role: UserRole): Promise<User> {
+    return await invoke<User>('set_user_role', { email, role });
+  }
+
+  /**
+   * Remove a user; admins only
+   */
+  static async removeUser(email: string): Promise<void> {
+    await invoke('remove_user', { email });
+  }
+}
@@ -8,23 +8,79 @@ export interface Person {
   email: string;
   name: string;
   team?: string;
+  organization?: string;
   manager?: string;
   notes?: string;
+  active?: boolean;
+  external?: boolean;
+  avatar_path?: string;
+  skills?: PersonSkill[];
   created_at: string;
   updated_at: string;
+  version?: number;
+}
+
+export interface PersonSkill {
+  skill: string;
+  level?: number;
+  created_at: string;
+}
+
+export interface PersonSuggestion {
+  email: string;
+  name: string;
+  team?: string;
 }
 
 export interface Team {
   name: string;
   description?: string;
   manager?: string;
+  parent_team?: string;
+  created_at: string;
+  updated_at: string;
+}
+
+export type OrganizationKind = 'vendor' | 'client' | 'partner' | 'internal';
+
+export interface Organization {
+  name: string;
+  kind: OrganizationKind;
+  notes?: string;
   created_at: string;
   updated_at: string;
 }
 
+export interface OrganizationInvolvement {
+  email: string;
+  name: string;
+  relation: string;
+  role?: string;
+}
+
+export interface OrganizationProject {
+  project: Project;
+  involvement: OrganizationInvolvement[];
+}
+
+export interface OrganizationOverview {
+  organization: Organization;
+  people: Person[];
+  projects: OrganizationProject[];
+}
+
+export interface TeamTreeNode extends Team {
+  subteams: TeamTreeNode[];
+}
+
+export type SubteamPolicy = 'refuse' | 'detach';
+
+export type ProjectStatus = 'planning' | 'active' | 'on_hold' | 'done';
+
 export interface Project {
   id: string;
   name: string;
+  slug: string;
   description?: string;
   type: string;
   requirements_owner?: string;
@@ -34,7 +90,170 @@ export interface Project {
   start_date?: string;
   due_date?: string;
   jira_initiative?: string;
+  blocked?: boolean;
+  blocked_reason?: string;
+  budget_amount?: number;
+  budget_currency?: string;
+  status?: ProjectStatus;
+  phase?: string;
+  board_position?: number;
+  created_at: string;
+  updated_at: string;
+  version?: number;
+  custom_fields?: Record<string, string>;
+  external_refs?: ExternalRef[];
+}
+
+export interface RoleUsage {
+  role: string;
+  uses: number;
+}
+
+/** Spellings in use for the same role, most used first */
+export interface RoleVariants {
+  canonical: string;
+  variants: RoleUsage[];
+}
+
+export interface BoardColumn {
+  status: ProjectStatus;
+  projects: Project[];
+}
+
+export type CustomFieldType = 'text' | 'number' | 'date' | 'bool';
+
+export interface CustomField {
+  key: string;
+  label: string;
+  field_type: CustomFieldType;
+  applies_to: 'project';
+  created_at: string;
+  updated_at: string;
+}
+
+export type ActivityKind =
+  | 'note_added'
+  | 'milestone_created'
+  | 'milestone_completed'
+  | 'resource_added'
+  | 'date_changed'
+  | 'blocked'
+  | 'unblocked';
+
+export interface ActivityItem {
+  kind: ActivityKind;
+  summary: string;
+  actor?: string;
+  timestamp: string;
+}
+
+export interface BlockerEntry {
+  project_id: string;
+  reason?: string;
+  blocked_at: string;
+  cleared_at?: string;
+}
+
+export interface PhaseChange {
+  project_id: string;
+  from_phase?: string;
+  to_phase?: string;
+  backwards: boolean;
+  changed_at: string;
+}
+
+export interface PhaseDuration {
+  phase: string;
+  days: number;
+  current: boolean;
+}
+
+export interface Retrospective {
+  id: string;
+  project_id: string;
+  went_well?: string;
+  needs_improvement?: string;
+  action_summary?: string;
+  completed_on: string;
+  created_at: string;
+  updated_at: string;
+}
+
+export type DecisionStatus = 'proposed' | 'accepted' | 'superseded';
+
+export interface Decision {
+  id: string;
+  project_id: string;
+  title: string;
+  context?: string;
+  decision: string;
+  decided_on?: string;
+  status: DecisionStatus;
+  superseded_by?: string;
+  approvers: string[];
+  created_at: string;
+  updated_at: string;
+}
+
+export interface ProjectWatcher {
+  project_id: string;
+  watcher_email: string;
+  created_at: string;
+}
+
+export type FocusKind =
+  | 'overdue_milestone'
+  | 'action_item_due'
+  | 'stakeholder_follow_up'
+  | 'blocked_project'
+  | 'missing_fields';
+
+export interface FocusItem {
+  kind: FocusKind;
+  score: number;
+  project_id: string;
+  project_name: string;
+  // Record to open, such as milestone:<id> or person:<email>
+  link: string;
+  title: string;
+  reason: string;
+  date?: string;
+}
+
+export type ExternalRefTarget = 'project' | 'milestone';
+
+export interface ExternalRef {
+  // Missing for references read from the older Jira fields
+  id?: string;
+  entity_type: ExternalRefTarget;
+  entity_id: string;
+  system: string;
+  key: string;
+  url?: string;
   created_at: string;
+}
+
+export type NoteType = 'project' | 'milestone' | 'stakeholder';
+
+export interface Attachment {
+  id: string;
+  note_type: NoteType;
+  note_id: string;
+  filename: string;
+  mime_type: string;
+  size_bytes: number;
+  sha256: string;
+  created_at: string;
+}
+
+export type ReferenceKind = 'project' | 'person' | 'milestone';
+
+export interface Backlink {
+  note_type: NoteType;
+  note_id: string;
+  title: string;
+  project_id: string;
+  project_name: string;
   updated_at: string;
 }
 
@@ -44,10 +263,108 @@ export interface Note {
   body: string;
   created_at: string;
   updated_at: string;
+  version?: number;
+  attachments?: Attachment[];
 }
 
+export type NoteKind = 'freeform' | 'log';
+
 export interface ProjectNote extends Note {
   project_id: string;
+  kind?: NoteKind;
+}
+
+export interface NotePage<T> {
+  notes: T[];
+  next_cursor?: string;
+}
+
+export interface ProjectDocument {
+  id: string;
+  project_id: string;
+  title: string;
+  url: string;
+  doc_type?: string;
+  created_at: string;
+}
+
+export interface ProjectExpense {
+  id: string;
+  project_id: string;
+  description: string;
+  amount: number;
+  incurred_at: string;
+  created_at: string;
+}
+
+export interface BudgetStatus {
+  project_id: string;
+  budget_amount?: number;
+  budget_currency?: string;
+  total_spent: number;
+  remaining?: number;
+}
+
+export interface ProjectSnapshot {
+  id: string;
+  project_id: string;
+  label: string;
+  created_at: string;
+}
+
+export interface FieldChange {
+  field: string;
+  old: unknown;
+  new: unknown;
+}
+
+export interface MilestoneChange {
+  milestone_id: string;
+  name: string;
+  changes: FieldChange[];
+}
+
+export interface SnapshotDiff {
+  from: ProjectSnapshot;
+  to: ProjectSnapshot;
+  project_changes: FieldChange[];
+  added_milestones: Milestone[];
+  removed_milestones: Milestone[];
+  changed_milestones: MilestoneChange[];
+}
+
+export type RiskLevel = 'low' | 'medium' | 'high';
+
+export type RiskStatus = 'open' | 'mitigating' | 'closed';
+
+export interface ProjectRisk {
+  id: string;
+  project_id: string;
+  title: string;
+  description?: string;
+  severity: RiskLevel;
+  likelihood: RiskLevel;
+  mitigation?: string;
+  owner_email?: string;
+  status: RiskStatus;
+  created_at: string;
+  updated_at: string;
+}
+
+export type ActionItemStatus = 'open' | 'completed';
+
+export interface ActionItem {
+  id: string;
+  project_id: string;
+  milestone_id?: string;
+  assignee_email: string;
+  description: string;
+  due_date?: string;
+  status: ActionItemStatus;
+  source_note_id?: string;
+  completed_at?: string;
+  created_at: string;
+  updated_at: string;
 }
 
 export interface MilestoneNote extends Note {
@@ -59,6 +376,45 @@ export interface StakeholderNote extends Note {
   stakeholder_email: string;
 }
 
+export interface PersonNote extends Note {
+  person_email: string;
+}
+
+export interface OneOnOne {
+  id: string;
+  person_email: string;
+  occurred_at: string;
+  agenda?: string;
+  notes?: string;
+  followups?: string;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface LoggedOneOnOne {
+  one_on_one: OneOnOne;
+  action_items: ActionItem[];
+}
+
+export type NoteTarget =
+  | { type: 'project'; project_id: string }
+  | { type: 'milestone'; milestone_id: string }
+  | { type: 'stakeholder'; project_id: string; stakeholder_email: string };
+
+export interface InboxNote {
+  id: string;
+  title: string;
+  body: string;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface NewNote {
+  target: NoteTarget;
+  title: string;
+  body: string;
+}
+
 export interface Milestone {
   id: string;
   project_id: string;
@@ -71,17 +427,48 @@ export interface Milestone {
   start_date?: string;
   due_date?: string;
   jira_epic?: string;
+  recurrence_rule?: string;
+  series_id?: string;
+  series_index?: number;
+  estimated_days?: number;
+  actual_days?: number;
   created_at: string;
   updated_at: string;
+  version?: number;
+  checklist?: ChecklistItem[];
+  checklist_completion?: number | null;
+  external_refs?: ExternalRef[];
+}
+
+export interface ChecklistItem {
+  id: string;
+  milestone_id: string;
+  position: number;
+  text: string;
+  checked: boolean;
+  checked_at?: string | null;
+  created_at: string;
 }
 
 export interface ProjectStakeholder {
   project_id: string;
   stakeholder_email: string;
   role?: string;
+  influence?: number;
+  interest?: number;
   created_at: string;
 }
 
+export interface StakeholderMatrix {
+  project_id: string;
+  project_name: string;
+  manage_closely: ProjectStakeholder[];
+  keep_satisfied: ProjectStakeholder[];
+  keep_informed: ProjectStakeholder[];
+  monitor: ProjectStakeholder[];
+  unrated: ProjectStakeholder[];
+}
+
 export interface ProjectResource {
   project_id: string;
   person_email: string;
@@ -95,3 +482,355 @@ export interface MilestoneResource {
   role?: string;
   created_at: string;
 }
+
+export interface ResourceLimitWarning {
+  milestone_id: string;
+  resources: number;
+  limit: number;
+}
+
+export interface ProjectDashboard extends Project {
+  milestones: Milestone[];
+  resources: ProjectResource[];
+  stakeholders: ProjectStakeholder[];
+}
+
+export interface MilestoneSlippage {
+  milestone_id: string;
+  number: number;
+  name: string;
+  due_date?: string;
+  slip_count: number;
+  total_slip_days: number;
+}
+
+export interface MilestoneEffort {
+  milestone_id: string;
+  number: number;
+  name: string;
+  estimated_days?: number;
+  actual_days?: number;
+  variance_days?: number;
+  over_estimate: boolean;
+}
+
+export interface EffortSummary {
+  project_id: string;
+  total_estimated_days: number;
+  total_actual_days: number;
+  variance_days: number;
+  milestones: MilestoneEffort[];
+}
+
+export interface GroupCount {
+  name?: string;
+  count: number;
+}
+
+export interface StakeholderBrief {
+  project_id: string;
+  project_name: string;
+  stakeholder: ProjectStakeholder;
+  person: Person;
+  organization?: Organization;
+  recent_notes: StakeholderNote[];
+  open_questions: string[];
+  approved_decisions: Decision[];
+}
+
+export interface PortfolioStats {
+  as_of: string;
+  total_projects: number;
+  projects_by_type: GroupCount[];
+  projects_by_team: GroupCount[];
+  total_milestones: number;
+  milestones_due_this_month: number;
+  milestones_due_this_quarter: number;
+  unassigned_people: number;
+  teams_without_manager: number;
+  notes_last_7_days: number;
+  average_milestones_per_project: number;
+}
+
+export interface QuarterProject {
+  project_id: string;
+  project_name: string;
+  milestones: Milestone[];
+}
+
+export interface QuarterTeam {
+  team?: string;
+  milestone_count: number;
+  projects: QuarterProject[];
+}
+
+export type AvailabilityStatus = 'unassigned' | 'frees_up' | 'busy' | 'unknown';
+
+export interface PersonAvailability {
+  email: string;
+  name: string;
+  team?: string;
+  status: AvailabilityStatus;
+  free_on?: string;
+  open_milestones: number;
+  undated_milestones: number;
+}
+
+export interface TeamAvailability {
+  team?: string;
+  people: PersonAvailability[];
+}
+
+export interface AvailabilityForecast {
+  horizon_days: number;
+  until: string;
+  unassigned: number;
+  freeing_up: number;
+  busy: number;
+  unknown: number;
+  teams: TeamAvailability[];
+}
+
+export interface QuarterPlan {
+  year: number;
+  quarter: number;
+  start: string;
+  end: string;
+  milestone_count: number;
+  teams: QuarterTeam[];
+}
+
+export interface ProjectDependency {
+  project_id: string;
+  depends_on_project_id: string;
+  note?: string;
+  created_at: string;
+}
+
+export interface DependencyNode {
+  project_id: string;
+  project_name: string;
+  blocked: boolean;
+  upstream_blocked: boolean;
+}
+
+export interface DependencyGraph {
+  nodes: DependencyNode[];
+  edges: ProjectDependency[];
+}
+
+export type DiagramKind = 'org' | 'teams' | 'projects';
+
+export type DiagramFormat = 'mermaid' | 'dot';
+
+export interface PersonGap {
+  email: string;
+  name: string;
+}
+
+export interface TeamGap {
+  name: string;
+}
+
+export interface ProjectGap {
+  id: string;
+  name: string;
+}
+
+export interface MilestoneGap {
+  id: string;
+  project_id: string;
+  project_name: string;
+  number: number;
+  name: string;
+}
+
+export interface StakeholderGap {
+  project_id: string;
+  project_name: string;
+  stakeholder_email: string;
+}
+
+export interface ReferenceGap {
+  note_type: NoteType;
+  note_id: string;
+  note_title: string;
+  project_id: string;
+  project_name: string;
+  reference: string;
+}
+
+export type LinkKind = 'design_doc' | 'document' | 'jira_initiative' | 'jira_epic';
+
+export type LinkStatus = 'ok' | 'dead' | 'unreachable' | 'skipped';
+
+export interface LinkCheck {
+  url: string;
+  status: LinkStatus;
+  http_status?: number;
+  error?: string;
+  checked_at: string;
+}
+
+/** A stored link with its last check, if it has been checked */
+export interface LinkReport {
+  project_id: string;
+  project_name: string;
+  kind: LinkKind;
+  label: string;
+  url: string;
+  check?: LinkCheck;
+}
+
+export interface LinkCheckProgress {
+  url: string;
+  status: LinkStatus;
+  checked: number;
+  total: number;
+}
+
+export interface HygieneReport {
+  people_missing_manager: PersonGap[];
+  people_missing_team: PersonGap[];
+  teams_missing_manager: TeamGap[];
+  projects_missing_technical_lead: ProjectGap[];
+  projects_missing_requirements_owner: ProjectGap[];
+  projects_missing_due_date: ProjectGap[];
+  milestones_missing_due_date: MilestoneGap[];
+  milestones_missing_lead: MilestoneGap[];
+  unknown_stakeholders: StakeholderGap[];
+  people_outside_allowed_domains: PersonGap[];
+  dangling_references: ReferenceGap[];
+  projects_missing_retrospective: ProjectGap[];
+}
+
+export interface NextMilestone {
+  id: string;
+  name: string;
+  due_date: string;
+}
+
+export interface ProjectSummary {
+  project_id: string;
+  project_name: string;
+  total_milestones: number;
+  completed_milestones: number;
+  completion_estimated: boolean;
+  progress_percent?: number;
+  next_milestone?: NextMilestone;
+  recent_notes: number;
+  phase?: string;
+  phase_durations: PhaseDuration[];
+}
+
+export interface ResourceSuggestion {
+  email: string;
+  name: string;
+  team_match: boolean;
+  on_project: boolean;
+  has_skill: boolean;
+  skill_level?: number;
+  concurrent_milestones: number;
+  reason: string;
+}
+
+export interface TeamAssignment {
+  added: string[];
+  skipped: string[];
+}
+
+export interface ProjectRoleAssignment {
+  project_id: string;
+  project_name: string;
+  role: 'technical_lead' | 'manager' | 'requirements_owner';
+}
+
+export interface PersonDeactivation {
+  person: Person;
+  project_roles: ProjectRoleAssignment[];
+  removed_milestone_assignments: number;
+}
+
+export type DirectoryField = 'name' | 'team' | 'manager';
+
+export interface DirectoryImportOptions {
+  create_teams: boolean;
+  update_fields: DirectoryField[];
+}
+
+export interface ImportReport {
+  created: number;
+  updated: number;
+  skipped: number;
+  warnings: string[];
+}
+
+export interface SchemaInfo {
+  current_version: number;
+  supported_version: number;
+}
+
+export interface McpStatus {
+  running: boolean;
+  address?: string;
+  active_sessions: number;
+}
+
+export interface NotificationSettings {
+  enabled: boolean;
+  days_before: number;
+}
+
+export interface BackupRecord {
+  started_at: string;
+  path?: string;
+  size_bytes?: number;
+  pruned: number;
+  error?: string;
+}
+
+export interface BackupStatus {
+  directory: string;
+  retention_count: number;
+  last_attempt?: BackupRecord;
+  last_success?: BackupRecord;
+}
+
+export interface WorkbookSheet {
+  name: string;
+  rows: number;
+}
+
+export interface Initiative {
+  id: string;
+  name: string;
+  description?: string;
+  quarter: string;
+  owner_email?: string;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface InitiativeProgress {
+  initiative: Initiative;
+  projects: ProjectSummary[];
+  total_milestones: number;
+  completed_milestones: number;
+  progress_percent?: number;
+  open_high_risks: number;
+}
+
+export type UserRole = 'viewer' | 'editor' | 'admin';
+
+export interface User {
+  email: string;
+  role: UserRole;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface CurrentRole {
+  email?: string;
+  role: UserRole;
+  permissions_enabled: boolean;
+}